@@ -0,0 +1,367 @@
+//! REPL editing help: tab completion of command names, subcommands and keys
+//! (looked up with a short `kv.scan`), and the remaining arguments of the
+//! command being typed shown as a dimmed hint, like redis-cli.
+
+use colored::Colorize;
+use rustyline::completion::Completer;
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use serde_json::json;
+use std::borrow::Cow;
+use std::time::Duration;
+use synap_sdk::SynapClient;
+
+/// Which arguments of a command are keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyArgs {
+    None,
+    First,
+    All,
+    /// Keys and values alternate, as in `MSET`
+    EveryOther,
+}
+
+impl KeyArgs {
+    /// Whether argument `index` (0-based, after the command name) is a key
+    pub fn is_key(self, index: usize) -> bool {
+        match self {
+            KeyArgs::None => false,
+            KeyArgs::First => index == 0,
+            KeyArgs::All => true,
+            KeyArgs::EveryOther => index.is_multiple_of(2),
+        }
+    }
+}
+
+/// One command the REPL knows; `name` may include a subcommand
+struct CommandSpec {
+    name: &'static str,
+    args: &'static str,
+    keys: KeyArgs,
+}
+
+const fn spec(name: &'static str, args: &'static str, keys: KeyArgs) -> CommandSpec {
+    CommandSpec { name, args, keys }
+}
+
+const COMMANDS: &[CommandSpec] = &[
+    spec("SET", "key value [ttl]", KeyArgs::First),
+    spec("GET", "key", KeyArgs::First),
+    spec("DEL", "key [key ...]", KeyArgs::All),
+    spec("DELETE", "key [key ...]", KeyArgs::All),
+    spec("EXISTS", "key", KeyArgs::First),
+    spec("INCR", "key [amount]", KeyArgs::First),
+    spec("DECR", "key [amount]", KeyArgs::First),
+    spec("EXPIRE", "key seconds", KeyArgs::First),
+    spec("TTL", "key", KeyArgs::First),
+    spec("PERSIST", "key", KeyArgs::First),
+    spec("KEYS", "[pattern]", KeyArgs::None),
+    spec("SCAN", "[pattern] [count]", KeyArgs::None),
+    spec("DBSIZE", "", KeyArgs::None),
+    spec("MSET", "key value [key value ...]", KeyArgs::EveryOther),
+    spec("MGET", "key [key ...]", KeyArgs::All),
+    spec("HSET", "key field value [field value ...]", KeyArgs::First),
+    spec("HGET", "key field", KeyArgs::First),
+    spec("HGETALL", "key", KeyArgs::First),
+    spec("HDEL", "key field [field ...]", KeyArgs::First),
+    spec("LPUSH", "key value [value ...]", KeyArgs::First),
+    spec("RPUSH", "key value [value ...]", KeyArgs::First),
+    spec("LRANGE", "key start stop", KeyArgs::First),
+    spec("SADD", "key member [member ...]", KeyArgs::First),
+    spec("SMEMBERS", "key", KeyArgs::First),
+    spec(
+        "ZADD",
+        "key score member [score member ...]",
+        KeyArgs::First,
+    ),
+    spec("ZRANGE", "key start stop [WITHSCORES]", KeyArgs::First),
+    spec("XADD", "room event data", KeyArgs::First),
+    spec("XRANGE", "room [from-offset] [count]", KeyArgs::First),
+    spec("QUEUE CREATE", "queue", KeyArgs::None),
+    spec("QUEUE PUBLISH", "queue message [priority]", KeyArgs::None),
+    spec("QUEUE CONSUME", "queue [consumer-id]", KeyArgs::None),
+    spec("QUEUE ACK", "queue message-id", KeyArgs::None),
+    spec("QUEUE NACK", "queue message-id", KeyArgs::None),
+    spec("QUEUE STATS", "queue", KeyArgs::None),
+    spec("PUBLISH", "topic message", KeyArgs::None),
+    spec("SUBSCRIBE", "topic [topic ...]", KeyArgs::None),
+    spec("SELECT", "db", KeyArgs::None),
+    spec("FLUSHDB", "", KeyArgs::None),
+    spec("FLUSHALL", "", KeyArgs::None),
+    spec("DUMP", "key file", KeyArgs::First),
+    spec(
+        "RESTORE",
+        "key file [TTL ms] [REPLACE] [ABSTTL]",
+        KeyArgs::First,
+    ),
+    spec("DUMPALL", "file", KeyArgs::None),
+    spec("RESTOREALL", "file", KeyArgs::None),
+    spec("SCRIPT KILL", "", KeyArgs::None),
+    spec(
+        "CLUSTER SETSLOT",
+        "slot[-end] MIGRATING|IMPORTING|NODE|STABLE [node-id]",
+        KeyArgs::None,
+    ),
+    spec(
+        "CLUSTER MIGRATE",
+        "slot[-end] node-id [RATE keys-per-sec]",
+        KeyArgs::None,
+    ),
+    spec("CLUSTER MIGRATIONS", "", KeyArgs::None),
+    spec("CLUSTER THROTTLE", "keys/s", KeyArgs::None),
+    spec("WAL DUMP", "path [options]", KeyArgs::None),
+    spec("SNAPSHOT DUMP", "path [options]", KeyArgs::None),
+    spec("PROFILE LIST", "", KeyArgs::None),
+    spec("PROFILE USE", "name", KeyArgs::None),
+    spec("INFO", "", KeyArgs::None),
+    spec("STATS", "", KeyArgs::None),
+    spec("HOTKEYS", "[count]", KeyArgs::None),
+    spec("SLOWLOG GET", "[count]", KeyArgs::None),
+    spec("SLOWLOG LEN", "", KeyArgs::None),
+    spec("SLOWLOG RESET", "", KeyArgs::None),
+    spec("PING", "", KeyArgs::None),
+    spec("HELP", "", KeyArgs::None),
+    spec("QUIT", "", KeyArgs::None),
+    spec("EXIT", "", KeyArgs::None),
+];
+
+/// Key arguments of `command` (upper case, without subcommand)
+pub fn key_args(command: &str) -> KeyArgs {
+    COMMANDS
+        .iter()
+        .find(|spec| spec.name == command)
+        .map_or(KeyArgs::None, |spec| spec.keys)
+}
+
+/// The command spelled by the first words of `words`, and how many words its
+/// name takes
+fn lookup(words: &[&str]) -> Option<(&'static CommandSpec, usize)> {
+    let first = words.first()?.to_uppercase();
+    if let Some(sub) = words.get(1) {
+        let name = format!("{} {}", first, sub.to_uppercase());
+        if let Some(spec) = COMMANDS.iter().find(|spec| spec.name == name) {
+            return Some((spec, 2));
+        }
+    }
+    COMMANDS
+        .iter()
+        .find(|spec| spec.name == first)
+        .map(|spec| (spec, 1))
+}
+
+/// Subcommands of `QUEUE`, `CLUSTER`, ...; empty for other commands
+fn subcommands(command: &str) -> Vec<&'static str> {
+    let prefix = format!("{} ", command.to_uppercase());
+    COMMANDS
+        .iter()
+        .filter_map(|spec| spec.name.strip_prefix(&prefix))
+        .collect()
+}
+
+/// Split a usage string into arguments, keeping `[...]` groups together
+fn usage_args(usage: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+    for (i, c) in usage.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ' ' if depth == 0 => {
+                if let Some(s) = start.take() {
+                    args.push(&usage[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(s) = start {
+        args.push(&usage[s..]);
+    }
+    args
+}
+
+/// Match the case the user is typing in
+fn same_case(typed: &str, name: &str) -> String {
+    if !typed.is_empty() && typed.chars().all(|c| !c.is_ascii_uppercase()) {
+        name.to_lowercase()
+    } else {
+        name.to_string()
+    }
+}
+
+/// rustyline helper for the REPL
+pub struct CliHelper {
+    sdk: Option<SynapClient>,
+    namespace: String,
+    profiles: Vec<String>,
+}
+
+impl CliHelper {
+    pub fn new(profiles: Vec<String>) -> Self {
+        Self {
+            sdk: None,
+            namespace: String::new(),
+            profiles,
+        }
+    }
+
+    /// Complete keys from this connection; called again after `SELECT` and
+    /// `PROFILE USE`
+    pub fn connect(&mut self, sdk: &SynapClient, namespace: &str) {
+        self.sdk = Some(sdk.clone());
+        self.namespace = namespace.to_string();
+    }
+
+    /// Up to 50 keys starting with `prefix`. Gives up after 500ms so a slow
+    /// or unreachable server never stalls the prompt.
+    fn keys(&self, prefix: &str) -> Vec<String> {
+        let Some(sdk) = &self.sdk else {
+            return Vec::new();
+        };
+        let payload = json!({"prefix": format!("{}{}", self.namespace, prefix), "limit": 50});
+        let scan = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(tokio::time::timeout(
+                Duration::from_millis(500),
+                sdk.send_command("kv.scan", payload),
+            ))
+        });
+        let Ok(Ok(res)) = scan else {
+            return Vec::new();
+        };
+        res["keys"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|key| key.as_str())
+            .map(|key| key.strip_prefix(&self.namespace).unwrap_or(key).to_string())
+            .collect()
+    }
+}
+
+impl Completer for CliHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &line[start..];
+        let before: Vec<&str> = line[..start].split_whitespace().collect();
+        let upper = word.to_uppercase();
+
+        let candidates = match before.as_slice() {
+            [] => {
+                let mut names: Vec<String> = COMMANDS
+                    .iter()
+                    .map(|spec| spec.name.split(' ').next().unwrap_or(spec.name))
+                    .filter(|name| name.starts_with(&upper))
+                    .map(|name| format!("{} ", same_case(word, name)))
+                    .collect();
+                names.dedup();
+                names
+            }
+            [command] if !subcommands(command).is_empty() => subcommands(command)
+                .into_iter()
+                .filter(|sub| sub.starts_with(&upper))
+                .map(|sub| format!("{} ", same_case(word, sub)))
+                .collect(),
+            words => match lookup(words) {
+                Some((spec, _)) if spec.name == "PROFILE USE" => self
+                    .profiles
+                    .iter()
+                    .filter(|name| name.starts_with(word))
+                    .cloned()
+                    .collect(),
+                Some((spec, taken)) if spec.keys.is_key(words.len() - taken) => self.keys(word),
+                _ => Vec::new(),
+            },
+        };
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for CliHelper {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<String> {
+        if pos < line.len() {
+            return None;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if !line.ends_with(char::is_whitespace) {
+            // Still typing the command name: show the rest of the first match
+            let [word] = words.as_slice() else {
+                return None;
+            };
+            let upper = word.to_uppercase();
+            let name = COMMANDS
+                .iter()
+                .map(|spec| spec.name.split(' ').next().unwrap_or(spec.name))
+                .find(|name| name.starts_with(&upper) && name.len() > upper.len())?;
+            return Some(same_case(word, &name[word.len()..]));
+        }
+
+        if let [command] = words.as_slice() {
+            let subcommands = subcommands(command);
+            if !subcommands.is_empty() {
+                return Some(same_case(command, &subcommands.join("|")));
+            }
+        }
+        let (spec, taken) = lookup(&words)?;
+        let mut remaining = usage_args(spec.args);
+        for _ in taken..words.len() {
+            // A repeating group keeps being hinted
+            if remaining.first()?.contains("...") {
+                break;
+            }
+            remaining.remove(0);
+        }
+        if remaining.is_empty() {
+            return None;
+        }
+        Some(remaining.join(" "))
+    }
+}
+
+impl Highlighter for CliHelper {
+    /// Known command names in bold
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        let trimmed = line.trim_start();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let command = trimmed[..end].to_uppercase();
+        let known = COMMANDS
+            .iter()
+            .any(|spec| spec.name.split(' ').next() == Some(command.as_str()));
+        if !known {
+            return Cow::Borrowed(line);
+        }
+        let offset = line.len() - trimmed.len();
+        Cow::Owned(format!(
+            "{}{}{}",
+            &line[..offset],
+            trimmed[..end].bold(),
+            &trimmed[end..]
+        ))
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dimmed().to_string())
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _kind: CmdKind) -> bool {
+        true
+    }
+}
+
+impl Validator for CliHelper {}
+
+impl Helper for CliHelper {}
@@ -0,0 +1,67 @@
+//! Offline `WAL DUMP` / `SNAPSHOT DUMP`: decode persistence files without a
+//! running server, for incident forensics and support.
+
+use anyhow::{Context, Result, anyhow, bail};
+use synap_server::persistence::inspect::{InspectFilter, dump_snapshot, dump_wal, parse_time};
+
+/// Largest writes listed by `--stats` unless `--top` says otherwise
+const DEFAULT_TOP: usize = 10;
+
+/// Run `WAL DUMP path [options]` or `SNAPSHOT DUMP path [options]`
+pub async fn run(command: &str, args: &[String]) -> Result<String> {
+    let [sub, path, options @ ..] = args else {
+        bail!(
+            "Usage: {command} DUMP path [--prefix p] [--since t] [--until t] [--op name] [--stats] [--top n]"
+        );
+    };
+    if !sub.eq_ignore_ascii_case("DUMP") {
+        bail!("Unknown {command} subcommand: {sub}");
+    }
+
+    let mut filter = InspectFilter::default();
+    let mut stats = false;
+    let mut top = DEFAULT_TOP;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let mut value = || {
+            options
+                .next()
+                .with_context(|| format!("{option} needs a value"))
+        };
+        match option.as_str() {
+            "--prefix" => filter.key_prefix = Some(value()?.clone()),
+            "--since" => filter.since = Some(time(value()?)?),
+            "--until" => filter.until = Some(time(value()?)?),
+            "--op" => filter
+                .operations
+                .extend(value()?.split(',').map(str::to_lowercase)),
+            "--stats" => stats = true,
+            "--top" => {
+                top = value()?
+                    .parse()
+                    .with_context(|| format!("{option} needs a number"))?
+            }
+            other => bail!("Unknown option: {other}"),
+        }
+    }
+
+    let path = std::path::Path::new(path);
+    let dump = match command {
+        "WAL" => dump_wal(path, &filter).await,
+        _ => dump_snapshot(path, &filter).await,
+    }
+    .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+
+    Ok(if stats {
+        format!("{}\n{}", dump.stats(top), dump.summary())
+    } else {
+        dump.to_string()
+    })
+}
+
+/// Unix seconds or an RFC 3339 timestamp
+fn time(text: &str) -> Result<u64> {
+    parse_time(text).with_context(|| {
+        format!("Invalid time '{text}': use Unix seconds or RFC 3339 (2026-01-31T12:00:00Z)")
+    })
+}
@@ -1,13 +1,23 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use colored::Colorize;
-use rustyline::DefaultEditor;
+use futures::StreamExt;
 use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{CompletionType, Config, Editor};
 use serde_json::{Value, json};
-use std::time::Instant;
-use synap_sdk::{SynapClient, SynapConfig};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use synap_sdk::{RestoreOptions, SynapClient, SynapError};
 use tracing::{error, info};
 
+mod complete;
+mod inspect;
+mod profile;
+
+use complete::CliHelper;
+use profile::{Profile, ProfileFile};
+
 #[derive(Parser, Debug)]
 #[command(name = "synap-cli")]
 #[command(
@@ -22,54 +32,172 @@ struct Args {
     #[arg(short = 'u', long)]
     url: Option<String>,
 
-    /// Server host (used when --url is not set)
-    #[arg(short = 'H', long, default_value = "127.0.0.1")]
-    host: String,
+    /// Server host (used when --url is not set) [default: 127.0.0.1]
+    #[arg(short = 'H', long)]
+    host: Option<String>,
+
+    /// Server port (used when --url is not set) [default: 15500]
+    #[arg(short = 'p', long)]
+    port: Option<u16>,
+
+    /// Transport protocol when using -h/-p: http, rpc, resp3 [default: http]
+    #[arg(long)]
+    transport: Option<String>,
+
+    /// Logical database [default: 0]
+    #[arg(short = 'n', long)]
+    db: Option<u32>,
+
+    /// API key sent as a Bearer token
+    #[arg(long, env = "SYNAP_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
+    /// Username for HTTP Basic Auth
+    #[arg(long, env = "SYNAP_USER")]
+    user: Option<String>,
+
+    /// Password for --user; prefer SYNAP_PASSWORD over the flag
+    #[arg(long, env = "SYNAP_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// Connect over HTTPS
+    #[arg(long, env = "SYNAP_TLS")]
+    tls: bool,
+
+    /// PEM bundle of extra root certificates for --tls
+    #[arg(long, env = "SYNAP_CACERT")]
+    cacert: Option<PathBuf>,
+
+    /// Connection profile from the profiles file; flags above override it
+    #[arg(short = 'P', long)]
+    profile: Option<String>,
 
-    /// Server port (used when --url is not set)
-    #[arg(short = 'p', long, default_value = "15500")]
-    port: u16,
+    /// Profiles file [default: ~/.config/synap/config.toml]
+    #[arg(long)]
+    config: Option<PathBuf>,
 
-    /// Transport protocol when using -h/-p: http, rpc, resp3
-    #[arg(long, default_value = "http")]
-    transport: String,
+    /// Print each reply as the server's raw JSON response, without colors.
+    /// Errors go to stderr as JSON and the exit code is non-zero
+    #[arg(long)]
+    json: bool,
+
+    /// Read commands from stdin, one per line; exits non-zero if any failed
+    #[arg(long, conflicts_with = "command")]
+    pipe: bool,
 
     /// Command to execute (non-interactive mode)
-    #[arg(trailing_var_arg = true)]
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     command: Vec<String>,
 }
 
 impl Args {
-    fn effective_url(&self) -> String {
-        if let Some(ref url) = self.url {
-            return url.clone();
+    /// The selected profile with the connection flags applied on top
+    fn effective_profile(&self, profile: Option<&Profile>) -> Profile {
+        let mut profile = profile.cloned().unwrap_or_default();
+        if self.host.is_some() || self.port.is_some() || self.transport.is_some() {
+            profile.url = None;
+        }
+        if let Some(url) = &self.url {
+            profile.url = Some(url.clone());
+        }
+        if let Some(host) = &self.host {
+            profile.host = Some(host.clone());
+        }
+        if let Some(port) = self.port {
+            profile.port = Some(port);
+        }
+        if let Some(transport) = &self.transport {
+            profile.transport = Some(transport.clone());
+        }
+        if let Some(db) = self.db {
+            profile.db = Some(db);
         }
-        match self.transport.to_lowercase().as_str() {
-            "rpc" | "synap" | "synaprpc" => format!("synap://{}:{}", self.host, self.port),
-            "resp3" | "redis" => format!("resp3://{}:{}", self.host, self.port),
-            _ => format!("http://{}:{}", self.host, self.port),
+        if let Some(api_key) = &self.api_key {
+            profile.api_key = Some(api_key.clone());
+            profile.username = None;
+            profile.password = None;
         }
+        if let Some(user) = &self.user {
+            profile.username = Some(user.clone());
+            profile.password = None;
+            profile.api_key = None;
+        }
+        if let Some(password) = &self.password {
+            profile.password = Some(password.clone());
+        }
+        if self.tls {
+            profile.tls.enabled = true;
+        }
+        if let Some(cacert) = &self.cacert {
+            profile.tls.ca_cert = Some(cacert.clone());
+        }
+        profile
     }
 }
 
 // ── CLI client wrapping the SDK ──────────────────────────────────────────────
 
+/// A command's answer: the formatted text the REPL shows and the raw response
+/// `--json` prints
+struct Reply {
+    text: String,
+    json: Value,
+}
+
+impl Reply {
+    fn new(text: impl Into<String>, json: Value) -> Self {
+        Self {
+            text: text.into(),
+            json,
+        }
+    }
+
+    /// `OK`, for commands whose response carries nothing else
+    fn ok() -> Self {
+        Self::new("OK".green().to_string(), json!({"success": true}))
+    }
+}
+
 struct CliClient {
     sdk: SynapClient,
+    /// `--json`: replies are printed as raw JSON
+    json: bool,
+    /// Key prefix of the profile's namespace, empty for none
+    namespace: String,
 }
 
 impl CliClient {
-    fn new(url: &str) -> Result<Self> {
-        let config = SynapConfig::new(url);
+    fn new(profile: &Profile, json: bool) -> Result<Self> {
+        let config = profile.sdk_config()?;
         let sdk =
             SynapClient::new(config).map_err(|e| anyhow::anyhow!("Failed to connect: {}", e))?;
-        Ok(Self { sdk })
+        Ok(Self {
+            sdk,
+            json,
+            namespace: profile.namespace.clone().unwrap_or_default(),
+        })
     }
 
-    async fn execute_command(&self, command: &str, args: &[String]) -> Result<String> {
+    /// Switch to database `db`, checking first that the server has it
+    async fn select(&mut self, args: &[String]) -> Result<Reply> {
+        let [db] = args else {
+            anyhow::bail!("Usage: SELECT db");
+        };
+        let db: u32 = db
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid database: {}", db))?;
+        let sdk = self.sdk.select(db);
+        sdk.send_command("kv.dbsize", json!({})).await?;
+        self.sdk = sdk;
+        Ok(Reply::ok())
+    }
+
+    async fn execute_command(&self, command: &str, args: &[String]) -> Result<Reply> {
         let start = Instant::now();
+        let command = command.to_uppercase();
+        let args = &self.scope_keys(&command, args);
 
-        let response = match command.to_uppercase().as_str() {
+        let response = match command.as_str() {
             "SET" => self.cmd_set(args).await?,
             "GET" => self.cmd_get(args).await?,
             "DEL" | "DELETE" => self.cmd_del(args).await?,
@@ -84,84 +212,146 @@ impl CliClient {
             "DBSIZE" => self.cmd_dbsize().await?,
             "FLUSHDB" => self.cmd_flushdb().await?,
             "FLUSHALL" => self.cmd_flushall().await?,
+            "DUMP" => self.cmd_dump(args).await?,
+            "RESTORE" => self.cmd_restore(args).await?,
+            "DUMPALL" => self.cmd_dumpall(args).await?,
+            "RESTOREALL" => self.cmd_restoreall(args).await?,
             "INFO" | "STATS" => self.cmd_stats().await?,
+            "HOTKEYS" => self.cmd_hotkeys(args).await?,
+            "SLOWLOG" => self.cmd_slowlog(args).await?,
             "PING" => self.cmd_ping().await?,
             "MSET" => self.cmd_mset(args).await?,
             "MGET" => self.cmd_mget(args).await?,
-            "HELP" => Self::help_text()?,
+            "HSET" => self.cmd_hset(args).await?,
+            "HGET" => self.cmd_hget(args).await?,
+            "HGETALL" => self.cmd_hgetall(args).await?,
+            "HDEL" => self.cmd_hdel(args).await?,
+            "LPUSH" => self.cmd_push("list.lpush", "LPUSH", args).await?,
+            "RPUSH" => self.cmd_push("list.rpush", "RPUSH", args).await?,
+            "LRANGE" => self.cmd_lrange(args).await?,
+            "SADD" => self.cmd_sadd(args).await?,
+            "SMEMBERS" => self.cmd_smembers(args).await?,
+            "ZADD" => self.cmd_zadd(args).await?,
+            "ZRANGE" => self.cmd_zrange(args).await?,
+            "XADD" => self.cmd_xadd(args).await?,
+            "XRANGE" => self.cmd_xrange(args).await?,
+            "QUEUE" => self.cmd_queue(args).await?,
+            "PUBLISH" => self.cmd_publish(args).await?,
+            "SUBSCRIBE" => self.cmd_subscribe(args).await?,
+            "SCRIPT" => self.cmd_script(args).await?,
+            "CLUSTER" => self.cmd_cluster(args).await?,
+            "WAL" | "SNAPSHOT" => {
+                let text = inspect::run(&command, args).await?;
+                Reply::new(text.clone(), Value::String(text))
+            }
+            "HELP" => {
+                let text = Self::help_text();
+                Reply::new(text.clone(), Value::String(text))
+            }
             _ => return Err(anyhow::anyhow!("Unknown command: {}", command)),
         };
 
         let elapsed = start.elapsed();
-        Ok(format!(
-            "{}\n{}",
-            response,
-            format!("({:.2?})", elapsed).dimmed()
+        Ok(Reply::new(
+            format!(
+                "{}\n{}",
+                response.text,
+                format!("({:.2?})", elapsed).dimmed()
+            ),
+            response.json,
         ))
     }
 
     // ── Command helpers ──────────────────────────────────────────────────────
 
+    /// Prefix the key arguments of `command` with the profile's namespace
+    fn scope_keys(&self, command: &str, args: &[String]) -> Vec<String> {
+        let mut args = args.to_vec();
+        if self.namespace.is_empty() {
+            return args;
+        }
+        let scope = |key: &mut String| key.insert_str(0, &self.namespace);
+        match command {
+            "KEYS" | "SCAN" if args.is_empty() => args.push(self.namespace.clone()),
+            "KEYS" | "SCAN" => args.iter_mut().take(1).for_each(scope),
+            _ => {
+                let keys = complete::key_args(command);
+                args.iter_mut()
+                    .enumerate()
+                    .filter(|(i, _)| keys.is_key(*i))
+                    .for_each(|(_, key)| scope(key));
+            }
+        }
+        args
+    }
+
     async fn send(&self, cmd: &str, payload: Value) -> Result<Value> {
-        self.sdk
-            .send_command(cmd, payload)
-            .await
-            .map_err(|e| anyhow::anyhow!("{}", e))
+        Ok(self.sdk.send_command(cmd, payload).await?)
     }
 
-    async fn cmd_set(&self, args: &[String]) -> Result<String> {
+    async fn cmd_set(&self, args: &[String]) -> Result<Reply> {
         if args.len() < 2 {
             return Err(anyhow::anyhow!("Usage: SET key value [ttl]"));
         }
         let ttl = args.get(2).and_then(|s| s.parse::<u64>().ok());
-        self.send(
-            "kv.set",
-            json!({"key": args[0], "value": args[1], "ttl": ttl}),
-        )
-        .await?;
-        Ok("OK".green().to_string())
+        let res = self
+            .send(
+                "kv.set",
+                json!({"key": args[0], "value": args[1], "ttl": ttl}),
+            )
+            .await?;
+        Ok(Reply::new("OK".green().to_string(), res))
     }
 
-    async fn cmd_get(&self, args: &[String]) -> Result<String> {
+    async fn cmd_get(&self, args: &[String]) -> Result<Reply> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: GET key"));
         }
         let res = self.send("kv.get", json!({"key": args[0]})).await?;
         if res.is_null() {
-            Ok("(nil)".dimmed().to_string())
+            Ok(Reply::new("(nil)".dimmed().to_string(), res))
         } else if let Some(obj) = res.as_object() {
             // HTTP returns {"found": bool, "value": ...}
             if obj.get("found").and_then(|v| v.as_bool()).unwrap_or(false) {
                 let val = &obj["value"];
-                Ok(format!("\"{}\"", val.as_str().unwrap_or(&val.to_string())))
+                Ok(Reply::new(
+                    format!("\"{}\"", val.as_str().unwrap_or(&val.to_string())),
+                    res,
+                ))
             } else {
-                Ok("(nil)".dimmed().to_string())
+                Ok(Reply::new("(nil)".dimmed().to_string(), res))
             }
         } else {
             // Binary transports return the value directly
-            Ok(format!("\"{}\"", res.as_str().unwrap_or(&res.to_string())))
+            Ok(Reply::new(
+                format!("\"{}\"", res.as_str().unwrap_or(&res.to_string())),
+                res,
+            ))
         }
     }
 
-    async fn cmd_del(&self, args: &[String]) -> Result<String> {
+    async fn cmd_del(&self, args: &[String]) -> Result<Reply> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: DEL key [key ...]"));
         }
         let res = self.send("kv.mdel", json!({"keys": args})).await?;
         let deleted = res["deleted"].as_u64().unwrap_or(0);
-        Ok(format!("(integer) {}", deleted))
+        Ok(Reply::new(format!("(integer) {}", deleted), res))
     }
 
-    async fn cmd_exists(&self, args: &[String]) -> Result<String> {
+    async fn cmd_exists(&self, args: &[String]) -> Result<Reply> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: EXISTS key"));
         }
         let res = self.send("kv.exists", json!({"key": args[0]})).await?;
         let exists = res["exists"].as_bool().unwrap_or(false);
-        Ok(format!("(integer) {}", if exists { 1 } else { 0 }))
+        Ok(Reply::new(
+            format!("(integer) {}", if exists { 1 } else { 0 }),
+            res,
+        ))
     }
 
-    async fn cmd_incr(&self, args: &[String]) -> Result<String> {
+    async fn cmd_incr(&self, args: &[String]) -> Result<Reply> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: INCR key [amount]"));
         }
@@ -170,10 +360,10 @@ impl CliClient {
             .send("kv.incr", json!({"key": args[0], "amount": amount}))
             .await?;
         let value = res["value"].as_i64().or(res.as_i64()).unwrap_or(0);
-        Ok(format!("(integer) {}", value))
+        Ok(Reply::new(format!("(integer) {}", value), res))
     }
 
-    async fn cmd_decr(&self, args: &[String]) -> Result<String> {
+    async fn cmd_decr(&self, args: &[String]) -> Result<Reply> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: DECR key [amount]"));
         }
@@ -182,10 +372,10 @@ impl CliClient {
             .send("kv.decr", json!({"key": args[0], "amount": amount}))
             .await?;
         let value = res["value"].as_i64().or(res.as_i64()).unwrap_or(0);
-        Ok(format!("(integer) {}", value))
+        Ok(Reply::new(format!("(integer) {}", value), res))
     }
 
-    async fn cmd_expire(&self, args: &[String]) -> Result<String> {
+    async fn cmd_expire(&self, args: &[String]) -> Result<Reply> {
         if args.len() < 2 {
             return Err(anyhow::anyhow!("Usage: EXPIRE key seconds"));
         }
@@ -196,28 +386,34 @@ impl CliClient {
             .send("kv.expire", json!({"key": args[0], "ttl": ttl}))
             .await?;
         let result = res["result"].as_bool().unwrap_or(false);
-        Ok(format!("(integer) {}", if result { 1 } else { 0 }))
+        Ok(Reply::new(
+            format!("(integer) {}", if result { 1 } else { 0 }),
+            res,
+        ))
     }
 
-    async fn cmd_ttl(&self, args: &[String]) -> Result<String> {
+    async fn cmd_ttl(&self, args: &[String]) -> Result<Reply> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: TTL key"));
         }
         let res = self.send("kv.ttl", json!({"key": args[0]})).await?;
         let ttl = res["ttl"].as_i64().or(res.as_i64()).unwrap_or(-2);
-        Ok(format!("(integer) {}", ttl))
+        Ok(Reply::new(format!("(integer) {}", ttl), res))
     }
 
-    async fn cmd_persist(&self, args: &[String]) -> Result<String> {
+    async fn cmd_persist(&self, args: &[String]) -> Result<Reply> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: PERSIST key"));
         }
         let res = self.send("kv.persist", json!({"key": args[0]})).await?;
         let result = res["result"].as_bool().unwrap_or(false);
-        Ok(format!("(integer) {}", if result { 1 } else { 0 }))
+        Ok(Reply::new(
+            format!("(integer) {}", if result { 1 } else { 0 }),
+            res,
+        ))
     }
 
-    async fn cmd_keys(&self, args: &[String]) -> Result<String> {
+    async fn cmd_keys(&self, args: &[String]) -> Result<Reply> {
         let pattern = args.first().map(|s| s.as_str());
         let res = self
             .send("kv.scan", json!({"prefix": pattern, "limit": 1000}))
@@ -225,42 +421,132 @@ impl CliClient {
         let empty = vec![];
         let keys = res["keys"].as_array().unwrap_or(&empty);
         if keys.is_empty() {
-            return Ok("(empty list)".dimmed().to_string());
+            return Ok(Reply::new("(empty list)".dimmed().to_string(), res));
         }
         let output = keys
             .iter()
             .enumerate()
-            .map(|(i, k)| format!("{}) \"{}\"", i + 1, k.as_str().unwrap_or("")))
+            .map(|(i, k)| {
+                let key = k.as_str().unwrap_or("");
+                let key = key.strip_prefix(&self.namespace).unwrap_or(key);
+                format!("{}) \"{}\"", i + 1, key)
+            })
             .collect::<Vec<_>>()
             .join("\n");
-        Ok(output)
+        Ok(Reply::new(output, res))
     }
 
-    async fn cmd_scan(&self, args: &[String]) -> Result<String> {
+    async fn cmd_scan(&self, args: &[String]) -> Result<Reply> {
         self.cmd_keys(args).await
     }
 
-    async fn cmd_dbsize(&self) -> Result<String> {
+    async fn cmd_dbsize(&self) -> Result<Reply> {
         let res = self.send("kv.dbsize", json!({})).await?;
         let size = res["size"]
             .as_u64()
             .or(res["total_keys"].as_u64())
             .or(res.as_u64())
             .unwrap_or(0);
-        Ok(format!("(integer) {}", size))
+        Ok(Reply::new(format!("(integer) {}", size), res))
+    }
+
+    async fn cmd_flushdb(&self) -> Result<Reply> {
+        let res = self.send("kv.flushdb", json!({})).await?;
+        Ok(Reply::new("OK".green().to_string(), res))
+    }
+
+    async fn cmd_flushall(&self) -> Result<Reply> {
+        let res = self.send("kv.flushall", json!({})).await?;
+        Ok(Reply::new("OK".green().to_string(), res))
+    }
+
+    /// Save one key, of any type, to a file
+    async fn cmd_dump(&self, args: &[String]) -> Result<Reply> {
+        let [key, path] = args else {
+            anyhow::bail!("Usage: DUMP key file");
+        };
+        let Some(payload) = self.sdk.kv().dump(key).await? else {
+            return Ok(Reply::new("(nil)".dimmed().to_string(), Value::Null));
+        };
+        tokio::fs::write(path, &payload)
+            .await
+            .with_context(|| format!("Failed to write {}", path))?;
+        Ok(Reply::new(
+            format!(
+                "{} ({} bytes written to {})",
+                "OK".green(),
+                payload.len(),
+                path
+            ),
+            json!({"bytes": payload.len(), "file": path}),
+        ))
+    }
+
+    /// Create a key from a file written by DUMP
+    async fn cmd_restore(&self, args: &[String]) -> Result<Reply> {
+        const USAGE: &str = "Usage: RESTORE key file [TTL ms] [REPLACE] [ABSTTL]";
+        let (Some(key), Some(path)) = (args.first(), args.get(1)) else {
+            anyhow::bail!(USAGE);
+        };
+        let mut options = RestoreOptions::default();
+        let mut flags = args[2..].iter();
+        while let Some(flag) = flags.next() {
+            match flag.to_uppercase().as_str() {
+                "TTL" => {
+                    let ms = flags.next().and_then(|ms| ms.parse().ok());
+                    options.ttl_ms = Some(ms.context(USAGE)?);
+                }
+                "REPLACE" => options.replace = true,
+                "ABSTTL" => options.absttl = true,
+                _ => anyhow::bail!(USAGE),
+            }
+        }
+        let payload = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path))?;
+        self.sdk.kv().restore(key, payload, options).await?;
+        Ok(Reply::ok())
     }
 
-    async fn cmd_flushdb(&self) -> Result<String> {
-        self.send("kv.flushdb", json!({})).await?;
-        Ok("OK".green().to_string())
+    /// Download every store to a portable dump file
+    async fn cmd_dumpall(&self, args: &[String]) -> Result<Reply> {
+        let path = args.first().context("Usage: DUMPALL file")?;
+        let dump = self.sdk.export_snapshot().await?;
+        tokio::fs::write(path, &dump)
+            .await
+            .with_context(|| format!("Failed to write {}", path))?;
+        Ok(Reply::new(
+            format!(
+                "{} ({} bytes written to {})",
+                "OK".green(),
+                dump.len(),
+                path
+            ),
+            json!({"bytes": dump.len(), "file": path}),
+        ))
     }
 
-    async fn cmd_flushall(&self) -> Result<String> {
-        self.send("kv.flushall", json!({})).await?;
-        Ok("OK".green().to_string())
+    /// Load a file written by DUMPALL into the server
+    async fn cmd_restoreall(&self, args: &[String]) -> Result<Reply> {
+        let path = args.first().context("Usage: RESTOREALL file")?;
+        let dump = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path))?;
+        let summary = self.sdk.import_snapshot(dump).await?;
+        Ok(Reply::new(
+            format!(
+                "{} ({} keys, {} queue messages, {} stream events; {} expired keys skipped)",
+                "OK".green(),
+                summary.keys,
+                summary.queue_messages,
+                summary.stream_events,
+                summary.expired_keys
+            ),
+            serde_json::to_value(&summary)?,
+        ))
     }
 
-    async fn cmd_stats(&self) -> Result<String> {
+    async fn cmd_stats(&self) -> Result<Reply> {
         let res = self.send("kv.stats", json!({})).await?;
         let mut output = Vec::new();
         output.push("# Keyspace".to_string());
@@ -277,18 +563,93 @@ impl CliClient {
             "hit_rate: {:.2}%",
             res["hit_rate"].as_f64().unwrap_or(0.0) * 100.0
         ));
-        Ok(output.join("\n"))
+        Ok(Reply::new(output.join("\n"), res))
+    }
+
+    /// Keys with the most sampled GETs and SETs, this interval and the last
+    async fn cmd_hotkeys(&self, args: &[String]) -> Result<Reply> {
+        let top = match args {
+            [] => 10,
+            [count] => count.parse::<u64>().context("Count must be a number")?,
+            _ => anyhow::bail!("Usage: HOTKEYS [count]"),
+        };
+        let res = self.send("monitoring.hotkeys", json!({"top": top})).await?;
+        let mut output = vec![format!(
+            "# Current interval ({}s of {}s, 1 in {} operations sampled)",
+            res["elapsed_secs"], res["interval_secs"], res["sample_rate"]
+        )];
+        for (window, title) in [("current", None), ("previous", Some("# Previous interval"))] {
+            if let Some(title) = title {
+                output.push(String::new());
+                output.push(title.to_string());
+            }
+            let keys = res[window].as_array().cloned().unwrap_or_default();
+            if keys.is_empty() {
+                output.push("(empty)".dimmed().to_string());
+            }
+            for (i, key) in keys.iter().enumerate() {
+                output.push(format!(
+                    "{}) \"{}\"  reads: {}  writes: {}",
+                    i + 1,
+                    key["key"].as_str().unwrap_or_default(),
+                    key["reads"],
+                    key["writes"]
+                ));
+            }
+        }
+        Ok(Reply::new(output.join("\n"), res))
     }
 
-    async fn cmd_ping(&self) -> Result<String> {
+    async fn cmd_slowlog(&self, args: &[String]) -> Result<Reply> {
+        const USAGE: &str = "Usage: SLOWLOG GET [count] | LEN | RESET";
+        match args.first().map(|s| s.to_uppercase()).as_deref() {
+            Some("GET") => {
+                let count = match args.get(1) {
+                    Some(count) => Some(count.parse::<u64>().context("Count must be a number")?),
+                    None => None,
+                };
+                let res = self.send("slowlog.get", json!({"count": count})).await?;
+                Ok(Reply::new(
+                    format_slowlog(&res["entries"].as_array().cloned().unwrap_or_default()),
+                    res,
+                ))
+            }
+            Some("LEN") => {
+                let res = self.send("slowlog.len", json!({})).await?;
+                Ok(Reply::new(format!("(integer) {}", res["len"]), res))
+            }
+            Some("RESET") => {
+                let res = self.send("slowlog.reset", json!({})).await?;
+                Ok(Reply::new(
+                    format!(
+                        "{} ({} entries cleared)",
+                        "OK".green(),
+                        res["cleared"].as_u64().unwrap_or(0)
+                    ),
+                    res,
+                ))
+            }
+            _ => Err(anyhow::anyhow!(USAGE)),
+        }
+    }
+
+    async fn cmd_ping(&self) -> Result<Reply> {
         // Use kv.dbsize as a connectivity check — works on all transports.
         match self.send("kv.dbsize", json!({})).await {
-            Ok(_) => Ok("PONG".green().to_string()),
-            Err(_) => Ok("Server unreachable".red().to_string()),
+            Ok(_) => Ok(Reply::new(
+                "PONG".green().to_string(),
+                json!({"pong": true}),
+            )),
+            // Scripts need a failing exit code rather than a message
+            Err(e) if self.json => Err(e),
+            Err(_) => Ok(Reply::new(
+                "Server unreachable".red().to_string(),
+                json!({"pong": false}),
+            )),
         }
     }
 
-    async fn cmd_mset(&self, args: &[String]) -> Result<String> {
+    async fn cmd_mset(&self, args: &[String]) -> Result<Reply> {
         if args.len() < 2 || !args.len().is_multiple_of(2) {
             return Err(anyhow::anyhow!("Usage: MSET key value [key value ...]"));
         }
@@ -296,11 +657,11 @@ impl CliClient {
             .chunks(2)
             .map(|c| json!({"key": c[0], "value": c[1]}))
             .collect();
-        self.send("kv.mset", json!({"pairs": pairs})).await?;
-        Ok("OK".green().to_string())
+        let res = self.send("kv.mset", json!({"pairs": pairs})).await?;
+        Ok(Reply::new("OK".green().to_string(), res))
     }
 
-    async fn cmd_mget(&self, args: &[String]) -> Result<String> {
+    async fn cmd_mget(&self, args: &[String]) -> Result<Reply> {
         if args.is_empty() {
             return Err(anyhow::anyhow!("Usage: MGET key [key ...]"));
         }
@@ -319,11 +680,567 @@ impl CliClient {
             })
             .collect::<Vec<_>>()
             .join("\n");
-        Ok(output)
+        Ok(Reply::new(output, res))
+    }
+
+    async fn cmd_hset(&self, args: &[String]) -> Result<Reply> {
+        if args.len() < 3 || args.len().is_multiple_of(2) {
+            return Err(anyhow::anyhow!(
+                "Usage: HSET key field value [field value ...]"
+            ));
+        }
+        let mut created = 0;
+        for pair in args[1..].chunks(2) {
+            let res = self
+                .send(
+                    "hash.set",
+                    json!({"key": args[0], "field": pair[0], "value": pair[1]}),
+                )
+                .await?;
+            if res["created"].as_bool().unwrap_or(false) {
+                created += 1;
+            }
+        }
+        Ok(Reply::new(
+            format!("(integer) {}", created),
+            json!({"created": created}),
+        ))
+    }
+
+    async fn cmd_hget(&self, args: &[String]) -> Result<Reply> {
+        let [key, field] = args else {
+            anyhow::bail!("Usage: HGET key field");
+        };
+        let res = self
+            .send("hash.get", json!({"key": key, "field": field}))
+            .await?;
+        if res["found"].as_bool().unwrap_or(false) {
+            Ok(Reply::new(quote(&res["value"]), res))
+        } else {
+            Ok(Reply::new("(nil)".dimmed().to_string(), res))
+        }
+    }
+
+    async fn cmd_hgetall(&self, args: &[String]) -> Result<Reply> {
+        let [key] = args else {
+            anyhow::bail!("Usage: HGETALL key");
+        };
+        let res = self.send("hash.getall", json!({"key": key})).await?;
+        let mut fields: Vec<(&String, &Value)> = res["fields"]
+            .as_object()
+            .map(|fields| fields.iter().collect())
+            .unwrap_or_default();
+        fields.sort_by_key(|(field, _)| *field);
+        let flat: Vec<Value> = fields
+            .into_iter()
+            .flat_map(|(field, value)| [Value::String(field.clone()), value.clone()])
+            .collect();
+        Ok(Reply::new(numbered(&flat), res))
+    }
+
+    async fn cmd_hdel(&self, args: &[String]) -> Result<Reply> {
+        if args.len() < 2 {
+            return Err(anyhow::anyhow!("Usage: HDEL key field [field ...]"));
+        }
+        let res = self
+            .send("hash.del", json!({"key": args[0], "fields": &args[1..]}))
+            .await?;
+        Ok(Reply::new(
+            format!("(integer) {}", res["deleted"].as_u64().unwrap_or(0)),
+            res,
+        ))
+    }
+
+    /// LPUSH / RPUSH, answering with the new length of the list
+    async fn cmd_push(&self, cmd: &str, name: &str, args: &[String]) -> Result<Reply> {
+        if args.len() < 2 {
+            return Err(anyhow::anyhow!("Usage: {} key value [value ...]", name));
+        }
+        let res = self
+            .send(cmd, json!({"key": args[0], "values": &args[1..]}))
+            .await?;
+        Ok(Reply::new(
+            format!("(integer) {}", res["length"].as_u64().unwrap_or(0)),
+            res,
+        ))
+    }
+
+    async fn cmd_lrange(&self, args: &[String]) -> Result<Reply> {
+        let [key, start, stop] = args else {
+            anyhow::bail!("Usage: LRANGE key start stop");
+        };
+        let start = start.parse::<i64>().context("start must be an integer")?;
+        let stop = stop.parse::<i64>().context("stop must be an integer")?;
+        let res = self
+            .send(
+                "list.lrange",
+                json!({"key": key, "start": start, "stop": stop}),
+            )
+            .await?;
+        Ok(Reply::new(
+            numbered(&res["values"].as_array().cloned().unwrap_or_default()),
+            res,
+        ))
+    }
+
+    async fn cmd_sadd(&self, args: &[String]) -> Result<Reply> {
+        if args.len() < 2 {
+            return Err(anyhow::anyhow!("Usage: SADD key member [member ...]"));
+        }
+        let res = self
+            .send("set.add", json!({"key": args[0], "members": &args[1..]}))
+            .await?;
+        Ok(Reply::new(
+            format!("(integer) {}", res["added"].as_u64().unwrap_or(0)),
+            res,
+        ))
+    }
+
+    async fn cmd_smembers(&self, args: &[String]) -> Result<Reply> {
+        let [key] = args else {
+            anyhow::bail!("Usage: SMEMBERS key");
+        };
+        let res = self.send("set.members", json!({"key": key})).await?;
+        let mut members = res["members"].as_array().cloned().unwrap_or_default();
+        members.sort_by_key(|m| m.to_string());
+        Ok(Reply::new(numbered(&members), res))
+    }
+
+    async fn cmd_zadd(&self, args: &[String]) -> Result<Reply> {
+        if args.len() < 3 || args.len().is_multiple_of(2) {
+            return Err(anyhow::anyhow!(
+                "Usage: ZADD key score member [score member ...]"
+            ));
+        }
+        let mut added = 0;
+        for pair in args[1..].chunks(2) {
+            let score = pair[0]
+                .parse::<f64>()
+                .with_context(|| format!("Invalid score '{}'", pair[0]))?;
+            let res = self
+                .send(
+                    "sortedset.zadd",
+                    json!({"key": args[0], "member": pair[1], "score": score}),
+                )
+                .await?;
+            added += res["added"].as_u64().unwrap_or(0);
+        }
+        Ok(Reply::new(
+            format!("(integer) {}", added),
+            json!({"added": added}),
+        ))
+    }
+
+    async fn cmd_zrange(&self, args: &[String]) -> Result<Reply> {
+        const USAGE: &str = "Usage: ZRANGE key start stop [WITHSCORES]";
+        let (key, start, stop, with_scores) = match args {
+            [key, start, stop] => (key, start, stop, false),
+            [key, start, stop, flag] if flag.eq_ignore_ascii_case("WITHSCORES") => {
+                (key, start, stop, true)
+            }
+            _ => anyhow::bail!(USAGE),
+        };
+        let start = start.parse::<i64>().context("start must be an integer")?;
+        let stop = stop.parse::<i64>().context("stop must be an integer")?;
+        let res = self
+            .send(
+                "sortedset.zrange",
+                json!({"key": key, "start": start, "stop": stop, "withscores": with_scores}),
+            )
+            .await?;
+        let members: Vec<Value> = res["members"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|m| {
+                let score = with_scores
+                    .then(|| m["score"].as_f64())
+                    .flatten()
+                    .map(|score| Value::String(score.to_string()));
+                std::iter::once(m["member"].clone()).chain(score)
+            })
+            .collect();
+        Ok(Reply::new(numbered(&members), res))
+    }
+
+    /// Append an event to a stream room, creating the room first if needed
+    async fn cmd_xadd(&self, args: &[String]) -> Result<Reply> {
+        let [room, event, data] = args else {
+            anyhow::bail!("Usage: XADD room event data");
+        };
+        self.send("stream.get_or_create", json!({"room": room}))
+            .await?;
+        // Data that parses as JSON is stored as JSON, anything else as a string
+        let data = serde_json::from_str::<Value>(data).unwrap_or_else(|_| json!(data));
+        let res = self
+            .send(
+                "stream.publish",
+                json!({"room": room, "event": event, "data": data}),
+            )
+            .await?;
+        Ok(Reply::new(
+            format!("(integer) {}", res["offset"].as_u64().unwrap_or(0)),
+            res,
+        ))
+    }
+
+    /// Read events from `from` onwards as the `synap-cli` subscriber
+    async fn cmd_xrange(&self, args: &[String]) -> Result<Reply> {
+        const USAGE: &str = "Usage: XRANGE room [from-offset] [count]";
+        let (room, from, count) = match args {
+            [room] => (room, None, None),
+            [room, from] => (room, Some(from), None),
+            [room, from, count] => (room, Some(from), Some(count)),
+            _ => anyhow::bail!(USAGE),
+        };
+        let from = from
+            .map(|f| f.parse::<u64>().context("from-offset must be a number"))
+            .transpose()?
+            .unwrap_or(0);
+        let count = count
+            .map(|c| c.parse::<u64>().context("count must be a number"))
+            .transpose()?
+            .unwrap_or(100);
+        let res = self
+            .send(
+                "stream.consume",
+                json!({"room": room, "subscriber_id": "synap-cli", "from_offset": from, "limit": count}),
+            )
+            .await?;
+        Ok(Reply::new(
+            format_stream_events(&res["events"].as_array().cloned().unwrap_or_default()),
+            res,
+        ))
+    }
+
+    async fn cmd_queue(&self, args: &[String]) -> Result<Reply> {
+        const USAGE: &str = "Usage: QUEUE CREATE|PUBLISH|CONSUME|ACK|NACK|STATS queue ...";
+        let (Some(sub), Some(queue)) = (args.first(), args.get(1)) else {
+            anyhow::bail!(USAGE);
+        };
+        let queues = self.sdk.queue();
+        let rest = &args[2..];
+        match sub.to_uppercase().as_str() {
+            "CREATE" => {
+                queues.create_queue(queue, None, None).await?;
+                Ok(Reply::ok())
+            }
+            "PUBLISH" => {
+                let (message, priority) = match rest {
+                    [message] => (message, None),
+                    [message, priority] => (
+                        message,
+                        Some(priority.parse::<u8>().context("Priority must be 0-9")?),
+                    ),
+                    _ => anyhow::bail!("Usage: QUEUE PUBLISH queue message [priority]"),
+                };
+                let id = queues
+                    .publish(queue, message.as_bytes(), priority, None)
+                    .await?;
+                Ok(Reply::new(format!("\"{}\"", id), json!({"message_id": id})))
+            }
+            "CONSUME" => {
+                let consumer = match rest {
+                    [] => "synap-cli",
+                    [consumer] => consumer.as_str(),
+                    _ => anyhow::bail!("Usage: QUEUE CONSUME queue [consumer-id]"),
+                };
+                let Some(message) = queues.consume(queue, consumer).await? else {
+                    return Ok(Reply::new("(nil)".dimmed().to_string(), Value::Null));
+                };
+                Ok(Reply::new(
+                    format!(
+                        "id: {}\npriority: {}\nretries: {}/{}\npayload: \"{}\"",
+                        message.id.cyan(),
+                        message.priority,
+                        message.retry_count,
+                        message.max_retries,
+                        String::from_utf8_lossy(&message.payload)
+                    ),
+                    serde_json::to_value(&message)?,
+                ))
+            }
+            verb @ ("ACK" | "NACK") => {
+                let [message_id] = rest else {
+                    anyhow::bail!("Usage: QUEUE {} queue message-id", verb);
+                };
+                if verb == "ACK" {
+                    queues.ack(queue, message_id).await?;
+                } else {
+                    queues.nack(queue, message_id).await?;
+                }
+                Ok(Reply::ok())
+            }
+            "STATS" => {
+                let stats = queues.stats(queue).await?;
+                let text = [
+                    format!("# Queue {}", queue),
+                    format!("depth: {}", stats.depth),
+                    format!("consumers: {}", stats.consumers),
+                    format!("published: {}", stats.published),
+                    format!("consumed: {}", stats.consumed),
+                    format!("acked: {}", stats.acked),
+                    format!("nacked: {}", stats.nacked),
+                    format!("dead_lettered: {}", stats.dead_lettered),
+                ]
+                .join("\n");
+                Ok(Reply::new(text, serde_json::to_value(&stats)?))
+            }
+            _ => Err(anyhow::anyhow!(USAGE)),
+        }
+    }
+
+    async fn cmd_publish(&self, args: &[String]) -> Result<Reply> {
+        let [topic, message] = args else {
+            anyhow::bail!("Usage: PUBLISH topic message");
+        };
+        // Messages that parse as JSON are sent as JSON, anything else as a string
+        let data = serde_json::from_str::<Value>(message).unwrap_or_else(|_| json!(message));
+        let matched = self.sdk.pubsub().publish(topic, data, None, None).await?;
+        Ok(Reply::new(
+            format!("(integer) {}", matched),
+            json!({"subscribers_matched": matched}),
+        ))
     }
 
-    fn help_text() -> Result<String> {
-        Ok(format!(
+    /// Print messages from `topics` as they arrive, until Ctrl-C. With
+    /// `--json` each message is one JSON line on stdout
+    async fn cmd_subscribe(&self, args: &[String]) -> Result<Reply> {
+        if args.is_empty() {
+            anyhow::bail!("Usage: SUBSCRIBE topic [topic ...]");
+        }
+        let (stream, handle) = self.sdk.pubsub().observe("synap-cli", args.to_vec());
+        let mut stream = std::pin::pin!(stream);
+        info!(
+            "Subscribed to {}; press Ctrl-C to stop",
+            args.join(", ").bold()
+        );
+
+        let mut received = 0u64;
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => break,
+                message = stream.next() => match message {
+                    Some(message) if self.json => {
+                        received += 1;
+                        println!("{}", json!({"topic": message.topic, "data": message.data}));
+                    }
+                    Some(message) => {
+                        received += 1;
+                        info!("{} {}", message.topic.cyan(), quote(&message.data));
+                    }
+                    None => break,
+                },
+            }
+        }
+        handle.unsubscribe();
+        Ok(Reply::new(
+            format!("(messages received: {})", received)
+                .dimmed()
+                .to_string(),
+            json!({"received": received}),
+        ))
+    }
+
+    async fn cmd_script(&self, args: &[String]) -> Result<Reply> {
+        match args.first().map(|s| s.to_uppercase()).as_deref() {
+            Some("KILL") => {
+                let res = self.send("script.kill", json!({})).await?;
+                if res["terminated"].as_bool().unwrap_or(false) {
+                    Ok(Reply::new("OK".green().to_string(), res))
+                } else {
+                    Ok(Reply::new("(no script running)".yellow().to_string(), res))
+                }
+            }
+            _ => Err(anyhow::anyhow!("Usage: SCRIPT KILL")),
+        }
+    }
+
+    async fn cmd_cluster(&self, args: &[String]) -> Result<Reply> {
+        match args.first().map(|s| s.to_uppercase()).as_deref() {
+            Some("SETSLOT") => self.cmd_cluster_setslot(&args[1..]).await,
+            Some("MIGRATE") => self.cmd_cluster_migrate(&args[1..]).await,
+            Some("MIGRATIONS") => self.cmd_cluster_migrations().await,
+            Some("THROTTLE") => {
+                let rate = args
+                    .get(1)
+                    .context("Usage: CLUSTER THROTTLE keys-per-sec")?
+                    .parse::<u64>()
+                    .context("Throttle must be a number of keys per second")?;
+                let res = self
+                    .send("cluster.throttle", json!({"max_keys_per_sec": rate}))
+                    .await?;
+                Ok(Reply::new("OK".green().to_string(), res))
+            }
+            _ => Err(anyhow::anyhow!(
+                "Usage: CLUSTER SETSLOT|MIGRATE|MIGRATIONS|THROTTLE ..."
+            )),
+        }
+    }
+
+    async fn cmd_cluster_setslot(&self, args: &[String]) -> Result<Reply> {
+        const USAGE: &str =
+            "Usage: CLUSTER SETSLOT slot[-end] MIGRATING|IMPORTING|NODE node-id | STABLE";
+        let (start, end) = parse_slot_range(args.first().context(USAGE)?)?;
+        let action = args.get(1).context(USAGE)?.to_lowercase();
+        let node_id = args.get(2);
+        if action != "stable" && node_id.is_none() {
+            return Err(anyhow::anyhow!(USAGE));
+        }
+
+        let res = self
+            .send(
+                "cluster.setslot",
+                json!({"slot": start, "end_slot": end, "action": action, "node_id": node_id}),
+            )
+            .await?;
+        Ok(Reply::new(
+            format!(
+                "{} ({} slots)",
+                "OK".green(),
+                res["slots"].as_u64().unwrap_or(0)
+            ),
+            res,
+        ))
+    }
+
+    /// Start the migration, report progress until every slot is copied, then
+    /// hand the range over to the destination
+    async fn cmd_cluster_migrate(&self, args: &[String]) -> Result<Reply> {
+        const USAGE: &str = "Usage: CLUSTER MIGRATE slot[-end] node-id [RATE keys-per-sec]";
+        let (start, end) = parse_slot_range(args.first().context(USAGE)?)?;
+        let to_node = args.get(1).context(USAGE)?;
+        let rate = match args.get(2).map(|s| s.to_uppercase()).as_deref() {
+            Some("RATE") => Some(
+                args.get(3)
+                    .context(USAGE)?
+                    .parse::<u64>()
+                    .context("RATE must be a number of keys per second")?,
+            ),
+            Some(_) => return Err(anyhow::anyhow!(USAGE)),
+            None => None,
+        };
+
+        let res = self
+            .send(
+                "cluster.migrate",
+                json!({"start": start, "end": end, "to_node": to_node, "max_keys_per_sec": rate}),
+            )
+            .await?;
+        let started = res["slots_started"].as_u64().unwrap_or(0);
+        if started == 0 {
+            return Ok(Reply::new(
+                format!("(slots {}-{} already on {})", start, end, to_node)
+                    .dimmed()
+                    .to_string(),
+                res,
+            ));
+        }
+        info!(
+            "Migrating {} slots to {} ({})",
+            started,
+            to_node,
+            match res["max_keys_per_sec"].as_u64().unwrap_or(0) {
+                0 => "unthrottled".to_string(),
+                n => format!("{} keys/s", n),
+            }
+        );
+
+        loop {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let overview = self.send("cluster.migrations", json!({})).await?;
+            let empty = vec![];
+            let ours: Vec<&Value> = overview["migrations"]
+                .as_array()
+                .unwrap_or(&empty)
+                .iter()
+                .filter(|m| {
+                    let slot = m["slot"].as_u64().unwrap_or(u64::MAX);
+                    (start as u64..=end as u64).contains(&slot) && m["to_node"] == to_node.as_str()
+                })
+                .collect();
+
+            if let Some(failed) = ours.iter().find(|m| m["state"] == "Failed") {
+                return Err(anyhow::anyhow!(
+                    "Migration of slot {} failed; run CLUSTER SETSLOT {} STABLE to clear it",
+                    failed["slot"],
+                    failed["slot"]
+                ));
+            }
+
+            let copied = ours.iter().filter(|m| m["copy_done"] == true).count();
+            let keys: u64 = ours
+                .iter()
+                .filter_map(|m| m["keys_migrated"].as_u64())
+                .sum();
+            let total: u64 = ours.iter().filter_map(|m| m["total_keys"].as_u64()).sum();
+            info!(
+                "{}/{} slots copied, {}/{} keys",
+                copied,
+                ours.len(),
+                keys,
+                total
+            );
+            if copied == ours.len() {
+                break;
+            }
+        }
+
+        let res = self
+            .send(
+                "cluster.setslot",
+                json!({"slot": start, "end_slot": end, "action": "node", "node_id": to_node}),
+            )
+            .await?;
+        Ok(Reply::new(
+            format!(
+                "{} slots {}-{} now owned by {}",
+                "OK".green(),
+                start,
+                end,
+                to_node
+            ),
+            res,
+        ))
+    }
+
+    async fn cmd_cluster_migrations(&self) -> Result<Reply> {
+        let res = self.send("cluster.migrations", json!({})).await?;
+        let empty = vec![];
+        let migrations = res["migrations"].as_array().unwrap_or(&empty);
+        if migrations.is_empty() {
+            return Ok(Reply::new("(no migrations)".dimmed().to_string(), res));
+        }
+
+        let mut output: Vec<String> = migrations
+            .iter()
+            .map(|m| {
+                format!(
+                    "slot {} {} -> {} {} {}/{} keys{}",
+                    m["slot"],
+                    m["from_node"].as_str().unwrap_or(""),
+                    m["to_node"].as_str().unwrap_or(""),
+                    m["state"].as_str().unwrap_or(""),
+                    m["keys_migrated"],
+                    m["total_keys"],
+                    if m["copy_done"] == true {
+                        " (copied)"
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .collect();
+        output.push(format!(
+            "{} active, {} copied, throttle {} keys/s (0 = unthrottled)",
+            res["active"], res["copied"], res["max_keys_per_sec"]
+        ));
+        Ok(Reply::new(output.join("\n"), res))
+    }
+
+    fn help_text() -> String {
+        format!(
             r#"{}
 
 {}
@@ -349,12 +1266,94 @@ impl CliClient {
   MGET key [key ...]         Get values of multiple keys
 
 {}
-  FLUSHDB                    Remove all keys from database
+  HSET key field value [field value ...]
+                             Set hash fields
+  HGET key field             Get the value of a hash field
+  HGETALL key                Get all fields and values of a hash
+  HDEL key field [field ...] Delete hash fields
+
+{}
+  LPUSH key value [value ...]
+                             Prepend values to a list
+  RPUSH key value [value ...]
+                             Append values to a list
+  LRANGE key start stop      Get a range of list elements (-1 = last)
+
+{}
+  SADD key member [member ...]
+                             Add members to a set
+  SMEMBERS key               Get all members of a set
+
+{}
+  ZADD key score member [score member ...]
+                             Add members to a sorted set
+  ZRANGE key start stop [WITHSCORES]
+                             Get members by rank, lowest score first
+
+{}
+  XADD room event data       Append an event to a stream (creates the room)
+  XRANGE room [from-offset] [count]
+                             Read events from an offset (default 0, 100 events)
+
+{}
+  QUEUE CREATE queue         Create a queue
+  QUEUE PUBLISH queue message [priority]
+                             Enqueue a message (priority 0-9)
+  QUEUE CONSUME queue [consumer-id]
+                             Take the next message
+  QUEUE ACK queue message-id Acknowledge a consumed message
+  QUEUE NACK queue message-id
+                             Return a consumed message to the queue
+  QUEUE STATS queue          Depth and counters of a queue
+
+{}
+  PUBLISH topic message      Publish to a topic, returns the subscribers matched
+  SUBSCRIBE topic [topic ...]
+                             Print messages as they arrive until Ctrl-C
+                             (wildcards: user.* and user.#)
+
+{}
+  SELECT db                  Switch logical database (interactive only; or -n db)
+  FLUSHDB                    Remove all keys from the selected database
   FLUSHALL                   Remove all keys from all databases
+  DUMP key file              Save one key, with its TTL, to a file
+  RESTORE key file [TTL ms] [REPLACE] [ABSTTL]
+                             Create a key from a file written by DUMP
+  DUMPALL file               Export every store to a portable dump file
+  RESTOREALL file            Import a dump file written by DUMPALL
+
+{}
+  SCRIPT KILL                Stop the Lua scripts currently running
+
+{}
+  CLUSTER SETSLOT slot[-end] MIGRATING|IMPORTING|NODE node-id
+  CLUSTER SETSLOT slot[-end] STABLE
+                             Set the migration state or owner of slots
+  CLUSTER MIGRATE slot[-end] node-id [RATE keys-per-sec]
+                             Move slots to a node, reporting progress
+  CLUSTER MIGRATIONS         Show slot migration progress
+  CLUSTER THROTTLE keys/s    Limit migration speed (0 = unthrottled)
+
+{}
+  WAL DUMP path [options]    Decode a WAL file (no server needed)
+  SNAPSHOT DUMP path [options]
+                             List the keys of a snapshot file
+    --prefix p               Only keys starting with p
+    --since t / --until t    Only writes in a time range (Unix seconds or RFC 3339)
+    --op name[,name]         Only these operations (kv_set, hash_del) or datatypes (kv, hash)
+    --stats [--top n]        Operation mix and the n largest writes instead
+
+{}
+  PROFILE LIST               List the connection profiles (* = current)
+  PROFILE USE name           Reconnect with another profile (interactive only)
 
 {}
   INFO                       Get server statistics
   STATS                      Alias for INFO
+  HOTKEYS [count]            Keys with the most GETs and SETs per interval
+  SLOWLOG GET [count]        Slow commands, newest first, then totals per command
+  SLOWLOG LEN                Number of slow log entries
+  SLOWLOG RESET              Clear the slow log
   PING                       Ping the server
   HELP                       Show this help message
   QUIT                       Exit the CLI
@@ -364,16 +1363,241 @@ impl CliClient {
   --url resp3://host:6379    RESP3 Redis-compatible protocol
   --url http://host:15500    HTTP/REST (default)
   -p 15501 --transport rpc   Shortcut for SynapRPC
+  --profile prod             Connect with a profile from ~/.config/synap/config.toml
 "#,
             "Synap CLI - Available Commands".bold().cyan(),
             "Basic Commands:".bold(),
             "TTL Commands:".bold(),
             "Key Discovery:".bold(),
             "Batch Commands:".bold(),
+            "Hash Commands:".bold(),
+            "List Commands:".bold(),
+            "Set Commands:".bold(),
+            "Sorted Set Commands:".bold(),
+            "Stream Commands:".bold(),
+            "Queue Commands:".bold(),
+            "Pub/Sub Commands:".bold(),
             "Database Commands:".bold(),
+            "Scripting Commands:".bold(),
+            "Cluster Commands:".bold(),
+            "Offline Commands:".bold(),
+            "Profile Commands:".bold(),
             "Server Commands:".bold(),
             "Transport Options:".bold(),
-        ))
+        )
+    }
+}
+
+/// A string value in quotes, anything else as JSON
+fn quote(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{}\"", s),
+        other => format!("\"{}\"", other),
+    }
+}
+
+/// `1) "a"` per line, or `(empty list)`
+fn numbered(values: &[Value]) -> String {
+    if values.is_empty() {
+        return "(empty list)".dimmed().to_string();
+    }
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!("{}) {}", i + 1, quote(v)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Stream events as `offset event data`; data is shown as the JSON it was
+/// published as
+fn format_stream_events(events: &[Value]) -> String {
+    if events.is_empty() {
+        return "(empty list)".dimmed().to_string();
+    }
+    events
+        .iter()
+        .enumerate()
+        .map(|(i, e)| {
+            let bytes: Vec<u8> = e["data"]
+                .as_array()
+                .map(|b| {
+                    b.iter()
+                        .filter_map(|b| b.as_u64())
+                        .map(|b| b as u8)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let data = String::from_utf8_lossy(&bytes);
+            format!(
+                "{}) {} {} {}",
+                i + 1,
+                format!("#{}", e["offset"]).cyan(),
+                e["event"].as_str().unwrap_or_default().bold(),
+                data
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Slow log entries, newest first, followed by their count, slowest and total
+/// duration per command, most frequent first
+fn format_slowlog(entries: &[Value]) -> String {
+    if entries.is_empty() {
+        return "(empty)".dimmed().to_string();
+    }
+    let ms = |us: u64| format!("{:.2}ms", us as f64 / 1000.0);
+
+    let mut output = Vec::new();
+    let mut by_command: Vec<(String, usize, u64, u64)> = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let command = entry["command"].as_str().unwrap_or_default();
+        let duration_us = entry["duration_us"].as_u64().unwrap_or(0);
+        let mut line = format!(
+            "{}) #{} {}  {}  at {}",
+            i + 1,
+            entry["id"],
+            command,
+            ms(duration_us),
+            entry["timestamp"]
+        );
+        if let Some(request_id) = entry["request_id"].as_str() {
+            line.push_str(&format!("  request {}", request_id));
+        }
+        output.push(line);
+
+        match by_command.iter_mut().find(|(name, ..)| name == command) {
+            Some((_, count, max, total)) => {
+                *count += 1;
+                *max = (*max).max(duration_us);
+                *total += duration_us;
+            }
+            None => by_command.push((command.to_string(), 1, duration_us, duration_us)),
+        }
+    }
+
+    by_command.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.3.cmp(&a.3)));
+    output.push(String::new());
+    output.push("# By command".to_string());
+    for (command, count, max, total) in by_command {
+        output.push(format!(
+            "{}  {} entries  max {}  total {}",
+            command,
+            count,
+            ms(max),
+            ms(total)
+        ));
+    }
+    output.join("\n")
+}
+
+/// Parse `slot` or `start-end`
+fn parse_slot_range(arg: &str) -> Result<(u16, u16)> {
+    let (start, end) = arg.split_once('-').unwrap_or((arg, arg));
+    let start = start
+        .parse::<u16>()
+        .with_context(|| format!("Invalid slot '{}'", start))?;
+    let end = end
+        .parse::<u16>()
+        .with_context(|| format!("Invalid slot '{}'", end))?;
+    if start > end {
+        return Err(anyhow::anyhow!("Invalid slot range {}-{}", start, end));
+    }
+    Ok((start, end))
+}
+
+/// `PROFILE LIST` or `PROFILE USE name`
+enum ProfileCommand<'a> {
+    List,
+    Use(&'a str),
+}
+
+impl<'a> ProfileCommand<'a> {
+    fn parse(args: &'a [String]) -> Result<Self> {
+        match args {
+            [sub] if sub.eq_ignore_ascii_case("LIST") => Ok(Self::List),
+            [sub, name] if sub.eq_ignore_ascii_case("USE") => Ok(Self::Use(name)),
+            _ => Err(anyhow::anyhow!("Usage: PROFILE LIST | PROFILE USE name")),
+        }
+    }
+}
+
+/// `PROFILE LIST` output, marking the current profile
+fn list_profiles(profiles: &ProfileFile, current: Option<&str>) -> Reply {
+    let json = json!({
+        "current": current,
+        "profiles": profiles.profiles.keys().collect::<Vec<_>>(),
+    });
+    if profiles.profiles.is_empty() {
+        return Reply::new("(no profiles)".dimmed().to_string(), json);
+    }
+    let text = profiles
+        .profiles
+        .iter()
+        .map(|(name, profile)| {
+            let marker = if current == Some(name.as_str()) {
+                "*"
+            } else {
+                " "
+            };
+            format!("{} {} ({})", marker, name.bold(), profile.describe())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Reply::new(text, json)
+}
+
+/// Print a reply: its JSON on stdout with `--json`, else the formatted text
+fn print_reply(json: bool, reply: &Reply) {
+    if json {
+        println!("{}", reply.json);
+    } else {
+        info!("{}", reply.text);
+    }
+}
+
+/// Print an error: `{"error": ..., "code": ...}` on stderr with `--json`,
+/// `code` being the server's error code when it sent one
+fn print_error(json: bool, e: &anyhow::Error) {
+    if json {
+        let code = e.downcast_ref::<SynapError>().and_then(|e| e.error_code());
+        eprintln!("{}", json!({"error": e.to_string(), "code": code}));
+    } else {
+        error!(error = %e, "{}", format!("Error: {}", e).red());
+    }
+}
+
+/// Split a command line into arguments. Double or single quotes keep spaces
+/// in one argument; inside double quotes `\"` and `\\` are escapes.
+fn split_line(line: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            return Ok(args);
+        };
+        let mut arg = String::new();
+        if first == '"' || first == '\'' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some(c) if c == first => break,
+                    Some('\\') if first == '"' => match chars.next() {
+                        Some(c) => arg.push(c),
+                        None => anyhow::bail!("Unterminated quote in: {}", line),
+                    },
+                    Some(c) => arg.push(c),
+                    None => anyhow::bail!("Unterminated quote in: {}", line),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                arg.push(c);
+            }
+        }
+        args.push(arg);
     }
 }
 
@@ -382,8 +1606,17 @@ impl CliClient {
 #[tokio::main]
 async fn main() -> Result<()> {
     let log_level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+    let args = Args::parse();
+    // With --json stdout carries only replies, so logs go to stderr
+    let json = args.json;
     tracing_subscriber::fmt()
-        .with_writer(std::io::stdout)
+        .with_writer(move || -> Box<dyn std::io::Write> {
+            if json {
+                Box::new(std::io::stderr())
+            } else {
+                Box::new(std::io::stdout())
+            }
+        })
         .with_env_filter(tracing_subscriber::EnvFilter::new(log_level))
         .with_target(false)
         .with_thread_ids(false)
@@ -391,43 +1624,121 @@ async fn main() -> Result<()> {
         .with_file(false)
         .with_line_number(false)
         .init();
+    if args.json || args.pipe {
+        colored::control::set_override(false);
+    }
 
-    let args = Args::parse();
-    let url = args.effective_url();
-    let client = CliClient::new(&url)?;
+    let profiles = match args.config.clone().or_else(profile::default_path) {
+        Some(path) => ProfileFile::load(&path)?,
+        None => ProfileFile::default(),
+    };
+    let current = args.profile.clone().or_else(|| profiles.default.clone());
+    let selected = current
+        .as_deref()
+        .map(|name| profiles.get(name))
+        .transpose()?;
+    let profile = args.effective_profile(selected);
+    let client = CliClient::new(&profile, json)?;
 
-    if !args.command.is_empty() {
+    if args.pipe {
+        run_pipe(client).await
+    } else if !args.command.is_empty() {
         let cmd = &args.command[0];
         let cmd_args: Vec<String> = args.command[1..].to_vec();
-        match client.execute_command(cmd, &cmd_args).await {
-            Ok(output) => {
-                info!("{}", output);
+        let result = if cmd.eq_ignore_ascii_case("PROFILE") {
+            ProfileCommand::parse(&cmd_args).and_then(|command| match command {
+                ProfileCommand::List => Ok(list_profiles(&profiles, current.as_deref())),
+                ProfileCommand::Use(_) => Err(anyhow::anyhow!(
+                    "PROFILE USE only works in interactive mode; pass --profile instead"
+                )),
+            })
+        } else {
+            client.execute_command(cmd, &cmd_args).await
+        };
+        match result {
+            Ok(reply) => {
+                print_reply(json, &reply);
                 Ok(())
             }
             Err(e) => {
-                error!(error = %e, "{}", format!("Error: {}", e).red());
+                print_error(json, &e);
                 std::process::exit(1);
             }
         }
     } else {
-        run_interactive(client, &url).await
+        run_interactive(client, &profile, profiles, current).await
+    }
+}
+
+/// `--pipe`: run the commands read from stdin in order. Blank lines and
+/// lines starting with `#` are skipped; a failed command does not stop the
+/// rest, but makes the exit code non-zero.
+async fn run_pipe(mut client: CliClient) -> Result<()> {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut failed = false;
+    while let Some(line) = lines.next_line().await? {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let result = match split_line(&line) {
+            Ok(parts) => match parts.split_first() {
+                None => continue,
+                Some((cmd, args)) if cmd.eq_ignore_ascii_case("SELECT") => {
+                    client.select(args).await
+                }
+                Some((cmd, args)) => client.execute_command(cmd, args).await,
+            },
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(reply) => print_reply(client.json, &reply),
+            Err(e) => {
+                failed = true;
+                print_error(client.json, &e);
+            }
+        }
     }
+    if failed {
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
-async fn run_interactive(client: CliClient, url: &str) -> Result<()> {
+async fn run_interactive(
+    mut client: CliClient,
+    profile: &Profile,
+    profiles: ProfileFile,
+    mut current: Option<String>,
+) -> Result<()> {
+    let mut url = profile.url();
     info!(
         "{}",
         format!("Synap CLI v{}", env!("CARGO_PKG_VERSION"))
             .bold()
             .cyan()
     );
-    info!("Connected to {}", url);
+    match &current {
+        Some(name) => info!("Connected to {} (profile {})", url, name),
+        None => info!("Connected to {}", url),
+    }
     info!("Type {} for available commands\n", "HELP".bold());
 
-    let mut rl = DefaultEditor::new()?;
+    let config = Config::builder()
+        .completion_type(CompletionType::List)
+        .build();
+    let mut rl: Editor<CliHelper, DefaultHistory> = Editor::with_config(config)?;
+    let mut helper = CliHelper::new(profiles.names().into_iter().map(String::from).collect());
+    helper.connect(&client.sdk, &client.namespace);
+    rl.set_helper(Some(helper));
 
     loop {
-        let prompt = format!("{}> ", url.green());
+        let target = current.as_deref().unwrap_or(&url);
+        let prompt = match client.sdk.db() {
+            0 => format!("{}> ", target.green()),
+            db => format!("{}> ", format!("{}[{}]", target, db).green()),
+        };
         let readline = rl.readline(&prompt);
 
         match readline {
@@ -438,10 +1749,14 @@ async fn run_interactive(client: CliClient, url: &str) -> Result<()> {
 
                 rl.add_history_entry(&line)?;
 
-                let parts: Vec<String> = line.split_whitespace().map(String::from).collect();
-                if parts.is_empty() {
-                    continue;
-                }
+                let parts = match split_line(&line) {
+                    Ok(parts) if parts.is_empty() => continue,
+                    Ok(parts) => parts,
+                    Err(e) => {
+                        print_error(client.json, &e);
+                        continue;
+                    }
+                };
 
                 let cmd = &parts[0];
                 let args = &parts[1..];
@@ -451,11 +1766,49 @@ async fn run_interactive(client: CliClient, url: &str) -> Result<()> {
                     break;
                 }
 
-                match client.execute_command(cmd, args).await {
-                    Ok(output) => info!("{}", output),
-                    Err(e) => {
-                        error!(error = %e, "{}", format!("Error: {}", e).red());
+                if cmd.eq_ignore_ascii_case("SELECT") {
+                    match client.select(args).await {
+                        Ok(reply) => {
+                            if let Some(helper) = rl.helper_mut() {
+                                helper.connect(&client.sdk, &client.namespace);
+                            }
+                            print_reply(client.json, &reply)
+                        }
+                        Err(e) => print_error(client.json, &e),
+                    }
+                    continue;
+                }
+
+                if cmd.eq_ignore_ascii_case("PROFILE") {
+                    match ProfileCommand::parse(args) {
+                        Ok(ProfileCommand::List) => {
+                            print_reply(client.json, &list_profiles(&profiles, current.as_deref()))
+                        }
+                        Ok(ProfileCommand::Use(name)) => {
+                            match profiles
+                                .get(name)
+                                .and_then(|p| Ok((CliClient::new(p, client.json)?, p)))
+                            {
+                                Ok((switched, p)) => {
+                                    client = switched;
+                                    if let Some(helper) = rl.helper_mut() {
+                                        helper.connect(&client.sdk, &client.namespace);
+                                    }
+                                    url = p.url();
+                                    current = Some(name.to_string());
+                                    info!("Connected to {} (profile {})", url, name);
+                                }
+                                Err(e) => print_error(client.json, &e),
+                            }
+                        }
+                        Err(e) => print_error(client.json, &e),
                     }
+                    continue;
+                }
+
+                match client.execute_command(cmd, args).await {
+                    Ok(reply) => print_reply(client.json, &reply),
+                    Err(e) => print_error(client.json, &e),
                 }
             }
             Err(ReadlineError::Interrupted) => {
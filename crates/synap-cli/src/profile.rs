@@ -0,0 +1,222 @@
+//! Named connection profiles (`--profile prod`, `PROFILE LIST/USE`), read
+//! from `~/.config/synap/config.toml` so credentials stay out of shell
+//! history:
+//!
+//! ```toml
+//! default = "local"
+//!
+//! [profiles.local]
+//! host = "127.0.0.1"
+//!
+//! [profiles.prod]
+//! url = "https://synap.example.com:15500"
+//! api_key = "sk_live_..."
+//! namespace = "tenant-a:"
+//!
+//! [profiles.prod.tls]
+//! ca_cert = "/etc/synap/ca.pem"
+//!
+//! [profiles.staging]
+//! host = "staging.internal"
+//! username = "ops"
+//! password = "..."
+//! tls = { enabled = true }
+//! ```
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use synap_sdk::SynapConfig;
+
+/// The profiles file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileFile {
+    /// Profile used when `--profile` is not given
+    pub default: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// One named connection
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Full server URL; wins over `host`/`port`/`transport`
+    pub url: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    /// http, rpc or resp3
+    pub transport: Option<String>,
+    pub api_key: Option<String>,
+    /// HTTP Basic Auth user; used instead of `api_key`
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Prefix prepended to every key the CLI reads or writes
+    pub namespace: Option<String>,
+    /// Logical database; 0 when unset
+    pub db: Option<u32>,
+    #[serde(default)]
+    pub tls: ProfileTls,
+}
+
+/// TLS settings for `https://` profiles
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileTls {
+    /// Connect with `https://` instead of `http://`
+    #[serde(default)]
+    pub enabled: bool,
+    /// PEM bundle of extra root certificates
+    pub ca_cert: Option<PathBuf>,
+    /// PEM client certificate for mutual TLS
+    pub client_cert: Option<PathBuf>,
+    /// PEM PKCS #8 key for `client_cert`
+    pub client_key: Option<PathBuf>,
+    /// Skip certificate verification (development only)
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+/// `$XDG_CONFIG_HOME/synap/config.toml`, falling back to
+/// `~/.config/synap/config.toml`
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+    Some(base.join("synap").join("config.toml"))
+}
+
+impl ProfileFile {
+    /// Read the profiles file. A missing file is an empty one.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let file: Self =
+            toml::from_str(&text).with_context(|| format!("Invalid {}", path.display()))?;
+        if let Some(default) = &file.default
+            && !file.profiles.contains_key(default)
+        {
+            bail!(
+                "{}: default profile '{}' is not defined",
+                path.display(),
+                default
+            );
+        }
+        Ok(file)
+    }
+
+    /// The named profile
+    pub fn get(&self, name: &str) -> Result<&Profile> {
+        self.profiles.get(name).with_context(|| {
+            format!(
+                "Unknown profile '{}' (known: {})",
+                name,
+                self.names().join(", ")
+            )
+        })
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.profiles.keys().map(String::as_str).collect()
+    }
+}
+
+impl Profile {
+    /// Server URL, defaulting to `http://127.0.0.1:15500`; `tls.enabled`
+    /// turns `http://` into `https://`
+    pub fn url(&self) -> String {
+        if let Some(url) = &self.url {
+            return match url.strip_prefix("http://") {
+                Some(rest) if self.tls.enabled => format!("https://{}", rest),
+                _ => url.clone(),
+            };
+        }
+        let host = self.host.as_deref().unwrap_or("127.0.0.1");
+        let port = self.port.unwrap_or(15500);
+        match self
+            .transport
+            .as_deref()
+            .unwrap_or("http")
+            .to_lowercase()
+            .as_str()
+        {
+            "rpc" | "synap" | "synaprpc" => format!("synap://{}:{}", host, port),
+            "resp3" | "redis" => format!("resp3://{}:{}", host, port),
+            _ if self.tls.enabled => format!("https://{}:{}", host, port),
+            _ => format!("http://{}:{}", host, port),
+        }
+    }
+
+    /// SDK configuration with the profile's credentials and TLS files
+    pub fn sdk_config(&self) -> Result<SynapConfig> {
+        let url = self.url();
+        if self.tls.enabled && !url.starts_with("https://") {
+            bail!(
+                "TLS is only supported over HTTP; {} is a plain TCP transport",
+                url
+            );
+        }
+        let mut config = SynapConfig::new(url);
+        match (&self.api_key, &self.username, &self.password) {
+            (Some(_), Some(_), _) => bail!("Set either an API key or a username, not both"),
+            (Some(api_key), None, _) => config = config.with_api_key(api_key),
+            (None, Some(username), Some(password)) => {
+                config = config.with_basic_auth(username, password);
+            }
+            (None, Some(username), None) => bail!("No password given for user '{}'", username),
+            (None, None, Some(_)) => bail!("A password needs a username"),
+            (None, None, None) => {}
+        }
+        let read = |path: &PathBuf| {
+            std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+        };
+        if let Some(ca_cert) = &self.tls.ca_cert {
+            config = config.with_root_ca_pem(read(ca_cert)?);
+        }
+        match (&self.tls.client_cert, &self.tls.client_key) {
+            (Some(cert), Some(key)) => {
+                config = config.with_client_cert_pem(read(cert)?, read(key)?);
+            }
+            (None, None) => {}
+            _ => bail!("tls.client_cert and tls.client_key must be set together"),
+        }
+        if self.tls.insecure {
+            config = config.with_danger_accept_invalid_certs();
+        }
+        if let Some(db) = self.db {
+            config = config.with_db(db);
+        }
+        Ok(config)
+    }
+
+    /// One-line summary for `PROFILE LIST`; never shows the API key
+    pub fn describe(&self) -> String {
+        let mut parts = vec![self.url()];
+        if self.api_key.is_some() {
+            parts.push("api key".to_string());
+        }
+        if let Some(username) = &self.username {
+            parts.push(format!("user {}", username));
+        }
+        if let Some(namespace) = &self.namespace {
+            parts.push(format!("namespace {}", namespace));
+        }
+        if let Some(db) = self.db.filter(|db| *db != 0) {
+            parts.push(format!("db {}", db));
+        }
+        if self.tls.insecure {
+            parts.push("insecure tls".to_string());
+        }
+        parts.join(", ")
+    }
+}
@@ -127,6 +127,10 @@ impl<K: Hash + Eq + Clone, V: Clone> ArcCache<K, V> {
         }
     }
 
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.t1.contains_key(key) || self.t2.contains_key(key)
+    }
+
     pub fn len(&self) -> usize {
         self.t1.len() + self.t2.len()
     }
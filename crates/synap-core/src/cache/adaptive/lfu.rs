@@ -68,6 +68,10 @@ impl<K: Hash + Eq + Clone, V: Clone> LfuCache<K, V> {
         }
     }
 
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.cache.contains_key(key)
+    }
+
     pub fn len(&self) -> usize {
         self.cache.len()
     }
@@ -5,7 +5,8 @@
 //! - LFU (Least Frequently Used)
 //! - ARC (Adaptive Replacement Cache)
 //!
-//! The system can adapt between strategies based on workload patterns
+//! The system can adapt between strategies based on workload patterns, and
+//! [`simulator`] replays sampled access traces against each of them
 
 use std::collections::{HashMap, VecDeque};
 use std::hash::Hash;
@@ -13,9 +14,11 @@ use std::time::Instant;
 
 pub mod arc;
 pub mod lfu;
+pub mod simulator;
 
 pub use arc::ArcCache;
 pub use lfu::LfuCache;
+pub use simulator::{AccessSampler, SimulationResult};
 
 /// Cache eviction strategy
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -28,6 +31,28 @@ pub enum CacheStrategy {
     Arc,
 }
 
+impl CacheStrategy {
+    /// All strategies, in declaration order
+    pub const ALL: [CacheStrategy; 3] =
+        [CacheStrategy::Lru, CacheStrategy::Lfu, CacheStrategy::Arc];
+
+    /// Lowercase name ("lru", "lfu", "arc")
+    pub fn name(&self) -> &'static str {
+        match self {
+            CacheStrategy::Lru => "lru",
+            CacheStrategy::Lfu => "lfu",
+            CacheStrategy::Arc => "arc",
+        }
+    }
+
+    /// Parse a strategy name, case-insensitively
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.name().eq_ignore_ascii_case(name))
+    }
+}
+
 /// Cache statistics for adaptive selection
 #[derive(Debug, Clone, Default)]
 pub struct CacheStats {
@@ -127,6 +152,13 @@ impl<K: Hash + Eq + Clone, V: Clone> AdaptiveCache<K, V> {
         cache
     }
 
+    /// Create a cache that stays on `strategy` instead of adapting
+    pub fn pinned(capacity: usize, strategy: CacheStrategy) -> Self {
+        let mut cache = Self::new(capacity, strategy);
+        cache.evaluation_window = u64::MAX;
+        cache
+    }
+
     /// Get value from cache
     pub fn get(&mut self, key: &K) -> Option<V> {
         let result = match self.current_strategy {
@@ -156,12 +188,20 @@ impl<K: Hash + Eq + Clone, V: Clone> AdaptiveCache<K, V> {
             CacheStrategy::Lru => self.insert_lru(key, value),
             CacheStrategy::Lfu => {
                 if let Some(cache) = self.lfu_cache.as_mut() {
+                    let evicts = cache.len() >= self.capacity && !cache.contains_key(&key);
                     cache.insert(key, value);
+                    if evicts && let Some(stats) = self.stats.get_mut(&CacheStrategy::Lfu) {
+                        stats.record_eviction();
+                    }
                 }
             }
             CacheStrategy::Arc => {
                 if let Some(cache) = self.arc_cache.as_mut() {
+                    let evicts = cache.len() >= self.capacity && !cache.contains_key(&key);
                     cache.insert(key, value);
+                    if evicts && let Some(stats) = self.stats.get_mut(&CacheStrategy::Arc) {
+                        stats.record_eviction();
+                    }
                 }
             }
         }
@@ -0,0 +1,212 @@
+//! Eviction Simulation
+//!
+//! Predicts how a candidate eviction strategy would behave on the live
+//! workload before it is switched on:
+//! - [`AccessSampler`] records reads for a fixed, hash-selected subset of keys
+//!   (1 in `sample_rate`), so every access to a sampled key is kept and its
+//!   reuse pattern is preserved
+//! - [`simulate`] replays that trace through a pinned [`AdaptiveCache`] sized
+//!   to the same fraction of the target capacity, and reports the resulting
+//!   [`CacheStats`]
+//!
+//! Scaling keys and capacity down by the same factor keeps the projected hit
+//! rate close to the full-size one, at a fraction of the memory and CPU.
+
+use super::{AdaptiveCache, CacheStats, CacheStrategy};
+use crate::core::EvictionPolicy;
+use ahash::RandomState;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// Fixed seeds so the sampled key subset is stable for the process lifetime.
+const SAMPLER_SEEDS: (u64, u64, u64, u64) = (
+    0x5ca1_ab1e_0000_0001,
+    0x5ca1_ab1e_0000_0002,
+    0x5ca1_ab1e_0000_0003,
+    0x5ca1_ab1e_0000_0004,
+);
+
+/// Bounded trace of reads to a hash-sampled subset of keys
+pub struct AccessSampler {
+    sample_rate: u32,
+    max_samples: usize,
+    hasher: RandomState,
+    trace: Mutex<VecDeque<String>>,
+}
+
+impl AccessSampler {
+    /// Sample 1 in `sample_rate` keys, keeping the last `max_samples` accesses
+    pub fn new(sample_rate: u32, max_samples: usize) -> Self {
+        let (k0, k1, k2, k3) = SAMPLER_SEEDS;
+        Self {
+            sample_rate: sample_rate.max(1),
+            max_samples: max_samples.max(1),
+            hasher: RandomState::with_seeds(k0, k1, k2, k3),
+            trace: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Record an access to `key` if it belongs to the sampled subset
+    #[inline]
+    pub fn record(&self, key: &str) {
+        if !self.is_sampled(key) {
+            return;
+        }
+
+        let mut trace = self.trace.lock();
+        if trace.len() >= self.max_samples {
+            trace.pop_front();
+        }
+        trace.push_back(key.to_string());
+    }
+
+    /// Whether accesses to `key` are recorded
+    #[inline]
+    pub fn is_sampled(&self, key: &str) -> bool {
+        self.sample_rate == 1
+            || self
+                .hasher
+                .hash_one(key)
+                .is_multiple_of(self.sample_rate as u64)
+    }
+
+    /// One in how many keys is sampled
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Maximum number of accesses kept
+    pub fn max_samples(&self) -> usize {
+        self.max_samples
+    }
+
+    pub fn len(&self) -> usize {
+        self.trace.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trace.lock().is_empty()
+    }
+
+    /// Copy of the recorded trace, oldest access first
+    pub fn snapshot(&self) -> Vec<String> {
+        self.trace.lock().iter().cloned().collect()
+    }
+
+    /// Drop the recorded trace
+    pub fn clear(&self) {
+        self.trace.lock().clear();
+    }
+}
+
+/// Outcome of replaying a trace against one strategy
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    pub strategy: CacheStrategy,
+    /// Capacity of the simulated cache, in entries
+    pub capacity: usize,
+    pub stats: CacheStats,
+}
+
+/// Replay `trace` through a cache of `capacity` entries using `strategy`.
+///
+/// Each access is a read; a miss loads the key into the cache, as a
+/// cache-aside client refilling from the source of truth would.
+pub fn simulate<K: Hash + Eq + Clone>(
+    trace: &[K],
+    capacity: usize,
+    strategy: CacheStrategy,
+) -> SimulationResult {
+    let capacity = capacity.max(1);
+    let mut cache = AdaptiveCache::pinned(capacity, strategy);
+
+    for key in trace {
+        if cache.get(key).is_none() {
+            cache.insert(key.clone(), ());
+        }
+    }
+
+    SimulationResult {
+        strategy,
+        capacity,
+        stats: cache.get_stats().clone(),
+    }
+}
+
+/// Strategy modelling a KV eviction policy, if one does.
+///
+/// Random and TTL-ordered policies (and `noeviction`) have no counterpart.
+pub fn strategy_for_policy(policy: EvictionPolicy) -> Option<CacheStrategy> {
+    match policy {
+        EvictionPolicy::AllKeysLru | EvictionPolicy::VolatileLru => Some(CacheStrategy::Lru),
+        EvictionPolicy::AllKeysLfu | EvictionPolicy::VolatileLfu => Some(CacheStrategy::Lfu),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampler_keeps_stable_subset_and_bound() {
+        let sampler = AccessSampler::new(4, 10);
+        let sampled: Vec<String> = (0..1000)
+            .map(|i| format!("key:{i}"))
+            .filter(|k| sampler.is_sampled(k))
+            .collect();
+
+        // Roughly a quarter of the keys, always the same ones.
+        assert!(sampled.len() > 150 && sampled.len() < 350);
+        assert!(sampled.iter().all(|k| sampler.is_sampled(k)));
+
+        for key in &sampled {
+            sampler.record(key);
+        }
+        sampler.record(&sampled[0]);
+        assert_eq!(sampler.len(), 10);
+        assert_eq!(sampler.snapshot().last(), Some(&sampled[0]));
+
+        sampler.clear();
+        assert!(sampler.is_empty());
+    }
+
+    #[test]
+    fn test_simulate_frequency_skewed_trace() {
+        // Two hot keys, each read twice in a row, interleaved with a scan of
+        // cold keys that never repeat: LFU keeps the hot keys, LRU lets the
+        // scan flush them so only the back-to-back rereads hit.
+        let mut trace = Vec::new();
+        for i in 0..500 {
+            for key in ["hot:1", "hot:1", "hot:2", "hot:2"] {
+                trace.push(key.to_string());
+            }
+            for j in 0..3 {
+                trace.push(format!("cold:{i}:{j}"));
+            }
+        }
+
+        let lru = simulate(&trace, 3, CacheStrategy::Lru);
+        let lfu = simulate(&trace, 3, CacheStrategy::Lfu);
+
+        assert_eq!(lru.stats.hits + lru.stats.misses, trace.len() as u64);
+        assert_eq!(lru.stats.hits, 1000);
+        assert!(lru.stats.evictions > 0);
+        assert_eq!(lfu.stats.hits, 2 + 499 * 4);
+        assert!(lfu.stats.evictions > 0);
+    }
+
+    #[test]
+    fn test_strategy_for_policy() {
+        assert_eq!(
+            strategy_for_policy(EvictionPolicy::VolatileLru),
+            Some(CacheStrategy::Lru)
+        );
+        assert_eq!(
+            strategy_for_policy(EvictionPolicy::AllKeysLfu),
+            Some(CacheStrategy::Lfu)
+        );
+        assert_eq!(strategy_for_policy(EvictionPolicy::AllKeysRandom), None);
+    }
+}
@@ -8,5 +8,5 @@
 pub mod adaptive;
 pub mod l2_disk;
 
-pub use adaptive::{AdaptiveCache, CacheStats, CacheStrategy};
+pub use adaptive::{AccessSampler, AdaptiveCache, CacheStats, CacheStrategy, SimulationResult};
 pub use l2_disk::{L2CacheConfig, L2CacheStats, L2DiskCache};
@@ -45,6 +45,11 @@ pub struct ClusterConfig {
     #[serde(default = "default_migration_timeout_secs")]
     pub migration_timeout_secs: u64,
 
+    /// Keys per second slot migrations copy (0 = unthrottled); adjustable at
+    /// runtime through `POST /cluster/migrations/throttle`
+    #[serde(default)]
+    pub migration_max_keys_per_sec: u64,
+
     /// Raft election timeout (milliseconds)
     #[serde(default = "default_raft_election_timeout_ms")]
     pub raft_election_timeout_ms: u64,
@@ -95,6 +100,7 @@ impl Default for ClusterConfig {
             require_full_coverage: true,
             migration_batch_size: 100,
             migration_timeout_secs: 60,
+            migration_max_keys_per_sec: 0,
             raft_election_timeout_ms: 1000,
             raft_heartbeat_interval_ms: 100,
         }
@@ -110,7 +116,8 @@ impl ClusterConfig {
     /// - `SYNAP_CLUSTER_SEEDS` (comma-separated host:port list)
     /// - `SYNAP_CLUSTER_PORT` (u16)
     /// - `SYNAP_CLUSTER_NODE_TIMEOUT_MS`, `SYNAP_CLUSTER_MIGRATION_BATCH_SIZE`,
-    ///   `SYNAP_CLUSTER_MIGRATION_TIMEOUT_SECS`, `SYNAP_CLUSTER_RAFT_ELECTION_TIMEOUT_MS`,
+    ///   `SYNAP_CLUSTER_MIGRATION_TIMEOUT_SECS`, `SYNAP_CLUSTER_MIGRATION_MAX_KEYS_PER_SEC`,
+    ///   `SYNAP_CLUSTER_RAFT_ELECTION_TIMEOUT_MS`,
     ///   `SYNAP_CLUSTER_RAFT_HEARTBEAT_INTERVAL_MS` (integers)
     /// - `SYNAP_CLUSTER_REQUIRE_FULL_COVERAGE` (bool)
     ///
@@ -160,6 +167,9 @@ impl ClusterConfig {
         if let Some(v) = get("SYNAP_CLUSTER_MIGRATION_TIMEOUT_SECS") {
             cfg.migration_timeout_secs = v.parse().unwrap_or(cfg.migration_timeout_secs);
         }
+        if let Some(v) = get("SYNAP_CLUSTER_MIGRATION_MAX_KEYS_PER_SEC") {
+            cfg.migration_max_keys_per_sec = v.parse().unwrap_or(cfg.migration_max_keys_per_sec);
+        }
         if let Some(v) = get("SYNAP_CLUSTER_RAFT_ELECTION_TIMEOUT_MS") {
             cfg.raft_election_timeout_ms = v.parse().unwrap_or(cfg.raft_election_timeout_ms);
         }
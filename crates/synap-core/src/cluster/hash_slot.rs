@@ -3,6 +3,8 @@
 //! Redis-compatible hash slot calculation using CRC16.
 
 use crate::cluster::types::TOTAL_SLOTS;
+use crate::core::SynapError;
+use crate::core::error::Result;
 
 /// CRC16 lookup table (Redis-compatible)
 const CRC16_TABLE: [u16; 256] = [
@@ -84,6 +86,42 @@ pub fn hash_slot(key: &str) -> u16 {
     crc % TOTAL_SLOTS
 }
 
+/// Slot shared by all `keys`, for multi-key commands in cluster mode
+///
+/// Returns `None` for no keys and a `CROSSSLOT` error naming the first two
+/// keys that hash to different slots. Keys with the same hash tag
+/// (`{user:1}.profile`, `{user:1}.sessions`) always share a slot.
+///
+/// # Example
+/// ```
+/// use synap_core::cluster::hash_slot::{hash_slot, same_slot};
+///
+/// let slot = same_slot(["{user:1}.profile", "{user:1}.sessions"]).unwrap();
+/// assert_eq!(slot, Some(hash_slot("user:1")));
+/// assert!(same_slot(["user:1", "user:2"]).is_err());
+/// ```
+pub fn same_slot<I, K>(keys: I) -> Result<Option<u16>>
+where
+    I: IntoIterator<Item = K>,
+    K: AsRef<str>,
+{
+    let mut first: Option<(K, u16)> = None;
+    for key in keys {
+        let slot = hash_slot(key.as_ref());
+        match &first {
+            None => first = Some((key, slot)),
+            Some((first_key, first_slot)) if *first_slot != slot => {
+                return Err(SynapError::ClusterCrossSlot {
+                    first: first_key.as_ref().to_string(),
+                    other: key.as_ref().to_string(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(first.map(|(_, slot)| slot))
+}
+
 /// Hash slot type wrapper
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct HashSlot(u16);
@@ -150,6 +188,24 @@ mod tests {
         assert_ne!(slot1, slot4);
     }
 
+    #[test]
+    fn test_same_slot() {
+        assert_eq!(same_slot(Vec::<String>::new()).unwrap(), None);
+        assert_eq!(
+            same_slot(["{user:1}.profile", "{user:1}.sessions", "user:1"]).unwrap(),
+            Some(hash_slot("user:1"))
+        );
+
+        // An empty tag hashes the whole key
+        assert_eq!(hash_slot("{}.a"), crc16(b"{}.a") % TOTAL_SLOTS);
+
+        let err = same_slot(["{user:1}.profile", "{user:2}.profile"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "CROSSSLOT Keys in request don't hash to the same slot: '{user:1}.profile' and '{user:2}.profile'"
+        );
+    }
+
     #[test]
     fn test_hash_slot_consistency() {
         // Same key should always produce same slot
@@ -5,6 +5,7 @@
 //! - Zero downtime (keys available on both nodes during migration)
 //! - Migration state tracking
 //! - Rollback support
+//! - Throttling (keys per second, adjustable while migrations run)
 
 use super::hash_slot::hash_slot;
 use super::types::{ClusterError, ClusterResult};
@@ -12,6 +13,7 @@ use crate::core::{KVStore, SynapError};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
@@ -44,6 +46,8 @@ pub struct SlotMigration {
     pub keys_migrated: usize,
     /// Total keys to migrate (estimated)
     pub total_keys: usize,
+    /// Every key has been copied; the migration waits for completion
+    pub copy_done: bool,
     /// Started timestamp
     pub started_at: u64,
     /// Completed timestamp
@@ -68,6 +72,9 @@ pub struct SlotMigrationManager {
 
     /// Optional KV store for key migration (set during initialization)
     kv_store: Option<Arc<KVStore>>,
+
+    /// Keys copied per second across migrations (0 = unthrottled)
+    max_keys_per_sec: Arc<AtomicU64>,
 }
 
 enum MigrationCommand {
@@ -84,6 +91,7 @@ enum MigrationCommand {
     Complete {
         slot: u16,
     },
+    AttachKvStore(Arc<KVStore>),
 }
 
 impl SlotMigrationManager {
@@ -104,6 +112,7 @@ impl SlotMigrationManager {
         let migrations = Arc::new(RwLock::new(HashMap::new()));
         let migrations_clone = Arc::clone(&migrations);
         let kv_store_clone = kv_store.clone();
+        let max_keys_per_sec = Arc::new(AtomicU64::new(0));
 
         tokio::spawn(Self::migration_worker(
             migrations_clone,
@@ -111,6 +120,7 @@ impl SlotMigrationManager {
             batch_size,
             timeout,
             kv_store_clone,
+            Arc::clone(&max_keys_per_sec),
         ));
 
         Self {
@@ -119,9 +129,35 @@ impl SlotMigrationManager {
             timeout,
             migration_tx,
             kv_store,
+            max_keys_per_sec,
         }
     }
 
+    /// Hand the KV store to the migration worker.
+    ///
+    /// The store and the manager reference each other (the store asks the
+    /// manager for ASK redirects), so a server builds the manager first and
+    /// attaches the store once it exists. Migrations started before that stay
+    /// pending and run once the store arrives.
+    pub fn attach_kv_store(&self, kv_store: Arc<KVStore>) {
+        let _ = self
+            .migration_tx
+            .send(MigrationCommand::AttachKvStore(kv_store));
+    }
+
+    /// Limit how many keys per second migrations copy (0 = unthrottled).
+    /// Takes effect from the next batch, including for running migrations.
+    pub fn set_throttle(&self, max_keys_per_sec: u64) {
+        self.max_keys_per_sec
+            .store(max_keys_per_sec, Ordering::Relaxed);
+        info!("Slot migration throttle set to {} keys/s", max_keys_per_sec);
+    }
+
+    /// Current throttle in keys per second (0 = unthrottled)
+    pub fn throttle(&self) -> u64 {
+        self.max_keys_per_sec.load(Ordering::Relaxed)
+    }
+
     /// Set KV store for key migration (must be called before migration)
     /// Note: This doesn't update the worker, use new_with_kv_store instead
     pub fn set_kv_store(&mut self, kv_store: Arc<KVStore>) {
@@ -159,21 +195,7 @@ impl SlotMigrationManager {
             return Err(ClusterError::SlotMigrating(slot));
         }
 
-        let migration = SlotMigration {
-            slot,
-            from_node: from_node.clone(),
-            to_node: to_node.clone(),
-            state: MigrationState::Pending,
-            keys_migrated: 0,
-            total_keys: 0,
-            started_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
-            completed_at: None,
-        };
-
-        migrations.insert(slot, migration);
+        migrations.insert(slot, Self::new_migration(slot, &from_node, &to_node));
 
         info!(
             "Starting migration: slot {} from {} to {}",
@@ -189,6 +211,56 @@ impl SlotMigrationManager {
         Ok(())
     }
 
+    /// Start migrating several slots, each from its own source node, to
+    /// `to_node`. Nothing is started if any of the slots already has a
+    /// migration.
+    pub fn start_migrations(&self, slots: &[(u16, String)], to_node: &str) -> ClusterResult<()> {
+        let mut migrations = self.migrations.write();
+
+        if let Some((slot, _)) = slots.iter().find(|(slot, _)| migrations.contains_key(slot)) {
+            return Err(ClusterError::SlotMigrating(*slot));
+        }
+
+        for (slot, from_node) in slots {
+            migrations.insert(*slot, Self::new_migration(*slot, from_node, to_node));
+            let _ = self.migration_tx.send(MigrationCommand::Start {
+                slot: *slot,
+                from_node: from_node.clone(),
+                to_node: to_node.to_string(),
+            });
+        }
+
+        info!("Starting migration of {} slots to {}", slots.len(), to_node);
+        Ok(())
+    }
+
+    fn new_migration(slot: u16, from_node: &str, to_node: &str) -> SlotMigration {
+        SlotMigration {
+            slot,
+            from_node: from_node.to_string(),
+            to_node: to_node.to_string(),
+            state: MigrationState::Pending,
+            keys_migrated: 0,
+            total_keys: 0,
+            copy_done: false,
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            completed_at: None,
+        }
+    }
+
+    /// Forget a slot's migration, whatever its state (`CLUSTER SETSLOT
+    /// STABLE`). A copy still running stops at its next batch.
+    pub fn clear_migration(&self, slot: u16) -> Option<SlotMigration> {
+        let removed = self.migrations.write().remove(&slot);
+        if removed.is_some() {
+            debug!("Cleared migration state for slot {}", slot);
+        }
+        removed
+    }
+
     /// Cancel a migration
     pub fn cancel_migration(&self, slot: u16) -> ClusterResult<()> {
         let mut migrations = self.migrations.write();
@@ -238,6 +310,13 @@ impl SlotMigrationManager {
         migrations.get(&slot).cloned()
     }
 
+    /// All migrations, ordered by slot
+    pub fn list_migrations(&self) -> Vec<SlotMigration> {
+        let mut migrations: Vec<SlotMigration> = self.migrations.read().values().cloned().collect();
+        migrations.sort_by_key(|m| m.slot);
+        migrations
+    }
+
     /// Check if slot is migrating
     pub fn is_migrating(&self, slot: u16) -> bool {
         let migrations = self.migrations.read();
@@ -247,18 +326,23 @@ impl SlotMigrationManager {
             .unwrap_or(false)
     }
 
-    /// Migrate keys for a slot in batches
+    /// Migrate keys for a slot in batches, recording progress after each
+    /// batch. Stops early when the migration is cancelled, completed or
+    /// cleared meanwhile.
     async fn migrate_keys_batch(
         kv_store: &Arc<KVStore>,
+        migrations: &RwLock<HashMap<u16, SlotMigration>>,
         slot: u16,
         batch_size: usize,
-        migration: &mut SlotMigration,
+        max_keys_per_sec: &AtomicU64,
     ) -> Result<usize, SynapError> {
         // Get all keys for this slot
         let all_keys = Self::get_keys_for_slot_internal(kv_store, slot).await?;
 
         let total_keys = all_keys.len();
-        migration.total_keys = total_keys;
+        if let Some(migration) = migrations.write().get_mut(&slot) {
+            migration.total_keys = total_keys;
+        }
 
         if total_keys == 0 {
             debug!("No keys to migrate for slot {}", slot);
@@ -267,37 +351,93 @@ impl SlotMigrationManager {
 
         // Migrate keys in batches
         let mut migrated = 0;
-        let mut batch = Vec::new();
 
-        for key in all_keys {
-            batch.push(key);
+        for batch in all_keys.chunks(batch_size.max(1)) {
+            let running = migrations
+                .read()
+                .get(&slot)
+                .is_some_and(|m| m.state == MigrationState::InProgress);
+            if !running {
+                debug!("Migration of slot {} stopped after {} keys", slot, migrated);
+                break;
+            }
 
-            if batch.len() >= batch_size {
-                // Migrate this batch
-                let batch_migrated = Self::migrate_batch(kv_store, &batch, slot).await?;
-                migrated += batch_migrated;
+            migrated += Self::migrate_batch(kv_store, batch, slot).await?;
+            if let Some(migration) = migrations.write().get_mut(&slot) {
                 migration.keys_migrated = migrated;
+            }
 
-                debug!(
-                    "Migrated batch: {}/{} keys for slot {}",
-                    migrated, total_keys, slot
-                );
+            debug!(
+                "Migrated batch: {}/{} keys for slot {}",
+                migrated, total_keys, slot
+            );
 
-                batch.clear();
+            tokio::time::sleep(Self::batch_pause(
+                batch.len(),
+                max_keys_per_sec.load(Ordering::Relaxed),
+            ))
+            .await;
+        }
 
-                // Small delay between batches to avoid overwhelming the system
-                tokio::time::sleep(Duration::from_millis(10)).await;
-            }
+        Ok(migrated)
+    }
+
+    /// Pause after a batch: long enough to hold the throttle when one is
+    /// set, otherwise a short delay to avoid overwhelming the system
+    fn batch_pause(batch_len: usize, max_keys_per_sec: u64) -> Duration {
+        if max_keys_per_sec == 0 {
+            Duration::from_millis(10)
+        } else {
+            Duration::from_secs_f64(batch_len as f64 / max_keys_per_sec as f64)
         }
+    }
 
-        // Migrate remaining keys
-        if !batch.is_empty() {
-            let batch_migrated = Self::migrate_batch(kv_store, &batch, slot).await?;
-            migrated += batch_migrated;
-            migration.keys_migrated = migrated;
+    /// Copy one slot's keys and record the outcome
+    async fn run_migration(
+        kv_store: &Arc<KVStore>,
+        migrations: &RwLock<HashMap<u16, SlotMigration>>,
+        slot: u16,
+        batch_size: usize,
+        max_keys_per_sec: &AtomicU64,
+    ) {
+        debug!("Migration worker: Starting migration slot {}", slot);
+
+        // Update state to in progress, unless it was cancelled while queued
+        let should_migrate = {
+            let mut migrations = migrations.write();
+            match migrations.get_mut(&slot) {
+                Some(migration) if migration.state == MigrationState::Pending => {
+                    migration.state = MigrationState::InProgress;
+                    true
+                }
+                _ => false,
+            }
+        };
+        if !should_migrate {
+            return;
         }
 
-        Ok(migrated)
+        let result =
+            Self::migrate_keys_batch(kv_store, migrations, slot, batch_size, max_keys_per_sec)
+                .await;
+
+        let mut migrations = migrations.write();
+        if let Some(migration) = migrations.get_mut(&slot) {
+            match result {
+                Ok(migrated) if migration.state == MigrationState::InProgress => {
+                    migration.copy_done = true;
+                    info!(
+                        "Migration copied {}/{} keys for slot {}, waiting for completion",
+                        migrated, migration.total_keys, slot
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!("Migration failed for slot {}: {}", slot, e);
+                    migration.state = MigrationState::Failed;
+                }
+            }
+        }
     }
 
     /// Get keys for a slot (internal helper)
@@ -346,17 +486,11 @@ impl SlotMigrationManager {
         mut migration_rx: mpsc::UnboundedReceiver<MigrationCommand>,
         batch_size: usize,
         _timeout: Duration,
-        kv_store: Option<Arc<KVStore>>,
+        mut kv_store: Option<Arc<KVStore>>,
+        max_keys_per_sec: Arc<AtomicU64>,
     ) {
-        // Wait for KV store to be set
-        let kv_store = match kv_store {
-            Some(store) => store,
-            None => {
-                warn!("Migration worker: KV store not set, migrations will be queued");
-                // Store command and wait
-                return;
-            }
-        };
+        // Slots started before the KV store was attached
+        let mut queued: Vec<u16> = Vec::new();
 
         while let Some(cmd) = migration_rx.recv().await {
             match cmd {
@@ -364,73 +498,31 @@ impl SlotMigrationManager {
                     slot,
                     from_node: _,
                     to_node: _,
-                } => {
-                    debug!("Migration worker: Starting migration slot {}", slot);
-
-                    // Update state to in progress
-                    let should_migrate = {
-                        let mut migrations = migrations.write();
-                        if let Some(migration) = migrations.get_mut(&slot) {
-                            migration.state = MigrationState::InProgress;
-                            true
-                        } else {
-                            false
-                        }
-                    };
-
-                    if should_migrate {
-                        // Perform actual key migration
-                        // Get slot number before async operation
-                        let slot_num = slot;
-
-                        // Clone migration state for async work
-                        let migration_state = {
-                            let migrations = migrations.read();
-                            migrations.get(&slot).cloned()
-                        };
-
-                        if let Some(mut migration) = migration_state {
-                            // Migrate keys
-                            let migration_result = Self::migrate_keys_batch(
-                                &kv_store,
-                                slot_num,
-                                batch_size,
-                                &mut migration,
-                            )
-                            .await;
-
-                            // Update migration state based on result
-                            {
-                                let mut migrations = migrations.write();
-                                if let Some(migration_in_map) = migrations.get_mut(&slot) {
-                                    migration_in_map.keys_migrated = migration.keys_migrated;
-                                    migration_in_map.total_keys = migration.total_keys;
-
-                                    match migration_result {
-                                        Ok(_) => {
-                                            if migration.keys_migrated >= migration.total_keys {
-                                                info!(
-                                                    "Migration complete: {}/{} keys migrated for slot {}",
-                                                    migration.keys_migrated,
-                                                    migration.total_keys,
-                                                    slot
-                                                );
-                                                // All keys migrated, but wait for explicit complete
-                                                debug!(
-                                                    "All keys migrated for slot {}, waiting for completion",
-                                                    slot
-                                                );
-                                            }
-                                        }
-                                        Err(e) => {
-                                            warn!("Migration failed for slot {}: {}", slot, e);
-                                            migration_in_map.state = MigrationState::Failed;
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                } => match &kv_store {
+                    Some(store) => {
+                        Self::run_migration(store, &migrations, slot, batch_size, &max_keys_per_sec)
+                            .await
+                    }
+                    None => {
+                        warn!(
+                            "Migration worker: KV store not set, slot {} stays queued",
+                            slot
+                        );
+                        queued.push(slot);
+                    }
+                },
+                MigrationCommand::AttachKvStore(store) => {
+                    for slot in queued.drain(..) {
+                        Self::run_migration(
+                            &store,
+                            &migrations,
+                            slot,
+                            batch_size,
+                            &max_keys_per_sec,
+                        )
+                        .await;
                     }
+                    kv_store = Some(store);
                 }
                 MigrationCommand::Cancel { slot } => {
                     debug!("Migration worker: Cancelling migration slot {}", slot);
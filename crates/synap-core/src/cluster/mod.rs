@@ -19,9 +19,9 @@ pub mod types;
 pub use config::ClusterConfig;
 pub use discovery::{ClusterDiscovery, start_discovery_server};
 pub use failover::ClusterFailover;
-pub use hash_slot::{HashSlot, hash_slot};
+pub use hash_slot::{HashSlot, hash_slot, same_slot};
 pub use migration::SlotMigrationManager;
-pub use raft::RaftNode;
+pub use raft::{RaftNode, VoteState};
 pub use topology::{ClusterTopology, NodeInfo};
 pub use types::{
     ClusterCommand, ClusterError, ClusterNode, ClusterResult, ClusterState, SlotAssignment,
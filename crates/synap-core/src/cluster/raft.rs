@@ -11,6 +11,9 @@
 //! consensus work (archived phase10/phase11). The `#[allow(dead_code)]` markers
 //! in this module are justified staging artifacts, not debt; see
 //! `.rulebook/decisions/004-keep-cluster-consensus-layer-as-experimental-staged-not-gated-or-removed.md`.
+//!
+//! The vote rules themselves ([`VoteState`]) are live: replication failover
+//! uses them to elect the replica that replaces a dead master.
 
 use super::types::ClusterResult;
 use parking_lot::RwLock;
@@ -39,6 +42,96 @@ pub struct RaftLogEntry {
     pub command: Vec<u8>,
 }
 
+/// Grant or refuse a vote under the Raft rules: never for an outdated term,
+/// at most one candidate per term.
+fn grant_vote(
+    current_term: &mut u64,
+    voted_for: &mut Option<String>,
+    candidate_id: &str,
+    term: u64,
+) -> bool {
+    // If term is outdated, reject
+    if term < *current_term {
+        return false;
+    }
+
+    // If term is newer, update term and reset vote
+    if term > *current_term {
+        *current_term = term;
+        *voted_for = None;
+    }
+
+    // Vote if haven't voted or voted for same candidate
+    if voted_for.is_none() || voted_for.as_deref() == Some(candidate_id) {
+        *voted_for = Some(candidate_id.to_string());
+        info!("Voted for {} in term {}", candidate_id, term);
+        true
+    } else {
+        false
+    }
+}
+
+/// Term and vote bookkeeping for one voter, without the timers of [`RaftNode`]
+#[derive(Debug, Default, Clone)]
+pub struct VoteState {
+    current_term: u64,
+    voted_for: Option<String>,
+}
+
+impl VoteState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn current_term(&self) -> u64 {
+        self.current_term
+    }
+
+    pub fn voted_for(&self) -> Option<&str> {
+        self.voted_for.as_deref()
+    }
+
+    /// Start an election: move to the next term and vote for ourselves
+    pub fn start_election(&mut self, node_id: &str) -> u64 {
+        self.current_term += 1;
+        self.voted_for = Some(node_id.to_string());
+        self.current_term
+    }
+
+    /// Adopt a newer term seen from another node. Returns whether it was newer.
+    pub fn observe_term(&mut self, term: u64) -> bool {
+        if term > self.current_term {
+            self.current_term = term;
+            self.voted_for = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Handle a vote request. As in Raft, a candidate whose log is behind the
+    /// voter's (`candidate_log_index < own_log_index`) is refused, so the
+    /// winner always holds every entry a majority has.
+    pub fn request_vote(
+        &mut self,
+        candidate_id: &str,
+        term: u64,
+        candidate_log_index: u64,
+        own_log_index: u64,
+    ) -> bool {
+        if candidate_log_index < own_log_index {
+            self.observe_term(term);
+            return false;
+        }
+        grant_vote(
+            &mut self.current_term,
+            &mut self.voted_for,
+            candidate_id,
+            term,
+        )
+    }
+}
+
 /// Raft node
 pub struct RaftNode {
     /// Node ID
@@ -181,25 +274,12 @@ impl RaftNode {
         let mut current_term = self.current_term.write();
         let mut voted_for = self.voted_for.write();
 
-        // If term is outdated, reject
-        if term < *current_term {
-            return Ok(false);
-        }
-
-        // If term is newer, update term and reset vote
-        if term > *current_term {
-            *current_term = term;
-            *voted_for = None;
-        }
-
-        // Vote if haven't voted or voted for same candidate
-        if voted_for.is_none() || voted_for.as_ref() == Some(&candidate_id.to_string()) {
-            *voted_for = Some(candidate_id.to_string());
-            info!("Voted for {} in term {}", candidate_id, term);
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+        Ok(grant_vote(
+            &mut current_term,
+            &mut voted_for,
+            candidate_id,
+            term,
+        ))
     }
 
     /// Receive heartbeat from leader
@@ -325,6 +325,28 @@ mod tests {
         assert!(node.request_vote("node-3", 2).unwrap());
     }
 
+    #[test]
+    fn test_vote_state_refuses_stale_candidates() {
+        let mut votes = VoteState::new();
+
+        // A candidate behind our replication offset is refused, but its term
+        // is still adopted.
+        assert!(!votes.request_vote("node-2", 1, 10, 20));
+        assert_eq!(votes.current_term(), 1);
+        assert_eq!(votes.voted_for(), None);
+
+        assert!(votes.request_vote("node-3", 1, 20, 20));
+        assert!(!votes.request_vote("node-2", 1, 30, 20));
+
+        // Our own election moves to the next term.
+        assert_eq!(votes.start_election("node-1"), 2);
+        assert_eq!(votes.voted_for(), Some("node-1"));
+        assert!(!votes.request_vote("node-3", 2, 50, 20));
+        assert!(!votes.observe_term(2));
+        assert!(votes.observe_term(3));
+        assert!(votes.request_vote("node-3", 3, 50, 20));
+    }
+
     #[tokio::test]
     async fn test_raft_heartbeat() {
         let node = RaftNode::new(
@@ -559,6 +581,7 @@ mod tests {
             ("SYNAP_CLUSTER_NODE_ADDRESS", "10.0.0.5:7000"),
             ("SYNAP_CLUSTER_SEEDS", "127.0.0.1:7001, 127.0.0.1:7002"),
             ("SYNAP_CLUSTER_MIGRATION_BATCH_SIZE", "250"),
+            ("SYNAP_CLUSTER_MIGRATION_MAX_KEYS_PER_SEC", "5000"),
             ("SYNAP_CLUSTER_RAFT_ELECTION_TIMEOUT_MS", "bad-number"),
         ]
         .into_iter()
@@ -570,6 +593,7 @@ mod tests {
         assert_eq!(cfg.node_address.to_string(), "10.0.0.5:7000");
         assert_eq!(cfg.seed_nodes.len(), 2);
         assert_eq!(cfg.migration_batch_size, 250);
+        assert_eq!(cfg.migration_max_keys_per_sec, 5000);
         // Invalid value falls back to the default (1000), not a panic.
         assert_eq!(cfg.raft_election_timeout_ms, 1000);
 
@@ -599,4 +623,90 @@ mod tests {
         // Non-destructive copy model: the source keeps its key after rollback.
         assert_eq!(kv.get("mkey").await.unwrap(), Some(b"v".to_vec()));
     }
+
+    #[tokio::test]
+    async fn test_migration_queued_until_store_attached() {
+        use crate::core::{KVConfig, KVStore};
+        let kv = std::sync::Arc::new(KVStore::new(KVConfig::default()));
+        for i in 0..5 {
+            kv.set(&format!("{{tag}}:{i}"), b"v".to_vec(), None)
+                .await
+                .unwrap();
+        }
+        let slot = crate::cluster::hash_slot("{tag}");
+
+        let mgr = SlotMigrationManager::new(2, Duration::from_secs(60));
+        mgr.set_throttle(1_000);
+        assert_eq!(mgr.throttle(), 1_000);
+        mgr.start_migrations(&[(slot, "src".to_string())], "dst")
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            mgr.get_migration(slot).unwrap().state,
+            MigrationState::Pending
+        );
+
+        mgr.attach_kv_store(kv);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let m = mgr.get_migration(slot).unwrap();
+        assert_eq!(m.state, MigrationState::InProgress);
+        assert!(m.copy_done);
+        assert_eq!(m.total_keys, 5);
+        assert_eq!(m.keys_migrated, 5);
+    }
+
+    #[tokio::test]
+    async fn test_start_migrations_is_all_or_nothing() {
+        let mgr = SlotMigrationManager::new(100, Duration::from_secs(60));
+        mgr.start_migration(11, "a".to_string(), "b".to_string())
+            .unwrap();
+
+        let slots: Vec<(u16, String)> = (10..=12).map(|slot| (slot, "a".to_string())).collect();
+        assert!(matches!(
+            mgr.start_migrations(&slots, "b"),
+            Err(ClusterError::SlotMigrating(11))
+        ));
+        assert!(mgr.get_migration(10).is_none());
+
+        assert!(mgr.clear_migration(11).is_some());
+        mgr.start_migrations(&slots, "b").unwrap();
+        let listed: Vec<u16> = mgr.list_migrations().iter().map(|m| m.slot).collect();
+        assert_eq!(listed, vec![10, 11, 12]);
+    }
+
+    #[test]
+    fn test_topology_set_slots_owner() {
+        let topology = ClusterTopology::new("node-0".to_string());
+        topology.initialize_cluster(2).unwrap();
+        let node_0_end = topology.get_node("node-0").unwrap().slots[0].end;
+
+        // Move the last 10 slots of node-0 and one in the middle
+        topology
+            .set_slots_owner(SlotRange::new(node_0_end - 9, node_0_end), "node-1")
+            .unwrap();
+        topology
+            .set_slots_owner(SlotRange::new(100, 100), "node-1")
+            .unwrap();
+
+        assert_eq!(topology.get_slot_owner(node_0_end).unwrap(), "node-1");
+        assert_eq!(topology.get_slot_owner(100).unwrap(), "node-1");
+        assert!(topology.has_full_coverage());
+
+        let node_0 = topology.get_node("node-0").unwrap();
+        assert_eq!(
+            node_0.slots,
+            vec![SlotRange::new(0, 99), SlotRange::new(101, node_0_end - 10)]
+        );
+        let node_1 = topology.get_node("node-1").unwrap();
+        assert_eq!(node_1.slots.len(), 2);
+        assert_eq!(node_1.slots[0], SlotRange::new(100, 100));
+        assert_eq!(node_1.slots[1].start, node_0_end - 9);
+
+        assert!(
+            topology
+                .set_slots_owner(SlotRange::new(0, 0), "missing")
+                .is_err()
+        );
+    }
 }
@@ -115,6 +115,58 @@ impl ClusterTopology {
         Ok(())
     }
 
+    /// Hand every slot in `range` to `node_id`, keeping the slot lists of the
+    /// previous owners and the new one in step (`CLUSTER SETSLOT ... NODE`)
+    pub fn set_slots_owner(&self, range: SlotRange, node_id: &str) -> ClusterResult<()> {
+        let mut nodes = self.nodes.write();
+        let mut slots = self.slot_assignments.write();
+
+        if !nodes.contains_key(node_id) {
+            return Err(ClusterError::NodeNotFound(node_id.to_string()));
+        }
+
+        let mut affected = vec![node_id.to_string()];
+        for slot in range.start..=range.end {
+            if let Some(previous) = slots.insert(slot, node_id.to_string())
+                && !affected.contains(&previous)
+            {
+                affected.push(previous);
+            }
+        }
+
+        for id in &affected {
+            let ranges = Self::owned_ranges(&slots, id);
+            if let Some(node) = nodes.get_mut(id) {
+                node.slots = ranges;
+            }
+        }
+
+        info!(
+            "Slots {}-{} now owned by node {}",
+            range.start, range.end, node_id
+        );
+        Ok(())
+    }
+
+    /// Slots assigned to `node_id`, merged into contiguous ranges
+    fn owned_ranges(slots: &HashMap<u16, String>, node_id: &str) -> Vec<SlotRange> {
+        let mut owned: Vec<u16> = slots
+            .iter()
+            .filter(|(_, owner)| owner.as_str() == node_id)
+            .map(|(slot, _)| *slot)
+            .collect();
+        owned.sort_unstable();
+
+        let mut ranges: Vec<SlotRange> = Vec::new();
+        for slot in owned {
+            match ranges.last_mut() {
+                Some(range) if range.end + 1 == slot => range.end = slot,
+                _ => ranges.push(SlotRange::new(slot, slot)),
+            }
+        }
+        ranges
+    }
+
     /// Get node that owns a slot
     pub fn get_slot_owner(&self, slot: u16) -> ClusterResult<String> {
         let slots = self.slot_assignments.read();
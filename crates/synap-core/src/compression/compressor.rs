@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
+use std::sync::Arc;
 use tracing::debug;
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
 
 /// Compression algorithm selection
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -39,15 +41,47 @@ impl Default for CompressionConfig {
     }
 }
 
+/// Zstd dictionary, digested once and reused for every payload
+struct ZstdDictionary {
+    raw: Arc<[u8]>,
+    encoder: EncoderDictionary<'static>,
+    decoder: DecoderDictionary<'static>,
+}
+
 /// Main compressor interface
 pub struct Compressor {
     config: CompressionConfig,
+    dictionary: Option<ZstdDictionary>,
 }
 
 impl Compressor {
     /// Create new compressor with configuration
     pub fn new(config: CompressionConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            dictionary: None,
+        }
+    }
+
+    /// Create a compressor whose Zstd payloads use `dictionary`.
+    ///
+    /// Any bytes work as a raw-content dictionary; samples of the payloads
+    /// to come compress best. Both sides must use the same dictionary.
+    pub fn with_dictionary(config: CompressionConfig, dictionary: &[u8]) -> Self {
+        let dictionary = ZstdDictionary {
+            raw: Arc::from(dictionary),
+            encoder: EncoderDictionary::copy(dictionary, config.zstd_level),
+            decoder: DecoderDictionary::copy(dictionary),
+        };
+        Self {
+            config,
+            dictionary: Some(dictionary),
+        }
+    }
+
+    /// Dictionary used for Zstd payloads, if any
+    pub fn dictionary(&self) -> Option<&Arc<[u8]>> {
+        self.dictionary.as_ref().map(|d| &d.raw)
     }
 
     /// Compress data using specified algorithm
@@ -120,7 +154,17 @@ impl Compressor {
 
     /// Compress using Zstd
     fn compress_zstd(&self, data: &[u8]) -> Result<Vec<u8>, std::io::Error> {
-        let compressed = zstd::encode_all(data, self.config.zstd_level)?;
+        let compressed = match &self.dictionary {
+            Some(dictionary) => {
+                let mut encoder = zstd::stream::write::Encoder::with_prepared_dictionary(
+                    Vec::new(),
+                    &dictionary.encoder,
+                )?;
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+            None => zstd::encode_all(data, self.config.zstd_level)?,
+        };
 
         let ratio = data.len() as f64 / compressed.len() as f64;
         debug!(
@@ -135,7 +179,18 @@ impl Compressor {
 
     /// Decompress using Zstd
     fn decompress_zstd(&self, data: &[u8]) -> Result<Vec<u8>, std::io::Error> {
-        let decompressed = zstd::decode_all(data)?;
+        let decompressed = match &self.dictionary {
+            Some(dictionary) => {
+                let mut decoder = zstd::stream::read::Decoder::with_prepared_dictionary(
+                    data,
+                    &dictionary.decoder,
+                )?;
+                let mut decompressed = Vec::new();
+                decoder.read_to_end(&mut decompressed)?;
+                decompressed
+            }
+            None => zstd::decode_all(data)?,
+        };
 
         debug!(
             "Zstd decompressed: {} → {} bytes",
@@ -208,6 +263,34 @@ mod tests {
         assert_eq!(data.to_vec(), decompressed);
     }
 
+    #[test]
+    fn test_zstd_dictionary() {
+        let config = CompressionConfig {
+            enabled: true,
+            min_payload_size: 10,
+            default_algorithm: CompressionAlgorithm::Zstd,
+            ..Default::default()
+        };
+        let dictionary = br#"{"key":"user:1000","value":"active","ttl":3600}"#.repeat(20);
+        let with_dict = Compressor::with_dictionary(config.clone(), &dictionary);
+        let plain = Compressor::new(config);
+
+        let data = br#"{"key":"user:1001","value":"active","ttl":3600}"#;
+        let compressed = with_dict.compress(data, None).unwrap();
+
+        // The dictionary already holds most of the payload
+        assert!(compressed.len() < plain.compress(data, None).unwrap().len());
+        let decompressed = with_dict
+            .decompress(&compressed, CompressionAlgorithm::Zstd)
+            .unwrap();
+        assert_eq!(data.to_vec(), decompressed);
+        assert!(
+            plain
+                .decompress(&compressed, CompressionAlgorithm::Zstd)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_skip_small_payloads() {
         let config = CompressionConfig {
@@ -0,0 +1,304 @@
+//! Per-link compression for node-to-node traffic.
+//!
+//! A link (one master → replica connection, say) carries a sequence of frames
+//! that the caller length-prefixes itself. [`LinkCodec`] compresses each frame
+//! with Zstd through [`Compressor`]. The first frames of a link are sampled
+//! into a raw-content dictionary; once it is full, the sender ships it inline
+//! with the next frame and both ends reuse it for the rest of the link. Small
+//! frames such as single replicated operations share most of their bytes with
+//! the sample, so they shrink too.
+//!
+//! Encoded frame layout:
+//!
+//! | Tag | Body                                                          |
+//! |-----|---------------------------------------------------------------|
+//! | `0` | Raw frame                                                     |
+//! | `1` | Zstd frame                                                    |
+//! | `2` | Zstd frame using the link dictionary                          |
+//! | `3` | `u32` BE dictionary length, dictionary, then a tag `2` body   |
+
+use super::compressor::{CompressionAlgorithm, CompressionConfig, Compressor};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const TAG_RAW: u8 = 0;
+const TAG_ZSTD: u8 = 1;
+const TAG_ZSTD_DICTIONARY: u8 = 2;
+const TAG_NEW_DICTIONARY: u8 = 3;
+
+/// Link compression configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkCompressionConfig {
+    /// Offer (or accept) compression on this node's links. Both ends of a
+    /// link must enable it; otherwise the link stays uncompressed.
+    pub enabled: bool,
+    /// Zstd compression level (1-22)
+    pub zstd_level: i32,
+    /// Frames smaller than this are sent as they are
+    pub min_frame_size: usize,
+    /// Bytes of early traffic sampled into the link dictionary (0 disables it)
+    pub dictionary_size: usize,
+}
+
+impl Default for LinkCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            zstd_level: 3,
+            min_frame_size: 64,
+            dictionary_size: 16 * 1024,
+        }
+    }
+}
+
+/// Bytes through a link before and after compression
+#[derive(Debug, Default)]
+pub struct LinkCompressionStats {
+    raw_bytes: AtomicU64,
+    wire_bytes: AtomicU64,
+}
+
+impl LinkCompressionStats {
+    /// Count one frame of `raw` bytes that took `wire` bytes on the wire
+    pub fn record(&self, raw: usize, wire: usize) {
+        self.raw_bytes.fetch_add(raw as u64, Ordering::Relaxed);
+        self.wire_bytes.fetch_add(wire as u64, Ordering::Relaxed);
+    }
+
+    /// Frame bytes before compression
+    pub fn raw_bytes(&self) -> u64 {
+        self.raw_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Frame bytes as sent on the wire
+    pub fn wire_bytes(&self) -> u64 {
+        self.wire_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Bandwidth saved by compression
+    pub fn saved_bytes(&self) -> u64 {
+        self.raw_bytes().saturating_sub(self.wire_bytes())
+    }
+}
+
+/// One end of a compressed link.
+///
+/// Use one codec per direction: the sender calls [`encode`](Self::encode)
+/// and the receiver [`decode`](Self::decode) on frames in the same order.
+pub struct LinkCodec {
+    config: LinkCompressionConfig,
+    plain: Compressor,
+    dictionary: Option<Compressor>,
+    /// Sender only: frames sampled while the dictionary fills up
+    sample: Vec<u8>,
+    /// Sender only: the dictionary is built but the receiver has not got it
+    dictionary_pending: bool,
+    stats: Arc<LinkCompressionStats>,
+}
+
+impl LinkCodec {
+    /// Create a codec for one link
+    pub fn new(config: LinkCompressionConfig) -> Self {
+        let plain = Compressor::new(Self::compressor_config(&config));
+        Self {
+            config,
+            plain,
+            dictionary: None,
+            sample: Vec::new(),
+            dictionary_pending: false,
+            stats: Arc::new(LinkCompressionStats::default()),
+        }
+    }
+
+    fn compressor_config(config: &LinkCompressionConfig) -> CompressionConfig {
+        CompressionConfig {
+            enabled: true,
+            min_payload_size: 0,
+            default_algorithm: CompressionAlgorithm::Zstd,
+            zstd_level: config.zstd_level,
+        }
+    }
+
+    /// Byte counters of this link, shareable with whoever reports them
+    pub fn stats(&self) -> Arc<LinkCompressionStats> {
+        Arc::clone(&self.stats)
+    }
+
+    /// True once both ends hold the link dictionary (sender side)
+    pub fn has_dictionary(&self) -> bool {
+        self.dictionary.is_some() && !self.dictionary_pending
+    }
+
+    /// Compress one frame for the wire
+    pub fn encode(&mut self, frame: &[u8]) -> io::Result<Vec<u8>> {
+        let encoded = if frame.len() < self.config.min_frame_size {
+            tagged(TAG_RAW, frame)
+        } else {
+            self.sample_frame(frame);
+            match &self.dictionary {
+                Some(dictionary) if self.dictionary_pending => {
+                    let raw = dictionary
+                        .dictionary()
+                        .expect("dictionary compressor holds a dictionary");
+                    let body = dictionary.compress(frame, None)?;
+                    let mut out = Vec::with_capacity(5 + raw.len() + body.len());
+                    out.push(TAG_NEW_DICTIONARY);
+                    out.extend_from_slice(&(raw.len() as u32).to_be_bytes());
+                    out.extend_from_slice(raw);
+                    out.extend_from_slice(&body);
+                    self.dictionary_pending = false;
+                    out
+                }
+                Some(dictionary) => Self::smaller_of(
+                    frame,
+                    TAG_ZSTD_DICTIONARY,
+                    dictionary.compress(frame, None)?,
+                ),
+                None => Self::smaller_of(frame, TAG_ZSTD, self.plain.compress(frame, None)?),
+            }
+        };
+
+        self.stats.record(frame.len(), encoded.len());
+        Ok(encoded)
+    }
+
+    /// Restore one frame received from the wire
+    pub fn decode(&mut self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let (&tag, body) = data
+            .split_first()
+            .ok_or_else(|| invalid("empty link frame"))?;
+
+        let frame = match tag {
+            TAG_RAW => body.to_vec(),
+            TAG_ZSTD => self.plain.decompress(body, CompressionAlgorithm::Zstd)?,
+            TAG_ZSTD_DICTIONARY => self
+                .dictionary
+                .as_ref()
+                .ok_or_else(|| invalid("link frame uses a dictionary that was never sent"))?
+                .decompress(body, CompressionAlgorithm::Zstd)?,
+            TAG_NEW_DICTIONARY => {
+                let len_bytes = body
+                    .get(..4)
+                    .ok_or_else(|| invalid("truncated link dictionary"))?;
+                let len = u32::from_be_bytes(len_bytes.try_into().expect("4 bytes")) as usize;
+                let raw = body
+                    .get(4..4 + len)
+                    .ok_or_else(|| invalid("truncated link dictionary"))?;
+                let dictionary =
+                    Compressor::with_dictionary(Self::compressor_config(&self.config), raw);
+                let frame = dictionary.decompress(&body[4 + len..], CompressionAlgorithm::Zstd)?;
+                self.dictionary = Some(dictionary);
+                frame
+            }
+            other => return Err(invalid(&format!("unknown link frame tag {other}"))),
+        };
+
+        self.stats.record(frame.len(), data.len());
+        Ok(frame)
+    }
+
+    /// Add a frame to the dictionary sample, building the dictionary once
+    /// the sample is full
+    fn sample_frame(&mut self, frame: &[u8]) {
+        let size = self.config.dictionary_size;
+        if size == 0 || self.dictionary.is_some() {
+            return;
+        }
+        let take = frame.len().min(size - self.sample.len());
+        self.sample.extend_from_slice(&frame[..take]);
+        if self.sample.len() >= size {
+            let sample = std::mem::take(&mut self.sample);
+            self.dictionary = Some(Compressor::with_dictionary(
+                Self::compressor_config(&self.config),
+                &sample,
+            ));
+            self.dictionary_pending = true;
+        }
+    }
+
+    /// The compressed body, or the raw frame when compression did not help
+    fn smaller_of(frame: &[u8], tag: u8, compressed: Vec<u8>) -> Vec<u8> {
+        if compressed.len() < frame.len() {
+            tagged(tag, &compressed)
+        } else {
+            tagged(TAG_RAW, frame)
+        }
+    }
+}
+
+fn tagged(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 1);
+    out.push(tag);
+    out.extend_from_slice(body);
+    out
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation(i: usize) -> Vec<u8> {
+        format!(r#"{{"op":"kv.set","key":"session:{i:06}","value":"user-{i}","ttl":3600}}"#)
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_round_trip_builds_and_reuses_dictionary() {
+        let config = LinkCompressionConfig {
+            enabled: true,
+            min_frame_size: 16,
+            dictionary_size: 1024,
+            ..Default::default()
+        };
+        let mut sender = LinkCodec::new(config.clone());
+        let mut receiver = LinkCodec::new(config);
+
+        for i in 0..200 {
+            let frame = operation(i);
+            let wire = sender.encode(&frame).unwrap();
+            assert_eq!(receiver.decode(&wire).unwrap(), frame);
+        }
+        assert!(sender.has_dictionary());
+
+        // Frames sent after the dictionary are well under half their size
+        let frame = operation(500);
+        let wire = sender.encode(&frame).unwrap();
+        assert_eq!(wire[0], TAG_ZSTD_DICTIONARY);
+        assert!(wire.len() * 2 < frame.len());
+        assert_eq!(receiver.decode(&wire).unwrap(), frame);
+
+        let stats = sender.stats();
+        assert!(stats.saved_bytes() > 0);
+        assert_eq!(stats.raw_bytes(), receiver.stats().raw_bytes());
+        assert_eq!(stats.wire_bytes(), receiver.stats().wire_bytes());
+    }
+
+    #[test]
+    fn test_small_and_incompressible_frames_stay_raw() {
+        let mut codec = LinkCodec::new(LinkCompressionConfig::default());
+        assert_eq!(codec.encode(b"tiny").unwrap(), b"\0tiny");
+
+        let noise: Vec<u8> = (0..256u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        let wire = codec.encode(&noise).unwrap();
+        assert_eq!(wire[0], TAG_RAW);
+        assert_eq!(codec.decode(&wire).unwrap(), noise);
+    }
+
+    #[test]
+    fn test_rejects_unknown_dictionary() {
+        let mut codec = LinkCodec::new(LinkCompressionConfig::default());
+        assert!(codec.decode(&[TAG_ZSTD_DICTIONARY, 1, 2, 3]).is_err());
+        assert!(codec.decode(&[TAG_NEW_DICTIONARY, 0, 0, 1]).is_err());
+        assert!(codec.decode(&[9]).is_err());
+        assert!(codec.decode(&[]).is_err());
+    }
+}
@@ -1,3 +1,7 @@
 pub mod compressor;
+pub mod link;
+pub mod value;
 
 pub use compressor::{CompressionAlgorithm, Compressor};
+pub use link::{LinkCodec, LinkCompressionConfig, LinkCompressionStats};
+pub use value::{ValueCodec, ValueCompressionConfig, ValueCompressionReport};
@@ -0,0 +1,254 @@
+//! Compression of stored values at rest.
+//!
+//! A store with value compression enabled keeps every value behind a one-byte
+//! header naming its encoding, so a read knows whether to decompress without
+//! consulting the configuration it was written under:
+//!
+//! | Tag | Body        |
+//! |-----|-------------|
+//! | `0` | Raw value   |
+//! | `1` | LZ4 frame   |
+//! | `2` | Zstd frame  |
+//!
+//! Values under the size threshold, and values that do not shrink, stay raw.
+//! Snapshots use the same layout for every KV value, whether or not the store
+//! that wrote them compresses.
+
+use super::compressor::{CompressionAlgorithm, CompressionConfig, Compressor};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub const TAG_RAW: u8 = 0;
+pub const TAG_LZ4: u8 = 1;
+pub const TAG_ZSTD: u8 = 2;
+
+/// Value compression configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValueCompressionConfig {
+    /// Compress values as they are written (disabled by default)
+    pub enabled: bool,
+    /// `lz4` (faster) or `zstd` (smaller)
+    pub algorithm: CompressionAlgorithm,
+    /// Values smaller than this are stored raw
+    pub min_size_bytes: usize,
+    /// Zstd compression level (1-22)
+    pub zstd_level: i32,
+}
+
+impl Default for ValueCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            algorithm: CompressionAlgorithm::Lz4,
+            min_size_bytes: 1024,
+            zstd_level: 3,
+        }
+    }
+}
+
+/// Value bytes written through a codec, before and after compression
+#[derive(Debug, Default)]
+pub struct ValueCompressionStats {
+    values: AtomicU64,
+    compressed_values: AtomicU64,
+    raw_bytes: AtomicU64,
+    stored_bytes: AtomicU64,
+}
+
+impl ValueCompressionStats {
+    /// Count one value of `raw` bytes stored as `stored` bytes (header included)
+    pub fn record(&self, raw: usize, stored: usize, compressed: bool) {
+        self.values.fetch_add(1, Ordering::Relaxed);
+        if compressed {
+            self.compressed_values.fetch_add(1, Ordering::Relaxed);
+        }
+        self.raw_bytes.fetch_add(raw as u64, Ordering::Relaxed);
+        self.stored_bytes
+            .fetch_add(stored as u64, Ordering::Relaxed);
+    }
+
+    /// Point-in-time copy of the counters
+    pub fn snapshot(&self) -> ValueCompressionReport {
+        let raw_bytes = self.raw_bytes.load(Ordering::Relaxed);
+        let stored_bytes = self.stored_bytes.load(Ordering::Relaxed);
+        ValueCompressionReport {
+            values: self.values.load(Ordering::Relaxed),
+            compressed_values: self.compressed_values.load(Ordering::Relaxed),
+            raw_bytes,
+            stored_bytes,
+            ratio: if stored_bytes == 0 {
+                1.0
+            } else {
+                raw_bytes as f64 / stored_bytes as f64
+            },
+        }
+    }
+}
+
+/// Compression counters of one store, as reported by its stats
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValueCompressionReport {
+    /// Values written since startup
+    pub values: u64,
+    /// Of those, values stored compressed
+    pub compressed_values: u64,
+    /// Bytes written before compression
+    pub raw_bytes: u64,
+    /// Bytes actually stored, headers included
+    pub stored_bytes: u64,
+    /// `raw_bytes / stored_bytes` (1.0 before any write)
+    pub ratio: f64,
+}
+
+/// Encodes values for storage and restores them on read
+pub struct ValueCodec {
+    config: ValueCompressionConfig,
+    compressor: Compressor,
+    stats: ValueCompressionStats,
+}
+
+impl std::fmt::Debug for ValueCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValueCodec")
+            .field("config", &self.config)
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+impl ValueCodec {
+    /// Create a codec, or `None` when compression is disabled
+    pub fn from_config(config: &ValueCompressionConfig) -> Option<Arc<Self>> {
+        config.enabled.then(|| Arc::new(Self::new(config.clone())))
+    }
+
+    /// Create a codec for `config`, compressing whether or not it is enabled
+    pub fn new(config: ValueCompressionConfig) -> Self {
+        let compressor = Compressor::new(CompressionConfig {
+            enabled: true,
+            min_payload_size: 0,
+            default_algorithm: config.algorithm,
+            zstd_level: config.zstd_level,
+        });
+        Self {
+            config,
+            compressor,
+            stats: ValueCompressionStats::default(),
+        }
+    }
+
+    /// Byte counters of every value encoded so far
+    pub fn stats(&self) -> &ValueCompressionStats {
+        &self.stats
+    }
+
+    /// Header and body for `value`, compressed when it is large enough and
+    /// compression makes it smaller
+    pub fn encode(&self, value: &[u8]) -> Vec<u8> {
+        let compressed = if value.len() < self.config.min_size_bytes.max(1) {
+            None
+        } else {
+            let tag = match self.config.algorithm {
+                CompressionAlgorithm::None => None,
+                CompressionAlgorithm::Lz4 => Some(TAG_LZ4),
+                CompressionAlgorithm::Zstd => Some(TAG_ZSTD),
+            };
+            tag.and_then(|tag| {
+                self.compressor
+                    .compress(value, None)
+                    .ok()
+                    .filter(|body| body.len() < value.len())
+                    .map(|body| tagged(tag, &body))
+            })
+        };
+
+        let encoded = compressed.unwrap_or_else(|| tagged(TAG_RAW, value));
+        self.stats
+            .record(value.len(), encoded.len(), encoded[0] != TAG_RAW);
+        encoded
+    }
+}
+
+/// The value held by `stored` (a header and body), borrowed when it is raw
+pub fn decode(stored: &[u8]) -> io::Result<Cow<'_, [u8]>> {
+    let (&tag, body) = stored
+        .split_first()
+        .ok_or_else(|| invalid("empty stored value"))?;
+    Ok(match tag {
+        TAG_RAW => Cow::Borrowed(body),
+        TAG_LZ4 => Cow::Owned(decompressor().decompress(body, CompressionAlgorithm::Lz4)?),
+        TAG_ZSTD => Cow::Owned(decompressor().decompress(body, CompressionAlgorithm::Zstd)?),
+        other => return Err(invalid(&format!("unknown stored value tag {other}"))),
+    })
+}
+
+/// Decompression needs no settings; any compressor restores any frame
+fn decompressor() -> Compressor {
+    Compressor::new(CompressionConfig::default())
+}
+
+fn tagged(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 1);
+    out.push(tag);
+    out.extend_from_slice(body);
+    out
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codec(algorithm: CompressionAlgorithm) -> ValueCodec {
+        ValueCodec::new(ValueCompressionConfig {
+            enabled: true,
+            algorithm,
+            min_size_bytes: 64,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_round_trip_compresses_large_values() {
+        let value = "session-payload ".repeat(200).into_bytes();
+        for (algorithm, tag) in [
+            (CompressionAlgorithm::Lz4, TAG_LZ4),
+            (CompressionAlgorithm::Zstd, TAG_ZSTD),
+        ] {
+            let codec = codec(algorithm);
+            let stored = codec.encode(&value);
+            assert_eq!(stored[0], tag);
+            assert!(stored.len() < value.len() / 4);
+            assert_eq!(decode(&stored).unwrap(), value.as_slice());
+
+            let report = codec.stats().snapshot();
+            assert_eq!(report.compressed_values, 1);
+            assert_eq!(report.raw_bytes, value.len() as u64);
+            assert!(report.ratio > 4.0);
+        }
+    }
+
+    #[test]
+    fn test_small_and_incompressible_values_stay_raw() {
+        let codec = codec(CompressionAlgorithm::Lz4);
+
+        let small = codec.encode(b"tiny");
+        assert_eq!(small, b"\0tiny");
+        assert!(matches!(decode(&small).unwrap(), Cow::Borrowed(b"tiny")));
+
+        // 256 distinct bytes don't shrink under LZ4
+        let noise: Vec<u8> = (0..=255).collect();
+        assert_eq!(codec.encode(&noise)[0], TAG_RAW);
+        assert_eq!(codec.stats().snapshot().compressed_values, 0);
+
+        assert!(decode(&[]).is_err());
+        assert!(decode(&[9, 1, 2]).is_err());
+    }
+}
@@ -78,6 +78,12 @@ impl BitmapValue {
         }
     }
 
+    /// Seconds left before the bitmap expires, `None` without a TTL
+    pub fn ttl(&self) -> Option<u64> {
+        self.ttl_secs
+            .map(|ttl| (self.created_at + ttl).saturating_sub(Self::current_timestamp()))
+    }
+
     /// Ensure bitmap is large enough for the given bit offset
     fn ensure_capacity(&mut self, offset: usize) {
         let byte_index = offset / 8;
@@ -206,6 +212,54 @@ impl BitmapValue {
         None
     }
 
+    /// Resolve an inclusive Redis-style range into bit offsets within the
+    /// bitmap. Negative indices count from the end; `None` if the range is
+    /// empty.
+    fn bit_bounds(&self, start: i64, end: i64, unit: BitRangeUnit) -> Option<(usize, usize)> {
+        let len = match unit {
+            BitRangeUnit::Byte => self.data.len(),
+            BitRangeUnit::Bit => self.data.len() * 8,
+        } as i64;
+        let resolve = |index: i64| {
+            if index < 0 {
+                (len + index).max(0)
+            } else {
+                index
+            }
+        };
+        let (start, end) = (resolve(start), resolve(end).min(len - 1));
+        if len == 0 || start > end {
+            return None;
+        }
+
+        let (start, end) = (start as usize, end as usize);
+        Some(match unit {
+            BitRangeUnit::Byte => (start * 8, end * 8 + 7),
+            BitRangeUnit::Bit => (start, end),
+        })
+    }
+
+    /// BITCOUNT over `[start, end]` in `unit`s, with Redis semantics:
+    /// negative indices count from the end.
+    pub fn bitcount_range(&self, start: i64, end: i64, unit: BitRangeUnit) -> usize {
+        self.bit_bounds(start, end, unit)
+            .map_or(0, |(start, end)| self.bitcount(Some(start), Some(end)))
+    }
+
+    /// BITPOS over `[start, end]` in `unit`s, with Redis semantics: -1 when
+    /// no bit matches, except that a search for a clear bit with no `end`
+    /// finds the first bit past the bitmap.
+    pub fn bitpos_range(&self, value: u8, start: i64, end: Option<i64>, unit: BitRangeUnit) -> i64 {
+        let Some((first, last)) = self.bit_bounds(start, end.unwrap_or(-1), unit) else {
+            return -1;
+        };
+        match self.bitpos(value, Some(first), Some(last)) {
+            Some(position) => position as i64,
+            None if value == 0 && end.is_none() => last as i64 + 1,
+            None => -1,
+        }
+    }
+
     /// Get bitmap length in bytes
     pub fn len_bytes(&self) -> usize {
         self.data.len()
@@ -554,8 +608,64 @@ impl BitmapStore {
         Ok(pos)
     }
 
+    /// BITCOUNT with a Redis-style range (see [`BitmapValue::bitcount_range`])
+    ///
+    /// A missing key counts 0, as in Redis.
+    pub fn bitcount_range(
+        &self,
+        key: &str,
+        start: i64,
+        end: i64,
+        unit: BitRangeUnit,
+    ) -> Result<usize> {
+        let shard = self.shard(key);
+        let map = shard.read();
+
+        let count = match map.get(key) {
+            Some(bitmap) if !bitmap.is_expired() => bitmap.bitcount_range(start, end, unit),
+            _ => 0,
+        };
+        self.stats.write().bitcount_count += 1;
+
+        Ok(count)
+    }
+
+    /// BITPOS with a Redis-style range (see [`BitmapValue::bitpos_range`])
+    ///
+    /// A missing key is all clear bits: 0 when looking for a clear bit, -1
+    /// for a set one.
+    pub fn bitpos_range(
+        &self,
+        key: &str,
+        value: u8,
+        start: i64,
+        end: Option<i64>,
+        unit: BitRangeUnit,
+    ) -> Result<i64> {
+        if value != 0 && value != 1 {
+            return Err(SynapError::InvalidValue(
+                "Bit value must be 0 or 1".to_string(),
+            ));
+        }
+
+        let shard = self.shard(key);
+        let map = shard.read();
+
+        let position = match map.get(key) {
+            Some(bitmap) if !bitmap.is_expired() => bitmap.bitpos_range(value, start, end, unit),
+            _ if value == 0 => 0,
+            _ => -1,
+        };
+        self.stats.write().bitpos_count += 1;
+
+        Ok(position)
+    }
+
     /// BITOP - Perform bitwise operation on multiple bitmaps
     /// Operations: AND, OR, XOR, NOT
+    ///
+    /// As in Redis, missing source keys count as empty bitmaps, and an empty
+    /// result deletes the destination. Returns the result length in bytes.
     pub fn bitop(
         &self,
         operation: BitmapOperation,
@@ -568,7 +678,13 @@ impl BitmapStore {
             ));
         }
 
-        // Get all source bitmaps
+        if operation == BitmapOperation::Not && source_keys.len() != 1 {
+            return Err(SynapError::InvalidRequest(
+                "NOT operation requires exactly 1 source key".to_string(),
+            ));
+        }
+
+        // Get all source bitmaps; missing and expired keys are empty
         let mut source_bitmaps = Vec::new();
         let mut max_len = 0;
 
@@ -576,55 +692,32 @@ impl BitmapStore {
             let shard = self.shard(source_key);
             let map = shard.read();
 
-            if let Some(bitmap) = map.get(source_key) {
-                if bitmap.is_expired() {
-                    continue; // Skip expired bitmaps
-                }
-                max_len = max_len.max(bitmap.len_bytes());
-                source_bitmaps.push(bitmap.data.clone());
-            }
-        }
-
-        if source_bitmaps.is_empty() {
-            return Err(SynapError::NotFound);
+            let data = match map.get(source_key) {
+                Some(bitmap) if !bitmap.is_expired() => bitmap.data.clone(),
+                _ => Vec::new(),
+            };
+            max_len = max_len.max(data.len());
+            source_bitmaps.push(data);
         }
 
         // Perform operation
         let result_data = match operation {
-            BitmapOperation::And => {
-                if source_bitmaps.len() < 2 {
-                    return Err(SynapError::InvalidRequest(
-                        "AND operation requires at least 2 source keys".to_string(),
-                    ));
-                }
-                self.bitop_and(&source_bitmaps, max_len)
-            }
-            BitmapOperation::Or => {
-                // OR can work with any number of sources (even empty, but we check above)
-                self.bitop_or(&source_bitmaps, max_len)
-            }
-            BitmapOperation::Xor => {
-                if source_bitmaps.len() < 2 {
-                    return Err(SynapError::InvalidRequest(
-                        "XOR operation requires at least 2 source keys".to_string(),
-                    ));
-                }
-                self.bitop_xor(&source_bitmaps, max_len)
-            }
-            BitmapOperation::Not => {
-                if source_bitmaps.len() != 1 {
-                    return Err(SynapError::InvalidRequest(
-                        "NOT operation requires exactly 1 source key".to_string(),
-                    ));
-                }
-                self.bitop_not(&source_bitmaps[0], max_len)
-            }
+            BitmapOperation::And => self.bitop_and(&source_bitmaps, max_len),
+            BitmapOperation::Or => self.bitop_or(&source_bitmaps, max_len),
+            BitmapOperation::Xor => self.bitop_xor(&source_bitmaps, max_len),
+            BitmapOperation::Not => self.bitop_not(&source_bitmaps[0], max_len),
         };
 
         // Store result
         let dest_shard = self.shard(dest_key);
         let mut map = dest_shard.write();
 
+        if result_data.is_empty() {
+            map.remove(dest_key);
+            self.stats.write().bitop_count += 1;
+            return Ok(0);
+        }
+
         // Remove expired value if present
         if let Some(existing_bitmap) = map.get(dest_key)
             && existing_bitmap.is_expired()
@@ -650,18 +743,14 @@ impl BitmapStore {
         let mut result = vec![0xFFu8; max_len]; // Start with all 1s
         for bitmap in bitmaps {
             let common = result.len().min(bitmap.len());
+            // BITOP AND semantics: any byte past the shortest source becomes 0.
+            result[common..].fill(0);
             if common == 0 {
                 continue;
             }
             // SAFETY: slices are in-bounds; SIMD dispatch is runtime-checked.
             let tmp = crate::simd::bitop_and(&result[..common], &bitmap[..common]);
             result[..common].copy_from_slice(&tmp);
-            // BITOP AND semantics: any byte past the shortest source becomes 0.
-            if bitmap.len() < result.len() {
-                for byte in &mut result[common..] {
-                    *byte = 0;
-                }
-            }
         }
         result
     }
@@ -782,6 +871,47 @@ impl BitmapStore {
         Ok(results)
     }
 
+    /// Bytes of every bitmap that has not expired, for dumps and full syncs
+    pub fn dump(&self) -> HashMap<String, Vec<u8>> {
+        let mut out = HashMap::new();
+        for shard in &self.shards {
+            let guard = shard.read();
+            for (key, bitmap) in guard.iter() {
+                if !bitmap.is_expired() {
+                    out.insert(key.clone(), bitmap.data.clone());
+                }
+            }
+        }
+        out
+    }
+
+    /// Seconds left before the bitmap at `key` expires, `None` if it has no
+    /// TTL or does not exist
+    pub fn ttl(&self, key: &str) -> Option<u64> {
+        self.shard(key).read().get(key).and_then(BitmapValue::ttl)
+    }
+
+    /// Replace the bitmap at `key` with `data`, expiring `ttl_secs` from now,
+    /// for full syncs and dump imports
+    pub fn restore(&self, key: &str, data: Vec<u8>, ttl_secs: Option<u64>) {
+        let mut bitmap = BitmapValue::new(ttl_secs);
+        bitmap.data = data;
+        self.shard(key).write().insert(key.to_string(), bitmap);
+    }
+
+    /// Remove every bitmap, for full syncs. Returns how many there were.
+    pub fn flush(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut map = shard.write();
+                let flushed = map.len();
+                map.clear();
+                flushed
+            })
+            .sum()
+    }
+
     /// Get statistics
     pub fn stats(&self) -> BitmapStats {
         let mut stats = self.stats.read().clone();
@@ -816,6 +946,30 @@ pub enum BitmapOperation {
     Not,
 }
 
+/// Whether BITCOUNT/BITPOS range indices count bytes or bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitRangeUnit {
+    /// Byte offsets, the Redis default
+    Byte,
+    /// Bit offsets
+    Bit,
+}
+
+impl std::str::FromStr for BitRangeUnit {
+    type Err = SynapError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "BYTE" => Ok(BitRangeUnit::Byte),
+            "BIT" => Ok(BitRangeUnit::Bit),
+            _ => Err(SynapError::InvalidRequest(format!(
+                "Invalid range unit: {}. Must be BYTE or BIT",
+                s
+            ))),
+        }
+    }
+}
+
 /// Bitfield overflow behavior
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum BitfieldOverflow {
@@ -707,3 +707,138 @@ fn test_bitfield_overflow_wrap_and_fail() {
             .is_err()
     );
 }
+
+#[test]
+fn test_bitcount_range_units_and_negative_indices() {
+    let mut bitmap = BitmapValue::new(None);
+    // "foobar", the BITCOUNT example from the Redis docs
+    bitmap.data = b"foobar".to_vec();
+
+    assert_eq!(bitmap.bitcount_range(0, -1, BitRangeUnit::Byte), 26);
+    assert_eq!(bitmap.bitcount_range(0, 0, BitRangeUnit::Byte), 4);
+    assert_eq!(bitmap.bitcount_range(1, 1, BitRangeUnit::Byte), 6);
+    assert_eq!(bitmap.bitcount_range(1, 1, BitRangeUnit::Bit), 1);
+    assert_eq!(bitmap.bitcount_range(5, 30, BitRangeUnit::Bit), 17);
+    assert_eq!(bitmap.bitcount_range(-2, -1, BitRangeUnit::Byte), 7);
+    // Clamped at the end; empty once start passes end
+    assert_eq!(bitmap.bitcount_range(4, 100, BitRangeUnit::Byte), 7);
+    assert_eq!(bitmap.bitcount_range(3, 1, BitRangeUnit::Byte), 0);
+}
+
+#[test]
+fn test_bitpos_range_follows_redis() {
+    let mut bitmap = BitmapValue::new(None);
+    bitmap.data = vec![0xff, 0xf0, 0x00];
+
+    assert_eq!(bitmap.bitpos_range(0, 0, None, BitRangeUnit::Byte), 12);
+    assert_eq!(bitmap.bitpos_range(1, 2, None, BitRangeUnit::Byte), -1);
+    assert_eq!(bitmap.bitpos_range(1, 7, Some(15), BitRangeUnit::Bit), 7);
+    assert_eq!(bitmap.bitpos_range(0, -1, None, BitRangeUnit::Byte), 16);
+
+    // All ones: a clear bit is found past the end only when no end is given
+    bitmap.data = vec![0xff, 0xff];
+    assert_eq!(bitmap.bitpos_range(0, 0, None, BitRangeUnit::Byte), 16);
+    assert_eq!(bitmap.bitpos_range(0, 0, Some(-1), BitRangeUnit::Byte), -1);
+}
+
+#[test]
+fn test_store_range_queries_on_missing_keys() {
+    let store = BitmapStore::new();
+    assert_eq!(
+        store
+            .bitcount_range("missing", 0, -1, BitRangeUnit::Byte)
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        store
+            .bitpos_range("missing", 0, 0, None, BitRangeUnit::Byte)
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        store
+            .bitpos_range("missing", 1, 0, None, BitRangeUnit::Byte)
+            .unwrap(),
+        -1
+    );
+    assert!(
+        store
+            .bitpos_range("missing", 2, 0, None, BitRangeUnit::Byte)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_bitop_treats_missing_sources_as_empty() {
+    let store = BitmapStore::new();
+    store.setbit("a", 0, 1).unwrap();
+    store.setbit("a", 9, 1).unwrap();
+    let sources = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+
+    // AND with a missing key is all zeros, as long as the longest source
+    assert_eq!(
+        store
+            .bitop(BitmapOperation::And, "and", &sources(&["a", "missing"]))
+            .unwrap(),
+        2
+    );
+    assert_eq!(store.bitcount("and", None, None).unwrap(), 0);
+
+    // A single source is copied
+    store
+        .bitop(BitmapOperation::Xor, "xor", &sources(&["a"]))
+        .unwrap();
+    assert_eq!(store.bitcount("xor", None, None).unwrap(), 2);
+
+    // An empty result deletes the destination
+    store.setbit("dest", 3, 1).unwrap();
+    assert_eq!(
+        store
+            .bitop(BitmapOperation::Or, "dest", &sources(&["missing"]))
+            .unwrap(),
+        0
+    );
+    assert!(store.getbit("dest", 3).is_err());
+
+    assert!(
+        store
+            .bitop(BitmapOperation::Not, "not", &sources(&["a", "missing"]))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_dump_restore_round_trip() {
+    let store = BitmapStore::new();
+    store.setbit("plain", 9, 1).unwrap();
+    store.restore("expiring", vec![0b1010_0000], Some(600));
+
+    let dumped = store.dump();
+    assert_eq!(dumped.len(), 2);
+    assert_eq!(dumped["plain"], vec![0, 0b0100_0000]);
+    assert_eq!(store.ttl("plain"), None);
+    let ttl = store.ttl("expiring").unwrap();
+    assert!(ttl > 590 && ttl <= 600);
+
+    let copy = BitmapStore::new();
+    copy.setbit("plain", 0, 1).unwrap();
+    for (key, data) in dumped {
+        copy.restore(&key, data, None);
+    }
+    // The restored bitmap replaces what was there
+    assert_eq!(copy.getbit("plain", 0).unwrap(), 0);
+    assert_eq!(copy.getbit("plain", 9).unwrap(), 1);
+    assert_eq!(copy.bitcount("expiring", None, None).unwrap(), 2);
+}
+
+#[test]
+fn test_flush() {
+    let store = BitmapStore::new();
+    store.setbit("a", 1, 1).unwrap();
+    store.setbit("b", 2, 1).unwrap();
+
+    assert_eq!(store.flush(), 2);
+    assert!(store.dump().is_empty());
+    assert!(store.getbit("a", 1).is_err());
+}
@@ -0,0 +1,385 @@
+//! Bloom filters.
+//!
+//! Each key holds a scalable Bloom filter: a membership test that never gives
+//! a false negative and gives a false positive at about the filter's error
+//! rate, in a fixed number of bits per item instead of the item itself.
+//!
+//! A filter starts with one layer sized for its capacity. When that layer is
+//! full the next item goes into a new layer [`EXPANSION`] times larger with
+//! half the error rate, so the compound error rate stays under twice the one
+//! the filter was reserved with however many items it grows to. Items are
+//! hashed with a fixed function, so the bits mean the same after a restart.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use super::error::{Result, SynapError};
+
+/// Capacity of a filter created by adding to a missing key.
+pub const DEFAULT_CAPACITY: u64 = 100;
+
+/// Error rate of a filter created by adding to a missing key.
+pub const DEFAULT_ERROR_RATE: f64 = 0.01;
+
+/// How many times larger each new layer is than the last.
+pub const EXPANSION: u64 = 2;
+
+/// Largest capacity a filter can be reserved with.
+const MAX_CAPACITY: u64 = 1 << 32;
+
+/// One fixed-size layer of a filter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BloomLayer {
+    bits: Vec<u64>,
+    num_bits: u64,
+    hashes: u32,
+    capacity: u64,
+    items: u64,
+}
+
+impl BloomLayer {
+    fn new(capacity: u64, error_rate: f64) -> Self {
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = ((-(capacity as f64) * error_rate.ln()) / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let hashes = (-error_rate.log2()).ceil().max(1.0) as u32;
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            hashes,
+            capacity,
+            items: 0,
+        }
+    }
+
+    fn positions(&self, (h1, h2): (u64, u64)) -> impl Iterator<Item = u64> + '_ {
+        (0..self.hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+
+    fn contains(&self, hash: (u64, u64)) -> bool {
+        self.positions(hash)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    fn insert(&mut self, hash: (u64, u64)) {
+        let positions: Vec<u64> = self.positions(hash).collect();
+        for bit in positions {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.items += 1;
+    }
+}
+
+/// The two base hashes of an item, combined as `h1 + i * h2` for the `i`th
+/// bit. FNV-1a finished with a SplitMix64 round, which unlike the std
+/// hashers is guaranteed not to change between releases.
+fn item_hash(item: &[u8]) -> (u64, u64) {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in item {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    let mix = |mut z: u64| {
+        z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    let h1 = mix(hash);
+    (h1, mix(h1) | 1)
+}
+
+/// A scalable Bloom filter, as held under one key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloomFilter {
+    error_rate: f64,
+    capacity: u64,
+    layers: Vec<BloomLayer>,
+}
+
+impl BloomFilter {
+    /// An empty filter for `capacity` items at `error_rate`.
+    pub fn new(error_rate: f64, capacity: u64) -> Result<Self> {
+        if !(error_rate > 0.0 && error_rate < 1.0) {
+            return Err(SynapError::InvalidValue(
+                "Bloom filter error rate must be between 0 and 1".to_string(),
+            ));
+        }
+        if capacity == 0 || capacity > MAX_CAPACITY {
+            return Err(SynapError::InvalidValue(format!(
+                "Bloom filter capacity must be between 1 and {MAX_CAPACITY}"
+            )));
+        }
+        Ok(Self {
+            error_rate,
+            capacity,
+            layers: vec![BloomLayer::new(capacity, error_rate)],
+        })
+    }
+
+    /// Whether `item` may have been added; `false` means it never was.
+    pub fn contains(&self, item: &[u8]) -> bool {
+        let hash = item_hash(item);
+        self.layers.iter().any(|layer| layer.contains(hash))
+    }
+
+    /// Add `item`. Returns `false` if it may have been added already.
+    pub fn insert(&mut self, item: &[u8]) -> bool {
+        let hash = item_hash(item);
+        if self.layers.iter().any(|layer| layer.contains(hash)) {
+            return false;
+        }
+        let last = self.layers.last().expect("a filter has at least one layer");
+        if last.items >= last.capacity {
+            let layer = BloomLayer::new(
+                last.capacity.saturating_mul(EXPANSION),
+                self.error_rate / 2f64.powi(self.layers.len() as i32),
+            );
+            self.layers.push(layer);
+        }
+        self.layers
+            .last_mut()
+            .expect("a filter has at least one layer")
+            .insert(hash);
+        true
+    }
+
+    /// Error rate the filter was reserved with.
+    pub fn error_rate(&self) -> f64 {
+        self.error_rate
+    }
+
+    /// Capacity of the first layer.
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Items added (not counting repeats the filter caught).
+    pub fn items(&self) -> u64 {
+        self.layers.iter().map(|layer| layer.items).sum()
+    }
+
+    /// Layers the filter has grown to.
+    pub fn layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Bytes of bit array held.
+    pub fn size_bytes(&self) -> usize {
+        self.layers.iter().map(|layer| layer.bits.len() * 8).sum()
+    }
+}
+
+/// Bloom filter counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BloomFilterStats {
+    pub filters: usize,
+    /// Bytes of bit array held across every filter.
+    pub memory_bytes: usize,
+    pub adds: u64,
+    pub checks: u64,
+}
+
+/// Per-key Bloom filters.
+pub struct BloomFilterStore {
+    filters: RwLock<HashMap<String, BloomFilter, ahash::RandomState>>,
+    adds: AtomicU64,
+    checks: AtomicU64,
+}
+
+impl Default for BloomFilterStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BloomFilterStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self {
+            filters: RwLock::new(HashMap::default()),
+            adds: AtomicU64::new(0),
+            checks: AtomicU64::new(0),
+        }
+    }
+
+    /// Create an empty filter on `key` for `capacity` items at `error_rate`.
+    ///
+    /// Returns `false`, leaving it as it is, if `key` already holds one.
+    pub fn reserve(&self, key: &str, error_rate: f64, capacity: u64) -> Result<bool> {
+        let filter = BloomFilter::new(error_rate, capacity)?;
+        let mut filters = self.filters.write();
+        if filters.contains_key(key) {
+            return Ok(false);
+        }
+        filters.insert(key.to_owned(), filter);
+        Ok(true)
+    }
+
+    /// Add `items` to the filter on `key`, creating it with
+    /// [`DEFAULT_ERROR_RATE`] and [`DEFAULT_CAPACITY`].
+    ///
+    /// Returns, per item, whether it was new to the filter.
+    pub fn add<T: AsRef<[u8]>>(&self, key: &str, items: &[T]) -> Vec<bool> {
+        let mut filters = self.filters.write();
+        let filter = filters.entry(key.to_owned()).or_insert_with(|| {
+            BloomFilter::new(DEFAULT_ERROR_RATE, DEFAULT_CAPACITY)
+                .expect("the defaults are a valid filter")
+        });
+        self.adds.fetch_add(items.len() as u64, Ordering::Relaxed);
+        items
+            .iter()
+            .map(|item| filter.insert(item.as_ref()))
+            .collect()
+    }
+
+    /// Check `items` against the filter on `key`; a missing key holds none.
+    pub fn exists<T: AsRef<[u8]>>(&self, key: &str, items: &[T]) -> Vec<bool> {
+        self.checks.fetch_add(items.len() as u64, Ordering::Relaxed);
+        let filters = self.filters.read();
+        match filters.get(key) {
+            Some(filter) => items
+                .iter()
+                .map(|item| filter.contains(item.as_ref()))
+                .collect(),
+            None => vec![false; items.len()],
+        }
+    }
+
+    /// A copy of the filter on `key`.
+    pub fn get(&self, key: &str) -> Option<BloomFilter> {
+        self.filters.read().get(key).cloned()
+    }
+
+    /// Put `filter` on `key`, replacing any there. Used by recovery and
+    /// replica full syncs.
+    pub fn restore(&self, key: &str, filter: BloomFilter) {
+        self.filters.write().insert(key.to_owned(), filter);
+    }
+
+    /// Remove every filter, for full syncs. Returns how many there were.
+    pub fn flush(&self) -> usize {
+        let mut filters = self.filters.write();
+        let flushed = filters.len();
+        filters.clear();
+        flushed
+    }
+
+    /// Copy every filter, for snapshots and full syncs.
+    pub fn dump(&self) -> HashMap<String, BloomFilter> {
+        self.filters
+            .read()
+            .iter()
+            .map(|(key, filter)| (key.clone(), filter.clone()))
+            .collect()
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> BloomFilterStats {
+        let filters = self.filters.read();
+        BloomFilterStats {
+            filters: filters.len(),
+            memory_bytes: filters.values().map(BloomFilter::size_bytes).sum(),
+            adds: self.adds.load(Ordering::Relaxed),
+            checks: self.checks.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(range: std::ops::Range<u32>) -> Vec<Vec<u8>> {
+        range.map(|i| format!("item:{i}").into_bytes()).collect()
+    }
+
+    #[test]
+    fn added_items_always_exist() {
+        let store = BloomFilterStore::new();
+        store.reserve("seen", 0.01, 1_000).unwrap();
+
+        let added = items(0..1_000);
+        let new = store.add("seen", &added).into_iter().filter(|new| *new);
+        assert!(new.count() > 980);
+        assert!(store.exists("seen", &added).iter().all(|exists| *exists));
+        assert_eq!(store.add("seen", &added[..1]), vec![false]);
+    }
+
+    #[test]
+    fn false_positive_rate_stays_near_the_error_rate() {
+        let store = BloomFilterStore::new();
+        store.reserve("seen", 0.01, 10_000).unwrap();
+        store.add("seen", &items(0..10_000));
+
+        let false_positives = store
+            .exists("seen", &items(10_000..30_000))
+            .into_iter()
+            .filter(|exists| *exists)
+            .count();
+        assert!(false_positives < 400, "{false_positives} false positives");
+    }
+
+    #[test]
+    fn full_filters_grow_a_layer() {
+        let store = BloomFilterStore::new();
+        store.add("seen", &items(0..DEFAULT_CAPACITY as u32));
+        assert_eq!(store.get("seen").unwrap().layers(), 1);
+
+        store.add("seen", &items(1_000..1_300));
+        let filter = store.get("seen").unwrap();
+        assert_eq!(filter.layers(), 3);
+        assert!(filter.items() > 350);
+        let all: Vec<_> = items(0..DEFAULT_CAPACITY as u32)
+            .into_iter()
+            .chain(items(1_000..1_300))
+            .collect();
+        assert!(store.exists("seen", &all).iter().all(|exists| *exists));
+    }
+
+    #[test]
+    fn reserve_validates_and_keeps_existing_filters() {
+        let store = BloomFilterStore::new();
+        assert!(store.reserve("seen", 0.0, 100).is_err());
+        assert!(store.reserve("seen", 1.0, 100).is_err());
+        assert!(store.reserve("seen", 0.01, 0).is_err());
+
+        assert!(store.reserve("seen", 0.001, 100).unwrap());
+        assert!(!store.reserve("seen", 0.01, 100).unwrap());
+        let filter = store.get("seen").unwrap();
+        assert_eq!((filter.error_rate(), filter.capacity()), (0.001, 100));
+    }
+
+    #[test]
+    fn missing_keys_hold_nothing() {
+        let store = BloomFilterStore::new();
+        assert_eq!(store.exists("nope", &[b"a"]), vec![false]);
+        assert!(store.get("nope").is_none());
+    }
+
+    #[test]
+    fn item_hash_is_stable() {
+        // Persisted bits depend on it: changing it breaks every snapshot
+        assert_eq!(
+            item_hash(b"synap"),
+            (0x8b37_3b9f_7677_7c3e, 0xbd66_4e63_deee_d0eb)
+        );
+    }
+
+    #[test]
+    fn dump_and_restore_round_trip() {
+        let store = BloomFilterStore::new();
+        store.add("seen", &items(0..50));
+
+        let copy = BloomFilterStore::new();
+        for (key, filter) in store.dump() {
+            copy.restore(&key, filter);
+        }
+        assert_eq!(copy.get("seen"), store.get("seen"));
+        assert!(copy.exists("seen", &items(0..50)).iter().all(|e| *e));
+    }
+}
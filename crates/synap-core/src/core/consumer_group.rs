@@ -19,6 +19,10 @@ pub struct ConsumerMember {
     pub last_heartbeat: Instant,
     /// Session timeout in seconds
     pub session_timeout_secs: u64,
+    /// Delayed visibility for this member, overriding the group's
+    /// `min_age_ms`
+    #[serde(default)]
+    pub min_age_ms: Option<u64>,
     /// Metadata
     #[serde(default)]
     pub metadata: HashMap<String, String>,
@@ -32,6 +36,7 @@ impl ConsumerMember {
             partitions: Vec::new(),
             last_heartbeat: Instant::now(),
             session_timeout_secs,
+            min_age_ms: None,
             metadata: HashMap::new(),
         }
     }
@@ -60,7 +65,7 @@ pub enum AssignmentStrategy {
 }
 
 /// Consumer group configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConsumerGroupConfig {
     /// Assignment strategy
     pub strategy: AssignmentStrategy,
@@ -72,6 +77,10 @@ pub struct ConsumerGroupConfig {
     pub auto_commit: bool,
     /// Auto-commit interval in seconds
     pub auto_commit_interval_secs: u64,
+    /// Default delayed visibility for members: events younger than this are
+    /// held back when a member consumes without an explicit `min_age_ms`
+    #[serde(default)]
+    pub min_age_ms: Option<u64>,
 }
 
 impl Default for ConsumerGroupConfig {
@@ -82,6 +91,7 @@ impl Default for ConsumerGroupConfig {
             rebalance_timeout_secs: 60,
             auto_commit: true,
             auto_commit_interval_secs: 5,
+            min_age_ms: None,
         }
     }
 }
@@ -161,6 +171,24 @@ impl ConsumerGroup {
         member
     }
 
+    /// Rejoin with a known member ID, e.g. one restored after a restart.
+    /// The member starts a fresh session and keeps its delayed visibility.
+    pub fn rejoin(
+        &mut self,
+        member_id: &str,
+        session_timeout_secs: u64,
+        min_age_ms: Option<u64>,
+    ) -> ConsumerMember {
+        let mut member = ConsumerMember::new(self.id.clone(), session_timeout_secs);
+        member.id = member_id.to_string();
+        member.min_age_ms = min_age_ms;
+
+        self.members.insert(member.id.clone(), member.clone());
+        self.state = GroupState::Rebalancing;
+
+        member
+    }
+
     /// Leave the consumer group
     pub fn leave(&mut self, member_id: &str) -> Result<(), String> {
         if self.members.remove(member_id).is_some() {
@@ -336,6 +364,29 @@ impl ConsumerGroup {
             .ok_or_else(|| format!("Member {} not found", member_id))
     }
 
+    /// Set a member's delayed visibility (`None` falls back to the group's)
+    pub fn set_member_min_age(
+        &mut self,
+        member_id: &str,
+        min_age_ms: Option<u64>,
+    ) -> Result<(), String> {
+        let member = self
+            .members
+            .get_mut(member_id)
+            .ok_or_else(|| format!("Member {} not found", member_id))?;
+        member.min_age_ms = min_age_ms;
+        Ok(())
+    }
+
+    /// Delayed visibility a member consumes with: its own setting, else the
+    /// group default, else none
+    pub fn member_min_age(&self, member_id: &str) -> Result<u64, String> {
+        self.members
+            .get(member_id)
+            .map(|m| m.min_age_ms.or(self.config.min_age_ms).unwrap_or(0))
+            .ok_or_else(|| format!("Member {} not found", member_id))
+    }
+
     /// Commit offset for a partition
     pub fn commit_offset(&mut self, partition_id: usize, offset: u64) {
         self.committed_offsets.insert(partition_id, offset);
@@ -346,6 +397,23 @@ impl ConsumerGroup {
         self.committed_offsets.get(&partition_id).copied()
     }
 
+    /// Lag of every partition: `end_offsets` maps a partition to its last
+    /// published offset, and a partition never committed counts from 0
+    pub fn lag(&self, end_offsets: &HashMap<usize, u64>) -> Vec<PartitionLag> {
+        (0..self.partition_count)
+            .map(|partition_id| {
+                let end_offset = end_offsets.get(&partition_id).copied().unwrap_or(0);
+                let committed_offset = self.get_offset(partition_id);
+                PartitionLag {
+                    partition_id,
+                    end_offset,
+                    committed_offset,
+                    lag: end_offset.saturating_sub(committed_offset.unwrap_or(0)),
+                }
+            })
+            .collect()
+    }
+
     /// Get group state
     pub fn state(&self) -> GroupState {
         self.state.clone()
@@ -397,6 +465,18 @@ pub struct ConsumerGroupStats {
     pub last_rebalance_secs: u64,
 }
 
+/// How far a group is behind on one partition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionLag {
+    pub partition_id: usize,
+    /// Last published offset
+    pub end_offset: u64,
+    /// `None` until the group commits on this partition
+    pub committed_offset: Option<u64>,
+    /// `end_offset - committed_offset`, clamped at 0
+    pub lag: u64,
+}
+
 /// Consumer group manager
 #[derive(Clone)]
 pub struct ConsumerGroupManager {
@@ -455,6 +535,23 @@ impl ConsumerGroupManager {
         Ok(group.join(session_timeout_secs))
     }
 
+    /// Put a member back into a group under its previous ID
+    pub async fn restore_member(
+        &self,
+        group_id: &str,
+        member_id: &str,
+        session_timeout_secs: u64,
+        min_age_ms: Option<u64>,
+    ) -> Result<ConsumerMember, String> {
+        let mut groups = self.groups.write();
+
+        let group = groups
+            .get_mut(group_id)
+            .ok_or_else(|| format!("Consumer group '{}' not found", group_id))?;
+
+        Ok(group.rejoin(member_id, session_timeout_secs, min_age_ms))
+    }
+
     /// Leave a consumer group
     pub async fn leave_group(&self, group_id: &str, member_id: &str) -> Result<(), String> {
         let mut groups = self.groups.write();
@@ -503,6 +600,33 @@ impl ConsumerGroupManager {
         group.get_assignment(member_id)
     }
 
+    /// Set a member's delayed visibility
+    pub async fn set_member_min_age(
+        &self,
+        group_id: &str,
+        member_id: &str,
+        min_age_ms: Option<u64>,
+    ) -> Result<(), String> {
+        let mut groups = self.groups.write();
+
+        let group = groups
+            .get_mut(group_id)
+            .ok_or_else(|| format!("Consumer group '{}' not found", group_id))?;
+
+        group.set_member_min_age(member_id, min_age_ms)
+    }
+
+    /// Delayed visibility (ms) a member consumes with
+    pub async fn member_min_age(&self, group_id: &str, member_id: &str) -> Result<u64, String> {
+        let groups = self.groups.read();
+
+        let group = groups
+            .get(group_id)
+            .ok_or_else(|| format!("Consumer group '{}' not found", group_id))?;
+
+        group.member_min_age(member_id)
+    }
+
     /// Commit offset
     pub async fn commit_offset(
         &self,
@@ -535,6 +659,16 @@ impl ConsumerGroupManager {
         Ok(group.get_offset(partition_id))
     }
 
+    /// Current members of a group
+    pub async fn group_members(&self, group_id: &str) -> Result<Vec<ConsumerMember>, String> {
+        let groups = self.groups.read();
+
+        groups
+            .get(group_id)
+            .ok_or_else(|| format!("Consumer group '{}' not found", group_id))
+            .map(|g| g.members())
+    }
+
     /// Get group statistics
     pub async fn group_stats(&self, group_id: &str) -> Result<ConsumerGroupStats, String> {
         let groups = self.groups.read();
@@ -545,6 +679,20 @@ impl ConsumerGroupManager {
             .map(|g| g.stats())
     }
 
+    /// Per-partition lag of a group against its topic's `end_offsets`
+    pub async fn group_lag(
+        &self,
+        group_id: &str,
+        end_offsets: &HashMap<usize, u64>,
+    ) -> Result<Vec<PartitionLag>, String> {
+        let groups = self.groups.read();
+
+        groups
+            .get(group_id)
+            .ok_or_else(|| format!("Consumer group '{}' not found", group_id))
+            .map(|g| g.lag(end_offsets))
+    }
+
     /// List all groups
     pub async fn list_groups(&self) -> Vec<String> {
         let groups = self.groups.read();
@@ -624,6 +772,37 @@ mod tests {
         manager.leave_group("group1", &member.id).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_restore_member_keeps_id() {
+        let manager = ConsumerGroupManager::new(ConsumerGroupConfig::default());
+        manager
+            .create_group("group1", "topic1", 4, None)
+            .await
+            .unwrap();
+
+        let member = manager
+            .restore_member("group1", "member-a", 30, Some(500))
+            .await
+            .unwrap();
+        assert_eq!(member.id, "member-a");
+        manager.rebalance_group("group1").await.unwrap();
+
+        assert_eq!(
+            manager
+                .get_assignment("group1", "member-a")
+                .await
+                .unwrap()
+                .len(),
+            4
+        );
+        assert_eq!(
+            manager.member_min_age("group1", "member-a").await.unwrap(),
+            500
+        );
+        assert_eq!(manager.group_members("group1").await.unwrap().len(), 1);
+        assert!(manager.restore_member("nope", "m", 30, None).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_round_robin_assignment() {
         let config = ConsumerGroupConfig {
@@ -683,6 +862,30 @@ mod tests {
         assert_eq!(a1.len() + a2.len() + a3.len(), 7);
     }
 
+    #[tokio::test]
+    async fn test_member_min_age_defaults() {
+        let manager = ConsumerGroupManager::new(ConsumerGroupConfig::default());
+        let config = ConsumerGroupConfig {
+            min_age_ms: Some(30_000),
+            ..Default::default()
+        };
+        manager
+            .create_group("delayed", "topic", 2, Some(config))
+            .await
+            .unwrap();
+
+        let m1 = manager.join_group("delayed", 30).await.unwrap();
+        let m2 = manager.join_group("delayed", 30).await.unwrap();
+        manager
+            .set_member_min_age("delayed", &m2.id, Some(0))
+            .await
+            .unwrap();
+
+        assert_eq!(manager.member_min_age("delayed", &m1.id).await, Ok(30_000));
+        assert_eq!(manager.member_min_age("delayed", &m2.id).await, Ok(0));
+        assert!(manager.member_min_age("delayed", "nobody").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_offset_commit() {
         let manager = ConsumerGroupManager::new(ConsumerGroupConfig::default());
@@ -703,6 +906,28 @@ mod tests {
         assert_eq!(offset1, Some(200));
     }
 
+    #[tokio::test]
+    async fn test_group_lag() {
+        let manager = ConsumerGroupManager::new(ConsumerGroupConfig::default());
+        manager
+            .create_group("lag-group", "topic", 3, None)
+            .await
+            .unwrap();
+        manager.commit_offset("lag-group", 0, 40).await.unwrap();
+        manager.commit_offset("lag-group", 1, 90).await.unwrap();
+
+        let end_offsets = HashMap::from([(0, 100), (1, 80), (2, 10)]);
+        let lag = manager.group_lag("lag-group", &end_offsets).await.unwrap();
+
+        assert_eq!(lag.len(), 3);
+        assert_eq!((lag[0].committed_offset, lag[0].lag), (Some(40), 60));
+        // A commit past the end offset never reports negative lag
+        assert_eq!(lag[1].lag, 0);
+        // Never committed: the whole partition is behind
+        assert_eq!((lag[2].committed_offset, lag[2].lag), (None, 10));
+        assert!(manager.group_lag("nope", &end_offsets).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_heartbeat() {
         let manager = ConsumerGroupManager::new(ConsumerGroupConfig::default());
@@ -3,9 +3,45 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use thiserror::Error;
 
+/// Structured description of a failed command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorInfo {
+    /// Stable upper-snake-case code (e.g. `KEY_NOT_FOUND`, `CAS_FAILED`)
+    pub code: String,
+    /// Broad class the code belongs to
+    pub category: ErrorCategory,
+    /// Human-readable message, same text as `Response::error`
+    pub message: String,
+    /// Whether sending the same request again may succeed
+    pub retryable: bool,
+}
+
+/// Broad class of a failed command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// The addressed key, queue, message or resource does not exist
+    NotFound,
+    /// The write lost against current state (key exists, CAS mismatch)
+    Conflict,
+    /// The request itself is malformed or out of range
+    InvalidRequest,
+    /// Missing or rejected credentials, or insufficient permissions
+    Auth,
+    /// A size, memory, quota or rate limit was hit
+    LimitExceeded,
+    /// The server or slot cannot serve the request right now
+    Unavailable,
+    /// The key lives on another cluster node
+    Redirect,
+    /// Unexpected server-side failure
+    Internal,
+}
+
 /// Main error type for Synap operations
 #[derive(Debug, Error)]
 pub enum SynapError {
@@ -75,6 +111,24 @@ pub enum SynapError {
     #[error("Quota exceeded: {0}")]
     QuotaExceeded(String),
 
+    #[error("Key '{key}' would exceed {limit} ({size} > {max})")]
+    KeyLimitExceeded {
+        key: String,
+        limit: &'static str,
+        max: usize,
+        size: usize,
+    },
+
+    #[error("Message of {size} bytes exceeds the {max} byte limit for topic {topic}")]
+    MessageTooLarge {
+        topic: String,
+        size: usize,
+        max: usize,
+    },
+
+    #[error("Publish rate limit of {limit} messages/sec exceeded for topic {topic}")]
+    PublishRateLimited { topic: String, limit: u32 },
+
     #[error("Bad request: {0}")]
     BadRequest(String),
 
@@ -98,6 +152,10 @@ pub enum SynapError {
     /// Cluster error: Slot not assigned
     #[error("CLUSTERDOWN Slot {slot} not assigned")]
     ClusterSlotNotAssigned { slot: u16 },
+
+    /// Cluster error: Keys of a multi-key command hash to different slots
+    #[error("CROSSSLOT Keys in request don't hash to the same slot: '{first}' and '{other}'")]
+    ClusterCrossSlot { first: String, other: String },
 }
 
 impl SynapError {
@@ -127,12 +185,110 @@ impl SynapError {
             Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             Self::Forbidden(_) => StatusCode::FORBIDDEN,
             Self::QuotaExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::KeyLimitExceeded { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::MessageTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::PublishRateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
             Self::BadRequest(_) => StatusCode::BAD_REQUEST,
             Self::NotFound => StatusCode::NOT_FOUND,
             Self::ResourceNotFound(_) => StatusCode::NOT_FOUND,
             Self::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::ClusterMoved { .. } | Self::ClusterAsk { .. } => StatusCode::MOVED_PERMANENTLY,
             Self::ClusterSlotNotAssigned { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            Self::ClusterCrossSlot { .. } => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// Stable machine-readable code for this error. Clients branch on it, so
+    /// existing codes must never change.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::KeyNotFound(_) => "KEY_NOT_FOUND",
+            Self::KeyExists(_) => "KEY_EXISTS",
+            Self::InvalidValue(_) => "INVALID_VALUE",
+            Self::MemoryLimitExceeded => "MEMORY_LIMIT_EXCEEDED",
+            Self::TTLInvalid(_) => "INVALID_TTL",
+            Self::CASFailed { .. } => "CAS_FAILED",
+            Self::UnknownCommand(_) => "UNKNOWN_COMMAND",
+            Self::InvalidRequest(_) => "INVALID_REQUEST",
+            Self::SerializationError(_) => "SERIALIZATION_ERROR",
+            Self::InternalError(_) | Self::InternalServerError(_) => "INTERNAL",
+            Self::QueueNotFound(_) => "QUEUE_NOT_FOUND",
+            Self::QueueFull(_) => "QUEUE_FULL",
+            Self::MessageNotFound(_) => "MESSAGE_NOT_FOUND",
+            Self::ConsumerNotFound(_) => "CONSUMER_NOT_FOUND",
+            Self::IoError(_) => "IO_ERROR",
+            Self::IndexOutOfRange => "INDEX_OUT_OF_RANGE",
+            Self::StreamOffsetOutOfRange { .. } => "OFFSET_OUT_OF_RANGE",
+            Self::KeyExpired => "KEY_EXPIRED",
+            Self::Timeout => "TIMEOUT",
+            Self::Unauthorized(_) => "UNAUTHORIZED",
+            Self::Forbidden(_) => "FORBIDDEN",
+            Self::QuotaExceeded(_) => "QUOTA_EXCEEDED",
+            Self::KeyLimitExceeded { .. } => "KEY_LIMIT_EXCEEDED",
+            Self::MessageTooLarge { .. } => "MESSAGE_TOO_LARGE",
+            Self::PublishRateLimited { .. } => "RATE_LIMITED",
+            Self::BadRequest(_) => "BAD_REQUEST",
+            Self::NotFound | Self::ResourceNotFound(_) => "NOT_FOUND",
+            Self::ClusterMoved { .. } => "MOVED",
+            Self::ClusterAsk { .. } => "ASK",
+            Self::ClusterSlotNotAssigned { .. } => "CLUSTERDOWN",
+            Self::ClusterCrossSlot { .. } => "CROSSSLOT",
+        }
+    }
+
+    /// Broad class of this error
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::KeyNotFound(_)
+            | Self::QueueNotFound(_)
+            | Self::MessageNotFound(_)
+            | Self::ConsumerNotFound(_)
+            | Self::KeyExpired
+            | Self::NotFound
+            | Self::ResourceNotFound(_) => ErrorCategory::NotFound,
+            Self::KeyExists(_) | Self::CASFailed { .. } => ErrorCategory::Conflict,
+            Self::InvalidValue(_)
+            | Self::TTLInvalid(_)
+            | Self::UnknownCommand(_)
+            | Self::InvalidRequest(_)
+            | Self::IndexOutOfRange
+            | Self::StreamOffsetOutOfRange { .. }
+            | Self::BadRequest(_)
+            | Self::ClusterCrossSlot { .. } => ErrorCategory::InvalidRequest,
+            Self::Unauthorized(_) | Self::Forbidden(_) => ErrorCategory::Auth,
+            Self::MemoryLimitExceeded
+            | Self::QueueFull(_)
+            | Self::QuotaExceeded(_)
+            | Self::KeyLimitExceeded { .. }
+            | Self::MessageTooLarge { .. }
+            | Self::PublishRateLimited { .. } => ErrorCategory::LimitExceeded,
+            Self::Timeout | Self::ClusterSlotNotAssigned { .. } => ErrorCategory::Unavailable,
+            Self::ClusterMoved { .. } | Self::ClusterAsk { .. } => ErrorCategory::Redirect,
+            Self::SerializationError(_)
+            | Self::InternalError(_)
+            | Self::InternalServerError(_)
+            | Self::IoError(_) => ErrorCategory::Internal,
+        }
+    }
+
+    /// Whether sending the same request again, unchanged, may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Timeout
+                | Self::QuotaExceeded(_)
+                | Self::PublishRateLimited { .. }
+                | Self::ClusterSlotNotAssigned { .. }
+        )
+    }
+
+    /// Structured form of this error for the command envelope
+    pub fn error_info(&self) -> ErrorInfo {
+        ErrorInfo {
+            code: self.error_code().to_string(),
+            category: self.category(),
+            message: self.to_string(),
+            retryable: self.is_retryable(),
         }
     }
 }
@@ -144,6 +300,9 @@ impl IntoResponse for SynapError {
         let body = Json(json!({
             "error": self.to_string(),
             "code": status.as_u16(),
+            "error_code": self.error_code(),
+            "category": self.category(),
+            "retryable": self.is_retryable(),
         }));
 
         (status, body).into_response()
@@ -208,6 +367,50 @@ mod tests {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[test]
+    fn test_error_info() {
+        let info = SynapError::KeyNotFound("k".to_string()).error_info();
+        assert_eq!(info.code, "KEY_NOT_FOUND");
+        assert_eq!(info.category, ErrorCategory::NotFound);
+        assert_eq!(info.message, "Key not found: k");
+        assert!(!info.retryable);
+
+        let err = SynapError::PublishRateLimited {
+            topic: "chat".to_string(),
+            limit: 10,
+        };
+        assert_eq!(err.error_code(), "RATE_LIMITED");
+        assert_eq!(err.category(), ErrorCategory::LimitExceeded);
+        assert!(err.is_retryable());
+
+        let err = SynapError::Forbidden("kv.set".to_string());
+        assert_eq!(err.category(), ErrorCategory::Auth);
+        assert!(!err.is_retryable());
+
+        assert_eq!(
+            SynapError::ClusterSlotNotAssigned { slot: 7 }.category(),
+            ErrorCategory::Unavailable
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_body_carries_code() {
+        let response = SynapError::CASFailed {
+            expected: "1".to_string(),
+            actual: "2".to_string(),
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["error_code"], "CAS_FAILED");
+        assert_eq!(body["category"], "conflict");
+        assert_eq!(body["retryable"], false);
+    }
+
     #[test]
     fn test_all_error_variants() {
         // Test that all error variants can be created
@@ -260,4 +463,39 @@ mod tests {
             "Stream offset 100 out of range; earliest retained offset is 500"
         );
     }
+
+    #[test]
+    fn test_key_limit_error() {
+        let err = SynapError::KeyLimitExceeded {
+            key: "events".to_string(),
+            limit: "max_list_length",
+            max: 100,
+            size: 101,
+        };
+        assert_eq!(err.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            err.to_string(),
+            "Key 'events' would exceed max_list_length (101 > 100)"
+        );
+    }
+
+    #[test]
+    fn test_publish_limit_errors() {
+        let err = SynapError::MessageTooLarge {
+            topic: "chat".to_string(),
+            size: 2048,
+            max: 1024,
+        };
+        assert_eq!(err.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            err.to_string(),
+            "Message of 2048 bytes exceeds the 1024 byte limit for topic chat"
+        );
+
+        let err = SynapError::PublishRateLimited {
+            topic: "chat".to_string(),
+            limit: 10,
+        };
+        assert_eq!(err.status_code(), StatusCode::TOO_MANY_REQUESTS);
+    }
 }
@@ -49,6 +49,14 @@ impl DistanceUnit {
             DistanceUnit::Feet => EARTH_RADIUS_FT,
         }
     }
+
+    /// Parse an optional unit argument: meters when it is absent, and the
+    /// invalid-unit error for anything but `m`, `km`, `mi` or `ft`. Every
+    /// transport parses units through this, so a typo never falls back to
+    /// meters.
+    pub fn parse_or_meters(unit: Option<&str>) -> Result<Self> {
+        unit.map_or(Ok(DistanceUnit::Meters), str::parse)
+    }
 }
 
 impl std::str::FromStr for DistanceUnit {
@@ -237,7 +245,7 @@ impl GeospatialStore {
             opts.ch = ch;
 
             let (added_count, _changed_count) =
-                self.sorted_set_store.zadd(key, member, score, &opts);
+                self.sorted_set_store.try_zadd(key, member, score, &opts)?;
             added += added_count;
         }
 
@@ -647,6 +655,21 @@ mod tests {
         GeospatialStore::new(sorted_set_store)
     }
 
+    #[test]
+    fn test_parse_or_meters() {
+        assert_eq!(
+            DistanceUnit::parse_or_meters(None).unwrap(),
+            DistanceUnit::Meters
+        );
+        assert_eq!(
+            DistanceUnit::parse_or_meters(Some("KM")).unwrap(),
+            DistanceUnit::Kilometers
+        );
+        let err = DistanceUnit::parse_or_meters(Some("furlongs")).unwrap_err();
+        assert!(matches!(err, SynapError::InvalidValue(_)));
+        assert!(err.to_string().contains("Invalid distance unit: furlongs"));
+    }
+
     #[test]
     fn test_geoadd_basic() {
         let store = create_store();
@@ -16,6 +16,7 @@
 //! ```
 
 use super::error::{Result, SynapError};
+use super::memory_profile::KeyMemory;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -73,6 +74,12 @@ impl HashValue {
         }
     }
 
+    /// Seconds left before the hash expires, `None` without a TTL
+    pub fn ttl(&self) -> Option<u64> {
+        self.ttl_secs
+            .map(|ttl| (self.created_at + ttl).saturating_sub(Self::current_timestamp()))
+    }
+
     /// Get number of fields
     pub fn len(&self) -> usize {
         self.fields.len()
@@ -83,6 +90,16 @@ impl HashValue {
         self.fields.is_empty()
     }
 
+    /// Encoding and estimated memory of the hash stored under `key`
+    pub fn memory(&self, key: &str) -> KeyMemory {
+        let item_bytes = self
+            .fields
+            .iter()
+            .map(|(field, value)| field.len() + value.len())
+            .sum();
+        KeyMemory::new(key, "hashtable", self.fields.len(), item_bytes)
+    }
+
     /// Set a field value, returns true if field was created (false if updated)
     pub fn set_field(&mut self, field: String, value: Vec<u8>) -> bool {
         self.updated_at = Self::current_timestamp();
@@ -152,6 +169,8 @@ pub struct HashStore {
     mem_bytes: Arc<std::sync::atomic::AtomicI64>,
     /// Optional keyspace-notification publisher (Redis `notify-keyspace-events`).
     keyspace_notifier: Option<Arc<crate::core::KeyspaceNotifier>>,
+    /// Per-key field cap (`max_hash_fields`).
+    key_limits: crate::core::KeyLimits,
 }
 
 /// Statistics for hash operations
@@ -178,6 +197,46 @@ impl HashStore {
         out
     }
 
+    /// Fields of one hash, as [`dump`](Self::dump) would persist them
+    pub fn dump_key(&self, key: &str) -> Option<HashMap<String, Vec<u8>>> {
+        let guard = self.shard_for_key(key).data.read();
+        guard.get(key).map(|hv| hv.fields.clone())
+    }
+
+    /// Seconds left before the hash at `key` expires, `None` if it has no
+    /// TTL or does not exist
+    pub fn ttl(&self, key: &str) -> Option<u64> {
+        let guard = self.shard_for_key(key).data.read();
+        guard.get(key).and_then(HashValue::ttl)
+    }
+
+    /// Replace the hash at `key` with `fields`, expiring `ttl_secs` from now.
+    /// Unlike HMSET, fields not in `fields` do not survive; full syncs and
+    /// dump imports rely on that. Empty `fields` removes the key.
+    pub fn restore(&self, key: &str, fields: HashMap<String, Vec<u8>>, ttl_secs: Option<u64>) {
+        let mut data = self.shard_for_key(key).data.write();
+        if fields.is_empty() {
+            data.remove(key);
+            return;
+        }
+        let mut hash = HashValue::new(ttl_secs);
+        hash.set_multiple(fields);
+        data.insert(key.to_string(), hash);
+    }
+
+    /// Remove every hash, for full syncs. Returns how many there were.
+    pub fn flush(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut map = shard.data.write();
+                let flushed = map.len();
+                map.clear();
+                flushed
+            })
+            .sum()
+    }
+
     /// Create new hash store
     pub fn new() -> Self {
         // Build the fixed-size shard array directly — no fallible Vec→array
@@ -191,6 +250,7 @@ impl HashStore {
             mem: None,
             mem_bytes: Arc::new(std::sync::atomic::AtomicI64::new(0)),
             keyspace_notifier: None,
+            key_limits: crate::core::KeyLimits::default(),
         }
     }
 
@@ -211,6 +271,67 @@ impl HashStore {
         self
     }
 
+    /// Attach per-key limits. Only `max_hash_fields` applies to hashes.
+    pub fn with_key_limits(mut self, limits: crate::core::KeyLimits) -> Self {
+        self.key_limits = limits;
+        self
+    }
+
+    /// The `n` hashes with the most fields, largest first.
+    pub fn largest_keys(&self, n: usize) -> Vec<crate::core::KeySize> {
+        let mut largest = crate::core::limits::Largest::new(n);
+        for shard in self.shards.iter() {
+            for (key, hv) in shard.data.read().iter() {
+                if !hv.is_expired() {
+                    largest.offer(key, hv.len());
+                }
+            }
+        }
+        largest.into_vec()
+    }
+
+    /// Encoding and estimated memory of `key`, `None` when it holds no hash.
+    pub fn key_memory(&self, key: &str) -> Option<KeyMemory> {
+        let map = self.shard_for_key(key).data.read();
+        map.get(key)
+            .filter(|v| !v.is_expired())
+            .map(|v| v.memory(key))
+    }
+
+    /// Estimated memory of every live hash, with the `top` largest by bytes.
+    pub fn memory_profile(&self, top: usize) -> crate::core::MemoryProfile {
+        let mut profiler = crate::core::memory_profile::Profiler::new(top);
+        for shard in self.shards.iter() {
+            for (key, v) in shard.data.read().iter() {
+                if !v.is_expired() {
+                    profiler.offer(key, v.memory(key));
+                }
+            }
+        }
+        profiler.finish()
+    }
+
+    /// Refuse a write that would add fields to `key` past `max_hash_fields`.
+    /// Must run before the hash entry is created so a refused write leaves
+    /// nothing behind.
+    fn check_fields<'a>(
+        &self,
+        data: &HashMap<String, HashValue>,
+        key: &str,
+        fields: impl Iterator<Item = &'a str>,
+    ) -> Result<()> {
+        if self.key_limits.max_hash_fields.is_none() {
+            return Ok(());
+        }
+        let (len, added) = match data.get(key) {
+            Some(hash) if !hash.is_expired() => {
+                (hash.len(), fields.filter(|f| !hash.has_field(f)).count())
+            }
+            _ => (0, fields.count()),
+        };
+        self.key_limits.check_hash(key, len, added)
+    }
+
     /// Publish a hash keyspace notification for `key` if a notifier is attached.
     #[inline]
     fn notify_keyspace(&self, event: &str, key: &str) {
@@ -278,6 +399,7 @@ impl HashStore {
         // publishing the keyspace notification.
         let created = {
             let mut data = shard.data.write();
+            self.check_fields(&data, key, std::iter::once(field))?;
 
             // Get or create hash
             let hash = data
@@ -441,10 +563,11 @@ impl HashStore {
 
     /// HSCAN - cursor-based incremental scan of a hash's fields.
     ///
-    /// `cursor` is an offset into a stably-sorted snapshot of the fields; returns
-    /// the next cursor (0 when the scan is complete) and the matched field/value
-    /// pairs within the scanned window. `pattern` is an optional glob over field
-    /// names; `count` bounds the window size (min 1).
+    /// `cursor` is an offset into the fields in sorted order; returns the next
+    /// cursor (0 when the scan is complete) and the matched field/value pairs
+    /// within the scanned window. `pattern` is an optional glob over field
+    /// names; `count` bounds the window size (min 1). Only the window is
+    /// copied out of the hash.
     pub fn hscan(
         &self,
         key: &str,
@@ -452,17 +575,25 @@ impl HashStore {
         pattern: Option<&str>,
         count: usize,
     ) -> Result<HScanPage> {
-        let mut fields: Vec<(String, Vec<u8>)> = self.hgetall(key)?.into_iter().collect();
-        fields.sort_by(|a, b| a.0.cmp(&b.0));
-        let total = fields.len();
-        let start = (cursor as usize).min(total);
-        let end = start.saturating_add(count.max(1)).min(total);
-        let items = fields[start..end]
-            .iter()
+        let shard = self.shard_for_key(key);
+        let data = shard.data.read();
+
+        let Some(hash) = data.get(key) else {
+            return Ok((0, Vec::new()));
+        };
+        if hash.is_expired() {
+            drop(data);
+            shard.data.write().remove(key);
+            return Ok((0, Vec::new()));
+        }
+
+        let fields = hash.fields.iter().map(|(f, v)| (f.as_str(), v)).collect();
+        let (next, window) = crate::core::scan::scan_window(fields, cursor, count);
+        let items = window
+            .into_iter()
             .filter(|(f, _)| pattern.is_none_or(|p| crate::core::glob_match(p, f)))
-            .cloned()
+            .map(|(f, v)| (f.to_owned(), v.clone()))
             .collect();
-        let next = if end < total { end as u64 } else { 0 };
         Ok((next, items))
     }
 
@@ -528,6 +659,7 @@ impl HashStore {
         self.check_admit(fields.iter().map(|(f, v)| f.len() + v.len()).sum())?;
         let shard = self.shard_for_key(key);
         let mut data = shard.data.write();
+        self.check_fields(&data, key, fields.keys().map(String::as_str))?;
 
         // Get or create hash
         let hash = data
@@ -584,6 +716,7 @@ impl HashStore {
     pub fn hincrby(&self, key: &str, field: &str, increment: i64) -> Result<i64> {
         let shard = self.shard_for_key(key);
         let mut data = shard.data.write();
+        self.check_fields(&data, key, std::iter::once(field))?;
 
         // Get or create hash
         let hash = data
@@ -635,6 +768,7 @@ impl HashStore {
     pub fn hincrbyfloat(&self, key: &str, field: &str, increment: f64) -> Result<f64> {
         let shard = self.shard_for_key(key);
         let mut data = shard.data.write();
+        self.check_fields(&data, key, std::iter::once(field))?;
 
         // Get or create hash
         let hash = data
@@ -684,6 +818,7 @@ impl HashStore {
         self.check_admit(field.len() + value.len())?;
         let shard = self.shard_for_key(key);
         let mut data = shard.data.write();
+        self.check_fields(&data, key, std::iter::once(field))?;
 
         // Get or create hash
         let hash = data
@@ -774,6 +909,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hash_field_limit_refuses_new_fields_only() {
+        let store = HashStore::new().with_key_limits(crate::core::KeyLimits {
+            max_hash_fields: Some(2),
+            ..Default::default()
+        });
+        store.hset("h", "a", b"1".to_vec()).unwrap();
+        store.hset("h", "b", b"2".to_vec()).unwrap();
+
+        let err = store.hset("h", "c", b"3".to_vec());
+        assert!(matches!(err, Err(SynapError::KeyLimitExceeded { .. })));
+        let err = store.hmset(
+            "h",
+            HashMap::from([
+                ("b".to_string(), b"x".to_vec()),
+                ("c".to_string(), b"y".to_vec()),
+            ]),
+        );
+        assert!(matches!(err, Err(SynapError::KeyLimitExceeded { .. })));
+        assert_eq!(store.hget("h", "b").unwrap(), Some(b"2".to_vec()));
+
+        // Overwriting and incrementing existing fields is fine
+        store.hset("h", "a", b"10".to_vec()).unwrap();
+        assert_eq!(store.hincrby("h", "a", 1).unwrap(), 11);
+        assert!(store.hincrby("h", "z", 1).is_err());
+
+        // A refused write to a fresh key leaves nothing behind
+        let big: HashMap<_, _> = (0..3).map(|i| (format!("f{i}"), vec![])).collect();
+        assert!(store.hmset("other", big).is_err());
+        assert_eq!(store.hlen("other").unwrap(), 0);
+
+        let largest = store.largest_keys(10);
+        assert_eq!(largest.len(), 1);
+        assert_eq!(largest[0].key, "h");
+        assert_eq!(largest[0].size, 2);
+    }
+
     #[tokio::test]
     async fn budget_is_shared_across_datatypes() {
         use crate::core::{GlobalMemory, KVConfig, KVStore};
@@ -0,0 +1,234 @@
+//! Hot key detection.
+//!
+//! [`HotKeyTracker`] counts reads and writes per key for 1 in `sample_rate`
+//! KV operations, in fixed intervals. Sampling operations rather than keys
+//! means a key's share of the samples tracks its share of the traffic, so
+//! the hottest keys surface whichever they are. Counts are scaled back up by
+//! the sample rate when reported.
+//!
+//! The table is bounded: once `max_tracked` keys are counted in an interval,
+//! the coldest half is dropped to make room, so a long tail of one-off keys
+//! cannot push out the hot ones.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// Kind of access recorded by [`HotKeyTracker::record`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotKeyOp {
+    Read,
+    Write,
+}
+
+/// Estimated accesses of one key in an interval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HotKey {
+    pub key: String,
+    pub reads: u64,
+    pub writes: u64,
+}
+
+impl HotKey {
+    pub fn total(&self) -> u64 {
+        self.reads + self.writes
+    }
+}
+
+/// The hottest keys of the running and the last completed interval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HotKeysReport {
+    pub sample_rate: u32,
+    pub interval_secs: u64,
+    /// Seconds into the running interval.
+    pub elapsed_secs: u64,
+    /// Hottest keys so far in the running interval, hottest first.
+    pub current: Vec<HotKey>,
+    /// Hottest keys of the last completed interval, hottest first; empty
+    /// when no access was sampled in it.
+    pub previous: Vec<HotKey>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counts {
+    reads: u64,
+    writes: u64,
+}
+
+impl Counts {
+    fn total(&self) -> u64 {
+        self.reads + self.writes
+    }
+}
+
+struct Window {
+    started: Instant,
+    counts: HashMap<String, Counts>,
+    previous: HashMap<String, Counts>,
+}
+
+/// Sampled per-interval access counts of KV keys.
+pub struct HotKeyTracker {
+    sample_rate: u32,
+    interval: Duration,
+    max_tracked: usize,
+    ops: AtomicU64,
+    window: Mutex<Window>,
+}
+
+impl HotKeyTracker {
+    /// Count 1 in `sample_rate` operations per `interval`, tracking at most
+    /// `max_tracked` keys at a time.
+    pub fn new(sample_rate: u32, interval: Duration, max_tracked: usize) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            interval: interval.max(Duration::from_secs(1)),
+            max_tracked: max_tracked.max(2),
+            ops: AtomicU64::new(0),
+            window: Mutex::new(Window {
+                started: Instant::now(),
+                counts: HashMap::new(),
+                previous: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Record an access to `key` if this operation is sampled
+    #[inline]
+    pub fn record(&self, key: &str, op: HotKeyOp) {
+        if self.sample_rate > 1
+            && !self
+                .ops
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(self.sample_rate as u64)
+        {
+            return;
+        }
+
+        let mut window = self.window.lock();
+        self.roll(&mut window);
+        if !window.counts.contains_key(key) && window.counts.len() >= self.max_tracked {
+            Self::drop_coldest_half(&mut window.counts);
+        }
+        let counts = window.counts.entry(key.to_string()).or_default();
+        match op {
+            HotKeyOp::Read => counts.reads += 1,
+            HotKeyOp::Write => counts.writes += 1,
+        }
+    }
+
+    /// The `top` hottest keys of the running and the last completed interval
+    pub fn report(&self, top: usize) -> HotKeysReport {
+        let mut window = self.window.lock();
+        self.roll(&mut window);
+        HotKeysReport {
+            sample_rate: self.sample_rate,
+            interval_secs: self.interval.as_secs(),
+            elapsed_secs: window.started.elapsed().as_secs(),
+            current: self.hottest(&window.counts, top),
+            previous: self.hottest(&window.previous, top),
+        }
+    }
+
+    /// One in how many operations is sampled
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Length of one counting interval
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Close the running interval if it is over. An interval with no
+    /// sampled access in between leaves nothing to report as previous.
+    fn roll(&self, window: &mut Window) {
+        let elapsed = window.started.elapsed();
+        if elapsed < self.interval {
+            return;
+        }
+        window.previous = if elapsed < self.interval * 2 {
+            std::mem::take(&mut window.counts)
+        } else {
+            window.counts.clear();
+            HashMap::new()
+        };
+        let intervals = (elapsed.as_nanos() / self.interval.as_nanos()) as u32;
+        window.started += self.interval * intervals;
+    }
+
+    fn drop_coldest_half(counts: &mut HashMap<String, Counts>) {
+        let mut totals: Vec<u64> = counts.values().map(Counts::total).collect();
+        let mid = totals.len() / 2;
+        let (_, cutoff, _) = totals.select_nth_unstable(mid);
+        let cutoff = *cutoff;
+        counts.retain(|_, c| c.total() > cutoff);
+    }
+
+    fn hottest(&self, counts: &HashMap<String, Counts>, top: usize) -> Vec<HotKey> {
+        let scale = self.sample_rate as u64;
+        let mut keys: Vec<HotKey> = counts
+            .iter()
+            .map(|(key, c)| HotKey {
+                key: key.clone(),
+                reads: c.reads * scale,
+                writes: c.writes * scale,
+            })
+            .collect();
+        keys.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.key.cmp(&b.key)));
+        keys.truncate(top);
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_are_ranked_and_scaled() {
+        let tracker = HotKeyTracker::new(1, Duration::from_secs(60), 100);
+        for _ in 0..5 {
+            tracker.record("hot", HotKeyOp::Read);
+        }
+        tracker.record("hot", HotKeyOp::Write);
+        tracker.record("warm", HotKeyOp::Write);
+
+        let report = tracker.report(1);
+        assert_eq!(
+            report.current,
+            vec![HotKey {
+                key: "hot".to_string(),
+                reads: 5,
+                writes: 1
+            }]
+        );
+        assert!(report.previous.is_empty());
+    }
+
+    #[test]
+    fn only_one_in_sample_rate_operations_is_counted() {
+        let tracker = HotKeyTracker::new(4, Duration::from_secs(60), 100);
+        for _ in 0..8 {
+            tracker.record("k", HotKeyOp::Read);
+        }
+        // Two samples, scaled by the rate.
+        assert_eq!(tracker.report(10).current[0].reads, 8);
+    }
+
+    #[test]
+    fn full_table_keeps_the_hot_keys() {
+        let tracker = HotKeyTracker::new(1, Duration::from_secs(60), 4);
+        for _ in 0..10 {
+            tracker.record("hot", HotKeyOp::Read);
+        }
+        for i in 0..20 {
+            tracker.record(&format!("cold:{i}"), HotKeyOp::Read);
+        }
+        let report = tracker.report(1);
+        assert_eq!(report.current[0].key, "hot");
+    }
+}
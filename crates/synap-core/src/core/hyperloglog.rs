@@ -81,6 +81,12 @@ impl HyperLogLogValue {
         }
     }
 
+    /// Seconds left before the HLL expires, `None` without a TTL
+    pub fn ttl(&self) -> Option<u64> {
+        self.ttl_secs
+            .map(|ttl| (self.created_at + ttl).saturating_sub(Self::current_timestamp()))
+    }
+
     /// Add element(s) to HyperLogLog
     /// Returns number of elements that were actually new (approximate)
     pub fn pfadd(&mut self, elements: Vec<Vec<u8>>) -> usize {
@@ -282,20 +288,8 @@ impl HyperLogLogStore {
 
     /// PFMERGE - Merge multiple HyperLogLogs into destination
     pub fn pfmerge(&self, dest_key: &str, source_keys: Vec<String>) -> Result<u64> {
-        let dest_shard = self.shard(dest_key);
-        let mut dest_map = dest_shard.write();
-
-        // Create or get destination HLL
-        let dest_hll = dest_map
-            .entry(dest_key.to_string())
-            .or_insert_with(|| HyperLogLogValue::new(None));
-
-        if dest_hll.is_expired() {
-            let ttl = dest_hll.ttl_secs;
-            *dest_hll = HyperLogLogValue::new(ttl);
-        }
-
-        // Collect source HLLs
+        // Collect source HLLs before locking the destination: a source in the
+        // destination's shard would otherwise deadlock on the read lock
         let mut source_hlls = Vec::new();
         for source_key in &source_keys {
             if source_key == dest_key {
@@ -312,6 +306,19 @@ impl HyperLogLogStore {
             }
         }
 
+        let dest_shard = self.shard(dest_key);
+        let mut dest_map = dest_shard.write();
+
+        // Create or get destination HLL
+        let dest_hll = dest_map
+            .entry(dest_key.to_string())
+            .or_insert_with(|| HyperLogLogValue::new(None));
+
+        if dest_hll.is_expired() {
+            let ttl = dest_hll.ttl_secs;
+            *dest_hll = HyperLogLogValue::new(ttl);
+        }
+
         // Merge all sources into destination
         for source_hll in source_hlls {
             dest_hll.merge(&source_hll);
@@ -323,6 +330,92 @@ impl HyperLogLogStore {
         Ok(count)
     }
 
+    /// Dump the registers of every live HLL (key -> registers), for full
+    /// syncs and dumps
+    pub fn dump(&self) -> HashMap<String, Vec<u8>> {
+        let mut out = HashMap::new();
+        for shard in &self.shards {
+            let guard = shard.read();
+            for (key, hll) in guard.iter() {
+                if !hll.is_expired() {
+                    out.insert(key.clone(), hll.registers.clone());
+                }
+            }
+        }
+        out
+    }
+
+    /// Registers of the HLL at `key`, or `None` if it is missing or expired
+    pub fn registers(&self, key: &str) -> Option<Vec<u8>> {
+        let map = self.shard(key).read();
+        map.get(key)
+            .filter(|hll| !hll.is_expired())
+            .map(|hll| hll.registers.clone())
+    }
+
+    /// Replace the registers of the HLL at `key`, creating it without a TTL
+    /// if it is missing or expired. Used to replay a logged PFMERGE.
+    pub fn restore_registers(&self, key: &str, registers: Vec<u8>) -> Result<()> {
+        if registers.len() != HLL_REGISTER_COUNT {
+            return Err(SynapError::InvalidValue(format!(
+                "HyperLogLog needs {} registers, got {}",
+                HLL_REGISTER_COUNT,
+                registers.len()
+            )));
+        }
+
+        let mut map = self.shard(key).write();
+        let hll = map
+            .entry(key.to_string())
+            .or_insert_with(|| HyperLogLogValue::new(None));
+        if hll.is_expired() {
+            *hll = HyperLogLogValue::new(None);
+        }
+        hll.registers = registers;
+        hll.updated_at = HyperLogLogValue::current_timestamp();
+
+        Ok(())
+    }
+
+    /// Seconds left before the HLL at `key` expires, `None` if it has no TTL
+    /// or does not exist
+    pub fn ttl(&self, key: &str) -> Option<u64> {
+        self.shard(key)
+            .read()
+            .get(key)
+            .and_then(HyperLogLogValue::ttl)
+    }
+
+    /// Replace the HLL at `key` with `registers`, expiring `ttl_secs` from
+    /// now, for full syncs and dump imports.
+    pub fn restore(&self, key: &str, registers: Vec<u8>, ttl_secs: Option<u64>) -> Result<()> {
+        if registers.len() != HLL_REGISTER_COUNT {
+            return Err(SynapError::InvalidValue(format!(
+                "HyperLogLog needs {} registers, got {}",
+                HLL_REGISTER_COUNT,
+                registers.len()
+            )));
+        }
+
+        let mut hll = HyperLogLogValue::new(ttl_secs);
+        hll.registers = registers;
+        self.shard(key).write().insert(key.to_string(), hll);
+        Ok(())
+    }
+
+    /// Remove every HyperLogLog, for full syncs. Returns how many there were.
+    pub fn flush(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut map = shard.write();
+                let flushed = map.len();
+                map.clear();
+                flushed
+            })
+            .sum()
+    }
+
     /// Get statistics
     pub fn stats(&self) -> HyperLogLogStats {
         let mut stats = self.stats.read().clone();
@@ -637,6 +730,43 @@ mod tests {
         assert!(merged >= 2);
     }
 
+    #[test]
+    fn pfmerge_source_in_destination_shard() {
+        let store = HyperLogLogStore::new();
+
+        let source = (0..)
+            .map(|i| format!("source:{i}"))
+            .find(|key| store.shard_index(key) == store.shard_index("dest"))
+            .unwrap();
+        store
+            .pfadd(&source, vec![b"alpha".to_vec(), b"beta".to_vec()], None)
+            .unwrap();
+
+        let merged = store.pfmerge("dest", vec![source]).unwrap();
+        assert!(merged >= 2);
+    }
+
+    #[test]
+    fn restore_registers_round_trips() {
+        let store = HyperLogLogStore::new();
+        store
+            .pfadd("source", vec![b"a".to_vec(), b"b".to_vec()], None)
+            .unwrap();
+        assert_eq!(store.registers("missing"), None);
+
+        let registers = store.registers("source").unwrap();
+        store.restore_registers("copy", registers).unwrap();
+        assert_eq!(
+            store.pfcount("copy").unwrap(),
+            store.pfcount("source").unwrap()
+        );
+
+        assert!(matches!(
+            store.restore_registers("copy", vec![0; 16]),
+            Err(SynapError::InvalidValue(_))
+        ));
+    }
+
     #[test]
     fn reset_stats_clears_counters() {
         let store = HyperLogLogStore::new();
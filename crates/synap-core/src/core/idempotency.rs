@@ -0,0 +1,121 @@
+//! Time-bounded memory of publish idempotency keys.
+//!
+//! A producer that retries a publish after a timeout cannot tell whether the
+//! first attempt landed. Tagging both attempts with the same idempotency key
+//! lets the queue or stream room recognise the retry: each resource keeps an
+//! [`IdempotencyWindow`] of the keys it has seen recently and what the first
+//! publish produced (a message id or an offset), and answers a repeat with
+//! that instead of publishing again.
+//!
+//! Keys are forgotten once they are older than the window, so memory is
+//! bounded by the publish rate times the window. The window lives in memory
+//! only: a restart or failover forgets it.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Default `dedup_window_secs` of queues and stream rooms
+pub(crate) fn default_dedup_window_secs() -> u64 {
+    300
+}
+
+/// Recently seen idempotency keys of one queue or room.
+#[derive(Debug)]
+pub(crate) struct IdempotencyWindow<V> {
+    window: Duration,
+    seen: HashMap<String, V>,
+    /// Keys in the order they were first seen, which is also expiry order
+    order: VecDeque<(Instant, String)>,
+}
+
+impl<V: Clone> IdempotencyWindow<V> {
+    /// Remember keys for `window_secs`; `0` remembers nothing, so every
+    /// publish goes through.
+    pub(crate) fn new(window_secs: u64) -> Self {
+        Self {
+            window: Duration::from_secs(window_secs),
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// What the first publish with `key` produced, if it is still remembered
+    pub(crate) fn get(&mut self, key: &str) -> Option<V> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Remember that `key` produced `value`
+    pub(crate) fn insert(&mut self, key: String, value: V) {
+        self.insert_at(key, value, Instant::now());
+    }
+
+    fn get_at(&mut self, key: &str, now: Instant) -> Option<V> {
+        self.prune(now);
+        self.seen.get(key).cloned()
+    }
+
+    fn insert_at(&mut self, key: String, value: V, now: Instant) {
+        if self.window.is_zero() {
+            return;
+        }
+        self.prune(now);
+        if !self.seen.contains_key(&key) {
+            self.order.push_back((now, key.clone()));
+            self.seen.insert(key, value);
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some((seen_at, _)) = self.order.front() {
+            if now.duration_since(*seen_at) < self.window {
+                break;
+            }
+            if let Some((_, key)) = self.order.pop_front() {
+                self.seen.remove(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_key_returns_first_value() {
+        let mut window = IdempotencyWindow::new(60);
+        assert_eq!(window.get("k1"), None);
+        window.insert("k1".to_string(), 7u64);
+        window.insert("k1".to_string(), 8u64);
+        assert_eq!(window.get("k1"), Some(7));
+        assert_eq!(window.get("k2"), None);
+        assert_eq!(window.seen.len(), 1);
+    }
+
+    #[test]
+    fn test_keys_expire_after_the_window() {
+        let mut window = IdempotencyWindow::new(10);
+        let start = Instant::now();
+        window.insert_at("old".to_string(), 1u64, start);
+        window.insert_at("new".to_string(), 2u64, start + Duration::from_secs(5));
+
+        assert_eq!(
+            window.get_at("old", start + Duration::from_secs(9)),
+            Some(1)
+        );
+        assert_eq!(window.get_at("old", start + Duration::from_secs(10)), None);
+        assert_eq!(
+            window.get_at("new", start + Duration::from_secs(10)),
+            Some(2)
+        );
+        assert_eq!(window.seen.len(), 1);
+    }
+
+    #[test]
+    fn test_zero_window_remembers_nothing() {
+        let mut window = IdempotencyWindow::new(0);
+        window.insert("k".to_string(), 1u64);
+        assert_eq!(window.get("k"), None);
+        assert!(window.seen.is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+//! Serialized payloads of single keys (`key.dump` / `key.restore`).
+//!
+//! A payload is opaque to clients but stable across versions and servers:
+//!
+//! | Field   | Encoding                                        |
+//! |---------|-------------------------------------------------|
+//! | version | `u8`, currently `1`                             |
+//! | type    | `u8`: string, hash, list, set, zset (`0`-`4`)   |
+//! | ttl     | `u64` LE, milliseconds left; `0` for no expiry  |
+//! | count   | `u32` LE, number of entries                     |
+//! | entries | length-prefixed blobs, see [`DumpedValue`]      |
+//! | crc     | `u32` LE CRC32 of everything before it          |
+//!
+//! Values are dumped as clients see them, so a payload restores into a
+//! server with different compression settings.
+
+use super::error::{Result, SynapError};
+use super::key_manager::KeyType;
+
+const VERSION: u8 = 1;
+/// version + type + ttl + count
+const HEADER_LEN: usize = 1 + 1 + 8 + 4;
+const CRC_LEN: usize = 4;
+
+/// The value of one key, in the order the store returns it
+#[derive(Debug, Clone, PartialEq)]
+pub enum DumpedValue {
+    String(Vec<u8>),
+    Hash(Vec<(String, Vec<u8>)>),
+    /// Elements from head to tail
+    List(Vec<Vec<u8>>),
+    Set(Vec<Vec<u8>>),
+    /// Members with their scores, lowest score first
+    SortedSet(Vec<(Vec<u8>, f64)>),
+}
+
+impl DumpedValue {
+    pub fn key_type(&self) -> KeyType {
+        match self {
+            Self::String(_) => KeyType::String,
+            Self::Hash(_) => KeyType::Hash,
+            Self::List(_) => KeyType::List,
+            Self::Set(_) => KeyType::Set,
+            Self::SortedSet(_) => KeyType::SortedSet,
+        }
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            Self::String(_) => 0,
+            Self::Hash(_) => 1,
+            Self::List(_) => 2,
+            Self::Set(_) => 3,
+            Self::SortedSet(_) => 4,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::String(_) => 1,
+            Self::Hash(fields) => fields.len(),
+            Self::List(items) | Self::Set(items) => items.len(),
+            Self::SortedSet(members) => members.len(),
+        }
+    }
+}
+
+/// A key's value and remaining time to live
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyDump {
+    pub value: DumpedValue,
+    /// Milliseconds left before the key expires
+    pub ttl_ms: Option<u64>,
+}
+
+impl KeyDump {
+    /// Serialize into a payload
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + CRC_LEN);
+        out.push(VERSION);
+        out.push(self.value.tag());
+        out.extend_from_slice(&self.ttl_ms.unwrap_or(0).to_le_bytes());
+        out.extend_from_slice(&(self.value.len() as u32).to_le_bytes());
+        match &self.value {
+            DumpedValue::String(value) => put_blob(&mut out, value),
+            DumpedValue::Hash(fields) => {
+                for (field, value) in fields {
+                    put_blob(&mut out, field.as_bytes());
+                    put_blob(&mut out, value);
+                }
+            }
+            DumpedValue::List(items) | DumpedValue::Set(items) => {
+                for item in items {
+                    put_blob(&mut out, item);
+                }
+            }
+            DumpedValue::SortedSet(members) => {
+                for (member, score) in members {
+                    put_blob(&mut out, member);
+                    out.extend_from_slice(&score.to_le_bytes());
+                }
+            }
+        }
+        let crc = crc32fast::hash(&out);
+        out.extend_from_slice(&crc.to_le_bytes());
+        out
+    }
+
+    /// Parse a payload produced by [`KeyDump::encode`]
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        if payload.len() < HEADER_LEN + CRC_LEN {
+            return Err(bad_payload());
+        }
+        let (body, crc) = payload.split_at(payload.len() - CRC_LEN);
+        if crc32fast::hash(body) != u32::from_le_bytes(crc.try_into().unwrap())
+            || body[0] != VERSION
+        {
+            return Err(bad_payload());
+        }
+
+        let mut reader = Reader {
+            buf: &body[HEADER_LEN..],
+        };
+        let tag = body[1];
+        let ttl_ms = u64::from_le_bytes(body[2..10].try_into().unwrap());
+        let count = u32::from_le_bytes(body[10..14].try_into().unwrap()) as usize;
+        // Every entry takes at least four bytes; don't trust `count` further
+        let capacity = count.min(reader.buf.len() / 4);
+
+        let value = match tag {
+            0 if count == 1 => DumpedValue::String(reader.blob()?),
+            1 => {
+                let mut fields = Vec::with_capacity(capacity);
+                for _ in 0..count {
+                    let field = String::from_utf8(reader.blob()?).map_err(|_| bad_payload())?;
+                    fields.push((field, reader.blob()?));
+                }
+                DumpedValue::Hash(fields)
+            }
+            2 | 3 => {
+                let mut items = Vec::with_capacity(capacity);
+                for _ in 0..count {
+                    items.push(reader.blob()?);
+                }
+                if tag == 2 {
+                    DumpedValue::List(items)
+                } else {
+                    DumpedValue::Set(items)
+                }
+            }
+            4 => {
+                let mut members = Vec::with_capacity(capacity);
+                for _ in 0..count {
+                    let member = reader.blob()?;
+                    members.push((
+                        member,
+                        f64::from_le_bytes(reader.take(8)?.try_into().unwrap()),
+                    ));
+                }
+                DumpedValue::SortedSet(members)
+            }
+            _ => return Err(bad_payload()),
+        };
+        if !reader.buf.is_empty() {
+            return Err(bad_payload());
+        }
+
+        Ok(Self {
+            value,
+            ttl_ms: (ttl_ms > 0).then_some(ttl_ms),
+        })
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(bad_payload());
+        }
+        let (head, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(head)
+    }
+
+    fn blob(&mut self) -> Result<Vec<u8>> {
+        let len = u32::from_le_bytes(self.take(4)?.try_into().unwrap()) as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+}
+
+fn put_blob(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn bad_payload() -> SynapError {
+    SynapError::InvalidRequest("DUMP payload version or checksum are wrong".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_every_type() {
+        let values = [
+            DumpedValue::String(b"hello".to_vec()),
+            DumpedValue::String(Vec::new()),
+            DumpedValue::Hash(vec![("f".into(), b"v".to_vec()), ("g".into(), vec![])]),
+            DumpedValue::List(vec![b"a".to_vec(), b"b".to_vec(), b"a".to_vec()]),
+            DumpedValue::Set(vec![b"m".to_vec()]),
+            DumpedValue::SortedSet(vec![(b"low".to_vec(), -1.5), (b"high".to_vec(), 1e9)]),
+        ];
+        for value in values {
+            for ttl_ms in [None, Some(1500)] {
+                let dump = KeyDump {
+                    value: value.clone(),
+                    ttl_ms,
+                };
+                assert_eq!(KeyDump::decode(&dump.encode()).unwrap(), dump);
+            }
+        }
+    }
+
+    #[test]
+    fn test_corrupt_payloads_are_rejected() {
+        let payload = KeyDump {
+            value: DumpedValue::List(vec![b"a".to_vec()]),
+            ttl_ms: None,
+        }
+        .encode();
+
+        let mut flipped = payload.clone();
+        flipped[HEADER_LEN + 4] ^= 0xff;
+        assert!(KeyDump::decode(&flipped).is_err());
+        assert!(KeyDump::decode(&payload[..payload.len() - 1]).is_err());
+        assert!(KeyDump::decode(&[]).is_err());
+
+        // A future version with a valid checksum
+        let mut newer = payload[..payload.len() - CRC_LEN].to_vec();
+        newer[0] = VERSION + 1;
+        let crc = crc32fast::hash(&newer);
+        newer.extend_from_slice(&crc.to_le_bytes());
+        assert!(KeyDump::decode(&newer).is_err());
+    }
+}
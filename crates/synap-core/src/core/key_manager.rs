@@ -1,4 +1,5 @@
 use super::error::{Result, SynapError};
+use super::key_dump::{DumpedValue, KeyDump};
 use super::{HashStore, KVStore, ListStore, SetStore, SortedSetStore};
 use rand::RngExt;
 use std::sync::Arc;
@@ -343,6 +344,113 @@ impl KeyManager {
         Ok(true)
     }
 
+    /// DUMP: Value and remaining TTL of a key, `None` if it does not exist
+    pub async fn dump(&self, key: &str) -> Result<Option<KeyDump>> {
+        debug!("DUMP key={}", key);
+
+        let value = match self.key_type(key).await? {
+            KeyType::String => match self.kv_store.get(key).await? {
+                Some(value) => DumpedValue::String(value),
+                None => return Ok(None),
+            },
+            KeyType::Hash => {
+                let mut fields: Vec<_> = self.hash_store.hgetall(key)?.into_iter().collect();
+                fields.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+                DumpedValue::Hash(fields)
+            }
+            KeyType::List => DumpedValue::List(self.list_store.lrange(key, 0, -1)?),
+            KeyType::Set => DumpedValue::Set(self.set_store.smembers(key)?),
+            KeyType::SortedSet => DumpedValue::SortedSet(
+                self.sorted_set_store
+                    .zrange(key, 0, -1, true)
+                    .into_iter()
+                    .map(|m| (m.member, m.score))
+                    .collect(),
+            ),
+            KeyType::None => return Ok(None),
+        };
+
+        // Only strings expire
+        let ttl_ms = match value {
+            DumpedValue::String(_) => self
+                .kv_store
+                .ttl(key)
+                .await
+                .ok()
+                .flatten()
+                .map(|secs| secs * 1000),
+            _ => None,
+        };
+
+        Ok(Some(KeyDump { value, ttl_ms }))
+    }
+
+    /// RESTORE: Create `key` from a dumped value, expiring after
+    /// `dump.ttl_ms`. Fails with `KeyExists` unless `replace` is set.
+    /// A TTL of zero has already run out: the old value is still replaced,
+    /// but nothing is created. Returns the value it replaced.
+    pub async fn restore(
+        &self,
+        key: &str,
+        dump: &KeyDump,
+        replace: bool,
+    ) -> Result<Option<KeyDump>> {
+        debug!("RESTORE key={}, replace={}", key, replace);
+
+        if dump.ttl_ms.is_some_and(|ms| ms > 0) && !matches!(dump.value, DumpedValue::String(_)) {
+            return Err(SynapError::InvalidRequest(format!(
+                "Only string keys can expire, payload holds a {}",
+                dump.value.key_type().as_str()
+            )));
+        }
+
+        let replaced = if self.exists(key).await? {
+            if !replace {
+                return Err(SynapError::KeyExists(key.to_string()));
+            }
+            let replaced = self.dump(key).await?;
+            self.delete(key).await?;
+            replaced
+        } else {
+            None
+        };
+
+        if dump.ttl_ms == Some(0) {
+            return Ok(replaced);
+        }
+
+        match &dump.value {
+            DumpedValue::String(value) => {
+                // Whole seconds, rounded up so a sub-second TTL doesn't become zero
+                let ttl = dump.ttl_ms.map(|ms| ms.div_ceil(1000));
+                self.kv_store.set(key, value.clone(), ttl).await?;
+            }
+            DumpedValue::Hash(fields) => {
+                for (field, value) in fields {
+                    self.hash_store.hset(key, field, value.clone())?;
+                }
+            }
+            DumpedValue::List(items) => {
+                if !items.is_empty() {
+                    self.list_store.rpush(key, items.clone(), false)?;
+                }
+            }
+            DumpedValue::Set(members) => {
+                if !members.is_empty() {
+                    self.set_store.sadd(key, members.clone())?;
+                }
+            }
+            DumpedValue::SortedSet(members) => {
+                for (member, score) in members {
+                    self.sorted_set_store
+                        .zadd(key, member.clone(), *score, &Default::default());
+                }
+            }
+        }
+
+        Ok(replaced)
+    }
+
     /// RANDOMKEY: Get a random key from any store
     pub async fn randomkey(&self) -> Result<Option<String>> {
         debug!("RANDOMKEY");
@@ -797,6 +905,62 @@ mod tests {
         assert!(mgr.rename("missing", "x").await.is_err());
     }
 
+    #[tokio::test]
+    async fn test_dump_and_restore() {
+        let (kv, hash, list, set, zset, mgr) = full_manager();
+        kv.set("k", b"v".to_vec(), Some(60)).await.unwrap();
+        hash.hset("h", "f", b"1".to_vec()).unwrap();
+        list.rpush("l", vec![b"a".to_vec(), b"b".to_vec()], false)
+            .unwrap();
+        set.sadd("s", vec![b"m".to_vec()]).unwrap();
+        zset.zadd("z", b"m".to_vec(), 2.5, &Default::default());
+
+        assert_eq!(mgr.dump("missing").await.unwrap(), None);
+        let string = mgr.dump("k").await.unwrap().unwrap();
+        assert_eq!(string.value, DumpedValue::String(b"v".to_vec()));
+        assert!(string.ttl_ms.unwrap() > 58_000);
+
+        for key in ["k", "h", "l", "s", "z"] {
+            let dump = mgr.dump(key).await.unwrap().unwrap();
+            let copy = format!("{key}2");
+            assert_eq!(mgr.restore(&copy, &dump, false).await.unwrap(), None);
+            assert_eq!(mgr.dump(&copy).await.unwrap().unwrap().value, dump.value);
+        }
+        assert!(kv.ttl("k2").await.unwrap().unwrap() > 58);
+        assert_eq!(
+            list.lrange("l2", 0, -1).unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+        assert_eq!(zset.zscore("z2", b"m"), Some(2.5));
+
+        // An existing key needs replace, which drops the old value entirely
+        let dump = mgr.dump("s").await.unwrap().unwrap();
+        assert!(matches!(
+            mgr.restore("h", &dump, false).await,
+            Err(SynapError::KeyExists(_))
+        ));
+        let replaced = mgr.restore("h", &dump, true).await.unwrap().unwrap();
+        assert_eq!(replaced.value.key_type(), KeyType::Hash);
+        assert_eq!(mgr.key_type("h").await.unwrap(), KeyType::Set);
+        assert!(hash.hgetall("h").unwrap_or_default().is_empty());
+
+        // Only strings carry a TTL
+        let expiring = KeyDump {
+            ttl_ms: Some(1_000),
+            ..dump
+        };
+        assert!(mgr.restore("s3", &expiring, false).await.is_err());
+        assert!(!mgr.exists("s3").await.unwrap());
+
+        // An expired TTL removes the old value and creates nothing
+        let expired = KeyDump {
+            ttl_ms: Some(0),
+            ..expiring
+        };
+        assert!(mgr.restore("l2", &expired, true).await.unwrap().is_some());
+        assert!(!mgr.exists("l2").await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_randomkey() {
         let (kv, _h, _l, _s, _z, mgr) = full_manager();
@@ -1,13 +1,15 @@
-use super::super::types::{KeyBuf, StoredValue};
+use super::super::types::{KVShardStats, KeyBuf, StoredValue};
 use ahash::RandomState;
-use parking_lot::{Mutex, RwLock};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use radix_trie::{Trie, TrieCommon};
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tracing::debug;
 
-pub(crate) const SHARD_COUNT: usize = 64;
 const HASHMAP_THRESHOLD: usize = 10_000; // Switch to RadixTrie after 10K keys
+/// One `Small` map bucket: the entry plus hashbrown's control byte
+const SMALL_SLOT_BYTES: usize = std::mem::size_of::<(KeyBuf, StoredValue)>() + 1;
 
 /// Storage backend for a shard (adaptive: HashMap for small, RadixTrie for large)
 /// Note: CompactString could reduce memory by 30% for short keys, but RadixTrie
@@ -29,7 +31,6 @@ impl ShardStorage {
         Self::Small(HashMap::with_hasher(RandomState::new()))
     }
 
-    #[allow(dead_code)]
     pub(crate) fn len(&self) -> usize {
         match self {
             Self::Small(map) => map.len(),
@@ -117,6 +118,57 @@ impl ShardStorage {
         }
     }
 
+    /// Bytes of unused map capacity [`shrink_to_fit`](Self::shrink_to_fit)
+    /// would return. A RadixTrie frees its nodes on removal, so it has none.
+    pub(crate) fn slack_bytes(&self) -> usize {
+        match self {
+            Self::Small(map) => (map.capacity() - map.len()) * SMALL_SLOT_BYTES,
+            Self::Large(_) => 0,
+        }
+    }
+
+    /// Release unused map capacity, returning the estimated bytes freed
+    pub(crate) fn shrink_to_fit(&mut self) -> usize {
+        match self {
+            Self::Small(map) => {
+                let before = map.capacity();
+                map.shrink_to_fit();
+                before.saturating_sub(map.capacity()) * SMALL_SLOT_BYTES
+            }
+            Self::Large(_) => 0,
+        }
+    }
+
+    /// Copy every unshared value of at least `min_bytes` into a fresh
+    /// allocation (see [`StoredValue::relocate`]), returning the values and
+    /// bytes copied
+    pub(crate) fn relocate_values(&mut self, min_bytes: usize) -> (u64, u64) {
+        let mut relocated = (0, 0);
+        let mut count = |bytes: usize| {
+            if bytes > 0 {
+                relocated.0 += 1;
+                relocated.1 += bytes as u64;
+            }
+        };
+        match self {
+            Self::Small(map) => map.values_mut().for_each(|v| count(v.relocate(min_bytes))),
+            Self::Large(trie) => {
+                // radix_trie has no mutable iterator, so look the keys up again
+                let keys: Vec<String> = trie
+                    .iter()
+                    .filter(|(_, v)| v.data().len() >= min_bytes)
+                    .map(|(k, _)| k.clone())
+                    .collect();
+                for key in keys {
+                    if let Some(value) = trie.get_mut(&key) {
+                        count(value.relocate(min_bytes));
+                    }
+                }
+            }
+        }
+        relocated
+    }
+
     /// Upgrade from HashMap to RadixTrie when threshold is reached
     fn upgrade_to_trie(&mut self) {
         if let Self::Small(map) = self {
@@ -148,9 +200,17 @@ impl ShardStorage {
 /// authoritative expiration index. After [`ShardStorage::upgrade_to_trie`]
 /// flips the shard to `Large` mode, the heap entries become stale; the
 /// next cleanup pass drains them once and the sampling path takes over.
+///
+/// `data` is only reachable through [`read`](Self::read) and
+/// [`write`](Self::write), which count lock acquisitions and the ones that
+/// had to wait for another holder, so hot shards show up in
+/// [`KVStore::shard_stats`](super::KVStore::shard_stats).
 pub(crate) struct KVShard {
-    pub(crate) data: RwLock<ShardStorage>,
+    data: RwLock<ShardStorage>,
     pub(crate) ttl_heap: Mutex<BinaryHeap<Reverse<(u64, KeyBuf)>>>,
+    reads: AtomicU64,
+    writes: AtomicU64,
+    contended: AtomicU64,
 }
 
 impl KVShard {
@@ -158,7 +218,54 @@ impl KVShard {
         Self {
             data: RwLock::new(ShardStorage::new()),
             ttl_heap: Mutex::new(BinaryHeap::new()),
+            reads: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
+            contended: AtomicU64::new(0),
+        }
+    }
+
+    /// Take the shard's read lock
+    #[inline]
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, ShardStorage> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.data.try_read().unwrap_or_else(|| {
+            self.contended.fetch_add(1, Ordering::Relaxed);
+            self.data.read()
+        })
+    }
+
+    /// Take the shard's write lock
+    #[inline]
+    pub(crate) fn write(&self) -> RwLockWriteGuard<'_, ShardStorage> {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        self.data.try_write().unwrap_or_else(|| {
+            self.contended.fetch_add(1, Ordering::Relaxed);
+            self.data.write()
+        })
+    }
+
+    /// Key count and lock counters of this shard, reported as shard `index`
+    pub(crate) fn stats(&self, index: usize) -> KVShardStats {
+        KVShardStats {
+            shard: index,
+            keys: self.data.read().len(),
+            reads: self.reads.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            contended: self.contended.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Release unused TTL heap capacity when at least `min_bytes` would be
+    /// freed, returning the bytes freed
+    pub(crate) fn shrink_ttl_heap(&self, min_bytes: usize) -> usize {
+        const ENTRY_BYTES: usize = std::mem::size_of::<Reverse<(u64, KeyBuf)>>();
+        let mut heap = self.ttl_heap.lock();
+        let before = heap.capacity();
+        if (before - heap.len()) * ENTRY_BYTES < min_bytes.max(1) {
+            return 0;
         }
+        heap.shrink_to_fit();
+        before.saturating_sub(heap.capacity()) * ENTRY_BYTES
     }
 
     /// Push `(expires_at, key)` onto the TTL heap if `value` is expiring.
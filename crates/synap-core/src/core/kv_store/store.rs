@@ -1,10 +1,13 @@
 use super::super::error::{Result, SynapError};
 use super::super::types::{
-    AtomicKVStats, EvictionPolicy, Expiry, KVConfig, KVStats, KeyBuf, SetOptions, SetResult,
-    StoredValue,
+    AtomicKVStats, DefragConfig, DefragReport, EvictionPolicy, Expiry, KVConfig, KVShardStats,
+    KVStats, KeyBuf, SetOptions, SetResult, StoredValue,
 };
-use super::storage::{KVShard, SHARD_COUNT, ShardStorage};
+use super::storage::{KVShard, ShardStorage};
+use crate::compression::value::{self, ValueCodec, ValueCompressionReport};
 use ahash::RandomState;
+use rand::seq::IteratorRandom;
+use std::borrow::Cow;
 use std::cmp::Reverse;
 use std::hash::{BuildHasher, Hasher};
 use std::sync::Arc;
@@ -19,11 +22,11 @@ fn shard_hasher() -> &'static RandomState {
     HASHER.get_or_init(RandomState::new)
 }
 
-/// Key-Value store using 64-way sharded radix tries for lock-free concurrency
-/// Eliminates lock contention by distributing keys across multiple shards
+/// Key-Value store sharded over `KVConfig::shard_count` radix tries
+/// Eliminates lock contention by distributing keys across independently locked shards
 #[derive(Clone)]
 pub struct KVStore {
-    shards: Arc<[Arc<KVShard>; SHARD_COUNT]>,
+    shards: Arc<[Arc<KVShard>]>,
     stats: Arc<AtomicKVStats>,
     config: KVConfig,
     /// Optional L1/L2 cache layer
@@ -53,10 +56,25 @@ pub struct KVStore {
     /// idle cost is the router lookup inside
     /// [`KeyWatchNotifier::notify`](crate::core::KeyWatchNotifier::notify).
     watch_notifier: Option<Arc<crate::core::KeyWatchNotifier>>,
+    /// Optional read sampler feeding the eviction simulator. `None` (the
+    /// default) costs one branch per GET.
+    access_sampler: Option<Arc<crate::cache::AccessSampler>>,
+    /// Optional hot key tracker counting sampled GETs and SETs. `None` (the
+    /// default) costs one branch per read and write.
+    hot_keys: Option<Arc<crate::core::HotKeyTracker>>,
+    /// Optional per-namespace key and memory quotas. `None` (the default)
+    /// costs one branch per write and removal.
+    quotas: Option<crate::core::NamespaceQuotas>,
+    /// Per-key size cap (`max_value_bytes`).
+    key_limits: crate::core::KeyLimits,
+    /// Value compression at rest, built from `config.compression`. When set,
+    /// every non-integer value is stored behind a one-byte encoding header
+    /// (see [`crate::compression::value`]).
+    codec: Option<Arc<ValueCodec>>,
 }
 
 impl KVStore {
-    /// Create a new KV store with 64-way sharding
+    /// Create a new KV store with `config.shard_count` shards
     pub fn new(config: KVConfig) -> Self {
         Self::new_with_cache(config, None)
     }
@@ -95,6 +113,220 @@ impl KVStore {
         self
     }
 
+    /// Attach an access sampler that records GETs for eviction simulation.
+    /// A no-op when `sampler` is `None`.
+    pub fn with_access_sampler(
+        mut self,
+        sampler: Option<Arc<crate::cache::AccessSampler>>,
+    ) -> Self {
+        self.access_sampler = sampler;
+        self
+    }
+
+    /// The attached access sampler, if any.
+    pub fn access_sampler(&self) -> Option<&Arc<crate::cache::AccessSampler>> {
+        self.access_sampler.as_ref()
+    }
+
+    /// Attach a hot key tracker that counts GETs and SETs per key. A no-op
+    /// when `tracker` is `None`.
+    pub fn with_hot_keys(mut self, tracker: Option<Arc<crate::core::HotKeyTracker>>) -> Self {
+        self.hot_keys = tracker;
+        self
+    }
+
+    /// The attached hot key tracker, if any.
+    pub fn hot_keys(&self) -> Option<&Arc<crate::core::HotKeyTracker>> {
+        self.hot_keys.as_ref()
+    }
+
+    /// Count an access to `key` toward the hot keys, if tracked.
+    #[inline]
+    fn record_hot(&self, key: &str, op: crate::core::HotKeyOp) {
+        if let Some(ref tracker) = self.hot_keys {
+            tracker.record(key, op);
+        }
+    }
+
+    /// Attach per-namespace quotas, counting the keys already stored so a
+    /// store recovered from persistence starts with accurate usage. A no-op
+    /// when `quotas` is `None`.
+    pub fn with_namespace_quotas(mut self, quotas: Option<crate::core::NamespaceQuotas>) -> Self {
+        if let Some(ref quotas) = quotas {
+            quotas.reset();
+            for shard in self.shards.iter() {
+                for (key, value) in shard.read().iter() {
+                    quotas.charge(&key, 1, self.estimate_entry_size(&key, &value) as i64);
+                }
+            }
+        }
+        self.quotas = quotas;
+        self
+    }
+
+    /// Attach per-key limits. Only `max_value_bytes` applies to string values.
+    pub fn with_key_limits(mut self, limits: crate::core::KeyLimits) -> Self {
+        self.key_limits = limits;
+        self
+    }
+
+    /// The attached per-key limits.
+    pub fn key_limits(&self) -> &crate::core::KeyLimits {
+        &self.key_limits
+    }
+
+    /// Compression counters for values written since startup, or `None`
+    /// when value compression is off.
+    pub fn compression_stats(&self) -> Option<ValueCompressionReport> {
+        self.codec.as_ref().map(|codec| codec.stats().snapshot())
+    }
+
+    /// Stored form of a value about to be written.
+    #[inline]
+    fn encode_value(&self, value: Vec<u8>) -> Vec<u8> {
+        match self.codec {
+            Some(ref codec) => codec.encode(&value),
+            None => value,
+        }
+    }
+
+    /// Stored form of a shared value about to be written. Without compression
+    /// this is the same buffer.
+    #[inline]
+    fn encode_shared(&self, value: &Arc<[u8]>) -> Arc<[u8]> {
+        match self.codec {
+            Some(ref codec) => codec.encode(value).into(),
+            None => Arc::clone(value),
+        }
+    }
+
+    /// The value `stored` holds, decompressed if it had to be.
+    #[inline]
+    pub(crate) fn value_bytes<'a>(&self, stored: &'a StoredValue) -> Result<Cow<'a, [u8]>> {
+        match stored {
+            StoredValue::Int { .. } => Ok(Cow::Borrowed(stored.data())),
+            _ if self.codec.is_some() => value::decode(stored.data())
+                .map_err(|e| SynapError::InternalError(format!("Corrupt stored value: {e}"))),
+            _ => Ok(Cow::Borrowed(stored.data())),
+        }
+    }
+
+    /// Like [`value_bytes`](Self::value_bytes), as a shared buffer: a
+    /// refcount bump when the store does not compress.
+    #[inline]
+    fn value_shared(&self, stored: &StoredValue) -> Result<Arc<[u8]>> {
+        if self.codec.is_none() {
+            return Ok(stored.data_arc());
+        }
+        Ok(match self.value_bytes(stored)? {
+            Cow::Borrowed(bytes) => Arc::from(bytes),
+            Cow::Owned(bytes) => bytes.into(),
+        })
+    }
+
+    /// Encoding tag and body of `stored`, as snapshots persist it. Values of
+    /// a store without compression are raw.
+    pub fn stored_parts<'a>(&self, stored: &'a StoredValue) -> (u8, &'a [u8]) {
+        match stored {
+            StoredValue::Int { .. } => (value::TAG_RAW, stored.data()),
+            _ if self.codec.is_some() => match stored.data().split_first() {
+                Some((&tag, body)) => (tag, body),
+                None => (value::TAG_RAW, &[]),
+            },
+            _ => (value::TAG_RAW, stored.data()),
+        }
+    }
+
+    /// The `n` largest string values by stored bytes, largest first.
+    pub fn largest_keys(&self, n: usize) -> Vec<crate::core::KeySize> {
+        let mut largest = crate::core::limits::Largest::new(n);
+        for shard in self.shards.iter() {
+            for (key, value) in shard.read().iter() {
+                if !value.is_expired() {
+                    largest.offer(&key, value.data().len());
+                }
+            }
+        }
+        largest.into_vec()
+    }
+
+    /// Encoding and estimated memory of `key`, `None` when it holds no string.
+    pub fn key_memory(&self, key: &str) -> Option<crate::core::KeyMemory> {
+        let data = self.get_shard(key).read();
+        data.get(key)
+            .filter(|value| !value.is_expired())
+            .map(|value| value.memory(key))
+    }
+
+    /// Estimated memory of every live string, with the `top` largest by bytes.
+    pub fn memory_profile(&self, top: usize) -> crate::core::MemoryProfile {
+        let mut profiler = crate::core::memory_profile::Profiler::new(top);
+        for shard in self.shards.iter() {
+            for (key, value) in shard.read().iter() {
+                if !value.is_expired() {
+                    profiler.offer(&key, value.memory(&key));
+                }
+            }
+        }
+        profiler.finish()
+    }
+
+    /// The `n` keys read or written longest ago, most idle first, with their
+    /// idle seconds as `size`. Only keys with a TTL track access, so keys
+    /// without one are left out.
+    pub fn most_idle_keys(&self, n: usize) -> Vec<crate::core::KeySize> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut largest = crate::core::limits::Largest::new(n);
+        for shard in self.shards.iter() {
+            for (key, value) in shard.read().iter() {
+                let last_access = value.last_access();
+                if last_access > 0 && !value.is_expired() {
+                    largest.offer(&key, now.saturating_sub(u64::from(last_access)) as usize);
+                }
+            }
+        }
+        largest.into_vec()
+    }
+
+    /// The attached namespace quotas, if any.
+    pub fn namespace_quotas(&self) -> Option<&crate::core::NamespaceQuotas> {
+        self.quotas.as_ref()
+    }
+
+    /// Change in key count and bytes of replacing `key`'s entry in `data`
+    /// with one of `new_size` bytes.
+    fn quota_delta(&self, data: &ShardStorage, key: &str, new_size: usize) -> (i64, i64) {
+        match data.get(key) {
+            Some(old) => (
+                0,
+                new_size as i64 - self.estimate_entry_size(key, old) as i64,
+            ),
+            None => (1, new_size as i64),
+        }
+    }
+
+    /// Charge replacing `key`'s entry in `data` with one of `new_size` bytes
+    /// to its namespace quota, refusing the write if it would exceed it.
+    #[inline]
+    fn charge_quota(&self, data: &ShardStorage, key: &str, new_size: usize) -> Result<()> {
+        let Some(ref quotas) = self.quotas else {
+            return Ok(());
+        };
+        let (keys, bytes) = self.quota_delta(data, key, new_size);
+        quotas.try_charge(key, keys, bytes)
+    }
+
+    /// Release a removed entry from its namespace quota.
+    #[inline]
+    fn release_quota(&self, key: &str, value: &StoredValue) {
+        if let Some(ref quotas) = self.quotas {
+            quotas.charge(key, -1, -(self.estimate_entry_size(key, value) as i64));
+        }
+    }
+
     /// Publish a keyspace notification for `key` if a notifier is attached.
     #[inline]
     fn notify_keyspace(&self, class: crate::core::EventClass, event: &str, key: &str) {
@@ -125,6 +357,20 @@ impl KVStore {
         }
     }
 
+    /// Invalidate client-side caches of `key`, for writes that publish no
+    /// watch event.
+    #[inline]
+    pub(super) fn invalidate_tracked(&self, key: &str) {
+        if let Some(tracking) = self.watch_notifier.as_ref().and_then(|n| n.tracking()) {
+            tracking.invalidate(key);
+        }
+    }
+
+    /// The client-side caching table, when the watch notifier carries one.
+    pub fn tracking(&self) -> Option<&Arc<crate::core::TrackingTable>> {
+        self.watch_notifier.as_ref()?.tracking()
+    }
+
     /// Attach a shared [`GlobalMemory`](crate::core::GlobalMemory) budget so this
     /// KV store's memory participates in the cross-datatype `maxmemory` limit.
     /// Registers this store's live byte counter, so its existing per-mutation
@@ -155,24 +401,32 @@ impl KVStore {
         }
     }
 
+    /// Build `config.shard_count` empty shards (at least one)
+    fn new_shards(config: &KVConfig) -> Arc<[Arc<KVShard>]> {
+        (0..config.shard_count.max(1))
+            .map(|_| Arc::new(KVShard::new()))
+            .collect()
+    }
+
     /// Create KV store with optional cache layer
     pub fn new_with_cache(config: KVConfig, cache_size: Option<usize>) -> Self {
+        let shards = Self::new_shards(&config);
         info!(
-            "Initializing sharded KV store (64 shards) with max_memory={}MB, eviction={:?}",
-            config.max_memory_mb, config.eviction_policy
+            "Initializing sharded KV store ({} shards) with max_memory={}MB, eviction={:?}",
+            shards.len(),
+            config.max_memory_mb,
+            config.eviction_policy
         );
 
-        // Initialize all 64 shards
-        let shards: [Arc<KVShard>; SHARD_COUNT] = std::array::from_fn(|_| Arc::new(KVShard::new()));
-
         // Initialize cache if requested
         let cache = cache_size.map(|size| {
             info!("Enabling L1 cache with {} entries", size);
             Arc::new(crate::core::CacheLayer::new(size))
         });
+        let codec = ValueCodec::from_config(&config.compression);
 
         Self {
-            shards: Arc::new(shards),
+            shards,
             stats: Arc::new(AtomicKVStats::default()),
             config,
             cache,
@@ -182,6 +436,11 @@ impl KVStore {
             key_locks: Arc::new(crate::core::KeyLockManager::new()),
             keyspace_notifier: None,
             watch_notifier: None,
+            access_sampler: None,
+            hot_keys: None,
+            quotas: None,
+            key_limits: crate::core::KeyLimits::default(),
+            codec,
         }
     }
 
@@ -192,22 +451,23 @@ impl KVStore {
         topology: Arc<crate::cluster::topology::ClusterTopology>,
         migration: Option<Arc<crate::cluster::migration::SlotMigrationManager>>,
     ) -> Self {
+        let shards = Self::new_shards(&config);
         info!(
-            "Initializing sharded KV store with cluster mode (64 shards) with max_memory={}MB, eviction={:?}",
-            config.max_memory_mb, config.eviction_policy
+            "Initializing sharded KV store with cluster mode ({} shards) with max_memory={}MB, eviction={:?}",
+            shards.len(),
+            config.max_memory_mb,
+            config.eviction_policy
         );
 
-        // Initialize all 64 shards
-        let shards: [Arc<KVShard>; SHARD_COUNT] = std::array::from_fn(|_| Arc::new(KVShard::new()));
-
         // Initialize cache if requested
         let cache = cache_size.map(|size| {
             info!("Enabling L1 cache with {} entries", size);
             Arc::new(crate::core::CacheLayer::new(size))
         });
+        let codec = ValueCodec::from_config(&config.compression);
 
         Self {
-            shards: Arc::new(shards),
+            shards,
             stats: Arc::new(AtomicKVStats::default()),
             config,
             cache,
@@ -217,6 +477,11 @@ impl KVStore {
             key_locks: Arc::new(crate::core::KeyLockManager::new()),
             keyspace_notifier: None,
             watch_notifier: None,
+            access_sampler: None,
+            hot_keys: None,
+            quotas: None,
+            key_limits: crate::core::KeyLimits::default(),
+            codec,
         }
     }
 
@@ -269,12 +534,31 @@ impl KVStore {
         }
     }
 
+    /// Check a multi-key command (cluster mode routing): every key must hash to
+    /// the same slot, and that slot must be served by this node
+    pub(super) fn check_cluster_routing_all<I, K>(&self, keys: I) -> Result<()>
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<str>,
+    {
+        if self.cluster_topology.is_none() {
+            return Ok(());
+        }
+
+        let mut keys = keys.into_iter().peekable();
+        let Some(first) = keys.peek().map(|key| key.as_ref().to_string()) else {
+            return Ok(());
+        };
+        crate::cluster::hash_slot::same_slot(keys)?;
+        self.check_cluster_routing(&first)
+    }
+
     /// Get shard index for a key using consistent hashing
     #[inline]
     fn shard_for_key(&self, key: &str) -> usize {
         let mut hasher = shard_hasher().build_hasher();
         hasher.write(key.as_bytes());
-        (hasher.finish() as usize) % SHARD_COUNT
+        (hasher.finish() as usize) % self.shards.len()
     }
 
     /// Get reference to shard for a key
@@ -300,6 +584,87 @@ impl KVStore {
         })
     }
 
+    /// Start the background defragmentation task.
+    ///
+    /// Every `interval_secs` it compares accounted memory with its peak since
+    /// the last pass; once it has dropped by `min_freed_bytes` (a delete or
+    /// expiry burst) it runs [`defrag`](Self::defrag) on a blocking thread.
+    pub fn start_defrag(&self, config: DefragConfig) -> tokio::task::JoinHandle<()> {
+        info!(
+            "Starting defragmentation task (interval={}s, min_freed={}B)",
+            config.interval_secs, config.min_freed_bytes
+        );
+
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+            let mut peak = 0u64;
+
+            loop {
+                interval.tick().await;
+                let used = store
+                    .stats
+                    .total_memory_bytes
+                    .load(Ordering::Relaxed)
+                    .max(0) as u64;
+                peak = peak.max(used);
+                if peak - used < config.min_freed_bytes {
+                    continue;
+                }
+
+                let (pass_store, pass_config) = (store.clone(), config.clone());
+                if let Err(e) =
+                    tokio::task::spawn_blocking(move || pass_store.defrag(&pass_config)).await
+                {
+                    warn!("Defragmentation pass failed: {}", e);
+                }
+                peak = used;
+            }
+        })
+    }
+
+    /// Run one defragmentation pass over every shard.
+    ///
+    /// Shrinks shard maps and TTL heaps holding at least
+    /// `min_reclaimable_bytes` of unused capacity, and copies values of at
+    /// least `relocate_value_bytes` into fresh allocations. Shards are locked
+    /// one at a time, so writers only wait on the shard being compacted.
+    pub fn defrag(&self, config: &DefragConfig) -> DefragReport {
+        let mut report = DefragReport::default();
+
+        for shard in self.shards.iter() {
+            let mut data = shard.write();
+            if data.slack_bytes() >= config.min_reclaimable_bytes.max(1) {
+                report.reclaimed_bytes += data.shrink_to_fit() as u64;
+                report.shards_compacted += 1;
+            }
+            if config.relocate_value_bytes > 0 {
+                let (values, bytes) = data.relocate_values(config.relocate_value_bytes);
+                report.relocated_values += values;
+                report.relocated_bytes += bytes;
+            }
+            drop(data);
+            report.reclaimed_bytes += shard.shrink_ttl_heap(config.min_reclaimable_bytes) as u64;
+        }
+
+        self.stats.defrag_passes.fetch_add(1, Ordering::Relaxed);
+        self.stats
+            .defrag_reclaimed_bytes
+            .fetch_add(report.reclaimed_bytes, Ordering::Relaxed);
+        self.stats
+            .defrag_relocated_bytes
+            .fetch_add(report.relocated_bytes, Ordering::Relaxed);
+        debug!(
+            "Defragmentation: {} shards compacted, ~{} bytes reclaimed, {} values ({} bytes) relocated",
+            report.shards_compacted,
+            report.reclaimed_bytes,
+            report.relocated_values,
+            report.relocated_bytes
+        );
+        report
+    }
+
     /// Set a key-value pair.
     ///
     /// S-12: accepts `impl Into<String>` so callers that already hold an owned `String`
@@ -312,6 +677,7 @@ impl KVStore {
         ttl_secs: Option<u64>,
     ) -> Result<()> {
         let key: String = key.into();
+        self.record_hot(&key, crate::core::HotKeyOp::Write);
         // Isolate this write against an in-flight EXEC touching the same key
         // (audit M-010). EXEC calls `set_unlocked` while holding the lock itself.
         let _guard = self.key_locks.read_key(&key).await;
@@ -335,38 +701,19 @@ impl KVStore {
 
         // Check cluster routing (returns error if key doesn't belong to this node)
         self.check_cluster_routing(&key)?;
+        self.key_limits.check_value(&key, value.len())?;
 
-        let stored = StoredValue::new(Arc::clone(&value), ttl_secs);
+        let stored = StoredValue::new(self.encode_shared(&value), ttl_secs);
         let entry_size = self.estimate_entry_size(&key, &stored);
 
         // Check memory limits against the shared cross-datatype budget (audit
         // M-018) — evict KV if policy allows, error on noeviction.
-        {
-            let (current_bytes, max_bytes) = self.mem_used_and_max();
-            if max_bytes > 0 && current_bytes + entry_size as i64 > max_bytes {
-                if self.config.eviction_policy == EvictionPolicy::NoEviction {
-                    warn!(
-                        "Memory limit exceeded (noeviction): {}/{}",
-                        current_bytes, max_bytes
-                    );
-                    return Err(SynapError::MemoryLimitExceeded);
-                }
-                self.evict_until_free(entry_size);
-                // Re-check after eviction.
-                let (after, _) = self.mem_used_and_max();
-                if after + entry_size as i64 > max_bytes {
-                    warn!(
-                        "Memory limit exceeded after eviction: {}/{}",
-                        after, max_bytes
-                    );
-                    return Err(SynapError::MemoryLimitExceeded);
-                }
-            }
-        }
+        self.ensure_memory_for(entry_size)?;
 
         // Insert value in the appropriate shard — key moved directly, no extra allocation.
         let shard = self.get_shard(&key);
-        let mut data = shard.data.write();
+        let mut data = shard.write();
+        self.charge_quota(&data, &key, entry_size)?;
 
         // Save key length before moving key into the HashMap (needed for overwrite accounting).
         let key_len = key.len();
@@ -431,10 +778,11 @@ impl KVStore {
     pub async fn set_with_opts(
         &self,
         key: &str,
-        value: Vec<u8>,
+        value: impl Into<Arc<[u8]>>,
         expiry: Option<Expiry>,
         opts: SetOptions,
     ) -> Result<SetResult> {
+        let value: Arc<[u8]> = value.into();
         debug!(
             "SET key={} size={} expiry={:?} nx={} xx={} keepttl={} get={}",
             key,
@@ -445,36 +793,18 @@ impl KVStore {
             opts.keep_ttl,
             opts.return_old,
         );
+        self.record_hot(key, crate::core::HotKeyOp::Write);
 
         self.check_cluster_routing(key)?;
+        self.key_limits.check_value(key, value.len())?;
 
         // --- Pre-lock memory check + eviction ---
         // Estimate size conservatively before building the StoredValue.
         let approx_size = key.len() + value.len() + std::mem::size_of::<StoredValue>();
-        {
-            let (current_bytes, max_bytes) = self.mem_used_and_max();
-            if max_bytes > 0 && current_bytes + approx_size as i64 > max_bytes {
-                if self.config.eviction_policy == EvictionPolicy::NoEviction {
-                    warn!(
-                        "Memory limit exceeded (noeviction): {}/{}",
-                        current_bytes, max_bytes
-                    );
-                    return Err(SynapError::MemoryLimitExceeded);
-                }
-                self.evict_until_free(approx_size);
-                let (after, _) = self.mem_used_and_max();
-                if after + approx_size as i64 > max_bytes {
-                    warn!(
-                        "Memory limit exceeded after eviction: {}/{}",
-                        after, max_bytes
-                    );
-                    return Err(SynapError::MemoryLimitExceeded);
-                }
-            }
-        }
+        self.ensure_memory_for(approx_size)?;
 
         let shard = self.get_shard(key);
-        let mut data = shard.data.write();
+        let mut data = shard.write();
 
         // --- NX / XX guard (under write lock — no TOCTOU) ---
         let existing = data.get(key);
@@ -482,10 +812,9 @@ impl KVStore {
 
         if opts.if_absent && key_exists {
             // NX: key exists → do NOT set, return old value if requested
-            let old_value = if opts.return_old {
-                existing.map(|v| v.data().to_vec())
-            } else {
-                None
+            let old_value = match existing {
+                Some(v) if opts.return_old => Some(self.value_bytes(v)?.into_owned()),
+                _ => None,
             };
             return Ok(SetResult {
                 written: false,
@@ -501,15 +830,15 @@ impl KVStore {
         }
 
         // --- Capture old value for GET option ---
-        let old_value = if opts.return_old {
-            existing
-                .filter(|v| !v.is_expired())
-                .map(|v| v.data().to_vec())
-        } else {
-            None
+        let old_value = match existing {
+            Some(v) if opts.return_old && !v.is_expired() => {
+                Some(self.value_bytes(v)?.into_owned())
+            }
+            _ => None,
         };
 
         // --- Compute the new StoredValue ---
+        let encoded = self.encode_shared(&value);
         let stored = if opts.keep_ttl {
             // KEEPTTL: carry forward existing expiry timestamp, ignore new expiry
             let existing_expires_at_ms = data
@@ -517,13 +846,13 @@ impl KVStore {
                 .filter(|v| !v.is_expired())
                 .and_then(|v| v.expires_at_ms());
             match existing_expires_at_ms {
-                Some(ms) => StoredValue::with_expires_at_ms(value.clone(), ms),
-                None => StoredValue::Persistent(value.clone().into()),
+                Some(ms) => StoredValue::with_expires_at_ms(encoded, ms),
+                None => StoredValue::Persistent(encoded),
             }
         } else {
             match expiry {
-                Some(exp) => StoredValue::with_expiry(value.clone(), exp),
-                None => StoredValue::Persistent(value.clone().into()),
+                Some(exp) => StoredValue::with_expiry(encoded, exp),
+                None => StoredValue::Persistent(encoded),
             }
         };
 
@@ -531,6 +860,7 @@ impl KVStore {
         let entry_size = self.estimate_entry_size(key, &stored);
 
         // --- Insert ---
+        self.charge_quota(&data, key, entry_size)?;
         shard.track_ttl(&stored, key);
         let old_entry = data.insert(key.to_string(), stored);
         let is_new = old_entry.is_none() || old_entry.as_ref().is_some_and(|v| v.is_expired());
@@ -570,7 +900,7 @@ impl KVStore {
                     Expiry::UnixMilliseconds(ms) => ms / 1_000,
                 }
             });
-            cache.put(key.to_string(), value, cache_expiry_secs);
+            cache.put(key.to_string(), value.to_vec(), cache_expiry_secs);
         }
 
         Ok(SetResult {
@@ -598,6 +928,11 @@ impl KVStore {
         // Check cluster routing (returns error if key doesn't belong to this node)
         self.check_cluster_routing(key)?;
 
+        if let Some(ref sampler) = self.access_sampler {
+            sampler.record(key);
+        }
+        self.record_hot(key, crate::core::HotKeyOp::Read);
+
         // Try L1 cache first
         if let Some(ref cache) = self.cache
             && let Some(cached_value) = cache.get(key)
@@ -618,15 +953,16 @@ impl KVStore {
 
         // --- Hot path: read lock ---
         {
-            let data = shard.data.read();
+            let data = shard.read();
             if let Some(value) = data.get(key) {
                 if !value.is_expired() {
                     // Atomic LRU update — safe under read lock.
                     value.update_access();
                     self.stats.hits.fetch_add(1, Ordering::Relaxed);
 
-                    // Zero-copy: bump the shared buffer's refcount.
-                    let value_data = value.data_arc();
+                    // Zero-copy: bump the shared buffer's refcount (unless
+                    // the value has to be decompressed).
+                    let value_data = self.value_shared(value)?;
                     let ttl = value.ttl_remaining();
                     drop(data);
 
@@ -647,13 +983,14 @@ impl KVStore {
 
         // --- Cold path: write lock (expired key removal) ---
         {
-            let mut data = shard.data.write();
+            let mut data = shard.write();
             // Re-check under write lock (another thread may have already removed it).
             if let Some(value) = data.get(key) {
                 if value.is_expired() {
                     debug!("Key expired: {}", key);
                     if let Some(expired_val) = data.remove(key) {
                         let removed_size = self.estimate_entry_size(key, &expired_val);
+                        self.release_quota(key, &expired_val);
                         self.stats.total_keys.fetch_sub(1, Ordering::Relaxed);
                         self.stats
                             .total_memory_bytes
@@ -672,7 +1009,7 @@ impl KVStore {
                     // Treat as a hit.
                     value.update_access();
                     self.stats.hits.fetch_add(1, Ordering::Relaxed);
-                    let value_data = value.data_arc();
+                    let value_data = self.value_shared(value)?;
                     let ttl = value.ttl_remaining();
                     drop(data);
                     if let Some(ref cache) = self.cache {
@@ -707,11 +1044,12 @@ impl KVStore {
         }
 
         let shard = self.get_shard(key);
-        let mut data = shard.data.write();
+        let mut data = shard.write();
         let removed = data.remove(key);
 
         if let Some(removed_val) = removed {
             let removed_size = self.estimate_entry_size(key, &removed_val);
+            self.release_quota(key, &removed_val);
             self.stats.dels.fetch_add(1, Ordering::Relaxed);
             self.stats.total_keys.fetch_sub(1, Ordering::Relaxed);
             self.stats
@@ -729,7 +1067,7 @@ impl KVStore {
     /// Check if a key exists
     pub async fn exists(&self, key: &str) -> Result<bool> {
         let shard = self.get_shard(key);
-        let data = shard.data.read();
+        let data = shard.read();
         if let Some(value) = data.get(key) {
             Ok(!value.is_expired())
         } else {
@@ -742,15 +1080,34 @@ impl KVStore {
         self.stats.snapshot()
     }
 
+    /// Number of shards keys are spread over
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Key count and lock activity of every shard, in shard order
+    pub fn shard_stats(&self) -> Vec<KVShardStats> {
+        self.shards
+            .iter()
+            .enumerate()
+            .map(|(index, shard)| shard.stats(index))
+            .collect()
+    }
+
     /// Get the KV store configuration
     pub fn config(&self) -> &KVConfig {
         &self.config
     }
 
+    /// The shared cross-datatype memory budget, when attached
+    pub fn global_memory(&self) -> Option<&crate::core::GlobalMemory> {
+        self.mem.as_ref()
+    }
+
     /// Get remaining TTL for a key
     pub async fn ttl(&self, key: &str) -> Result<Option<u64>> {
         let shard = self.get_shard(key);
-        let data = shard.data.read();
+        let data = shard.read();
         if let Some(value) = data.get(key) {
             if value.is_expired() {
                 Ok(Some(0))
@@ -769,6 +1126,7 @@ impl KVStore {
     pub async fn incr(&self, key: &str, amount: i64) -> Result<i64> {
         // Isolate against an in-flight EXEC on the same key (audit M-010).
         let _guard = self.key_locks.read_key(key).await;
+        self.record_hot(key, crate::core::HotKeyOp::Write);
         self.incr_unlocked(key, amount).await
     }
 
@@ -779,7 +1137,7 @@ impl KVStore {
         debug!("INCR key={}, amount={}", key, amount);
 
         let shard = self.get_shard(key);
-        let mut data = shard.data.write();
+        let mut data = shard.write();
 
         // Fast path: the key exists and is live — parse the integer straight from
         // the stored bytes (no `to_vec`) and update the value in place with
@@ -790,19 +1148,46 @@ impl KVStore {
             if value.is_expired() {
                 // Expired — overwrite as a fresh int-encoded counter (0 + amount).
                 let nv = amount;
-                *value = StoredValue::new_int(nv);
+                let fresh = StoredValue::new_int(nv);
+                if let Some(ref quotas) = self.quotas {
+                    let grown = self.estimate_entry_size(key, &fresh) as i64
+                        - self.estimate_entry_size(key, value) as i64;
+                    quotas.try_charge(key, 0, grown)?;
+                }
+                *value = fresh;
                 nv
             } else {
-                let cur = value.as_int().ok_or_else(|| {
+                let cur = match value {
+                    // Under compression only the `Int` encoding is header-free.
+                    StoredValue::Persistent(_) | StoredValue::Expiring { .. }
+                        if self.codec.is_some() =>
+                    {
+                        let bytes = self.value_bytes(value)?;
+                        std::str::from_utf8(&bytes)
+                            .ok()
+                            .and_then(|s| s.trim().parse::<i64>().ok())
+                    }
+                    _ => value.as_int(),
+                }
+                .ok_or_else(|| {
                     SynapError::InvalidValue("Value is not a valid integer".to_string())
                 })?;
                 let nv = cur.checked_add(amount).ok_or_else(|| {
                     SynapError::InvalidValue("Integer overflow on INCR/DECR".to_string())
                 })?;
+                if let Some(ref quotas) = self.quotas {
+                    let grown = nv.to_string().len() as i64 - value.data().len() as i64;
+                    quotas.try_charge(key, 0, grown)?;
+                }
                 // Int variant: in-place integer add + inline re-render — zero
                 // heap allocation (Redis object.c int-encoding analogue).
                 // Persistent upgrades to Int; Expiring keeps its TTL.
-                value.set_int(nv);
+                match self.codec {
+                    Some(ref codec) if matches!(value, StoredValue::Expiring { .. }) => {
+                        value.set_data(codec.encode(nv.to_string().as_bytes()))
+                    }
+                    _ => value.set_int(nv),
+                }
                 value.update_access();
                 nv
             }
@@ -811,6 +1196,7 @@ impl KVStore {
             // the map entry itself).
             let nv = amount;
             let stored = StoredValue::new_int(nv);
+            self.charge_quota(&data, key, self.estimate_entry_size(key, &stored))?;
             shard.track_ttl(&stored, key);
             data.insert(key.to_string(), stored);
             nv
@@ -836,11 +1222,35 @@ impl KVStore {
     /// Set multiple key-value pairs
     pub async fn mset(&self, pairs: Vec<(String, Vec<u8>)>) -> Result<()> {
         debug!("MSET count={}", pairs.len());
+        for (key, _) in &pairs {
+            self.record_hot(key, crate::core::HotKeyOp::Write);
+        }
+
+        self.check_cluster_routing_all(pairs.iter().map(|(key, _)| key))?;
+        for (key, value) in &pairs {
+            self.key_limits.check_value(key, value.len())?;
+        }
+
+        // Refuse the whole batch before writing any key if it would take a
+        // namespace over its quota.
+        if let Some(ref quotas) = self.quotas {
+            quotas.check_batch(pairs.iter().map(|(key, value)| {
+                let new_size = key.len() + value.len() + std::mem::size_of::<StoredValue>();
+                let (keys, bytes) = self.quota_delta(&self.get_shard(key).read(), key, new_size);
+                (key.as_str(), keys, bytes)
+            }))?;
+        }
+
+        // Cached copies are invalidated once every shard is written
+        let tracked: Vec<String> = match self.tracking() {
+            Some(_) => pairs.iter().map(|(key, _)| key.clone()).collect(),
+            None => Vec::new(),
+        };
 
         // Group pairs by shard so we acquire each shard's write lock only once.
-        let mut by_shard: Vec<Vec<(String, Vec<u8>)>> = (0..SHARD_COUNT).map(|_| vec![]).collect();
+        let mut by_shard: Vec<Vec<(String, Vec<u8>)>> =
+            (0..self.shards.len()).map(|_| vec![]).collect();
         for (key, value) in pairs {
-            self.check_cluster_routing(&key)?;
             let idx = self.shard_for_key(&key);
             by_shard[idx].push((key, value));
         }
@@ -855,22 +1265,18 @@ impl KVStore {
                 .iter()
                 .map(|(k, v)| k.len() + v.len() + std::mem::size_of::<StoredValue>())
                 .sum();
-            {
-                let (current, max_bytes) = self.mem_used_and_max();
-                if max_bytes > 0 && current + group_size as i64 > max_bytes {
-                    if self.config.eviction_policy == EvictionPolicy::NoEviction {
-                        return Err(SynapError::MemoryLimitExceeded);
-                    }
-                    self.evict_until_free(group_size);
-                }
-            }
+            self.ensure_memory_for(group_size)?;
 
             let shard = &self.shards[idx];
-            let mut data = shard.data.write();
+            let mut data = shard.write();
             for (key, value) in group {
-                let stored = StoredValue::Persistent(value.into());
+                let stored = StoredValue::Persistent(self.encode_value(value).into());
                 let entry_size =
                     key.len() + stored.data().len() + std::mem::size_of::<StoredValue>();
+                if let Some(ref quotas) = self.quotas {
+                    let (keys, bytes) = self.quota_delta(&data, &key, entry_size);
+                    quotas.charge(&key, keys, bytes);
+                }
                 let old = data.insert(key, stored);
                 self.stats.sets.fetch_add(1, Ordering::Relaxed);
                 if old.is_none() {
@@ -886,6 +1292,9 @@ impl KVStore {
                 }
             }
         }
+        for key in &tracked {
+            self.invalidate_tracked(key);
+        }
 
         Ok(())
     }
@@ -895,7 +1304,7 @@ impl KVStore {
     /// Shard-aware: keys are bucketed by shard index, and each shard's
     /// `RwLock` is acquired exactly once for the entire batch instead of
     /// once per key. This collapses what used to be `O(n)` lock-acquire
-    /// cycles into `O(min(n, SHARD_COUNT))` and lets the read lock cover
+    /// cycles into `O(min(n, shard_count))` and lets the read lock cover
     /// many lookups, dramatically reducing contention with concurrent
     /// writers on the same shard.
     pub async fn mget(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
@@ -917,10 +1326,16 @@ impl KVStore {
 
         let mut results: Vec<Option<Arc<[u8]>>> = vec![None; keys.len()];
 
-        // 1. Cluster routing check for every key — fail fast on the
-        //    first wrong-owner key, matching single-key get() semantics.
+        // 1. Cluster routing check — all keys in one slot, owned by this node.
+        self.check_cluster_routing_all(keys)?;
+
+        if let Some(ref sampler) = self.access_sampler {
+            for key in keys {
+                sampler.record(key);
+            }
+        }
         for key in keys {
-            self.check_cluster_routing(key)?;
+            self.record_hot(key, crate::core::HotKeyOp::Read);
         }
 
         // 2. L1 cache pass — anything served from cache skips the shard.
@@ -946,7 +1361,8 @@ impl KVStore {
         }
 
         // 3. Bucket pending keys by shard.
-        let mut buckets: Vec<Vec<(usize, &str)>> = (0..SHARD_COUNT).map(|_| Vec::new()).collect();
+        let mut buckets: Vec<Vec<(usize, &str)>> =
+            (0..self.shards.len()).map(|_| Vec::new()).collect();
         for (orig, key) in pending {
             let idx = self.shard_for_key(key);
             buckets[idx].push((orig, key));
@@ -966,7 +1382,7 @@ impl KVStore {
 
             // Hot path: read lock spans the entire bucket.
             {
-                let data = shard.data.read();
+                let data = shard.read();
                 for &(orig, key) in bucket {
                     self.stats.gets.fetch_add(1, Ordering::Relaxed);
                     match data.get(key) {
@@ -975,7 +1391,7 @@ impl KVStore {
                             value.update_access();
                             self.stats.hits.fetch_add(1, Ordering::Relaxed);
                             // Shared buffer — a refcount bump, not a copy.
-                            let value_arc = value.data_arc();
+                            let value_arc = self.value_shared(value)?;
                             let ttl = value.ttl_remaining();
                             if let Some(ref cache) = self.cache {
                                 cache.put(key.to_string(), value_arc.to_vec(), ttl);
@@ -996,7 +1412,7 @@ impl KVStore {
 
             // Cold path: only when at least one key needs eviction.
             if !expired.is_empty() {
-                let mut data = shard.data.write();
+                let mut data = shard.write();
                 for (orig, key) in expired {
                     // Re-check under the write lock — another writer may
                     // have removed or refreshed the entry between locks.
@@ -1006,7 +1422,7 @@ impl KVStore {
                             // Race: another writer refreshed the key.
                             v.update_access();
                             self.stats.hits.fetch_add(1, Ordering::Relaxed);
-                            let value_arc = v.data_arc();
+                            let value_arc = self.value_shared(v)?;
                             let ttl = v.ttl_remaining();
                             if let Some(ref cache) = self.cache {
                                 cache.put(key.to_string(), value_arc.to_vec(), ttl);
@@ -1023,6 +1439,7 @@ impl KVStore {
                     if still_expired {
                         if let Some(removed) = data.remove(key) {
                             let removed_size = self.estimate_entry_size(key, &removed);
+                            self.release_quota(key, &removed);
                             self.stats.total_keys.fetch_sub(1, Ordering::Relaxed);
                             self.stats
                                 .total_memory_bytes
@@ -1041,6 +1458,8 @@ impl KVStore {
     pub async fn mdel(&self, keys: &[String]) -> Result<usize> {
         debug!("MDEL count={}", keys.len());
 
+        self.check_cluster_routing_all(keys)?;
+
         let mut count = 0;
         for key in keys {
             if self.delete(key).await? {
@@ -1051,6 +1470,24 @@ impl KVStore {
         Ok(count)
     }
 
+    /// Warm a key ahead of traffic: mark it recently used and, when the L1
+    /// cache is enabled, load it there. Unlike a GET this is not counted in
+    /// the hit/miss stats, the access sampler or cluster routing. Returns the
+    /// value size, or `None` when the key is missing or expired.
+    pub fn prime(&self, key: &str) -> Option<usize> {
+        let shard = self.get_shard(key);
+        let data = shard.read();
+        let value = data.get(key).filter(|value| !value.is_expired())?;
+        value.update_access();
+        let bytes = self.value_bytes(value).ok()?;
+
+        if let Some(ref cache) = self.cache {
+            let ttl = value.ttl_remaining();
+            cache.put(key.to_string(), bytes.to_vec(), ttl);
+        }
+        Some(bytes.len())
+    }
+
     /// Scan keys with optional prefix
     pub async fn scan(&self, prefix: Option<&str>, limit: usize) -> Result<Vec<String>> {
         debug!("SCAN prefix={:?}, limit={}", prefix, limit);
@@ -1059,7 +1496,7 @@ impl KVStore {
 
         // Scan all shards
         for shard in self.shards.iter() {
-            let data = shard.data.read();
+            let data = shard.read();
 
             let shard_keys: Vec<String> = if let Some(prefix) = prefix {
                 data.get_prefix_keys(prefix)
@@ -1079,6 +1516,48 @@ impl KVStore {
         Ok(keys)
     }
 
+    /// Scan keys in lexicographic order, resuming after `cursor`
+    ///
+    /// Returns up to `limit` keys greater than `cursor` (all keys when `None`)
+    /// plus the cursor for the next page, which is `None` once the scan is
+    /// complete. Keys written behind the cursor during a scan are skipped.
+    pub async fn scan_page(
+        &self,
+        prefix: Option<&str>,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<(Vec<String>, Option<String>)> {
+        debug!(
+            "SCAN prefix={:?}, cursor={:?}, limit={}",
+            prefix, cursor, limit
+        );
+
+        let mut keys = Vec::new();
+        for shard in self.shards.iter() {
+            let data = shard.read();
+            let shard_keys = if let Some(prefix) = prefix {
+                data.get_prefix_keys(prefix)
+            } else {
+                data.keys()
+            };
+            keys.extend(
+                shard_keys
+                    .into_iter()
+                    .filter(|key| cursor.is_none_or(|cursor| key.as_str() > cursor)),
+            );
+        }
+
+        let limit = limit.max(1);
+        keys.sort_unstable();
+        let next_cursor = if keys.len() > limit {
+            keys.truncate(limit);
+            keys.last().cloned()
+        } else {
+            None
+        };
+        Ok((keys, next_cursor))
+    }
+
     /// Clean up expired keys.
     ///
     /// For `Small` (HashMap) shards the per-shard TTL min-heap is the
@@ -1124,7 +1603,7 @@ impl KVStore {
                     // deleted, overwritten with a new TTL, or converted
                     // to Persistent. Only evict if the stored expires_at
                     // matches the heap entry.
-                    let data = shard.data.read();
+                    let data = shard.read();
                     match data.get(key.as_str()) {
                         Some(v) if v.expires_at_ms() == Some(exp) && v.is_expired() => {
                             keys_to_remove.push(key);
@@ -1138,10 +1617,11 @@ impl KVStore {
 
                 // Batch-remove under a single write lock.
                 if !keys_to_remove.is_empty() {
-                    let mut data = shard.data.write();
+                    let mut data = shard.write();
                     for key in &keys_to_remove {
                         if let Some(removed_val) = data.remove(key.as_str()) {
                             let removed_size = self.estimate_entry_size(key.as_str(), &removed_val);
+                            self.release_quota(key.as_str(), &removed_val);
                             self.stats
                                 .total_memory_bytes
                                 .fetch_sub(removed_size as i64, Ordering::Relaxed);
@@ -1156,13 +1636,13 @@ impl KVStore {
 
             // --- Sampling fallback (for Large/trie shards or heap lag) ---
             {
-                let is_large = matches!(*shard.data.read(), ShardStorage::Large(_));
+                let is_large = matches!(*shard.read(), ShardStorage::Large(_));
                 if is_large {
                     for _ in 0..MAX_ITERATIONS {
                         let mut expired_keys = Vec::new();
 
                         {
-                            let data = shard.data.read();
+                            let data = shard.read();
                             let all_entries = data.iter();
                             let sampled: Vec<(String, bool)> = all_entries
                                 .into_iter()
@@ -1178,10 +1658,11 @@ impl KVStore {
                         }
 
                         if !expired_keys.is_empty() {
-                            let mut data = shard.data.write();
+                            let mut data = shard.write();
                             for key in &expired_keys {
                                 if let Some(removed_val) = data.remove(key) {
                                     let removed_size = self.estimate_entry_size(key, &removed_val);
+                                    self.release_quota(key, &removed_val);
                                     self.stats
                                         .total_memory_bytes
                                         .fetch_sub(removed_size as i64, Ordering::Relaxed);
@@ -1223,14 +1704,44 @@ impl KVStore {
         key.len() + value.data().len() + std::mem::size_of::<StoredValue>()
     }
 
+    /// Make room for a write of `needed_bytes` under the memory limit:
+    /// evict per the policy when over it, and refuse the write with
+    /// `MemoryLimitExceeded` (counted in `rejected_writes`) when eviction is
+    /// off or cannot free enough.
+    fn ensure_memory_for(&self, needed_bytes: usize) -> Result<()> {
+        let (current_bytes, max_bytes) = self.mem_used_and_max();
+        if max_bytes <= 0 || current_bytes + needed_bytes as i64 <= max_bytes {
+            return Ok(());
+        }
+        if self.config.eviction_policy != EvictionPolicy::NoEviction {
+            self.evict_until_free(needed_bytes);
+            let (after, _) = self.mem_used_and_max();
+            if after + needed_bytes as i64 <= max_bytes {
+                return Ok(());
+            }
+        }
+
+        warn!(
+            "Memory limit exceeded ({:?}): {}/{}",
+            self.config.eviction_policy,
+            self.mem_used_and_max().0,
+            max_bytes
+        );
+        self.stats.rejected_writes.fetch_add(1, Ordering::Relaxed);
+        Err(SynapError::MemoryLimitExceeded)
+    }
+
     /// Evict keys from all shards until at least `needed_bytes` of memory have been freed,
     /// or until no more evictable candidates remain.
     ///
-    /// Uses approximated LRU / random sampling matching Redis behaviour:
+    /// Uses approximated LRU / LFU / random sampling matching Redis behaviour:
     /// pick `sample_size` random keys per shard, evict the worst candidates.
     fn evict_until_free(&self, needed_bytes: usize) {
         use EvictionPolicy::*;
         let policy = self.config.eviction_policy;
+        if policy == NoEviction {
+            return;
+        }
         let sample_size = self.config.eviction_sample_size.max(1);
         // Free against the shared cross-datatype budget when attached.
         let (_, max_bytes) = self.mem_used_and_max();
@@ -1251,96 +1762,40 @@ impl KVStore {
                     break 'outer;
                 }
 
-                let mut data = shard.data.write();
-
-                // Collect candidate (key, score) pairs from a sample.
-                // score: lower means "evict first".
-                // data.keys() returns Vec<String> — call into_iter() to get a consuming iterator.
-                let all_keys = data.keys(); // Vec<String>
-                let candidates: Vec<(String, u64)> = match policy {
-                    AllKeysLru => all_keys
-                        .into_iter()
-                        .take(sample_size)
-                        .map(|k| {
-                            let la =
-                                data.get(k.as_str()).map(|v| v.last_access()).unwrap_or(0) as u64;
-                            (k, la)
-                        })
-                        .collect(),
-                    VolatileLru => all_keys
-                        .into_iter()
-                        .filter(|k| {
-                            data.get(k.as_str())
-                                .map(|v| matches!(v, StoredValue::Expiring { .. }))
-                                .unwrap_or(false)
-                        })
-                        .take(sample_size)
-                        .map(|k| {
-                            let la =
-                                data.get(k.as_str()).map(|v| v.last_access()).unwrap_or(0) as u64;
-                            (k, la)
-                        })
-                        .collect(),
-                    VolatileTtl =>
-                    // Score by expires_at ascending (soonest-expiring first).
-                    {
-                        all_keys
-                            .into_iter()
-                            .filter(|k| {
-                                data.get(k.as_str())
-                                    .map(|v| v.expires_at_ms().is_some())
-                                    .unwrap_or(false)
-                            })
-                            .take(sample_size)
-                            .map(|k| {
-                                let exp = data
-                                    .get(k.as_str())
-                                    .and_then(|v| v.expires_at_ms())
-                                    .unwrap_or(u64::MAX);
-                                (k, exp)
-                            })
-                            .collect()
-                    }
-                    AllKeysRandom => all_keys
-                        .into_iter()
-                        .take(sample_size)
-                        .map(|k| (k, 0u64))
-                        .collect(),
-                    VolatileRandom => all_keys
-                        .into_iter()
-                        .filter(|k| {
-                            data.get(k.as_str())
-                                .map(|v| matches!(v, StoredValue::Expiring { .. }))
-                                .unwrap_or(false)
-                        })
-                        .take(sample_size)
-                        .map(|k| (k, 0u64))
-                        .collect(),
-                    // LFU: score by access frequency — the lowest-frequency key in
-                    // the sample is evicted first.
-                    AllKeysLfu => all_keys
-                        .into_iter()
-                        .take(sample_size)
-                        .map(|k| {
-                            let f = data.get(k.as_str()).map(|v| v.freq()).unwrap_or(0) as u64;
-                            (k, f)
-                        })
-                        .collect(),
-                    VolatileLfu => all_keys
-                        .into_iter()
-                        .filter(|k| {
-                            data.get(k.as_str())
-                                .map(|v| matches!(v, StoredValue::Expiring { .. }))
-                                .unwrap_or(false)
-                        })
-                        .take(sample_size)
-                        .map(|k| {
-                            let f = data.get(k.as_str()).map(|v| v.freq()).unwrap_or(0) as u64;
-                            (k, f)
-                        })
-                        .collect(),
-                    NoEviction => break 'outer,
-                };
+                let mut data = shard.write();
+
+                // Score a random sample of the eligible keys; lower means
+                // "evict first". Random sampling keeps the approximation fair:
+                // iteration order would offer the same few keys every round.
+                let volatile_only = matches!(
+                    policy,
+                    VolatileLru | VolatileRandom | VolatileTtl | VolatileLfu
+                );
+                let candidates: Vec<(String, u64)> = data
+                    .keys()
+                    .into_iter()
+                    .filter(|k| {
+                        !volatile_only
+                            || data
+                                .get(k.as_str())
+                                .is_some_and(|v| v.expires_at_ms().is_some())
+                    })
+                    .sample(&mut rand::rng(), sample_size)
+                    .into_iter()
+                    .map(|k| {
+                        let value = data.get(k.as_str());
+                        let score = match policy {
+                            AllKeysLru | VolatileLru => value.map_or(0, |v| v.last_access() as u64),
+                            AllKeysLfu | VolatileLfu => value.map_or(0, |v| v.freq() as u64),
+                            // Soonest-expiring first
+                            VolatileTtl => {
+                                value.and_then(|v| v.expires_at_ms()).unwrap_or(u64::MAX)
+                            }
+                            AllKeysRandom | VolatileRandom | NoEviction => 0,
+                        };
+                        (k, score)
+                    })
+                    .collect();
 
                 if candidates.is_empty() {
                     continue;
@@ -1356,10 +1811,15 @@ impl KVStore {
                     && let Some(val) = data.remove(&key)
                 {
                     let size = self.estimate_entry_size(&key, &val) as i64;
+                    self.release_quota(&key, &val);
                     self.stats.total_keys.fetch_sub(1, Ordering::Relaxed);
                     self.stats
                         .total_memory_bytes
                         .fetch_sub(size, Ordering::Relaxed);
+                    self.stats.evicted_keys.fetch_add(1, Ordering::Relaxed);
+                    self.stats
+                        .evicted_bytes
+                        .fetch_add(size as u64, Ordering::Relaxed);
                     freed += size;
                     debug!("Evicted key={} size={} policy={:?}", key, size, policy);
                     evicted_notify.push(key);
@@ -1389,7 +1849,7 @@ impl KVStore {
 
         // Collect keys from all shards
         for shard in self.shards.iter() {
-            let data = shard.data.read();
+            let data = shard.read();
             all_keys.extend(data.keys());
         }
 
@@ -1412,6 +1872,12 @@ impl KVStore {
             ));
         }
 
+        Ok(self.flush())
+    }
+
+    /// Remove every key, whatever `allow_flush_commands` says, for full
+    /// syncs. Returns how many there were.
+    pub fn flush(&self) -> usize {
         let count = self.stats.total_keys.load(Ordering::Relaxed).max(0) as usize;
 
         // Invalidate cache
@@ -1421,13 +1887,16 @@ impl KVStore {
 
         // Clear all shards (data + TTL heap)
         for shard in self.shards.iter() {
-            let mut data = shard.data.write();
+            let mut data = shard.write();
             data.clear();
             shard.ttl_heap.lock().clear();
         }
 
         self.stats.total_keys.store(0, Ordering::Relaxed);
         self.stats.total_memory_bytes.store(0, Ordering::Relaxed);
+        if let Some(ref quotas) = self.quotas {
+            quotas.reset();
+        }
 
         // No per-key events on flush (Redis parity), but the version counters
         // must not survive into the keys' next incarnations.
@@ -1435,7 +1904,7 @@ impl KVStore {
             n.forget_all();
         }
 
-        Ok(count)
+        count
     }
 
     /// Flush all databases (alias for flushdb in single-db mode)
@@ -1453,11 +1922,11 @@ impl KVStore {
         debug!("EXPIRE key={}, ttl={}", key, ttl_secs);
 
         let shard = self.get_shard(key);
-        let mut data = shard.data.write();
+        let mut data = shard.write();
 
         if let Some(value) = data.remove(key) {
             // Convert to expiring variant or update existing
-            let new_value = StoredValue::new(value.data().to_vec(), Some(ttl_secs));
+            let new_value = StoredValue::new(self.restamp(&value), Some(ttl_secs));
             shard.track_ttl(&new_value, key);
             data.insert(key.to_string(), new_value);
             drop(data);
@@ -1474,11 +1943,11 @@ impl KVStore {
         debug!("PERSIST key={}", key);
 
         let shard = self.get_shard(key);
-        let mut data = shard.data.write();
+        let mut data = shard.write();
 
         if let Some(value) = data.remove(key) {
             // Convert to persistent variant
-            let new_value = StoredValue::new(value.data().to_vec(), None);
+            let new_value = StoredValue::new(self.restamp(&value), None);
             data.insert(key.to_string(), new_value);
             drop(data);
             self.notify_keyspace(crate::core::EventClass::Generic, "persist", key);
@@ -1489,26 +1958,36 @@ impl KVStore {
         }
     }
 
+    /// Payload of `value` for a new entry replacing it. An integer loses its
+    /// encoding, so under compression its digits take a header.
+    fn restamp(&self, value: &StoredValue) -> Arc<[u8]> {
+        match value {
+            StoredValue::Int { .. } => self.encode_value(value.data().to_vec()).into(),
+            _ => value.data_arc(),
+        }
+    }
+
     /// Dump all key-value pairs for persistence
     pub async fn dump(&self) -> Result<std::collections::HashMap<String, Vec<u8>>> {
         let mut dump = std::collections::HashMap::new();
 
         // Collect from all shards
-        for shard in self.shards.iter() {
-            let entries = {
-                let data = shard.data.read();
-                data.iter()
-            };
-
-            for (key, value) in entries {
-                if !value.is_expired() {
-                    dump.insert(key, value.data().to_vec());
-                }
+        for index in 0..self.shards.len() {
+            for (key, value) in self.dump_shard(index) {
+                dump.insert(key, self.value_bytes(&value)?.into_owned());
             }
         }
 
         Ok(dump)
     }
+
+    /// Live entries of shard `index`, collected under that shard's read lock
+    /// only. Values are shared, so this copies keys but not value bytes.
+    pub fn dump_shard(&self, index: usize) -> Vec<(String, StoredValue)> {
+        let mut entries = self.shards[index].read().iter();
+        entries.retain(|(_, value)| !value.is_expired());
+        entries
+    }
 }
 
 #[path = "store_string_ops.rs"]
@@ -20,7 +20,7 @@ impl KVStore {
         debug!("APPEND key={}, append_size={}", key, value.len());
 
         let shard = self.get_shard(key);
-        let mut data = shard.data.write();
+        let mut data = shard.write();
 
         // A partial mutation must ship the *resulting* value to watchers, not
         // the operand, so the merged bytes are captured here — but only when a
@@ -28,11 +28,24 @@ impl KVStore {
         let watching = self.watch_notifier.is_some();
         let mut watch_value: Option<Vec<u8>> = None;
 
+        let new_len = match data.get(key) {
+            Some(v) if !v.is_expired() => self.value_bytes(v)?.len() + value.len(),
+            _ => value.len(),
+        };
+        self.key_limits.check_value(key, new_len)?;
+        if self.quotas.is_some() {
+            self.charge_quota(
+                &data,
+                key,
+                key.len() + new_len + std::mem::size_of::<StoredValue>(),
+            )?;
+        }
+
         let new_length = if let Some(stored_value) = data.get_mut(key) {
             if stored_value.is_expired() {
                 // Key expired, treat as new
                 let new_data = value;
-                *stored_value = StoredValue::new(new_data.clone(), None);
+                *stored_value = StoredValue::new(self.encode_value(new_data.clone()), None);
                 if watching {
                     watch_value = Some(new_data.to_vec());
                 }
@@ -40,18 +53,18 @@ impl KVStore {
             } else {
                 // Append to existing (copy-on-write: the Arc payload is immutable).
                 stored_value.update_access();
-                let mut merged = stored_value.data().to_vec();
+                let mut merged = self.value_bytes(stored_value)?.into_owned();
                 merged.extend_from_slice(&value);
                 let len = merged.len();
                 if watching {
                     watch_value = Some(merged.clone());
                 }
-                stored_value.set_data(merged);
+                stored_value.set_data(self.encode_value(merged));
                 len
             }
         } else {
             // Key doesn't exist, create new
-            let new_value = StoredValue::new(value.clone(), None);
+            let new_value = StoredValue::new(self.encode_value(value.clone()), None);
             data.insert(key.to_string(), new_value);
             if watching {
                 watch_value = Some(value.to_vec());
@@ -79,14 +92,14 @@ impl KVStore {
         debug!("GETRANGE key={}, start={}, end={}", key, start, end);
 
         let shard = self.get_shard(key);
-        let data = shard.data.read();
+        let data = shard.read();
 
         if let Some(value) = data.get(key) {
             if value.is_expired() {
                 return Ok(Vec::new());
             }
 
-            let bytes = value.data();
+            let bytes = self.value_bytes(value)?;
             let len = bytes.len() as isize;
 
             // Normalize indices (handle negative indices)
@@ -129,24 +142,38 @@ impl KVStore {
         );
 
         let shard = self.get_shard(key);
-        let mut data = shard.data.write();
+        let mut data = shard.write();
 
         // A partial mutation must ship the *resulting* value to watchers, not
         // the operand. Captured only when a watch notifier is attached.
         let watching = self.watch_notifier.is_some();
         let mut watch_value: Option<Vec<u8>> = None;
 
+        let new_len = match data.get(key) {
+            Some(v) if !v.is_expired() => self.value_bytes(v)?.len().max(offset + value.len()),
+            _ => offset + value.len(),
+        };
+        self.key_limits.check_value(key, new_len)?;
+        if self.quotas.is_some() {
+            self.charge_quota(
+                &data,
+                key,
+                key.len() + new_len + std::mem::size_of::<StoredValue>(),
+            )?;
+        }
+
         let new_length = if let Some(stored_value) = data.get_mut(key) {
             if stored_value.is_expired() {
                 // Key expired, create new string with padding
                 let mut new_data = vec![0u8; offset];
                 new_data.extend_from_slice(&value);
-                *stored_value = StoredValue::new(new_data.clone(), None);
-                new_data.len()
+                let len = new_data.len();
+                *stored_value = StoredValue::new(self.encode_value(new_data), None);
+                len
             } else {
                 // Update existing (copy-on-write: the Arc payload is immutable).
                 stored_value.update_access();
-                let mut bytes = stored_value.data().to_vec();
+                let mut bytes = self.value_bytes(stored_value)?.into_owned();
 
                 // Extend if necessary
                 let required_len = offset + value.len();
@@ -160,19 +187,20 @@ impl KVStore {
                 if watching {
                     watch_value = Some(bytes.clone());
                 }
-                stored_value.set_data(bytes);
+                stored_value.set_data(self.encode_value(bytes));
                 len
             }
         } else {
             // Key doesn't exist, create new with padding
             let mut new_data = vec![0u8; offset];
             new_data.extend_from_slice(&value);
-            let new_value = StoredValue::new(new_data.clone(), None);
-            data.insert(key.to_string(), new_value);
+            let len = new_data.len();
             if watching {
                 watch_value = Some(new_data.clone());
             }
-            new_data.len()
+            let new_value = StoredValue::new(self.encode_value(new_data), None);
+            data.insert(key.to_string(), new_value);
+            len
         };
 
         self.stats.sets.fetch_add(1, Ordering::Relaxed);
@@ -194,7 +222,7 @@ impl KVStore {
         debug!("STRLEN key={}", key);
 
         let shard = self.get_shard(key);
-        let data = shard.data.read();
+        let data = shard.read();
 
         if let Some(value) = data.get(key) {
             if value.is_expired() {
@@ -206,7 +234,7 @@ impl KVStore {
             self.stats.gets.fetch_add(1, Ordering::Relaxed);
             self.stats.hits.fetch_add(1, Ordering::Relaxed);
 
-            Ok(value.data().len())
+            Ok(self.value_bytes(value)?.len())
         } else {
             self.stats.gets.fetch_add(1, Ordering::Relaxed);
             self.stats.misses.fetch_add(1, Ordering::Relaxed);
@@ -218,23 +246,24 @@ impl KVStore {
     /// Returns the old value, or None if key didn't exist
     pub async fn getset(&self, key: &str, value: Vec<u8>) -> Result<Option<Vec<u8>>> {
         debug!("GETSET key={}, value_size={}", key, value.len());
+        self.key_limits.check_value(key, value.len())?;
 
         // Isolate against an in-flight EXEC on the same key (audit M-010).
         let _guard = self.key_locks.read_key(key).await;
 
         let shard = self.get_shard(key);
-        let mut data = shard.data.write();
-
-        let old_value = data.remove(key).map(|stored_value| {
-            if stored_value.is_expired() {
-                Vec::new() // Return empty for expired keys
-            } else {
-                stored_value.data().to_vec()
-            }
-        });
+        let mut data = shard.write();
 
         // Insert new value
-        let new_value = StoredValue::new(value.clone(), None);
+        let new_value = StoredValue::new(self.encode_value(value.clone()), None);
+        self.charge_quota(&data, key, self.estimate_entry_size(key, &new_value))?;
+
+        let old_value = match data.get(key) {
+            Some(stored_value) if stored_value.is_expired() => Some(Vec::new()), // Return empty for expired keys
+            Some(stored_value) => Some(self.value_bytes(stored_value)?.into_owned()),
+            None => None,
+        };
+
         data.insert(key.to_string(), new_value);
 
         self.stats.gets.fetch_add(1, Ordering::Relaxed);
@@ -267,11 +296,16 @@ impl KVStore {
             return Ok(true);
         }
 
+        self.check_cluster_routing_all(pairs.iter().map(|(key, _)| key))?;
+        for (key, value) in &pairs {
+            self.key_limits.check_value(key, value.len())?;
+        }
+
         // Check if all keys don't exist (need to check all shards)
         // Quick check: if any key exists, return false
         for (key, _) in &pairs {
             let shard = self.get_shard(key);
-            let data = shard.data.read();
+            let data = shard.read();
             if let Some(value) = data.get(key)
                 && !value.is_expired()
             {
@@ -279,18 +313,38 @@ impl KVStore {
             }
         }
 
+        // Refuse the whole batch if it would take a namespace over its quota
+        if let Some(ref quotas) = self.quotas {
+            quotas.check_batch(pairs.iter().map(|(key, value)| {
+                let new_size = key.len() + value.len() + std::mem::size_of::<StoredValue>();
+                let (keys, bytes) = self.quota_delta(&self.get_shard(key).read(), key, new_size);
+                (key.as_str(), keys, bytes)
+            }))?;
+        }
+
         // All keys are free, now set them all atomically
         for (key, value) in &pairs {
             let shard = self.get_shard(key);
-            let mut data = shard.data.write();
+            let mut data = shard.write();
 
-            data.insert(key.clone(), StoredValue::new(value.clone(), None));
+            if let Some(ref quotas) = self.quotas {
+                let new_size = key.len() + value.len() + std::mem::size_of::<StoredValue>();
+                let (keys, bytes) = self.quota_delta(&data, key, new_size);
+                quotas.charge(key, keys, bytes);
+            }
+            data.insert(
+                key.clone(),
+                StoredValue::new(self.encode_value(value.clone()), None),
+            );
 
             // Invalidate cache
             if let Some(ref cache) = self.cache {
                 cache.delete(key);
             }
         }
+        for (key, _) in &pairs {
+            self.invalidate_tracked(key);
+        }
 
         self.stats
             .sets
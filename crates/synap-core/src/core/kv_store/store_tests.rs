@@ -271,6 +271,26 @@ async fn test_mdel() {
     assert!(store.exists("key3").await.unwrap());
 }
 
+#[tokio::test]
+async fn test_prime() {
+    let store = KVStore::new_with_cache(KVConfig::default(), Some(16));
+
+    store.set("hot", b"value".to_vec(), None).await.unwrap();
+    store.set("gone", b"x".to_vec(), Some(0)).await.unwrap();
+    let before = store.stats().await;
+
+    assert_eq!(store.prime("hot"), Some(5));
+    assert_eq!(store.prime("missing"), None);
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    assert_eq!(store.prime("gone"), None);
+
+    // Priming is not a read
+    let after = store.stats().await;
+    assert_eq!(after.gets, before.gets);
+    assert_eq!(after.misses, before.misses);
+    assert_eq!(store.get("hot").await.unwrap(), Some(b"value".to_vec()));
+}
+
 #[tokio::test]
 async fn test_scan() {
     let store = KVStore::new(KVConfig::default());
@@ -288,6 +308,29 @@ async fn test_scan() {
     assert!(keys.contains(&"user:2".to_string()));
 }
 
+#[tokio::test]
+async fn test_scan_page_resumes_after_cursor() {
+    let store = KVStore::new(KVConfig::default());
+    for i in 0..5 {
+        store
+            .set(&format!("user:{i}"), b"x".to_vec(), None)
+            .await
+            .unwrap();
+    }
+    store.set("product:1", b"x".to_vec(), None).await.unwrap();
+
+    let (page, cursor) = store.scan_page(Some("user:"), None, 2).await.unwrap();
+    assert_eq!(page, vec!["user:0", "user:1"]);
+    assert_eq!(cursor.as_deref(), Some("user:1"));
+
+    let (page, cursor) = store
+        .scan_page(Some("user:"), Some("user:1"), 3)
+        .await
+        .unwrap();
+    assert_eq!(page, vec!["user:2", "user:3", "user:4"]);
+    assert_eq!(cursor, None);
+}
+
 #[tokio::test]
 async fn test_stats() {
     let store = KVStore::new(KVConfig::default());
@@ -983,7 +1026,7 @@ async fn test_set_px_millisecond_expiry() {
 
     // remaining_ttl_ms should be ≤ 5000 and > 4000 (test runs fast)
     let shard = store.get_shard("px_key");
-    let data = shard.data.read();
+    let data = shard.read();
     let stored = data.get("px_key").unwrap();
     let ms = stored.remaining_ttl_ms().unwrap();
     assert!(ms <= 5_000, "TTL in ms must not exceed 5000 (got {ms})");
@@ -1053,12 +1096,12 @@ async fn test_get_updates_last_access_for_lru() {
     // Read last_access directly from shard data.
     let old_last = {
         let shard = store.get_shard("old_key");
-        let data = shard.data.read();
+        let data = shard.read();
         data.get("old_key").unwrap().last_access()
     };
     let new_last = {
         let shard = store.get_shard("new_key");
-        let data = shard.data.read();
+        let data = shard.read();
         data.get("new_key").unwrap().last_access()
     };
 
@@ -1186,6 +1229,85 @@ async fn test_eviction_noeviction_returns_error_when_full() {
     );
 }
 
+/// Eviction keeps the store under its limit and is counted in stats; writes
+/// refused because nothing can be evicted are counted too.
+#[tokio::test]
+async fn test_eviction_counters() {
+    let store = KVStore::new(KVConfig {
+        max_memory_mb: 1,
+        eviction_policy: EvictionPolicy::AllKeysLru,
+        ..KVConfig::default()
+    });
+    let big_val = vec![0u8; 50_000];
+    for i in 0..40 {
+        store
+            .set(&format!("fill_{i}"), big_val.clone(), None)
+            .await
+            .unwrap();
+    }
+    let stats = store.stats().await;
+    assert!(stats.evicted_keys > 0);
+    assert!(stats.evicted_bytes >= stats.evicted_keys * 50_000);
+    assert!(stats.total_memory_bytes <= 1024 * 1024);
+    assert_eq!(stats.rejected_writes, 0);
+
+    // volatile-ttl has nothing to evict when no key has a TTL
+    let store = KVStore::new(KVConfig {
+        max_memory_mb: 1,
+        eviction_policy: EvictionPolicy::VolatileTtl,
+        ..KVConfig::default()
+    });
+    let mut refused = 0;
+    for i in 0..40 {
+        if let Err(e) = store.set(&format!("fill_{i}"), big_val.clone(), None).await {
+            assert!(matches!(e, SynapError::MemoryLimitExceeded));
+            refused += 1;
+        }
+    }
+    let stats = store.stats().await;
+    assert!(refused > 0);
+    assert_eq!(stats.rejected_writes, refused);
+    assert_eq!(stats.evicted_keys, 0);
+}
+
+#[tokio::test]
+async fn test_defrag_shrinks_shards_and_relocates_values() {
+    let store = KVStore::new(KVConfig::default());
+    for i in 0..5_000 {
+        store
+            .set(&format!("k{i}"), b"v".to_vec(), None)
+            .await
+            .unwrap();
+    }
+    for i in 10..5_000 {
+        store.delete(&format!("k{i}")).await.unwrap();
+    }
+    store.set("big", vec![7u8; 100_000], None).await.unwrap();
+    store.set("shared", vec![9u8; 100_000], None).await.unwrap();
+    // A reader holding the payload keeps it in place
+    let held = store.get_shared("shared").await.unwrap().unwrap();
+
+    let config = DefragConfig {
+        min_reclaimable_bytes: 1024,
+        ..DefragConfig::default()
+    };
+    let report = store.defrag(&config);
+    assert!(report.shards_compacted > 0);
+    assert!(report.reclaimed_bytes > 0);
+    assert_eq!(report.relocated_values, 1);
+    assert_eq!(report.relocated_bytes, 100_000);
+
+    // Data is untouched and a second pass has nothing left to shrink
+    assert_eq!(store.get("k3").await.unwrap(), Some(b"v".to_vec()));
+    assert_eq!(store.get("big").await.unwrap(), Some(vec![7u8; 100_000]));
+    assert_eq!(&held[..], &[9u8; 100_000][..]);
+    assert_eq!(store.defrag(&config).shards_compacted, 0);
+
+    let stats = store.stats().await;
+    assert_eq!(stats.defrag_passes, 2);
+    assert_eq!(stats.defrag_reclaimed_bytes, report.reclaimed_bytes);
+}
+
 /// 4.5 (eviction) — allkeys-lfu evicts under pressure without erroring, and a
 /// frequently-accessed volatile key is not the first to go. Assertions are loose
 /// (eviction is sampled + sharded) but exercise the LFU eviction arm.
@@ -1219,7 +1341,7 @@ async fn test_eviction_allkeys_lfu_fires() {
     assert!(store.dbsize().await.unwrap() < 41);
 }
 
-/// Shard-aware MGET preserves input order across all 64 shards.
+/// Shard-aware MGET preserves input order across all shards.
 #[tokio::test]
 async fn test_mget_shard_aware_ordering() {
     let store = KVStore::new(KVConfig::default());
@@ -1247,6 +1369,45 @@ async fn test_mget_shard_aware_ordering() {
     }
 }
 
+/// The shard count comes from the config and every shard reports its own
+/// keys and lock activity.
+#[tokio::test]
+async fn test_configured_shard_count_and_shard_stats() {
+    let store = KVStore::new(KVConfig {
+        shard_count: 7,
+        ..KVConfig::default()
+    });
+    assert_eq!(store.shard_count(), 7);
+
+    for i in 0..200 {
+        store
+            .set(&format!("shard-{i}"), b"v".to_vec(), None)
+            .await
+            .unwrap();
+    }
+    for i in 0..200 {
+        assert!(store.get(&format!("shard-{i}")).await.unwrap().is_some());
+    }
+
+    let stats = store.shard_stats();
+    assert_eq!(stats.len(), 7);
+    assert!(stats.iter().enumerate().all(|(i, s)| s.shard == i));
+    assert_eq!(stats.iter().map(|s| s.keys).sum::<usize>(), 200);
+    assert!(stats.iter().all(|s| s.keys > 0));
+    assert!(stats.iter().map(|s| s.writes).sum::<u64>() >= 200);
+    assert!(stats.iter().map(|s| s.reads).sum::<u64>() >= 200);
+
+    let dumped: usize = (0..7).map(|i| store.dump_shard(i).len()).sum();
+    assert_eq!(dumped, 200);
+
+    // Zero shards is treated as one
+    let single = KVStore::new(KVConfig {
+        shard_count: 0,
+        ..KVConfig::default()
+    });
+    assert_eq!(single.shard_count(), 1);
+}
+
 /// TTL min-heap drains expired entries in expiry order.
 #[tokio::test]
 async fn test_ttl_heap_expiry_order() {
@@ -1317,6 +1478,23 @@ async fn test_ttl_heap_stale_entries() {
     assert_eq!(val.as_deref(), Some(b"v2".as_slice()));
 }
 
+/// GET and MGET feed the attached access sampler; writes do not.
+#[tokio::test]
+async fn test_access_sampler_records_reads() {
+    let sampler = Arc::new(crate::cache::AccessSampler::new(1, 100));
+    let store = KVStore::new(KVConfig::default()).with_access_sampler(Some(sampler.clone()));
+
+    store.set("a", b"1".to_vec(), None).await.unwrap();
+    store.get("a").await.unwrap();
+    store.get("missing").await.unwrap();
+    store
+        .mget(&["a".to_string(), "b".to_string()])
+        .await
+        .unwrap();
+
+    assert_eq!(sampler.snapshot(), vec!["a", "missing", "a", "b"]);
+}
+
 // ── KV watch (phase21) ───────────────────────────────────────────────────────
 
 mod watch {
@@ -1638,3 +1816,230 @@ mod watch {
         );
     }
 }
+
+#[tokio::test]
+async fn test_namespace_quotas() {
+    use crate::core::{NamespaceLimit, NamespaceQuotas};
+
+    let store = KVStore::new(KVConfig {
+        allow_flush_commands: true,
+        ..Default::default()
+    });
+    store.set("tenant:old", b"x".to_vec(), None).await.unwrap();
+    let store = store.with_namespace_quotas(Some(NamespaceQuotas::new(vec![NamespaceLimit {
+        prefix: "tenant:".to_string(),
+        max_keys: Some(3),
+        max_memory_bytes: None,
+    }])));
+    let quotas = store.namespace_quotas().unwrap().clone();
+    // Keys stored before the quotas were attached are counted
+    assert_eq!(quotas.usage()[0].keys, 1);
+
+    store.set("tenant:a", b"1".to_vec(), None).await.unwrap();
+    store.incr("tenant:n", 1).await.unwrap();
+    assert!(matches!(
+        store.set("tenant:b", b"1".to_vec(), None).await,
+        Err(SynapError::QuotaExceeded(_))
+    ));
+    // Overwrites and keys outside the namespace still work
+    store.set("tenant:a", b"2".to_vec(), None).await.unwrap();
+    store.set("other", b"1".to_vec(), None).await.unwrap();
+    // A batch over the quota writes nothing
+    assert!(
+        store
+            .mset(vec![
+                ("x".to_string(), b"1".to_vec()),
+                ("tenant:c".to_string(), b"1".to_vec()),
+            ])
+            .await
+            .is_err()
+    );
+    assert!(!store.exists("x").await.unwrap());
+
+    store.delete("tenant:old").await.unwrap();
+    store.set("tenant:b", b"1".to_vec(), None).await.unwrap();
+    assert_eq!(quotas.usage()[0].keys, 3);
+
+    store.flushdb().await.unwrap();
+    assert_eq!(quotas.usage()[0].keys, 0);
+}
+
+#[tokio::test]
+async fn test_value_size_limit() {
+    let store = KVStore::new(KVConfig::default()).with_key_limits(crate::core::KeyLimits {
+        max_value_bytes: Some(4),
+        ..Default::default()
+    });
+    store.set("k", b"abcd".to_vec(), None).await.unwrap();
+    assert!(matches!(
+        store.set("k", b"abcde".to_vec(), None).await,
+        Err(SynapError::KeyLimitExceeded { size: 5, .. })
+    ));
+    // Growing writes are measured by the resulting value
+    assert!(store.append("k", b"e".to_vec()).await.is_err());
+    assert!(store.setrange("k", 4, b"e".to_vec()).await.is_err());
+    assert_eq!(store.setrange("k", 0, b"AB".to_vec()).await.unwrap(), 4);
+    // A batch with one oversized value writes nothing
+    assert!(
+        store
+            .mset(vec![
+                ("x".to_string(), b"1".to_vec()),
+                ("y".to_string(), b"too big".to_vec()),
+            ])
+            .await
+            .is_err()
+    );
+    assert!(!store.exists("x").await.unwrap());
+
+    store.set("small", b"1".to_vec(), None).await.unwrap();
+    let largest = store.largest_keys(1);
+    assert_eq!(largest[0].key, "k");
+    assert_eq!(largest[0].size, 4);
+}
+
+#[tokio::test]
+async fn test_value_compression_is_transparent() {
+    use crate::compression::ValueCompressionConfig;
+
+    let store = KVStore::new(KVConfig {
+        compression: ValueCompressionConfig {
+            enabled: true,
+            min_size_bytes: 64,
+            ..Default::default()
+        },
+        ..KVConfig::default()
+    });
+    let large = "abcdefgh".repeat(64).into_bytes();
+
+    store.set("big", large.clone(), None).await.unwrap();
+    store
+        .set("small", b"tiny".to_vec(), Some(60))
+        .await
+        .unwrap();
+    assert_eq!(store.get("big").await.unwrap(), Some(large.clone()));
+    assert_eq!(store.get("small").await.unwrap(), Some(b"tiny".to_vec()));
+    assert_eq!(
+        store
+            .mget(&["big".to_string(), "small".to_string()])
+            .await
+            .unwrap(),
+        vec![Some(large.clone()), Some(b"tiny".to_vec())]
+    );
+    // Memory accounting sees the compressed size
+    assert!(store.stats().await.total_memory_bytes < large.len() as i64);
+
+    // String commands work on the decompressed value
+    assert_eq!(store.strlen("big").await.unwrap(), 512);
+    assert_eq!(store.getrange("big", 0, 3).await.unwrap(), b"abcd");
+    assert_eq!(store.append("big", b"!".to_vec()).await.unwrap(), 513);
+    assert_eq!(store.setrange("small", 0, b"T".to_vec()).await.unwrap(), 4);
+    assert_eq!(
+        store.getset("small", b"new".to_vec()).await.unwrap(),
+        Some(b"Tiny".to_vec())
+    );
+
+    // Counters, including ones that lose their integer encoding
+    store.set("n", b"41".to_vec(), Some(60)).await.unwrap();
+    assert_eq!(store.incr("n", 1).await.unwrap(), 42);
+    assert_eq!(store.incr("n", 1).await.unwrap(), 43);
+    store.incr("c", 5).await.unwrap();
+    store.expire("c", 60).await.unwrap();
+    assert_eq!(store.get("c").await.unwrap(), Some(b"5".to_vec()));
+    assert_eq!(store.incr("c", 1).await.unwrap(), 6);
+
+    let dump = store.dump().await.unwrap();
+    assert_eq!(dump["big"].len(), 513);
+
+    let report = store.compression_stats().unwrap();
+    assert_eq!(report.compressed_values, 2);
+    assert!(report.ratio > 1.0);
+    assert!(
+        KVStore::new(KVConfig::default())
+            .compression_stats()
+            .is_none()
+    );
+}
+
+// ── Client-side caching ──────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn tracked_keys_are_invalidated_by_every_write_path() {
+    use crate::core::{Invalidation, KeyWatchNotifier, PubSubRouter, TrackingTable};
+
+    let tracking = Arc::new(TrackingTable::default());
+    let notifier = KeyWatchNotifier::new(Arc::new(PubSubRouter::new()), 0)
+        .with_tracking(Arc::clone(&tracking));
+    let config = KVConfig {
+        allow_flush_commands: true,
+        ..Default::default()
+    };
+    let store = KVStore::new(config).with_watch_notifier(Some(Arc::new(notifier)));
+    let (client, mut rx) = tracking.register();
+    let track = |key: &str| assert!(tracking.track(client, &[key.to_string()]));
+
+    track("a");
+    store.set("a", b"1".to_vec(), None).await.unwrap();
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        Invalidation::Keys(vec!["a".to_string()])
+    );
+
+    // A TTL change leaves the cached value valid
+    track("a");
+    store.expire("a", 60).await.unwrap();
+    assert!(rx.try_recv().is_err());
+
+    store
+        .mset(vec![("a".to_string(), b"2".to_vec())])
+        .await
+        .unwrap();
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        Invalidation::Keys(vec!["a".to_string()])
+    );
+
+    track("b");
+    store
+        .msetnx(vec![("b".to_string(), b"1".to_vec())])
+        .await
+        .unwrap();
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        Invalidation::Keys(vec!["b".to_string()])
+    );
+
+    track("b");
+    store.delete("b").await.unwrap();
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        Invalidation::Keys(vec!["b".to_string()])
+    );
+
+    store.flushdb().await.unwrap();
+    assert_eq!(rx.try_recv().unwrap(), Invalidation::Flush);
+}
+
+#[tokio::test]
+async fn test_memory_profile_and_idle_keys() {
+    let store = KVStore::new(KVConfig::default());
+
+    store.set("counter", b"41".to_vec(), None).await.unwrap();
+    store.incr("counter", 1).await.unwrap();
+    store.set("blob", vec![0u8; 500], Some(60)).await.unwrap();
+
+    let counter = store.key_memory("counter").unwrap();
+    assert_eq!(counter.encoding, "int");
+    assert_eq!((counter.elements, counter.item_bytes), (1, 2));
+    assert_eq!(store.key_memory("blob").unwrap().encoding, "raw");
+    assert!(store.key_memory("missing").is_none());
+
+    let profile = store.memory_profile(10);
+    assert_eq!(profile.keys, 2);
+    assert_eq!(profile.item_bytes, 502);
+    assert_eq!(profile.biggest[0].key, "blob");
+
+    // Only the key with a TTL tracks access.
+    let idle = store.most_idle_keys(10);
+    assert_eq!(idle.len(), 1);
+    assert_eq!(idle[0].key, "blob");
+}
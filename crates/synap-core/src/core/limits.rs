@@ -0,0 +1,202 @@
+//! Per-key size guardrails.
+//!
+//! One runaway key — a multi-megabyte string, a hash that grows a field per
+//! request, an unbounded list used as a log — can take a node down on its own:
+//! every read of it is huge, every snapshot copies it, and eviction cannot
+//! split it. [`KeyLimits`] caps the size of a single key per datatype. A write
+//! that would take a key past its cap is refused with
+//! [`SynapError::KeyLimitExceeded`] before anything is changed.
+//!
+//! Limits are attached to the stores with `with_key_limits` after recovery, so
+//! replaying a snapshot or WAL never fails because a limit was lowered. Writes
+//! that do not grow a key are always allowed, so a key already over a lowered
+//! limit can still be trimmed.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{Result, SynapError};
+
+/// Most members one `SRANDMEMBER` with a negative count returns when
+/// [`KeyLimits::max_random_count`] is unset.
+pub const DEFAULT_MAX_RANDOM_COUNT: usize = 100_000;
+
+/// Hard per-key limits. `None` means unlimited, the default for each size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyLimits {
+    /// Largest string value in bytes.
+    #[serde(default)]
+    pub max_value_bytes: Option<usize>,
+    /// Most fields in one hash.
+    #[serde(default)]
+    pub max_hash_fields: Option<usize>,
+    /// Most elements in one list.
+    #[serde(default)]
+    pub max_list_length: Option<usize>,
+    /// Most members in one sorted set.
+    #[serde(default)]
+    pub max_zset_members: Option<usize>,
+    /// Most members one `SRANDMEMBER` with a negative count returns. Repeats
+    /// make the reply independent of the set's size, so this is always
+    /// bounded: [`DEFAULT_MAX_RANDOM_COUNT`] when unset.
+    #[serde(default)]
+    pub max_random_count: Option<usize>,
+}
+
+impl KeyLimits {
+    /// Refuse a string value of `size` bytes.
+    pub fn check_value(&self, key: &str, size: usize) -> Result<()> {
+        check(key, "max_value_bytes", self.max_value_bytes, size, size)
+    }
+
+    /// Refuse adding `added` fields to a hash of `len` fields.
+    pub fn check_hash(&self, key: &str, len: usize, added: usize) -> Result<()> {
+        check(
+            key,
+            "max_hash_fields",
+            self.max_hash_fields,
+            len + added,
+            added,
+        )
+    }
+
+    /// Refuse adding `added` elements to a list of `len` elements.
+    pub fn check_list(&self, key: &str, len: usize, added: usize) -> Result<()> {
+        check(
+            key,
+            "max_list_length",
+            self.max_list_length,
+            len + added,
+            added,
+        )
+    }
+
+    /// Refuse adding `added` members to a sorted set of `len` members.
+    pub fn check_zset(&self, key: &str, len: usize, added: usize) -> Result<()> {
+        check(
+            key,
+            "max_zset_members",
+            self.max_zset_members,
+            len + added,
+            added,
+        )
+    }
+
+    /// Refuse a random sample of `count` members that may repeat.
+    pub fn check_random_count(&self, count: u64) -> Result<()> {
+        let max = self.max_random_count.unwrap_or(DEFAULT_MAX_RANDOM_COUNT);
+        if count > max as u64 {
+            return Err(SynapError::InvalidRequest(format!(
+                "count {} exceeds max_random_count ({})",
+                count, max
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn check(
+    key: &str,
+    limit: &'static str,
+    max: Option<usize>,
+    size: usize,
+    growth: usize,
+) -> Result<()> {
+    match max {
+        Some(max) if growth > 0 && size > max => Err(SynapError::KeyLimitExceeded {
+            key: key.to_string(),
+            limit,
+            max,
+            size,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// A key and its size in a datatype's own unit: bytes for strings, fields for
+/// hashes, elements for lists, members for sorted sets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeySize {
+    pub key: String,
+    pub size: usize,
+}
+
+/// The `n` largest keys offered to it, cloning a key only while it is a
+/// contender.
+pub(crate) struct Largest {
+    n: usize,
+    heap: BinaryHeap<Reverse<(usize, String)>>,
+}
+
+impl Largest {
+    pub(crate) fn new(n: usize) -> Self {
+        Self {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    pub(crate) fn offer(&mut self, key: &str, size: usize) {
+        if self.n == 0 {
+            return;
+        }
+        if self.heap.len() == self.n {
+            match self.heap.peek() {
+                Some(Reverse((smallest, _))) if size > *smallest => {
+                    self.heap.pop();
+                }
+                _ => return,
+            }
+        }
+        self.heap.push(Reverse((size, key.to_string())));
+    }
+
+    /// Largest first.
+    pub(crate) fn into_vec(self) -> Vec<KeySize> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((size, key))| KeySize { key, size })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_growth_past_the_limit_is_refused() {
+        let limits = KeyLimits {
+            max_list_length: Some(3),
+            ..Default::default()
+        };
+        assert!(limits.check_list("l", 2, 1).is_ok());
+        let err = limits.check_list("l", 3, 1).unwrap_err();
+        assert!(matches!(
+            err,
+            SynapError::KeyLimitExceeded {
+                limit: "max_list_length",
+                max: 3,
+                size: 4,
+                ..
+            }
+        ));
+        // Already over a lowered limit, but not growing
+        assert!(limits.check_list("l", 10, 0).is_ok());
+        // Other datatypes are unlimited
+        assert!(limits.check_hash("h", 1_000_000, 1).is_ok());
+    }
+
+    #[test]
+    fn largest_keeps_the_biggest_in_order() {
+        let mut largest = Largest::new(2);
+        for (key, size) in [("a", 5), ("b", 1), ("c", 9), ("d", 7)] {
+            largest.offer(key, size);
+        }
+        let keys: Vec<_> = largest.into_vec().into_iter().map(|k| k.key).collect();
+        assert_eq!(keys, vec!["c", "d"]);
+    }
+}
@@ -17,6 +17,7 @@
 //! ```
 
 use super::error::{Result, SynapError};
+use super::memory_profile::KeyMemory;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
@@ -204,6 +205,12 @@ impl ListValue {
         }
     }
 
+    /// Seconds left before the list expires, `None` without a TTL
+    pub fn ttl(&self) -> Option<u64> {
+        self.ttl_secs
+            .map(|ttl| (self.created_at + ttl).saturating_sub(Self::current_timestamp()))
+    }
+
     /// Get number of elements
     pub fn len(&self) -> usize {
         match &self.repr {
@@ -217,6 +224,18 @@ impl ListValue {
         self.len() == 0
     }
 
+    /// Encoding and estimated memory of the list stored under `key`
+    pub fn memory(&self, key: &str) -> KeyMemory {
+        match &self.repr {
+            ListRepr::Packed { buf, count } => {
+                KeyMemory::new(key, "packed", *count, buf.len() - 4 * count)
+            }
+            ListRepr::Deque(d) => {
+                KeyMemory::new(key, "deque", d.len(), d.iter().map(Vec::len).sum())
+            }
+        }
+    }
+
     /// Push element to left (front)
     pub fn lpush(&mut self, value: Vec<u8>) {
         self.updated_at = Self::current_timestamp();
@@ -373,11 +392,12 @@ impl ListValue {
             return;
         }
 
-        // Normalize indices
+        // Normalize indices. A start past the end is left alone so the range
+        // below comes out empty (`LTRIM key 1 0` on one element clears it).
         let start = if start < 0 {
             (len + start).max(0)
         } else {
-            start.min(len - 1)
+            start
         };
 
         let stop = if stop < 0 {
@@ -705,6 +725,11 @@ mod tests {
 
         let range = store.lrange("mylist", 0, -1).unwrap();
         assert_eq!(range, vec![b"1".to_vec(), b"2".to_vec(), b"3".to_vec()]);
+
+        // An empty range clears the list, however short it is
+        store.rpush("single", vec![b"x".to_vec()], false).unwrap();
+        store.ltrim("single", 1, 0).unwrap();
+        assert!(matches!(store.llen("single"), Err(SynapError::NotFound)));
     }
 
     #[test]
@@ -788,6 +813,38 @@ mod tests {
         assert_eq!(dest_range, vec![b"c".to_vec()]);
     }
 
+    #[test]
+    fn test_list_length_limit() {
+        let store = ListStore::new().with_key_limits(crate::core::KeyLimits {
+            max_list_length: Some(2),
+            ..Default::default()
+        });
+        store
+            .rpush("full", vec![b"a".to_vec(), b"b".to_vec()], false)
+            .unwrap();
+
+        let err = store.lpush("full", vec![b"c".to_vec()], false);
+        assert!(matches!(err, Err(SynapError::KeyLimitExceeded { .. })));
+        let err = store.linsert("full", true, b"a".to_vec(), b"c".to_vec());
+        assert!(matches!(err, Err(SynapError::KeyLimitExceeded { .. })));
+
+        // A refused push to a fresh key leaves nothing behind
+        assert!(store.rpush("new", vec![vec![]; 3], false).is_err());
+        assert!(!store.exists("new"));
+
+        // RPOPLPUSH into a full list keeps the source intact
+        store.rpush("src", vec![b"x".to_vec()], false).unwrap();
+        assert!(store.rpoplpush("src", "full").is_err());
+        assert_eq!(store.llen("src").unwrap(), 1);
+        // Rotating a full list onto itself does not grow it
+        assert_eq!(store.rpoplpush("full", "full").unwrap(), b"b".to_vec());
+
+        // Shrinking always works
+        store.lpop("full", None).unwrap();
+        store.rpush("full", vec![b"d".to_vec()], false).unwrap();
+        assert_eq!(store.largest_keys(1)[0].size, 2);
+    }
+
     #[test]
     fn test_llen() {
         let store = ListStore::new();
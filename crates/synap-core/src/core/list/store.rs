@@ -99,6 +99,8 @@ pub struct ListStore {
     mem_bytes: Arc<std::sync::atomic::AtomicI64>,
     /// Optional keyspace-notification publisher (Redis `notify-keyspace-events`).
     keyspace_notifier: Option<Arc<crate::core::KeyspaceNotifier>>,
+    /// Per-key length cap (`max_list_length`).
+    key_limits: crate::core::KeyLimits,
 }
 
 impl Default for ListStore {
@@ -120,6 +122,42 @@ impl ListStore {
         out
     }
 
+    /// One list, as [`dump`](Self::dump) would persist it
+    pub fn dump_key(&self, key: &str) -> Option<ListValue> {
+        self.shard(key).read().get(key).cloned()
+    }
+
+    /// Replace the list at `key` with `elements`, expiring `ttl_secs` from
+    /// now, for full syncs and dump imports. Empty `elements` removes the key.
+    pub fn restore(&self, key: &str, elements: Vec<Vec<u8>>, ttl_secs: Option<u64>) {
+        {
+            let mut map = self.shard(key).write();
+            if elements.is_empty() {
+                map.remove(key);
+                return;
+            }
+            let mut list = ListValue::new(ttl_secs);
+            for element in elements {
+                list.rpush(element);
+            }
+            map.insert(key.to_string(), list);
+        }
+        self.notify_waiters(key);
+    }
+
+    /// Remove every list, for full syncs. Returns how many there were.
+    pub fn flush(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut map = shard.write();
+                let flushed = map.len();
+                map.clear();
+                flushed
+            })
+            .sum()
+    }
+
     /// Create new list store
     pub fn new() -> Self {
         let mut shards = Vec::with_capacity(SHARD_COUNT);
@@ -135,6 +173,7 @@ impl ListStore {
             mem: None,
             mem_bytes: Arc::new(std::sync::atomic::AtomicI64::new(0)),
             keyspace_notifier: None,
+            key_limits: crate::core::KeyLimits::default(),
         }
     }
 
@@ -155,6 +194,60 @@ impl ListStore {
         self
     }
 
+    /// Attach per-key limits. Only `max_list_length` applies to lists.
+    pub fn with_key_limits(mut self, limits: crate::core::KeyLimits) -> Self {
+        self.key_limits = limits;
+        self
+    }
+
+    /// The `n` longest lists, largest first.
+    pub fn largest_keys(&self, n: usize) -> Vec<crate::core::KeySize> {
+        let mut largest = crate::core::limits::Largest::new(n);
+        for shard in self.shards.iter() {
+            for (key, v) in shard.read().iter() {
+                if !v.is_expired() {
+                    largest.offer(key, v.len());
+                }
+            }
+        }
+        largest.into_vec()
+    }
+
+    /// Encoding and estimated memory of `key`, `None` when it holds no list.
+    pub fn key_memory(&self, key: &str) -> Option<crate::core::KeyMemory> {
+        let map = self.shard(key).read();
+        map.get(key)
+            .filter(|v| !v.is_expired())
+            .map(|v| v.memory(key))
+    }
+
+    /// Estimated memory of every live list, with the `top` largest by bytes.
+    pub fn memory_profile(&self, top: usize) -> crate::core::MemoryProfile {
+        let mut profiler = crate::core::memory_profile::Profiler::new(top);
+        for shard in self.shards.iter() {
+            for (key, v) in shard.read().iter() {
+                if !v.is_expired() {
+                    profiler.offer(key, v.memory(key));
+                }
+            }
+        }
+        profiler.finish()
+    }
+
+    /// Refuse adding `added` elements to `key` past `max_list_length`.
+    fn check_length(
+        &self,
+        map: &HashMap<String, ListValue>,
+        key: &str,
+        added: usize,
+    ) -> Result<()> {
+        let len = map
+            .get(key)
+            .filter(|list| !list.is_expired())
+            .map_or(0, ListValue::len);
+        self.key_limits.check_list(key, len, added)
+    }
+
     /// Publish a list keyspace notification for `key` if a notifier is attached.
     #[inline]
     fn notify_keyspace(&self, event: &str, key: &str) {
@@ -241,6 +334,7 @@ impl ListStore {
         if only_if_exists && !map.contains_key(key) {
             return Ok(0);
         }
+        self.check_length(&map, key, values.len())?;
 
         let list = map
             .entry(key.to_string())
@@ -278,6 +372,7 @@ impl ListStore {
         if only_if_exists && !map.contains_key(key) {
             return Ok(0);
         }
+        self.check_length(&map, key, values.len())?;
 
         let list = map
             .entry(key.to_string())
@@ -517,6 +612,10 @@ impl ListStore {
             return Err(SynapError::KeyExpired);
         }
 
+        if self.key_limits.max_list_length.is_some() && list.lpos(&pivot).is_some() {
+            self.key_limits.check_list(key, list.len(), 1)?;
+        }
+
         self.stats.linsert_count.fetch_add(1, Ordering::Relaxed);
         list.linsert(before, &pivot, value)
     }
@@ -546,6 +645,9 @@ impl ListStore {
         if source_idx == dest_idx {
             let shard = &self.shards[source_idx];
             let mut map = shard.write();
+            if source != destination {
+                self.check_length(&map, destination, 1)?;
+            }
 
             // Pop from source
             let source_list = map.get_mut(source).ok_or(SynapError::NotFound)?;
@@ -603,6 +705,7 @@ impl ListStore {
         } else {
             (&mut *second_map, &mut *first_map)
         };
+        self.check_length(dest_map, destination, 1)?;
 
         // Pop from source
         let source_list = source_map.get_mut(source).ok_or(SynapError::NotFound)?;
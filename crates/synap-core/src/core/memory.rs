@@ -24,8 +24,9 @@ use parking_lot::RwLock;
 #[derive(Clone)]
 pub struct GlobalMemory {
     counters: Arc<RwLock<Vec<Arc<AtomicI64>>>>,
-    /// Hard cap in bytes; `0` means unlimited.
-    max_bytes: i64,
+    /// Hard cap in bytes; `0` means unlimited. Shared by every clone, so
+    /// [`set_max_bytes`](Self::set_max_bytes) applies to all stores at once.
+    max_bytes: Arc<AtomicI64>,
 }
 
 impl GlobalMemory {
@@ -33,7 +34,7 @@ impl GlobalMemory {
     pub fn new(max_bytes: usize) -> Self {
         Self {
             counters: Arc::new(RwLock::new(Vec::new())),
-            max_bytes: max_bytes as i64,
+            max_bytes: Arc::new(AtomicI64::new(max_bytes as i64)),
         }
     }
 
@@ -53,13 +54,20 @@ impl GlobalMemory {
 
     /// Configured cap in bytes (`0` = unlimited).
     pub fn max_bytes(&self) -> i64 {
-        self.max_bytes
+        self.max_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Change the cap (`0` = unlimited). Stores over a lowered cap evict or
+    /// refuse growing writes from their next write on.
+    pub fn set_max_bytes(&self, max_bytes: usize) {
+        self.max_bytes.store(max_bytes as i64, Ordering::Relaxed);
     }
 
     /// True if adding `size` bytes would push the accounted total over the cap.
     /// Always false when the cap is unlimited or `size <= 0`.
     pub fn would_exceed(&self, size: i64) -> bool {
-        self.max_bytes > 0 && size > 0 && self.used() + size > self.max_bytes
+        let max_bytes = self.max_bytes();
+        max_bytes > 0 && size > 0 && self.used() + size > max_bytes
     }
 }
 
@@ -67,7 +75,7 @@ impl std::fmt::Debug for GlobalMemory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("GlobalMemory")
             .field("used", &self.used())
-            .field("max_bytes", &self.max_bytes)
+            .field("max_bytes", &self.max_bytes())
             .finish()
     }
 }
@@ -99,6 +107,19 @@ mod tests {
         assert!(!m.would_exceed(200));
     }
 
+    #[test]
+    fn cap_change_applies_to_every_clone() {
+        let m = GlobalMemory::new(1000);
+        let shared = m.clone();
+        let c = Arc::new(AtomicI64::new(900));
+        m.register(c);
+        assert!(shared.would_exceed(200));
+
+        m.set_max_bytes(2000);
+        assert_eq!(shared.max_bytes(), 2000);
+        assert!(!shared.would_exceed(200));
+    }
+
     #[test]
     fn unlimited_never_exceeds() {
         let m = GlobalMemory::new(0);
@@ -0,0 +1,150 @@
+//! Per-key memory estimates by datatype and encoding.
+//!
+//! Backs MEMORY USAGE and the memory doctor. Sizes are estimates, not
+//! allocator-exact figures: the bytes of a key's items, plus a fixed cost per
+//! key and a per-item cost that depends on the encoding holding the items (a
+//! 4-byte length prefix in a packed buffer, a table or tree slot otherwise).
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::limits::{KeySize, Largest};
+
+/// Bytes charged to every key for its name's allocation and map slot.
+pub const KEY_OVERHEAD_BYTES: usize = 64;
+
+/// Per-item bytes of each encoding on top of the item itself.
+pub(crate) fn item_overhead(encoding: &str) -> usize {
+    match encoding {
+        "packed" => 4,
+        "deque" => 16,
+        "hashset" => 24,
+        "hashtable" => 32,
+        // Score in the member map and again in the sorted index.
+        "skiplist" => 40,
+        _ => 0,
+    }
+}
+
+/// Encoding and estimated size of one key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyMemory {
+    /// `int` or `raw` for strings, `packed` or `deque` for lists, `packed`
+    /// or `hashset` for sets, `hashtable` for hashes, `skiplist` for sorted
+    /// sets.
+    pub encoding: &'static str,
+    /// Items held: 1 for a string, fields, elements or members otherwise.
+    pub elements: usize,
+    /// Bytes of the items alone; hash fields count their name and value.
+    pub item_bytes: usize,
+    /// Estimated total, including key and per-item overhead.
+    pub bytes: usize,
+}
+
+impl KeyMemory {
+    pub(crate) fn new(
+        key: &str,
+        encoding: &'static str,
+        elements: usize,
+        item_bytes: usize,
+    ) -> Self {
+        Self {
+            encoding,
+            elements,
+            item_bytes,
+            bytes: key.len() + KEY_OVERHEAD_BYTES + item_bytes + elements * item_overhead(encoding),
+        }
+    }
+
+    /// Mean bytes per item, 0 for a key holding none.
+    pub fn avg_item_bytes(&self) -> usize {
+        self.item_bytes.checked_div(self.elements).unwrap_or(0)
+    }
+}
+
+/// Estimated memory of every live key of one datatype.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryProfile {
+    pub keys: usize,
+    pub elements: usize,
+    pub item_bytes: usize,
+    pub bytes: usize,
+    /// Keys held in each encoding.
+    pub encodings: BTreeMap<&'static str, usize>,
+    /// The largest keys by estimated bytes, largest first.
+    pub biggest: Vec<KeySize>,
+}
+
+impl MemoryProfile {
+    /// Mean bytes per item across the datatype.
+    pub fn avg_item_bytes(&self) -> usize {
+        self.item_bytes.checked_div(self.elements).unwrap_or(0)
+    }
+
+    /// Estimated bytes per key, overhead included.
+    pub fn bytes_per_key(&self) -> usize {
+        self.bytes.checked_div(self.keys).unwrap_or(0)
+    }
+}
+
+/// Folds the keys of a store into a [`MemoryProfile`], keeping the `top`
+/// largest.
+pub(crate) struct Profiler {
+    profile: MemoryProfile,
+    largest: Largest,
+}
+
+impl Profiler {
+    pub(crate) fn new(top: usize) -> Self {
+        Self {
+            profile: MemoryProfile::default(),
+            largest: Largest::new(top),
+        }
+    }
+
+    pub(crate) fn offer(&mut self, key: &str, memory: KeyMemory) {
+        let profile = &mut self.profile;
+        profile.keys += 1;
+        profile.elements += memory.elements;
+        profile.item_bytes += memory.item_bytes;
+        profile.bytes += memory.bytes;
+        *profile.encodings.entry(memory.encoding).or_default() += 1;
+        self.largest.offer(key, memory.bytes);
+    }
+
+    pub(crate) fn finish(mut self) -> MemoryProfile {
+        self.profile.biggest = self.largest.into_vec();
+        self.profile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_items_cost_less_than_table_slots() {
+        let packed = KeyMemory::new("k", "packed", 10, 100);
+        let table = KeyMemory::new("k", "hashset", 10, 100);
+        assert_eq!(packed.bytes, 1 + KEY_OVERHEAD_BYTES + 100 + 40);
+        assert_eq!(table.bytes, 1 + KEY_OVERHEAD_BYTES + 100 + 240);
+        assert_eq!(packed.avg_item_bytes(), 10);
+    }
+
+    #[test]
+    fn profiler_totals_and_ranks_keys() {
+        let mut profiler = Profiler::new(1);
+        profiler.offer("small", KeyMemory::new("small", "raw", 1, 10));
+        profiler.offer("big", KeyMemory::new("big", "raw", 1, 3000));
+        let profile = profiler.finish();
+
+        assert_eq!(profile.keys, 2);
+        assert_eq!(profile.item_bytes, 3010);
+        assert_eq!(profile.avg_item_bytes(), 1505);
+        assert_eq!(profile.encodings.get("raw"), Some(&2));
+        assert_eq!(profile.biggest.len(), 1);
+        assert_eq!(profile.biggest[0].key, "big");
+        assert_eq!(profile.bytes_per_key(), profile.bytes / 2);
+    }
+}
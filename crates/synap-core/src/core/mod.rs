@@ -1,62 +1,96 @@
 pub mod bitmap;
+pub mod bloom;
 pub mod cache;
 pub mod consumer_group;
 pub mod error;
 pub mod geospatial;
 pub mod glob;
 pub mod hash;
+pub mod hot_keys;
 pub mod hyperloglog;
+mod idempotency;
+pub mod key_dump;
 pub mod key_lock;
 pub mod key_manager;
 pub mod keyspace;
 pub mod kv_store;
+pub mod limits;
 pub mod list;
+pub mod lock;
 pub mod memory;
+pub mod memory_profile;
 pub mod partition;
 pub mod pubsub;
+pub mod pubsub_filter;
 pub mod queue;
+pub mod quota;
+pub mod ratelimit;
+mod scan;
 pub mod set;
 pub mod sorted_set;
 pub mod stream;
+pub mod timeseries;
+pub mod trace;
+pub mod tracking;
 pub mod transaction;
 pub mod types;
 pub mod watch;
 
 pub use bitmap::{
-    BitfieldOperation, BitfieldOverflow, BitmapOperation, BitmapStats, BitmapStore, BitmapValue,
+    BitRangeUnit, BitfieldOperation, BitfieldOverflow, BitmapOperation, BitmapStats, BitmapStore,
+    BitmapValue,
 };
+pub use bloom::{BloomFilter, BloomFilterStats, BloomFilterStore};
 pub use cache::{CacheLayer, CacheStats};
 pub use consumer_group::{
     AssignmentStrategy, ConsumerGroup, ConsumerGroupConfig, ConsumerGroupManager,
-    ConsumerGroupStats, ConsumerMember, GroupState,
+    ConsumerGroupStats, ConsumerMember, GroupState, PartitionLag,
 };
-pub use error::SynapError;
+pub use error::{ErrorCategory, ErrorInfo, SynapError};
 pub use geospatial::{
     Coordinate, DistanceUnit, GeoQueryOptions, GeoSearchParams, GeospatialStats, GeospatialStore,
 };
 pub use glob::glob_match;
 pub use hash::{HashStats, HashStore, HashValue};
+pub use hot_keys::{HotKey, HotKeyOp, HotKeyTracker, HotKeysReport};
 pub use hyperloglog::{HyperLogLogStats, HyperLogLogStore, HyperLogLogValue};
+pub use key_dump::{DumpedValue, KeyDump};
 pub use key_lock::KeyLockManager;
 pub use key_manager::{KeyManager, KeyType};
 pub use keyspace::{EventClass, KeyspaceEventFlags, KeyspaceNotifier};
 pub use kv_store::KVStore;
+pub use limits::{KeyLimits, KeySize};
 pub use list::{ListStats, ListStore, ListValue};
+pub use lock::{LockGrant, LockManager, LockStats};
 pub use memory::GlobalMemory;
+pub use memory_profile::{KeyMemory, MemoryProfile};
 pub use partition::{
     CompactionResult, PartitionConfig, PartitionEvent, PartitionManager, PartitionStats,
     PartitionedTopic, RetentionPolicy,
 };
 pub use pubsub::{
-    Message, MessageSender, PubSubRouter, PubSubStats, PublishResult, SubscribeResult, TopicInfo,
+    FilterStats, Message, MessageSender, PubSubConfig, PubSubRouter, PubSubStats, PublishResult,
+    SubscribeResult, TopicInfo, TopicLimits, TopicRoute,
 };
-pub use queue::{QueueConfig, QueueManager, QueueMessage, QueueStats};
+pub use pubsub_filter::SubscriptionFilter;
+pub use queue::{QueueConfig, QueueManager, QueueMessage, QueuePublish, QueueStats};
+pub use quota::{NamespaceLimit, NamespaceQuotas, NamespaceUsage};
+pub use ratelimit::{RateLimit, RateLimitDecision, RateLimitStats, RateLimitStore};
 pub use set::{SetStats, SetStore, SetValue};
 pub use sorted_set::{
-    Aggregate, OrderedFloat, ScoredMember, SortedSetStats, SortedSetStore, SortedSetValue,
-    ZAddOptions,
+    Aggregate, LeaderboardEvent, LeaderboardNotifier, LeaderboardWindow, MAX_LEADERBOARD_TOP_N,
+    OrderedFloat, ScoredMember, SortedSetStats, SortedSetStore, SortedSetValue, ZAddOptions,
 };
 pub use stream::{RoomStats, StreamConfig, StreamEvent, StreamManager};
+pub use timeseries::{
+    SeriesOptions, TimeSeriesAggregation, TimeSeriesPoint, TimeSeriesResolution, TimeSeriesStats,
+    TimeSeriesStore,
+};
+pub use trace::{MessageTrace, MessageTracer, NackOutcome, TraceEvent, TraceSource, TraceStep};
+pub use tracking::{DEFAULT_TRACKING_MAX_KEYS, Invalidation, TrackingStats, TrackingTable};
 pub use transaction::{CommittedWrite, Transaction, TransactionCommand, TransactionManager};
-pub use types::{EvictionPolicy, Expiry, KVConfig, KVStats, SetOptions, SetResult, StoredValue};
+pub use types::{
+    DefragConfig, DefragReport, EvictionPolicy, Expiry, KVConfig, KVShardStats, KVStats,
+    SetOptions, SetResult, StoredValue, default_shard_count,
+};
 pub use watch::{DEFAULT_INLINE_VALUE_CAP, KeyWatchNotifier, WatchEvent};
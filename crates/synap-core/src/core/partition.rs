@@ -93,6 +93,9 @@ pub struct PartitionEvent {
     pub data: Vec<u8>,
     /// Timestamp
     pub timestamp: u64,
+    /// Timestamp in milliseconds
+    #[serde(default)]
+    pub timestamp_ms: u64,
     /// Size in bytes (for retention calculation)
     pub size_bytes: u64,
     /// Metadata
@@ -106,6 +109,10 @@ impl PartitionEvent {
             + key.as_ref().map(|k| k.len()).unwrap_or(0) as u64
             + topic.len() as u64
             + event_type.len() as u64;
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
 
         Self {
             id: Uuid::new_v4().to_string(),
@@ -115,14 +122,22 @@ impl PartitionEvent {
             event_type,
             key,
             data,
-            timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
+            timestamp: timestamp_ms / 1000,
+            timestamp_ms,
             size_bytes,
             metadata: HashMap::new(),
         }
     }
+
+    /// Creation time in milliseconds; events serialized before `timestamp_ms`
+    /// existed fall back to the second-resolution `timestamp`
+    pub fn published_at_ms(&self) -> u64 {
+        if self.timestamp_ms > 0 {
+            self.timestamp_ms
+        } else {
+            self.timestamp.saturating_mul(1000)
+        }
+    }
 }
 
 /// Single partition within a topic
@@ -174,10 +189,27 @@ impl Partition {
 
     /// Read events from offset
     pub fn read(&self, from_offset: u64, limit: usize) -> Vec<PartitionEvent> {
+        self.read_with_min_age(from_offset, limit, 0)
+    }
+
+    /// Read events from offset, stopping at the first event published less
+    /// than `min_age_ms` ago (`0` reads everything)
+    pub fn read_with_min_age(
+        &self,
+        from_offset: u64,
+        limit: usize,
+        min_age_ms: u64,
+    ) -> Vec<PartitionEvent> {
+        let visible_until = (SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64)
+            .saturating_sub(min_age_ms);
         self.buffer
             .iter()
             .filter(|evt| evt.offset >= from_offset)
             .take(limit)
+            .take_while(|evt| min_age_ms == 0 || evt.published_at_ms() <= visible_until)
             .cloned()
             .collect()
     }
@@ -385,10 +417,22 @@ impl PartitionedTopic {
         partition_id: usize,
         from_offset: u64,
         limit: usize,
+    ) -> Result<Vec<PartitionEvent>, String> {
+        self.consume_partition_with_min_age(partition_id, from_offset, limit, 0)
+    }
+
+    /// Consume from specific partition, holding back events published less
+    /// than `min_age_ms` ago
+    pub fn consume_partition_with_min_age(
+        &self,
+        partition_id: usize,
+        from_offset: u64,
+        limit: usize,
+        min_age_ms: u64,
     ) -> Result<Vec<PartitionEvent>, String> {
         self.partitions
             .get(partition_id)
-            .map(|p| p.read(from_offset, limit))
+            .map(|p| p.read_with_min_age(from_offset, limit, min_age_ms))
             .ok_or_else(|| format!("Partition {} not found", partition_id))
     }
 
@@ -502,13 +546,27 @@ impl PartitionManager {
         partition_id: usize,
         from_offset: u64,
         limit: usize,
+    ) -> Result<Vec<PartitionEvent>, String> {
+        self.consume_partition_with_min_age(topic, partition_id, from_offset, limit, 0)
+            .await
+    }
+
+    /// Consume from specific partition with delayed visibility: events
+    /// published less than `min_age_ms` ago are held back for a later call
+    pub async fn consume_partition_with_min_age(
+        &self,
+        topic: &str,
+        partition_id: usize,
+        from_offset: u64,
+        limit: usize,
+        min_age_ms: u64,
     ) -> Result<Vec<PartitionEvent>, String> {
         let topics = self.topics.read();
 
         topics
             .get(topic)
             .ok_or_else(|| format!("Topic '{}' not found", topic))?
-            .consume_partition(partition_id, from_offset, limit)
+            .consume_partition_with_min_age(partition_id, from_offset, limit, min_age_ms)
     }
 
     /// Consume from all partitions
@@ -594,6 +652,26 @@ impl PartitionManager {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_partition_read_with_min_age() {
+        let mut partition = Partition::new(0, "t".to_string(), PartitionConfig::default());
+        let mut old = PartitionEvent::new("t".to_string(), "e".to_string(), None, vec![0]);
+        old.timestamp_ms -= 10_000;
+        partition.append(old);
+        partition.append(PartitionEvent::new(
+            "t".to_string(),
+            "e".to_string(),
+            None,
+            vec![1],
+        ));
+
+        let visible = partition.read_with_min_age(0, 10, 5_000);
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].offset, 0);
+        assert!(partition.read_with_min_age(1, 10, 5_000).is_empty());
+        assert_eq!(partition.read(0, 10).len(), 2);
+    }
+
     #[tokio::test]
     async fn test_partition_creation() {
         let config = PartitionConfig {
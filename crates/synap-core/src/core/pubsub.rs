@@ -1,15 +1,16 @@
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use radix_trie::{Trie, TrieCommon};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tracing::{debug, warn};
 use uuid::Uuid;
 
 use super::SynapError;
+use super::pubsub_filter::SubscriptionFilter;
 
 /// Unique identifier for a subscriber
 pub type SubscriberId = String;
@@ -25,6 +26,64 @@ pub type MessageSender = mpsc::Sender<Message>;
 /// server memory without bound (audit M-011).
 pub const SUBSCRIBER_CHANNEL_CAPACITY: usize = 1024;
 
+/// Publish limits. Unset limits are unlimited; the first `topics` entry whose
+/// pattern matches a topic overrides the defaults for that topic, field by
+/// field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PubSubConfig {
+    /// Largest message accepted, in bytes (JSON payload plus metadata)
+    #[serde(default)]
+    pub max_message_bytes: Option<usize>,
+    /// Messages per second one publisher may send to one topic
+    #[serde(default)]
+    pub max_messages_per_sec: Option<u32>,
+    #[serde(default)]
+    pub topics: Vec<TopicLimits>,
+    /// Routing rules installed at startup; more can be added at runtime
+    #[serde(default)]
+    pub routes: Vec<TopicRoute>,
+}
+
+/// Publish limits for the topics matching `pattern`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopicLimits {
+    /// Exact topic or wildcard pattern (`sensors.*`, `events.#`)
+    pub pattern: String,
+    #[serde(default)]
+    pub max_message_bytes: Option<usize>,
+    #[serde(default)]
+    pub max_messages_per_sec: Option<u32>,
+}
+
+/// Routing rule: messages published to a topic matching `source` are also
+/// delivered to the subscribers of every `targets` topic. Targets are routed
+/// in turn, so `orders.eu -> orders.all -> audit` delivers to all three.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicRoute {
+    pub name: String,
+    /// Exact topic or wildcard pattern (`orders.*`, `events.#`)
+    pub source: String,
+    /// Exact topics that also receive the message
+    pub targets: Vec<String>,
+    /// Deliver to `targets` only, not to the subscribers of the published
+    /// topic itself (an alias that renames a topic)
+    #[serde(default)]
+    pub redirect: bool,
+}
+
+/// Installed routes, indexed for lookup on every publish
+#[derive(Default)]
+struct RouteTable {
+    by_name: BTreeMap<String, Arc<TopicRoute>>,
+    /// Routes on exact topics
+    exact: Trie<String, Vec<Arc<TopicRoute>>>,
+    /// Routes on wildcard patterns
+    wildcard: Vec<(WildcardMatcher, Arc<TopicRoute>)>,
+}
+
+/// Rate buckets kept before idle ones are pruned
+const RATE_BUCKET_PRUNE_THRESHOLD: usize = 4096;
+
 /// Pub/Sub Router - manages topic-based publish/subscribe messaging
 #[derive(Clone)]
 pub struct PubSubRouter {
@@ -39,6 +98,44 @@ pub struct PubSubRouter {
 
     /// Statistics
     stats: Arc<RwLock<PubSubStats>>,
+
+    /// Publish limits, enforced by [`PubSubRouter::publish_as`]
+    limits: Arc<PublishLimits>,
+
+    /// Content filters of the subscribers that set one
+    filters: Arc<RwLock<HashMap<SubscriberId, Arc<FilterState>>>>,
+
+    /// Topic aliases and fan-out rules
+    routes: Arc<RwLock<RouteTable>>,
+}
+
+/// A subscriber's filter and how many messages it let through or held back
+struct FilterState {
+    filter: SubscriptionFilter,
+    matched: AtomicU64,
+    filtered: AtomicU64,
+}
+
+/// Compiled [`PubSubConfig`] plus the per-publisher rate buckets
+#[derive(Default)]
+struct PublishLimits {
+    max_message_bytes: Option<usize>,
+    max_messages_per_sec: Option<u32>,
+    rules: Vec<(WildcardMatcher, TopicLimits)>,
+    buckets: Mutex<RateBuckets>,
+}
+
+/// Token buckets by topic, then publisher
+#[derive(Default)]
+struct RateBuckets {
+    by_topic: HashMap<String, HashMap<String, RateBucket>>,
+    len: usize,
+}
+
+/// Token bucket holding up to one second's worth of messages
+struct RateBucket {
+    tokens: f64,
+    refilled: Instant,
 }
 
 /// Subscribers for a specific topic
@@ -87,6 +184,26 @@ pub struct PubSubStats {
     /// Subscribers disconnected because their delivery buffer was full.
     #[serde(default)]
     pub slow_consumers_dropped: u64,
+    /// Publishes rejected for exceeding the topic's message size limit.
+    #[serde(default)]
+    pub messages_too_large: u64,
+    /// Publishes rejected for exceeding the topic's publish rate limit.
+    #[serde(default)]
+    pub messages_rate_limited: u64,
+    /// Deliveries skipped because the subscriber's filter did not match.
+    #[serde(default)]
+    pub messages_filtered: u64,
+}
+
+/// Filter counters of one subscriber
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterStats {
+    pub subscriber_id: SubscriberId,
+    pub filter: String,
+    /// Messages on the subscribed topics that passed the filter
+    pub matched: u64,
+    /// Messages on the subscribed topics that the filter held back
+    pub filtered: u64,
 }
 
 /// Published message
@@ -113,6 +230,9 @@ pub struct PublishResult {
     pub message_id: MessageId,
     pub topic: String,
     pub subscribers_matched: usize,
+    /// Other topics the routing rules delivered the message to
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub routed_to: Vec<String>,
 }
 
 impl PubSubRouter {
@@ -129,10 +249,62 @@ impl PubSubRouter {
                 messages_published: 0,
                 messages_delivered: 0,
                 slow_consumers_dropped: 0,
+                messages_too_large: 0,
+                messages_rate_limited: 0,
+                messages_filtered: 0,
             })),
+            limits: Arc::new(PublishLimits::default()),
+            filters: Arc::new(RwLock::new(HashMap::new())),
+            routes: Arc::new(RwLock::new(RouteTable::default())),
         }
     }
 
+    /// Create a router that enforces `config`'s publish limits
+    pub fn with_config(config: PubSubConfig) -> Result<Self, SynapError> {
+        let positive = |bytes: Option<usize>, rate: Option<u32>, scope: &str| {
+            if bytes == Some(0) || rate == Some(0) {
+                return Err(SynapError::InvalidValue(format!(
+                    "pubsub limits for {scope} must be positive; leave them unset for no limit"
+                )));
+            }
+            Ok(())
+        };
+        positive(
+            config.max_message_bytes,
+            config.max_messages_per_sec,
+            "all topics",
+        )?;
+
+        let mut rules = Vec::with_capacity(config.topics.len());
+        for topic in config.topics {
+            if topic.pattern.is_empty() {
+                return Err(SynapError::InvalidValue(
+                    "pubsub topic limit pattern cannot be empty".to_string(),
+                ));
+            }
+            positive(
+                topic.max_message_bytes,
+                topic.max_messages_per_sec,
+                &topic.pattern,
+            )?;
+            rules.push((Self::compile_pattern(&topic.pattern)?, topic));
+        }
+
+        let router = Self {
+            limits: Arc::new(PublishLimits {
+                max_message_bytes: config.max_message_bytes,
+                max_messages_per_sec: config.max_messages_per_sec,
+                rules,
+                buckets: Mutex::new(RateBuckets::default()),
+            }),
+            ..Self::new()
+        };
+        for route in config.routes {
+            router.set_route(route)?;
+        }
+        Ok(router)
+    }
+
     /// Register a WebSocket connection for a subscriber
     pub fn register_connection(&self, subscriber_id: String, sender: MessageSender) {
         let mut connections = self.connections.write();
@@ -155,6 +327,16 @@ impl PubSubRouter {
 
     /// Subscribe to one or more topics (exact or wildcard)
     pub fn subscribe(&self, topics: Vec<String>) -> Result<SubscribeResult, SynapError> {
+        self.subscribe_filtered(topics, None)
+    }
+
+    /// Subscribe to one or more topics, receiving only the messages that
+    /// pass `filter`
+    pub fn subscribe_filtered(
+        &self,
+        topics: Vec<String>,
+        filter: Option<SubscriptionFilter>,
+    ) -> Result<SubscribeResult, SynapError> {
         let subscriber_id = Uuid::new_v4().to_string();
         let mut subscription_count = 0;
 
@@ -205,6 +387,17 @@ impl PubSubRouter {
             }
         }
 
+        if let Some(filter) = filter {
+            self.filters.write().insert(
+                subscriber_id.clone(),
+                Arc::new(FilterState {
+                    filter,
+                    matched: AtomicU64::new(0),
+                    filtered: AtomicU64::new(0),
+                }),
+            );
+        }
+
         // Update stats
         self.update_stats();
 
@@ -261,6 +454,8 @@ impl PubSubRouter {
             let before_len = wildcards.len();
             wildcards.retain(|sub| sub.subscriber_id != subscriber_id);
             unsubscribed += before_len - wildcards.len();
+
+            self.filters.write().remove(subscriber_id);
         }
 
         // Update stats
@@ -273,6 +468,54 @@ impl PubSubRouter {
         Ok(unsubscribed)
     }
 
+    /// Publish a message on behalf of a client, enforcing the topic's size and
+    /// rate limits. `publisher` identifies the client (API key, user,
+    /// connection) for the per-publisher rate limit.
+    ///
+    /// Server-internal publishers (keyspace notifications, KV watch) use
+    /// [`PubSubRouter::publish`], which is not limited.
+    pub fn publish_as(
+        &self,
+        publisher: &str,
+        topic: &str,
+        payload: serde_json::Value,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<PublishResult, SynapError> {
+        let (max_bytes, max_rate) = self.limits.for_topic(topic);
+
+        if let Some(max) = max_bytes {
+            let size = message_size(&payload, metadata.as_ref());
+            if size > max {
+                self.stats.write().messages_too_large += 1;
+                debug!(
+                    "Rejected {} byte message from {} to topic {} (limit {})",
+                    size, publisher, topic, max
+                );
+                return Err(SynapError::MessageTooLarge {
+                    topic: topic.to_string(),
+                    size,
+                    max,
+                });
+            }
+        }
+
+        if let Some(limit) = max_rate
+            && !self.limits.try_acquire(topic, publisher, limit)
+        {
+            self.stats.write().messages_rate_limited += 1;
+            debug!(
+                "Rate limited publisher {} on topic {} ({} msg/s)",
+                publisher, topic, limit
+            );
+            return Err(SynapError::PublishRateLimited {
+                topic: topic.to_string(),
+                limit,
+            });
+        }
+
+        self.publish(topic, payload, metadata)
+    }
+
     /// Publish a message to a topic
     pub fn publish(
         &self,
@@ -296,17 +539,25 @@ impl PubSubRouter {
             timestamp: Self::current_timestamp(),
         };
 
-        // Find all matching subscribers
-        let mut subscribers = self.find_exact_subscribers(topic);
-        subscribers.extend(self.find_wildcard_subscribers(topic));
+        // Find all matching subscribers, of every topic the routes add. A
+        // subscriber reached through several topics gets the message once.
+        let delivery_topics = self.route_topics(topic);
+        let mut subscribers = HashSet::new();
+        for delivery_topic in &delivery_topics {
+            subscribers.extend(self.find_exact_subscribers(delivery_topic));
+            subscribers.extend(self.find_wildcard_subscribers(delivery_topic));
+        }
+        let filtered = self.apply_filters(&message, &mut subscribers);
 
         let subscriber_count = subscribers.len();
 
-        // Update message count for exact topic
+        // Update message count for exact topics
         {
             let topics_map = self.topics.read();
-            if let Some(topic_subs) = topics_map.get(topic) {
-                topic_subs.message_count.fetch_add(1, Ordering::Relaxed);
+            for delivery_topic in &delivery_topics {
+                if let Some(topic_subs) = topics_map.get(delivery_topic) {
+                    topic_subs.message_count.fetch_add(1, Ordering::Relaxed);
+                }
             }
         }
 
@@ -315,6 +566,7 @@ impl PubSubRouter {
             let mut stats = self.stats.write();
             stats.messages_published += 1;
             stats.messages_delivered += subscriber_count as u64;
+            stats.messages_filtered += filtered;
         }
 
         debug!(
@@ -334,6 +586,7 @@ impl PubSubRouter {
             message_id: message.id,
             topic: topic.to_string(),
             subscribers_matched: subscriber_count,
+            routed_to: delivery_topics.into_iter().filter(|t| t != topic).collect(),
         })
     }
 
@@ -345,6 +598,15 @@ impl PubSubRouter {
     /// nothing: the exact-match arm tests the subscriber set in place, and the
     /// wildcard arm stops at the first pattern that matches.
     pub fn has_subscriber(&self, topic: &str) -> bool {
+        if self.routes.read().by_name.is_empty() {
+            return self.topic_has_subscriber(topic);
+        }
+        self.route_topics(topic)
+            .iter()
+            .any(|t| self.topic_has_subscriber(t))
+    }
+
+    fn topic_has_subscriber(&self, topic: &str) -> bool {
         {
             let topics_map = self.topics.read();
             if topics_map
@@ -362,6 +624,91 @@ impl PubSubRouter {
             .any(|sub| sub.compiled_pattern.matches(&topic_segments))
     }
 
+    /// Add a routing rule, replacing the one with the same name
+    pub fn set_route(&self, route: TopicRoute) -> Result<(), SynapError> {
+        if route.name.is_empty() || route.source.is_empty() {
+            return Err(SynapError::InvalidValue(
+                "Route name and source cannot be empty".to_string(),
+            ));
+        }
+        if route.targets.is_empty() {
+            return Err(SynapError::InvalidValue(format!(
+                "Route '{}' needs at least one target topic",
+                route.name
+            )));
+        }
+        if let Some(target) = route
+            .targets
+            .iter()
+            .find(|t| t.is_empty() || Self::is_wildcard_pattern(t))
+        {
+            return Err(SynapError::InvalidValue(format!(
+                "Route target '{}' must be a non-empty topic without wildcards",
+                target
+            )));
+        }
+        if Self::is_wildcard_pattern(&route.source) {
+            Self::compile_pattern(&route.source)?;
+        }
+
+        debug!(
+            "Route {}: {} -> {:?} (redirect: {})",
+            route.name, route.source, route.targets, route.redirect
+        );
+        let mut routes = self.routes.write();
+        routes.by_name.insert(route.name.clone(), Arc::new(route));
+        routes.reindex();
+        Ok(())
+    }
+
+    /// Remove a routing rule. Returns whether it existed.
+    pub fn delete_route(&self, name: &str) -> bool {
+        let mut routes = self.routes.write();
+        let existed = routes.by_name.remove(name).is_some();
+        if existed {
+            routes.reindex();
+        }
+        existed
+    }
+
+    /// Every routing rule, by name
+    pub fn list_routes(&self) -> Vec<TopicRoute> {
+        self.routes
+            .read()
+            .by_name
+            .values()
+            .map(|route| TopicRoute::clone(route))
+            .collect()
+    }
+
+    /// Topics a message published to `topic` is delivered to: `topic` itself
+    /// unless a redirect matched it, plus every route target, followed
+    /// transitively. Each topic is visited once, so routing cycles end.
+    pub fn route_topics(&self, topic: &str) -> Vec<String> {
+        let routes = self.routes.read();
+        if routes.by_name.is_empty() {
+            return vec![topic.to_string()];
+        }
+
+        let mut delivery = Vec::new();
+        let mut seen = HashSet::from([topic.to_string()]);
+        let mut pending = VecDeque::from([topic.to_string()]);
+        while let Some(current) = pending.pop_front() {
+            let matched = routes.matching(&current);
+            if !matched.iter().any(|route| route.redirect) {
+                delivery.push(current);
+            }
+            for route in matched {
+                for target in &route.targets {
+                    if seen.insert(target.clone()) {
+                        pending.push_back(target.clone());
+                    }
+                }
+            }
+        }
+        delivery
+    }
+
     /// Get statistics
     pub fn get_stats(&self) -> PubSubStats {
         self.stats.read().clone()
@@ -464,6 +811,51 @@ impl PubSubRouter {
         stats.total_wildcard_subscriptions = wildcards.len();
     }
 
+    /// Filter counters of every subscriber that set a filter, by subscriber id
+    pub fn filter_stats(&self) -> Vec<FilterStats> {
+        let mut stats: Vec<FilterStats> = self
+            .filters
+            .read()
+            .iter()
+            .map(|(subscriber_id, state)| FilterStats {
+                subscriber_id: subscriber_id.clone(),
+                filter: state.filter.expression().to_string(),
+                matched: state.matched.load(Ordering::Relaxed),
+                filtered: state.filtered.load(Ordering::Relaxed),
+            })
+            .collect();
+        stats.sort_by(|a, b| a.subscriber_id.cmp(&b.subscriber_id));
+        stats
+    }
+
+    /// Drop the subscribers whose filter rejects `message`, counting both
+    /// outcomes per filter. Returns how many were dropped.
+    fn apply_filters(&self, message: &Message, subscribers: &mut HashSet<SubscriberId>) -> u64 {
+        let filters = self.filters.read();
+        if filters.is_empty() {
+            return 0;
+        }
+
+        let mut filtered = 0;
+        subscribers.retain(|sub_id| {
+            let Some(state) = filters.get(sub_id) else {
+                return true;
+            };
+            if state
+                .filter
+                .matches(&message.payload, message.metadata.as_ref())
+            {
+                state.matched.fetch_add(1, Ordering::Relaxed);
+                true
+            } else {
+                state.filtered.fetch_add(1, Ordering::Relaxed);
+                filtered += 1;
+                false
+            }
+        });
+        filtered
+    }
+
     /// Get current Unix timestamp in seconds
     fn current_timestamp() -> u64 {
         SystemTime::now()
@@ -577,6 +969,141 @@ fn glob_segment_matches(pieces: &[String], segment: &str) -> bool {
     remaining.ends_with(last.as_str())
 }
 
+impl RouteTable {
+    /// Rebuild the lookup indexes from `by_name`
+    fn reindex(&mut self) {
+        self.exact = Trie::new();
+        self.wildcard.clear();
+        for route in self.by_name.values() {
+            if PubSubRouter::is_wildcard_pattern(&route.source) {
+                let matcher = PubSubRouter::compile_pattern(&route.source)
+                    .expect("route patterns are validated by set_route");
+                self.wildcard.push((matcher, route.clone()));
+            } else if let Some(routes) = self.exact.get_mut(&route.source) {
+                routes.push(route.clone());
+            } else {
+                self.exact.insert(route.source.clone(), vec![route.clone()]);
+            }
+        }
+    }
+
+    /// Routes whose source matches `topic`
+    fn matching(&self, topic: &str) -> Vec<Arc<TopicRoute>> {
+        let mut matched = self.exact.get(topic).cloned().unwrap_or_default();
+        if !self.wildcard.is_empty() {
+            let segments: Vec<&str> = topic.split('.').collect();
+            matched.extend(
+                self.wildcard
+                    .iter()
+                    .filter(|(matcher, _)| matcher.matches(&segments))
+                    .map(|(_, route)| route.clone()),
+            );
+        }
+        matched
+    }
+}
+
+impl PublishLimits {
+    /// Size and rate limit for `topic`
+    fn for_topic(&self, topic: &str) -> (Option<usize>, Option<u32>) {
+        let defaults = (self.max_message_bytes, self.max_messages_per_sec);
+        if self.rules.is_empty() {
+            return defaults;
+        }
+
+        let segments: Vec<&str> = topic.split('.').collect();
+        match self.rules.iter().find(|(m, _)| m.matches(&segments)) {
+            Some((_, rule)) => (
+                rule.max_message_bytes.or(defaults.0),
+                rule.max_messages_per_sec.or(defaults.1),
+            ),
+            None => defaults,
+        }
+    }
+
+    /// Take one token from `publisher`'s bucket for `topic`
+    fn try_acquire(&self, topic: &str, publisher: &str, limit: u32) -> bool {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock();
+
+        if let Some(bucket) = buckets
+            .by_topic
+            .get_mut(topic)
+            .and_then(|publishers| publishers.get_mut(publisher))
+        {
+            return bucket.try_take(limit, now);
+        }
+
+        if buckets.len >= RATE_BUCKET_PRUNE_THRESHOLD {
+            buckets.prune(now);
+        }
+        let mut bucket = RateBucket {
+            tokens: f64::from(limit),
+            refilled: now,
+        };
+        let allowed = bucket.try_take(limit, now);
+        buckets
+            .by_topic
+            .entry(topic.to_string())
+            .or_default()
+            .insert(publisher.to_string(), bucket);
+        buckets.len += 1;
+        allowed
+    }
+}
+
+impl RateBuckets {
+    /// Drop buckets that have refilled completely. A full bucket behaves
+    /// exactly like a missing one, so this never changes a decision.
+    fn prune(&mut self, now: Instant) {
+        let mut len = 0;
+        self.by_topic.retain(|_, publishers| {
+            publishers
+                .retain(|_, bucket| now.duration_since(bucket.refilled) < Duration::from_secs(1));
+            len += publishers.len();
+            !publishers.is_empty()
+        });
+        self.len = len;
+    }
+}
+
+impl RateBucket {
+    fn try_take(&mut self, limit: u32, now: Instant) -> bool {
+        let limit = f64::from(limit);
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit).min(limit);
+        self.refilled = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Size of a message as counted by the size limit: the JSON-encoded payload
+/// plus metadata keys and values
+fn message_size(payload: &serde_json::Value, metadata: Option<&HashMap<String, String>>) -> usize {
+    struct Counter(usize);
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    let _ = serde_json::to_writer(&mut counter, payload);
+    counter.0
+        + metadata.map_or(0, |m| {
+            m.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>()
+        })
+}
+
 /// Topic information
 #[derive(Debug, Serialize)]
 pub struct TopicInfo {
@@ -814,4 +1341,254 @@ mod tests {
         let msg = rx.try_recv().expect("the glob subscriber gets the message");
         assert_eq!(msg.topic, "__watch@0__:user:1");
     }
+
+    #[tokio::test]
+    async fn filtered_subscriber_only_receives_matching_messages() {
+        let router = PubSubRouter::new();
+        let filter = SubscriptionFilter::parse(r#"$.severity == "high""#).unwrap();
+        let filtered = router
+            .subscribe_filtered(vec!["alerts.*".to_string()], Some(filter))
+            .unwrap();
+        let plain = router.subscribe(vec!["alerts.*".to_string()]).unwrap();
+        let (tx, mut rx) = mpsc::channel::<Message>(8);
+        router.register_connection(filtered.subscriber_id.clone(), tx);
+
+        let low = router
+            .publish("alerts.disk", serde_json::json!({"severity": "low"}), None)
+            .unwrap();
+        assert_eq!(low.subscribers_matched, 1);
+        let high = router
+            .publish("alerts.disk", serde_json::json!({"severity": "high"}), None)
+            .unwrap();
+        assert_eq!(high.subscribers_matched, 2);
+
+        assert_eq!(rx.try_recv().unwrap().payload["severity"], "high");
+        assert!(rx.try_recv().is_err());
+
+        let stats = router.filter_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].subscriber_id, filtered.subscriber_id);
+        assert_eq!(stats[0].filter, r#"$.severity == "high""#);
+        assert_eq!((stats[0].matched, stats[0].filtered), (1, 1));
+        assert_eq!(router.get_stats().messages_filtered, 1);
+
+        router.unsubscribe(&plain.subscriber_id, None).unwrap();
+        router.unsubscribe(&filtered.subscriber_id, None).unwrap();
+        assert!(router.filter_stats().is_empty());
+    }
+
+    fn limited_router() -> PubSubRouter {
+        PubSubRouter::with_config(PubSubConfig {
+            max_message_bytes: Some(64),
+            max_messages_per_sec: None,
+            topics: vec![
+                TopicLimits {
+                    pattern: "sensors.#".to_string(),
+                    max_message_bytes: None,
+                    max_messages_per_sec: Some(2),
+                },
+                TopicLimits {
+                    pattern: "bulk".to_string(),
+                    max_message_bytes: Some(4096),
+                    max_messages_per_sec: None,
+                },
+            ],
+            routes: Vec::new(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_message_size_limit_per_topic() {
+        let router = limited_router();
+        let big = serde_json::json!({ "data": "x".repeat(100) });
+
+        let err = router
+            .publish_as("client", "chat", big.clone(), None)
+            .unwrap_err();
+        assert!(matches!(err, SynapError::MessageTooLarge { max: 64, .. }));
+        // `sensors.#` only sets a rate, so the default size limit still applies
+        assert!(matches!(
+            router.publish_as("client", "sensors.temp", big.clone(), None),
+            Err(SynapError::MessageTooLarge { .. })
+        ));
+        assert!(
+            router
+                .publish_as("client", "bulk", big.clone(), None)
+                .is_ok()
+        );
+        // Server-internal publishes are not limited
+        assert!(router.publish("chat", big, None).is_ok());
+
+        assert_eq!(router.get_stats().messages_too_large, 2);
+    }
+
+    #[test]
+    fn test_publish_rate_limit_per_publisher() {
+        let router = limited_router();
+        let msg = || serde_json::json!({ "v": 1 });
+
+        assert!(router.publish_as("a", "sensors.temp", msg(), None).is_ok());
+        assert!(router.publish_as("a", "sensors.temp", msg(), None).is_ok());
+        let err = router
+            .publish_as("a", "sensors.temp", msg(), None)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SynapError::PublishRateLimited { limit: 2, .. }
+        ));
+
+        // Other publishers and other topics have their own budget
+        assert!(router.publish_as("b", "sensors.temp", msg(), None).is_ok());
+        assert!(
+            router
+                .publish_as("a", "sensors.humidity", msg(), None)
+                .is_ok()
+        );
+        for _ in 0..10 {
+            assert!(router.publish_as("a", "chat", msg(), None).is_ok());
+        }
+        assert_eq!(router.get_stats().messages_rate_limited, 1);
+    }
+
+    #[test]
+    fn test_rate_bucket_refills() {
+        let start = Instant::now();
+        let mut bucket = RateBucket {
+            tokens: 1.0,
+            refilled: start,
+        };
+        assert!(bucket.try_take(2, start));
+        assert!(!bucket.try_take(2, start));
+        assert!(bucket.try_take(2, start + Duration::from_millis(500)));
+        // Never holds more than one second's worth
+        let later = start + Duration::from_secs(60);
+        assert!(bucket.try_take(2, later));
+        assert!(bucket.try_take(2, later));
+        assert!(!bucket.try_take(2, later));
+    }
+
+    fn route(name: &str, source: &str, targets: &[&str], redirect: bool) -> TopicRoute {
+        TopicRoute {
+            name: name.to_string(),
+            source: source.to_string(),
+            targets: targets.iter().map(|t| t.to_string()).collect(),
+            redirect,
+        }
+    }
+
+    fn connect(router: &PubSubRouter, topic: &str) -> mpsc::Receiver<Message> {
+        let sub = router.subscribe(vec![topic.to_string()]).unwrap();
+        let (tx, rx) = mpsc::channel(16);
+        router.register_connection(sub.subscriber_id, tx);
+        rx
+    }
+
+    #[test]
+    fn test_routes_fan_out_and_redirect() {
+        let router = PubSubRouter::new();
+        router
+            .set_route(route("all", "orders.*", &["orders.all"], false))
+            .unwrap();
+        router
+            .set_route(route("audit", "orders.all", &["audit"], false))
+            .unwrap();
+        router
+            .set_route(route("rename", "legacy_orders", &["orders.us"], true))
+            .unwrap();
+        let mut eu = connect(&router, "orders.eu");
+        let mut all = connect(&router, "orders.all");
+        let mut audit = connect(&router, "audit");
+        let mut legacy = connect(&router, "legacy_orders");
+        // Subscribed through two routed topics, delivered once
+        let mut both = connect(&router, "orders.#");
+
+        let result = router
+            .publish("orders.eu", serde_json::json!({"id": 1}), None)
+            .unwrap();
+        assert_eq!(result.routed_to, ["orders.all", "audit"]);
+        assert_eq!(result.subscribers_matched, 4);
+        for rx in [&mut eu, &mut all, &mut audit, &mut both] {
+            assert_eq!(rx.try_recv().unwrap().topic, "orders.eu");
+        }
+        assert!(both.try_recv().is_err());
+
+        // A redirect skips the source topic's own subscribers
+        let result = router
+            .publish("legacy_orders", serde_json::json!({"id": 2}), None)
+            .unwrap();
+        assert_eq!(result.routed_to, ["orders.us", "orders.all", "audit"]);
+        assert!(legacy.try_recv().is_err());
+        assert!(all.try_recv().is_ok());
+        assert!(router.has_subscriber("legacy_orders"));
+
+        assert!(router.delete_route("all"));
+        assert!(!router.delete_route("all"));
+        let result = router
+            .publish("orders.eu", serde_json::json!({"id": 3}), None)
+            .unwrap();
+        assert!(result.routed_to.is_empty());
+        assert_eq!(
+            router
+                .list_routes()
+                .iter()
+                .map(|r| r.name.as_str())
+                .collect::<Vec<_>>(),
+            ["audit", "rename"]
+        );
+    }
+
+    #[test]
+    fn test_route_cycles_and_validation() {
+        let router = PubSubRouter::new();
+        router.set_route(route("ab", "a", &["b"], false)).unwrap();
+        router.set_route(route("ba", "b", &["a"], false)).unwrap();
+        assert_eq!(router.route_topics("a"), ["a", "b"]);
+
+        assert!(router.set_route(route("none", "a", &[], false)).is_err());
+        assert!(
+            router
+                .set_route(route("wild", "a", &["b.*"], false))
+                .is_err()
+        );
+        assert!(
+            router
+                .set_route(route("bad", "#.x", &["b"], false))
+                .is_err()
+        );
+        assert!(router.set_route(route("", "a", &["b"], false)).is_err());
+
+        // Same name replaces the rule
+        router.set_route(route("ab", "a", &["c"], false)).unwrap();
+        assert_eq!(router.route_topics("a"), ["a", "c"]);
+
+        let config = PubSubConfig {
+            routes: vec![route("eu", "orders.eu", &["orders.all"], false)],
+            ..Default::default()
+        };
+        let router = PubSubRouter::with_config(config).unwrap();
+        assert_eq!(
+            router.route_topics("orders.eu"),
+            ["orders.eu", "orders.all"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_limits_rejected() {
+        let zero = PubSubConfig {
+            max_messages_per_sec: Some(0),
+            ..Default::default()
+        };
+        assert!(PubSubRouter::with_config(zero).is_err());
+
+        let bad_pattern = PubSubConfig {
+            topics: vec![TopicLimits {
+                pattern: "#.events".to_string(),
+                max_message_bytes: Some(10),
+                max_messages_per_sec: None,
+            }],
+            ..Default::default()
+        };
+        assert!(PubSubRouter::with_config(bad_pattern).is_err());
+    }
 }
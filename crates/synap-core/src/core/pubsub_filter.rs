@@ -0,0 +1,279 @@
+//! Subscription filters: content-based matching evaluated server-side so a
+//! subscriber only receives the messages it wants.
+//!
+//! A filter is one or more conditions joined by `&&`:
+//!
+//! - `$.path.to[0].field == <json>` compares a value in the payload with a JSON
+//!   literal (`"text"`, `42`, `true`, `null`, ...),
+//! - `header.<name> == "text"` compares a metadata entry with a string.
+//!
+//! `!=` negates a condition. A path that is missing from the message never
+//! equals anything, so `!=` matches it. Numbers compare by value (`1 == 1.0`).
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::SynapError;
+
+/// Longest filter expression accepted, in bytes
+pub const MAX_FILTER_LEN: usize = 1024;
+
+/// Most conditions one filter may combine
+pub const MAX_FILTER_CONDITIONS: usize = 16;
+
+/// A parsed subscription filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriptionFilter {
+    expression: String,
+    conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    target: Target,
+    negated: bool,
+    value: Value,
+}
+
+/// What a condition reads from the message
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Payload(Vec<PathSegment>),
+    Header(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+fn invalid(reason: impl std::fmt::Display) -> SynapError {
+    SynapError::InvalidValue(format!("invalid subscription filter: {reason}"))
+}
+
+impl SubscriptionFilter {
+    /// Parse a filter expression
+    pub fn parse(expression: &str) -> Result<Self, SynapError> {
+        if expression.len() > MAX_FILTER_LEN {
+            return Err(invalid(format!("longer than {MAX_FILTER_LEN} bytes")));
+        }
+
+        let mut conditions = Vec::new();
+        let mut rest = expression.trim();
+        if rest.is_empty() {
+            return Err(invalid("empty expression"));
+        }
+        loop {
+            if conditions.len() == MAX_FILTER_CONDITIONS {
+                return Err(invalid(format!(
+                    "more than {MAX_FILTER_CONDITIONS} conditions"
+                )));
+            }
+            let (condition, remaining) = parse_condition(rest)?;
+            conditions.push(condition);
+
+            rest = remaining.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            rest = rest
+                .strip_prefix("&&")
+                .ok_or_else(|| invalid(format!("expected '&&' before '{rest}'")))?
+                .trim_start();
+        }
+
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            conditions,
+        })
+    }
+
+    /// The expression this filter was parsed from
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Whether a message with this payload and metadata passes the filter
+    pub fn matches(&self, payload: &Value, metadata: Option<&HashMap<String, String>>) -> bool {
+        self.conditions.iter().all(|condition| {
+            let equal = match &condition.target {
+                Target::Payload(path) => {
+                    resolve(payload, path).is_some_and(|found| json_eq(found, &condition.value))
+                }
+                Target::Header(name) => metadata
+                    .and_then(|m| m.get(name))
+                    .is_some_and(|found| condition.value.as_str() == Some(found.as_str())),
+            };
+            equal != condition.negated
+        })
+    }
+}
+
+/// Parse one `<target> <op> <literal>` condition, returning the unparsed rest
+fn parse_condition(input: &str) -> Result<(Condition, &str), SynapError> {
+    let op_at = input
+        .find("==")
+        .into_iter()
+        .chain(input.find("!="))
+        .min()
+        .ok_or_else(|| invalid(format!("expected '==' or '!=' in '{input}'")))?;
+    let target = parse_target(input[..op_at].trim())?;
+    let negated = input[op_at..].starts_with("!=");
+    let literal = input[op_at + 2..].trim_start();
+
+    let mut values = serde_json::Deserializer::from_str(literal).into_iter::<Value>();
+    let value = match values.next() {
+        Some(Ok(value)) => value,
+        _ => {
+            return Err(invalid(format!(
+                "expected a JSON value after the operator in '{input}'"
+            )));
+        }
+    };
+    if matches!(target, Target::Header(_)) && !value.is_string() {
+        return Err(invalid("header conditions compare against a string"));
+    }
+
+    let condition = Condition {
+        target,
+        negated,
+        value,
+    };
+    Ok((condition, &literal[values.byte_offset()..]))
+}
+
+fn parse_target(target: &str) -> Result<Target, SynapError> {
+    if let Some(name) = target.strip_prefix("header.") {
+        if name.is_empty() {
+            return Err(invalid("missing header name"));
+        }
+        return Ok(Target::Header(name.to_string()));
+    }
+
+    let Some(mut path) = target.strip_prefix('$') else {
+        return Err(invalid(format!(
+            "'{target}' is neither a '$.' path nor a 'header.' name"
+        )));
+    };
+    let mut segments = Vec::new();
+    while !path.is_empty() {
+        if let Some(rest) = path.strip_prefix('.') {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(invalid(format!("empty field name in '{target}'")));
+            }
+            segments.push(PathSegment::Field(rest[..end].to_string()));
+            path = &rest[end..];
+        } else if let Some(rest) = path.strip_prefix('[') {
+            let (index, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| invalid(format!("unclosed '[' in '{target}'")))?;
+            let index = index
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("bad array index '{index}' in '{target}'")))?;
+            segments.push(PathSegment::Index(index));
+            path = rest;
+        } else {
+            return Err(invalid(format!("unexpected '{path}' in '{target}'")));
+        }
+    }
+    Ok(Target::Payload(segments))
+}
+
+fn resolve<'a>(value: &'a Value, path: &[PathSegment]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(value, |current, segment| match segment {
+            PathSegment::Field(name) => current.get(name),
+            PathSegment::Index(index) => current.get(index),
+        })
+}
+
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn payload_path_equality() {
+        let filter = SubscriptionFilter::parse(r#"$.order.items[1].sku == "B-2""#).unwrap();
+        let order = json!({"order": {"items": [{"sku": "A-1"}, {"sku": "B-2"}]}});
+        assert!(filter.matches(&order, None));
+        assert!(!filter.matches(&json!({"order": {"items": []}}), None));
+        assert!(!filter.matches(&json!("B-2"), None));
+    }
+
+    #[test]
+    fn numbers_compare_by_value() {
+        let filter = SubscriptionFilter::parse("$.level == 3").unwrap();
+        assert!(filter.matches(&json!({"level": 3.0}), None));
+        assert!(!filter.matches(&json!({"level": "3"}), None));
+    }
+
+    #[test]
+    fn header_match_and_conjunction() {
+        let filter =
+            SubscriptionFilter::parse(r#"header.region == "eu" && $.status != "draft""#).unwrap();
+        let eu = headers(&[("region", "eu")]);
+        assert!(filter.matches(&json!({"status": "live"}), Some(&eu)));
+        assert!(filter.matches(&json!({}), Some(&eu)));
+        assert!(!filter.matches(&json!({"status": "draft"}), Some(&eu)));
+        assert!(!filter.matches(&json!({"status": "live"}), None));
+        assert!(!filter.matches(
+            &json!({"status": "live"}),
+            Some(&headers(&[("region", "us")]))
+        ));
+    }
+
+    #[test]
+    fn literals_may_contain_operators() {
+        let filter = SubscriptionFilter::parse(r#"$.note == "a && b == c" && $.n == 1"#).unwrap();
+        assert!(filter.matches(&json!({"note": "a && b == c", "n": 1}), None));
+        assert_eq!(
+            filter.expression(),
+            r#"$.note == "a && b == c" && $.n == 1"#
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_rejected() {
+        for expression in [
+            "",
+            "$.a",
+            "a == 1",
+            "$.a == ",
+            "$.a == nope",
+            "$.a == 1 $.b == 2",
+            "$.a[x] == 1",
+            "$..a == 1",
+            "header. == \"x\"",
+            "header.region == 1",
+        ] {
+            let err = SubscriptionFilter::parse(expression).unwrap_err();
+            assert!(
+                matches!(err, SynapError::InvalidValue(_)),
+                "{expression}: {err}"
+            );
+        }
+
+        let too_many = vec!["$.a == 1"; MAX_FILTER_CONDITIONS + 1].join(" && ");
+        assert!(SubscriptionFilter::parse(&too_many).is_err());
+        let too_long = format!("$.a == \"{}\"", "x".repeat(MAX_FILTER_LEN));
+        assert!(SubscriptionFilter::parse(&too_long).is_err());
+    }
+}
@@ -3,22 +3,27 @@
 //! Split out of the former monolithic `queue.rs` (phase2 modularization).
 //! `QueueMessage`, `QueueConfig`, `QueueStats` and the per-queue `Queue`
 //! live in the parent module; this file holds the manager-level API.
-use super::{MessageId, Queue, QueueConfig, QueueMessage, QueueStats};
+use super::{MessageId, Queue, QueueConfig, QueueMessage, QueuePublish, QueueStats};
 use crate::core::error::{Result, SynapError};
+use crate::core::trace::{ENQUEUED_AT_HEADER, MessageTracer, TraceStep};
+use bytes::Bytes;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info};
 
 /// Queue manager (manages multiple queues)
 #[derive(Clone)]
 pub struct QueueManager {
     queues: Arc<RwLock<HashMap<String, Queue>>>,
-    default_config: QueueConfig,
+    /// Config of queues created without one; changeable at runtime
+    default_config: Arc<RwLock<QueueConfig>>,
     /// Registered contribution to the shared cross-datatype budget (audit M-018).
     mem_bytes: Arc<std::sync::atomic::AtomicI64>,
     mem_attached: bool,
+    /// Delivery timelines, when `message_trace` is enabled
+    tracer: Option<Arc<MessageTracer>>,
 }
 
 impl QueueManager {
@@ -27,9 +32,10 @@ impl QueueManager {
         info!("Initializing Queue Manager");
         Self {
             queues: Arc::new(RwLock::new(HashMap::new())),
-            default_config: config,
+            default_config: Arc::new(RwLock::new(config)),
             mem_bytes: Arc::new(std::sync::atomic::AtomicI64::new(0)),
             mem_attached: false,
+            tracer: None,
         }
     }
 
@@ -42,6 +48,32 @@ impl QueueManager {
         self
     }
 
+    /// Record message timelines in `tracer`, including for queues already
+    /// restored from persistence.
+    pub fn with_tracer(mut self, tracer: Option<Arc<MessageTracer>>) -> Self {
+        for queue in self.queues.write().values_mut() {
+            queue.tracer = tracer.clone();
+        }
+        self.tracer = tracer;
+        self
+    }
+
+    /// Message timelines, or `None` when tracing is disabled.
+    pub fn tracer(&self) -> Option<&Arc<MessageTracer>> {
+        self.tracer.as_ref()
+    }
+
+    /// Config given to queues created without one
+    pub fn default_config(&self) -> QueueConfig {
+        self.default_config.read().clone()
+    }
+
+    /// Change the config of queues created from now on; existing queues keep
+    /// theirs
+    pub fn set_default_config(&self, config: QueueConfig) {
+        *self.default_config.write() = config;
+    }
+
     /// Total queued + dead-letter message-payload bytes across all queues.
     pub fn memory_bytes(&self) -> usize {
         let mut total = 0usize;
@@ -90,11 +122,13 @@ impl QueueManager {
         debug!("Creating queue: {}", name);
 
         let mut queues = self.queues.write();
-        let queue_config = config.unwrap_or_else(|| self.default_config.clone());
+        let queue_config = config.unwrap_or_else(|| self.default_config());
 
-        queues
-            .entry(name.to_string())
-            .or_insert_with(|| Queue::new(name.to_string(), queue_config));
+        queues.entry(name.to_string()).or_insert_with(|| {
+            let mut queue = Queue::new(name.to_string(), queue_config);
+            queue.tracer = self.tracer.clone();
+            queue
+        });
 
         Ok(())
     }
@@ -103,7 +137,7 @@ impl QueueManager {
     pub async fn publish(
         &self,
         queue_name: &str,
-        payload: Vec<u8>,
+        payload: impl Into<Bytes>,
         priority: Option<u8>,
         max_retries: Option<u32>,
     ) -> Result<MessageId> {
@@ -118,7 +152,7 @@ impl QueueManager {
     pub async fn publish_with_message(
         &self,
         queue_name: &str,
-        payload: Vec<u8>,
+        payload: impl Into<Bytes>,
         priority: Option<u8>,
         max_retries: Option<u32>,
     ) -> Result<QueueMessage> {
@@ -129,17 +163,53 @@ impl QueueManager {
             .get_mut(queue_name)
             .ok_or_else(|| SynapError::QueueNotFound(queue_name.to_string()))?;
 
-        let priority = priority.unwrap_or(queue.config.default_priority);
-        // If max_retries is not specified (None), use default. If specified (even as 0), use it.
-        let max_retries = max_retries.unwrap_or(queue.config.default_max_retries);
+        publish_to(queue, payload, priority, max_retries)
+    }
 
-        let message = QueueMessage::new(payload, priority, max_retries);
-        let message_id = queue.publish(message.clone())?;
+    /// Publish message to queue unless `idempotency_key` was already published
+    /// to it within the queue's `dedup_window_secs`. A repeat enqueues nothing
+    /// and reports the id of the message the key first published, so a
+    /// producer can retry a publish whose outcome it never saw.
+    pub async fn publish_idempotent(
+        &self,
+        queue_name: &str,
+        payload: impl Into<Bytes>,
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+        idempotency_key: &str,
+    ) -> Result<QueuePublish> {
+        debug!("Publishing to queue: {} (idempotent)", queue_name);
 
-        // Verify message ID matches (should always be true)
-        debug_assert_eq!(message.id, message_id);
+        let mut queues = self.queues.write();
+        let queue = queues
+            .get_mut(queue_name)
+            .ok_or_else(|| SynapError::QueueNotFound(queue_name.to_string()))?;
+
+        if let Some(message_id) = queue.idempotency.get(idempotency_key) {
+            debug!(
+                "Duplicate publish to queue {} (key {})",
+                queue_name, idempotency_key
+            );
+            return Ok(QueuePublish::Duplicate(message_id));
+        }
 
-        Ok(message)
+        let message = publish_to(queue, payload, priority, max_retries)?;
+        queue
+            .idempotency
+            .insert(idempotency_key.to_string(), message.id.clone());
+        Ok(QueuePublish::Published(message))
+    }
+
+    /// Remaining publish capacity of a queue (`max_depth` minus current depth).
+    ///
+    /// Lets the transaction manager reject an EXEC up front, before any of its
+    /// commands are applied, when a queued publish would overflow the queue.
+    pub fn available_capacity(&self, queue_name: &str) -> Result<usize> {
+        let queues = self.queues.read();
+        let queue = queues
+            .get(queue_name)
+            .ok_or_else(|| SynapError::QueueNotFound(queue_name.to_string()))?;
+        Ok(queue.config.max_depth.saturating_sub(queue.messages.len()))
     }
 
     /// Consume message from queue
@@ -190,6 +260,28 @@ impl QueueManager {
         queue.nack(message_id, requeue)
     }
 
+    /// Take the oldest message off a queue's dead letter list, or `None`
+    /// when it is empty or the queue does not exist
+    pub async fn pop_dead_letter(&self, queue_name: &str) -> Result<Option<QueueMessage>> {
+        let mut queues = self.queues.write();
+        Ok(queues
+            .get_mut(queue_name)
+            .and_then(|queue| queue.dead_letter.pop_front())
+            .map(Arc::unwrap_or_clone))
+    }
+
+    /// Put back a message taken with [`pop_dead_letter`](Self::pop_dead_letter)
+    /// so it is the next one taken
+    pub async fn return_dead_letter(&self, queue_name: &str, message: QueueMessage) -> Result<()> {
+        let mut queues = self.queues.write();
+        let queue = queues
+            .get_mut(queue_name)
+            .ok_or_else(|| SynapError::QueueNotFound(queue_name.to_string()))?;
+
+        queue.dead_letter.push_front(Arc::new(message));
+        Ok(())
+    }
+
     /// Get queue statistics
     pub async fn stats(&self, queue_name: &str) -> Result<QueueStats> {
         let queues = self.queues.read();
@@ -200,6 +292,32 @@ impl QueueManager {
         Ok(queue.stats.clone())
     }
 
+    /// Up to `limit` ready messages of `queue_name` from `offset`, in
+    /// delivery order, without consuming them, and how many are ready in all.
+    /// Messages delivered and not yet acked are not listed. Positions shift
+    /// as the queue is consumed or published to, so paging a busy queue may
+    /// skip or repeat a message.
+    pub async fn browse(
+        &self,
+        queue_name: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<QueueMessage>, usize)> {
+        let queues = self.queues.read();
+        let queue = queues
+            .get(queue_name)
+            .ok_or_else(|| SynapError::QueueNotFound(queue_name.to_string()))?;
+
+        let messages = queue
+            .messages
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|arc_msg| (**arc_msg).clone())
+            .collect();
+        Ok((messages, queue.messages.len()))
+    }
+
     /// List all queues
     pub async fn list_queues(&self) -> Result<Vec<String>> {
         let queues = self.queues.read();
@@ -216,6 +334,12 @@ impl QueueManager {
             .ok_or_else(|| SynapError::QueueNotFound(queue_name.to_string()))?;
 
         let count = queue.messages.len();
+        if let Some(ref tracer) = self.tracer {
+            tracer.record_all(
+                queue.messages.iter().map(|m| m.id.as_str()),
+                TraceStep::Purged,
+            );
+        }
         queue.messages.clear();
         queue.stats.depth = 0;
 
@@ -245,4 +369,44 @@ impl QueueManager {
 
         Ok(dump)
     }
+
+    /// Messages of one queue, as [`dump`](Self::dump) would persist them.
+    /// `None` when the queue does not exist.
+    pub fn dump_queue(&self, queue_name: &str) -> Option<Vec<QueueMessage>> {
+        let queues = self.queues.read();
+        queues.get(queue_name).map(|queue| {
+            queue
+                .messages
+                .iter()
+                .map(|arc_msg| (**arc_msg).clone())
+                .collect()
+        })
+    }
+}
+
+/// Enqueue a new message on `queue`, filling in the queue's defaults
+fn publish_to(
+    queue: &mut Queue,
+    payload: impl Into<Bytes>,
+    priority: Option<u8>,
+    max_retries: Option<u32>,
+) -> Result<QueueMessage> {
+    let priority = priority.unwrap_or(queue.config.default_priority);
+    // If max_retries is not specified (None), use default. If specified (even as 0), use it.
+    let max_retries = max_retries.unwrap_or(queue.config.default_max_retries);
+
+    let mut message = QueueMessage::new(payload, priority, max_retries);
+    let enqueued_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    message
+        .headers
+        .insert(ENQUEUED_AT_HEADER.to_string(), enqueued_at.to_string());
+    let message_id = queue.publish(message.clone())?;
+
+    // Verify message ID matches (should always be true)
+    debug_assert_eq!(message.id, message_id);
+
+    Ok(message)
 }
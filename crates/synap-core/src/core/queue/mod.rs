@@ -1,4 +1,9 @@
 use super::error::{Result, SynapError};
+use super::idempotency::{IdempotencyWindow, default_dedup_window_secs};
+use super::trace::{
+    CONSUMER_HEADER, DELIVERY_ATTEMPT_HEADER, MessageTracer, NackOutcome, TraceSource, TraceStep,
+};
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, HashMap, VecDeque};
@@ -14,17 +19,14 @@ pub type MessageId = String;
 pub type ConsumerId = String;
 
 /// Queue message with metadata
-/// Uses Arc for payload sharing to reduce memory usage
+/// Uses `Bytes` so the payload is shared, not copied, between the queue, the
+/// WAL and replicas
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueueMessage {
     /// Unique message identifier
     pub id: MessageId,
-    /// Message payload (bytes) - Arc-shared to avoid cloning
-    #[serde(
-        serialize_with = "serialize_arc_payload",
-        deserialize_with = "deserialize_arc_payload"
-    )]
-    pub payload: Arc<Vec<u8>>,
+    /// Message payload (bytes) - reference-counted, so clones are cheap
+    pub payload: Bytes,
     /// Priority (0-9, where 9 is highest)
     pub priority: u8,
     /// Number of times this message was retried
@@ -39,25 +41,6 @@ pub struct QueueMessage {
     pub headers: HashMap<String, String>,
 }
 
-// Serialization helpers for Arc<Vec<u8>>
-fn serialize_arc_payload<S>(
-    payload: &Arc<Vec<u8>>,
-    serializer: S,
-) -> std::result::Result<S::Ok, S::Error>
-where
-    S: serde::Serializer,
-{
-    serializer.serialize_bytes(payload.as_ref())
-}
-
-fn deserialize_arc_payload<'de, D>(deserializer: D) -> std::result::Result<Arc<Vec<u8>>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    let vec = Vec::<u8>::deserialize(deserializer)?;
-    Ok(Arc::new(vec))
-}
-
 fn current_timestamp() -> u32 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -67,10 +50,10 @@ fn current_timestamp() -> u32 {
 
 impl QueueMessage {
     /// Create a new queue message
-    pub fn new(payload: Vec<u8>, priority: u8, max_retries: u32) -> Self {
+    pub fn new(payload: impl Into<Bytes>, priority: u8, max_retries: u32) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
-            payload: Arc::new(payload),
+            payload: payload.into(),
             priority: priority.min(9), // Cap at 9
             retry_count: 0,
             max_retries,
@@ -128,6 +111,11 @@ pub struct QueueConfig {
     /// unlimited (default; preserves the previous unthrottled behavior).
     #[serde(default)]
     pub prefetch_limit: usize,
+    /// How long a publish idempotency key is remembered, in seconds: a
+    /// publish repeating a key seen within this window is not enqueued again.
+    /// `0` turns deduplication off for the queue.
+    #[serde(default = "default_dedup_window_secs")]
+    pub dedup_window_secs: u64,
 }
 
 impl Default for QueueConfig {
@@ -138,10 +126,36 @@ impl Default for QueueConfig {
             default_max_retries: 3,
             default_priority: 5,
             prefetch_limit: 0,
+            dedup_window_secs: default_dedup_window_secs(),
         }
     }
 }
 
+/// What [`QueueManager::publish_idempotent`] did
+#[derive(Debug, Clone)]
+pub enum QueuePublish {
+    /// The message was enqueued
+    Published(QueueMessage),
+    /// The key was seen within the queue's window, so nothing was enqueued;
+    /// holds the id of the message the key first published
+    Duplicate(MessageId),
+}
+
+impl QueuePublish {
+    /// Id of the message the key published, now or the first time
+    pub fn message_id(&self) -> &str {
+        match self {
+            Self::Published(message) => &message.id,
+            Self::Duplicate(id) => id,
+        }
+    }
+
+    /// Whether the publish was a repeat and enqueued nothing
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, Self::Duplicate(_))
+    }
+}
+
 /// Queue statistics
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct QueueStats {
@@ -171,8 +185,12 @@ struct Queue {
     /// "active" while it holds at least one such message; this backs an honest
     /// `stats.consumers` instead of the previous hardcoded 1.
     active_consumers: HashMap<ConsumerId, u32>,
+    /// Idempotency keys published within `config.dedup_window_secs`
+    idempotency: IdempotencyWindow<MessageId>,
     stats: QueueStats,
     config: QueueConfig,
+    /// Delivery timelines, when `message_trace` is enabled
+    tracer: Option<Arc<MessageTracer>>,
 }
 
 impl Queue {
@@ -184,8 +202,16 @@ impl Queue {
             deadlines: BinaryHeap::new(),
             dead_letter: VecDeque::new(),
             active_consumers: HashMap::new(),
+            idempotency: IdempotencyWindow::new(config.dedup_window_secs),
             stats: QueueStats::default(),
             config,
+            tracer: None,
+        }
+    }
+
+    fn trace(&self, message_id: &str, step: TraceStep) {
+        if let Some(ref tracer) = self.tracer {
+            tracer.record(message_id, step);
         }
     }
 
@@ -208,6 +234,15 @@ impl Queue {
         }
 
         let message_id = message.id.clone();
+        if let Some(ref tracer) = self.tracer {
+            tracer.publish(
+                &message_id,
+                TraceSource::Queue(self.name.clone()),
+                TraceStep::Enqueued {
+                    priority: message.priority,
+                },
+            );
+        }
         let message_arc = Arc::new(message);
 
         // Insert in priority order (higher priority first)
@@ -255,14 +290,36 @@ impl Queue {
             // Track the deadline for O(expired) sweeping instead of O(pending).
             self.deadlines.push(Reverse((deadline, message_id)));
             // Mark the consumer active while it holds this unacked message.
-            *self.active_consumers.entry(consumer_id).or_insert(0) += 1;
+            *self
+                .active_consumers
+                .entry(consumer_id.clone())
+                .or_insert(0) += 1;
 
             self.stats.consumed += 1;
             self.stats.depth = self.messages.len();
             self.stats.consumers = self.active_consumers.len();
 
-            // Return cloned message (Arc deref + clone)
-            Some((*message_arc).clone())
+            // Every nack and expired deadline counts a retry, so this is the
+            // delivery attempt
+            let attempt = message_arc.retry_count + 1;
+            self.trace(
+                &message_arc.id,
+                TraceStep::Delivered {
+                    consumer: consumer_id.clone(),
+                    attempt,
+                },
+            );
+
+            // Return cloned message (Arc deref + clone) with this delivery's
+            // trace headers
+            let mut message = (*message_arc).clone();
+            message
+                .headers
+                .insert(DELIVERY_ATTEMPT_HEADER.to_string(), attempt.to_string());
+            message
+                .headers
+                .insert(CONSUMER_HEADER.to_string(), consumer_id);
+            Some(message)
         } else {
             None
         }
@@ -273,6 +330,12 @@ impl Queue {
         if let Some(pending) = self.pending.remove(message_id) {
             self.stats.acked += 1;
             self.release_consumer(&pending.consumer_id);
+            self.trace(
+                message_id,
+                TraceStep::Acked {
+                    consumer: pending.consumer_id,
+                },
+            );
             Ok(())
         } else {
             Err(SynapError::MessageNotFound(message_id.to_string()))
@@ -281,6 +344,12 @@ impl Queue {
 
     /// Negative acknowledge (requeue or dead letter)
     fn nack(&mut self, message_id: &str, requeue: bool) -> Result<()> {
+        self.reject(message_id, requeue, false)
+    }
+
+    /// Take a message back from its consumer, after a nack or, when
+    /// `expired`, a missed ack deadline
+    fn reject(&mut self, message_id: &str, requeue: bool, expired: bool) -> Result<()> {
         if let Some(pending) = self.pending.remove(message_id) {
             // The consumer no longer holds this message (requeued or dead-lettered).
             self.release_consumer(&pending.consumer_id);
@@ -295,7 +364,7 @@ impl Queue {
             message.increment_retry();
 
             // Then check if exceeded max retries
-            if message.is_dead() {
+            let outcome = if message.is_dead() {
                 // Move to dead letter queue
                 debug!(
                     "Message {} exceeded retries (retry_count={}, max={}), moving to DLQ",
@@ -303,6 +372,7 @@ impl Queue {
                 );
                 self.dead_letter.push_back(Arc::new(message));
                 self.stats.dead_lettered += 1;
+                NackOutcome::DeadLettered
             } else if requeue {
                 // Requeue with updated retry count
                 debug!(
@@ -311,7 +381,20 @@ impl Queue {
                 );
                 self.messages.push_back(Arc::new(message));
                 self.stats.depth = self.messages.len();
-            }
+                NackOutcome::Requeued
+            } else {
+                NackOutcome::Discarded
+            };
+
+            let consumer = pending.consumer_id;
+            self.trace(
+                message_id,
+                if expired {
+                    TraceStep::Expired { consumer, outcome }
+                } else {
+                    TraceStep::Nacked { consumer, outcome }
+                },
+            );
 
             Ok(())
         } else {
@@ -348,7 +431,7 @@ impl Queue {
 
         for message_id in to_requeue {
             debug!("Message {} ACK deadline expired, requeuing", message_id);
-            let _ = self.nack(&message_id, true);
+            let _ = self.reject(&message_id, true, true);
         }
     }
 }
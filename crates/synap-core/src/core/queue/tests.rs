@@ -16,7 +16,29 @@ async fn test_queue_publish_consume() {
     // Consume
     let message = manager.consume("test_queue", "consumer1").await.unwrap();
     assert!(message.is_some());
-    assert_eq!(*message.unwrap().payload, b"Hello");
+    assert_eq!(message.unwrap().payload, &b"Hello"[..]);
+}
+
+#[tokio::test]
+async fn test_queue_browse_leaves_messages_in_place() {
+    let manager = QueueManager::new(QueueConfig::default());
+    manager.create_queue("jobs", None).await.unwrap();
+    for (payload, priority) in [(b"low", 1), (b"top", 9), (b"mid", 5)] {
+        manager
+            .publish("jobs", payload.to_vec(), Some(priority), None)
+            .await
+            .unwrap();
+    }
+
+    let (page, total) = manager.browse("jobs", 1, 5).await.unwrap();
+    assert_eq!(total, 3);
+    let payloads: Vec<_> = page.iter().map(|m| m.payload.as_ref()).collect();
+    assert_eq!(payloads, [&b"mid"[..], &b"low"[..]]);
+
+    // Browsing consumed nothing
+    let first = manager.consume("jobs", "c1").await.unwrap().unwrap();
+    assert_eq!(first.payload, &b"top"[..]);
+    assert!(manager.browse("missing", 0, 5).await.is_err());
 }
 
 #[tokio::test]
@@ -44,7 +66,7 @@ async fn test_queue_priority() {
         .await
         .unwrap()
         .unwrap();
-    assert_eq!(*msg1.payload, b"High");
+    assert_eq!(msg1.payload, &b"High"[..]);
     assert_eq!(msg1.priority, 9);
 
     let msg2 = manager
@@ -52,7 +74,7 @@ async fn test_queue_priority() {
         .await
         .unwrap()
         .unwrap();
-    assert_eq!(*msg2.payload, b"Medium");
+    assert_eq!(msg2.payload, &b"Medium"[..]);
     assert_eq!(msg2.priority, 5);
 
     let msg3 = manager
@@ -60,7 +82,7 @@ async fn test_queue_priority() {
         .await
         .unwrap()
         .unwrap();
-    assert_eq!(*msg3.payload, b"Low");
+    assert_eq!(msg3.payload, &b"Low"[..]);
     assert_eq!(msg3.priority, 1);
 }
 
@@ -140,6 +162,30 @@ async fn test_queue_dead_letter() {
     assert!(message.is_none());
 }
 
+#[tokio::test]
+async fn test_pop_and_return_dead_letter() {
+    let manager = QueueManager::new(QueueConfig::default());
+    manager.create_queue("jobs", None).await.unwrap();
+    for payload in [b"a", b"b"] {
+        manager
+            .publish("jobs", payload.to_vec(), None, Some(0))
+            .await
+            .unwrap();
+        let message = manager.consume("jobs", "c1").await.unwrap().unwrap();
+        manager.nack("jobs", &message.id, true).await.unwrap();
+    }
+
+    let first = manager.pop_dead_letter("jobs").await.unwrap().unwrap();
+    assert_eq!(&first.payload[..], b"a");
+    manager.return_dead_letter("jobs", first).await.unwrap();
+    let again = manager.pop_dead_letter("jobs").await.unwrap().unwrap();
+    assert_eq!(&again.payload[..], b"a");
+    let second = manager.pop_dead_letter("jobs").await.unwrap().unwrap();
+    assert_eq!(&second.payload[..], b"b");
+    assert!(manager.pop_dead_letter("jobs").await.unwrap().is_none());
+    assert!(manager.pop_dead_letter("missing").await.unwrap().is_none());
+}
+
 // ==================== DEADLINE SWEEP TESTS (M-017) ====================
 
 fn queue_with_deadline(secs: u64) -> Queue {
@@ -825,3 +871,173 @@ async fn test_priority_with_concurrent_consumers() {
         );
     }
 }
+
+// ==================== IDEMPOTENT PUBLISH TESTS ====================
+
+#[tokio::test]
+async fn test_queue_publish_idempotent_dedups_retries() {
+    let manager = QueueManager::new(QueueConfig::default());
+    manager.create_queue("orders", None).await.unwrap();
+
+    let first = manager
+        .publish_idempotent("orders", b"order-1".to_vec(), None, None, "key-1")
+        .await
+        .unwrap();
+    assert!(!first.is_duplicate());
+
+    let retry = manager
+        .publish_idempotent("orders", b"order-1".to_vec(), None, None, "key-1")
+        .await
+        .unwrap();
+    assert!(retry.is_duplicate());
+    assert_eq!(retry.message_id(), first.message_id());
+
+    manager
+        .publish_idempotent("orders", b"order-2".to_vec(), None, None, "key-2")
+        .await
+        .unwrap();
+
+    let stats = manager.stats("orders").await.unwrap();
+    assert_eq!(stats.depth, 2);
+    assert_eq!(stats.published, 2);
+}
+
+#[tokio::test]
+async fn test_queue_dedup_window_is_per_queue() {
+    let manager = QueueManager::new(QueueConfig::default());
+    manager.create_queue("a", None).await.unwrap();
+    manager
+        .create_queue(
+            "off",
+            Some(QueueConfig {
+                dedup_window_secs: 0,
+                ..QueueConfig::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+    // The same key on another queue is a different publish
+    manager
+        .publish_idempotent("a", b"x".to_vec(), None, None, "k")
+        .await
+        .unwrap();
+    manager
+        .publish_idempotent("off", b"x".to_vec(), None, None, "k")
+        .await
+        .unwrap();
+
+    // A zero window keeps nothing, so the retry is enqueued again
+    let retry = manager
+        .publish_idempotent("off", b"x".to_vec(), None, None, "k")
+        .await
+        .unwrap();
+    assert!(!retry.is_duplicate());
+    assert_eq!(manager.stats("off").await.unwrap().depth, 2);
+    assert_eq!(manager.stats("a").await.unwrap().depth, 1);
+}
+
+// ==================== MESSAGE TRACE TESTS ====================
+
+#[tokio::test]
+async fn test_delivered_messages_carry_trace_headers() {
+    use crate::core::trace::{CONSUMER_HEADER, DELIVERY_ATTEMPT_HEADER, ENQUEUED_AT_HEADER};
+
+    let manager = QueueManager::new(QueueConfig::default());
+    manager.create_queue("jobs", None).await.unwrap();
+    let published = manager
+        .publish_with_message("jobs", b"job".to_vec(), None, None)
+        .await
+        .unwrap();
+    assert!(published.headers.contains_key(ENQUEUED_AT_HEADER));
+
+    let first = manager.consume("jobs", "c1").await.unwrap().unwrap();
+    assert_eq!(first.headers[DELIVERY_ATTEMPT_HEADER], "1");
+    assert_eq!(first.headers[CONSUMER_HEADER], "c1");
+    assert_eq!(
+        first.headers[ENQUEUED_AT_HEADER],
+        published.headers[ENQUEUED_AT_HEADER]
+    );
+
+    manager.nack("jobs", &first.id, true).await.unwrap();
+    let second = manager.consume("jobs", "c2").await.unwrap().unwrap();
+    assert_eq!(second.headers[DELIVERY_ATTEMPT_HEADER], "2");
+    assert_eq!(second.headers[CONSUMER_HEADER], "c2");
+}
+
+#[tokio::test]
+async fn test_message_trace_timeline() {
+    let tracer = Arc::new(MessageTracer::new(100, 32));
+    let manager = QueueManager::new(QueueConfig::default());
+    // Queues created before the tracer is attached are traced too
+    manager.create_queue("jobs", None).await.unwrap();
+    let manager = manager.with_tracer(Some(Arc::clone(&tracer)));
+
+    let id = manager
+        .publish("jobs", b"job".to_vec(), Some(7), None)
+        .await
+        .unwrap();
+    manager.consume("jobs", "c1").await.unwrap().unwrap();
+    manager.nack("jobs", &id, true).await.unwrap();
+    manager.consume("jobs", "c2").await.unwrap().unwrap();
+    manager.ack("jobs", &id).await.unwrap();
+
+    let trace = tracer.get(&id).unwrap();
+    assert_eq!(trace.source, TraceSource::Queue("jobs".to_string()));
+    assert_eq!(trace.deliveries, 2);
+    assert_eq!(trace.consumers, ["c1", "c2"]);
+    assert!(trace.acked_at.is_some());
+    let steps: Vec<_> = trace.events.into_iter().map(|e| e.step).collect();
+    assert_eq!(
+        steps,
+        [
+            TraceStep::Enqueued { priority: 7 },
+            TraceStep::Delivered {
+                consumer: "c1".to_string(),
+                attempt: 1
+            },
+            TraceStep::Nacked {
+                consumer: "c1".to_string(),
+                outcome: NackOutcome::Requeued
+            },
+            TraceStep::Delivered {
+                consumer: "c2".to_string(),
+                attempt: 2
+            },
+            TraceStep::Acked {
+                consumer: "c2".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_message_trace_expired_to_dead_letter() {
+    let tracer = Arc::new(MessageTracer::new(100, 32));
+    let mut queue = queue_with_deadline(0);
+    queue.tracer = Some(Arc::clone(&tracer));
+    let id = queue
+        .publish(QueueMessage::new(b"job".to_vec(), 5, 0))
+        .unwrap();
+
+    // No retries left, so the missed deadline dead-letters the message
+    queue.consume("c1".to_string()).unwrap();
+    queue.check_expired_pending();
+    assert_eq!(queue.dead_letter.len(), 1);
+
+    let steps: Vec<_> = tracer
+        .get(&id)
+        .unwrap()
+        .events
+        .into_iter()
+        .map(|e| e.step)
+        .collect();
+    assert_eq!(
+        steps[2],
+        TraceStep::Expired {
+            consumer: "c1".to_string(),
+            outcome: NackOutcome::DeadLettered
+        }
+    );
+    assert_eq!(steps.len(), 3);
+}
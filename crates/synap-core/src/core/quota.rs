@@ -0,0 +1,264 @@
+//! Per-namespace quotas for the KV store.
+//!
+//! A namespace is a key prefix (`tenant-a:`, `cache:`) with an optional cap on
+//! its key count and on its memory bytes, measured the same way as the KV
+//! store's own `total_memory_bytes`. A key belongs to the namespace with the
+//! longest matching prefix; keys outside every namespace are not limited.
+//!
+//! The KV store keeps the counters: every write charges its change in key count
+//! and size with [`NamespaceQuotas::try_charge`], which refuses growth past a
+//! cap, and every removal releases it with [`NamespaceQuotas::charge`]. Writes
+//! that shrink a namespace are always allowed, so a namespace over its quota
+//! (e.g. after the limits were lowered) can still be cleaned up.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{Result, SynapError};
+
+/// Limits for one namespace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceLimit {
+    /// Key prefix that defines the namespace
+    pub prefix: String,
+    /// Maximum number of keys. `None` means no limit.
+    #[serde(default)]
+    pub max_keys: Option<u64>,
+    /// Maximum memory in bytes. `None` means no limit.
+    #[serde(default)]
+    pub max_memory_bytes: Option<u64>,
+}
+
+/// Current usage of one namespace against its limits.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NamespaceUsage {
+    pub prefix: String,
+    pub keys: u64,
+    pub max_keys: Option<u64>,
+    pub memory_bytes: u64,
+    pub max_memory_bytes: Option<u64>,
+}
+
+struct Namespace {
+    limit: NamespaceLimit,
+    keys: AtomicI64,
+    bytes: AtomicI64,
+}
+
+impl Namespace {
+    /// Name of the limit that `keys` more keys and `bytes` more bytes would
+    /// break, if any
+    fn exceeded_by(&self, keys: i64, bytes: i64) -> Option<String> {
+        if let Some(max) = self.limit.max_keys
+            && keys > 0
+            && self.keys.load(Ordering::Relaxed) + keys > max as i64
+        {
+            return Some(format!("key quota ({max} keys)"));
+        }
+        if let Some(max) = self.limit.max_memory_bytes
+            && bytes > 0
+            && self.bytes.load(Ordering::Relaxed) + bytes > max as i64
+        {
+            return Some(format!("memory quota ({max} bytes)"));
+        }
+        None
+    }
+
+    fn error(&self, limit: String) -> SynapError {
+        SynapError::QuotaExceeded(format!(
+            "Namespace '{}' would exceed its {limit}",
+            self.limit.prefix
+        ))
+    }
+}
+
+/// Usage counters and limits for a set of namespaces.
+///
+/// Cheap to clone (`Arc` inside). Check and charge are two atomic steps, so
+/// concurrent writers to one namespace can overshoot a cap by at most one
+/// write each.
+#[derive(Clone)]
+pub struct NamespaceQuotas {
+    /// Longest prefix first, so the first match is the most specific one
+    namespaces: Arc<Vec<Namespace>>,
+}
+
+impl NamespaceQuotas {
+    /// Create quotas for `limits`. A repeated prefix keeps its last entry.
+    pub fn new(limits: Vec<NamespaceLimit>) -> Self {
+        let mut by_prefix: HashMap<String, NamespaceLimit> = HashMap::new();
+        for limit in limits {
+            by_prefix.insert(limit.prefix.clone(), limit);
+        }
+        let mut namespaces: Vec<Namespace> = by_prefix
+            .into_values()
+            .map(|limit| Namespace {
+                limit,
+                keys: AtomicI64::new(0),
+                bytes: AtomicI64::new(0),
+            })
+            .collect();
+        namespaces.sort_by(|a, b| {
+            b.limit
+                .prefix
+                .len()
+                .cmp(&a.limit.prefix.len())
+                .then_with(|| a.limit.prefix.cmp(&b.limit.prefix))
+        });
+        Self {
+            namespaces: Arc::new(namespaces),
+        }
+    }
+
+    /// True if no namespace is configured
+    pub fn is_empty(&self) -> bool {
+        self.namespaces.is_empty()
+    }
+
+    fn namespace_of(&self, key: &str) -> Option<&Namespace> {
+        self.namespaces
+            .iter()
+            .find(|ns| key.starts_with(ns.limit.prefix.as_str()))
+    }
+
+    /// Charge `keys` more keys and `bytes` more bytes (negative to release)
+    /// to `key`'s namespace, with no check.
+    pub fn charge(&self, key: &str, keys: i64, bytes: i64) {
+        if let Some(ns) = self.namespace_of(key) {
+            ns.keys.fetch_add(keys, Ordering::Relaxed);
+            ns.bytes.fetch_add(bytes, Ordering::Relaxed);
+        }
+    }
+
+    /// Charge a write to `key`'s namespace, or refuse it with
+    /// [`SynapError::QuotaExceeded`] if it would grow the namespace past a cap.
+    pub fn try_charge(&self, key: &str, keys: i64, bytes: i64) -> Result<()> {
+        let Some(ns) = self.namespace_of(key) else {
+            return Ok(());
+        };
+        if let Some(limit) = ns.exceeded_by(keys, bytes) {
+            return Err(ns.error(limit));
+        }
+        ns.keys.fetch_add(keys, Ordering::Relaxed);
+        ns.bytes.fetch_add(bytes, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Check a batch of `(key, keys, bytes)` writes as a whole without
+    /// charging it, so a multi-key write is refused before any key is written.
+    pub fn check_batch<'a>(
+        &self,
+        writes: impl IntoIterator<Item = (&'a str, i64, i64)>,
+    ) -> Result<()> {
+        let mut totals: HashMap<&str, (i64, i64)> = HashMap::new();
+        for (key, keys, bytes) in writes {
+            if let Some(ns) = self.namespace_of(key) {
+                let total = totals.entry(ns.limit.prefix.as_str()).or_default();
+                total.0 += keys;
+                total.1 += bytes;
+            }
+        }
+        for ns in self.namespaces.iter() {
+            if let Some(&(keys, bytes)) = totals.get(ns.limit.prefix.as_str())
+                && let Some(limit) = ns.exceeded_by(keys, bytes)
+            {
+                return Err(ns.error(limit));
+            }
+        }
+        Ok(())
+    }
+
+    /// Zero every counter (the store was flushed)
+    pub fn reset(&self) {
+        for ns in self.namespaces.iter() {
+            ns.keys.store(0, Ordering::Relaxed);
+            ns.bytes.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Usage of every namespace, sorted by prefix
+    pub fn usage(&self) -> Vec<NamespaceUsage> {
+        let mut usage: Vec<NamespaceUsage> = self
+            .namespaces
+            .iter()
+            .map(|ns| NamespaceUsage {
+                prefix: ns.limit.prefix.clone(),
+                keys: ns.keys.load(Ordering::Relaxed).max(0) as u64,
+                max_keys: ns.limit.max_keys,
+                memory_bytes: ns.bytes.load(Ordering::Relaxed).max(0) as u64,
+                max_memory_bytes: ns.limit.max_memory_bytes,
+            })
+            .collect();
+        usage.sort_by(|a, b| a.prefix.cmp(&b.prefix));
+        usage
+    }
+}
+
+impl std::fmt::Debug for NamespaceQuotas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NamespaceQuotas")
+            .field("namespaces", &self.usage())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(prefix: &str, max_keys: Option<u64>, max_memory_bytes: Option<u64>) -> NamespaceLimit {
+        NamespaceLimit {
+            prefix: prefix.to_string(),
+            max_keys,
+            max_memory_bytes,
+        }
+    }
+
+    #[test]
+    fn longest_prefix_wins_and_caps_are_enforced() {
+        let q = NamespaceQuotas::new(vec![
+            limit("tenant:", Some(10), None),
+            limit("tenant:big:", None, Some(100)),
+        ]);
+
+        q.try_charge("tenant:a", 1, 1000).unwrap();
+        q.try_charge("tenant:big:a", 1, 60).unwrap();
+        // 60 + 50 > 100
+        assert!(matches!(
+            q.try_charge("tenant:big:b", 1, 50),
+            Err(SynapError::QuotaExceeded(_))
+        ));
+        // Outside every namespace
+        q.try_charge("other", 1, 1_000_000).unwrap();
+
+        let usage = q.usage();
+        assert_eq!(usage[0].prefix, "tenant:");
+        assert_eq!((usage[0].keys, usage[0].memory_bytes), (1, 1000));
+        assert_eq!((usage[1].keys, usage[1].memory_bytes), (1, 60));
+    }
+
+    #[test]
+    fn shrinking_is_always_allowed() {
+        let q = NamespaceQuotas::new(vec![limit("a:", Some(1), Some(10))]);
+        q.try_charge("a:1", 1, 10).unwrap();
+        assert!(q.try_charge("a:2", 1, 0).is_err());
+        assert!(q.try_charge("a:1", 0, 1).is_err());
+        q.try_charge("a:1", 0, -5).unwrap();
+        q.charge("a:1", -1, -5);
+        q.try_charge("a:2", 1, 10).unwrap();
+
+        q.reset();
+        assert_eq!(q.usage()[0].keys, 0);
+    }
+
+    #[test]
+    fn batches_are_checked_as_a_whole() {
+        let q = NamespaceQuotas::new(vec![limit("a:", Some(2), None)]);
+        q.try_charge("a:1", 1, 1).unwrap();
+        assert!(q.check_batch([("a:2", 1, 1), ("b:1", 1, 1)]).is_ok());
+        assert!(q.check_batch([("a:2", 1, 1), ("a:3", 1, 1)]).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+//! Rate limiters as a data type.
+//!
+//! Each key holds one limiter, checked and updated atomically by
+//! [`RateLimitStore::check`]. Building the same thing from `INCR` + `EXPIRE`
+//! races between the two commands; here the decision and the update happen
+//! under one lock.
+//!
+//! Two algorithms are supported:
+//!
+//! - **Token bucket** — holds up to `capacity` tokens and refills at
+//!   `refill_per_sec`. Allows bursts up to the capacity, then the refill rate.
+//! - **Sliding window counter** — at most `limit` units per `window`. The
+//!   count is estimated from the current and previous fixed windows, weighting
+//!   the previous one by how much of it still overlaps the sliding window, so a
+//!   burst at a window boundary cannot double the limit.
+//!
+//! Limiters live in memory only. A key checked with a different algorithm than
+//! the one it holds starts over; a changed capacity or limit applies at once.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Checks between sweeps of idle limiters.
+const SWEEP_INTERVAL: u64 = 4096;
+
+/// How a key is limited.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimit {
+    /// Up to `capacity` at once, refilled at `refill_per_sec`.
+    TokenBucket { capacity: u64, refill_per_sec: f64 },
+    /// At most `limit` per sliding `window`.
+    SlidingWindow { limit: u64, window: Duration },
+}
+
+impl RateLimit {
+    /// The most a single check can ever be allowed to take.
+    pub fn max_cost(&self) -> u64 {
+        match *self {
+            RateLimit::TokenBucket { capacity, .. } => capacity,
+            RateLimit::SlidingWindow { limit, .. } => limit,
+        }
+    }
+}
+
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    /// Units still available after this check.
+    pub remaining: u64,
+    /// When a denied check of the same cost would be allowed; `None` if it
+    /// was allowed.
+    pub retry_after: Option<Duration>,
+}
+
+/// Rate limiter counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RateLimitStats {
+    /// Keys holding a limiter.
+    pub keys: usize,
+    pub allowed: u64,
+    pub denied: u64,
+}
+
+enum Limiter {
+    Bucket {
+        tokens: f64,
+        updated: Instant,
+    },
+    Window {
+        started: Instant,
+        current: u64,
+        previous: u64,
+    },
+}
+
+impl Limiter {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        match *limit {
+            RateLimit::TokenBucket { capacity, .. } => Limiter::Bucket {
+                tokens: capacity as f64,
+                updated: now,
+            },
+            RateLimit::SlidingWindow { .. } => Limiter::Window {
+                started: now,
+                current: 0,
+                previous: 0,
+            },
+        }
+    }
+
+    fn matches(&self, limit: &RateLimit) -> bool {
+        matches!(
+            (self, limit),
+            (Limiter::Bucket { .. }, RateLimit::TokenBucket { .. })
+                | (Limiter::Window { .. }, RateLimit::SlidingWindow { .. })
+        )
+    }
+
+    fn check(&mut self, limit: &RateLimit, cost: u64, now: Instant) -> RateLimitDecision {
+        match (self, *limit) {
+            (
+                Limiter::Bucket { tokens, updated },
+                RateLimit::TokenBucket {
+                    capacity,
+                    refill_per_sec,
+                },
+            ) => {
+                let elapsed = now.saturating_duration_since(*updated).as_secs_f64();
+                *tokens = (*tokens + elapsed * refill_per_sec).min(capacity as f64);
+                *updated = now;
+
+                let cost = cost as f64;
+                if *tokens >= cost {
+                    *tokens -= cost;
+                    return RateLimitDecision {
+                        allowed: true,
+                        remaining: *tokens as u64,
+                        retry_after: None,
+                    };
+                }
+                RateLimitDecision {
+                    allowed: false,
+                    remaining: *tokens as u64,
+                    retry_after: Some(saturating_secs((cost - *tokens) / refill_per_sec)),
+                }
+            }
+            (
+                Limiter::Window {
+                    started,
+                    current,
+                    previous,
+                },
+                RateLimit::SlidingWindow { limit, window },
+            ) => {
+                let passed = now.saturating_duration_since(*started);
+                if passed >= window {
+                    let windows = (passed.as_nanos() / window.as_nanos().max(1)) as u32;
+                    *previous = if windows == 1 { *current } else { 0 };
+                    *current = 0;
+                    *started += window * windows;
+                }
+                let into = now.saturating_duration_since(*started).as_secs_f64();
+                let overlap = 1.0 - into / window.as_secs_f64();
+                let estimate = *previous as f64 * overlap + *current as f64;
+
+                if estimate + cost as f64 <= limit as f64 {
+                    *current += cost;
+                    return RateLimitDecision {
+                        allowed: true,
+                        remaining: (limit as f64 - estimate - cost as f64) as u64,
+                        retry_after: None,
+                    };
+                }
+
+                let remaining = (limit as f64 - estimate).max(0.0) as u64;
+                let to_window_end = window.saturating_sub(now - *started);
+                // Room left once the previous window's weight has decayed
+                // enough; otherwise only a new window frees space.
+                let room = limit.saturating_sub(*current + cost) as f64;
+                let retry_after = if *current + cost <= limit && *previous > 0 {
+                    let at = window.as_secs_f64() * (1.0 - room / *previous as f64);
+                    saturating_secs((at - into).max(0.0))
+                } else {
+                    to_window_end
+                };
+                RateLimitDecision {
+                    allowed: false,
+                    remaining,
+                    retry_after: Some(retry_after),
+                }
+            }
+            _ => unreachable!("a limiter is replaced when its algorithm changes"),
+        }
+    }
+
+    /// Whether dropping the limiter would change no future decision: the
+    /// bucket has refilled, or both windows have passed.
+    fn idle(&self, limit: &RateLimit, now: Instant) -> bool {
+        match (self, *limit) {
+            (
+                Limiter::Bucket { tokens, updated },
+                RateLimit::TokenBucket {
+                    capacity,
+                    refill_per_sec,
+                },
+            ) => {
+                let elapsed = now.saturating_duration_since(*updated).as_secs_f64();
+                tokens + elapsed * refill_per_sec >= capacity as f64
+            }
+            (Limiter::Window { started, .. }, RateLimit::SlidingWindow { window, .. }) => {
+                now.saturating_duration_since(*started) >= window * 2
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Per-key rate limiters.
+pub struct RateLimitStore {
+    /// Key → the limit it was last checked with, and its state.
+    limiters: Mutex<HashMap<String, (RateLimit, Limiter), ahash::RandomState>>,
+    checks: AtomicU64,
+    allowed: AtomicU64,
+    denied: AtomicU64,
+}
+
+impl Default for RateLimitStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimitStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self {
+            limiters: Mutex::new(HashMap::default()),
+            checks: AtomicU64::new(0),
+            allowed: AtomicU64::new(0),
+            denied: AtomicU64::new(0),
+        }
+    }
+
+    /// Take `cost` units from the limiter on `key`, creating it full.
+    ///
+    /// A denied check takes nothing.
+    pub fn check(&self, key: &str, limit: &RateLimit, cost: u64) -> RateLimitDecision {
+        self.check_at(key, limit, cost, Instant::now())
+    }
+
+    fn check_at(&self, key: &str, limit: &RateLimit, cost: u64, now: Instant) -> RateLimitDecision {
+        let mut limiters = self.limiters.lock();
+
+        let checks = self.checks.fetch_add(1, Ordering::Relaxed) + 1;
+        if checks.is_multiple_of(SWEEP_INTERVAL) {
+            limiters.retain(|_, (limit, limiter)| !limiter.idle(limit, now));
+        }
+
+        let (current, limiter) = limiters
+            .entry(key.to_owned())
+            .or_insert_with(|| (*limit, Limiter::new(limit, now)));
+        if !limiter.matches(limit) {
+            *limiter = Limiter::new(limit, now);
+        }
+        *current = *limit;
+        let decision = limiter.check(limit, cost, now);
+
+        let counter = if decision.allowed {
+            &self.allowed
+        } else {
+            &self.denied
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        decision
+    }
+
+    /// Drop the limiter on `key`, so its next check starts full.
+    pub fn reset(&self, key: &str) -> bool {
+        self.limiters.lock().remove(key).is_some()
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> RateLimitStats {
+        RateLimitStats {
+            keys: self.limiters.lock().len(),
+            allowed: self.allowed.load(Ordering::Relaxed),
+            denied: self.denied.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// `secs` as a duration, `Duration::MAX` past what one can hold (a refill
+/// rate so low the wait overflows).
+fn saturating_secs(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUCKET: RateLimit = RateLimit::TokenBucket {
+        capacity: 3,
+        refill_per_sec: 1.0,
+    };
+    const WINDOW: RateLimit = RateLimit::SlidingWindow {
+        limit: 4,
+        window: Duration::from_secs(10),
+    };
+
+    #[test]
+    fn a_bucket_allows_a_burst_then_the_refill_rate() {
+        let store = RateLimitStore::new();
+        let t0 = Instant::now();
+
+        for remaining in [2, 1, 0] {
+            let decision = store.check_at("k", &BUCKET, 1, t0);
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+        }
+        let denied = store.check_at("k", &BUCKET, 1, t0);
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, Some(Duration::from_secs(1)));
+
+        let later = t0 + Duration::from_millis(1500);
+        assert!(store.check_at("k", &BUCKET, 1, later).allowed);
+        assert!(!store.check_at("k", &BUCKET, 1, later).allowed);
+        // Never refills past the capacity
+        let much_later = t0 + Duration::from_secs(60);
+        assert!(store.check_at("k", &BUCKET, 3, much_later).allowed);
+        assert!(!store.check_at("k", &BUCKET, 1, much_later).allowed);
+    }
+
+    #[test]
+    fn a_tiny_refill_rate_saturates_the_wait() {
+        let store = RateLimitStore::new();
+        let t0 = Instant::now();
+        let slow = RateLimit::TokenBucket {
+            capacity: 1,
+            refill_per_sec: 1e-300,
+        };
+
+        assert!(store.check_at("k", &slow, 1, t0).allowed);
+        let denied = store.check_at("k", &slow, 1, t0);
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, Some(Duration::MAX));
+    }
+
+    #[test]
+    fn a_denied_check_takes_nothing() {
+        let store = RateLimitStore::new();
+        let t0 = Instant::now();
+
+        assert!(store.check_at("k", &BUCKET, 2, t0).allowed);
+        assert!(!store.check_at("k", &BUCKET, 2, t0).allowed);
+        assert!(store.check_at("k", &BUCKET, 1, t0).allowed);
+
+        let stats = store.stats();
+        assert_eq!((stats.keys, stats.allowed, stats.denied), (1, 2, 1));
+    }
+
+    #[test]
+    fn a_sliding_window_weights_the_previous_window() {
+        let store = RateLimitStore::new();
+        let t0 = Instant::now();
+
+        for _ in 0..4 {
+            assert!(store.check_at("k", &WINDOW, 1, t0).allowed);
+        }
+        let denied = store.check_at("k", &WINDOW, 1, t0);
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, Some(Duration::from_secs(10)));
+
+        // A quarter into the next window, three quarters of the previous four
+        // still count: room for one
+        let t1 = t0 + Duration::from_millis(12_500);
+        let allowed = store.check_at("k", &WINDOW, 1, t1);
+        assert!(allowed.allowed);
+        assert_eq!(allowed.remaining, 0);
+        let denied = store.check_at("k", &WINDOW, 1, t1);
+        assert!(!denied.allowed);
+        // Room for one more once the old window weighs 2: halfway through
+        assert_eq!(denied.retry_after, Some(Duration::from_millis(2500)));
+
+        // Two windows on, the old counts are gone
+        assert!(
+            store
+                .check_at("k", &WINDOW, 4, t0 + Duration::from_secs(30))
+                .allowed
+        );
+    }
+
+    #[test]
+    fn keys_are_limited_independently_and_reset() {
+        let store = RateLimitStore::new();
+        let t0 = Instant::now();
+
+        assert!(store.check_at("a", &BUCKET, 3, t0).allowed);
+        assert!(store.check_at("b", &BUCKET, 3, t0).allowed);
+        assert!(!store.check_at("a", &BUCKET, 1, t0).allowed);
+
+        assert!(store.reset("a"));
+        assert!(!store.reset("a"));
+        assert!(store.check_at("a", &BUCKET, 3, t0).allowed);
+    }
+
+    #[test]
+    fn idle_limiters_are_swept() {
+        let store = RateLimitStore::new();
+        let t0 = Instant::now();
+        store.check_at("drained", &BUCKET, 3, t0);
+        for i in 2..SWEEP_INTERVAL {
+            store.check_at(&format!("k:{i}"), &WINDOW, 1, t0);
+        }
+
+        // The next check sweeps: every limiter has refilled or is past both
+        // windows, so nothing is left worth keeping
+        store.check_at("drained", &BUCKET, 1, t0 + Duration::from_secs(20));
+        assert_eq!(store.stats().keys, 1);
+    }
+
+    #[test]
+    fn switching_algorithms_starts_over() {
+        let store = RateLimitStore::new();
+        let t0 = Instant::now();
+
+        assert!(store.check_at("k", &BUCKET, 3, t0).allowed);
+        assert!(store.check_at("k", &WINDOW, 4, t0).allowed);
+        assert!(store.check_at("k", &BUCKET, 3, t0).allowed);
+    }
+}
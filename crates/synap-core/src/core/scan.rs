@@ -0,0 +1,61 @@
+//! Cursor pages for `HSCAN` / `SSCAN` / `ZSCAN`.
+//!
+//! A collection scan cursor is an offset into the collection's elements in
+//! sorted order. Each page selects its window with `select_nth_unstable`
+//! over borrowed elements, so a page costs O(n + count log count) and copies
+//! only the elements it returns, however large the collection.
+
+/// Select page `cursor..cursor + count` (`count` at least 1) of `items` in
+/// sorted order. Returns the next cursor, 0 once the scan is complete, and
+/// the window.
+pub(crate) fn scan_window<T: Ord>(mut items: Vec<T>, cursor: u64, count: usize) -> (u64, Vec<T>) {
+    let total = items.len();
+    let start = usize::try_from(cursor).unwrap_or(usize::MAX).min(total);
+    let end = start.saturating_add(count.max(1)).min(total);
+    if start == end {
+        return (0, Vec::new());
+    }
+
+    if start > 0 {
+        items.select_nth_unstable(start - 1);
+    }
+    let mut window = items.split_off(start);
+    if end < total {
+        window.select_nth_unstable(end - start);
+        window.truncate(end - start);
+    }
+    window.sort_unstable();
+
+    let next = if end < total { end as u64 } else { 0 };
+    (next, window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_cover_every_item_once_in_order() {
+        let items: Vec<u32> = (0..1000).map(|i| (i * 7919) % 1000).collect();
+        let mut seen = Vec::new();
+        let mut cursor = 0;
+        loop {
+            let (next, page) = scan_window(items.clone(), cursor, 64);
+            assert!(page.len() <= 64);
+            seen.extend(page);
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        assert_eq!(seen, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_cursor_past_end_and_zero_count() {
+        assert_eq!(scan_window(vec![3, 1, 2], 10, 5), (0, vec![]));
+        assert_eq!(scan_window(vec![3, 1, 2], 0, 0), (1, vec![1]));
+        assert_eq!(scan_window(vec![3, 1, 2], 1, 5), (0, vec![2, 3]));
+        assert_eq!(scan_window(Vec::<u8>::new(), 0, 5), (0, vec![]));
+    }
+}
@@ -17,9 +17,10 @@
 //! ```
 
 use super::error::{Result, SynapError};
+use super::memory_profile::KeyMemory;
 use ahash::RandomState as AHashState;
 use parking_lot::RwLock;
-use rand::seq::SliceRandom;
+use rand::seq::{IndexedRandom, SliceRandom};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
@@ -212,6 +213,12 @@ impl SetValue {
         }
     }
 
+    /// Seconds left before the set expires, `None` without a TTL
+    pub fn ttl(&self) -> Option<u64> {
+        self.ttl_secs
+            .map(|ttl| (self.created_at + ttl).saturating_sub(Self::current_timestamp()))
+    }
+
     /// Get number of members
     pub fn len(&self) -> usize {
         match &self.repr {
@@ -225,6 +232,18 @@ impl SetValue {
         self.len() == 0
     }
 
+    /// Encoding and estimated memory of the set stored under `key`
+    pub fn memory(&self, key: &str) -> KeyMemory {
+        match &self.repr {
+            SetRepr::Packed { buf, count } => {
+                KeyMemory::new(key, "packed", *count, buf.len() - 4 * count)
+            }
+            SetRepr::Hash(h) => {
+                KeyMemory::new(key, "hashset", h.len(), h.iter().map(Vec::len).sum())
+            }
+        }
+    }
+
     /// Add one member. Returns true when it was not already present.
     pub fn insert_one(&mut self, member: Vec<u8>) -> bool {
         self.updated_at = Self::current_timestamp();
@@ -290,6 +309,14 @@ impl SetValue {
         }
     }
 
+    /// Borrow every member, in no particular order
+    fn member_slices(&self) -> Vec<&[u8]> {
+        match &self.repr {
+            SetRepr::Packed { buf, .. } => SetRepr::packed_iter(buf).collect(),
+            SetRepr::Hash(h) => h.iter().map(Vec::as_slice).collect(),
+        }
+    }
+
     /// Get all members
     pub fn members(&self) -> Vec<Vec<u8>> {
         match &self.repr {
@@ -298,26 +325,34 @@ impl SetValue {
         }
     }
 
-    /// Pop random member
+    /// Remove and return `count` distinct random members, or all of them if
+    /// there are fewer
     pub fn pop(&mut self, count: usize) -> Vec<Vec<u8>> {
         self.updated_at = Self::current_timestamp();
-        let taken: Vec<Vec<u8>> = self.members().into_iter().take(count).collect();
+        let taken = self.random_members(count);
         for m in &taken {
             self.remove_one(m);
         }
         taken
     }
 
-    /// Get random member(s) without removing
+    /// Get `count` distinct random members without removing them, or all of
+    /// them (shuffled) if there are fewer
     pub fn random_members(&self, count: usize) -> Vec<Vec<u8>> {
         let mut members = self.members();
-        if count >= members.len() {
-            members
-        } else {
-            let mut rng = rand::rng();
-            members.shuffle(&mut rng);
-            members.into_iter().take(count).collect()
-        }
+        let mut rng = rand::rng();
+        let (chosen, _) = members.partial_shuffle(&mut rng, count);
+        chosen.to_vec()
+    }
+
+    /// Get exactly `count` random members, each drawn independently, so the
+    /// same member may come back more than once
+    pub fn random_members_with_repeats(&self, count: usize) -> Vec<Vec<u8>> {
+        let members = self.members();
+        let mut rng = rand::rng();
+        (0..count)
+            .filter_map(|_| members.choose(&mut rng).cloned())
+            .collect()
     }
 }
 
@@ -386,6 +421,8 @@ pub struct SetStore {
     mem_bytes: Arc<std::sync::atomic::AtomicI64>,
     /// Optional keyspace-notification publisher (Redis `notify-keyspace-events`).
     keyspace_notifier: Option<Arc<crate::core::KeyspaceNotifier>>,
+    /// Bounds `SRANDMEMBER` samples; sets have no size limit of their own yet.
+    key_limits: crate::core::KeyLimits,
 }
 
 impl Default for SetStore {
@@ -407,6 +444,37 @@ impl SetStore {
         out
     }
 
+    /// One set, as [`dump`](Self::dump) would persist it
+    pub fn dump_key(&self, key: &str) -> Option<SetValue> {
+        self.shard(key).read().get(key).cloned()
+    }
+
+    /// Replace the set at `key` with `members`, expiring `ttl_secs` from now,
+    /// for full syncs and dump imports. Empty `members` removes the key.
+    pub fn restore(&self, key: &str, members: Vec<Vec<u8>>, ttl_secs: Option<u64>) {
+        let mut map = self.shard(key).write();
+        if members.is_empty() {
+            map.remove(key);
+            return;
+        }
+        let mut set = SetValue::new(ttl_secs);
+        set.add(members);
+        map.insert(key.to_string(), set);
+    }
+
+    /// Remove every set, for full syncs. Returns how many there were.
+    pub fn flush(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut map = shard.write();
+                let flushed = map.len();
+                map.clear();
+                flushed
+            })
+            .sum()
+    }
+
     /// Create new set store
     pub fn new() -> Self {
         let mut shards = Vec::with_capacity(SHARD_COUNT);
@@ -420,6 +488,7 @@ impl SetStore {
             mem: None,
             mem_bytes: Arc::new(std::sync::atomic::AtomicI64::new(0)),
             keyspace_notifier: None,
+            key_limits: crate::core::KeyLimits::default(),
         }
     }
 
@@ -440,6 +509,12 @@ impl SetStore {
         self
     }
 
+    /// Enforce per-key limits; only `max_random_count` applies to sets.
+    pub fn with_key_limits(mut self, limits: crate::core::KeyLimits) -> Self {
+        self.key_limits = limits;
+        self
+    }
+
     /// Publish a set keyspace notification for `key` if a notifier is attached.
     #[inline]
     fn notify_keyspace(&self, event: &str, key: &str) {
@@ -581,10 +656,11 @@ impl SetStore {
 
     /// SSCAN - cursor-based incremental scan of a set's members.
     ///
-    /// `cursor` is an offset into a stably-sorted snapshot of the members; returns
-    /// the next cursor (0 when complete) and the matched members within the
+    /// `cursor` is an offset into the members in sorted order; returns the
+    /// next cursor (0 when complete) and the matched members within the
     /// window. `pattern` is an optional glob over members; `count` bounds the
-    /// window size (min 1). A missing key scans as empty.
+    /// window size (min 1). A missing or expired key scans as empty. Only the
+    /// window is copied out of the set.
     pub fn sscan(
         &self,
         key: &str,
@@ -592,19 +668,21 @@ impl SetStore {
         pattern: Option<&str>,
         count: usize,
     ) -> Result<(u64, Vec<Vec<u8>>)> {
-        let mut members = self.smembers(key).unwrap_or_default();
-        members.sort();
-        let total = members.len();
-        let start = (cursor as usize).min(total);
-        let end = start.saturating_add(count.max(1)).min(total);
-        let items = members[start..end]
-            .iter()
+        let shard = self.shard(key);
+        let map = shard.read();
+
+        let Some(set) = map.get(key).filter(|set| !set.is_expired()) else {
+            return Ok((0, Vec::new()));
+        };
+
+        let (next, window) = crate::core::scan::scan_window(set.member_slices(), cursor, count);
+        let items = window
+            .into_iter()
             .filter(|m| {
-                pattern.is_none_or(|p| crate::core::glob_match(p, &String::from_utf8_lossy(m)))
+                pattern.is_none_or(|p| crate::core::glob::glob_match_bytes(p.as_bytes(), m))
             })
-            .cloned()
+            .map(<[u8]>::to_vec)
             .collect();
-        let next = if end < total { end as u64 } else { 0 };
         Ok((next, items))
     }
 
@@ -624,17 +702,42 @@ impl SetStore {
         Ok(set.len())
     }
 
+    /// Encoding and estimated memory of `key`, `None` when it holds no set.
+    pub fn key_memory(&self, key: &str) -> Option<KeyMemory> {
+        let map = self.shard(key).read();
+        map.get(key)
+            .filter(|v| !v.is_expired())
+            .map(|v| v.memory(key))
+    }
+
+    /// Estimated memory of every live set, with the `top` largest by bytes.
+    pub fn memory_profile(&self, top: usize) -> crate::core::MemoryProfile {
+        let mut profiler = crate::core::memory_profile::Profiler::new(top);
+        for shard in self.shards.iter() {
+            for (key, v) in shard.read().iter() {
+                if !v.is_expired() {
+                    profiler.offer(key, v.memory(key));
+                }
+            }
+        }
+        profiler.finish()
+    }
+
     /// SPOP - Remove and return random member(s)
+    ///
+    /// Pops one member when `count` is `None`. A missing key pops nothing.
     pub fn spop(&self, key: &str, count: Option<usize>) -> Result<Vec<Vec<u8>>> {
         let shard = self.shard(key);
         let mut map = shard.write();
 
-        let set = map.get_mut(key).ok_or(SynapError::NotFound)?;
+        let Some(set) = map.get_mut(key) else {
+            return Ok(Vec::new());
+        };
 
         // Check expiration
         if set.is_expired() {
             map.remove(key);
-            return Err(SynapError::KeyExpired);
+            return Ok(Vec::new());
         }
 
         let count = count.unwrap_or(1);
@@ -650,20 +753,32 @@ impl SetStore {
     }
 
     /// SRANDMEMBER - Get random member(s) without removing
-    pub fn srandmember(&self, key: &str, count: Option<usize>) -> Result<Vec<Vec<u8>>> {
+    ///
+    /// As in Redis, a positive `count` returns up to that many distinct
+    /// members and a negative one returns exactly `-count` members, possibly
+    /// repeated, refused past [`KeyLimits::max_random_count`]. `None` returns
+    /// one member; a missing key returns none.
+    ///
+    /// [`KeyLimits::max_random_count`]: crate::core::KeyLimits::max_random_count
+    pub fn srandmember(&self, key: &str, count: Option<i64>) -> Result<Vec<Vec<u8>>> {
+        let count = count.unwrap_or(1);
+        if count < 0 {
+            self.key_limits.check_random_count(count.unsigned_abs())?;
+        }
+
         let shard = self.shard(key);
         let map = shard.read();
 
-        let set = map.get(key).ok_or(SynapError::NotFound)?;
-
-        // Check expiration
-        if set.is_expired() {
-            return Err(SynapError::KeyExpired);
-        }
+        let Some(set) = map.get(key).filter(|set| !set.is_expired()) else {
+            return Ok(Vec::new());
+        };
 
-        let count = count.unwrap_or(1);
         self.stats.srandmember_count.fetch_add(1, Ordering::Relaxed);
-        Ok(set.random_members(count))
+        if count < 0 {
+            Ok(set.random_members_with_repeats(count.unsigned_abs() as usize))
+        } else {
+            Ok(set.random_members(count as usize))
+        }
     }
 
     /// SMOVE - Move member from source to destination
@@ -852,52 +967,81 @@ impl SetStore {
         Ok(result.into_iter().collect())
     }
 
-    /// SINTERSTORE - Store intersection in destination
-    pub fn sinterstore(&self, destination: &str, keys: &[String]) -> Result<usize> {
-        let members = self.sinter(keys)?;
-        let count = members.len();
+    /// SINTERCARD - Size of the intersection of multiple sets
+    ///
+    /// Stops counting at `limit` when it is not 0, so callers that only need
+    /// to know whether the overlap reaches a threshold skip the rest.
+    pub fn sintercard(&self, keys: &[String], limit: usize) -> Result<usize> {
+        let Some((first, rest)) = keys.split_first() else {
+            return Ok(0);
+        };
 
-        // Store result in destination
-        let shard = self.shard(destination);
-        let mut map = shard.write();
+        let read = |key: &String| {
+            let map = self.shard(key).read();
+            map.get(key)
+                .filter(|s| !s.is_expired())
+                .map(|s| s.to_hash_set())
+        };
+        let Some(first) = read(first) else {
+            return Ok(0);
+        };
+        let mut others = Vec::with_capacity(rest.len());
+        for key in rest {
+            match read(key) {
+                Some(set) => others.push(set),
+                None => return Ok(0), // Missing set means empty intersection
+            }
+        }
 
-        let mut set = SetValue::new(None);
-        set.replace_members(members.into_iter().collect());
-        map.insert(destination.to_string(), set);
+        let limit = if limit == 0 { usize::MAX } else { limit };
+        let count = first
+            .iter()
+            .filter(|member| others.iter().all(|set| set.contains(*member)))
+            .take(limit)
+            .count();
 
+        self.stats.sinter_count.fetch_add(1, Ordering::Relaxed);
         Ok(count)
     }
 
-    /// SUNIONSTORE - Store union in destination
-    pub fn sunionstore(&self, destination: &str, keys: &[String]) -> Result<usize> {
-        let members = self.sunion(keys)?;
+    /// Replace `destination` with `members`, removing it when there are none
+    fn store_result(&self, destination: &str, members: Vec<Vec<u8>>, event: &str) -> usize {
         let count = members.len();
-
-        // Store result in destination
         let shard = self.shard(destination);
         let mut map = shard.write();
 
+        if members.is_empty() {
+            if map.remove(destination).is_some() {
+                drop(map);
+                self.notify_keyspace("del", destination);
+            }
+            return 0;
+        }
+
         let mut set = SetValue::new(None);
-        set.replace_members(members.into_iter().collect());
+        set.replace_members(members);
         map.insert(destination.to_string(), set);
+        drop(map);
+        self.notify_keyspace(event, destination);
+        count
+    }
 
-        Ok(count)
+    /// SINTERSTORE - Store intersection in destination
+    pub fn sinterstore(&self, destination: &str, keys: &[String]) -> Result<usize> {
+        let members = self.sinter(keys)?;
+        Ok(self.store_result(destination, members, "sinterstore"))
+    }
+
+    /// SUNIONSTORE - Store union in destination
+    pub fn sunionstore(&self, destination: &str, keys: &[String]) -> Result<usize> {
+        let members = self.sunion(keys)?;
+        Ok(self.store_result(destination, members, "sunionstore"))
     }
 
     /// SDIFFSTORE - Store difference in destination
     pub fn sdiffstore(&self, destination: &str, keys: &[String]) -> Result<usize> {
         let members = self.sdiff(keys)?;
-        let count = members.len();
-
-        // Store result in destination
-        let shard = self.shard(destination);
-        let mut map = shard.write();
-
-        let mut set = SetValue::new(None);
-        set.replace_members(members.into_iter().collect());
-        map.insert(destination.to_string(), set);
-
-        Ok(count)
+        Ok(self.store_result(destination, members, "sdiffstore"))
     }
 
     /// Get statistics
@@ -1154,6 +1298,66 @@ mod tests {
         assert_eq!(all.len(), 3);
     }
 
+    #[test]
+    fn test_srandmember_count_semantics() {
+        let store = seeded();
+
+        // Negative counts may repeat members and always return -count of them
+        let repeated = store.srandmember("s1", Some(-10)).unwrap();
+        assert_eq!(repeated.len(), 10);
+        assert!(
+            repeated
+                .iter()
+                .all(|m| store.sismember("s1", m.clone()).unwrap())
+        );
+
+        let distinct = store.srandmember("s1", Some(3)).unwrap();
+        let unique: HashSet<_> = distinct.iter().collect();
+        assert_eq!(unique.len(), 3);
+
+        assert!(store.srandmember("s1", Some(0)).unwrap().is_empty());
+        assert_eq!(store.srandmember("s1", None).unwrap().len(), 1);
+        assert!(store.srandmember("missing", Some(-3)).unwrap().is_empty());
+        assert_eq!(store.scard("s1").unwrap(), 3);
+    }
+
+    #[test]
+    fn test_srandmember_repeats_are_bounded() {
+        let store = seeded();
+        assert!(matches!(
+            store.srandmember("s1", Some(i64::MIN)),
+            Err(SynapError::InvalidRequest(_))
+        ));
+
+        let store = seeded().with_key_limits(crate::core::KeyLimits {
+            max_random_count: Some(4),
+            ..Default::default()
+        });
+        assert_eq!(store.srandmember("s1", Some(-4)).unwrap().len(), 4);
+        assert!(store.srandmember("s1", Some(-5)).is_err());
+        assert!(store.srandmember("missing", Some(-5)).is_err());
+        // Distinct samples are bounded by the set itself
+        assert_eq!(store.srandmember("s1", Some(i64::MAX)).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_spop_is_random_and_drains() {
+        // Popping one member of a two-member set many times must pick
+        // each of them at some point
+        let mut seen = HashSet::new();
+        for _ in 0..64 {
+            let store = SetStore::new();
+            store.sadd("s", vec![b"a".to_vec(), b"b".to_vec()]).unwrap();
+            seen.extend(store.spop("s", None).unwrap());
+        }
+        assert_eq!(seen.len(), 2);
+
+        let store = seeded();
+        assert_eq!(store.spop("s1", Some(10)).unwrap().len(), 3);
+        assert!(!store.exists("s1"));
+        assert!(store.spop("s1", Some(1)).unwrap().is_empty());
+    }
+
     #[test]
     fn test_smove_between_sets() {
         let store = seeded();
@@ -1199,6 +1403,43 @@ mod tests {
         assert!(!store.delete("s1").unwrap());
     }
 
+    #[test]
+    fn test_sintercard() {
+        let store = seeded();
+        let keys = vec!["s1".to_string(), "s2".to_string()];
+
+        assert_eq!(store.sintercard(&keys, 0).unwrap(), 2);
+        assert_eq!(store.sintercard(&keys, 1).unwrap(), 1);
+        assert_eq!(store.sintercard(&keys, 10).unwrap(), 2);
+        assert_eq!(
+            store
+                .sintercard(&["s1".to_string(), "missing".to_string()], 0)
+                .unwrap(),
+            0
+        );
+        assert_eq!(store.sintercard(&[], 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_store_variants_replace_destination() {
+        let store = seeded();
+        store.sadd("dest", vec![b"old".to_vec()]).unwrap();
+
+        let keys = vec!["s1".to_string(), "s2".to_string()];
+        assert_eq!(store.sunionstore("dest", &keys).unwrap(), 4);
+        assert!(!store.sismember("dest", b"old".to_vec()).unwrap());
+
+        // The destination may be one of the sources
+        let keys = vec!["dest".to_string(), "s2".to_string()];
+        assert_eq!(store.sdiffstore("dest", &keys).unwrap(), 1);
+        assert_eq!(store.smembers("dest").unwrap(), vec![b"a".to_vec()]);
+
+        // An empty result removes the destination
+        let keys = vec!["s1".to_string(), "missing".to_string()];
+        assert_eq!(store.sinterstore("dest", &keys).unwrap(), 0);
+        assert!(!store.exists("dest"));
+    }
+
     #[test]
     fn test_value_helpers() {
         let mut v = SetValue::new(None);
@@ -1252,4 +1493,26 @@ mod tests {
         assert_eq!(items.len(), 2);
         assert_eq!(store.sscan("missing", 0, None, 10).unwrap(), (0, vec![]));
     }
+
+    #[test]
+    fn test_memory_reports_encoding() {
+        let store = SetStore::new();
+        store
+            .sadd("small", vec![b"a".to_vec(), b"bc".to_vec()])
+            .unwrap();
+        let small = store.key_memory("small").unwrap();
+        assert_eq!(small.encoding, "packed");
+        assert_eq!((small.elements, small.item_bytes), (2, 3));
+
+        let many = (0..200).map(|i| format!("m{i}").into_bytes()).collect();
+        store.sadd("big", many).unwrap();
+        assert_eq!(store.key_memory("big").unwrap().encoding, "hashset");
+        assert!(store.key_memory("missing").is_none());
+
+        let profile = store.memory_profile(1);
+        assert_eq!(profile.keys, 2);
+        assert_eq!(profile.elements, 202);
+        assert_eq!(profile.encodings.get("packed"), Some(&1));
+        assert_eq!(profile.biggest[0].key, "big");
+    }
 }
@@ -0,0 +1,496 @@
+//! Leaderboard notifications for sorted sets.
+//!
+//! A leaderboard UI cares about a handful of changes — a member entering or
+//! leaving the top of the board, or jumping a long way — not about every
+//! `ZADD`. Polling `ZREVRANGE` to find them costs a full read per client per
+//! tick. Instead, a key can be given a [`LeaderboardWindow`]; mutations of that
+//! key then publish [`LeaderboardEvent`]s on `__zset@0__:<key>` through the
+//! same [`PubSubRouter`] KV watch uses, with the same best-effort delivery and
+//! slow-consumer policy.
+//!
+//! Ranks are 0-based from the highest score, as returned by `ZREVRANK`.
+//!
+//! # Cost
+//!
+//! A key with no window, or whose channel has no subscriber, costs one map
+//! lookup per mutation. A watched key copies its top `top_n` members before and
+//! after each mutation, which is why `top_n` is capped at
+//! [`MAX_LEADERBOARD_TOP_N`]. Full ranks (an O(n) count) are only computed for
+//! members an event is actually published for.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use super::{OrderedFloat, SortedSetValue};
+use crate::core::SynapError;
+use crate::core::pubsub::PubSubRouter;
+
+/// Largest `top_n` a window may watch.
+pub const MAX_LEADERBOARD_TOP_N: usize = 1000;
+
+/// Which changes of a sorted set are published to its watchers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LeaderboardWindow {
+    /// Size of the watched top of the board. A member moving into or out of
+    /// the `top_n` highest scores publishes `entered` / `left`.
+    pub top_n: usize,
+    /// Publish `rank_changed` when a score update moves a member past at least
+    /// this many others. `None` publishes top-N changes only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rank_threshold: Option<usize>,
+}
+
+impl LeaderboardWindow {
+    /// Reject windows that would watch nothing or cost too much per write.
+    pub fn validate(&self) -> Result<(), SynapError> {
+        if self.top_n == 0 || self.top_n > MAX_LEADERBOARD_TOP_N {
+            return Err(SynapError::InvalidValue(format!(
+                "top_n must be between 1 and {MAX_LEADERBOARD_TOP_N}, got {}",
+                self.top_n
+            )));
+        }
+        if self.rank_threshold == Some(0) {
+            return Err(SynapError::InvalidValue(
+                "rank_threshold must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One leaderboard change delivered to watchers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LeaderboardEvent {
+    /// The sorted set that changed.
+    pub key: String,
+    /// `entered` or `left` the top N, or `rank_changed`.
+    pub event: String,
+    /// The member, decoded as UTF-8 (lossily for binary members).
+    pub member: String,
+    /// The member's score after the change; absent once it was removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// The member's rank after the change; absent once it was removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rank: Option<usize>,
+    /// The member's rank before the change, when known: it was inside the
+    /// window, or its own score changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_rank: Option<usize>,
+    /// Per-key counter, so a client can detect a gap after a slow-consumer
+    /// disconnect.
+    pub version: u64,
+}
+
+/// A watched key's window and event counter.
+struct Board {
+    window: LeaderboardWindow,
+    version: AtomicU64,
+}
+
+/// A watched board just before a mutation, taken under the shard lock.
+pub(super) struct BoardSnapshot {
+    board: Arc<Board>,
+    /// The top `top_n` members, highest score first.
+    top: Vec<Vec<u8>>,
+    /// The member whose score the mutation sets, with its score before.
+    member: Option<(Vec<u8>, Option<f64>)>,
+}
+
+/// Publishes leaderboard events for the sorted sets that have a window.
+pub struct LeaderboardNotifier {
+    pubsub: Arc<PubSubRouter>,
+    db: u32,
+    boards: RwLock<HashMap<String, Arc<Board>>>,
+}
+
+impl LeaderboardNotifier {
+    /// Create a notifier bound to `pubsub`. `db` is the logical database index
+    /// in the channel name, `0` in practice.
+    pub fn new(pubsub: Arc<PubSubRouter>, db: u32) -> Self {
+        Self {
+            pubsub,
+            db,
+            boards: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The channel a watcher subscribes to for `key`.
+    pub fn channel_for(&self, key: &str) -> String {
+        format!("__zset@{}__:{}", self.db, key)
+    }
+
+    /// Watch `key` with `window`, replacing any previous window. The event
+    /// counter carries over, so versions stay monotonic.
+    pub fn set_window(&self, key: &str, window: LeaderboardWindow) -> Result<(), SynapError> {
+        window.validate()?;
+        let mut boards = self.boards.write();
+        let version = boards
+            .get(key)
+            .map_or(0, |board| board.version.load(Ordering::Relaxed));
+        boards.insert(
+            key.to_string(),
+            Arc::new(Board {
+                window,
+                version: AtomicU64::new(version),
+            }),
+        );
+        Ok(())
+    }
+
+    /// The window `key` is watched with.
+    pub fn window(&self, key: &str) -> Option<LeaderboardWindow> {
+        self.boards.read().get(key).map(|board| board.window)
+    }
+
+    /// Stop watching `key`. Returns whether it had a window.
+    pub fn remove_window(&self, key: &str) -> bool {
+        self.boards.write().remove(key).is_some()
+    }
+
+    /// Every watched key and its window.
+    pub fn windows(&self) -> Vec<(String, LeaderboardWindow)> {
+        self.boards
+            .read()
+            .iter()
+            .map(|(key, board)| (key.clone(), board.window))
+            .collect()
+    }
+
+    /// Capture `key` before a mutation, or `None` when nobody would see the
+    /// result. `member` is the member whose score the mutation sets, if any.
+    pub(super) fn snapshot(
+        &self,
+        key: &str,
+        zset: Option<&SortedSetValue>,
+        member: Option<&[u8]>,
+    ) -> Option<BoardSnapshot> {
+        let board = self.boards.read().get(key).cloned()?;
+        if !self.pubsub.has_subscriber(&self.channel_for(key)) {
+            return None;
+        }
+        let top = zset
+            .map(|z| {
+                top_entries(z, board.window.top_n)
+                    .map(|(_, m)| m.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let member = member.map(|m| (m.to_vec(), zset.and_then(|z| z.zscore(m))));
+        Some(BoardSnapshot { board, top, member })
+    }
+
+    /// The events between `before` and the mutated `zset` (`None` when the
+    /// key is gone). Called under the same shard lock as [`Self::snapshot`],
+    /// so versions follow the order of the writes.
+    pub(super) fn changes(
+        &self,
+        key: &str,
+        before: BoardSnapshot,
+        zset: Option<&SortedSetValue>,
+    ) -> Vec<LeaderboardEvent> {
+        let window = before.board.window;
+        let after: Vec<(f64, &[u8])> = zset
+            .map(|z| {
+                top_entries(z, window.top_n)
+                    .map(|(s, m)| (s.get(), m.as_slice()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let was_top: HashSet<&[u8]> = before.top.iter().map(Vec::as_slice).collect();
+        let is_top: HashSet<&[u8]> = after.iter().map(|(_, m)| *m).collect();
+
+        let score_of = |member: &[u8]| zset.and_then(|z| z.zscore(member));
+        let rank_of = |member: &[u8]| zset.and_then(|z| z.zrevrank(member));
+
+        // Where the member whose score was set came from, and how far it moved
+        let moved = before.member.as_ref().and_then(|(member, old)| {
+            let (z, old) = (zset?, (*old)?);
+            let new = z.zscore(member)?;
+            Some((member.as_slice(), new > old, passed(z, member, old, new)))
+        });
+        let previous_rank_of = |member: &[u8], rank: Option<usize>| {
+            let (moved_member, up, passed) = moved?;
+            if moved_member != member {
+                return None;
+            }
+            let rank = rank?;
+            Some(if up { rank + passed } else { rank - passed })
+        };
+
+        let mut events = Vec::new();
+        let mut event = |kind: &str, member: &[u8], score, rank, previous_rank| {
+            events.push(LeaderboardEvent {
+                key: key.to_string(),
+                event: kind.to_string(),
+                member: String::from_utf8_lossy(member).into_owned(),
+                score,
+                rank,
+                previous_rank,
+                version: before.board.version.fetch_add(1, Ordering::Relaxed) + 1,
+            });
+        };
+
+        for (previous_rank, member) in before.top.iter().enumerate() {
+            if !is_top.contains(member.as_slice()) {
+                let score = score_of(member);
+                let rank = score.and_then(|_| rank_of(member));
+                event("left", member, score, rank, Some(previous_rank));
+            }
+        }
+        for (rank, (score, member)) in after.iter().enumerate() {
+            if !was_top.contains(member) {
+                let previous_rank = previous_rank_of(member, Some(rank));
+                event("entered", member, Some(*score), Some(rank), previous_rank);
+            }
+        }
+        if let (Some((member, _, passed)), Some(threshold)) = (moved, window.rank_threshold) {
+            // Entering or leaving the window was already reported
+            let crossed = was_top.contains(member) != is_top.contains(member);
+            if !crossed && passed >= threshold {
+                let rank = match after.iter().position(|(_, m)| *m == member) {
+                    Some(rank) => Some(rank),
+                    None => rank_of(member),
+                };
+                let previous_rank = previous_rank_of(member, rank);
+                event(
+                    "rank_changed",
+                    member,
+                    score_of(member),
+                    rank,
+                    previous_rank,
+                );
+            }
+        }
+        events
+    }
+
+    /// Publish `events`, after the shard lock is released.
+    pub(super) fn publish(&self, key: &str, events: Vec<LeaderboardEvent>) {
+        let channel = self.channel_for(key);
+        for event in events {
+            // A notification must never fail the command that produced it
+            if let Ok(json) = serde_json::to_value(&event) {
+                let _ = self.pubsub.publish(&channel, json, None);
+            }
+        }
+    }
+}
+
+/// The `n` highest-scored entries, highest first.
+fn top_entries(zset: &SortedSetValue, n: usize) -> impl Iterator<Item = &(OrderedFloat, Vec<u8>)> {
+    zset.sorted.keys().rev().take(n)
+}
+
+/// How many other members `member` passed moving from score `old` to `new`.
+fn passed(zset: &SortedSetValue, member: &[u8], old: f64, new: f64) -> usize {
+    let (old, new) = (OrderedFloat::new(old), OrderedFloat::new(new));
+    if old == new {
+        return 0;
+    }
+    let (low, high) = if old < new { (old, new) } else { (new, old) };
+    zset.sorted
+        .range((
+            Bound::Excluded((low, member.to_vec())),
+            Bound::Excluded((high, member.to_vec())),
+        ))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SortedSetStore;
+
+    fn board(window: LeaderboardWindow) -> (Arc<PubSubRouter>, SortedSetStore) {
+        let router = Arc::new(PubSubRouter::new());
+        let notifier = Arc::new(LeaderboardNotifier::new(Arc::clone(&router), 0));
+        notifier.set_window("board", window).unwrap();
+        let store = SortedSetStore::new().with_leaderboard_notifier(Some(notifier));
+        (router, store)
+    }
+
+    fn top(n: usize) -> LeaderboardWindow {
+        LeaderboardWindow {
+            top_n: n,
+            rank_threshold: None,
+        }
+    }
+
+    type Receiver = tokio::sync::mpsc::Receiver<crate::core::Message>;
+
+    /// Subscribe to the board's channel.
+    fn watch(router: &PubSubRouter) -> Receiver {
+        let id = router
+            .subscribe(vec!["__zset@0__:board".to_string()])
+            .unwrap()
+            .subscriber_id;
+        let (tx, rx) = tokio::sync::mpsc::channel(64);
+        router.register_connection(id, tx);
+        rx
+    }
+
+    fn events(rx: &mut Receiver) -> Vec<LeaderboardEvent> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|message| serde_json::from_value(message.payload).unwrap())
+            .collect()
+    }
+
+    fn add(store: &SortedSetStore, member: &str, score: f64) {
+        store.zadd("board", member.into(), score, &Default::default());
+    }
+
+    /// Events published so far, as `(event, member, rank, previous_rank)`.
+    fn drain(rx: &mut Receiver) -> Vec<(String, String, Option<usize>, Option<usize>)> {
+        events(rx)
+            .into_iter()
+            .map(|e| (e.event, e.member, e.rank, e.previous_rank))
+            .collect()
+    }
+
+    fn ev(
+        event: &str,
+        member: &str,
+        rank: Option<usize>,
+        previous_rank: Option<usize>,
+    ) -> (String, String, Option<usize>, Option<usize>) {
+        (event.to_string(), member.to_string(), rank, previous_rank)
+    }
+
+    #[test]
+    fn windows_are_validated() {
+        let notifier = LeaderboardNotifier::new(Arc::new(PubSubRouter::new()), 0);
+        assert!(notifier.set_window("k", top(0)).is_err());
+        assert!(
+            notifier
+                .set_window("k", top(MAX_LEADERBOARD_TOP_N + 1))
+                .is_err()
+        );
+        let zero_threshold = LeaderboardWindow {
+            top_n: 10,
+            rank_threshold: Some(0),
+        };
+        assert!(notifier.set_window("k", zero_threshold).is_err());
+        assert!(notifier.window("k").is_none());
+
+        notifier.set_window("k", top(10)).unwrap();
+        assert_eq!(notifier.window("k"), Some(top(10)));
+        assert!(notifier.remove_window("k"));
+        assert!(!notifier.remove_window("k"));
+    }
+
+    #[test]
+    fn an_unwatched_board_publishes_nothing() {
+        let (router, store) = board(top(3));
+        add(&store, "alice", 10.0);
+        store.zadd("other", b"bob".to_vec(), 5.0, &Default::default());
+        assert_eq!(router.get_stats().messages_published, 0);
+    }
+
+    #[test]
+    fn entering_the_top_pushes_the_last_member_out() {
+        let (router, store) = board(top(2));
+        let mut rx = watch(&router);
+
+        add(&store, "alice", 30.0);
+        add(&store, "bob", 20.0);
+        assert_eq!(
+            drain(&mut rx),
+            vec![
+                ev("entered", "alice", Some(0), None),
+                ev("entered", "bob", Some(1), None),
+            ]
+        );
+
+        // Below the window: nothing to report
+        add(&store, "carol", 10.0);
+        assert!(drain(&mut rx).is_empty());
+
+        store.zincrby("board", b"carol".to_vec(), 15.0);
+        assert_eq!(
+            drain(&mut rx),
+            vec![
+                ev("left", "bob", Some(2), Some(1)),
+                ev("entered", "carol", Some(1), Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn removal_reports_left_and_promotes_the_next_member() {
+        let (router, store) = board(top(2));
+        add(&store, "alice", 30.0);
+        add(&store, "bob", 20.0);
+        add(&store, "carol", 10.0);
+        let mut rx = watch(&router);
+
+        store.zrem("board", &[b"alice".to_vec()]);
+        assert_eq!(
+            drain(&mut rx),
+            vec![
+                ev("left", "alice", None, Some(0)),
+                ev("entered", "carol", Some(1), None),
+            ]
+        );
+
+        store.delete("board");
+        assert_eq!(
+            drain(&mut rx),
+            vec![
+                ev("left", "bob", None, Some(0)),
+                ev("left", "carol", None, Some(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rank_changes_below_the_threshold_are_quiet() {
+        let (router, store) = board(LeaderboardWindow {
+            top_n: 1,
+            rank_threshold: Some(3),
+        });
+        for (i, name) in ["a", "b", "c", "d", "e", "f"].iter().enumerate() {
+            add(&store, name, (60 - i * 10) as f64);
+        }
+        let mut rx = watch(&router);
+
+        // f (rank 5) passes e and d: two places
+        add(&store, "f", 35.0);
+        assert!(drain(&mut rx).is_empty());
+
+        // d (now rank 4) passes f, c and b: three places
+        add(&store, "d", 55.0);
+        assert_eq!(
+            drain(&mut rx),
+            vec![ev("rank_changed", "d", Some(1), Some(4))]
+        );
+
+        // And back down
+        add(&store, "d", 1.0);
+        assert_eq!(
+            drain(&mut rx),
+            vec![ev("rank_changed", "d", Some(5), Some(1))]
+        );
+    }
+
+    #[test]
+    fn versions_are_monotonic_across_window_changes() {
+        let router = Arc::new(PubSubRouter::new());
+        let notifier = Arc::new(LeaderboardNotifier::new(Arc::clone(&router), 0));
+        notifier.set_window("board", top(5)).unwrap();
+        let store = SortedSetStore::new().with_leaderboard_notifier(Some(notifier.clone()));
+        let mut rx = watch(&router);
+
+        add(&store, "a", 1.0);
+        notifier.set_window("board", top(10)).unwrap();
+        add(&store, "b", 2.0);
+
+        let versions: Vec<u64> = events(&mut rx).iter().map(|e| e.version).collect();
+        assert_eq!(versions, vec![1, 2]);
+    }
+}
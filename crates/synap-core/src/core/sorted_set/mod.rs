@@ -6,6 +6,7 @@
 //!
 //! Use cases: leaderboards, priority queues, time-series, rate limiting
 
+use crate::core::memory_profile::KeyMemory;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap};
@@ -213,6 +214,12 @@ impl SortedSetValue {
         self.scores.len()
     }
 
+    /// Encoding and estimated memory of the sorted set stored under `key`
+    pub fn memory(&self, key: &str) -> KeyMemory {
+        let item_bytes = self.scores.keys().map(Vec::len).sum();
+        KeyMemory::new(key, "skiplist", self.scores.len(), item_bytes)
+    }
+
     /// Increment score of member
     /// Returns new score
     pub fn zincrby(&mut self, member: Vec<u8>, increment: f64) -> f64 {
@@ -434,6 +441,14 @@ impl SortedSetValue {
     pub fn zmscore(&self, members: &[Vec<u8>]) -> Vec<Option<f64>> {
         members.iter().map(|member| self.zscore(member)).collect()
     }
+
+    /// Borrow every member with its score, in no particular order
+    pub(crate) fn member_scores(&self) -> Vec<(&[u8], OrderedFloat)> {
+        self.scores
+            .iter()
+            .map(|(m, s)| (m.as_slice(), *s))
+            .collect()
+    }
 }
 
 impl Default for SortedSetValue {
@@ -442,7 +457,11 @@ impl Default for SortedSetValue {
     }
 }
 
+mod leaderboard;
 mod store;
+pub use leaderboard::{
+    LeaderboardEvent, LeaderboardNotifier, LeaderboardWindow, MAX_LEADERBOARD_TOP_N,
+};
 pub use store::{Aggregate, SortedSetStats, SortedSetStore};
 
 #[cfg(test)]
@@ -527,6 +546,41 @@ mod tests {
         assert_eq!(score, 3.5);
     }
 
+    #[test]
+    fn test_zset_member_limit() {
+        let store = SortedSetStore::new().with_key_limits(crate::core::KeyLimits {
+            max_zset_members: Some(2),
+            ..Default::default()
+        });
+        let opts = ZAddOptions::default();
+        store.try_zadd("z", b"a".to_vec(), 1.0, &opts).unwrap();
+        store.try_zadd("z", b"b".to_vec(), 2.0, &opts).unwrap();
+
+        let err = store.try_zadd("z", b"c".to_vec(), 3.0, &opts);
+        assert!(matches!(
+            err,
+            Err(crate::core::SynapError::KeyLimitExceeded { .. })
+        ));
+        assert!(store.try_zincrby("z", b"c".to_vec(), 1.0).is_err());
+        assert_eq!(store.zcard("z"), 2);
+
+        // Updating an existing member, or XX on a missing one, does not grow
+        store.try_zadd("z", b"a".to_vec(), 5.0, &opts).unwrap();
+        assert_eq!(store.try_zincrby("z", b"b".to_vec(), 1.0).unwrap(), 3.0);
+        let xx = ZAddOptions {
+            xx: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            store.try_zadd("z", b"c".to_vec(), 1.0, &xx).unwrap(),
+            (0, 0)
+        );
+
+        // The unchecked path used by recovery and replication is not limited
+        store.zadd("z", b"c".to_vec(), 3.0, &opts);
+        assert_eq!(store.largest_keys(1)[0].size, 3);
+    }
+
     #[test]
     fn test_zrange() {
         let mut zset = SortedSetValue::new();
@@ -735,6 +789,39 @@ mod tests {
         assert_eq!(store.zscore("zd", b"a"), Some(1.0));
     }
 
+    #[test]
+    fn test_store_set_operations_without_destination() {
+        let store = SortedSetStore::new();
+        seed(&store, "z1", &[("a", 1.0), ("b", 2.0), ("c", 3.0)]);
+        seed(&store, "z2", &[("b", 10.0), ("c", 20.0), ("d", 30.0)]);
+
+        let inter = store.zinter(&["z1", "z2"], None, Aggregate::Sum);
+        let pairs: Vec<_> = inter
+            .iter()
+            .map(|m| (m.member.as_slice(), m.score))
+            .collect();
+        assert_eq!(pairs, vec![(&b"b"[..], 12.0), (&b"c"[..], 23.0)]);
+
+        // Ordered by aggregated score: a=1, b=min(2,20)=2, c=min(3,40)=3, d=60.
+        let union = store.zunion(&["z1", "z2"], Some(&[1.0, 2.0]), Aggregate::Min);
+        let members: Vec<_> = union.iter().map(|m| m.member.as_slice()).collect();
+        assert_eq!(members, vec![&b"a"[..], b"b", b"c", b"d"]);
+        assert_eq!(union[3].score, 60.0);
+
+        let diff = store.zdiff(&["z2", "z1"]);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].member, b"d");
+
+        // A missing source empties an intersection; nothing is written anywhere.
+        assert!(
+            store
+                .zinter(&["z1", "missing"], None, Aggregate::Sum)
+                .is_empty()
+        );
+        assert!(store.zdiff(&[]).is_empty());
+        assert_eq!(store.stats().total_keys, 2);
+    }
+
     #[test]
     fn test_value_ttl_helpers() {
         let mut v = SortedSetValue::with_ttl(3600);
@@ -4,6 +4,7 @@
 //! The value type (`SortedSetValue`) and its helpers (`OrderedFloat`,
 //! `ScoredMember`, `ZAddOptions`) live in the parent module; this file holds
 //! the store-level API, its stats and the `Aggregate` mode.
+use super::leaderboard::{BoardSnapshot, LeaderboardEvent, LeaderboardNotifier};
 use super::{ScoredMember, SortedSetValue, ZAddOptions};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
@@ -28,6 +29,17 @@ pub enum Aggregate {
     Max,
 }
 
+impl Aggregate {
+    /// Combine one member's (already weighted) scores from each source set.
+    fn apply(self, scores: &[f64]) -> f64 {
+        match self {
+            Aggregate::Sum => scores.iter().sum(),
+            Aggregate::Min => scores.iter().cloned().fold(f64::INFINITY, f64::min),
+            Aggregate::Max => scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
 /// Sorted Set store with 64-way sharding
 pub struct SortedSetStore {
     shards: [Arc<RwLock<HashMap<String, SortedSetValue>>>; 64],
@@ -42,6 +54,11 @@ pub struct SortedSetStore {
     notify_channels: std::sync::atomic::AtomicU64,
     /// Optional keyspace-notification publisher (Redis `notify-keyspace-events`).
     keyspace_notifier: Option<Arc<crate::core::KeyspaceNotifier>>,
+    /// Optional leaderboard-notification publisher for keys with a window.
+    leaderboard_notifier: Option<Arc<LeaderboardNotifier>>,
+    /// Per-key member cap (`max_zset_members`), enforced by `try_zadd` and
+    /// `try_zincrby`.
+    key_limits: crate::core::KeyLimits,
 }
 
 impl SortedSetStore {
@@ -62,6 +79,64 @@ impl SortedSetStore {
         out
     }
 
+    /// Members and scores of one sorted set, as [`dump`](Self::dump) would
+    /// persist them
+    pub fn dump_key(&self, key: &str) -> Option<Vec<(Vec<u8>, f64)>> {
+        let guard = self.shards[self.shard_index(key)].read();
+        guard.get(key).map(|v| {
+            v.members_with_scores()
+                .into_iter()
+                .map(|sm| (sm.member, sm.score))
+                .collect()
+        })
+    }
+
+    /// Seconds left before the sorted set at `key` expires, `None` if it has
+    /// no TTL or does not exist
+    pub fn ttl(&self, key: &str) -> Option<u64> {
+        let guard = self.shards[self.shard_index(key)].read();
+        guard
+            .get(key)
+            .and_then(SortedSetValue::ttl)
+            .map(|secs| secs.max(0) as u64)
+    }
+
+    /// Replace the sorted set at `key` with `members`, expiring `ttl_secs`
+    /// from now, for full syncs and dump imports. Empty `members` removes the
+    /// key.
+    pub fn restore(&self, key: &str, members: Vec<(Vec<u8>, f64)>, ttl_secs: Option<u64>) {
+        {
+            let mut guard = self.shards[self.shard_index(key)].write();
+            if members.is_empty() {
+                guard.remove(key);
+                return;
+            }
+            let mut value = match ttl_secs {
+                Some(ttl) => SortedSetValue::with_ttl(ttl.min(u32::MAX as u64) as u32),
+                None => SortedSetValue::new(),
+            };
+            let opts = ZAddOptions::default();
+            for (member, score) in members {
+                value.zadd(member, score, &opts);
+            }
+            guard.insert(key.to_string(), value);
+        }
+        self.notify_waiters(key);
+    }
+
+    /// Remove every sorted set, for full syncs. Returns how many there were.
+    pub fn flush(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                let mut map = shard.write();
+                let flushed = map.len();
+                map.clear();
+                flushed
+            })
+            .sum()
+    }
+
     /// Create a new sorted set store
     pub fn new() -> Self {
         Self {
@@ -73,6 +148,8 @@ impl SortedSetStore {
             notify_tx: Arc::new(RwLock::new(HashMap::new())),
             notify_channels: std::sync::atomic::AtomicU64::new(0),
             keyspace_notifier: None,
+            leaderboard_notifier: None,
+            key_limits: crate::core::KeyLimits::default(),
         }
     }
 
@@ -190,6 +267,114 @@ impl SortedSetStore {
         self
     }
 
+    /// Attach a leaderboard-notification publisher so keys given a window
+    /// publish `entered` / `left` / `rank_changed` events. A no-op when
+    /// `notifier` is `None`.
+    pub fn with_leaderboard_notifier(mut self, notifier: Option<Arc<LeaderboardNotifier>>) -> Self {
+        self.leaderboard_notifier = notifier;
+        self
+    }
+
+    /// Attach per-key limits. Only `max_zset_members` applies to sorted sets,
+    /// and only through the `try_` writers.
+    pub fn with_key_limits(mut self, limits: crate::core::KeyLimits) -> Self {
+        self.key_limits = limits;
+        self
+    }
+
+    /// The `n` sorted sets with the most members, largest first.
+    pub fn largest_keys(&self, n: usize) -> Vec<crate::core::KeySize> {
+        let mut largest = crate::core::limits::Largest::new(n);
+        for shard in self.shards.iter() {
+            for (key, v) in shard.read().iter() {
+                largest.offer(key, v.zcard());
+            }
+        }
+        largest.into_vec()
+    }
+
+    /// Encoding and estimated memory of `key`, `None` when it holds no sorted set.
+    pub fn key_memory(&self, key: &str) -> Option<crate::core::KeyMemory> {
+        let map = self.shards[self.shard_index(key)].read();
+        map.get(key)
+            .filter(|v| !v.is_expired())
+            .map(|v| v.memory(key))
+    }
+
+    /// Estimated memory of every live sorted set, with the `top` largest by bytes.
+    pub fn memory_profile(&self, top: usize) -> crate::core::MemoryProfile {
+        let mut profiler = crate::core::memory_profile::Profiler::new(top);
+        for shard in self.shards.iter() {
+            for (key, v) in shard.read().iter() {
+                if !v.is_expired() {
+                    profiler.offer(key, v.memory(key));
+                }
+            }
+        }
+        profiler.finish()
+    }
+
+    /// Refuse a write that would add `member` to `key` past `max_zset_members`.
+    fn check_member(
+        &self,
+        map: &HashMap<String, SortedSetValue>,
+        key: &str,
+        member: &[u8],
+        only_existing: bool,
+    ) -> Result<(), crate::core::SynapError> {
+        let Some(zset) = map.get(key) else {
+            return self
+                .key_limits
+                .check_zset(key, 0, usize::from(!only_existing));
+        };
+        let added = !only_existing && zset.zscore(member).is_none();
+        self.key_limits
+            .check_zset(key, zset.zcard(), usize::from(added))
+    }
+
+    /// The attached leaderboard-notification publisher, where windows are set.
+    pub fn leaderboard_notifier(&self) -> Option<&Arc<LeaderboardNotifier>> {
+        self.leaderboard_notifier.as_ref()
+    }
+
+    /// Capture a watched `key` before a mutation; `member` is the member
+    /// whose score the mutation sets.
+    #[inline]
+    fn leaderboard_snapshot(
+        &self,
+        key: &str,
+        map: &HashMap<String, SortedSetValue>,
+        member: Option<&[u8]>,
+    ) -> Option<BoardSnapshot> {
+        self.leaderboard_notifier
+            .as_ref()?
+            .snapshot(key, map.get(key), member)
+    }
+
+    /// The leaderboard events of a mutation, still under the shard lock.
+    #[inline]
+    fn leaderboard_changes(
+        &self,
+        key: &str,
+        before: Option<BoardSnapshot>,
+        map: &HashMap<String, SortedSetValue>,
+    ) -> Vec<LeaderboardEvent> {
+        match (&self.leaderboard_notifier, before) {
+            (Some(n), Some(before)) => n.changes(key, before, map.get(key)),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Publish leaderboard events once the shard lock is released.
+    #[inline]
+    fn publish_leaderboard(&self, key: &str, events: Vec<LeaderboardEvent>) {
+        if let Some(ref n) = self.leaderboard_notifier
+            && !events.is_empty()
+        {
+            n.publish(key, events);
+        }
+    }
+
     /// Publish a sorted-set keyspace notification for `key` if a notifier is attached.
     #[inline]
     fn notify_keyspace(&self, event: &str, key: &str) {
@@ -240,7 +425,10 @@ impl SortedSetStore {
         self.shards[idx].clone()
     }
 
-    /// Add member with score to sorted set
+    /// Add member with score to sorted set.
+    ///
+    /// Not subject to `max_zset_members`: this is the path recovery, replication
+    /// and internal fixtures use. Client writes go through [`Self::try_zadd`].
     pub fn zadd(
         &self,
         key: &str,
@@ -248,32 +436,65 @@ impl SortedSetStore {
         score: f64,
         opts: &ZAddOptions,
     ) -> (usize, usize) {
-        let result = {
+        self.zadd_inner(key, member, score, opts, false)
+            .unwrap_or_default()
+    }
+
+    /// Add member with score, refusing a new member past `max_zset_members`
+    /// with [`crate::core::SynapError::KeyLimitExceeded`].
+    pub fn try_zadd(
+        &self,
+        key: &str,
+        member: Vec<u8>,
+        score: f64,
+        opts: &ZAddOptions,
+    ) -> Result<(usize, usize), crate::core::SynapError> {
+        self.zadd_inner(key, member, score, opts, true)
+    }
+
+    fn zadd_inner(
+        &self,
+        key: &str,
+        member: Vec<u8>,
+        score: f64,
+        opts: &ZAddOptions,
+        enforce_limits: bool,
+    ) -> Result<(usize, usize), crate::core::SynapError> {
+        let (result, events) = {
             let shard = self.get_or_create(key);
             let mut map = shard.write();
+            if enforce_limits {
+                self.check_member(&map, key, &member, opts.xx)?;
+            }
+            let before = self.leaderboard_snapshot(key, &map, Some(&member));
             let zset = map.entry(key.to_string()).or_default();
-            zset.zadd(member, score, opts)
+            let result = zset.zadd(member, score, opts);
+            (result, self.leaderboard_changes(key, before, &map))
         };
         // A member is now available — wake any blocked BZPOPMIN/BZPOPMAX waiter.
         self.notify_waiters(key);
         self.notify_keyspace("zadd", key);
-        result
+        self.publish_leaderboard(key, events);
+        Ok(result)
     }
 
     /// Remove members from sorted set
     pub fn zrem(&self, key: &str, members: &[Vec<u8>]) -> usize {
         let shard = self.get_or_create(key);
-        let removed = {
+        let (removed, events) = {
             let mut map = shard.write();
-            if let Some(zset) = map.get_mut(key) {
+            let before = self.leaderboard_snapshot(key, &map, None);
+            let removed = if let Some(zset) = map.get_mut(key) {
                 zset.zrem(members)
             } else {
                 0
-            }
+            };
+            (removed, self.leaderboard_changes(key, before, &map))
         };
         if removed > 0 {
             self.notify_keyspace("zrem", key);
         }
+        self.publish_leaderboard(key, events);
         removed
     }
 
@@ -291,12 +512,42 @@ impl SortedSetStore {
         map.get(key).map(|zset| zset.zcard()).unwrap_or(0)
     }
 
-    /// Increment score
+    /// Increment score. Like [`Self::zadd`], not subject to `max_zset_members`.
     pub fn zincrby(&self, key: &str, member: Vec<u8>, increment: f64) -> f64 {
+        self.zincrby_inner(key, member, increment, false)
+            .unwrap_or_default()
+    }
+
+    /// Increment score, refusing a new member past `max_zset_members`.
+    pub fn try_zincrby(
+        &self,
+        key: &str,
+        member: Vec<u8>,
+        increment: f64,
+    ) -> Result<f64, crate::core::SynapError> {
+        self.zincrby_inner(key, member, increment, true)
+    }
+
+    fn zincrby_inner(
+        &self,
+        key: &str,
+        member: Vec<u8>,
+        increment: f64,
+        enforce_limits: bool,
+    ) -> Result<f64, crate::core::SynapError> {
         let shard = self.get_or_create(key);
-        let mut map = shard.write();
-        let zset = map.entry(key.to_string()).or_default();
-        zset.zincrby(member, increment)
+        let (score, events) = {
+            let mut map = shard.write();
+            if enforce_limits {
+                self.check_member(&map, key, &member, false)?;
+            }
+            let before = self.leaderboard_snapshot(key, &map, Some(&member));
+            let zset = map.entry(key.to_string()).or_default();
+            let score = zset.zincrby(member, increment);
+            (score, self.leaderboard_changes(key, before, &map))
+        };
+        self.publish_leaderboard(key, events);
+        Ok(score)
     }
 
     /// Get range by rank
@@ -346,20 +597,12 @@ impl SortedSetStore {
 
     /// Pop minimum scored members
     pub fn zpopmin(&self, key: &str, count: usize) -> Vec<ScoredMember> {
-        let shard = self.get_or_create(key);
-        let mut map = shard.write();
-        map.get_mut(key)
-            .map(|zset| zset.zpopmin(count))
-            .unwrap_or_default()
+        self.mutate(key, |zset| zset.zpopmin(count))
     }
 
     /// Pop maximum scored members
     pub fn zpopmax(&self, key: &str, count: usize) -> Vec<ScoredMember> {
-        let shard = self.get_or_create(key);
-        let mut map = shard.write();
-        map.get_mut(key)
-            .map(|zset| zset.zpopmax(count))
-            .unwrap_or_default()
+        self.mutate(key, |zset| zset.zpopmax(count))
     }
 
     /// Get statistics
@@ -392,21 +635,42 @@ impl SortedSetStore {
     /// Delete a sorted set
     pub fn delete(&self, key: &str) -> bool {
         let shard = self.get_or_create(key);
-        let mut map = shard.write();
-        map.remove(key).is_some()
+        let (deleted, events) = {
+            let mut map = shard.write();
+            let before = self.leaderboard_snapshot(key, &map, None);
+            let deleted = map.remove(key).is_some();
+            (deleted, self.leaderboard_changes(key, before, &map))
+        };
+        self.publish_leaderboard(key, events);
+        deleted
     }
 
-    /// Compute intersection of multiple sorted sets and store in destination
-    /// Returns count of members in result
-    pub fn zinterstore(
+    /// Apply `f` to an existing sorted set, publishing leaderboard events
+    /// for it. Returns `R::default()` when the key does not exist.
+    fn mutate<R: Default>(&self, key: &str, f: impl FnOnce(&mut SortedSetValue) -> R) -> R {
+        let shard = self.get_or_create(key);
+        let (result, events) = {
+            let mut map = shard.write();
+            let before = self.leaderboard_snapshot(key, &map, None);
+            let result = map.get_mut(key).map(f).unwrap_or_default();
+            (result, self.leaderboard_changes(key, before, &map))
+        };
+        self.publish_leaderboard(key, events);
+        result
+    }
+
+    /// Intersection scores of `keys`, weighted and aggregated.
+    ///
+    /// `None` when there is nothing to compute (no keys, or a source set is
+    /// missing); the STORE variant then leaves the destination untouched.
+    fn inter_scores(
         &self,
-        destination: &str,
         keys: &[&str],
         weights: Option<&[f64]>,
         aggregate: Aggregate,
-    ) -> usize {
+    ) -> Option<HashMap<Vec<u8>, f64>> {
         if keys.is_empty() {
-            return 0;
+            return None;
         }
 
         let default_weights = vec![1.0; keys.len()];
@@ -417,16 +681,13 @@ impl SortedSetStore {
         for (i, key) in keys.iter().enumerate() {
             let shard = self.get_or_create(key);
             let map = shard.read();
-            if let Some(zset) = map.get(*key) {
-                let mut weighted_set = HashMap::new();
-                for (member, score) in &zset.scores {
-                    weighted_set.insert(member.clone(), score.get() * weights[i]);
-                }
-                sets.push(weighted_set);
-            } else {
-                // If any set doesn't exist, intersection is empty
-                return 0;
+            // If any set doesn't exist, intersection is empty
+            let zset = map.get(*key)?;
+            let mut weighted_set = HashMap::new();
+            for (member, score) in &zset.scores {
+                weighted_set.insert(member.clone(), score.get() * weights[i]);
             }
+            sets.push(weighted_set);
         }
 
         // Compute intersection
@@ -447,44 +708,22 @@ impl SortedSetStore {
                 }
 
                 if exists_in_all {
-                    let aggregated = match aggregate {
-                        Aggregate::Sum => all_scores.iter().sum(),
-                        Aggregate::Min => all_scores.iter().cloned().fold(f64::INFINITY, f64::min),
-                        Aggregate::Max => {
-                            all_scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
-                        }
-                    };
-                    result.insert(member.clone(), aggregated);
+                    result.insert(member.clone(), aggregate.apply(&all_scores));
                 }
             }
         }
-
-        // Store result
-        let count = result.len();
-        let dest_shard = self.get_or_create(destination);
-        let mut dest_map = dest_shard.write();
-
-        let mut dest_zset = SortedSetValue::new();
-        let opts = ZAddOptions::default();
-        for (member, score) in result {
-            dest_zset.zadd(member, score, &opts);
-        }
-
-        dest_map.insert(destination.to_string(), dest_zset);
-        count
+        Some(result)
     }
 
-    /// Compute union of multiple sorted sets and store in destination
-    /// Returns count of members in result
-    pub fn zunionstore(
+    /// Union scores of `keys`, weighted and aggregated. `None` when no keys.
+    fn union_scores(
         &self,
-        destination: &str,
         keys: &[&str],
         weights: Option<&[f64]>,
         aggregate: Aggregate,
-    ) -> usize {
+    ) -> Option<HashMap<Vec<u8>, f64>> {
         if keys.is_empty() {
-            return 0;
+            return None;
         }
 
         let default_weights = vec![1.0; keys.len()];
@@ -506,48 +745,23 @@ impl SortedSetStore {
             }
         }
 
-        // Aggregate scores
-        let mut result = HashMap::new();
-        for (member, scores) in all_members {
-            let aggregated = match aggregate {
-                Aggregate::Sum => scores.iter().sum(),
-                Aggregate::Min => scores.iter().cloned().fold(f64::INFINITY, f64::min),
-                Aggregate::Max => scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
-            };
-            result.insert(member, aggregated);
-        }
-
-        // Store result
-        let count = result.len();
-        let dest_shard = self.get_or_create(destination);
-        let mut dest_map = dest_shard.write();
-
-        let mut dest_zset = SortedSetValue::new();
-        let opts = ZAddOptions::default();
-        for (member, score) in result {
-            dest_zset.zadd(member, score, &opts);
-        }
-
-        dest_map.insert(destination.to_string(), dest_zset);
-        count
+        Some(
+            all_members
+                .into_iter()
+                .map(|(member, scores)| (member, aggregate.apply(&scores)))
+                .collect(),
+        )
     }
 
-    /// Compute difference of first set minus other sets and store in destination
-    /// Returns count of members in result
-    pub fn zdiffstore(&self, destination: &str, keys: &[&str]) -> usize {
-        if keys.is_empty() {
-            return 0;
-        }
+    /// Members of the first set absent from the others, with their original
+    /// scores. `None` when no keys or the first set is missing.
+    fn diff_scores(&self, keys: &[&str]) -> Option<HashMap<Vec<u8>, f64>> {
+        let first_key = *keys.first()?;
 
         // Read first set
-        let first_key = keys[0];
         let first_shard = self.get_or_create(first_key);
         let first_map = first_shard.read();
-
-        let first_set = match first_map.get(first_key) {
-            Some(zset) => zset.scores.clone(),
-            None => return 0, // First set doesn't exist, result is empty
-        };
+        let first_set = first_map.get(first_key)?.scores.clone();
         drop(first_map);
 
         // Read other sets to subtract
@@ -562,29 +776,117 @@ impl SortedSetStore {
             }
         }
 
-        // Compute difference
-        let mut result = HashMap::new();
-        for (member, score) in first_set {
-            if !subtract_members.contains(&member) {
-                result.insert(member, score.get());
-            }
-        }
+        Some(
+            first_set
+                .into_iter()
+                .filter(|(member, _)| !subtract_members.contains(member))
+                .map(|(member, score)| (member, score.get()))
+                .collect(),
+        )
+    }
 
-        // Store result
+    /// Replace `destination` with `result`, returning its cardinality.
+    fn store_scores(&self, destination: &str, result: HashMap<Vec<u8>, f64>) -> usize {
         let count = result.len();
-        let dest_shard = self.get_or_create(destination);
-        let mut dest_map = dest_shard.write();
-
         let mut dest_zset = SortedSetValue::new();
         let opts = ZAddOptions::default();
         for (member, score) in result {
             dest_zset.zadd(member, score, &opts);
         }
 
-        dest_map.insert(destination.to_string(), dest_zset);
+        let dest_shard = self.get_or_create(destination);
+        let events = {
+            let mut dest_map = dest_shard.write();
+            let before = self.leaderboard_snapshot(destination, &dest_map, None);
+            dest_map.insert(destination.to_string(), dest_zset);
+            self.leaderboard_changes(destination, before, &dest_map)
+        };
+        self.publish_leaderboard(destination, events);
         count
     }
 
+    /// Order a computed result like a sorted set: by score, then member.
+    fn sorted_members(result: Option<HashMap<Vec<u8>, f64>>) -> Vec<ScoredMember> {
+        let mut members: Vec<ScoredMember> = result
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(member, score)| ScoredMember { member, score })
+            .collect();
+        members.sort_by(|a, b| {
+            a.score
+                .total_cmp(&b.score)
+                .then_with(|| a.member.cmp(&b.member))
+        });
+        members
+    }
+
+    /// Compute intersection of multiple sorted sets (ZINTER)
+    /// Returns members ordered by aggregated score
+    pub fn zinter(
+        &self,
+        keys: &[&str],
+        weights: Option<&[f64]>,
+        aggregate: Aggregate,
+    ) -> Vec<ScoredMember> {
+        Self::sorted_members(self.inter_scores(keys, weights, aggregate))
+    }
+
+    /// Compute union of multiple sorted sets (ZUNION)
+    /// Returns members ordered by aggregated score
+    pub fn zunion(
+        &self,
+        keys: &[&str],
+        weights: Option<&[f64]>,
+        aggregate: Aggregate,
+    ) -> Vec<ScoredMember> {
+        Self::sorted_members(self.union_scores(keys, weights, aggregate))
+    }
+
+    /// Compute difference of first set minus other sets (ZDIFF)
+    /// Returns members ordered by score
+    pub fn zdiff(&self, keys: &[&str]) -> Vec<ScoredMember> {
+        Self::sorted_members(self.diff_scores(keys))
+    }
+
+    /// Compute intersection of multiple sorted sets and store in destination
+    /// Returns count of members in result
+    pub fn zinterstore(
+        &self,
+        destination: &str,
+        keys: &[&str],
+        weights: Option<&[f64]>,
+        aggregate: Aggregate,
+    ) -> usize {
+        match self.inter_scores(keys, weights, aggregate) {
+            Some(result) => self.store_scores(destination, result),
+            None => 0,
+        }
+    }
+
+    /// Compute union of multiple sorted sets and store in destination
+    /// Returns count of members in result
+    pub fn zunionstore(
+        &self,
+        destination: &str,
+        keys: &[&str],
+        weights: Option<&[f64]>,
+        aggregate: Aggregate,
+    ) -> usize {
+        match self.union_scores(keys, weights, aggregate) {
+            Some(result) => self.store_scores(destination, result),
+            None => 0,
+        }
+    }
+
+    /// Compute difference of first set minus other sets and store in destination
+    /// Returns count of members in result
+    pub fn zdiffstore(&self, destination: &str, keys: &[&str]) -> usize {
+        match self.diff_scores(keys) {
+            Some(result) => self.store_scores(destination, result),
+            None => 0,
+        }
+    }
+
     /// Get range by score (wrapper for store)
     pub fn zrangebyscore(
         &self,
@@ -602,20 +904,12 @@ impl SortedSetStore {
 
     /// Remove members by rank range
     pub fn zremrangebyrank(&self, key: &str, start: i64, stop: i64) -> usize {
-        let shard = self.get_or_create(key);
-        let mut map = shard.write();
-        map.get_mut(key)
-            .map(|zset| zset.zremrangebyrank(start, stop))
-            .unwrap_or(0)
+        self.mutate(key, |zset| zset.zremrangebyrank(start, stop))
     }
 
     /// Remove members by score range
     pub fn zremrangebyscore(&self, key: &str, min: f64, max: f64) -> usize {
-        let shard = self.get_or_create(key);
-        let mut map = shard.write();
-        map.get_mut(key)
-            .map(|zset| zset.zremrangebyscore(min, max))
-            .unwrap_or(0)
+        self.mutate(key, |zset| zset.zremrangebyscore(min, max))
     }
 
     /// Get multiple scores
@@ -629,10 +923,11 @@ impl SortedSetStore {
 
     /// ZSCAN - cursor-based incremental scan of a sorted set's members.
     ///
-    /// `cursor` is an offset into a member-sorted snapshot; returns the next
-    /// cursor (0 when complete) and the matched `(member, score)` pairs within
-    /// the window. `pattern` is an optional glob over members; `count` bounds the
-    /// window size (min 1).
+    /// `cursor` is an offset into the members in member (not score) order;
+    /// returns the next cursor (0 when complete) and the matched
+    /// `(member, score)` pairs within the window. `pattern` is an optional glob
+    /// over members; `count` bounds the window size (min 1). Only the window
+    /// is copied out of the sorted set.
     pub fn zscan(
         &self,
         key: &str,
@@ -640,23 +935,21 @@ impl SortedSetStore {
         pattern: Option<&str>,
         count: usize,
     ) -> (u64, Vec<(Vec<u8>, f64)>) {
-        let mut members: Vec<(Vec<u8>, f64)> = self
-            .zrange(key, 0, -1, true)
+        let shard = self.get_or_create(key);
+        let map = shard.read();
+
+        let Some(zset) = map.get(key) else {
+            return (0, Vec::new());
+        };
+
+        let (next, window) = crate::core::scan::scan_window(zset.member_scores(), cursor, count);
+        let items = window
             .into_iter()
-            .map(|sm| (sm.member, sm.score))
-            .collect();
-        members.sort_by(|a, b| a.0.cmp(&b.0));
-        let total = members.len();
-        let start = (cursor as usize).min(total);
-        let end = start.saturating_add(count.max(1)).min(total);
-        let items = members[start..end]
-            .iter()
             .filter(|(m, _)| {
-                pattern.is_none_or(|p| crate::core::glob_match(p, &String::from_utf8_lossy(m)))
+                pattern.is_none_or(|p| crate::core::glob::glob_match_bytes(p.as_bytes(), m))
             })
-            .cloned()
+            .map(|(m, s)| (m.to_vec(), s.get()))
             .collect();
-        let next = if end < total { end as u64 } else { 0 };
         (next, items)
     }
 }
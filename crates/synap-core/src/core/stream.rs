@@ -1,4 +1,6 @@
 use super::error::SynapError;
+use super::idempotency::{IdempotencyWindow, default_dedup_window_secs};
+use super::trace::{MessageTracer, TraceSource, TraceStep};
 use parking_lot::RwLock;
 /// Event Stream module for Kafka-style room-based broadcasting
 ///
@@ -35,6 +37,11 @@ pub struct StreamConfig {
     pub auto_compact: bool,
     /// Compaction interval in seconds
     pub compact_interval_secs: u64,
+    /// How long a publish idempotency key is remembered, in seconds: a
+    /// publish repeating a key seen within this window is not appended again.
+    /// `0` turns deduplication off for the room.
+    #[serde(default = "default_dedup_window_secs")]
+    pub dedup_window_secs: u64,
 }
 
 fn default_max_unread_buffer_size() -> usize {
@@ -49,6 +56,7 @@ impl Default for StreamConfig {
             retention_secs: 3600, // 1 hour default retention
             auto_compact: true,
             compact_interval_secs: 60, // Compact every minute
+            dedup_window_secs: default_dedup_window_secs(),
         }
     }
 }
@@ -68,6 +76,9 @@ pub struct StreamEvent {
     pub data: Vec<u8>,
     /// Unix timestamp when created
     pub timestamp: u64,
+    /// Unix timestamp when created, in milliseconds
+    #[serde(default)]
+    pub timestamp_ms: u64,
     /// Optional metadata
     #[serde(default)]
     pub metadata: HashMap<String, String>,
@@ -76,19 +87,36 @@ pub struct StreamEvent {
 impl StreamEvent {
     /// Create a new stream event
     pub fn new(room: String, event: String, data: Vec<u8>) -> Self {
+        let timestamp_ms = now_ms();
         Self {
             id: Uuid::new_v4().to_string(),
             offset: 0, // Will be set by room
             room,
             event,
             data,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
+            timestamp: timestamp_ms / 1000,
+            timestamp_ms,
             metadata: HashMap::new(),
         }
     }
+
+    /// Creation time in milliseconds; events serialized before `timestamp_ms`
+    /// existed fall back to the second-resolution `timestamp`
+    pub fn published_at_ms(&self) -> u64 {
+        if self.timestamp_ms > 0 {
+            self.timestamp_ms
+        } else {
+            self.timestamp.saturating_mul(1000)
+        }
+    }
+}
+
+/// Current Unix time in milliseconds
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
 }
 
 /// Room statistics
@@ -139,6 +167,8 @@ struct Room {
     subscribers: HashMap<String, Subscriber>,
     /// Room statistics
     stats: RoomStats,
+    /// Idempotency keys published within `config.dedup_window_secs`
+    idempotency: IdempotencyWindow<u64>,
     /// Configuration
     config: StreamConfig,
 }
@@ -161,6 +191,7 @@ impl Room {
             next_offset: 0,
             min_offset: 0,
             subscribers: HashMap::new(),
+            idempotency: IdempotencyWindow::new(config.dedup_window_secs),
             config,
         }
     }
@@ -215,6 +246,11 @@ impl Room {
 
     /// Consume events starting from an offset.
     ///
+    /// With a non-zero `min_age_ms` only events published at least that long
+    /// ago are delivered: the batch stops at the first younger event, so a
+    /// consumer deliberately lags behind real time and picks the rest up on a
+    /// later call.
+    ///
     /// Returns [`SynapError::StreamOffsetOutOfRange`] when `from_offset` addresses
     /// data that retention has already evicted (`from_offset < min_offset` after at
     /// least one eviction), so a lagging consumer learns it fell off the retention
@@ -224,6 +260,21 @@ impl Room {
         subscriber_id: &str,
         from_offset: u64,
         limit: usize,
+        min_age_ms: u64,
+    ) -> Result<Vec<StreamEvent>, SynapError> {
+        let events = self.read(from_offset, limit, min_age_ms)?;
+        let next_offset = events.last().map(|e| e.offset + 1).unwrap_or(from_offset);
+        self.record_consumed(subscriber_id, from_offset, next_offset, events.len());
+        Ok(events)
+    }
+
+    /// Read events starting from an offset without touching subscriber state.
+    /// See [`consume`](Self::consume) for the `min_age_ms` and out-of-range rules.
+    fn read(
+        &self,
+        from_offset: u64,
+        limit: usize,
+        min_age_ms: u64,
     ) -> Result<Vec<StreamEvent>, SynapError> {
         // A request below the earliest retained offset means the wanted events were
         // evicted. `min_offset > 0` distinguishes real eviction from a fresh room,
@@ -240,16 +291,24 @@ impl Room {
         // buffer (audit M-016: O(limit) rather than O(buffer)).
         let start_idx =
             (from_offset.saturating_sub(self.min_offset) as usize).min(self.buffer.len());
-        let events: Vec<StreamEvent> = self
+        let visible_until = now_ms().saturating_sub(min_age_ms);
+        Ok(self
             .buffer
             .range(start_idx..)
             .take(limit)
+            .take_while(|evt| min_age_ms == 0 || evt.published_at_ms() <= visible_until)
             .cloned()
-            .collect();
-
-        // Update subscriber after collecting events
-        let last_offset = events.last().map(|e| e.offset + 1).unwrap_or(from_offset);
+            .collect())
+    }
 
+    /// Advance a subscriber to `next_offset` after it was handed `count` events
+    fn record_consumed(
+        &mut self,
+        subscriber_id: &str,
+        from_offset: u64,
+        next_offset: u64,
+        count: usize,
+    ) {
         let subscriber = self
             .subscribers
             .entry(subscriber_id.to_string())
@@ -260,12 +319,10 @@ impl Room {
             });
 
         subscriber.last_active = std::time::Instant::now();
-        subscriber.last_offset = last_offset;
+        subscriber.last_offset = next_offset;
 
         self.stats.subscriber_count = self.subscribers.len();
-        self.stats.total_consumed += events.len() as u64;
-
-        Ok(events)
+        self.stats.total_consumed += count as u64;
     }
 
     /// Get room statistics
@@ -306,6 +363,8 @@ pub struct StreamManager {
     /// Registered contribution to the shared cross-datatype budget (audit M-018).
     mem_bytes: Arc<std::sync::atomic::AtomicI64>,
     mem_attached: bool,
+    /// Delivery timelines, when `message_trace` is enabled
+    tracer: Option<Arc<MessageTracer>>,
 }
 
 impl StreamManager {
@@ -316,6 +375,7 @@ impl StreamManager {
             config,
             mem_bytes: Arc::new(std::sync::atomic::AtomicI64::new(0)),
             mem_attached: false,
+            tracer: None,
         }
     }
 
@@ -328,6 +388,42 @@ impl StreamManager {
         self
     }
 
+    /// Record event timelines (publish and subscriber reads) in `tracer`
+    pub fn with_tracer(mut self, tracer: Option<Arc<MessageTracer>>) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    /// Event timelines, or `None` when tracing is disabled
+    pub fn tracer(&self) -> Option<&Arc<MessageTracer>> {
+        self.tracer.as_ref()
+    }
+
+    /// Start the timeline of an event just appended at `offset`
+    fn trace_publish(&self, room: &str, event_id: &str, offset: u64) {
+        if let Some(ref tracer) = self.tracer {
+            tracer.publish(
+                event_id,
+                TraceSource::Stream(room.to_string()),
+                TraceStep::Published { offset },
+            );
+        }
+    }
+
+    /// Record that `subscriber_id` was handed `events`
+    fn trace_read(&self, subscriber_id: &str, events: &[StreamEvent]) {
+        if let Some(ref tracer) = self.tracer
+            && !events.is_empty()
+        {
+            tracer.record_all(
+                events.iter().map(|evt| evt.id.as_str()),
+                TraceStep::Read {
+                    consumer: subscriber_id.to_string(),
+                },
+            );
+        }
+    }
+
     /// Total buffered event-payload bytes across all rooms.
     pub fn memory_bytes(&self) -> usize {
         let mut total = 0usize;
@@ -350,8 +446,24 @@ impl StreamManager {
         }
     }
 
+    /// Config of rooms created without one
+    pub fn default_config(&self) -> StreamConfig {
+        self.config.clone()
+    }
+
     /// Create a new room
     pub async fn create_room(&self, room_name: &str) -> Result<(), String> {
+        self.create_room_with_config(room_name, self.config.clone())
+            .await
+    }
+
+    /// Create a new room with its own config, e.g. a different idempotency
+    /// window. Compaction still runs on the manager's schedule.
+    pub async fn create_room_with_config(
+        &self,
+        room_name: &str,
+        config: StreamConfig,
+    ) -> Result<(), String> {
         let mut rooms = self.rooms.write();
 
         if rooms.contains_key(room_name) {
@@ -360,7 +472,7 @@ impl StreamManager {
 
         rooms.insert(
             room_name.to_string(),
-            Room::new(room_name.to_string(), self.config.clone()),
+            Room::new(room_name.to_string(), config),
         );
 
         Ok(())
@@ -403,11 +515,46 @@ impl StreamManager {
             .ok_or_else(|| format!("Room '{}' not found", room))?;
 
         let event = StreamEvent::new(room.to_string(), event_type.to_string(), data);
+        let event_id = event.id.clone();
         let offset = room_obj.publish(event);
+        self.trace_publish(room, &event_id, offset);
 
         Ok(offset)
     }
 
+    /// Publish an event to a room unless `idempotency_key` was already
+    /// published to it within the room's `dedup_window_secs`.
+    ///
+    /// Returns the event's offset and whether the publish was a repeat; a
+    /// repeat appends nothing and returns the offset the key first got.
+    pub async fn publish_idempotent(
+        &self,
+        room: &str,
+        event_type: &str,
+        data: Vec<u8>,
+        idempotency_key: &str,
+    ) -> Result<(u64, bool), String> {
+        let mut rooms = self.rooms.write();
+
+        let room_obj = rooms
+            .get_mut(room)
+            .ok_or_else(|| format!("Room '{}' not found", room))?;
+
+        if let Some(offset) = room_obj.idempotency.get(idempotency_key) {
+            return Ok((offset, true));
+        }
+
+        let event = StreamEvent::new(room.to_string(), event_type.to_string(), data);
+        let event_id = event.id.clone();
+        let offset = room_obj.publish(event);
+        room_obj
+            .idempotency
+            .insert(idempotency_key.to_string(), offset);
+        self.trace_publish(room, &event_id, offset);
+
+        Ok((offset, false))
+    }
+
     /// Consume events from a room
     pub async fn consume(
         &self,
@@ -415,6 +562,21 @@ impl StreamManager {
         subscriber_id: &str,
         from_offset: u64,
         limit: usize,
+    ) -> Result<Vec<StreamEvent>, String> {
+        self.consume_with_min_age(room, subscriber_id, from_offset, limit, 0)
+            .await
+    }
+
+    /// Consume events from a room, holding back events published less than
+    /// `min_age_ms` ago (delayed visibility, e.g. to leave room for upstream
+    /// corrections). `0` delivers everything, like [`consume`](Self::consume).
+    pub async fn consume_with_min_age(
+        &self,
+        room: &str,
+        subscriber_id: &str,
+        from_offset: u64,
+        limit: usize,
+        min_age_ms: u64,
     ) -> Result<Vec<StreamEvent>, String> {
         let mut rooms = self.rooms.write();
 
@@ -426,9 +588,78 @@ impl StreamManager {
         // the existing REST/RESP3/SynapRPC callers keep their `Result<_, String>`
         // signature; the handler still surfaces it as HTTP 400 with the earliest
         // retained offset in the message.
-        room_obj
-            .consume(subscriber_id, from_offset, limit)
-            .map_err(|e| e.to_string())
+        let events = room_obj
+            .consume(subscriber_id, from_offset, limit, min_age_ms)
+            .map_err(|e| e.to_string())?;
+        self.trace_read(subscriber_id, &events);
+        Ok(events)
+    }
+
+    /// Consume from several rooms at once, each from its own offset.
+    ///
+    /// Events are interleaved by publish time (ties go to the room listed
+    /// first) while each room keeps its own offset order, and at most `limit`
+    /// events are returned in total. Only rooms that actually delivered events
+    /// advance the subscriber's position; every room must exist and appear
+    /// once.
+    pub async fn consume_many(
+        &self,
+        subscriber_id: &str,
+        rooms: &[(String, u64)],
+        limit: usize,
+    ) -> Result<Vec<StreamEvent>, String> {
+        let mut guard = self.rooms.write();
+
+        let mut batches = Vec::with_capacity(rooms.len());
+        for (i, (room, from_offset)) in rooms.iter().enumerate() {
+            if rooms[..i].iter().any(|(other, _)| other == room) {
+                return Err(format!("Room '{}' requested more than once", room));
+            }
+            let room_obj = guard
+                .get(room)
+                .ok_or_else(|| format!("Room '{}' not found", room))?;
+            let events = room_obj
+                .read(*from_offset, limit, 0)
+                .map_err(|e| e.to_string())?;
+            batches.push(VecDeque::from(events));
+        }
+
+        let mut merged = Vec::new();
+        let mut delivered = vec![0usize; rooms.len()];
+        while merged.len() < limit {
+            let next = batches
+                .iter()
+                .enumerate()
+                .filter_map(|(i, b)| b.front().map(|evt| (evt.published_at_ms(), i)))
+                .min();
+            let Some((_, i)) = next else { break };
+            if let Some(evt) = batches[i].pop_front() {
+                merged.push(evt);
+                delivered[i] += 1;
+            }
+        }
+
+        for ((room, from_offset), count) in rooms.iter().zip(delivered) {
+            if count == 0 {
+                continue;
+            }
+            if let Some(room_obj) = guard.get_mut(room) {
+                room_obj.record_consumed(
+                    subscriber_id,
+                    *from_offset,
+                    from_offset + count as u64,
+                    count,
+                );
+            }
+        }
+        self.trace_read(subscriber_id, &merged);
+
+        Ok(merged)
+    }
+
+    /// Whether a room exists
+    pub fn has_room(&self, room: &str) -> bool {
+        self.rooms.read().contains_key(room)
     }
 
     /// Get room statistics
@@ -593,6 +824,37 @@ mod tests {
         assert_eq!(events[1].offset, 1);
     }
 
+    #[tokio::test]
+    async fn test_stream_consume_many_interleaves_rooms() {
+        let manager = StreamManager::new(StreamConfig::default());
+        manager.create_room("a").await.unwrap();
+        manager.create_room("b").await.unwrap();
+
+        for i in 0..3u8 {
+            manager.publish("a", "msg", vec![i]).await.unwrap();
+            manager.publish("b", "msg", vec![i]).await.unwrap();
+        }
+
+        let rooms = vec![("a".to_string(), 1), ("b".to_string(), 0)];
+        let events = manager.consume_many("dash", &rooms, 4).await.unwrap();
+        assert_eq!(events.len(), 4);
+        for room in ["a", "b"] {
+            let offsets: Vec<u64> = events
+                .iter()
+                .filter(|e| e.room == room)
+                .map(|e| e.offset)
+                .collect();
+            assert!(offsets.windows(2).all(|w| w[0] < w[1]));
+        }
+        assert_eq!(events.iter().filter(|e| e.room == "a").count(), 2);
+        assert_eq!(manager.room_stats("b").await.unwrap().total_consumed, 2);
+
+        let missing = vec![("a".to_string(), 0), ("nope".to_string(), 0)];
+        assert!(manager.consume_many("dash", &missing, 10).await.is_err());
+        let duplicate = vec![("a".to_string(), 0), ("a".to_string(), 1)];
+        assert!(manager.consume_many("dash", &duplicate, 10).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_stream_offset_tracking() {
         let manager = StreamManager::new(StreamConfig::default());
@@ -680,6 +942,7 @@ mod tests {
                 retention_secs: 0,
                 auto_compact: false,
                 compact_interval_secs: 60,
+                dedup_window_secs: 0,
             },
         )
     }
@@ -712,7 +975,7 @@ mod tests {
 
         // Consuming from an evicted offset returns an explicit out-of-range error
         // carrying the earliest retained offset, not a silently later batch.
-        let err = room.consume("s", 0, 10).unwrap_err();
+        let err = room.consume("s", 0, 10, 0).unwrap_err();
         assert!(matches!(
             err,
             SynapError::StreamOffsetOutOfRange {
@@ -722,7 +985,7 @@ mod tests {
         ));
 
         // Consuming from the earliest retained offset still works.
-        let got = room.consume("s", 3, 10).unwrap();
+        let got = room.consume("s", 3, 10, 0).unwrap();
         assert_eq!(got.len(), 2);
     }
 
@@ -731,7 +994,7 @@ mod tests {
         // A room that has never evicted (min_offset == 0) never errors at offset 0.
         let mut room = room_with(100, 100);
         room.publish(StreamEvent::new("r".to_string(), "e".to_string(), vec![0]));
-        let got = room.consume("s", 0, 10).unwrap();
+        let got = room.consume("s", 0, 10, 0).unwrap();
         assert_eq!(got.len(), 1);
     }
 
@@ -741,7 +1004,7 @@ mod tests {
 
         // Publish e0, then a subscriber reads only up to offset 0 (last_offset=1).
         room.publish(StreamEvent::new("r".to_string(), "e".to_string(), vec![0]));
-        let got = room.consume("s", 0, 1).unwrap();
+        let got = room.consume("s", 0, 1, 0).unwrap();
         assert_eq!(got.len(), 1);
 
         // Publish e1..e4 without the subscriber catching up. Evictions of events
@@ -764,7 +1027,7 @@ mod tests {
 
         // Publish e0, subscriber reads only up to offset 0 (committed = 1).
         room.publish(StreamEvent::new("r".to_string(), "e".to_string(), vec![0]));
-        assert_eq!(room.consume("s", 0, 1).unwrap().len(), 1);
+        assert_eq!(room.consume("s", 0, 1, 0).unwrap().len(), 1);
 
         // Publish e1..e6 without the subscriber catching up.
         for i in 1..=6u8 {
@@ -781,6 +1044,29 @@ mod tests {
         assert_eq!(room.stats().min_offset, 2);
     }
 
+    #[test]
+    fn test_consume_min_age_holds_back_recent_events() {
+        let mut room = room_with(100, 100);
+
+        // e0 was published ten seconds ago, e1 and e2 just now
+        let mut old = StreamEvent::new("r".to_string(), "e".to_string(), vec![0]);
+        old.timestamp_ms -= 10_000;
+        room.publish(old);
+        room.publish(StreamEvent::new("r".to_string(), "e".to_string(), vec![1]));
+        room.publish(StreamEvent::new("r".to_string(), "e".to_string(), vec![2]));
+
+        let got = room.consume("s", 0, 10, 5_000).unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].offset, 0);
+        assert_eq!(room.subscribers["s"].last_offset, 1);
+
+        // Nothing is old enough yet; the subscriber stays where it was
+        assert!(room.consume("s", 1, 10, 5_000).unwrap().is_empty());
+        assert_eq!(room.subscribers["s"].last_offset, 1);
+
+        assert_eq!(room.consume("s", 1, 10, 0).unwrap().len(), 2);
+    }
+
     #[test]
     fn test_stream_no_protection_without_subscribers_even_with_hard_cap() {
         // A wide hard cap must not make an unsubscribed room grow: nobody to
@@ -792,4 +1078,70 @@ mod tests {
         assert_eq!(room.buffer.len(), 2);
         assert_eq!(room.stats().dropped, 0);
     }
+
+    #[tokio::test]
+    async fn test_stream_publish_idempotent_dedups_retries() {
+        let manager = StreamManager::new(StreamConfig::default());
+        manager.create_room("chat").await.unwrap();
+        manager
+            .create_room_with_config(
+                "no-dedup",
+                StreamConfig {
+                    dedup_window_secs: 0,
+                    ..manager.default_config()
+                },
+            )
+            .await
+            .unwrap();
+
+        let first = manager
+            .publish_idempotent("chat", "msg", vec![1], "k1")
+            .await
+            .unwrap();
+        let retry = manager
+            .publish_idempotent("chat", "msg", vec![1], "k1")
+            .await
+            .unwrap();
+        assert_eq!(first, (0, false));
+        assert_eq!(retry, (0, true));
+        assert_eq!(manager.room_stats("chat").await.unwrap().total_published, 1);
+
+        manager
+            .publish_idempotent("no-dedup", "msg", vec![1], "k1")
+            .await
+            .unwrap();
+        let again = manager
+            .publish_idempotent("no-dedup", "msg", vec![1], "k1")
+            .await
+            .unwrap();
+        assert_eq!(again, (1, false));
+    }
+
+    #[tokio::test]
+    async fn test_stream_traces_publish_and_reads() {
+        let tracer = Arc::new(MessageTracer::new(100, 32));
+        let manager =
+            StreamManager::new(StreamConfig::default()).with_tracer(Some(Arc::clone(&tracer)));
+        manager.create_room("chat").await.unwrap();
+        manager.create_room("news").await.unwrap();
+        manager.publish("chat", "msg", vec![1]).await.unwrap();
+        manager.publish("news", "msg", vec![2]).await.unwrap();
+
+        let events = manager.consume("chat", "s1", 0, 10).await.unwrap();
+        manager
+            .consume_many(
+                "s2",
+                &[("chat".to_string(), 0), ("news".to_string(), 0)],
+                10,
+            )
+            .await
+            .unwrap();
+
+        let trace = tracer.get(&events[0].id).unwrap();
+        assert_eq!(trace.source, TraceSource::Stream("chat".to_string()));
+        assert_eq!(trace.events[0].step, TraceStep::Published { offset: 0 });
+        assert_eq!(trace.deliveries, 2);
+        assert_eq!(trace.consumers, ["s1", "s2"]);
+        assert_eq!(tracer.len(), 2);
+    }
 }
@@ -0,0 +1,531 @@
+//! Time-series counters.
+//!
+//! Each key holds one series: increments are added into fixed buckets of the
+//! series' [`TimeSeriesResolution`] (1s, 1m or 1h), keyed by the bucket's
+//! start in unix milliseconds. A bucket keeps the sum, count and largest of its
+//! increments, which is enough to answer every [`TimeSeriesAggregation`] over
+//! any range, at the series' resolution or any multiple of it.
+//!
+//! Buckets older than the series' retention are trimmed as the series is
+//! written and read, and by a periodic sweep that also drops series with
+//! nothing left.
+//!
+//! Series live in memory only: they are not written to the WAL or snapshots,
+//! are not replicated, and are gone after a restart. They are reached through
+//! the `ts.*` commands; there are no REST routes for them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::error::{Result, SynapError};
+
+/// Increments between sweeps of expired buckets.
+const SWEEP_INTERVAL: u64 = 4096;
+
+/// Retention of a series created without one.
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Width of a series' buckets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeSeriesResolution {
+    #[default]
+    #[serde(rename = "1s")]
+    Second,
+    #[serde(rename = "1m")]
+    Minute,
+    #[serde(rename = "1h")]
+    Hour,
+}
+
+impl TimeSeriesResolution {
+    /// Bucket width in milliseconds.
+    pub fn as_millis(self) -> u64 {
+        match self {
+            TimeSeriesResolution::Second => 1_000,
+            TimeSeriesResolution::Minute => 60_000,
+            TimeSeriesResolution::Hour => 3_600_000,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            TimeSeriesResolution::Second => "1s",
+            TimeSeriesResolution::Minute => "1m",
+            TimeSeriesResolution::Hour => "1h",
+        }
+    }
+}
+
+/// How the increments in a range bucket are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeSeriesAggregation {
+    /// Total of the increments.
+    #[default]
+    Sum,
+    /// Mean increment.
+    Avg,
+    /// Largest single increment.
+    Max,
+}
+
+/// Settings a series is created with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeriesOptions {
+    /// Bucket width; [`TimeSeriesResolution::Second`] when `None`.
+    pub resolution: Option<TimeSeriesResolution>,
+    /// How long buckets are kept; [`DEFAULT_RETENTION`] when `None`.
+    pub retention: Option<Duration>,
+}
+
+/// One bucket of a series or of a range query.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeSeriesPoint {
+    /// Bucket start, in unix milliseconds.
+    pub timestamp: u64,
+    pub value: f64,
+}
+
+/// Time-series counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimeSeriesStats {
+    pub series: usize,
+    /// Buckets held across every series.
+    pub buckets: usize,
+    pub increments: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    sum: f64,
+    count: u64,
+    max: f64,
+}
+
+impl Bucket {
+    fn add(&mut self, other: &Bucket) {
+        self.sum += other.sum;
+        self.count += other.count;
+        self.max = self.max.max(other.max);
+    }
+
+    fn value(&self, aggregation: TimeSeriesAggregation) -> f64 {
+        match aggregation {
+            TimeSeriesAggregation::Sum => self.sum,
+            TimeSeriesAggregation::Avg => self.sum / self.count as f64,
+            TimeSeriesAggregation::Max => self.max,
+        }
+    }
+}
+
+struct Series {
+    resolution: TimeSeriesResolution,
+    retention: Duration,
+    buckets: BTreeMap<u64, Bucket>,
+}
+
+impl Series {
+    /// Drop the buckets that ended before the retention window.
+    fn trim(&mut self, now: u64) {
+        let cutoff = now.saturating_sub(self.retention.as_millis() as u64);
+        let keep_from = cutoff - cutoff % self.resolution.as_millis();
+        if self
+            .buckets
+            .first_key_value()
+            .is_some_and(|(start, _)| *start < keep_from)
+        {
+            self.buckets = self.buckets.split_off(&keep_from);
+        }
+    }
+}
+
+/// Per-key time-series counters.
+pub struct TimeSeriesStore {
+    series: Mutex<HashMap<String, Series, ahash::RandomState>>,
+    increments: AtomicU64,
+}
+
+impl Default for TimeSeriesStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+impl TimeSeriesStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self {
+            series: Mutex::new(HashMap::default()),
+            increments: AtomicU64::new(0),
+        }
+    }
+
+    /// Add `value` to the bucket holding `timestamp` (unix milliseconds, now
+    /// when `None`), creating the series with `options`.
+    ///
+    /// For an existing series a different resolution is an error, and a
+    /// retention replaces the current one. Returns the bucket's new total.
+    pub fn incr(
+        &self,
+        key: &str,
+        value: f64,
+        timestamp: Option<u64>,
+        options: &SeriesOptions,
+    ) -> Result<TimeSeriesPoint> {
+        self.incr_at(key, value, timestamp, options, unix_millis())
+    }
+
+    fn incr_at(
+        &self,
+        key: &str,
+        value: f64,
+        timestamp: Option<u64>,
+        options: &SeriesOptions,
+        now: u64,
+    ) -> Result<TimeSeriesPoint> {
+        if !value.is_finite() {
+            return Err(SynapError::InvalidValue(
+                "Time-series increments must be finite".to_string(),
+            ));
+        }
+        if options
+            .retention
+            .is_some_and(|retention| retention.is_zero())
+        {
+            return Err(SynapError::InvalidValue(
+                "Retention must be at least 1 ms".to_string(),
+            ));
+        }
+
+        let mut all = self.series.lock();
+        let increments = self.increments.fetch_add(1, Ordering::Relaxed) + 1;
+        if increments.is_multiple_of(SWEEP_INTERVAL) {
+            all.retain(|_, series| {
+                series.trim(now);
+                !series.buckets.is_empty()
+            });
+        }
+
+        if let Some(series) = all.get(key)
+            && let Some(resolution) = options.resolution
+            && resolution != series.resolution
+        {
+            return Err(SynapError::InvalidValue(format!(
+                "Time series '{key}' has resolution {}, not {}",
+                series.resolution.name(),
+                resolution.name()
+            )));
+        }
+        let timestamp = timestamp.unwrap_or(now);
+        let retention = options.retention.unwrap_or_else(|| {
+            all.get(key)
+                .map_or(DEFAULT_RETENTION, |series| series.retention)
+        });
+        if timestamp < now.saturating_sub(retention.as_millis() as u64) {
+            return Err(SynapError::InvalidValue(format!(
+                "Timestamp {timestamp} is older than the retention of time series '{key}'"
+            )));
+        }
+
+        let series = all.entry(key.to_owned()).or_insert_with(|| Series {
+            resolution: options.resolution.unwrap_or_default(),
+            retention,
+            buckets: BTreeMap::new(),
+        });
+        series.retention = retention;
+        series.trim(now);
+
+        let start = timestamp - timestamp % series.resolution.as_millis();
+        let bucket = series.buckets.entry(start).or_insert(Bucket {
+            sum: 0.0,
+            count: 0,
+            max: value,
+        });
+        bucket.add(&Bucket {
+            sum: value,
+            count: 1,
+            max: value,
+        });
+        Ok(TimeSeriesPoint {
+            timestamp: start,
+            value: bucket.sum,
+        })
+    }
+
+    /// Aggregate the buckets starting in `from..=to` (unix milliseconds).
+    ///
+    /// Buckets are grouped into `bucket`-millisecond intervals, the series'
+    /// resolution when `None`; the interval must be a multiple of it. Empty
+    /// intervals are left out, and a missing key has no points.
+    pub fn range(
+        &self,
+        key: &str,
+        from: u64,
+        to: u64,
+        aggregation: TimeSeriesAggregation,
+        bucket: Option<u64>,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        self.range_at(key, from, to, aggregation, bucket, unix_millis())
+    }
+
+    fn range_at(
+        &self,
+        key: &str,
+        from: u64,
+        to: u64,
+        aggregation: TimeSeriesAggregation,
+        bucket: Option<u64>,
+        now: u64,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        let mut all = self.series.lock();
+        let Some(series) = all.get_mut(key) else {
+            return Ok(Vec::new());
+        };
+        let resolution = series.resolution.as_millis();
+        let width = bucket.unwrap_or(resolution);
+        if width == 0 || !width.is_multiple_of(resolution) {
+            return Err(SynapError::InvalidValue(format!(
+                "Bucket must be a multiple of the {} resolution of time series '{key}'",
+                series.resolution.name()
+            )));
+        }
+        series.trim(now);
+        if from > to {
+            return Ok(Vec::new());
+        }
+
+        let mut groups: Vec<(u64, Bucket)> = Vec::new();
+        for (start, bucket) in series.buckets.range(from..=to) {
+            let group = start - start % width;
+            match groups.last_mut() {
+                Some((last, total)) if *last == group => total.add(bucket),
+                _ => groups.push((group, *bucket)),
+            }
+        }
+        Ok(groups
+            .into_iter()
+            .map(|(timestamp, total)| TimeSeriesPoint {
+                timestamp,
+                value: total.value(aggregation),
+            })
+            .collect())
+    }
+
+    /// Drop the series on `key`.
+    pub fn delete(&self, key: &str) -> bool {
+        self.series.lock().remove(key).is_some()
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> TimeSeriesStats {
+        let all = self.series.lock();
+        TimeSeriesStats {
+            series: all.len(),
+            buckets: all.values().map(|series| series.buckets.len()).sum(),
+            increments: self.increments.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000_000;
+
+    fn points(points: &[(u64, f64)]) -> Vec<TimeSeriesPoint> {
+        points
+            .iter()
+            .map(|&(timestamp, value)| TimeSeriesPoint { timestamp, value })
+            .collect()
+    }
+
+    #[test]
+    fn increments_land_in_their_bucket() {
+        let store = TimeSeriesStore::new();
+        let options = SeriesOptions::default();
+
+        let point = store
+            .incr_at("hits", 2.0, Some(NOW + 250), &options, NOW)
+            .unwrap();
+        assert_eq!(point, points(&[(NOW, 2.0)])[0]);
+        let point = store
+            .incr_at("hits", 3.0, Some(NOW + 999), &options, NOW)
+            .unwrap();
+        assert_eq!(point.value, 5.0);
+        store
+            .incr_at("hits", 1.0, Some(NOW + 1_000), &options, NOW)
+            .unwrap();
+
+        let range = store
+            .range_at(
+                "hits",
+                NOW,
+                NOW + 5_000,
+                TimeSeriesAggregation::Sum,
+                None,
+                NOW,
+            )
+            .unwrap();
+        assert_eq!(range, points(&[(NOW, 5.0), (NOW + 1_000, 1.0)]));
+        assert!(
+            store
+                .range_at(
+                    "missing",
+                    0,
+                    u64::MAX,
+                    TimeSeriesAggregation::Sum,
+                    None,
+                    NOW
+                )
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn ranges_aggregate_into_wider_buckets() {
+        let store = TimeSeriesStore::new();
+        let options = SeriesOptions::default();
+        for (offset, value) in [(0, 4.0), (1_000, 2.0), (2_000, 9.0), (60_000, 1.0)] {
+            store
+                .incr_at("latency", value, Some(NOW + offset), &options, NOW)
+                .unwrap();
+        }
+        let minute = NOW - NOW % 60_000;
+        let range = |aggregation| {
+            store
+                .range_at("latency", 0, u64::MAX, aggregation, Some(60_000), NOW)
+                .unwrap()
+        };
+
+        assert_eq!(
+            range(TimeSeriesAggregation::Sum),
+            points(&[(minute, 15.0), (minute + 60_000, 1.0)])
+        );
+        assert_eq!(
+            range(TimeSeriesAggregation::Avg),
+            points(&[(minute, 5.0), (minute + 60_000, 1.0)])
+        );
+        assert_eq!(
+            range(TimeSeriesAggregation::Max),
+            points(&[(minute, 9.0), (minute + 60_000, 1.0)])
+        );
+
+        // Only whole multiples of the resolution
+        assert!(
+            store
+                .range_at(
+                    "latency",
+                    0,
+                    u64::MAX,
+                    TimeSeriesAggregation::Sum,
+                    Some(1_500),
+                    NOW
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn the_resolution_is_fixed_when_the_series_is_created() {
+        let store = TimeSeriesStore::new();
+        let hourly = SeriesOptions {
+            resolution: Some(TimeSeriesResolution::Hour),
+            retention: None,
+        };
+        let point = store.incr_at("jobs", 1.0, None, &hourly, NOW).unwrap();
+        assert_eq!(point.timestamp, NOW - NOW % 3_600_000);
+
+        // Later increments may leave it out, but not change it
+        assert!(
+            store
+                .incr_at("jobs", 1.0, None, &SeriesOptions::default(), NOW)
+                .is_ok()
+        );
+        let minutely = SeriesOptions {
+            resolution: Some(TimeSeriesResolution::Minute),
+            retention: None,
+        };
+        assert!(store.incr_at("jobs", 1.0, None, &minutely, NOW).is_err());
+    }
+
+    #[test]
+    fn buckets_past_the_retention_are_trimmed() {
+        let store = TimeSeriesStore::new();
+        let options = SeriesOptions {
+            resolution: None,
+            retention: Some(Duration::from_secs(10)),
+        };
+        store.incr_at("k", 1.0, None, &options, NOW).unwrap();
+        store
+            .incr_at("k", 1.0, None, &options, NOW + 5_000)
+            .unwrap();
+
+        // Too old to be written
+        assert!(
+            store
+                .incr_at("k", 1.0, Some(NOW), &options, NOW + 20_000)
+                .is_err()
+        );
+        let range = store
+            .range_at(
+                "k",
+                0,
+                u64::MAX,
+                TimeSeriesAggregation::Sum,
+                None,
+                NOW + 12_000,
+            )
+            .unwrap();
+        assert_eq!(range, points(&[(NOW + 5_000, 1.0)]));
+        assert_eq!(store.stats().buckets, 1);
+    }
+
+    #[test]
+    fn expired_series_are_swept() {
+        let store = TimeSeriesStore::new();
+        let options = SeriesOptions {
+            resolution: None,
+            retention: Some(Duration::from_secs(1)),
+        };
+        for i in 1..SWEEP_INTERVAL {
+            store
+                .incr_at(&format!("k:{i}"), 1.0, None, &options, NOW)
+                .unwrap();
+        }
+
+        store
+            .incr_at("fresh", 1.0, None, &options, NOW + 60_000)
+            .unwrap();
+        let stats = store.stats();
+        assert_eq!((stats.series, stats.buckets), (1, 1));
+
+        assert!(store.delete("fresh"));
+        assert!(!store.delete("fresh"));
+    }
+
+    #[test]
+    fn non_finite_increments_are_rejected() {
+        let store = TimeSeriesStore::new();
+        let options = SeriesOptions::default();
+        assert!(store.incr_at("k", f64::NAN, None, &options, NOW).is_err());
+        assert!(
+            store
+                .incr_at("k", f64::INFINITY, None, &options, NOW)
+                .is_err()
+        );
+        assert_eq!(store.stats().series, 0);
+    }
+}
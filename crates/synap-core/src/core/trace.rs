@@ -0,0 +1,359 @@
+//! Message delivery timelines.
+//!
+//! [`MessageTracer`] records the life of queue messages and stream events —
+//! publish, each delivery with its consumer and attempt, ack, nack, ack
+//! deadline expiry — so an operator can answer "where did my message go"
+//! with `GET /messages/{id}/trace`.
+//!
+//! The table is bounded: past `max_tracked` messages the trace of the oldest
+//! publish is dropped, and past `max_events` steps of one message the oldest
+//! steps after its publish are.
+//!
+//! Delivered queue messages also carry trace headers, whether or not the
+//! timeline is recorded: [`ENQUEUED_AT_HEADER`], [`DELIVERY_ATTEMPT_HEADER`]
+//! and [`CONSUMER_HEADER`].
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Header with the Unix time (milliseconds) the message was enqueued
+pub const ENQUEUED_AT_HEADER: &str = "x-synap-enqueued-at";
+/// Header with the delivery attempt, 1 on the first delivery
+pub const DELIVERY_ATTEMPT_HEADER: &str = "x-synap-delivery-attempt";
+/// Header with the id of the consumer the message was delivered to
+pub const CONSUMER_HEADER: &str = "x-synap-consumer";
+
+/// Where a traced message was published, e.g. `{"queue": "orders"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceSource {
+    Queue(String),
+    /// A stream room
+    Stream(String),
+}
+
+/// What became of a message its consumer gave back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NackOutcome {
+    /// Put back on the queue for another delivery
+    Requeued,
+    /// Out of retries, moved to the dead letter queue
+    DeadLettered,
+    /// Nacked without requeue, so dropped
+    Discarded,
+}
+
+/// One step of a message's life, e.g. `{"step": "acked", "consumer": "w1"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum TraceStep {
+    /// Published to a queue
+    Enqueued {
+        priority: u8,
+    },
+    /// Appended to a stream room
+    Published {
+        offset: u64,
+    },
+    /// Handed to a queue consumer
+    Delivered {
+        consumer: String,
+        attempt: u32,
+    },
+    /// Read by a stream subscriber
+    Read {
+        consumer: String,
+    },
+    Acked {
+        consumer: String,
+    },
+    Nacked {
+        consumer: String,
+        outcome: NackOutcome,
+    },
+    /// The consumer's ack deadline passed
+    Expired {
+        consumer: String,
+        outcome: NackOutcome,
+    },
+    /// Removed by a queue purge
+    Purged,
+}
+
+/// A step and when it happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEvent {
+    /// Unix timestamp (milliseconds)
+    pub at: u64,
+    #[serde(flatten)]
+    pub step: TraceStep,
+}
+
+/// The recorded life of one message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageTrace {
+    pub id: String,
+    pub source: TraceSource,
+    /// Unix timestamp (milliseconds) of the publish
+    pub enqueued_at: u64,
+    /// Queue deliveries, or stream reads
+    #[serde(default)]
+    pub deliveries: u32,
+    /// Consumers the message went to, in order of first delivery
+    #[serde(default)]
+    pub consumers: Vec<String>,
+    /// Unix timestamp (milliseconds) of the ack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acked_at: Option<u64>,
+    /// Steps in order. Past the per-message limit the oldest steps after the
+    /// publish are dropped and counted in `dropped_events`.
+    #[serde(default)]
+    pub events: Vec<TraceEvent>,
+    #[serde(default)]
+    pub dropped_events: u64,
+}
+
+#[derive(Debug, Default)]
+struct Traces {
+    by_id: HashMap<String, MessageTrace>,
+    /// Ids in publish order, oldest first
+    order: VecDeque<String>,
+}
+
+/// Bounded per-message timelines of queues and streams.
+#[derive(Debug)]
+pub struct MessageTracer {
+    max_tracked: usize,
+    max_events: usize,
+    traces: Mutex<Traces>,
+}
+
+impl MessageTracer {
+    /// Keep the timelines of the last `max_tracked` published messages, at
+    /// most `max_events` steps each (at least 2: the publish and the latest).
+    pub fn new(max_tracked: usize, max_events: usize) -> Self {
+        Self {
+            max_tracked: max_tracked.max(1),
+            max_events: max_events.max(2),
+            traces: Mutex::new(Traces::default()),
+        }
+    }
+
+    /// Start the timeline of a message just published to `source`.
+    pub fn publish(&self, id: &str, source: TraceSource, step: TraceStep) {
+        let at = now_ms();
+        let trace = MessageTrace {
+            id: id.to_string(),
+            source,
+            enqueued_at: at,
+            deliveries: 0,
+            consumers: Vec::new(),
+            acked_at: None,
+            events: vec![TraceEvent { at, step }],
+            dropped_events: 0,
+        };
+
+        let mut traces = self.traces.lock();
+        if traces.by_id.insert(id.to_string(), trace).is_none() {
+            traces.order.push_back(id.to_string());
+        }
+        while traces.order.len() > self.max_tracked {
+            if let Some(oldest) = traces.order.pop_front() {
+                traces.by_id.remove(&oldest);
+            }
+        }
+    }
+
+    /// Add a step to a message's timeline. Messages that are not traced
+    /// (published before tracing was on, or evicted) are ignored.
+    pub fn record(&self, id: &str, step: TraceStep) {
+        self.record_all([id], step);
+    }
+
+    /// Add the same step to several timelines, e.g. a batch of stream events
+    /// read by one subscriber.
+    pub fn record_all<'a>(&self, ids: impl IntoIterator<Item = &'a str>, step: TraceStep) {
+        let at = now_ms();
+        let mut traces = self.traces.lock();
+        for id in ids {
+            if let Some(trace) = traces.by_id.get_mut(id) {
+                self.apply(trace, at, step.clone());
+            }
+        }
+    }
+
+    fn apply(&self, trace: &mut MessageTrace, at: u64, step: TraceStep) {
+        match &step {
+            TraceStep::Delivered { consumer, .. } | TraceStep::Read { consumer } => {
+                trace.deliveries += 1;
+                if !trace.consumers.contains(consumer) {
+                    trace.consumers.push(consumer.clone());
+                }
+            }
+            TraceStep::Acked { .. } => trace.acked_at = Some(at),
+            _ => {}
+        }
+
+        if trace.events.len() >= self.max_events {
+            trace.events.remove(1);
+            trace.dropped_events += 1;
+        }
+        trace.events.push(TraceEvent { at, step });
+    }
+
+    /// Timeline of a message, or `None` if it is not traced.
+    pub fn get(&self, id: &str) -> Option<MessageTrace> {
+        self.traces.lock().by_id.get(id).cloned()
+    }
+
+    /// Number of messages traced.
+    pub fn len(&self) -> usize {
+        self.traces.lock().by_id.len()
+    }
+
+    /// Whether no message is traced.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_trace_round_trips() {
+        let trace = MessageTrace {
+            id: "m1".to_string(),
+            source: TraceSource::Queue("orders".to_string()),
+            enqueued_at: 1_000,
+            deliveries: 1,
+            consumers: vec!["w1".to_string()],
+            acked_at: None,
+            events: vec![
+                TraceEvent {
+                    at: 1_000,
+                    step: TraceStep::Enqueued { priority: 5 },
+                },
+                TraceEvent {
+                    at: 1_500,
+                    step: TraceStep::Nacked {
+                        consumer: "w1".to_string(),
+                        outcome: NackOutcome::DeadLettered,
+                    },
+                },
+            ],
+            dropped_events: 0,
+        };
+        let value = serde_json::to_value(&trace).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "id": "m1",
+                "source": {"queue": "orders"},
+                "enqueued_at": 1000,
+                "deliveries": 1,
+                "consumers": ["w1"],
+                "events": [
+                    {"at": 1000, "step": "enqueued", "priority": 5},
+                    {"at": 1500, "step": "nacked", "consumer": "w1", "outcome": "dead_lettered"}
+                ],
+                "dropped_events": 0
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<MessageTrace>(value).unwrap(),
+            trace
+        );
+    }
+
+    fn delivered(consumer: &str, attempt: u32) -> TraceStep {
+        TraceStep::Delivered {
+            consumer: consumer.to_string(),
+            attempt,
+        }
+    }
+
+    #[test]
+    fn test_timeline_summary() {
+        let tracer = MessageTracer::new(10, 16);
+        tracer.publish(
+            "m1",
+            TraceSource::Queue("jobs".to_string()),
+            TraceStep::Enqueued { priority: 5 },
+        );
+        tracer.record("m1", delivered("w1", 1));
+        tracer.record(
+            "m1",
+            TraceStep::Expired {
+                consumer: "w1".to_string(),
+                outcome: NackOutcome::Requeued,
+            },
+        );
+        tracer.record("m1", delivered("w2", 2));
+        tracer.record(
+            "m1",
+            TraceStep::Acked {
+                consumer: "w2".to_string(),
+            },
+        );
+        tracer.record("unknown", delivered("w1", 1));
+
+        let trace = tracer.get("m1").unwrap();
+        assert_eq!(trace.deliveries, 2);
+        assert_eq!(trace.consumers, ["w1", "w2"]);
+        assert!(trace.acked_at.is_some_and(|at| at >= trace.enqueued_at));
+        assert_eq!(trace.events.len(), 5);
+        assert!(tracer.get("unknown").is_none());
+    }
+
+    #[test]
+    fn test_bounded() {
+        let tracer = MessageTracer::new(2, 3);
+        for id in ["a", "b", "c"] {
+            tracer.publish(
+                id,
+                TraceSource::Stream("room".to_string()),
+                TraceStep::Published { offset: 0 },
+            );
+        }
+        assert_eq!(tracer.len(), 2);
+        assert!(tracer.get("a").is_none());
+
+        for consumer in ["s1", "s2", "s3"] {
+            tracer.record(
+                "c",
+                TraceStep::Read {
+                    consumer: consumer.to_string(),
+                },
+            );
+        }
+        let trace = tracer.get("c").unwrap();
+        assert_eq!(trace.dropped_events, 1);
+        assert_eq!(trace.deliveries, 3);
+        assert_eq!(
+            trace.events.iter().map(|e| &e.step).collect::<Vec<_>>(),
+            [
+                &TraceStep::Published { offset: 0 },
+                &TraceStep::Read {
+                    consumer: "s2".to_string()
+                },
+                &TraceStep::Read {
+                    consumer: "s3".to_string()
+                },
+            ]
+        );
+    }
+}
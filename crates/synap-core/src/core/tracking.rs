@@ -0,0 +1,320 @@
+//! Server-assisted client-side caching.
+//!
+//! A client that caches KV values locally registers the keys it has read, and
+//! the server tells it when one of them changes, the way Redis `CLIENT
+//! TRACKING` does. The [`TrackingTable`] remembers which clients hold which
+//! keys; the [`KeyWatchNotifier`](crate::core::KeyWatchNotifier) it is attached
+//! to calls [`TrackingTable::invalidate`] on every KV mutation.
+//!
+//! # Semantics
+//!
+//! Each key is invalidated **once** per registration: the entry is dropped when
+//! the invalidation is sent, and the client re-registers the key on its next
+//! read. A client that cannot keep up is disconnected, like a slow pub/sub
+//! subscriber, and must flush its cache — it can no longer tell which of its
+//! entries are stale. The table is capped at `max_keys`; past that the oldest
+//! registration is invalidated to make room, so the cap costs cache hits, never
+//! correctness.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// Default cap on the number of tracked keys.
+pub const DEFAULT_TRACKING_MAX_KEYS: usize = 1_000_000;
+
+/// Invalidations buffered per client before it is dropped as a slow consumer.
+const CLIENT_BUFFER: usize = 1024;
+
+/// One message pushed to a tracking client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Invalidation {
+    /// These keys changed; drop them from the local cache.
+    Keys(Vec<String>),
+    /// Every key changed (`FLUSHDB`); drop the whole local cache.
+    Flush,
+}
+
+/// Tracking table counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrackingStats {
+    /// Connected tracking clients.
+    pub clients: usize,
+    /// Keys with at least one registration.
+    pub tracked_keys: usize,
+    /// Invalidation messages sent.
+    pub invalidations_sent: u64,
+    /// Clients disconnected for not draining their invalidations.
+    pub slow_clients_dropped: u64,
+}
+
+/// Which clients cache which keys.
+pub struct TrackingTable {
+    max_keys: usize,
+    /// Tracked key → ids of the clients caching it.
+    keys: RwLock<HashMap<String, HashSet<u64>, ahash::RandomState>>,
+    /// Registration order, for evicting past `max_keys`. May hold keys that
+    /// were already invalidated; those are skipped on eviction.
+    order: Mutex<VecDeque<String>>,
+    /// `keys.len()`, readable without the lock — the idle fast path.
+    tracked: AtomicUsize,
+    clients: RwLock<HashMap<u64, mpsc::Sender<Invalidation>, ahash::RandomState>>,
+    next_client: AtomicU64,
+    invalidations_sent: AtomicU64,
+    slow_clients_dropped: AtomicU64,
+}
+
+impl Default for TrackingTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRACKING_MAX_KEYS)
+    }
+}
+
+impl TrackingTable {
+    /// Create a table tracking at most `max_keys` keys.
+    pub fn new(max_keys: usize) -> Self {
+        Self {
+            max_keys: max_keys.max(1),
+            keys: RwLock::new(HashMap::default()),
+            order: Mutex::new(VecDeque::new()),
+            tracked: AtomicUsize::new(0),
+            clients: RwLock::new(HashMap::default()),
+            next_client: AtomicU64::new(1),
+            invalidations_sent: AtomicU64::new(0),
+            slow_clients_dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Register a client and return its id with the receiving end of its
+    /// invalidations. The channel closes when the client is dropped.
+    pub fn register(&self) -> (u64, mpsc::Receiver<Invalidation>) {
+        let id = self.next_client.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel(CLIENT_BUFFER);
+        self.clients.write().insert(id, tx);
+        (id, rx)
+    }
+
+    /// Remove a client and every registration it holds.
+    pub fn unregister(&self, client: u64) {
+        if self.clients.write().remove(&client).is_none() {
+            return;
+        }
+        let mut keys = self.keys.write();
+        keys.retain(|_, clients| {
+            clients.remove(&client);
+            !clients.is_empty()
+        });
+        self.tracked.store(keys.len(), Ordering::Relaxed);
+    }
+
+    /// Register `keys` as cached by `client`.
+    ///
+    /// Returns `false` when the client is not (or no longer) registered, in
+    /// which case nothing is tracked.
+    pub fn track(&self, client: u64, keys: &[String]) -> bool {
+        if !self.clients.read().contains_key(&client) {
+            return false;
+        }
+        let mut evicted = Vec::new();
+        {
+            let mut table = self.keys.write();
+            let mut order = self.order.lock();
+            for key in keys {
+                let clients = table.entry(key.clone()).or_default();
+                if clients.insert(client) && clients.len() == 1 {
+                    order.push_back(key.clone());
+                }
+            }
+            while table.len() > self.max_keys {
+                let Some(oldest) = order.pop_front() else {
+                    break;
+                };
+                if let Some(clients) = table.remove(&oldest) {
+                    evicted.push((oldest, clients));
+                }
+            }
+            // Invalidated keys leave stale entries behind; compact before the
+            // queue outgrows the table it indexes.
+            if order.len() > table.len().saturating_mul(2).max(1024) {
+                order.retain(|key| table.contains_key(key));
+            }
+            self.tracked.store(table.len(), Ordering::Relaxed);
+        }
+        for (key, clients) in evicted {
+            self.send(&clients, || Invalidation::Keys(vec![key.clone()]));
+        }
+        true
+    }
+
+    /// Invalidate `key` for every client caching it, and stop tracking it.
+    ///
+    /// Costs one atomic load while nothing is tracked, which is what keeps it
+    /// affordable on the KV write path.
+    pub fn invalidate(&self, key: &str) {
+        if self.tracked.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        if !self.keys.read().contains_key(key) {
+            return;
+        }
+        let clients = {
+            let mut table = self.keys.write();
+            let clients = table.remove(key);
+            self.tracked.store(table.len(), Ordering::Relaxed);
+            clients
+        };
+        if let Some(clients) = clients {
+            self.send(&clients, || Invalidation::Keys(vec![key.to_owned()]));
+        }
+    }
+
+    /// Invalidate every key for every client (`FLUSHDB`).
+    pub fn invalidate_all(&self) {
+        {
+            let mut table = self.keys.write();
+            table.clear();
+            self.order.lock().clear();
+            self.tracked.store(0, Ordering::Relaxed);
+        }
+        let clients: HashSet<u64> = self.clients.read().keys().copied().collect();
+        self.send(&clients, || Invalidation::Flush);
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> TrackingStats {
+        TrackingStats {
+            clients: self.clients.read().len(),
+            tracked_keys: self.tracked.load(Ordering::Relaxed),
+            invalidations_sent: self.invalidations_sent.load(Ordering::Relaxed),
+            slow_clients_dropped: self.slow_clients_dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Push one message to each of `clients`, dropping the ones whose buffer
+    /// is full or whose receiver is gone.
+    fn send(&self, clients: &HashSet<u64>, message: impl Fn() -> Invalidation) {
+        let mut gone = Vec::new();
+        {
+            let senders = self.clients.read();
+            for id in clients {
+                let Some(sender) = senders.get(id) else {
+                    continue;
+                };
+                match sender.try_send(message()) {
+                    Ok(()) => {
+                        self.invalidations_sent.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(mpsc::error::TrySendError::Full(_)) => {
+                        self.slow_clients_dropped.fetch_add(1, Ordering::Relaxed);
+                        gone.push(*id);
+                    }
+                    Err(mpsc::error::TrySendError::Closed(_)) => gone.push(*id),
+                }
+            }
+        }
+        // Dropping the sender closes the client's channel, which ends its
+        // connection: it must flush, having missed an invalidation.
+        for id in gone {
+            self.unregister(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(names: &[&str]) -> Vec<String> {
+        names.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn a_tracked_key_is_invalidated_once() {
+        let table = TrackingTable::default();
+        let (client, mut rx) = table.register();
+        assert!(table.track(client, &keys(&["a", "b"])));
+
+        table.invalidate("a");
+        table.invalidate("a");
+        table.invalidate("untracked");
+
+        assert_eq!(rx.try_recv().unwrap(), Invalidation::Keys(keys(&["a"])));
+        assert!(rx.try_recv().is_err(), "the registration is consumed");
+        assert_eq!(table.stats().tracked_keys, 1);
+    }
+
+    #[test]
+    fn only_the_clients_caching_a_key_hear_about_it() {
+        let table = TrackingTable::default();
+        let (reader, mut reader_rx) = table.register();
+        let (_other, mut other_rx) = table.register();
+        table.track(reader, &keys(&["k"]));
+
+        table.invalidate("k");
+
+        assert!(reader_rx.try_recv().is_ok());
+        assert!(other_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn flush_reaches_every_client() {
+        let table = TrackingTable::default();
+        let (a, mut a_rx) = table.register();
+        let (_b, mut b_rx) = table.register();
+        table.track(a, &keys(&["k"]));
+
+        table.invalidate_all();
+
+        assert_eq!(a_rx.try_recv().unwrap(), Invalidation::Flush);
+        assert_eq!(b_rx.try_recv().unwrap(), Invalidation::Flush);
+        assert_eq!(table.stats().tracked_keys, 0);
+    }
+
+    #[test]
+    fn past_the_cap_the_oldest_key_is_invalidated() {
+        let table = TrackingTable::new(2);
+        let (client, mut rx) = table.register();
+        table.track(client, &keys(&["a", "b", "c"]));
+
+        assert_eq!(rx.try_recv().unwrap(), Invalidation::Keys(keys(&["a"])));
+        assert_eq!(table.stats().tracked_keys, 2);
+    }
+
+    #[test]
+    fn unregister_drops_the_clients_registrations() {
+        let table = TrackingTable::default();
+        let (client, _rx) = table.register();
+        table.track(client, &keys(&["a", "b"]));
+
+        table.unregister(client);
+
+        assert_eq!(table.stats().tracked_keys, 0);
+        assert!(
+            !table.track(client, &keys(&["a"])),
+            "unknown clients are refused"
+        );
+    }
+
+    #[test]
+    fn a_slow_client_is_dropped() {
+        let table = TrackingTable::default();
+        let (client, mut rx) = table.register();
+
+        for i in 0..=CLIENT_BUFFER {
+            let key = format!("k{i}");
+            table.track(client, std::slice::from_ref(&key));
+            table.invalidate(&key);
+        }
+
+        assert_eq!(table.stats().slow_clients_dropped, 1);
+        assert_eq!(table.stats().clients, 0);
+        while rx.try_recv().is_ok() {}
+        assert!(matches!(
+            rx.try_recv(),
+            Err(mpsc::error::TryRecvError::Disconnected)
+        ));
+    }
+}
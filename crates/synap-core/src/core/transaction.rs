@@ -7,9 +7,11 @@
 //! - Key versioning for WATCH (optimistic locking)
 //! - Atomic execution with sorted multi-key locking (deadlock prevention)
 //! - Automatic rollback on conflict
+//! - Queue and stream publishes queued alongside data-structure writes, so a
+//!   KV update and the job it enqueues commit together
 
 use super::error::{Result, SynapError};
-use super::{HashStore, KVStore, ListStore, SetStore, SortedSetStore};
+use super::{HashStore, KVStore, ListStore, QueueManager, SetStore, SortedSetStore, StreamManager};
 use parking_lot::RwLock;
 use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
@@ -75,6 +77,19 @@ pub enum TransactionCommand {
         key: String,
         members: Vec<Vec<u8>>,
     },
+    /// Queue commands
+    QueuePublish {
+        queue: String,
+        payload: Vec<u8>,
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+    },
+    /// Stream commands
+    StreamPublish {
+        room: String,
+        event: String,
+        data: Vec<u8>,
+    },
 }
 
 /// The durable effect of a committed transaction command.
@@ -127,6 +142,22 @@ pub enum CommittedWrite {
         key: String,
         members: Vec<Vec<u8>>,
     },
+    /// A message enqueued by the transaction, carrying the id and resolved
+    /// priority/retry settings it was published with.
+    QueuePublish {
+        queue: String,
+        message_id: String,
+        payload: Vec<u8>,
+        priority: u8,
+        max_retries: u32,
+    },
+    /// An event appended to a stream room at `offset`.
+    StreamPublish {
+        room: String,
+        event: String,
+        data: Vec<u8>,
+        offset: u64,
+    },
 }
 
 /// Watched key version info (stored at WATCH time)
@@ -228,6 +259,10 @@ impl Transaction {
                 | TransactionCommand::SetRem { key, .. } => {
                     keys.insert(key.clone());
                 }
+                // Publishes append to a queue/room rather than a keyed value;
+                // they are serialized by the EXEC lock, not the per-key locks.
+                TransactionCommand::QueuePublish { .. }
+                | TransactionCommand::StreamPublish { .. } => {}
             }
         }
 
@@ -262,6 +297,10 @@ pub struct TransactionManager {
     set_store: Arc<SetStore>,
     #[allow(dead_code)] // Reserved for future SortedSet transaction operations
     sorted_set_store: Arc<SortedSetStore>,
+    /// Queue manager for `QueuePublish`; `None` when the queue system is disabled.
+    queue_manager: Option<Arc<QueueManager>>,
+    /// Stream manager for `StreamPublish`; `None` when streams are disabled.
+    stream_manager: Option<Arc<StreamManager>>,
     /// Serializes EXEC so two transactions cannot interleave and the WATCH
     /// check-and-apply happens as one atomic critical section (audit M-008).
     exec_lock: Arc<tokio::sync::Mutex<()>>,
@@ -285,10 +324,24 @@ impl TransactionManager {
             list_store: _list_store,
             set_store: _set_store,
             sorted_set_store: _sorted_set_store,
+            queue_manager: None,
+            stream_manager: None,
             exec_lock: Arc::new(tokio::sync::Mutex::new(())),
         }
     }
 
+    /// Enable `QueuePublish` inside transactions.
+    pub fn with_queue_manager(mut self, queue_manager: Option<Arc<QueueManager>>) -> Self {
+        self.queue_manager = queue_manager;
+        self
+    }
+
+    /// Enable `StreamPublish` inside transactions.
+    pub fn with_stream_manager(mut self, stream_manager: Option<Arc<StreamManager>>) -> Self {
+        self.stream_manager = stream_manager;
+        self
+    }
+
     /// Start a new transaction (MULTI)
     pub fn multi(&self, client_id: String) -> Result<()> {
         debug!("MULTI client_id={}", client_id);
@@ -406,13 +459,18 @@ impl TransactionManager {
             return Ok(None);
         }
 
-        // Get all keys to lock (sorted to prevent deadlock)
-        let keys_to_lock = transaction.get_keys_to_lock();
-
-        if keys_to_lock.is_empty() {
+        if transaction.is_empty() {
             return Ok(Some((Vec::new(), Vec::new())));
         }
 
+        // Reject up front any publish that cannot succeed, so a missing queue,
+        // a full queue or an unknown room never leaves the transaction applied
+        // halfway.
+        self.validate_publishes(&transaction.commands)?;
+
+        // Get all keys to lock (sorted to prevent deadlock)
+        let keys_to_lock = transaction.get_keys_to_lock();
+
         // Hold the per-key locks for the union of touched keys across the whole
         // command execution, so a non-transactional writer to any of those keys
         // is ordered entirely before or after the EXEC — never interleaved
@@ -474,6 +532,47 @@ impl TransactionManager {
         Ok(false)
     }
 
+    /// Check that every queued publish has a target that exists and, for
+    /// queues, enough remaining depth for all of the transaction's messages.
+    fn validate_publishes(&self, commands: &[TransactionCommand]) -> Result<()> {
+        let mut per_queue: HashMap<&str, usize> = HashMap::new();
+
+        for cmd in commands {
+            match cmd {
+                TransactionCommand::QueuePublish { queue, .. } => {
+                    if self.queue_manager.is_none() {
+                        return Err(SynapError::InvalidRequest(
+                            "Queue system disabled".to_string(),
+                        ));
+                    }
+                    *per_queue.entry(queue.as_str()).or_insert(0) += 1;
+                }
+                TransactionCommand::StreamPublish { room, .. } => {
+                    let stream_manager = self.stream_manager.as_ref().ok_or_else(|| {
+                        SynapError::InvalidRequest("Stream system disabled".to_string())
+                    })?;
+                    if !stream_manager.has_room(room) {
+                        return Err(SynapError::InvalidRequest(format!(
+                            "Room '{}' not found",
+                            room
+                        )));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(queue_manager) = &self.queue_manager {
+            for (queue, count) in per_queue {
+                if queue_manager.available_capacity(queue)? < count {
+                    return Err(SynapError::QueueFull(queue.to_string()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Execute all commands in the transaction.
     ///
     /// Returns the per-command JSON results *and* the durable [`CommittedWrite`]
@@ -615,6 +714,43 @@ impl TransactionManager {
                     });
                     serde_json::json!({"removed": removed})
                 }
+                TransactionCommand::QueuePublish {
+                    queue,
+                    payload,
+                    priority,
+                    max_retries,
+                } => {
+                    let queue_manager = self.queue_manager.as_ref().ok_or_else(|| {
+                        SynapError::InvalidRequest("Queue system disabled".to_string())
+                    })?;
+                    let message = queue_manager
+                        .publish_with_message(queue, payload.clone(), *priority, *max_retries)
+                        .await?;
+                    writes.push(CommittedWrite::QueuePublish {
+                        queue: queue.clone(),
+                        message_id: message.id.clone(),
+                        payload: payload.clone(),
+                        priority: message.priority,
+                        max_retries: message.max_retries,
+                    });
+                    serde_json::json!({"message_id": message.id})
+                }
+                TransactionCommand::StreamPublish { room, event, data } => {
+                    let stream_manager = self.stream_manager.as_ref().ok_or_else(|| {
+                        SynapError::InvalidRequest("Stream system disabled".to_string())
+                    })?;
+                    let offset = stream_manager
+                        .publish(room, event, data.clone())
+                        .await
+                        .map_err(SynapError::InvalidRequest)?;
+                    writes.push(CommittedWrite::StreamPublish {
+                        room: room.clone(),
+                        event: event.clone(),
+                        data: data.clone(),
+                        offset,
+                    });
+                    serde_json::json!({"offset": offset, "room": room})
+                }
             };
 
             results.push(result);
@@ -1044,4 +1180,171 @@ mod tests {
 
         assert!(manager.exec(cid).await.unwrap().is_none());
     }
+
+    fn make_manager_with_messaging() -> (
+        Arc<KVStore>,
+        Arc<QueueManager>,
+        Arc<StreamManager>,
+        TransactionManager,
+    ) {
+        let (kv, _hash, _list, _set, manager) = make_manager();
+        let queues = Arc::new(QueueManager::new(super::super::QueueConfig::default()));
+        let streams = Arc::new(StreamManager::new(super::super::StreamConfig::default()));
+        let manager = manager
+            .with_queue_manager(Some(Arc::clone(&queues)))
+            .with_stream_manager(Some(Arc::clone(&streams)));
+        (kv, queues, streams, manager)
+    }
+
+    /// A KV write, a queue publish and a stream publish commit together, and
+    /// the publishes surface as committed writes for the WAL.
+    #[tokio::test]
+    async fn test_exec_commits_kv_write_with_queue_and_stream_publish() {
+        let (kv, queues, streams, manager) = make_manager_with_messaging();
+        queues.create_queue("jobs", None).await.unwrap();
+        streams.create_room("events").await.unwrap();
+
+        let cid = "cid";
+        manager.multi(cid.to_string()).unwrap();
+        for cmd in [
+            TransactionCommand::KVSet {
+                key: "order:1".into(),
+                value: b"paid".to_vec(),
+                ttl: None,
+            },
+            TransactionCommand::QueuePublish {
+                queue: "jobs".into(),
+                payload: b"ship order:1".to_vec(),
+                priority: Some(7),
+                max_retries: None,
+            },
+            TransactionCommand::StreamPublish {
+                room: "events".into(),
+                event: "order.paid".into(),
+                data: b"{}".to_vec(),
+            },
+        ] {
+            manager.queue_command_if_transaction(cid, cmd).unwrap();
+        }
+
+        let (results, writes) = manager.exec(cid).await.unwrap().expect("committed");
+        assert_eq!(results.len(), 3);
+        assert_eq!(kv.get("order:1").await.unwrap(), Some(b"paid".to_vec()));
+        assert_eq!(queues.stats("jobs").await.unwrap().depth, 1);
+        assert_eq!(streams.room_stats("events").await.unwrap().message_count, 1);
+
+        match &writes[1] {
+            CommittedWrite::QueuePublish {
+                queue,
+                message_id,
+                priority,
+                max_retries,
+                ..
+            } => {
+                assert_eq!(queue, "jobs");
+                assert_eq!(results[1]["message_id"], serde_json::json!(message_id));
+                assert_eq!(*priority, 7);
+                assert_eq!(*max_retries, 3);
+            }
+            other => panic!("expected QueuePublish, got {other:?}"),
+        }
+        assert!(matches!(
+            &writes[2],
+            CommittedWrite::StreamPublish { room, offset: 0, .. } if room == "events"
+        ));
+    }
+
+    /// A publish to a missing queue rejects the whole EXEC before anything is
+    /// applied — the earlier KV write must not land.
+    #[tokio::test]
+    async fn test_exec_publish_to_missing_queue_applies_nothing() {
+        let (kv, _queues, _streams, manager) = make_manager_with_messaging();
+        let cid = "cid";
+        manager.multi(cid.to_string()).unwrap();
+        manager
+            .queue_command_if_transaction(
+                cid,
+                TransactionCommand::KVSet {
+                    key: "k".into(),
+                    value: b"v".to_vec(),
+                    ttl: None,
+                },
+            )
+            .unwrap();
+        manager
+            .queue_command_if_transaction(
+                cid,
+                TransactionCommand::QueuePublish {
+                    queue: "missing".into(),
+                    payload: b"x".to_vec(),
+                    priority: None,
+                    max_retries: None,
+                },
+            )
+            .unwrap();
+
+        assert!(matches!(
+            manager.exec(cid).await,
+            Err(SynapError::QueueNotFound(_))
+        ));
+        assert_eq!(kv.get("k").await.unwrap(), None);
+    }
+
+    /// More messages than the queue has room for reject the EXEC up front.
+    #[tokio::test]
+    async fn test_exec_publish_beyond_queue_depth_applies_nothing() {
+        let (_kv, queues, _streams, manager) = make_manager_with_messaging();
+        queues
+            .create_queue(
+                "small",
+                Some(super::super::QueueConfig {
+                    max_depth: 1,
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        let cid = "cid";
+        manager.multi(cid.to_string()).unwrap();
+        for _ in 0..2 {
+            manager
+                .queue_command_if_transaction(
+                    cid,
+                    TransactionCommand::QueuePublish {
+                        queue: "small".into(),
+                        payload: b"x".to_vec(),
+                        priority: None,
+                        max_retries: None,
+                    },
+                )
+                .unwrap();
+        }
+
+        assert!(matches!(
+            manager.exec(cid).await,
+            Err(SynapError::QueueFull(_))
+        ));
+        assert_eq!(queues.stats("small").await.unwrap().depth, 0);
+    }
+
+    /// Publishing without the queue system wired in is an error, not a no-op.
+    #[tokio::test]
+    async fn test_exec_queue_publish_without_queue_manager_fails() {
+        let (_kv, _hash, _list, _set, manager) = make_manager();
+        let cid = "cid";
+        manager.multi(cid.to_string()).unwrap();
+        manager
+            .queue_command_if_transaction(
+                cid,
+                TransactionCommand::QueuePublish {
+                    queue: "jobs".into(),
+                    payload: b"x".to_vec(),
+                    priority: None,
+                    max_retries: None,
+                },
+            )
+            .unwrap();
+        assert!(manager.exec(cid).await.is_err());
+    }
 }
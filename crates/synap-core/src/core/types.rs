@@ -176,7 +176,7 @@ impl StoredValue {
     }
 
     /// Create a new stored value that expires at a specific absolute millisecond timestamp.
-    pub fn with_expires_at_ms(data: Vec<u8>, expires_at_ms: u64) -> Self {
+    pub fn with_expires_at_ms(data: impl Into<Arc<[u8]>>, expires_at_ms: u64) -> Self {
         Self::Expiring {
             data: data.into(),
             expires_at: expires_at_ms,
@@ -323,6 +323,22 @@ impl StoredValue {
         }
     }
 
+    /// Copy a payload of at least `min_bytes` into a fresh allocation so the
+    /// allocator can release the fragmented pages the old one sat in. Shared
+    /// payloads (a reader still holds the `Arc`) are left alone, since the old
+    /// buffer would stay alive anyway. Returns the bytes copied.
+    pub fn relocate(&mut self, min_bytes: usize) -> usize {
+        match self {
+            Self::Persistent(data) | Self::Expiring { data, .. }
+                if data.len() >= min_bytes && Arc::strong_count(data) == 1 =>
+            {
+                *data = Arc::from(&data[..]);
+                data.len()
+            }
+            _ => 0,
+        }
+    }
+
     /// Get last access timestamp in seconds (for LRU eviction).
     pub fn last_access(&self) -> u32 {
         match self {
@@ -331,6 +347,16 @@ impl StoredValue {
         }
     }
 
+    /// Encoding (`int` or `raw`) and estimated memory of the value stored
+    /// under `key`.
+    pub fn memory(&self, key: &str) -> crate::core::KeyMemory {
+        let encoding = match self {
+            Self::Int { .. } => "int",
+            Self::Persistent(_) | Self::Expiring { .. } => "raw",
+        };
+        crate::core::KeyMemory::new(key, encoding, 1, self.data().len())
+    }
+
     // ── Integer encoding (phase13 int-encoding-counters) ─────────────────────
 
     /// Create an integer-encoded persistent value.
@@ -405,6 +431,22 @@ pub enum EvictionPolicy {
     VolatileLfu,
 }
 
+impl EvictionPolicy {
+    /// Config name of the policy (e.g. `allkeys-lru`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NoEviction => "noeviction",
+            Self::AllKeysLru => "allkeys-lru",
+            Self::VolatileLru => "volatile-lru",
+            Self::AllKeysRandom => "allkeys-random",
+            Self::VolatileRandom => "volatile-random",
+            Self::VolatileTtl => "volatile-ttl",
+            Self::AllKeysLfu => "allkeys-lfu",
+            Self::VolatileLfu => "volatile-lfu",
+        }
+    }
+}
+
 /// Configuration for KV store
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KVConfig {
@@ -421,6 +463,19 @@ pub struct KVConfig {
     pub max_value_size_bytes: Option<usize>,
     /// Number of random keys sampled per shard during eviction (Redis default: 5).
     pub eviction_sample_size: usize,
+    /// Number of independently locked shards keys are spread over (default:
+    /// one per CPU). Snapshots and the WAL are keyed by name, not shard, so
+    /// this can change between restarts.
+    #[serde(default = "default_shard_count")]
+    pub shard_count: usize,
+    /// Compression of large values at rest (off by default)
+    #[serde(default)]
+    pub compression: crate::compression::ValueCompressionConfig,
+}
+
+/// One shard per available CPU
+pub fn default_shard_count() -> usize {
+    std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
 }
 
 impl Default for KVConfig {
@@ -432,10 +487,70 @@ impl Default for KVConfig {
             allow_flush_commands: false,
             max_value_size_bytes: None,
             eviction_sample_size: 5,
+            shard_count: default_shard_count(),
+            compression: Default::default(),
         }
     }
 }
 
+/// Background defragmentation of the KV store (`KVStore::start_defrag`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefragConfig {
+    /// Run the background task (disabled by default)
+    pub enabled: bool,
+    /// How often the task checks whether a pass is due, in seconds
+    pub interval_secs: u64,
+    /// Run a pass once accounted memory has dropped this many bytes below its
+    /// peak since the last pass (0 runs one on every check)
+    pub min_freed_bytes: u64,
+    /// Shrink a shard's map only when at least this many bytes of unused
+    /// capacity would be returned
+    pub min_reclaimable_bytes: usize,
+    /// Copy values at least this large into fresh allocations (0 disables)
+    pub relocate_value_bytes: usize,
+}
+
+impl Default for DefragConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 30,
+            min_freed_bytes: 64 * 1024 * 1024,
+            min_reclaimable_bytes: 64 * 1024,
+            relocate_value_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Outcome of one defragmentation pass (`KVStore::defrag`)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DefragReport {
+    /// Shards whose maps were shrunk
+    pub shards_compacted: usize,
+    /// Estimated bytes of unused map and heap capacity released
+    pub reclaimed_bytes: u64,
+    /// Values copied into fresh allocations
+    pub relocated_values: u64,
+    /// Bytes copied by those relocations
+    pub relocated_bytes: u64,
+}
+
+/// Lock activity of one KV shard (returned by `KVStore::shard_stats()`)
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct KVShardStats {
+    /// Shard index
+    pub shard: usize,
+    /// Keys held by the shard, including expired ones not yet cleaned up
+    pub keys: usize,
+    /// Read lock acquisitions
+    pub reads: u64,
+    /// Write lock acquisitions
+    pub writes: u64,
+    /// Acquisitions that had to wait for another holder
+    pub contended: u64,
+}
+
 /// Statistics for KV store (snapshot — returned by `KVStore::stats()`)
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct KVStats {
@@ -453,6 +568,18 @@ pub struct KVStats {
     pub hits: u64,
     /// Number of cache misses
     pub misses: u64,
+    /// Keys evicted to stay under the memory limit
+    pub evicted_keys: u64,
+    /// Bytes freed by eviction
+    pub evicted_bytes: u64,
+    /// Writes refused because the memory limit was reached
+    pub rejected_writes: u64,
+    /// Defragmentation passes run
+    pub defrag_passes: u64,
+    /// Estimated bytes released by defragmentation
+    pub defrag_reclaimed_bytes: u64,
+    /// Bytes copied into fresh allocations by defragmentation
+    pub defrag_relocated_bytes: u64,
 }
 
 impl KVStats {
@@ -488,6 +615,12 @@ pub struct AtomicKVStats {
     pub dels: AtomicU64,
     pub hits: AtomicU64,
     pub misses: AtomicU64,
+    pub evicted_keys: AtomicU64,
+    pub evicted_bytes: AtomicU64,
+    pub rejected_writes: AtomicU64,
+    pub defrag_passes: AtomicU64,
+    pub defrag_reclaimed_bytes: AtomicU64,
+    pub defrag_relocated_bytes: AtomicU64,
 }
 
 impl AtomicKVStats {
@@ -500,6 +633,12 @@ impl AtomicKVStats {
             dels: self.dels.load(Ordering::Relaxed),
             hits: self.hits.load(Ordering::Relaxed),
             misses: self.misses.load(Ordering::Relaxed),
+            evicted_keys: self.evicted_keys.load(Ordering::Relaxed),
+            evicted_bytes: self.evicted_bytes.load(Ordering::Relaxed),
+            rejected_writes: self.rejected_writes.load(Ordering::Relaxed),
+            defrag_passes: self.defrag_passes.load(Ordering::Relaxed),
+            defrag_reclaimed_bytes: self.defrag_reclaimed_bytes.load(Ordering::Relaxed),
+            defrag_relocated_bytes: self.defrag_relocated_bytes.load(Ordering::Relaxed),
         }
     }
 }
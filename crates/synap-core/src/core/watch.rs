@@ -27,6 +27,7 @@ use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 
 use crate::core::pubsub::PubSubRouter;
+use crate::core::tracking::TrackingTable;
 
 /// Default cap on an inlined value, in bytes.
 ///
@@ -76,6 +77,8 @@ pub struct KeyWatchNotifier {
     /// store. Entries are dropped when a key is deleted or expires, so a
     /// watched-then-deleted key does not leak.
     versions: RwLock<std::collections::HashMap<String, Arc<AtomicU64>, ahash::RandomState>>,
+    /// Client-side caching registrations, invalidated on the same events.
+    tracking: Option<Arc<TrackingTable>>,
 }
 
 impl KeyWatchNotifier {
@@ -94,9 +97,22 @@ impl KeyWatchNotifier {
             db,
             inline_value_cap,
             versions: RwLock::new(std::collections::HashMap::default()),
+            tracking: None,
         }
     }
 
+    /// Attach a client-side caching [`TrackingTable`], invalidated on every
+    /// event that changes or removes a key.
+    pub fn with_tracking(mut self, tracking: Arc<TrackingTable>) -> Self {
+        self.tracking = Some(tracking);
+        self
+    }
+
+    /// The attached tracking table, if any.
+    pub fn tracking(&self) -> Option<&Arc<TrackingTable>> {
+        self.tracking.as_ref()
+    }
+
     /// The channel a watcher subscribes to for `key`.
     pub fn channel_for(&self, key: &str) -> String {
         format!("__watch@{}__:{}", self.db, key)
@@ -119,6 +135,13 @@ impl KeyWatchNotifier {
     /// the key's channel: an idle key costs one router lookup, which is what
     /// keeps this affordable on the hot write path.
     pub fn notify(&self, event: &str, key: &str, value: Option<&[u8]>) {
+        // A TTL change leaves the cached value valid
+        if let Some(ref tracking) = self.tracking
+            && !matches!(event, "expire" | "persist")
+        {
+            tracking.invalidate(key);
+        }
+
         let channel = self.channel_for(key);
 
         // The fast path. Checked before the version bump too, so an unwatched
@@ -172,6 +195,9 @@ impl KeyWatchNotifier {
     /// into the keys' next incarnations.
     pub fn forget_all(&self) {
         self.versions.write().clear();
+        if let Some(ref tracking) = self.tracking {
+            tracking.invalidate_all();
+        }
     }
 
     /// Next monotonic version for `key`, starting at 1.
@@ -9,7 +9,7 @@
 //!   geospatial, streams, partitions, consumer groups, queues, pub/sub,
 //!   transactions, and the shared [`core::error::SynapError`].
 //! - [`cache`] — adaptive L1/L2 caching.
-//! - [`compression`] — LZ4/Zstd compressors.
+//! - [`compression`] — LZ4/Zstd compressors, per-link and stored-value compression.
 //! - [`simd`] — SIMD-accelerated primitives.
 //! - [`cluster`] — hash-slot routing, topology, migration and raft primitives
 //!   (kept alongside `core` because the sharded KV store references them).
@@ -0,0 +1,389 @@
+//! Live migration from Redis (`synap-migrate from-redis`)
+//!
+//! Walks the Redis keyspace with SCAN and copies every key into Synap over
+//! the API while Redis keeps serving traffic. Strings, hashes, lists, sets
+//! and sorted sets are sent as `key.restore` payloads, which replace the key
+//! in one step; streams become stream rooms with one event per entry.
+//!
+//! The SCAN cursor is checkpointed after every batch, so an interrupted run
+//! resumes where it stopped. Keys changed in Redis after they were copied
+//! are not copied again: run a second pass with `--restart` right before
+//! moving clients over.
+
+use anyhow::{Context, Result, bail};
+use indicatif::{ProgressBar, ProgressStyle};
+use redis::aio::MultiplexedConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use synap_core::core::{DumpedValue, KeyDump};
+use synap_sdk::{RestoreOptions, SynapClient, SynapConfig};
+use tracing::{info, warn};
+
+/// Entries read per XRANGE call
+const STREAM_PAGE: usize = 500;
+
+/// Stream event type of migrated stream entries
+const STREAM_EVENT: &str = "xadd";
+
+/// An XRANGE reply: entry IDs with their field-value pairs
+type StreamEntries = Vec<(String, Vec<(Vec<u8>, Vec<u8>)>)>;
+
+/// Settings of one `from-redis` run
+pub struct FromRedisOptions {
+    /// Redis URL, e.g. `redis://:password@localhost:6379/0`
+    pub redis_url: String,
+    /// Synap URL, e.g. `http://localhost:15500`
+    pub synap_url: String,
+    /// Synap API key or JWT
+    pub api_key: Option<String>,
+    /// Only migrate keys matching this glob (SCAN MATCH)
+    pub pattern: Option<String>,
+    /// SCAN COUNT hint, and keys between checkpoints
+    pub batch_size: usize,
+    /// Keys per second; unlimited when unset
+    pub rate_limit: Option<u32>,
+    /// Progress file
+    pub checkpoint: PathBuf,
+    /// Ignore the checkpoint and start from the first key
+    pub restart: bool,
+    /// Read and convert every key without writing to Synap
+    pub dry_run: bool,
+}
+
+/// Progress of a run, saved after every SCAN batch
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Checkpoint {
+    /// Redis URL without credentials; a checkpoint only resumes its own source
+    source: String,
+    pattern: Option<String>,
+    /// SCAN cursor of the next batch
+    cursor: u64,
+    migrated: u64,
+    skipped: u64,
+    done: bool,
+}
+
+/// Why a key was not migrated
+enum Outcome {
+    Migrated,
+    Skipped(&'static str),
+}
+
+/// Copy the Redis keyspace into Synap
+pub async fn migrate_from_redis(options: &FromRedisOptions) -> Result<()> {
+    let source = redact(&options.redis_url);
+    let mut checkpoint = if options.restart {
+        None
+    } else {
+        load_checkpoint(&options.checkpoint).await?
+    }
+    .unwrap_or_else(|| Checkpoint {
+        source: source.clone(),
+        pattern: options.pattern.clone(),
+        ..Checkpoint::default()
+    });
+    if checkpoint.source != source || checkpoint.pattern != options.pattern {
+        bail!(
+            "Checkpoint {:?} belongs to {} (pattern {:?}); use --restart or another --checkpoint",
+            options.checkpoint,
+            checkpoint.source,
+            checkpoint.pattern
+        );
+    }
+    if checkpoint.done {
+        info!(
+            "Checkpoint {:?} says the migration is complete ({} keys); use --restart for another pass",
+            options.checkpoint, checkpoint.migrated
+        );
+        return Ok(());
+    }
+    if checkpoint.cursor != 0 {
+        info!(
+            "Resuming at SCAN cursor {} ({} keys migrated, {} skipped)",
+            checkpoint.cursor, checkpoint.migrated, checkpoint.skipped
+        );
+    }
+
+    let mut redis = redis::Client::open(options.redis_url.as_str())
+        .context("Invalid Redis URL")?
+        .get_multiplexed_async_connection()
+        .await
+        .with_context(|| format!("Failed to connect to Redis at {}", source))?;
+    let mut config = SynapConfig::new(options.synap_url.as_str());
+    if let Some(api_key) = &options.api_key {
+        config = config.with_api_key(api_key);
+    }
+    let synap = SynapClient::new(config).context("Failed to create Synap client")?;
+    let mut target = Target {
+        synap,
+        rooms: None,
+        dry_run: options.dry_run,
+    };
+
+    let total: u64 = redis::cmd("DBSIZE").query_async(&mut redis).await?;
+    info!("Migrating from {} ({} keys)", source, total);
+    if options.dry_run {
+        info!("DRY RUN - keys are read and converted but not written");
+    }
+
+    let pb = ProgressBar::new(total);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")?
+            .progress_chars("=>-"),
+    );
+    pb.set_position(checkpoint.migrated + checkpoint.skipped);
+
+    let mut pacer = options.rate_limit.map(|rate| {
+        let mut pacer = tokio::time::interval(Duration::from_secs(1) / rate.max(1));
+        pacer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        pacer
+    });
+
+    loop {
+        let mut scan = redis::cmd("SCAN");
+        scan.arg(checkpoint.cursor);
+        if let Some(pattern) = &options.pattern {
+            scan.arg("MATCH").arg(pattern);
+        }
+        scan.arg("COUNT").arg(options.batch_size);
+        let (next, keys): (u64, Vec<Vec<u8>>) = scan
+            .query_async(&mut redis)
+            .await
+            .context("Redis SCAN failed")?;
+
+        for key in keys {
+            if let Some(pacer) = &mut pacer {
+                pacer.tick().await;
+            }
+            let name = String::from_utf8_lossy(&key).into_owned();
+            pb.set_message(name.clone());
+            let outcome = target
+                .migrate_key(&mut redis, key)
+                .await
+                .with_context(|| format!("Failed to migrate key {:?}", name))?;
+            match outcome {
+                Outcome::Migrated => checkpoint.migrated += 1,
+                Outcome::Skipped(reason) => {
+                    warn!("Skipping key {:?}: {}", name, reason);
+                    checkpoint.skipped += 1;
+                }
+            }
+            pb.inc(1);
+        }
+
+        checkpoint.cursor = next;
+        checkpoint.done = next == 0;
+        if !options.dry_run {
+            save_checkpoint(&options.checkpoint, &checkpoint).await?;
+        }
+        if checkpoint.done {
+            break;
+        }
+    }
+
+    pb.finish_with_message("done");
+    info!(
+        "Migration finished: {} keys migrated, {} skipped",
+        checkpoint.migrated, checkpoint.skipped
+    );
+    Ok(())
+}
+
+/// Where keys are written
+struct Target {
+    synap: SynapClient,
+    /// Synap stream rooms, listed on the first Redis stream
+    rooms: Option<HashSet<String>>,
+    dry_run: bool,
+}
+
+impl Target {
+    async fn migrate_key(
+        &mut self,
+        redis: &mut MultiplexedConnection,
+        key: Vec<u8>,
+    ) -> Result<Outcome> {
+        let Ok(name) = String::from_utf8(key) else {
+            return Ok(Outcome::Skipped("key name is not UTF-8"));
+        };
+
+        let key_type: String = redis::cmd("TYPE").arg(&name).query_async(redis).await?;
+        if key_type == "stream" {
+            return self.migrate_stream(redis, &name).await;
+        }
+
+        let pttl: i64 = redis::cmd("PTTL").arg(&name).query_async(redis).await?;
+        if pttl == -2 {
+            return Ok(Outcome::Skipped("key expired or was deleted"));
+        }
+        let value = match key_type.as_str() {
+            "string" => {
+                let value: Option<Vec<u8>> =
+                    redis::cmd("GET").arg(&name).query_async(redis).await?;
+                value.map(DumpedValue::String)
+            }
+            "hash" => {
+                let fields: Vec<(Vec<u8>, Vec<u8>)> =
+                    redis::cmd("HGETALL").arg(&name).query_async(redis).await?;
+                let mut converted = Vec::with_capacity(fields.len());
+                for (field, value) in fields {
+                    let Ok(field) = String::from_utf8(field) else {
+                        return Ok(Outcome::Skipped("hash field is not UTF-8"));
+                    };
+                    converted.push((field, value));
+                }
+                non_empty(converted).map(DumpedValue::Hash)
+            }
+            "list" => {
+                let items: Vec<Vec<u8>> = redis::cmd("LRANGE")
+                    .arg(&name)
+                    .arg(0)
+                    .arg(-1)
+                    .query_async(redis)
+                    .await?;
+                non_empty(items).map(DumpedValue::List)
+            }
+            "set" => {
+                let members: Vec<Vec<u8>> =
+                    redis::cmd("SMEMBERS").arg(&name).query_async(redis).await?;
+                non_empty(members).map(DumpedValue::Set)
+            }
+            "zset" => {
+                let members: Vec<(Vec<u8>, f64)> = redis::cmd("ZRANGE")
+                    .arg(&name)
+                    .arg(0)
+                    .arg(-1)
+                    .arg("WITHSCORES")
+                    .query_async(redis)
+                    .await?;
+                non_empty(members).map(DumpedValue::SortedSet)
+            }
+            "none" => None,
+            _ => return Ok(Outcome::Skipped("type is not supported by Synap")),
+        };
+        let Some(value) = value else {
+            return Ok(Outcome::Skipped("key expired or was deleted"));
+        };
+
+        // Synap only expires strings
+        let mut ttl_ms = u64::try_from(pttl).ok().filter(|ttl| *ttl > 0);
+        if ttl_ms.is_some() && !matches!(value, DumpedValue::String(_)) {
+            warn!(
+                "Key {:?} is a {} with a TTL; it is migrated without one",
+                name, key_type
+            );
+            ttl_ms = None;
+        }
+
+        if !self.dry_run {
+            let payload = KeyDump { value, ttl_ms }.encode();
+            let options = RestoreOptions {
+                replace: true,
+                ..RestoreOptions::default()
+            };
+            self.synap.kv().restore(&name, payload, options).await?;
+        }
+        Ok(Outcome::Migrated)
+    }
+
+    /// Recreate a stream as a room with one event per entry. The entry ID
+    /// and fields are the event's data: `{"id": "...", "fields": {...}}`.
+    async fn migrate_stream(
+        &mut self,
+        redis: &mut MultiplexedConnection,
+        name: &str,
+    ) -> Result<Outcome> {
+        let mut events = Vec::new();
+        let mut start = "-".to_string();
+        loop {
+            let entries: StreamEntries = redis::cmd("XRANGE")
+                .arg(name)
+                .arg(&start)
+                .arg("+")
+                .arg("COUNT")
+                .arg(STREAM_PAGE)
+                .query_async(redis)
+                .await?;
+            let last_page = entries.len() < STREAM_PAGE;
+            if let Some((id, _)) = entries.last() {
+                start = format!("({}", id);
+            }
+            for (id, fields) in entries {
+                let mut data = Map::with_capacity(fields.len());
+                for (field, value) in fields {
+                    let (Ok(field), Ok(value)) =
+                        (String::from_utf8(field), String::from_utf8(value))
+                    else {
+                        return Ok(Outcome::Skipped("stream entry is not UTF-8"));
+                    };
+                    data.insert(field, Value::String(value));
+                }
+                events.push(json!({ "id": id, "fields": data }));
+            }
+            if last_page {
+                break;
+            }
+        }
+        if self.dry_run {
+            return Ok(Outcome::Migrated);
+        }
+
+        // Start from an empty room so a resumed run does not publish twice
+        let streams = self.synap.stream();
+        let rooms = match &mut self.rooms {
+            Some(rooms) => rooms,
+            None => self
+                .rooms
+                .insert(streams.list().await?.into_iter().collect()),
+        };
+        if rooms.contains(name) {
+            streams.delete_room(name).await?;
+        }
+        streams.create_room(name, None).await?;
+        rooms.insert(name.to_string());
+        for event in events {
+            streams.publish(name, STREAM_EVENT, event).await?;
+        }
+        Ok(Outcome::Migrated)
+    }
+}
+
+/// Redis drops empty collections; an empty read means the key went away
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+    (!items.is_empty()).then_some(items)
+}
+
+/// The URL without a password, for logs and checkpoints
+fn redact(url: &str) -> String {
+    match (url.split_once("://"), url.rsplit_once('@')) {
+        (Some((scheme, _)), Some((_, host))) => format!("{}://{}", scheme, host),
+        _ => url.to_string(),
+    }
+}
+
+async fn load_checkpoint(path: &Path) -> Result<Option<Checkpoint>> {
+    match tokio::fs::read(path).await {
+        Ok(data) => {
+            Ok(Some(serde_json::from_slice(&data).with_context(|| {
+                format!("Failed to parse checkpoint {:?}", path)
+            })?))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read checkpoint {:?}", path)),
+    }
+}
+
+async fn save_checkpoint(path: &Path, checkpoint: &Checkpoint) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, serde_json::to_vec_pretty(checkpoint)?)
+        .await
+        .with_context(|| format!("Failed to write checkpoint {:?}", tmp))?;
+    tokio::fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("Failed to write checkpoint {:?}", path))?;
+    Ok(())
+}
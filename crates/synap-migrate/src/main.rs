@@ -1,7 +1,8 @@
 //! Synap Migration Tool
 //!
 //! Migrates standalone Synap installations to HiveHub.Cloud SaaS mode
-//! by adding user namespace prefixes to all resources.
+//! by adding user namespace prefixes to all resources, and copies live
+//! Redis instances into Synap (`from-redis`).
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
@@ -10,6 +11,7 @@ use tracing::{info, warn};
 use uuid::Uuid;
 
 mod backup;
+mod from_redis;
 mod migrate;
 mod rollback;
 mod snapshot;
@@ -17,7 +19,7 @@ mod validate;
 
 #[derive(Parser)]
 #[command(name = "synap-migrate")]
-#[command(about = "Migration tool for Synap standalone to HiveHub.Cloud SaaS mode", long_about = None)]
+#[command(about = "Migration tool for Synap standalone to HiveHub.Cloud SaaS mode, and Redis to Synap", long_about = None)]
 #[command(version)]
 struct Cli {
     #[command(subcommand)]
@@ -92,6 +94,45 @@ enum Commands {
         #[arg(short, long, default_value = "./data")]
         data_dir: PathBuf,
     },
+
+    /// Copy every key of a running Redis instance into Synap
+    FromRedis {
+        /// Redis URL (redis://[:password@]host:port[/db])
+        #[arg(long, default_value = "redis://127.0.0.1:6379")]
+        redis_url: String,
+
+        /// Synap server URL
+        #[arg(long, default_value = "http://127.0.0.1:15500")]
+        synap_url: String,
+
+        /// Synap API key or JWT
+        #[arg(long)]
+        api_key: Option<String>,
+
+        /// Only migrate keys matching this glob pattern
+        #[arg(long = "match")]
+        pattern: Option<String>,
+
+        /// Keys per SCAN batch; progress is checkpointed after each batch
+        #[arg(long, default_value_t = 1000)]
+        batch_size: usize,
+
+        /// Maximum keys per second
+        #[arg(long)]
+        rate_limit: Option<u32>,
+
+        /// Progress file, used to resume an interrupted migration
+        #[arg(long, default_value = "./redis-migration.json")]
+        checkpoint: PathBuf,
+
+        /// Ignore the checkpoint and copy every key again
+        #[arg(long)]
+        restart: bool,
+
+        /// Read and convert keys without writing to Synap
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
@@ -190,6 +231,33 @@ async fn main() -> Result<()> {
             let status = validate::check_status(&data_dir).await?;
             println!("{}", status);
         }
+
+        Commands::FromRedis {
+            redis_url,
+            synap_url,
+            api_key,
+            pattern,
+            batch_size,
+            rate_limit,
+            checkpoint,
+            restart,
+            dry_run,
+        } => {
+            let options = from_redis::FromRedisOptions {
+                redis_url,
+                synap_url,
+                api_key,
+                pattern,
+                batch_size,
+                rate_limit,
+                checkpoint,
+                restart,
+                dry_run,
+            };
+            from_redis::migrate_from_redis(&options)
+                .await
+                .context("Redis migration failed")?;
+        }
     }
 
     Ok(())
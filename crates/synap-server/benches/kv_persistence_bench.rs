@@ -31,6 +31,8 @@ fn bench_kv_with_persistence(c: &mut Criterion) {
             fsync_mode: FsyncMode::Always,
             fsync_interval_ms: 10,
             max_size_mb: 100,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         },
         snapshot: SnapshotConfig {
             enabled: true,
@@ -39,7 +41,10 @@ fn bench_kv_with_persistence(c: &mut Criterion) {
             operation_threshold: 10000,
             max_snapshots: 3,
             compression: false,
+            incremental: Default::default(),
         },
+        durable_subscriptions: false,
+        archive: Default::default(),
     };
 
     let rt = Runtime::new().unwrap();
@@ -53,6 +58,7 @@ fn bench_kv_with_persistence(c: &mut Criterion) {
             allow_flush_commands: false,
             max_value_size_bytes: None,
             eviction_sample_size: 5,
+            ..Default::default()
         }));
 
         let persist = Arc::new(PersistenceLayer::new(persist_config.clone()).await.unwrap());
@@ -162,8 +168,12 @@ fn bench_fsync_modes(c: &mut Criterion) {
                         fsync_mode: *mode,
                         fsync_interval_ms: 10,
                         max_size_mb: 100,
+                        group_commit: Default::default(),
+                        fsync_by_class: Default::default(),
                     },
                     snapshot: SnapshotConfig::default(),
+                    durable_subscriptions: false,
+                    archive: Default::default(),
                 };
 
                 let rt = Runtime::new().unwrap();
@@ -221,6 +231,8 @@ fn bench_recovery(c: &mut Criterion) {
             fsync_mode: FsyncMode::Always,
             fsync_interval_ms: 10,
             max_size_mb: 100,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         },
         snapshot: SnapshotConfig {
             enabled: true,
@@ -229,7 +241,10 @@ fn bench_recovery(c: &mut Criterion) {
             operation_threshold: 10000,
             max_snapshots: 3,
             compression: false,
+            incremental: Default::default(),
         },
+        durable_subscriptions: false,
+        archive: Default::default(),
     };
 
     let rt = Runtime::new().unwrap();
@@ -298,7 +313,10 @@ fn bench_snapshot_creation(c: &mut Criterion) {
                         operation_threshold: 10000,
                         max_snapshots: 3,
                         compression: false,
+                        incremental: Default::default(),
                     },
+                    durable_subscriptions: false,
+                    archive: Default::default(),
                 };
 
                 let rt = Runtime::new().unwrap();
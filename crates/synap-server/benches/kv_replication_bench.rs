@@ -41,9 +41,12 @@ async fn create_kv_with_master() -> (Arc<MasterNode>, Arc<KVStore>, std::net::So
 
     let kv = Arc::new(KVStore::new(KVConfig::default()));
     let master = Arc::new(
-        MasterNode::new(config, Arc::clone(&kv), None)
-            .await
-            .unwrap(),
+        MasterNode::new(
+            config,
+            synap_server::persistence::StoreArcs::kv_only(Arc::clone(&kv)),
+        )
+        .await
+        .unwrap(),
     );
 
     tokio::time::sleep(Duration::from_millis(50)).await;
@@ -125,7 +128,7 @@ fn bench_kv_set_with_1_replica(c: &mut Criterion) {
 
                 master.replicate(Operation::KVSet {
                     key,
-                    value,
+                    value: value.into(),
                     ttl: None,
                 });
             }
@@ -168,7 +171,7 @@ fn bench_kv_set_with_3_replicas(c: &mut Criterion) {
 
                 master.replicate(Operation::KVSet {
                     key,
-                    value,
+                    value: value.into(),
                     ttl: None,
                 });
             }
@@ -230,7 +233,7 @@ fn bench_kv_get_with_1_replica(c: &mut Criterion) {
             kv.set(&key, value.clone(), None).await.unwrap();
             master.replicate(Operation::KVSet {
                 key,
-                value,
+                value: value.into(),
                 ttl: None,
             });
         }
@@ -294,7 +297,7 @@ fn bench_kv_batch_operations(c: &mut Criterion) {
                     kv.set(&key, value.clone(), None).await.unwrap();
                     master.replicate(Operation::KVSet {
                         key,
-                        value,
+                        value: value.into(),
                         ttl: None,
                     });
                 }
@@ -368,7 +371,7 @@ fn bench_kv_mixed_workload(c: &mut Criterion) {
                 kv.set(&key, value.clone(), None).await.unwrap();
                 master.replicate(Operation::KVSet {
                     key,
-                    value,
+                    value: value.into(),
                     ttl: None,
                 });
             }
@@ -394,7 +397,7 @@ fn bench_kv_mixed_workload(c: &mut Criterion) {
                         kv.set(&key, value.clone(), None).await.unwrap();
                         master.replicate(Operation::KVSet {
                             key,
-                            value,
+                            value: value.into(),
                             ttl: None,
                         });
                     }
@@ -28,7 +28,7 @@ fn bench_wal_throughput(c: &mut Criterion) {
                     for i in 0..batch_size {
                         let op = Operation::KVSet {
                             key: format!("key_{}", i),
-                            value: vec![0u8; 64],
+                            value: vec![0u8; 64].into(),
                             ttl: None,
                         };
                         wal.append(op).await.unwrap();
@@ -165,7 +165,7 @@ fn bench_recovery(c: &mut Criterion) {
         for i in 5000..10000 {
             let op = Operation::KVSet {
                 key: format!("key_{:08}", i),
-                value: vec![0u8; 64],
+                value: vec![0u8; 64].into(),
                 ttl: None,
             };
             wal.append(op).await.unwrap();
@@ -221,7 +221,7 @@ fn bench_concurrent_wal(c: &mut Criterion) {
                             for i in 0..100 {
                                 let op = Operation::KVSet {
                                     key: format!("writer_{}_key_{}", writer_id, i),
-                                    value: vec![0u8; 64],
+                                    value: vec![0u8; 64].into(),
                                     ttl: None,
                                 };
                                 wal_clone.append(op).await.unwrap();
@@ -35,8 +35,12 @@ fn bench_queue_with_persistence(c: &mut Criterion) {
                         fsync_mode: *mode,
                         fsync_interval_ms: 10,
                         max_size_mb: 100,
+                        group_commit: Default::default(),
+                        fsync_by_class: Default::default(),
                     },
                     snapshot: SnapshotConfig::default(),
+                    durable_subscriptions: false,
+                    archive: Default::default(),
                 };
 
                 let rt = Runtime::new().unwrap();
@@ -104,8 +108,12 @@ fn bench_queue_consume_with_persist(c: &mut Criterion) {
             fsync_mode: FsyncMode::Always,
             fsync_interval_ms: 10,
             max_size_mb: 100,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         },
         snapshot: SnapshotConfig::default(),
+        durable_subscriptions: false,
+        archive: Default::default(),
     };
 
     let rt = Runtime::new().unwrap();
@@ -173,8 +181,12 @@ fn bench_concurrent_with_persist(c: &mut Criterion) {
             fsync_mode: FsyncMode::Always,
             fsync_interval_ms: 10,
             max_size_mb: 100,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         },
         snapshot: SnapshotConfig::default(),
+        durable_subscriptions: false,
+        archive: Default::default(),
     };
 
     group.bench_function("10_concurrent_publishers", |b| {
@@ -1,3 +1,4 @@
+use bytes::Bytes;
 use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 use std::hint::black_box;
 use std::sync::Arc;
@@ -20,7 +21,7 @@ fn bench_replication_log_append(c: &mut Criterion) {
                 for i in 0..size {
                     let op = Operation::KVSet {
                         key: format!("key_{}", i),
-                        value: vec![i as u8],
+                        value: vec![i as u8].into(),
                         ttl: None,
                     };
                     black_box(log.append(op));
@@ -42,7 +43,7 @@ fn bench_replication_log_get_from_offset(c: &mut Criterion) {
     for i in 0..10_000 {
         log.append(Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![i as u8],
+            value: vec![i as u8].into(),
             ttl: None,
         });
     }
@@ -83,13 +84,16 @@ fn bench_master_replication(c: &mut Criterion) {
                     config.replica_listen_address = Some("127.0.0.1:0".parse().unwrap());
 
                     let kv = Arc::new(KVStore::new(KVConfig::default()));
-                    let master = MasterNode::new(config, kv, None).await.unwrap();
+                    let master =
+                        MasterNode::new(config, synap_server::persistence::StoreArcs::kv_only(kv))
+                            .await
+                            .unwrap();
 
                     // Replicate operations
                     for i in 0..batch_size {
                         let op = Operation::KVSet {
                             key: format!("key_{}", i),
-                            value: vec![i as u8],
+                            value: vec![i as u8].into(),
                             ttl: None,
                         };
                         black_box(master.replicate(op));
@@ -180,13 +184,50 @@ fn bench_snapshot_apply(c: &mut Criterion) {
     group.finish();
 }
 
+/// Fan-out of one write to the WAL, the replication log and 8 replica
+/// streams: each hop clones the value payload. `vec` is the cost of the
+/// former `Vec<u8>` payload, `bytes` the shared `Bytes` payload used now.
+fn bench_operation_fanout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("operation_fanout");
+    const HOPS: usize = 10;
+
+    for value_size in [64, 4096, 65_536] {
+        group.throughput(Throughput::Bytes((value_size * HOPS) as u64));
+
+        let value = vec![0xAB_u8; value_size];
+        group.bench_with_input(BenchmarkId::new("vec", value_size), &value, |b, value| {
+            b.iter(|| {
+                for _ in 0..HOPS {
+                    black_box(value.clone());
+                }
+            });
+        });
+
+        let shared = Bytes::from(value.clone());
+        group.bench_with_input(
+            BenchmarkId::new("bytes", value_size),
+            &shared,
+            |b, shared| {
+                b.iter(|| {
+                    for _ in 0..HOPS {
+                        black_box(shared.clone());
+                    }
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_replication_log_append,
     bench_replication_log_get_from_offset,
     bench_master_replication,
     bench_snapshot_creation,
-    bench_snapshot_apply
+    bench_snapshot_apply,
+    bench_operation_fanout
 );
 
 criterion_main!(benches);
@@ -0,0 +1,10 @@
+fn main() {
+    // The gRPC stubs are only generated for the `grpc` feature, so default
+    // builds do not need `protoc`
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/synap.proto");
+        tonic_build::compile_protos("proto/synap.proto")
+            .expect("failed to compile proto/synap.proto");
+    }
+}
@@ -1,7 +1,17 @@
-//! Audit logging for authentication events
+//! Audit logging for authentication events, admin operations and, when
+//! `auth.audit.log_writes` is set, every write
+//!
+//! Entries are kept in memory for `GET /audit` and can also be appended to a
+//! JSON lines file and published to a stream room.
 
+use super::AuthContext;
+use crate::config::AuditConfig;
+use crate::core::StreamManager;
 use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
@@ -35,6 +45,12 @@ pub enum AuthEventType {
     ApiKeyRevoked,
     /// Permission denied
     PermissionDenied,
+    /// Role granted to or revoked from a user
+    RoleChanged,
+    /// Admin operation (flush, failover, cluster change, ...)
+    AdminOperation,
+    /// Data write, recorded only when `log_writes` is set
+    Write,
 }
 
 /// Audit log entry
@@ -134,6 +150,26 @@ impl AuditLogEntry {
         entry.error_message = Some(error_msg);
         entry
     }
+
+    /// Create an entry for an operation performed by `ctx`
+    pub fn operation(
+        event_type: AuthEventType,
+        ctx: &AuthContext,
+        action: impl Into<String>,
+        resource: Option<String>,
+        success: bool,
+    ) -> Self {
+        let mut entry = Self::new(
+            event_type,
+            ctx.user_id.clone(),
+            ctx.client_ip.to_string(),
+            success,
+        );
+        entry.api_key_id = ctx.api_key_id.clone();
+        entry.action = Some(action.into());
+        entry.resource = resource;
+        entry
+    }
 }
 
 /// Audit log manager
@@ -142,6 +178,9 @@ pub struct AuditLogManager {
     entries: Arc<RwLock<Vec<AuditLogEntry>>>,
     max_entries: usize,
     enabled: bool,
+    log_writes: bool,
+    file: Option<Arc<Mutex<File>>>,
+    stream: Option<(Arc<StreamManager>, String)>,
 }
 
 impl AuditLogManager {
@@ -151,9 +190,37 @@ impl AuditLogManager {
             entries: Arc::new(RwLock::new(Vec::new())),
             max_entries,
             enabled: true,
+            log_writes: false,
+            file: None,
+            stream: None,
         }
     }
 
+    /// Create an audit log manager from config, opening the audit file for
+    /// appending. The stream sink is attached with [`Self::with_stream`].
+    pub fn from_config(config: &AuditConfig) -> std::io::Result<Self> {
+        let mut manager = Self::new(config.max_entries);
+        manager.enabled = config.enabled;
+        manager.log_writes = config.log_writes;
+        if let Some(path) = &config.file {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            manager.file = Some(Arc::new(Mutex::new(file)));
+        }
+        Ok(manager)
+    }
+
+    /// Also publish each entry as an `audit` event to `room`
+    pub fn with_stream(mut self, streams: Arc<StreamManager>, room: String) -> Self {
+        self.stream = Some((streams, room));
+        self
+    }
+
+    /// Check if every write is recorded, not only auth events and admin
+    /// operations
+    pub fn logs_writes(&self) -> bool {
+        self.enabled && self.log_writes
+    }
+
     /// Enable or disable audit logging
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
@@ -210,6 +277,14 @@ impl AuditLogManager {
                     entry.client_ip
                 );
             }
+            AuthEventType::AdminOperation => {
+                info!(
+                    "AUDIT: Admin operation - user: {}, action: {}, ip: {}",
+                    entry.username.as_deref().unwrap_or("unknown"),
+                    entry.action.as_deref().unwrap_or("unknown"),
+                    entry.client_ip
+                );
+            }
             _ => {
                 debug!(
                     "AUDIT: {:?} - user: {:?}, ip: {}",
@@ -218,6 +293,10 @@ impl AuditLogManager {
             }
         }
 
+        if self.file.is_some() || self.stream.is_some() {
+            self.write_sinks(&entry).await;
+        }
+
         // Store entry
         let mut entries = self.entries.write().await;
         entries.push(entry);
@@ -228,6 +307,33 @@ impl AuditLogManager {
         }
     }
 
+    /// Append an entry to the audit file and stream room
+    async fn write_sinks(&self, entry: &AuditLogEntry) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("AUDIT: failed to serialize entry: {}", e);
+                return;
+            }
+        };
+
+        if let Some(file) = &self.file
+            && let Err(e) = writeln!(file.lock(), "{line}")
+        {
+            warn!("AUDIT: failed to write audit file: {}", e);
+        }
+
+        if let Some((streams, room)) = &self.stream {
+            let published = match streams.get_or_create_room(room).await {
+                Ok(_) => streams.publish(room, "audit", line.into_bytes()).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = published {
+                warn!("AUDIT: failed to publish to stream '{}': {}", room, e);
+            }
+        }
+    }
+
     /// Get audit log entries
     pub async fn get_entries(
         &self,
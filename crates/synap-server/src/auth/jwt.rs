@@ -0,0 +1,251 @@
+//! JWT access tokens and refresh tokens
+//!
+//! `POST /auth/login` trades a username and password for a short-lived access
+//! token (an HS256 JWT naming the user) and a long-lived refresh token. The
+//! access token is sent as `Authorization: Bearer <token>`; it carries no
+//! permissions, so role changes and disabled accounts take effect on the next
+//! request. The refresh token is an opaque random string, stored only as a
+//! SHA-256 digest, and is single use: `POST /auth/refresh` consumes it and
+//! returns a new pair.
+
+use super::AuthResult;
+use crate::config::JwtConfig;
+use crate::core::SynapError;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use parking_lot::RwLock;
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Prefix of refresh tokens, to tell them apart from API keys (`sk_`)
+const REFRESH_TOKEN_PREFIX: &str = "rt_";
+
+/// Access token claims
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    /// Username
+    pub sub: String,
+    pub iss: String,
+    /// Issued at (Unix seconds)
+    pub iat: i64,
+    /// Expires at (Unix seconds)
+    pub exp: i64,
+}
+
+/// Tokens returned by login and refresh
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub token_type: &'static str,
+    /// Access token lifetime in seconds
+    pub expires_in: u64,
+    pub refresh_token: String,
+    /// Refresh token lifetime in seconds
+    pub refresh_expires_in: u64,
+}
+
+#[derive(Debug, Clone)]
+struct RefreshGrant {
+    username: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Issues and validates tokens
+#[derive(Clone)]
+pub struct TokenManager {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    validation: Validation,
+    issuer: String,
+    access_ttl: u64,
+    refresh_ttl: u64,
+    /// SHA-256 of each outstanding refresh token -> grant
+    refresh_tokens: Arc<RwLock<HashMap<String, RefreshGrant>>>,
+}
+
+impl TokenManager {
+    pub fn new(config: &JwtConfig) -> Self {
+        let secret = if config.secret.is_empty() {
+            warn!(
+                "auth.jwt.secret not set: using a random signing secret, tokens will not survive a restart"
+            );
+            rand::rng().random::<[u8; 32]>().to_vec()
+        } else {
+            config.secret.as_bytes().to_vec()
+        };
+
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[&config.issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+
+        Self {
+            encoding_key: EncodingKey::from_secret(&secret),
+            decoding_key: DecodingKey::from_secret(&secret),
+            validation,
+            issuer: config.issuer.clone(),
+            access_ttl: config.access_token_ttl_secs,
+            refresh_ttl: config.refresh_token_ttl_secs,
+            refresh_tokens: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Whether a bearer credential is a JWT rather than an API key
+    pub fn is_jwt(token: &str) -> bool {
+        token.split('.').count() == 3
+    }
+
+    /// Issue an access token and a refresh token for `username`
+    pub fn issue(&self, username: &str) -> AuthResult<TokenPair> {
+        let now = Utc::now();
+        let claims = Claims {
+            sub: username.to_string(),
+            iss: self.issuer.clone(),
+            iat: now.timestamp(),
+            exp: now.timestamp() + self.access_ttl as i64,
+        };
+        let access_token =
+            jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &self.encoding_key)
+                .map_err(|e| SynapError::InternalError(format!("Failed to sign token: {}", e)))?;
+
+        let refresh_token = format!(
+            "{}{}",
+            REFRESH_TOKEN_PREFIX,
+            hex::encode(rand::rng().random::<[u8; 32]>())
+        );
+        let mut grants = self.refresh_tokens.write();
+        grants.retain(|_, grant| grant.expires_at > now);
+        grants.insert(
+            digest(&refresh_token),
+            RefreshGrant {
+                username: username.to_string(),
+                expires_at: now + Duration::seconds(self.refresh_ttl as i64),
+            },
+        );
+
+        debug!("Issued tokens for user: {}", username);
+        Ok(TokenPair {
+            access_token,
+            token_type: "Bearer",
+            expires_in: self.access_ttl,
+            refresh_token,
+            refresh_expires_in: self.refresh_ttl,
+        })
+    }
+
+    /// Validate an access token's signature, issuer and expiry
+    pub fn verify(&self, token: &str) -> AuthResult<Claims> {
+        jsonwebtoken::decode::<Claims>(token, &self.decoding_key, &self.validation)
+            .map(|data| data.claims)
+            .map_err(|e| SynapError::Unauthorized(format!("Invalid token: {}", e)))
+    }
+
+    /// Consume a refresh token, returning the user it was issued to
+    pub fn redeem(&self, refresh_token: &str) -> AuthResult<String> {
+        let grant = self
+            .refresh_tokens
+            .write()
+            .remove(&digest(refresh_token))
+            .ok_or_else(|| SynapError::Unauthorized("Invalid refresh token".to_string()))?;
+
+        if grant.expires_at <= Utc::now() {
+            return Err(SynapError::Unauthorized(
+                "Refresh token expired".to_string(),
+            ));
+        }
+        Ok(grant.username)
+    }
+
+    /// Revoke a refresh token. Returns whether it was outstanding.
+    pub fn revoke(&self, refresh_token: &str) -> bool {
+        self.refresh_tokens
+            .write()
+            .remove(&digest(refresh_token))
+            .is_some()
+    }
+
+    /// Revoke every refresh token issued to `username`
+    pub fn revoke_user(&self, username: &str) {
+        self.refresh_tokens
+            .write()
+            .retain(|_, grant| grant.username != username);
+    }
+}
+
+fn digest(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> TokenManager {
+        TokenManager::new(&JwtConfig {
+            secret: "test-secret".to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_issue_and_verify() {
+        let tokens = manager();
+        let pair = tokens.issue("alice").unwrap();
+
+        assert!(TokenManager::is_jwt(&pair.access_token));
+        assert!(!TokenManager::is_jwt(&pair.refresh_token));
+        let claims = tokens.verify(&pair.access_token).unwrap();
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.iss, "synap");
+        assert_eq!(claims.exp - claims.iat, 900);
+    }
+
+    #[test]
+    fn test_verify_rejects_foreign_and_expired_tokens() {
+        let tokens = manager();
+        let other = TokenManager::new(&JwtConfig {
+            secret: "other-secret".to_string(),
+            ..Default::default()
+        });
+        let pair = other.issue("alice").unwrap();
+        assert!(tokens.verify(&pair.access_token).is_err());
+
+        let claims = Claims {
+            sub: "alice".to_string(),
+            iss: "synap".to_string(),
+            iat: 0,
+            exp: 1,
+        };
+        let token = jsonwebtoken::encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &tokens.encoding_key,
+        )
+        .unwrap();
+        assert!(tokens.verify(&token).is_err());
+    }
+
+    #[test]
+    fn test_refresh_token_is_single_use() {
+        let tokens = manager();
+        let pair = tokens.issue("alice").unwrap();
+
+        assert_eq!(tokens.redeem(&pair.refresh_token).unwrap(), "alice");
+        assert!(tokens.redeem(&pair.refresh_token).is_err());
+    }
+
+    #[test]
+    fn test_revoke_user() {
+        let tokens = manager();
+        let alice = tokens.issue("alice").unwrap();
+        let bob = tokens.issue("bob").unwrap();
+
+        tokens.revoke_user("alice");
+        assert!(tokens.redeem(&alice.refresh_token).is_err());
+        assert!(tokens.revoke(&bob.refresh_token));
+        assert!(!tokens.revoke(&bob.refresh_token));
+    }
+}
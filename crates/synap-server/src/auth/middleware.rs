@@ -1,4 +1,4 @@
-use super::{ApiKeyManager, AuthContext, UserManager};
+use super::{ApiKeyManager, AuthContext, TokenManager, UserManager};
 use axum::{
     extract::{ConnectInfo, Request},
     http::{StatusCode, header},
@@ -23,6 +23,8 @@ pub enum AuthRejection {
     MalformedHeader,
     #[error("invalid username or password")]
     InvalidCredentials,
+    #[error("access token rejected (bad signature, expired, or user disabled)")]
+    InvalidToken,
 }
 
 /// Authentication middleware
@@ -66,7 +68,22 @@ impl AuthMiddleware {
         let client_ip = Self::get_client_ip(&req);
         debug!("Processing authentication for IP: {}", client_ip);
 
-        // Try API Key authentication first (from header or query param)
+        // Try a JWT access token first
+        match Self::authenticate_jwt(&auth, &req, client_ip) {
+            Ok(Some(auth_context)) => {
+                req.extensions_mut().insert(auth_context);
+                return Ok(next.run(req).await);
+            }
+            Err(rejection) => {
+                debug!("Access token rejected: {rejection}");
+                return Err(StatusCode::UNAUTHORIZED);
+            }
+            Ok(None) => {
+                // No access token provided, continue to API key
+            }
+        }
+
+        // Try API Key authentication (from header or query param)
         match Self::authenticate_api_key(&auth, &req, client_ip) {
             Ok(Some(auth_context)) => {
                 req.extensions_mut().insert(auth_context);
@@ -110,6 +127,48 @@ impl AuthMiddleware {
         Ok(next.run(req).await)
     }
 
+    /// Authenticate via a JWT access token from `/auth/login`
+    /// Returns Ok(Some(AuthContext)) on success, Ok(None) if no bearer JWT
+    /// was sent or login tokens are disabled, Err(AuthRejection) if the token
+    /// is invalid or its user no longer exists or is disabled
+    pub fn authenticate_jwt(
+        auth: &AuthMiddleware,
+        req: &Request,
+        client_ip: IpAddr,
+    ) -> Result<Option<AuthContext>, AuthRejection> {
+        let Some(tokens) = auth.user_manager.tokens() else {
+            return Ok(None);
+        };
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        let Some(token) = token.filter(|token| TokenManager::is_jwt(token)) else {
+            return Ok(None);
+        };
+
+        let claims = tokens
+            .verify(token)
+            .map_err(|_| AuthRejection::InvalidToken)?;
+        // Permissions are looked up per request, not baked into the token
+        let user = auth
+            .user_manager
+            .get_user(&claims.sub)
+            .filter(|user| user.enabled)
+            .ok_or(AuthRejection::InvalidToken)?;
+        debug!("Authenticated via access token: {}", user.username);
+
+        Ok(Some(AuthContext {
+            permissions: auth.user_manager.get_user_permissions(&user.username),
+            user_id: Some(user.username),
+            api_key_id: None,
+            client_ip,
+            is_admin: user.is_admin,
+        }))
+    }
+
     /// Authenticate via API key
     /// Returns Ok(Some(AuthContext)) on success, Ok(None) if no API key provided,
     /// Err(AuthRejection) if API key provided but invalid
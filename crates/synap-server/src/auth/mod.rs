@@ -2,6 +2,7 @@ pub mod acl;
 pub mod api_key;
 pub mod audit;
 pub mod extractor;
+pub mod jwt;
 pub mod mcp_context;
 pub mod middleware;
 pub mod password_validation;
@@ -16,6 +17,7 @@ pub use extractor::{
     AuthContextExtractor, require_admin, require_auth, require_permission,
     require_resource_permission,
 };
+pub use jwt::{Claims, TokenManager, TokenPair};
 pub use mcp_context::{clear_auth_context, get_auth_context, set_auth_context, with_auth_context};
 pub use middleware::{AuthMiddleware, AuthRejection};
 pub use password_validation::{PasswordRequirements, validate_password, validate_password_strict};
@@ -71,4 +73,14 @@ impl AuthContext {
     pub fn is_authenticated(&self) -> bool {
         self.user_id.is_some() || self.api_key_id.is_some()
     }
+
+    /// Who made the request, for usage attribution and per-client limits:
+    /// `api_key:<id>`, `user:<id>` or `ip:<address>`
+    pub fn client_id(&self) -> String {
+        match (&self.api_key_id, &self.user_id) {
+            (Some(id), _) => format!("api_key:{id}"),
+            (None, Some(user)) => format!("user:{user}"),
+            (None, None) => format!("ip:{}", self.client_ip),
+        }
+    }
 }
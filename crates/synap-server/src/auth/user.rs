@@ -1,4 +1,6 @@
-use super::{AuthResult, Permission, Role, password_validation::validate_password};
+use super::{
+    AuthResult, Permission, Role, jwt::TokenManager, password_validation::validate_password,
+};
 use crate::core::SynapError;
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
@@ -126,6 +128,8 @@ pub struct UserManager {
     roles: Arc<RwLock<HashMap<String, Role>>>,
     /// Root username (protected user, cannot be deleted)
     root_username: Arc<RwLock<Option<String>>>,
+    /// Login tokens, when enabled
+    tokens: Option<TokenManager>,
 }
 
 impl UserManager {
@@ -141,6 +145,25 @@ impl UserManager {
             users: Arc::new(RwLock::new(HashMap::new())),
             roles: Arc::new(RwLock::new(roles)),
             root_username: Arc::new(RwLock::new(None)),
+            tokens: None,
+        }
+    }
+
+    /// Issue JWT access tokens and refresh tokens on login
+    pub fn with_tokens(mut self, tokens: TokenManager) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    /// Token manager, if login tokens are enabled
+    pub fn tokens(&self) -> Option<&TokenManager> {
+        self.tokens.as_ref()
+    }
+
+    /// Drop outstanding refresh tokens after a credential or account change
+    fn revoke_tokens(&self, username: &str) {
+        if let Some(tokens) = &self.tokens {
+            tokens.revoke_user(username);
         }
     }
 
@@ -315,7 +338,9 @@ impl UserManager {
             ));
         }
 
-        Ok(self.users.write().remove(username).is_some())
+        let deleted = self.users.write().remove(username).is_some();
+        self.revoke_tokens(username);
+        Ok(deleted)
     }
 
     /// Change user password
@@ -328,6 +353,8 @@ impl UserManager {
             .ok_or_else(|| SynapError::KeyNotFound(format!("User {} not found", username)))?;
 
         user.change_password(new_password)?;
+        drop(users);
+        self.revoke_tokens(username);
         Ok(())
     }
 
@@ -341,6 +368,10 @@ impl UserManager {
             .ok_or_else(|| SynapError::KeyNotFound(format!("User {} not found", username)))?;
 
         user.enabled = enabled;
+        drop(users);
+        if !enabled {
+            self.revoke_tokens(username);
+        }
         Ok(())
     }
 
@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
-use crate::core::{EvictionPolicy, KVConfig, QueueConfig};
+use crate::compression::ValueCompressionConfig;
+use crate::core::{
+    DefragConfig, EvictionPolicy, KVConfig, KeyLimits, LeaderboardWindow, NamespaceLimit,
+    NamespaceQuotas, PubSubConfig, QueueConfig,
+};
 use crate::persistence::PersistenceConfig;
 use crate::replication::ReplicationConfig;
 
@@ -37,10 +41,18 @@ pub struct ServerConfig {
     #[serde(default)]
     pub synap_rpc: SynapRpcConfig,
 
+    /// gRPC listener (`grpc` feature, `docs/users/api/GRPC.md`)
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+
     /// Network resource limits shared by the binary listeners
     #[serde(default)]
     pub network: NetworkConfig,
 
+    /// HTTP response compression (`docs/users/guides/COMPRESSION.md`)
+    #[serde(default)]
+    pub http_compression: HttpCompressionConfig,
+
     /// Cluster mode (disabled by default) — topology + slot migration (issue #232)
     #[serde(default)]
     pub cluster: crate::cluster::ClusterConfig,
@@ -48,6 +60,102 @@ pub struct ServerConfig {
     /// Value-carrying KV watch (`docs/features/kv-watch.md`). Always on; this only tunes it.
     #[serde(default)]
     pub watch: WatchConfig,
+
+    /// Sorted sets that publish leaderboard notifications from boot, keyed by
+    /// sorted-set key (`docs/features/leaderboard-notifications.md`)
+    #[serde(default)]
+    pub leaderboards: std::collections::HashMap<String, LeaderboardWindow>,
+
+    /// Hard per-key size limits (`docs/users/configuration/KEY_LIMITS.md`)
+    #[serde(default)]
+    pub limits: KeyLimits,
+
+    /// Seed data loaded on first boot (`docs/users/configuration/FIXTURES.md`)
+    #[serde(default)]
+    pub fixtures: FixturesConfig,
+
+    /// Pub/Sub publish limits (`docs/users/pubsub/PUBSUB.md`)
+    #[serde(default)]
+    pub pubsub: PubSubConfig,
+
+    /// Cold start warmup (`docs/users/configuration/WARMUP.md`)
+    #[serde(default)]
+    pub warmup: WarmupConfig,
+
+    /// Tokio runtime diagnostics (`runtime-diagnostics` feature)
+    #[serde(default)]
+    pub diagnostics: DiagnosticsConfig,
+
+    /// Mutating command records for compliance plugins
+    /// (`docs/users/operations/COMMAND_HOOKS.md`)
+    #[serde(default)]
+    pub command_hooks: crate::monitoring::CommandHooksConfig,
+
+    /// Logical databases selected with the envelope's `db` field
+    /// (`docs/users/configuration/DATABASES.md`)
+    #[serde(default)]
+    pub databases: crate::databases::DatabasesConfig,
+
+    /// Where `config.set` keeps its overrides
+    /// (`docs/users/configuration/RUNTIME_CONFIG.md`)
+    #[serde(default)]
+    pub runtime_config: crate::runtime_config::RuntimeConfigOptions,
+
+    /// Readiness probe thresholds (`docs/users/operations/MONITORING.md`)
+    #[serde(default)]
+    pub health: crate::health::HealthConfig,
+
+    /// Per-connection limits (`docs/users/operations/CLIENTS.md`)
+    #[serde(default)]
+    pub clients: crate::monitoring::ClientLimitsConfig,
+
+    /// Initial slow log threshold and length; both can be changed at runtime
+    /// with `slowlog.config.set` (`docs/users/operations/SLOWLOG.md`)
+    #[serde(default)]
+    pub slowlog: crate::monitoring::SlowLogConfig,
+
+    /// Latency spike history per command class and snapshot
+    /// (`docs/users/operations/LATENCY.md`)
+    #[serde(default)]
+    pub latency_monitor: crate::monitoring::LatencyMonitorConfig,
+
+    /// OpenTelemetry span export (`otel` feature,
+    /// `docs/users/operations/TRACING.md`)
+    #[serde(default)]
+    pub telemetry: crate::telemetry::TelemetryConfig,
+
+    /// Relays from stream rooms and topics to external Kafka clusters
+    /// (`kafka` feature, `docs/users/streams/KAFKA_RELAY.md`)
+    #[serde(default)]
+    pub kafka_relay: KafkaRelayConfig,
+
+    /// Delivery timelines of queue messages and stream events
+    /// (`docs/users/operations/MESSAGE_TRACE.md`)
+    #[serde(default)]
+    pub message_trace: MessageTraceConfig,
+}
+
+/// Cold start warmup. Off unless a manifest is set, here or through
+/// `SYNAP_WARMUP_MANIFEST`, or `wait_for_manifest` is on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WarmupConfig {
+    /// Manifest (`.json`, `.yaml`, `.yml`) primed at startup
+    #[serde(default)]
+    pub manifest: Option<std::path::PathBuf>,
+    /// Report not ready until a warmup has completed, e.g. one sent through
+    /// `POST /warmup` by the deploy tooling
+    #[serde(default)]
+    pub wait_for_manifest: bool,
+}
+
+/// Fixture loading. Disabled unless a path is set, here or through
+/// `SYNAP_FIXTURES`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixturesConfig {
+    /// Fixture file (`.json`, `.yaml`, `.yml`) or directory of fixture files,
+    /// applied in file name order.
+    #[serde(default)]
+    pub path: Option<std::path::PathBuf>,
 }
 
 /// KV watch tuning (phase22). The watch channel family itself has no enable
@@ -58,16 +166,25 @@ pub struct WatchConfig {
     /// are delivered as notify-only with `truncated: true`.
     #[serde(default = "default_watch_max_inline_value_bytes")]
     pub max_inline_value_bytes: usize,
+    /// Most keys the client-side caching table tracks. Past it the oldest
+    /// registration is invalidated to make room.
+    #[serde(default = "default_watch_tracking_max_keys")]
+    pub tracking_max_keys: usize,
 }
 
 fn default_watch_max_inline_value_bytes() -> usize {
     crate::core::DEFAULT_INLINE_VALUE_CAP
 }
 
+fn default_watch_tracking_max_keys() -> usize {
+    crate::core::DEFAULT_TRACKING_MAX_KEYS
+}
+
 impl Default for WatchConfig {
     fn default() -> Self {
         Self {
             max_inline_value_bytes: default_watch_max_inline_value_bytes(),
+            tracking_max_keys: default_watch_tracking_max_keys(),
         }
     }
 }
@@ -102,6 +219,69 @@ impl Default for NetworkConfig {
     }
 }
 
+/// HTTP response compression. The encoding is negotiated per request from
+/// `Accept-Encoding`; a client that sends none gets identity responses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpCompressionConfig {
+    /// Compress responses at all
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Offer `gzip`
+    #[serde(default = "default_true")]
+    pub gzip: bool,
+    /// Offer `zstd`
+    #[serde(default = "default_true")]
+    pub zstd: bool,
+    /// Responses smaller than this are sent uncompressed: below about a
+    /// kilobyte the framing overhead eats the saving.
+    #[serde(default = "default_compression_min_size_bytes")]
+    pub min_size_bytes: u64,
+    /// Negotiate `permessage-deflate` on WebSocket connections
+    #[serde(default = "default_true")]
+    pub websocket: bool,
+    /// WebSocket messages smaller than this are sent uncompressed
+    #[serde(default = "default_compression_min_size_bytes")]
+    pub websocket_min_size_bytes: u64,
+}
+
+fn default_compression_min_size_bytes() -> u64 {
+    1024
+}
+
+impl Default for HttpCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            gzip: true,
+            zstd: true,
+            min_size_bytes: default_compression_min_size_bytes(),
+            websocket: true,
+            websocket_min_size_bytes: default_compression_min_size_bytes(),
+        }
+    }
+}
+
+/// Tokio runtime diagnostics. Only builds with the `runtime-diagnostics`
+/// feature read it (`docs/users/operations/RUNTIME_DIAGNOSTICS.md`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiagnosticsConfig {
+    /// Serve task data to `tokio-console` (needs the `runtime-diagnostics`
+    /// feature and a `--cfg tokio_unstable` build)
+    pub tokio_console: bool,
+    /// Address the console server listens on
+    pub console_bind: std::net::SocketAddr,
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            tokio_console: false,
+            console_bind: std::net::SocketAddr::from(([127, 0, 0, 1], 6669)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Server {
     pub host: String,
@@ -128,6 +308,128 @@ pub struct KVStoreConfig {
     /// Maximum allowed value size in bytes. None means no limit.
     #[serde(default)]
     pub max_value_size_bytes: Option<usize>,
+    /// GET sampling for the eviction simulator (`/memory/eviction-simulation`)
+    #[serde(default)]
+    pub access_sampling: AccessSamplingConfig,
+    /// Sampled GET/SET counts per key (`GET /monitoring/hotkeys`)
+    #[serde(default)]
+    pub hot_keys: HotKeysConfig,
+    /// Key count and memory caps per key prefix (`GET /quotas`)
+    #[serde(default)]
+    pub quotas: Vec<NamespaceLimit>,
+    /// Background shrinking of shard maps after delete bursts
+    #[serde(default)]
+    pub defrag: DefragConfig,
+    /// Independently locked shards the keyspace is split over (default: one per CPU)
+    #[serde(default = "crate::core::default_shard_count")]
+    pub shard_count: usize,
+    /// LZ4/Zstd compression of large values in memory and in snapshots
+    #[serde(default)]
+    pub compression: ValueCompressionConfig,
+}
+
+impl KVStoreConfig {
+    /// Build the namespace quotas, or `None` when none are configured.
+    pub fn namespace_quotas(&self) -> Option<NamespaceQuotas> {
+        (!self.quotas.is_empty()).then(|| NamespaceQuotas::new(self.quotas.clone()))
+    }
+}
+
+/// Access sampling for eviction simulation. Disabled by default: when off, the
+/// read path pays a single branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessSamplingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Sample 1 in N keys (by key hash). Every read of a sampled key is kept.
+    #[serde(default = "default_access_sample_rate")]
+    pub sample_rate: u32,
+    /// Number of most recent sampled reads kept for replay.
+    #[serde(default = "default_access_max_samples")]
+    pub max_samples: usize,
+}
+
+fn default_access_sample_rate() -> u32 {
+    16
+}
+
+fn default_access_max_samples() -> usize {
+    100_000
+}
+
+impl Default for AccessSamplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: default_access_sample_rate(),
+            max_samples: default_access_max_samples(),
+        }
+    }
+}
+
+impl AccessSamplingConfig {
+    /// Sampler to attach to the KV store, or `None` when sampling is disabled.
+    pub fn build(&self) -> Option<std::sync::Arc<crate::cache::AccessSampler>> {
+        self.enabled.then(|| {
+            std::sync::Arc::new(crate::cache::AccessSampler::new(
+                self.sample_rate,
+                self.max_samples,
+            ))
+        })
+    }
+}
+
+/// Hot key tracking. Disabled by default: when off, each GET and SET pays a
+/// single branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotKeysConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Count 1 in N GETs and SETs, whichever key they touch.
+    #[serde(default = "default_hot_keys_sample_rate")]
+    pub sample_rate: u32,
+    /// Length of one counting interval in seconds.
+    #[serde(default = "default_hot_keys_interval_secs")]
+    pub interval_secs: u64,
+    /// Keys counted at once; the coldest half is dropped when full.
+    #[serde(default = "default_hot_keys_max_tracked")]
+    pub max_tracked: usize,
+}
+
+fn default_hot_keys_sample_rate() -> u32 {
+    10
+}
+
+fn default_hot_keys_interval_secs() -> u64 {
+    60
+}
+
+fn default_hot_keys_max_tracked() -> usize {
+    10_000
+}
+
+impl Default for HotKeysConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: default_hot_keys_sample_rate(),
+            interval_secs: default_hot_keys_interval_secs(),
+            max_tracked: default_hot_keys_max_tracked(),
+        }
+    }
+}
+
+impl HotKeysConfig {
+    /// Tracker to attach to the KV store, or `None` when tracking is disabled.
+    pub fn build(&self) -> Option<std::sync::Arc<crate::core::HotKeyTracker>> {
+        self.enabled.then(|| {
+            std::sync::Arc::new(crate::core::HotKeyTracker::new(
+                self.sample_rate,
+                std::time::Duration::from_secs(self.interval_secs),
+                self.max_tracked,
+            ))
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,13 +442,101 @@ pub struct QueueSystemConfig {
     /// Per-consumer prefetch limit (QoS). 0 = unlimited (default).
     #[serde(default)]
     pub prefetch_limit: usize,
+    /// Seconds a publish idempotency key is remembered per queue. 0 = off.
+    #[serde(default = "default_queue_dedup_window_secs")]
+    pub dedup_window_secs: u64,
+}
+
+fn default_queue_dedup_window_secs() -> u64 {
+    QueueConfig::default().dedup_window_secs
+}
+
+/// Message delivery timelines. Disabled by default: when off, each publish,
+/// delivery and ack pays a single branch. Trace headers are added to
+/// delivered queue messages either way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTraceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Messages traced at once; the trace of the oldest publish is dropped
+    /// when full.
+    #[serde(default = "default_message_trace_max_tracked")]
+    pub max_tracked: usize,
+    /// Steps kept per message; the oldest after the publish are dropped.
+    #[serde(default = "default_message_trace_max_events")]
+    pub max_events: usize,
+}
+
+fn default_message_trace_max_tracked() -> usize {
+    10_000
+}
+
+fn default_message_trace_max_events() -> usize {
+    32
+}
+
+impl Default for MessageTraceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_tracked: default_message_trace_max_tracked(),
+            max_events: default_message_trace_max_events(),
+        }
+    }
+}
+
+impl MessageTraceConfig {
+    /// Tracer to attach to the queue and stream managers, or `None` when
+    /// tracing is disabled.
+    pub fn build(&self) -> Option<std::sync::Arc<crate::core::MessageTracer>> {
+        self.enabled.then(|| {
+            std::sync::Arc::new(crate::core::MessageTracer::new(
+                self.max_tracked,
+                self.max_events,
+            ))
+        })
+    }
 }
 
+/// Request rate limits. Each caller has its own budget: per API key, per
+/// user, or per IP for anonymous requests. `requests_per_second` and
+/// `burst_size` apply unless a tier matches the caller.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimitConfig {
     pub enabled: bool,
     pub requests_per_second: u64,
     pub burst_size: u64,
+    /// Budgets for specific callers. The first tier that lists the caller's
+    /// API key ID, username or IP applies.
+    #[serde(default)]
+    pub tiers: Vec<RateLimitTier>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            requests_per_second: 1000,
+            burst_size: 100,
+            tiers: Vec::new(),
+        }
+    }
+}
+
+/// A named budget and the callers it applies to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitTier {
+    pub name: String,
+    pub requests_per_second: u64,
+    pub burst_size: u64,
+    /// API key IDs
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// Usernames
+    #[serde(default)]
+    pub users: Vec<String>,
+    #[serde(default)]
+    pub ips: Vec<std::net::IpAddr>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -224,6 +614,52 @@ pub struct AuthConfig {
     /// Default TTL for temporary API keys (in seconds)
     #[serde(default = "default_key_ttl")]
     pub default_key_ttl: u64,
+    /// Tokens issued by `POST /auth/login`
+    #[serde(default)]
+    pub jwt: JwtConfig,
+    /// Audit log of authentication events and admin operations
+    #[serde(default)]
+    pub audit: AuditConfig,
+}
+
+/// Audit log configuration. Entries are always kept in memory for
+/// `GET /audit`; the file and stream sinks are optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Enable/disable audit logging
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Entries kept in memory for `GET /audit`
+    #[serde(default = "default_audit_max_entries")]
+    pub max_entries: usize,
+    /// Also record every write (commands and REST writes), not only
+    /// authentication events and admin operations
+    #[serde(default)]
+    pub log_writes: bool,
+    /// Append each entry as a JSON line to this file
+    #[serde(default)]
+    pub file: Option<std::path::PathBuf>,
+    /// Publish each entry as an `audit` event to this stream room
+    #[serde(default)]
+    pub stream_room: Option<String>,
+}
+
+/// JWT access token and refresh token configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtConfig {
+    /// HMAC-SHA256 signing secret. When empty a random secret is generated at
+    /// startup, so tokens do not survive a restart or work across nodes.
+    #[serde(default)]
+    pub secret: String,
+    /// Access token lifetime (in seconds)
+    #[serde(default = "default_access_token_ttl")]
+    pub access_token_ttl_secs: u64,
+    /// Refresh token lifetime (in seconds)
+    #[serde(default = "default_refresh_token_ttl")]
+    pub refresh_token_ttl_secs: u64,
+    /// `iss` claim written to and required in access tokens
+    #[serde(default = "default_jwt_issuer")]
+    pub issuer: String,
 }
 
 /// Root user configuration
@@ -244,6 +680,22 @@ fn default_key_ttl() -> u64 {
     3600 // 1 hour
 }
 
+fn default_access_token_ttl() -> u64 {
+    900 // 15 minutes
+}
+
+fn default_refresh_token_ttl() -> u64 {
+    7 * 24 * 3600 // 7 days
+}
+
+fn default_audit_max_entries() -> usize {
+    1000
+}
+
+fn default_jwt_issuer() -> String {
+    "synap".to_string()
+}
+
 fn default_root_username() -> String {
     "root".to_string()
 }
@@ -269,6 +721,31 @@ impl Default for AuthConfig {
             require_auth: false,
             root: RootUserConfig::default(),
             default_key_ttl: 3600,
+            jwt: JwtConfig::default(),
+            audit: AuditConfig::default(),
+        }
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: default_audit_max_entries(),
+            log_writes: false,
+            file: None,
+            stream_room: None,
+        }
+    }
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            secret: String::new(),
+            access_token_ttl_secs: default_access_token_ttl(),
+            refresh_token_ttl_secs: default_refresh_token_ttl(),
+            issuer: default_jwt_issuer(),
         }
     }
 }
@@ -352,6 +829,83 @@ impl Default for SynapRpcConfig {
     }
 }
 
+/// gRPC listener configuration (`grpc` feature).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcConfig {
+    /// Enable the gRPC listener (default: false).
+    pub enabled: bool,
+    /// TCP port the gRPC listener binds to (default: 15502).
+    pub port: u16,
+    /// Host/IP to bind (default: "127.0.0.1" — loopback only for safety).
+    pub host: String,
+    /// Largest request or response message accepted (default: 64 MiB).
+    pub max_message_bytes: usize,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 15502,
+            host: "127.0.0.1".to_string(),
+            max_message_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// `kafka_relay` section (`kafka` feature). Without the feature the relays
+/// are parsed but not started.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KafkaRelayConfig {
+    pub relays: Vec<KafkaRelaySpec>,
+}
+
+/// One relay: the events of a stream room or a partitioned topic, forwarded
+/// to a Kafka topic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaRelaySpec {
+    /// Unique relay name; also names its offset checkpoints
+    pub name: String,
+    /// Stream room to relay (set this or `topic`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<String>,
+    /// Partitioned topic to relay (set this or `room`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// Kafka bootstrap servers, `host:port[,host:port...]`
+    pub brokers: String,
+    /// Kafka topic the events are produced to
+    pub kafka_topic: String,
+    /// Extra librdkafka producer properties, e.g. `security.protocol` or
+    /// `compression.type`
+    #[serde(default)]
+    pub producer: std::collections::HashMap<String, String>,
+    /// Events read and sent per round (default: 100)
+    #[serde(default = "default_kafka_relay_batch_size")]
+    pub batch_size: usize,
+    /// First retry delay after a failed batch (default: 100 ms)
+    #[serde(default = "default_kafka_relay_min_backoff_ms")]
+    pub min_backoff_ms: u64,
+    /// Retry delays double up to this (default: 30 s)
+    #[serde(default = "default_kafka_relay_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+fn default_kafka_relay_batch_size() -> usize {
+    100
+}
+
+fn default_kafka_relay_min_backoff_ms() -> u64 {
+    100
+}
+
+fn default_kafka_relay_max_backoff_ms() -> u64 {
+    30_000
+}
+
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
@@ -367,6 +921,12 @@ impl Default for ServerConfig {
                 ttl_cleanup_interval_ms: 100,
                 allow_flush_commands: false,
                 max_value_size_bytes: None,
+                access_sampling: AccessSamplingConfig::default(),
+                hot_keys: HotKeysConfig::default(),
+                quotas: Vec::new(),
+                defrag: DefragConfig::default(),
+                shard_count: crate::core::default_shard_count(),
+                compression: ValueCompressionConfig::default(),
             },
             queue: QueueSystemConfig {
                 enabled: true,
@@ -375,6 +935,7 @@ impl Default for ServerConfig {
                 default_max_retries: 3,
                 default_priority: 5,
                 prefetch_limit: 0,
+                dedup_window_secs: default_queue_dedup_window_secs(),
             },
             logging: LoggingConfig {
                 level: "info".to_string(),
@@ -390,11 +951,7 @@ impl Default for ServerConfig {
                     prefix: "/kv".to_string(),
                 },
             },
-            rate_limit: RateLimitConfig {
-                enabled: false,
-                requests_per_second: 1000,
-                burst_size: 100,
-            },
+            rate_limit: RateLimitConfig::default(),
             persistence: PersistenceConfig::default(),
             replication: ReplicationConfig::default(),
             mcp: McpConfig::default(),
@@ -404,9 +961,27 @@ impl Default for ServerConfig {
 
             resp3: Resp3Config::default(),
             synap_rpc: SynapRpcConfig::default(),
+            grpc: GrpcConfig::default(),
             network: NetworkConfig::default(),
+            http_compression: HttpCompressionConfig::default(),
             cluster: crate::cluster::ClusterConfig::default(),
             watch: WatchConfig::default(),
+            leaderboards: Default::default(),
+            limits: KeyLimits::default(),
+            fixtures: FixturesConfig::default(),
+            pubsub: PubSubConfig::default(),
+            warmup: WarmupConfig::default(),
+            diagnostics: DiagnosticsConfig::default(),
+            command_hooks: Default::default(),
+            databases: Default::default(),
+            runtime_config: Default::default(),
+            health: Default::default(),
+            clients: Default::default(),
+            slowlog: Default::default(),
+            latency_monitor: Default::default(),
+            telemetry: Default::default(),
+            kafka_relay: Default::default(),
+            message_trace: Default::default(),
         }
     }
 }
@@ -428,6 +1003,20 @@ impl ServerConfig {
             allow_flush_commands: self.kv_store.allow_flush_commands,
             max_value_size_bytes: self.kv_store.max_value_size_bytes,
             eviction_sample_size: 5,
+            shard_count: self.kv_store.shard_count,
+            compression: self.kv_store.compression.clone(),
+        }
+    }
+
+    /// Per-key limits, with the older `kv_store.max_value_size_bytes` standing
+    /// in for `limits.max_value_bytes` when only it is set.
+    pub fn key_limits(&self) -> KeyLimits {
+        KeyLimits {
+            max_value_bytes: self
+                .limits
+                .max_value_bytes
+                .or(self.kv_store.max_value_size_bytes),
+            ..self.limits
         }
     }
 
@@ -439,6 +1028,7 @@ impl ServerConfig {
             default_max_retries: self.queue.default_max_retries,
             default_priority: self.queue.default_priority,
             prefetch_limit: self.queue.prefetch_limit,
+            dedup_window_secs: self.queue.dedup_window_secs,
         }
     }
 
@@ -472,6 +1062,14 @@ mod tests {
         assert_eq!(SynapRpcConfig::default().host, "127.0.0.1");
     }
 
+    #[test]
+    fn grpc_is_off_and_loopback_by_default() {
+        let from_empty: GrpcConfig = serde_yaml::from_str("{}").unwrap();
+        assert!(!from_empty.enabled);
+        assert_eq!(from_empty.host, "127.0.0.1");
+        assert_eq!(from_empty.port, 15502);
+    }
+
     #[test]
     fn network_limits_default_to_phase6c_values() {
         // Omitted network block → defaults preserve prior behavior (phase6i).
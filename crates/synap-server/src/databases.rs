@@ -0,0 +1,367 @@
+//! Logical databases (`SELECT n`).
+//!
+//! `databases.count` splits the keyspace into numbered databases. Database 0
+//! is the one the rest of the server is built around; databases 1 and up
+//! each get their own KV, hash, list, set, sorted set, HyperLogLog, bitmap,
+//! geospatial and Bloom filter stores, and with persistence enabled their own
+//! WAL and snapshots under a `db<n>` directory next to database 0's:
+//!
+//! ```text
+//! data/wal/synap.wal          data/snapshots/snapshot-*.bin
+//! data/wal/db1/synap.wal      data/snapshots/db1/snapshot-*.bin
+//! ```
+//!
+//! A command picks its database with the envelope's `db` field, and a RESP3
+//! connection with `SELECT`. Queues, streams, Pub/Sub, locks, rate limiters
+//! and time series are shared by every database. Replication and cluster
+//! mode support database 0 only, so neither starts with more databases.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::ServerConfig;
+use crate::core::{
+    BitmapStore, BloomFilterStore, GeospatialStore, GlobalMemory, HashStore, HyperLogLogStore,
+    KVStore, KeyWatchNotifier, KeyspaceEventFlags, KeyspaceNotifier, ListStore, PubSubRouter,
+    SetStore, SortedSetStore, TransactionManager,
+};
+use crate::monitoring::LatencyMonitor;
+use crate::persistence::{PersistenceConfig, PersistenceLayer, StoreArcs, recover};
+
+/// Namespaces whose commands run in the selected database. Commands of
+/// other namespaces (queues, streams, Pub/Sub, admin) act on state shared by
+/// every database.
+const PER_DATABASE: &[&str] = &[
+    "kv",
+    "key",
+    "hash",
+    "list",
+    "set",
+    "sortedset",
+    "hyperloglog",
+    "bitmap",
+    "geospatial",
+    "bf",
+    "memory",
+    "transaction",
+    "script",
+    "function",
+];
+
+/// Whether `command` runs in the selected database
+pub fn is_per_database(command: &str) -> bool {
+    command
+        .split_once('.')
+        .is_some_and(|(namespace, _)| PER_DATABASE.contains(&namespace))
+}
+
+/// How many logical databases the server has
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabasesConfig {
+    /// Databases 0 to `count - 1` can be selected
+    pub count: u32,
+}
+
+impl Default for DatabasesConfig {
+    fn default() -> Self {
+        Self { count: 1 }
+    }
+}
+
+/// The stores and persistence of one database other than 0
+pub struct Database {
+    pub kv_store: Arc<KVStore>,
+    pub hash_store: Arc<HashStore>,
+    pub list_store: Arc<ListStore>,
+    pub set_store: Arc<SetStore>,
+    pub sorted_set_store: Arc<SortedSetStore>,
+    pub hyperloglog_store: Arc<HyperLogLogStore>,
+    pub bitmap_store: Arc<BitmapStore>,
+    pub geospatial_store: Arc<GeospatialStore>,
+    pub bloom_store: Arc<BloomFilterStore>,
+    pub transaction_manager: Arc<TransactionManager>,
+    pub persistence: Option<Arc<PersistenceLayer>>,
+}
+
+impl Database {
+    /// In-memory database, e.g. for tests
+    pub fn new(kv_store: KVStore) -> Self {
+        Self::from_stores(
+            Arc::new(kv_store),
+            Arc::new(HashStore::new()),
+            Arc::new(ListStore::new()),
+            Arc::new(SetStore::new()),
+            Arc::new(SortedSetStore::new()),
+            Arc::new(HyperLogLogStore::new()),
+            Arc::new(BloomFilterStore::new()),
+            None,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_stores(
+        kv_store: Arc<KVStore>,
+        hash_store: Arc<HashStore>,
+        list_store: Arc<ListStore>,
+        set_store: Arc<SetStore>,
+        sorted_set_store: Arc<SortedSetStore>,
+        hyperloglog_store: Arc<HyperLogLogStore>,
+        bloom_store: Arc<BloomFilterStore>,
+        persistence: Option<Arc<PersistenceLayer>>,
+    ) -> Self {
+        let transaction_manager = Arc::new(TransactionManager::new(
+            kv_store.clone(),
+            hash_store.clone(),
+            list_store.clone(),
+            set_store.clone(),
+            sorted_set_store.clone(),
+        ));
+        Self {
+            geospatial_store: Arc::new(GeospatialStore::new(sorted_set_store.clone())),
+            bitmap_store: Arc::new(BitmapStore::new()),
+            kv_store,
+            hash_store,
+            list_store,
+            set_store,
+            sorted_set_store,
+            hyperloglog_store,
+            bloom_store,
+            transaction_manager,
+            persistence,
+        }
+    }
+
+    fn store_arcs(&self) -> StoreArcs {
+        StoreArcs {
+            kv_store: self.kv_store.clone(),
+            hash_store: Some(self.hash_store.clone()),
+            list_store: Some(self.list_store.clone()),
+            set_store: Some(self.set_store.clone()),
+            sorted_set_store: Some(self.sorted_set_store.clone()),
+            hyperloglog_store: Some(self.hyperloglog_store.clone()),
+            bloom_store: Some(self.bloom_store.clone()),
+            bitmap_store: Some(self.bitmap_store.clone()),
+            queue_manager: None,
+            stream_manager: None,
+        }
+    }
+}
+
+/// Databases 1 and up; database 0 lives in [`AppState`](crate::AppState)
+#[derive(Default)]
+pub struct Databases {
+    extra: Vec<Database>,
+}
+
+impl Databases {
+    /// Databases 1 to `extra.len()`
+    pub fn new(extra: Vec<Database>) -> Self {
+        Self { extra }
+    }
+
+    /// Build databases 1 to `databases.count - 1`, recovering each from its
+    /// own WAL and snapshots, and start their snapshot tasks. They share
+    /// database 0's memory budget.
+    pub async fn open(
+        config: &ServerConfig,
+        global_mem: &GlobalMemory,
+        pubsub: &Arc<PubSubRouter>,
+        latency: &Arc<LatencyMonitor>,
+    ) -> anyhow::Result<Self> {
+        let count = config.databases.count;
+        if count == 0 {
+            anyhow::bail!("databases.count must be at least 1");
+        }
+        if count > 1 && config.cluster.enabled {
+            anyhow::bail!("cluster mode supports database 0 only; set databases.count to 1");
+        }
+        if count > 1 && config.replication.enabled {
+            anyhow::bail!("replication supports database 0 only; set databases.count to 1");
+        }
+
+        let kv_config = config.to_kv_config();
+        let key_limits = config.key_limits();
+        let flags = KeyspaceEventFlags::parse(&config.server.notify_keyspace_events);
+        let mut extra = Vec::new();
+        for db in 1..count {
+            let persistence_config = database_persistence(&config.persistence, db);
+            let recovered = match recover(
+                &persistence_config,
+                kv_config.clone(),
+                config.to_queue_config(),
+            )
+            .await
+            {
+                Ok(recovered) => Some(recovered),
+                Err(e) => {
+                    warn!("Recovery of database {} failed: {}, starting fresh", db, e);
+                    None
+                }
+            };
+            let (kv, hs, ls, ss, zs, hll, bf) = match recovered {
+                Some((kv, hs, ls, ss, zs, hll, bf, _, _)) => (kv, hs, ls, ss, zs, hll, bf),
+                None => (
+                    KVStore::new(kv_config.clone()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                ),
+            };
+
+            let keyspace = flags
+                .is_active()
+                .then(|| Arc::new(KeyspaceNotifier::new(pubsub.clone(), flags, db)));
+            let watch = Some(Arc::new(KeyWatchNotifier::with_inline_cap(
+                pubsub.clone(),
+                db,
+                config.watch.max_inline_value_bytes,
+            )));
+            let kv_store = Arc::new(
+                kv.with_global_memory(global_mem.clone())
+                    .with_keyspace_notifier(keyspace.clone())
+                    .with_watch_notifier(watch)
+                    .with_key_limits(key_limits),
+            );
+            let hash_store = Arc::new(
+                hs.unwrap_or_default()
+                    .with_global_memory(global_mem.clone())
+                    .with_keyspace_notifier(keyspace.clone())
+                    .with_key_limits(key_limits),
+            );
+            let list_store = Arc::new(
+                ls.unwrap_or_default()
+                    .with_global_memory(global_mem.clone())
+                    .with_keyspace_notifier(keyspace.clone())
+                    .with_key_limits(key_limits),
+            );
+            let set_store = Arc::new(
+                ss.unwrap_or_default()
+                    .with_global_memory(global_mem.clone())
+                    .with_keyspace_notifier(keyspace.clone())
+                    .with_key_limits(key_limits),
+            );
+            let sorted_set_store = Arc::new(
+                zs.unwrap_or_default()
+                    .with_global_memory(global_mem.clone())
+                    .with_keyspace_notifier(keyspace)
+                    .with_key_limits(key_limits),
+            );
+
+            let persistence = if config.persistence.enabled {
+                let layer = PersistenceLayer::new(persistence_config).await?;
+                Some(Arc::new(layer.with_latency_monitor(latency.clone())))
+            } else {
+                None
+            };
+
+            let database = Database::from_stores(
+                kv_store,
+                hash_store,
+                list_store,
+                set_store,
+                sorted_set_store,
+                Arc::new(hll.unwrap_or_default()),
+                Arc::new(bf.unwrap_or_default()),
+                persistence,
+            );
+            if let Some(layer) = &database.persistence {
+                layer.clone().start_snapshot_task(database.store_arcs());
+            }
+            extra.push(database);
+        }
+        if count > 1 {
+            info!("{} logical databases", count);
+        }
+        Ok(Self { extra })
+    }
+
+    /// Number of databases, including database 0
+    pub fn count(&self) -> u32 {
+        self.extra.len() as u32 + 1
+    }
+
+    /// Database `db`; `None` for database 0 and for databases that do not
+    /// exist
+    pub fn get(&self, db: u32) -> Option<&Database> {
+        let index = (db as usize).checked_sub(1)?;
+        self.extra.get(index)
+    }
+
+    /// Databases 1 and up, with their number
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Database)> {
+        self.extra
+            .iter()
+            .enumerate()
+            .map(|(index, database)| (index as u32 + 1, database))
+    }
+}
+
+/// Database `db`'s WAL and snapshots, in a `db<n>` directory next to database
+/// 0's. Archiving and durable subscriptions stay with database 0.
+pub fn database_persistence(config: &PersistenceConfig, db: u32) -> PersistenceConfig {
+    let mut config = config.clone();
+    let dir = format!("db{}", db);
+    config.wal.path = nested(&config.wal.path, &dir);
+    config.snapshot.directory = config.snapshot.directory.join(&dir);
+    config.durable_subscriptions = false;
+    config.archive.enabled = false;
+    config
+}
+
+fn nested(file: &Path, dir: &str) -> PathBuf {
+    let parent = file.parent().unwrap_or_else(|| Path::new(""));
+    match file.file_name() {
+        Some(name) => parent.join(dir).join(name),
+        None => parent.join(dir),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_persistence_paths() {
+        let config = PersistenceConfig::default();
+        let db2 = database_persistence(&config, 2);
+        assert_eq!(db2.wal.path, PathBuf::from("./data/wal/db2/synap.wal"));
+        assert_eq!(
+            db2.snapshot.directory,
+            PathBuf::from("./data/snapshots/db2")
+        );
+        assert!(!db2.archive.enabled);
+    }
+
+    #[test]
+    fn test_per_database_commands() {
+        assert!(is_per_database("kv.set"));
+        assert!(is_per_database("sortedset.add"));
+        assert!(is_per_database("transaction.exec"));
+        assert!(!is_per_database("queue.publish"));
+        assert!(!is_per_database("config.get"));
+        assert!(!is_per_database("kv"));
+    }
+
+    #[test]
+    fn test_database_numbering() {
+        let databases = Databases::new(vec![
+            Database::new(KVStore::new(Default::default())),
+            Database::new(KVStore::new(Default::default())),
+        ]);
+        assert_eq!(databases.count(), 3);
+        assert!(databases.get(0).is_none());
+        assert!(databases.get(2).is_some());
+        assert!(databases.get(3).is_none());
+        assert_eq!(
+            databases.iter().map(|(db, _)| db).collect::<Vec<_>>(),
+            [1, 2]
+        );
+    }
+}
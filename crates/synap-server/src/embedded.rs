@@ -0,0 +1,467 @@
+//! Embedded mode: Synap inside the host process.
+//!
+//! [`Embedded`] starts the stores, and persistence when
+//! `persistence.enabled` is set, without any listener. Commands run through
+//! the handlers behind `POST /api/v1/command`, so names, payloads, results
+//! and errors are those the SDK sees, without the network hop. Useful for
+//! tests, CLIs and single-binary apps.
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use synap_server::{Embedded, ServerConfig};
+//!
+//! let synap = Embedded::start(ServerConfig::default()).await?;
+//! synap.kv().set("user:1", "alice", Some(3600)).await?;
+//! let name: Option<String> = synap.kv().get("user:1").await?;
+//!
+//! // Anything else by command name, as with `SynapClient::send_command`
+//! synap
+//!     .send_command("list.rpush", serde_json::json!({"key": "jobs", "values": ["a"]}))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! No listener runs unless [`Embedded::listen`] starts the HTTP API. Not
+//! started: authentication, replication, cluster mode, Hub integration and
+//! the partitioned streams and consumer groups.
+
+use crate::auth::{ApiKeyManager, AuthContext, UserManager};
+use crate::config::{McpConfig, RateLimitConfig, ServerConfig};
+use crate::core::{
+    BitmapStore, BloomFilterStore, GeospatialStore, HashStore, HyperLogLogStore, KVStore,
+    ListStore, PubSubRouter, QueueManager, SetStore, SortedSetStore, SynapError,
+    TransactionManager,
+};
+use crate::monitoring::{
+    ClientListManager, CommandHooks, CommandProtocol, LatencyMonitor, MonitoringManager,
+    SlowLogManager,
+};
+use crate::persistence::{PersistenceLayer, StoreArcs, recover};
+use crate::protocol::command::Command;
+use crate::protocol::command::kv::{
+    KeyListResponse, KvDecr, KvDel, KvExists, KvGet, KvIncr, KvKeys, KvSet, KvStatsResponse,
+};
+use crate::server::envelope::Request;
+use crate::server::handlers::{hook_command, run_command, write_resource};
+use crate::server::{AppState, create_router};
+use anyhow::Context;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// A Synap instance running in the host process
+#[derive(Clone)]
+pub struct Embedded {
+    state: AppState,
+    ctx: AuthContext,
+}
+
+impl Embedded {
+    /// Start the stores described by `config`, recovering from disk when
+    /// persistence is enabled. Listener, auth, replication and cluster
+    /// settings are ignored.
+    pub async fn start(config: ServerConfig) -> anyhow::Result<Self> {
+        let kv_config = config.to_kv_config();
+        let queue_config = config.to_queue_config();
+        let queue_enabled = config.queue.enabled && cfg!(feature = "queues");
+        let namespace_quotas = config.kv_store.namespace_quotas();
+        let message_tracer = config.message_trace.build();
+
+        let (
+            kv_store,
+            hash_store,
+            list_store,
+            set_store,
+            sorted_set_store,
+            hyperloglog_store,
+            bloom_store,
+            queue_manager,
+        ) = if config.persistence.enabled {
+            let (kv, hs, ls, ss, zs, hll, bf, qm, _offset) =
+                recover(&config.persistence, kv_config, queue_config)
+                    .await
+                    .context("recovery failed")?;
+            (
+                Arc::new(kv.with_namespace_quotas(namespace_quotas)),
+                Arc::new(hs.unwrap_or_else(HashStore::new)),
+                Arc::new(ls.unwrap_or_else(ListStore::new)),
+                Arc::new(ss.unwrap_or_else(SetStore::new)),
+                Arc::new(zs.unwrap_or_else(SortedSetStore::new)),
+                Arc::new(hll.unwrap_or_else(HyperLogLogStore::new)),
+                Arc::new(bf.unwrap_or_else(BloomFilterStore::new)),
+                qm.filter(|_| queue_enabled)
+                    .map(|qm| Arc::new(qm.with_tracer(message_tracer.clone()))),
+            )
+        } else {
+            (
+                Arc::new(KVStore::new(kv_config).with_namespace_quotas(namespace_quotas)),
+                Arc::new(HashStore::new()),
+                Arc::new(ListStore::new()),
+                Arc::new(SetStore::new()),
+                Arc::new(SortedSetStore::new()),
+                Arc::new(HyperLogLogStore::new()),
+                Arc::new(BloomFilterStore::new()),
+                queue_enabled.then(|| {
+                    Arc::new(QueueManager::new(queue_config).with_tracer(message_tracer.clone()))
+                }),
+            )
+        };
+        let bitmap_store = Arc::new(BitmapStore::new());
+        kv_store.start_ttl_cleanup();
+        if config.kv_store.defrag.enabled {
+            kv_store.start_defrag(config.kv_store.defrag.clone());
+        }
+        if let Some(ref qm) = queue_manager {
+            qm.start_deadline_checker();
+        }
+
+        #[cfg(feature = "streams")]
+        let stream_manager = {
+            let streams = Arc::new(
+                crate::core::StreamManager::new(crate::core::StreamConfig::default())
+                    .with_tracer(message_tracer.clone()),
+            );
+            streams.clone().start_compaction_task();
+            Some(streams)
+        };
+        #[cfg(not(feature = "streams"))]
+        let stream_manager: Option<Arc<crate::core::StreamManager>> = None;
+
+        let latency = Arc::new(LatencyMonitor::new(config.latency_monitor.clone()));
+        let persistence = if config.persistence.enabled {
+            let layer = Arc::new(
+                PersistenceLayer::new(config.persistence.clone())
+                    .await
+                    .context("failed to open persistence")?
+                    .with_latency_monitor(latency.clone()),
+            );
+            layer.clone().start_snapshot_task(StoreArcs {
+                kv_store: kv_store.clone(),
+                hash_store: Some(hash_store.clone()),
+                list_store: Some(list_store.clone()),
+                set_store: Some(set_store.clone()),
+                sorted_set_store: Some(sorted_set_store.clone()),
+                hyperloglog_store: Some(hyperloglog_store.clone()),
+                bloom_store: Some(bloom_store.clone()),
+                bitmap_store: Some(bitmap_store.clone()),
+                queue_manager: queue_manager.clone(),
+                stream_manager: stream_manager.clone(),
+            });
+            Some(layer)
+        } else {
+            None
+        };
+
+        let monitoring = Arc::new(
+            MonitoringManager::new(
+                kv_store.clone(),
+                hash_store.clone(),
+                list_store.clone(),
+                set_store.clone(),
+                sorted_set_store.clone(),
+            )
+            .with_command_hooks(Arc::new(CommandHooks::from_config(&config.command_hooks)))
+            .with_latency_monitor(latency)
+            .with_health(config.health.clone())
+            .with_slow_log(Arc::new(SlowLogManager::with_config(
+                config.slowlog.clone(),
+            ))),
+        );
+        let transaction_manager = Arc::new(
+            TransactionManager::new(
+                kv_store.clone(),
+                hash_store.clone(),
+                list_store.clone(),
+                set_store.clone(),
+                sorted_set_store.clone(),
+            )
+            .with_queue_manager(queue_manager.clone())
+            .with_stream_manager(stream_manager.clone()),
+        );
+
+        let state = AppState {
+            kv_store,
+            hash_store,
+            list_store,
+            set_store,
+            sorted_set_store: sorted_set_store.clone(),
+            hyperloglog_store: hyperloglog_store.clone(),
+            bitmap_store,
+            geospatial_store: Arc::new(GeospatialStore::new(sorted_set_store)),
+            #[cfg(feature = "queues")]
+            queue_manager,
+            #[cfg(feature = "streams")]
+            stream_manager,
+            #[cfg(feature = "streams")]
+            partition_manager: None,
+            #[cfg(feature = "streams")]
+            consumer_group_manager: None,
+            pubsub_router: Some(Arc::new(PubSubRouter::with_config(config.pubsub)?)),
+            persistence,
+            monitoring,
+            transaction_manager,
+            #[cfg(feature = "scripting")]
+            script_manager: Arc::new(crate::scripting::ScriptManager::new(
+                std::time::Duration::from_secs(5),
+            )),
+            client_list_manager: Arc::new(
+                ClientListManager::new().with_limits(config.clients.clone()),
+            ),
+            #[cfg(feature = "cluster")]
+            cluster_topology: None,
+            #[cfg(feature = "cluster")]
+            cluster_migration: None,
+            hub_client: None,
+            user_manager: None,
+            require_auth: false,
+            replication: None,
+            failover: None,
+            lock_manager: Arc::default(),
+            rate_limits: Arc::default(),
+            time_series: Arc::default(),
+            bloom_store: bloom_store.clone(),
+            #[cfg(feature = "queues")]
+            shovels: None,
+            #[cfg(feature = "queues")]
+            webhooks: None,
+            schedules: Some(Arc::new(crate::scheduler::ScheduleManager::new())),
+        };
+
+        // The host process is trusted, as with authentication disabled
+        let mut ctx = AuthContext::anonymous(Ipv4Addr::LOCALHOST.into());
+        ctx.is_admin = true;
+
+        Ok(Self { state, ctx })
+    }
+
+    /// Server state, for the store APIs the commands do not cover
+    pub fn state(&self) -> &AppState {
+        &self.state
+    }
+
+    /// Run a typed command, as `SynapClient::execute` does
+    pub async fn execute<C: Command>(&self, command: &C) -> Result<C::Response, SynapError> {
+        let payload = serde_json::to_value(command)
+            .map_err(|e| SynapError::SerializationError(e.to_string()))?;
+        let response = self.send_command(C::NAME, payload).await?;
+        serde_json::from_value(response).map_err(|e| SynapError::SerializationError(e.to_string()))
+    }
+
+    /// Run a command by name, as `SynapClient::send_command` does. Writes
+    /// reach the command hooks registered on
+    /// `state().monitoring.command_hooks()`.
+    pub async fn send_command(&self, command: &str, payload: Value) -> Result<Value, SynapError> {
+        self.send_request(Request::new(command, payload)).await
+    }
+
+    /// Run a whole command envelope, keeping its `request_id` and logical
+    /// database
+    pub async fn send_request(&self, request: Request) -> Result<Value, SynapError> {
+        let hooked = if self.state.monitoring.command_hooks().is_active() {
+            write_resource(&request.command, &request.payload)
+        } else {
+            None
+        };
+        let command = request.command.clone();
+        let started = Instant::now();
+        let result = run_command(self.state.clone(), &self.ctx, request).await;
+        if let Some(resource) = hooked {
+            let error = result.as_ref().err().map(|e| e.to_string());
+            hook_command(
+                &self.state,
+                CommandProtocol::Embedded,
+                &self.ctx,
+                &command,
+                resource,
+                error,
+                started.elapsed(),
+            );
+        }
+        result
+    }
+
+    /// Serve the HTTP API on `addr` (port 0 picks a free one), for clients
+    /// that need more than [`Embedded::send_command`]: REST endpoints and
+    /// WebSockets. Authentication and rate limiting stay off. The listener
+    /// stops when the returned [`EmbeddedListener`] is dropped.
+    pub async fn listen(&self, addr: SocketAddr) -> std::io::Result<EmbeddedListener> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let app = create_router(
+            self.state.clone(),
+            RateLimitConfig::default(),
+            McpConfig::default(),
+            Arc::new(UserManager::new()),
+            Arc::new(ApiKeyManager::new()),
+            false,
+            false,
+        );
+        let task = tokio::spawn(async move {
+            let service = app.into_make_service_with_connect_info::<SocketAddr>();
+            if let Err(e) = axum::serve(listener, service).await {
+                tracing::error!("Embedded HTTP listener failed: {}", e);
+            }
+        });
+        Ok(EmbeddedListener { local_addr, task })
+    }
+
+    /// Key-value operations
+    pub fn kv(&self) -> EmbeddedKv<'_> {
+        EmbeddedKv { synap: self }
+    }
+
+    /// Write a snapshot now. Does nothing when persistence is disabled.
+    pub async fn snapshot(&self) -> Result<(), SynapError> {
+        let Some(ref layer) = self.state.persistence else {
+            return Ok(());
+        };
+        let state = &self.state;
+        let stores = StoreArcs {
+            kv_store: state.kv_store.clone(),
+            hash_store: Some(state.hash_store.clone()),
+            list_store: Some(state.list_store.clone()),
+            set_store: Some(state.set_store.clone()),
+            sorted_set_store: Some(state.sorted_set_store.clone()),
+            hyperloglog_store: Some(state.hyperloglog_store.clone()),
+            bloom_store: Some(state.bloom_store.clone()),
+            bitmap_store: Some(state.bitmap_store.clone()),
+            queue_manager: state.queue_manager().cloned(),
+            stream_manager: state.stream_manager().cloned(),
+        };
+        layer
+            .snapshot_now(stores.as_refs())
+            .await
+            .map_err(|e| SynapError::InternalError(e.to_string()))
+    }
+}
+
+/// The HTTP API of an [`Embedded`] instance, served until dropped
+pub struct EmbeddedListener {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl EmbeddedListener {
+    /// The address the API is served on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for EmbeddedListener {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Key-value operations on an [`Embedded`] instance, mirroring the SDK's
+/// `KVStore`
+pub struct EmbeddedKv<'a> {
+    synap: &'a Embedded,
+}
+
+impl EmbeddedKv<'_> {
+    /// Set a key-value pair, optionally expiring after `ttl` seconds
+    pub async fn set<K, V>(&self, key: K, value: V, ttl: Option<u64>) -> Result<(), SynapError>
+    where
+        K: AsRef<str>,
+        V: Serialize,
+    {
+        let command = KvSet {
+            key: key.as_ref().to_owned(),
+            value: serde_json::to_value(value)
+                .map_err(|e| SynapError::SerializationError(e.to_string()))?,
+            ttl,
+            client_id: None,
+        };
+        self.synap.execute(&command).await?;
+        Ok(())
+    }
+
+    /// Get a value by key. Returns `None` if the key doesn't exist or has
+    /// expired.
+    pub async fn get<K, V>(&self, key: K) -> Result<Option<V>, SynapError>
+    where
+        K: AsRef<str>,
+        V: for<'de> Deserialize<'de>,
+    {
+        let command = KvGet {
+            key: key.as_ref().to_owned(),
+            format: None,
+        };
+        let Some(value) = self.synap.execute(&command).await? else {
+            return Ok(None);
+        };
+
+        // Structured values are stored JSON-encoded and come back as strings
+        let decoded = match serde_json::from_value::<V>(value.clone()) {
+            Ok(decoded) => Ok(decoded),
+            Err(direct) => match value.as_str() {
+                Some(text) => serde_json::from_str(text).map_err(|_| direct),
+                None => Err(direct),
+            },
+        };
+        decoded
+            .map(Some)
+            .map_err(|e| SynapError::SerializationError(e.to_string()))
+    }
+
+    /// Delete a key. Returns whether it existed.
+    pub async fn delete<K: AsRef<str>>(&self, key: K) -> Result<bool, SynapError> {
+        let command = KvDel {
+            key: key.as_ref().to_owned(),
+            client_id: None,
+        };
+        Ok(self.synap.execute(&command).await?.deleted)
+    }
+
+    /// Check if a key exists
+    pub async fn exists<K: AsRef<str>>(&self, key: K) -> Result<bool, SynapError> {
+        let command = KvExists {
+            key: key.as_ref().to_owned(),
+        };
+        Ok(self.synap.execute(&command).await?.exists)
+    }
+
+    /// Increment a numeric value
+    pub async fn incr<K: AsRef<str>>(&self, key: K) -> Result<i64, SynapError> {
+        let command = KvIncr {
+            key: key.as_ref().to_owned(),
+            amount: None,
+            client_id: None,
+        };
+        Ok(self.synap.execute(&command).await?.value)
+    }
+
+    /// Decrement a numeric value
+    pub async fn decr<K: AsRef<str>>(&self, key: K) -> Result<i64, SynapError> {
+        let command = KvDecr {
+            key: key.as_ref().to_owned(),
+            amount: None,
+            client_id: None,
+        };
+        Ok(self.synap.execute(&command).await?.value)
+    }
+
+    /// Get KV store statistics
+    pub async fn stats(&self) -> Result<KvStatsResponse, SynapError> {
+        self.synap
+            .execute(&crate::protocol::command::kv::KvStats {})
+            .await
+    }
+
+    /// Get all keys matching a prefix
+    pub async fn keys<P: AsRef<str>>(&self, prefix: P) -> Result<Vec<String>, SynapError> {
+        let command = KvKeys {
+            prefix: Some(prefix.as_ref().to_owned()),
+        };
+        let KeyListResponse { keys, .. } = self.synap.execute(&command).await?;
+        Ok(keys)
+    }
+}
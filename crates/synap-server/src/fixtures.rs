@@ -0,0 +1,398 @@
+//! Seed data loaded on first boot.
+//!
+//! A fixture file describes keys, collections, queue messages and stream
+//! events in JSON or YAML:
+//!
+//! ```yaml
+//! kv:
+//!   greeting: hello
+//!   config:app: { theme: dark }
+//! ttl:
+//!   greeting: 3600
+//! hashes:
+//!   user:1: { name: Alice, plan: pro }
+//! lists:
+//!   jobs: [resize, encode]
+//! sets:
+//!   tags: [rust, cache]
+//! sorted_sets:
+//!   leaderboard: { alice: 120, bob: 95 }
+//! queues:
+//!   emails:
+//!     - payload: { to: alice@example.com }
+//!       priority: 5
+//! streams:
+//!   chat:
+//!     - event: message
+//!       data: { user: alice, text: hi }
+//! ```
+//!
+//! String values are stored as their raw bytes and anything else as JSON,
+//! matching what the REST API stores for the same request body. Fixtures are
+//! only applied when the data directory holds no snapshot and no WAL entries,
+//! so a restart never overwrites live data.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, bail};
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::info;
+
+use crate::core::ZAddOptions;
+use crate::persistence::{PersistenceConfig, SnapshotManager, StoreRefs};
+
+/// Contents of one or more fixture files
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Fixtures {
+    pub kv: BTreeMap<String, Value>,
+    /// TTL in seconds for keys listed under `kv`
+    pub ttl: BTreeMap<String, u64>,
+    pub hashes: BTreeMap<String, BTreeMap<String, Value>>,
+    pub lists: BTreeMap<String, Vec<Value>>,
+    pub sets: BTreeMap<String, Vec<Value>>,
+    /// Member to score
+    pub sorted_sets: BTreeMap<String, BTreeMap<String, f64>>,
+    pub queues: BTreeMap<String, Vec<QueueMessageFixture>>,
+    pub streams: BTreeMap<String, Vec<StreamEventFixture>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueueMessageFixture {
+    pub payload: Value,
+    #[serde(default)]
+    pub priority: Option<u8>,
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StreamEventFixture {
+    pub event: String,
+    #[serde(default)]
+    pub data: Value,
+}
+
+/// Counts of what [`Fixtures::apply`] loaded
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FixtureSummary {
+    pub keys: usize,
+    pub collections: usize,
+    pub queue_messages: usize,
+    pub stream_events: usize,
+}
+
+impl Fixtures {
+    /// Read a fixture file, or every `.json`/`.yaml`/`.yml` file in a
+    /// directory in file name order. Later files add to earlier ones, and a
+    /// key defined twice keeps the last definition.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.is_dir() {
+            return Self::load_file(path);
+        }
+
+        let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+            .with_context(|| format!("reading fixtures directory {}", path.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file() && format_of(p).is_some())
+            .collect();
+        files.sort();
+
+        let mut fixtures = Self::default();
+        for file in files {
+            fixtures.merge(Self::load_file(&file)?);
+        }
+        Ok(fixtures)
+    }
+
+    fn load_file(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading fixture file {}", path.display()))?;
+        let parsed = match format_of(path) {
+            Some(Format::Json) => serde_json::from_str(&content).map_err(anyhow::Error::from),
+            Some(Format::Yaml) => serde_yaml::from_str(&content).map_err(anyhow::Error::from),
+            None => bail!(
+                "unsupported fixture file {} (expected .json, .yaml or .yml)",
+                path.display()
+            ),
+        };
+        parsed.with_context(|| format!("parsing fixture file {}", path.display()))
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.kv.extend(other.kv);
+        self.ttl.extend(other.ttl);
+        self.hashes.extend(other.hashes);
+        self.lists.extend(other.lists);
+        self.sets.extend(other.sets);
+        self.sorted_sets.extend(other.sorted_sets);
+        self.queues.extend(other.queues);
+        self.streams.extend(other.streams);
+    }
+
+    /// Write the fixtures into the stores. Queues and streams are skipped
+    /// when their subsystem is disabled.
+    pub async fn apply(&self, stores: StoreRefs<'_>) -> anyhow::Result<FixtureSummary> {
+        if let Some(key) = self.ttl.keys().find(|k| !self.kv.contains_key(*k)) {
+            bail!("ttl given for {key}, which is not defined under kv");
+        }
+
+        let mut summary = FixtureSummary::default();
+        for (key, value) in &self.kv {
+            let ttl = self.ttl.get(key).copied();
+            stores
+                .kv_store
+                .set(key.as_str(), encode(value)?, ttl)
+                .await
+                .with_context(|| format!("seeding key {key}"))?;
+            summary.keys += 1;
+        }
+
+        if let Some(hash_store) = stores.hash_store {
+            for (key, fields) in &self.hashes {
+                for (field, value) in fields {
+                    hash_store
+                        .hset(key, field, encode(value)?)
+                        .with_context(|| format!("seeding hash {key}"))?;
+                }
+                summary.collections += 1;
+            }
+        }
+
+        if let Some(list_store) = stores.list_store {
+            for (key, values) in &self.lists {
+                let values = values.iter().map(encode).collect::<anyhow::Result<_>>()?;
+                list_store
+                    .rpush(key, values, false)
+                    .with_context(|| format!("seeding list {key}"))?;
+                summary.collections += 1;
+            }
+        }
+
+        if let Some(set_store) = stores.set_store {
+            for (key, members) in &self.sets {
+                let members = members.iter().map(encode).collect::<anyhow::Result<_>>()?;
+                set_store
+                    .sadd(key, members)
+                    .with_context(|| format!("seeding set {key}"))?;
+                summary.collections += 1;
+            }
+        }
+
+        if let Some(sorted_set_store) = stores.sorted_set_store {
+            let opts = ZAddOptions::default();
+            for (key, members) in &self.sorted_sets {
+                for (member, score) in members {
+                    sorted_set_store.zadd(key, member.as_bytes().to_vec(), *score, &opts);
+                }
+                summary.collections += 1;
+            }
+        }
+
+        if let Some(queue_manager) = stores.queue_manager {
+            for (queue, messages) in &self.queues {
+                queue_manager.create_queue(queue, None).await?;
+                for message in messages {
+                    queue_manager
+                        .publish(
+                            queue,
+                            encode(&message.payload)?,
+                            message.priority,
+                            message.max_retries,
+                        )
+                        .await
+                        .with_context(|| format!("seeding queue {queue}"))?;
+                    summary.queue_messages += 1;
+                }
+            }
+        }
+
+        if let Some(stream_manager) = stores.stream_manager {
+            for (room, events) in &self.streams {
+                // Fails only when the room exists, which is fine to reuse.
+                let _ = stream_manager.create_room(room).await;
+                for event in events {
+                    stream_manager
+                        .publish(room, &event.event, serde_json::to_vec(&event.data)?)
+                        .await
+                        .map_err(anyhow::Error::msg)
+                        .with_context(|| format!("seeding stream {room}"))?;
+                    summary.stream_events += 1;
+                }
+            }
+        }
+
+        info!(
+            "Fixtures loaded: {} keys, {} collections, {} queue messages, {} stream events",
+            summary.keys, summary.collections, summary.queue_messages, summary.stream_events
+        );
+        Ok(summary)
+    }
+}
+
+/// Whether the node starts with no stored data: persistence is off, or there
+/// is neither a snapshot nor a non-empty WAL to recover from.
+pub async fn is_first_boot(config: &PersistenceConfig) -> anyhow::Result<bool> {
+    if !config.enabled {
+        return Ok(true);
+    }
+
+    if SnapshotManager::new(config.snapshot.clone())
+        .has_snapshot()
+        .await?
+    {
+        return Ok(false);
+    }
+
+    let wal_len = match tokio::fs::metadata(&config.wal.path).await {
+        Ok(meta) => meta.len(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(!config.wal.enabled || wal_len == 0)
+}
+
+enum Format {
+    Json,
+    Yaml,
+}
+
+fn format_of(path: &Path) -> Option<Format> {
+    match path.extension()?.to_str()? {
+        "json" => Some(Format::Json),
+        "yaml" | "yml" => Some(Format::Yaml),
+        _ => None,
+    }
+}
+
+/// Strings as raw bytes, any other value as JSON.
+fn encode(value: &Value) -> anyhow::Result<Vec<u8>> {
+    Ok(match value.as_str() {
+        Some(s) => s.as_bytes().to_vec(),
+        None => serde_json::to_vec(value)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        HashStore, KVConfig, KVStore, ListStore, QueueConfig, QueueManager, SetStore,
+        SortedSetStore, StreamConfig, StreamManager,
+    };
+
+    const YAML: &str = r#"
+kv:
+  greeting: hello
+  config:app: { theme: dark }
+ttl:
+  greeting: 3600
+hashes:
+  user:1: { name: Alice, plan: pro }
+lists:
+  jobs: [resize, encode]
+sets:
+  tags: [rust, cache]
+sorted_sets:
+  leaderboard: { alice: 120, bob: 95 }
+queues:
+  emails:
+    - payload: { to: alice@example.com }
+      priority: 5
+streams:
+  chat:
+    - event: message
+      data: { user: alice }
+"#;
+
+    #[tokio::test]
+    async fn test_apply_seeds_every_datatype() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("01-base.yaml"), YAML).unwrap();
+        std::fs::write(
+            dir.path().join("02-override.json"),
+            r#"{"kv": {"greeting": "hi"}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "not a fixture").unwrap();
+
+        let fixtures = Fixtures::load(dir.path()).unwrap();
+
+        let kv = KVStore::new(KVConfig::default());
+        let hashes = HashStore::new();
+        let lists = ListStore::new();
+        let sets = SetStore::new();
+        let zsets = SortedSetStore::new();
+        let queues = QueueManager::new(QueueConfig::default());
+        let streams = StreamManager::new(StreamConfig::default());
+
+        let summary = fixtures
+            .apply(StoreRefs {
+                kv_store: &kv,
+                hash_store: Some(&hashes),
+                list_store: Some(&lists),
+                set_store: Some(&sets),
+                sorted_set_store: Some(&zsets),
+                hyperloglog_store: None,
+                bloom_store: None,
+                bitmap_store: None,
+                queue_manager: Some(&queues),
+                stream_manager: Some(&streams),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            summary,
+            FixtureSummary {
+                keys: 2,
+                collections: 4,
+                queue_messages: 1,
+                stream_events: 1,
+            }
+        );
+        assert_eq!(kv.get("greeting").await.unwrap(), Some(b"hi".to_vec()));
+        assert_eq!(
+            kv.get("config:app").await.unwrap(),
+            Some(br#"{"theme":"dark"}"#.to_vec())
+        );
+        assert!(kv.ttl("greeting").await.unwrap().is_some());
+        assert_eq!(
+            hashes.hget("user:1", "name").unwrap(),
+            Some(b"Alice".to_vec())
+        );
+        assert_eq!(lists.llen("jobs").unwrap(), 2);
+        assert_eq!(sets.scard("tags").unwrap(), 2);
+        assert_eq!(zsets.zscore("leaderboard", b"alice"), Some(120.0));
+        assert_eq!(queues.stats("emails").await.unwrap().depth, 1);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_for_unknown_key_is_rejected() {
+        let fixtures: Fixtures = serde_yaml::from_str("ttl: { missing: 10 }").unwrap();
+        let kv = KVStore::new(KVConfig::default());
+        assert!(fixtures.apply(StoreRefs::kv_only(&kv)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_first_boot_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = PersistenceConfig::default();
+        config.snapshot.directory = dir.path().join("snapshots");
+        config.wal.path = dir.path().join("synap.wal");
+        assert!(is_first_boot(&config).await.unwrap());
+
+        std::fs::write(&config.wal.path, b"").unwrap();
+        assert!(is_first_boot(&config).await.unwrap());
+
+        std::fs::write(&config.wal.path, b"entry").unwrap();
+        assert!(!is_first_boot(&config).await.unwrap());
+
+        config.enabled = false;
+        assert!(is_first_boot(&config).await.unwrap());
+    }
+}
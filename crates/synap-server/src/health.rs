@@ -0,0 +1,296 @@
+//! Liveness and readiness probes.
+//!
+//! `GET /health/live` answers 200 as long as the process serves HTTP, so an
+//! orchestrator only restarts a node that is truly stuck. `GET /health/ready`
+//! answers 200 only when every dependency check passes, and 503 with the
+//! failing checks and a `reason` otherwise:
+//!
+//! - `recovery`: persistence recovery has finished. While the snapshot and
+//!   WAL are replayed the HTTP port is served by [`StartupProbes`], which
+//!   reports alive but not ready.
+//! - `warmup`: no cold start warmup is pending (see [`crate::warmup`]).
+//! - `replication`: a replica's link to its master is up and, with
+//!   `health.max_replication_lag` set, it is no further behind.
+//! - `cluster`: every hash slot is assigned to a node.
+//! - `disk`: the volume holding the WAL has `health.min_free_disk_mb` free.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::cluster::topology::ClusterTopology;
+use crate::replication::ReplicationHandle;
+
+/// Readiness thresholds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Not ready while the WAL volume has less free space; 0 skips the check
+    pub min_free_disk_mb: u64,
+    /// Not ready while a replica is more operations behind its master; 0
+    /// only requires the link to be up
+    pub max_replication_lag: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            min_free_disk_mb: 256,
+            max_replication_lag: 0,
+        }
+    }
+}
+
+/// Outcome of one readiness check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Check {
+    pub fn pass() -> Self {
+        Self {
+            ok: true,
+            reason: None,
+        }
+    }
+
+    pub fn fail(reason: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+/// Every readiness check by name
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Readiness {
+    pub checks: BTreeMap<&'static str, Check>,
+}
+
+impl Readiness {
+    pub fn add(&mut self, name: &'static str, check: Check) {
+        self.checks.insert(name, check);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.checks.values().all(|check| check.ok)
+    }
+
+    /// Reasons of the failing checks, joined; `None` when ready
+    pub fn reason(&self) -> Option<String> {
+        let reasons: Vec<&str> = self
+            .checks
+            .values()
+            .filter_map(|check| check.reason.as_deref())
+            .collect();
+        (!reasons.is_empty()).then(|| reasons.join("; "))
+    }
+}
+
+/// A replica must be linked to its master, and within `max_lag` operations
+/// of it when set. A master is always ready.
+pub fn check_replication(handle: Option<&ReplicationHandle>, max_lag: u64) -> Check {
+    match handle {
+        Some(ReplicationHandle::Replica(replica)) => {
+            if !replica.is_connected() {
+                return Check::fail("replication link to master is down");
+            }
+            let lag = replica.lag();
+            if max_lag > 0 && lag > max_lag {
+                return Check::fail(format!(
+                    "replica is {} operations behind its master (max {})",
+                    lag, max_lag
+                ));
+            }
+            Check::pass()
+        }
+        Some(ReplicationHandle::Master(_)) | None => Check::pass(),
+    }
+}
+
+/// Every hash slot must be owned by a node
+pub fn check_cluster(topology: &ClusterTopology) -> Check {
+    if topology.has_full_coverage() {
+        Check::pass()
+    } else {
+        Check::fail(format!(
+            "cluster slots are not fully assigned ({:.1}% covered)",
+            topology.slot_coverage()
+        ))
+    }
+}
+
+/// The volume holding `dir` must have `min_free_mb` free. Passes when the
+/// free space cannot be read.
+pub fn check_disk(dir: &Path, min_free_mb: u64) -> Check {
+    if min_free_mb == 0 {
+        return Check::pass();
+    }
+    match free_disk_bytes(dir) {
+        Some(free) if free < min_free_mb * 1024 * 1024 => Check::fail(format!(
+            "{} MB free on the WAL volume (min {} MB)",
+            free / (1024 * 1024),
+            min_free_mb
+        )),
+        _ => Check::pass(),
+    }
+}
+
+/// Free bytes on the volume holding `path`, which need not exist yet
+pub fn free_disk_bytes(path: &Path) -> Option<u64> {
+    let path = existing_ancestor(path)?;
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    path.ancestors().find_map(|dir| dir.canonicalize().ok())
+}
+
+const RECOVERING: &str = "persistence recovery in progress";
+
+/// Probes served on the HTTP port while persistence recovery runs: alive,
+/// not ready, and 503 for every other request.
+pub struct StartupProbes {
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl StartupProbes {
+    /// Serve the startup probes on `listener` until [`stop`](Self::stop).
+    /// Pass a clone of the server's own listener, so the port answers
+    /// throughout startup and no connection is refused on the hand-over.
+    pub fn serve(listener: std::net::TcpListener) -> std::io::Result<Self> {
+        listener.set_nonblocking(true)?;
+        let listener = tokio::net::TcpListener::from_std(listener)?;
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let served = axum::serve(listener, startup_router())
+                .with_graceful_shutdown(async {
+                    let _ = stopped.await;
+                })
+                .await;
+            if let Err(e) = served {
+                warn!("Startup probes stopped: {}", e);
+            }
+        });
+        info!("Serving startup probes until recovery completes");
+        Ok(Self { shutdown, task })
+    }
+
+    /// Stop answering, once the full router is ready to take over
+    pub async fn stop(self) {
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+}
+
+fn startup_router() -> Router {
+    let alive = || async {
+        Json(json!({
+            "status": "starting",
+            "service": "synap",
+            "version": env!("CARGO_PKG_VERSION")
+        }))
+    };
+    Router::new()
+        .route("/health", get(alive))
+        .route("/health/live", get(alive))
+        .route(
+            "/health/ready",
+            get(|| async {
+                let mut readiness = Readiness::default();
+                readiness.add("recovery", Check::fail(RECOVERING));
+                readiness_response(&readiness, None)
+            }),
+        )
+        .fallback(|| async {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({
+                    "error": format!("Server is starting: {}", RECOVERING),
+                    "code": StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+                })),
+            )
+        })
+}
+
+/// 200 when every check passes, 503 with the reason otherwise. `warmup` is
+/// the last cold start warmup report, if any.
+pub fn readiness_response(readiness: &Readiness, warmup: Option<serde_json::Value>) -> Response {
+    let ready = readiness.is_ready();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let mut body = json!({
+        "ready": ready,
+        "checks": readiness.checks,
+        "warmup": warmup,
+    });
+    if let Some(reason) = readiness.reason() {
+        body["reason"] = json!(reason);
+    }
+    (status, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness_joins_failing_reasons() {
+        let mut readiness = Readiness::default();
+        readiness.add("warmup", Check::pass());
+        assert!(readiness.is_ready());
+        assert_eq!(readiness.reason(), None);
+
+        readiness.add("disk", Check::fail("disk full"));
+        readiness.add("cluster", Check::fail("slots missing"));
+        assert!(!readiness.is_ready());
+        assert_eq!(
+            readiness.reason().as_deref(),
+            Some("slots missing; disk full")
+        );
+    }
+
+    #[test]
+    fn test_cluster_check_needs_full_coverage() {
+        let topology = ClusterTopology::new("node-0".to_string());
+        assert!(!check_cluster(&topology).ok);
+        topology.initialize_cluster(1).unwrap();
+        assert!(check_cluster(&topology).ok);
+    }
+
+    #[test]
+    fn test_disk_check() {
+        let dir = std::env::temp_dir().join("synap-health-missing/wal");
+        assert!(check_disk(&dir, 0).ok);
+        assert!(free_disk_bytes(&dir).is_some());
+        assert!(!check_disk(&dir, u64::MAX / (2 * 1024 * 1024)).ok);
+    }
+}
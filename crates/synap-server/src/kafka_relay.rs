@@ -0,0 +1,728 @@
+//! Kafka relays.
+//!
+//! A relay forwards the events of a stream room or a partitioned topic to a
+//! topic on an external Kafka cluster, so a Synap instance at the edge can
+//! act as an outbox feeding a central pipeline. Relays are declared in the
+//! `kafka_relay` config section and run for the lifetime of the server.
+//!
+//! Each relay reads its source the way a consumer does, batch by batch, and
+//! produces one Kafka record per event: the event data as the value, the
+//! room name (or the event key, for topics) as the key, and the event type,
+//! source and offset as headers. Once Kafka acknowledged every record of a
+//! batch, the next offset is checkpointed in the KV store under
+//! `__synap:kafka_relay:<name>:<partition>`, so a restarted server resumes
+//! where it stopped. Delivery is at least once: a batch that failed part-way
+//! is sent again in full. Failures back off exponentially between
+//! `min_backoff_ms` and `max_backoff_ms`.
+//!
+//! Producing needs the `kafka` feature; without it the section is parsed
+//! but no relay starts.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::config::KafkaRelaySpec;
+use crate::core::{KVStore, PartitionManager, StreamManager, SynapError};
+use crate::metrics::{KAFKA_RELAY_ERRORS_TOTAL, KAFKA_RELAY_EVENTS_TOTAL};
+use crate::persistence::PersistenceLayer;
+use crate::server::AppState;
+
+/// Prefix of the KV keys holding relay offsets
+pub const CHECKPOINT_PREFIX: &str = "__synap:kafka_relay:";
+
+/// Wait before polling a source with no new events again
+const IDLE_POLL: Duration = Duration::from_millis(100);
+
+/// One event on its way to Kafka
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayRecord {
+    pub key: Option<Vec<u8>>,
+    pub payload: Vec<u8>,
+    pub headers: Vec<(&'static str, String)>,
+}
+
+/// Where a relay delivers its records: a Kafka producer, or a recorder in
+/// tests
+pub trait RelaySink: Send + Sync + 'static {
+    /// Resolves once the record was acknowledged
+    fn send(&self, record: RelayRecord) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+/// Counters of one relay since the server started
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RelayProgress {
+    /// Events acknowledged by Kafka
+    pub relayed: u64,
+    /// Events that left the source's retention before they were relayed
+    pub skipped: u64,
+    /// Failed batches
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Unix timestamp (milliseconds) of the last batch relayed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_relayed_at: Option<u64>,
+    /// Next offset to relay, by source partition (`0` for a room)
+    pub offsets: HashMap<usize, u64>,
+}
+
+/// What a relay needs from the server
+#[derive(Clone)]
+pub struct RelayContext {
+    pub kv_store: Arc<KVStore>,
+    pub stream_manager: Option<Arc<StreamManager>>,
+    pub partition_manager: Option<Arc<PartitionManager>>,
+    pub persistence: Option<Arc<PersistenceLayer>>,
+}
+
+impl RelayContext {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            kv_store: state.kv_store.clone(),
+            stream_manager: state.stream_manager.clone(),
+            partition_manager: state.partition_manager.clone(),
+            persistence: state.persistence.clone(),
+        }
+    }
+}
+
+/// The running relays; dropping this stops them
+#[derive(Default)]
+pub struct KafkaRelays {
+    relays: Vec<Running>,
+}
+
+struct Running {
+    name: String,
+    progress: Arc<Mutex<RelayProgress>>,
+    task: JoinHandle<()>,
+}
+
+impl KafkaRelays {
+    /// Start every relay of the `kafka_relay` section, producing to Kafka
+    #[cfg(feature = "kafka")]
+    pub fn start(specs: &[KafkaRelaySpec], context: RelayContext) -> Result<Self, SynapError> {
+        validate_all(specs)?;
+        let mut relays = Self::default();
+        for spec in specs {
+            let sink = kafka::KafkaSink::new(spec).map_err(|e| {
+                SynapError::InvalidRequest(format!("Kafka relay '{}': {}", spec.name, e))
+            })?;
+            relays.spawn(spec.clone(), sink, context.clone())?;
+        }
+        Ok(relays)
+    }
+
+    /// Start one relay delivering to `sink`
+    pub fn spawn<S: RelaySink>(
+        &mut self,
+        spec: KafkaRelaySpec,
+        sink: S,
+        context: RelayContext,
+    ) -> Result<(), SynapError> {
+        let source = validate(&spec)?;
+        if self.relays.iter().any(|relay| relay.name == spec.name) {
+            return Err(SynapError::InvalidRequest(format!(
+                "Duplicate Kafka relay name '{}'",
+                spec.name
+            )));
+        }
+        let progress = Arc::new(Mutex::new(RelayProgress::default()));
+        let worker = Worker {
+            subscriber_id: format!("kafka-relay:{}", spec.name),
+            spec: spec.clone(),
+            source,
+            sink,
+            progress: progress.clone(),
+            context,
+        };
+        info!(
+            "Kafka relay '{}' started: {} -> {}",
+            spec.name, worker.source, spec.kafka_topic
+        );
+        self.relays.push(Running {
+            name: spec.name,
+            progress,
+            task: tokio::spawn(worker.run()),
+        });
+        Ok(())
+    }
+
+    /// Counters of the relay called `name`
+    pub fn progress(&self, name: &str) -> Option<RelayProgress> {
+        self.relays
+            .iter()
+            .find(|relay| relay.name == name)
+            .map(|relay| relay.progress.lock().clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.relays.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.relays.is_empty()
+    }
+}
+
+impl Drop for KafkaRelays {
+    fn drop(&mut self) {
+        for relay in &self.relays {
+            relay.task.abort();
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+    Room(String),
+    Topic(String),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Room(room) => write!(f, "room '{}'", room),
+            Source::Topic(topic) => write!(f, "topic '{}'", topic),
+        }
+    }
+}
+
+#[cfg(feature = "kafka")]
+fn validate_all(specs: &[KafkaRelaySpec]) -> Result<(), SynapError> {
+    for (i, spec) in specs.iter().enumerate() {
+        validate(spec)?;
+        if specs[..i].iter().any(|other| other.name == spec.name) {
+            return Err(SynapError::InvalidRequest(format!(
+                "Duplicate Kafka relay name '{}'",
+                spec.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate(spec: &KafkaRelaySpec) -> Result<Source, SynapError> {
+    let invalid = |message: &str| {
+        SynapError::InvalidRequest(format!("Kafka relay '{}': {}", spec.name, message))
+    };
+
+    if spec.name.is_empty() || spec.brokers.is_empty() || spec.kafka_topic.is_empty() {
+        return Err(invalid("name, brokers and kafka_topic must not be empty"));
+    }
+    if spec.batch_size == 0 {
+        return Err(invalid("batch_size must be at least 1"));
+    }
+    if spec.min_backoff_ms == 0 || spec.min_backoff_ms > spec.max_backoff_ms {
+        return Err(invalid(
+            "min_backoff_ms must be at least 1 and at most max_backoff_ms",
+        ));
+    }
+    match (&spec.room, &spec.topic) {
+        (Some(room), None) if !room.is_empty() => Ok(Source::Room(room.clone())),
+        (None, Some(topic)) if !topic.is_empty() => Ok(Source::Topic(topic.clone())),
+        _ => Err(invalid("set exactly one of room and topic")),
+    }
+}
+
+struct Worker<S> {
+    spec: KafkaRelaySpec,
+    source: Source,
+    subscriber_id: String,
+    sink: S,
+    progress: Arc<Mutex<RelayProgress>>,
+    context: RelayContext,
+}
+
+impl<S: RelaySink> Worker<S> {
+    async fn run(self) {
+        let min_backoff = Duration::from_millis(self.spec.min_backoff_ms);
+        let max_backoff = Duration::from_millis(self.spec.max_backoff_ms);
+        let mut backoff = min_backoff;
+        let mut offsets = HashMap::new();
+
+        loop {
+            match self.relay_batches(&mut offsets).await {
+                Ok(0) => tokio::time::sleep(IDLE_POLL).await,
+                Ok(_) => backoff = min_backoff,
+                Err(e) => {
+                    warn!(
+                        "Kafka relay '{}' failed, retrying in {:?}: {}",
+                        self.spec.name, backoff, e
+                    );
+                    KAFKA_RELAY_ERRORS_TOTAL
+                        .with_label_values(&[self.spec.name.as_str()])
+                        .inc();
+                    {
+                        let mut progress = self.progress.lock();
+                        progress.errors += 1;
+                        progress.last_error = Some(e);
+                    }
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                }
+            }
+        }
+    }
+
+    /// Relay one batch from every partition of the source. Returns the
+    /// number of events relayed.
+    async fn relay_batches(&self, offsets: &mut HashMap<usize, u64>) -> Result<usize, String> {
+        let mut relayed = 0;
+        for partition in 0..self.partitions().await? {
+            let offset = match offsets.get(&partition) {
+                Some(offset) => *offset,
+                None => {
+                    let offset = self.load_checkpoint(partition).await;
+                    offsets.insert(partition, offset);
+                    offset
+                }
+            };
+
+            let events = self.read(partition, offset).await?;
+            let (Some((first, _)), Some((last, _))) = (events.first(), events.last()) else {
+                continue;
+            };
+            let (skipped, next) = (first.saturating_sub(offset), last + 1);
+            if skipped > 0 {
+                warn!(
+                    "Kafka relay '{}' skipped {} events of partition {} that left the retention window",
+                    self.spec.name, skipped, partition
+                );
+            }
+
+            let count = events.len();
+            let sends = events.into_iter().map(|(_, record)| self.sink.send(record));
+            futures_util::future::join_all(sends)
+                .await
+                .into_iter()
+                .collect::<Result<Vec<()>, String>>()?;
+
+            self.checkpoint(partition, next).await;
+            offsets.insert(partition, next);
+            KAFKA_RELAY_EVENTS_TOTAL
+                .with_label_values(&[self.spec.name.as_str()])
+                .inc_by(count as u64);
+            let mut progress = self.progress.lock();
+            progress.relayed += count as u64;
+            progress.skipped += skipped;
+            progress.last_relayed_at = Some(unix_millis());
+            progress.offsets.insert(partition, next);
+            relayed += count;
+        }
+        Ok(relayed)
+    }
+
+    async fn partitions(&self) -> Result<usize, String> {
+        match &self.source {
+            Source::Room(_) => Ok(1),
+            Source::Topic(topic) => Ok(self.partition_manager()?.topic_stats(topic).await?.len()),
+        }
+    }
+
+    /// Events from `offset` on, as `(offset, record)`. A room's events that
+    /// were already evicted are skipped rather than failing the relay.
+    async fn read(&self, partition: usize, offset: u64) -> Result<Vec<(u64, RelayRecord)>, String> {
+        let limit = self.spec.batch_size;
+        match &self.source {
+            Source::Room(room) => {
+                let streams = self.stream_manager()?;
+                let earliest = streams.room_stats(room).await?.min_offset;
+                let events = streams
+                    .consume(room, &self.subscriber_id, offset.max(earliest), limit)
+                    .await?;
+                Ok(events
+                    .into_iter()
+                    .map(|event| {
+                        let record = RelayRecord {
+                            key: Some(room.as_bytes().to_vec()),
+                            headers: vec![
+                                ("synap-event", event.event),
+                                ("synap-source", room.clone()),
+                                ("synap-offset", event.offset.to_string()),
+                                ("synap-timestamp-ms", event.timestamp_ms.to_string()),
+                            ],
+                            payload: event.data,
+                        };
+                        (event.offset, record)
+                    })
+                    .collect())
+            }
+            Source::Topic(topic) => {
+                let events = self
+                    .partition_manager()?
+                    .consume_partition(topic, partition, offset, limit)
+                    .await?;
+                Ok(events
+                    .into_iter()
+                    .map(|event| {
+                        let record = RelayRecord {
+                            key: event.key,
+                            headers: vec![
+                                ("synap-event", event.event_type),
+                                ("synap-source", topic.clone()),
+                                ("synap-partition", partition.to_string()),
+                                ("synap-offset", event.offset.to_string()),
+                                ("synap-timestamp-ms", event.timestamp_ms.to_string()),
+                            ],
+                            payload: event.data,
+                        };
+                        (event.offset, record)
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    fn stream_manager(&self) -> Result<&StreamManager, String> {
+        self.context
+            .stream_manager
+            .as_deref()
+            .ok_or_else(|| "Streams are disabled on this server".to_string())
+    }
+
+    fn partition_manager(&self) -> Result<&PartitionManager, String> {
+        self.context
+            .partition_manager
+            .as_deref()
+            .ok_or_else(|| "Partitioned topics are disabled on this server".to_string())
+    }
+
+    fn checkpoint_key(&self, partition: usize) -> String {
+        format!("{}{}:{}", CHECKPOINT_PREFIX, self.spec.name, partition)
+    }
+
+    /// The checkpointed offset of `partition`; `0` when there is none
+    async fn load_checkpoint(&self, partition: usize) -> u64 {
+        let key = self.checkpoint_key(partition);
+        match self.context.kv_store.get(&key).await {
+            Ok(Some(value)) => std::str::from_utf8(&value)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(|| {
+                    warn!("Ignoring unreadable Kafka relay checkpoint '{}'", key);
+                    0
+                }),
+            Ok(None) => 0,
+            Err(e) => {
+                warn!("Failed to read Kafka relay checkpoint '{}': {}", key, e);
+                0
+            }
+        }
+    }
+
+    /// Record that everything before `next` was relayed. A failed write is
+    /// logged; the relay goes on and may resend after a restart.
+    async fn checkpoint(&self, partition: usize, next: u64) {
+        let key = self.checkpoint_key(partition);
+        let value = next.to_string().into_bytes();
+        if let Err(e) = self
+            .context
+            .kv_store
+            .set(key.as_str(), value.clone(), None)
+            .await
+        {
+            warn!("Failed to checkpoint Kafka relay to '{}': {}", key, e);
+            return;
+        }
+        if let Some(ref persistence) = self.context.persistence
+            && let Err(e) = persistence.log_kv_set(key, value, None).await
+        {
+            warn!("Failed to log Kafka relay checkpoint to WAL: {}", e);
+        }
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use std::time::Duration;
+
+    use rdkafka::ClientConfig;
+    use rdkafka::message::{Header, OwnedHeaders};
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+
+    use super::{RelayRecord, RelaySink};
+    use crate::config::KafkaRelaySpec;
+
+    /// Longest wait for room in the producer queue
+    const QUEUE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub(super) struct KafkaSink {
+        producer: FutureProducer,
+        topic: String,
+    }
+
+    impl KafkaSink {
+        pub(super) fn new(spec: &KafkaRelaySpec) -> Result<Self, String> {
+            let mut config = ClientConfig::new();
+            config.set("bootstrap.servers", &spec.brokers);
+            for (key, value) in &spec.producer {
+                config.set(key, value);
+            }
+            let producer = config.create().map_err(|e| e.to_string())?;
+            Ok(Self {
+                producer,
+                topic: spec.kafka_topic.clone(),
+            })
+        }
+    }
+
+    impl RelaySink for KafkaSink {
+        async fn send(&self, record: RelayRecord) -> Result<(), String> {
+            let headers =
+                record
+                    .headers
+                    .into_iter()
+                    .fold(OwnedHeaders::new(), |headers, (key, value)| {
+                        headers.insert(Header {
+                            key,
+                            value: Some(value.as_str()),
+                        })
+                    });
+            let mut kafka_record = FutureRecord::to(&self.topic)
+                .payload(&record.payload)
+                .headers(headers);
+            if let Some(key) = &record.key {
+                kafka_record = kafka_record.key(key);
+            }
+            self.producer
+                .send(kafka_record, QUEUE_TIMEOUT)
+                .await
+                .map(drop)
+                .map_err(|(e, _)| e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{KVConfig, PartitionConfig, StreamConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Records what it is sent; fails the first `failures` sends
+    #[derive(Clone, Default)]
+    struct Recorder {
+        records: Arc<Mutex<Vec<RelayRecord>>>,
+        failures: Arc<AtomicUsize>,
+    }
+
+    impl RelaySink for Recorder {
+        async fn send(&self, record: RelayRecord) -> Result<(), String> {
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                return Err("broker unavailable".to_string());
+            }
+            self.records.lock().push(record);
+            Ok(())
+        }
+    }
+
+    fn context() -> RelayContext {
+        RelayContext {
+            kv_store: Arc::new(KVStore::new(KVConfig::default())),
+            stream_manager: Some(Arc::new(StreamManager::new(StreamConfig::default()))),
+            partition_manager: Some(Arc::new(PartitionManager::new(PartitionConfig::default()))),
+            persistence: None,
+        }
+    }
+
+    fn spec(name: &str, room: Option<&str>, topic: Option<&str>) -> KafkaRelaySpec {
+        KafkaRelaySpec {
+            name: name.to_string(),
+            room: room.map(str::to_string),
+            topic: topic.map(str::to_string),
+            brokers: "localhost:9092".to_string(),
+            kafka_topic: "synap.events".to_string(),
+            producer: HashMap::new(),
+            batch_size: 2,
+            min_backoff_ms: 10,
+            max_backoff_ms: 20,
+        }
+    }
+
+    async fn wait_for(relays: &KafkaRelays, name: &str, done: impl Fn(&RelayProgress) -> bool) {
+        for _ in 0..200 {
+            if relays.progress(name).is_some_and(|p| done(&p)) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("relay '{}' stalled: {:?}", name, relays.progress(name));
+    }
+
+    #[tokio::test]
+    async fn test_room_events_are_relayed_in_order_and_checkpointed() {
+        let context = context();
+        let streams = context.stream_manager.clone().unwrap();
+        streams.create_room("orders").await.unwrap();
+        for i in 0..5 {
+            streams
+                .publish("orders", "created", format!("order-{i}").into_bytes())
+                .await
+                .unwrap();
+        }
+
+        let sink = Recorder::default();
+        let mut relays = KafkaRelays::default();
+        relays
+            .spawn(
+                spec("orders", Some("orders"), None),
+                sink.clone(),
+                context.clone(),
+            )
+            .unwrap();
+        wait_for(&relays, "orders", |p| p.relayed == 5).await;
+
+        let records = sink.records.lock().clone();
+        let payloads: Vec<_> = records.iter().map(|r| r.payload.clone()).collect();
+        let expected: Vec<_> = (0..5).map(|i| format!("order-{i}").into_bytes()).collect();
+        assert_eq!(payloads, expected);
+        assert_eq!(records[0].key.as_deref(), Some(&b"orders"[..]));
+        assert!(
+            records[4]
+                .headers
+                .contains(&("synap-offset", "4".to_string()))
+        );
+        assert!(
+            records[4]
+                .headers
+                .contains(&("synap-event", "created".to_string()))
+        );
+
+        let checkpoint = context
+            .kv_store
+            .get("__synap:kafka_relay:orders:0")
+            .await
+            .unwrap();
+        assert_eq!(checkpoint, Some(b"5".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_relay_resumes_from_checkpoint() {
+        let context = context();
+        let streams = context.stream_manager.clone().unwrap();
+        streams.create_room("audit").await.unwrap();
+        for i in 0..4u8 {
+            streams.publish("audit", "entry", vec![i]).await.unwrap();
+        }
+        context
+            .kv_store
+            .set("__synap:kafka_relay:audit:0", b"3".to_vec(), None)
+            .await
+            .unwrap();
+
+        let sink = Recorder::default();
+        let mut relays = KafkaRelays::default();
+        relays
+            .spawn(spec("audit", Some("audit"), None), sink.clone(), context)
+            .unwrap();
+        wait_for(&relays, "audit", |p| p.offsets.get(&0) == Some(&4)).await;
+
+        let payloads: Vec<_> = sink
+            .records
+            .lock()
+            .iter()
+            .map(|r| r.payload.clone())
+            .collect();
+        assert_eq!(payloads, vec![vec![3]]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batches_are_retried() {
+        let context = context();
+        let streams = context.stream_manager.clone().unwrap();
+        streams.create_room("flaky").await.unwrap();
+        streams.publish("flaky", "e", b"a".to_vec()).await.unwrap();
+
+        let sink = Recorder::default();
+        sink.failures.store(3, Ordering::SeqCst);
+        let mut relays = KafkaRelays::default();
+        relays
+            .spawn(spec("flaky", Some("flaky"), None), sink.clone(), context)
+            .unwrap();
+        wait_for(&relays, "flaky", |p| p.relayed == 1).await;
+
+        let progress = relays.progress("flaky").unwrap();
+        assert_eq!(progress.errors, 3);
+        assert_eq!(progress.last_error.as_deref(), Some("broker unavailable"));
+        assert_eq!(sink.records.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_topic_partitions_are_relayed_with_event_keys() {
+        let context = context();
+        let partitions = context.partition_manager.clone().unwrap();
+        partitions.create_topic("clicks", None).await.unwrap();
+        for user in ["alice", "bob", "carol"] {
+            partitions
+                .publish(
+                    "clicks",
+                    "click",
+                    Some(user.as_bytes().to_vec()),
+                    b"{}".to_vec(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let sink = Recorder::default();
+        let mut relays = KafkaRelays::default();
+        relays
+            .spawn(spec("clicks", None, Some("clicks")), sink.clone(), context)
+            .unwrap();
+        wait_for(&relays, "clicks", |p| p.relayed == 3).await;
+
+        let mut keys: Vec<_> = sink
+            .records
+            .lock()
+            .iter()
+            .map(|r| r.key.clone().unwrap())
+            .collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![b"alice".to_vec(), b"bob".to_vec(), b"carol".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_relays_are_rejected() {
+        let mut relays = KafkaRelays::default();
+        let both = spec("both", Some("a"), Some("b"));
+        let neither = spec("neither", None, None);
+        let mut no_batch = spec("no-batch", Some("a"), None);
+        no_batch.batch_size = 0;
+        for bad in [both, neither, no_batch] {
+            assert!(matches!(
+                relays.spawn(bad, Recorder::default(), context()),
+                Err(SynapError::InvalidRequest(_))
+            ));
+        }
+
+        relays
+            .spawn(spec("dup", Some("a"), None), Recorder::default(), context())
+            .unwrap();
+        assert!(
+            relays
+                .spawn(spec("dup", Some("b"), None), Recorder::default(), context())
+                .is_err()
+        );
+        assert_eq!(relays.len(), 1);
+    }
+}
@@ -1,13 +1,28 @@
 pub mod auth;
 pub mod config;
+pub mod databases;
+pub mod embedded;
+pub mod fixtures;
+pub mod health;
 pub mod hub;
+#[cfg(feature = "streams")]
+pub mod kafka_relay;
 pub mod metrics;
 pub mod monitoring;
 pub mod persistence;
 pub mod protocol;
 pub mod replication;
+pub mod runtime_config;
+pub mod scheduler;
+#[cfg(feature = "scripting")]
 pub mod scripting;
 pub mod server;
+#[cfg(feature = "queues")]
+pub mod shovel;
+pub mod telemetry;
+pub mod warmup;
+#[cfg(feature = "queues")]
+pub mod webhook;
 
 // Engine modules live in the `synap-core` crate. Re-export them under their
 // original paths so existing `crate::core`, `crate::cluster`, `crate::cache`,
@@ -29,9 +44,14 @@ pub use core::{
     QueueConfig, QueueManager, RetentionPolicy, RoomStats, StreamConfig, StreamManager,
     SubscribeResult, SynapError, TopicInfo,
 };
+pub use embedded::{Embedded, EmbeddedKv, EmbeddedListener};
 pub use replication::{
     MasterNode, NodeRole, ReplicaNode, ReplicationConfig, ReplicationLog, ReplicationStats,
 };
+#[cfg(feature = "scripting")]
 pub use scripting::ScriptManager;
 pub use server::envelope::{Request, Response};
-pub use server::{AppState, create_router, get_mcp_tools, handle_mcp_tool, init_metrics};
+pub use server::{
+    AppState, create_router, create_router_with_compression, get_mcp_tools, handle_mcp_tool,
+    init_metrics,
+};
@@ -11,17 +11,29 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
-use synap_server::auth::{ApiKeyManager, UserManager};
-use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
-use synap_server::monitoring::{ClientListManager, MonitoringManager};
+#[cfg(feature = "scripting")]
+use synap_server::ScriptManager;
+use synap_server::auth::{ApiKeyManager, AuditLogManager, TokenManager, UserManager};
+use synap_server::core::{
+    BloomFilterStore, HashStore, HyperLogLogStore, ListStore, SetStore, SortedSetStore,
+};
+use synap_server::monitoring::{
+    ClientListManager, CommandHooks, LatencyMonitor, MonitoringManager, SlowLogManager,
+};
 use synap_server::persistence::{PersistenceLayer, recover};
 use synap_server::replication::NodeRole;
 use synap_server::{
-    AppState, ConsumerGroupConfig, ConsumerGroupManager, KVStore, PartitionConfig,
-    PartitionManager, PubSubRouter, QueueManager, ScriptManager, ServerConfig, StreamConfig,
-    StreamManager, create_router, init_metrics,
+    AppState, KVStore, PubSubRouter, QueueManager, ServerConfig, StreamManager,
+    create_router_with_compression, init_metrics,
+};
+#[cfg(feature = "streams")]
+use synap_server::{
+    ConsumerGroupConfig, ConsumerGroupManager, PartitionConfig, PartitionManager, StreamConfig,
 };
 use tracing::{error, info, warn};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 #[derive(Parser, Debug)]
 #[command(name = "synap-server")]
@@ -61,6 +73,23 @@ struct Args {
     /// SYNAP_HEALTH_ADDR (default "127.0.0.1:15500").
     #[arg(long, default_value_t = false)]
     health_check: bool,
+
+    /// Check the persistence files (snapshots, WAL, subscription log) named in
+    /// the config and exit 0 (consistent) or 1 (issues found). Run it while
+    /// the server is stopped.
+    #[arg(long, default_value_t = false)]
+    fsck: bool,
+
+    /// With --fsck, repair what can be repaired, keeping the originals in a
+    /// backup directory next to the WAL
+    #[arg(long, default_value_t = false, requires = "fsck")]
+    repair: bool,
+
+    /// Before starting, download the newest archived snapshot and the WAL
+    /// segments after it (`persistence.archive`) into the empty data paths;
+    /// startup recovery then replays them
+    #[arg(long, default_value_t = false)]
+    restore_from_archive: bool,
 }
 
 /// Plain-std HTTP GET /health probe — no shell, no HTTP client dependency.
@@ -84,6 +113,28 @@ fn run_health_check() -> ! {
     std::process::exit(if ok { 0 } else { 1 });
 }
 
+/// Offline check of the persistence files: prints the report and exits 0 when
+/// nothing needs attention, 1 when something does, 2 when the check failed.
+async fn run_fsck(config: &ServerConfig, repair: bool) -> ! {
+    let result = synap_server::persistence::fsck(
+        &config.persistence,
+        config.to_kv_config(),
+        config.to_queue_config(),
+        repair,
+    )
+    .await;
+    match result {
+        Ok(report) => {
+            println!("{report}");
+            std::process::exit(if report.needs_attention() { 1 } else { 0 });
+        }
+        Err(e) => {
+            eprintln!("fsck failed: {e}");
+            std::process::exit(2);
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
@@ -109,6 +160,11 @@ async fn main() -> Result<()> {
         config.server.port = port;
     }
 
+    // Offline check mode: exits the process, never starts the server.
+    if args.fsck {
+        run_fsck(&config, args.repair).await;
+    }
+
     // Override auth config from environment variables (Docker support)
     if let Ok(enabled) = std::env::var("SYNAP_AUTH_ENABLED") {
         config.auth.enabled = enabled.parse().unwrap_or(false);
@@ -130,12 +186,45 @@ async fn main() -> Result<()> {
     {
         config.auth.default_key_ttl = ttl;
     }
+    if let Ok(secret) = std::env::var("SYNAP_AUTH_JWT_SECRET") {
+        config.auth.jwt.secret = secret;
+    }
+
+    if let Ok(path) = std::env::var("SYNAP_FIXTURES") {
+        config.fixtures.path = Some(path.into());
+    }
+
+    if let Ok(path) = std::env::var("SYNAP_WARMUP_MANIFEST") {
+        config.warmup.manifest = Some(path.into());
+    }
+
+    // Values changed with config.set on a previous run win over the file
+    let runtime_config = Arc::new(synap_server::runtime_config::RuntimeConfig::load(
+        config.runtime_config.overrides_path.clone(),
+    )?);
+    runtime_config.apply_to(&mut config);
+
+    if let Ok(key) =
+        std::env::var("SYNAP_ARCHIVE_ACCESS_KEY_ID").or_else(|_| std::env::var("AWS_ACCESS_KEY_ID"))
+    {
+        config.persistence.archive.access_key_id = key;
+    }
+    if let Ok(secret) = std::env::var("SYNAP_ARCHIVE_SECRET_ACCESS_KEY")
+        .or_else(|_| std::env::var("AWS_SECRET_ACCESS_KEY"))
+    {
+        config.persistence.archive.secret_access_key = secret;
+    }
 
     if let Ok(cap) = std::env::var("SYNAP_WATCH_MAX_INLINE_VALUE_BYTES")
         && let Ok(bytes) = cap.parse::<usize>()
     {
         config.watch.max_inline_value_bytes = bytes;
     }
+    if let Ok(max) = std::env::var("SYNAP_WATCH_TRACKING_MAX_KEYS")
+        && let Ok(keys) = max.parse::<usize>()
+    {
+        config.watch.tracking_max_keys = keys;
+    }
 
     // Override HiveHub config from environment variables (Docker/Cloud support)
     // Task 9.7: Add environment variable support for HiveHub service API key
@@ -190,31 +279,45 @@ async fn main() -> Result<()> {
     // Initialize tracing based on config
     let log_level = std::env::var("RUST_LOG").unwrap_or_else(|_| config.logging.level.clone());
 
-    match config.logging.format.as_str() {
+    let log_layer = match config.logging.format.as_str() {
         "json" => {
             // JSON format for production (structured logging)
-            tracing_subscriber::fmt()
+            tracing_subscriber::fmt::layer()
                 .json()
-                .with_env_filter(tracing_subscriber::EnvFilter::new(log_level))
                 .with_target(true)
                 .with_thread_ids(true)
                 .with_thread_names(true)
                 .with_file(true)
                 .with_line_number(true)
                 .with_current_span(true)
-                .init();
+                .boxed()
         }
         _ => {
             // Pretty format for development (human-readable)
-            tracing_subscriber::fmt()
+            tracing_subscriber::fmt::layer()
                 .pretty()
-                .with_env_filter(tracing_subscriber::EnvFilter::new(log_level))
                 .with_target(true)
                 .with_thread_names(true)
                 .with_file(true)
                 .with_line_number(true)
-                .init();
+                .boxed()
         }
+    };
+    // The log level filters the log output only, so an attached tokio-console
+    // layer still sees the runtime's trace-level task events
+    let subscriber = tracing_subscriber::registry()
+        .with(log_layer.with_filter(tracing_subscriber::EnvFilter::new(log_level)));
+    #[cfg(feature = "runtime-diagnostics")]
+    let subscriber = subscriber.with(synap_server::monitoring::runtime::console_layer(
+        &config.diagnostics,
+    ));
+    #[cfg(feature = "otel")]
+    let subscriber = subscriber.with(synap_server::telemetry::layer(&config.telemetry)?);
+    subscriber.init();
+    if config.telemetry.enabled && !cfg!(feature = "otel") {
+        warn!(
+            "telemetry.enabled is set but this build has no `otel` feature; no spans are exported"
+        );
     }
 
     info!("Starting Synap Server v{}", env!("CARGO_PKG_VERSION"));
@@ -231,6 +334,10 @@ async fn main() -> Result<()> {
     // Recover from persistence or create fresh
     let kv_config = config.to_kv_config();
     let queue_config = config.to_queue_config();
+    if config.queue.enabled && !cfg!(feature = "queues") {
+        warn!("queue.enabled is set but this build has no `queues` feature; queues are off");
+    }
+    let queue_enabled = config.queue.enabled && cfg!(feature = "queues");
 
     // Shared cross-datatype memory budget (audit M-018): every store registers
     // its byte counter so `maxmemory` accounts for KV + collections + brokers,
@@ -240,31 +347,36 @@ async fn main() -> Result<()> {
     // Cluster mode wiring (issue #232): when enabled, build the topology + slot
     // migration manager from config and route KV access by hash slot. Disabled by
     // default → both None (standalone).
-    let (cluster_topology, cluster_migration) = if config.cluster.enabled {
-        use synap_server::cluster::{ClusterTopology, SlotMigrationManager};
-        match ClusterTopology::from_config(&config.cluster) {
-            Ok(topology) => {
-                info!("Cluster mode enabled");
-                let migration = Arc::new(SlotMigrationManager::new(
-                    config.cluster.migration_batch_size,
-                    config.cluster.migration_timeout(),
-                ));
-                (Some(Arc::new(topology)), Some(migration))
-            }
-            Err(e) => {
-                warn!("Cluster init failed, running standalone: {}", e);
-                (None, None)
+    if config.cluster.enabled && !cfg!(feature = "cluster") {
+        warn!("cluster.enabled is set but this build has no `cluster` feature; running standalone");
+    }
+    let (cluster_topology, cluster_migration) =
+        if config.cluster.enabled && cfg!(feature = "cluster") {
+            use synap_server::cluster::{ClusterTopology, SlotMigrationManager};
+            match ClusterTopology::from_config(&config.cluster) {
+                Ok(topology) => {
+                    info!("Cluster mode enabled");
+                    let migration = Arc::new(SlotMigrationManager::new(
+                        config.cluster.migration_batch_size,
+                        config.cluster.migration_timeout(),
+                    ));
+                    migration.set_throttle(config.cluster.migration_max_keys_per_sec);
+                    (Some(Arc::new(topology)), Some(migration))
+                }
+                Err(e) => {
+                    warn!("Cluster init failed, running standalone: {}", e);
+                    (None, None)
+                }
             }
-        }
-    } else {
-        (None, None)
-    };
+        } else {
+            (None, None)
+        };
 
     // Pub/Sub router — created before the stores so keyspace notifications can
     // publish through it. A keyspace notifier is built only when the config flag
     // string enables at least one target + class; otherwise it stays `None` and
     // every notify site is a single no-op branch on the write path.
-    let pubsub_router_inner = Arc::new(PubSubRouter::new());
+    let pubsub_router_inner = Arc::new(PubSubRouter::with_config(config.pubsub.clone())?);
     let keyspace_notifier = {
         use synap_server::core::{KeyspaceEventFlags, KeyspaceNotifier};
         let flags = KeyspaceEventFlags::parse(&config.server.notify_keyspace_events);
@@ -286,14 +398,80 @@ async fn main() -> Result<()> {
     // Value-carrying KV watch (docs/features/kv-watch.md) — always on, unlike keyspace
     // notifications: a watch that silently does nothing at the default
     // configuration would be worse than none. Idle cost is one router lookup
-    // per mutation.
+    // per mutation. Client-side caching invalidations ride the same hook, at
+    // one atomic load per mutation while no key is tracked.
     let watch_notifier = Some(Arc::new(
         synap_server::core::KeyWatchNotifier::with_inline_cap(
             pubsub_router_inner.clone(),
             0,
             config.watch.max_inline_value_bytes,
-        ),
+        )
+        .with_tracking(Arc::new(synap_server::core::TrackingTable::new(
+            config.watch.tracking_max_keys,
+        ))),
+    ));
+
+    // Sorted-set leaderboard notifications (docs/features/leaderboard-notifications.md).
+    // Keys under `leaderboards` are watched from boot; more can be added at
+    // runtime with `sortedset.notify.set`.
+    let leaderboard_notifier = Arc::new(synap_server::core::LeaderboardNotifier::new(
+        pubsub_router_inner.clone(),
+        0,
     ));
+    for (key, window) in &config.leaderboards {
+        leaderboard_notifier.set_window(key, *window)?;
+    }
+    let leaderboard_notifier = Some(leaderboard_notifier);
+
+    let access_sampler = config.kv_store.access_sampling.build();
+    let hot_keys = config.kv_store.hot_keys.build();
+    let message_tracer = config.message_trace.build();
+    let namespace_quotas = config.kv_store.namespace_quotas();
+    let key_limits = config.key_limits();
+
+    // Bind the HTTP port before recovery, which can take minutes on a large
+    // WAL: until the full router takes over, probes report alive but not
+    // ready instead of the port refusing connections.
+    let addr: SocketAddr = config.server_addr().parse()?;
+    let http_listener = std::net::TcpListener::bind(addr)?;
+    let startup_probes = if config.persistence.enabled {
+        Some(synap_server::health::StartupProbes::serve(
+            http_listener.try_clone()?,
+        )?)
+    } else {
+        None
+    };
+
+    // Runs before the first-boot check below, so restored data is never
+    // mistaken for an empty directory and seeded with fixtures
+    if args.restore_from_archive {
+        let report = synap_server::persistence::restore_from_archive(&config.persistence)
+            .await
+            .map_err(|e| anyhow::anyhow!("Restore from archive failed: {}", e))?;
+        info!(
+            "Restored from archive: snapshot {:?}, {} WAL segment(s) up to offset {:?}",
+            report.snapshot, report.segments, report.last_offset
+        );
+    }
+
+    // Decide before recovery touches the data directory: fixtures only seed a
+    // node that has nothing to recover.
+    let seed_fixtures = match &config.fixtures.path {
+        Some(_) if config.replication.role == NodeRole::Replica => {
+            info!("Fixtures ignored on a replica; data comes from the master");
+            false
+        }
+        Some(_) => synap_server::fixtures::is_first_boot(&config.persistence)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(
+                    "Could not inspect the data directory, skipping fixtures: {}",
+                    e
+                );
+                false
+            }),
+        None => false,
+    };
 
     type RecoveredStores = (
         Arc<KVStore>,
@@ -301,6 +479,8 @@ async fn main() -> Result<()> {
         Option<Arc<ListStore>>,
         Option<Arc<SetStore>>,
         Option<Arc<SortedSetStore>>,
+        Option<Arc<HyperLogLogStore>>,
+        Option<Arc<BloomFilterStore>>,
         Option<Arc<QueueManager>>,
         u64,
     );
@@ -310,45 +490,63 @@ async fn main() -> Result<()> {
         list_store_recovered,
         _set_store_recovered,
         _sorted_set_store_recovered,
+        hyperloglog_store_recovered,
+        bloom_store_recovered,
         queue_manager,
         _wal_offset,
     ): RecoveredStores = if config.persistence.enabled {
         info!("Persistence enabled, attempting recovery...");
         match recover(&config.persistence, kv_config.clone(), queue_config.clone()).await {
-            Ok((kv, hs, ls, ss, zs, qm, offset)) => {
+            Ok((kv, hs, ls, ss, zs, hll, bf, qm, offset)) => {
                 info!("Recovery successful, WAL offset: {}", offset);
                 (
                     Arc::new(
                         kv.with_global_memory(global_mem.clone())
                             .with_cluster(cluster_topology.clone(), cluster_migration.clone())
                             .with_keyspace_notifier(keyspace_notifier.clone())
-                            .with_watch_notifier(watch_notifier.clone()),
+                            .with_watch_notifier(watch_notifier.clone())
+                            .with_access_sampler(access_sampler.clone())
+                            .with_hot_keys(hot_keys.clone())
+                            .with_namespace_quotas(namespace_quotas.clone())
+                            .with_key_limits(key_limits),
                     ),
                     hs.map(|s| {
                         Arc::new(
                             s.with_global_memory(global_mem.clone())
-                                .with_keyspace_notifier(keyspace_notifier.clone()),
+                                .with_keyspace_notifier(keyspace_notifier.clone())
+                                .with_key_limits(key_limits),
                         )
                     }),
                     ls.map(|s| {
                         Arc::new(
                             s.with_global_memory(global_mem.clone())
-                                .with_keyspace_notifier(keyspace_notifier.clone()),
+                                .with_keyspace_notifier(keyspace_notifier.clone())
+                                .with_key_limits(key_limits),
                         )
                     }),
                     ss.map(|s| {
                         Arc::new(
                             s.with_global_memory(global_mem.clone())
-                                .with_keyspace_notifier(keyspace_notifier.clone()),
+                                .with_keyspace_notifier(keyspace_notifier.clone())
+                                .with_key_limits(key_limits),
                         )
                     }),
                     zs.map(|s| {
                         Arc::new(
                             s.with_global_memory(global_mem.clone())
-                                .with_keyspace_notifier(keyspace_notifier.clone()),
+                                .with_keyspace_notifier(keyspace_notifier.clone())
+                                .with_key_limits(key_limits)
+                                .with_leaderboard_notifier(leaderboard_notifier.clone()),
+                        )
+                    }),
+                    hll.map(Arc::new),
+                    bf.map(Arc::new),
+                    qm.filter(|_| queue_enabled).map(|s| {
+                        Arc::new(
+                            s.with_global_memory(global_mem.clone())
+                                .with_tracer(message_tracer.clone()),
                         )
                     }),
-                    qm.map(|s| Arc::new(s.with_global_memory(global_mem.clone()))),
                     offset,
                 )
             }
@@ -359,22 +557,40 @@ async fn main() -> Result<()> {
                         KVStore::new(kv_config.clone())
                             .with_cluster(cluster_topology.clone(), cluster_migration.clone())
                             .with_keyspace_notifier(keyspace_notifier.clone())
-                            .with_watch_notifier(watch_notifier.clone()),
+                            .with_watch_notifier(watch_notifier.clone())
+                            .with_access_sampler(access_sampler.clone())
+                            .with_hot_keys(hot_keys.clone())
+                            .with_namespace_quotas(namespace_quotas.clone())
+                            .with_key_limits(key_limits),
                     ),
                     Some(Arc::new(
-                        HashStore::new().with_keyspace_notifier(keyspace_notifier.clone()),
+                        HashStore::new()
+                            .with_keyspace_notifier(keyspace_notifier.clone())
+                            .with_key_limits(key_limits),
                     )),
                     Some(Arc::new(
-                        ListStore::new().with_keyspace_notifier(keyspace_notifier.clone()),
+                        ListStore::new()
+                            .with_keyspace_notifier(keyspace_notifier.clone())
+                            .with_key_limits(key_limits),
                     )),
                     Some(Arc::new(
-                        SetStore::new().with_keyspace_notifier(keyspace_notifier.clone()),
+                        SetStore::new()
+                            .with_keyspace_notifier(keyspace_notifier.clone())
+                            .with_key_limits(key_limits),
                     )),
                     Some(Arc::new(
-                        SortedSetStore::new().with_keyspace_notifier(keyspace_notifier.clone()),
+                        SortedSetStore::new()
+                            .with_keyspace_notifier(keyspace_notifier.clone())
+                            .with_key_limits(key_limits)
+                            .with_leaderboard_notifier(leaderboard_notifier.clone()),
                     )),
-                    if config.queue.enabled {
-                        Some(Arc::new(QueueManager::new(queue_config.clone())))
+                    Some(Arc::new(HyperLogLogStore::new())),
+                    Some(Arc::new(BloomFilterStore::new())),
+                    if queue_enabled {
+                        Some(Arc::new(
+                            QueueManager::new(queue_config.clone())
+                                .with_tracer(message_tracer.clone()),
+                        ))
                     } else {
                         None
                     },
@@ -390,31 +606,44 @@ async fn main() -> Result<()> {
                     .with_global_memory(global_mem.clone())
                     .with_cluster(cluster_topology.clone(), cluster_migration.clone())
                     .with_keyspace_notifier(keyspace_notifier.clone())
-                    .with_watch_notifier(watch_notifier.clone()),
+                    .with_watch_notifier(watch_notifier.clone())
+                    .with_access_sampler(access_sampler.clone())
+                    .with_hot_keys(hot_keys.clone())
+                    .with_namespace_quotas(namespace_quotas.clone())
+                    .with_key_limits(key_limits),
             ),
             Some(Arc::new(
                 HashStore::new()
                     .with_global_memory(global_mem.clone())
-                    .with_keyspace_notifier(keyspace_notifier.clone()),
+                    .with_keyspace_notifier(keyspace_notifier.clone())
+                    .with_key_limits(key_limits),
             )),
             Some(Arc::new(
                 ListStore::new()
                     .with_global_memory(global_mem.clone())
-                    .with_keyspace_notifier(keyspace_notifier.clone()),
+                    .with_keyspace_notifier(keyspace_notifier.clone())
+                    .with_key_limits(key_limits),
             )),
             Some(Arc::new(
                 SetStore::new()
                     .with_global_memory(global_mem.clone())
-                    .with_keyspace_notifier(keyspace_notifier.clone()),
+                    .with_keyspace_notifier(keyspace_notifier.clone())
+                    .with_key_limits(key_limits),
             )),
             Some(Arc::new(
                 SortedSetStore::new()
                     .with_global_memory(global_mem.clone())
-                    .with_keyspace_notifier(keyspace_notifier.clone()),
+                    .with_keyspace_notifier(keyspace_notifier.clone())
+                    .with_key_limits(key_limits)
+                    .with_leaderboard_notifier(leaderboard_notifier.clone()),
             )),
-            if config.queue.enabled {
+            Some(Arc::new(HyperLogLogStore::new())),
+            Some(Arc::new(BloomFilterStore::new())),
+            if queue_enabled {
                 Some(Arc::new(
-                    QueueManager::new(queue_config.clone()).with_global_memory(global_mem.clone()),
+                    QueueManager::new(queue_config.clone())
+                        .with_global_memory(global_mem.clone())
+                        .with_tracer(message_tracer.clone()),
                 ))
             } else {
                 None
@@ -425,6 +654,14 @@ async fn main() -> Result<()> {
 
     // Start TTL cleanup task
     kv_store.start_ttl_cleanup();
+    if config.kv_store.defrag.enabled {
+        kv_store.start_defrag(config.kv_store.defrag.clone());
+    }
+
+    // The migration worker copies slot keys out of the store it routes for
+    if let Some(ref migration) = cluster_migration {
+        migration.attach_kv_store(kv_store.clone());
+    }
 
     // Start queue deadline checker if queue enabled
     if let Some(ref qm) = queue_manager {
@@ -435,16 +672,22 @@ async fn main() -> Result<()> {
     }
 
     // Initialize stream manager (enabled by default for now)
+    #[cfg(feature = "streams")]
     let stream_manager = {
         let stream_mgr = Arc::new(
-            StreamManager::new(StreamConfig::default()).with_global_memory(global_mem.clone()),
+            StreamManager::new(StreamConfig::default())
+                .with_global_memory(global_mem.clone())
+                .with_tracer(message_tracer.clone()),
         );
         stream_mgr.clone().start_compaction_task();
         info!("Event Stream system enabled");
         Some(stream_mgr)
     };
+    #[cfg(not(feature = "streams"))]
+    let stream_manager: Option<Arc<StreamManager>> = None;
 
     // Initialize partitioned stream manager (Kafka-style)
+    #[cfg(feature = "streams")]
     let partition_manager = {
         let partition_mgr = Arc::new(PartitionManager::new(PartitionConfig::default()));
         partition_mgr.clone().start_compaction_task();
@@ -453,6 +696,7 @@ async fn main() -> Result<()> {
     };
 
     // Initialize consumer group manager
+    #[cfg(feature = "streams")]
     let consumer_group_manager = {
         let cg_mgr = Arc::new(ConsumerGroupManager::new(ConsumerGroupConfig::default()));
         cg_mgr.clone().start_rebalance_task();
@@ -461,10 +705,69 @@ async fn main() -> Result<()> {
     };
 
     // Pub/Sub router (created earlier so keyspace notifications share it).
-    let pubsub_router = {
-        info!("Pub/Sub system enabled");
-        Some(pubsub_router_inner)
-    };
+    // Without the `pubsub` feature it still carries KV watch and keyspace
+    // notifications; only the Pub/Sub API is left out.
+    #[cfg(feature = "pubsub")]
+    info!("Pub/Sub system enabled");
+    let pubsub_router = Some(pubsub_router_inner);
+
+    // Use recovered hash store (fallback shares the cross-datatype budget too).
+    let hash_store: Arc<synap_server::core::HashStore> =
+        hash_store_recovered.unwrap_or_else(|| {
+            Arc::new(
+                synap_server::core::HashStore::new()
+                    .with_global_memory(global_mem.clone())
+                    .with_keyspace_notifier(keyspace_notifier.clone())
+                    .with_key_limits(key_limits),
+            )
+        });
+    info!("Hash store initialized");
+
+    // Use recovered list store
+    let list_store: Arc<synap_server::core::ListStore> =
+        list_store_recovered.unwrap_or_else(|| {
+            Arc::new(
+                synap_server::core::ListStore::new()
+                    .with_global_memory(global_mem.clone())
+                    .with_keyspace_notifier(keyspace_notifier.clone())
+                    .with_key_limits(key_limits),
+            )
+        });
+    info!("List store initialized");
+
+    // Create set store (always fresh here — wire the shared budget).
+    let set_store = Arc::new(
+        synap_server::core::SetStore::new()
+            .with_global_memory(global_mem.clone())
+            .with_keyspace_notifier(keyspace_notifier.clone())
+            .with_key_limits(key_limits),
+    );
+    info!("Set store initialized");
+
+    // Use sorted set store (fresh or recovered)
+    let sorted_set_store = _sorted_set_store_recovered.unwrap_or_else(|| {
+        Arc::new(
+            synap_server::core::SortedSetStore::new()
+                .with_global_memory(global_mem.clone())
+                .with_keyspace_notifier(keyspace_notifier.clone())
+                .with_key_limits(key_limits)
+                .with_leaderboard_notifier(leaderboard_notifier.clone()),
+        )
+    });
+    info!("Sorted set store initialized");
+
+    // Use recovered HyperLogLog store (only logged merges are recovered)
+    let hyperloglog_store =
+        hyperloglog_store_recovered.unwrap_or_else(|| Arc::new(HyperLogLogStore::new()));
+    info!("HyperLogLog store initialized");
+
+    let bloom_store = bloom_store_recovered.unwrap_or_else(|| Arc::new(BloomFilterStore::new()));
+    info!("Bloom filter store initialized");
+
+    // Create Bitmap store
+    use synap_server::core::BitmapStore;
+    let bitmap_store = Arc::new(BitmapStore::new());
+    info!("Bitmap store initialized");
 
     // Start the replication node (self-runs on construction). The master is
     // handed to the persistence layer so every logged write is propagated to
@@ -475,8 +778,18 @@ async fn main() -> Result<()> {
             NodeRole::Master if config.replication.replica_listen_address.is_some() => {
                 match MasterNode::new(
                     config.replication.clone(),
-                    kv_store.clone(),
-                    stream_manager.clone(),
+                    synap_server::persistence::StoreArcs {
+                        kv_store: kv_store.clone(),
+                        hash_store: Some(hash_store.clone()),
+                        list_store: Some(list_store.clone()),
+                        set_store: Some(set_store.clone()),
+                        sorted_set_store: Some(sorted_set_store.clone()),
+                        hyperloglog_store: Some(hyperloglog_store.clone()),
+                        bloom_store: Some(bloom_store.clone()),
+                        bitmap_store: Some(bitmap_store.clone()),
+                        queue_manager: queue_manager.clone(),
+                        stream_manager: stream_manager.clone(),
+                    },
                 )
                 .await
                 {
@@ -513,8 +826,12 @@ async fn main() -> Result<()> {
     // master is present. The layer is the shared propagate hook: even with
     // persistence disabled, a master must still forward every write to replicas
     // (phase6j — replication decoupled from the WAL). A replication-only layer
-    // opens no WAL file.
-    let persistence = if config.persistence.enabled || replication_master.is_some() {
+    // opens no WAL file. Automatic failover needs the hook on replicas too, so
+    // a promoted replica can start propagating without a restart.
+    let auto_failover = config.replication.enabled && config.replication.failover.enabled;
+    let latency = Arc::new(LatencyMonitor::new(config.latency_monitor.clone()));
+    let persistence = if config.persistence.enabled || replication_master.is_some() || auto_failover
+    {
         match PersistenceLayer::new_with_replication(
             config.persistence.clone(),
             replication_master.clone(),
@@ -525,7 +842,7 @@ async fn main() -> Result<()> {
                 info!("Persistence layer initialized (WAL + Snapshots)");
                 // The background snapshot task is started later, once every
                 // store (hash/list/set/sorted-set) has been constructed.
-                Some(Arc::new(layer))
+                Some(Arc::new(layer.with_latency_monitor(latency.clone())))
             }
             Err(e) => {
                 warn!("Failed to initialize persistence: {}", e);
@@ -536,46 +853,6 @@ async fn main() -> Result<()> {
         None
     };
 
-    // Use recovered hash store (fallback shares the cross-datatype budget too).
-    let hash_store: Arc<synap_server::core::HashStore> =
-        hash_store_recovered.unwrap_or_else(|| {
-            Arc::new(
-                synap_server::core::HashStore::new()
-                    .with_global_memory(global_mem.clone())
-                    .with_keyspace_notifier(keyspace_notifier.clone()),
-            )
-        });
-    info!("Hash store initialized");
-
-    // Use recovered list store
-    let list_store: Arc<synap_server::core::ListStore> =
-        list_store_recovered.unwrap_or_else(|| {
-            Arc::new(
-                synap_server::core::ListStore::new()
-                    .with_global_memory(global_mem.clone())
-                    .with_keyspace_notifier(keyspace_notifier.clone()),
-            )
-        });
-    info!("List store initialized");
-
-    // Create set store (always fresh here — wire the shared budget).
-    let set_store = Arc::new(
-        synap_server::core::SetStore::new()
-            .with_global_memory(global_mem.clone())
-            .with_keyspace_notifier(keyspace_notifier.clone()),
-    );
-    info!("Set store initialized");
-
-    // Use sorted set store (fresh or recovered)
-    let sorted_set_store = _sorted_set_store_recovered.unwrap_or_else(|| {
-        Arc::new(
-            synap_server::core::SortedSetStore::new()
-                .with_global_memory(global_mem.clone())
-                .with_keyspace_notifier(keyspace_notifier.clone()),
-        )
-    });
-    info!("Sorted set store initialized");
-
     // Periodically recompute collection/broker memory into the shared budget so
     // `maxmemory` reflects all datatypes (audit M-018). KV updates its counter
     // live; the others are recomputed on an interval (drift-free, no fragile
@@ -605,6 +882,33 @@ async fn main() -> Result<()> {
         });
     }
 
+    if seed_fixtures && let Some(path) = &config.fixtures.path {
+        let stores = synap_server::persistence::StoreArcs {
+            kv_store: kv_store.clone(),
+            hash_store: Some(hash_store.clone()),
+            list_store: Some(list_store.clone()),
+            set_store: Some(set_store.clone()),
+            sorted_set_store: Some(sorted_set_store.clone()),
+            hyperloglog_store: Some(hyperloglog_store.clone()),
+            bloom_store: Some(bloom_store.clone()),
+            bitmap_store: Some(bitmap_store.clone()),
+            queue_manager: queue_manager.clone(),
+            stream_manager: stream_manager.clone(),
+        };
+        info!(
+            "Empty data directory, loading fixtures from {}",
+            path.display()
+        );
+        synap_server::fixtures::Fixtures::load(path)?
+            .apply(stores.as_refs())
+            .await?;
+        // Fixtures bypass the WAL; snapshot them so a restart recovers them
+        // instead of finding an empty directory and seeding again.
+        if let Some(ref layer) = persistence {
+            layer.snapshot_now(stores.as_refs()).await?;
+        }
+    }
+
     // Now that every store exists, start the background snapshot task so that
     // hash/list/set/sorted-set state is captured alongside KV/queue/stream.
     if let Some(ref layer) = persistence {
@@ -616,11 +920,41 @@ async fn main() -> Result<()> {
                 list_store: Some(list_store.clone()),
                 set_store: Some(set_store.clone()),
                 sorted_set_store: Some(sorted_set_store.clone()),
+                hyperloglog_store: Some(hyperloglog_store.clone()),
+                bloom_store: Some(bloom_store.clone()),
+                bitmap_store: Some(bitmap_store.clone()),
                 queue_manager: queue_manager.clone(),
                 stream_manager: stream_manager.clone(),
             });
     }
 
+    // Ship WAL segments and snapshots off-host
+    if config.persistence.enabled && config.persistence.archive.enabled {
+        match synap_server::persistence::WalArchiver::open(&config.persistence).await {
+            Ok(archiver) => {
+                archiver.start();
+            }
+            Err(e) => warn!("WAL archiving disabled: {}", e),
+        }
+    }
+
+    // Put back consumer groups and their members recorded in the durable
+    // subscription registry, then keep the registry in step with expiries.
+    #[cfg(feature = "streams")]
+    if let Some(ref layer) = persistence
+        && let Some(registry) = layer.subscriptions()
+        && let Some(ref cg_mgr) = consumer_group_manager
+    {
+        let summary = registry.restore(cg_mgr).await;
+        info!(
+            "Restored {} consumer groups, {} members and {} offsets from the subscription registry",
+            summary.groups, summary.members, summary.offsets
+        );
+        registry
+            .clone()
+            .start_prune_task(cg_mgr.clone(), Duration::from_secs(30));
+    }
+
     // Start the replication replica node now that every datatype store exists,
     // so the background sync loop converges KV *and* all collections/broker
     // datatypes (audit M-005) — not just KV. The master arm is handled above.
@@ -636,6 +970,9 @@ async fn main() -> Result<()> {
                 list_store: Some(list_store.clone()),
                 set_store: Some(set_store.clone()),
                 sorted_set_store: Some(sorted_set_store.clone()),
+                hyperloglog_store: Some(hyperloglog_store.clone()),
+                bloom_store: Some(bloom_store.clone()),
+                bitmap_store: Some(bitmap_store.clone()),
                 queue_manager: queue_manager.clone(),
                 stream_manager: stream_manager.clone(),
             },
@@ -656,52 +993,179 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Create HyperLogLog store
-    use synap_server::core::HyperLogLogStore;
-    let hyperloglog_store = Arc::new(HyperLogLogStore::new());
-    info!("HyperLogLog store initialized");
-
-    // Create Bitmap store
-    use synap_server::core::BitmapStore;
-    let bitmap_store = Arc::new(BitmapStore::new());
-    info!("Bitmap store initialized");
+    // Automatic failover: replicas elect and promote a new master when the
+    // current one goes down (docs/users/configuration/REPLICATION.md).
+    let failover = match &replication_handle {
+        Some(handle) if auto_failover => match config.replication.validate() {
+            Ok(()) => match synap_server::replication::FailoverCoordinator::new(
+                config.replication.clone(),
+                handle.clone(),
+                synap_server::persistence::StoreArcs {
+                    kv_store: kv_store.clone(),
+                    hash_store: Some(hash_store.clone()),
+                    list_store: Some(list_store.clone()),
+                    set_store: Some(set_store.clone()),
+                    sorted_set_store: Some(sorted_set_store.clone()),
+                    hyperloglog_store: Some(hyperloglog_store.clone()),
+                    bloom_store: Some(bloom_store.clone()),
+                    bitmap_store: Some(bitmap_store.clone()),
+                    queue_manager: queue_manager.clone(),
+                    stream_manager: stream_manager.clone(),
+                },
+                persistence.clone(),
+            ) {
+                Ok(coordinator) => {
+                    coordinator.start();
+                    Some(coordinator)
+                }
+                Err(e) => {
+                    warn!("Failed to start failover coordinator: {}", e);
+                    None
+                }
+            },
+            Err(e) => {
+                warn!("Automatic failover disabled: {}", e);
+                None
+            }
+        },
+        _ => None,
+    };
 
     // Create Geospatial store (depends on sorted_set_store)
     use synap_server::core::GeospatialStore;
     let geospatial_store = Arc::new(GeospatialStore::new(sorted_set_store.clone()));
     info!("Geospatial store initialized");
 
+    // Create audit log: in memory for GET /audit, plus the optional file and
+    // stream sinks
+    let mut audit_log = AuditLogManager::from_config(&config.auth.audit)?;
+    if let Some(room) = &config.auth.audit.stream_room {
+        match &stream_manager {
+            Some(streams) => audit_log = audit_log.with_stream(streams.clone(), room.clone()),
+            None => warn!("auth.audit.stream_room is set but streams are disabled"),
+        }
+    }
+
+    // Databases 1 and up (`databases.count`), each recovered from its own
+    // WAL and snapshots
+    let databases = Arc::new(
+        synap_server::databases::Databases::open(
+            &config,
+            &global_mem,
+            pubsub_router
+                .as_ref()
+                .expect("pub/sub router is always created"),
+            &latency,
+        )
+        .await?,
+    );
+
     // Create monitoring manager
-    let monitoring = Arc::new(MonitoringManager::new(
-        kv_store.clone(),
-        hash_store.clone(),
-        list_store.clone(),
-        set_store.clone(),
-        sorted_set_store.clone(),
-    ));
+    let monitoring = Arc::new(
+        MonitoringManager::new(
+            kv_store.clone(),
+            hash_store.clone(),
+            list_store.clone(),
+            set_store.clone(),
+            sorted_set_store.clone(),
+        )
+        .with_audit_log(Arc::new(audit_log))
+        .with_command_hooks(Arc::new(CommandHooks::from_config(&config.command_hooks)))
+        .with_latency_monitor(latency)
+        .with_health(config.health.clone())
+        .with_runtime_config(runtime_config)
+        .with_databases(databases)
+        .with_slow_log(Arc::new(SlowLogManager::with_config(
+            config.slowlog.clone(),
+        ))),
+    );
+    if let Some(path) = &config.command_hooks.sidecar_socket {
+        info!(
+            "Command hooks: streaming writes to sidecar at {}",
+            path.display()
+        );
+    }
     info!("Monitoring manager initialized");
+    let lock_manager = Arc::new(synap_server::core::LockManager::new());
+    if let Some(failover) = &failover {
+        failover.set_audit_log(monitoring.audit_log());
+        failover.set_lock_manager(lock_manager.clone());
+    }
+
+    // Cold start warmup: report not ready until the hot keys are primed, by
+    // the startup manifest or by the first POST /warmup
+    if config.warmup.manifest.is_some() || config.warmup.wait_for_manifest {
+        monitoring.warmup().hold();
+    }
+    if let Some(path) = config.warmup.manifest.clone() {
+        let warmup = monitoring.warmup();
+        let kv_store = kv_store.clone();
+        tokio::spawn(async move {
+            match synap_server::warmup::WarmupManifest::load(&path) {
+                Ok(manifest) => {
+                    warmup.run(&kv_store, &manifest).await;
+                }
+                Err(e) => {
+                    // A bad manifest must not keep the node out of rotation forever
+                    warn!("Skipping warmup: {:#}", e);
+                    warmup.mark_ready();
+                }
+            }
+        });
+    }
 
     // Create client list manager
-    let client_list_manager = Arc::new(ClientListManager::new());
+    let client_list_manager =
+        Arc::new(ClientListManager::new().with_limits(config.clients.clone()));
     info!("Client list manager initialized");
 
     // Create transaction manager
     use synap_server::core::TransactionManager;
-    let transaction_manager = Arc::new(TransactionManager::new(
-        kv_store.clone(),
-        hash_store.clone(),
-        list_store.clone(),
-        set_store.clone(),
-        sorted_set_store.clone(),
-    ));
+    let transaction_manager = Arc::new(
+        TransactionManager::new(
+            kv_store.clone(),
+            hash_store.clone(),
+            list_store.clone(),
+            set_store.clone(),
+            sorted_set_store.clone(),
+        )
+        .with_queue_manager(queue_manager.clone())
+        .with_stream_manager(stream_manager.clone()),
+    );
     info!("Transaction manager initialized");
 
     // Create script manager (Lua scripting)
-    let script_manager = Arc::new(ScriptManager::new(Duration::from_secs(5)));
+    // Function libraries are kept next to the snapshots so they survive restarts.
+    #[cfg(feature = "scripting")]
+    let functions = if config.persistence.enabled {
+        let path = config.persistence.snapshot.directory.join("functions.json");
+        match synap_server::scripting::FunctionRegistry::open(&path) {
+            Ok(registry) => registry,
+            Err(e) => {
+                // Keep the unreadable file intact rather than overwriting it.
+                warn!(
+                    "Failed to load function libraries from {}: {}; functions will not be persisted",
+                    path.display(),
+                    e
+                );
+                synap_server::scripting::FunctionRegistry::new()
+            }
+        }
+    } else {
+        synap_server::scripting::FunctionRegistry::new()
+    };
+    #[cfg(feature = "scripting")]
+    let script_manager =
+        Arc::new(ScriptManager::new(Duration::from_secs(5)).with_functions(functions));
+    #[cfg(feature = "scripting")]
     info!("Script manager initialized (default timeout: 5s)");
 
     // Initialize authentication managers
-    let user_manager = Arc::new(UserManager::new());
+    let user_manager = Arc::new(if config.auth.enabled {
+        UserManager::new().with_tokens(TokenManager::new(&config.auth.jwt))
+    } else {
+        UserManager::new()
+    });
     let api_key_manager = Arc::new(ApiKeyManager::new());
 
     // Initialize root user if authentication is enabled
@@ -812,6 +1276,70 @@ async fn main() -> Result<()> {
         None
     };
 
+    // Shovel definitions and counters are checkpointed next to the snapshots.
+    #[cfg(feature = "queues")]
+    let shovels = queue_manager.as_ref().map(|_| {
+        use synap_server::shovel::{SHOVELS_FILE, ShovelManager};
+        if !config.persistence.enabled {
+            return Arc::new(ShovelManager::new());
+        }
+        let path = config.persistence.snapshot.directory.join(SHOVELS_FILE);
+        match ShovelManager::open(&path) {
+            Ok(manager) => Arc::new(manager),
+            Err(e) => {
+                // Keep the unreadable file intact rather than overwriting it.
+                warn!(
+                    "Failed to load shovels from {}: {}; shovels will not be persisted",
+                    path.display(),
+                    e
+                );
+                Arc::new(ShovelManager::new())
+            }
+        }
+    });
+
+    // Webhooks are checkpointed the same way.
+    #[cfg(feature = "queues")]
+    let webhooks = queue_manager.as_ref().map(|_| {
+        use synap_server::webhook::{WEBHOOKS_FILE, WebhookManager};
+        if !config.persistence.enabled {
+            return Arc::new(WebhookManager::new());
+        }
+        let path = config.persistence.snapshot.directory.join(WEBHOOKS_FILE);
+        match WebhookManager::open(&path) {
+            Ok(manager) => Arc::new(manager),
+            Err(e) => {
+                warn!(
+                    "Failed to load webhooks from {}: {}; webhooks will not be persisted",
+                    path.display(),
+                    e
+                );
+                Arc::new(WebhookManager::new())
+            }
+        }
+    });
+
+    // Schedules are checkpointed the same way; they run with or without queues.
+    let schedules = {
+        use synap_server::scheduler::{SCHEDULES_FILE, ScheduleManager};
+        if config.persistence.enabled {
+            let path = config.persistence.snapshot.directory.join(SCHEDULES_FILE);
+            match ScheduleManager::open(&path) {
+                Ok(manager) => Arc::new(manager),
+                Err(e) => {
+                    warn!(
+                        "Failed to load schedules from {}: {}; schedules will not be persisted",
+                        path.display(),
+                        e
+                    );
+                    Arc::new(ScheduleManager::new())
+                }
+            }
+        } else {
+            Arc::new(ScheduleManager::new())
+        }
+    };
+
     // Create application state with persistence and streams
     let app_state = AppState {
         kv_store,
@@ -822,17 +1350,24 @@ async fn main() -> Result<()> {
         hyperloglog_store,
         bitmap_store,
         geospatial_store,
+        #[cfg(feature = "queues")]
         queue_manager,
+        #[cfg(feature = "streams")]
         stream_manager,
+        #[cfg(feature = "streams")]
         partition_manager,
+        #[cfg(feature = "streams")]
         consumer_group_manager,
         pubsub_router,
         persistence,
         monitoring,
         transaction_manager,
+        #[cfg(feature = "scripting")]
         script_manager,
         client_list_manager,
+        #[cfg(feature = "cluster")]
         cluster_topology: cluster_topology.clone(),
+        #[cfg(feature = "cluster")]
         cluster_migration: cluster_migration.clone(),
 
         hub_client,
@@ -843,8 +1378,54 @@ async fn main() -> Result<()> {
         },
         require_auth: config.auth.enabled && config.auth.require_auth,
         replication: replication_handle,
+        failover,
+        lock_manager,
+        rate_limits: Arc::new(synap_server::core::RateLimitStore::new()),
+        time_series: Arc::new(synap_server::core::TimeSeriesStore::new()),
+        bloom_store,
+        #[cfg(feature = "queues")]
+        shovels,
+        #[cfg(feature = "queues")]
+        webhooks,
+        schedules: Some(schedules),
     };
 
+    #[cfg(feature = "queues")]
+    if let Some(shovels) = &app_state.shovels {
+        match synap_server::shovel::ShovelContext::new(&app_state) {
+            Ok(context) => shovels.resume(context),
+            Err(e) => warn!("Failed to resume shovels: {}", e),
+        }
+    }
+    #[cfg(feature = "queues")]
+    if let Some(webhooks) = &app_state.webhooks {
+        match synap_server::webhook::WebhookContext::new(&app_state) {
+            Ok(context) => webhooks.resume(context),
+            Err(e) => warn!("Failed to resume webhooks: {}", e),
+        }
+    }
+    if let Some(schedules) = &app_state.schedules {
+        schedules.resume(synap_server::scheduler::ScheduleContext::new(&app_state));
+    }
+
+    // Kafka relays run for as long as `_kafka_relays` is alive.
+    #[cfg(feature = "kafka")]
+    let _kafka_relays = if config.kafka_relay.relays.is_empty() {
+        None
+    } else {
+        use synap_server::kafka_relay::{KafkaRelays, RelayContext};
+        match KafkaRelays::start(&config.kafka_relay.relays, RelayContext::new(&app_state)) {
+            Ok(relays) => Some(relays),
+            Err(e) => {
+                error!("Failed to start Kafka relays: {}", e);
+                std::process::exit(1);
+            }
+        }
+    };
+    if !config.kafka_relay.relays.is_empty() && !cfg!(feature = "kafka") {
+        warn!("kafka_relay.relays is set but this build has no `kafka` feature; no relay starts");
+    }
+
     // Initialize Prometheus metrics
     init_metrics();
 
@@ -881,8 +1462,22 @@ async fn main() -> Result<()> {
         None
     };
 
+    // Spawn optional gRPC listener (`grpc` feature).
+    #[cfg(feature = "grpc")]
+    if config.grpc.enabled {
+        use synap_server::protocol::grpc::spawn_grpc_listener;
+        let grpc_addr: SocketAddr = format!("{}:{}", config.grpc.host, config.grpc.port)
+            .parse()
+            .expect("invalid grpc bind address");
+        spawn_grpc_listener(app_state.clone(), grpc_addr, config.grpc.max_message_bytes).await?;
+        info!("gRPC listener started on {grpc_addr}");
+    }
+    if config.grpc.enabled && !cfg!(feature = "grpc") {
+        warn!("grpc.enabled is set but this build has no `grpc` feature; no gRPC listener starts");
+    }
+
     // Create router with rate limiting and authentication
-    let app = create_router(
+    let app = create_router_with_compression(
         app_state,
         config.rate_limit.clone(),
         config.mcp.clone(),
@@ -890,22 +1485,25 @@ async fn main() -> Result<()> {
         api_key_manager.clone(),
         config.auth.enabled,
         config.auth.require_auth,
+        &config.http_compression,
     );
 
-    if config.rate_limit.enabled {
-        info!(
-            "Rate limiting enabled: {} req/s (burst: {})",
-            config.rate_limit.requests_per_second, config.rate_limit.burst_size
-        );
+    if let Some(probes) = startup_probes {
+        probes.stop().await;
     }
-
-    // Bind server
-    let addr: SocketAddr = config.server_addr().parse()?;
     info!("Listening on http://{}", addr);
 
-    // Start server
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    // Start server on the port bound before recovery
+    http_listener.set_nonblocking(true)?;
+    let listener = tokio::net::TcpListener::from_std(http_listener)?;
+    // Peer addresses feed the WebSocket handlers and per-IP limits
+    let served = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await;
+    synap_server::telemetry::shutdown();
+    served?;
 
     Ok(())
 }
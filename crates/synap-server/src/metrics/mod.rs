@@ -2,6 +2,8 @@
 //!
 //! Comprehensive metrics collection for all Synap components:
 //! - KV Store operations
+//! - Hash, list, set and sorted set operations
+//! - Transactions and scripts
 //! - Queue operations
 //! - Stream operations
 //! - Pub/Sub operations
@@ -14,8 +16,8 @@ use std::time::Instant;
 
 use lazy_static::lazy_static;
 use prometheus::{
-    Encoder, HistogramVec, IntCounterVec, IntGaugeVec, TextEncoder, register_histogram_vec,
-    register_int_counter_vec, register_int_gauge_vec,
+    Encoder, HistogramVec, IntCounter, IntCounterVec, IntGaugeVec, TextEncoder,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge_vec,
 };
 
 // Sub-millisecond buckets for TCP protocol latency (µs–ms range).
@@ -66,6 +68,70 @@ lazy_static! {
         &["type"]
     ).expect("metric registration uses a static, unique name");
 
+    /// Keys evicted from the KV store to stay under `max_memory_mb`
+    pub static ref KV_EVICTED_KEYS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_kv_evicted_keys_total",
+        "Total number of keys evicted from the KV store under memory pressure",
+        &["policy"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Bytes freed by KV evictions
+    pub static ref KV_EVICTED_BYTES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_kv_evicted_bytes_total",
+        "Total bytes freed by KV store evictions",
+        &["policy"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// KV writes refused because memory could not be freed
+    pub static ref KV_REJECTED_WRITES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_kv_rejected_writes_total",
+        "Total KV writes refused with a memory limit error",
+        &["policy"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// KV defragmentation passes run
+    pub static ref KV_DEFRAG_PASSES_TOTAL: IntCounter = register_int_counter!(
+        "synap_kv_defrag_passes_total",
+        "Total number of KV defragmentation passes"
+    ).expect("metric registration uses a static, unique name");
+
+    /// KV defragmentation bytes: `reclaimed` (unused map capacity released)
+    /// and `relocated` (large values copied into fresh allocations)
+    pub static ref KV_DEFRAG_BYTES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_kv_defrag_bytes_total",
+        "Bytes reclaimed or relocated by KV defragmentation",
+        &["kind"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Keys held by each KV shard
+    pub static ref KV_SHARD_KEYS: IntGaugeVec = register_int_gauge_vec!(
+        "synap_kv_shard_keys",
+        "Keys held by each KV shard",
+        &["shard"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// KV shard lock acquisitions by shard and mode (`read` or `write`)
+    pub static ref KV_SHARD_LOCK_ACQUISITIONS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_kv_shard_lock_acquisitions_total",
+        "Lock acquisitions on each KV shard, by mode",
+        &["shard", "mode"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// KV shard lock acquisitions that waited for another holder
+    pub static ref KV_SHARD_LOCK_CONTENDED_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_kv_shard_lock_contended_total",
+        "Lock acquisitions on each KV shard that had to wait",
+        &["shard"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Command hook records by hook and outcome: `delivered`, `failed` (the
+    /// hook returned an error) or `dropped` (its queue was full)
+    pub static ref COMMAND_HOOK_RECORDS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_command_hook_records_total",
+        "Mutating command records handled by each command hook, by outcome",
+        &["hook", "outcome"]
+    ).expect("metric registration uses a static, unique name");
+
     /// Accounted memory per datatype toward the shared `maxmemory` budget (audit
     /// M-018). The sum across datatypes is the total the eviction/refusal path uses.
     pub static ref DATATYPE_MEMORY_BYTES: IntGaugeVec = register_int_gauge_vec!(
@@ -74,6 +140,14 @@ lazy_static! {
         &["datatype"]
     ).expect("metric registration uses a static, unique name");
 
+    /// Estimated operations on the hottest keys in the last completed hot key
+    /// interval (`kv_store.hot_keys`)
+    pub static ref KV_HOT_KEY_OPERATIONS: IntGaugeVec = register_int_gauge_vec!(
+        "synap_kv_hot_key_operations",
+        "Estimated operations on the hottest KV keys in the last completed interval",
+        &["key", "operation"]
+    ).expect("metric registration uses a static, unique name");
+
     // ============================================================================
     // Queue Metrics
     // ============================================================================
@@ -157,6 +231,13 @@ lazy_static! {
         &["topic"]
     ).expect("metric registration uses a static, unique name");
 
+    /// Publishes rejected by per-topic limits
+    pub static ref PUBSUB_REJECTED_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_pubsub_rejected_total",
+        "Publishes rejected by per-topic size and rate limits",
+        &["reason"]
+    ).expect("metric registration uses a static, unique name");
+
     /// Active subscriptions
     pub static ref PUBSUB_SUBSCRIPTIONS: IntGaugeVec = register_int_gauge_vec!(
         "synap_pubsub_subscriptions",
@@ -189,6 +270,14 @@ lazy_static! {
         &["direction"]
     ).expect("metric registration uses a static, unique name");
 
+    /// Replication link bytes before (`raw`) and after (`wire`) compression;
+    /// `raw - wire` is the bandwidth saved
+    pub static ref REPL_COMPRESSION_BYTES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_replication_compression_bytes_total",
+        "Bytes through compressed replication links, before and after compression",
+        &["direction", "stage"]
+    ).expect("metric registration uses a static, unique name");
+
     // ============================================================================
     // HTTP Server Metrics
     // ============================================================================
@@ -215,6 +304,40 @@ lazy_static! {
         &["type"]
     ).expect("metric registration uses a static, unique name");
 
+    /// Requests that used a deprecated endpoint, command or field
+    pub static ref DEPRECATED_REQUESTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_deprecated_requests_total",
+        "Total requests using a deprecated API surface",
+        &["surface"]
+    ).expect("metric registration uses a static, unique name");
+
+    // ============================================================================
+    // WAL Group Commit Metrics
+    // ============================================================================
+
+    /// Entries written per WAL group commit
+    pub static ref WAL_COMMIT_BATCH_SIZE: HistogramVec = register_histogram_vec!(
+        "synap_wal_commit_batch_size",
+        "Entries written per WAL group commit",
+        &[],
+        vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0, 1024.0]
+    ).expect("metric registration uses a static, unique name");
+
+    /// WAL group commits, by whether they were fsynced (`true` / `false`)
+    pub static ref WAL_COMMITS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_wal_commits_total",
+        "Total WAL group commits, by whether they were fsynced",
+        &["fsync"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Flush + fsync latency of a WAL group commit
+    pub static ref WAL_FSYNC_DURATION: HistogramVec = register_histogram_vec!(
+        "synap_wal_fsync_duration_seconds",
+        "WAL flush and fsync latency per group commit in seconds",
+        &[],
+        vec![0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.5, 1.0]
+    ).expect("metric registration uses a static, unique name");
+
     // ============================================================================
     // RESP3 TCP Protocol Metrics
     // ============================================================================
@@ -389,6 +512,126 @@ lazy_static! {
     ).expect("metric registration uses a static, unique name");
 }
 
+// A second block: one `lazy_static!` holding every metric exceeds the macro
+// recursion limit
+lazy_static! {
+    // ============================================================================
+    // Hash, List, Set and Sorted Set Metrics
+    // ============================================================================
+
+    /// Total hash operations by command and status
+    pub static ref HASH_OPS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_hash_operations_total",
+        "Total number of hash operations by command",
+        &["operation", "status"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Hash operation latency in seconds
+    pub static ref HASH_OP_DURATION: HistogramVec = register_histogram_vec!(
+        "synap_hash_operation_duration_seconds",
+        "Hash operation latency in seconds",
+        &["operation"],
+        vec![0.00001, 0.0001, 0.001, 0.01, 0.1, 1.0]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Total list operations by command and status
+    pub static ref LIST_OPS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_list_operations_total",
+        "Total number of list operations by command",
+        &["operation", "status"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// List operation latency in seconds
+    pub static ref LIST_OP_DURATION: HistogramVec = register_histogram_vec!(
+        "synap_list_operation_duration_seconds",
+        "List operation latency in seconds",
+        &["operation"],
+        vec![0.00001, 0.0001, 0.001, 0.01, 0.1, 1.0]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Total set operations by command and status
+    pub static ref SET_OPS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_set_operations_total",
+        "Total number of set operations by command",
+        &["operation", "status"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Set operation latency in seconds
+    pub static ref SET_OP_DURATION: HistogramVec = register_histogram_vec!(
+        "synap_set_operation_duration_seconds",
+        "Set operation latency in seconds",
+        &["operation"],
+        vec![0.00001, 0.0001, 0.001, 0.01, 0.1, 1.0]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Total sorted set operations by command and status
+    pub static ref SORTED_SET_OPS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_sorted_set_operations_total",
+        "Total number of sorted set operations by command",
+        &["operation", "status"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Sorted set operation latency in seconds
+    pub static ref SORTED_SET_OP_DURATION: HistogramVec = register_histogram_vec!(
+        "synap_sorted_set_operation_duration_seconds",
+        "Sorted set operation latency in seconds",
+        &["operation"],
+        vec![0.00001, 0.0001, 0.001, 0.01, 0.1, 1.0]
+    ).expect("metric registration uses a static, unique name");
+
+    // ============================================================================
+    // Transaction and Script Metrics
+    // ============================================================================
+
+    /// Total transaction operations by command and status
+    pub static ref TRANSACTION_OPS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_transaction_operations_total",
+        "Total number of transaction operations by command",
+        &["operation", "status"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Transaction operation latency in seconds
+    pub static ref TRANSACTION_OP_DURATION: HistogramVec = register_histogram_vec!(
+        "synap_transaction_operation_duration_seconds",
+        "Transaction operation latency in seconds",
+        &["operation"],
+        vec![0.0001, 0.001, 0.01, 0.1, 1.0, 10.0]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Total script and function operations by command and status
+    pub static ref SCRIPT_OPS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_script_operations_total",
+        "Total number of script and function operations by command",
+        &["operation", "status"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Script operation latency in seconds
+    pub static ref SCRIPT_OP_DURATION: HistogramVec = register_histogram_vec!(
+        "synap_script_operation_duration_seconds",
+        "Script operation latency in seconds",
+        &["operation"],
+        vec![0.0001, 0.001, 0.01, 0.1, 1.0, 10.0]
+    ).expect("metric registration uses a static, unique name");
+
+    // ============================================================================
+    // Kafka Relay Metrics
+    // ============================================================================
+
+    /// Events a Kafka relay delivered
+    pub static ref KAFKA_RELAY_EVENTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_kafka_relay_events_total",
+        "Total events delivered to Kafka per relay",
+        &["relay"]
+    ).expect("metric registration uses a static, unique name");
+
+    /// Batches a Kafka relay failed to read or deliver
+    pub static ref KAFKA_RELAY_ERRORS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "synap_kafka_relay_errors_total",
+        "Total failed relay batches per relay",
+        &["relay"]
+    ).expect("metric registration uses a static, unique name");
+}
+
 /// Encode all metrics to Prometheus text format
 pub fn encode_metrics() -> Result<String, Box<dyn std::error::Error>> {
     let encoder = TextEncoder::new();
@@ -442,6 +685,16 @@ pub fn record_pubsub_message(topic: &str) {
     PUBSUB_MESSAGES_TOTAL.with_label_values(&[topic]).inc();
 }
 
+/// Record a publish rejected by a per-topic limit; other errors are ignored
+pub fn record_pubsub_rejected(err: &crate::core::SynapError) {
+    let reason = match err {
+        crate::core::SynapError::MessageTooLarge { .. } => "message_too_large",
+        crate::core::SynapError::PublishRateLimited { .. } => "rate_limited",
+        _ => return,
+    };
+    PUBSUB_REJECTED_TOTAL.with_label_values(&[reason]).inc();
+}
+
 /// Record HTTP request
 pub fn record_http_request(method: &str, path: &str, status: u16, duration_secs: f64) {
     HTTP_REQUESTS_TOTAL
@@ -452,6 +705,13 @@ pub fn record_http_request(method: &str, path: &str, status: u16, duration_secs:
         .observe(duration_secs);
 }
 
+/// Record a request using a deprecated API surface
+pub fn record_deprecated_request(surface: &str) {
+    DEPRECATED_REQUESTS_TOTAL
+        .with_label_values(&[surface])
+        .inc();
+}
+
 /// Update replication lag
 pub fn update_replication_lag(replica_id: &str, lag: i64) {
     REPL_LAG.with_label_values(&[replica_id]).set(lag);
@@ -463,6 +723,33 @@ pub fn record_replication_op(op_type: &str, status: &str, bytes: u64) {
     REPL_BYTES_TOTAL.with_label_values(&["sent"]).inc_by(bytes);
 }
 
+/// Record one WAL group commit of `entries` entries, fsynced in
+/// `fsync_secs` when it was fsynced
+pub fn record_wal_commit(entries: usize, fsync_secs: Option<f64>) {
+    WAL_COMMIT_BATCH_SIZE
+        .with_label_values(&[] as &[&str; 0])
+        .observe(entries as f64);
+    match fsync_secs {
+        Some(secs) => {
+            WAL_COMMITS_TOTAL.with_label_values(&["true"]).inc();
+            WAL_FSYNC_DURATION
+                .with_label_values(&[] as &[&str; 0])
+                .observe(secs);
+        }
+        None => WAL_COMMITS_TOTAL.with_label_values(&["false"]).inc(),
+    }
+}
+
+/// Record one frame through a compressed replication link
+pub fn record_replication_compression(direction: &str, raw: usize, wire: usize) {
+    REPL_COMPRESSION_BYTES_TOTAL
+        .with_label_values(&[direction, "raw"])
+        .inc_by(raw as u64);
+    REPL_COMPRESSION_BYTES_TOTAL
+        .with_label_values(&[direction, "wire"])
+        .inc_by(wire as u64);
+}
+
 // ── System / host / process snapshot helpers ──────────────────────────────────
 
 /// Set the per-process CPU + memory gauges (this Synap process only).
@@ -515,6 +802,64 @@ pub fn reset_broker_gauges() {
     QUEUE_DEPTH.reset();
     QUEUE_DLQ_TOTAL.reset();
     DATATYPE_MEMORY_BYTES.reset();
+    KV_HOT_KEY_OPERATIONS.reset();
+}
+
+/// Bring the KV eviction counters up to the store's own totals.
+/// The store counts evictions itself, so this runs on each scrape.
+pub fn sync_kv_eviction_counters(
+    policy: &str,
+    evicted_keys: u64,
+    evicted_bytes: u64,
+    rejected: u64,
+) {
+    for (counter, total) in [
+        (&*KV_EVICTED_KEYS_TOTAL, evicted_keys),
+        (&*KV_EVICTED_BYTES_TOTAL, evicted_bytes),
+        (&*KV_REJECTED_WRITES_TOTAL, rejected),
+    ] {
+        let counter = counter.with_label_values(&[policy]);
+        counter.inc_by(total.saturating_sub(counter.get()));
+    }
+}
+
+/// Bring the KV defragmentation counters up to the store's own totals,
+/// like [`sync_kv_eviction_counters`].
+pub fn sync_kv_defrag_counters(passes: u64, reclaimed_bytes: u64, relocated_bytes: u64) {
+    KV_DEFRAG_PASSES_TOTAL.inc_by(passes.saturating_sub(KV_DEFRAG_PASSES_TOTAL.get()));
+    for (kind, total) in [
+        ("reclaimed", reclaimed_bytes),
+        ("relocated", relocated_bytes),
+    ] {
+        let counter = KV_DEFRAG_BYTES_TOTAL.with_label_values(&[kind]);
+        counter.inc_by(total.saturating_sub(counter.get()));
+    }
+}
+
+/// Set one KV shard's key gauge and bring its lock counters up to the
+/// shard's own totals, like [`sync_kv_eviction_counters`].
+pub fn sync_kv_shard_metrics(shard: usize, keys: usize, reads: u64, writes: u64, contended: u64) {
+    let shard = shard.to_string();
+    KV_SHARD_KEYS.with_label_values(&[&shard]).set(keys as i64);
+    for (mode, total) in [("read", reads), ("write", writes)] {
+        let counter = KV_SHARD_LOCK_ACQUISITIONS_TOTAL.with_label_values(&[&shard, mode]);
+        counter.inc_by(total.saturating_sub(counter.get()));
+    }
+    let counter = KV_SHARD_LOCK_CONTENDED_TOTAL.with_label_values(&[&shard]);
+    counter.inc_by(contended.saturating_sub(counter.get()));
+}
+
+/// Bring one command hook's counters up to its own totals, like
+/// [`sync_kv_eviction_counters`].
+pub fn sync_command_hook_counters(hook: &str, delivered: u64, failed: u64, dropped: u64) {
+    for (outcome, total) in [
+        ("delivered", delivered),
+        ("failed", failed),
+        ("dropped", dropped),
+    ] {
+        let counter = COMMAND_HOOK_RECORDS_TOTAL.with_label_values(&[hook, outcome]);
+        counter.inc_by(total.saturating_sub(counter.get()));
+    }
 }
 
 /// Set the accounted memory (bytes) for one datatype (audit M-018).
@@ -524,6 +869,16 @@ pub fn set_datatype_memory(datatype: &str, bytes: i64) {
         .set(bytes);
 }
 
+/// Set the estimated reads and writes of one hot key.
+pub fn set_hot_key_gauges(key: &str, reads: u64, writes: u64) {
+    KV_HOT_KEY_OPERATIONS
+        .with_label_values(&[key, "read"])
+        .set(reads as i64);
+    KV_HOT_KEY_OPERATIONS
+        .with_label_values(&[key, "write"])
+        .set(writes as i64);
+}
+
 /// Set the live gauges for one stream/room.
 pub fn set_stream_gauges(room: &str, message_count: i64, last_offset: i64, subscribers: i64) {
     STREAM_BUFFER_SIZE
@@ -755,6 +1110,167 @@ pub fn synap_rpc_frame_sizes(in_bytes: usize, out_bytes: usize) {
     }
 }
 
+// ── Data model helpers ────────────────────────────────────────────────────────
+
+/// Operation metrics of a data model family, by command namespace
+fn family_metrics(namespace: &str) -> Option<(&'static IntCounterVec, &'static HistogramVec)> {
+    match namespace {
+        "hash" => Some((&HASH_OPS_TOTAL, &HASH_OP_DURATION)),
+        "list" => Some((&LIST_OPS_TOTAL, &LIST_OP_DURATION)),
+        "set" => Some((&SET_OPS_TOTAL, &SET_OP_DURATION)),
+        "sortedset" => Some((&SORTED_SET_OPS_TOTAL, &SORTED_SET_OP_DURATION)),
+        "transaction" => Some((&TRANSACTION_OPS_TOTAL, &TRANSACTION_OP_DURATION)),
+        "script" | "function" => Some((&SCRIPT_OPS_TOTAL, &SCRIPT_OP_DURATION)),
+        _ => None,
+    }
+}
+
+/// RESP3 and SynapRPC verbs of each data model family, by namespace
+const FAMILY_VERBS: &[(&str, &[&str])] = &[
+    (
+        "hash",
+        &[
+            "HSET",
+            "HGET",
+            "HDEL",
+            "HINCRBY",
+            "HINCRBYFLOAT",
+            "HGETALL",
+            "HMSET",
+            "HMGET",
+            "HLEN",
+            "HEXISTS",
+            "HKEYS",
+            "HVALS",
+            "HSCAN",
+        ],
+    ),
+    (
+        "list",
+        &[
+            "LPUSH",
+            "RPUSH",
+            "LPOP",
+            "RPOP",
+            "LRANGE",
+            "LLEN",
+            "BLPOP",
+            "BRPOP",
+            "BRPOPLPUSH",
+        ],
+    ),
+    (
+        "set",
+        &[
+            "SADD",
+            "SMEMBERS",
+            "SREM",
+            "SISMEMBER",
+            "SCARD",
+            "SPOP",
+            "SRANDMEMBER",
+            "SINTER",
+            "SUNION",
+            "SDIFF",
+            "SINTERSTORE",
+            "SUNIONSTORE",
+            "SDIFFSTORE",
+            "SINTERCARD",
+            "SSCAN",
+        ],
+    ),
+    (
+        "sortedset",
+        &[
+            "ZADD", "ZRANGE", "ZSCORE", "ZCARD", "ZREM", "BZPOPMIN", "BZPOPMAX", "ZSCAN", "ZNOTIFY",
+        ],
+    ),
+    (
+        "transaction",
+        &["MULTI", "EXEC", "DISCARD", "WATCH", "UNWATCH"],
+    ),
+    (
+        "script",
+        &[
+            "EVAL",
+            "EVALSHA",
+            "SCRIPT",
+            "SCRIPT.LOAD",
+            "SCRIPT.EXISTS",
+            "SCRIPT.FLUSH",
+            "SCRIPT.KILL",
+        ],
+    ),
+];
+
+/// Record one `/api/v1/command` command in its data model family's metrics:
+/// `hash.*`, `list.*`, `set.*`, `sortedset.*`, `transaction.*`, `script.*`
+/// and `function.*`. Other commands are ignored. The operation label is the
+/// name after the namespace, except that `function.*` keeps its full name so
+/// it does not collide with `script.*`.
+pub fn record_command_op(command: &str, ok: bool, duration_secs: f64) {
+    let Some((namespace, operation)) = command.split_once('.') else {
+        return;
+    };
+    let Some((ops, duration)) = family_metrics(namespace) else {
+        return;
+    };
+    let operation = if namespace == "function" {
+        command
+    } else {
+        operation
+    };
+    let status = if ok { "success" } else { "error" };
+    ops.with_label_values(&[operation, status]).inc();
+    duration
+        .with_label_values(&[operation])
+        .observe(duration_secs);
+}
+
+/// Record one RESP3 or SynapRPC command in its data model family's metrics,
+/// like [`record_command_op`]. The operation label is the lowercased verb.
+/// Handles are pre-resolved, as for [`record_resp3_command`].
+pub fn record_verb_op(verb: &str, ok: bool, duration_secs: f64) {
+    static HANDLES: std::sync::OnceLock<std::collections::HashMap<&'static str, CmdMetricHandles>> =
+        std::sync::OnceLock::new();
+    let cache = HANDLES.get_or_init(|| {
+        FAMILY_VERBS
+            .iter()
+            .flat_map(|&(namespace, verbs)| {
+                let (ops, duration) =
+                    family_metrics(namespace).expect("every family namespace has metrics");
+                verbs.iter().map(move |&verb| {
+                    let operation = verb.to_ascii_lowercase();
+                    (
+                        verb,
+                        CmdMetricHandles {
+                            ok: ops.with_label_values(&[&operation, "success"]),
+                            err: ops.with_label_values(&[&operation, "error"]),
+                            duration: duration.with_label_values(&[&operation]),
+                        },
+                    )
+                })
+            })
+            .collect()
+    });
+    // SynapRPC accepts verbs in any case
+    let upper;
+    let verb = if verb.bytes().any(|b| b.is_ascii_lowercase()) {
+        upper = verb.to_ascii_uppercase();
+        upper.as_str()
+    } else {
+        verb
+    };
+    if let Some(h) = cache.get(verb) {
+        if ok {
+            h.ok.inc()
+        } else {
+            h.err.inc()
+        }
+        h.duration.observe(duration_secs);
+    }
+}
+
 // ── PerfTimer — drop-based latency recorder ───────────────────────────────────
 
 /// Zero-overhead RAII timer.  On drop it records elapsed time to a
@@ -836,6 +1352,45 @@ mod tests {
         assert!(metrics.contains("synap_queue_operations_total"));
     }
 
+    #[test]
+    fn test_record_data_model_ops() {
+        record_command_op("hash.set", true, 0.001);
+        record_command_op("sortedset.zadd", false, 0.001);
+        record_command_op("function.load", true, 0.001);
+        record_command_op("kv.set", true, 0.001);
+        record_verb_op("LPUSH", true, 0.001);
+        record_verb_op("sinter", true, 0.001);
+        record_verb_op("EXEC", true, 0.001);
+        record_verb_op("SCRIPT.LOAD", true, 0.001);
+        record_verb_op("SET", true, 0.001);
+
+        let out = encode_metrics().unwrap();
+        assert!(out.contains("synap_hash_operations_total{operation=\"set\",status=\"success\"}"));
+        assert!(
+            out.contains("synap_sorted_set_operations_total{operation=\"zadd\",status=\"error\"}")
+        );
+        assert!(out.contains(
+            "synap_script_operations_total{operation=\"function.load\",status=\"success\"}"
+        ));
+        assert!(out.contains(
+            "synap_script_operations_total{operation=\"script.load\",status=\"success\"}"
+        ));
+        assert!(
+            out.contains("synap_list_operations_total{operation=\"lpush\",status=\"success\"}")
+        );
+        assert!(
+            out.contains("synap_set_operations_total{operation=\"sinter\",status=\"success\"}")
+        );
+        assert!(
+            out.contains(
+                "synap_transaction_operations_total{operation=\"exec\",status=\"success\"}"
+            )
+        );
+        assert!(out.contains("synap_list_operation_duration_seconds_bucket{operation=\"lpush\""));
+        // KV commands keep their own metrics
+        assert!(!out.contains("synap_set_operations_total{operation=\"set\""));
+    }
+
     #[test]
     fn test_encode_metrics() {
         // Record some metrics first
@@ -857,12 +1412,22 @@ mod tests {
         record_stream_event("room", "evt");
         record_pubsub_op("publish", "success");
         record_pubsub_message("topic");
+        record_pubsub_rejected(&crate::core::SynapError::PublishRateLimited {
+            topic: "topic".to_string(),
+            limit: 1,
+        });
         record_http_request("GET", "/api/kv", 200, 0.003);
         update_replication_lag("replica-1", 5);
         record_replication_op("write", "success", 1024);
+        record_replication_compression("sent", 1024, 256);
         set_process_metrics(1_000_000, 2_000_000, 12.5);
         set_host_metrics(500, 1000, 0.1, 0.2, 0.3);
         set_datatype_memory("hash", 4096);
+        sync_kv_eviction_counters("allkeys-lru", 3, 150, 1);
+        sync_kv_eviction_counters("allkeys-lru", 5, 250, 1);
+        sync_kv_defrag_counters(2, 4096, 65536);
+        sync_command_hook_counters("sidecar", 10, 1, 2);
+        sync_kv_shard_metrics(3, 120, 900, 40, 7);
         set_stream_gauges("room", 10, 9, 2);
         set_partition_gauges("topic", "0", 100, 99);
         set_consumer_group_members("g", "topic", 3);
@@ -885,7 +1450,21 @@ mod tests {
         let out = encode_metrics().unwrap();
         assert!(out.contains("synap_http_requests_total"));
         assert!(out.contains("synap_resp3_commands_total"));
+        assert!(out.contains("synap_pubsub_rejected_total"));
         assert!(out.contains("synap_process_memory_bytes"));
         assert!(out.contains("synap_datatype_memory_bytes"));
+        assert!(out.contains("synap_kv_evicted_keys_total{policy=\"allkeys-lru\"} 5"));
+        assert!(out.contains("synap_kv_rejected_writes_total"));
+        assert!(out.contains("synap_kv_defrag_bytes_total{kind=\"reclaimed\"}"));
+        assert!(out.contains("synap_kv_shard_keys{shard=\"3\"} 120"));
+        assert!(
+            out.contains("synap_kv_shard_lock_acquisitions_total{mode=\"write\",shard=\"3\"} 40")
+        );
+        assert!(out.contains("synap_kv_shard_lock_contended_total{shard=\"3\"} 7"));
+        assert!(
+            out.contains(
+                "synap_command_hook_records_total{hook=\"sidecar\",outcome=\"dropped\"} 2"
+            )
+        );
     }
 }
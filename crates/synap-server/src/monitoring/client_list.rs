@@ -1,11 +1,37 @@
 //! Client Connection Tracking
 //!
-//! Track active client connections (WebSocket, HTTP long-polling, etc.)
+//! Track active client connections (WebSocket, HTTP long-polling, etc.),
+//! disconnect them (CLIENT KILL), hold commands back (CLIENT PAUSE) and
+//! enforce per-connection limits.
 
-use serde::Serialize;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::SystemTime;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{Notify, RwLock};
+
+/// Per-connection limits; 0 leaves a limit off
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientLimitsConfig {
+    /// HTTP requests one connection may have in flight at once; more are
+    /// answered with 429. Matters for HTTP/2, which multiplexes requests.
+    pub max_in_flight: usize,
+    /// Topics, keys or rooms one WebSocket connection may subscribe to
+    pub max_subscriptions: usize,
+}
+
+/// Which commands a CLIENT PAUSE holds back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PauseMode {
+    /// Every command
+    All,
+    /// Writes only; reads keep being served
+    Write,
+}
 
 /// Client information
 #[derive(Debug, Clone, Serialize)]
@@ -81,9 +107,49 @@ impl ClientInfo {
     }
 }
 
+/// Resolves when the client is killed with [`ClientListManager::kill`].
+/// Connection loops select on [`killed`](Self::killed) and close.
+#[derive(Debug, Clone)]
+pub struct KillSignal(Arc<Notify>);
+
+impl KillSignal {
+    /// Wait until the client is killed
+    pub async fn killed(&self) {
+        self.0.notified().await
+    }
+}
+
+struct TrackedClient {
+    info: ClientInfo,
+    kill: Arc<Notify>,
+}
+
+/// An HTTP request counted against its connection's in-flight limit until
+/// dropped
+pub struct InFlightGuard {
+    in_flight: Arc<Mutex<HashMap<SocketAddr, usize>>>,
+    addr: SocketAddr,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock();
+        if let Some(count) = in_flight.get_mut(&self.addr) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.remove(&self.addr);
+            }
+        }
+    }
+}
+
 /// Client list manager
 pub struct ClientListManager {
-    clients: Arc<RwLock<Vec<ClientInfo>>>,
+    clients: Arc<RwLock<Vec<TrackedClient>>>,
+    limits: ClientLimitsConfig,
+    in_flight: Arc<Mutex<HashMap<SocketAddr, usize>>>,
+    pause: Arc<Mutex<Option<(Instant, PauseMode)>>>,
+    unpaused: Arc<Notify>,
 }
 
 impl Default for ClientListManager {
@@ -97,24 +163,126 @@ impl ClientListManager {
     pub fn new() -> Self {
         Self {
             clients: Arc::new(RwLock::new(Vec::new())),
+            limits: ClientLimitsConfig::default(),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            pause: Arc::new(Mutex::new(None)),
+            unpaused: Arc::new(Notify::new()),
         }
     }
 
-    /// Add a client connection
-    pub async fn add(&self, client: ClientInfo) {
+    /// Enforce per-connection limits, e.g. from `clients`
+    pub fn with_limits(mut self, limits: ClientLimitsConfig) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Per-connection limits
+    pub fn limits(&self) -> &ClientLimitsConfig {
+        &self.limits
+    }
+
+    /// Add a client connection. The connection should close once the
+    /// returned signal fires.
+    pub async fn add(&self, client: ClientInfo) -> KillSignal {
+        let kill = Arc::new(Notify::new());
         let mut clients = self.clients.write().await;
-        clients.push(client);
+        clients.push(TrackedClient {
+            info: client,
+            kill: kill.clone(),
+        });
+        KillSignal(kill)
     }
 
     /// Remove a client connection
     pub async fn remove(&self, id: &str) {
         let mut clients = self.clients.write().await;
-        clients.retain(|c| c.id != id);
+        clients.retain(|c| c.info.id != id);
     }
 
     /// Get all clients
     pub async fn list(&self) -> Vec<ClientInfo> {
-        self.clients.read().await.clone()
+        self.clients
+            .read()
+            .await
+            .iter()
+            .map(|c| c.info.clone())
+            .collect()
+    }
+
+    /// Disconnect the clients matching `id` and/or `addr` (`ip:port`).
+    /// Returns how many were signalled; a client leaves the list once its
+    /// connection has closed.
+    pub async fn kill(&self, id: Option<&str>, addr: Option<&str>) -> usize {
+        if id.is_none() && addr.is_none() {
+            return 0;
+        }
+        let clients = self.clients.read().await;
+        clients
+            .iter()
+            .filter(|c| id.is_none_or(|id| c.info.id == id))
+            .filter(|c| addr.is_none_or(|addr| c.info.addr == addr))
+            .inspect(|c| c.kill.notify_one())
+            .count()
+    }
+
+    /// Hold back commands for `timeout`: all of them, or only writes
+    pub fn pause(&self, timeout: Duration, mode: PauseMode) {
+        *self.pause.lock() = Some((Instant::now() + timeout, mode));
+    }
+
+    /// End a pause early. Returns whether one was active.
+    pub fn unpause(&self) -> bool {
+        let was_paused = self
+            .pause
+            .lock()
+            .take()
+            .is_some_and(|(until, _)| until > Instant::now());
+        self.unpaused.notify_waiters();
+        was_paused
+    }
+
+    /// The running pause, if any: its mode and the time left
+    pub fn paused(&self) -> Option<(PauseMode, Duration)> {
+        let pause = *self.pause.lock();
+        pause.and_then(|(until, mode)| {
+            let left = until.saturating_duration_since(Instant::now());
+            (!left.is_zero()).then_some((mode, left))
+        })
+    }
+
+    /// Wait out a pause that applies to a command, a write or not
+    pub async fn wait_unpaused(&self, write: bool) {
+        loop {
+            // Registered before the check so an unpause in between is not missed
+            let unpaused = self.unpaused.notified();
+            tokio::pin!(unpaused);
+            unpaused.as_mut().enable();
+            let Some((mode, left)) = self.paused() else {
+                return;
+            };
+            if mode == PauseMode::Write && !write {
+                return;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(left) => {}
+                _ = unpaused => {}
+            }
+        }
+    }
+
+    /// Count a request against its connection's in-flight limit. `None` when
+    /// the connection already has `max_in_flight` requests running.
+    pub fn begin_request(&self, addr: SocketAddr) -> Option<InFlightGuard> {
+        let mut in_flight = self.in_flight.lock();
+        let count = in_flight.entry(addr).or_default();
+        if self.limits.max_in_flight > 0 && *count >= self.limits.max_in_flight {
+            return None;
+        }
+        *count += 1;
+        Some(InFlightGuard {
+            in_flight: self.in_flight.clone(),
+            addr,
+        })
     }
 
     /// Get client count
@@ -132,6 +300,10 @@ impl Clone for ClientListManager {
     fn clone(&self) -> Self {
         Self {
             clients: self.clients.clone(),
+            limits: self.limits.clone(),
+            in_flight: self.in_flight.clone(),
+            pause: self.pause.clone(),
+            unpaused: self.unpaused.clone(),
         }
     }
 }
@@ -213,6 +385,79 @@ mod tests {
         assert_eq!(clients[2].id, "client2");
     }
 
+    #[tokio::test]
+    async fn test_client_kill_by_id_and_addr() {
+        let manager = ClientListManager::new();
+        let a = manager
+            .add(ClientInfo::new(
+                "a".to_string(),
+                "10.0.0.1:1000".to_string(),
+                SystemTime::now(),
+            ))
+            .await;
+        manager
+            .add(ClientInfo::new(
+                "b".to_string(),
+                "10.0.0.2:2000".to_string(),
+                SystemTime::now(),
+            ))
+            .await;
+
+        assert_eq!(manager.kill(None, None).await, 0);
+        assert_eq!(manager.kill(Some("a"), Some("10.0.0.2:2000")).await, 0);
+        assert_eq!(manager.kill(None, Some("10.0.0.1:1000")).await, 1);
+        // The signal is kept until the connection waits for it
+        tokio::time::timeout(Duration::from_secs(1), a.killed())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_pause_holds_writes_only_in_write_mode() {
+        let manager = ClientListManager::new();
+        manager.pause(Duration::from_secs(60), PauseMode::Write);
+        assert_eq!(manager.paused().unwrap().0, PauseMode::Write);
+
+        // Reads pass
+        tokio::time::timeout(Duration::from_millis(100), manager.wait_unpaused(false))
+            .await
+            .unwrap();
+        // Writes wait until unpaused
+        let held =
+            tokio::time::timeout(Duration::from_millis(50), manager.wait_unpaused(true)).await;
+        assert!(held.is_err());
+
+        let waiter = {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.wait_unpaused(true).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(manager.unpause());
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(manager.paused().is_none());
+    }
+
+    #[test]
+    fn test_in_flight_limit_per_connection() {
+        let manager = ClientListManager::new().with_limits(ClientLimitsConfig {
+            max_in_flight: 2,
+            max_subscriptions: 0,
+        });
+        let conn: SocketAddr = "10.0.0.1:1000".parse().unwrap();
+        let other: SocketAddr = "10.0.0.1:1001".parse().unwrap();
+
+        let first = manager.begin_request(conn).unwrap();
+        let _second = manager.begin_request(conn).unwrap();
+        assert!(manager.begin_request(conn).is_none());
+        assert!(manager.begin_request(other).is_some());
+
+        drop(first);
+        assert!(manager.begin_request(conn).is_some());
+    }
+
     #[tokio::test]
     async fn test_client_info_new() {
         let connected_at = SystemTime::now();
@@ -0,0 +1,463 @@
+//! Command hooks for compliance and SIEM plugins
+//!
+//! Every mutating command — over `/api/v1/command`, REST writes, RESP3,
+//! SynapRPC and embedded mode — is handed to the registered hooks as a [`CommandRecord`] once
+//! it has run. A hook is either a [`CommandHook`] registered at startup
+//! (embedded servers, custom binaries) or the built-in Unix socket sidecar,
+//! which receives one JSON line per record (`command_hooks.sidecar_socket`).
+//!
+//! Each hook has its own bounded queue and worker task. Emitting never waits:
+//! when a hook is `queue_capacity` records behind, further records for it are
+//! dropped and counted, so a slow plugin cannot stall requests.
+
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Records queued per hook unless configured otherwise
+const DEFAULT_QUEUE_CAPACITY: usize = 10_000;
+
+/// Command hooks configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandHooksConfig {
+    /// Stream each record as a JSON line to the sidecar listening on this
+    /// Unix socket, reconnecting as needed
+    pub sidecar_socket: Option<std::path::PathBuf>,
+    /// Records queued per hook before new ones are dropped
+    pub queue_capacity: usize,
+}
+
+impl Default for CommandHooksConfig {
+    fn default() -> Self {
+        Self {
+            sidecar_socket: None,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+        }
+    }
+}
+
+/// Interface a command arrived on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandProtocol {
+    /// `POST /api/v1/command`, or a REST write route
+    Http,
+    /// `GET /api/v1/command/ws`
+    #[serde(rename = "websocket")]
+    WebSocket,
+    /// RESP3 listener
+    Resp3,
+    /// SynapRPC listener
+    SynapRpc,
+    /// [`Embedded::send_command`](crate::Embedded::send_command)
+    Embedded,
+}
+
+/// One mutating command, after it ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRecord {
+    /// When the command finished
+    pub timestamp: DateTime<Utc>,
+    /// Interface it arrived on
+    pub protocol: CommandProtocol,
+    /// Command name (`kv.set`, `SET`) or REST route (`POST /kv/set`)
+    pub command: String,
+    /// What it touched: ACL resources (`kv:user:1`) for `/api/v1/command`,
+    /// the request path for REST, the first argument for RESP3 and SynapRPC
+    pub resource: Option<String>,
+    /// Authenticated user, if any
+    pub user: Option<String>,
+    /// API key ID, if the caller used one
+    pub api_key_id: Option<String>,
+    /// Client IP, or `conn:<id>` for SynapRPC sessions
+    pub client: String,
+    /// Whether the command succeeded
+    pub success: bool,
+    /// Error returned to the client, on failure
+    pub error: Option<String>,
+    /// Execution time in microseconds
+    pub duration_us: u64,
+}
+
+impl CommandRecord {
+    /// A record for `command` from `client`, finished now after `duration`
+    pub fn new(
+        protocol: CommandProtocol,
+        command: impl Into<String>,
+        client: impl Into<String>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            protocol,
+            command: command.into(),
+            resource: None,
+            user: None,
+            api_key_id: None,
+            client: client.into(),
+            success: true,
+            error: None,
+            duration_us: duration.as_micros() as u64,
+        }
+    }
+}
+
+/// A plugin receiving command records
+///
+/// Records arrive in command order on the hook's own worker task, so
+/// `on_command` may do I/O without slowing requests. An error is counted
+/// as a failed delivery; the record is not retried.
+pub trait CommandHook: Send + Sync + 'static {
+    /// Name used in logs and metrics, unique among the registered hooks
+    fn name(&self) -> &str;
+
+    /// Handle one record
+    fn on_command<'a>(&'a self, record: &'a CommandRecord) -> BoxFuture<'a, std::io::Result<()>>;
+}
+
+/// True for RESP3 and SynapRPC commands that change data, matched
+/// case-insensitively
+pub fn is_write_command(cmd: &str) -> bool {
+    let mut buf = [0u8; 32];
+    let Some(upper) = buf.get_mut(..cmd.len()) else {
+        return false;
+    };
+    upper.copy_from_slice(cmd.as_bytes());
+    upper.make_ascii_uppercase();
+    matches!(
+        &*upper,
+        // Strings and keys
+        b"SET" | b"DEL" | b"EXPIRE" | b"PERSIST" | b"INCR" | b"INCRBY" | b"DECR" | b"DECRBY"
+            | b"MSET" | b"MSETNX" | b"APPEND" | b"SETRANGE" | b"SETBIT" | b"GETSET"
+            | b"FLUSHALL" | b"FLUSHDB"
+            // Collections
+            | b"HSET" | b"HDEL" | b"HINCRBY" | b"HINCRBYFLOAT" | b"HMSET"
+            | b"LPUSH" | b"RPUSH" | b"LPOP" | b"RPOP" | b"BLPOP" | b"BRPOP" | b"BRPOPLPUSH"
+            | b"SADD" | b"SREM" | b"ZADD" | b"ZREM" | b"BZPOPMIN" | b"BZPOPMAX"
+            | b"PFADD" | b"PFMERGE" | b"GEOADD"
+            // Queues, streams and pub/sub
+            | b"QCREATE" | b"QDELETE" | b"QPUBLISH" | b"QCONSUME" | b"QACK" | b"QNACK"
+            | b"QPURGE" | b"SCREATE" | b"SDELETE" | b"SGETORCREATE" | b"SPUBLISH"
+            | b"XADD" | b"XDEL" | b"XACK" | b"PUBLISH"
+            // Scripts and transactions
+            | b"EVAL" | b"EVALSHA" | b"EXEC" | b"SCRIPT.LOAD" | b"SCRIPT.FLUSH"
+            | b"SCRIPT.KILL"
+    )
+}
+
+/// Delivery counters of one hook
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandHookStats {
+    /// Hook name
+    pub name: String,
+    /// Records handled successfully
+    pub delivered: u64,
+    /// Records the hook returned an error for
+    pub failed: u64,
+    /// Records dropped because the hook's queue was full
+    pub dropped: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    delivered: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+    /// Set while the queue is full, so the overflow is logged once
+    lagging: AtomicBool,
+}
+
+struct HookQueue {
+    name: String,
+    sender: mpsc::Sender<Arc<CommandRecord>>,
+    counters: Arc<Counters>,
+}
+
+/// Registered command hooks
+pub struct CommandHooks {
+    queue_capacity: usize,
+    hooks: RwLock<Vec<HookQueue>>,
+    active: AtomicBool,
+}
+
+impl CommandHooks {
+    /// No hooks yet; each one registered later queues up to
+    /// `queue_capacity` records
+    pub fn new(queue_capacity: usize) -> Self {
+        Self {
+            queue_capacity: queue_capacity.max(1),
+            hooks: RwLock::new(Vec::new()),
+            active: AtomicBool::new(false),
+        }
+    }
+
+    /// Hooks sized and set up from config: the sidecar, if configured. Must
+    /// be called inside a Tokio runtime.
+    pub fn from_config(config: &CommandHooksConfig) -> Self {
+        let hooks = Self::new(config.queue_capacity);
+        if let Some(path) = &config.sidecar_socket {
+            #[cfg(unix)]
+            hooks.register(Arc::new(UnixSocketHook::new(path)));
+            #[cfg(not(unix))]
+            warn!(
+                "command_hooks.sidecar_socket ({}) needs Unix sockets; ignored",
+                path.display()
+            );
+        }
+        hooks
+    }
+
+    /// True once a hook is registered. Callers check this before building
+    /// a record.
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Register `hook` and start its worker. Must be called inside a Tokio
+    /// runtime.
+    pub fn register(&self, hook: Arc<dyn CommandHook>) {
+        let (sender, mut receiver) = mpsc::channel::<Arc<CommandRecord>>(self.queue_capacity);
+        let counters = Arc::new(Counters::default());
+        let name = hook.name().to_string();
+
+        let worker_counters = counters.clone();
+        tokio::spawn(async move {
+            while let Some(record) = receiver.recv().await {
+                let counter = match hook.on_command(&record).await {
+                    Ok(()) => &worker_counters.delivered,
+                    Err(_) => &worker_counters.failed,
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        self.hooks.write().push(HookQueue {
+            name,
+            sender,
+            counters,
+        });
+        self.active.store(true, Ordering::Relaxed);
+    }
+
+    /// Queue `record` for every hook, dropping it for hooks whose queue is
+    /// full
+    pub fn emit(&self, record: CommandRecord) {
+        let record = Arc::new(record);
+        for hook in self.hooks.read().iter() {
+            match hook.sender.try_send(record.clone()) {
+                Ok(()) => hook.counters.lagging.store(false, Ordering::Relaxed),
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    hook.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                    if !hook.counters.lagging.swap(true, Ordering::Relaxed) {
+                        warn!(
+                            "Command hook '{}' is {} records behind, dropping records",
+                            hook.name, self.queue_capacity
+                        );
+                    }
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    hook.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Delivery counters of every hook, in registration order
+    pub fn stats(&self) -> Vec<CommandHookStats> {
+        self.hooks
+            .read()
+            .iter()
+            .map(|hook| CommandHookStats {
+                name: hook.name.clone(),
+                delivered: hook.counters.delivered.load(Ordering::Relaxed),
+                failed: hook.counters.failed.load(Ordering::Relaxed),
+                dropped: hook.counters.dropped.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+}
+
+impl Default for CommandHooks {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUEUE_CAPACITY)
+    }
+}
+
+/// Sidecar hook: writes each record as a JSON line to a Unix socket
+///
+/// Connects on the first record and again after any write error; records
+/// that arrive while the sidecar is unreachable fail.
+#[cfg(unix)]
+pub struct UnixSocketHook {
+    path: std::path::PathBuf,
+    connection: tokio::sync::Mutex<Option<tokio::net::UnixStream>>,
+}
+
+#[cfg(unix)]
+impl UnixSocketHook {
+    /// A hook for the sidecar listening on `path`
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            connection: tokio::sync::Mutex::new(None),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl CommandHook for UnixSocketHook {
+    fn name(&self) -> &str {
+        "sidecar"
+    }
+
+    fn on_command<'a>(&'a self, record: &'a CommandRecord) -> BoxFuture<'a, std::io::Result<()>> {
+        use tokio::io::AsyncWriteExt;
+
+        Box::pin(async move {
+            let mut line = serde_json::to_vec(record)?;
+            line.push(b'\n');
+
+            let mut connection = self.connection.lock().await;
+            let stream = match connection.as_mut() {
+                Some(stream) => stream,
+                None => connection.insert(tokio::net::UnixStream::connect(&self.path).await?),
+            };
+            let written = stream.write_all(&line).await;
+            if written.is_err() {
+                *connection = None;
+            }
+            written
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// Records commands, optionally waiting until released
+    struct Recorder {
+        commands: Mutex<Vec<String>>,
+        gate: tokio::sync::Semaphore,
+    }
+
+    impl CommandHook for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        fn on_command<'a>(
+            &'a self,
+            record: &'a CommandRecord,
+        ) -> BoxFuture<'a, std::io::Result<()>> {
+            Box::pin(async move {
+                self.gate.acquire().await.expect("gate open").forget();
+                self.commands.lock().push(record.command.clone());
+                Ok(())
+            })
+        }
+    }
+
+    fn record(command: &str) -> CommandRecord {
+        CommandRecord::new(CommandProtocol::Http, command, "127.0.0.1", Duration::ZERO)
+    }
+
+    async fn wait_for(hooks: &CommandHooks, delivered: u64) {
+        for _ in 0..100 {
+            if hooks.stats()[0].delivered >= delivered {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("hook did not receive {delivered} records");
+    }
+
+    #[tokio::test]
+    async fn test_records_reach_hooks_in_order() {
+        let hooks = CommandHooks::new(16);
+        assert!(!hooks.is_active());
+        let recorder = Arc::new(Recorder {
+            commands: Mutex::new(Vec::new()),
+            gate: tokio::sync::Semaphore::new(tokio::sync::Semaphore::MAX_PERMITS),
+        });
+        hooks.register(recorder.clone());
+        assert!(hooks.is_active());
+
+        for command in ["kv.set", "kv.del", "hash.set"] {
+            hooks.emit(record(command));
+        }
+        wait_for(&hooks, 3).await;
+        assert_eq!(*recorder.commands.lock(), ["kv.set", "kv.del", "hash.set"]);
+    }
+
+    #[tokio::test]
+    async fn test_slow_hook_drops_instead_of_blocking() {
+        let hooks = CommandHooks::new(4);
+        let recorder = Arc::new(Recorder {
+            commands: Mutex::new(Vec::new()),
+            gate: tokio::sync::Semaphore::new(0),
+        });
+        hooks.register(recorder.clone());
+
+        // The stalled worker holds one record and the queue four more
+        for i in 0..20 {
+            hooks.emit(record(&format!("kv.set:{i}")));
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let stats = &hooks.stats()[0];
+        assert_eq!(stats.name, "recorder");
+        assert!((15..=16).contains(&stats.dropped), "{stats:?}");
+
+        recorder.gate.add_permits(20);
+        wait_for(&hooks, 20 - stats.dropped).await;
+        assert_eq!(recorder.commands.lock()[0], "kv.set:0");
+    }
+
+    #[test]
+    fn test_write_commands() {
+        for cmd in ["SET", "hset", "Zadd", "QPUBLISH", "script.flush"] {
+            assert!(is_write_command(cmd), "{cmd}");
+        }
+        for cmd in [
+            "GET",
+            "hgetall",
+            "ZSCAN",
+            "PING",
+            "A_VERY_LONG_UNKNOWN_COMMAND_NAME_X",
+        ] {
+            assert!(!is_write_command(cmd), "{cmd}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sidecar_receives_json_lines() {
+        use tokio::io::AsyncBufReadExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hooks.sock");
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+        let hooks = CommandHooks::new(16);
+        hooks.register(Arc::new(UnixSocketHook::new(&path)));
+        let mut sent = record("kv.set");
+        sent.resource = Some("kv:user:1".to_string());
+        hooks.emit(sent);
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut lines = tokio::io::BufReader::new(stream).lines();
+        let line = lines.next_line().await.unwrap().unwrap();
+        let received: CommandRecord = serde_json::from_str(&line).unwrap();
+        assert_eq!(received.command, "kv.set");
+        assert_eq!(received.protocol, CommandProtocol::Http);
+        assert_eq!(received.resource.as_deref(), Some("kv:user:1"));
+    }
+}
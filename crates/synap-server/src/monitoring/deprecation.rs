@@ -0,0 +1,196 @@
+//! Deprecated API Surfaces
+//!
+//! Registry of HTTP endpoints, commands and request fields that still work but
+//! have a replacement, plus per-client usage counts so operators can see who
+//! has to migrate before a surface is removed. Requests are attributed to the
+//! API key (or user) that made them, falling back to the client IP.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A surface that still works but should no longer be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    /// `"METHOD /path"` for endpoints, the command name for commands,
+    /// `"METHOD /path field"` for request fields
+    pub surface: &'static str,
+    /// What to use instead
+    pub replacement: &'static str,
+    /// Planned removal as an HTTP-date, when one is set
+    pub sunset: Option<&'static str>,
+}
+
+/// Every deprecated surface
+pub const DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        surface: "POST /pubsub/subscribe",
+        replacement: "GET /pubsub/ws (WebSocket)",
+        sunset: None,
+    },
+    Deprecation {
+        surface: "kv.keys",
+        replacement: "kv.scan",
+        sunset: None,
+    },
+    Deprecation {
+        surface: "POST /kv/set ttl",
+        replacement: "expiry",
+        sunset: None,
+    },
+];
+
+/// Look up a deprecated surface by name
+pub fn lookup(surface: &str) -> Option<&'static Deprecation> {
+    DEPRECATIONS.iter().find(|d| d.surface == surface)
+}
+
+/// Look up a deprecated endpoint by method and route pattern
+/// (e.g. `"POST"`, `"/pubsub/subscribe"`)
+pub fn lookup_route(method: &str, route: &str) -> Option<&'static Deprecation> {
+    DEPRECATIONS.iter().find(|d| {
+        d.surface
+            .split_once(' ')
+            .is_some_and(|(m, path)| m == method && path == route)
+    })
+}
+
+/// Most clients tracked per surface; requests from further clients still
+/// count towards the surface total.
+const MAX_CLIENTS_PER_SURFACE: usize = 1024;
+
+/// Usage of one surface by one client
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientUsage {
+    /// `api_key:<id>`, `user:<name>` or `ip:<address>`
+    pub client: String,
+    pub count: u64,
+    /// Unix timestamps (seconds)
+    pub first_seen: u64,
+    pub last_seen: u64,
+}
+
+/// One row of the deprecation report
+#[derive(Debug, Clone, Serialize)]
+pub struct DeprecationUsage {
+    #[serde(flatten)]
+    pub deprecation: Deprecation,
+    pub total: u64,
+    /// Busiest clients first
+    pub clients: Vec<ClientUsage>,
+}
+
+#[derive(Default)]
+struct SurfaceUsage {
+    total: u64,
+    clients: HashMap<String, ClientUsage>,
+}
+
+/// Per-client usage counters of deprecated surfaces
+#[derive(Default)]
+pub struct DeprecationTracker {
+    usage: Mutex<HashMap<&'static str, SurfaceUsage>>,
+}
+
+impl DeprecationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one use of `deprecation` by `client`
+    pub fn record(&self, deprecation: &'static Deprecation, client: &str) {
+        crate::metrics::record_deprecated_request(deprecation.surface);
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut usage = self.usage.lock();
+        let surface = usage.entry(deprecation.surface).or_default();
+        surface.total += 1;
+
+        if let Some(entry) = surface.clients.get_mut(client) {
+            entry.count += 1;
+            entry.last_seen = now;
+        } else if surface.clients.len() < MAX_CLIENTS_PER_SURFACE {
+            surface.clients.insert(
+                client.to_string(),
+                ClientUsage {
+                    client: client.to_string(),
+                    count: 1,
+                    first_seen: now,
+                    last_seen: now,
+                },
+            );
+        }
+    }
+
+    /// Usage of every registered surface, including unused ones
+    pub fn report(&self) -> Vec<DeprecationUsage> {
+        let usage = self.usage.lock();
+        DEPRECATIONS
+            .iter()
+            .map(|deprecation| {
+                let (total, mut clients) = match usage.get(deprecation.surface) {
+                    Some(s) => (s.total, s.clients.values().cloned().collect::<Vec<_>>()),
+                    None => (0, Vec::new()),
+                };
+                clients.sort_by(|a, b| b.count.cmp(&a.count).then(a.client.cmp(&b.client)));
+                DeprecationUsage {
+                    deprecation: *deprecation,
+                    total,
+                    clients,
+                }
+            })
+            .collect()
+    }
+
+    /// Forget all recorded usage
+    pub fn reset(&self) {
+        self.usage.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_route_and_surface() {
+        let subscribe = lookup_route("POST", "/pubsub/subscribe").unwrap();
+        assert_eq!(subscribe.surface, "POST /pubsub/subscribe");
+        assert!(lookup_route("GET", "/pubsub/subscribe").is_none());
+        assert!(lookup_route("POST", "/kv/set").is_none());
+        assert_eq!(lookup("kv.keys").unwrap().replacement, "kv.scan");
+        assert!(lookup("kv.scan").is_none());
+    }
+
+    #[test]
+    fn test_tracker_counts_per_client() {
+        let tracker = DeprecationTracker::new();
+        let keys = lookup("kv.keys").unwrap();
+        tracker.record(keys, "api_key:a");
+        tracker.record(keys, "api_key:b");
+        tracker.record(keys, "api_key:b");
+
+        let report = tracker.report();
+        assert_eq!(report.len(), DEPRECATIONS.len());
+        let row = report
+            .iter()
+            .find(|r| r.deprecation.surface == "kv.keys")
+            .unwrap();
+        assert_eq!(row.total, 3);
+        assert_eq!(row.clients[0].client, "api_key:b");
+        assert_eq!(row.clients[0].count, 2);
+        assert_eq!(row.clients[1].count, 1);
+        assert!(
+            report
+                .iter()
+                .filter(|r| r.deprecation.surface != "kv.keys")
+                .all(|r| r.total == 0)
+        );
+
+        tracker.reset();
+        assert!(tracker.report().iter().all(|r| r.total == 0));
+    }
+}
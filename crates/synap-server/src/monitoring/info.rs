@@ -202,6 +202,8 @@ pub struct StatsInfo {
     pub keyspace_hits: u64,
     #[serde(rename = "keyspace_misses")]
     pub keyspace_misses: u64,
+    #[serde(rename = "evicted_keys")]
+    pub evicted_keys: u64,
     #[serde(rename = "pubsub_channels")]
     pub pubsub_channels: usize,
     #[serde(rename = "pubsub_patterns")]
@@ -228,6 +230,7 @@ impl StatsInfo {
             total_connections_received: 0, // Would need connection counter
             keyspace_hits: kv_stats.hits,
             keyspace_misses: kv_stats.misses,
+            evicted_keys: kv_stats.evicted_keys,
             pubsub_channels: 0, // Would need pubsub stats
             pubsub_patterns: 0,
         }
@@ -253,6 +256,9 @@ pub struct ReplicationInfo {
     /// Replica-only: operations this replica is behind the master.
     #[serde(rename = "slave_repl_lag", skip_serializing_if = "Option::is_none")]
     pub slave_repl_lag: Option<u64>,
+    /// Bytes saved on the wire by replication link compression.
+    #[serde(rename = "repl_compression_saved_bytes")]
+    pub repl_compression_saved_bytes: u64,
 }
 
 impl ReplicationInfo {
@@ -269,6 +275,7 @@ impl ReplicationInfo {
             Some(ReplicationHandle::Master(master)) => {
                 let slaves = master.list_replicas().len() as u32;
                 let offset = master.replication_offset();
+                let saved = master.stats().compression_saved_bytes;
                 Self {
                     role: "master".to_string(),
                     connected_slaves: slaves,
@@ -277,6 +284,7 @@ impl ReplicationInfo {
                     repl_backlog_size: offset as usize,
                     master_link_status: None,
                     slave_repl_lag: None,
+                    repl_compression_saved_bytes: saved,
                 }
             }
             Some(ReplicationHandle::Replica(replica)) => {
@@ -291,6 +299,7 @@ impl ReplicationInfo {
                         if stats.connected { "up" } else { "down" }.to_string(),
                     ),
                     slave_repl_lag: Some(stats.lag_operations),
+                    repl_compression_saved_bytes: stats.compression_saved_bytes,
                 }
             }
             None => Self {
@@ -301,15 +310,27 @@ impl ReplicationInfo {
                 repl_backlog_size: 0,
                 master_link_status: None,
                 slave_repl_lag: None,
+                repl_compression_saved_bytes: 0,
             },
         }
     }
 }
 
-/// Keyspace information
-#[derive(Debug, Serialize)]
+/// Keyspace information: `db0` and every other database holding keys
+#[derive(Debug)]
 pub struct KeyspaceInfo {
-    pub db0: String,
+    pub databases: Vec<(u32, String)>,
+}
+
+impl Serialize for KeyspaceInfo {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.databases.len()))?;
+        for (db, line) in &self.databases {
+            map.serialize_entry(&format!("db{}", db), line)?;
+        }
+        map.end()
+    }
 }
 
 impl KeyspaceInfo {
@@ -321,26 +342,56 @@ impl KeyspaceInfo {
         set_store: Arc<SetStore>,
         sorted_set_store: Arc<SortedSetStore>,
     ) -> Self {
-        let kv_stats = kv_store.stats().await;
-        let hash_stats = hash_store.stats();
-        let list_stats = list_store.stats();
-        let set_stats = set_store.stats();
-        let sorted_set_stats = sorted_set_store.stats();
-
-        // Format: keys=total,expires=expired_keys,avg_ttl=avg_ttl
-        let db0 = format!(
-            "keys={},expires=0,avg_ttl=0",
-            kv_stats.total_keys.max(0) as usize
-                + hash_stats.total_hashes
-                + list_stats.total_lists
-                + set_stats.total_sets
-                + sorted_set_stats.total_keys
-        );
+        let keys = count_keys(
+            &kv_store,
+            &hash_store,
+            &list_store,
+            &set_store,
+            &sorted_set_store,
+        )
+        .await;
+        Self {
+            databases: vec![(0, keyspace_line(keys))],
+        }
+    }
 
-        Self { db0 }
+    /// Add databases 1 and up that hold keys
+    pub async fn add_databases(&mut self, databases: &crate::databases::Databases) {
+        for (db, database) in databases.iter() {
+            let keys = count_keys(
+                &database.kv_store,
+                &database.hash_store,
+                &database.list_store,
+                &database.set_store,
+                &database.sorted_set_store,
+            )
+            .await;
+            if keys > 0 {
+                self.databases.push((db, keyspace_line(keys)));
+            }
+        }
     }
 }
 
+async fn count_keys(
+    kv_store: &KVStore,
+    hash_store: &HashStore,
+    list_store: &ListStore,
+    set_store: &SetStore,
+    sorted_set_store: &SortedSetStore,
+) -> usize {
+    kv_store.stats().await.total_keys.max(0) as usize
+        + hash_store.stats().total_hashes
+        + list_store.stats().total_lists
+        + set_store.stats().total_sets
+        + sorted_set_store.stats().total_keys
+}
+
+// Format: keys=total,expires=expired_keys,avg_ttl=avg_ttl
+fn keyspace_line(keys: usize) -> String {
+    format!("keys={},expires=0,avg_ttl=0", keys)
+}
+
 /// Format bytes to human-readable format
 fn format_bytes(bytes: usize) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
@@ -396,7 +447,17 @@ mod replication_info_tests {
         };
         let kv = Arc::new(KVStore::new(KVConfig::default()));
         let stream = Arc::new(StreamManager::new(StreamConfig::default()));
-        let master = Arc::new(MasterNode::new(config, kv, Some(stream)).await.unwrap());
+        let master = Arc::new(
+            MasterNode::new(
+                config,
+                crate::persistence::StoreArcs {
+                    stream_manager: Some(stream),
+                    ..crate::persistence::StoreArcs::kv_only(kv)
+                },
+            )
+            .await
+            .unwrap(),
+        );
 
         let handle = ReplicationHandle::Master(master);
         let info = ReplicationInfo::collect(Some(&handle)).await;
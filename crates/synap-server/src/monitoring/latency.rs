@@ -0,0 +1,236 @@
+//! Latency Monitoring
+//!
+//! Keeps a short history of latency spikes per event, like Redis' LATENCY
+//! monitor: one event per command class (`command.kv`, `command.queue`, ...)
+//! and one per background job (`snapshot`, `snapshot.incremental`). Only
+//! spikes at or above the threshold are kept, so a slow burst of commands can
+//! be lined up with the snapshot that ran at the same second.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+/// Spikes kept per event (Redis keeps 160)
+pub const DEFAULT_HISTORY_LEN: usize = 160;
+
+/// Latency monitor configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyMonitorConfig {
+    /// Record events that take at least this many milliseconds; 0 turns the
+    /// monitor off
+    pub threshold_ms: u64,
+    /// Spikes kept per event, oldest dropped first
+    pub history_len: usize,
+}
+
+impl Default for LatencyMonitorConfig {
+    fn default() -> Self {
+        Self {
+            threshold_ms: 100,
+            history_len: DEFAULT_HISTORY_LEN,
+        }
+    }
+}
+
+/// One recorded spike. Spikes of an event within the same second are merged,
+/// keeping the slowest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LatencySample {
+    /// Unix time in seconds
+    pub timestamp: u64,
+    pub latency_ms: u64,
+}
+
+/// Last spike of an event, as reported by LATENCY LATEST
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LatencyLatest {
+    pub event: String,
+    pub timestamp: u64,
+    pub latency_ms: u64,
+    /// Slowest spike since the event was last reset
+    pub max_latency_ms: u64,
+}
+
+#[derive(Default)]
+struct EventHistory {
+    samples: VecDeque<LatencySample>,
+    max_latency_ms: u64,
+}
+
+/// Latency spike history per event
+pub struct LatencyMonitor {
+    config: LatencyMonitorConfig,
+    events: Mutex<BTreeMap<String, EventHistory>>,
+}
+
+impl Default for LatencyMonitor {
+    fn default() -> Self {
+        Self::new(LatencyMonitorConfig::default())
+    }
+}
+
+impl LatencyMonitor {
+    /// Create a latency monitor
+    pub fn new(config: LatencyMonitorConfig) -> Self {
+        Self {
+            config,
+            events: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Event recorded for a `/api/v1/command` command: `command.` followed by
+    /// its namespace, e.g. `command.kv` for `kv.set`
+    pub fn command_event(command: &str) -> String {
+        let class = command.split_once('.').map_or(command, |(ns, _)| ns);
+        format!("command.{class}")
+    }
+
+    /// Record `event` if it took at least the threshold
+    pub fn record(&self, event: &str, duration: Duration) {
+        let latency_ms = duration.as_millis() as u64;
+        if self.config.threshold_ms == 0 || latency_ms < self.config.threshold_ms {
+            return;
+        }
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut events = self.events.lock();
+        let history = match events.get_mut(event) {
+            Some(history) => history,
+            None => events.entry(event.to_string()).or_default(),
+        };
+        history.max_latency_ms = history.max_latency_ms.max(latency_ms);
+        match history.samples.back_mut() {
+            Some(last) if last.timestamp == timestamp => {
+                last.latency_ms = last.latency_ms.max(latency_ms);
+            }
+            _ => {
+                history.samples.push_back(LatencySample {
+                    timestamp,
+                    latency_ms,
+                });
+                if history.samples.len() > self.config.history_len.max(1) {
+                    history.samples.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Last spike of every event with one, by event name
+    pub fn latest(&self) -> Vec<LatencyLatest> {
+        self.events
+            .lock()
+            .iter()
+            .filter_map(|(event, history)| {
+                history.samples.back().map(|last| LatencyLatest {
+                    event: event.clone(),
+                    timestamp: last.timestamp,
+                    latency_ms: last.latency_ms,
+                    max_latency_ms: history.max_latency_ms,
+                })
+            })
+            .collect()
+    }
+
+    /// Spikes of `event`, oldest first; empty for an unknown event
+    pub fn history(&self, event: &str) -> Vec<LatencySample> {
+        self.events
+            .lock()
+            .get(event)
+            .map(|history| history.samples.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Drop the history of `events`, or of every event when empty. Returns
+    /// how many events had one.
+    pub fn reset(&self, events: &[String]) -> usize {
+        let mut all = self.events.lock();
+        if events.is_empty() {
+            let count = all.len();
+            all.clear();
+            return count;
+        }
+        events
+            .iter()
+            .filter(|event| all.remove(event.as_str()).is_some())
+            .count()
+    }
+
+    /// Get configuration
+    pub fn config(&self) -> &LatencyMonitorConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(threshold_ms: u64, history_len: usize) -> LatencyMonitor {
+        LatencyMonitor::new(LatencyMonitorConfig {
+            threshold_ms,
+            history_len,
+        })
+    }
+
+    #[test]
+    fn test_command_event_uses_namespace() {
+        assert_eq!(LatencyMonitor::command_event("kv.set"), "command.kv");
+        assert_eq!(
+            LatencyMonitor::command_event("queue.consume"),
+            "command.queue"
+        );
+        assert_eq!(LatencyMonitor::command_event("info"), "command.info");
+    }
+
+    #[test]
+    fn test_record_below_threshold_is_ignored() {
+        let latency = monitor(10, 4);
+        latency.record("command.kv", Duration::from_millis(9));
+        assert!(latency.latest().is_empty());
+
+        let disabled = monitor(0, 4);
+        disabled.record("command.kv", Duration::from_secs(5));
+        assert!(disabled.latest().is_empty());
+    }
+
+    #[test]
+    fn test_spikes_in_one_second_merge() {
+        let latency = monitor(10, 4);
+        latency.record("snapshot", Duration::from_millis(20));
+        latency.record("snapshot", Duration::from_millis(50));
+        latency.record("snapshot", Duration::from_millis(30));
+
+        let history = latency.history("snapshot");
+        // Three spikes land in one or, at a second boundary, two samples.
+        assert!(history.len() <= 2);
+        assert_eq!(history.iter().map(|s| s.latency_ms).max(), Some(50));
+
+        let latest = latency.latest();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].event, "snapshot");
+        assert_eq!(latest[0].max_latency_ms, 50);
+    }
+
+    #[test]
+    fn test_reset_named_and_all_events() {
+        let latency = monitor(1, 4);
+        for event in ["command.kv", "command.hash", "snapshot"] {
+            latency.record(event, Duration::from_millis(5));
+        }
+
+        assert_eq!(
+            latency.reset(&["snapshot".to_string(), "missing".to_string()]),
+            1
+        );
+        assert!(latency.history("snapshot").is_empty());
+        assert_eq!(latency.latest().len(), 2);
+
+        assert_eq!(latency.reset(&[]), 2);
+        assert!(latency.latest().is_empty());
+    }
+}
@@ -10,12 +10,20 @@ pub struct MemoryUsage {
     pub key: String,
     pub bytes: usize,
     pub human: String,
+    /// Datatype, as reported by TYPE
+    #[serde(rename = "type")]
+    pub key_type: &'static str,
+    /// Internal representation, e.g. `packed` or `hashset` for a set
+    pub encoding: &'static str,
+    /// Fields, elements or members held; 1 for a string
+    pub elements: usize,
+    pub avg_item_bytes: usize,
 }
 
 impl MemoryUsage {
-    /// Calculate memory usage for a key (simplified version)
+    /// Calculate memory usage for a key (estimated from its encoding)
     /// Takes stores directly to avoid KeyManager dependency issue
-    pub async fn calculate_with_stores(
+    pub fn calculate_with_stores(
         key_type: crate::core::KeyType,
         key: &str,
         kv_store: &crate::core::KVStore,
@@ -26,60 +34,41 @@ impl MemoryUsage {
     ) -> Option<MemoryUsage> {
         use crate::core::KeyType;
 
-        let bytes = match key_type {
-            KeyType::String => {
-                // KV store: value size + key overhead
-                if let Ok(Some(value)) = kv_store.get(key).await {
-                    value.len() + key.len() + 64 // Rough overhead estimate
-                } else {
-                    return None;
-                }
-            }
-            KeyType::Hash => {
-                // Hash: sum of all field values + overhead
-                if let Ok(fields) = hash_store.hgetall(key) {
-                    let fields_size: usize = fields.values().map(|v| v.len()).sum();
-                    fields_size + (fields.len() * 32) + key.len() + 64
-                } else {
-                    return None;
-                }
-            }
-            KeyType::List => {
-                // List: sum of all elements + overhead
-                if let Ok(elements) = list_store.lrange(key, 0, -1) {
-                    let elements_size: usize = elements.iter().map(|v| v.len()).sum();
-                    elements_size + (elements.len() * 16) + key.len() + 64
-                } else {
-                    return None;
-                }
-            }
-            KeyType::Set => {
-                // Set: sum of all members + overhead
-                if let Ok(members) = set_store.smembers(key) {
-                    let members_size: usize = members.iter().map(|v: &Vec<u8>| v.len()).sum();
-                    members_size + (members.len() * 24) + key.len() + 64
-                } else {
-                    return None;
-                }
-            }
-            KeyType::SortedSet => {
-                // Sorted set: sum of members + scores + overhead
-                let members = sorted_set_store.zrange(key, 0, -1, false);
-                let members_size: usize = members.iter().map(|m| m.member.len() + 8).sum(); // +8 for score
-                members_size + (members.len() * 32) + key.len() + 64
-            }
+        let memory = match key_type {
+            KeyType::String => kv_store.key_memory(key)?,
+            KeyType::Hash => hash_store.key_memory(key)?,
+            KeyType::List => list_store.key_memory(key)?,
+            KeyType::Set => set_store.key_memory(key)?,
+            KeyType::SortedSet => sorted_set_store.key_memory(key)?,
             KeyType::None => return None,
         };
 
         Some(MemoryUsage {
             key: key.to_string(),
-            bytes,
-            human: format_bytes(bytes),
+            bytes: memory.bytes,
+            human: format_bytes(memory.bytes),
+            key_type: key_type.as_str(),
+            encoding: memory.encoding,
+            elements: memory.elements,
+            avg_item_bytes: memory.avg_item_bytes(),
         })
     }
+
+    /// Usage reported for a key that does not exist
+    pub fn missing(key: &str) -> MemoryUsage {
+        MemoryUsage {
+            key: key.to_string(),
+            bytes: 0,
+            human: "0B".to_string(),
+            key_type: crate::core::KeyType::None.as_str(),
+            encoding: "none",
+            elements: 0,
+            avg_item_bytes: 0,
+        }
+    }
 }
 
-fn format_bytes(bytes: usize) -> String {
+pub(crate) fn format_bytes(bytes: usize) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
     let mut size = bytes as f64;
     let mut unit_idx = 0;
@@ -8,22 +8,55 @@
 //!
 //! Additional monitoring commands:
 //! - SLOWLOG: Slow query logging
+//! - LATENCY: Latency spikes per command class and background job
 //! - MEMORY USAGE: Per-key memory tracking
-//! - CLIENT LIST: Active connection tracking
+//! - CLIENT LIST / KILL / PAUSE: Active connection tracking and control
+//! - DEPRECATIONS: Per-client usage of deprecated API surfaces
+//! - AUDIT: Authentication events, admin operations and permission denials
+//! - COMMAND HOOKS: Mutating command records streamed to plugins
+//! - READINESS: Warmup gate and dependency checks (see [`crate::health`])
+//! - RUNTIME: Tokio scheduler diagnostics (`runtime-diagnostics` feature)
 
+use crate::auth::AuditLogManager;
 use crate::core::{HashStore, KVStore, ListStore, SetStore, SortedSetStore};
+use crate::databases::Databases;
+use crate::health::HealthConfig;
+use crate::runtime_config::RuntimeConfig;
+use crate::warmup::Warmup;
 use std::sync::Arc;
 use std::time::Instant;
 
 mod client_list;
+mod command_hooks;
+mod deprecation;
 mod info;
+mod latency;
 mod memory_usage;
+#[cfg(feature = "runtime-diagnostics")]
+pub mod runtime;
 mod slowlog;
 
-pub use client_list::{ClientInfo, ClientList, ClientListManager};
+pub use client_list::{
+    ClientInfo, ClientLimitsConfig, ClientList, ClientListManager, InFlightGuard, KillSignal,
+    PauseMode,
+};
+#[cfg(unix)]
+pub use command_hooks::UnixSocketHook;
+pub use command_hooks::{
+    CommandHook, CommandHookStats, CommandHooks, CommandHooksConfig, CommandProtocol,
+    CommandRecord, is_write_command,
+};
+pub use deprecation::{
+    ClientUsage, DEPRECATIONS, Deprecation, DeprecationTracker, DeprecationUsage, lookup,
+    lookup_route,
+};
 pub use info::{InfoSection, KeyspaceInfo, MemoryInfo, ReplicationInfo, ServerInfo, StatsInfo};
+pub use latency::{LatencyLatest, LatencyMonitor, LatencyMonitorConfig, LatencySample};
 pub use memory_usage::MemoryUsage;
-pub use slowlog::{SlowLog, SlowLogEntry, SlowLogManager};
+pub(crate) use memory_usage::format_bytes;
+#[cfg(feature = "runtime-diagnostics")]
+pub use runtime::{BlockingPoolReport, RuntimeReport, WorkerReport};
+pub use slowlog::{SlowLog, SlowLogConfig, SlowLogEntry, SlowLogManager};
 
 /// Type alias for store references tuple (to reduce complexity)
 pub type StoreRefs = (
@@ -43,6 +76,14 @@ pub struct MonitoringManager {
     set_store: Arc<SetStore>,
     sorted_set_store: Arc<SortedSetStore>,
     slow_log: Arc<SlowLogManager>,
+    latency: Arc<LatencyMonitor>,
+    deprecations: Arc<DeprecationTracker>,
+    audit_log: Arc<AuditLogManager>,
+    command_hooks: Arc<CommandHooks>,
+    warmup: Arc<Warmup>,
+    health: HealthConfig,
+    runtime_config: Arc<RuntimeConfig>,
+    databases: Arc<Databases>,
     start_time: Instant,
 }
 
@@ -62,10 +103,64 @@ impl MonitoringManager {
             set_store,
             sorted_set_store,
             slow_log: Arc::new(SlowLogManager::new()),
+            latency: Arc::new(LatencyMonitor::default()),
+            deprecations: Arc::new(DeprecationTracker::new()),
+            audit_log: Arc::new(AuditLogManager::default()),
+            command_hooks: Arc::new(CommandHooks::default()),
+            warmup: Arc::new(Warmup::new()),
+            health: HealthConfig::default(),
+            runtime_config: Arc::new(RuntimeConfig::default()),
+            databases: Arc::new(Databases::default()),
             start_time: Instant::now(),
         }
     }
 
+    /// Replace the default in-memory audit log, e.g. with one built from
+    /// `auth.audit`
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLogManager>) -> Self {
+        self.audit_log = audit_log;
+        self
+    }
+
+    /// Replace the default command hook registry, e.g. with one sized by
+    /// `command_hooks.queue_capacity`
+    pub fn with_command_hooks(mut self, command_hooks: Arc<CommandHooks>) -> Self {
+        self.command_hooks = command_hooks;
+        self
+    }
+
+    /// Replace the default slow log, e.g. with one built from `slowlog`
+    pub fn with_slow_log(mut self, slow_log: Arc<SlowLogManager>) -> Self {
+        self.slow_log = slow_log;
+        self
+    }
+
+    /// Replace the default latency monitor, e.g. with one built from
+    /// `latency_monitor` and shared with the persistence layer
+    pub fn with_latency_monitor(mut self, latency: Arc<LatencyMonitor>) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Readiness thresholds, e.g. from `health`
+    pub fn with_health(mut self, health: HealthConfig) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Replace the default runtime config, e.g. with one keeping overrides
+    /// at `runtime_config.overrides_path`
+    pub fn with_runtime_config(mut self, runtime_config: Arc<RuntimeConfig>) -> Self {
+        self.runtime_config = runtime_config;
+        self
+    }
+
+    /// Attach databases 1 and up; without them only database 0 exists
+    pub fn with_databases(mut self, databases: Arc<Databases>) -> Self {
+        self.databases = databases;
+        self
+    }
+
     /// Get server uptime in seconds
     pub fn uptime_secs(&self) -> u64 {
         self.start_time.elapsed().as_secs()
@@ -76,6 +171,46 @@ impl MonitoringManager {
         self.slow_log.clone()
     }
 
+    /// Get latency spike monitor
+    pub fn latency(&self) -> Arc<LatencyMonitor> {
+        self.latency.clone()
+    }
+
+    /// Get deprecated-surface usage tracker
+    pub fn deprecations(&self) -> Arc<DeprecationTracker> {
+        self.deprecations.clone()
+    }
+
+    /// Get the audit log of authentication events and admin operations
+    pub fn audit_log(&self) -> Arc<AuditLogManager> {
+        self.audit_log.clone()
+    }
+
+    /// Get the hooks that receive mutating command records
+    pub fn command_hooks(&self) -> Arc<CommandHooks> {
+        self.command_hooks.clone()
+    }
+
+    /// Get the runtime config overrides
+    pub fn runtime_config(&self) -> Arc<RuntimeConfig> {
+        self.runtime_config.clone()
+    }
+
+    /// Get databases 1 and up
+    pub fn databases(&self) -> Arc<Databases> {
+        self.databases.clone()
+    }
+
+    /// Get the readiness thresholds
+    pub fn health_config(&self) -> &HealthConfig {
+        &self.health
+    }
+
+    /// Get the cold start warmup readiness gate
+    pub fn warmup(&self) -> Arc<Warmup> {
+        self.warmup.clone()
+    }
+
     /// Get KV store reference
     pub fn kv_store(&self) -> Arc<KVStore> {
         self.kv_store.clone()
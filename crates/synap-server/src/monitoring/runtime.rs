@@ -0,0 +1,247 @@
+//! Tokio runtime diagnostics (`runtime-diagnostics` feature)
+//!
+//! A latency spike with no slow command behind it is usually the event loop:
+//! a worker stuck in blocking code, a flooded injection queue, or a saturated
+//! blocking pool. [`RuntimeReport::sample`] watches the scheduler for a short
+//! window and reports how busy each worker was while it watched. Builds with
+//! `--cfg tokio_unstable` add poll counts, mean poll times and the blocking
+//! pool.
+
+use crate::config::DiagnosticsConfig;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::runtime::{Handle, RuntimeFlavor, RuntimeMetrics};
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Default `sample_ms` of `GET /debug/runtime`
+pub const DEFAULT_SAMPLE_MS: u64 = 100;
+/// Largest `sample_ms` accepted
+pub const MAX_SAMPLE_MS: u64 = 5_000;
+/// A worker busy for at least this share of the window counts as saturated
+const SATURATED_BUSY_RATIO: f64 = 0.9;
+
+/// Scheduler state over one sampling window
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeReport {
+    /// `multi_thread` or `current_thread`
+    pub flavor: &'static str,
+    /// Length of the sampling window in milliseconds
+    pub sample_ms: u64,
+    pub workers: usize,
+    /// Tasks spawned and not yet finished
+    pub alive_tasks: usize,
+    /// Tasks waiting in the shared injection queue
+    pub global_queue_depth: usize,
+    /// Workers busy for at least 90% of the window
+    pub saturated_workers: usize,
+    pub worker_stats: Vec<WorkerReport>,
+    /// Present in `--cfg tokio_unstable` builds only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocking_pool: Option<BlockingPoolReport>,
+    /// Whether this build records poll counts and poll times
+    pub tokio_unstable: bool,
+}
+
+/// One worker thread over the sampling window
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerReport {
+    pub worker: usize,
+    /// Share of the window spent running tasks (0.0 - 1.0)
+    pub busy_ratio: f64,
+    /// Times the worker went idle during the window
+    pub parks: u64,
+    /// Tasks polled during the window (`tokio_unstable` builds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub polls: Option<u64>,
+    /// Moving average of task poll times, in microseconds (`tokio_unstable` builds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_poll_time_us: Option<u64>,
+    /// Tasks waiting in the worker's local queue (`tokio_unstable` builds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_queue_depth: Option<usize>,
+}
+
+/// The `spawn_blocking` pool
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockingPoolReport {
+    pub threads: usize,
+    pub idle_threads: usize,
+    /// Blocking tasks waiting for a thread
+    pub queue_depth: usize,
+}
+
+/// Cumulative per-worker counters at one instant
+struct Counters {
+    busy: Vec<Duration>,
+    parks: Vec<u64>,
+    polls: Vec<u64>,
+}
+
+impl Counters {
+    fn read(metrics: &RuntimeMetrics) -> Self {
+        let workers = 0..metrics.num_workers();
+        Self {
+            busy: workers
+                .clone()
+                .map(|w| metrics.worker_total_busy_duration(w))
+                .collect(),
+            parks: workers
+                .clone()
+                .map(|w| metrics.worker_park_count(w))
+                .collect(),
+            polls: workers.map(|w| poll_count(metrics, w)).collect(),
+        }
+    }
+}
+
+impl RuntimeReport {
+    /// Watch the runtime behind `handle` for `window` and report on it
+    pub async fn sample(handle: &Handle, window: Duration) -> Self {
+        let metrics = handle.metrics();
+        let before = Counters::read(&metrics);
+        let started = tokio::time::Instant::now();
+        tokio::time::sleep(window).await;
+        let elapsed = started.elapsed();
+        let after = Counters::read(&metrics);
+
+        let worker_stats: Vec<WorkerReport> = (0..before.busy.len())
+            .map(|w| {
+                let busy = after.busy[w].saturating_sub(before.busy[w]);
+                WorkerReport {
+                    worker: w,
+                    busy_ratio: (busy.as_secs_f64() / elapsed.as_secs_f64().max(f64::EPSILON))
+                        .min(1.0),
+                    parks: after.parks[w].saturating_sub(before.parks[w]),
+                    polls: cfg!(tokio_unstable)
+                        .then(|| after.polls[w].saturating_sub(before.polls[w])),
+                    mean_poll_time_us: mean_poll_time(&metrics, w).map(|t| t.as_micros() as u64),
+                    local_queue_depth: local_queue_depth(&metrics, w),
+                }
+            })
+            .collect();
+
+        Self {
+            flavor: match handle.runtime_flavor() {
+                RuntimeFlavor::CurrentThread => "current_thread",
+                RuntimeFlavor::MultiThread => "multi_thread",
+                _ => "other",
+            },
+            sample_ms: elapsed.as_millis() as u64,
+            workers: metrics.num_workers(),
+            alive_tasks: metrics.num_alive_tasks(),
+            global_queue_depth: metrics.global_queue_depth(),
+            saturated_workers: worker_stats
+                .iter()
+                .filter(|w| w.busy_ratio >= SATURATED_BUSY_RATIO)
+                .count(),
+            worker_stats,
+            blocking_pool: blocking_pool(&metrics),
+            tokio_unstable: cfg!(tokio_unstable),
+        }
+    }
+}
+
+/// Start the tokio-console server, or explain why it cannot run. Returns the
+/// layer to add to the tracing subscriber.
+pub fn console_layer<S>(config: &DiagnosticsConfig) -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    if !config.tokio_console {
+        return None;
+    }
+    if !cfg!(tokio_unstable) {
+        // Logging is not up yet, so this goes straight to stderr
+        eprintln!(
+            "diagnostics.tokio_console needs a build with RUSTFLAGS=\"--cfg tokio_unstable\"; \
+             tokio-console will show no tasks"
+        );
+    }
+    Some(
+        console_subscriber::ConsoleLayer::builder()
+            .server_addr(config.console_bind)
+            .spawn(),
+    )
+}
+
+#[cfg(tokio_unstable)]
+fn poll_count(metrics: &RuntimeMetrics, worker: usize) -> u64 {
+    metrics.worker_poll_count(worker)
+}
+
+#[cfg(not(tokio_unstable))]
+fn poll_count(_metrics: &RuntimeMetrics, _worker: usize) -> u64 {
+    0
+}
+
+#[cfg(tokio_unstable)]
+fn mean_poll_time(metrics: &RuntimeMetrics, worker: usize) -> Option<Duration> {
+    Some(metrics.worker_mean_poll_time(worker))
+}
+
+#[cfg(not(tokio_unstable))]
+fn mean_poll_time(_metrics: &RuntimeMetrics, _worker: usize) -> Option<Duration> {
+    None
+}
+
+#[cfg(tokio_unstable)]
+fn local_queue_depth(metrics: &RuntimeMetrics, worker: usize) -> Option<usize> {
+    Some(metrics.worker_local_queue_depth(worker))
+}
+
+#[cfg(not(tokio_unstable))]
+fn local_queue_depth(_metrics: &RuntimeMetrics, _worker: usize) -> Option<usize> {
+    None
+}
+
+#[cfg(tokio_unstable)]
+fn blocking_pool(metrics: &RuntimeMetrics) -> Option<BlockingPoolReport> {
+    Some(BlockingPoolReport {
+        threads: metrics.num_blocking_threads(),
+        idle_threads: metrics.num_idle_blocking_threads(),
+        queue_depth: metrics.blocking_queue_depth(),
+    })
+}
+
+#[cfg(not(tokio_unstable))]
+fn blocking_pool(_metrics: &RuntimeMetrics) -> Option<BlockingPoolReport> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_sample_sees_a_blocked_worker() {
+        let handle = Handle::current();
+        let sampler = tokio::spawn(async move {
+            RuntimeReport::sample(&handle, Duration::from_millis(400)).await
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Hog one worker with blocking code inside the window. Busy time is
+        // only credited once the task yields, so let it finish first.
+        tokio::spawn(async {
+            std::thread::sleep(Duration::from_millis(250));
+        })
+        .await
+        .unwrap();
+        let report = sampler.await.unwrap();
+
+        assert_eq!(report.flavor, "multi_thread");
+        assert_eq!(report.workers, 2);
+        assert_eq!(report.worker_stats.len(), 2);
+        assert!(report.sample_ms >= 400);
+        assert!(report.worker_stats.iter().any(|w| w.busy_ratio >= 0.5));
+        assert!(
+            report
+                .worker_stats
+                .iter()
+                .all(|w| (0.0..=1.0).contains(&w.busy_ratio))
+        );
+        assert_eq!(report.tokio_unstable, cfg!(tokio_unstable));
+    }
+}
@@ -1,9 +1,11 @@
 //! Slow Query Logging
 //!
-//! Tracks commands that exceed a configurable time threshold
+//! Tracks commands that exceed a configurable time threshold. The threshold
+//! and length can be changed at runtime (`slowlog.config.set`).
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
 use tokio::sync::RwLock;
 
@@ -15,6 +17,8 @@ pub struct SlowLogEntry {
     pub duration_us: u64,
     pub command: String,
     pub args: Vec<String>,
+    /// `X-Request-Id` of the HTTP request that ran the command, when there was one.
+    pub request_id: Option<String>,
 }
 
 impl SlowLogEntry {
@@ -28,14 +32,19 @@ impl SlowLogEntry {
             duration_us: duration.as_micros() as u64,
             command,
             args,
+            request_id: crate::server::request_id::current(),
         }
     }
 }
 
 /// Slow log configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SlowLogConfig {
+    /// Log commands that take at least this many milliseconds; 0 logs every
+    /// command
     pub threshold_ms: u64,
+    /// Entries kept, oldest dropped first
     pub max_entries: usize,
 }
 
@@ -51,7 +60,8 @@ impl Default for SlowLogConfig {
 /// Slow log manager
 pub struct SlowLogManager {
     entries: Arc<RwLock<Vec<SlowLogEntry>>>,
-    config: SlowLogConfig,
+    threshold_ms: Arc<AtomicU64>,
+    max_entries: Arc<AtomicUsize>,
     next_id: Arc<RwLock<u64>>,
 }
 
@@ -64,27 +74,27 @@ impl Default for SlowLogManager {
 impl SlowLogManager {
     /// Create a new slow log manager
     pub fn new() -> Self {
-        Self {
-            entries: Arc::new(RwLock::new(Vec::new())),
-            config: SlowLogConfig::default(),
-            next_id: Arc::new(RwLock::new(0)),
-        }
+        Self::with_config(SlowLogConfig::default())
     }
 
     /// Create with custom configuration
     pub fn with_config(config: SlowLogConfig) -> Self {
         Self {
             entries: Arc::new(RwLock::new(Vec::new())),
-            config,
+            threshold_ms: Arc::new(AtomicU64::new(config.threshold_ms)),
+            max_entries: Arc::new(AtomicUsize::new(config.max_entries)),
             next_id: Arc::new(RwLock::new(0)),
         }
     }
 
-    /// Record a command execution if it exceeds threshold
+    /// Record a command execution if it exceeds threshold.
+    ///
+    /// The entry is tagged with the current request id (see
+    /// [`crate::server::request_id::current`]).
     pub async fn record(&self, command: String, args: Vec<String>, duration: Duration) {
         let ultra_ms = duration.as_millis() as u64;
 
-        if ultra_ms < self.config.threshold_ms {
+        if ultra_ms < self.threshold_ms.load(Ordering::Relaxed) {
             return; // Not slow enough
         }
 
@@ -97,8 +107,10 @@ impl SlowLogManager {
         entries.push(entry);
 
         // Keep only the last N entries
-        if entries.len() > self.config.max_entries {
-            entries.remove(0);
+        let max_entries = self.max_entries.load(Ordering::Relaxed);
+        if entries.len() > max_entries {
+            let excess = entries.len() - max_entries;
+            entries.drain(..excess);
         }
     }
 
@@ -130,8 +142,30 @@ impl SlowLogManager {
     }
 
     /// Get configuration
-    pub fn config(&self) -> &SlowLogConfig {
-        &self.config
+    pub fn config(&self) -> SlowLogConfig {
+        SlowLogConfig {
+            threshold_ms: self.threshold_ms.load(Ordering::Relaxed),
+            max_entries: self.max_entries.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Change the threshold and length at runtime. Entries over a lowered
+    /// length are dropped, oldest first. Returns the new configuration.
+    pub async fn configure(
+        &self,
+        threshold_ms: Option<u64>,
+        max_entries: Option<usize>,
+    ) -> SlowLogConfig {
+        if let Some(threshold_ms) = threshold_ms {
+            self.threshold_ms.store(threshold_ms, Ordering::Relaxed);
+        }
+        if let Some(max_entries) = max_entries {
+            let mut entries = self.entries.write().await;
+            self.max_entries.store(max_entries, Ordering::Relaxed);
+            let excess = entries.len().saturating_sub(max_entries);
+            entries.drain(..excess);
+        }
+        self.config()
     }
 }
 
@@ -139,7 +173,8 @@ impl Clone for SlowLogManager {
     fn clone(&self) -> Self {
         Self {
             entries: self.entries.clone(),
-            config: self.config.clone(),
+            threshold_ms: self.threshold_ms.clone(),
+            max_entries: self.max_entries.clone(),
             next_id: self.next_id.clone(),
         }
     }
@@ -202,6 +237,20 @@ mod tests {
         assert_eq!(entries[0].command, "test");
         assert_eq!(entries[0].args, vec!["arg1"]);
         assert!(entries[0].duration_us >= 15000);
+        assert_eq!(entries[0].request_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_slowlog_entry_carries_request_id() {
+        let slowlog = SlowLogManager::new();
+        crate::server::request_id::scope(
+            "req-7".to_string(),
+            slowlog.record("test".to_string(), vec![], Duration::from_millis(15)),
+        )
+        .await;
+
+        let entries = slowlog.get(None).await;
+        assert_eq!(entries[0].request_id.as_deref(), Some("req-7"));
     }
 
     #[tokio::test]
@@ -265,6 +314,40 @@ mod tests {
         assert_eq!(entries[2].command, "cmd2");
     }
 
+    #[tokio::test]
+    async fn test_slowlog_configure_at_runtime() {
+        let slowlog = SlowLogManager::new();
+        for i in 0..4 {
+            slowlog
+                .record(format!("cmd{}", i), vec![], Duration::from_millis(15))
+                .await;
+        }
+
+        let config = slowlog.configure(Some(20), Some(2)).await;
+        assert_eq!(
+            config,
+            SlowLogConfig {
+                threshold_ms: 20,
+                max_entries: 2
+            }
+        );
+        // Shrinking drops the oldest entries
+        let entries = slowlog.get(None).await;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "cmd3");
+        assert_eq!(entries[1].command, "cmd2");
+
+        // The new threshold applies to clones too
+        let clone = slowlog.clone();
+        clone
+            .record("fast".to_string(), vec![], Duration::from_millis(15))
+            .await;
+        assert_eq!(slowlog.len().await, 2);
+
+        // Unset fields are kept
+        assert_eq!(slowlog.configure(None, None).await, config);
+    }
+
     #[tokio::test]
     async fn test_slowlog_entry_ids() {
         let slowlog = SlowLogManager::new();
@@ -14,9 +14,11 @@
 
 use crate::core::sorted_set::{SortedSetStore, ZAddOptions};
 use crate::core::{
-    Aggregate, HashStore, KVStore, ListStore, QueueManager, SetStore, StreamManager, SynapError,
+    Aggregate, BitmapStore, BloomFilterStore, HashStore, HyperLogLogStore, KVStore, ListStore,
+    QueueManager, SetStore, StreamManager, SynapError,
 };
 use crate::persistence::types::Operation;
+use bytes::Bytes;
 
 /// Borrowed bundle of every store a persistence routine can touch.
 ///
@@ -31,6 +33,9 @@ pub struct StoreRefs<'a> {
     pub list_store: Option<&'a ListStore>,
     pub set_store: Option<&'a SetStore>,
     pub sorted_set_store: Option<&'a SortedSetStore>,
+    pub hyperloglog_store: Option<&'a HyperLogLogStore>,
+    pub bloom_store: Option<&'a BloomFilterStore>,
+    pub bitmap_store: Option<&'a BitmapStore>,
     pub queue_manager: Option<&'a QueueManager>,
     pub stream_manager: Option<&'a StreamManager>,
 }
@@ -44,6 +49,9 @@ impl<'a> StoreRefs<'a> {
             list_store: None,
             set_store: None,
             sorted_set_store: None,
+            hyperloglog_store: None,
+            bloom_store: None,
+            bitmap_store: None,
             queue_manager: None,
             stream_manager: None,
         }
@@ -59,6 +67,9 @@ pub struct StoreArcs {
     pub list_store: Option<std::sync::Arc<ListStore>>,
     pub set_store: Option<std::sync::Arc<SetStore>>,
     pub sorted_set_store: Option<std::sync::Arc<SortedSetStore>>,
+    pub hyperloglog_store: Option<std::sync::Arc<HyperLogLogStore>>,
+    pub bloom_store: Option<std::sync::Arc<BloomFilterStore>>,
+    pub bitmap_store: Option<std::sync::Arc<BitmapStore>>,
     pub queue_manager: Option<std::sync::Arc<QueueManager>>,
     pub stream_manager: Option<std::sync::Arc<StreamManager>>,
 }
@@ -72,6 +83,9 @@ impl StoreArcs {
             list_store: None,
             set_store: None,
             sorted_set_store: None,
+            hyperloglog_store: None,
+            bloom_store: None,
+            bitmap_store: None,
             queue_manager: None,
             stream_manager: None,
         }
@@ -85,6 +99,9 @@ impl StoreArcs {
             list_store: self.list_store.as_deref(),
             set_store: self.set_store.as_deref(),
             sorted_set_store: self.sorted_set_store.as_deref(),
+            hyperloglog_store: self.hyperloglog_store.as_deref(),
+            bloom_store: self.bloom_store.as_deref(),
+            bitmap_store: self.bitmap_store.as_deref(),
             queue_manager: self.queue_manager.as_deref(),
             stream_manager: self.stream_manager.as_deref(),
         }
@@ -103,13 +120,16 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
         list_store,
         set_store,
         sorted_set_store,
+        hyperloglog_store,
+        bloom_store,
+        bitmap_store,
         queue_manager,
         stream_manager,
     } = stores;
     match op {
         // ── KV ──────────────────────────────────────────────────────────────
         Operation::KVSet { key, value, ttl } => {
-            kv_store.set(key, value, ttl).await?;
+            kv_store.set(key, &value[..], ttl).await?;
         }
         Operation::KVDel { keys } => {
             for key in keys {
@@ -134,7 +154,7 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
             {
                 qm.publish(
                     &queue,
-                    (*message.payload).clone(),
+                    message.payload,
                     Some(message.priority),
                     Some(message.max_retries),
                 )
@@ -166,14 +186,14 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
                 // Idempotent room creation (synap#165): never errors on an
                 // existing room, so replaying the same room is a no-op.
                 let _ = sm.get_or_create_room(&room).await;
-                let _ = sm.publish(&room, &event_type, payload).await;
+                let _ = sm.publish(&room, &event_type, payload.into()).await;
             }
         }
 
         // ── Hash ────────────────────────────────────────────────────────────
         Operation::HashSet { key, field, value } => {
             if let Some(h) = hash_store {
-                h.hset(&key, &field, value)?;
+                h.hset(&key, &field, value.into())?;
             }
         }
         Operation::HashDel { key, fields } => {
@@ -204,9 +224,9 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
         Operation::ListPush { key, values, left } => {
             if let Some(l) = list_store {
                 if left {
-                    l.lpush(&key, values, false)?;
+                    l.lpush(&key, into_vecs(values), false)?;
                 } else {
-                    l.rpush(&key, values, false)?;
+                    l.rpush(&key, into_vecs(values), false)?;
                 }
             }
         }
@@ -221,7 +241,7 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
         }
         Operation::ListSet { key, index, value } => {
             if let Some(l) = list_store {
-                let _ = l.lset(&key, index, value);
+                let _ = l.lset(&key, index, value.into());
             }
         }
         Operation::ListTrim { key, start, stop } => {
@@ -231,7 +251,7 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
         }
         Operation::ListRem { key, count, value } => {
             if let Some(l) = list_store {
-                let _ = l.lrem(&key, count, value);
+                let _ = l.lrem(&key, count, value.into());
             }
         }
         Operation::ListInsert {
@@ -241,7 +261,7 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
             value,
         } => {
             if let Some(l) = list_store {
-                let _ = l.linsert(&key, before, pivot, value);
+                let _ = l.linsert(&key, before, pivot.into(), value.into());
             }
         }
         Operation::ListRpoplpush {
@@ -256,12 +276,12 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
         // ── Set ─────────────────────────────────────────────────────────────
         Operation::SetAdd { key, members } => {
             if let Some(s) = set_store {
-                let _ = s.sadd(&key, members);
+                let _ = s.sadd(&key, into_vecs(members));
             }
         }
         Operation::SetRem { key, members } => {
             if let Some(s) = set_store {
-                let _ = s.srem(&key, members);
+                let _ = s.srem(&key, into_vecs(members));
             }
         }
         Operation::SetMove {
@@ -270,7 +290,7 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
             member,
         } => {
             if let Some(s) = set_store {
-                let _ = s.smove(&source, &destination, member);
+                let _ = s.smove(&source, &destination, member.into());
             }
         }
         Operation::SetInterStore { destination, keys } => {
@@ -308,12 +328,12 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
                     ch: false,
                     incr: false,
                 };
-                let _ = z.zadd(&key, member, score, &opts);
+                let _ = z.zadd(&key, member.into(), score, &opts);
             }
         }
         Operation::ZRem { key, members } => {
             if let Some(z) = sorted_set_store {
-                let _ = z.zrem(&key, &members);
+                let _ = z.zrem(&key, &into_vecs(members));
             }
         }
         Operation::ZIncrBy {
@@ -322,7 +342,7 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
             increment,
         } => {
             if let Some(z) = sorted_set_store {
-                let _ = z.zincrby(&key, member, increment);
+                let _ = z.zincrby(&key, member.into(), increment);
             }
         }
         Operation::ZRemRangeByRank { key, start, stop } => {
@@ -365,6 +385,88 @@ pub async fn apply_operation(op: Operation, stores: StoreRefs<'_>) -> Result<(),
                 let _ = z.zdiffstore(&destination, &key_refs);
             }
         }
+
+        // ── HyperLogLog ─────────────────────────────────────────────────────
+        Operation::HyperLogLogMerge {
+            destination,
+            registers,
+        } => {
+            if let Some(hll) = hyperloglog_store {
+                hll.restore_registers(&destination, Vec::from(registers))?;
+            }
+        }
+
+        // ── Bloom filters ───────────────────────────────────────────────────
+        Operation::BloomReserve {
+            key,
+            error_rate,
+            capacity,
+        } => {
+            if let Some(bf) = bloom_store {
+                bf.reserve(&key, error_rate, capacity)?;
+            }
+        }
+        Operation::BloomAdd { key, items } => {
+            if let Some(bf) = bloom_store {
+                bf.add(&key, &items);
+            }
+        }
+        Operation::BloomLoad { key, filter } => {
+            if let Some(bf) = bloom_store {
+                bf.restore(&key, filter);
+            }
+        }
+
+        // ── Whole values (full syncs and dumps) ─────────────────────────────
+        Operation::HashLoad { key, fields, ttl } => {
+            if let Some(h) = hash_store {
+                let fields = fields.into_iter().map(|(f, v)| (f, Vec::from(v))).collect();
+                h.restore(&key, fields, ttl);
+            }
+        }
+        Operation::ListLoad { key, values, ttl } => {
+            if let Some(l) = list_store {
+                l.restore(&key, into_vecs(values), ttl);
+            }
+        }
+        Operation::SetLoad { key, members, ttl } => {
+            if let Some(s) = set_store {
+                s.restore(&key, into_vecs(members), ttl);
+            }
+        }
+        Operation::SortedSetLoad { key, members, ttl } => {
+            if let Some(z) = sorted_set_store {
+                let members = members
+                    .into_iter()
+                    .map(|(m, s)| (Vec::from(m), s))
+                    .collect();
+                z.restore(&key, members, ttl);
+            }
+        }
+        Operation::HyperLogLogLoad {
+            key,
+            registers,
+            ttl,
+        } => {
+            if let Some(hll) = hyperloglog_store {
+                hll.restore(&key, Vec::from(registers), ttl)?;
+            }
+        }
+        Operation::HyperLogLogAdd { key, elements, ttl } => {
+            if let Some(hll) = hyperloglog_store {
+                hll.pfadd(&key, into_vecs(elements), ttl)?;
+            }
+        }
+        Operation::BitmapLoad { key, data, ttl } => {
+            if let Some(bm) = bitmap_store {
+                bm.restore(&key, Vec::from(data), ttl);
+            }
+        }
+
+        // ── Subscriptions ───────────────────────────────────────────────────
+        // Replayed by `SubscriptionRegistry` from its own log, never applied
+        // to the data stores.
+        Operation::SubscriptionPut { .. } | Operation::SubscriptionDelete { .. } => {}
     }
     Ok(())
 }
@@ -377,6 +479,12 @@ fn parse_aggregate(aggregate: &str) -> Aggregate {
     }
 }
 
+/// Hand `Bytes` members to a collection store. `Vec::from` reuses the buffer
+/// when the operation holds the only reference (WAL replay, replica decode).
+fn into_vecs(values: Vec<Bytes>) -> Vec<Vec<u8>> {
+    values.into_iter().map(Vec::from).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,6 +498,9 @@ mod tests {
         list: ListStore,
         set: SetStore,
         zset: SortedSetStore,
+        hll: HyperLogLogStore,
+        bloom: BloomFilterStore,
+        bitmap: BitmapStore,
         queue: QueueManager,
         stream: StreamManager,
     }
@@ -401,6 +512,9 @@ mod tests {
             list: ListStore::new(),
             set: SetStore::new(),
             zset: SortedSetStore::new(),
+            hll: HyperLogLogStore::new(),
+            bloom: BloomFilterStore::new(),
+            bitmap: BitmapStore::new(),
             queue: QueueManager::new(QueueConfig::default()),
             stream: StreamManager::new(StreamConfig::default()),
         }
@@ -416,6 +530,9 @@ mod tests {
                 list_store: Some(&s.list),
                 set_store: Some(&s.set),
                 sorted_set_store: Some(&s.zset),
+                hyperloglog_store: Some(&s.hll),
+                bloom_store: Some(&s.bloom),
+                bitmap_store: Some(&s.bitmap),
                 queue_manager: Some(&s.queue),
                 stream_manager: Some(&s.stream),
             },
@@ -431,7 +548,7 @@ mod tests {
             &s,
             Operation::KVSet {
                 key: "a".into(),
-                value: b"1".to_vec(),
+                value: Bytes::from_static(b"1"),
                 ttl: None,
             },
         )
@@ -467,7 +584,7 @@ mod tests {
             Operation::HashSet {
                 key: "h".into(),
                 field: "f".into(),
-                value: b"v".to_vec(),
+                value: Bytes::from_static(b"v"),
             },
         )
         .await;
@@ -510,7 +627,11 @@ mod tests {
             &s,
             Operation::ListPush {
                 key: "l".into(),
-                values: vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()],
+                values: vec![
+                    Bytes::from_static(b"a"),
+                    Bytes::from_static(b"b"),
+                    Bytes::from_static(b"c"),
+                ],
                 left: false,
             },
         )
@@ -519,7 +640,7 @@ mod tests {
             &s,
             Operation::ListPush {
                 key: "l".into(),
-                values: vec![b"z".to_vec()],
+                values: vec![Bytes::from_static(b"z")],
                 left: true,
             },
         )
@@ -529,7 +650,7 @@ mod tests {
             Operation::ListSet {
                 key: "l".into(),
                 index: 0,
-                value: b"Z".to_vec(),
+                value: Bytes::from_static(b"Z"),
             },
         )
         .await;
@@ -538,8 +659,8 @@ mod tests {
             Operation::ListInsert {
                 key: "l".into(),
                 before: true,
-                pivot: b"a".to_vec(),
-                value: b"a0".to_vec(),
+                pivot: Bytes::from_static(b"a"),
+                value: Bytes::from_static(b"a0"),
             },
         )
         .await;
@@ -548,7 +669,7 @@ mod tests {
             Operation::ListRem {
                 key: "l".into(),
                 count: 1,
-                value: b"a0".to_vec(),
+                value: Bytes::from_static(b"a0"),
             },
         )
         .await;
@@ -589,7 +710,7 @@ mod tests {
             &s,
             Operation::SetAdd {
                 key: "s1".into(),
-                members: vec![b"a".to_vec(), b"b".to_vec()],
+                members: vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")],
             },
         )
         .await;
@@ -597,7 +718,7 @@ mod tests {
             &s,
             Operation::SetAdd {
                 key: "s2".into(),
-                members: vec![b"b".to_vec(), b"c".to_vec()],
+                members: vec![Bytes::from_static(b"b"), Bytes::from_static(b"c")],
             },
         )
         .await;
@@ -605,7 +726,7 @@ mod tests {
             &s,
             Operation::SetRem {
                 key: "s1".into(),
-                members: vec![b"a".to_vec()],
+                members: vec![Bytes::from_static(b"a")],
             },
         )
         .await;
@@ -614,7 +735,7 @@ mod tests {
             Operation::SetMove {
                 source: "s2".into(),
                 destination: "s1".into(),
-                member: b"c".to_vec(),
+                member: Bytes::from_static(b"c"),
             },
         )
         .await;
@@ -653,7 +774,7 @@ mod tests {
                 &s,
                 Operation::ZAdd {
                     key: "z1".into(),
-                    member: m.as_bytes().to_vec(),
+                    member: m.as_bytes().to_vec().into(),
                     score,
                     nx: false,
                     xx: false,
@@ -667,7 +788,7 @@ mod tests {
             &s,
             Operation::ZIncrBy {
                 key: "z1".into(),
-                member: b"a".to_vec(),
+                member: Bytes::from_static(b"a"),
                 increment: 10.0,
             },
         )
@@ -676,7 +797,7 @@ mod tests {
             &s,
             Operation::ZRem {
                 key: "z1".into(),
-                members: vec![b"b".to_vec()],
+                members: vec![Bytes::from_static(b"b")],
             },
         )
         .await;
@@ -703,7 +824,7 @@ mod tests {
             &s,
             Operation::ZAdd {
                 key: "z2".into(),
-                member: b"a".to_vec(),
+                member: Bytes::from_static(b"a"),
                 score: 5.0,
                 nx: false,
                 xx: false,
@@ -743,6 +864,122 @@ mod tests {
         assert_eq!(s.zset.zscore("z1", b"a"), Some(11.0));
     }
 
+    #[tokio::test]
+    async fn applies_hyperloglog_merge() {
+        let s = stores();
+        let source = HyperLogLogStore::new();
+        source
+            .pfadd("daily", vec![b"a".to_vec(), b"b".to_vec()], None)
+            .unwrap();
+
+        apply(
+            &s,
+            Operation::HyperLogLogMerge {
+                destination: "weekly".into(),
+                registers: source.registers("daily").unwrap().into(),
+            },
+        )
+        .await;
+        assert_eq!(
+            s.hll.pfcount("weekly").unwrap(),
+            source.pfcount("daily").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_bloom_operations() {
+        let s = stores();
+        apply(
+            &s,
+            Operation::BloomReserve {
+                key: "seen".into(),
+                error_rate: 0.001,
+                capacity: 500,
+            },
+        )
+        .await;
+        apply(
+            &s,
+            Operation::BloomAdd {
+                key: "seen".into(),
+                items: vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")],
+            },
+        )
+        .await;
+        assert_eq!(s.bloom.exists("seen", &[b"a", b"b"]), vec![true, true]);
+        assert_eq!(s.bloom.get("seen").unwrap().capacity(), 500);
+
+        // A load replaces whatever the key held
+        let source = BloomFilterStore::new();
+        source.add("other", &[b"c"]);
+        apply(
+            &s,
+            Operation::BloomLoad {
+                key: "seen".into(),
+                filter: source.get("other").unwrap(),
+            },
+        )
+        .await;
+        assert_eq!(s.bloom.get("seen"), source.get("other"));
+    }
+
+    #[tokio::test]
+    async fn load_operations_replace_whole_values() {
+        let s = stores();
+        s.hash.hset("h", "stale", b"x".to_vec()).unwrap();
+        s.list.rpush("l", vec![b"stale".to_vec()], false).unwrap();
+        s.set.sadd("s", vec![b"stale".to_vec()]).unwrap();
+        s.zset
+            .zadd("z", b"stale".to_vec(), 1.0, &ZAddOptions::default());
+
+        apply(
+            &s,
+            Operation::HashLoad {
+                key: "h".into(),
+                fields: vec![("f".into(), Bytes::from_static(b"v"))],
+                ttl: Some(600),
+            },
+        )
+        .await;
+        apply(
+            &s,
+            Operation::ListLoad {
+                key: "l".into(),
+                values: vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")],
+                ttl: None,
+            },
+        )
+        .await;
+        apply(
+            &s,
+            Operation::SetLoad {
+                key: "s".into(),
+                members: vec![Bytes::from_static(b"m")],
+                ttl: Some(600),
+            },
+        )
+        .await;
+        apply(
+            &s,
+            Operation::SortedSetLoad {
+                key: "z".into(),
+                members: vec![(Bytes::from_static(b"m"), 2.0)],
+                ttl: Some(600),
+            },
+        )
+        .await;
+
+        assert_eq!(s.hash.hkeys("h").unwrap(), vec!["f".to_string()]);
+        assert!(s.hash.ttl("h").is_some_and(|ttl| ttl > 590));
+        assert_eq!(
+            s.list.lrange("l", 0, -1).unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+        assert_eq!(s.set.smembers("s").unwrap(), vec![b"m".to_vec()]);
+        assert_eq!(s.zset.zscore("z", b"stale"), None);
+        assert!(s.zset.ttl("z").is_some_and(|ttl| ttl > 590));
+    }
+
     #[tokio::test]
     async fn applies_queue_and_stream_operations() {
         let s = stores();
@@ -778,7 +1015,7 @@ mod tests {
             Operation::StreamPublish {
                 room: "r".into(),
                 event_type: "e".into(),
-                payload: b"data".to_vec(),
+                payload: Bytes::from_static(b"data"),
             },
         )
         .await;
@@ -793,7 +1030,7 @@ mod tests {
             Operation::StreamPublish {
                 room: "r".into(),
                 event_type: "e".into(),
-                payload: b"data".to_vec(),
+                payload: Bytes::from_static(b"data"),
             },
             StoreRefs {
                 kv_store: &s.kv,
@@ -801,6 +1038,9 @@ mod tests {
                 list_store: None,
                 set_store: None,
                 sorted_set_store: None,
+                hyperloglog_store: None,
+                bloom_store: None,
+                bitmap_store: None,
                 queue_manager: None,
                 stream_manager: None,
             },
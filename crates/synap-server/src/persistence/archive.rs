@@ -0,0 +1,433 @@
+//! WAL archiving to S3-compatible object storage (`persistence.archive`).
+//!
+//! Every `interval_secs` the archiver cuts the WAL entries written since its
+//! last pass into a sealed segment object and uploads the snapshots the
+//! bucket does not have yet, giving off-host durability without a replica.
+//! Objects live under the configured prefix:
+//!
+//! - `wal/<first offset>-<last offset>.wal`: WAL entries in their on-disk
+//!   framing, so a segment is itself a valid WAL file (`synap-cli WAL DUMP`
+//!   reads it)
+//! - `snapshots/<wal offset>-<file name>`: snapshot files as written locally
+//!
+//! Retention keeps the newest `keep_snapshots` snapshots (and drops those
+//! older than `max_age_days`, except the newest), then deletes the segments
+//! every remaining snapshot already covers.
+//!
+//! [`restore_from_archive`] is the way back: it downloads the newest
+//! snapshot and the segments after it into the configured data paths, and
+//! the normal startup recovery replays them.
+
+use super::object_store::{ObjectInfo, ObjectStore};
+use super::snapshot::SnapshotManager;
+use super::types::{ArchiveConfig, PersistenceConfig, PersistenceError, Result, WALEntry};
+use std::collections::HashSet;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tracing::{debug, info, warn};
+
+/// WAL frame header: entry size (u64) + CRC32 (u32)
+const FRAME_HEADER_LEN: u64 = 12;
+
+/// What one archiving pass did
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ArchiveReport {
+    pub segments_uploaded: usize,
+    pub entries_archived: u64,
+    pub snapshots_uploaded: usize,
+    pub objects_deleted: usize,
+}
+
+/// What [`restore_from_archive`] put in place
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RestoreReport {
+    /// Object key of the restored snapshot
+    pub snapshot: Option<String>,
+    pub segments: usize,
+    /// Offset of the last WAL entry restored
+    pub last_offset: Option<u64>,
+}
+
+/// An archived WAL segment, parsed from its key
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Segment {
+    key: String,
+    first: u64,
+    last: u64,
+}
+
+/// An archived snapshot, parsed from its key
+#[derive(Debug, Clone)]
+struct ArchivedSnapshot {
+    object: ObjectInfo,
+    wal_offset: u64,
+    file_name: String,
+}
+
+/// Ships WAL segments and snapshots to object storage
+pub struct WalArchiver {
+    config: ArchiveConfig,
+    store: ObjectStore,
+    wal_path: PathBuf,
+    snapshots: SnapshotManager,
+    /// WAL byte position up to which every entry is archived
+    position: u64,
+    /// Offset of the newest archived entry
+    last_offset: Option<u64>,
+}
+
+impl WalArchiver {
+    /// Connect to the bucket and find where the archive left off
+    pub async fn open(config: &PersistenceConfig) -> Result<Self> {
+        let store = ObjectStore::new(&config.archive)?;
+        let archive = config.archive.clone();
+        let last_offset = list_segments(&store, &archive)
+            .await?
+            .iter()
+            .map(|segment| segment.last)
+            .max();
+        info!(
+            "WAL archiver shipping to {}/{}/{} (archived up to offset {:?})",
+            archive.endpoint, archive.bucket, archive.prefix, last_offset
+        );
+        Ok(Self {
+            config: archive,
+            store,
+            wal_path: config.wal.path.clone(),
+            snapshots: SnapshotManager::new(config.snapshot.clone()),
+            position: 0,
+            last_offset,
+        })
+    }
+
+    /// Run one archiving pass: new WAL entries, new snapshots, retention
+    pub async fn run_once(&mut self) -> Result<ArchiveReport> {
+        let mut report = ArchiveReport::default();
+        self.ship_wal(&mut report).await?;
+        self.ship_snapshots(&mut report).await?;
+        self.apply_retention(&mut report).await?;
+        Ok(report)
+    }
+
+    /// Archive in the background every `interval_secs`
+    pub fn start(mut self) -> tokio::task::JoinHandle<()> {
+        let interval = Duration::from_secs(self.config.interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.run_once().await {
+                    Ok(report) if report != ArchiveReport::default() => {
+                        info!("WAL archive pass: {:?}", report)
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("WAL archive pass failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Upload the complete WAL entries past `position` as sealed segments
+    async fn ship_wal(&mut self, report: &mut ArchiveReport) -> Result<()> {
+        let file = match tokio::fs::File::open(&self.wal_path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let len = file.metadata().await?.len();
+        if len < self.position {
+            warn!(
+                "WAL {:?} shrank below the archived position, rescanning it",
+                self.wal_path
+            );
+            self.position = 0;
+        }
+
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(self.position)).await?;
+        let max_segment = self.config.segment_max_mb.max(1) * 1024 * 1024;
+        let mut scanned = self.position;
+        let mut segment: Vec<u8> = Vec::new();
+        let mut range: Option<(u64, u64)> = None;
+        let mut entries = 0u64;
+
+        while scanned + FRAME_HEADER_LEN <= len {
+            let size = reader.read_u64().await?;
+            let checksum = reader.read_u32().await?;
+            // A frame still being written (or a garbage size) ends the pass;
+            // the next one picks it up
+            if size > len - scanned - FRAME_HEADER_LEN {
+                break;
+            }
+            let mut data = vec![0u8; size as usize];
+            reader.read_exact(&mut data).await?;
+            if crc32fast::hash(&data) != checksum {
+                warn!(
+                    "WAL entry at byte {} fails its checksum, archiving stops there",
+                    scanned
+                );
+                break;
+            }
+            let offset = WALEntry::decode(&data)
+                .map_err(|_| PersistenceError::InvalidEntry)?
+                .offset;
+            scanned += FRAME_HEADER_LEN + size;
+
+            if self.last_offset.is_some_and(|last| offset <= last) {
+                // Already archived before a restart or a rescan
+                if range.is_none() {
+                    self.position = scanned;
+                }
+                continue;
+            }
+            segment.extend_from_slice(&size.to_be_bytes());
+            segment.extend_from_slice(&checksum.to_be_bytes());
+            segment.extend_from_slice(&data);
+            range = Some((range.map_or(offset, |(first, _)| first), offset));
+            entries += 1;
+
+            if segment.len() >= max_segment {
+                self.upload_segment(std::mem::take(&mut segment), range.take(), scanned)
+                    .await?;
+                report.segments_uploaded += 1;
+                report.entries_archived += std::mem::take(&mut entries);
+            }
+        }
+
+        if range.is_some() {
+            self.upload_segment(segment, range, scanned).await?;
+            report.segments_uploaded += 1;
+            report.entries_archived += entries;
+        }
+        Ok(())
+    }
+
+    async fn upload_segment(
+        &mut self,
+        segment: Vec<u8>,
+        range: Option<(u64, u64)>,
+        end_position: u64,
+    ) -> Result<()> {
+        let Some((first, last)) = range else {
+            return Ok(());
+        };
+        let key = segment_key(&self.config, first, last);
+        debug!("Archiving WAL entries {}-{} to {}", first, last, key);
+        self.store.put(&key, segment).await?;
+        self.position = end_position;
+        self.last_offset = Some(last);
+        Ok(())
+    }
+
+    /// Upload the local snapshots the bucket does not have yet
+    async fn ship_snapshots(&mut self, report: &mut ArchiveReport) -> Result<()> {
+        let archived: HashSet<String> = list_snapshots(&self.store, &self.config)
+            .await?
+            .into_iter()
+            .map(|snapshot| snapshot.file_name)
+            .collect();
+        for path in self.snapshots.list_snapshots().await? {
+            let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            if archived.contains(&file_name) {
+                continue;
+            }
+            let (_, wal_offset) = SnapshotManager::header(&path).await?;
+            let key = format!(
+                "{}snapshots/{:020}-{}",
+                self.config.prefix, wal_offset, file_name
+            );
+            debug!("Archiving snapshot {:?} to {}", path, key);
+            self.store.put_file(&key, &path).await?;
+            report.snapshots_uploaded += 1;
+        }
+        Ok(())
+    }
+
+    /// Drop old snapshots, then the segments every kept snapshot covers
+    async fn apply_retention(&mut self, report: &mut ArchiveReport) -> Result<()> {
+        let mut snapshots = list_snapshots(&self.store, &self.config).await?;
+        if snapshots.is_empty() {
+            // Without a snapshot every segment is needed to rebuild the data
+            return Ok(());
+        }
+        snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.wal_offset));
+
+        let cutoff = self
+            .config
+            .max_age_days
+            .map(|days| chrono::Utc::now() - chrono::Duration::days(days as i64));
+        let keep = self.config.keep_snapshots.max(1);
+        let (kept, expired): (Vec<_>, Vec<_>) =
+            snapshots
+                .into_iter()
+                .enumerate()
+                .partition(|(i, snapshot)| {
+                    *i == 0
+                        || (*i < keep
+                            && cutoff.is_none_or(|cutoff| {
+                                chrono::DateTime::parse_from_rfc3339(&snapshot.object.last_modified)
+                                    .map_or(true, |modified| modified >= cutoff)
+                            }))
+                });
+        for (_, snapshot) in expired {
+            info!("Archive retention: deleting {}", snapshot.object.key);
+            self.store.delete(&snapshot.object.key).await?;
+            report.objects_deleted += 1;
+        }
+
+        let oldest_needed = kept
+            .iter()
+            .map(|(_, snapshot)| snapshot.wal_offset)
+            .min()
+            .unwrap_or(0);
+        for segment in list_segments(&self.store, &self.config).await? {
+            if segment.last < oldest_needed {
+                debug!("Archive retention: deleting {}", segment.key);
+                self.store.delete(&segment.key).await?;
+                report.objects_deleted += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Download the newest archived snapshot and the WAL segments after it into
+/// the paths of `config`, for the next recovery to replay. Refuses to run
+/// over existing local data.
+pub async fn restore_from_archive(config: &PersistenceConfig) -> Result<RestoreReport> {
+    let wal_path = &config.wal.path;
+    if tokio::fs::metadata(wal_path)
+        .await
+        .is_ok_and(|meta| meta.len() > 0)
+    {
+        return Err(PersistenceError::Archive(format!(
+            "{} is not empty; move the local data aside before restoring",
+            wal_path.display()
+        )));
+    }
+    let local_snapshots = SnapshotManager::new(config.snapshot.clone());
+    if local_snapshots.has_snapshot().await? {
+        return Err(PersistenceError::Archive(format!(
+            "{} already holds snapshots; move the local data aside before restoring",
+            config.snapshot.directory.display()
+        )));
+    }
+
+    let store = ObjectStore::new(&config.archive)?;
+    let mut report = RestoreReport::default();
+
+    let newest = list_snapshots(&store, &config.archive)
+        .await?
+        .into_iter()
+        .max_by_key(|snapshot| snapshot.wal_offset);
+    let from = newest.as_ref().map_or(0, |snapshot| snapshot.wal_offset);
+    if let Some(snapshot) = newest {
+        tokio::fs::create_dir_all(&config.snapshot.directory).await?;
+        let path = config.snapshot.directory.join(&snapshot.file_name);
+        download(&store, &snapshot.object.key, &path).await?;
+        info!(
+            "Restored snapshot {} (WAL offset {})",
+            snapshot.object.key, snapshot.wal_offset
+        );
+        report.snapshot = Some(snapshot.object.key);
+    }
+
+    let mut segments: Vec<Segment> = list_segments(&store, &config.archive)
+        .await?
+        .into_iter()
+        .filter(|segment| segment.last >= from)
+        .collect();
+    segments.sort_by_key(|segment| segment.first);
+
+    if let Some(parent) = wal_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut partial = wal_path.clone().into_os_string();
+    partial.push(".new");
+    let partial = PathBuf::from(partial);
+    let mut wal = tokio::fs::File::create(&partial).await?;
+    let segment_path = partial.with_extension("segment");
+    for segment in segments {
+        // Offsets are contiguous; a hole means a segment was lost, and
+        // replaying past it would apply writes out of context
+        let expected = report.last_offset.map_or(from, |last| last + 1);
+        if segment.first > expected && !(report.last_offset.is_none() && from == 0) {
+            warn!(
+                "Archived WAL has a gap before offset {} (expected {}); restoring up to the gap",
+                segment.first, expected
+            );
+            break;
+        }
+        download(&store, &segment.key, &segment_path).await?;
+        let bytes = tokio::fs::read(&segment_path).await?;
+        wal.write_all(&bytes).await?;
+        report.segments += 1;
+        report.last_offset = Some(segment.last);
+    }
+    let _ = tokio::fs::remove_file(&segment_path).await;
+    wal.sync_all().await?;
+    drop(wal);
+    tokio::fs::rename(&partial, wal_path).await?;
+
+    info!(
+        "Restored {} WAL segment(s) up to offset {:?}",
+        report.segments, report.last_offset
+    );
+    Ok(report)
+}
+
+async fn download(store: &ObjectStore, key: &str, path: &Path) -> Result<()> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    store.get_to_file(key, &partial).await?;
+    tokio::fs::rename(&partial, path).await?;
+    Ok(())
+}
+
+fn segment_key(config: &ArchiveConfig, first: u64, last: u64) -> String {
+    format!("{}wal/{:020}-{:020}.wal", config.prefix, first, last)
+}
+
+async fn list_segments(store: &ObjectStore, config: &ArchiveConfig) -> Result<Vec<Segment>> {
+    let prefix = format!("{}wal/", config.prefix);
+    Ok(store
+        .list(&prefix)
+        .await?
+        .into_iter()
+        .filter_map(|object| {
+            let name = object.key.strip_prefix(&prefix)?.strip_suffix(".wal")?;
+            let (first, last) = name.split_once('-')?;
+            Some(Segment {
+                first: first.parse().ok()?,
+                last: last.parse().ok()?,
+                key: object.key,
+            })
+        })
+        .collect())
+}
+
+async fn list_snapshots(
+    store: &ObjectStore,
+    config: &ArchiveConfig,
+) -> Result<Vec<ArchivedSnapshot>> {
+    let prefix = format!("{}snapshots/", config.prefix);
+    Ok(store
+        .list(&prefix)
+        .await?
+        .into_iter()
+        .filter_map(|object| {
+            let name = object.key.strip_prefix(&prefix)?;
+            let (offset, file_name) = name.split_once('-')?;
+            Some(ArchivedSnapshot {
+                wal_offset: offset.parse().ok()?,
+                file_name: file_name.to_string(),
+                object,
+            })
+        })
+        .collect())
+}
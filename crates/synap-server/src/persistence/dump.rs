@@ -0,0 +1,471 @@
+//! Portable dump files (`GET /snapshot/export`, `POST /snapshot/import`).
+//!
+//! A snapshot in the data directory is only meaningful next to the WAL it
+//! was taken with. A dump stands alone: every key of every persisted store as
+//! a replayable [`Operation`], TTLs and queue messages included, so it can
+//! seed another instance or sit in cold storage. Bitmaps are in it even though
+//! snapshots and the WAL leave them out. What a dump does not hold is the
+//! state that only lives in memory: time series, locks and rate limiters.
+//!
+//! Layout: the `SYNAPDMP` magic, a version byte, a bincode [`DumpHeader`],
+//! the bincode operation list, then a little-endian CRC32 of everything
+//! before it.
+
+use super::apply::{StoreRefs, apply_operation};
+use super::types::{Operation, PersistenceError, Result};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use tracing::info;
+
+const DUMP_MAGIC: &[u8; 8] = b"SYNAPDMP";
+/// Version 2 added bitmaps; version 1 dumps still import
+const DUMP_VERSION: u8 = 2;
+
+/// What a dump holds, written ahead of its operations
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DumpHeader {
+    /// Unix seconds at export; TTLs count down from here
+    pub created_at: u64,
+    /// Version of the server that wrote the dump
+    pub server_version: String,
+    /// KV keys, hashes, lists, sets, sorted sets, HyperLogLogs, Bloom filters
+    /// and bitmaps
+    pub keys: usize,
+    pub queues: usize,
+    pub queue_messages: usize,
+    pub streams: usize,
+    pub stream_events: usize,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportSummary {
+    #[serde(flatten)]
+    pub header: DumpHeader,
+    /// Keys whose TTL ran out between export and import, and were skipped
+    pub expired_keys: usize,
+    pub operations_applied: usize,
+}
+
+/// Every key in `stores` as operations that rebuild it, with a header
+/// counting them. Stores that are absent contribute nothing.
+///
+/// Collections are written whole (`HashLoad`, `ListLoad`, ...), so replaying
+/// into an instance that already holds a key replaces it rather than merging
+/// into it. TTLs are the seconds each key had left.
+pub async fn store_operations(stores: StoreRefs<'_>) -> Result<(Vec<Operation>, DumpHeader)> {
+    let StoreRefs {
+        kv_store,
+        hash_store,
+        list_store,
+        set_store,
+        sorted_set_store,
+        hyperloglog_store,
+        bloom_store,
+        bitmap_store,
+        queue_manager,
+        stream_manager,
+    } = stores;
+    let mut header = DumpHeader {
+        created_at: unix_now(),
+        server_version: env!("CARGO_PKG_VERSION").to_string(),
+        ..Default::default()
+    };
+    let mut operations = Vec::new();
+
+    let keys = kv_store
+        .keys()
+        .await
+        .map_err(|e| PersistenceError::RecoveryFailed(e.to_string()))?;
+    for key in keys {
+        if let Ok(Some(value)) = kv_store.get_shared(&key).await {
+            // Remaining seconds, not the original TTL
+            let ttl = kv_store.ttl(&key).await.ok().flatten();
+            header.keys += 1;
+            operations.push(Operation::KVSet {
+                key,
+                value: Bytes::from_owner(value),
+                ttl,
+            });
+        }
+    }
+
+    if let Some(hs) = hash_store {
+        let hashes = hs.dump();
+        header.keys += hashes.len();
+        for (key, fields) in hashes {
+            let ttl = hs.ttl(&key);
+            operations.push(Operation::HashLoad {
+                fields: fields.into_iter().map(|(f, v)| (f, v.into())).collect(),
+                key,
+                ttl,
+            });
+        }
+    }
+
+    if let Some(ls) = list_store {
+        let lists = ls.dump();
+        header.keys += lists.len();
+        for (key, list) in lists {
+            operations.push(Operation::ListLoad {
+                key,
+                ttl: list.ttl(),
+                values: list.into_elements().into_iter().map(Bytes::from).collect(),
+            });
+        }
+    }
+
+    if let Some(ss) = set_store {
+        let sets = ss.dump();
+        header.keys += sets.len();
+        for (key, set) in sets {
+            operations.push(Operation::SetLoad {
+                key,
+                ttl: set.ttl(),
+                members: set.into_members().into_iter().map(Bytes::from).collect(),
+            });
+        }
+    }
+
+    if let Some(zs) = sorted_set_store {
+        let sorted_sets = zs.dump();
+        header.keys += sorted_sets.len();
+        for (key, members) in sorted_sets {
+            let ttl = zs.ttl(&key);
+            operations.push(Operation::SortedSetLoad {
+                members: members.into_iter().map(|(m, s)| (m.into(), s)).collect(),
+                key,
+                ttl,
+            });
+        }
+    }
+
+    if let Some(hll) = hyperloglog_store {
+        let hlls = hll.dump();
+        header.keys += hlls.len();
+        for (key, registers) in hlls {
+            let ttl = hll.ttl(&key);
+            operations.push(Operation::HyperLogLogLoad {
+                key,
+                registers: registers.into(),
+                ttl,
+            });
+        }
+    }
+
+    if let Some(bf) = bloom_store {
+        let filters = bf.dump();
+        header.keys += filters.len();
+        for (key, filter) in filters {
+            operations.push(Operation::BloomLoad { key, filter });
+        }
+    }
+
+    if let Some(bm) = bitmap_store {
+        let bitmaps = bm.dump();
+        header.keys += bitmaps.len();
+        for (key, data) in bitmaps {
+            let ttl = bm.ttl(&key);
+            operations.push(Operation::BitmapLoad {
+                key,
+                data: data.into(),
+                ttl,
+            });
+        }
+    }
+
+    if let Some(qm) = queue_manager {
+        let queues = qm
+            .dump()
+            .await
+            .map_err(|e| PersistenceError::RecoveryFailed(e.to_string()))?;
+        header.queues = queues.len();
+        for (queue, messages) in queues {
+            header.queue_messages += messages.len();
+            for message in messages {
+                operations.push(Operation::QueuePublish {
+                    queue: queue.clone(),
+                    message,
+                });
+            }
+        }
+    }
+
+    if let Some(sm) = stream_manager {
+        let rooms = sm.get_all_events().await;
+        header.streams = rooms.len();
+        for (room, events) in rooms {
+            header.stream_events += events.len();
+            for event in events {
+                operations.push(Operation::StreamPublish {
+                    room: room.clone(),
+                    event_type: event.event,
+                    payload: event.data.into(),
+                });
+            }
+        }
+    }
+
+    Ok((operations, header))
+}
+
+/// Write everything in `stores` to a dump
+pub async fn export(stores: StoreRefs<'_>) -> Result<Vec<u8>> {
+    let (operations, header) = store_operations(stores).await?;
+    let out = encode_dump(&header, &operations)?;
+    info!(
+        "Exported dump: {} keys, {} queues, {} streams, {} bytes",
+        header.keys,
+        header.queues,
+        header.streams,
+        out.len()
+    );
+    Ok(out)
+}
+
+/// Read the header of a dump without applying it
+pub fn read_header(dump: &[u8]) -> Result<DumpHeader> {
+    Ok(decode_dump(dump)?.0)
+}
+
+/// Replay a dump into `stores`. Keys already present are replaced, whatever
+/// they held. TTLs resume from where they stood at export, and keys that
+/// expired in between are skipped.
+pub async fn import(stores: StoreRefs<'_>, dump: &[u8]) -> Result<ImportSummary> {
+    let (header, operations) = decode_dump(dump)?;
+    let elapsed = unix_now().saturating_sub(header.created_at);
+
+    let mut expired_keys = 0;
+    let mut operations_applied = 0;
+    for mut operation in operations {
+        if let Some(ttl) = ttl_mut(&mut operation) {
+            if *ttl <= elapsed {
+                expired_keys += 1;
+                continue;
+            }
+            *ttl -= elapsed;
+        }
+        apply_operation(operation, stores)
+            .await
+            .map_err(|e| PersistenceError::RecoveryFailed(e.to_string()))?;
+        operations_applied += 1;
+    }
+
+    info!(
+        "Imported dump from {} (v{}): {} operations, {} expired keys skipped",
+        header.created_at, header.server_version, operations_applied, expired_keys
+    );
+    Ok(ImportSummary {
+        header,
+        expired_keys,
+        operations_applied,
+    })
+}
+
+/// Remaining TTL carried by a key-writing operation, if it has one
+fn ttl_mut(operation: &mut Operation) -> Option<&mut u64> {
+    match operation {
+        Operation::KVSet { ttl, .. }
+        | Operation::HashLoad { ttl, .. }
+        | Operation::ListLoad { ttl, .. }
+        | Operation::SetLoad { ttl, .. }
+        | Operation::SortedSetLoad { ttl, .. }
+        | Operation::HyperLogLogLoad { ttl, .. }
+        | Operation::BitmapLoad { ttl, .. } => ttl.as_mut(),
+        _ => None,
+    }
+}
+
+fn encode_dump(header: &DumpHeader, operations: &[Operation]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(DUMP_MAGIC.len() + 1);
+    out.extend_from_slice(DUMP_MAGIC);
+    out.push(DUMP_VERSION);
+    out.extend(encode(header)?);
+    out.extend(encode(&operations)?);
+    let checksum = crc32fast::hash(&out);
+    out.extend_from_slice(&checksum.to_le_bytes());
+    Ok(out)
+}
+
+fn decode_dump(dump: &[u8]) -> Result<(DumpHeader, Vec<Operation>)> {
+    let not_a_dump = || PersistenceError::SerializationError("not a Synap dump file".to_string());
+    let body_len = dump.len().checked_sub(4).ok_or_else(not_a_dump)?;
+    let (body, trailer) = dump.split_at(body_len);
+    let Some(rest) = body.strip_prefix(DUMP_MAGIC.as_slice()) else {
+        return Err(not_a_dump());
+    };
+    let (&version, rest) = rest.split_first().ok_or_else(not_a_dump)?;
+    if !(1..=DUMP_VERSION).contains(&version) {
+        return Err(PersistenceError::SerializationError(format!(
+            "unsupported dump version {version}"
+        )));
+    }
+
+    let stored = u32::from_le_bytes(trailer.try_into().unwrap_or_default());
+    let computed = crc32fast::hash(body);
+    if stored != computed {
+        return Err(PersistenceError::ChecksumMismatch {
+            expected: stored as u64,
+            actual: computed as u64,
+        });
+    }
+
+    let (header, read): (DumpHeader, _) = decode(rest)?;
+    let (operations, _): (Vec<Operation>, _) = decode(&rest[read..])?;
+    Ok((header, operations))
+}
+
+fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    bincode::serde::encode_to_vec(value, bincode::config::legacy())
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))
+}
+
+fn decode<T: serde::de::DeserializeOwned>(data: &[u8]) -> Result<(T, usize)> {
+    bincode::serde::decode_from_slice(data, bincode::config::legacy())
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        BitmapStore, HashStore, KVConfig, KVStore, ListStore, QueueConfig, QueueManager,
+    };
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let kv = KVStore::new(KVConfig::default());
+        let hashes = HashStore::new();
+        let lists = ListStore::new();
+        let queues = QueueManager::new(QueueConfig::default());
+        kv.set("plain", b"v1".to_vec(), None).await.unwrap();
+        kv.set("session", b"v2".to_vec(), Some(3600)).await.unwrap();
+        hashes.hset("user:1", "name", b"ada".to_vec()).unwrap();
+        hashes.restore(
+            "session:1",
+            [("token".to_string(), b"t".to_vec())].into(),
+            Some(600),
+        );
+        lists
+            .rpush("jobs", vec![b"a".to_vec(), b"b".to_vec()], false)
+            .unwrap();
+        let bitmaps = BitmapStore::new();
+        bitmaps.setbit("seen", 9, 1).unwrap();
+        queues.create_queue("emails", None).await.unwrap();
+        queues
+            .publish("emails", b"hello".to_vec(), Some(7), None)
+            .await
+            .unwrap();
+
+        let source = StoreRefs {
+            hash_store: Some(&hashes),
+            list_store: Some(&lists),
+            bitmap_store: Some(&bitmaps),
+            queue_manager: Some(&queues),
+            ..StoreRefs::kv_only(&kv)
+        };
+        let dump = export(source).await.unwrap();
+        let header = read_header(&dump).unwrap();
+        assert_eq!(header.keys, 6);
+        assert_eq!(header.queues, 1);
+        assert_eq!(header.queue_messages, 1);
+
+        // The target already holds other versions of the list and the hash
+        let kv2 = KVStore::new(KVConfig::default());
+        let hashes2 = HashStore::new();
+        let lists2 = ListStore::new();
+        let bitmaps2 = BitmapStore::new();
+        let queues2 = QueueManager::new(QueueConfig::default());
+        lists2
+            .rpush("jobs", vec![b"stale".to_vec()], false)
+            .unwrap();
+        hashes2.hset("user:1", "stale", b"x".to_vec()).unwrap();
+        let target = StoreRefs {
+            hash_store: Some(&hashes2),
+            list_store: Some(&lists2),
+            bitmap_store: Some(&bitmaps2),
+            queue_manager: Some(&queues2),
+            ..StoreRefs::kv_only(&kv2)
+        };
+        let summary = import(target, &dump).await.unwrap();
+        assert_eq!(summary.header, header);
+        assert_eq!(summary.expired_keys, 0);
+
+        assert_eq!(kv2.get("plain").await.unwrap(), Some(b"v1".to_vec()));
+        let ttl = kv2.ttl("session").await.unwrap().unwrap();
+        assert!(ttl > 3590 && ttl <= 3600);
+        assert_eq!(
+            hashes2.hgetall("user:1").unwrap(),
+            [("name".to_string(), b"ada".to_vec())].into()
+        );
+        let ttl = hashes2.ttl("session:1").unwrap();
+        assert!(ttl > 590 && ttl <= 600);
+        assert_eq!(
+            lists2.lrange("jobs", 0, -1).unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+        assert_eq!(bitmaps2.getbit("seen", 9).unwrap(), 1);
+        assert_eq!(bitmaps2.getbit("seen", 8).unwrap(), 0);
+        let message = queues2.consume("emails", "c1").await.unwrap().unwrap();
+        assert_eq!(message.payload.as_ref(), b"hello");
+        assert_eq!(message.priority, 7);
+    }
+
+    #[tokio::test]
+    async fn test_import_ages_ttls_by_time_since_export() {
+        let header = DumpHeader {
+            created_at: unix_now() - 100,
+            ..Default::default()
+        };
+        let kv_set = |key: &str, ttl| Operation::KVSet {
+            key: key.to_string(),
+            value: Bytes::from_static(b"v"),
+            ttl,
+        };
+        let dump = encode_dump(
+            &header,
+            &[
+                kv_set("gone", Some(60)),
+                kv_set("kept", Some(500)),
+                kv_set("forever", None),
+            ],
+        )
+        .unwrap();
+
+        let kv = KVStore::new(KVConfig::default());
+        let summary = import(StoreRefs::kv_only(&kv), &dump).await.unwrap();
+        assert_eq!(summary.expired_keys, 1);
+        assert_eq!(summary.operations_applied, 2);
+        assert!(!kv.exists("gone").await.unwrap());
+        let ttl = kv.ttl("kept").await.unwrap().unwrap();
+        assert!(ttl > 390 && ttl <= 400);
+        assert_eq!(kv.ttl("forever").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_corrupt_dumps() {
+        let kv = KVStore::new(KVConfig::default());
+        kv.set("k", b"v".to_vec(), None).await.unwrap();
+        let mut dump = export(StoreRefs::kv_only(&kv)).await.unwrap();
+
+        let middle = dump.len() / 2;
+        dump[middle] ^= 0xFF;
+        assert!(matches!(
+            import(StoreRefs::kv_only(&kv), &dump).await,
+            Err(PersistenceError::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            read_header(b"SYNAP004 snapshot"),
+            Err(PersistenceError::SerializationError(_))
+        ));
+        assert!(read_header(&[]).is_err());
+    }
+}
@@ -0,0 +1,583 @@
+//! Offline consistency check and repair of the persistence files
+//! (`synap-server --fsck [--repair]`).
+//!
+//! Checks, without starting the server:
+//! - every snapshot's header, sections and trailing checksum, incremental
+//!   snapshots included
+//! - the WAL and the subscription log: cut-off tails, corrupt entries and
+//!   offsets that go backwards
+//! - the newest good snapshot against the WAL: a snapshot that expects WAL
+//!   entries that don't exist, or entries missing between the two
+//! - leftover files: `*.new` files from interrupted rewrites and snapshots
+//!   past `max_snapshots`
+//! - the recovered state: keys that exist as more than one type, and queue
+//!   ACK/NACK entries for messages that were never published
+//!
+//! Repair copies or moves every file it changes into `fsck-backup-<unix
+//! time>` next to the WAL first. Corrupt snapshots and leftovers are moved
+//! aside, and logs are rewritten up to their last readable entry without
+//! dangling queue entries. Type conflicts, offset problems and replay
+//! failures are only reported: fixing them takes an operator's decision.
+
+use super::restore;
+use super::snapshot::SnapshotManager;
+use super::subscriptions::SubscriptionRegistry;
+use super::types::{Operation, PersistenceConfig, Result, WALEntry};
+use crate::core::{KVConfig, QueueConfig};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+/// Log frame header: entry size (u64) + CRC32 (u32)
+const FRAME_HEADER_LEN: u64 = 12;
+
+/// What an [`FsckIssue`] is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsckIssueKind {
+    /// A snapshot that fails to load
+    CorruptSnapshot,
+    /// A log entry with a bad checksum or that does not decode
+    CorruptLogEntry,
+    /// A log that ends in the middle of an entry
+    TornLogTail,
+    /// Log entries whose offset is not above the previous one
+    OffsetOutOfOrder,
+    /// The newest snapshot resumes past the end of the WAL
+    SnapshotAheadOfWal,
+    /// WAL entries missing between the newest snapshot and the WAL
+    WalGap,
+    /// A leftover file recovery does not use
+    OrphanedFile,
+    /// A key that exists as more than one type
+    TypeConflict,
+    /// A queue ACK/NACK for a message that was never published
+    DanglingQueueEntry,
+    /// Replaying the WAL onto the snapshot fails
+    ReplayFailed,
+}
+
+impl FsckIssueKind {
+    /// Whether `--repair` fixes this kind of issue
+    pub fn repairable(self) -> bool {
+        matches!(
+            self,
+            Self::CorruptSnapshot
+                | Self::CorruptLogEntry
+                | Self::TornLogTail
+                | Self::OrphanedFile
+                | Self::DanglingQueueEntry
+        )
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::CorruptSnapshot => "corrupt_snapshot",
+            Self::CorruptLogEntry => "corrupt_log_entry",
+            Self::TornLogTail => "torn_log_tail",
+            Self::OffsetOutOfOrder => "offset_out_of_order",
+            Self::SnapshotAheadOfWal => "snapshot_ahead_of_wal",
+            Self::WalGap => "wal_gap",
+            Self::OrphanedFile => "orphaned_file",
+            Self::TypeConflict => "type_conflict",
+            Self::DanglingQueueEntry => "dangling_queue_entry",
+            Self::ReplayFailed => "replay_failed",
+        }
+    }
+}
+
+/// One problem found by [`fsck`]
+#[derive(Debug, Clone, Serialize)]
+pub struct FsckIssue {
+    pub kind: FsckIssueKind,
+    pub path: PathBuf,
+    pub detail: String,
+}
+
+/// Result of [`fsck`]
+#[derive(Debug, Default, Serialize)]
+pub struct FsckReport {
+    pub snapshots_checked: usize,
+    pub log_entries_checked: u64,
+    pub keys_checked: usize,
+    pub issues: Vec<FsckIssue>,
+    /// Where the originals of repaired files were kept, if anything was repaired
+    pub backup_dir: Option<PathBuf>,
+    /// What the repair did
+    pub repairs: Vec<String>,
+}
+
+impl FsckReport {
+    /// True if an issue remains: nothing was repaired, or it can't be
+    pub fn needs_attention(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| self.backup_dir.is_none() || !issue.kind.repairable())
+    }
+
+    fn issue(&mut self, kind: FsckIssueKind, path: &Path, detail: String) {
+        self.issues.push(FsckIssue {
+            kind,
+            path: path.to_path_buf(),
+            detail,
+        });
+    }
+}
+
+impl fmt::Display for FsckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked {} snapshot(s), {} log entries and {} keys",
+            self.snapshots_checked, self.log_entries_checked, self.keys_checked
+        )?;
+        if self.issues.is_empty() {
+            return write!(f, "No issues found");
+        }
+        for issue in &self.issues {
+            writeln!(
+                f,
+                "[{}] {}: {}",
+                issue.kind.as_str(),
+                issue.path.display(),
+                issue.detail
+            )?;
+        }
+        match &self.backup_dir {
+            Some(dir) => {
+                writeln!(f, "Repaired (originals kept in {}):", dir.display())?;
+                for repair in &self.repairs {
+                    writeln!(f, "  {repair}")?;
+                }
+            }
+            None if self.issues.iter().any(|i| i.kind.repairable()) => {
+                writeln!(f, "Run with --repair to fix the repairable issues")?;
+            }
+            None => {}
+        }
+        let unrepairable = self.issues.iter().filter(|i| !i.kind.repairable()).count();
+        if unrepairable > 0 {
+            write!(f, "{unrepairable} issue(s) need manual attention")?;
+        }
+        Ok(())
+    }
+}
+
+/// A readable frame of a log
+struct Frame {
+    len: u64,
+    offset: u64,
+}
+
+/// The readable prefix of a log file
+struct LogScan {
+    path: PathBuf,
+    frames: Vec<Frame>,
+    entries: Vec<WALEntry>,
+    /// Bytes after the last readable frame
+    unreadable: u64,
+    /// Offsets of frames a repair drops
+    drop: HashSet<u64>,
+}
+
+impl LogScan {
+    fn needs_rewrite(&self) -> bool {
+        self.unreadable > 0 || !self.drop.is_empty()
+    }
+}
+
+/// Check the persistence files described by `config`, and repair what can be
+/// repaired when `repair` is set. The server must be stopped: the files are
+/// read directly and rewritten in place.
+pub async fn fsck(
+    config: &PersistenceConfig,
+    kv_config: KVConfig,
+    queue_config: QueueConfig,
+    repair: bool,
+) -> Result<FsckReport> {
+    let mut report = FsckReport::default();
+
+    // Snapshots, oldest first: recovery loads the newest, so a corrupt newest
+    // snapshot stops it even when an older one is good
+    let snapshots = SnapshotManager::new(config.snapshot.clone());
+    let paths = snapshots.list_snapshots().await?;
+    let mut latest = None;
+    let mut corrupt = Vec::new();
+    for path in &paths {
+        report.snapshots_checked += 1;
+        match snapshots.load(path).await {
+            Ok(snapshot) => latest = Some(snapshot),
+            Err(e) => {
+                report.issue(FsckIssueKind::CorruptSnapshot, path, e.to_string());
+                corrupt.push(path.clone());
+            }
+        }
+    }
+
+    // Increments are layered onto the newest snapshot, as recovery does
+    for path in snapshots.list_increments().await? {
+        report.snapshots_checked += 1;
+        if let Err(e) = snapshots.load_increment(&path).await {
+            report.issue(FsckIssueKind::CorruptSnapshot, &path, e.to_string());
+            corrupt.push(path);
+        }
+    }
+    if let Some(snapshot) = latest.as_mut() {
+        snapshots.apply_increments(snapshot).await?;
+    }
+
+    let mut orphans = Vec::new();
+    let expired = paths.len().saturating_sub(config.snapshot.max_snapshots);
+    for path in paths[..expired].iter().filter(|p| !corrupt.contains(p)) {
+        report.issue(
+            FsckIssueKind::OrphanedFile,
+            path,
+            format!(
+                "snapshot past the max_snapshots retention ({})",
+                config.snapshot.max_snapshots
+            ),
+        );
+        orphans.push(path.clone());
+    }
+    let mut dirs = vec![config.snapshot.directory.clone()];
+    if let Some(dir) = config.wal.path.parent()
+        && !dirs.contains(&dir.to_path_buf())
+    {
+        dirs.push(dir.to_path_buf());
+    }
+    for dir in dirs {
+        for path in leftover_rewrites(&dir).await? {
+            report.issue(
+                FsckIssueKind::OrphanedFile,
+                &path,
+                "leftover from an interrupted log rewrite".to_string(),
+            );
+            orphans.push(path);
+        }
+    }
+
+    let mut wal = scan_log(&config.wal.path, &mut report).await?;
+    let subscriptions = scan_log(&SubscriptionRegistry::log_path(&config.wal), &mut report).await?;
+
+    if let Some(wal) = wal.as_mut() {
+        check_offsets(wal, latest.as_ref().map(|s| s.wal_offset), &mut report);
+        find_dangling_queue_entries(wal, latest.as_ref(), &mut report);
+    }
+
+    // Replay what recovery would, from the readable part of the WAL
+    let from = latest.as_ref().map_or(0, |s| s.wal_offset);
+    let entries = wal
+        .as_mut()
+        .map(|wal| std::mem::take(&mut wal.entries))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| entry.offset >= from)
+        .collect();
+    match restore(latest, entries, kv_config, queue_config).await {
+        Ok((kv, hashes, lists, sets, sorted_sets, hyperloglogs, blooms, _, _)) => {
+            let mut types: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+            for key in kv.keys().await? {
+                types.entry(key).or_default().push("string");
+            }
+            let collections = [
+                ("hash", hashes.map(|s| s.dump().into_keys().collect())),
+                ("list", lists.map(|s| s.dump().into_keys().collect())),
+                ("set", sets.map(|s| s.dump().into_keys().collect())),
+                (
+                    "sorted set",
+                    sorted_sets.map(|s| s.dump().into_keys().collect()),
+                ),
+                (
+                    "hyperloglog",
+                    hyperloglogs.map(|s| s.dump().into_keys().collect()),
+                ),
+                (
+                    "bloom filter",
+                    blooms.map(|s| s.dump().into_keys().collect()),
+                ),
+            ];
+            for (name, keys) in collections {
+                for key in keys.unwrap_or_else(Vec::new) {
+                    types.entry(key).or_default().push(name);
+                }
+            }
+            report.keys_checked = types.len();
+            for (key, names) in types.iter().filter(|(_, names)| names.len() > 1) {
+                report.issue(
+                    FsckIssueKind::TypeConflict,
+                    &config.wal.path,
+                    format!("key '{key}' exists as a {}", names.join(" and a ")),
+                );
+            }
+        }
+        Err(e) => report.issue(
+            FsckIssueKind::ReplayFailed,
+            &config.wal.path,
+            format!("replaying the WAL onto the snapshot fails: {e}"),
+        ),
+    }
+
+    if repair && report.issues.iter().any(|i| i.kind.repairable()) {
+        let secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let backup = config
+            .wal
+            .path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join(format!("fsck-backup-{secs}"));
+        tokio::fs::create_dir_all(&backup).await?;
+
+        for path in corrupt.iter().chain(&orphans) {
+            move_into(path, &backup).await?;
+            report
+                .repairs
+                .push(format!("moved {} aside", path.display()));
+        }
+        for log in [wal, subscriptions].into_iter().flatten() {
+            if log.needs_rewrite() {
+                let kept = rewrite_log(&log, &backup).await?;
+                report.repairs.push(format!(
+                    "rewrote {} with {kept} of its {} readable entries",
+                    log.path.display(),
+                    log.frames.len()
+                ));
+            }
+        }
+        report.backup_dir = Some(backup);
+    }
+
+    Ok(report)
+}
+
+/// Read a log up to its first unreadable entry. `None` if it doesn't exist.
+async fn scan_log(path: &Path, report: &mut FsckReport) -> Result<Option<LogScan>> {
+    let len = match tokio::fs::metadata(path).await {
+        Ok(meta) => meta.len(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut reader = BufReader::new(File::open(path).await?);
+    let mut scan = LogScan {
+        path: path.to_path_buf(),
+        frames: Vec::new(),
+        entries: Vec::new(),
+        unreadable: 0,
+        drop: HashSet::new(),
+    };
+
+    let mut pos = 0u64;
+    let mut backwards = Vec::new();
+    while pos < len {
+        let remaining = len - pos;
+        let problem = if remaining < FRAME_HEADER_LEN {
+            Some((FsckIssueKind::TornLogTail, "entry header is cut off"))
+        } else {
+            let size = reader.read_u64().await?;
+            let checksum = reader.read_u32().await?;
+            if size > remaining - FRAME_HEADER_LEN {
+                Some((FsckIssueKind::TornLogTail, "entry is cut off"))
+            } else {
+                let mut data = vec![0u8; size as usize];
+                reader.read_exact(&mut data).await?;
+                if crc32fast::hash(&data) != checksum {
+                    Some((FsckIssueKind::CorruptLogEntry, "entry checksum mismatch"))
+                } else if let Ok(entry) = WALEntry::decode(&data) {
+                    if let Some(prev) = scan.frames.last()
+                        && entry.offset <= prev.offset
+                    {
+                        backwards.push((pos, prev.offset, entry.offset));
+                    }
+                    scan.frames.push(Frame {
+                        len: FRAME_HEADER_LEN + size,
+                        offset: entry.offset,
+                    });
+                    scan.entries.push(entry);
+                    pos += FRAME_HEADER_LEN + size;
+                    None
+                } else {
+                    Some((FsckIssueKind::CorruptLogEntry, "entry does not decode"))
+                }
+            }
+        };
+        if let Some((kind, what)) = problem {
+            scan.unreadable = remaining;
+            report.issue(
+                kind,
+                path,
+                format!(
+                    "{what} at byte {pos}; the last {remaining} bytes ({} entries read before it) \
+                     can't be recovered",
+                    scan.frames.len()
+                ),
+            );
+            break;
+        }
+    }
+
+    if let Some((pos, prev, offset)) = backwards.first() {
+        report.issue(
+            FsckIssueKind::OffsetOutOfOrder,
+            path,
+            format!(
+                "{} entries have an offset at or below the one before (first at byte {pos}: \
+                 {offset} after {prev})",
+                backwards.len()
+            ),
+        );
+    }
+    report.log_entries_checked += scan.frames.len() as u64;
+    Ok(Some(scan))
+}
+
+/// Readable entries of a log and the problems that ended the read early, for
+/// [`inspect`](super::inspect). `None` if the log doesn't exist.
+pub(super) async fn read_log(path: &Path) -> Result<Option<(Vec<WALEntry>, Vec<FsckIssue>)>> {
+    let mut report = FsckReport::default();
+    let scan = scan_log(path, &mut report).await?;
+    Ok(scan.map(|scan| (scan.entries, report.issues)))
+}
+
+/// Compare the newest good snapshot's resume point with the WAL's range
+fn check_offsets(wal: &LogScan, snapshot_offset: Option<u64>, report: &mut FsckReport) {
+    // Offsets start at 1; a server opening this WAL logs `next` next
+    let next = wal
+        .frames
+        .iter()
+        .map(|f| f.offset)
+        .max()
+        .map_or(1, |max| max + 1);
+    if let Some(resume) = snapshot_offset
+        && resume > next
+    {
+        report.issue(
+            FsckIssueKind::SnapshotAheadOfWal,
+            &wal.path,
+            format!(
+                "the newest snapshot resumes at offset {resume} but the WAL ends before {next}; \
+                 entries logged from now on would be skipped by recovery until the next snapshot"
+            ),
+        );
+        return;
+    }
+    let resume = snapshot_offset.unwrap_or(0).max(1);
+    if let Some(first) = wal.frames.first()
+        && first.offset > resume
+    {
+        report.issue(
+            FsckIssueKind::WalGap,
+            &wal.path,
+            format!(
+                "WAL starts at offset {} but recovery needs entries from {resume}",
+                first.offset
+            ),
+        );
+    }
+}
+
+/// ACK/NACK entries whose message is neither in the snapshot nor published
+/// earlier in the WAL
+fn find_dangling_queue_entries(
+    wal: &mut LogScan,
+    snapshot: Option<&super::types::Snapshot>,
+    report: &mut FsckReport,
+) {
+    let mut published: HashSet<&str> = snapshot
+        .map(|s| {
+            s.queue_data
+                .values()
+                .flatten()
+                .map(|m| m.id.as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut dangling = Vec::new();
+    for entry in &wal.entries {
+        match &entry.operation {
+            Operation::QueuePublish { message, .. } => {
+                published.insert(message.id.as_str());
+            }
+            Operation::QueueAck { queue, message_id }
+            | Operation::QueueNack {
+                queue, message_id, ..
+            } if !published.contains(message_id.as_str()) => {
+                dangling.push((entry.offset, queue, message_id));
+            }
+            _ => {}
+        }
+    }
+    if let Some((offset, queue, message_id)) = dangling.first() {
+        report.issue(
+            FsckIssueKind::DanglingQueueEntry,
+            &wal.path,
+            format!(
+                "{} queue ACK/NACK entries for messages that were never published (first at \
+                 offset {offset}: message '{message_id}' in queue '{queue}')",
+                dangling.len()
+            ),
+        );
+    }
+    wal.drop = dangling.into_iter().map(|(offset, _, _)| offset).collect();
+}
+
+/// `*.new` files left by an interrupted log rewrite
+async fn leftover_rewrites(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(found),
+        Err(e) => return Err(e.into()),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("new") {
+            found.push(path);
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// Move a file into the backup directory
+async fn move_into(path: &Path, backup: &Path) -> Result<()> {
+    let target = backup.join(path.file_name().unwrap_or_default());
+    if tokio::fs::rename(path, &target).await.is_err() {
+        // Different filesystem
+        tokio::fs::copy(path, &target).await?;
+        tokio::fs::remove_file(path).await?;
+    }
+    Ok(())
+}
+
+/// Back up a log, then rewrite it with its readable frames minus the
+/// dropped ones. Returns the number of frames kept.
+async fn rewrite_log(log: &LogScan, backup: &Path) -> Result<usize> {
+    let file_name = log.path.file_name().unwrap_or_default();
+    tokio::fs::copy(&log.path, backup.join(file_name)).await?;
+
+    let mut new_name = file_name.to_os_string();
+    new_name.push(".new");
+    let new_path = log.path.with_file_name(new_name);
+    let mut reader = BufReader::new(File::open(&log.path).await?);
+    let mut writer = BufWriter::new(File::create(&new_path).await?);
+    let mut kept = 0;
+    for frame in &log.frames {
+        let mut bytes = vec![0u8; frame.len as usize];
+        reader.read_exact(&mut bytes).await?;
+        if !log.drop.contains(&frame.offset) {
+            writer.write_all(&bytes).await?;
+            kept += 1;
+        }
+    }
+    writer.flush().await?;
+    writer.get_ref().sync_all().await?;
+    drop(writer);
+    tokio::fs::rename(&new_path, &log.path).await?;
+    Ok(kept)
+}
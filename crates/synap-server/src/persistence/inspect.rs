@@ -0,0 +1,680 @@
+//! Offline decoding of the WAL and snapshots for incident forensics
+//! (`synap-cli WAL DUMP` and `synap-cli SNAPSHOT DUMP`).
+//!
+//! Nothing here needs a running server or changes a file. A log is read up
+//! to its first unreadable entry, the way `--fsck` reads it, and a snapshot
+//! is loaded and checksum-verified. [`InspectFilter`] narrows the records by
+//! key prefix, time range and operation; [`InspectStats`] sums up the
+//! operation mix and the largest writes.
+
+use super::fsck::read_log;
+use super::snapshot::SnapshotManager;
+use super::types::{Operation, Result, Snapshot, SnapshotConfig, WALEntry};
+use bytes::Bytes;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Value bytes shown in a record's detail
+const PREVIEW_BYTES: usize = 48;
+
+/// Which records a dump keeps
+#[derive(Debug, Clone, Default)]
+pub struct InspectFilter {
+    /// Keep records touching a key, queue or room with this prefix
+    pub key_prefix: Option<String>,
+    /// Keep records written at or after this Unix time (seconds)
+    pub since: Option<u64>,
+    /// Keep records written at or before this Unix time (seconds)
+    pub until: Option<u64>,
+    /// Keep these operations, by name (`kv_set`) or datatype (`kv`, `hash`,
+    /// `sorted_set`); empty keeps them all
+    pub operations: Vec<String>,
+}
+
+impl InspectFilter {
+    fn matches(&self, record: &InspectRecord) -> bool {
+        if let Some(ref prefix) = self.key_prefix
+            && !record
+                .keys
+                .iter()
+                .any(|key| key.starts_with(prefix.as_str()))
+        {
+            return false;
+        }
+        if self.since.is_some_and(|since| record.timestamp < since)
+            || self.until.is_some_and(|until| record.timestamp > until)
+        {
+            return false;
+        }
+        self.operations.is_empty()
+            || self
+                .operations
+                .iter()
+                .any(|op| op == record.operation || op == record.datatype)
+    }
+}
+
+/// One decoded WAL entry or snapshot key
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectRecord {
+    /// WAL offset; `None` for a snapshot key
+    pub offset: Option<u64>,
+    /// Unix time (seconds) of the write, or of the snapshot
+    pub timestamp: u64,
+    /// Operation name; for a snapshot key, its datatype
+    pub operation: &'static str,
+    /// `kv`, `queue`, `stream`, `hash`, `list`, `set`, `sorted_set` or
+    /// `subscription`
+    pub datatype: &'static str,
+    /// Keys, queues or rooms the record touches
+    pub keys: Vec<String>,
+    /// Value, member and payload bytes the record carries
+    pub bytes: usize,
+    /// Short description of the arguments
+    pub detail: String,
+    /// `X-Request-Id` of the request that made the write, when recorded
+    pub request_id: Option<String>,
+}
+
+impl InspectRecord {
+    fn from_entry(entry: WALEntry) -> Self {
+        let (datatype, keys, bytes, detail) = describe(&entry.operation);
+        Self {
+            offset: Some(entry.offset),
+            timestamp: entry.timestamp,
+            operation: entry.operation.name(),
+            datatype,
+            keys,
+            bytes,
+            detail,
+            request_id: entry.request_id,
+        }
+    }
+
+    fn snapshot_key(
+        timestamp: u64,
+        datatype: &'static str,
+        key: &str,
+        bytes: usize,
+        detail: String,
+    ) -> Self {
+        Self {
+            offset: None,
+            timestamp,
+            operation: datatype,
+            datatype,
+            keys: vec![key.to_string()],
+            bytes,
+            detail,
+            request_id: None,
+        }
+    }
+}
+
+impl fmt::Display for InspectRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.offset {
+            Some(offset) => write!(f, "{offset:>10}")?,
+            None => write!(f, "{:>10}", "-")?,
+        }
+        write!(
+            f,
+            "  {}  {:<20} {}  {}B",
+            format_time(self.timestamp),
+            self.operation,
+            self.keys.join(" "),
+            self.bytes
+        )?;
+        if !self.detail.is_empty() {
+            write!(f, "  {}", self.detail)?;
+        }
+        if let Some(ref request_id) = self.request_id {
+            write!(f, "  request_id={request_id}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The records of one WAL or snapshot file that passed an [`InspectFilter`]
+#[derive(Debug, Serialize)]
+pub struct InspectDump {
+    pub path: PathBuf,
+    /// Records decoded, before filtering
+    pub scanned: usize,
+    pub records: Vec<InspectRecord>,
+    /// Why a log could not be read to its end
+    pub problems: Vec<String>,
+}
+
+impl InspectDump {
+    fn new(path: &Path, records: Vec<InspectRecord>, filter: &InspectFilter) -> Self {
+        let scanned = records.len();
+        Self {
+            path: path.to_path_buf(),
+            scanned,
+            records: records.into_iter().filter(|r| filter.matches(r)).collect(),
+            problems: Vec::new(),
+        }
+    }
+
+    /// Operation mix of the kept records and the `top` largest writes
+    pub fn stats(&self, top: usize) -> InspectStats {
+        let mut operations: BTreeMap<&'static str, OperationMix> = BTreeMap::new();
+        for record in &self.records {
+            let mix = operations.entry(record.operation).or_default();
+            mix.count += 1;
+            mix.bytes += record.bytes as u64;
+        }
+        let mut largest: Vec<_> = self.records.iter().filter(|r| r.bytes > 0).collect();
+        largest.sort_by_key(|r| std::cmp::Reverse(r.bytes));
+
+        InspectStats {
+            records: self.records.len(),
+            first_timestamp: self.records.iter().map(|r| r.timestamp).min(),
+            last_timestamp: self.records.iter().map(|r| r.timestamp).max(),
+            operations,
+            largest: largest.into_iter().take(top).cloned().collect(),
+        }
+    }
+
+    /// How many records were kept out of how many, and why a log could not
+    /// be read to its end
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} of {} records from {}",
+            self.records.len(),
+            self.scanned,
+            self.path.display()
+        );
+        for problem in &self.problems {
+            summary.push_str(&format!("\nwarning: {problem}"));
+        }
+        summary
+    }
+}
+
+impl fmt::Display for InspectDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(f, "{record}")?;
+        }
+        f.write_str(&self.summary())
+    }
+}
+
+/// Records and bytes of one operation in [`InspectStats`]
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct OperationMix {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+/// Summary of an [`InspectDump`] (`--stats`)
+#[derive(Debug, Serialize)]
+pub struct InspectStats {
+    pub records: usize,
+    pub first_timestamp: Option<u64>,
+    pub last_timestamp: Option<u64>,
+    /// Per operation name
+    pub operations: BTreeMap<&'static str, OperationMix>,
+    /// Records carrying the most bytes, largest first
+    pub largest: Vec<InspectRecord>,
+}
+
+impl fmt::Display for InspectStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} records", self.records)?;
+        if let (Some(first), Some(last)) = (self.first_timestamp, self.last_timestamp) {
+            write!(f, ", {} .. {}", format_time(first), format_time(last))?;
+        }
+        writeln!(f)?;
+        if self.operations.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            f,
+            "\n{:<20} {:>10} {:>7} {:>14}",
+            "operation", "count", "share", "bytes"
+        )?;
+        for (operation, mix) in &self.operations {
+            writeln!(
+                f,
+                "{:<20} {:>10} {:>6.1}% {:>14}",
+                operation,
+                mix.count,
+                mix.count as f64 * 100.0 / self.records as f64,
+                mix.bytes
+            )?;
+        }
+
+        if !self.largest.is_empty() {
+            writeln!(f, "\nlargest writes:")?;
+            for record in &self.largest {
+                writeln!(f, "{record}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Decode the log at `path` (the WAL or the subscription log) up to its
+/// first unreadable entry, keeping the entries `filter` matches
+pub async fn dump_wal(path: &Path, filter: &InspectFilter) -> Result<InspectDump> {
+    let (entries, issues) = read_log(path).await?.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} does not exist", path.display()),
+        )
+    })?;
+    let records = entries.into_iter().map(InspectRecord::from_entry).collect();
+    let mut dump = InspectDump::new(path, records, filter);
+    dump.problems = issues.into_iter().map(|issue| issue.detail).collect();
+    Ok(dump)
+}
+
+/// Load the snapshot at `path` and list its keys that `filter` matches, one
+/// record per key (per queue or room for queues and streams). Every record
+/// carries the snapshot's own timestamp.
+pub async fn dump_snapshot(path: &Path, filter: &InspectFilter) -> Result<InspectDump> {
+    let snapshot = SnapshotManager::new(SnapshotConfig::default())
+        .load(path)
+        .await?;
+    Ok(InspectDump::new(path, snapshot_records(&snapshot), filter))
+}
+
+/// Parse a time filter bound: Unix seconds or an RFC 3339 timestamp
+pub fn parse_time(text: &str) -> Option<u64> {
+    text.parse().ok().or_else(|| {
+        chrono::DateTime::parse_from_rfc3339(text)
+            .ok()
+            .and_then(|time| u64::try_from(time.timestamp()).ok())
+    })
+}
+
+fn format_time(timestamp: u64) -> String {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map_or_else(
+            || timestamp.to_string(),
+            |time| time.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        )
+}
+
+/// Datatype, keys, carried bytes and argument summary of an operation
+fn describe(operation: &Operation) -> (&'static str, Vec<String>, usize, String) {
+    use Operation::*;
+
+    let one = |key: &String| vec![key.clone()];
+    let two = |source: &String, destination: &String| vec![source.clone(), destination.clone()];
+    let stored = |destination: &String, keys: &[String]| {
+        std::iter::once(destination).chain(keys).cloned().collect()
+    };
+    let total = |values: &[Bytes]| values.iter().map(Bytes::len).sum::<usize>();
+    let side = |left: bool| if left { "left" } else { "right" };
+    let with_ttl = |detail: String, ttl: &Option<u64>| match ttl {
+        Some(ttl) if detail.is_empty() => format!("ttl={ttl}s"),
+        Some(ttl) => format!("{detail} ttl={ttl}s"),
+        None => detail,
+    };
+
+    match operation {
+        KVSet { key, value, ttl } => {
+            let mut detail = preview(value);
+            if let Some(ttl) = ttl {
+                detail.push_str(&format!(" ttl={ttl}s"));
+            }
+            ("kv", one(key), value.len(), detail)
+        }
+        KVDel { keys } => ("kv", keys.clone(), 0, String::new()),
+        KVRename {
+            source,
+            destination,
+        } => ("kv", two(source, destination), 0, String::new()),
+        QueuePublish { queue, message } => (
+            "queue",
+            one(queue),
+            message.payload.len(),
+            format!(
+                "id={} priority={} {}",
+                message.id,
+                message.priority,
+                preview(&message.payload)
+            ),
+        ),
+        QueueAck { queue, message_id } => ("queue", one(queue), 0, format!("id={message_id}")),
+        QueueNack {
+            queue,
+            message_id,
+            requeue,
+        } => (
+            "queue",
+            one(queue),
+            0,
+            format!("id={message_id} requeue={requeue}"),
+        ),
+        StreamPublish {
+            room,
+            event_type,
+            payload,
+        } => (
+            "stream",
+            one(room),
+            payload.len(),
+            format!("{event_type} {}", preview(payload)),
+        ),
+        HashSet { key, field, value } => (
+            "hash",
+            one(key),
+            field.len() + value.len(),
+            format!("{field}={}", preview(value)),
+        ),
+        HashDel { key, fields } => ("hash", one(key), 0, fields.join(" ")),
+        HashIncrBy {
+            key,
+            field,
+            increment,
+        } => ("hash", one(key), 0, format!("{field} += {increment}")),
+        HashIncrByFloat {
+            key,
+            field,
+            increment,
+        } => ("hash", one(key), 0, format!("{field} += {increment}")),
+        ListPush { key, values, left } => (
+            "list",
+            one(key),
+            total(values),
+            format!("{} value(s) on the {}", values.len(), side(*left)),
+        ),
+        ListPop { key, count, left } => (
+            "list",
+            one(key),
+            0,
+            format!("{count} from the {}", side(*left)),
+        ),
+        ListSet { key, index, value } => (
+            "list",
+            one(key),
+            value.len(),
+            format!("[{index}]={}", preview(value)),
+        ),
+        ListTrim { key, start, stop } => ("list", one(key), 0, format!("{start}..={stop}")),
+        ListRem { key, count, value } => {
+            ("list", one(key), 0, format!("{count} x {}", preview(value)))
+        }
+        ListInsert {
+            key,
+            before,
+            pivot,
+            value,
+        } => (
+            "list",
+            one(key),
+            value.len(),
+            format!(
+                "{} {} {}",
+                preview(value),
+                if *before { "before" } else { "after" },
+                preview(pivot)
+            ),
+        ),
+        ListRpoplpush {
+            source,
+            destination,
+        } => ("list", two(source, destination), 0, String::new()),
+        SetAdd { key, members } => (
+            "set",
+            one(key),
+            total(members),
+            format!("{} member(s)", members.len()),
+        ),
+        SetRem { key, members } => ("set", one(key), 0, format!("{} member(s)", members.len())),
+        SetMove {
+            source,
+            destination,
+            member,
+        } => ("set", two(source, destination), 0, preview(member)),
+        SetInterStore { destination, keys }
+        | SetUnionStore { destination, keys }
+        | SetDiffStore { destination, keys } => {
+            ("set", stored(destination, keys), 0, String::new())
+        }
+        ZAdd {
+            key, member, score, ..
+        } => (
+            "sorted_set",
+            one(key),
+            member.len(),
+            format!("{} score={score}", preview(member)),
+        ),
+        ZRem { key, members } => (
+            "sorted_set",
+            one(key),
+            0,
+            format!("{} member(s)", members.len()),
+        ),
+        ZIncrBy {
+            key,
+            member,
+            increment,
+        } => (
+            "sorted_set",
+            one(key),
+            0,
+            format!("{} += {increment}", preview(member)),
+        ),
+        ZRemRangeByRank { key, start, stop } => {
+            ("sorted_set", one(key), 0, format!("rank {start}..={stop}"))
+        }
+        ZRemRangeByScore { key, min, max } => {
+            ("sorted_set", one(key), 0, format!("score {min}..={max}"))
+        }
+        ZInterStore {
+            destination,
+            keys,
+            aggregate,
+            ..
+        }
+        | ZUnionStore {
+            destination,
+            keys,
+            aggregate,
+            ..
+        } => (
+            "sorted_set",
+            stored(destination, keys),
+            0,
+            format!("aggregate={aggregate}"),
+        ),
+        ZDiffStore { destination, keys } => {
+            ("sorted_set", stored(destination, keys), 0, String::new())
+        }
+        HyperLogLogMerge {
+            destination,
+            registers,
+        } => (
+            "hyperloglog",
+            one(destination),
+            registers.len(),
+            String::new(),
+        ),
+        BloomReserve {
+            key,
+            error_rate,
+            capacity,
+        } => (
+            "bloom",
+            one(key),
+            0,
+            format!("capacity={capacity} error_rate={error_rate}"),
+        ),
+        BloomAdd { key, items } => (
+            "bloom",
+            one(key),
+            total(items),
+            format!("{} item(s)", items.len()),
+        ),
+        BloomLoad { key, filter } => (
+            "bloom",
+            one(key),
+            filter.size_bytes(),
+            format!("{} item(s)", filter.items()),
+        ),
+        HashLoad { key, fields, ttl } => (
+            "hash",
+            one(key),
+            fields.iter().map(|(f, v)| f.len() + v.len()).sum(),
+            with_ttl(format!("{} field(s)", fields.len()), ttl),
+        ),
+        ListLoad { key, values, ttl } => (
+            "list",
+            one(key),
+            total(values),
+            with_ttl(format!("{} value(s)", values.len()), ttl),
+        ),
+        SetLoad { key, members, ttl } => (
+            "set",
+            one(key),
+            total(members),
+            with_ttl(format!("{} member(s)", members.len()), ttl),
+        ),
+        SortedSetLoad { key, members, ttl } => (
+            "sorted_set",
+            one(key),
+            members.iter().map(|(m, _)| m.len()).sum(),
+            with_ttl(format!("{} member(s)", members.len()), ttl),
+        ),
+        HyperLogLogLoad {
+            key,
+            registers,
+            ttl,
+        } => (
+            "hyperloglog",
+            one(key),
+            registers.len(),
+            with_ttl(String::new(), ttl),
+        ),
+        HyperLogLogAdd { key, elements, ttl } => (
+            "hyperloglog",
+            one(key),
+            total(elements),
+            with_ttl(format!("{} element(s)", elements.len()), ttl),
+        ),
+        BitmapLoad { key, data, ttl } => {
+            ("bitmap", one(key), data.len(), with_ttl(String::new(), ttl))
+        }
+        SubscriptionPut { record } => ("subscription", vec![record.id()], 0, String::new()),
+        SubscriptionDelete { id } => ("subscription", one(id), 0, String::new()),
+    }
+}
+
+/// One record per key of a snapshot, sorted by datatype and key
+fn snapshot_records(snapshot: &Snapshot) -> Vec<InspectRecord> {
+    let at = snapshot.timestamp;
+    let mut records = Vec::new();
+
+    for (key, value) in &snapshot.kv_data {
+        records.push(InspectRecord::snapshot_key(
+            at,
+            "kv",
+            key,
+            value.len(),
+            preview(value),
+        ));
+    }
+    for (queue, messages) in &snapshot.queue_data {
+        let bytes = messages.iter().map(|m| m.payload.len()).sum();
+        let detail = format!("{} message(s)", messages.len());
+        records.push(InspectRecord::snapshot_key(
+            at, "queue", queue, bytes, detail,
+        ));
+    }
+    for (room, events) in &snapshot.stream_data {
+        let bytes = events.iter().map(|e| e.data.len()).sum();
+        let detail = format!("{} event(s)", events.len());
+        records.push(InspectRecord::snapshot_key(
+            at, "stream", room, bytes, detail,
+        ));
+    }
+    for (key, fields) in &snapshot.hash_data {
+        let bytes = fields.iter().map(|(f, v)| f.len() + v.len()).sum();
+        let detail = format!("{} field(s)", fields.len());
+        records.push(InspectRecord::snapshot_key(at, "hash", key, bytes, detail));
+    }
+    for (key, list) in &snapshot.list_data {
+        let detail = format!("{} element(s)", list.len());
+        records.push(InspectRecord::snapshot_key(
+            at,
+            "list",
+            key,
+            list.element_bytes(),
+            detail,
+        ));
+    }
+    for (key, set) in &snapshot.set_data {
+        let detail = format!("{} member(s)", set.len());
+        records.push(InspectRecord::snapshot_key(
+            at,
+            "set",
+            key,
+            set.member_bytes(),
+            detail,
+        ));
+    }
+    for (key, members) in &snapshot.sorted_set_data {
+        let bytes = members.iter().map(|(m, _)| m.len()).sum();
+        let detail = format!("{} member(s)", members.len());
+        records.push(InspectRecord::snapshot_key(
+            at,
+            "sorted_set",
+            key,
+            bytes,
+            detail,
+        ));
+    }
+    for (key, registers) in &snapshot.hyperloglog_data {
+        records.push(InspectRecord::snapshot_key(
+            at,
+            "hyperloglog",
+            key,
+            registers.len(),
+            String::new(),
+        ));
+    }
+    for (key, filter) in &snapshot.bloom_data {
+        let detail = format!("{} item(s)", filter.items());
+        records.push(InspectRecord::snapshot_key(
+            at,
+            "bloom",
+            key,
+            filter.size_bytes(),
+            detail,
+        ));
+    }
+
+    records.sort_by(|a, b| (a.datatype, &a.keys).cmp(&(b.datatype, &b.keys)));
+    records
+}
+
+/// A value as quoted text when it is printable UTF-8 and as hex otherwise,
+/// cut to [`PREVIEW_BYTES`]
+fn preview(value: &[u8]) -> String {
+    let shown = &value[..value.len().min(PREVIEW_BYTES)];
+    let more = if shown.len() < value.len() { "…" } else { "" };
+    let text = match std::str::from_utf8(shown) {
+        Ok(text) => Some(text),
+        // A multi-byte character cut by the preview limit
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&shown[..e.valid_up_to()]).ok(),
+        Err(_) => None,
+    };
+    match text {
+        Some(text) if !text.chars().any(char::is_control) => format!("{text:?}{more}"),
+        _ => {
+            let hex: String = shown.iter().map(|b| format!("{b:02x}")).collect();
+            format!("0x{hex}{more}")
+        }
+    }
+}
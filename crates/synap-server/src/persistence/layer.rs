@@ -1,9 +1,12 @@
 use super::apply::{StoreArcs, StoreRefs};
-use super::types::{FsyncMode, Operation, PersistenceConfig};
+use super::snapshot::ChangedKeys;
+use super::types::{DataClass, FsyncMode, Operation, PersistenceConfig};
 use super::{AsyncWAL, SnapshotManager};
 use crate::core::sorted_set::ZAddOptions;
-use parking_lot::RwLock;
+use bytes::Bytes;
+use parking_lot::{Mutex, RwLock};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use tracing::info;
 
@@ -18,10 +21,34 @@ pub struct PersistenceLayer {
     config: PersistenceConfig,
     last_snapshot: Arc<RwLock<Instant>>,
     operations_since_snapshot: Arc<RwLock<usize>>,
+    /// `snapshot.interval_secs` and `snapshot.operation_threshold`, changeable
+    /// at runtime
+    snapshot_interval_secs: AtomicU64,
+    snapshot_operation_threshold: AtomicUsize,
     /// When set (master role), every recorded operation is also propagated to
     /// connected replicas (audit M-005). Propagation is decoupled from the WAL
     /// so a master replicates even when persistence is disabled (phase6j).
-    replication_master: Option<Arc<crate::replication::MasterNode>>,
+    /// Swapped in when a replica is promoted by automatic failover.
+    replication_master: RwLock<Option<Arc<crate::replication::MasterNode>>>,
+    /// Durable consumer registrations, kept in their own log. Present only
+    /// when persistence and `durable_subscriptions` are both enabled.
+    subscriptions: Option<Arc<super::SubscriptionRegistry>>,
+    /// Keys written since the last snapshot, when incremental snapshots are
+    /// enabled
+    incremental: Mutex<IncrementalState>,
+    /// Records slow snapshots as `snapshot` and `snapshot.incremental`
+    latency: Option<Arc<crate::monitoring::LatencyMonitor>>,
+}
+
+/// Where the incremental snapshot chain stands
+#[derive(Default)]
+struct IncrementalState {
+    changed: ChangedKeys,
+    /// WAL offset of the last snapshot this process wrote. The first
+    /// periodic snapshot after startup is always a full one.
+    last_offset: Option<u64>,
+    /// Increments written since the last full snapshot
+    since_full: usize,
 }
 
 impl PersistenceLayer {
@@ -45,25 +72,59 @@ impl PersistenceLayer {
         } else {
             None
         };
+        let subscriptions = if config.enabled && config.durable_subscriptions {
+            Some(Arc::new(
+                super::SubscriptionRegistry::open(&config.wal).await?,
+            ))
+        } else {
+            None
+        };
         let snapshot_mgr = SnapshotManager::new(config.snapshot.clone());
 
         Ok(Self {
             wal,
             snapshot_mgr: Arc::new(snapshot_mgr),
+            snapshot_interval_secs: AtomicU64::new(config.snapshot.interval_secs),
+            snapshot_operation_threshold: AtomicUsize::new(config.snapshot.operation_threshold),
             config,
             last_snapshot: Arc::new(RwLock::new(Instant::now())),
             operations_since_snapshot: Arc::new(RwLock::new(0)),
-            replication_master,
+            replication_master: RwLock::new(replication_master),
+            subscriptions,
+            incremental: Mutex::new(IncrementalState::default()),
+            latency: None,
         })
     }
 
+    /// Report snapshot durations to `latency`
+    pub fn with_latency_monitor(mut self, latency: Arc<crate::monitoring::LatencyMonitor>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    fn record_latency(&self, event: &str, started: Instant) {
+        if let Some(latency) = &self.latency {
+            latency.record(event, started.elapsed());
+        }
+    }
+
+    /// Durable subscription registry, if enabled
+    pub fn subscriptions(&self) -> Option<&Arc<super::SubscriptionRegistry>> {
+        self.subscriptions.as_ref()
+    }
+
     /// Propagate an operation to connected replicas when running as master.
     fn maybe_replicate(&self, operation: &Operation) {
-        if let Some(master) = &self.replication_master {
+        if let Some(master) = self.replication_master.read().as_ref() {
             master.replicate(operation.clone());
         }
     }
 
+    /// Start (or stop) propagating recorded operations through `master`
+    pub fn set_replication_master(&self, master: Option<Arc<crate::replication::MasterNode>>) {
+        *self.replication_master.write() = master;
+    }
+
     /// Record an operation: propagate to replicas (always) and append to the WAL
     /// (only when a WAL sink is present).
     ///
@@ -75,6 +136,11 @@ impl PersistenceLayer {
         self.maybe_replicate(&operation);
 
         if let Some(wal) = &self.wal {
+            // Noted before the append, so a snapshot that sees the entry's
+            // offset also sees the key
+            if self.tracks_changes() {
+                self.incremental.lock().changed.record(&operation);
+            }
             wal.append(operation).await?;
             *self.operations_since_snapshot.write() += 1;
         }
@@ -90,6 +156,10 @@ impl PersistenceLayer {
     /// appended to the WAL as a single atomic batch — so a MULTI/EXEC survives a
     /// crash all-or-nothing rather than as interleavable single appends. With
     /// persistence disabled the ops are still replicated (no WAL append).
+    ///
+    /// Stream publishes are replicated but left out of the WAL batch, matching
+    /// [`log_stream_publish`](Self::log_stream_publish): streams are durable
+    /// through their own persistence and snapshots (audit M-014).
     pub async fn log_transaction(
         &self,
         writes: &[crate::core::CommittedWrite],
@@ -109,8 +179,21 @@ impl PersistenceLayer {
         }
 
         if let Some(wal) = &self.wal {
-            wal.append_batch(ops).await?;
-            *self.operations_since_snapshot.write() += writes.len();
+            let durable: Vec<Operation> = ops
+                .into_iter()
+                .filter(|op| !matches!(op, Operation::StreamPublish { .. }))
+                .collect();
+            let count = durable.len();
+            if count > 0 {
+                if self.tracks_changes() {
+                    let mut incremental = self.incremental.lock();
+                    for op in &durable {
+                        incremental.changed.record(op);
+                    }
+                }
+                wal.append_batch(durable).await?;
+                *self.operations_since_snapshot.write() += count;
+            }
         }
 
         Ok(())
@@ -122,14 +205,14 @@ impl PersistenceLayer {
         match write {
             Cw::KvSet { key, value, ttl } => Operation::KVSet {
                 key: key.clone(),
-                value: value.clone(),
+                value: Bytes::copy_from_slice(value),
                 ttl: *ttl,
             },
             Cw::KvDel { keys } => Operation::KVDel { keys: keys.clone() },
             Cw::HashSet { key, field, value } => Operation::HashSet {
                 key: key.clone(),
                 field: field.clone(),
-                value: value.clone(),
+                value: Bytes::copy_from_slice(value),
             },
             Cw::HashDel { key, fields } => Operation::HashDel {
                 key: key.clone(),
@@ -142,7 +225,7 @@ impl PersistenceLayer {
             },
             Cw::ListPush { key, values, left } => Operation::ListPush {
                 key: key.clone(),
-                values: values.clone(),
+                values: copied(values),
                 left: *left,
             },
             Cw::ListPop { key, left } => Operation::ListPop {
@@ -152,16 +235,62 @@ impl PersistenceLayer {
             },
             Cw::SetAdd { key, members } => Operation::SetAdd {
                 key: key.clone(),
-                members: members.clone(),
+                members: copied(members),
             },
             Cw::SetRem { key, members } => Operation::SetRem {
                 key: key.clone(),
-                members: members.clone(),
+                members: copied(members),
+            },
+            Cw::QueuePublish {
+                queue,
+                message_id,
+                payload,
+                priority,
+                max_retries,
+            } => {
+                let mut message =
+                    crate::core::QueueMessage::new(payload.clone(), *priority, *max_retries);
+                message.id = message_id.clone();
+                Operation::QueuePublish {
+                    queue: queue.clone(),
+                    message,
+                }
+            }
+            Cw::StreamPublish {
+                room, event, data, ..
+            } => Operation::StreamPublish {
+                room: room.clone(),
+                event_type: event.clone(),
+                payload: Bytes::copy_from_slice(data),
             },
         }
     }
 
-    /// Returns true when the WAL fsync mode is Always (sync durability).
+    /// Seconds and operations between periodic snapshots
+    pub fn snapshot_schedule(&self) -> (u64, usize) {
+        (
+            self.snapshot_interval_secs.load(Ordering::Relaxed),
+            self.snapshot_operation_threshold.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Change the periodic snapshot schedule. The snapshot task checks it
+    /// once a minute.
+    pub fn set_snapshot_schedule(&self, interval_secs: u64, operation_threshold: usize) {
+        self.snapshot_interval_secs
+            .store(interval_secs, Ordering::Relaxed);
+        self.snapshot_operation_threshold
+            .store(operation_threshold, Ordering::Relaxed);
+    }
+
+    /// Directory holding the WAL, when one is written
+    pub fn wal_dir(&self) -> Option<std::path::PathBuf> {
+        self.wal.as_ref()?;
+        self.config.wal.path.parent().map(|dir| dir.to_path_buf())
+    }
+
+    /// Returns true when the WAL fsync mode for KV entries is Always (sync
+    /// durability).
     ///
     /// When true, the SET handler must log to WAL BEFORE writing to memory.
     /// When false, WAL is written asynchronously after the memory write (current
@@ -169,17 +298,22 @@ impl PersistenceLayer {
     pub fn is_sync_durability(&self) -> bool {
         self.config.enabled
             && self.config.wal.enabled
-            && self.config.wal.fsync_mode == FsyncMode::Always
+            && self.config.wal.fsync_mode_for(Some(DataClass::Kv)) == FsyncMode::Always
     }
 
     /// Log a KV SET operation (non-blocking with group commit)
     pub async fn log_kv_set(
         &self,
         key: String,
-        value: Vec<u8>,
+        value: impl Into<Bytes>,
         ttl: Option<u64>,
     ) -> super::types::Result<()> {
-        self.record(Operation::KVSet { key, value, ttl }).await
+        self.record(Operation::KVSet {
+            key,
+            value: value.into(),
+            ttl,
+        })
+        .await
     }
 
     /// Log a KV DELETE operation
@@ -205,9 +339,14 @@ impl PersistenceLayer {
         &self,
         key: String,
         field: String,
-        value: Vec<u8>,
+        value: impl Into<Bytes>,
     ) -> super::types::Result<()> {
-        self.record(Operation::HashSet { key, field, value }).await
+        self.record(Operation::HashSet {
+            key,
+            field,
+            value: value.into(),
+        })
+        .await
     }
 
     /// Log a Hash DELETE operation
@@ -252,7 +391,12 @@ impl PersistenceLayer {
         values: Vec<Vec<u8>>,
         left: bool,
     ) -> super::types::Result<()> {
-        self.record(Operation::ListPush { key, values, left }).await
+        self.record(Operation::ListPush {
+            key,
+            values: shared(values),
+            left,
+        })
+        .await
     }
 
     /// Log a List POP operation
@@ -270,9 +414,14 @@ impl PersistenceLayer {
         &self,
         key: String,
         index: i64,
-        value: Vec<u8>,
+        value: impl Into<Bytes>,
     ) -> super::types::Result<()> {
-        self.record(Operation::ListSet { key, index, value }).await
+        self.record(Operation::ListSet {
+            key,
+            index,
+            value: value.into(),
+        })
+        .await
     }
 
     /// Log a List TRIM operation
@@ -290,9 +439,14 @@ impl PersistenceLayer {
         &self,
         key: String,
         count: i64,
-        value: Vec<u8>,
+        value: impl Into<Bytes>,
     ) -> super::types::Result<()> {
-        self.record(Operation::ListRem { key, count, value }).await
+        self.record(Operation::ListRem {
+            key,
+            count,
+            value: value.into(),
+        })
+        .await
     }
 
     /// Log a List INSERT operation
@@ -300,14 +454,14 @@ impl PersistenceLayer {
         &self,
         key: String,
         before: bool,
-        pivot: Vec<u8>,
-        value: Vec<u8>,
+        pivot: impl Into<Bytes>,
+        value: impl Into<Bytes>,
     ) -> super::types::Result<()> {
         self.record(Operation::ListInsert {
             key,
             before,
-            pivot,
-            value,
+            pivot: pivot.into(),
+            value: value.into(),
         })
         .await
     }
@@ -386,27 +540,102 @@ impl PersistenceLayer {
             let last = self.last_snapshot.read();
             let ops = self.operations_since_snapshot.read();
 
-            let time_elapsed = last.elapsed().as_secs() >= self.config.snapshot.interval_secs;
-            let ops_threshold = *ops >= self.config.snapshot.operation_threshold;
+            let (interval_secs, operation_threshold) = self.snapshot_schedule();
+            let time_elapsed = last.elapsed().as_secs() >= interval_secs;
+            let ops_threshold = *ops >= operation_threshold;
 
             time_elapsed || ops_threshold
         };
 
         if should_snapshot {
-            info!("Creating periodic snapshot");
+            if self.increment_due() {
+                info!("Creating incremental snapshot");
+                self.snapshot_increment(stores).await?;
+            } else {
+                info!("Creating periodic snapshot");
+                self.snapshot_now(stores).await?;
+            }
+        }
+
+        Ok(())
+    }
 
-            // A WAL-less layer (persistence disabled) has no offset to record.
-            let wal_offset = self.wal.as_ref().map(|w| w.current_offset()).unwrap_or(0);
+    /// Create a snapshot regardless of the interval and operation threshold
+    pub async fn snapshot_now(&self, stores: StoreRefs<'_>) -> super::types::Result<()> {
+        if !self.config.enabled || !self.config.snapshot.enabled {
+            return Ok(());
+        }
 
-            self.snapshot_mgr
-                .create_snapshot(stores, wal_offset)
-                .await?;
+        // A WAL-less layer (persistence disabled) has no offset to record.
+        let wal_offset = self.wal.as_ref().map(|w| w.current_offset()).unwrap_or(0);
+        let changed = std::mem::take(&mut self.incremental.lock().changed);
 
-            // Reset counters
-            *self.last_snapshot.write() = Instant::now();
-            *self.operations_since_snapshot.write() = 0;
+        let started = Instant::now();
+        if let Err(e) = self.snapshot_mgr.create_snapshot(stores, wal_offset).await {
+            // The next increment still has to carry these keys
+            self.incremental.lock().changed.merge(changed);
+            return Err(e);
+        }
+        self.record_latency("snapshot", started);
+        {
+            let mut incremental = self.incremental.lock();
+            incremental.last_offset = Some(wal_offset);
+            incremental.since_full = 0;
         }
 
+        // Reset counters
+        *self.last_snapshot.write() = Instant::now();
+        *self.operations_since_snapshot.write() = 0;
+
+        Ok(())
+    }
+
+    /// Whether written keys are tracked for incremental snapshots
+    fn tracks_changes(&self) -> bool {
+        self.config.enabled
+            && self.config.snapshot.enabled
+            && self.config.snapshot.incremental.enabled
+    }
+
+    /// Whether the next periodic snapshot can be an increment on the last one
+    fn increment_due(&self) -> bool {
+        let incremental = self.incremental.lock();
+        self.tracks_changes()
+            && self.wal.is_some()
+            && incremental.last_offset.is_some()
+            && incremental.since_full + 1 < self.config.snapshot.incremental.full_every
+    }
+
+    /// Write the keys changed since the last snapshot as an increment on it
+    async fn snapshot_increment(&self, stores: StoreRefs<'_>) -> super::types::Result<()> {
+        let wal_offset = self.wal.as_ref().map(|w| w.current_offset()).unwrap_or(0);
+        let (changed, base_offset) = {
+            let mut incremental = self.incremental.lock();
+            (
+                std::mem::take(&mut incremental.changed),
+                incremental.last_offset.unwrap_or(0),
+            )
+        };
+
+        if !changed.is_empty() {
+            let started = Instant::now();
+            if let Err(e) = self
+                .snapshot_mgr
+                .create_increment(stores, &changed, base_offset, wal_offset)
+                .await
+            {
+                self.incremental.lock().changed.merge(changed);
+                return Err(e);
+            }
+            self.record_latency("snapshot.incremental", started);
+            let mut incremental = self.incremental.lock();
+            incremental.last_offset = Some(wal_offset);
+            incremental.since_full += 1;
+        }
+
+        *self.last_snapshot.write() = Instant::now();
+        *self.operations_since_snapshot.write() = 0;
+
         Ok(())
     }
 
@@ -431,7 +660,11 @@ impl PersistenceLayer {
         key: String,
         members: Vec<Vec<u8>>,
     ) -> super::types::Result<()> {
-        self.record(Operation::SetAdd { key, members }).await
+        self.record(Operation::SetAdd {
+            key,
+            members: shared(members),
+        })
+        .await
     }
 
     /// Log a Set REMOVE operation (SREM)
@@ -440,7 +673,11 @@ impl PersistenceLayer {
         key: String,
         members: Vec<Vec<u8>>,
     ) -> super::types::Result<()> {
-        self.record(Operation::SetRem { key, members }).await
+        self.record(Operation::SetRem {
+            key,
+            members: shared(members),
+        })
+        .await
     }
 
     /// Log a Set MOVE operation (SMOVE)
@@ -448,12 +685,12 @@ impl PersistenceLayer {
         &self,
         source: String,
         destination: String,
-        member: Vec<u8>,
+        member: impl Into<Bytes>,
     ) -> super::types::Result<()> {
         self.record(Operation::SetMove {
             source,
             destination,
-            member,
+            member: member.into(),
         })
         .await
     }
@@ -494,13 +731,13 @@ impl PersistenceLayer {
     pub async fn log_zadd(
         &self,
         key: String,
-        member: Vec<u8>,
+        member: impl Into<Bytes>,
         score: f64,
         opts: &ZAddOptions,
     ) -> super::types::Result<()> {
         self.record(Operation::ZAdd {
             key,
-            member,
+            member: member.into(),
             score,
             nx: opts.nx,
             xx: opts.xx,
@@ -512,19 +749,23 @@ impl PersistenceLayer {
 
     /// Log a Sorted Set REMOVE operation (ZREM)
     pub async fn log_zrem(&self, key: String, members: Vec<Vec<u8>>) -> super::types::Result<()> {
-        self.record(Operation::ZRem { key, members }).await
+        self.record(Operation::ZRem {
+            key,
+            members: shared(members),
+        })
+        .await
     }
 
     /// Log a Sorted Set INCREMENT BY operation (ZINCRBY)
     pub async fn log_zincrby(
         &self,
         key: String,
-        member: Vec<u8>,
+        member: impl Into<Bytes>,
         increment: f64,
     ) -> super::types::Result<()> {
         self.record(Operation::ZIncrBy {
             key,
-            member,
+            member: member.into(),
             increment,
         })
         .await
@@ -596,6 +837,62 @@ impl PersistenceLayer {
             .await
     }
 
+    /// Log a HyperLogLog ADD operation (PFADD)
+    pub async fn log_hyperloglog_add(
+        &self,
+        key: String,
+        elements: Vec<Vec<u8>>,
+        ttl: Option<u64>,
+    ) -> super::types::Result<()> {
+        self.record(Operation::HyperLogLogAdd {
+            key,
+            elements: shared(elements),
+            ttl,
+        })
+        .await
+    }
+
+    /// Log a HyperLogLog MERGE operation (PFMERGE) with the merged registers
+    pub async fn log_hyperloglog_merge(
+        &self,
+        destination: String,
+        registers: Vec<u8>,
+    ) -> super::types::Result<()> {
+        self.record(Operation::HyperLogLogMerge {
+            destination,
+            registers: registers.into(),
+        })
+        .await
+    }
+
+    /// Log a Bloom filter RESERVE operation (BF.RESERVE)
+    pub async fn log_bloom_reserve(
+        &self,
+        key: String,
+        error_rate: f64,
+        capacity: u64,
+    ) -> super::types::Result<()> {
+        self.record(Operation::BloomReserve {
+            key,
+            error_rate,
+            capacity,
+        })
+        .await
+    }
+
+    /// Log a Bloom filter ADD operation (BF.ADD, BF.MADD)
+    pub async fn log_bloom_add(
+        &self,
+        key: String,
+        items: Vec<Vec<u8>>,
+    ) -> super::types::Result<()> {
+        self.record(Operation::BloomAdd {
+            key,
+            items: shared(items),
+        })
+        .await
+    }
+
     /// No explicit flush needed with AsyncWAL (group commit handles it)
     pub async fn flush(&self) -> super::types::Result<()> {
         // AsyncWAL handles batching and flushing automatically
@@ -603,6 +900,16 @@ impl PersistenceLayer {
     }
 }
 
+/// Take ownership of logged members without copying them (`Bytes::from(Vec)`
+/// adopts the allocation).
+fn shared(values: Vec<Vec<u8>>) -> Vec<Bytes> {
+    values.into_iter().map(Bytes::from).collect()
+}
+
+fn copied(values: &[Vec<u8>]) -> Vec<Bytes> {
+    values.iter().map(|v| Bytes::copy_from_slice(v)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -650,6 +957,19 @@ mod tests {
                 key: "s".into(),
                 members: vec![b"m".to_vec()],
             },
+            CommittedWrite::QueuePublish {
+                queue: "q".into(),
+                message_id: "mid".into(),
+                payload: b"job".to_vec(),
+                priority: 5,
+                max_retries: 3,
+            },
+            CommittedWrite::StreamPublish {
+                room: "r".into(),
+                event: "e".into(),
+                data: b"d".to_vec(),
+                offset: 0,
+            },
         ]
     }
 
@@ -779,6 +1099,18 @@ mod tests {
             .log_zdiffstore("d".into(), vec!["z".into()])
             .await
             .unwrap();
+        layer
+            .log_hyperloglog_merge("hll".into(), vec![0; 16384])
+            .await
+            .unwrap();
+        layer
+            .log_bloom_reserve("bf".into(), 0.01, 1000)
+            .await
+            .unwrap();
+        layer
+            .log_bloom_add("bf".into(), vec![b"a".to_vec()])
+            .await
+            .unwrap();
         layer.flush().await.unwrap();
 
         let _ = std::fs::remove_dir_all(dir);
@@ -830,6 +1162,13 @@ mod tests {
                         field: "f".into(),
                         value: b"hv".to_vec(),
                     },
+                    CommittedWrite::QueuePublish {
+                        queue: "tq".into(),
+                        message_id: "mid".into(),
+                        payload: b"job".to_vec(),
+                        priority: 5,
+                        max_retries: 3,
+                    },
                 ])
                 .await
                 .unwrap();
@@ -837,12 +1176,13 @@ mod tests {
             tokio::time::sleep(std::time::Duration::from_millis(150)).await;
         }
 
-        let (kv, hash, _l, _s, _z, _q, _off) =
+        let (kv, hash, _l, _s, _z, _hll, _bf, queues, _off) =
             crate::persistence::recover(&config, KVConfig::default(), QueueConfig::default())
                 .await
                 .unwrap();
         assert_eq!(kv.get("tk").await.unwrap(), Some(b"tv".to_vec()));
         assert_eq!(hash.unwrap().hget("th", "f").unwrap(), Some(b"hv".to_vec()));
+        assert_eq!(queues.unwrap().stats("tq").await.unwrap().depth, 1);
 
         let _ = std::fs::remove_dir_all(dir);
     }
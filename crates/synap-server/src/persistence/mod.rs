@@ -1,9 +1,15 @@
 pub mod apply;
+pub mod archive;
+pub mod dump;
+pub mod fsck;
+pub mod inspect;
 pub mod layer;
+pub mod object_store;
 pub mod queue_persistence;
 pub mod recovery;
 pub mod snapshot;
 pub mod stream_persistence;
+pub mod subscriptions;
 /// Persistence module for WAL and Snapshots
 ///
 /// Provides durability for KV Store and Queue System through:
@@ -17,15 +23,33 @@ pub mod wal_async;
 pub mod wal_optimized;
 
 pub use apply::{StoreArcs, StoreRefs};
+pub use archive::{ArchiveReport, RestoreReport, WalArchiver, restore_from_archive};
+pub use dump::{DumpHeader, ImportSummary};
+pub use fsck::{FsckIssue, FsckIssueKind, FsckReport, fsck};
+pub use inspect::{
+    InspectDump, InspectFilter, InspectRecord, InspectStats, OperationMix, dump_snapshot, dump_wal,
+};
 pub use layer::PersistenceLayer;
 pub use queue_persistence::QueuePersistence;
-pub use recovery::recover;
+pub use recovery::{RecoveredState, recover, restore};
 pub use snapshot::SnapshotManager;
 pub use stream_persistence::{StreamEvent, StreamPersistence};
+pub use subscriptions::{RestoreSummary, SubscriptionRecord, SubscriptionRegistry};
 pub use types::{Operation, PersistenceConfig, PersistenceError, Result, Snapshot, WALEntry};
 pub use wal::WriteAheadLog;
 pub use wal_async::AsyncWAL;
 pub use wal_optimized::OptimizedWAL;
 
+/// Replace `path` with `data` through a temporary file, so a crash leaves
+/// either the old contents or the new ones
+pub(crate) fn write_atomically(path: &std::path::Path, data: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
+
 #[cfg(test)]
 mod tests;
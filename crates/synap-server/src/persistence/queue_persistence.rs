@@ -164,6 +164,8 @@ mod tests {
             fsync_mode: super::super::types::FsyncMode::Always,
             fsync_interval_ms: 10,
             max_size_mb: 10,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         };
 
         // Write some operations
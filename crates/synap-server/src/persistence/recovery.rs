@@ -1,7 +1,9 @@
-use super::types::{PersistenceConfig, Result};
+use super::types::{PersistenceConfig, Result, Snapshot, WALEntry};
 use super::{SnapshotManager, WriteAheadLog};
 use crate::core::QueueConfig;
+use crate::core::bloom::BloomFilterStore;
 use crate::core::hash::HashStore;
+use crate::core::hyperloglog::HyperLogLogStore;
 use crate::core::kv_store::KVStore;
 use crate::core::list::ListStore;
 use crate::core::queue::QueueManager;
@@ -10,20 +12,25 @@ use crate::core::sorted_set::{SortedSetStore, ZAddOptions};
 use crate::core::types::KVConfig;
 use tracing::info;
 
-/// Recover system state from persistence
-pub async fn recover(
-    config: &PersistenceConfig,
-    kv_config: KVConfig,
-    queue_config: QueueConfig,
-) -> Result<(
+/// Stores rebuilt from persistence, and the WAL offset they reflect
+pub type RecoveredState = (
     KVStore,
     Option<HashStore>,
     Option<ListStore>,
     Option<SetStore>,
     Option<SortedSetStore>,
+    Option<HyperLogLogStore>,
+    Option<BloomFilterStore>,
     Option<QueueManager>,
     u64,
-)> {
+);
+
+/// Recover system state from persistence
+pub async fn recover(
+    config: &PersistenceConfig,
+    kv_config: KVConfig,
+    queue_config: QueueConfig,
+) -> Result<RecoveredState> {
     if !config.enabled {
         info!("Persistence disabled, starting with fresh state");
         return Ok((
@@ -32,6 +39,8 @@ pub async fn recover(
             Some(ListStore::new()),
             Some(SetStore::new()),
             Some(SortedSetStore::new()),
+            Some(HyperLogLogStore::new()),
+            Some(BloomFilterStore::new()),
             Some(QueueManager::new(queue_config)),
             0,
         ));
@@ -43,18 +52,60 @@ pub async fn recover(
     let wal = WriteAheadLog::open(config.wal.clone()).await?;
 
     // Step 1: Load latest snapshot (if exists)
-    let (kv_store, hash_store, list_store, set_store, sorted_set_store, queue_manager, last_offset) =
-        if let Some((snapshot, path)) = snapshot_mgr.load_latest().await? {
+    let snapshot = match snapshot_mgr.load_latest().await? {
+        Some((snapshot, path)) => {
             info!(
                 "Loaded snapshot from {:?} at offset {}",
                 path, snapshot.wal_offset
             );
+            Some(snapshot)
+        }
+        None => {
+            info!("No snapshot found, starting fresh");
+            None
+        }
+    };
+
+    // Step 2: Replay WAL from snapshot offset
+    let last_offset = snapshot.as_ref().map_or(0, |s| s.wal_offset);
+    info!("Replaying WAL from offset {}...", last_offset);
+    let entries = wal.replay(last_offset).await?;
+
+    restore(snapshot, entries, kv_config, queue_config).await
+}
+
+/// Rebuild the stores from a snapshot and the WAL entries logged after it
+pub async fn restore(
+    mut snapshot: Option<Snapshot>,
+    entries: Vec<WALEntry>,
+    kv_config: KVConfig,
+    queue_config: QueueConfig,
+) -> Result<RecoveredState> {
+    // Restore HyperLogLog store
+    let hyperloglog_store = HyperLogLogStore::new();
+    if let Some(snapshot) = snapshot.as_mut() {
+        for (key, registers) in std::mem::take(&mut snapshot.hyperloglog_data) {
+            hyperloglog_store.restore_registers(&key, registers)?;
+        }
+    }
+    let hyperloglog_store = Some(hyperloglog_store);
 
-            // Restore KV store
+    // Restore Bloom filter store
+    let bloom_store = BloomFilterStore::new();
+    if let Some(snapshot) = snapshot.as_mut() {
+        for (key, filter) in std::mem::take(&mut snapshot.bloom_data) {
+            bloom_store.restore(&key, filter);
+        }
+    }
+    let bloom_store = Some(bloom_store);
+
+    let (kv_store, hash_store, list_store, set_store, sorted_set_store, queue_manager, last_offset) =
+        if let Some(snapshot) = snapshot {
+            // Restore KV store. MSET groups the entries by shard and takes
+            // each shard's write lock once; the snapshot is keyed by name, so
+            // the shard count may differ from the one it was taken with.
             let kv = KVStore::new(kv_config);
-            for (key, value) in snapshot.kv_data {
-                kv.set(&key, value, None).await?;
-            }
+            kv.mset(snapshot.kv_data.into_iter().collect()).await?;
 
             // Restore Queue manager
             let queues = QueueManager::new(queue_config);
@@ -64,7 +115,7 @@ pub async fn recover(
                     queues
                         .publish(
                             &queue_name,
-                            (*message.payload).clone(), // Convert Arc<Vec<u8>> to Vec<u8>
+                            message.payload,
                             Some(message.priority),
                             Some(message.max_retries),
                         )
@@ -120,7 +171,6 @@ pub async fn recover(
                 snapshot.wal_offset,
             )
         } else {
-            info!("No snapshot found, starting fresh");
             (
                 KVStore::new(kv_config),
                 Some(HashStore::new()),
@@ -132,9 +182,6 @@ pub async fn recover(
             )
         };
 
-    // Step 2: Replay WAL from snapshot offset
-    info!("Replaying WAL from offset {}...", last_offset);
-    let entries = wal.replay(last_offset).await?;
     let mut replayed = 0;
 
     for entry in entries {
@@ -146,6 +193,9 @@ pub async fn recover(
                 list_store: list_store.as_ref(),
                 set_store: set_store.as_ref(),
                 sorted_set_store: sorted_set_store.as_ref(),
+                hyperloglog_store: hyperloglog_store.as_ref(),
+                bloom_store: bloom_store.as_ref(),
+                bitmap_store: None, // Bitmaps are not logged
                 queue_manager: queue_manager.as_ref(),
                 stream_manager: None, // WAL recovery skips streams (StreamPersistence owns them)
             },
@@ -164,6 +214,8 @@ pub async fn recover(
         list_store,
         set_store,
         sorted_set_store,
+        hyperloglog_store,
+        bloom_store,
         queue_manager,
         final_offset,
     ))
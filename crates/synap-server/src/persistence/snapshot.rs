@@ -1,19 +1,32 @@
-use super::types::{PersistenceError, Result, Snapshot, SnapshotConfig, StreamEvent};
+use super::types::{Operation, PersistenceError, Result, Snapshot, SnapshotConfig, StreamEvent};
+use crate::core::bloom::BloomFilter;
 use crate::core::queue::QueueMessage;
-use serde::Serialize;
 use serde::de::DeserializeOwned;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
 use tracing::{debug, info, warn};
 
-/// v2 = kv + queue + stream. v3 (current) also persists hash/list/set/sorted-set.
-const SNAPSHOT_VERSION: u8 = 3;
-const SNAPSHOT_MAGIC: &[u8; 8] = b"SYNAP003";
+/// v2 = kv + queue + stream. v3 also persists hash/list/set/sorted-set. v4
+/// puts an encoding header on every KV value so compressed values are written
+/// as they are stored. v5 adds the HyperLogLog registers and v6 (current) the
+/// Bloom filters.
+const SNAPSHOT_VERSION: u8 = 6;
+const SNAPSHOT_MAGIC: &[u8; 8] = b"SYNAP006";
+const SNAPSHOT_MAGIC_V5: &[u8; 8] = b"SYNAP005";
+const SNAPSHOT_MAGIC_V4: &[u8; 8] = b"SYNAP004";
+const SNAPSHOT_MAGIC_V3: &[u8; 8] = b"SYNAP003";
 const SNAPSHOT_MAGIC_V2: &[u8; 8] = b"SYNAP002";
 
+/// Incremental snapshots: the keys written between two snapshots, layered
+/// over a full one on load. v2 adds the HyperLogLog section and v3 the Bloom
+/// filter one.
+const INCREMENT_VERSION: u8 = 3;
+const INCREMENT_MAGIC: &[u8; 8] = b"SYNAPINC";
+
 /// Snapshot manager for periodic state dumps with streaming support
 pub struct SnapshotManager {
     config: SnapshotConfig,
@@ -37,6 +50,10 @@ impl SnapshotManager {
             list_store,
             set_store,
             sorted_set_store,
+            hyperloglog_store,
+            bloom_store,
+            // Bitmaps are memory-only; dumps and full syncs carry them
+            bitmap_store: _,
             queue_manager,
             stream_manager,
         } = stores;
@@ -50,10 +67,13 @@ impl SnapshotManager {
 
         let filename = format!("snapshot-v{}-{}.bin", SNAPSHOT_VERSION, timestamp);
         let path = self.config.directory.join(&filename);
+        // Written under another name and renamed once complete, so a reader
+        // listing the directory (the WAL archiver) never sees half a snapshot
+        let partial_path = self.config.directory.join(format!("{}.new", filename));
 
         info!("Creating streaming snapshot at {:?}", path);
 
-        let file = File::create(&path).await?;
+        let file = File::create(&partial_path).await?;
         let mut writer = BufWriter::new(file);
         let mut checksum = CRC64::new();
 
@@ -70,20 +90,29 @@ impl SnapshotManager {
         writer.write_u64(wal_offset).await?;
         checksum.update(&wal_offset.to_le_bytes());
 
-        // Stream KV data
-        let kv_data = kv_store.dump().await?;
-        let kv_count = kv_data.len() as u64;
+        // Stream KV data, one shard read lock at a time. Entries share the
+        // stored value buffers, so nothing is copied but the keys.
+        let kv_shards: Vec<_> = (0..kv_store.shard_count())
+            .map(|index| kv_store.dump_shard(index))
+            .collect();
+        let kv_count = kv_shards.iter().map(Vec::len).sum::<usize>() as u64;
 
         writer.write_u64(kv_count).await?;
         checksum.update(&kv_count.to_le_bytes());
 
-        debug!("Streaming {} KV entries", kv_count);
+        debug!(
+            "Streaming {} KV entries from {} shards",
+            kv_count,
+            kv_shards.len()
+        );
 
-        for (key, value) in kv_data {
-            // Write key length + key + value length + value
+        for (key, value) in kv_shards.into_iter().flatten() {
+            // Write key length + key + value length + encoding tag + value,
+            // the value still compressed if the store compressed it
+            let (tag, value) = kv_store.stored_parts(&value);
             let key_bytes = key.as_bytes();
             let key_len = key_bytes.len() as u32;
-            let value_len = value.len() as u32;
+            let value_len = value.len() as u32 + 1;
 
             writer.write_u32(key_len).await?;
             checksum.update(&key_len.to_le_bytes());
@@ -94,8 +123,11 @@ impl SnapshotManager {
             writer.write_u32(value_len).await?;
             checksum.update(&value_len.to_le_bytes());
 
-            writer.write_all(&value).await?;
-            checksum.update(&value);
+            writer.write_u8(tag).await?;
+            checksum.update(&[tag]);
+
+            writer.write_all(value).await?;
+            checksum.update(value);
         }
 
         // Stream queue data (if available)
@@ -223,20 +255,38 @@ impl SnapshotManager {
         debug!("Streaming {} sorted sets", sorted_set_data.len());
         write_map_section(&mut writer, &mut checksum, &sorted_set_data).await?;
 
+        // HyperLogLog registers (v5+)
+        let hyperloglog_data = match hyperloglog_store {
+            Some(hll) => hll.dump(),
+            None => HashMap::new(),
+        };
+        debug!("Streaming {} HyperLogLogs", hyperloglog_data.len());
+        write_map_section(&mut writer, &mut checksum, &hyperloglog_data).await?;
+
+        // Bloom filters (v6+)
+        let bloom_data = match bloom_store {
+            Some(bf) => bf.dump(),
+            None => HashMap::new(),
+        };
+        debug!("Streaming {} Bloom filters", bloom_data.len());
+        write_map_section(&mut writer, &mut checksum, &bloom_data).await?;
+
         // Write checksum at end
         let final_checksum = checksum.finalize();
         writer.write_u64(final_checksum).await?;
 
         writer.flush().await?;
         writer.into_inner().sync_all().await?;
+        tokio::fs::rename(&partial_path, &path).await?;
 
         info!(
             "Streaming snapshot created successfully: {:?} (checksum: {})",
             path, final_checksum
         );
 
-        // Cleanup old snapshots
+        // Cleanup old snapshots, and the increments this one supersedes
         self.cleanup_old_snapshots().await?;
+        self.cleanup_increments(wal_offset).await?;
 
         Ok(path)
     }
@@ -250,11 +300,58 @@ impl SnapshotManager {
             return Ok(None);
         }
 
-        // Get the most recent snapshot
+        // Get the most recent snapshot, then layer the increments written
+        // after it
         let latest = &snapshots[snapshots.len() - 1];
-        info!("Loading snapshot from {:?}", latest);
+        let mut snapshot = self.load(latest).await?;
+        self.apply_increments(&mut snapshot).await?;
+        Ok(Some((snapshot, latest.clone())))
+    }
 
-        let file = File::open(latest).await?;
+    /// Layer the incremental snapshots that continue `snapshot`'s chain onto
+    /// it, oldest first, advancing its `wal_offset`. Increments from an
+    /// older chain are skipped; a gap ends the chain, and the WAL covers
+    /// the rest.
+    pub async fn apply_increments(&self, snapshot: &mut Snapshot) -> Result<usize> {
+        let mut applied = 0;
+        for path in self.list_increments().await? {
+            if increment_offset(&path).is_some_and(|offset| offset <= snapshot.wal_offset) {
+                continue;
+            }
+            let increment = match self.load_increment(&path).await {
+                Ok(increment) => increment,
+                Err(e) => {
+                    warn!(
+                        "Skipping unreadable incremental snapshot {:?} ({}); replaying the WAL from offset {}",
+                        path, e, snapshot.wal_offset
+                    );
+                    break;
+                }
+            };
+            if increment.base_offset != snapshot.wal_offset {
+                warn!(
+                    "Incremental snapshot {:?} starts at offset {}, expected {}; replaying the WAL from there",
+                    path, increment.base_offset, snapshot.wal_offset
+                );
+                break;
+            }
+            increment.apply_to(snapshot);
+            applied += 1;
+        }
+        if applied > 0 {
+            info!(
+                "Applied {} incremental snapshots, now at offset {}",
+                applied, snapshot.wal_offset
+            );
+        }
+        Ok(applied)
+    }
+
+    /// Load and verify one snapshot file
+    pub async fn load(&self, path: &Path) -> Result<Snapshot> {
+        info!("Loading snapshot from {:?}", path);
+
+        let file = File::open(path).await?;
         let mut reader = BufReader::new(file);
 
         // Running digest, updated with the exact same byte sequence the writer
@@ -267,24 +364,23 @@ impl SnapshotManager {
         reader.read_exact(&mut magic).await?;
         checksum.update(&magic);
 
-        // v3 (SNAPSHOT_MAGIC) carries the hash/list/set/sorted-set sections;
-        // v2 (SNAPSHOT_MAGIC_V2) does not. Any other magic is unreadable.
-        let has_collections = if &magic == SNAPSHOT_MAGIC {
-            true
-        } else if &magic == SNAPSHOT_MAGIC_V2 {
-            false
-        } else {
-            return Err(PersistenceError::SnapshotCorrupted(latest.clone()));
+        // v3+ carries the hash/list/set/sorted-set sections; v2 does not.
+        // v4+ KV values start with an encoding tag, v5 adds HyperLogLogs and
+        // v6 Bloom filters. Any other magic is unreadable.
+        let Some(format) = magic_version(&magic) else {
+            return Err(PersistenceError::SnapshotCorrupted(path.to_path_buf()));
         };
+        let has_collections = format >= 3;
+        let tagged_values = format >= 4;
 
         let version = reader.read_u8().await?;
         checksum.update(&[version]);
-        if version != 2 && version != SNAPSHOT_VERSION {
+        if !(2..=SNAPSHOT_VERSION).contains(&version) {
             warn!(
-                "Unsupported snapshot version: expected 2 or {}, got {}",
+                "Unsupported snapshot version: expected 2 to {}, got {}",
                 SNAPSHOT_VERSION, version
             );
-            return Err(PersistenceError::SnapshotCorrupted(latest.clone()));
+            return Err(PersistenceError::SnapshotCorrupted(path.to_path_buf()));
         }
 
         // Read metadata
@@ -313,6 +409,9 @@ impl SnapshotManager {
             reader.read_exact(&mut value).await?;
             checksum.update(&value);
 
+            if tagged_values {
+                value = crate::compression::value::decode(&value)?.into_owned();
+            }
             kv_data.insert(key, value);
         }
 
@@ -398,6 +497,16 @@ impl SnapshotManager {
                 HashMap::new(),
             )
         };
+        let hyperloglog_data = if format >= 5 {
+            read_map_section(&mut reader, &mut checksum).await?
+        } else {
+            HashMap::new()
+        };
+        let bloom_data = if format >= 6 {
+            read_map_section(&mut reader, &mut checksum).await?
+        } else {
+            HashMap::new()
+        };
 
         // Verify integrity: the trailing CRC64 must match the running digest.
         let stored_checksum = reader.read_u64().await?;
@@ -405,9 +514,9 @@ impl SnapshotManager {
         if stored_checksum != computed_checksum {
             warn!(
                 "Snapshot checksum mismatch at {:?} (stored={:#x}, computed={:#x})",
-                latest, stored_checksum, computed_checksum
+                path, stored_checksum, computed_checksum
             );
-            return Err(PersistenceError::SnapshotCorrupted(latest.clone()));
+            return Err(PersistenceError::SnapshotCorrupted(path.to_path_buf()));
         }
 
         info!(
@@ -430,13 +539,265 @@ impl SnapshotManager {
             set_data,
             sorted_set_data,
             hash_data,
+            hyperloglog_data,
+            bloom_data,
         };
 
-        Ok(Some((snapshot, latest.clone())))
+        Ok(snapshot)
+    }
+
+    /// Write an incremental snapshot holding the current value of every key
+    /// in `changed`, and a deletion for each one that no longer exists.
+    /// `base_offset` is the WAL offset of the snapshot it builds on.
+    pub async fn create_increment(
+        &self,
+        stores: super::apply::StoreRefs<'_>,
+        changed: &ChangedKeys,
+        base_offset: u64,
+        wal_offset: u64,
+    ) -> Result<PathBuf> {
+        tokio::fs::create_dir_all(&self.config.directory).await?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let filename = format!("snapshot-inc-{:020}-{}.inc", wal_offset, timestamp);
+        let path = self.config.directory.join(&filename);
+        let partial_path = self.config.directory.join(format!("{}.new", filename));
+
+        let mut removed = ChangedKeys::default();
+        let mut kv_data = HashMap::new();
+        for key in &changed.kv {
+            match stores.kv_store.get(key).await? {
+                Some(value) => {
+                    kv_data.insert(key.clone(), value);
+                }
+                None => {
+                    removed.kv.insert(key.clone());
+                }
+            }
+        }
+        let queue_data = collect_changed(&changed.queue, &mut removed.queue, |name| {
+            stores.queue_manager.and_then(|qm| qm.dump_queue(name))
+        });
+        let hash_data = collect_changed(&changed.hash, &mut removed.hash, |key| {
+            stores.hash_store.and_then(|hs| hs.dump_key(key))
+        });
+        let list_data = collect_changed(&changed.list, &mut removed.list, |key| {
+            stores.list_store.and_then(|ls| ls.dump_key(key))
+        });
+        let set_data = collect_changed(&changed.set, &mut removed.set, |key| {
+            stores.set_store.and_then(|ss| ss.dump_key(key))
+        });
+        let sorted_set_data =
+            collect_changed(&changed.sorted_set, &mut removed.sorted_set, |key| {
+                stores.sorted_set_store.and_then(|zs| zs.dump_key(key))
+            });
+        // Removed HyperLogLogs are written as `None` in their own section, so
+        // the `removed` record keeps the layout v1 increments use
+        let hyperloglog_data: HashMap<String, Option<Vec<u8>>> = changed
+            .hyperloglog
+            .iter()
+            .map(|key| {
+                let registers = stores.hyperloglog_store.and_then(|hll| hll.registers(key));
+                (key.clone(), registers)
+            })
+            .collect();
+        // Bloom filters are never removed, only created and added to
+        let bloom_data: HashMap<String, BloomFilter> = changed
+            .bloom
+            .iter()
+            .filter_map(|key| Some((key.clone(), stores.bloom_store?.get(key)?)))
+            .collect();
+
+        info!(
+            "Creating incremental snapshot at {:?} ({} changed keys since offset {})",
+            path,
+            changed.len(),
+            base_offset
+        );
+
+        let file = File::create(&partial_path).await?;
+        let mut writer = BufWriter::new(file);
+        let mut checksum = CRC64::new();
+
+        writer.write_all(INCREMENT_MAGIC).await?;
+        checksum.update(INCREMENT_MAGIC);
+        writer.write_u8(INCREMENT_VERSION).await?;
+        checksum.update(&[INCREMENT_VERSION]);
+        for field in [timestamp, base_offset, wal_offset] {
+            writer.write_u64(field).await?;
+            checksum.update(&field.to_le_bytes());
+        }
+
+        write_map_section(&mut writer, &mut checksum, &kv_data).await?;
+        write_map_section(&mut writer, &mut checksum, &queue_data).await?;
+        write_map_section(&mut writer, &mut checksum, &hash_data).await?;
+        write_map_section(&mut writer, &mut checksum, &list_data).await?;
+        write_map_section(&mut writer, &mut checksum, &set_data).await?;
+        write_map_section(&mut writer, &mut checksum, &sorted_set_data).await?;
+        write_map_section(&mut writer, &mut checksum, &hyperloglog_data).await?;
+        write_map_section(&mut writer, &mut checksum, &bloom_data).await?;
+
+        let removed_data = bincode::serde::encode_to_vec(&removed, bincode::config::legacy())
+            .map_err(std::io::Error::other)?;
+        let removed_len = removed_data.len() as u32;
+        writer.write_u32(removed_len).await?;
+        checksum.update(&removed_len.to_le_bytes());
+        writer.write_all(&removed_data).await?;
+        checksum.update(&removed_data);
+
+        writer.write_u64(checksum.finalize()).await?;
+        writer.flush().await?;
+        writer.into_inner().sync_all().await?;
+        tokio::fs::rename(&partial_path, &path).await?;
+
+        Ok(path)
+    }
+
+    /// Load and verify one incremental snapshot file
+    pub async fn load_increment(&self, path: &Path) -> Result<SnapshotIncrement> {
+        let file = File::open(path).await?;
+        let mut reader = BufReader::new(file);
+        let mut checksum = CRC64::new();
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).await?;
+        checksum.update(&magic);
+        let version = reader.read_u8().await?;
+        checksum.update(&[version]);
+        if &magic != INCREMENT_MAGIC || !(1..=INCREMENT_VERSION).contains(&version) {
+            return Err(PersistenceError::SnapshotCorrupted(path.to_path_buf()));
+        }
+
+        let mut header = [0u64; 3];
+        for field in &mut header {
+            *field = reader.read_u64().await?;
+            checksum.update(&field.to_le_bytes());
+        }
+        let [timestamp, base_offset, wal_offset] = header;
+
+        let kv_data = read_map_section(&mut reader, &mut checksum).await?;
+        let queue_data = read_map_section(&mut reader, &mut checksum).await?;
+        let hash_data = read_map_section(&mut reader, &mut checksum).await?;
+        let list_data = read_map_section(&mut reader, &mut checksum).await?;
+        let set_data = read_map_section(&mut reader, &mut checksum).await?;
+        let sorted_set_data = read_map_section(&mut reader, &mut checksum).await?;
+        let hyperloglogs: HashMap<String, Option<Vec<u8>>> = if version >= 2 {
+            read_map_section(&mut reader, &mut checksum).await?
+        } else {
+            HashMap::new()
+        };
+        let bloom_data = if version >= 3 {
+            read_map_section(&mut reader, &mut checksum).await?
+        } else {
+            HashMap::new()
+        };
+
+        let removed_len = reader.read_u32().await?;
+        checksum.update(&removed_len.to_le_bytes());
+        let mut removed_data = vec![0u8; removed_len as usize];
+        reader.read_exact(&mut removed_data).await?;
+        checksum.update(&removed_data);
+        let (mut removed, _): (ChangedKeys, _) =
+            bincode::serde::decode_from_slice(&removed_data, bincode::config::legacy())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        let stored_checksum = reader.read_u64().await?;
+        if stored_checksum != checksum.finalize() {
+            warn!("Incremental snapshot checksum mismatch at {:?}", path);
+            return Err(PersistenceError::SnapshotCorrupted(path.to_path_buf()));
+        }
+
+        let mut hyperloglog_data = HashMap::new();
+        for (key, registers) in hyperloglogs {
+            match registers {
+                Some(registers) => {
+                    hyperloglog_data.insert(key, registers);
+                }
+                None => {
+                    removed.hyperloglog.insert(key);
+                }
+            }
+        }
+
+        Ok(SnapshotIncrement {
+            timestamp,
+            base_offset,
+            wal_offset,
+            changes: Snapshot {
+                version: INCREMENT_VERSION as u32,
+                timestamp,
+                wal_offset,
+                kv_data,
+                queue_data,
+                stream_data: HashMap::new(),
+                list_data,
+                set_data,
+                sorted_set_data,
+                hash_data,
+                hyperloglog_data,
+                bloom_data,
+            },
+            removed,
+        })
+    }
+
+    /// Incremental snapshot files, oldest first
+    pub async fn list_increments(&self) -> Result<Vec<PathBuf>> {
+        if !self.config.directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut increments = Vec::new();
+        let mut dir = tokio::fs::read_dir(&self.config.directory).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if increment_offset(&path).is_some() {
+                increments.push(path);
+            }
+        }
+
+        // The zero-padded WAL offset leads the name
+        increments.sort();
+        Ok(increments)
+    }
+
+    /// Delete the increments a full snapshot at `wal_offset` supersedes
+    async fn cleanup_increments(&self, wal_offset: u64) -> Result<()> {
+        for path in self.list_increments().await? {
+            if increment_offset(&path).is_some_and(|offset| offset <= wal_offset) {
+                debug!("Removing superseded incremental snapshot: {:?}", path);
+                tokio::fs::remove_file(&path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Creation time and WAL offset from a snapshot's header, without
+    /// loading it
+    pub async fn header(path: &Path) -> Result<(u64, u64)> {
+        let mut reader = File::open(path).await?;
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).await?;
+        if magic_version(&magic).is_none() {
+            return Err(PersistenceError::SnapshotCorrupted(path.to_path_buf()));
+        }
+        let _version = reader.read_u8().await?;
+        let timestamp = reader.read_u64().await?;
+        let wal_offset = reader.read_u64().await?;
+        Ok((timestamp, wal_offset))
+    }
+
+    /// Whether the snapshot directory holds at least one snapshot
+    pub async fn has_snapshot(&self) -> Result<bool> {
+        Ok(!self.list_snapshots().await?.is_empty())
     }
 
     /// List all snapshots in directory (sorted by timestamp)
-    async fn list_snapshots(&self) -> Result<Vec<PathBuf>> {
+    /// Snapshot files in the snapshot directory, oldest first
+    pub async fn list_snapshots(&self) -> Result<Vec<PathBuf>> {
         if !self.config.directory.exists() {
             return Ok(Vec::new());
         }
@@ -501,6 +862,240 @@ impl SnapshotManager {
     }
 }
 
+/// Keys written since the last snapshot, by data type. Streams are left
+/// out: they are durable through their own persistence.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ChangedKeys {
+    pub kv: HashSet<String>,
+    pub queue: HashSet<String>,
+    pub hash: HashSet<String>,
+    pub list: HashSet<String>,
+    pub set: HashSet<String>,
+    pub sorted_set: HashSet<String>,
+    /// Kept out of the serialized record; increments carry removed
+    /// HyperLogLogs in their own section
+    #[serde(skip)]
+    pub hyperloglog: HashSet<String>,
+    /// Kept out of the serialized record too; Bloom filters are never removed
+    #[serde(skip)]
+    pub bloom: HashSet<String>,
+}
+
+impl ChangedKeys {
+    /// Note the keys `operation` writes
+    pub fn record(&mut self, operation: &Operation) {
+        match operation {
+            Operation::KVSet { key, .. } => {
+                self.kv.insert(key.clone());
+            }
+            Operation::KVDel { keys } => self.kv.extend(keys.iter().cloned()),
+            Operation::KVRename {
+                source,
+                destination,
+            } => {
+                self.kv.insert(source.clone());
+                self.kv.insert(destination.clone());
+            }
+            Operation::QueuePublish { queue, .. }
+            | Operation::QueueAck { queue, .. }
+            | Operation::QueueNack { queue, .. } => {
+                self.queue.insert(queue.clone());
+            }
+            Operation::HashSet { key, .. }
+            | Operation::HashDel { key, .. }
+            | Operation::HashIncrBy { key, .. }
+            | Operation::HashIncrByFloat { key, .. }
+            | Operation::HashLoad { key, .. } => {
+                self.hash.insert(key.clone());
+            }
+            Operation::ListPush { key, .. }
+            | Operation::ListPop { key, .. }
+            | Operation::ListSet { key, .. }
+            | Operation::ListTrim { key, .. }
+            | Operation::ListRem { key, .. }
+            | Operation::ListInsert { key, .. }
+            | Operation::ListLoad { key, .. } => {
+                self.list.insert(key.clone());
+            }
+            Operation::ListRpoplpush {
+                source,
+                destination,
+            } => {
+                self.list.insert(source.clone());
+                self.list.insert(destination.clone());
+            }
+            Operation::SetAdd { key, .. }
+            | Operation::SetRem { key, .. }
+            | Operation::SetLoad { key, .. } => {
+                self.set.insert(key.clone());
+            }
+            Operation::SetMove {
+                source,
+                destination,
+                ..
+            } => {
+                self.set.insert(source.clone());
+                self.set.insert(destination.clone());
+            }
+            Operation::SetInterStore { destination, .. }
+            | Operation::SetUnionStore { destination, .. }
+            | Operation::SetDiffStore { destination, .. } => {
+                self.set.insert(destination.clone());
+            }
+            Operation::ZAdd { key, .. }
+            | Operation::ZRem { key, .. }
+            | Operation::ZIncrBy { key, .. }
+            | Operation::ZRemRangeByRank { key, .. }
+            | Operation::ZRemRangeByScore { key, .. }
+            | Operation::SortedSetLoad { key, .. } => {
+                self.sorted_set.insert(key.clone());
+            }
+            Operation::ZInterStore { destination, .. }
+            | Operation::ZUnionStore { destination, .. }
+            | Operation::ZDiffStore { destination, .. } => {
+                self.sorted_set.insert(destination.clone());
+            }
+            Operation::HyperLogLogMerge { destination, .. } => {
+                self.hyperloglog.insert(destination.clone());
+            }
+            Operation::HyperLogLogLoad { key, .. } | Operation::HyperLogLogAdd { key, .. } => {
+                self.hyperloglog.insert(key.clone());
+            }
+            Operation::BloomReserve { key, .. }
+            | Operation::BloomAdd { key, .. }
+            | Operation::BloomLoad { key, .. } => {
+                self.bloom.insert(key.clone());
+            }
+            Operation::StreamPublish { .. }
+            | Operation::BitmapLoad { .. }
+            | Operation::SubscriptionPut { .. }
+            | Operation::SubscriptionDelete { .. } => {}
+        }
+    }
+
+    /// Add the keys of `other`
+    pub fn merge(&mut self, other: ChangedKeys) {
+        self.kv.extend(other.kv);
+        self.queue.extend(other.queue);
+        self.hash.extend(other.hash);
+        self.list.extend(other.list);
+        self.set.extend(other.set);
+        self.sorted_set.extend(other.sorted_set);
+        self.hyperloglog.extend(other.hyperloglog);
+        self.bloom.extend(other.bloom);
+    }
+
+    pub fn len(&self) -> usize {
+        self.kv.len()
+            + self.queue.len()
+            + self.hash.len()
+            + self.list.len()
+            + self.set.len()
+            + self.sorted_set.len()
+            + self.hyperloglog.len()
+            + self.bloom.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The keys written between two snapshots: their values at `wal_offset`,
+/// and the ones deleted since `base_offset`
+#[derive(Debug)]
+pub struct SnapshotIncrement {
+    pub timestamp: u64,
+    /// WAL offset of the snapshot (full or incremental) this one builds on
+    pub base_offset: u64,
+    pub wal_offset: u64,
+    /// Current values of the changed keys; streams are never included
+    pub changes: Snapshot,
+    pub removed: ChangedKeys,
+}
+
+impl SnapshotIncrement {
+    /// Layer this increment onto `snapshot`
+    pub fn apply_to(self, snapshot: &mut Snapshot) {
+        let Self {
+            timestamp,
+            wal_offset,
+            changes,
+            removed,
+            ..
+        } = self;
+        remove_keys(&mut snapshot.kv_data, removed.kv);
+        remove_keys(&mut snapshot.queue_data, removed.queue);
+        remove_keys(&mut snapshot.hash_data, removed.hash);
+        remove_keys(&mut snapshot.list_data, removed.list);
+        remove_keys(&mut snapshot.set_data, removed.set);
+        remove_keys(&mut snapshot.sorted_set_data, removed.sorted_set);
+        remove_keys(&mut snapshot.hyperloglog_data, removed.hyperloglog);
+        snapshot.kv_data.extend(changes.kv_data);
+        snapshot.queue_data.extend(changes.queue_data);
+        snapshot.hash_data.extend(changes.hash_data);
+        snapshot.list_data.extend(changes.list_data);
+        snapshot.set_data.extend(changes.set_data);
+        snapshot.sorted_set_data.extend(changes.sorted_set_data);
+        snapshot.hyperloglog_data.extend(changes.hyperloglog_data);
+        snapshot.bloom_data.extend(changes.bloom_data);
+        snapshot.timestamp = timestamp;
+        snapshot.wal_offset = wal_offset;
+    }
+}
+
+fn remove_keys<V>(map: &mut HashMap<String, V>, keys: HashSet<String>) {
+    for key in keys {
+        map.remove(&key);
+    }
+}
+
+/// Current value of each key in `keys`; the ones without a value go to
+/// `removed`
+fn collect_changed<V>(
+    keys: &HashSet<String>,
+    removed: &mut HashSet<String>,
+    value: impl Fn(&str) -> Option<V>,
+) -> HashMap<String, V> {
+    let mut out = HashMap::new();
+    for key in keys {
+        match value(key) {
+            Some(v) => {
+                out.insert(key.clone(), v);
+            }
+            None => {
+                removed.insert(key.clone());
+            }
+        }
+    }
+    out
+}
+
+/// Format version a full snapshot's magic names, `None` if it is not one
+fn magic_version(magic: &[u8; 8]) -> Option<u8> {
+    [
+        SNAPSHOT_MAGIC,
+        SNAPSHOT_MAGIC_V5,
+        SNAPSHOT_MAGIC_V4,
+        SNAPSHOT_MAGIC_V3,
+        SNAPSHOT_MAGIC_V2,
+    ]
+    .iter()
+    .position(|known| *known == magic)
+    .map(|age| SNAPSHOT_VERSION - age as u8)
+}
+
+/// WAL offset of an incremental snapshot, from its
+/// `snapshot-inc-{offset}-{timestamp}.inc` file name
+fn increment_offset(path: &Path) -> Option<u64> {
+    if path.extension().and_then(|s| s.to_str()) != Some("inc") {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    let (offset, _) = stem.strip_prefix("snapshot-inc-")?.split_once('-')?;
+    offset.parse().ok()
+}
+
 /// Snapshot statistics
 #[derive(Debug)]
 pub struct SnapshotStats {
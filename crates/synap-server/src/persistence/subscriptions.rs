@@ -0,0 +1,310 @@
+//! Durable subscription registry.
+//!
+//! Consumer groups, their memberships and committed offsets, and the positions
+//! of stream WebSocket subscribers otherwise live only in memory, so a restart
+//! silently orphans every consumer. With `persistence.durable_subscriptions`
+//! enabled each change is appended to a dedicated log (`subscriptions.wal`
+//! next to the main WAL) and replayed at boot.
+//!
+//! The log is kept apart from the main WAL because snapshots truncate the main
+//! WAL and do not capture subscriptions. It is compacted on every open, so it
+//! holds roughly one entry per live record.
+
+use super::types::{Operation, Result, WALConfig};
+use super::wal::WriteAheadLog;
+use crate::core::{ConsumerGroupConfig, ConsumerGroupManager};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// File name of the subscription log, placed next to the main WAL
+const LOG_FILE_NAME: &str = "subscriptions.wal";
+
+/// One durable piece of subscription state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SubscriptionRecord {
+    /// A consumer group and the settings it was created with
+    ConsumerGroup {
+        group_id: String,
+        topic: String,
+        partition_count: usize,
+        config: ConsumerGroupConfig,
+    },
+    /// A member of a consumer group
+    GroupMember {
+        group_id: String,
+        member_id: String,
+        session_timeout_secs: u64,
+        min_age_ms: Option<u64>,
+    },
+    /// A committed partition offset of a consumer group
+    GroupOffset {
+        group_id: String,
+        partition_id: usize,
+        offset: u64,
+    },
+    /// Next offset of a stream WebSocket subscriber in a room
+    StreamSubscriber {
+        room: String,
+        subscriber_id: String,
+        offset: u64,
+    },
+}
+
+impl SubscriptionRecord {
+    /// Registry key; writing a record with the same key replaces the old one
+    pub fn id(&self) -> String {
+        match self {
+            Self::ConsumerGroup { group_id, .. } => format!("group:{}", group_id),
+            Self::GroupMember {
+                group_id,
+                member_id,
+                ..
+            } => format!("member:{}:{}", group_id, member_id),
+            Self::GroupOffset {
+                group_id,
+                partition_id,
+                ..
+            } => format!("offset:{}:{}", group_id, partition_id),
+            Self::StreamSubscriber {
+                room,
+                subscriber_id,
+                ..
+            } => format!("stream:{}:{}", room, subscriber_id),
+        }
+    }
+
+    /// Consumer group this record belongs to, if any
+    fn group_id(&self) -> Option<&str> {
+        match self {
+            Self::ConsumerGroup { group_id, .. }
+            | Self::GroupMember { group_id, .. }
+            | Self::GroupOffset { group_id, .. } => Some(group_id),
+            Self::StreamSubscriber { .. } => None,
+        }
+    }
+}
+
+/// What [`SubscriptionRegistry::restore`] put back
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RestoreSummary {
+    pub groups: usize,
+    pub members: usize,
+    pub offsets: usize,
+}
+
+/// Durable subscription records backed by their own append-only log
+pub struct SubscriptionRegistry {
+    log: tokio::sync::Mutex<WriteAheadLog>,
+    records: RwLock<BTreeMap<String, SubscriptionRecord>>,
+}
+
+impl SubscriptionRegistry {
+    /// Path of the subscription log for a given main WAL configuration
+    pub fn log_path(wal: &WALConfig) -> PathBuf {
+        wal.path.with_file_name(LOG_FILE_NAME)
+    }
+
+    /// Open the log, replay it and compact it down to the live records
+    pub async fn open(wal: &WALConfig) -> Result<Self> {
+        let mut config = wal.clone();
+        config.path = Self::log_path(wal);
+        let mut log = WriteAheadLog::open(config).await?;
+
+        let mut records = BTreeMap::new();
+        for entry in log.replay(0).await? {
+            match entry.operation {
+                Operation::SubscriptionPut { record } => {
+                    records.insert(record.id(), record);
+                }
+                Operation::SubscriptionDelete { id } => {
+                    records.remove(&id);
+                }
+                _ => {}
+            }
+        }
+
+        // Rewrite the log with only the surviving records
+        log.truncate(u64::MAX).await?;
+        for record in records.values() {
+            log.append(Operation::SubscriptionPut {
+                record: record.clone(),
+            })
+            .await?;
+        }
+        log.flush().await?;
+
+        info!(
+            "Subscription registry opened with {} durable records",
+            records.len()
+        );
+
+        Ok(Self {
+            log: tokio::sync::Mutex::new(log),
+            records: RwLock::new(records),
+        })
+    }
+
+    /// Add or replace a record
+    pub async fn put(&self, record: SubscriptionRecord) -> Result<()> {
+        let mut log = self.log.lock().await;
+        if self.records.read().get(&record.id()) == Some(&record) {
+            return Ok(());
+        }
+        log.append(Operation::SubscriptionPut {
+            record: record.clone(),
+        })
+        .await?;
+        self.records.write().insert(record.id(), record);
+        Ok(())
+    }
+
+    /// Remove a record by [`id`](SubscriptionRecord::id); unknown IDs are ignored
+    pub async fn remove(&self, id: &str) -> Result<()> {
+        let mut log = self.log.lock().await;
+        if !self.records.read().contains_key(id) {
+            return Ok(());
+        }
+        log.append(Operation::SubscriptionDelete { id: id.to_string() })
+            .await?;
+        self.records.write().remove(id);
+        Ok(())
+    }
+
+    /// Remove every record matching `predicate`
+    pub async fn remove_where(
+        &self,
+        predicate: impl Fn(&SubscriptionRecord) -> bool,
+    ) -> Result<()> {
+        let ids: Vec<String> = self
+            .records
+            .read()
+            .iter()
+            .filter(|(_, record)| predicate(record))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in ids {
+            self.remove(&id).await?;
+        }
+        Ok(())
+    }
+
+    /// All records, ordered by ID
+    pub fn records(&self) -> Vec<SubscriptionRecord> {
+        self.records.read().values().cloned().collect()
+    }
+
+    /// Next offset recorded for a stream subscriber in a room
+    pub fn stream_offset(&self, room: &str, subscriber_id: &str) -> Option<u64> {
+        let id = format!("stream:{}:{}", room, subscriber_id);
+        match self.records.read().get(&id) {
+            Some(SubscriptionRecord::StreamSubscriber { offset, .. }) => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Recreate consumer groups, members and committed offsets.
+    ///
+    /// Restored members start a fresh session: a consumer that comes back
+    /// within its session timeout keeps its member ID and assignment, one that
+    /// does not is dropped by the next rebalance like any other dead member.
+    pub async fn restore(&self, consumer_groups: &ConsumerGroupManager) -> RestoreSummary {
+        let records = self.records();
+        let mut summary = RestoreSummary::default();
+
+        for record in &records {
+            if let SubscriptionRecord::ConsumerGroup {
+                group_id,
+                topic,
+                partition_count,
+                config,
+            } = record
+            {
+                match consumer_groups
+                    .create_group(group_id, topic, *partition_count, Some(config.clone()))
+                    .await
+                {
+                    Ok(()) => summary.groups += 1,
+                    Err(e) => warn!("Failed to restore consumer group {}: {}", group_id, e),
+                }
+            }
+        }
+
+        for record in &records {
+            match record {
+                SubscriptionRecord::GroupMember {
+                    group_id,
+                    member_id,
+                    session_timeout_secs,
+                    min_age_ms,
+                } => {
+                    let restored = consumer_groups
+                        .restore_member(group_id, member_id, *session_timeout_secs, *min_age_ms)
+                        .await;
+                    summary.members += usize::from(restored.is_ok());
+                }
+                SubscriptionRecord::GroupOffset {
+                    group_id,
+                    partition_id,
+                    offset,
+                } => {
+                    let committed = consumer_groups
+                        .commit_offset(group_id, *partition_id, *offset)
+                        .await;
+                    summary.offsets += usize::from(committed.is_ok());
+                }
+                _ => {}
+            }
+        }
+
+        for group_id in consumer_groups.list_groups().await {
+            let _ = consumer_groups.rebalance_group(&group_id).await;
+        }
+
+        summary
+    }
+
+    /// Drop member records of members that expired or left, and records of
+    /// groups that no longer exist. Runs every `interval`.
+    pub fn start_prune_task(
+        self: Arc<Self>,
+        consumer_groups: Arc<ConsumerGroupManager>,
+        interval: Duration,
+    ) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.prune(&consumer_groups).await {
+                    warn!("Failed to prune subscription registry: {}", e);
+                }
+            }
+        });
+    }
+
+    pub(crate) async fn prune(&self, consumer_groups: &ConsumerGroupManager) -> Result<()> {
+        let groups = consumer_groups.list_groups().await;
+        let mut live_members = std::collections::HashSet::new();
+        for group_id in &groups {
+            if let Ok(members) = consumer_groups.group_members(group_id).await {
+                live_members.extend(members.into_iter().map(|m| (group_id.clone(), m.id)));
+            }
+        }
+
+        self.remove_where(|record| match record {
+            SubscriptionRecord::GroupMember {
+                group_id,
+                member_id,
+                ..
+            } => !live_members.contains(&(group_id.clone(), member_id.clone())),
+            other => other
+                .group_id()
+                .is_some_and(|group_id| !groups.iter().any(|g| g == group_id)),
+        })
+        .await
+    }
+}
@@ -1,5 +1,6 @@
 use super::*;
 use crate::core::{HashStore, KVConfig, KVStore, ListStore, QueueConfig, SetStore, SortedSetStore};
+use bytes::Bytes;
 
 #[tokio::test]
 async fn test_wal_append_and_replay() {
@@ -10,6 +11,8 @@ async fn test_wal_append_and_replay() {
         fsync_mode: types::FsyncMode::Always,
         fsync_interval_ms: 1000,
         max_size_mb: 1024,
+        group_commit: Default::default(),
+        fsync_by_class: Default::default(),
     };
 
     // Clean up any existing file
@@ -20,13 +23,13 @@ async fn test_wal_append_and_replay() {
 
     let op1 = types::Operation::KVSet {
         key: "key1".to_string(),
-        value: b"value1".to_vec(),
+        value: Bytes::from_static(b"value1"),
         ttl: None,
     };
 
     let op2 = types::Operation::KVSet {
         key: "key2".to_string(),
-        value: b"value2".to_vec(),
+        value: Bytes::from_static(b"value2"),
         ttl: Some(3600),
     };
 
@@ -44,7 +47,7 @@ async fn test_wal_append_and_replay() {
     match &entries[0].operation {
         types::Operation::KVSet { key, value, ttl } => {
             assert_eq!(key, "key1");
-            assert_eq!(value, b"value1");
+            assert_eq!(value, &b"value1"[..]);
             assert_eq!(*ttl, None);
         }
         _ => panic!("Expected KVSet operation"),
@@ -53,7 +56,7 @@ async fn test_wal_append_and_replay() {
     match &entries[1].operation {
         types::Operation::KVSet { key, value, ttl } => {
             assert_eq!(key, "key2");
-            assert_eq!(value, b"value2");
+            assert_eq!(value, &b"value2"[..]);
             assert_eq!(*ttl, Some(3600));
         }
         _ => panic!("Expected KVSet operation"),
@@ -63,6 +66,58 @@ async fn test_wal_append_and_replay() {
     let _ = tokio::fs::remove_file(&config.path).await;
 }
 
+#[tokio::test]
+async fn test_wal_entry_records_request_id() {
+    let config = types::WALConfig {
+        enabled: true,
+        path: "/tmp/test_wal_request_id.wal".into(),
+        buffer_size_kb: 64,
+        fsync_mode: types::FsyncMode::Always,
+        fsync_interval_ms: 1000,
+        max_size_mb: 1024,
+        group_commit: Default::default(),
+        fsync_by_class: Default::default(),
+    };
+    let _ = tokio::fs::remove_file(&config.path).await;
+
+    let wal = AsyncWAL::open(config.clone()).await.unwrap();
+    let op = |key: &str| types::Operation::KVDel {
+        keys: vec![key.to_string()],
+    };
+
+    wal.append(op("untagged")).await.unwrap();
+    crate::server::request_id::scope("req-1".to_string(), wal.append(op("tagged")))
+        .await
+        .unwrap();
+
+    let entries = wal.replay(&config.path, 0).await.unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].request_id, None);
+    assert_eq!(entries[1].request_id.as_deref(), Some("req-1"));
+
+    let _ = tokio::fs::remove_file(&config.path).await;
+}
+
+#[test]
+fn test_wal_entry_decodes_pre_request_id_layout() {
+    // Entries written before `request_id` existed are (offset, timestamp, op).
+    let legacy = (
+        7u64,
+        1_700_000_000u64,
+        types::Operation::KVDel {
+            keys: vec!["k".to_string()],
+        },
+    );
+    let data = bincode::serde::encode_to_vec(&legacy, bincode::config::legacy()).unwrap();
+
+    let entry = types::WALEntry::decode(&data).unwrap();
+    assert_eq!(entry.offset, 7);
+    assert_eq!(entry.request_id, None);
+    assert!(matches!(entry.operation, types::Operation::KVDel { .. }));
+
+    assert!(types::WALEntry::decode(&data[..data.len() - 1]).is_err());
+}
+
 #[tokio::test]
 async fn test_crash_recovery() {
     use std::path::PathBuf;
@@ -83,6 +138,8 @@ async fn test_crash_recovery() {
             fsync_mode: types::FsyncMode::Always,
             fsync_interval_ms: 1000,
             max_size_mb: 1024,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         },
         snapshot: types::SnapshotConfig {
             enabled: true,
@@ -91,7 +148,10 @@ async fn test_crash_recovery() {
             operation_threshold: 10_000,
             max_snapshots: 10,
             compression: false,
+            incremental: Default::default(),
         },
+        durable_subscriptions: false,
+        archive: Default::default(),
     };
 
     let kv_config = KVConfig {
@@ -101,13 +161,14 @@ async fn test_crash_recovery() {
         allow_flush_commands: false,
         max_value_size_bytes: None,
         eviction_sample_size: 5,
+        ..Default::default()
     };
 
     let queue_config = QueueConfig::default();
 
     // First run: create data
     {
-        let (kv, _hs, _ls, _ss, _zs, _qm, _offset) =
+        let (kv, _hs, _ls, _ss, _zs, _hll, _bf, _qm, _offset) =
             recover(&persist_config, kv_config.clone(), queue_config.clone())
                 .await
                 .unwrap();
@@ -123,7 +184,7 @@ async fn test_crash_recovery() {
 
     // Second run: recover data
     {
-        let (kv, _hs, _ls, _ss, _zs, _qm, _offset) =
+        let (kv, _hs, _ls, _ss, _zs, _hll, _bf, _qm, _offset) =
             recover(&persist_config, kv_config.clone(), queue_config.clone())
                 .await
                 .unwrap();
@@ -155,6 +216,7 @@ async fn test_snapshot_create_and_load() {
         operation_threshold: 10_000,
         max_snapshots: 5,
         compression: false,
+        incremental: Default::default(),
     };
 
     let snapshot_mgr = SnapshotManager::new(config);
@@ -181,7 +243,7 @@ async fn test_snapshot_create_and_load() {
     // Load snapshot
     let (snapshot, _path) = snapshot_mgr.load_latest().await.unwrap().unwrap();
 
-    assert_eq!(snapshot.version, 3); // v3 adds hash/list/set/sorted-set sections
+    assert_eq!(snapshot.version, 6); // v6 adds Bloom filters
     assert_eq!(snapshot.wal_offset, 42);
     assert_eq!(snapshot.kv_data.len(), 2);
     assert_eq!(snapshot.kv_data.get("key1").unwrap(), b"value1");
@@ -205,6 +267,7 @@ async fn test_snapshot_rejects_corrupted_checksum() {
         operation_threshold: 10_000,
         max_snapshots: 5,
         compression: false,
+        incremental: Default::default(),
     };
     let snapshot_mgr = SnapshotManager::new(config);
 
@@ -249,6 +312,7 @@ async fn test_snapshot_roundtrip_all_datatypes() {
         operation_threshold: 10_000,
         max_snapshots: 5,
         compression: false,
+        incremental: Default::default(),
     };
     let snapshot_mgr = SnapshotManager::new(config);
 
@@ -274,6 +338,14 @@ async fn test_snapshot_roundtrip_all_datatypes() {
     let opts = crate::core::sorted_set::ZAddOptions::default();
     sorted_set_store.zadd("z", b"zm".to_vec(), 1.5, &opts);
 
+    let hyperloglog_store = crate::core::HyperLogLogStore::new();
+    hyperloglog_store
+        .pfadd("hll", vec![b"a".to_vec(), b"b".to_vec()], None)
+        .unwrap();
+
+    let bloom_store = crate::core::BloomFilterStore::new();
+    bloom_store.add("bf", &[b"a", b"b"]);
+
     // Snapshot every datatype, then reload from disk.
     snapshot_mgr
         .create_snapshot(
@@ -283,6 +355,9 @@ async fn test_snapshot_roundtrip_all_datatypes() {
                 list_store: Some(&list_store),
                 set_store: Some(&set_store),
                 sorted_set_store: Some(&sorted_set_store),
+                hyperloglog_store: Some(&hyperloglog_store),
+                bloom_store: Some(&bloom_store),
+                bitmap_store: None,
                 queue_manager: None,
                 stream_manager: None,
             },
@@ -293,7 +368,7 @@ async fn test_snapshot_roundtrip_all_datatypes() {
 
     let (snapshot, _path) = snapshot_mgr.load_latest().await.unwrap().unwrap();
 
-    assert_eq!(snapshot.version, 3);
+    assert_eq!(snapshot.version, 6);
     assert_eq!(snapshot.wal_offset, 99);
     assert_eq!(snapshot.kv_data.get("kvk").unwrap(), b"kvv");
     let h = snapshot.hash_data.get("h").unwrap();
@@ -305,10 +380,99 @@ async fn test_snapshot_roundtrip_all_datatypes() {
     assert_eq!(z.len(), 1);
     assert_eq!(z[0].0, b"zm");
     assert!((z[0].1 - 1.5).abs() < 1e-9);
+    assert_eq!(
+        snapshot.hyperloglog_data.get("hll"),
+        hyperloglog_store.registers("hll").as_ref()
+    );
+    assert_eq!(
+        snapshot.bloom_data.get("bf"),
+        bloom_store.get("bf").as_ref()
+    );
 
     let _ = tokio::fs::remove_dir_all(&snapshot_dir).await;
 }
 
+#[tokio::test]
+async fn test_snapshot_recovers_into_different_shard_count() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = fsck_config(dir.path());
+    let shards = |shard_count| KVConfig {
+        shard_count,
+        ..KVConfig::default()
+    };
+
+    let kv_store = KVStore::new(shards(16));
+    for i in 0..500 {
+        kv_store
+            .set(&format!("user:{i}"), format!("v{i}").into_bytes(), None)
+            .await
+            .unwrap();
+    }
+    SnapshotManager::new(config.snapshot.clone())
+        .create_snapshot(apply::StoreRefs::kv_only(&kv_store), 0)
+        .await
+        .unwrap();
+
+    let (kv, _hs, _ls, _ss, _zs, _hll, _bf, _qm, _offset) =
+        recover(&config, shards(3), QueueConfig::default())
+            .await
+            .unwrap();
+
+    assert_eq!(kv.shard_count(), 3);
+    assert_eq!(kv.dbsize().await.unwrap(), 500);
+    assert_eq!(kv.get("user:42").await.unwrap(), Some(b"v42".to_vec()));
+    let keys: usize = kv.shard_stats().iter().map(|s| s.keys).sum();
+    assert_eq!(keys, 500);
+}
+
+#[tokio::test]
+async fn test_snapshot_keeps_compressed_values_across_config_changes() {
+    use crate::compression::{CompressionAlgorithm, ValueCompressionConfig};
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = fsck_config(dir.path());
+    let compressed = KVConfig {
+        compression: ValueCompressionConfig {
+            enabled: true,
+            algorithm: CompressionAlgorithm::Zstd,
+            min_size_bytes: 64,
+            ..Default::default()
+        },
+        ..KVConfig::default()
+    };
+    let large = "document body ".repeat(100).into_bytes();
+
+    let kv_store = KVStore::new(compressed.clone());
+    kv_store.set("doc", large.clone(), None).await.unwrap();
+    kv_store.set("small", b"tiny".to_vec(), None).await.unwrap();
+    kv_store.incr("hits", 7).await.unwrap();
+    let path = SnapshotManager::new(config.snapshot.clone())
+        .create_snapshot(apply::StoreRefs::kv_only(&kv_store), 0)
+        .await
+        .unwrap();
+    // The large value went to disk compressed
+    let file_len = tokio::fs::metadata(&path).await.unwrap().len();
+    assert!(file_len < large.len() as u64 / 2);
+
+    // A store without compression reads the same snapshot
+    let (kv, _hs, _ls, _ss, _zs, _hll, _bf, _qm, _offset) =
+        recover(&config, KVConfig::default(), QueueConfig::default())
+            .await
+            .unwrap();
+    assert_eq!(kv.get("doc").await.unwrap(), Some(large.clone()));
+    assert_eq!(kv.get("small").await.unwrap(), Some(b"tiny".to_vec()));
+    assert_eq!(kv.get("hits").await.unwrap(), Some(b"7".to_vec()));
+    assert!(kv.compression_stats().is_none());
+
+    // ... and recovering with compression on compresses it again
+    let (kv, _hs, _ls, _ss, _zs, _hll, _bf, _qm, _offset) =
+        recover(&config, compressed, QueueConfig::default())
+            .await
+            .unwrap();
+    assert_eq!(kv.get("doc").await.unwrap(), Some(large));
+    assert_eq!(kv.compression_stats().unwrap().compressed_values, 1);
+}
+
 #[tokio::test]
 async fn persistence_propagates_writes_to_replication_master() {
     use crate::replication::{MasterNode, NodeRole, ReplicationConfig};
@@ -322,7 +486,11 @@ async fn persistence_propagates_writes_to_replication_master() {
     repl_config.replica_listen_address = Some("127.0.0.1:0".parse().unwrap());
 
     let kv = Arc::new(KVStore::new(KVConfig::default()));
-    let master = Arc::new(MasterNode::new(repl_config, kv, None).await.unwrap());
+    let master = Arc::new(
+        MasterNode::new(repl_config, crate::persistence::StoreArcs::kv_only(kv))
+            .await
+            .unwrap(),
+    );
     let before = master.replication_offset();
 
     let wal_path = PathBuf::from("/tmp/test_repl_piggyback.wal");
@@ -336,6 +504,8 @@ async fn persistence_propagates_writes_to_replication_master() {
             fsync_mode: types::FsyncMode::Always,
             fsync_interval_ms: 1000,
             max_size_mb: 1024,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         },
         snapshot: types::SnapshotConfig {
             enabled: false,
@@ -344,7 +514,10 @@ async fn persistence_propagates_writes_to_replication_master() {
             operation_threshold: 10_000,
             max_snapshots: 5,
             compression: false,
+            incremental: Default::default(),
         },
+        durable_subscriptions: false,
+        archive: Default::default(),
     };
 
     let layer = PersistenceLayer::new_with_replication(persist_config, Some(master.clone()))
@@ -378,6 +551,7 @@ async fn test_snapshot_cleanup_old() {
         operation_threshold: 10_000,
         max_snapshots: 3, // Keep only 3
         compression: false,
+        incremental: Default::default(),
     };
 
     let snapshot_mgr = SnapshotManager::new(config);
@@ -410,3 +584,795 @@ async fn test_snapshot_cleanup_old() {
     // Cleanup
     let _ = tokio::fs::remove_dir_all(&snapshot_dir).await;
 }
+
+fn subscription_wal_config(name: &str) -> types::WALConfig {
+    types::WALConfig {
+        enabled: true,
+        path: std::env::temp_dir().join(name).join("synap.wal"),
+        buffer_size_kb: 64,
+        fsync_mode: types::FsyncMode::Always,
+        fsync_interval_ms: 1000,
+        max_size_mb: 1024,
+        group_commit: Default::default(),
+        fsync_by_class: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_subscription_registry_survives_reopen() {
+    let config = subscription_wal_config("synap_test_subscriptions_reopen");
+    let _ = tokio::fs::remove_dir_all(config.path.parent().unwrap()).await;
+
+    {
+        let registry = SubscriptionRegistry::open(&config).await.unwrap();
+        registry
+            .put(SubscriptionRecord::StreamSubscriber {
+                room: "chat".to_string(),
+                subscriber_id: "alice".to_string(),
+                offset: 3,
+            })
+            .await
+            .unwrap();
+        registry
+            .put(SubscriptionRecord::StreamSubscriber {
+                room: "chat".to_string(),
+                subscriber_id: "alice".to_string(),
+                offset: 7,
+            })
+            .await
+            .unwrap();
+        registry
+            .put(SubscriptionRecord::StreamSubscriber {
+                room: "news".to_string(),
+                subscriber_id: "bob".to_string(),
+                offset: 1,
+            })
+            .await
+            .unwrap();
+        registry.remove("stream:news:bob").await.unwrap();
+    }
+
+    let registry = SubscriptionRegistry::open(&config).await.unwrap();
+    assert_eq!(registry.records().len(), 1);
+    assert_eq!(registry.stream_offset("chat", "alice"), Some(7));
+    assert_eq!(registry.stream_offset("news", "bob"), None);
+
+    // Reopening compacted the log down to the single live record
+    let mut log_config = config.clone();
+    log_config.path = SubscriptionRegistry::log_path(&config);
+    let log = WriteAheadLog::open(log_config).await.unwrap();
+    assert_eq!(log.replay(0).await.unwrap().len(), 1);
+
+    let _ = tokio::fs::remove_dir_all(config.path.parent().unwrap()).await;
+}
+
+#[tokio::test]
+async fn test_subscription_registry_restores_consumer_groups() {
+    use crate::core::{ConsumerGroupConfig, ConsumerGroupManager};
+
+    let config = subscription_wal_config("synap_test_subscriptions_restore");
+    let _ = tokio::fs::remove_dir_all(config.path.parent().unwrap()).await;
+
+    let member_id = {
+        let registry = SubscriptionRegistry::open(&config).await.unwrap();
+        let manager = ConsumerGroupManager::new(ConsumerGroupConfig::default());
+        manager
+            .create_group("billing", "orders", 4, None)
+            .await
+            .unwrap();
+        let member = manager.join_group("billing", 30).await.unwrap();
+
+        registry
+            .put(SubscriptionRecord::ConsumerGroup {
+                group_id: "billing".to_string(),
+                topic: "orders".to_string(),
+                partition_count: 4,
+                config: ConsumerGroupConfig::default(),
+            })
+            .await
+            .unwrap();
+        registry
+            .put(SubscriptionRecord::GroupMember {
+                group_id: "billing".to_string(),
+                member_id: member.id.clone(),
+                session_timeout_secs: 30,
+                min_age_ms: Some(500),
+            })
+            .await
+            .unwrap();
+        registry
+            .put(SubscriptionRecord::GroupOffset {
+                group_id: "billing".to_string(),
+                partition_id: 2,
+                offset: 42,
+            })
+            .await
+            .unwrap();
+        member.id
+    };
+
+    // Simulated restart: fresh manager, registry reopened from disk
+    let registry = SubscriptionRegistry::open(&config).await.unwrap();
+    let manager = ConsumerGroupManager::new(ConsumerGroupConfig::default());
+    let summary = registry.restore(&manager).await;
+    assert_eq!(
+        summary,
+        RestoreSummary {
+            groups: 1,
+            members: 1,
+            offsets: 1
+        }
+    );
+    assert_eq!(manager.get_offset("billing", 2).await.unwrap(), Some(42));
+    assert_eq!(
+        manager.member_min_age("billing", &member_id).await.unwrap(),
+        500
+    );
+    assert_eq!(
+        manager
+            .get_assignment("billing", &member_id)
+            .await
+            .unwrap()
+            .len(),
+        4
+    );
+
+    // Once the member leaves, pruning drops its record but keeps the group
+    manager.leave_group("billing", &member_id).await.unwrap();
+    registry.prune(&manager).await.unwrap();
+    let ids: Vec<String> = registry.records().iter().map(|r| r.id()).collect();
+    assert_eq!(ids, vec!["group:billing", "offset:billing:2"]);
+
+    // Deleting the group drops everything that belongs to it
+    manager.delete_group("billing").await.unwrap();
+    registry.prune(&manager).await.unwrap();
+    assert!(registry.records().is_empty());
+
+    let _ = tokio::fs::remove_dir_all(config.path.parent().unwrap()).await;
+}
+
+fn fsck_config(dir: &std::path::Path) -> types::PersistenceConfig {
+    types::PersistenceConfig {
+        enabled: true,
+        wal: types::WALConfig {
+            enabled: true,
+            path: dir.join("synap.wal"),
+            buffer_size_kb: 64,
+            fsync_mode: types::FsyncMode::Always,
+            fsync_interval_ms: 1000,
+            max_size_mb: 1024,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
+        },
+        snapshot: types::SnapshotConfig {
+            enabled: true,
+            directory: dir.join("snapshots"),
+            interval_secs: 300,
+            operation_threshold: 10_000,
+            max_snapshots: 3,
+            compression: false,
+            incremental: Default::default(),
+        },
+        durable_subscriptions: false,
+        archive: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_incremental_snapshots_layer_over_full() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let mut config = fsck_config(dir.path());
+    config.snapshot.interval_secs = 0;
+    config.snapshot.incremental = types::IncrementalSnapshotConfig {
+        enabled: true,
+        full_every: 3,
+    };
+    let layer = PersistenceLayer::new(config.clone()).await.unwrap();
+    let kv = KVStore::new(KVConfig::default());
+    let hashes = HashStore::new();
+    let stores = || apply::StoreRefs {
+        hash_store: Some(&hashes),
+        ..apply::StoreRefs::kv_only(&kv)
+    };
+    let set = |key: &'static str, value: &'static str| {
+        let kv = &kv;
+        let layer = &layer;
+        async move {
+            kv.set(key, value.as_bytes().to_vec(), None).await.unwrap();
+            layer
+                .log_kv_set(key.to_string(), value.as_bytes().to_vec(), None)
+                .await
+                .unwrap();
+        }
+    };
+
+    // The first periodic snapshot is full
+    set("a", "1").await;
+    set("b", "1").await;
+    layer.maybe_snapshot(stores()).await.unwrap();
+    let snapshots = SnapshotManager::new(config.snapshot.clone());
+    let full = snapshots.list_snapshots().await.unwrap();
+    assert_eq!(full.len(), 1);
+    let (_, full_offset) = SnapshotManager::header(&full[0]).await.unwrap();
+
+    // Then increments holding only what changed
+    set("a", "2").await;
+    kv.delete("b").await.unwrap();
+    layer.log_kv_del(vec!["b".to_string()]).await.unwrap();
+    hashes.hset("h", "f", b"v".to_vec()).unwrap();
+    layer
+        .log_hash_set("h".to_string(), "f".to_string(), b"v".to_vec())
+        .await
+        .unwrap();
+    layer.maybe_snapshot(stores()).await.unwrap();
+    set("c", "1").await;
+    layer.maybe_snapshot(stores()).await.unwrap();
+
+    let increments = snapshots.list_increments().await.unwrap();
+    assert_eq!(increments.len(), 2);
+    let first = snapshots.load_increment(&increments[0]).await.unwrap();
+    assert_eq!(first.base_offset, full_offset);
+    assert_eq!(first.wal_offset, full_offset + 3);
+    let second = snapshots.load_increment(&increments[1]).await.unwrap();
+    assert_eq!(second.base_offset, first.wal_offset);
+    assert_eq!(first.changes.kv_data.len(), 1);
+    assert!(first.removed.kv.contains("b"));
+    assert!(first.changes.hash_data.contains_key("h"));
+    assert_eq!(snapshots.list_snapshots().await.unwrap().len(), 1);
+
+    // Recovery layers base + increments and replays only what follows
+    set("d", "1").await;
+    let (kv2, hashes2, ..) = recover(&config, KVConfig::default(), QueueConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(kv2.get("a").await.unwrap(), Some(b"2".to_vec()));
+    assert_eq!(kv2.get("b").await.unwrap(), None);
+    assert_eq!(kv2.get("c").await.unwrap(), Some(b"1".to_vec()));
+    assert_eq!(kv2.get("d").await.unwrap(), Some(b"1".to_vec()));
+    assert_eq!(
+        hashes2.unwrap().hget("h", "f").unwrap(),
+        Some(b"v".to_vec())
+    );
+    let (layered, _) = snapshots.load_latest().await.unwrap().unwrap();
+    assert_eq!(layered.wal_offset, second.wal_offset);
+
+    // full_every bounds the chain; the full snapshot supersedes the increments
+    layer.maybe_snapshot(stores()).await.unwrap();
+    let latest = snapshots.list_snapshots().await.unwrap().pop().unwrap();
+    let (_, latest_offset) = SnapshotManager::header(&latest).await.unwrap();
+    assert_eq!(latest_offset, second.wal_offset + 1);
+    assert!(snapshots.list_increments().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_fsck_reports_and_repairs() {
+    use FsckIssueKind::*;
+    use tokio::io::AsyncWriteExt;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = fsck_config(dir.path());
+    let check = |repair| fsck(&config, KVConfig::default(), QueueConfig::default(), repair);
+
+    let report = check(false).await.unwrap();
+    assert!(report.issues.is_empty());
+    assert!(!report.needs_attention());
+
+    // "a" is written as a string and as a hash; the ACK has no publish
+    let mut wal = WriteAheadLog::open(config.wal.clone()).await.unwrap();
+    for operation in [
+        types::Operation::KVSet {
+            key: "a".to_string(),
+            value: Bytes::from_static(b"1"),
+            ttl: None,
+        },
+        types::Operation::HashSet {
+            key: "a".to_string(),
+            field: "f".to_string(),
+            value: Bytes::from_static(b"1"),
+        },
+        types::Operation::QueueAck {
+            queue: "jobs".to_string(),
+            message_id: "ghost".to_string(),
+        },
+    ] {
+        wal.append(operation).await.unwrap();
+    }
+    wal.flush().await.unwrap();
+    drop(wal);
+    // An entry cut off by a crash
+    let mut file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(&config.wal.path)
+        .await
+        .unwrap();
+    file.write_all(&[0, 0, 0, 0, 0, 0, 0, 100, 1, 2, 3])
+        .await
+        .unwrap();
+    drop(file);
+
+    // A good snapshot, a corrupt newer one and a leftover rewrite
+    let kv = KVStore::new(KVConfig::default());
+    kv.set("b", b"2".to_vec(), None).await.unwrap();
+    SnapshotManager::new(config.snapshot.clone())
+        .create_snapshot(apply::StoreRefs::kv_only(&kv), 1)
+        .await
+        .unwrap();
+    let corrupt = config.snapshot.directory.join("snapshot-v3-9999999999.bin");
+    tokio::fs::write(&corrupt, b"SYNAP003 garbage")
+        .await
+        .unwrap();
+    tokio::fs::write(dir.path().join("synap.wal.new"), b"partial")
+        .await
+        .unwrap();
+
+    let report = check(false).await.unwrap();
+    let kinds: Vec<_> = report.issues.iter().map(|i| i.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            CorruptSnapshot,
+            OrphanedFile,
+            TornLogTail,
+            DanglingQueueEntry,
+            TypeConflict
+        ]
+    );
+    assert_eq!(report.log_entries_checked, 3);
+    assert_eq!(report.keys_checked, 2);
+    assert!(report.needs_attention());
+
+    let report = check(true).await.unwrap();
+    let backup = report.backup_dir.clone().unwrap();
+    assert!(backup.join("synap.wal").exists());
+    assert!(backup.join("snapshot-v3-9999999999.bin").exists());
+    assert!(!corrupt.exists());
+    // The type conflict needs a decision
+    assert!(report.needs_attention());
+
+    let report = check(false).await.unwrap();
+    let kinds: Vec<_> = report.issues.iter().map(|i| i.kind).collect();
+    assert_eq!(kinds, vec![TypeConflict]);
+    assert_eq!(report.log_entries_checked, 2);
+}
+
+#[tokio::test]
+async fn test_inspect_filters_wal_and_summarizes() {
+    use tokio::io::AsyncWriteExt;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = fsck_config(dir.path());
+    let mut wal = WriteAheadLog::open(config.wal.clone()).await.unwrap();
+    for operation in [
+        types::Operation::KVSet {
+            key: "user:1".to_string(),
+            value: Bytes::from_static(b"alice"),
+            ttl: Some(60),
+        },
+        types::Operation::KVSet {
+            key: "user:2".to_string(),
+            value: vec![0xff; 4096].into(),
+            ttl: None,
+        },
+        types::Operation::HashSet {
+            key: "user:1:profile".to_string(),
+            field: "name".to_string(),
+            value: Bytes::from_static(b"Alice"),
+        },
+        types::Operation::KVDel {
+            keys: vec!["session:9".to_string()],
+        },
+    ] {
+        wal.append(operation).await.unwrap();
+    }
+    wal.flush().await.unwrap();
+    drop(wal);
+    let mut file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(&config.wal.path)
+        .await
+        .unwrap();
+    file.write_all(&[0, 0, 0, 0]).await.unwrap();
+    drop(file);
+
+    let all = dump_wal(&config.wal.path, &InspectFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(all.scanned, 4);
+    assert_eq!(all.records.len(), 4);
+    assert_eq!(all.problems.len(), 1);
+    assert!(all.records[0].detail.contains("\"alice\" ttl=60s"));
+    assert!(all.records[1].detail.starts_with("0xffff"));
+
+    let filter = InspectFilter {
+        key_prefix: Some("user:".to_string()),
+        operations: vec!["kv".to_string()],
+        ..Default::default()
+    };
+    let users = dump_wal(&config.wal.path, &filter).await.unwrap();
+    let keys: Vec<_> = users.records.iter().map(|r| r.keys[0].as_str()).collect();
+    assert_eq!(keys, ["user:1", "user:2"]);
+
+    let future = InspectFilter {
+        since: Some(u64::MAX),
+        ..Default::default()
+    };
+    assert!(
+        dump_wal(&config.wal.path, &future)
+            .await
+            .unwrap()
+            .records
+            .is_empty()
+    );
+
+    let stats = all.stats(1);
+    assert_eq!(stats.records, 4);
+    assert_eq!(stats.operations["kv_set"].count, 2);
+    assert_eq!(stats.operations["kv_set"].bytes, 4096 + 5);
+    assert_eq!(stats.operations["hash_set"].count, 1);
+    assert_eq!(stats.largest.len(), 1);
+    assert_eq!(stats.largest[0].keys, ["user:2"]);
+    assert!(stats.to_string().contains("largest writes:"));
+
+    assert!(
+        dump_wal(&dir.path().join("missing.wal"), &InspectFilter::default())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_inspect_lists_snapshot_keys() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = fsck_config(dir.path());
+    let kv = KVStore::new(KVConfig::default());
+    kv.set("a:1", b"one".to_vec(), None).await.unwrap();
+    kv.set("b:1", b"two".to_vec(), None).await.unwrap();
+    let hashes = HashStore::new();
+    hashes.hset("a:h", "f", b"v".to_vec()).unwrap();
+    let path = SnapshotManager::new(config.snapshot.clone())
+        .create_snapshot(
+            apply::StoreRefs {
+                kv_store: &kv,
+                hash_store: Some(&hashes),
+                list_store: None,
+                set_store: None,
+                sorted_set_store: None,
+                hyperloglog_store: None,
+                bloom_store: None,
+                bitmap_store: None,
+                queue_manager: None,
+                stream_manager: None,
+            },
+            7,
+        )
+        .await
+        .unwrap();
+
+    let filter = InspectFilter {
+        key_prefix: Some("a:".to_string()),
+        ..Default::default()
+    };
+    let dump = dump_snapshot(&path, &filter).await.unwrap();
+    assert_eq!(dump.scanned, 3);
+    let records: Vec<_> = dump
+        .records
+        .iter()
+        .map(|r| (r.datatype, r.keys[0].as_str(), r.bytes))
+        .collect();
+    assert_eq!(records, [("hash", "a:h", 2), ("kv", "a:1", 3)]);
+    assert!(dump.records.iter().all(|r| r.offset.is_none()));
+}
+
+#[tokio::test]
+async fn test_hyperloglog_merge_replays_from_wal() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = fsck_config(dir.path());
+
+    let source = crate::core::HyperLogLogStore::new();
+    source
+        .pfadd(
+            "visits",
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()],
+            None,
+        )
+        .unwrap();
+    source
+        .pfmerge("visits:all", vec!["visits".to_string()])
+        .unwrap();
+
+    let mut wal = WriteAheadLog::open(config.wal.clone()).await.unwrap();
+    wal.append(types::Operation::HyperLogLogMerge {
+        destination: "visits:all".to_string(),
+        registers: source.registers("visits:all").unwrap().into(),
+    })
+    .await
+    .unwrap();
+    wal.flush().await.unwrap();
+    drop(wal);
+
+    // Only the merge was logged: the destination comes back, the source doesn't.
+    let (_kv, _hs, _ls, _ss, _zs, hll, _bf, _qm, _offset) =
+        recover(&config, KVConfig::default(), QueueConfig::default())
+            .await
+            .unwrap();
+    let hll = hll.unwrap();
+    assert_eq!(hll.pfcount("visits:all").unwrap(), 3);
+    assert!(hll.pfcount("visits").is_err());
+}
+
+#[tokio::test]
+async fn test_hyperloglog_add_replays_from_wal() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = fsck_config(dir.path());
+
+    let mut wal = WriteAheadLog::open(config.wal.clone()).await.unwrap();
+    for elements in [&[b"a", b"b"][..], &[b"b", b"c"][..]] {
+        wal.append(types::Operation::HyperLogLogAdd {
+            key: "visits".to_string(),
+            elements: elements.iter().map(|e| Bytes::from_static(*e)).collect(),
+            ttl: Some(3600),
+        })
+        .await
+        .unwrap();
+    }
+    wal.flush().await.unwrap();
+    drop(wal);
+
+    let (_kv, _hs, _ls, _ss, _zs, hll, _bf, _qm, _offset) =
+        recover(&config, KVConfig::default(), QueueConfig::default())
+            .await
+            .unwrap();
+    let hll = hll.unwrap();
+    assert_eq!(hll.pfcount("visits").unwrap(), 3);
+    assert!(hll.ttl("visits").is_some_and(|ttl| ttl > 3590));
+}
+
+#[tokio::test]
+async fn test_bloom_filter_replays_from_wal() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = fsck_config(dir.path());
+
+    let mut wal = WriteAheadLog::open(config.wal.clone()).await.unwrap();
+    wal.append(types::Operation::BloomReserve {
+        key: "seen".to_string(),
+        error_rate: 0.001,
+        capacity: 500,
+    })
+    .await
+    .unwrap();
+    wal.append(types::Operation::BloomAdd {
+        key: "seen".to_string(),
+        items: vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")],
+    })
+    .await
+    .unwrap();
+    wal.flush().await.unwrap();
+    drop(wal);
+
+    let (_kv, _hs, _ls, _ss, _zs, _hll, bf, _qm, _offset) =
+        recover(&config, KVConfig::default(), QueueConfig::default())
+            .await
+            .unwrap();
+    let bf = bf.unwrap();
+    assert_eq!(bf.exists("seen", &[b"a", b"b"]), vec![true, true]);
+    let filter = bf.get("seen").unwrap();
+    assert_eq!(filter.capacity(), 500);
+    assert_eq!(filter.items(), 2);
+}
+
+#[test]
+fn test_bytes_payloads_keep_the_vec_wire_format() {
+    // Operation payloads were `Vec<u8>` before they became `Bytes`; WAL files
+    // and snapshots written then must decode byte-for-byte the same way.
+    #[derive(serde::Serialize)]
+    enum LegacyOperation {
+        KVSet {
+            key: String,
+            value: Vec<u8>,
+            ttl: Option<u64>,
+        },
+    }
+
+    let legacy = bincode::serde::encode_to_vec(
+        LegacyOperation::KVSet {
+            key: "user:1".into(),
+            value: b"alice".to_vec(),
+            ttl: Some(60),
+        },
+        bincode::config::legacy(),
+    )
+    .unwrap();
+    let current = bincode::serde::encode_to_vec(
+        types::Operation::KVSet {
+            key: "user:1".into(),
+            value: Bytes::from_static(b"alice"),
+            ttl: Some(60),
+        },
+        bincode::config::legacy(),
+    )
+    .unwrap();
+    assert_eq!(legacy, current);
+
+    let (decoded, _): (types::Operation, usize) =
+        bincode::serde::decode_from_slice(&legacy, bincode::config::legacy()).unwrap();
+    match decoded {
+        types::Operation::KVSet { key, value, ttl } => {
+            assert_eq!(key, "user:1");
+            assert_eq!(value, &b"alice"[..]);
+            assert_eq!(ttl, Some(60));
+        }
+        other => panic!("Expected KVSet, got {other:?}"),
+    }
+}
+
+/// In-memory stand-in for an S3 bucket named `bucket`: PUT, GET, DELETE and
+/// ListObjectsV2, without checking signatures
+async fn fake_bucket() -> (
+    String,
+    std::sync::Arc<parking_lot::Mutex<std::collections::BTreeMap<String, Vec<u8>>>>,
+) {
+    use axum::body::Bytes as Body;
+    use axum::extract::{Query, State};
+    use axum::http::{Method, StatusCode, Uri};
+
+    type Objects = std::sync::Arc<parking_lot::Mutex<std::collections::BTreeMap<String, Vec<u8>>>>;
+
+    fn decode(s: &str) -> String {
+        let bytes = s.as_bytes();
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'%' && i + 2 < bytes.len() {
+                out.push(u8::from_str_radix(&s[i + 1..i + 3], 16).unwrap());
+                i += 3;
+            } else {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    async fn handle(
+        State(objects): State<Objects>,
+        method: Method,
+        uri: Uri,
+        Query(query): Query<std::collections::HashMap<String, String>>,
+        body: Body,
+    ) -> (StatusCode, Vec<u8>) {
+        let path = decode(uri.path());
+        let key = path.strip_prefix("/bucket/").unwrap_or("").to_string();
+        let mut objects = objects.lock();
+        match method {
+            Method::PUT => {
+                objects.insert(key, body.to_vec());
+                (StatusCode::OK, Vec::new())
+            }
+            Method::DELETE => {
+                objects.remove(&key);
+                (StatusCode::NO_CONTENT, Vec::new())
+            }
+            Method::GET if key.is_empty() => {
+                let prefix = query.get("prefix").cloned().unwrap_or_default();
+                let contents: String = objects
+                    .iter()
+                    .filter(|(k, _)| k.starts_with(&prefix))
+                    .map(|(k, v)| {
+                        format!(
+                            "<Contents><Key>{}</Key><LastModified>{}</LastModified><Size>{}</Size></Contents>",
+                            k,
+                            chrono::Utc::now().to_rfc3339(),
+                            v.len()
+                        )
+                    })
+                    .collect();
+                let xml = format!(
+                    "<ListBucketResult><IsTruncated>false</IsTruncated>{}</ListBucketResult>",
+                    contents
+                );
+                (StatusCode::OK, xml.into_bytes())
+            }
+            Method::GET => match objects.get(&key) {
+                Some(value) => (StatusCode::OK, value.clone()),
+                None => (
+                    StatusCode::NOT_FOUND,
+                    b"<Error><Code>NoSuchKey</Code></Error>".to_vec(),
+                ),
+            },
+            _ => (StatusCode::METHOD_NOT_ALLOWED, Vec::new()),
+        }
+    }
+
+    let objects = Objects::default();
+    let app = axum::Router::new()
+        .fallback(handle)
+        .with_state(objects.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}", addr), objects)
+}
+
+#[tokio::test]
+async fn test_archive_ships_wal_and_snapshots_and_restores() {
+    let (endpoint, objects) = fake_bucket().await;
+    let archive = types::ArchiveConfig {
+        enabled: true,
+        endpoint,
+        bucket: "bucket".to_string(),
+        access_key_id: "test".to_string(),
+        secret_access_key: "secret".to_string(),
+        keep_snapshots: 1,
+        ..Default::default()
+    };
+    let source_dir = tempfile::TempDir::new().unwrap();
+    let mut source = fsck_config(source_dir.path());
+    source.archive = archive.clone();
+
+    let set = |key: &str| types::Operation::KVSet {
+        key: key.to_string(),
+        value: Bytes::copy_from_slice(key.as_bytes()),
+        ttl: None,
+    };
+    let mut wal = WriteAheadLog::open(source.wal.clone()).await.unwrap();
+    for key in ["a", "b", "c"] {
+        wal.append(set(key)).await.unwrap();
+    }
+    wal.flush().await.unwrap();
+
+    let mut archiver = WalArchiver::open(&source).await.unwrap();
+    let report = archiver.run_once().await.unwrap();
+    assert_eq!(report.segments_uploaded, 1);
+    assert_eq!(report.entries_archived, 3);
+    // Nothing new, nothing shipped
+    assert_eq!(archiver.run_once().await.unwrap(), ArchiveReport::default());
+
+    // A snapshot of a-c, then two more writes
+    let kv = KVStore::new(KVConfig::default());
+    for key in ["a", "b", "c"] {
+        kv.set(key, key.as_bytes().to_vec(), None).await.unwrap();
+    }
+    SnapshotManager::new(source.snapshot.clone())
+        .create_snapshot(apply::StoreRefs::kv_only(&kv), wal.current_offset())
+        .await
+        .unwrap();
+    for key in ["d", "e"] {
+        wal.append(set(key)).await.unwrap();
+    }
+    wal.flush().await.unwrap();
+
+    // The new entries and the snapshot go up; the first segment is covered
+    // by the snapshot and retention drops it
+    let report = archiver.run_once().await.unwrap();
+    assert_eq!(report.segments_uploaded, 1);
+    assert_eq!(report.entries_archived, 2);
+    assert_eq!(report.snapshots_uploaded, 1);
+    assert_eq!(report.objects_deleted, 1);
+    let keys: Vec<String> = objects.lock().keys().cloned().collect();
+    assert_eq!(keys.len(), 2);
+    assert!(keys.iter().any(|k| k.starts_with("synap/snapshots/")));
+    assert!(keys.contains(&"synap/wal/00000000000000000004-00000000000000000005.wal".to_string()));
+
+    // A fresh archiver resumes after the archived offsets
+    let mut resumed = WalArchiver::open(&source).await.unwrap();
+    assert_eq!(resumed.run_once().await.unwrap(), ArchiveReport::default());
+
+    // Restore into an empty directory and recover everything
+    let target_dir = tempfile::TempDir::new().unwrap();
+    let mut target = fsck_config(target_dir.path());
+    target.archive = archive;
+    let restored = restore_from_archive(&target).await.unwrap();
+    assert!(restored.snapshot.is_some());
+    assert_eq!(restored.segments, 1);
+    assert_eq!(restored.last_offset, Some(5));
+
+    let (kv, ..) = recover(&target, KVConfig::default(), QueueConfig::default())
+        .await
+        .unwrap();
+    for key in ["a", "b", "c", "d", "e"] {
+        assert_eq!(kv.get(key).await.unwrap(), Some(key.as_bytes().to_vec()));
+    }
+
+    // Restoring over existing data is refused
+    assert!(restore_from_archive(&target).await.is_err());
+}
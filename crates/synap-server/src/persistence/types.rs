@@ -1,6 +1,8 @@
+use crate::core::bloom::BloomFilter;
 use crate::core::queue::QueueMessage;
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -30,6 +32,9 @@ pub enum PersistenceError {
 
     #[error("Recovery failed: {0}")]
     RecoveryFailed(String),
+
+    #[error("Archive error: {0}")]
+    Archive(String),
 }
 
 impl From<bincode::error::EncodeError> for PersistenceError {
@@ -58,15 +63,54 @@ pub struct WALEntry {
     pub offset: u64,
     pub timestamp: u64,
     pub operation: Operation,
+    /// `X-Request-Id` of the request that produced the write, for correlating a
+    /// WAL entry with server logs. `None` for writes outside an HTTP request and
+    /// for entries written before request ids were recorded.
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+/// WAL entry layout written before `request_id` was added. Bincode is not
+/// self-describing, so older entries are decoded with this shape instead.
+#[derive(Deserialize)]
+struct WALEntryV1 {
+    offset: u64,
+    timestamp: u64,
+    operation: Operation,
+}
+
+impl WALEntry {
+    /// Decode one serialized entry, accepting both the current layout and the
+    /// pre-`request_id` one so existing WAL files keep replaying after upgrade.
+    pub fn decode(data: &[u8]) -> std::result::Result<Self, bincode::error::DecodeError> {
+        let config = bincode::config::legacy();
+        match bincode::serde::decode_from_slice::<WALEntry, _>(data, config) {
+            Ok((entry, _)) => Ok(entry),
+            Err(err) => match bincode::serde::decode_from_slice::<WALEntryV1, _>(data, config) {
+                Ok((v1, read)) if read == data.len() => Ok(WALEntry {
+                    offset: v1.offset,
+                    timestamp: v1.timestamp,
+                    operation: v1.operation,
+                    request_id: None,
+                }),
+                _ => Err(err),
+            },
+        }
+    }
 }
 
 /// Operations that can be persisted
+///
+/// Value payloads are [`Bytes`], so handing an operation to the WAL, the
+/// replication log and every replica clones a reference count rather than the
+/// value. `Bytes` serializes exactly like `Vec<u8>`, so existing WAL files and
+/// snapshots decode unchanged.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Operation {
     /// KV Store SET operation
     KVSet {
         key: String,
-        value: Vec<u8>,
+        value: Bytes,
         ttl: Option<u64>,
     },
 
@@ -96,14 +140,14 @@ pub enum Operation {
     StreamPublish {
         room: String,
         event_type: String,
-        payload: Vec<u8>,
+        payload: Bytes,
     },
 
     /// Hash SET operation
     HashSet {
         key: String,
         field: String,
-        value: Vec<u8>,
+        value: Bytes,
     },
 
     /// Hash DELETE operation
@@ -126,7 +170,7 @@ pub enum Operation {
     /// List PUSH operation (LPUSH or RPUSH)
     ListPush {
         key: String,
-        values: Vec<Vec<u8>>,
+        values: Vec<Bytes>,
         left: bool, // true = LPUSH, false = RPUSH
     },
 
@@ -141,7 +185,7 @@ pub enum Operation {
     ListSet {
         key: String,
         index: i64,
-        value: Vec<u8>,
+        value: Bytes,
     },
 
     /// List TRIM operation (LTRIM)
@@ -151,31 +195,31 @@ pub enum Operation {
     ListRem {
         key: String,
         count: i64,
-        value: Vec<u8>,
+        value: Bytes,
     },
 
     /// List INSERT operation (LINSERT)
     ListInsert {
         key: String,
         before: bool,
-        pivot: Vec<u8>,
-        value: Vec<u8>,
+        pivot: Bytes,
+        value: Bytes,
     },
 
     /// List RPOPLPUSH operation
     ListRpoplpush { source: String, destination: String },
 
     /// Set ADD operation (SADD)
-    SetAdd { key: String, members: Vec<Vec<u8>> },
+    SetAdd { key: String, members: Vec<Bytes> },
 
     /// Set REMOVE operation (SREM)
-    SetRem { key: String, members: Vec<Vec<u8>> },
+    SetRem { key: String, members: Vec<Bytes> },
 
     /// Set MOVE operation (SMOVE)
     SetMove {
         source: String,
         destination: String,
-        member: Vec<u8>,
+        member: Bytes,
     },
 
     /// Set INTER STORE operation (SINTERSTORE)
@@ -199,7 +243,7 @@ pub enum Operation {
     /// Sorted Set ADD operation (ZADD)
     ZAdd {
         key: String,
-        member: Vec<u8>,
+        member: Bytes,
         score: f64,
         nx: bool,
         xx: bool,
@@ -208,12 +252,12 @@ pub enum Operation {
     },
 
     /// Sorted Set REMOVE operation (ZREM)
-    ZRem { key: String, members: Vec<Vec<u8>> },
+    ZRem { key: String, members: Vec<Bytes> },
 
     /// Sorted Set INCREMENT BY operation (ZINCRBY)
     ZIncrBy {
         key: String,
-        member: Vec<u8>,
+        member: Bytes,
         increment: f64,
     },
 
@@ -244,6 +288,192 @@ pub enum Operation {
         destination: String,
         keys: Vec<String>,
     },
+
+    /// Subscription registry: add or replace a durable record. Written only
+    /// to the subscription log, never to the main WAL.
+    SubscriptionPut {
+        record: super::subscriptions::SubscriptionRecord,
+    },
+
+    /// Subscription registry: remove a durable record by its ID
+    SubscriptionDelete { id: String },
+
+    // Operations from here on were added later. New ones go at the end, so
+    // the variant indices of older WAL entries stay the same.
+    /// HyperLogLog MERGE operation (PFMERGE). Carries the destination's
+    /// registers after the merge, since the sources may not be in the log.
+    HyperLogLogMerge {
+        destination: String,
+        registers: Bytes,
+    },
+
+    /// Bloom filter RESERVE operation (BF.RESERVE)
+    BloomReserve {
+        key: String,
+        error_rate: f64,
+        capacity: u64,
+    },
+
+    /// Bloom filter ADD operation (BF.ADD, BF.MADD)
+    BloomAdd { key: String, items: Vec<Bytes> },
+
+    /// A whole Bloom filter, for full syncs and dumps, which only have its
+    /// bits and not the items it was built from
+    BloomLoad { key: String, filter: BloomFilter },
+
+    /// A whole hash, for full syncs and dumps. Replaces the hash at `key`
+    /// rather than merging into it; `ttl` is the seconds it had left.
+    HashLoad {
+        key: String,
+        fields: Vec<(String, Bytes)>,
+        ttl: Option<u64>,
+    },
+
+    /// A whole list, for full syncs and dumps (see [`Self::HashLoad`])
+    ListLoad {
+        key: String,
+        values: Vec<Bytes>,
+        ttl: Option<u64>,
+    },
+
+    /// A whole set, for full syncs and dumps (see [`Self::HashLoad`])
+    SetLoad {
+        key: String,
+        members: Vec<Bytes>,
+        ttl: Option<u64>,
+    },
+
+    /// A whole sorted set, for full syncs and dumps (see [`Self::HashLoad`])
+    SortedSetLoad {
+        key: String,
+        members: Vec<(Bytes, f64)>,
+        ttl: Option<u64>,
+    },
+
+    /// A whole HyperLogLog, for full syncs and dumps (see [`Self::HashLoad`])
+    HyperLogLogLoad {
+        key: String,
+        registers: Bytes,
+        ttl: Option<u64>,
+    },
+
+    /// A whole bitmap, for full syncs and dumps (see [`Self::HashLoad`])
+    BitmapLoad {
+        key: String,
+        data: Bytes,
+        ttl: Option<u64>,
+    },
+
+    /// HyperLogLog ADD operation (PFADD). Adding an element twice changes
+    /// nothing, so replaying it is safe.
+    HyperLogLogAdd {
+        key: String,
+        elements: Vec<Bytes>,
+        ttl: Option<u64>,
+    },
+}
+
+impl Operation {
+    /// Data class the operation writes to. Subscription records have none:
+    /// they live in their own log, and bitmaps are never logged.
+    pub fn data_class(&self) -> Option<DataClass> {
+        Some(match self {
+            Self::KVSet { .. } | Self::KVDel { .. } | Self::KVRename { .. } => DataClass::Kv,
+            Self::QueuePublish { .. } | Self::QueueAck { .. } | Self::QueueNack { .. } => {
+                DataClass::Queue
+            }
+            Self::StreamPublish { .. } => DataClass::Stream,
+            Self::HashSet { .. }
+            | Self::HashDel { .. }
+            | Self::HashIncrBy { .. }
+            | Self::HashIncrByFloat { .. }
+            | Self::HashLoad { .. } => DataClass::Hash,
+            Self::ListPush { .. }
+            | Self::ListPop { .. }
+            | Self::ListSet { .. }
+            | Self::ListTrim { .. }
+            | Self::ListRem { .. }
+            | Self::ListInsert { .. }
+            | Self::ListRpoplpush { .. }
+            | Self::ListLoad { .. } => DataClass::List,
+            Self::SetAdd { .. }
+            | Self::SetRem { .. }
+            | Self::SetMove { .. }
+            | Self::SetInterStore { .. }
+            | Self::SetUnionStore { .. }
+            | Self::SetDiffStore { .. }
+            | Self::SetLoad { .. } => DataClass::Set,
+            Self::ZAdd { .. }
+            | Self::ZRem { .. }
+            | Self::ZIncrBy { .. }
+            | Self::ZRemRangeByRank { .. }
+            | Self::ZRemRangeByScore { .. }
+            | Self::ZInterStore { .. }
+            | Self::ZUnionStore { .. }
+            | Self::ZDiffStore { .. }
+            | Self::SortedSetLoad { .. } => DataClass::SortedSet,
+            Self::HyperLogLogMerge { .. }
+            | Self::HyperLogLogLoad { .. }
+            | Self::HyperLogLogAdd { .. } => DataClass::HyperLogLog,
+            Self::BloomReserve { .. } | Self::BloomAdd { .. } | Self::BloomLoad { .. } => {
+                DataClass::Bloom
+            }
+            Self::SubscriptionPut { .. }
+            | Self::SubscriptionDelete { .. }
+            | Self::BitmapLoad { .. } => return None,
+        })
+    }
+
+    /// Snake-case operation name, e.g. `kv_set` or `zadd`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::KVSet { .. } => "kv_set",
+            Self::KVDel { .. } => "kv_del",
+            Self::KVRename { .. } => "kv_rename",
+            Self::QueuePublish { .. } => "queue_publish",
+            Self::QueueAck { .. } => "queue_ack",
+            Self::QueueNack { .. } => "queue_nack",
+            Self::StreamPublish { .. } => "stream_publish",
+            Self::HashSet { .. } => "hash_set",
+            Self::HashDel { .. } => "hash_del",
+            Self::HashIncrBy { .. } => "hash_incr_by",
+            Self::HashIncrByFloat { .. } => "hash_incr_by_float",
+            Self::ListPush { .. } => "list_push",
+            Self::ListPop { .. } => "list_pop",
+            Self::ListSet { .. } => "list_set",
+            Self::ListTrim { .. } => "list_trim",
+            Self::ListRem { .. } => "list_rem",
+            Self::ListInsert { .. } => "list_insert",
+            Self::ListRpoplpush { .. } => "list_rpoplpush",
+            Self::SetAdd { .. } => "set_add",
+            Self::SetRem { .. } => "set_rem",
+            Self::SetMove { .. } => "set_move",
+            Self::SetInterStore { .. } => "set_inter_store",
+            Self::SetUnionStore { .. } => "set_union_store",
+            Self::SetDiffStore { .. } => "set_diff_store",
+            Self::ZAdd { .. } => "zadd",
+            Self::ZRem { .. } => "zrem",
+            Self::ZIncrBy { .. } => "zincr_by",
+            Self::ZRemRangeByRank { .. } => "zrem_range_by_rank",
+            Self::ZRemRangeByScore { .. } => "zrem_range_by_score",
+            Self::ZInterStore { .. } => "zinter_store",
+            Self::ZUnionStore { .. } => "zunion_store",
+            Self::ZDiffStore { .. } => "zdiff_store",
+            Self::HyperLogLogMerge { .. } => "hyperloglog_merge",
+            Self::BloomReserve { .. } => "bloom_reserve",
+            Self::BloomAdd { .. } => "bloom_add",
+            Self::BloomLoad { .. } => "bloom_load",
+            Self::HashLoad { .. } => "hash_load",
+            Self::ListLoad { .. } => "list_load",
+            Self::SetLoad { .. } => "set_load",
+            Self::SortedSetLoad { .. } => "sorted_set_load",
+            Self::HyperLogLogLoad { .. } => "hyperloglog_load",
+            Self::BitmapLoad { .. } => "bitmap_load",
+            Self::HyperLogLogAdd { .. } => "hyperloglog_add",
+            Self::SubscriptionPut { .. } => "subscription_put",
+            Self::SubscriptionDelete { .. } => "subscription_delete",
+        }
+    }
 }
 
 /// Snapshot containing full system state
@@ -264,6 +494,10 @@ pub struct Snapshot {
     pub sorted_set_data: HashMap<String, Vec<(Vec<u8>, f64)>>, // Key -> Vec<(member, score)>
     #[serde(default)]
     pub hash_data: HashMap<String, HashMap<String, Vec<u8>>>, // Key -> field -> value
+    #[serde(default)]
+    pub hyperloglog_data: HashMap<String, Vec<u8>>, // Key -> registers
+    #[serde(default)]
+    pub bloom_data: HashMap<String, BloomFilter>, // Key -> filter
 }
 
 /// Stream event for snapshot (simplified from stream::StreamEvent)
@@ -282,6 +516,13 @@ pub struct PersistenceConfig {
     pub enabled: bool,
     pub wal: WALConfig,
     pub snapshot: SnapshotConfig,
+    /// Keep consumer groups, memberships, committed offsets and stream
+    /// WebSocket positions across restarts (`subscriptions.wal`)
+    #[serde(default)]
+    pub durable_subscriptions: bool,
+    /// Ship WAL segments and snapshots to S3-compatible object storage
+    #[serde(default)]
+    pub archive: ArchiveConfig,
 }
 
 impl Default for PersistenceConfig {
@@ -290,6 +531,8 @@ impl Default for PersistenceConfig {
             enabled: true, // Persistence enabled by default for data safety
             wal: WALConfig::default(),
             snapshot: SnapshotConfig::default(),
+            durable_subscriptions: false,
+            archive: ArchiveConfig::default(),
         }
     }
 }
@@ -303,6 +546,13 @@ pub struct WALConfig {
     pub fsync_mode: FsyncMode,
     pub fsync_interval_ms: u64,
     pub max_size_mb: usize,
+    /// How the async writer groups appends into one commit
+    #[serde(default)]
+    pub group_commit: GroupCommitConfig,
+    /// Per data class overrides of `fsync_mode`, e.g. `queue: always` while
+    /// KV stays `periodic`. A commit is fsynced as its strictest entry asks.
+    #[serde(default)]
+    pub fsync_by_class: BTreeMap<DataClass, FsyncMode>,
 }
 
 impl Default for WALConfig {
@@ -314,6 +564,69 @@ impl Default for WALConfig {
             fsync_mode: FsyncMode::Periodic,
             fsync_interval_ms: 1000,
             max_size_mb: 1024,
+            group_commit: GroupCommitConfig::default(),
+            fsync_by_class: BTreeMap::new(),
+        }
+    }
+}
+
+impl WALConfig {
+    /// Fsync mode for entries of `class`
+    pub fn fsync_mode_for(&self, class: Option<DataClass>) -> FsyncMode {
+        class
+            .and_then(|class| self.fsync_by_class.get(&class).copied())
+            .unwrap_or(self.fsync_mode)
+    }
+}
+
+/// Group commit tuning for the async WAL writer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupCommitConfig {
+    /// Most appends written and fsynced as one commit
+    pub max_batch_size: usize,
+    /// How long the writer waits for more appends after the first one of a
+    /// commit. 0 commits whatever is queued right away.
+    pub max_delay_us: u64,
+}
+
+impl Default for GroupCommitConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 1000,
+            max_delay_us: 0,
+        }
+    }
+}
+
+/// Data classes that can carry their own WAL fsync mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataClass {
+    Kv,
+    Hash,
+    List,
+    Set,
+    SortedSet,
+    Queue,
+    Stream,
+    #[serde(rename = "hyperloglog")]
+    HyperLogLog,
+    Bloom,
+}
+
+impl DataClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Kv => "kv",
+            Self::Hash => "hash",
+            Self::List => "list",
+            Self::Set => "set",
+            Self::SortedSet => "sorted_set",
+            Self::Queue => "queue",
+            Self::Stream => "stream",
+            Self::HyperLogLog => "hyperloglog",
+            Self::Bloom => "bloom",
         }
     }
 }
@@ -327,6 +640,10 @@ pub struct SnapshotConfig {
     pub operation_threshold: usize,
     pub max_snapshots: usize,
     pub compression: bool,
+    /// Write only the keys changed since the previous snapshot between full
+    /// snapshots
+    #[serde(default)]
+    pub incremental: IncrementalSnapshotConfig,
 }
 
 impl Default for SnapshotConfig {
@@ -338,6 +655,72 @@ impl Default for SnapshotConfig {
             operation_threshold: 10_000,
             max_snapshots: 10,
             compression: false, // Disabled for now
+            incremental: IncrementalSnapshotConfig::default(),
+        }
+    }
+}
+
+/// Incremental snapshots: periodic snapshots persist only the keys written
+/// since the previous one, layered over the last full snapshot on recovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IncrementalSnapshotConfig {
+    pub enabled: bool,
+    /// Every `full_every`-th periodic snapshot is a full one, which bounds
+    /// the chain recovery has to layer. `SAVE` and shutdown snapshots are
+    /// always full.
+    pub full_every: usize,
+}
+
+impl Default for IncrementalSnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            full_every: 6,
+        }
+    }
+}
+
+/// WAL archiving to S3-compatible object storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    pub enabled: bool,
+    /// Service endpoint, e.g. `https://s3.us-east-1.amazonaws.com` or
+    /// `http://minio:9000`. Buckets are addressed path-style.
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    /// Key prefix for every archived object
+    pub prefix: String,
+    /// Env: `SYNAP_ARCHIVE_ACCESS_KEY_ID` (or `AWS_ACCESS_KEY_ID`)
+    pub access_key_id: String,
+    /// Env: `SYNAP_ARCHIVE_SECRET_ACCESS_KEY` (or `AWS_SECRET_ACCESS_KEY`)
+    pub secret_access_key: String,
+    /// How often new WAL entries and snapshots are shipped
+    pub interval_secs: u64,
+    /// Largest WAL segment uploaded in one object
+    pub segment_max_mb: usize,
+    /// Archived snapshots to keep, newest first (at least one is always kept)
+    pub keep_snapshots: usize,
+    /// Also delete archived snapshots older than this, except the newest
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            bucket: String::new(),
+            region: "us-east-1".to_string(),
+            prefix: "synap/".to_string(),
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            interval_secs: 60,
+            segment_max_mb: 64,
+            keep_snapshots: 7,
+            max_age_days: None,
         }
     }
 }
@@ -353,3 +736,14 @@ pub enum FsyncMode {
     /// Never fsync (fastest, least safe)
     Never,
 }
+
+impl FsyncMode {
+    /// The safer of the two modes
+    pub fn stricter(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Always, _) | (_, Self::Always) => Self::Always,
+            (Self::Periodic, _) | (_, Self::Periodic) => Self::Periodic,
+            _ => Self::Never,
+        }
+    }
+}
@@ -73,9 +73,8 @@ impl WriteAheadLog {
             }
 
             // Try to deserialize
-            match bincode::serde::decode_from_slice::<WALEntry, _>(&data, bincode::config::legacy())
-            {
-                Ok((entry, _)) => {
+            match WALEntry::decode(&data) {
+                Ok(entry) => {
                     max_offset = max_offset.max(entry.offset);
                 }
                 Err(_) => {
@@ -100,6 +99,7 @@ impl WriteAheadLog {
                 .unwrap_or_default()
                 .as_secs(),
             operation,
+            request_id: crate::server::request_id::current(),
         };
 
         // Serialize entry
@@ -177,9 +177,7 @@ impl WriteAheadLog {
             }
 
             // Deserialize
-            let (entry, _): (WALEntry, _) =
-                bincode::serde::decode_from_slice(&data, bincode::config::legacy())
-                    .map_err(|_| PersistenceError::InvalidEntry)?;
+            let entry = WALEntry::decode(&data).map_err(|_| PersistenceError::InvalidEntry)?;
 
             if entry.offset >= from_offset {
                 entries.push(entry);
@@ -13,6 +13,7 @@ enum WriteRequest {
     /// A single operation with its completion notification.
     Single {
         operation: Operation,
+        request_id: Option<String>,
         response_tx: oneshot::Sender<Result<u64>>,
     },
     /// A group of operations that MUST be written contiguously and confirmed as a
@@ -21,6 +22,7 @@ enum WriteRequest {
     /// interleaves between them, and confirms once with all assigned offsets.
     Batch {
         operations: Vec<Operation>,
+        request_id: Option<String>,
         response_tx: oneshot::Sender<Result<Vec<u64>>>,
     },
 }
@@ -100,9 +102,8 @@ impl AsyncWAL {
             }
 
             // Try to deserialize
-            match bincode::serde::decode_from_slice::<WALEntry, _>(&data, bincode::config::legacy())
-            {
-                Ok((entry, _)) => {
+            match WALEntry::decode(&data) {
+                Ok(entry) => {
                     max_offset = max_offset.max(entry.offset);
                 }
                 Err(_) => {
@@ -122,109 +123,130 @@ impl AsyncWAL {
         current_offset: Arc<AtomicU64>,
         config: WALConfig,
     ) {
-        const MAX_BATCH_SIZE: usize = 1000;
-        let batch_timeout = Duration::from_millis(10);
+        let max_batch_size = config.group_commit.max_batch_size.max(1);
+        let max_delay = Duration::from_micros(config.group_commit.max_delay_us);
 
-        let mut batch: Vec<WriteRequest> = Vec::with_capacity(MAX_BATCH_SIZE);
+        let mut batch: Vec<WriteRequest> = Vec::with_capacity(max_batch_size);
         let mut last_fsync = std::time::Instant::now();
 
-        loop {
-            // Collect requests for group commit
-            tokio::select! {
-                // Receive first request (blocking)
-                Some(req) = rx.recv() => {
-                    batch.push(req);
-
-                    // Try to fill batch (non-blocking)
-                    while batch.len() < MAX_BATCH_SIZE {
-                        match rx.try_recv() {
-                            Ok(req) => batch.push(req),
-                            Err(_) => break,
-                        }
-                    }
-                }
+        // Block for the first request of a commit; stop once every sender is gone
+        while let Some(req) = rx.recv().await {
+            batch.push(req);
 
-                // Timeout for small batches
-                _ = tokio::time::sleep(batch_timeout), if !batch.is_empty() => {
-                    // Process current batch
+            // Fill the commit with what is already queued, then keep waiting
+            // up to `max_delay` for more
+            while batch.len() < max_batch_size {
+                match rx.try_recv() {
+                    Ok(req) => batch.push(req),
+                    Err(_) => break,
                 }
-
-                else => break,  // Channel closed
             }
-
-            if !batch.is_empty() {
-                // Each request produces exactly one response (a single offset, or
-                // the vector of offsets for a batch). A `Batch` request's ops are
-                // written back-to-back here, so they land contiguously in the WAL.
-                enum Pending {
-                    Single(oneshot::Sender<Result<u64>>, Result<u64>),
-                    Batch(oneshot::Sender<Result<Vec<u64>>>, Result<Vec<u64>>),
+            if !max_delay.is_zero() {
+                let deadline = tokio::time::Instant::now() + max_delay;
+                while batch.len() < max_batch_size {
+                    match tokio::time::timeout_at(deadline, rx.recv()).await {
+                        Ok(Some(req)) => batch.push(req),
+                        Ok(None) | Err(_) => break,
+                    }
                 }
-                let mut responses: Vec<Pending> = Vec::with_capacity(batch.len());
-
-                for request in batch.drain(..) {
-                    match request {
-                        WriteRequest::Single {
-                            operation,
-                            response_tx,
-                        } => {
-                            let result = Self::write_one(&mut writer, &current_offset, operation)
+            }
+
+            // Each request produces exactly one response (a single offset, or
+            // the vector of offsets for a batch). A `Batch` request's ops are
+            // written back-to-back here, so they land contiguously in the WAL.
+            enum Pending {
+                Single(oneshot::Sender<Result<u64>>, Result<u64>),
+                Batch(oneshot::Sender<Result<Vec<u64>>>, Result<Vec<u64>>),
+            }
+            let mut responses: Vec<Pending> = Vec::with_capacity(batch.len());
+            // Entries in this commit, and the strictest fsync mode they ask for
+            let mut entries = 0;
+            let mut fsync_mode = FsyncMode::Never;
+
+            for request in batch.drain(..) {
+                match request {
+                    WriteRequest::Single {
+                        operation,
+                        request_id,
+                        response_tx,
+                    } => {
+                        entries += 1;
+                        fsync_mode =
+                            fsync_mode.stricter(config.fsync_mode_for(operation.data_class()));
+                        let result =
+                            Self::write_one(&mut writer, &current_offset, operation, request_id)
                                 .await
                                 .map(|(offset, _)| offset);
-                            responses.push(Pending::Single(response_tx, result));
+                        responses.push(Pending::Single(response_tx, result));
+                    }
+                    WriteRequest::Batch {
+                        operations,
+                        request_id,
+                        response_tx,
+                    } => {
+                        entries += operations.len();
+                        for operation in &operations {
+                            fsync_mode =
+                                fsync_mode.stricter(config.fsync_mode_for(operation.data_class()));
                         }
-                        WriteRequest::Batch {
-                            operations,
-                            response_tx,
-                        } => {
-                            let mut offsets = Vec::with_capacity(operations.len());
-                            let mut batch_result = Ok(());
-                            for operation in operations {
-                                match Self::write_one(&mut writer, &current_offset, operation).await
-                                {
-                                    Ok((offset, _)) => offsets.push(offset),
-                                    Err(e) => {
-                                        batch_result = Err(e);
-                                        break;
-                                    }
+                        let mut offsets = Vec::with_capacity(operations.len());
+                        let mut batch_result = Ok(());
+                        for operation in operations {
+                            match Self::write_one(
+                                &mut writer,
+                                &current_offset,
+                                operation,
+                                request_id.clone(),
+                            )
+                            .await
+                            {
+                                Ok((offset, _)) => offsets.push(offset),
+                                Err(e) => {
+                                    batch_result = Err(e);
+                                    break;
                                 }
                             }
-                            let result = batch_result.map(|_| offsets);
-                            responses.push(Pending::Batch(response_tx, result));
                         }
+                        let result = batch_result.map(|_| offsets);
+                        responses.push(Pending::Batch(response_tx, result));
                     }
                 }
+            }
 
-                // Group commit: single fsync for entire batch
-                let should_fsync = match config.fsync_mode {
-                    FsyncMode::Always => true,
-                    FsyncMode::Periodic => {
-                        let elapsed = last_fsync.elapsed();
-                        elapsed.as_millis() >= config.fsync_interval_ms as u128
-                    }
-                    FsyncMode::Never => false,
-                };
+            // Group commit: single fsync for entire batch
+            let should_fsync = match fsync_mode {
+                FsyncMode::Always => true,
+                FsyncMode::Periodic => {
+                    let elapsed = last_fsync.elapsed();
+                    elapsed.as_millis() >= config.fsync_interval_ms as u128
+                }
+                FsyncMode::Never => false,
+            };
 
-                if should_fsync {
-                    if let Err(e) = writer.flush().await {
-                        warn!("WAL flush failed: {}", e);
-                    }
-                    if let Err(e) = writer.get_ref().sync_all().await {
-                        warn!("WAL fsync failed: {}", e);
-                    }
-                    last_fsync = std::time::Instant::now();
-                    debug!("Group commit: {} requests fsynced", responses.len());
+            let fsync_secs = if should_fsync {
+                let started = std::time::Instant::now();
+                if let Err(e) = writer.flush().await {
+                    warn!("WAL flush failed: {}", e);
+                }
+                if let Err(e) = writer.get_ref().sync_all().await {
+                    warn!("WAL fsync failed: {}", e);
                 }
+                last_fsync = std::time::Instant::now();
+                debug!("Group commit: {} requests fsynced", responses.len());
+                Some(started.elapsed().as_secs_f64())
+            } else {
+                None
+            };
+            crate::metrics::record_wal_commit(entries, fsync_secs);
 
-                // Send responses back
-                for pending in responses {
-                    match pending {
-                        Pending::Single(tx, result) => {
-                            let _ = tx.send(result);
-                        }
-                        Pending::Batch(tx, result) => {
-                            let _ = tx.send(result);
-                        }
+            // Send responses back
+            for pending in responses {
+                match pending {
+                    Pending::Single(tx, result) => {
+                        let _ = tx.send(result);
+                    }
+                    Pending::Batch(tx, result) => {
+                        let _ = tx.send(result);
                     }
                 }
             }
@@ -239,6 +261,7 @@ impl AsyncWAL {
         writer: &mut BufWriter<File>,
         current_offset: &Arc<AtomicU64>,
         operation: Operation,
+        request_id: Option<String>,
     ) -> Result<(u64, ())> {
         let offset = current_offset.fetch_add(1, Ordering::SeqCst);
         let entry = WALEntry {
@@ -248,6 +271,7 @@ impl AsyncWAL {
                 .unwrap_or_default()
                 .as_secs(),
             operation,
+            request_id,
         };
         Self::write_entry(writer, &entry)
             .await
@@ -269,11 +293,15 @@ impl AsyncWAL {
     }
 
     /// Append an operation to the WAL (returns immediately, actual write is batched)
+    ///
+    /// The entry is tagged with the caller's request id, captured here because
+    /// the writer task runs outside the request's scope.
     pub async fn append(&self, operation: Operation) -> Result<u64> {
         let (tx, rx) = oneshot::channel();
 
         let request = WriteRequest::Single {
             operation,
+            request_id: crate::server::request_id::current(),
             response_tx: tx,
         };
 
@@ -309,6 +337,7 @@ impl AsyncWAL {
 
         let request = WriteRequest::Batch {
             operations,
+            request_id: crate::server::request_id::current(),
             response_tx: tx,
         };
 
@@ -362,9 +391,8 @@ impl AsyncWAL {
             }
 
             // Deserialize
-            match bincode::serde::decode_from_slice::<WALEntry, _>(&data, bincode::config::legacy())
-            {
-                Ok((entry, _)) => {
+            match WALEntry::decode(&data) {
+                Ok(entry) => {
                     if entry.offset >= from_offset {
                         entries.push(entry);
                     }
@@ -384,6 +412,8 @@ impl AsyncWAL {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::persistence::types::{DataClass, GroupCommitConfig};
+    use bytes::Bytes;
 
     fn wal_config(dir: &str) -> WALConfig {
         WALConfig {
@@ -411,12 +441,12 @@ mod tests {
         let ops = vec![
             Operation::KVSet {
                 key: "a".into(),
-                value: b"1".to_vec(),
+                value: Bytes::from_static(b"1"),
                 ttl: None,
             },
             Operation::KVSet {
                 key: "b".into(),
-                value: b"2".to_vec(),
+                value: Bytes::from_static(b"2"),
                 ttl: None,
             },
             Operation::KVDel {
@@ -433,7 +463,7 @@ mod tests {
         let single = wal
             .append(Operation::KVSet {
                 key: "c".into(),
-                value: b"3".to_vec(),
+                value: Bytes::from_static(b"3"),
                 ttl: None,
             })
             .await
@@ -471,7 +501,7 @@ mod tests {
             let o1 = wal
                 .append(Operation::KVSet {
                     key: "k".into(),
-                    value: b"v".to_vec(),
+                    value: Bytes::from_static(b"v"),
                     ttl: None,
                 })
                 .await
@@ -486,4 +516,51 @@ mod tests {
             let _ = std::fs::remove_dir_all(&dir);
         }
     }
+
+    /// A per-class `always` fsyncs the commits carrying that class even when
+    /// the default mode never does, and concurrent appends are grouped up to
+    /// `max_batch_size`.
+    #[tokio::test]
+    async fn fsync_by_class_and_group_commit_limits() {
+        let dir = "./target/wal_fsync_by_class";
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).unwrap();
+        let config = WALConfig {
+            fsync_mode: FsyncMode::Never,
+            fsync_by_class: [(DataClass::Queue, FsyncMode::Always)].into(),
+            group_commit: GroupCommitConfig {
+                max_batch_size: 4,
+                max_delay_us: 2_000,
+            },
+            ..wal_config(dir)
+        };
+        let wal = AsyncWAL::open(config.clone()).await.unwrap();
+        let set = |key: String| Operation::KVSet {
+            key,
+            value: Bytes::from_static(b"v"),
+            ttl: None,
+        };
+
+        // KV entries stay buffered under `never`
+        wal.append(set("k".into())).await.unwrap();
+        assert!(wal.replay(&config.path, 0).await.unwrap().is_empty());
+
+        // A queue entry's commit is fsynced, taking the KV entry with it
+        wal.append(Operation::QueueAck {
+            queue: "q".into(),
+            message_id: "m".into(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(wal.replay(&config.path, 0).await.unwrap().len(), 2);
+
+        let appends = (0..10).map(|i| wal.append(set(format!("k{i}"))));
+        let offsets: Vec<u64> = futures_util::future::try_join_all(appends).await.unwrap();
+        let mut sorted = offsets.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 10);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
@@ -33,6 +33,7 @@ pub struct WALStats {
 enum WriteCommand {
     Append {
         operation: Operation,
+        request_id: Option<String>,
         response_tx: oneshot::Sender<Result<u64>>,
     },
     Flush {
@@ -166,9 +167,10 @@ impl OptimizedWAL {
                 match cmd {
                     WriteCommand::Append {
                         operation,
+                        request_id,
                         response_tx,
                     } => {
-                        appends.push((operation, response_tx));
+                        appends.push((operation, request_id, response_tx));
                     }
                     WriteCommand::Flush { response_tx } => {
                         flushes.push(response_tx);
@@ -179,12 +181,13 @@ impl OptimizedWAL {
 
             // Write all append operations
             let mut write_results = Vec::new();
-            for (operation, _) in &appends {
+            for (operation, request_id, _) in &appends {
                 let offset = current_offset.fetch_add(1, Ordering::SeqCst);
                 let entry = WALEntry {
                     offset,
                     timestamp: Self::current_timestamp(),
                     operation: operation.clone(),
+                    request_id: request_id.clone(),
                 };
 
                 match Self::write_entry(&mut writer, &entry).await {
@@ -224,7 +227,7 @@ impl OptimizedWAL {
             }
 
             // Send responses
-            for (result, (_, response_tx)) in write_results.into_iter().zip(appends) {
+            for (result, (_, _, response_tx)) in write_results.into_iter().zip(appends) {
                 let _ = response_tx.send(result);
             }
 
@@ -268,6 +271,7 @@ impl OptimizedWAL {
         self.writer_tx
             .send(WriteCommand::Append {
                 operation,
+                request_id: crate::server::request_id::current(),
                 response_tx,
             })
             .map_err(|_| PersistenceError::IOError(std::io::Error::other("WAL writer closed")))?;
@@ -333,9 +337,7 @@ impl OptimizedWAL {
                 break;
             }
 
-            if let Ok((entry, _)) =
-                bincode::serde::decode_from_slice::<WALEntry, _>(&data, bincode::config::legacy())
-            {
+            if let Ok(entry) = WALEntry::decode(&data) {
                 max_offset = max_offset.max(entry.offset);
             }
         }
@@ -367,9 +369,8 @@ impl OptimizedWAL {
                 break;
             }
 
-            match bincode::serde::decode_from_slice::<WALEntry, _>(&data, bincode::config::legacy())
-            {
-                Ok((entry, _)) => entries.push(entry),
+            match WALEntry::decode(&data) {
+                Ok(entry) => entries.push(entry),
                 Err(e) => {
                     warn!("Failed to deserialize WAL entry: {}", e);
                     break;
@@ -399,6 +400,8 @@ mod tests {
             fsync_mode: FsyncMode::Periodic,
             fsync_interval_ms: 100,
             max_size_mb: 10,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         };
 
         let wal = OptimizedWAL::open(config.clone()).await.unwrap();
@@ -411,7 +414,7 @@ mod tests {
                 wal_clone
                     .append(Operation::KVSet {
                         key: format!("key_{}", i),
-                        value: vec![i as u8; 100],
+                        value: vec![i as u8; 100].into(),
                         ttl: None,
                     })
                     .await
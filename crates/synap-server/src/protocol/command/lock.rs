@@ -0,0 +1,95 @@
+//! `lock.*` commands: lease-based distributed locks with fencing tokens.
+//!
+//! The lock name travels as `key`, so cluster routing and command permissions
+//! treat it like any other key (`lock:<name>`).
+
+use serde::{Deserialize, Serialize};
+
+use super::commands;
+
+/// `lock.acquire`: take a lock for `ttl` milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockAcquire {
+    pub key: String,
+    /// Lease length in milliseconds
+    pub ttl: u64,
+}
+
+/// `lock.renew`: extend the lease held with `token` to `ttl` milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockRenew {
+    pub key: String,
+    pub token: u64,
+    pub ttl: u64,
+}
+
+/// `lock.release`: drop the lease held with `token`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockRelease {
+    pub key: String,
+    pub token: u64,
+}
+
+/// `lock.acquire` response
+///
+/// On success `token` is the fencing token. When the lock is held, `ttl` is
+/// the holder's remaining lease in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockAcquireResponse {
+    pub acquired: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+}
+
+/// `lock.renew` response; `false` once the lease is lost
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockRenewResponse {
+    pub renewed: bool,
+}
+
+/// `lock.release` response; `false` when the lease was already lost
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockReleaseResponse {
+    pub released: bool,
+}
+
+commands! {
+    LockAcquire => "lock.acquire", LockAcquireResponse;
+    LockRenew => "lock.renew", LockRenewResponse;
+    LockRelease => "lock.release", LockReleaseResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_acquire_response_omits_absent_fields() {
+        let granted = LockAcquireResponse {
+            acquired: true,
+            token: Some(7),
+            ttl: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&granted).unwrap(),
+            json!({"acquired": true, "token": 7})
+        );
+        let refused: LockAcquireResponse = serde_json::from_value(json!({"ttl": 500})).unwrap();
+        assert!(!refused.acquired);
+        assert_eq!(refused.token, None);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(LockAcquire::NAME, "lock.acquire");
+        assert_eq!(LockRenew::NAME, "lock.renew");
+        assert_eq!(LockRelease::NAME, "lock.release");
+    }
+}
@@ -0,0 +1,52 @@
+//! Typed command payloads.
+//!
+//! The payloads and responses of the commands whose handlers decode a typed
+//! struct instead of picking fields out of the JSON payload. The Rust SDK
+//! keeps its own copies in `synap_sdk::protocol`; its embedded-server tests
+//! send each of them through these handlers, so a renamed field fails there.
+//!
+//! - [`bloom`] — the `bf.*` commands.
+//! - [`kv`] — the `kv.*` and `key.*` commands.
+//! - [`lock`] — the `lock.*` commands.
+//! - [`ratelimit`] — the `ratelimit.*` commands.
+//! - [`schedule`] — the `schedule.*` commands.
+//! - [`timeseries`] — the `ts.*` commands.
+//! - [`trace`] — the `message.trace` command.
+
+pub mod bloom;
+pub mod kv;
+pub mod lock;
+pub mod ratelimit;
+pub mod schedule;
+pub mod timeseries;
+pub mod trace;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// A command payload and the response it produces.
+///
+/// The handler decodes the payload of a [`Request`](crate::Request) named
+/// [`NAME`](Command::NAME) into `Self` and answers with `Self::Response`.
+/// [`Embedded::execute`](crate::Embedded::execute) runs one in process.
+pub trait Command: Serialize + DeserializeOwned {
+    /// Command name, e.g. `"kv.set"`
+    const NAME: &'static str;
+
+    /// Payload returned on success
+    type Response: Serialize + DeserializeOwned;
+}
+
+/// Implement [`Command`] for a list of `Type => "name", Response` entries.
+macro_rules! commands {
+    ($($command:ty => $name:literal, $response:ty;)+) => {
+        $(
+            impl $crate::protocol::command::Command for $command {
+                const NAME: &'static str = $name;
+                type Response = $response;
+            }
+        )+
+    };
+}
+
+pub(crate) use commands;
@@ -0,0 +1,99 @@
+//! `ratelimit.*` commands: atomic per-key rate limiters.
+
+use serde::{Deserialize, Serialize};
+
+use super::commands;
+
+fn one() -> u64 {
+    1
+}
+
+fn is_one(value: &u64) -> bool {
+    *value == 1
+}
+
+/// Rate limiting algorithm
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAlgorithm {
+    /// `max_tokens` at once, refilled at `refill_rate` per second
+    #[default]
+    TokenBucket,
+    /// At most `max_tokens` per sliding `window`
+    SlidingWindow,
+}
+
+/// `ratelimit.check`: take `cost` units from the limiter on `key`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitCheck {
+    pub key: String,
+    /// Bucket capacity, or the limit per window
+    pub max_tokens: u64,
+    /// Tokens added per second (token bucket)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refill_rate: Option<f64>,
+    /// Window length in milliseconds (sliding window)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<u64>,
+    #[serde(default)]
+    pub algorithm: RateLimitAlgorithm,
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub cost: u64,
+}
+
+/// `ratelimit.reset`: drop the limiter on `key`, so it starts full
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitReset {
+    pub key: String,
+}
+
+/// `ratelimit.check` response
+///
+/// `retry_after` is the wait in milliseconds before the same check would be
+/// allowed, present only when it was denied.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitCheckResponse {
+    pub allowed: bool,
+    pub remaining: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+}
+
+/// `ratelimit.reset` response; `false` when the key held no limiter
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitResetResponse {
+    pub reset: bool,
+}
+
+commands! {
+    RateLimitCheck => "ratelimit.check", RateLimitCheckResponse;
+    RateLimitReset => "ratelimit.reset", RateLimitResetResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_defaults() {
+        let check: RateLimitCheck =
+            serde_json::from_value(json!({"key": "api:1", "max_tokens": 10, "refill_rate": 2.5}))
+                .unwrap();
+        assert_eq!(check.algorithm, RateLimitAlgorithm::TokenBucket);
+        assert_eq!(check.cost, 1);
+        assert_eq!(
+            serde_json::to_value(&check).unwrap(),
+            json!({"key": "api:1", "max_tokens": 10, "refill_rate": 2.5, "algorithm": "token_bucket"})
+        );
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(RateLimitCheck::NAME, "ratelimit.check");
+        assert_eq!(RateLimitReset::NAME, "ratelimit.reset");
+    }
+}
@@ -0,0 +1,183 @@
+//! `schedule.*` commands: cron schedules that publish a templated message.
+//!
+//! A schedule fires on a five-field cron expression evaluated in UTC and
+//! publishes its `payload` to a queue, Pub/Sub topic or stream room. String
+//! values in the payload may hold placeholders, filled in at each fire:
+//!
+//! - `{{schedule}}` — the schedule name
+//! - `{{fire_time}}` — the fire time, RFC 3339 (`2026-01-31T02:00:00Z`)
+//! - `{{fire_timestamp}}` — the fire time in Unix seconds
+//! - `{{fire_count}}` — 1 on the first fire, counting failed ones
+//! - `{{uuid}}` — a random UUID, different on every fire
+//!
+//! A string that is nothing but `{{fire_timestamp}}` or `{{fire_count}}`
+//! becomes a JSON number.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::commands;
+
+/// Where a schedule publishes, e.g. `{"queue": "reports"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleTarget {
+    Queue(String),
+    Topic(String),
+    /// A stream room
+    Stream(String),
+}
+
+/// `schedule.create`: start a schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleSpec {
+    pub name: String,
+    /// Five fields (minute, hour, day of month, month, day of week) or one of
+    /// `@yearly`, `@monthly`, `@weekly`, `@daily`, `@hourly`; always UTC
+    pub cron: String,
+    pub target: ScheduleTarget,
+    /// Message template
+    pub payload: Value,
+    /// Event type of stream publishes; `"scheduled"` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+}
+
+/// Counters of one schedule
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleProgress {
+    /// Fires whose message was published
+    pub fired: u64,
+    /// Fires whose publish failed
+    pub failed: u64,
+    /// Unix timestamp (milliseconds) of the last fire
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_fired_at: Option<u64>,
+    /// Unix timestamp (milliseconds) of the next fire
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_fire_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// A schedule with its counters, as returned by `schedule.create`,
+/// `schedule.get` and `schedule.list`
+///
+/// The counters sit next to `spec` rather than inside it, so the spec keeps
+/// rejecting unknown fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleStatus {
+    pub spec: ScheduleSpec,
+    #[serde(flatten)]
+    pub progress: ScheduleProgress,
+}
+
+/// `schedule.list`: every schedule, sorted by name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleList {}
+
+/// `schedule.get`: one schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleGet {
+    pub name: String,
+}
+
+/// `schedule.delete`: stop and forget a schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleDelete {
+    pub name: String,
+}
+
+/// `schedule.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleListResponse {
+    pub schedules: Vec<ScheduleStatus>,
+}
+
+/// `schedule.get` response; `schedule` is absent when there is none by that
+/// name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleGetResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<ScheduleStatus>,
+}
+
+/// `schedule.delete` response; `false` when there was none by that name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleDeleteResponse {
+    pub deleted: bool,
+}
+
+commands! {
+    ScheduleSpec => "schedule.create", ScheduleStatus;
+    ScheduleList => "schedule.list", ScheduleListResponse;
+    ScheduleGet => "schedule.get", ScheduleGetResponse;
+    ScheduleDelete => "schedule.delete", ScheduleDeleteResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_status_round_trips() {
+        let status = ScheduleStatus {
+            spec: ScheduleSpec {
+                name: "nightly".to_string(),
+                cron: "0 2 * * *".to_string(),
+                target: ScheduleTarget::Queue("reports".to_string()),
+                payload: json!({"at": "{{fire_time}}"}),
+                event: None,
+            },
+            progress: ScheduleProgress {
+                fired: 3,
+                ..Default::default()
+            },
+        };
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "spec": {
+                    "name": "nightly",
+                    "cron": "0 2 * * *",
+                    "target": {"queue": "reports"},
+                    "payload": {"at": "{{fire_time}}"}
+                },
+                "fired": 3,
+                "failed": 0
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ScheduleStatus>(value).unwrap(),
+            status
+        );
+    }
+
+    #[test]
+    fn test_spec_rejects_unknown_fields() {
+        let spec = json!({
+            "name": "n",
+            "cron": "* * * * *",
+            "target": {"topic": "ticks"},
+            "payload": null,
+            "timezone": "Europe/Lisbon"
+        });
+        assert!(serde_json::from_value::<ScheduleSpec>(spec).is_err());
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(ScheduleSpec::NAME, "schedule.create");
+        assert_eq!(ScheduleList::NAME, "schedule.list");
+        assert_eq!(ScheduleGet::NAME, "schedule.get");
+        assert_eq!(ScheduleDelete::NAME, "schedule.delete");
+    }
+}
@@ -0,0 +1,42 @@
+//! `message.trace` command: the delivery timeline of a queue message or
+//! stream event, recorded by [`MessageTracer`](crate::core::MessageTracer).
+
+use serde::{Deserialize, Serialize};
+
+use super::commands;
+use crate::core::MessageTrace;
+
+/// `message.trace`: the timeline of a queue message or stream event id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageTraceGet {
+    pub id: String,
+}
+
+/// `message.trace` response; `trace` is absent when the id was not traced or
+/// its trace was evicted
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageTraceResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<MessageTrace>,
+}
+
+commands! {
+    MessageTraceGet => "message.trace", MessageTraceResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(MessageTraceGet::NAME, "message.trace");
+        assert_eq!(
+            serde_json::from_value::<MessageTraceResponse>(json!({})).unwrap(),
+            MessageTraceResponse::default()
+        );
+    }
+}
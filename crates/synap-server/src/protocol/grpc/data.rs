@@ -0,0 +1,256 @@
+//! `KvService`, `HashService` and `ListService`.
+
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use super::pb::{self, hash_service_server::HashService, kv_service_server::KvService};
+use super::pb::{ListEnd, list_service_server::ListService};
+use super::{select_db, synap_status};
+use crate::server::handlers::AppState;
+
+pub(super) struct KvGrpc {
+    state: Arc<AppState>,
+}
+
+impl KvGrpc {
+    pub(super) fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl KvService for KvGrpc {
+    async fn get(
+        &self,
+        request: Request<pb::KeyRequest>,
+    ) -> Result<Response<pb::GetResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let value = state
+            .kv_store
+            .get_shared(&request.get_ref().key)
+            .await
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::GetResponse {
+            value: value.map(|v| v.to_vec()),
+        }))
+    }
+
+    async fn set(&self, request: Request<pb::SetRequest>) -> Result<Response<pb::Empty>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.into_inner();
+        state
+            .kv_store
+            .set(req.key, req.value, req.ttl_secs)
+            .await
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::Empty {}))
+    }
+
+    async fn delete(
+        &self,
+        request: Request<pb::KeysRequest>,
+    ) -> Result<Response<pb::CountResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let mut count = 0;
+        for key in &request.get_ref().keys {
+            if state.kv_store.delete(key).await.map_err(synap_status)? {
+                count += 1;
+            }
+        }
+        Ok(Response::new(pb::CountResponse { count }))
+    }
+
+    async fn exists(
+        &self,
+        request: Request<pb::KeyRequest>,
+    ) -> Result<Response<pb::BoolResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let value = state
+            .kv_store
+            .exists(&request.get_ref().key)
+            .await
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::BoolResponse { value }))
+    }
+
+    async fn expire(
+        &self,
+        request: Request<pb::ExpireRequest>,
+    ) -> Result<Response<pb::BoolResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.get_ref();
+        let value = state
+            .kv_store
+            .expire(&req.key, req.ttl_secs)
+            .await
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::BoolResponse { value }))
+    }
+
+    async fn ttl(
+        &self,
+        request: Request<pb::KeyRequest>,
+    ) -> Result<Response<pb::TtlResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let ttl_secs = state
+            .kv_store
+            .ttl(&request.get_ref().key)
+            .await
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::TtlResponse { ttl_secs }))
+    }
+
+    async fn incr(
+        &self,
+        request: Request<pb::IncrRequest>,
+    ) -> Result<Response<pb::IntResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.get_ref();
+        let value = state
+            .kv_store
+            .incr(&req.key, req.by)
+            .await
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::IntResponse { value }))
+    }
+}
+
+pub(super) struct HashGrpc {
+    state: Arc<AppState>,
+}
+
+impl HashGrpc {
+    pub(super) fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl HashService for HashGrpc {
+    async fn set(
+        &self,
+        request: Request<pb::HashSetRequest>,
+    ) -> Result<Response<pb::CountResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.into_inner();
+        let mut count = 0;
+        for (field, value) in req.fields {
+            if state
+                .hash_store
+                .hset(&req.key, &field, value)
+                .map_err(synap_status)?
+            {
+                count += 1;
+            }
+        }
+        Ok(Response::new(pb::CountResponse { count }))
+    }
+
+    async fn get(
+        &self,
+        request: Request<pb::HashFieldRequest>,
+    ) -> Result<Response<pb::GetResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.get_ref();
+        let value = state
+            .hash_store
+            .hget(&req.key, &req.field)
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::GetResponse { value }))
+    }
+
+    async fn delete(
+        &self,
+        request: Request<pb::HashDeleteRequest>,
+    ) -> Result<Response<pb::CountResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.get_ref();
+        let count = state
+            .hash_store
+            .hdel(&req.key, &req.fields)
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::CountResponse {
+            count: count as u64,
+        }))
+    }
+
+    async fn get_all(
+        &self,
+        request: Request<pb::KeyRequest>,
+    ) -> Result<Response<pb::HashGetAllResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let fields = state
+            .hash_store
+            .hgetall(&request.get_ref().key)
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::HashGetAllResponse { fields }))
+    }
+}
+
+pub(super) struct ListGrpc {
+    state: Arc<AppState>,
+}
+
+impl ListGrpc {
+    pub(super) fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl ListService for ListGrpc {
+    async fn push(
+        &self,
+        request: Request<pb::ListPushRequest>,
+    ) -> Result<Response<pb::CountResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.into_inner();
+        let len = match req.end() {
+            ListEnd::Left => state.list_store.lpush(&req.key, req.values, false),
+            ListEnd::Right => state.list_store.rpush(&req.key, req.values, false),
+        }
+        .map_err(synap_status)?;
+        Ok(Response::new(pb::CountResponse { count: len as u64 }))
+    }
+
+    async fn pop(
+        &self,
+        request: Request<pb::ListPopRequest>,
+    ) -> Result<Response<pb::ValuesResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.get_ref();
+        let count = Some(req.count.unwrap_or(1) as usize);
+        let values = match req.end() {
+            ListEnd::Left => state.list_store.lpop(&req.key, count),
+            ListEnd::Right => state.list_store.rpop(&req.key, count),
+        }
+        .map_err(synap_status)?;
+        Ok(Response::new(pb::ValuesResponse { values }))
+    }
+
+    async fn range(
+        &self,
+        request: Request<pb::ListRangeRequest>,
+    ) -> Result<Response<pb::ValuesResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let req = request.get_ref();
+        let values = state
+            .list_store
+            .lrange(&req.key, req.start, req.stop)
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::ValuesResponse { values }))
+    }
+
+    async fn len(
+        &self,
+        request: Request<pb::KeyRequest>,
+    ) -> Result<Response<pb::CountResponse>, Status> {
+        let state = select_db(&self.state, request.metadata())?;
+        let len = state
+            .list_store
+            .llen(&request.get_ref().key)
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::CountResponse { count: len as u64 }))
+    }
+}
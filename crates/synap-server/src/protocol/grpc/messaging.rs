@@ -0,0 +1,393 @@
+//! `QueueService`, `StreamService` and `PubSubService`.
+//!
+//! The streaming calls run one task per call that feeds a bounded channel,
+//! the same loops the WebSocket consumers use: queues and stream rooms are
+//! polled every 100 ms, Pub/Sub messages are pushed by the router. A call is
+//! tracked in the client list for as long as it is open, so `CLIENT KILL`
+//! ends it.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use tracing::{error, info};
+
+use super::pb::{self, pub_sub_service_server::PubSubService};
+use super::pb::{queue_service_server::QueueService, stream_service_server::StreamService};
+use super::synap_status;
+use crate::core::pubsub::Message as PubSubMessage;
+use crate::server::handlers::AppState;
+
+/// How often a consume call looks for new queue messages or stream events
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Items buffered per streaming call before the producer waits for the client
+const STREAM_BUFFER: usize = 64;
+
+/// Largest batch of stream events read per poll
+const STREAM_BATCH: usize = 100;
+
+/// Register a streaming call in the client list; `None` is the peer address
+/// when the transport does not report one
+async fn track_client(
+    state: &AppState,
+    client_id: &str,
+    remote: Option<std::net::SocketAddr>,
+) -> crate::monitoring::KillSignal {
+    let addr = remote.map(|a| a.to_string()).unwrap_or_default();
+    let info = crate::monitoring::ClientInfo::new(client_id.to_string(), addr, SystemTime::now());
+    state.client_list_manager.add(info).await
+}
+
+/// Stream manager errors are plain strings; a missing room is the one a
+/// client can act on
+fn stream_status(error: String) -> Status {
+    if error.contains("not found") {
+        Status::not_found(error)
+    } else {
+        Status::internal(error)
+    }
+}
+
+pub(super) struct QueueGrpc {
+    state: Arc<AppState>,
+}
+
+impl QueueGrpc {
+    pub(super) fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    fn queues(&self) -> Result<Arc<crate::core::QueueManager>, Status> {
+        self.state
+            .queue_manager()
+            .cloned()
+            .ok_or_else(|| Status::unavailable("queue subsystem not enabled"))
+    }
+}
+
+#[tonic::async_trait]
+impl QueueService for QueueGrpc {
+    type ConsumeStream = ReceiverStream<Result<pb::QueueMessage, Status>>;
+
+    async fn publish(
+        &self,
+        request: Request<pb::QueuePublishRequest>,
+    ) -> Result<Response<pb::QueuePublishResponse>, Status> {
+        let req = request.into_inner();
+        let priority = match req.priority {
+            Some(p) if p > 9 => return Err(Status::invalid_argument("priority must be 0-9")),
+            p => p.map(|p| p as u8),
+        };
+        let queues = self.queues()?;
+        let (message_id, duplicate) = match req.idempotency_key.as_deref() {
+            Some(key) => queues
+                .publish_idempotent(&req.queue, req.payload, priority, req.max_retries, key)
+                .await
+                .map(|published| (published.message_id().to_string(), published.is_duplicate())),
+            None => queues
+                .publish(&req.queue, req.payload, priority, req.max_retries)
+                .await
+                .map(|message_id| (message_id, false)),
+        }
+        .map_err(synap_status)?;
+        Ok(Response::new(pb::QueuePublishResponse {
+            message_id,
+            duplicate,
+        }))
+    }
+
+    async fn consume(
+        &self,
+        request: Request<pb::QueueConsumeRequest>,
+    ) -> Result<Response<Self::ConsumeStream>, Status> {
+        let queues = self.queues()?;
+        let remote = request.remote_addr();
+        let pb::QueueConsumeRequest { queue, consumer_id } = request.into_inner();
+
+        let client_id = format!("grpc-queue-{}-{}", queue, consumer_id);
+        let kill = track_client(&self.state, &client_id, remote).await;
+        let clients = self.state.client_list_manager.clone();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+        tokio::spawn(async move {
+            loop {
+                let next = match queues.consume(&queue, &consumer_id).await {
+                    Ok(Some(msg)) => Ok(pb::QueueMessage {
+                        id: msg.id,
+                        payload: msg.payload.to_vec(),
+                        priority: msg.priority.into(),
+                        retry_count: msg.retry_count,
+                        max_retries: msg.max_retries,
+                        headers: msg.headers,
+                    }),
+                    Ok(None) => {
+                        // Nothing ready: wait for the next poll, the client
+                        // hanging up, or a kill
+                        tokio::select! {
+                            _ = tokio::time::sleep(POLL_INTERVAL) => continue,
+                            _ = tx.closed() => break,
+                            _ = kill.killed() => break,
+                        }
+                    }
+                    Err(e) => {
+                        error!("Queue consume error: {}", e);
+                        Err(synap_status(e))
+                    }
+                };
+                let failed = next.is_err();
+                if tx.send(next).await.is_err() || failed {
+                    break;
+                }
+            }
+            clients.remove(&client_id).await;
+            info!("gRPC queue consumer {} disconnected", consumer_id);
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn ack(
+        &self,
+        request: Request<pb::QueueAckRequest>,
+    ) -> Result<Response<pb::Empty>, Status> {
+        let req = request.get_ref();
+        self.queues()?
+            .ack(&req.queue, &req.message_id)
+            .await
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::Empty {}))
+    }
+
+    async fn nack(
+        &self,
+        request: Request<pb::QueueNackRequest>,
+    ) -> Result<Response<pb::Empty>, Status> {
+        let req = request.get_ref();
+        self.queues()?
+            .nack(&req.queue, &req.message_id, req.requeue.unwrap_or(true))
+            .await
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::Empty {}))
+    }
+}
+
+pub(super) struct StreamGrpc {
+    state: Arc<AppState>,
+}
+
+impl StreamGrpc {
+    pub(super) fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    fn rooms(&self) -> Result<Arc<crate::core::StreamManager>, Status> {
+        self.state
+            .stream_manager()
+            .cloned()
+            .ok_or_else(|| Status::unavailable("stream subsystem not enabled"))
+    }
+}
+
+#[tonic::async_trait]
+impl StreamService for StreamGrpc {
+    type ConsumeStream = ReceiverStream<Result<pb::StreamEvent, Status>>;
+
+    async fn publish(
+        &self,
+        request: Request<pb::StreamPublishRequest>,
+    ) -> Result<Response<pb::StreamPublishResponse>, Status> {
+        let req = request.into_inner();
+        let rooms = self.rooms()?;
+        let (offset, duplicate) = match req.idempotency_key.as_deref() {
+            Some(key) => {
+                rooms
+                    .publish_idempotent(&req.room, &req.event, req.data, key)
+                    .await
+            }
+            None => rooms
+                .publish(&req.room, &req.event, req.data)
+                .await
+                .map(|offset| (offset, false)),
+        }
+        .map_err(stream_status)?;
+        Ok(Response::new(pb::StreamPublishResponse {
+            offset,
+            duplicate,
+        }))
+    }
+
+    async fn consume(
+        &self,
+        request: Request<pb::StreamConsumeRequest>,
+    ) -> Result<Response<Self::ConsumeStream>, Status> {
+        let rooms = self.rooms()?;
+        let remote = request.remote_addr();
+        let pb::StreamConsumeRequest {
+            room,
+            subscriber_id,
+            mut from_offset,
+        } = request.into_inner();
+        if !rooms.has_room(&room) {
+            return Err(Status::not_found(format!("Room '{}' not found", room)));
+        }
+
+        let client_id = format!("grpc-stream-{}-{}", room, subscriber_id);
+        let kill = track_client(&self.state, &client_id, remote).await;
+        let clients = self.state.client_list_manager.clone();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+        tokio::spawn(async move {
+            'poll: loop {
+                match rooms
+                    .consume(&room, &subscriber_id, from_offset, STREAM_BATCH)
+                    .await
+                {
+                    Ok(events) if !events.is_empty() => {
+                        for event in events {
+                            from_offset = event.offset + 1;
+                            let event = pb::StreamEvent {
+                                id: event.id,
+                                offset: event.offset,
+                                event: event.event,
+                                data: event.data,
+                                timestamp: event.timestamp,
+                            };
+                            if tx.send(Ok(event)).await.is_err() {
+                                break 'poll;
+                            }
+                        }
+                    }
+                    Ok(_) => {
+                        tokio::select! {
+                            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                            _ = tx.closed() => break,
+                            _ = kill.killed() => break,
+                        }
+                    }
+                    Err(e) => {
+                        error!("Stream consume error: {}", e);
+                        let _ = tx.send(Err(stream_status(e))).await;
+                        break;
+                    }
+                }
+            }
+            clients.remove(&client_id).await;
+            info!("gRPC stream subscriber {} disconnected", subscriber_id);
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+pub(super) struct PubSubGrpc {
+    state: Arc<AppState>,
+}
+
+impl PubSubGrpc {
+    pub(super) fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    fn router(&self) -> Result<Arc<crate::core::PubSubRouter>, Status> {
+        self.state
+            .pubsub_router
+            .clone()
+            .ok_or_else(|| Status::unavailable("pubsub subsystem not enabled"))
+    }
+}
+
+#[tonic::async_trait]
+impl PubSubService for PubSubGrpc {
+    type SubscribeStream = ReceiverStream<Result<pb::PubSubMessage, Status>>;
+
+    async fn publish(
+        &self,
+        request: Request<pb::PubSubPublishRequest>,
+    ) -> Result<Response<pb::PubSubPublishResponse>, Status> {
+        let publisher = match request.remote_addr() {
+            Some(addr) => format!("grpc:{addr}"),
+            None => "grpc".to_string(),
+        };
+        let req = request.into_inner();
+        // Same payload rule as SynapRPC's PUBLISH: JSON when it parses,
+        // otherwise a JSON string
+        let payload: serde_json::Value =
+            serde_json::from_slice(&req.payload).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&req.payload).into_owned())
+            });
+        let result = self
+            .router()?
+            .publish_as(&publisher, &req.topic, payload, None)
+            .inspect_err(crate::metrics::record_pubsub_rejected)
+            .map_err(synap_status)?;
+        Ok(Response::new(pb::PubSubPublishResponse {
+            message_id: result.message_id,
+            subscribers_matched: result.subscribers_matched as u64,
+        }))
+    }
+
+    async fn subscribe(
+        &self,
+        request: Request<pb::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let router = self.router()?;
+        let remote = request.remote_addr();
+        let topics = request.into_inner().topics;
+        if topics.is_empty() {
+            return Err(Status::invalid_argument(
+                "Subscribe requires at least one topic",
+            ));
+        }
+        let max = self.state.client_list_manager.limits().max_subscriptions;
+        if max > 0 && topics.len() > max {
+            return Err(Status::invalid_argument(format!(
+                "Too many subscriptions on one connection: {} (max {})",
+                topics.len(),
+                max
+            )));
+        }
+
+        let subscriber_id = router
+            .subscribe(topics)
+            .map_err(synap_status)?
+            .subscriber_id;
+        let (messages_tx, mut messages) =
+            mpsc::channel::<PubSubMessage>(crate::core::pubsub::SUBSCRIBER_CHANNEL_CAPACITY);
+        router.register_connection(subscriber_id.clone(), messages_tx);
+
+        let client_id = format!("grpc-pubsub-{}", subscriber_id);
+        let kill = track_client(&self.state, &client_id, remote).await;
+        let clients = self.state.client_list_manager.clone();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    Some(message) = messages.recv() => {
+                        let message = pb::PubSubMessage {
+                            id: message.id,
+                            topic: message.topic,
+                            payload: message.payload.to_string().into_bytes(),
+                            timestamp: message.timestamp,
+                        };
+                        if tx.send(Ok(message)).await.is_err() {
+                            break;
+                        }
+                    }
+                    _ = tx.closed() => break,
+                    _ = kill.killed() => break,
+                    else => break,
+                }
+            }
+            clients.remove(&client_id).await;
+            router.unregister_connection(&subscriber_id);
+            let _ = router.unsubscribe(&subscriber_id, None);
+            info!("gRPC subscriber {} disconnected", subscriber_id);
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
@@ -0,0 +1,205 @@
+//! gRPC listener (`grpc` feature).
+//!
+//! Typed protobuf services over tonic for clients that prefer gRPC to the
+//! JSON envelope: `KvService`, `HashService` and `ListService` in [`data`],
+//! and `QueueService`, `StreamService` and `PubSubService` in [`messaging`],
+//! whose consume and subscribe calls are server-streaming RPCs. The schema
+//! is `proto/synap.proto`.
+//!
+//! Like the other binary listeners, the services call the stores directly
+//! and authenticate with the user store: when `require_auth` is set every
+//! call needs `authorization: Basic …` metadata. KV, hash and list calls run
+//! in the logical database named by the `synap-db` metadata entry.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::core::ErrorCategory;
+use base64::{Engine as _, engine::general_purpose};
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::{Code, Status};
+
+use crate::auth::UserManager;
+use crate::core::SynapError;
+use crate::server::handlers::AppState;
+
+mod data;
+mod messaging;
+
+/// Generated messages and service stubs for `synap.v1`
+pub mod pb {
+    tonic::include_proto!("synap.v1");
+}
+
+/// Metadata entry selecting the logical database of a KV, hash or list call
+pub const DB_METADATA_KEY: &str = "synap-db";
+
+/// Metadata entry carrying the [`SynapError::error_code`] of a failed call
+pub const ERROR_CODE_METADATA_KEY: &str = "synap-error-code";
+
+/// Rejects calls without valid Basic credentials when auth is required
+#[derive(Clone)]
+struct AuthInterceptor {
+    user_manager: Option<Arc<UserManager>>,
+    require_auth: bool,
+}
+
+impl tonic::service::Interceptor for AuthInterceptor {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
+        if !self.require_auth {
+            return Ok(request);
+        }
+        let (username, password) = basic_credentials(request.metadata())
+            .ok_or_else(|| Status::unauthenticated("NOAUTH Authentication required"))?;
+        let manager = self
+            .user_manager
+            .as_ref()
+            .ok_or_else(|| Status::unauthenticated("WRONGPASS invalid username-password pair"))?;
+        manager
+            .authenticate(&username, &password)
+            .map_err(|_| Status::unauthenticated("WRONGPASS invalid username-password pair"))?;
+        Ok(request)
+    }
+}
+
+/// `user:password` from `authorization: Basic …` metadata
+fn basic_credentials(metadata: &MetadataMap) -> Option<(String, String)> {
+    let header = metadata.get("authorization")?.to_str().ok()?;
+    let encoded = header.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(general_purpose::STANDARD.decode(encoded).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+    Some((username.to_string(), password.to_string()))
+}
+
+/// The state of the database named by the call's `synap-db` metadata
+fn select_db(state: &AppState, metadata: &MetadataMap) -> Result<AppState, Status> {
+    let db = match metadata.get(DB_METADATA_KEY) {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .ok_or_else(|| Status::invalid_argument("synap-db must be a database number"))?,
+        None => 0,
+    };
+    state.select(db).map_err(synap_status)
+}
+
+/// gRPC status for a store error, with its stable code in the
+/// `synap-error-code` trailer
+fn synap_status(error: SynapError) -> Status {
+    let code = match error.category() {
+        ErrorCategory::NotFound => Code::NotFound,
+        ErrorCategory::Conflict => Code::FailedPrecondition,
+        ErrorCategory::InvalidRequest => Code::InvalidArgument,
+        ErrorCategory::Auth => match error {
+            SynapError::Unauthorized(_) => Code::Unauthenticated,
+            _ => Code::PermissionDenied,
+        },
+        ErrorCategory::LimitExceeded => Code::ResourceExhausted,
+        ErrorCategory::Unavailable => Code::Unavailable,
+        ErrorCategory::Redirect => Code::FailedPrecondition,
+        ErrorCategory::Internal => Code::Internal,
+    };
+    let mut status = Status::new(code, error.to_string());
+    status.metadata_mut().insert(
+        ERROR_CODE_METADATA_KEY,
+        MetadataValue::from_static(error.error_code()),
+    );
+    status
+}
+
+/// Spawn the gRPC listener on `addr` and return the address it bound.
+///
+/// The server runs as a background task for the life of the process.
+pub async fn spawn_grpc_listener(
+    state: AppState,
+    addr: SocketAddr,
+    max_message_bytes: usize,
+) -> std::io::Result<SocketAddr> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+
+    let auth = AuthInterceptor {
+        user_manager: state.user_manager.clone(),
+        require_auth: state.require_auth,
+    };
+    let state = Arc::new(state);
+
+    macro_rules! service {
+        ($server:ty, $service:expr) => {
+            InterceptedService::new(
+                <$server>::new($service)
+                    .max_decoding_message_size(max_message_bytes)
+                    .max_encoding_message_size(max_message_bytes),
+                auth.clone(),
+            )
+        };
+    }
+
+    let router = tonic::transport::Server::builder()
+        .add_service(service!(
+            pb::kv_service_server::KvServiceServer<data::KvGrpc>,
+            data::KvGrpc::new(state.clone())
+        ))
+        .add_service(service!(
+            pb::hash_service_server::HashServiceServer<data::HashGrpc>,
+            data::HashGrpc::new(state.clone())
+        ))
+        .add_service(service!(
+            pb::list_service_server::ListServiceServer<data::ListGrpc>,
+            data::ListGrpc::new(state.clone())
+        ))
+        .add_service(service!(
+            pb::queue_service_server::QueueServiceServer<messaging::QueueGrpc>,
+            messaging::QueueGrpc::new(state.clone())
+        ))
+        .add_service(service!(
+            pb::stream_service_server::StreamServiceServer<messaging::StreamGrpc>,
+            messaging::StreamGrpc::new(state.clone())
+        ))
+        .add_service(service!(
+            pb::pub_sub_service_server::PubSubServiceServer<messaging::PubSubGrpc>,
+            messaging::PubSubGrpc::new(state)
+        ));
+
+    tokio::spawn(async move {
+        let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
+        if let Err(e) = router.serve_with_incoming(incoming).await {
+            tracing::error!("gRPC server error: {}", e);
+        }
+    });
+    tracing::info!("gRPC server listening on {}", local_addr);
+
+    Ok(local_addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_credentials_are_decoded_from_metadata() {
+        let mut metadata = MetadataMap::new();
+        let encoded = general_purpose::STANDARD.encode("alice:s3cret:with-colon");
+        metadata.insert("authorization", format!("Basic {encoded}").parse().unwrap());
+
+        assert_eq!(
+            basic_credentials(&metadata),
+            Some(("alice".to_string(), "s3cret:with-colon".to_string()))
+        );
+    }
+
+    #[test]
+    fn store_errors_map_to_grpc_codes_with_their_error_code() {
+        let status = synap_status(SynapError::QueueNotFound("jobs".into()));
+        assert_eq!(status.code(), Code::NotFound);
+        assert_eq!(
+            status.metadata().get(ERROR_CODE_METADATA_KEY).unwrap(),
+            "QUEUE_NOT_FOUND"
+        );
+
+        let status = synap_status(SynapError::QueueFull("jobs".into()));
+        assert_eq!(status.code(), Code::ResourceExhausted);
+    }
+}
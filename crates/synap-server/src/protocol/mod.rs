@@ -5,6 +5,11 @@
 //! - [`synap_rpc`] — Synap's binary RPC surface. The wire layer belongs to
 //!   [`thunder`]; what lives here is the command catalog, the protocol
 //!   configuration and the listener that binds them together.
+//! - [`grpc`] — typed protobuf services over tonic (`grpc` feature).
+//! - [`command`] — typed payloads and responses of the commands whose
+//!   handlers decode a struct.
+//! - [`encoding`] — the JSON, MessagePack and CBOR bodies of
+//!   `POST /api/v1/command`.
 //!
 //! Nothing in this module is published. The `synap-protocol` crate that used to
 //! carry the RESP3 parser and the HTTP envelope existed only because publishing
@@ -12,5 +17,10 @@
 //! now coming from Thunder's registry crate, the rest is server-internal and
 //! stays that way.
 
+pub mod command;
+pub mod encoding;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod publisher;
 pub mod resp3;
 pub mod synap_rpc;
@@ -0,0 +1,27 @@
+//! Identity of the client issuing `PUBLISH` on a binary listener.
+//!
+//! Command dispatch only sees the command arguments, so the connection loops
+//! scope the caller's identity around `PUBLISH` and the command reads it back
+//! for the per-publisher rate limit of [`PubSubRouter::publish_as`] — the same
+//! task-local approach the HTTP request id uses.
+//!
+//! [`PubSubRouter::publish_as`]: crate::core::PubSubRouter::publish_as
+
+use std::sync::Arc;
+
+tokio::task_local! {
+    static CURRENT: Arc<str>;
+}
+
+/// The publishing client, or `fallback` outside a scoped `PUBLISH` (direct
+/// dispatch in tests, scripts).
+pub fn current_or(fallback: &str) -> Arc<str> {
+    CURRENT
+        .try_with(Arc::clone)
+        .unwrap_or_else(|_| Arc::from(fallback))
+}
+
+/// Run `fut` with `publisher` as the publishing client.
+pub async fn scope<F: Future>(publisher: Arc<str>, fut: F) -> F::Output {
+    CURRENT.scope(publisher, fut).await
+}
@@ -1,9 +1,11 @@
 use super::{AppState, Resp3Value, arg_bytes, arg_f64, arg_i64, arg_str, arg_u64, err_wrong_args};
 use crate::core::geospatial::{DistanceUnit, GeoQueryOptions, GeoSearchParams};
+#[cfg(feature = "scripting")]
 use crate::scripting::ScriptExecContext;
 
 // ── Queue commands (3.1) ──────────────────────────────────────────────────────
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qcreate(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("QCREATE");
@@ -22,6 +24,7 @@ pub(super) async fn cmd_qcreate(state: &AppState, args: &[Resp3Value]) -> Resp3V
     }
 }
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qdelete(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("QDELETE");
@@ -41,6 +44,7 @@ pub(super) async fn cmd_qdelete(state: &AppState, args: &[Resp3Value]) -> Resp3V
     }
 }
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qlist(state: &AppState) -> Resp3Value {
     let qm = match state.queue_manager.as_ref() {
         Some(qm) => qm,
@@ -57,6 +61,7 @@ pub(super) async fn cmd_qlist(state: &AppState) -> Resp3Value {
     }
 }
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qpublish(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 3 {
         return err_wrong_args("QPUBLISH");
@@ -88,6 +93,7 @@ pub(super) async fn cmd_qpublish(state: &AppState, args: &[Resp3Value]) -> Resp3
     }
 }
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qconsume(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("QCONSUME");
@@ -104,7 +110,7 @@ pub(super) async fn cmd_qconsume(state: &AppState, args: &[Resp3Value]) -> Resp3
     match qm.consume(&name, &consumer_id).await {
         Ok(Some(msg)) => Resp3Value::Array(vec![
             Resp3Value::BulkString(msg.id.as_bytes().to_vec()),
-            Resp3Value::BulkString((*msg.payload).clone()),
+            Resp3Value::BulkString(msg.payload.to_vec()),
             Resp3Value::Integer(msg.priority as i64),
             Resp3Value::Integer(msg.retry_count as i64),
         ]),
@@ -113,6 +119,7 @@ pub(super) async fn cmd_qconsume(state: &AppState, args: &[Resp3Value]) -> Resp3
     }
 }
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qack(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 3 {
         return err_wrong_args("QACK");
@@ -135,6 +142,7 @@ pub(super) async fn cmd_qack(state: &AppState, args: &[Resp3Value]) -> Resp3Valu
     }
 }
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qnack(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 3 {
         return err_wrong_args("QNACK");
@@ -166,6 +174,7 @@ pub(super) async fn cmd_qnack(state: &AppState, args: &[Resp3Value]) -> Resp3Val
     }
 }
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qstats(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("QSTATS");
@@ -199,6 +208,7 @@ pub(super) async fn cmd_qstats(state: &AppState, args: &[Resp3Value]) -> Resp3Va
     }
 }
 
+#[cfg(feature = "queues")]
 pub(super) async fn cmd_qpurge(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("QPURGE");
@@ -220,6 +230,7 @@ pub(super) async fn cmd_qpurge(state: &AppState, args: &[Resp3Value]) -> Resp3Va
 // ── Stream commands (3.2) ─────────────────────────────────────────────────────
 
 /// `XADD <room> * <event_type> <data> [field value ...]`
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_xadd(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     // Minimum: XADD <room> <id> <field> <value>
     if args.len() < 5 {
@@ -246,6 +257,7 @@ pub(super) async fn cmd_xadd(state: &AppState, args: &[Resp3Value]) -> Resp3Valu
 }
 
 /// `XREAD [COUNT <n>] [BLOCK <ms>] STREAMS <room> <offset>`
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_xread(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 4 {
         return err_wrong_args("XREAD");
@@ -298,6 +310,7 @@ pub(super) async fn cmd_xread(state: &AppState, args: &[Resp3Value]) -> Resp3Val
 }
 
 /// `XREADGROUP GROUP <group> <consumer> [COUNT <n>] STREAMS <room> <offset>`
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_xreadgroup(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 7 {
         return err_wrong_args("XREADGROUP");
@@ -350,6 +363,7 @@ pub(super) async fn cmd_xreadgroup(state: &AppState, args: &[Resp3Value]) -> Res
 }
 
 /// `XRANGE <room> <start> <end> [COUNT <n>]`
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_xrange(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 4 {
         return err_wrong_args("XRANGE");
@@ -394,6 +408,7 @@ pub(super) async fn cmd_xrange(state: &AppState, args: &[Resp3Value]) -> Resp3Va
 }
 
 /// `XDEL <room> <id> [id ...]` — append-only model; always returns 0
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_xdel(_state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 3 {
         return err_wrong_args("XDEL");
@@ -402,6 +417,7 @@ pub(super) async fn cmd_xdel(_state: &AppState, args: &[Resp3Value]) -> Resp3Val
 }
 
 /// `XINFO STREAM <room>` or `XINFO ROOMS`
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_xinfo(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("XINFO");
@@ -456,6 +472,7 @@ pub(super) async fn cmd_xinfo(state: &AppState, args: &[Resp3Value]) -> Resp3Val
 }
 
 /// `XACK <room> <group> <id> [id ...]` — returns count of IDs acknowledged
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_xack(_state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 4 {
         return err_wrong_args("XACK");
@@ -466,6 +483,7 @@ pub(super) async fn cmd_xack(_state: &AppState, args: &[Resp3Value]) -> Resp3Val
 
 // ── Pub/Sub commands (3.3) ────────────────────────────────────────────────────
 
+#[cfg(feature = "pubsub")]
 pub(super) async fn cmd_publish(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 3 {
         return err_wrong_args("PUBLISH");
@@ -486,12 +504,17 @@ pub(super) async fn cmd_publish(state: &AppState, args: &[Resp3Value]) -> Resp3V
         String::from_utf8(payload)
             .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()),
     );
-    match ps.publish(&topic, json_payload, None) {
+    let publisher = crate::protocol::publisher::current_or("resp3");
+    match ps
+        .publish_as(&publisher, &topic, json_payload, None)
+        .inspect_err(crate::metrics::record_pubsub_rejected)
+    {
         Ok(result) => Resp3Value::Integer(result.subscribers_matched as i64),
         Err(e) => Resp3Value::Error(format!("ERR {e}")),
     }
 }
 
+#[cfg(feature = "pubsub")]
 pub(super) async fn cmd_subscribe(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("SUBSCRIBE");
@@ -510,6 +533,7 @@ pub(super) async fn cmd_subscribe(state: &AppState, args: &[Resp3Value]) -> Resp
     }
 }
 
+#[cfg(feature = "pubsub")]
 pub(super) async fn cmd_unsubscribe(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     let ps = match state.pubsub_router.as_ref() {
         Some(ps) => ps,
@@ -527,6 +551,7 @@ pub(super) async fn cmd_unsubscribe(state: &AppState, args: &[Resp3Value]) -> Re
     }
 }
 
+#[cfg(feature = "pubsub")]
 pub(super) async fn cmd_psubscribe(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("PSUBSCRIBE");
@@ -542,6 +567,7 @@ pub(super) async fn cmd_psubscribe(state: &AppState, args: &[Resp3Value]) -> Res
     }
 }
 
+#[cfg(feature = "pubsub")]
 pub(super) async fn cmd_pubsub(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("PUBSUB");
@@ -840,6 +866,7 @@ pub(super) async fn cmd_unwatch(state: &AppState, args: &[Resp3Value]) -> Resp3V
 // ── Script commands (3.5) ─────────────────────────────────────────────────────
 
 /// `EVAL <script> <numkeys> [key ...] [arg ...]`
+#[cfg(feature = "scripting")]
 pub(super) async fn cmd_eval(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 3 {
         return err_wrong_args("EVAL");
@@ -863,6 +890,8 @@ pub(super) async fn cmd_eval(state: &AppState, args: &[Resp3Value]) -> Resp3Valu
         list_store: state.list_store.clone(),
         set_store: state.set_store.clone(),
         sorted_set_store: state.sorted_set_store.clone(),
+        queue_manager: state.queue_manager().cloned(),
+        stream_manager: state.stream_manager().cloned(),
     };
     match state
         .script_manager
@@ -875,6 +904,7 @@ pub(super) async fn cmd_eval(state: &AppState, args: &[Resp3Value]) -> Resp3Valu
 }
 
 /// `EVALSHA <sha> <numkeys> [key ...] [arg ...]`
+#[cfg(feature = "scripting")]
 pub(super) async fn cmd_evalsha(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 3 {
         return err_wrong_args("EVALSHA");
@@ -898,6 +928,8 @@ pub(super) async fn cmd_evalsha(state: &AppState, args: &[Resp3Value]) -> Resp3V
         list_store: state.list_store.clone(),
         set_store: state.set_store.clone(),
         sorted_set_store: state.sorted_set_store.clone(),
+        queue_manager: state.queue_manager().cloned(),
+        stream_manager: state.stream_manager().cloned(),
     };
     match state
         .script_manager
@@ -910,6 +942,7 @@ pub(super) async fn cmd_evalsha(state: &AppState, args: &[Resp3Value]) -> Resp3V
 }
 
 /// `SCRIPT LOAD|EXISTS|FLUSH|KILL ...`
+#[cfg(feature = "scripting")]
 pub(super) async fn cmd_script(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 2 {
         return err_wrong_args("SCRIPT");
@@ -1081,6 +1114,17 @@ pub(super) async fn cmd_geopos(state: &AppState, args: &[Resp3Value]) -> Resp3Va
     }
 }
 
+/// Unit argument at `idx` through [`DistanceUnit::parse_or_meters`]
+fn arg_unit(args: &[Resp3Value], idx: usize) -> Result<DistanceUnit, Resp3Value> {
+    let unit = args.get(idx).map(|a| {
+        a.as_bytes()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default()
+    });
+    DistanceUnit::parse_or_meters(unit.as_deref())
+        .map_err(|e| Resp3Value::Error(format!("ERR {e}")))
+}
+
 /// `GEODIST key member1 member2 [m|km|mi|ft]`
 pub(super) async fn cmd_geodist(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 4 {
@@ -1098,11 +1142,10 @@ pub(super) async fn cmd_geodist(state: &AppState, args: &[Resp3Value]) -> Resp3V
         Some(m) => m,
         None => return Resp3Value::Error("ERR member2 required".into()),
     };
-    let unit: DistanceUnit = args
-        .get(4)
-        .and_then(|a| a.as_str())
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(DistanceUnit::Meters);
+    let unit = match arg_unit(args, 4) {
+        Ok(unit) => unit,
+        Err(e) => return e,
+    };
     match state.geospatial_store.geodist(&key, &m1, &m2, unit) {
         Ok(Some(dist)) => Resp3Value::BulkString(format!("{dist:.4}").into_bytes()),
         Ok(None) => Resp3Value::Null,
@@ -1198,11 +1241,10 @@ pub(super) async fn cmd_georadius(state: &AppState, args: &[Resp3Value]) -> Resp
         Some(v) => v,
         None => return Resp3Value::Error("ERR radius must be a float".into()),
     };
-    let unit: DistanceUnit = args
-        .get(5)
-        .and_then(|a| a.as_str())
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(DistanceUnit::Meters);
+    let unit = match arg_unit(args, 5) {
+        Ok(unit) => unit,
+        Err(e) => return e,
+    };
     let tail = parse_geo_option_tail(args, 6);
     // Internal API uses (lat, lon) — convert from Redis (lon, lat)
     match state.geospatial_store.georadius(
@@ -1240,11 +1282,10 @@ pub(super) async fn cmd_georadiusbymember(state: &AppState, args: &[Resp3Value])
         Some(v) => v,
         None => return Resp3Value::Error("ERR radius must be a float".into()),
     };
-    let unit: DistanceUnit = args
-        .get(4)
-        .and_then(|a| a.as_str())
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(DistanceUnit::Meters);
+    let unit = match arg_unit(args, 4) {
+        Ok(unit) => unit,
+        Err(e) => return e,
+    };
     let tail = parse_geo_option_tail(args, 5);
     match state.geospatial_store.georadiusbymember(
         &key,
@@ -1275,8 +1316,8 @@ struct GeoSearchArgs {
 }
 
 /// Parse GEOSEARCH arguments starting after the key (index 2). Unknown tokens
-/// are skipped (matching prior behavior).
-fn parse_geosearch_args(args: &[Resp3Value]) -> GeoSearchArgs {
+/// are skipped (matching prior behavior); an unknown unit is an error.
+fn parse_geosearch_args(args: &[Resp3Value]) -> Result<GeoSearchArgs, Resp3Value> {
     let mut parsed = GeoSearchArgs::default();
     let mut i = 2;
     while i < args.len() {
@@ -1293,23 +1334,13 @@ fn parse_geosearch_args(args: &[Resp3Value]) -> GeoSearchArgs {
             }
             Some("BYRADIUS") => {
                 let r = arg_f64(args, i + 1).unwrap_or(0.0);
-                let u = args
-                    .get(i + 2)
-                    .and_then(|a| a.as_str())
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or(DistanceUnit::Meters);
-                parsed.by_radius = Some((r, u));
+                parsed.by_radius = Some((r, arg_unit(args, i + 2)?));
                 i += 3;
             }
             Some("BYBOX") => {
                 let w = arg_f64(args, i + 1).unwrap_or(0.0);
                 let h = arg_f64(args, i + 2).unwrap_or(0.0);
-                let u = args
-                    .get(i + 3)
-                    .and_then(|a| a.as_str())
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or(DistanceUnit::Meters);
-                parsed.by_box = Some((w, h, u));
+                parsed.by_box = Some((w, h, arg_unit(args, i + 3)?));
                 i += 4;
             }
             Some("WITHDIST") => {
@@ -1335,7 +1366,7 @@ fn parse_geosearch_args(args: &[Resp3Value]) -> GeoSearchArgs {
             _ => i += 1,
         }
     }
-    parsed
+    Ok(parsed)
 }
 
 /// `GEOSEARCH key FROMMEMBER member|FROMLONLAT lon lat BYRADIUS r unit|BYBOX w h unit …`
@@ -1347,7 +1378,10 @@ pub(super) async fn cmd_geosearch(state: &AppState, args: &[Resp3Value]) -> Resp
         Some(k) => k,
         None => return Resp3Value::Error("ERR key must be a string".into()),
     };
-    let parsed = parse_geosearch_args(args);
+    let parsed = match parse_geosearch_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => return e,
+    };
     match state.geospatial_store.geosearch(
         &key,
         GeoSearchParams {
@@ -1374,6 +1408,7 @@ pub(super) async fn cmd_geosearch(state: &AppState, args: &[Resp3Value]) -> Resp
 // SDELETE/SLIST/SSTATS) so RESP3 clients — the TS/Python SDKs map `stream.*`
 // to these raw commands on every native transport — reach streams too.
 
+#[cfg(feature = "streams")]
 fn stream_manager_or_err(
     state: &AppState,
 ) -> Result<std::sync::Arc<crate::core::StreamManager>, Resp3Value> {
@@ -1383,6 +1418,7 @@ fn stream_manager_or_err(
         .ok_or_else(|| Resp3Value::Error("ERR stream subsystem not enabled".into()))
 }
 
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_screate(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     // SCREATE room [max_events] — the SDKs send an optional max_events which
     // the server ignores (rooms are configured server-side), like SynapRPC.
@@ -1403,6 +1439,7 @@ pub(super) async fn cmd_screate(state: &AppState, args: &[Resp3Value]) -> Resp3V
     }
 }
 
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_sgetorcreate(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() != 2 {
         return err_wrong_args("SGETORCREATE");
@@ -1421,6 +1458,7 @@ pub(super) async fn cmd_sgetorcreate(state: &AppState, args: &[Resp3Value]) -> R
     }
 }
 
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_spublish(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() != 4 {
         return err_wrong_args("SPUBLISH");
@@ -1447,6 +1485,7 @@ pub(super) async fn cmd_spublish(state: &AppState, args: &[Resp3Value]) -> Resp3
     }
 }
 
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_sread(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() < 4 {
         return err_wrong_args("SREAD");
@@ -1464,11 +1503,15 @@ pub(super) async fn cmd_sread(state: &AppState, args: &[Resp3Value]) -> Resp3Val
         None => return Resp3Value::Error("ERR offset must be an integer".into()),
     };
     let limit = arg_u64(args, 4).map(|n| n as usize).unwrap_or(100);
+    let min_age_ms = arg_u64(args, 5).unwrap_or(0);
     let sm = match stream_manager_or_err(state) {
         Ok(sm) => sm,
         Err(e) => return e,
     };
-    match sm.consume(&room, &subscriber_id, from_offset, limit).await {
+    match sm
+        .consume_with_min_age(&room, &subscriber_id, from_offset, limit, min_age_ms)
+        .await
+    {
         Ok(events) => Resp3Value::Array(
             events
                 .into_iter()
@@ -1502,6 +1545,7 @@ pub(super) async fn cmd_sread(state: &AppState, args: &[Resp3Value]) -> Resp3Val
     }
 }
 
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_sdelete(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() != 2 {
         return err_wrong_args("SDELETE");
@@ -1520,6 +1564,7 @@ pub(super) async fn cmd_sdelete(state: &AppState, args: &[Resp3Value]) -> Resp3V
     }
 }
 
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_slist(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() != 1 {
         return err_wrong_args("SLIST");
@@ -1537,6 +1582,7 @@ pub(super) async fn cmd_slist(state: &AppState, args: &[Resp3Value]) -> Resp3Val
     )
 }
 
+#[cfg(feature = "streams")]
 pub(super) async fn cmd_sstats(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     if args.len() != 2 {
         return err_wrong_args("SSTATS");
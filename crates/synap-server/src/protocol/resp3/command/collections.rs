@@ -370,6 +370,189 @@ pub(super) async fn cmd_scard(state: &AppState, args: &[Resp3Value]) -> Resp3Val
     }
 }
 
+/// Reply to `SPOP`/`SRANDMEMBER`: one member (or null) without a count, an
+/// array with one
+fn sampled_reply(
+    result: Result<Vec<Vec<u8>>, crate::core::SynapError>,
+    with_count: bool,
+) -> Resp3Value {
+    match result {
+        Ok(members) if with_count => {
+            Resp3Value::Array(members.into_iter().map(Resp3Value::BulkString).collect())
+        }
+        Ok(members) => members
+            .into_iter()
+            .next()
+            .map_or(Resp3Value::Null, Resp3Value::BulkString),
+        Err(e) => Resp3Value::Error(format!("ERR {e}")),
+    }
+}
+
+/// `SPOP key [count]`
+pub(super) async fn cmd_spop(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    if !(2..=3).contains(&args.len()) {
+        return err_wrong_args("SPOP");
+    }
+    let key = match arg_str(args, 1) {
+        Some(k) => k,
+        None => return err_wrong_args("SPOP"),
+    };
+    let count = match args.len() {
+        2 => None,
+        _ => match arg_u64(args, 2) {
+            Some(n) => Some(n as usize),
+            None => return Resp3Value::Error("ERR value is out of range, must be positive".into()),
+        },
+    };
+    sampled_reply(state.set_store.spop(&key, count), count.is_some())
+}
+
+/// `SRANDMEMBER key [count]`; a negative count may repeat members
+pub(super) async fn cmd_srandmember(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    if !(2..=3).contains(&args.len()) {
+        return err_wrong_args("SRANDMEMBER");
+    }
+    let key = match arg_str(args, 1) {
+        Some(k) => k,
+        None => return err_wrong_args("SRANDMEMBER"),
+    };
+    let count = match args.len() {
+        2 => None,
+        _ => match arg_i64(args, 2) {
+            Some(n) => Some(n),
+            None => return Resp3Value::Error("ERR value is not an integer or out of range".into()),
+        },
+    };
+    sampled_reply(state.set_store.srandmember(&key, count), count.is_some())
+}
+
+// ── Set algebra ───────────────────────────────────────────────────────────────
+
+/// String arguments from `start` on, checked to hash to one cluster slot
+fn same_slot_keys(
+    state: &AppState,
+    args: &[Resp3Value],
+    start: usize,
+) -> Result<Vec<String>, Resp3Value> {
+    let keys: Vec<String> = (start..args.len())
+        .filter_map(|i| arg_str(args, i))
+        .collect();
+    if keys.len() != args.len() - start {
+        return Err(Resp3Value::Error("ERR keys must be strings".into()));
+    }
+    state
+        .check_same_slot(&keys)
+        .map_err(|e| Resp3Value::Error(format!("ERR {e}")))?;
+    Ok(keys)
+}
+
+fn members_reply(result: Result<Vec<Vec<u8>>, crate::core::SynapError>) -> Resp3Value {
+    match result {
+        Ok(members) => Resp3Value::Array(members.into_iter().map(Resp3Value::BulkString).collect()),
+        Err(e) => Resp3Value::Error(format!("ERR {e}")),
+    }
+}
+
+fn count_reply(result: Result<usize, crate::core::SynapError>) -> Resp3Value {
+    match result {
+        Ok(n) => Resp3Value::Integer(n as i64),
+        Err(e) => Resp3Value::Error(format!("ERR {e}")),
+    }
+}
+
+/// `SINTER key [key ...]`
+pub(super) async fn cmd_sinter(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    if args.len() < 2 {
+        return err_wrong_args("SINTER");
+    }
+    match same_slot_keys(state, args, 1) {
+        Ok(keys) => members_reply(state.set_store.sinter(&keys)),
+        Err(e) => e,
+    }
+}
+
+/// `SUNION key [key ...]`
+pub(super) async fn cmd_sunion(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    if args.len() < 2 {
+        return err_wrong_args("SUNION");
+    }
+    match same_slot_keys(state, args, 1) {
+        Ok(keys) => members_reply(state.set_store.sunion(&keys)),
+        Err(e) => e,
+    }
+}
+
+/// `SDIFF key [key ...]`
+pub(super) async fn cmd_sdiff(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    if args.len() < 2 {
+        return err_wrong_args("SDIFF");
+    }
+    match same_slot_keys(state, args, 1) {
+        Ok(keys) => members_reply(state.set_store.sdiff(&keys)),
+        Err(e) => e,
+    }
+}
+
+/// `SINTERSTORE destination key [key ...]`
+pub(super) async fn cmd_sinterstore(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    if args.len() < 3 {
+        return err_wrong_args("SINTERSTORE");
+    }
+    match same_slot_keys(state, args, 1) {
+        Ok(keys) => count_reply(state.set_store.sinterstore(&keys[0], &keys[1..])),
+        Err(e) => e,
+    }
+}
+
+/// `SUNIONSTORE destination key [key ...]`
+pub(super) async fn cmd_sunionstore(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    if args.len() < 3 {
+        return err_wrong_args("SUNIONSTORE");
+    }
+    match same_slot_keys(state, args, 1) {
+        Ok(keys) => count_reply(state.set_store.sunionstore(&keys[0], &keys[1..])),
+        Err(e) => e,
+    }
+}
+
+/// `SDIFFSTORE destination key [key ...]`
+pub(super) async fn cmd_sdiffstore(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    if args.len() < 3 {
+        return err_wrong_args("SDIFFSTORE");
+    }
+    match same_slot_keys(state, args, 1) {
+        Ok(keys) => count_reply(state.set_store.sdiffstore(&keys[0], &keys[1..])),
+        Err(e) => e,
+    }
+}
+
+/// `SINTERCARD numkeys key [key ...] [LIMIT limit]`
+pub(super) async fn cmd_sintercard(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    let numkeys = match arg_u64(args, 1) {
+        Some(n) if n > 0 => n as usize,
+        Some(_) => return Resp3Value::Error("ERR numkeys should be greater than 0".into()),
+        None => return err_wrong_args("SINTERCARD"),
+    };
+    let end = 2 + numkeys;
+    if args.len() < end {
+        return Resp3Value::Error("ERR Number of keys can't be greater than number of args".into());
+    }
+    let limit = match args.len() - end {
+        0 => 0,
+        2 if arg_str(args, end).is_some_and(|o| o.eq_ignore_ascii_case("LIMIT")) => {
+            match arg_u64(args, end + 1) {
+                Some(n) => n as usize,
+                None => return Resp3Value::Error("ERR LIMIT can't be negative".into()),
+            }
+        }
+        _ => return Resp3Value::Error("ERR syntax error".into()),
+    };
+    match same_slot_keys(state, &args[..end], 2) {
+        Ok(keys) => count_reply(state.set_store.sintercard(&keys, limit)),
+        Err(e) => e,
+    }
+}
+
 // ── Sorted set commands ───────────────────────────────────────────────────────
 
 pub(super) async fn cmd_zadd(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
@@ -389,8 +572,10 @@ pub(super) async fn cmd_zadd(state: &AppState, args: &[Resp3Value]) -> Resp3Valu
         None => return err_wrong_args("ZADD"),
     };
     let opts = ZAddOptions::default();
-    let (added, _) = state.sorted_set_store.zadd(&key, member, score, &opts);
-    Resp3Value::Integer(added as i64)
+    match state.sorted_set_store.try_zadd(&key, member, score, &opts) {
+        Ok((added, _)) => Resp3Value::Integer(added as i64),
+        Err(e) => Resp3Value::Error(format!("ERR {e}")),
+    }
 }
 
 pub(super) async fn cmd_zrange(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
@@ -487,7 +672,7 @@ pub(super) async fn cmd_pfadd(state: &AppState, args: &[Resp3Value]) -> Resp3Val
         None => return err_wrong_args("PFADD"),
     };
     let elements: Vec<Vec<u8>> = (2..args.len()).filter_map(|i| arg_bytes(args, i)).collect();
-    match state.hyperloglog_store.pfadd(&key, elements, None) {
+    match crate::server::handlers::hll::pfadd_and_log(state, &key, elements, None).await {
         Ok(n) => Resp3Value::Integer(if n > 0 { 1 } else { 0 }),
         Err(e) => Resp3Value::Error(format!("ERR {e}")),
     }
@@ -517,15 +702,21 @@ pub(super) async fn cmd_bitcount(state: &AppState, args: &[Resp3Value]) -> Resp3
         Some(k) => k,
         None => return err_wrong_args("BITCOUNT"),
     };
-    let (start, end) = if args.len() >= 4 {
-        let s = arg_i64(args, 2).unwrap_or(0).max(0) as usize;
-        let e = arg_i64(args, 3).unwrap_or(-1);
-        let e = if e < 0 { None } else { Some(e as usize) };
-        (Some(s), e)
-    } else {
-        (None, None)
-    };
-    match state.bitmap_store.bitcount(&key, start, end) {
+    // BITCOUNT key [start end [BYTE | BIT]], byte offsets by default
+    let (start, end) = match args.len() {
+        2 => (0, -1),
+        4 | 5 => match (arg_i64(args, 2), arg_i64(args, 3)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Resp3Value::Error("ERR value is not an integer or out of range".into()),
+        },
+        _ => return Resp3Value::Error("ERR syntax error".into()),
+    };
+    let unit = match arg_str(args, 4).as_deref().map(str::parse) {
+        None => crate::core::BitRangeUnit::Byte,
+        Some(Ok(unit)) => unit,
+        Some(Err(_)) => return Resp3Value::Error("ERR syntax error".into()),
+    };
+    match state.bitmap_store.bitcount_range(&key, start, end, unit) {
         Ok(n) => Resp3Value::Integer(n as i64),
         Err(e) => Resp3Value::Error(format!("ERR {e}")),
     }
@@ -587,7 +778,10 @@ pub(super) async fn cmd_pfmerge(state: &AppState, args: &[Resp3Value]) -> Resp3V
         None => return Resp3Value::Error("ERR dest key must be a string".into()),
     };
     let sources: Vec<String> = (2..args.len()).filter_map(|i| arg_str(args, i)).collect();
-    match state.hyperloglog_store.pfmerge(&dest, sources) {
+    if let Err(e) = state.check_same_slot(std::iter::once(&dest).chain(&sources)) {
+        return Resp3Value::Error(format!("ERR {e}"));
+    }
+    match crate::server::handlers::hll::pfmerge_and_log(state, &dest, sources).await {
         Ok(_) => Resp3Value::SimpleString("OK".into()),
         Err(e) => Resp3Value::Error(format!("ERR {e}")),
     }
@@ -748,6 +942,54 @@ pub(super) async fn cmd_zscan(state: &AppState, args: &[Resp3Value]) -> Resp3Val
     ])
 }
 
+/// `ZNOTIFY SET key top_n [rank_threshold] | GET key | DEL key` — leaderboard
+/// notification windows (docs/features/leaderboard-notifications.md).
+pub(super) async fn cmd_znotify(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
+    let (Some(sub), Some(key)) = (arg_str(args, 1), arg_str(args, 2)) else {
+        return err_wrong_args("ZNOTIFY");
+    };
+    let Some(notifier) = state.sorted_set_store.leaderboard_notifier() else {
+        return Resp3Value::Error("ERR leaderboard notifications not enabled".into());
+    };
+    match sub.to_ascii_uppercase().as_str() {
+        "SET" => {
+            let Some(top_n) = arg_u64(args, 3) else {
+                return Resp3Value::Error("ERR top_n is not an integer".into());
+            };
+            let rank_threshold = match args.get(4) {
+                Some(_) => match arg_u64(args, 4) {
+                    Some(t) => Some(t as usize),
+                    None => {
+                        return Resp3Value::Error("ERR rank_threshold is not an integer".into());
+                    }
+                },
+                None => None,
+            };
+            let window = crate::core::LeaderboardWindow {
+                top_n: top_n as usize,
+                rank_threshold,
+            };
+            match notifier.set_window(&key, window) {
+                Ok(()) => Resp3Value::SimpleString("OK".into()),
+                Err(e) => Resp3Value::Error(format!("ERR {e}")),
+            }
+        }
+        "GET" => match notifier.window(&key) {
+            Some(w) => Resp3Value::Array(vec![
+                Resp3Value::Integer(w.top_n as i64),
+                w.rank_threshold
+                    .map(|t| Resp3Value::Integer(t as i64))
+                    .unwrap_or(Resp3Value::Null),
+            ]),
+            None => Resp3Value::Null,
+        },
+        "DEL" => Resp3Value::Integer(notifier.remove_window(&key) as i64),
+        other => Resp3Value::Error(format!(
+            "ERR unknown ZNOTIFY subcommand '{other}'. Try SET, GET, DEL"
+        )),
+    }
+}
+
 // ── Blocking pops (BLPOP / BRPOP / BZPOPMIN / BZPOPMAX / BRPOPLPUSH) ───────────
 
 /// Split a blocking `CMD key [key ...] timeout` into `(keys, timeout)`. The last
@@ -782,6 +1024,9 @@ pub(super) async fn cmd_blpop(state: &AppState, args: &[Resp3Value]) -> Resp3Val
         Some(x) => x,
         None => return err_wrong_args("BLPOP"),
     };
+    if let Err(e) = state.check_same_slot(&keys) {
+        return Resp3Value::Error(format!("ERR {e}"));
+    }
     match state.list_store.blpop(keys, timeout).await {
         Ok((key, value)) => Resp3Value::Array(vec![
             Resp3Value::BulkString(key.into_bytes()),
@@ -797,6 +1042,9 @@ pub(super) async fn cmd_brpop(state: &AppState, args: &[Resp3Value]) -> Resp3Val
         Some(x) => x,
         None => return err_wrong_args("BRPOP"),
     };
+    if let Err(e) = state.check_same_slot(&keys) {
+        return Resp3Value::Error(format!("ERR {e}"));
+    }
     match state.list_store.brpop(keys, timeout).await {
         Ok((key, value)) => Resp3Value::Array(vec![
             Resp3Value::BulkString(key.into_bytes()),
@@ -813,6 +1061,9 @@ pub(super) async fn cmd_bzpopmin(state: &AppState, args: &[Resp3Value]) -> Resp3
         Some(x) => x,
         None => return err_wrong_args("BZPOPMIN"),
     };
+    if let Err(e) = state.check_same_slot(&keys) {
+        return Resp3Value::Error(format!("ERR {e}"));
+    }
     match state.sorted_set_store.bzpopmin(keys, timeout).await {
         Ok((key, member, score)) => Resp3Value::Array(vec![
             Resp3Value::BulkString(key.into_bytes()),
@@ -829,6 +1080,9 @@ pub(super) async fn cmd_bzpopmax(state: &AppState, args: &[Resp3Value]) -> Resp3
         Some(x) => x,
         None => return err_wrong_args("BZPOPMAX"),
     };
+    if let Err(e) = state.check_same_slot(&keys) {
+        return Resp3Value::Error(format!("ERR {e}"));
+    }
     match state.sorted_set_store.bzpopmax(keys, timeout).await {
         Ok((key, member, score)) => Resp3Value::Array(vec![
             Resp3Value::BulkString(key.into_bytes()),
@@ -862,6 +1116,9 @@ pub(super) async fn cmd_brpoplpush(state: &AppState, args: &[Resp3Value]) -> Res
     } else {
         Some(timeout_secs as u64)
     };
+    if let Err(e) = state.check_same_slot([&source, &dest]) {
+        return Resp3Value::Error(format!("ERR {e}"));
+    }
     match state.list_store.brpoplpush(&source, &dest, timeout).await {
         Ok(value) => Resp3Value::BulkString(value),
         Err(_) => Resp3Value::Null,
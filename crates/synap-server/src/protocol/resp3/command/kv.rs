@@ -301,8 +301,17 @@ pub(super) async fn cmd_scan(state: &AppState, args: &[Resp3Value]) -> Resp3Valu
 
 // ── Misc ──────────────────────────────────────────────────────────────────────
 
+pub(super) async fn cmd_flushdb(state: &AppState) -> Resp3Value {
+    let _ = state.kv_store.flushdb().await;
+    Resp3Value::SimpleString("OK".into())
+}
+
+/// Flushes every database, whichever one the connection selected
 pub(super) async fn cmd_flushall(state: &AppState) -> Resp3Value {
-    let _ = state.kv_store.flushall().await;
+    let _ = state.monitoring.kv_store().flushall().await;
+    for (_, database) in state.monitoring.databases().iter() {
+        let _ = database.kv_store.flushall().await;
+    }
     Resp3Value::SimpleString("OK".into())
 }
 
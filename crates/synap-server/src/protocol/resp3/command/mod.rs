@@ -49,7 +49,6 @@ pub async fn dispatch(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
     match cmd {
         "PING" => kv::cmd_ping(args),
         "QUIT" => Resp3Value::SimpleString("OK".into()),
-        "SELECT" => Resp3Value::SimpleString("OK".into()), // single DB only
 
         "SET" => kv::cmd_set(state, args).await,
         "GET" => kv::cmd_get(state, args).await,
@@ -93,6 +92,15 @@ pub async fn dispatch(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
         "SREM" => collections::cmd_srem(state, args).await,
         "SISMEMBER" => collections::cmd_sismember(state, args).await,
         "SCARD" => collections::cmd_scard(state, args).await,
+        "SPOP" => collections::cmd_spop(state, args).await,
+        "SRANDMEMBER" => collections::cmd_srandmember(state, args).await,
+        "SINTER" => collections::cmd_sinter(state, args).await,
+        "SUNION" => collections::cmd_sunion(state, args).await,
+        "SDIFF" => collections::cmd_sdiff(state, args).await,
+        "SINTERSTORE" => collections::cmd_sinterstore(state, args).await,
+        "SUNIONSTORE" => collections::cmd_sunionstore(state, args).await,
+        "SDIFFSTORE" => collections::cmd_sdiffstore(state, args).await,
+        "SINTERCARD" => collections::cmd_sintercard(state, args).await,
 
         "ZADD" => collections::cmd_zadd(state, args).await,
         "ZRANGE" => collections::cmd_zrange(state, args).await,
@@ -109,42 +117,71 @@ pub async fn dispatch(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
         "SETBIT" => collections::cmd_setbit(state, args).await,
         "GETBIT" => collections::cmd_getbit(state, args).await,
 
-        "FLUSHALL" | "FLUSHDB" => kv::cmd_flushall(state).await,
+        "FLUSHDB" => kv::cmd_flushdb(state).await,
+        "FLUSHALL" => kv::cmd_flushall(state).await,
 
         // ── Queue (3.1) ───────────────────────────────────────────────────────────
+        #[cfg(feature = "queues")]
         "QCREATE" => advanced::cmd_qcreate(state, args).await,
+        #[cfg(feature = "queues")]
         "QDELETE" => advanced::cmd_qdelete(state, args).await,
+        #[cfg(feature = "queues")]
         "QLIST" => advanced::cmd_qlist(state).await,
+        #[cfg(feature = "queues")]
         "QPUBLISH" => advanced::cmd_qpublish(state, args).await,
+        #[cfg(feature = "queues")]
         "QCONSUME" => advanced::cmd_qconsume(state, args).await,
+        #[cfg(feature = "queues")]
         "QACK" => advanced::cmd_qack(state, args).await,
+        #[cfg(feature = "queues")]
         "QNACK" => advanced::cmd_qnack(state, args).await,
+        #[cfg(feature = "queues")]
         "QSTATS" => advanced::cmd_qstats(state, args).await,
 
         // Event streams (mirrors the SynapRPC stream family)
+        #[cfg(feature = "streams")]
         "SCREATE" => advanced::cmd_screate(state, args).await,
+        #[cfg(feature = "streams")]
         "SGETORCREATE" => advanced::cmd_sgetorcreate(state, args).await,
+        #[cfg(feature = "streams")]
         "SPUBLISH" => advanced::cmd_spublish(state, args).await,
+        #[cfg(feature = "streams")]
         "SREAD" => advanced::cmd_sread(state, args).await,
+        #[cfg(feature = "streams")]
         "SDELETE" => advanced::cmd_sdelete(state, args).await,
+        #[cfg(feature = "streams")]
         "SLIST" => advanced::cmd_slist(state, args).await,
+        #[cfg(feature = "streams")]
         "SSTATS" => advanced::cmd_sstats(state, args).await,
+        #[cfg(feature = "queues")]
         "QPURGE" => advanced::cmd_qpurge(state, args).await,
 
         // ── Stream — Redis X* names (3.2) ─────────────────────────────────────────
+        #[cfg(feature = "streams")]
         "XADD" => advanced::cmd_xadd(state, args).await,
+        #[cfg(feature = "streams")]
         "XREAD" => advanced::cmd_xread(state, args).await,
+        #[cfg(feature = "streams")]
         "XREADGROUP" => advanced::cmd_xreadgroup(state, args).await,
+        #[cfg(feature = "streams")]
         "XRANGE" => advanced::cmd_xrange(state, args).await,
+        #[cfg(feature = "streams")]
         "XDEL" => advanced::cmd_xdel(state, args).await,
+        #[cfg(feature = "streams")]
         "XINFO" => advanced::cmd_xinfo(state, args).await,
+        #[cfg(feature = "streams")]
         "XACK" => advanced::cmd_xack(state, args).await,
 
         // ── Pub/Sub (3.3) ────────────────────────────────────────────────────────
+        #[cfg(feature = "pubsub")]
         "PUBLISH" => advanced::cmd_publish(state, args).await,
+        #[cfg(feature = "pubsub")]
         "SUBSCRIBE" => advanced::cmd_subscribe(state, args).await,
+        #[cfg(feature = "pubsub")]
         "UNSUBSCRIBE" => advanced::cmd_unsubscribe(state, args).await,
+        #[cfg(feature = "pubsub")]
         "PSUBSCRIBE" => advanced::cmd_psubscribe(state, args).await,
+        #[cfg(feature = "pubsub")]
         "PUBSUB" => advanced::cmd_pubsub(state, args).await,
 
         // ── Transactions (3.4) ───────────────────────────────────────────────────
@@ -156,8 +193,11 @@ pub async fn dispatch(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
         "UNWATCH" => advanced::cmd_unwatch(state, args).await,
 
         // ── Scripts (3.5) ────────────────────────────────────────────────────────
+        #[cfg(feature = "scripting")]
         "EVAL" => advanced::cmd_eval(state, args).await,
+        #[cfg(feature = "scripting")]
         "EVALSHA" => advanced::cmd_evalsha(state, args).await,
+        #[cfg(feature = "scripting")]
         "SCRIPT" => advanced::cmd_script(state, args).await,
 
         // ── HyperLogLog (3.6) ────────────────────────────────────────────────────
@@ -188,6 +228,7 @@ pub async fn dispatch(state: &AppState, args: &[Resp3Value]) -> Resp3Value {
         "HSCAN" => collections::cmd_hscan(state, args).await,
         "SSCAN" => collections::cmd_sscan(state, args).await,
         "ZSCAN" => collections::cmd_zscan(state, args).await,
+        "ZNOTIFY" => collections::cmd_znotify(state, args).await,
 
         _ => Resp3Value::Error(format!("ERR unknown command '{cmd}'")),
     }
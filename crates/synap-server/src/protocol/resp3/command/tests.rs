@@ -58,6 +58,14 @@ fn make_state() -> AppState {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     }
 }
 
@@ -248,6 +256,116 @@ async fn test_sismember_false() {
     assert_eq!(result, Resp3Value::Integer(0));
 }
 
+#[tokio::test]
+async fn test_spop_and_srandmember() {
+    let state = make_state();
+    dispatch(&state, &args(&["SADD", "sr", "a", "b", "c"])).await;
+
+    // Without a count: a single member
+    assert!(matches!(
+        dispatch(&state, &args(&["SRANDMEMBER", "sr"])).await,
+        Resp3Value::BulkString(_)
+    ));
+    // A negative count may repeat members
+    match dispatch(&state, &args(&["SRANDMEMBER", "sr", "-5"])).await {
+        Resp3Value::Array(members) => assert_eq!(members.len(), 5),
+        other => panic!("Expected Array from SRANDMEMBER, got {other:?}"),
+    }
+    // ... up to `limits.max_random_count`
+    match dispatch(
+        &state,
+        &args(&["SRANDMEMBER", "sr", "-9223372036854775808"]),
+    )
+    .await
+    {
+        Resp3Value::Error(e) => assert!(e.contains("max_random_count"), "{e}"),
+        other => panic!("Expected Error from SRANDMEMBER, got {other:?}"),
+    }
+
+    match dispatch(&state, &args(&["SPOP", "sr", "2"])).await {
+        Resp3Value::Array(members) => assert_eq!(members.len(), 2),
+        other => panic!("Expected Array from SPOP, got {other:?}"),
+    }
+    assert!(matches!(
+        dispatch(&state, &args(&["SPOP", "sr"])).await,
+        Resp3Value::BulkString(_)
+    ));
+    assert_eq!(
+        dispatch(&state, &args(&["SPOP", "sr"])).await,
+        Resp3Value::Null
+    );
+    assert!(matches!(
+        dispatch(&state, &args(&["SPOP", "sr", "-1"])).await,
+        Resp3Value::Error(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_set_algebra_commands() {
+    let state = make_state();
+    dispatch(&state, &args(&["SADD", "sa", "a", "b", "c"])).await;
+    dispatch(&state, &args(&["SADD", "sb", "b", "c", "d"])).await;
+
+    let sorted = |value: Resp3Value| match value {
+        Resp3Value::Array(mut members) => {
+            members.sort_by_key(|m| format!("{m:?}"));
+            members
+        }
+        other => panic!("Expected Array, got {other:?}"),
+    };
+    let bulk = |m: &str| Resp3Value::BulkString(m.as_bytes().to_vec());
+    assert_eq!(
+        sorted(dispatch(&state, &args(&["SINTER", "sa", "sb"])).await),
+        vec![bulk("b"), bulk("c")]
+    );
+    assert_eq!(
+        sorted(dispatch(&state, &args(&["SDIFF", "sa", "sb"])).await),
+        vec![bulk("a")]
+    );
+    assert_eq!(
+        sorted(dispatch(&state, &args(&["SUNION", "sa", "sb"])).await).len(),
+        4
+    );
+
+    assert_eq!(
+        dispatch(&state, &args(&["SUNIONSTORE", "su", "sa", "sb"])).await,
+        Resp3Value::Integer(4)
+    );
+    assert_eq!(
+        dispatch(&state, &args(&["SCARD", "su"])).await,
+        Resp3Value::Integer(4)
+    );
+    assert_eq!(
+        dispatch(&state, &args(&["SINTERSTORE", "si", "sa", "sb"])).await,
+        Resp3Value::Integer(2)
+    );
+    assert_eq!(
+        dispatch(&state, &args(&["SDIFFSTORE", "sd", "sa", "sb"])).await,
+        Resp3Value::Integer(1)
+    );
+
+    assert_eq!(
+        dispatch(&state, &args(&["SINTERCARD", "2", "sa", "sb"])).await,
+        Resp3Value::Integer(2)
+    );
+    assert_eq!(
+        dispatch(
+            &state,
+            &args(&["SINTERCARD", "2", "sa", "sb", "LIMIT", "1"])
+        )
+        .await,
+        Resp3Value::Integer(1)
+    );
+    assert!(matches!(
+        dispatch(&state, &args(&["SINTERCARD", "3", "sa", "sb"])).await,
+        Resp3Value::Error(_)
+    ));
+    assert!(matches!(
+        dispatch(&state, &args(&["SINTERCARD", "1", "sa", "sb"])).await,
+        Resp3Value::Error(_)
+    ));
+}
+
 #[tokio::test]
 async fn test_zadd_then_zscore() {
     let state = make_state();
@@ -319,6 +437,25 @@ async fn test_bitcount_after_setbit() {
     assert_eq!(result, Resp3Value::Integer(1));
 }
 
+#[tokio::test]
+async fn test_bitcount_ranges_are_bytes_unless_bit_is_given() {
+    let state = make_state();
+    dispatch(&state, &args(&["SETBIT", "bm3", "3", "1"])).await;
+    dispatch(&state, &args(&["SETBIT", "bm3", "12", "1"])).await;
+
+    let count = |range: &'static [&'static str]| {
+        let mut command = vec!["BITCOUNT", "bm3"];
+        command.extend_from_slice(range);
+        let state = &state;
+        async move { dispatch(state, &args(&command)).await }
+    };
+    assert_eq!(count(&["1", "1"]).await, Resp3Value::Integer(1));
+    assert_eq!(count(&["0", "-1"]).await, Resp3Value::Integer(2));
+    assert_eq!(count(&["1", "1", "BIT"]).await, Resp3Value::Integer(0));
+    assert_eq!(count(&["3", "3", "bit"]).await, Resp3Value::Integer(1));
+    assert!(matches!(count(&["0"]).await, Resp3Value::Error(_)));
+}
+
 #[tokio::test]
 async fn test_flushall_returns_ok() {
     let state = make_state();
@@ -527,6 +664,33 @@ async fn test_zscan_returns_member_score_pairs() {
     }
 }
 
+#[tokio::test]
+async fn test_znotify_requires_notifier() {
+    let state = make_state();
+    let result = dispatch(&state, &args(&["ZNOTIFY", "GET", "board"])).await;
+    assert!(matches!(result, Resp3Value::Error(_)));
+}
+
+#[tokio::test]
+async fn test_znotify_set_get_del() {
+    let mut state = make_state();
+    let notifier =
+        crate::core::LeaderboardNotifier::new(Arc::new(crate::core::PubSubRouter::new()), 0);
+    state.sorted_set_store =
+        Arc::new(SortedSetStore::new().with_leaderboard_notifier(Some(Arc::new(notifier))));
+    let set = dispatch(&state, &args(&["ZNOTIFY", "SET", "board", "5", "2"])).await;
+    assert_eq!(set, Resp3Value::SimpleString("OK".into()));
+    let get = dispatch(&state, &args(&["ZNOTIFY", "GET", "board"])).await;
+    assert_eq!(
+        get,
+        Resp3Value::Array(vec![Resp3Value::Integer(5), Resp3Value::Integer(2)])
+    );
+    let del = dispatch(&state, &args(&["ZNOTIFY", "DEL", "board"])).await;
+    assert_eq!(del, Resp3Value::Integer(1));
+    let get = dispatch(&state, &args(&["ZNOTIFY", "GET", "board"])).await;
+    assert_eq!(get, Resp3Value::Null);
+}
+
 #[tokio::test]
 async fn test_blpop_immediate_hit() {
     let state = make_state();
@@ -661,6 +825,62 @@ async fn test_geodist_palermo_catania_km() {
     }
 }
 
+#[tokio::test]
+async fn test_geo_unknown_unit_is_an_error() {
+    let state = make_state();
+    dispatch(
+        &state,
+        &args(&[
+            "GEOADD",
+            "geo5",
+            "13.361389",
+            "38.115556",
+            "Palermo",
+            "15.087269",
+            "37.502669",
+            "Catania",
+        ]),
+    )
+    .await;
+
+    // Every GEO command refuses a unit it does not know instead of reading
+    // it as meters
+    for command in [
+        vec!["GEODIST", "geo5", "Palermo", "Catania", "furlongs"],
+        vec!["GEORADIUS", "geo5", "15", "37", "200", "furlongs"],
+        vec!["GEORADIUSBYMEMBER", "geo5", "Palermo", "200", "furlongs"],
+        vec![
+            "GEOSEARCH",
+            "geo5",
+            "FROMMEMBER",
+            "Palermo",
+            "BYRADIUS",
+            "200",
+            "furlongs",
+        ],
+        vec![
+            "GEOSEARCH",
+            "geo5",
+            "FROMLONLAT",
+            "15",
+            "37",
+            "BYBOX",
+            "400",
+            "400",
+            "furlongs",
+        ],
+    ] {
+        let result = dispatch(&state, &args(&command)).await;
+        match result {
+            Resp3Value::Error(e) => assert!(
+                e.starts_with("ERR Invalid value: Invalid distance unit: furlongs"),
+                "{command:?}: {e}"
+            ),
+            other => panic!("{command:?}: expected an error, got {other:?}"),
+        }
+    }
+}
+
 #[tokio::test]
 async fn test_synap_kvstats_returns_stats_array() {
     let state = make_state();
@@ -1078,6 +1298,32 @@ async fn test_resp3_publish_matches_subscriber() {
     }
 }
 
+#[tokio::test]
+async fn test_resp3_publish_rate_limited_per_publisher() {
+    use crate::core::{PubSubConfig, PubSubRouter};
+    use crate::protocol::publisher;
+
+    let mut state = make_state();
+    state.pubsub_router = Some(Arc::new(
+        PubSubRouter::with_config(PubSubConfig {
+            max_messages_per_sec: Some(1),
+            ..Default::default()
+        })
+        .unwrap(),
+    ));
+    let cmd = args(&["PUBLISH", "r3.limited", "hi"]);
+    let publish = || dispatch(&state, &cmd);
+
+    let result = publisher::scope("ip:10.0.0.1".into(), publish()).await;
+    assert_eq!(result, Resp3Value::Integer(0));
+    match publisher::scope("ip:10.0.0.1".into(), publish()).await {
+        Resp3Value::Error(e) => assert!(e.contains("rate limit"), "unexpected error: {e}"),
+        other => panic!("expected rate limit error, got {other:?}"),
+    }
+    let result = publisher::scope("ip:10.0.0.2".into(), publish()).await;
+    assert_eq!(result, Resp3Value::Integer(0));
+}
+
 #[tokio::test]
 async fn test_resp3_pubsub_channels_lists_topics() {
     let state = make_state_with_pubsub();
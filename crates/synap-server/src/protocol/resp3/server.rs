@@ -13,6 +13,9 @@
 //! - Each connection gets a `tracing::info_span!("resp3.conn", peer)`.
 //! - Each command gets a `tracing::debug_span!("resp3.cmd", cmd)`.
 //! - Commands slower than 1 ms are logged at WARN level.
+//!
+//! Write commands are also handed to the command hooks
+//! ([`crate::monitoring::CommandHooks`]) when any are registered.
 
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -20,8 +23,10 @@ use std::time::Instant;
 
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::Semaphore;
+use tracing::Instrument;
 
 use crate::metrics;
+use crate::monitoring::{CommandProtocol, CommandRecord, is_write_command};
 use crate::server::handlers::AppState;
 
 /// Maximum concurrent client connections accepted per binary listener. Beyond
@@ -110,6 +115,8 @@ async fn handle_connection(
     // resolved user once authenticated, for per-command ACL (phase6h).
     let mut authenticated = !state.require_auth;
     let mut auth_user: Option<crate::auth::User> = None;
+    // Stores of the database chosen with SELECT, database 0 until then
+    let mut selected = state.clone();
 
     loop {
         // Read the next frame, bounded by the idle timeout (slow-loris
@@ -245,6 +252,34 @@ async fn handle_connection(
             continue;
         }
 
+        // SELECT — switch this connection's database.
+        if cmd_upper == "SELECT" {
+            let db = match args.get(1).and_then(|a| a.as_str()) {
+                Some(db) if args.len() == 2 => db.parse::<u32>().ok(),
+                _ => {
+                    writer
+                        .write_error("ERR wrong number of arguments for 'SELECT' command")
+                        .await?;
+                    writer.flush().await?;
+                    continue;
+                }
+            };
+            match db.map(|db| state.select(db)) {
+                Some(Ok(database)) => {
+                    selected = database;
+                    writer.write_ok().await?;
+                }
+                Some(Err(_)) => writer.write_error("ERR DB index is out of range").await?,
+                None => {
+                    writer
+                        .write_error("ERR value is not an integer or out of range")
+                        .await?
+                }
+            }
+            writer.flush().await?;
+            continue;
+        }
+
         // Per-command ACL (phase6h): destructive/admin commands require an admin
         // user when auth is enforced. With auth disabled the binary port is
         // trusted (loopback by default), so no restriction is applied.
@@ -261,12 +296,23 @@ async fn handle_connection(
 
         // ── Dispatch with timing ─────────────────────────────────────────────
         let start = Instant::now();
-        let cmd_span = tracing::debug_span!("resp3.cmd", cmd = %cmd_upper, peer = %peer);
-        let response = {
-            let _g = cmd_span.enter();
-            dispatch(&state, &args).await
-        };
-        let elapsed = start.elapsed().as_secs_f64();
+        let cmd_span = crate::telemetry::command_span("resp3", cmd_upper);
+        let response = async {
+            if cmd_upper == "PUBLISH" {
+                let publisher = match &auth_user {
+                    Some(user) => format!("user:{}", user.username),
+                    None => format!("ip:{}", peer.ip()),
+                };
+                crate::protocol::publisher::scope(publisher.into(), dispatch(&selected, &args))
+                    .await
+            } else {
+                dispatch(&selected, &args).await
+            }
+        }
+        .instrument(cmd_span)
+        .await;
+        let duration = start.elapsed();
+        let elapsed = duration.as_secs_f64();
 
         // Write response and measure bytes.
         let before_write = writer.bytes_written();
@@ -285,6 +331,26 @@ async fn handle_connection(
         // Record metrics.
         let is_err = matches!(response, super::parser::Resp3Value::Error(_));
         metrics::record_resp3_command(cmd_upper, !is_err, elapsed);
+        metrics::record_verb_op(cmd_upper, !is_err, elapsed);
+
+        if is_write_command(cmd_upper) {
+            let hooks = state.monitoring.command_hooks();
+            if hooks.is_active() {
+                let mut record = CommandRecord::new(
+                    CommandProtocol::Resp3,
+                    cmd_upper,
+                    peer.ip().to_string(),
+                    duration,
+                );
+                record.resource = args.get(1).and_then(|a| a.as_str()).map(str::to_owned);
+                record.user = auth_user.as_ref().map(|u| u.username.clone());
+                if let Resp3Value::Error(message) = &response {
+                    record.success = false;
+                    record.error = Some(message.clone());
+                }
+                hooks.emit(record);
+            }
+        }
         metrics::resp3_bytes(0, written); // read bytes tracked per-frame below
 
         // Slow-command warning (threshold: 1 ms).
@@ -120,11 +120,8 @@ pub(super) async fn run(
             let key = arg_str(args, 0)?;
             let member1 = arg_bytes(args, 1)?;
             let member2 = arg_bytes(args, 2)?;
-            let unit = args
-                .get(3)
-                .and_then(|v| v.as_str())
-                .unwrap_or("m")
-                .parse::<crate::core::geospatial::DistanceUnit>()
+            let unit = args.get(3).map(|_| arg_str(args, 3)).transpose()?;
+            let unit = crate::core::geospatial::DistanceUnit::parse_or_meters(unit.as_deref())
                 .map_err(|e| e.to_string())?;
             state
                 .geospatial_store
@@ -394,6 +391,7 @@ pub(super) async fn run(
         }
 
         // ── Queue ─────────────────────────────────────────────────────────────
+        #[cfg(feature = "queues")]
         "QCREATE" => {
             let name = arg_str(args, 0)?;
             let qm = state
@@ -405,6 +403,7 @@ pub(super) async fn run(
                 .map(|()| SynapValue::Str("OK".into()))
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "queues")]
         "QDELETE" => {
             let name = arg_str(args, 0)?;
             let qm = state
@@ -416,6 +415,7 @@ pub(super) async fn run(
                 .map(SynapValue::Bool)
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "queues")]
         "QLIST" => {
             let qm = state
                 .queue_manager
@@ -426,6 +426,7 @@ pub(super) async fn run(
                 .map(|names| SynapValue::Array(names.into_iter().map(SynapValue::Str).collect()))
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "queues")]
         "QPUBLISH" => {
             // QPUBLISH queue payload [priority] [max_retries]
             let name = arg_str(args, 0)?;
@@ -441,6 +442,7 @@ pub(super) async fn run(
                 .map(SynapValue::Str)
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "queues")]
         "QCONSUME" => {
             // QCONSUME queue consumer_id
             let name = arg_str(args, 0)?;
@@ -460,7 +462,7 @@ pub(super) async fn run(
                         ),
                         (
                             SynapValue::Str("payload".into()),
-                            SynapValue::from((*msg.payload).clone()),
+                            SynapValue::from(msg.payload.to_vec()),
                         ),
                         (
                             SynapValue::Str("priority".into()),
@@ -474,6 +476,7 @@ pub(super) async fn run(
                 })
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "queues")]
         "QACK" => {
             let name = arg_str(args, 0)?;
             let message_id = arg_str(args, 1)?;
@@ -486,6 +489,7 @@ pub(super) async fn run(
                 .map(|()| SynapValue::Str("OK".into()))
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "queues")]
         "QNACK" => {
             // QNACK queue message_id [requeue:bool]  (default requeue = true)
             let name = arg_str(args, 0)?;
@@ -507,6 +511,7 @@ pub(super) async fn run(
                 .map(|()| SynapValue::Str("OK".into()))
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "queues")]
         "QSTATS" => {
             let name = arg_str(args, 0)?;
             let qm = state
@@ -549,6 +554,7 @@ pub(super) async fn run(
                 })
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "queues")]
         "QPURGE" => {
             let name = arg_str(args, 0)?;
             let qm = state
@@ -562,6 +568,7 @@ pub(super) async fn run(
         }
 
         // ── Stream ────────────────────────────────────────────────────────────
+        #[cfg(feature = "streams")]
         "SCREATE" => {
             let room = arg_str(args, 0)?;
             let sm = state
@@ -572,6 +579,7 @@ pub(super) async fn run(
                 .await
                 .map(|()| SynapValue::Str("OK".into()))
         }
+        #[cfg(feature = "streams")]
         "SGETORCREATE" => {
             // SGETORCREATE room
             // Idempotent: returns "CREATED" if a new room was created,
@@ -590,6 +598,7 @@ pub(super) async fn run(
                 })
             })
         }
+        #[cfg(feature = "streams")]
         "SPUBLISH" => {
             // SPUBLISH room event_type data
             let room = arg_str(args, 0)?;
@@ -603,8 +612,9 @@ pub(super) async fn run(
                 .await
                 .map(|offset| SynapValue::Int(offset as i64))
         }
+        #[cfg(feature = "streams")]
         "SREAD" => {
-            // SREAD room subscriber_id from_offset [limit]
+            // SREAD room subscriber_id from_offset [limit [min_age_ms]]
             let room = arg_str(args, 0)?;
             let subscriber_id = arg_str(args, 1)?;
             let from_offset = arg_int(args, 2)? as u64;
@@ -613,11 +623,16 @@ pub(super) async fn run(
                 .and_then(|v| v.as_int())
                 .map(|n| n as usize)
                 .unwrap_or(100);
+            let min_age_ms = args
+                .get(4)
+                .and_then(|v| v.as_int())
+                .map(|n| n.max(0) as u64)
+                .unwrap_or(0);
             let sm = state
                 .stream_manager
                 .as_deref()
                 .ok_or_else(|| "ERR stream subsystem not enabled".to_string())?;
-            sm.consume(&room, &subscriber_id, from_offset, limit)
+            sm.consume_with_min_age(&room, &subscriber_id, from_offset, limit, min_age_ms)
                 .await
                 .map(|events| {
                     SynapValue::Array(
@@ -642,6 +657,7 @@ pub(super) async fn run(
                     )
                 })
         }
+        #[cfg(feature = "streams")]
         "SDELETE" => {
             let room = arg_str(args, 0)?;
             let sm = state
@@ -652,6 +668,7 @@ pub(super) async fn run(
                 .await
                 .map(|()| SynapValue::Str("OK".into()))
         }
+        #[cfg(feature = "streams")]
         "SLIST" => {
             let sm = state
                 .stream_manager
@@ -662,6 +679,7 @@ pub(super) async fn run(
                 rooms.into_iter().map(SynapValue::Str).collect(),
             ))
         }
+        #[cfg(feature = "streams")]
         "SSTATS" => {
             let room = arg_str(args, 0)?;
             let sm = state
@@ -696,6 +714,7 @@ pub(super) async fn run(
         }
 
         // ── Pub/Sub ───────────────────────────────────────────────────────────
+        #[cfg(feature = "pubsub")]
         "PUBLISH" => {
             // PUBLISH topic payload_bytes
             // payload is deserialized as JSON when possible; falls back to string
@@ -709,7 +728,9 @@ pub(super) async fn run(
                 .pubsub_router
                 .as_deref()
                 .ok_or_else(|| "ERR pubsub subsystem not enabled".to_string())?;
-            ps.publish(&topic, payload_json, None)
+            let publisher = crate::protocol::publisher::current_or("synap_rpc");
+            ps.publish_as(&publisher, &topic, payload_json, None)
+                .inspect_err(crate::metrics::record_pubsub_rejected)
                 .map(|r| {
                     SynapValue::Map(vec![
                         (
@@ -724,6 +745,7 @@ pub(super) async fn run(
                 })
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "pubsub")]
         "SUBSCRIBE" => {
             // SUBSCRIBE topic [topic ...]
             // Returns subscriber_id; push frames arrive via the SynapRPC connection layer
@@ -753,6 +775,7 @@ pub(super) async fn run(
                 })
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "pubsub")]
         "UNSUBSCRIBE" => {
             // UNSUBSCRIBE subscriber_id [topic ...]  (no topics = unsubscribe all)
             let subscriber_id = arg_str(args, 0)?;
@@ -773,6 +796,7 @@ pub(super) async fn run(
                 .map(|n| SynapValue::Int(n as i64))
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "pubsub")]
         "TOPICS" => {
             let ps = state
                 .pubsub_router
@@ -782,6 +806,7 @@ pub(super) async fn run(
                 ps.list_topics().into_iter().map(SynapValue::Str).collect(),
             ))
         }
+        #[cfg(feature = "pubsub")]
         "PSSTATS" => {
             let ps = state
                 .pubsub_router
@@ -1056,6 +1081,7 @@ pub(super) async fn run(
         }
 
         // ── Scripting ─────────────────────────────────────────────────────────
+        #[cfg(feature = "scripting")]
         "EVAL" => {
             // EVAL script numkeys [key ...] [arg ...]
             let script = arg_str(args, 0)?;
@@ -1075,6 +1101,8 @@ pub(super) async fn run(
                 list_store: state.list_store.clone(),
                 set_store: state.set_store.clone(),
                 sorted_set_store: state.sorted_set_store.clone(),
+                queue_manager: state.queue_manager().cloned(),
+                stream_manager: state.stream_manager().cloned(),
             };
             state
                 .script_manager
@@ -1083,6 +1111,7 @@ pub(super) async fn run(
                 .map(|(v, _sha)| SynapValue::Str(serde_json::to_string(&v).unwrap_or_default()))
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "scripting")]
         "EVALSHA" => {
             // EVALSHA sha numkeys [key ...] [arg ...]
             let sha = arg_str(args, 0)?;
@@ -1102,6 +1131,8 @@ pub(super) async fn run(
                 list_store: state.list_store.clone(),
                 set_store: state.set_store.clone(),
                 sorted_set_store: state.sorted_set_store.clone(),
+                queue_manager: state.queue_manager().cloned(),
+                stream_manager: state.stream_manager().cloned(),
             };
             state
                 .script_manager
@@ -1110,11 +1141,13 @@ pub(super) async fn run(
                 .map(|v| SynapValue::Str(serde_json::to_string(&v).unwrap_or_default()))
                 .map_err(|e| e.to_string())
         }
+        #[cfg(feature = "scripting")]
         "SCRIPT.LOAD" => {
             let source = arg_str(args, 0)?;
             let sha = state.script_manager.load_script(&source);
             Ok(SynapValue::Str(sha))
         }
+        #[cfg(feature = "scripting")]
         "SCRIPT.EXISTS" => {
             let hashes: Vec<String> = args
                 .iter()
@@ -1125,10 +1158,12 @@ pub(super) async fn run(
                 exists.into_iter().map(SynapValue::Bool).collect(),
             ))
         }
+        #[cfg(feature = "scripting")]
         "SCRIPT.FLUSH" => {
             let n = state.script_manager.flush();
             Ok(SynapValue::Int(n as i64))
         }
+        #[cfg(feature = "scripting")]
         "SCRIPT.KILL" => {
             let killed = state.script_manager.kill_running();
             Ok(SynapValue::Bool(killed))
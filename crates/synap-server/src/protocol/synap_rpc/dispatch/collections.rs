@@ -225,6 +225,86 @@ pub(super) async fn run(
                 .map(|n| SynapValue::Int(n as i64))
                 .map_err(|e| e.to_string())
         }
+        // SPOP key [count] / SRANDMEMBER key [count]: one member (or null)
+        // without a count, an array with one
+        "SPOP" | "SRANDMEMBER" => {
+            let key = arg_str(args, 0)?;
+            let count = match args.len() {
+                1 => None,
+                2 => Some(arg_int(args, 1)?),
+                _ => return Err(format!("ERR wrong number of arguments for '{command}'")),
+            };
+            let members = if command == "SPOP" {
+                let count = count
+                    .map(|n| {
+                        usize::try_from(n)
+                            .map_err(|_| "ERR value is out of range, must be positive".to_string())
+                    })
+                    .transpose()?;
+                state.set_store.spop(&key, count)
+            } else {
+                state.set_store.srandmember(&key, count)
+            }
+            .map_err(|e| e.to_string())?;
+            Ok(match count {
+                Some(_) => SynapValue::Array(members.into_iter().map(SynapValue::from).collect()),
+                None => members
+                    .into_iter()
+                    .next()
+                    .map_or(SynapValue::Null, SynapValue::from),
+            })
+        }
+        "SINTER" | "SUNION" | "SDIFF" => {
+            let keys = same_slot_keys(state, args)?;
+            let members = match command {
+                "SINTER" => state.set_store.sinter(&keys),
+                "SUNION" => state.set_store.sunion(&keys),
+                _ => state.set_store.sdiff(&keys),
+            };
+            members
+                .map(|ms| SynapValue::Array(ms.into_iter().map(SynapValue::from).collect()))
+                .map_err(|e| e.to_string())
+        }
+        "SINTERSTORE" | "SUNIONSTORE" | "SDIFFSTORE" => {
+            if args.len() < 2 {
+                return Err(format!("ERR wrong number of arguments for '{command}'"));
+            }
+            let keys = same_slot_keys(state, args)?;
+            let (destination, sources) = (&keys[0], &keys[1..]);
+            let count = match command {
+                "SINTERSTORE" => state.set_store.sinterstore(destination, sources),
+                "SUNIONSTORE" => state.set_store.sunionstore(destination, sources),
+                _ => state.set_store.sdiffstore(destination, sources),
+            };
+            count
+                .map(|n| SynapValue::Int(n as i64))
+                .map_err(|e| e.to_string())
+        }
+        // SINTERCARD numkeys key [key ...] [LIMIT limit]
+        "SINTERCARD" => {
+            let numkeys = arg_int(args, 0)?;
+            if numkeys <= 0 {
+                return Err("ERR numkeys should be greater than 0".into());
+            }
+            let end = 1 + numkeys as usize;
+            if args.len() < end {
+                return Err("ERR Number of keys can't be greater than number of args".into());
+            }
+            let limit = match args.len() - end {
+                0 => 0,
+                2 if arg_str(args, end)?.eq_ignore_ascii_case("LIMIT") => {
+                    usize::try_from(arg_int(args, end + 1)?)
+                        .map_err(|_| "ERR LIMIT can't be negative".to_string())?
+                }
+                _ => return Err("ERR syntax error".into()),
+            };
+            let keys = same_slot_keys(state, &args[1..end])?;
+            state
+                .set_store
+                .sintercard(&keys, limit)
+                .map(|n| SynapValue::Int(n as i64))
+                .map_err(|e| e.to_string())
+        }
 
         // ── Sorted set ────────────────────────────────────────────────────────
         "ZADD" => {
@@ -232,8 +312,11 @@ pub(super) async fn run(
             let score = arg_float(args, 1)?;
             let member = arg_bytes(args, 2)?;
             let opts = ZAddOptions::default();
-            let (added, _) = state.sorted_set_store.zadd(&key, member, score, &opts);
-            Ok(SynapValue::Int(added as i64))
+            state
+                .sorted_set_store
+                .try_zadd(&key, member, score, &opts)
+                .map(|(added, _)| SynapValue::Int(added as i64))
+                .map_err(|e| e.to_string())
         }
         "ZRANGE" => {
             let key = arg_str(args, 0)?;
@@ -293,9 +376,8 @@ pub(super) async fn run(
                 .iter()
                 .filter_map(|v| v.as_bytes().map(|b| b.to_vec()))
                 .collect();
-            state
-                .hyperloglog_store
-                .pfadd(&key, elements, None)
+            crate::server::handlers::hll::pfadd_and_log(state, &key, elements, None)
+                .await
                 .map(|n| SynapValue::Bool(n > 0))
                 .map_err(|e| e.to_string())
         }
@@ -369,8 +451,10 @@ pub(super) async fn run(
                 .filter_map(|v| v.as_str().map(|s| s.to_owned()))
                 .collect();
             state
-                .hyperloglog_store
-                .pfmerge(&dest, sources)
+                .check_same_slot(std::iter::once(&dest).chain(&sources))
+                .map_err(|e| e.to_string())?;
+            crate::server::handlers::hll::pfmerge_and_log(state, &dest, sources)
+                .await
                 .map(|n| SynapValue::Int(n as i64))
                 .map_err(|e| e.to_string())
         }
@@ -449,10 +533,50 @@ pub(super) async fn run(
                 SynapValue::Map(pairs),
             ]))
         }
+        "ZNOTIFY" => {
+            // ZNOTIFY SET key top_n [rank_threshold] | GET key | DEL key
+            let sub = arg_str(args, 0)?.to_ascii_uppercase();
+            let key = arg_str(args, 1)?;
+            let notifier = state
+                .sorted_set_store
+                .leaderboard_notifier()
+                .ok_or_else(|| "ERR leaderboard notifications not enabled".to_string())?;
+            match sub.as_str() {
+                "SET" => {
+                    let window = crate::core::LeaderboardWindow {
+                        top_n: arg_int(args, 2)?.max(0) as usize,
+                        rank_threshold: match args.get(3) {
+                            Some(_) => Some(arg_int(args, 3)?.max(0) as usize),
+                            None => None,
+                        },
+                    };
+                    notifier
+                        .set_window(&key, window)
+                        .map(|()| SynapValue::Str("OK".into()))
+                        .map_err(|e| format!("ERR {e}"))
+                }
+                "GET" => Ok(notifier
+                    .window(&key)
+                    .map(|w| {
+                        SynapValue::Array(vec![
+                            SynapValue::Int(w.top_n as i64),
+                            w.rank_threshold
+                                .map(|t| SynapValue::Int(t as i64))
+                                .unwrap_or(SynapValue::Null),
+                        ])
+                    })
+                    .unwrap_or(SynapValue::Null)),
+                "DEL" => Ok(SynapValue::Int(notifier.remove_window(&key) as i64)),
+                _ => Err(format!(
+                    "ERR unknown ZNOTIFY subcommand '{sub}'. Try SET, GET, DEL"
+                )),
+            }
+        }
 
         // ── Blocking pops ──────────────────────────────────────────────────────
         "BLPOP" => {
             let (keys, timeout) = parse_block_keys_timeout(args)?;
+            state.check_same_slot(&keys).map_err(|e| e.to_string())?;
             match state.list_store.blpop(keys, timeout).await {
                 Ok((key, value)) => Ok(SynapValue::Array(vec![
                     SynapValue::Str(key),
@@ -463,6 +587,7 @@ pub(super) async fn run(
         }
         "BRPOP" => {
             let (keys, timeout) = parse_block_keys_timeout(args)?;
+            state.check_same_slot(&keys).map_err(|e| e.to_string())?;
             match state.list_store.brpop(keys, timeout).await {
                 Ok((key, value)) => Ok(SynapValue::Array(vec![
                     SynapValue::Str(key),
@@ -475,6 +600,9 @@ pub(super) async fn run(
             let source = arg_str(args, 0)?;
             let dest = arg_str(args, 1)?;
             let timeout = block_timeout(arg_int(args, 2)?)?;
+            state
+                .check_same_slot([&source, &dest])
+                .map_err(|e| e.to_string())?;
             match state.list_store.brpoplpush(&source, &dest, timeout).await {
                 Ok(value) => Ok(SynapValue::from(value)),
                 Err(_) => Ok(SynapValue::Null),
@@ -482,6 +610,7 @@ pub(super) async fn run(
         }
         "BZPOPMIN" => {
             let (keys, timeout) = parse_block_keys_timeout(args)?;
+            state.check_same_slot(&keys).map_err(|e| e.to_string())?;
             match state.sorted_set_store.bzpopmin(keys, timeout).await {
                 Ok((key, member, score)) => Ok(SynapValue::Array(vec![
                     SynapValue::Str(key),
@@ -493,6 +622,7 @@ pub(super) async fn run(
         }
         "BZPOPMAX" => {
             let (keys, timeout) = parse_block_keys_timeout(args)?;
+            state.check_same_slot(&keys).map_err(|e| e.to_string())?;
             match state.sorted_set_store.bzpopmax(keys, timeout).await {
                 Ok((key, member, score)) => Ok(SynapValue::Array(vec![
                     SynapValue::Str(key),
@@ -507,6 +637,18 @@ pub(super) async fn run(
     }
 }
 
+/// Every argument as a key, checked to hash to one cluster slot
+fn same_slot_keys(state: &AppState, args: &[SynapValue]) -> Result<Vec<String>, String> {
+    if args.is_empty() {
+        return Err("ERR wrong number of arguments".into());
+    }
+    let keys = (0..args.len())
+        .map(|i| arg_str(args, i))
+        .collect::<Result<Vec<_>, _>>()?;
+    state.check_same_slot(&keys).map_err(|e| e.to_string())?;
+    Ok(keys)
+}
+
 /// Map a blocking-op timeout in seconds to `Option<u64>`. Redis treats `0` as
 /// "block forever" (`None`); a negative timeout is an error.
 fn block_timeout(secs: i64) -> Result<Option<u64>, String> {
@@ -168,16 +168,22 @@ pub(super) async fn run(
         // ── Bitmap ────────────────────────────────────────────────────────────
         "BITCOUNT" => {
             let key = arg_str(args, 0)?;
+            // BITCOUNT key [start end [BYTE | BIT]], byte offsets by default
             let (start, end) = if args.len() >= 3 {
-                let s = arg_int(args, 1)?.max(0) as usize;
-                let e = arg_int(args, 2)?;
-                (Some(s), if e < 0 { None } else { Some(e as usize) })
+                (arg_int(args, 1)?, arg_int(args, 2)?)
             } else {
-                (None, None)
+                (0, -1)
+            };
+            let unit = if args.len() >= 4 {
+                arg_str(args, 3)?
+                    .parse()
+                    .map_err(|e: crate::core::SynapError| e.to_string())?
+            } else {
+                crate::core::BitRangeUnit::Byte
             };
             state
                 .bitmap_store
-                .bitcount(&key, start, end)
+                .bitcount_range(&key, start, end, unit)
                 .map(|n| SynapValue::Int(n as i64))
                 .map_err(|e| e.to_string())
         }
@@ -58,10 +58,13 @@ async fn run(state: &AppState, command: &str, args: Vec<SynapValue>) -> Result<S
 
         "HSET" | "HGET" | "HDEL" | "HINCRBY" | "HINCRBYFLOAT" | "HGETALL" | "HLEN" | "HEXISTS"
         | "LPUSH" | "RPUSH" | "LPOP" | "RPOP" | "LRANGE" | "LLEN" | "SADD" | "SMEMBERS"
-        | "SREM" | "SISMEMBER" | "SCARD" | "ZADD" | "ZRANGE" | "ZSCORE" | "ZCARD" | "ZREM"
-        | "PFADD" | "PFCOUNT" | "HMSET" | "HMGET" | "HKEYS" | "HVALS" | "PFMERGE" | "HLLSTATS"
-        | "HSCAN" | "SSCAN" | "ZSCAN" | "BLPOP" | "BRPOP" | "BRPOPLPUSH" | "BZPOPMIN"
-        | "BZPOPMAX" => collections::run(state, cmd, args).await,
+        | "SREM" | "SISMEMBER" | "SCARD" | "SPOP" | "SRANDMEMBER" | "SINTER" | "SUNION"
+        | "SDIFF" | "SINTERSTORE" | "SUNIONSTORE" | "SDIFFSTORE" | "SINTERCARD" | "ZADD"
+        | "ZRANGE" | "ZSCORE" | "ZCARD" | "ZREM" | "PFADD" | "PFCOUNT" | "HMSET" | "HMGET"
+        | "HKEYS" | "HVALS" | "PFMERGE" | "HLLSTATS" | "HSCAN" | "SSCAN" | "ZSCAN" | "ZNOTIFY"
+        | "BLPOP" | "BRPOP" | "BRPOPLPUSH" | "BZPOPMIN" | "BZPOPMAX" => {
+            collections::run(state, cmd, args).await
+        }
 
         _ => advanced::run(state, cmd, args).await,
     }
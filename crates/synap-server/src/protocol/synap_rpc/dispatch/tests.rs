@@ -58,6 +58,14 @@ fn make_state() -> AppState {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     }
 }
 
@@ -255,6 +263,68 @@ async fn test_sismember_true() {
     assert_eq!(resp.result, Ok(SynapValue::Bool(true)));
 }
 
+#[tokio::test]
+async fn test_srandmember_negative_count_repeats() {
+    let state = make_state();
+    dispatch(
+        &state,
+        req(1, "SADD", vec![str_arg("rpc_sr"), bytes_arg(b"only")]),
+    )
+    .await;
+
+    let resp = dispatch(
+        &state,
+        req(
+            2,
+            "SRANDMEMBER",
+            vec![str_arg("rpc_sr"), SynapValue::Int(-3)],
+        ),
+    )
+    .await;
+    let only = SynapValue::from(b"only".to_vec());
+    assert_eq!(
+        resp.result,
+        Ok(SynapValue::Array(vec![
+            only.clone(),
+            only.clone(),
+            only.clone()
+        ]))
+    );
+
+    let resp = dispatch(&state, req(3, "SPOP", vec![str_arg("rpc_sr")])).await;
+    assert_eq!(resp.result, Ok(only));
+    let resp = dispatch(&state, req(4, "SPOP", vec![str_arg("rpc_sr")])).await;
+    assert_eq!(resp.result, Ok(SynapValue::Null));
+}
+
+#[tokio::test]
+async fn test_set_algebra_commands() {
+    let state = make_state();
+    for (key, members) in [("rpc_sa", [b"a", b"b"]), ("rpc_sb", [b"b", b"c"])] {
+        let mut args = vec![str_arg(key)];
+        args.extend(members.iter().map(|m| bytes_arg(*m)));
+        dispatch(&state, req(1, "SADD", args)).await;
+    }
+    let keys = || vec![str_arg("rpc_sa"), str_arg("rpc_sb")];
+
+    let resp = dispatch(&state, req(2, "SINTER", keys())).await;
+    assert_eq!(
+        resp.result,
+        Ok(SynapValue::Array(vec![SynapValue::from(b"b".to_vec())]))
+    );
+
+    let mut store_args = vec![str_arg("rpc_su")];
+    store_args.extend(keys());
+    let resp = dispatch(&state, req(3, "SUNIONSTORE", store_args)).await;
+    assert_eq!(resp.result, Ok(SynapValue::Int(3)));
+
+    let mut card_args = vec![SynapValue::Int(2)];
+    card_args.extend(keys());
+    card_args.extend([str_arg("LIMIT"), SynapValue::Int(5)]);
+    let resp = dispatch(&state, req(4, "SINTERCARD", card_args)).await;
+    assert_eq!(resp.result, Ok(SynapValue::Int(1)));
+}
+
 #[tokio::test]
 async fn test_zadd_returns_count() {
     let state = make_state();
@@ -294,6 +364,53 @@ async fn test_zscore_after_zadd() {
     }
 }
 
+#[tokio::test]
+async fn test_znotify_set_get_del() {
+    let mut state = make_state();
+    let notifier =
+        crate::core::LeaderboardNotifier::new(Arc::new(crate::core::PubSubRouter::new()), 0);
+    state.sorted_set_store =
+        Arc::new(SortedSetStore::new().with_leaderboard_notifier(Some(Arc::new(notifier))));
+    let set = dispatch(
+        &state,
+        req(
+            1,
+            "ZNOTIFY",
+            vec![str_arg("SET"), str_arg("board"), SynapValue::Int(10)],
+        ),
+    )
+    .await;
+    assert_eq!(set.result, Ok(SynapValue::Str("OK".into())));
+    let get = dispatch(
+        &state,
+        req(2, "ZNOTIFY", vec![str_arg("GET"), str_arg("board")]),
+    )
+    .await;
+    assert_eq!(
+        get.result,
+        Ok(SynapValue::Array(vec![
+            SynapValue::Int(10),
+            SynapValue::Null
+        ]))
+    );
+    let del = dispatch(
+        &state,
+        req(3, "ZNOTIFY", vec![str_arg("DEL"), str_arg("board")]),
+    )
+    .await;
+    assert_eq!(del.result, Ok(SynapValue::Int(1)));
+    let bad = dispatch(
+        &state,
+        req(
+            4,
+            "ZNOTIFY",
+            vec![str_arg("SET"), str_arg("board"), SynapValue::Int(0)],
+        ),
+    )
+    .await;
+    assert!(bad.result.is_err(), "top_n 0 must be rejected");
+}
+
 #[tokio::test]
 async fn test_pfadd_result_is_ok() {
     let state = make_state();
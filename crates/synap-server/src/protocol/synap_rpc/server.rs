@@ -20,10 +20,14 @@
 //! # Tracing
 //! - Each request gets a `tracing::debug_span!("rpc.req", cmd)`.
 //! - Commands slower than 1 ms are logged at WARN level.
+//!
+//! Write commands are also handed to the command hooks
+//! ([`crate::monitoring::CommandHooks`]) when any are registered.
 
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
 
 use thunder::server::{
     AuthError, Credentials, Dispatch, ListenerConfig, ListenerHandle, MetricsObserver, Principal,
@@ -33,6 +37,7 @@ use thunder::server::{
 use crate::auth::User;
 use crate::core::pubsub::Message as PubSubMessage;
 use crate::metrics;
+use crate::monitoring::{CommandProtocol, CommandRecord, is_write_command};
 use crate::server::handlers::AppState;
 
 use super::dispatch::run_command;
@@ -58,6 +63,7 @@ impl MetricsObserver for SynapMetrics {
         is_error: bool,
     ) {
         metrics::record_synap_rpc_command(command, !is_error, duration.as_secs_f64());
+        metrics::record_verb_op(command, !is_error, duration.as_secs_f64());
         metrics::synap_rpc_frame_sizes(in_bytes, out_bytes);
         if duration > SLOW_COMMAND_THRESHOLD {
             tracing::warn!(
@@ -113,11 +119,47 @@ impl Dispatch for SynapDispatch {
             }
         }
 
-        let result = {
-            let span = tracing::debug_span!("rpc.req", cmd = %command);
-            let _guard = span.enter();
-            run_command(&self.state, command, args).await
-        };
+        let hooks = self.state.monitoring.command_hooks();
+        let hooked = (is_write_command(command) && hooks.is_active()).then(|| {
+            args.first()
+                .and_then(|a| a.as_bytes())
+                .map(|b| String::from_utf8_lossy(b).into_owned())
+        });
+        let started = Instant::now();
+
+        let result = async {
+            if command.eq_ignore_ascii_case("PUBLISH") {
+                let publisher = match session.principal_name() {
+                    Some(user) => format!("user:{user}"),
+                    None => format!("conn:{}", session.connection_id()),
+                };
+                crate::protocol::publisher::scope(
+                    publisher.into(),
+                    run_command(&self.state, command, args),
+                )
+                .await
+            } else {
+                run_command(&self.state, command, args).await
+            }
+        }
+        .instrument(crate::telemetry::command_span("synap_rpc", command))
+        .await;
+
+        if let Some(resource) = hooked {
+            let mut record = CommandRecord::new(
+                CommandProtocol::SynapRpc,
+                command.to_ascii_uppercase(),
+                format!("conn:{}", session.connection_id()),
+                started.elapsed(),
+            );
+            record.resource = resource;
+            record.user = session.principal_name();
+            if let Err(message) = &result {
+                record.success = false;
+                record.error = Some(message.clone());
+            }
+            hooks.emit(record);
+        }
 
         // After SUBSCRIBE / KV.WATCH succeeds, bridge the connection's push
         // channel to the pubsub router so publish() reaches this client.
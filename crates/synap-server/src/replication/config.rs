@@ -1,4 +1,5 @@
 use super::types::NodeRole;
+use crate::compression::LinkCompressionConfig;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
@@ -34,6 +35,71 @@ pub struct ReplicationConfig {
 
     /// Replica timeout in seconds (master marks replica dead)
     pub replica_timeout_secs: u64,
+
+    /// Automatic failover (replicas elect and promote a new master)
+    #[serde(default)]
+    pub failover: AutoFailoverConfig,
+
+    /// Compression of the master → replica link. Used only when both the
+    /// master and the replica enable it.
+    #[serde(default)]
+    pub compression: LinkCompressionConfig,
+}
+
+/// Automatic failover configuration
+///
+/// Every node of the replication group (master and replicas) lists the HTTP
+/// URLs of the others in `peers`. When the replicas lose the master for
+/// `down_after_ms`, they elect the most up-to-date replica by majority vote,
+/// which promotes itself and announces the new topology.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoFailoverConfig {
+    /// Enable automatic failover
+    pub enabled: bool,
+
+    /// Unique id of this node within the replication group
+    pub node_id: String,
+
+    /// HTTP URL clients and peers use to reach this node
+    pub advertise_url: Option<String>,
+
+    /// Replication address peers connect to once this node is master.
+    /// Defaults to `replica_listen_address`.
+    pub announce_address: Option<SocketAddr>,
+
+    /// HTTP URL of the initial master (replicas only), reported by the
+    /// topology endpoint until the first failover
+    pub master_url: Option<String>,
+
+    /// HTTP URLs of every other node in the group
+    pub peers: Vec<String>,
+
+    /// How long the master must be unreachable before an election starts
+    pub down_after_ms: u64,
+
+    /// Timeout of a vote round; failed rounds retry after a random fraction
+    /// of it so candidates do not keep splitting the vote
+    pub election_timeout_ms: u64,
+
+    /// Bearer token sent with vote and announce requests when auth is enabled
+    pub auth_token: Option<String>,
+}
+
+impl Default for AutoFailoverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            node_id: String::new(),
+            advertise_url: None,
+            announce_address: None,
+            master_url: None,
+            peers: Vec::new(),
+            down_after_ms: 5000,
+            election_timeout_ms: 1000,
+            auth_token: None,
+        }
+    }
 }
 
 impl Default for ReplicationConfig {
@@ -49,6 +115,8 @@ impl Default for ReplicationConfig {
             auto_reconnect: true,
             reconnect_delay_ms: 5000, // 5 seconds
             replica_timeout_secs: 30, // 30 seconds timeout
+            failover: AutoFailoverConfig::default(),
+            compression: LinkCompressionConfig::default(),
         }
     }
 }
@@ -78,6 +146,22 @@ impl ReplicationConfig {
             }
         }
 
+        if self.failover.enabled {
+            if self.failover.node_id.is_empty() {
+                return Err("Automatic failover requires failover.node_id".to_string());
+            }
+            if self.failover.peers.is_empty() {
+                return Err("Automatic failover requires failover.peers".to_string());
+            }
+            if self.role == NodeRole::Replica && self.replica_listen_address.is_none() {
+                return Err(
+                    "Automatic failover requires replica_listen_address on replicas, \
+                     to accept replicas once promoted"
+                        .to_string(),
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -132,6 +216,23 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_failover_validation() {
+        let mut config = ReplicationConfig::default();
+        config.enabled = true;
+        config.role = NodeRole::Replica;
+        config.master_address = Some(SocketAddr::from_str("127.0.0.1:15501").unwrap());
+        config.failover.enabled = true;
+
+        // Needs an id, peers, and a listen address for after promotion
+        assert!(config.validate().is_err());
+        config.failover.node_id = "replica-1".to_string();
+        config.failover.peers = vec!["http://replica-2:15500".to_string()];
+        assert!(config.validate().is_err());
+        config.replica_listen_address = Some(SocketAddr::from_str("0.0.0.0:15501").unwrap());
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_role_checks() {
         let mut config = ReplicationConfig::default();
@@ -4,12 +4,12 @@
 /// - Manual failover (admin command)
 /// - Graceful promotion (finish replicating)
 /// - Configuration update
-/// - Automatic mode (future)
+/// - Automatic mode: see `sentinel`
 use super::config::ReplicationConfig;
 use super::master::MasterNode;
 use super::replica::ReplicaNode;
 use super::types::{NodeRole, ReplicationError, ReplicationResult};
-use crate::core::KVStore;
+use crate::persistence::StoreArcs;
 use std::sync::Arc;
 use tracing::{info, warn};
 
@@ -32,10 +32,11 @@ impl FailoverManager {
     /// 3. Change role to master
     /// 4. Start accepting replica connections
     ///
-    /// Returns new master node
+    /// Returns new master node, serving full syncs from every store in
+    /// `stores`
     pub async fn promote_replica_to_master(
         replica: Arc<ReplicaNode>,
-        kv_store: Arc<KVStore>,
+        stores: StoreArcs,
     ) -> ReplicationResult<MasterNode> {
         info!("Starting replica promotion to master");
 
@@ -46,9 +47,8 @@ impl FailoverManager {
         let current_offset = replica.current_offset();
         info!("Replica synced at offset {}", current_offset);
 
-        // Stop replica (will disconnect from master)
-        // Note: In real implementation, we'd need a shutdown mechanism
-        warn!("Replica disconnect not fully implemented - manual intervention may be needed");
+        // Stop replica (disconnects from master, keeps applied data)
+        replica.stop();
 
         // Create new master configuration
         let master_config = ReplicationConfig {
@@ -65,7 +65,8 @@ impl FailoverManager {
         };
 
         // Create new master node
-        let master = MasterNode::new(master_config, kv_store, None).await?;
+        // Continue the old master's offsets so its replicas can resume
+        let master = MasterNode::with_start_offset(master_config, stores, current_offset).await?;
 
         info!("Replica successfully promoted to master");
 
@@ -98,11 +99,12 @@ impl FailoverManager {
         }
     }
 
-    /// Demote master to replica (reverse failover)
+    /// Demote master to replica (reverse failover). The new master's full
+    /// sync replaces the keys of every store in `stores`.
     pub async fn demote_master_to_replica(
         _master: MasterNode,
         new_master_addr: std::net::SocketAddr,
-        kv_store: Arc<KVStore>,
+        stores: StoreArcs,
     ) -> ReplicationResult<Arc<ReplicaNode>> {
         info!(
             "Demoting master to replica, new master: {}",
@@ -123,11 +125,7 @@ impl FailoverManager {
         };
 
         // Create new replica node
-        let replica = ReplicaNode::new(
-            replica_config,
-            crate::persistence::StoreArcs::kv_only(kv_store),
-        )
-        .await?;
+        let replica = ReplicaNode::new(replica_config, stores).await?;
 
         info!("Master successfully demoted to replica");
 
@@ -138,7 +136,7 @@ impl FailoverManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::KVConfig;
+    use crate::core::{HashStore, KVConfig, KVStore};
 
     #[tokio::test]
     async fn test_failover_manager_creation() {
@@ -157,16 +155,16 @@ mod tests {
         replica_config.master_address = Some("127.0.0.1:15501".parse().unwrap());
         replica_config.auto_reconnect = false;
 
-        let kv = Arc::new(KVStore::new(KVConfig::default()));
-        let replica = ReplicaNode::new(
-            replica_config,
-            crate::persistence::StoreArcs::kv_only(Arc::clone(&kv)),
-        )
-        .await
-        .unwrap();
+        let stores = StoreArcs {
+            hash_store: Some(Arc::new(HashStore::new())),
+            ..StoreArcs::kv_only(Arc::new(KVStore::new(KVConfig::default())))
+        };
+        let replica = ReplicaNode::new(replica_config, stores.clone())
+            .await
+            .unwrap();
 
-        // Promote to master
-        let master = FailoverManager::promote_replica_to_master(replica, kv)
+        // Promote to master, keeping every store for the next full syncs
+        let master = FailoverManager::promote_replica_to_master(replica, stores)
             .await
             .unwrap();
 
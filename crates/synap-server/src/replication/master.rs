@@ -1,10 +1,13 @@
 use super::config::ReplicationConfig;
 use super::replication_log::ReplicationLog;
 use super::types::{
-    ReplicaInfo, ReplicationCommand, ReplicationError, ReplicationOperation, ReplicationResult,
-    ReplicationStats,
+    PROTOCOL_VERSION, ReplicaHello, ReplicaInfo, ReplicationCommand, ReplicationError,
+    ReplicationOperation, ReplicationResult, ReplicationStats,
 };
-use crate::core::{KVStore, StreamManager};
+use crate::compression::{
+    CompressionAlgorithm, LinkCodec, LinkCompressionConfig, LinkCompressionStats,
+};
+use crate::persistence::StoreArcs;
 use crate::persistence::types::Operation;
 use parking_lot::RwLock;
 use std::collections::HashMap;
@@ -31,7 +34,7 @@ pub struct MasterNode {
     config: ReplicationConfig,
     replication_log: Arc<ReplicationLog>,
     #[allow(dead_code)]
-    stream_manager: Option<Arc<StreamManager>>,
+    stores: StoreArcs,
 
     /// Connected replicas
     replicas: Arc<RwLock<HashMap<String, ReplicaConnection>>>,
@@ -41,6 +44,9 @@ pub struct MasterNode {
 
     /// Total bytes replicated (accumulated)
     total_bytes: Arc<AtomicU64>,
+
+    /// Bytes through compressed replica links, across all links ever opened
+    compression_stats: Arc<LinkCompressionStats>,
 }
 
 struct ReplicaConnection {
@@ -50,12 +56,15 @@ struct ReplicaConnection {
     connected_at: u64,
     last_heartbeat: u64,
     sender: mpsc::UnboundedSender<ReplicationCommand>,
+    /// Byte counters of the link, if it is compressed
+    link_stats: Option<Arc<LinkCompressionStats>>,
 }
 
 enum ReplicationMessage {
     Operation {
         operation: Operation,
         offset: u64,
+        request_id: Option<String>,
     },
     #[allow(dead_code)]
     Heartbeat,
@@ -63,10 +72,19 @@ enum ReplicationMessage {
 
 impl MasterNode {
     /// Create a new master node
-    pub async fn new(
+    ///
+    /// `stores` are the datatypes sent to replicas on full sync; absent stores
+    /// are left out of the snapshot.
+    pub async fn new(config: ReplicationConfig, stores: StoreArcs) -> ReplicationResult<Self> {
+        Self::with_start_offset(config, stores, 0).await
+    }
+
+    /// Create a master node whose replication log continues from
+    /// `start_offset` (used when promoting a replica)
+    pub async fn with_start_offset(
         config: ReplicationConfig,
-        kv_store: Arc<KVStore>,
-        stream_manager: Option<Arc<StreamManager>>,
+        stores: StoreArcs,
+        start_offset: u64,
     ) -> ReplicationResult<Self> {
         if !config.is_master() {
             return Err(ReplicationError::NotMaster);
@@ -74,11 +92,11 @@ impl MasterNode {
 
         info!(
             "Initializing master node with stream support: {}",
-            stream_manager.is_some()
+            stores.stream_manager.is_some()
         );
 
         // Create replication log (1M operations buffer, like Redis)
-        let replication_log = Arc::new(ReplicationLog::new(1_000_000));
+        let replication_log = Arc::new(ReplicationLog::starting_at(1_000_000, start_offset));
 
         let (replication_tx, replication_rx) = mpsc::unbounded_channel();
         let replicas = Arc::new(RwLock::new(HashMap::new()));
@@ -89,15 +107,16 @@ impl MasterNode {
             .expect("master node is only started with a configured replica_listen_address");
         let replicas_clone = Arc::clone(&replicas);
         let log_clone = Arc::clone(&replication_log);
-        let kv_clone = Arc::clone(&kv_store);
-        let stream_clone = stream_manager.clone();
+        let stores_clone = stores.clone();
+        let compression_stats = Arc::new(LinkCompressionStats::default());
 
         tokio::spawn(Self::listen_for_replicas(
             listen_addr,
             replicas_clone,
             log_clone,
-            kv_clone,
-            stream_clone,
+            stores_clone,
+            config.compression.clone(),
+            Arc::clone(&compression_stats),
         ));
 
         // Spawn heartbeat task
@@ -120,10 +139,11 @@ impl MasterNode {
         Ok(Self {
             config,
             replication_log,
-            stream_manager,
+            stores,
             replicas,
             replication_tx,
             total_bytes,
+            compression_stats,
         })
     }
 
@@ -132,8 +152,9 @@ impl MasterNode {
         listen_addr: SocketAddr,
         replicas: Arc<RwLock<HashMap<String, ReplicaConnection>>>,
         replication_log: Arc<ReplicationLog>,
-        kv_store: Arc<KVStore>,
-        stream_manager: Option<Arc<StreamManager>>,
+        stores: StoreArcs,
+        compression: LinkCompressionConfig,
+        compression_stats: Arc<LinkCompressionStats>,
     ) {
         let listener = match TcpListener::bind(listen_addr).await {
             Ok(l) => l,
@@ -152,16 +173,16 @@ impl MasterNode {
 
                     let replicas_clone = Arc::clone(&replicas);
                     let log_clone = Arc::clone(&replication_log);
-                    let kv_clone = Arc::clone(&kv_store);
-                    let stream_clone = stream_manager.clone();
+                    let stores_clone = stores.clone();
 
                     tokio::spawn(Self::handle_replica(
                         stream,
                         addr,
                         replicas_clone,
                         log_clone,
-                        kv_clone,
-                        stream_clone,
+                        stores_clone,
+                        compression.clone(),
+                        Arc::clone(&compression_stats),
                     ));
                 }
                 Err(e) => {
@@ -177,8 +198,9 @@ impl MasterNode {
         addr: SocketAddr,
         replicas: Arc<RwLock<HashMap<String, ReplicaConnection>>>,
         replication_log: Arc<ReplicationLog>,
-        kv_store: Arc<KVStore>,
-        stream_manager: Option<Arc<StreamManager>>,
+        stores: StoreArcs,
+        compression: LinkCompressionConfig,
+        compression_stats: Arc<LinkCompressionStats>,
     ) {
         let replica_id = Uuid::new_v4().to_string();
         info!(
@@ -187,19 +209,17 @@ impl MasterNode {
             "New replica connection from master"
         );
 
-        // Read replica handshake (request offset)
+        // Read replica handshake (request offset, then optional capabilities)
         let mut buf = vec![0u8; 1024];
         debug!("Waiting for handshake from replica");
-        let requested_offset = match stream.read(&mut buf).await {
+        let (requested_offset, hello) = match stream.read(&mut buf).await {
             Ok(n) => {
                 if n == 0 {
                     warn!("Connection closed before handshake");
                     return;
                 }
                 debug!(bytes = n, "Received handshake bytes");
-                bincode::serde::decode_from_slice::<u64, _>(&buf[..n], bincode::config::legacy())
-                    .map(|(v, _)| v)
-                    .unwrap_or_default()
+                Self::parse_handshake(&buf[..n])
             }
             Err(e) => {
                 error!(error = %e, "Error reading handshake");
@@ -207,6 +227,40 @@ impl MasterNode {
             }
         };
 
+        // A replica on another protocol version would misread our frames.
+        // One that sent no hello speaks version 1.
+        let version = hello.as_ref().map_or(1, |hello| hello.protocol_version);
+        let Some(hello) = hello.filter(|_| version == PROTOCOL_VERSION) else {
+            let reason = format!(
+                "replica speaks replication protocol v{version} and the master \
+                 v{PROTOCOL_VERSION}; run the same Synap version on both"
+            );
+            error!(replica_id = %replica_id, addr = %addr, "Refusing replica: {}", reason);
+            let reply = ReplicationCommand::Refused { reason };
+            let _ = Self::send_command(&mut stream, &reply, None, &compression_stats).await;
+            return;
+        };
+
+        // Tell the replica which capabilities the link uses. Compression
+        // needs both sides to enable it.
+        let mut codec = None;
+        let algorithm =
+            if compression.enabled && hello.compression.contains(&CompressionAlgorithm::Zstd) {
+                codec = Some(LinkCodec::new(compression.clone()));
+                CompressionAlgorithm::Zstd
+            } else {
+                CompressionAlgorithm::None
+            };
+        let reply = ReplicationCommand::Hello {
+            compression: algorithm,
+            protocol_version: PROTOCOL_VERSION,
+        };
+        if let Err(e) = Self::send_command(&mut stream, &reply, None, &compression_stats).await {
+            warn!(replica_id = %replica_id, error = %e, "Failed to send link hello");
+            return;
+        }
+        info!(replica_id = %replica_id, compression = ?algorithm, "Link settings agreed");
+
         info!(
             replica_id = %replica_id,
             requested_offset = requested_offset,
@@ -220,8 +274,11 @@ impl MasterNode {
         // Full sync needed if:
         // 1. Requested offset is older than what we have in log
         // 2. This is a fresh connection (requested 0, replication log empty) - use snapshot
-        let needs_full_sync =
-            requested_offset < oldest_offset || (requested_offset == 0 && current_offset == 0);
+        // 3. Replica is ahead of us (it followed a previous master further than
+        //    we did) - its extra operations are not in our history
+        let needs_full_sync = requested_offset < oldest_offset
+            || requested_offset > current_offset
+            || (requested_offset == 0 && current_offset == 0);
 
         debug!(
             oldest_offset = oldest_offset,
@@ -249,6 +306,7 @@ impl MasterNode {
                     connected_at: Self::current_timestamp(),
                     last_heartbeat: Self::current_timestamp(),
                     sender: tx,
+                    link_stats: codec.as_ref().map(LinkCodec::stats),
                 },
             );
         }
@@ -257,14 +315,22 @@ impl MasterNode {
             info!(replica_id = %replica_id, "Performing full sync for replica");
             Self::send_full_sync(
                 &mut stream,
-                &kv_store,
-                stream_manager.as_ref().map(|s| s.as_ref()),
+                &stores,
                 &replication_log,
+                codec.as_mut(),
+                &compression_stats,
             )
             .await
         } else {
             info!(replica_id = %replica_id, "Performing partial sync for replica");
-            Self::send_partial_sync(&mut stream, requested_offset, &replication_log).await
+            Self::send_partial_sync(
+                &mut stream,
+                requested_offset,
+                &replication_log,
+                codec.as_mut(),
+                &compression_stats,
+            )
+            .await
         };
 
         if let Err(e) = sync_result {
@@ -278,7 +344,10 @@ impl MasterNode {
         // any operations that arrived during snapshot transfer; they are written
         // after the FullSync frame, so wire order stays snapshot-then-ops.
         while let Some(cmd) = rx.recv().await {
-            if Self::send_command(&mut stream, &cmd).await.is_err() {
+            if Self::send_command(&mut stream, &cmd, codec.as_mut(), &compression_stats)
+                .await
+                .is_err()
+            {
                 warn!("Replica {} disconnected", replica_id);
                 break;
             }
@@ -289,25 +358,39 @@ impl MasterNode {
         info!("Replica {} disconnected", replica_id);
     }
 
+    /// Split a handshake into the requested offset and, from replicas that
+    /// send one, their protocol version and capabilities
+    fn parse_handshake(data: &[u8]) -> (u64, Option<ReplicaHello>) {
+        let config = bincode::config::legacy();
+        let Ok((offset, read)) = bincode::serde::decode_from_slice::<u64, _>(data, config) else {
+            return (0, None);
+        };
+        let hello = bincode::serde::decode_from_slice::<ReplicaHello, _>(&data[read..], config)
+            .ok()
+            .map(|(hello, _)| hello);
+        (offset, hello)
+    }
+
     /// Send full sync (snapshot) to replica
     async fn send_full_sync(
         stream: &mut TcpStream,
-        kv_store: &KVStore,
-        stream_manager: Option<&StreamManager>,
+        stores: &StoreArcs,
         replication_log: &ReplicationLog,
+        codec: Option<&mut LinkCodec>,
+        compression_stats: &LinkCompressionStats,
     ) -> ReplicationResult<()> {
         let current_offset = replication_log.current_offset();
 
-        // Create snapshot with stream support
+        // Snapshot every datatype the master holds
         let snapshot_data =
-            super::sync::create_snapshot_with_streams(kv_store, stream_manager, current_offset)
+            super::sync::create_snapshot_from_stores(stores.as_refs(), current_offset)
                 .await
                 .map_err(ReplicationError::SerializationError)?;
 
         info!(
             "Created snapshot: {} bytes for full sync (streams: {})",
             snapshot_data.len(),
-            stream_manager.is_some()
+            stores.stream_manager.is_some()
         );
 
         let cmd = ReplicationCommand::FullSync {
@@ -316,18 +399,27 @@ impl MasterNode {
         };
 
         // Send with length prefix
-        Self::send_command(stream, &cmd).await?;
+        Self::send_command(stream, &cmd, codec, compression_stats).await?;
 
         debug!("Full sync sent, offset: {}", current_offset);
         Ok(())
     }
 
-    /// Send command with length prefix
+    /// Send command with length prefix, through the link codec when the link
+    /// is compressed
     async fn send_command(
         stream: &mut TcpStream,
         cmd: &ReplicationCommand,
+        codec: Option<&mut LinkCodec>,
+        compression_stats: &LinkCompressionStats,
     ) -> ReplicationResult<()> {
-        let data = bincode::serde::encode_to_vec(cmd, bincode::config::legacy())?;
+        let mut data = bincode::serde::encode_to_vec(cmd, bincode::config::legacy())?;
+        if let Some(codec) = codec {
+            let raw = data.len();
+            data = codec.encode(&data)?;
+            compression_stats.record(raw, data.len());
+            crate::metrics::record_replication_compression("sent", raw, data.len());
+        }
         let len = data.len() as u32;
 
         // Send length prefix
@@ -344,6 +436,8 @@ impl MasterNode {
         stream: &mut TcpStream,
         from_offset: u64,
         replication_log: &ReplicationLog,
+        codec: Option<&mut LinkCodec>,
+        compression_stats: &LinkCompressionStats,
     ) -> ReplicationResult<()> {
         let operations = replication_log.get_from_offset(from_offset)?;
         let op_count = operations.len();
@@ -356,7 +450,7 @@ impl MasterNode {
         // Use the length-prefixed framing the replica's read_command expects —
         // a raw write_all here desynchronized the frame boundary so the replica
         // misparsed the partial sync and applied nothing (issue #234).
-        Self::send_command(stream, &cmd).await?;
+        Self::send_command(stream, &cmd, codec, compression_stats).await?;
 
         debug!("Partial sync sent, {} operations", op_count);
         Ok(())
@@ -400,6 +494,7 @@ impl MasterNode {
                 ReplicationMessage::Operation {
                     operation,
                     offset: _offset,
+                    request_id,
                 } => {
                     // Operation already added to log by replicate() method
                     // Calculate size of serialized operation for byte tracking
@@ -408,6 +503,7 @@ impl MasterNode {
                             offset: _offset,
                             timestamp: Self::current_timestamp(),
                             operation: operation.clone(),
+                            request_id: request_id.clone(),
                         },
                         bincode::config::legacy(),
                     ) {
@@ -423,6 +519,7 @@ impl MasterNode {
                             offset: _offset,
                             timestamp: Self::current_timestamp(),
                             operation: operation.clone(),
+                            request_id: request_id.clone(),
                         });
 
                         let _ = replica.sender.send(cmd);
@@ -445,13 +542,21 @@ impl MasterNode {
     }
 
     /// Replicate an operation to all replicas
+    ///
+    /// The operation carries the caller's request id (if any) so replicas can log
+    /// it against the same id as the master.
     pub fn replicate(&self, operation: Operation) -> u64 {
-        let offset = self.replication_log.append(operation.clone());
+        let request_id = crate::server::request_id::current();
+        let offset = self
+            .replication_log
+            .append_with_request_id(operation.clone(), request_id.clone());
 
         // Send to replication task with offset
-        let _ = self
-            .replication_tx
-            .send(ReplicationMessage::Operation { operation, offset });
+        let _ = self.replication_tx.send(ReplicationMessage::Operation {
+            operation,
+            offset,
+            request_id,
+        });
 
         offset
     }
@@ -479,6 +584,11 @@ impl MasterNode {
                     connected_at: r.connected_at,
                     last_sync: r.last_heartbeat,
                     lag_ms,
+                    compressed: r.link_stats.is_some(),
+                    compression_saved_bytes: r
+                        .link_stats
+                        .as_ref()
+                        .map_or(0, |stats| stats.saved_bytes()),
                 }
             })
             .collect()
@@ -560,6 +670,7 @@ impl MasterNode {
             lag_ms,
             total_replicated: current_offset,
             total_bytes: self.total_bytes.load(Ordering::Relaxed),
+            compression_saved_bytes: self.compression_stats.saved_bytes(),
             last_heartbeat: Self::current_timestamp(),
             connected: !reps.is_empty(),
         }
@@ -576,7 +687,8 @@ impl MasterNode {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::KVConfig;
+    use crate::core::{KVConfig, KVStore};
+    use bytes::Bytes;
 
     #[tokio::test]
     async fn test_master_initialization() {
@@ -586,7 +698,7 @@ mod tests {
         config.replica_listen_address = Some("127.0.0.1:0".parse().unwrap());
 
         let kv = Arc::new(KVStore::new(KVConfig::default()));
-        let master = MasterNode::new(config, kv, None).await;
+        let master = MasterNode::new(config, StoreArcs::kv_only(kv)).await;
 
         assert!(master.is_ok());
     }
@@ -599,12 +711,14 @@ mod tests {
         config.replica_listen_address = Some("127.0.0.1:0".parse().unwrap());
 
         let kv = Arc::new(KVStore::new(KVConfig::default()));
-        let master = MasterNode::new(config, kv, None).await.unwrap();
+        let master = MasterNode::new(config, StoreArcs::kv_only(kv))
+            .await
+            .unwrap();
 
         // Replicate operation
         let op = Operation::KVSet {
             key: "test_key".to_string(),
-            value: b"test_value".to_vec(),
+            value: Bytes::from_static(b"test_value"),
             ttl: None,
         };
 
@@ -5,6 +5,7 @@
 /// - N Replica nodes (read-only)
 /// - Async replication (non-blocking)
 /// - Manual failover (promote replica to master)
+/// - Automatic failover (replicas elect a new master, see `sentinel`)
 ///
 /// Features:
 /// - Full sync on replica connect (snapshot + incremental)
@@ -16,14 +17,16 @@ pub mod failover;
 pub mod master;
 pub mod replica;
 pub mod replication_log;
+pub mod sentinel;
 pub mod sync;
 pub mod types;
 
-pub use config::ReplicationConfig;
+pub use config::{AutoFailoverConfig, ReplicationConfig};
 pub use failover::FailoverManager;
 pub use master::MasterNode;
 pub use replica::ReplicaNode;
 pub use replication_log::ReplicationLog;
+pub use sentinel::FailoverCoordinator;
 pub use types::{
     NodeRole, ReplicationCommand, ReplicationError, ReplicationResult, ReplicationStats,
 };
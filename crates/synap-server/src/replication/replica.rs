@@ -1,9 +1,12 @@
 use super::config::ReplicationConfig;
 use super::types::{
-    ReplicationCommand, ReplicationError, ReplicationOperation, ReplicationResult, ReplicationStats,
+    PROTOCOL_VERSION, ReplicaHello, ReplicationCommand, ReplicationError, ReplicationOperation,
+    ReplicationResult, ReplicationStats,
 };
+use crate::compression::{CompressionAlgorithm, LinkCodec, LinkCompressionStats};
 use crate::core::{
-    HashStore, KVStore, ListStore, QueueManager, SetStore, SortedSetStore, StreamManager,
+    BitmapStore, BloomFilterStore, HashStore, HyperLogLogStore, KVStore, ListStore, QueueManager,
+    SetStore, SortedSetStore, StreamManager,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -11,7 +14,7 @@ use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 use tracing::{debug, error, info, warn};
 
 /// Replica Node - Read-only node that receives operations from master
@@ -32,6 +35,9 @@ pub struct ReplicaNode {
     list_store: Option<Arc<ListStore>>,
     set_store: Option<Arc<SetStore>>,
     sorted_set_store: Option<Arc<SortedSetStore>>,
+    hyperloglog_store: Option<Arc<HyperLogLogStore>>,
+    bloom_store: Option<Arc<BloomFilterStore>>,
+    bitmap_store: Option<Arc<BitmapStore>>,
     queue_manager: Option<Arc<QueueManager>>,
 
     /// Current offset (last applied operation)
@@ -48,6 +54,19 @@ pub struct ReplicaNode {
 
     /// Replication stats
     stats: Arc<RwLock<ReplicationStats>>,
+
+    /// Bytes through compressed links to the master, across reconnects
+    compression_stats: LinkCompressionStats,
+
+    /// Master currently followed; starts as `config.master_address` and moves
+    /// on failover
+    master_address: parking_lot::RwLock<SocketAddr>,
+
+    /// Set once the replica is promoted or shut down
+    stopped: AtomicBool,
+
+    /// Wakes the replication loop when the master changes or the replica stops
+    interrupt: Notify,
 }
 
 impl ReplicaNode {
@@ -65,6 +84,9 @@ impl ReplicaNode {
             stores.stream_manager.is_some()
         );
 
+        let master_address = config.master_address.ok_or_else(|| {
+            ReplicationError::ConnectionFailed("replica requires master_address".to_string())
+        })?;
         let replica = Arc::new(Self {
             config,
             kv_store: stores.kv_store,
@@ -73,12 +95,19 @@ impl ReplicaNode {
             list_store: stores.list_store,
             set_store: stores.set_store,
             sorted_set_store: stores.sorted_set_store,
+            hyperloglog_store: stores.hyperloglog_store,
+            bloom_store: stores.bloom_store,
+            bitmap_store: stores.bitmap_store,
             queue_manager: stores.queue_manager,
             current_offset: Arc::new(AtomicU64::new(0)),
             master_offset: Arc::new(AtomicU64::new(0)),
             last_heartbeat: Arc::new(AtomicU64::new(0)),
             connected: Arc::new(AtomicBool::new(false)),
             stats: Arc::new(RwLock::new(ReplicationStats::default())),
+            compression_stats: LinkCompressionStats::default(),
+            master_address: parking_lot::RwLock::new(master_address),
+            stopped: AtomicBool::new(false),
+            interrupt: Notify::new(),
         });
 
         // Start replication loop in background task
@@ -95,28 +124,39 @@ impl ReplicaNode {
 
     /// Main replication loop - connect, sync, and receive updates
     async fn replication_loop(self: Arc<Self>) {
-        let master_addr = self
-            .config
-            .master_address
-            .expect("replica is only started with a configured master_address");
         let reconnect_delay = Duration::from_millis(self.config.reconnect_delay_ms);
 
         info!(
             "[REPLICA] Replication loop starting, master: {}, auto_reconnect: {}",
-            master_addr, self.config.auto_reconnect
+            self.master_address(),
+            self.config.auto_reconnect
         );
 
         loop {
+            let master_addr = self.master_address();
             info!("[REPLICA] Connecting to master at {}", master_addr);
 
-            match self.connect_and_sync(master_addr).await {
-                Ok(_) => {
-                    info!("[REPLICA] Replication connection closed normally");
-                }
-                Err(e) => {
-                    info!("[REPLICA] Replication error: {}", e);
-                    self.connected.store(false, Ordering::SeqCst);
+            let interrupted = tokio::select! {
+                result = self.connect_and_sync(master_addr) => {
+                    match result {
+                        Ok(_) => info!("[REPLICA] Replication connection closed normally"),
+                        Err(e @ ReplicationError::ProtocolMismatch(_)) => {
+                            error!("[REPLICA] Master refused: {}", e)
+                        }
+                        Err(e) => info!("[REPLICA] Replication error: {}", e),
+                    }
+                    false
                 }
+                _ = self.interrupt.notified() => true,
+            };
+            self.connected.store(false, Ordering::SeqCst);
+
+            if self.stopped.load(Ordering::SeqCst) {
+                break;
+            }
+            if interrupted {
+                // The master moved: follow it right away.
+                continue;
             }
 
             if !self.config.auto_reconnect {
@@ -125,7 +165,14 @@ impl ReplicaNode {
             }
 
             info!("[REPLICA] Reconnecting in {:?}", reconnect_delay);
-            tokio::time::sleep(reconnect_delay).await;
+            tokio::select! {
+                _ = tokio::time::sleep(reconnect_delay) => {}
+                _ = self.interrupt.notified() => {
+                    if self.stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                }
+            }
         }
 
         info!("[REPLICA] Replication loop ended");
@@ -143,9 +190,19 @@ impl ReplicaNode {
 
         info!("[REPLICA] TCP connected to master");
 
-        // Send handshake with current offset
+        // Send handshake with current offset, followed by our protocol
+        // version and capabilities
         let current_offset = self.current_offset.load(Ordering::SeqCst);
-        let handshake = bincode::serde::encode_to_vec(current_offset, bincode::config::legacy())?;
+        let hello = ReplicaHello {
+            compression: if self.config.compression.enabled {
+                vec![CompressionAlgorithm::Zstd]
+            } else {
+                Vec::new()
+            },
+            protocol_version: PROTOCOL_VERSION,
+        };
+        let handshake =
+            bincode::serde::encode_to_vec((current_offset, hello), bincode::config::legacy())?;
         info!("[REPLICA] Sending handshake, offset: {}", current_offset);
         stream.write_all(&handshake).await?;
         stream.flush().await?;
@@ -158,22 +215,65 @@ impl ReplicaNode {
 
         // Receive sync (full or partial)
         info!("[REPLICA] Calling receive_sync...");
-        self.receive_sync(&mut stream).await?;
+        let mut codec = None;
+        self.receive_sync(&mut stream, &mut codec).await?;
         info!("[REPLICA] receive_sync completed");
 
         // Receive ongoing replication commands
         info!("[REPLICA] Starting to receive commands...");
-        self.receive_commands(&mut stream).await?;
+        self.receive_commands(&mut stream, &mut codec).await?;
 
         Ok(())
     }
 
-    /// Receive initial sync from master
-    async fn receive_sync(&self, stream: &mut TcpStream) -> ReplicationResult<()> {
-        info!("Waiting to receive sync command from master...");
+    /// Receive the master's link settings, then the initial sync. A master
+    /// that answers with anything but a hello on our protocol version is
+    /// refused.
+    async fn receive_sync(
+        &self,
+        stream: &mut TcpStream,
+        codec: &mut Option<LinkCodec>,
+    ) -> ReplicationResult<()> {
+        info!("Waiting to receive link settings from master...");
+
+        let mismatch = |version: &str| {
+            ReplicationError::ProtocolMismatch(format!(
+                "master speaks replication protocol {version} and the replica \
+                 v{PROTOCOL_VERSION}; run the same Synap version on both"
+            ))
+        };
+        let hello = match self.read_command(stream, codec).await {
+            Ok(cmd) => cmd,
+            // Masters before version 2 send a hello this version cannot read
+            Err(ReplicationError::SerializationError(_)) => return Err(mismatch("v1")),
+            Err(e) => {
+                error!("Failed to read link settings: {}", e);
+                return Err(e);
+            }
+        };
+        match hello {
+            ReplicationCommand::Hello {
+                compression,
+                protocol_version: PROTOCOL_VERSION,
+            } => {
+                info!("Link settings from master: compression {:?}", compression);
+                if compression == CompressionAlgorithm::Zstd {
+                    *codec = Some(LinkCodec::new(self.config.compression.clone()));
+                }
+            }
+            ReplicationCommand::Hello {
+                protocol_version, ..
+            } => return Err(mismatch(&format!("v{protocol_version}"))),
+            ReplicationCommand::Refused { reason } => {
+                return Err(ReplicationError::ProtocolMismatch(reason));
+            }
+            // Masters before version 2 without link settings start syncing
+            // right away
+            _ => return Err(mismatch("v1")),
+        }
 
         // Read sync command with length prefix
-        let cmd = Self::read_command(stream).await.map_err(|e| {
+        let cmd = self.read_command(stream, codec).await.map_err(|e| {
             error!("Failed to read sync command: {}", e);
             e
         })?;
@@ -191,17 +291,13 @@ impl ReplicaNode {
                     snapshot_data.len()
                 );
 
-                // Apply snapshot with stream support
-                super::sync::apply_snapshot_with_streams(
-                    &self.kv_store,
-                    self.stream_manager.as_ref().map(|s| s.as_ref()),
-                    &snapshot_data,
-                )
-                .await
-                .map_err(|e| {
-                    error!("Failed to apply snapshot: {}", e);
-                    ReplicationError::SerializationError(e)
-                })?;
+                // Apply snapshot to every datatype store
+                super::sync::apply_snapshot_to_stores(self.store_refs(), &snapshot_data)
+                    .await
+                    .map_err(|e| {
+                        error!("Failed to apply snapshot: {}", e);
+                        ReplicationError::SerializationError(e)
+                    })?;
 
                 self.current_offset.store(offset, Ordering::SeqCst);
                 info!("Full sync completed, data restored at offset {}", offset);
@@ -231,8 +327,13 @@ impl ReplicaNode {
         Ok(())
     }
 
-    /// Read command with length prefix
-    async fn read_command(stream: &mut TcpStream) -> ReplicationResult<ReplicationCommand> {
+    /// Read command with length prefix, through the link codec when the link
+    /// is compressed
+    async fn read_command(
+        &self,
+        stream: &mut TcpStream,
+        codec: &mut Option<LinkCodec>,
+    ) -> ReplicationResult<ReplicationCommand> {
         // Read length prefix (4 bytes)
         let mut len_buf = [0u8; 4];
         stream.read_exact(&mut len_buf).await?;
@@ -241,6 +342,12 @@ impl ReplicaNode {
         // Read data
         let mut data = vec![0u8; len];
         stream.read_exact(&mut data).await?;
+        if let Some(codec) = codec {
+            let wire = data.len();
+            data = codec.decode(&data)?;
+            self.compression_stats.record(data.len(), wire);
+            crate::metrics::record_replication_compression("received", data.len(), wire);
+        }
 
         // Deserialize
         let (cmd, _) = bincode::serde::decode_from_slice(&data, bincode::config::legacy())?;
@@ -248,10 +355,14 @@ impl ReplicaNode {
     }
 
     /// Receive ongoing replication commands
-    async fn receive_commands(&self, stream: &mut TcpStream) -> ReplicationResult<()> {
+    async fn receive_commands(
+        &self,
+        stream: &mut TcpStream,
+        codec: &mut Option<LinkCodec>,
+    ) -> ReplicationResult<()> {
         loop {
             // Read command with length prefix
-            let cmd = match Self::read_command(stream).await {
+            let cmd = match self.read_command(stream, codec).await {
                 Ok(c) => c,
                 Err(ReplicationError::IOError(e))
                     if e.kind() == std::io::ErrorKind::UnexpectedEof =>
@@ -284,8 +395,27 @@ impl ReplicaNode {
     }
 
     /// Apply a single replication operation
+    /// Borrow the replica's stores for the snapshot/operation appliers
+    fn store_refs(&self) -> crate::persistence::apply::StoreRefs<'_> {
+        crate::persistence::apply::StoreRefs {
+            kv_store: &self.kv_store,
+            hash_store: self.hash_store.as_deref(),
+            list_store: self.list_store.as_deref(),
+            set_store: self.set_store.as_deref(),
+            sorted_set_store: self.sorted_set_store.as_deref(),
+            hyperloglog_store: self.hyperloglog_store.as_deref(),
+            bloom_store: self.bloom_store.as_deref(),
+            bitmap_store: self.bitmap_store.as_deref(),
+            queue_manager: self.queue_manager.as_deref(),
+            stream_manager: self.stream_manager.as_deref(),
+        }
+    }
+
     async fn apply_operation(&self, op: ReplicationOperation) -> ReplicationResult<()> {
-        debug!("Applying operation at offset {}", op.offset);
+        debug!(
+            request_id = op.request_id.as_deref().unwrap_or_default(),
+            "Applying operation at offset {}", op.offset
+        );
 
         // Deduplicate on replica join (#234): after a full sync at snapshot
         // offset X, the master re-streams operations from the replication log
@@ -310,19 +440,9 @@ impl ReplicaNode {
         // Apply via the shared applier so the replica converges to the master
         // for EVERY datatype, not just KV + stream (audit M-005 completion,
         // phase6j). Streams are applied on the replica (Some(stream_manager)).
-        if let Err(e) = crate::persistence::apply::apply_operation(
-            op.operation.clone(),
-            crate::persistence::apply::StoreRefs {
-                kv_store: &self.kv_store,
-                hash_store: self.hash_store.as_deref(),
-                list_store: self.list_store.as_deref(),
-                set_store: self.set_store.as_deref(),
-                sorted_set_store: self.sorted_set_store.as_deref(),
-                queue_manager: self.queue_manager.as_deref(),
-                stream_manager: self.stream_manager.as_deref(),
-            },
-        )
-        .await
+        if let Err(e) =
+            crate::persistence::apply::apply_operation(op.operation.clone(), self.store_refs())
+                .await
         {
             warn!(
                 "Failed to apply replicated operation at offset {}: {}",
@@ -361,6 +481,7 @@ impl ReplicaNode {
         stats.lag_operations = master_offset.saturating_sub(current_offset);
         stats.connected = self.connected.load(Ordering::SeqCst);
         stats.last_heartbeat = self.last_heartbeat.load(Ordering::SeqCst);
+        stats.compression_saved_bytes = self.compression_stats.saved_bytes();
 
         // Calculate lag in milliseconds
         let now = Self::current_timestamp();
@@ -374,6 +495,31 @@ impl ReplicaNode {
         stats
     }
 
+    /// Master this replica currently follows
+    pub fn master_address(&self) -> SocketAddr {
+        *self.master_address.read()
+    }
+
+    /// Follow a different master, dropping the current connection. The new
+    /// master sends a full or partial sync from our offset.
+    pub fn retarget(&self, master_address: SocketAddr) {
+        *self.master_address.write() = master_address;
+        info!("[REPLICA] Following new master at {}", master_address);
+        self.interrupt.notify_one();
+    }
+
+    /// Stop replicating, e.g. because this node is being promoted. Applied
+    /// data and the current offset are kept.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.interrupt.notify_one();
+    }
+
+    /// Whether [`stop`](Self::stop) was called
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
     /// Check if connected to master
     pub fn is_connected(&self) -> bool {
         self.connected.load(Ordering::SeqCst)
@@ -405,6 +551,7 @@ mod tests {
     use super::*;
     use crate::core::KVConfig;
     use crate::persistence::types::Operation;
+    use bytes::Bytes;
 
     #[tokio::test]
     async fn test_replica_initialization() {
@@ -439,9 +586,10 @@ mod tests {
             timestamp: 0,
             operation: Operation::KVSet {
                 key: "test_key".to_string(),
-                value: b"test_value".to_vec(),
+                value: Bytes::from_static(b"test_value"),
                 ttl: None,
             },
+            request_id: None,
         };
 
         replica.apply_operation(op).await.unwrap();
@@ -32,24 +32,42 @@ pub struct ReplicationLog {
 impl ReplicationLog {
     /// Create a new replication log
     pub fn new(max_size: usize) -> Self {
-        info!("Initializing replication log with max size: {}", max_size);
+        Self::starting_at(max_size, 0)
+    }
+
+    /// Create a replication log whose first operation gets `offset`.
+    ///
+    /// A replica promoted to master continues from the offset it had applied,
+    /// so the remaining replicas can resume instead of discarding new
+    /// operations as already seen.
+    pub fn starting_at(max_size: usize, offset: u64) -> Self {
+        info!(
+            "Initializing replication log with max size: {} (starting offset: {})",
+            max_size, offset
+        );
 
         Self {
             operations: Arc::new(RwLock::new(VecDeque::with_capacity(max_size))),
-            current_offset: Arc::new(AtomicU64::new(0)),
+            current_offset: Arc::new(AtomicU64::new(offset)),
             max_size,
-            oldest_offset: Arc::new(AtomicU64::new(0)),
+            oldest_offset: Arc::new(AtomicU64::new(offset)),
         }
     }
 
     /// Append operation to replication log
     pub fn append(&self, operation: Operation) -> u64 {
+        self.append_with_request_id(operation, None)
+    }
+
+    /// Append an operation tagged with the request id that produced it
+    pub fn append_with_request_id(&self, operation: Operation, request_id: Option<String>) -> u64 {
         let offset = self.current_offset.fetch_add(1, Ordering::SeqCst);
 
         let repl_op = ReplicationOperation {
             offset,
             timestamp: Self::current_timestamp(),
             operation,
+            request_id,
         };
 
         let mut ops = self.operations.write();
@@ -147,7 +165,7 @@ mod tests {
         for i in 0..10 {
             let op = Operation::KVSet {
                 key: format!("key_{}", i),
-                value: vec![i as u8],
+                value: vec![i as u8].into(),
                 ttl: None,
             };
             let offset = log.append(op);
@@ -166,7 +184,7 @@ mod tests {
         for i in 0..10 {
             let op = Operation::KVSet {
                 key: format!("key_{}", i),
-                value: vec![i as u8],
+                value: vec![i as u8].into(),
                 ttl: None,
             };
             log.append(op);
@@ -186,7 +204,7 @@ mod tests {
         for i in 0..20 {
             let op = Operation::KVSet {
                 key: format!("key_{}", i),
-                value: vec![i as u8],
+                value: vec![i as u8].into(),
                 ttl: None,
             };
             log.append(op);
@@ -207,7 +225,7 @@ mod tests {
         for i in 0..20 {
             let op = Operation::KVSet {
                 key: format!("key_{}", i),
-                value: vec![i as u8],
+                value: vec![i as u8].into(),
                 ttl: None,
             };
             log.append(op);
@@ -0,0 +1,673 @@
+/// Automatic failover - Sentinel-like coordinator
+///
+/// Every node of a replication group runs a [`FailoverCoordinator`] when
+/// `replication.failover.enabled` is set:
+/// - Replicas watch their master connection; once it has been down for
+///   `down_after_ms`, a replica asks its peers for the current topology and,
+///   if nobody has replaced the master yet, starts an election
+/// - Votes follow the Raft rules of [`VoteState`]: one vote per term, and a
+///   candidate whose replication offset is behind the voter's is refused. A
+///   voter that still reaches the master refuses as well
+/// - The winner (majority of the group, master included in the count) stops
+///   replicating, starts a master whose log continues from its offset, and
+///   announces itself; the other replicas retarget to it
+/// - The topology (epoch = election term) is served at
+///   `GET /replication/topology` so clients can find the current master
+use super::ReplicationHandle;
+use super::config::ReplicationConfig;
+use super::master::MasterNode;
+use super::replica::ReplicaNode;
+use super::types::{NodeRole, ReplicationError, ReplicationResult};
+use crate::auth::{AuditLogEntry, AuditLogManager, AuthEventType};
+use crate::cluster::VoteState;
+use crate::core::LockManager;
+use crate::persistence::{PersistenceLayer, StoreArcs};
+use parking_lot::{Mutex, RwLock};
+use rand::RngExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// The master as known by one node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MasterInfo {
+    /// `None` until the first failover when this node started as a replica
+    pub node_id: Option<String>,
+    /// HTTP URL clients should send writes to
+    pub url: Option<String>,
+    /// Address replicas connect to
+    pub replication_address: SocketAddr,
+}
+
+/// Replication topology served by `GET /replication/topology`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Topology {
+    /// Term of the election that produced the current master; 0 while the
+    /// configured master is still in charge
+    pub epoch: u64,
+    /// Id of the node answering
+    pub node_id: String,
+    /// Role of the node answering
+    pub role: NodeRole,
+    pub master: Option<MasterInfo>,
+}
+
+/// `POST /replication/failover/vote` body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteRequest {
+    pub term: u64,
+    pub candidate_id: String,
+    /// Replication offset the candidate has applied
+    pub offset: u64,
+}
+
+/// `POST /replication/failover/vote` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteResponse {
+    /// Voter's term after handling the request
+    pub term: u64,
+    pub granted: bool,
+}
+
+/// `POST /replication/failover/announce` body, sent by a newly promoted master
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Announcement {
+    pub epoch: u64,
+    pub master: MasterInfo,
+}
+
+struct State {
+    handle: ReplicationHandle,
+    epoch: u64,
+    master: Option<MasterInfo>,
+    /// Last time the replica was seen connected to its master
+    master_seen: Instant,
+}
+
+/// Watches the master and runs elections and promotions for this node
+pub struct FailoverCoordinator {
+    config: ReplicationConfig,
+    stores: StoreArcs,
+    persistence: Option<Arc<PersistenceLayer>>,
+    votes: Mutex<VoteState>,
+    state: RwLock<State>,
+    /// Serializes [`Self::handle_announce`], which awaits a demotion between
+    /// checking the epoch and installing the new master
+    announcing: tokio::sync::Mutex<()>,
+    http: reqwest::Client,
+    audit_log: OnceLock<Arc<AuditLogManager>>,
+    lock_manager: OnceLock<Arc<LockManager>>,
+}
+
+impl FailoverCoordinator {
+    /// Create a coordinator for the node currently running `handle`.
+    ///
+    /// `persistence` is the layer writes go through; a promoted node hands
+    /// its new master to it so writes reach the replicas.
+    pub fn new(
+        config: ReplicationConfig,
+        handle: ReplicationHandle,
+        stores: StoreArcs,
+        persistence: Option<Arc<PersistenceLayer>>,
+    ) -> ReplicationResult<Arc<Self>> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.failover.election_timeout_ms))
+            .build()
+            .map_err(|e| ReplicationError::ConnectionFailed(e.to_string()))?;
+
+        let master = match &handle {
+            ReplicationHandle::Master(_) => Self::own_master_info(&config),
+            ReplicationHandle::Replica(replica) => Some(MasterInfo {
+                node_id: None,
+                url: config.failover.master_url.clone(),
+                replication_address: replica.master_address(),
+            }),
+        };
+
+        Ok(Arc::new(Self {
+            config,
+            stores,
+            persistence,
+            votes: Mutex::new(VoteState::new()),
+            state: RwLock::new(State {
+                handle,
+                epoch: 0,
+                master,
+                master_seen: Instant::now(),
+            }),
+            announcing: tokio::sync::Mutex::new(()),
+            http,
+            audit_log: OnceLock::new(),
+            lock_manager: OnceLock::new(),
+        }))
+    }
+
+    /// Record promotions and demotions in `audit_log`
+    pub fn set_audit_log(&self, audit_log: Arc<AuditLogManager>) {
+        let _ = self.audit_log.set(audit_log);
+    }
+
+    /// Keep the fencing tokens of `lock_manager` on the current epoch, so a
+    /// promoted master's tokens outrank the old master's
+    pub fn set_lock_manager(&self, lock_manager: Arc<LockManager>) {
+        lock_manager.set_epoch(self.state.read().epoch);
+        let _ = self.lock_manager.set(lock_manager);
+    }
+
+    /// Move the fencing tokens to `epoch`
+    fn raise_lock_epoch(&self, epoch: u64) {
+        if let Some(lock_manager) = self.lock_manager.get() {
+            lock_manager.set_epoch(epoch);
+        }
+    }
+
+    /// Record a role change made by this node
+    async fn audit(&self, action: &str, epoch: u64) {
+        if let Some(audit_log) = self.audit_log.get() {
+            let mut entry =
+                AuditLogEntry::new(AuthEventType::AdminOperation, None, "local".into(), true);
+            entry.action = Some(action.to_string());
+            entry.resource = Some("replication".to_string());
+            entry
+                .metadata
+                .insert("epoch".to_string(), epoch.to_string());
+            audit_log.log(entry).await;
+        }
+    }
+
+    /// Start the background monitor
+    pub fn start(self: &Arc<Self>) {
+        let coordinator = Arc::clone(self);
+        tokio::spawn(async move {
+            coordinator.monitor_loop().await;
+        });
+    }
+
+    /// Replication role this node currently runs
+    pub fn handle(&self) -> ReplicationHandle {
+        self.state.read().handle.clone()
+    }
+
+    /// Topology as seen by this node
+    pub fn topology(&self) -> Topology {
+        let state = self.state.read();
+        Topology {
+            epoch: state.epoch,
+            node_id: self.config.failover.node_id.clone(),
+            role: match state.handle {
+                ReplicationHandle::Master(_) => NodeRole::Master,
+                ReplicationHandle::Replica(_) => NodeRole::Replica,
+            },
+            master: state.master.clone(),
+        }
+    }
+
+    /// Answer a peer's vote request
+    pub fn handle_vote(&self, request: &VoteRequest) -> VoteResponse {
+        let own_offset = {
+            let state = self.state.read();
+            match &state.handle {
+                ReplicationHandle::Replica(replica)
+                    if !replica.is_connected() && !self.master_reachable(&state) =>
+                {
+                    Some(replica.current_offset())
+                }
+                // A live master, or a replica that still reaches it, does not
+                // help replace it.
+                _ => None,
+            }
+        };
+
+        let mut votes = self.votes.lock();
+        let granted = match own_offset {
+            Some(offset) => {
+                votes.request_vote(&request.candidate_id, request.term, request.offset, offset)
+            }
+            None => {
+                votes.observe_term(request.term);
+                false
+            }
+        };
+        debug!(
+            candidate = %request.candidate_id,
+            term = request.term,
+            granted,
+            "Failover vote requested"
+        );
+
+        VoteResponse {
+            term: votes.current_term(),
+            granted,
+        }
+    }
+
+    /// Follow the master announced by a peer. Returns whether the topology
+    /// changed.
+    pub async fn handle_announce(&self, announcement: Announcement) -> ReplicationResult<bool> {
+        // Concurrent announcements are applied one at a time, so an older one
+        // can never overwrite a newer one that got through the check first.
+        let _announcing = self.announcing.lock().await;
+        if announcement.epoch <= self.state.read().epoch {
+            return Ok(false);
+        }
+        self.votes.lock().observe_term(announcement.epoch);
+
+        let is_self = announcement.master.node_id.as_deref() == Some(&self.config.failover.node_id);
+        if !is_self {
+            match self.handle() {
+                ReplicationHandle::Replica(replica) => {
+                    replica.retarget(announcement.master.replication_address);
+                }
+                ReplicationHandle::Master(_) => {
+                    let replica = self.demote(announcement.master.replication_address).await?;
+                    self.state.write().handle = ReplicationHandle::Replica(replica);
+                }
+            }
+        }
+
+        info!(
+            epoch = announcement.epoch,
+            master = ?announcement.master.node_id,
+            "Replication topology changed"
+        );
+        self.raise_lock_epoch(announcement.epoch);
+        let mut state = self.state.write();
+        state.epoch = announcement.epoch;
+        state.master = Some(announcement.master);
+        state.master_seen = Instant::now();
+        Ok(true)
+    }
+
+    async fn monitor_loop(self: Arc<Self>) {
+        let down_after = self.down_after();
+        let tick = (down_after / 4).clamp(Duration::from_millis(50), Duration::from_secs(1));
+
+        info!(
+            node_id = %self.config.failover.node_id,
+            peers = self.config.failover.peers.len(),
+            "Failover coordinator started"
+        );
+        // A node that restarts (e.g. a failed master coming back) first
+        // catches up with any failover that happened meanwhile.
+        self.discover().await;
+
+        loop {
+            tokio::time::sleep(tick).await;
+
+            {
+                let mut state = self.state.write();
+                match &state.handle {
+                    ReplicationHandle::Master(_) => continue,
+                    ReplicationHandle::Replica(replica) if replica.is_connected() => {
+                        state.master_seen = Instant::now();
+                        continue;
+                    }
+                    ReplicationHandle::Replica(_) if self.master_reachable(&state) => continue,
+                    ReplicationHandle::Replica(_) => {}
+                }
+            }
+
+            if self.discover().await {
+                continue;
+            }
+            if !self.run_election().await {
+                let jitter = rand::rng().random_range(0..=self.config.failover.election_timeout_ms);
+                tokio::time::sleep(Duration::from_millis(jitter)).await;
+            }
+        }
+    }
+
+    /// Ask peers for their topology and follow the newest one. Returns
+    /// whether it was newer than ours.
+    async fn discover(&self) -> bool {
+        let requests = self.config.failover.peers.iter().map(|peer| {
+            let request = self.http.get(Self::peer_url(peer, "replication/topology"));
+            self.send::<Topology>(request)
+        });
+        let newest = futures_util::future::join_all(requests)
+            .await
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t.master.map(|master| (t.epoch, master)))
+            .max_by_key(|(epoch, _)| *epoch);
+
+        let Some((epoch, master)) = newest else {
+            return false;
+        };
+        match self.handle_announce(Announcement { epoch, master }).await {
+            Ok(changed) => changed,
+            Err(e) => {
+                warn!("Failed to follow discovered master: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Run one election round, promoting this node if it wins
+    async fn run_election(&self) -> bool {
+        let ReplicationHandle::Replica(replica) = self.handle() else {
+            return false;
+        };
+        let node_id = &self.config.failover.node_id;
+        let term = self.votes.lock().start_election(node_id);
+        let request = VoteRequest {
+            term,
+            candidate_id: node_id.clone(),
+            offset: replica.current_offset(),
+        };
+        info!(
+            term,
+            offset = request.offset,
+            "Master down, starting failover election"
+        );
+
+        let requests = self.config.failover.peers.iter().map(|peer| {
+            let request = self
+                .http
+                .post(Self::peer_url(peer, "replication/failover/vote"))
+                .json(&request);
+            self.send::<VoteResponse>(request)
+        });
+        let responses = futures_util::future::join_all(requests).await;
+
+        // Our own vote, plus every grant
+        let mut granted = 1;
+        for response in responses.into_iter().flatten() {
+            if response.term > term {
+                self.votes.lock().observe_term(response.term);
+                return false;
+            }
+            if response.granted {
+                granted += 1;
+            }
+        }
+
+        let group_size = self.config.failover.peers.len() + 1;
+        let quorum = group_size / 2 + 1;
+        if granted < quorum {
+            debug!(term, granted, quorum, "Failover election lost");
+            return false;
+        }
+        // The master came back, or another candidate won and announced
+        // itself, while the votes were in flight.
+        if replica.is_connected() || self.votes.lock().current_term() != term {
+            return false;
+        }
+
+        match self.promote(replica, term).await {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Failover promotion failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Replace the replica with a master and tell the peers
+    async fn promote(&self, replica: Arc<ReplicaNode>, epoch: u64) -> ReplicationResult<()> {
+        replica.stop();
+        let offset = replica.current_offset();
+
+        let config = ReplicationConfig {
+            role: NodeRole::Master,
+            master_address: None,
+            ..self.config.clone()
+        };
+        let master =
+            Arc::new(MasterNode::with_start_offset(config, self.stores.clone(), offset).await?);
+        if let Some(persistence) = &self.persistence {
+            persistence.set_replication_master(Some(master.clone()));
+        }
+
+        let info = Self::own_master_info(&self.config)
+            .ok_or_else(|| ReplicationError::ConnectionFailed("no announce address".into()))?;
+        // Before this node grants its first lock as master
+        self.raise_lock_epoch(epoch);
+        {
+            let mut state = self.state.write();
+            state.handle = ReplicationHandle::Master(master);
+            state.epoch = epoch;
+            state.master = Some(info.clone());
+        }
+        info!(epoch, offset, "Promoted to replication master");
+        self.audit("failover.promote", epoch).await;
+
+        let announcement = Announcement {
+            epoch,
+            master: info,
+        };
+        let requests = self.config.failover.peers.iter().map(|peer| {
+            let request = self
+                .http
+                .post(Self::peer_url(peer, "replication/failover/announce"))
+                .json(&announcement);
+            self.send::<serde_json::Value>(request)
+        });
+        futures_util::future::join_all(requests).await;
+        Ok(())
+    }
+
+    /// Turn this (former) master into a replica of `master_address`. The old
+    /// replica listener stays bound until restart but no longer receives
+    /// operations.
+    async fn demote(&self, master_address: SocketAddr) -> ReplicationResult<Arc<ReplicaNode>> {
+        warn!(
+            "Another node was promoted, demoting to replica of {}",
+            master_address
+        );
+        if let Some(persistence) = &self.persistence {
+            persistence.set_replication_master(None);
+        }
+
+        let config = ReplicationConfig {
+            role: NodeRole::Replica,
+            master_address: Some(master_address),
+            ..self.config.clone()
+        };
+        ReplicaNode::new(config, self.stores.clone()).await
+    }
+
+    fn master_reachable(&self, state: &State) -> bool {
+        state.master_seen.elapsed() < self.down_after()
+    }
+
+    fn down_after(&self) -> Duration {
+        Duration::from_millis(self.config.failover.down_after_ms)
+    }
+
+    fn own_master_info(config: &ReplicationConfig) -> Option<MasterInfo> {
+        let replication_address = config
+            .failover
+            .announce_address
+            .or(config.replica_listen_address)?;
+        Some(MasterInfo {
+            node_id: Some(config.failover.node_id.clone()),
+            url: config.failover.advertise_url.clone(),
+            replication_address,
+        })
+    }
+
+    fn peer_url(peer: &str, path: &str) -> String {
+        format!("{}/{}", peer.trim_end_matches('/'), path)
+    }
+
+    /// Send a peer request; unreachable peers and errors count as no answer.
+    async fn send<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Option<T> {
+        let request = match &self.config.failover.auth_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response.json().await.ok(),
+            Err(e) => {
+                debug!("Failover peer request failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{KVConfig, KVStore};
+
+    fn dead_address() -> SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    }
+
+    async fn replica_coordinator(down_after_ms: u64) -> Arc<FailoverCoordinator> {
+        let mut config = ReplicationConfig {
+            enabled: true,
+            role: NodeRole::Replica,
+            master_address: Some(dead_address()),
+            replica_listen_address: Some(dead_address()),
+            reconnect_delay_ms: 50,
+            ..Default::default()
+        };
+        config.failover.enabled = true;
+        config.failover.node_id = "replica-1".to_string();
+        config.failover.peers = vec!["http://127.0.0.1:1".to_string()];
+        config.failover.down_after_ms = down_after_ms;
+
+        let stores = StoreArcs::kv_only(Arc::new(KVStore::new(KVConfig::default())));
+        let replica = ReplicaNode::new(config.clone(), stores.clone())
+            .await
+            .unwrap();
+        FailoverCoordinator::new(config, ReplicationHandle::Replica(replica), stores, None).unwrap()
+    }
+
+    fn vote(candidate: &str, term: u64, offset: u64) -> VoteRequest {
+        VoteRequest {
+            term,
+            candidate_id: candidate.to_string(),
+            offset,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vote_refused_while_master_reachable() {
+        let coordinator = replica_coordinator(60_000).await;
+
+        let response = coordinator.handle_vote(&vote("replica-2", 1, 100));
+        assert!(!response.granted);
+        assert_eq!(response.term, 1);
+    }
+
+    #[tokio::test]
+    async fn test_vote_granted_once_per_term_to_up_to_date_candidate() {
+        let coordinator = replica_coordinator(0).await;
+
+        // Our offset is 0, so any candidate is up to date; only one per term.
+        assert!(coordinator.handle_vote(&vote("replica-2", 1, 0)).granted);
+        assert!(!coordinator.handle_vote(&vote("replica-3", 1, 0)).granted);
+        assert!(coordinator.handle_vote(&vote("replica-3", 2, 5)).granted);
+    }
+
+    #[tokio::test]
+    async fn test_announce_retargets_replica() {
+        let coordinator = replica_coordinator(60_000).await;
+        let new_master = MasterInfo {
+            node_id: Some("replica-2".to_string()),
+            url: Some("http://replica-2:15500".to_string()),
+            replication_address: "127.0.0.1:25501".parse().unwrap(),
+        };
+
+        let changed = coordinator
+            .handle_announce(Announcement {
+                epoch: 3,
+                master: new_master.clone(),
+            })
+            .await
+            .unwrap();
+        assert!(changed);
+
+        let topology = coordinator.topology();
+        assert_eq!(topology.epoch, 3);
+        assert_eq!(topology.role, NodeRole::Replica);
+        assert_eq!(topology.master.as_ref(), Some(&new_master));
+        let ReplicationHandle::Replica(replica) = coordinator.handle() else {
+            panic!("still a replica");
+        };
+        assert_eq!(replica.master_address(), new_master.replication_address);
+
+        // Stale announcements are ignored
+        let stale = Announcement {
+            epoch: 2,
+            master: MasterInfo {
+                node_id: Some("replica-3".to_string()),
+                ..new_master
+            },
+        };
+        assert!(!coordinator.handle_announce(stale).await.unwrap());
+        assert_eq!(coordinator.topology().epoch, 3);
+    }
+
+    #[tokio::test]
+    async fn test_promote_continues_replica_offset() {
+        let coordinator = replica_coordinator(0).await;
+        let ReplicationHandle::Replica(replica) = coordinator.handle() else {
+            panic!("expected replica");
+        };
+
+        coordinator.promote(replica.clone(), 4).await.unwrap();
+
+        assert!(replica.is_stopped());
+        let topology = coordinator.topology();
+        assert_eq!(topology.role, NodeRole::Master);
+        assert_eq!(topology.epoch, 4);
+        assert_eq!(
+            topology.master.and_then(|m| m.node_id).as_deref(),
+            Some("replica-1")
+        );
+        let ReplicationHandle::Master(master) = coordinator.handle() else {
+            panic!("expected master");
+        };
+        assert_eq!(master.stats().master_offset, replica.current_offset());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_announces_keep_newest_epoch() {
+        let coordinator = replica_coordinator(0).await;
+        let ReplicationHandle::Replica(replica) = coordinator.handle() else {
+            panic!("expected replica");
+        };
+        coordinator.promote(replica, 1).await.unwrap();
+
+        let master = |node: &str, port: u16| MasterInfo {
+            node_id: Some(node.to_string()),
+            url: None,
+            replication_address: SocketAddr::from(([127, 0, 0, 1], port)),
+        };
+        // Both announcements demote the master, which awaits; the older one
+        // finishing last must not win.
+        let (newer, older) = tokio::join!(
+            coordinator.handle_announce(Announcement {
+                epoch: 3,
+                master: master("replica-3", 25503),
+            }),
+            coordinator.handle_announce(Announcement {
+                epoch: 2,
+                master: master("replica-2", 25502),
+            }),
+        );
+        assert!(newer.unwrap());
+        assert!(!older.unwrap());
+
+        let topology = coordinator.topology();
+        assert_eq!(topology.epoch, 3);
+        assert_eq!(topology.role, NodeRole::Replica);
+        assert_eq!(topology.master, Some(master("replica-3", 25503)));
+        let ReplicationHandle::Replica(replica) = coordinator.handle() else {
+            panic!("expected replica");
+        };
+        assert_eq!(
+            replica.master_address(),
+            master("replica-3", 25503).replication_address
+        );
+    }
+}
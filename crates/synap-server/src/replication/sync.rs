@@ -5,10 +5,14 @@
 //! - Incremental sync
 //! - Checksum verification
 
-use crate::core::{KVStore, StreamManager};
+use crate::core::{
+    BitmapStore, BloomFilterStore, HashStore, HyperLogLogStore, KVStore, ListStore, SetStore,
+    SortedSetStore, StreamManager,
+};
+use crate::persistence::apply::{StoreRefs, apply_operation};
 use crate::persistence::types::Operation;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Snapshot metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,53 +25,48 @@ pub struct SnapshotMetadata {
     pub checksum: u32,
 }
 
-/// Create a snapshot of KV store and streams for full sync
+/// Create a snapshot of the KV store for full sync
 pub async fn create_snapshot(kv_store: &KVStore, offset: u64) -> Result<Vec<u8>, String> {
-    create_snapshot_with_streams(kv_store, None, offset).await
+    create_snapshot_from_stores(StoreRefs::kv_only(kv_store), offset).await
 }
 
-/// Create a snapshot with optional stream manager
+/// Create a snapshot of the KV store and streams for full sync
 pub async fn create_snapshot_with_streams(
     kv_store: &KVStore,
     stream_manager: Option<&StreamManager>,
     offset: u64,
 ) -> Result<Vec<u8>, String> {
-    info!("Creating snapshot at offset {}", offset);
+    create_snapshot_from_stores(
+        StoreRefs {
+            stream_manager,
+            ..StoreRefs::kv_only(kv_store)
+        },
+        offset,
+    )
+    .await
+}
 
-    // Get all keys
-    let keys = kv_store.keys().await.map_err(|e| e.to_string())?;
-    let total_keys = keys.len();
-
-    // Serialize all key-value pairs
-    let mut operations = Vec::new();
-    for key in keys {
-        if let Ok(Some(value)) = kv_store.get(&key).await {
-            // Get TTL for the key (returns remaining seconds)
-            let ttl = kv_store.ttl(&key).await.ok().flatten();
-            operations.push(Operation::KVSet {
-                key: key.clone(),
-                value,
-                ttl,
-            });
-        }
-    }
+/// Create a snapshot of every store present in `stores` for full sync.
+///
+/// The snapshot is a list of replayable [`Operation`]s: KV keys, hashes,
+/// lists, sets, sorted sets, HyperLogLogs and Bloom filters with their TTLs,
+/// and stream events. Collections are written whole, so applying it replaces
+/// what a replica held under the same key. Queues are not part of it; their
+/// messages reach replicas through incremental replication.
+pub async fn create_snapshot_from_stores(
+    stores: StoreRefs<'_>,
+    offset: u64,
+) -> Result<Vec<u8>, String> {
+    info!("Creating snapshot at offset {}", offset);
 
-    // Add stream operations
-    let mut total_streams = 0;
-    if let Some(sm) = stream_manager {
-        let stream_data = sm.get_all_events().await;
-        total_streams = stream_data.len();
-
-        for (room, events) in stream_data {
-            for event in events {
-                operations.push(Operation::StreamPublish {
-                    room: room.clone(),
-                    event_type: event.event,
-                    payload: event.data,
-                });
-            }
-        }
-    }
+    let (operations, header) = crate::persistence::dump::store_operations(StoreRefs {
+        queue_manager: None,
+        ..stores
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let total_keys = header.keys;
+    let total_streams = header.streams;
 
     // Serialize operations
     let data = bincode::serde::encode_to_vec(&operations, bincode::config::legacy())
@@ -104,7 +103,7 @@ pub async fn create_snapshot_with_streams(
 
 /// Apply snapshot to KV store (without streams)
 pub async fn apply_snapshot(kv_store: &KVStore, snapshot: &[u8]) -> Result<u64, String> {
-    apply_snapshot_with_streams(kv_store, None, snapshot).await
+    apply_snapshot_to_stores(StoreRefs::kv_only(kv_store), snapshot).await
 }
 
 /// Apply snapshot with optional stream manager
@@ -112,6 +111,24 @@ pub async fn apply_snapshot_with_streams(
     kv_store: &KVStore,
     stream_manager: Option<&StreamManager>,
     snapshot: &[u8],
+) -> Result<u64, String> {
+    apply_snapshot_to_stores(
+        StoreRefs {
+            stream_manager,
+            ..StoreRefs::kv_only(kv_store)
+        },
+        snapshot,
+    )
+    .await
+}
+
+/// Apply a full-sync snapshot to `stores`, returning its offset. The stores
+/// are emptied first, so keys the master deleted do not survive the sync.
+/// Operations for a store that is absent are skipped; operations that fail
+/// to apply are skipped too, and counted in a warning.
+pub async fn apply_snapshot_to_stores(
+    stores: StoreRefs<'_>,
+    snapshot: &[u8],
 ) -> Result<u64, String> {
     // Deserialize metadata
     let (metadata, metadata_size): (SnapshotMetadata, usize) =
@@ -133,6 +150,9 @@ pub async fn apply_snapshot_with_streams(
         bincode::serde::decode_from_slice(data, bincode::config::legacy())
             .map_err(|e| e.to_string())?;
 
+    let flushed = flush_stores(stores).await;
+    debug!("Flushed {} keys and rooms before the full sync", flushed);
+
     info!(
         "Applying snapshot: {} operations ({} keys, {} streams), offset: {}",
         operations.len(),
@@ -142,34 +162,61 @@ pub async fn apply_snapshot_with_streams(
     );
 
     // Apply operations
+    let mut failed = 0usize;
+    let mut first_error = None;
     for op in operations {
-        match op {
-            Operation::KVSet { key, value, ttl } => {
-                let _ = kv_store.set(key, value, ttl).await; // S-12: move owned key
-            }
-            Operation::StreamPublish {
-                room,
-                event_type,
-                payload,
-            } => {
-                if let Some(sm) = stream_manager {
-                    // Idempotent room creation (synap#165).
-                    let _ = sm.get_or_create_room(&room).await;
-                    let _ = sm.publish(&room, &event_type, payload).await;
-                } else {
-                    debug!("Skipping stream operation (no stream manager)");
-                }
-            }
-            _ => {
-                debug!("Skipping non-SET/StreamPublish operation in snapshot");
-            }
+        let name = op.name();
+        if let Err(e) = apply_operation(op, stores).await {
+            debug!("Snapshot operation {} failed to apply: {}", name, e);
+            failed += 1;
+            first_error.get_or_insert_with(|| format!("{}: {}", name, e));
         }
     }
 
-    info!("Snapshot applied successfully");
+    if let Some(first_error) = first_error {
+        warn!(
+            "Snapshot applied with {} failed operation(s), offset: {}; first failure: {}",
+            failed, metadata.offset, first_error
+        );
+    } else {
+        info!("Snapshot applied successfully");
+    }
     Ok(metadata.offset)
 }
 
+/// Empty every store a full sync rewrites, returning how many keys and
+/// stream rooms were dropped. Queues are not in the snapshot and stay.
+async fn flush_stores(stores: StoreRefs<'_>) -> usize {
+    let StoreRefs {
+        kv_store,
+        hash_store,
+        list_store,
+        set_store,
+        sorted_set_store,
+        hyperloglog_store,
+        bloom_store,
+        bitmap_store,
+        queue_manager: _,
+        stream_manager,
+    } = stores;
+    let mut flushed = kv_store.flush();
+    flushed += hash_store.map_or(0, HashStore::flush);
+    flushed += list_store.map_or(0, ListStore::flush);
+    flushed += set_store.map_or(0, SetStore::flush);
+    flushed += sorted_set_store.map_or(0, SortedSetStore::flush);
+    flushed += hyperloglog_store.map_or(0, HyperLogLogStore::flush);
+    flushed += bloom_store.map_or(0, BloomFilterStore::flush);
+    flushed += bitmap_store.map_or(0, BitmapStore::flush);
+    if let Some(sm) = stream_manager {
+        for room in sm.list_rooms().await {
+            if sm.delete_room(&room).await.is_ok() {
+                flushed += 1;
+            }
+        }
+    }
+    flushed
+}
+
 fn current_timestamp() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -273,4 +320,113 @@ mod tests {
             remaining
         );
     }
+
+    #[tokio::test]
+    async fn test_snapshot_includes_collections() {
+        use crate::core::{HashStore, ListStore, SetStore, SortedSetStore, ZAddOptions};
+
+        let kv1 = KVStore::new(KVConfig::default());
+        let hash1 = HashStore::new();
+        let list1 = ListStore::new();
+        let set1 = SetStore::new();
+        let zset1 = SortedSetStore::new();
+        hash1.restore("h", [("f".to_string(), b"v".to_vec())].into(), Some(600));
+        list1
+            .rpush("l", vec![b"a".to_vec(), b"b".to_vec()], false)
+            .unwrap();
+        set1.sadd("s", vec![b"m".to_vec()]).unwrap();
+        zset1.zadd("z", b"m".to_vec(), 2.5, &ZAddOptions::default());
+
+        let snapshot = create_snapshot_from_stores(
+            StoreRefs {
+                hash_store: Some(&hash1),
+                list_store: Some(&list1),
+                set_store: Some(&set1),
+                sorted_set_store: Some(&zset1),
+                ..StoreRefs::kv_only(&kv1)
+            },
+            7,
+        )
+        .await
+        .unwrap();
+
+        let kv2 = KVStore::new(KVConfig::default());
+        let hash2 = HashStore::new();
+        let list2 = ListStore::new();
+        let set2 = SetStore::new();
+        let zset2 = SortedSetStore::new();
+        let stores = StoreRefs {
+            hash_store: Some(&hash2),
+            list_store: Some(&list2),
+            set_store: Some(&set2),
+            sorted_set_store: Some(&zset2),
+            ..StoreRefs::kv_only(&kv2)
+        };
+        // The replica still holds values the master no longer has
+        hash2.hset("h", "stale", b"x".to_vec()).unwrap();
+        set2.sadd("s", vec![b"stale".to_vec()]).unwrap();
+        // Applying twice (a resync) must not duplicate list elements
+        apply_snapshot_to_stores(stores, &snapshot).await.unwrap();
+        let offset = apply_snapshot_to_stores(stores, &snapshot).await.unwrap();
+
+        assert_eq!(offset, 7);
+        assert_eq!(
+            hash2.hgetall("h").unwrap(),
+            [("f".to_string(), b"v".to_vec())].into()
+        );
+        assert!(hash2.ttl("h").is_some_and(|ttl| ttl > 590));
+        assert_eq!(
+            list2.lrange("l", 0, -1).unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+        assert_eq!(set2.smembers("s").unwrap(), vec![b"m".to_vec()]);
+        assert_eq!(zset2.zscore("z", b"m"), Some(2.5));
+    }
+
+    #[tokio::test]
+    async fn test_full_sync_drops_keys_deleted_on_master() {
+        use crate::core::{HashStore, StreamConfig};
+
+        let kv1 = KVStore::new(KVConfig::default());
+        let hash1 = HashStore::new();
+        let streams1 = StreamManager::new(StreamConfig::default());
+        kv1.set("kept", b"v".to_vec(), None).await.unwrap();
+        let master = StoreRefs {
+            hash_store: Some(&hash1),
+            stream_manager: Some(&streams1),
+            ..StoreRefs::kv_only(&kv1)
+        };
+
+        // The replica synced while the master still had these keys
+        kv1.set("deleted", b"v".to_vec(), None).await.unwrap();
+        hash1.hset("h", "f", b"v".to_vec()).unwrap();
+        streams1.create_room("chat").await.unwrap();
+        streams1
+            .publish("chat", "message", b"hi".to_vec())
+            .await
+            .unwrap();
+        let kv2 = KVStore::new(KVConfig::default());
+        let hash2 = HashStore::new();
+        let streams2 = StreamManager::new(StreamConfig::default());
+        let replica = StoreRefs {
+            hash_store: Some(&hash2),
+            stream_manager: Some(&streams2),
+            ..StoreRefs::kv_only(&kv2)
+        };
+        let snapshot = create_snapshot_from_stores(master, 1).await.unwrap();
+        apply_snapshot_to_stores(replica, &snapshot).await.unwrap();
+        assert!(kv2.exists("deleted").await.unwrap());
+
+        // Then the master deleted them and the replica had to resync
+        kv1.delete("deleted").await.unwrap();
+        hash1.hdel("h", &["f".to_string()]).unwrap();
+        let snapshot = create_snapshot_from_stores(master, 5).await.unwrap();
+        apply_snapshot_to_stores(replica, &snapshot).await.unwrap();
+
+        assert!(!kv2.exists("deleted").await.unwrap());
+        assert_eq!(kv2.get("kept").await.unwrap(), Some(b"v".to_vec()));
+        assert!(hash2.dump().is_empty());
+        // Stream events are replaced, not published a second time
+        assert_eq!(streams2.get_all_events().await["chat"].len(), 1);
+    }
 }
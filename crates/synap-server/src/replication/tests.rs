@@ -1,5 +1,6 @@
 use super::*;
 use crate::core::{KVConfig, KVStore};
+use bytes::Bytes;
 use std::sync::Arc;
 
 #[tokio::test]
@@ -8,7 +9,7 @@ async fn test_replication_log_basic() {
 
     let op = crate::persistence::types::Operation::KVSet {
         key: "test".to_string(),
-        value: b"value".to_vec(),
+        value: Bytes::from_static(b"value"),
         ttl: None,
     };
 
@@ -26,9 +27,12 @@ async fn test_master_replica_sync() {
     master_config.replica_listen_address = Some("127.0.0.1:0".parse().unwrap());
 
     let master_kv = Arc::new(KVStore::new(KVConfig::default()));
-    let master = MasterNode::new(master_config.clone(), Arc::clone(&master_kv), None)
-        .await
-        .unwrap();
+    let master = MasterNode::new(
+        master_config.clone(),
+        crate::persistence::StoreArcs::kv_only(Arc::clone(&master_kv)),
+    )
+    .await
+    .unwrap();
 
     // Write some data to master
     master_kv
@@ -43,13 +47,13 @@ async fn test_master_replica_sync() {
     // Replicate operations
     master.replicate(crate::persistence::types::Operation::KVSet {
         key: "key1".to_string(),
-        value: b"value1".to_vec(),
+        value: Bytes::from_static(b"value1"),
         ttl: None,
     });
 
     master.replicate(crate::persistence::types::Operation::KVSet {
         key: "key2".to_string(),
-        value: b"value2".to_vec(),
+        value: Bytes::from_static(b"value2"),
         ttl: None,
     });
 
@@ -109,7 +113,7 @@ async fn test_replication_lag_calculation() {
     for i in 0..100 {
         log.append(crate::persistence::types::Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![i as u8],
+            value: vec![i as u8].into(),
             ttl: None,
         });
     }
@@ -127,7 +131,7 @@ async fn test_partial_resync() {
     for i in 0..50 {
         log.append(crate::persistence::types::Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![i as u8],
+            value: vec![i as u8].into(),
             ttl: None,
         });
     }
@@ -147,7 +151,7 @@ async fn test_full_sync_required() {
     for i in 0..50 {
         log.append(crate::persistence::types::Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![i as u8],
+            value: vec![i as u8].into(),
             ttl: None,
         });
     }
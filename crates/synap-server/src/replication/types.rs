@@ -1,7 +1,14 @@
+use crate::compression::CompressionAlgorithm;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use thiserror::Error;
 
+/// Version of the replication wire format, exchanged in the handshake.
+/// Version 2 added `request_id` to [`ReplicationOperation`]; a master and a
+/// replica on different versions refuse each other rather than misread
+/// frames.
+pub const PROTOCOL_VERSION: u32 = 2;
+
 /// Node role in replication topology
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -35,6 +42,28 @@ pub enum ReplicationCommand {
 
     /// Acknowledge - replica confirms receipt
     Ack { replica_id: String, offset: u64 },
+
+    /// Link settings the master picked from the replica's [`ReplicaHello`].
+    /// Sent uncompressed before the sync.
+    Hello {
+        compression: CompressionAlgorithm,
+        /// The master's [`PROTOCOL_VERSION`]
+        protocol_version: u32,
+    },
+
+    /// The master will not serve the replica, e.g. because it speaks another
+    /// protocol version. The master closes the connection after it.
+    Refused { reason: String },
+}
+
+/// Protocol version and capabilities a replica announces after its offset
+/// in the handshake. A handshake without one comes from a version 1 replica.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicaHello {
+    /// Link compression algorithms the replica accepts
+    pub compression: Vec<CompressionAlgorithm>,
+    /// The replica's [`PROTOCOL_VERSION`]
+    pub protocol_version: u32,
 }
 
 /// Operation to be replicated
@@ -43,6 +72,10 @@ pub struct ReplicationOperation {
     pub offset: u64,
     pub timestamp: u64,
     pub operation: crate::persistence::types::Operation,
+    /// `X-Request-Id` of the master-side request that produced the operation,
+    /// so a write can be traced from the master's logs to every replica's.
+    #[serde(default)]
+    pub request_id: Option<String>,
 }
 
 /// Replication statistics
@@ -60,6 +93,9 @@ pub struct ReplicationStats {
     pub total_replicated: u64,
     /// Total bytes replicated
     pub total_bytes: u64,
+    /// Bytes saved on the wire by link compression
+    #[serde(default)]
+    pub compression_saved_bytes: u64,
     /// Last heartbeat timestamp
     pub last_heartbeat: u64,
     /// Connection status
@@ -76,6 +112,12 @@ pub struct ReplicaInfo {
     pub connected_at: u64,
     pub last_sync: u64,
     pub lag_ms: u64,
+    /// Whether the link to this replica is compressed
+    #[serde(default)]
+    pub compressed: bool,
+    /// Bytes saved on the wire by compressing this link
+    #[serde(default)]
+    pub compression_saved_bytes: u64,
 }
 
 /// Replication error types
@@ -107,6 +149,9 @@ pub enum ReplicationError {
 
     #[error("Invalid offset: expected {expected}, got {actual}")]
     InvalidOffset { expected: u64, actual: u64 },
+
+    #[error("Replication protocol mismatch: {0}")]
+    ProtocolMismatch(String),
 }
 
 impl From<serde_json::Error> for ReplicationError {
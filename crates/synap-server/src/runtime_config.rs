@@ -0,0 +1,422 @@
+//! Runtime configuration (`config.get` / `config.set`).
+//!
+//! A fixed set of tunables, named by their path in the config file, can be
+//! read and changed while the server runs:
+//!
+//! ```yaml
+//! slowlog.threshold_ms: 5
+//! kv_store.max_memory_mb: 8192
+//! ```
+//!
+//! A change takes effect at once and is written to the overrides file
+//! (`runtime_config.overrides_path`), which startup applies over the config
+//! file and environment, so it survives a restart. Deleting the file goes
+//! back to the config file's values.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+
+use crate::config::ServerConfig;
+use crate::server::handlers::AppState;
+use crate::server::rate_limit::RateLimiter;
+
+/// A tunable parameter and its accepted range
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub min: u64,
+    pub max: u64,
+    pub description: &'static str,
+}
+
+/// Every parameter `config.set` accepts
+pub const PARAMETERS: &[Parameter] = &[
+    Parameter {
+        name: "kv_store.max_memory_mb",
+        min: 0,
+        max: u32::MAX as u64,
+        description: "Memory cap across all data types; 0 = unlimited",
+    },
+    Parameter {
+        name: "slowlog.threshold_ms",
+        min: 0,
+        max: u32::MAX as u64,
+        description: "Log commands slower than this",
+    },
+    Parameter {
+        name: "slowlog.max_entries",
+        min: 1,
+        max: 1_000_000,
+        description: "Slow log length",
+    },
+    Parameter {
+        name: "rate_limit.requests_per_second",
+        min: 1,
+        max: u32::MAX as u64,
+        description: "Global rate per caller",
+    },
+    Parameter {
+        name: "rate_limit.burst_size",
+        min: 1,
+        max: u32::MAX as u64,
+        description: "Global burst per caller",
+    },
+    Parameter {
+        name: "persistence.snapshot.interval_secs",
+        min: 1,
+        max: u32::MAX as u64,
+        description: "Seconds between periodic snapshots",
+    },
+    Parameter {
+        name: "persistence.snapshot.operation_threshold",
+        min: 1,
+        max: u32::MAX as u64,
+        description: "Operations that trigger a snapshot early",
+    },
+    Parameter {
+        name: "queue.max_depth",
+        min: 1,
+        max: u32::MAX as u64,
+        description: "Default depth of new queues",
+    },
+    Parameter {
+        name: "queue.ack_deadline_secs",
+        min: 1,
+        max: u32::MAX as u64,
+        description: "Default ACK deadline of new queues",
+    },
+    Parameter {
+        name: "queue.default_max_retries",
+        min: 0,
+        max: u32::MAX as u64,
+        description: "Default retries of new queues",
+    },
+    Parameter {
+        name: "queue.default_priority",
+        min: 0,
+        max: 9,
+        description: "Default message priority of new queues",
+    },
+    Parameter {
+        name: "queue.prefetch_limit",
+        min: 0,
+        max: u32::MAX as u64,
+        description: "Default per-consumer prefetch of new queues; 0 = unlimited",
+    },
+];
+
+/// Look up a parameter by name
+pub fn parameter(name: &str) -> Option<&'static Parameter> {
+    PARAMETERS.iter().find(|p| p.name == name)
+}
+
+/// Parameters whose name matches the glob `pattern`, e.g. `queue.*`
+pub fn matching(pattern: &str) -> impl Iterator<Item = &'static Parameter> + '_ {
+    PARAMETERS
+        .iter()
+        .filter(move |p| crate::core::glob_match(pattern, p.name))
+}
+
+/// Check `value` for parameter `name`. Numbers and numeric strings are
+/// accepted.
+pub fn validate(name: &str, value: &Value) -> Result<u64, String> {
+    let parameter = parameter(name).ok_or_else(|| format!("Unknown parameter '{}'", name))?;
+    let number = match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("'{}' must be a non-negative integer", name))?;
+    if number < parameter.min || number > parameter.max {
+        return Err(format!(
+            "'{}' must be between {} and {}",
+            name, parameter.min, parameter.max
+        ));
+    }
+    Ok(number)
+}
+
+/// Where overrides are kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfigOptions {
+    /// Overrides file; `null` keeps runtime changes until the next restart
+    pub overrides_path: Option<PathBuf>,
+}
+
+impl Default for RuntimeConfigOptions {
+    fn default() -> Self {
+        Self {
+            overrides_path: Some(PathBuf::from("./data/config.overrides.yml")),
+        }
+    }
+}
+
+/// Overrides set at runtime, and the live components without a home in
+/// [`AppState`]
+#[derive(Default)]
+pub struct RuntimeConfig {
+    overrides_path: Option<PathBuf>,
+    overrides: Mutex<BTreeMap<String, u64>>,
+    rate_limiter: Mutex<Option<Arc<RateLimiter>>>,
+}
+
+impl RuntimeConfig {
+    /// Keep overrides at `overrides_path`, loading the ones already there
+    pub fn load(overrides_path: Option<PathBuf>) -> anyhow::Result<Self> {
+        let overrides = match &overrides_path {
+            Some(path) if path.exists() => read_overrides(path)?,
+            _ => BTreeMap::new(),
+        };
+        if !overrides.is_empty() {
+            info!("Loaded {} runtime config override(s)", overrides.len());
+        }
+        Ok(Self {
+            overrides_path,
+            overrides: Mutex::new(overrides),
+            rate_limiter: Mutex::new(None),
+        })
+    }
+
+    /// Overrides set so far, by parameter
+    pub fn overrides(&self) -> BTreeMap<String, u64> {
+        self.overrides.lock().clone()
+    }
+
+    /// Where overrides are written, if anywhere
+    pub fn overrides_path(&self) -> Option<&Path> {
+        self.overrides_path.as_deref()
+    }
+
+    /// Apply the loaded overrides to `config` before the server is built
+    pub fn apply_to(&self, config: &mut ServerConfig) {
+        for (name, value) in self.overrides.lock().iter() {
+            set_startup_value(config, name, *value);
+        }
+    }
+
+    /// Make the router's rate limiter reachable by `config.set`; without
+    /// one, the `rate_limit.*` parameters are unavailable
+    pub fn attach_rate_limiter(&self, limiter: Arc<RateLimiter>) {
+        *self.rate_limiter.lock() = Some(limiter);
+    }
+
+    pub fn rate_limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.rate_limiter.lock().clone()
+    }
+
+    /// Record `changes` and rewrite the overrides file. Returns whether
+    /// they were written to disk.
+    pub fn record(&self, changes: &BTreeMap<String, u64>) -> anyhow::Result<bool> {
+        let mut overrides = self.overrides.lock();
+        overrides.extend(changes.iter().map(|(k, v)| (k.clone(), *v)));
+        let Some(path) = &self.overrides_path else {
+            return Ok(false);
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        let yaml = serde_yaml::to_string(&*overrides)?;
+        let tmp = path.with_extension("yml.tmp");
+        std::fs::write(&tmp, yaml).with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display()))?;
+        Ok(true)
+    }
+}
+
+fn read_overrides(path: &Path) -> anyhow::Result<BTreeMap<String, u64>> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let raw: BTreeMap<String, Value> = serde_yaml::from_str(&text)
+        .with_context(|| format!("parsing config overrides {}", path.display()))?;
+    raw.into_iter()
+        .map(|(name, value)| {
+            let value = validate(&name, &value)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+            Ok((name, value))
+        })
+        .collect()
+}
+
+fn set_startup_value(config: &mut ServerConfig, name: &str, value: u64) {
+    match name {
+        "kv_store.max_memory_mb" => config.kv_store.max_memory_mb = value as usize,
+        "slowlog.threshold_ms" => config.slowlog.threshold_ms = value,
+        "slowlog.max_entries" => config.slowlog.max_entries = value as usize,
+        "rate_limit.requests_per_second" => config.rate_limit.requests_per_second = value,
+        "rate_limit.burst_size" => config.rate_limit.burst_size = value,
+        "persistence.snapshot.interval_secs" => config.persistence.snapshot.interval_secs = value,
+        "persistence.snapshot.operation_threshold" => {
+            config.persistence.snapshot.operation_threshold = value as usize
+        }
+        "queue.max_depth" => config.queue.max_depth = value as usize,
+        "queue.ack_deadline_secs" => config.queue.ack_deadline_secs = value,
+        "queue.default_max_retries" => config.queue.default_max_retries = value as u32,
+        "queue.default_priority" => config.queue.default_priority = value as u8,
+        "queue.prefetch_limit" => config.queue.prefetch_limit = value as usize,
+        _ => {}
+    }
+}
+
+const MB: u64 = 1024 * 1024;
+
+/// Live value of parameter `name`; `None` when its component is off
+pub fn current(state: &AppState, name: &str) -> Option<u64> {
+    let slowlog = || state.monitoring.slow_log().config();
+    let rate_limits = || {
+        state
+            .monitoring
+            .runtime_config()
+            .rate_limiter()
+            .map(|limiter| limiter.limits())
+    };
+    let snapshots = || state.persistence.as_ref().map(|p| p.snapshot_schedule());
+    let queues = || state.queue_manager().map(|qm| qm.default_config());
+    match name {
+        "kv_store.max_memory_mb" => Some(match state.kv_store.global_memory() {
+            Some(mem) => mem.max_bytes().max(0) as u64 / MB,
+            None => state.kv_store.config().max_memory_mb as u64,
+        }),
+        "slowlog.threshold_ms" => Some(slowlog().threshold_ms),
+        "slowlog.max_entries" => Some(slowlog().max_entries as u64),
+        "rate_limit.requests_per_second" => rate_limits().map(|(rps, _)| rps),
+        "rate_limit.burst_size" => rate_limits().map(|(_, burst)| burst),
+        "persistence.snapshot.interval_secs" => snapshots().map(|(secs, _)| secs),
+        "persistence.snapshot.operation_threshold" => snapshots().map(|(_, ops)| ops as u64),
+        "queue.max_depth" => queues().map(|q| q.max_depth as u64),
+        "queue.ack_deadline_secs" => queues().map(|q| q.ack_deadline_secs),
+        "queue.default_max_retries" => queues().map(|q| q.default_max_retries as u64),
+        "queue.default_priority" => queues().map(|q| q.default_priority as u64),
+        "queue.prefetch_limit" => queues().map(|q| q.prefetch_limit as u64),
+        _ => None,
+    }
+}
+
+/// Why parameter `name` cannot be changed on this server, if it cannot
+pub fn unavailable(state: &AppState, name: &str) -> Option<&'static str> {
+    let runtime = state.monitoring.runtime_config();
+    if name.starts_with("rate_limit.") && runtime.rate_limiter().is_none() {
+        Some("rate limiting is disabled")
+    } else if name.starts_with("persistence.") && state.persistence.is_none() {
+        Some("persistence is disabled")
+    } else if name.starts_with("queue.") && state.queue_manager().is_none() {
+        Some("queues are disabled")
+    } else if name == "kv_store.max_memory_mb" && state.kv_store.global_memory().is_none() {
+        Some("the memory budget is fixed in embedded mode")
+    } else {
+        None
+    }
+}
+
+/// Apply a validated value to the live component
+pub async fn apply(state: &AppState, name: &str, value: u64) {
+    match name {
+        "kv_store.max_memory_mb" => {
+            if let Some(mem) = state.kv_store.global_memory() {
+                mem.set_max_bytes((value * MB) as usize);
+            }
+        }
+        "slowlog.threshold_ms" => {
+            state
+                .monitoring
+                .slow_log()
+                .configure(Some(value), None)
+                .await;
+        }
+        "slowlog.max_entries" => {
+            state
+                .monitoring
+                .slow_log()
+                .configure(None, Some(value as usize))
+                .await;
+        }
+        "rate_limit.requests_per_second" | "rate_limit.burst_size" => {
+            if let Some(limiter) = state.monitoring.runtime_config().rate_limiter() {
+                let (mut rps, mut burst) = limiter.limits();
+                if name == "rate_limit.burst_size" {
+                    burst = value;
+                } else {
+                    rps = value;
+                }
+                limiter.set_limits(rps, burst);
+            }
+        }
+        "persistence.snapshot.interval_secs" | "persistence.snapshot.operation_threshold" => {
+            if let Some(persistence) = &state.persistence {
+                let (mut secs, mut ops) = persistence.snapshot_schedule();
+                if name == "persistence.snapshot.interval_secs" {
+                    secs = value;
+                } else {
+                    ops = value as usize;
+                }
+                persistence.set_snapshot_schedule(secs, ops);
+            }
+        }
+        _ if name.starts_with("queue.") => {
+            if let Some(qm) = state.queue_manager() {
+                let mut defaults = qm.default_config();
+                match name {
+                    "queue.max_depth" => defaults.max_depth = value as usize,
+                    "queue.ack_deadline_secs" => defaults.ack_deadline_secs = value,
+                    "queue.default_max_retries" => defaults.default_max_retries = value as u32,
+                    "queue.default_priority" => defaults.default_priority = value as u8,
+                    "queue.prefetch_limit" => defaults.prefetch_limit = value as usize,
+                    _ => return,
+                }
+                qm.set_default_config(defaults);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_checks_name_type_and_range() {
+        assert_eq!(validate("slowlog.threshold_ms", &json!(5)), Ok(5));
+        assert_eq!(validate("queue.max_depth", &json!("100")), Ok(100));
+        assert!(validate("nope", &json!(1)).is_err());
+        assert!(validate("slowlog.max_entries", &json!(0)).is_err());
+        assert!(validate("queue.default_priority", &json!(10)).is_err());
+        assert!(validate("slowlog.threshold_ms", &json!(-1)).is_err());
+        assert!(validate("slowlog.threshold_ms", &json!(true)).is_err());
+    }
+
+    #[test]
+    fn test_matching_globs() {
+        let names: Vec<_> = matching("slowlog.*").map(|p| p.name).collect();
+        assert_eq!(names, ["slowlog.threshold_ms", "slowlog.max_entries"]);
+        assert_eq!(matching("*").count(), PARAMETERS.len());
+    }
+
+    #[test]
+    fn test_overrides_survive_a_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("overrides/config.overrides.yml");
+
+        let runtime = RuntimeConfig::load(Some(path.clone())).unwrap();
+        let changes = BTreeMap::from([
+            ("slowlog.threshold_ms".to_string(), 7),
+            ("queue.max_depth".to_string(), 500),
+        ]);
+        assert!(runtime.record(&changes).unwrap());
+
+        let reloaded = RuntimeConfig::load(Some(path)).unwrap();
+        assert_eq!(reloaded.overrides(), changes);
+        let mut config = ServerConfig::default();
+        reloaded.apply_to(&mut config);
+        assert_eq!(config.slowlog.threshold_ms, 7);
+        assert_eq!(config.queue.max_depth, 500);
+    }
+}
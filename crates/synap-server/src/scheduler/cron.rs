@@ -0,0 +1,290 @@
+//! Five-field cron expressions, evaluated in UTC.
+//!
+//! Fields are minute (0-59), hour (0-23), day of month (1-31), month (1-12
+//! or `JAN`-`DEC`) and day of week (0-7 or `SUN`-`SAT`, where 0 and 7 are
+//! both Sunday). Each field is `*`, a value, a range `a-b`, a list of those
+//! separated by commas, and any of them with a step: `*/15`, `10-40/10`, or
+//! `5/20` for "from 5 every 20". As in classic cron, a day matches when
+//! either the day of month or the day of week does if both are restricted.
+//!
+//! `@yearly` (`@annually`), `@monthly`, `@weekly`, `@daily` (`@midnight`)
+//! and `@hourly` stand for the usual expressions.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeDelta, Timelike, Utc};
+
+/// How far ahead [`CronExpr::next_after`] looks before deciding an
+/// expression never fires, e.g. `0 0 30 2 *`
+const SEARCH_YEARS: i32 = 5;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A parsed cron expression; one bit per allowed value of each field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day-of-month and day-of-week fields were `*`
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronExpr {
+    /// First minute strictly after `after` that the expression matches, or
+    /// `None` if it matches nothing in the next few years
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let after = after.naive_utc();
+        let mut t =
+            after.date().and_hms_opt(after.hour(), after.minute(), 0)? + TimeDelta::minutes(1);
+        let last_year = t.year() + SEARCH_YEARS;
+
+        while t.year() <= last_year {
+            if !has(self.months, t.month()) {
+                t = first_of_next_month(t.date())?;
+            } else if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !has(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + TimeDelta::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += TimeDelta::minutes(1);
+            } else {
+                return Some(t.and_utc());
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.any_day || self.any_weekday {
+            day && weekday
+        } else {
+            day || weekday
+        }
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = String;
+
+    fn from_str(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        let expanded = match expr.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(format!("Unknown cron macro '{}'", expr));
+            }
+            _ => expr,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Cron expression '{}' must have 5 fields (minute hour day month weekday), got {}",
+                expr,
+                fields.len()
+            ));
+        };
+
+        let mut weekdays = parse_field(weekday, "day of week", 0, 7, &WEEKDAYS, 0)?;
+        // 7 is another name for Sunday
+        if has(weekdays, 7) {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, "minute", 0, 59, &[], 0)?,
+            hours: parse_field(hour, "hour", 0, 23, &[], 0)?,
+            days: parse_field(day, "day of month", 1, 31, &[], 0)?,
+            months: parse_field(month, "month", 1, 12, &MONTHS, 1)?,
+            weekdays,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDateTime> {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)
+}
+
+/// Bits of the values `field` allows. `names[i]` stands for `first_name + i`.
+fn parse_field(
+    field: &str,
+    what: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    first_name: u32,
+) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        let value = match names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(text))
+        {
+            Some(index) => index as u32 + first_name,
+            None => text
+                .parse()
+                .map_err(|_| format!("Invalid {} '{}'", what, text))?,
+        };
+        if !(min..=max).contains(&value) {
+            return Err(format!(
+                "{} {} is out of range {}-{}",
+                what, value, min, max
+            ));
+        }
+        Ok(value)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid {} step '{}'", what, step))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+        let (low, high) = if range == "*" {
+            (min, max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            let (low, high) = (value(low)?, value(high)?);
+            if low > high {
+                return Err(format!("Invalid {} range '{}'", what, range));
+            }
+            (low, high)
+        } else {
+            let low = value(range)?;
+            // `5/20` runs from 5 to the end of the field
+            (low, if step.is_some() { max } else { low })
+        };
+        for value in (low..=high).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn next(expr: &str, after: &str) -> Option<String> {
+        let expr: CronExpr = expr.parse().unwrap();
+        expr.next_after(at(after))
+            .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+    }
+
+    #[test]
+    fn test_every_minute_skips_the_current_one() {
+        assert_eq!(
+            next("* * * * *", "2026-03-01T10:15:30Z").as_deref(),
+            Some("2026-03-01T10:16:00Z")
+        );
+        assert_eq!(
+            next("* * * * *", "2026-03-01T10:15:00Z").as_deref(),
+            Some("2026-03-01T10:16:00Z")
+        );
+    }
+
+    #[test]
+    fn test_fields_steps_and_ranges() {
+        assert_eq!(
+            next("*/15 * * * *", "2026-03-01T10:46:00Z").as_deref(),
+            Some("2026-03-01T11:00:00Z")
+        );
+        assert_eq!(
+            next("30 9-17/4 * * *", "2026-03-01T13:30:00Z").as_deref(),
+            Some("2026-03-01T17:30:00Z")
+        );
+        assert_eq!(
+            next("5/20 * * * *", "2026-03-01T10:46:00Z").as_deref(),
+            Some("2026-03-01T11:05:00Z")
+        );
+        assert_eq!(
+            next("0 0 1 jan,jul *", "2026-03-01T00:00:00Z").as_deref(),
+            Some("2026-07-01T00:00:00Z")
+        );
+        assert_eq!(
+            next("@yearly", "2026-03-01T00:00:00Z").as_deref(),
+            Some("2027-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_weekdays() {
+        // 2026-03-01 is a Sunday
+        assert_eq!(
+            next("0 9 * * MON-FRI", "2026-02-27T09:00:00Z").as_deref(),
+            Some("2026-03-02T09:00:00Z")
+        );
+        assert_eq!(
+            next("0 0 * * 7", "2026-02-25T00:00:00Z"),
+            next("0 0 * * 0", "2026-02-25T00:00:00Z")
+        );
+        // Restricted day of month and day of week: either one matches
+        assert_eq!(
+            next("0 0 13 * 5", "2026-03-01T00:00:00Z").as_deref(),
+            Some("2026-03-06T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_impossible_dates_never_fire() {
+        assert_eq!(
+            next("0 0 29 2 *", "2026-03-01T00:00:00Z").as_deref(),
+            Some("2028-02-29T00:00:00Z")
+        );
+        assert_eq!(next("0 0 30 2 *", "2026-03-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expr in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "10-5 * * * *",
+            "a * * * *",
+            "@fortnightly",
+        ] {
+            assert!(expr.parse::<CronExpr>().is_err(), "{:?} parsed", expr);
+        }
+    }
+}
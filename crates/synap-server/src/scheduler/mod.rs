@@ -0,0 +1,715 @@
+//! Cron schedules.
+//!
+//! A schedule publishes a templated message to a local queue, Pub/Sub topic
+//! or stream room every time its cron expression fires; see
+//! [`protocol::command::schedule`](crate::protocol::command::schedule) for the spec and the placeholders the
+//! payload may use. Expressions are evaluated in UTC by [`cron::CronExpr`].
+//!
+//! A fire whose publish fails (say the queue was deleted) is counted in
+//! `failed` with its `last_error` and not retried; the next fire tries
+//! again. Fires that fall while the server is down are skipped, not
+//! replayed.
+//!
+//! With persistence enabled the schedules and their counters are
+//! checkpointed to `schedules.json` next to the snapshots after every fire,
+//! so schedules resume after a restart.
+
+pub mod cron;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+pub use crate::protocol::command::schedule::{
+    ScheduleProgress, ScheduleSpec, ScheduleStatus, ScheduleTarget,
+};
+
+use crate::core::{PubSubRouter, QueueManager, StreamManager, SynapError};
+use crate::persistence::{PersistenceLayer, write_atomically};
+use crate::server::AppState;
+use cron::CronExpr;
+
+/// Schedule definitions and counters, kept in the snapshot directory
+pub const SCHEDULES_FILE: &str = "schedules.json";
+
+/// Event type of stream publishes without an `event`
+const DEFAULT_EVENT: &str = "scheduled";
+/// Longest single sleep before the wall clock is checked again, so a clock
+/// step does not leave a schedule sleeping past its fire time
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// One line of the checkpoint file
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    spec: ScheduleSpec,
+    progress: ScheduleProgress,
+}
+
+struct Entry {
+    spec: ScheduleSpec,
+    cron: CronExpr,
+    progress: Arc<Mutex<ScheduleProgress>>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// What a schedule needs from the server it runs on
+#[derive(Clone)]
+pub struct ScheduleContext {
+    queue_manager: Option<Arc<QueueManager>>,
+    stream_manager: Option<Arc<StreamManager>>,
+    pubsub_router: Option<Arc<PubSubRouter>>,
+    persistence: Option<Arc<PersistenceLayer>>,
+}
+
+impl ScheduleContext {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            queue_manager: state.queue_manager().cloned(),
+            stream_manager: state.stream_manager().cloned(),
+            pubsub_router: state.pubsub_router.clone(),
+            persistence: state.persistence.clone(),
+        }
+    }
+}
+
+/// Running schedules, optionally checkpointed to a file
+#[derive(Default)]
+pub struct ScheduleManager {
+    schedules: Mutex<HashMap<String, Entry>>,
+    path: Option<PathBuf>,
+}
+
+impl ScheduleManager {
+    /// In-memory manager; schedules stop for good on restart
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Manager checkpointed at `path`, loading the schedules saved there.
+    /// They start on [`resume`](Self::resume).
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, SynapError> {
+        let path = path.into();
+        let saved: Vec<Saved> = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| {
+                SynapError::SerializationError(format!("{}: {}", path.display(), e))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(SynapError::IoError(e.to_string())),
+        };
+
+        info!("Loaded {} schedules from {}", saved.len(), path.display());
+        let mut schedules = HashMap::new();
+        for saved in saved {
+            let cron = saved.spec.cron.parse().map_err(|e| {
+                SynapError::SerializationError(format!("{}: {}", path.display(), e))
+            })?;
+            let entry = Entry {
+                spec: saved.spec,
+                cron,
+                progress: Arc::new(Mutex::new(saved.progress)),
+                task: None,
+            };
+            schedules.insert(entry.spec.name.clone(), entry);
+        }
+        Ok(Self {
+            schedules: Mutex::new(schedules),
+            path: Some(path),
+        })
+    }
+
+    /// Start every loaded schedule
+    pub fn resume(self: &Arc<Self>, context: ScheduleContext) {
+        let mut schedules = self.schedules.lock();
+        for entry in schedules.values_mut() {
+            if entry.task.is_none() {
+                entry.task = Some(self.spawn(entry, context.clone()));
+            }
+        }
+    }
+
+    /// Validate and start a schedule (`schedule.create`)
+    pub async fn create(
+        self: &Arc<Self>,
+        spec: ScheduleSpec,
+        context: ScheduleContext,
+    ) -> Result<ScheduleStatus, SynapError> {
+        let cron = validate(&spec, &context).await?;
+
+        let mut schedules = self.schedules.lock();
+        if schedules.contains_key(&spec.name) {
+            return Err(SynapError::InvalidRequest(format!(
+                "Schedule '{}' already exists",
+                spec.name
+            )));
+        }
+        let mut entry = Entry {
+            spec,
+            cron,
+            progress: Arc::default(),
+            task: None,
+        };
+        // Known before the worker gets to it, so the reply can show it
+        entry.progress.lock().next_fire_at = entry.cron.next_after(Utc::now()).map(unix_millis);
+        entry.task = Some(self.spawn(&entry, context));
+        let status = status(&entry);
+        info!(
+            "Schedule '{}' started: '{}' -> {}",
+            entry.spec.name,
+            entry.spec.cron,
+            target_label(&entry.spec.target)
+        );
+        schedules.insert(entry.spec.name.clone(), entry);
+        self.persist(&schedules);
+        Ok(status)
+    }
+
+    /// Every schedule, sorted by name (`schedule.list`)
+    pub fn list(&self) -> Vec<ScheduleStatus> {
+        let mut list: Vec<ScheduleStatus> = self.schedules.lock().values().map(status).collect();
+        list.sort_by(|a, b| a.spec.name.cmp(&b.spec.name));
+        list
+    }
+
+    /// One schedule (`schedule.get`)
+    pub fn get(&self, name: &str) -> Option<ScheduleStatus> {
+        self.schedules.lock().get(name).map(status)
+    }
+
+    /// Stop and forget a schedule (`schedule.delete`)
+    pub fn delete(&self, name: &str) -> bool {
+        let mut schedules = self.schedules.lock();
+        let Some(entry) = schedules.remove(name) else {
+            return false;
+        };
+        if let Some(task) = entry.task {
+            task.abort();
+        }
+        info!("Schedule '{}' deleted", name);
+        self.persist(&schedules);
+        true
+    }
+
+    fn spawn(self: &Arc<Self>, entry: &Entry, context: ScheduleContext) -> JoinHandle<()> {
+        let worker = Worker {
+            spec: entry.spec.clone(),
+            cron: entry.cron.clone(),
+            progress: entry.progress.clone(),
+            manager: Arc::downgrade(self),
+            context,
+        };
+        tokio::spawn(worker.run())
+    }
+
+    fn checkpoint(&self) {
+        self.persist(&self.schedules.lock());
+    }
+
+    /// Write every schedule to the backing file, if any. A failed write is
+    /// logged; the schedules keep running.
+    fn persist(&self, schedules: &HashMap<String, Entry>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let mut saved: Vec<Saved> = schedules
+            .values()
+            .map(|entry| Saved {
+                spec: entry.spec.clone(),
+                progress: entry.progress.lock().clone(),
+            })
+            .collect();
+        saved.sort_by(|a, b| a.spec.name.cmp(&b.spec.name));
+        let result = serde_json::to_vec_pretty(&saved)
+            .map_err(|e| e.to_string())
+            .and_then(|data| write_atomically(path, &data).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!(
+                "Failed to checkpoint schedules to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+impl Drop for ScheduleManager {
+    fn drop(&mut self) {
+        for entry in self.schedules.get_mut().values_mut() {
+            if let Some(task) = entry.task.take() {
+                task.abort();
+            }
+        }
+    }
+}
+
+fn status(entry: &Entry) -> ScheduleStatus {
+    ScheduleStatus {
+        spec: entry.spec.clone(),
+        progress: entry.progress.lock().clone(),
+    }
+}
+
+fn target_label(target: &ScheduleTarget) -> String {
+    match target {
+        ScheduleTarget::Queue(queue) => format!("queue:{}", queue),
+        ScheduleTarget::Topic(topic) => format!("topic:{}", topic),
+        ScheduleTarget::Stream(room) => format!("stream:{}", room),
+    }
+}
+
+fn unix_millis(time: DateTime<Utc>) -> u64 {
+    time.timestamp_millis().max(0) as u64
+}
+
+async fn validate(spec: &ScheduleSpec, context: &ScheduleContext) -> Result<CronExpr, SynapError> {
+    let invalid = |message: String| Err(SynapError::InvalidRequest(message));
+
+    if spec.name.is_empty() {
+        return invalid("Schedule name must not be empty".to_string());
+    }
+    let cron: CronExpr = match spec.cron.parse() {
+        Ok(cron) => cron,
+        Err(e) => return invalid(e),
+    };
+    if cron.next_after(Utc::now()).is_none() {
+        return invalid(format!("Cron expression '{}' never fires", spec.cron));
+    }
+    // Rendering once with sample values catches unknown placeholders
+    render(&spec.payload, &Fire::sample(&spec.name)).map_err(SynapError::InvalidRequest)?;
+    if spec.event.is_some() && !matches!(spec.target, ScheduleTarget::Stream(_)) {
+        return invalid("event is only used with a stream target".to_string());
+    }
+
+    match &spec.target {
+        ScheduleTarget::Queue(queue) => {
+            let Some(queues) = &context.queue_manager else {
+                return invalid("Queue system disabled".to_string());
+            };
+            queues.stats(queue).await?;
+        }
+        ScheduleTarget::Topic(topic) => {
+            if topic.is_empty() {
+                return invalid("topic must not be empty".to_string());
+            }
+            if context.pubsub_router.is_none() {
+                return invalid("Pub/Sub is disabled on this server".to_string());
+            }
+        }
+        ScheduleTarget::Stream(room) => {
+            let Some(streams) = &context.stream_manager else {
+                return invalid("Stream system disabled".to_string());
+            };
+            streams
+                .room_stats(room)
+                .await
+                .map_err(SynapError::InvalidRequest)?;
+        }
+    }
+    if spec.event.as_deref() == Some("") {
+        return invalid("event must not be empty".to_string());
+    }
+    Ok(cron)
+}
+
+/// Values of the placeholders at one fire
+struct Fire<'a> {
+    schedule: &'a str,
+    time: DateTime<Utc>,
+    count: u64,
+}
+
+impl<'a> Fire<'a> {
+    fn sample(schedule: &'a str) -> Self {
+        Self {
+            schedule,
+            time: Utc::now(),
+            count: 1,
+        }
+    }
+
+    fn value(&self, placeholder: &str) -> Option<Value> {
+        Some(match placeholder {
+            "schedule" => Value::from(self.schedule),
+            "fire_time" => Value::from(self.time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            "fire_timestamp" => Value::from(self.time.timestamp()),
+            "fire_count" => Value::from(self.count),
+            "uuid" => Value::from(uuid::Uuid::new_v4().to_string()),
+            _ => return None,
+        })
+    }
+}
+
+/// `template` with the placeholders in its strings filled in
+fn render(template: &Value, fire: &Fire<'_>) -> Result<Value, String> {
+    Ok(match template {
+        Value::String(text) => render_str(text, fire)?,
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render(item, fire))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), render(value, fire)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn render_str(text: &str, fire: &Fire<'_>) -> Result<Value, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let placeholder = rest[start + 2..start + 2 + len].trim();
+        let value = fire
+            .value(placeholder)
+            .ok_or_else(|| format!("Unknown placeholder '{{{{{}}}}}'", placeholder))?;
+        // A string that is only a number placeholder becomes the number
+        if start == 0 && start + 4 + len == rest.len() && out.is_empty() && value.is_number() {
+            return Ok(value);
+        }
+        out.push_str(&rest[..start]);
+        match value {
+            Value::String(value) => out.push_str(&value),
+            value => out.push_str(&value.to_string()),
+        }
+        rest = &rest[start + 4 + len..];
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+struct Worker {
+    spec: ScheduleSpec,
+    cron: CronExpr,
+    progress: Arc<Mutex<ScheduleProgress>>,
+    manager: Weak<ScheduleManager>,
+    context: ScheduleContext,
+}
+
+impl Worker {
+    async fn run(self) {
+        let mut after = Utc::now();
+        loop {
+            let Some(next) = self.cron.next_after(after) else {
+                let mut progress = self.progress.lock();
+                progress.next_fire_at = None;
+                progress.last_error = Some(format!("'{}' never fires again", self.spec.cron));
+                return;
+            };
+            self.progress.lock().next_fire_at = Some(unix_millis(next));
+
+            loop {
+                let now = Utc::now();
+                if now >= next {
+                    break;
+                }
+                let wait = (next - now).to_std().unwrap_or_default();
+                tokio::time::sleep(wait.min(MAX_SLEEP)).await;
+            }
+
+            self.fire(next).await;
+            // Fires missed while this one ran are skipped
+            after = Utc::now().max(next);
+        }
+    }
+
+    async fn fire(&self, time: DateTime<Utc>) {
+        let count = {
+            let progress = self.progress.lock();
+            progress.fired + progress.failed + 1
+        };
+        let fire = Fire {
+            schedule: &self.spec.name,
+            time,
+            count,
+        };
+        let result = match render(&self.spec.payload, &fire) {
+            Ok(message) => self.publish(message).await,
+            Err(e) => Err(e),
+        };
+
+        {
+            let mut progress = self.progress.lock();
+            progress.last_fired_at = Some(unix_millis(time));
+            match result {
+                Ok(()) => progress.fired += 1,
+                Err(e) => {
+                    warn!("Schedule '{}' failed to publish: {}", self.spec.name, e);
+                    progress.failed += 1;
+                    progress.last_error = Some(e);
+                }
+            }
+        }
+        if let Some(manager) = self.manager.upgrade() {
+            manager.checkpoint();
+        }
+    }
+
+    async fn publish(&self, message: Value) -> Result<(), String> {
+        let missing = |what: &str| format!("{} disabled", what);
+        match &self.spec.target {
+            ScheduleTarget::Queue(queue) => {
+                let queues = self
+                    .context
+                    .queue_manager
+                    .as_ref()
+                    .ok_or_else(|| missing("Queue system"))?;
+                let payload = serde_json::to_vec(&message).map_err(|e| e.to_string())?;
+                let published = queues
+                    .publish_with_message(queue, payload, None, None)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if let Some(ref persistence) = self.context.persistence
+                    && let Err(e) = persistence
+                        .log_queue_publish(queue.to_string(), published)
+                        .await
+                {
+                    warn!("Failed to log scheduled publish to WAL: {}", e);
+                }
+            }
+            ScheduleTarget::Topic(topic) => {
+                let router = self
+                    .context
+                    .pubsub_router
+                    .as_ref()
+                    .ok_or_else(|| missing("Pub/Sub system"))?;
+                router
+                    .publish(topic, message, None)
+                    .map_err(|e| e.to_string())?;
+            }
+            ScheduleTarget::Stream(room) => {
+                let streams = self
+                    .context
+                    .stream_manager
+                    .as_ref()
+                    .ok_or_else(|| missing("Stream system"))?;
+                let data = serde_json::to_vec(&message).map_err(|e| e.to_string())?;
+                let event = self.spec.event.as_deref().unwrap_or(DEFAULT_EVENT);
+                streams.publish(room, event, data).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{QueueConfig, StreamConfig};
+    use serde_json::json;
+
+    fn context(queues: Option<Arc<QueueManager>>) -> ScheduleContext {
+        ScheduleContext {
+            queue_manager: queues,
+            stream_manager: Some(Arc::new(StreamManager::new(StreamConfig::default()))),
+            pubsub_router: Some(Arc::new(PubSubRouter::new())),
+            persistence: None,
+        }
+    }
+
+    fn spec(name: &str, cron: &str, target: ScheduleTarget, payload: Value) -> ScheduleSpec {
+        ScheduleSpec {
+            name: name.to_string(),
+            cron: cron.to_string(),
+            target,
+            payload,
+            event: None,
+        }
+    }
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_render_fills_placeholders() {
+        let fire = Fire {
+            schedule: "nightly",
+            time: at("2026-03-01T02:00:00Z"),
+            count: 7,
+        };
+        let rendered = render(
+            &json!({
+                "job": "report",
+                "at": "{{fire_time}}",
+                "ts": "{{ fire_timestamp }}",
+                "label": "{{schedule}} #{{fire_count}}",
+                "runs": ["{{fire_count}}", 1, null],
+                "braces": "{{"
+            }),
+            &fire,
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            json!({
+                "job": "report",
+                "at": "2026-03-01T02:00:00Z",
+                "ts": 1772330400,
+                "label": "nightly #7",
+                "runs": [7, 1, null],
+                "braces": "{{"
+            })
+        );
+
+        let uuid = render(&json!("{{uuid}}"), &fire).unwrap();
+        assert_eq!(uuid.as_str().unwrap().len(), 36);
+        assert!(render(&json!({"a": "{{nope}}"}), &fire).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_specs_are_rejected() {
+        let queues = Arc::new(QueueManager::new(QueueConfig::default()));
+        queues.create_queue("jobs", None).await.unwrap();
+        let manager = Arc::new(ScheduleManager::new());
+        let queue = || ScheduleTarget::Queue("jobs".to_string());
+
+        for spec in [
+            spec("", "* * * * *", queue(), json!(1)),
+            spec("s", "* * * *", queue(), json!(1)),
+            spec("s", "0 0 30 2 *", queue(), json!(1)),
+            spec("s", "* * * * *", queue(), json!("{{unknown}}")),
+            spec(
+                "s",
+                "* * * * *",
+                ScheduleTarget::Queue("missing".to_string()),
+                json!(1),
+            ),
+            spec(
+                "s",
+                "* * * * *",
+                ScheduleTarget::Stream("missing".to_string()),
+                json!(1),
+            ),
+            ScheduleSpec {
+                event: Some("tick".to_string()),
+                ..spec("s", "* * * * *", queue(), json!(1))
+            },
+        ] {
+            assert!(
+                manager
+                    .create(spec.clone(), context(Some(queues.clone())))
+                    .await
+                    .is_err(),
+                "{:?} accepted",
+                spec
+            );
+        }
+        assert!(
+            manager
+                .create(spec("s", "* * * * *", queue(), json!(1)), context(None))
+                .await
+                .is_err()
+        );
+        assert!(manager.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fire_publishes_to_each_target() {
+        let queues = Arc::new(QueueManager::new(QueueConfig::default()));
+        queues.create_queue("jobs", None).await.unwrap();
+        let context = context(Some(queues.clone()));
+        let streams = context.stream_manager.clone().unwrap();
+        streams.create_room("ticks").await.unwrap();
+        let time = at("2026-03-01T02:00:00Z");
+
+        let payload = json!({"schedule": "{{schedule}}", "n": "{{fire_count}}"});
+        let worker = |target| Worker {
+            spec: ScheduleSpec {
+                event: matches!(target, ScheduleTarget::Stream(_)).then(|| "tick".to_string()),
+                ..spec("nightly", "0 2 * * *", target, payload.clone())
+            },
+            cron: "0 2 * * *".parse().unwrap(),
+            progress: Arc::default(),
+            manager: Weak::new(),
+            context: context.clone(),
+        };
+
+        let to_queue = worker(ScheduleTarget::Queue("jobs".to_string()));
+        to_queue.fire(time).await;
+        to_queue.fire(time).await;
+        let first = queues.consume("jobs", "c").await.unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&first.payload).unwrap(),
+            json!({"schedule": "nightly", "n": 1})
+        );
+        let second = queues.consume("jobs", "c").await.unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&second.payload).unwrap()["n"],
+            json!(2)
+        );
+        assert_eq!(to_queue.progress.lock().fired, 2);
+        assert_eq!(
+            to_queue.progress.lock().last_fired_at,
+            Some(unix_millis(time))
+        );
+
+        let to_stream = worker(ScheduleTarget::Stream("ticks".to_string()));
+        to_stream.fire(time).await;
+        let events = streams.consume("ticks", "c", 0, 10).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "tick");
+
+        let to_topic = worker(ScheduleTarget::Topic("ticks".to_string()));
+        to_topic.fire(time).await;
+        assert_eq!(to_topic.progress.lock().fired, 1);
+
+        // A queue deleted after the schedule was created fails the fire
+        let to_missing = worker(ScheduleTarget::Queue("gone".to_string()));
+        to_missing.fire(time).await;
+        let progress = to_missing.progress.lock().clone();
+        assert_eq!((progress.fired, progress.failed), (0, 1));
+        assert!(progress.last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_schedules_survive_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SCHEDULES_FILE);
+        let queues = Arc::new(QueueManager::new(QueueConfig::default()));
+        queues.create_queue("jobs", None).await.unwrap();
+
+        let manager = Arc::new(ScheduleManager::open(&path).unwrap());
+        let created = manager
+            .create(
+                spec(
+                    "nightly",
+                    "@daily",
+                    ScheduleTarget::Queue("jobs".to_string()),
+                    json!({"at": "{{fire_time}}"}),
+                ),
+                context(Some(queues.clone())),
+            )
+            .await
+            .unwrap();
+        assert!(created.progress.next_fire_at.is_some());
+        drop(manager);
+
+        let reopened = Arc::new(ScheduleManager::open(&path).unwrap());
+        reopened.resume(context(Some(queues)));
+        let status = reopened.get("nightly").unwrap();
+        assert_eq!(status.spec, created.spec);
+
+        assert!(reopened.delete("nightly"));
+        assert!(!reopened.delete("nightly"));
+        let reopened = ScheduleManager::open(&path).unwrap();
+        assert!(reopened.list().is_empty());
+    }
+}
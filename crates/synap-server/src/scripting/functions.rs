@@ -0,0 +1,384 @@
+//! Function libraries (`FUNCTION LOAD` / `FCALL`)
+//!
+//! A library is a Lua chunk whose first line names it and whose body registers
+//! callable functions:
+//!
+//! ```lua
+//! #!lua name=counters
+//! redis.register_function('bump', function(keys, args)
+//!   return redis.call('INCRBY', keys[1], args[1])
+//! end)
+//! ```
+//!
+//! The table form `redis.register_function{function_name='bump', callback=...}`
+//! is accepted too. Function names are global across libraries. When the
+//! registry is backed by a file, every change is written through so libraries
+//! survive a restart.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use mlua::{HookTriggers, Lua, Table, Value as LuaValue, VmState};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{apply_sandbox, map_lua_error};
+use crate::core::SynapError;
+
+/// Registering a library must not take longer than this; it runs the chunk
+/// once to learn its function names.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A named group of Lua functions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionLibrary {
+    pub name: String,
+    pub code: String,
+    /// Functions registered by the library, sorted by name
+    pub functions: Vec<String>,
+}
+
+impl FunctionLibrary {
+    /// Library body with the `#!lua` header commented out, so line numbers in
+    /// Lua errors match the submitted code.
+    pub(super) fn lua_source(&self) -> String {
+        format!("--{}", self.code)
+    }
+}
+
+/// Loaded function libraries, optionally persisted to a JSON file
+#[derive(Default)]
+pub struct FunctionRegistry {
+    libraries: RwLock<HashMap<String, Arc<FunctionLibrary>>>,
+    path: Option<PathBuf>,
+}
+
+impl FunctionRegistry {
+    /// In-memory registry; libraries are lost on restart
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry persisted at `path`, loading the libraries already saved there
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, SynapError> {
+        let path = path.into();
+        let libraries = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice::<Vec<FunctionLibrary>>(&data)
+                .map_err(|e| SynapError::SerializationError(format!("{}: {}", path.display(), e)))?
+                .into_iter()
+                .map(|lib| (lib.name.clone(), Arc::new(lib)))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(SynapError::IoError(e.to_string())),
+        };
+
+        info!(
+            "Loaded {} function libraries from {}",
+            libraries.len(),
+            path.display()
+        );
+        Ok(Self {
+            libraries: RwLock::new(libraries),
+            path: Some(path),
+        })
+    }
+
+    /// Register a library (FUNCTION LOAD), returning its name.
+    ///
+    /// Fails if the library exists and `replace` is false, or if one of its
+    /// functions is already registered by another library.
+    pub fn load(&self, code: &str, replace: bool) -> Result<String, SynapError> {
+        let name = parse_header(code)?;
+        let mut library = FunctionLibrary {
+            name: name.clone(),
+            code: code.to_string(),
+            functions: Vec::new(),
+        };
+        library.functions = registered_functions(&library)?;
+
+        let mut libraries = self.libraries.write();
+        if libraries.contains_key(&name) && !replace {
+            return Err(SynapError::InvalidRequest(format!(
+                "Library '{}' already exists",
+                name
+            )));
+        }
+        for other in libraries.values().filter(|lib| lib.name != name) {
+            if let Some(clash) = library
+                .functions
+                .iter()
+                .find(|f| other.functions.contains(f))
+            {
+                return Err(SynapError::InvalidRequest(format!(
+                    "Function '{}' already exists in library '{}'",
+                    clash, other.name
+                )));
+            }
+        }
+
+        let previous = libraries.insert(name.clone(), Arc::new(library));
+        if let Err(e) = self.persist(&libraries) {
+            match previous {
+                Some(lib) => libraries.insert(name, lib),
+                None => libraries.remove(&name),
+            };
+            return Err(e);
+        }
+        Ok(name)
+    }
+
+    /// Remove a library and its functions (FUNCTION DELETE)
+    pub fn delete(&self, library: &str) -> Result<(), SynapError> {
+        let mut libraries = self.libraries.write();
+        let removed = libraries
+            .remove(library)
+            .ok_or_else(|| SynapError::ResourceNotFound(format!("Library '{}'", library)))?;
+        if let Err(e) = self.persist(&libraries) {
+            libraries.insert(library.to_string(), removed);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// All libraries, sorted by name (FUNCTION LIST)
+    pub fn list(&self) -> Vec<Arc<FunctionLibrary>> {
+        let mut libraries: Vec<_> = self.libraries.read().values().cloned().collect();
+        libraries.sort_by(|a, b| a.name.cmp(&b.name));
+        libraries
+    }
+
+    /// Remove every library (FUNCTION FLUSH), returning how many were removed
+    pub fn flush(&self) -> Result<usize, SynapError> {
+        let mut libraries = self.libraries.write();
+        let previous = std::mem::take(&mut *libraries);
+        if let Err(e) = self.persist(&libraries) {
+            *libraries = previous;
+            return Err(e);
+        }
+        Ok(previous.len())
+    }
+
+    /// Library that registers `function`
+    pub fn find(&self, function: &str) -> Option<Arc<FunctionLibrary>> {
+        self.libraries
+            .read()
+            .values()
+            .find(|lib| lib.functions.iter().any(|f| f == function))
+            .cloned()
+    }
+
+    /// Write the full library set to the backing file, if any. Written to a
+    /// temporary file and renamed, so a crash never leaves a torn file.
+    fn persist(&self, libraries: &HashMap<String, Arc<FunctionLibrary>>) -> Result<(), SynapError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut sorted: Vec<&FunctionLibrary> =
+            libraries.values().map(|lib| lib.as_ref()).collect();
+        sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        let data = serde_json::to_vec_pretty(&sorted)
+            .map_err(|e| SynapError::SerializationError(e.to_string()))?;
+
+        write_atomically(path, &data).map_err(|e| SynapError::IoError(e.to_string()))
+    }
+}
+
+fn write_atomically(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Library name from the `#!lua name=<name>` first line
+fn parse_header(code: &str) -> Result<String, SynapError> {
+    let header = code.lines().next().unwrap_or_default();
+    let rest = header.strip_prefix("#!lua").ok_or_else(|| {
+        SynapError::InvalidRequest(
+            "Missing library metadata: code must start with '#!lua name=<library>'".to_string(),
+        )
+    })?;
+
+    let name = rest
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix("name="))
+        .ok_or_else(|| SynapError::InvalidRequest("Library name was not given".to_string()))?;
+
+    if !is_valid_name(name) {
+        return Err(SynapError::InvalidRequest(format!(
+            "Invalid library name '{}': use letters, digits and underscores",
+            name
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Add `redis.register_function` to `redis`, recording callbacks in the
+/// returned table keyed by function name.
+pub(super) fn install_register_function(lua: &Lua, redis: &Table) -> Result<Table, SynapError> {
+    let registered = lua.create_table().map_err(map_lua_error)?;
+    let target = registered.clone();
+    let register = lua
+        .create_function(move |_lua, args: mlua::MultiValue| {
+            let mut args = args.into_iter();
+            let (name, callback) = match (args.next(), args.next()) {
+                (Some(LuaValue::String(name)), Some(LuaValue::Function(callback))) => {
+                    (name.to_str()?.to_string(), callback)
+                }
+                (Some(LuaValue::Table(spec)), None) => (
+                    spec.get::<String>("function_name")?,
+                    spec.get::<mlua::Function>("callback")?,
+                ),
+                _ => {
+                    return Err(mlua::Error::RuntimeError(
+                        "register_function expects (name, callback) or {function_name=..., callback=...}".to_string(),
+                    ));
+                }
+            };
+
+            if !is_valid_name(&name) {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "Invalid function name '{}': use letters, digits and underscores",
+                    name
+                )));
+            }
+            if target.contains_key(name.as_str())? {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "Function '{}' registered twice",
+                    name
+                )));
+            }
+            target.set(name, callback)
+        })
+        .map_err(map_lua_error)?;
+    redis
+        .set("register_function", register)
+        .map_err(map_lua_error)?;
+    Ok(registered)
+}
+
+/// Run the library body once, without data access, to collect its function
+/// names.
+fn registered_functions(library: &FunctionLibrary) -> Result<Vec<String>, SynapError> {
+    let lua = Lua::new();
+    apply_sandbox(&lua)?;
+
+    let redis = lua.create_table().map_err(map_lua_error)?;
+    let registered = install_register_function(&lua, &redis)?;
+    lua.globals().set("redis", redis).map_err(map_lua_error)?;
+
+    let deadline = Instant::now() + LOAD_TIMEOUT;
+    lua.set_global_hook(
+        HookTriggers::new().every_nth_instruction(super::INTERRUPT_CHECK_INSTRUCTIONS),
+        move |_lua, _debug| {
+            if Instant::now() >= deadline {
+                return Err(mlua::Error::RuntimeError(
+                    "Library took too long to load".to_string(),
+                ));
+            }
+            Ok(VmState::Continue)
+        },
+    )
+    .map_err(map_lua_error)?;
+
+    lua.load(library.lua_source())
+        .set_name(library.name.as_str())
+        .exec()
+        .map_err(map_lua_error)?;
+
+    let mut names = registered
+        .pairs::<String, LuaValue>()
+        .map(|pair| pair.map(|(name, _)| name))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_lua_error)?;
+    if names.is_empty() {
+        return Err(SynapError::InvalidRequest(
+            "No functions registered".to_string(),
+        ));
+    }
+    names.sort();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTERS: &str = "#!lua name=counters\n\
+        redis.register_function('bump', function(keys, args) return 1 end)\n\
+        redis.register_function{function_name='peek', callback=function(keys, args) return 0 end}\n";
+
+    #[test]
+    fn load_collects_function_names() {
+        let registry = FunctionRegistry::new();
+        assert_eq!(registry.load(COUNTERS, false).unwrap(), "counters");
+
+        let libs = registry.list();
+        assert_eq!(libs.len(), 1);
+        assert_eq!(libs[0].functions, vec!["bump", "peek"]);
+        assert_eq!(registry.find("peek").unwrap().name, "counters");
+        assert!(registry.find("missing").is_none());
+    }
+
+    #[test]
+    fn load_rejects_bad_libraries() {
+        let registry = FunctionRegistry::new();
+        let err = |code: &str| registry.load(code, false).unwrap_err().to_string();
+
+        assert!(err("redis.register_function('f', function() end)").contains("#!lua"));
+        assert!(err("#!lua\nreturn 1").contains("name"));
+        assert!(err("#!lua name=empty\nlocal x = 1").contains("No functions"));
+        assert!(err("#!lua name=io\nredis.call('GET', 'k')").contains("call"));
+        assert!(err("#!lua name=spin\nwhile true do end").contains("too long"));
+
+        registry.load(COUNTERS, false).unwrap();
+        assert!(err(COUNTERS).contains("already exists"));
+        assert!(
+            err("#!lua name=other\nredis.register_function('bump', function() end)")
+                .contains("library 'counters'")
+        );
+        // REPLACE swaps the library, including dropping functions it no longer has.
+        registry
+            .load(
+                "#!lua name=counters\nredis.register_function('bump', function() end)",
+                true,
+            )
+            .unwrap();
+        assert!(registry.find("peek").is_none());
+    }
+
+    #[test]
+    fn persisted_registry_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("functions.json");
+
+        let registry = FunctionRegistry::open(&path).unwrap();
+        registry.load(COUNTERS, false).unwrap();
+        registry
+            .load(
+                "#!lua name=gone\nredis.register_function('g', function() end)",
+                false,
+            )
+            .unwrap();
+        registry.delete("gone").unwrap();
+        assert!(registry.delete("gone").is_err());
+
+        let reopened = FunctionRegistry::open(&path).unwrap();
+        let libs = reopened.list();
+        assert_eq!(libs.len(), 1);
+        assert_eq!(libs[0].code, COUNTERS);
+
+        assert_eq!(reopened.flush().unwrap(), 1);
+        assert!(FunctionRegistry::open(&path).unwrap().list().is_empty());
+    }
+}
@@ -1,19 +1,24 @@
 use std::collections::HashMap;
 use std::sync::{
     Arc,
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicU64, AtomicUsize, Ordering},
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use mlua::{Lua, Value as LuaValue, Variadic};
+use mlua::{HookTriggers, Lua, Value as LuaValue, Variadic, VmState};
 use parking_lot::RwLock;
 use sha1::{Digest, Sha1};
 use tokio::time;
 
 use crate::core::{
-    HashStore, KVStore, ListStore, ScoredMember, SetStore, SortedSetStore, SynapError, ZAddOptions,
+    HashStore, KVStore, ListStore, QueueManager, ScoredMember, SetStore, SortedSetStore,
+    StreamManager, SynapError, ZAddOptions,
 };
 
+pub mod functions;
+
+pub use functions::{FunctionLibrary, FunctionRegistry};
+
 /// Context passed into script executions for Redis-style bridge calls
 #[derive(Clone)]
 pub struct ScriptExecContext {
@@ -22,6 +27,10 @@ pub struct ScriptExecContext {
     pub list_store: Arc<ListStore>,
     pub set_store: Arc<SetStore>,
     pub sorted_set_store: Arc<SortedSetStore>,
+    /// Queue subsystem, when enabled (`QPUBLISH`/`QCONSUME` from scripts)
+    pub queue_manager: Option<Arc<QueueManager>>,
+    /// Stream subsystem, when enabled (`XADD`/`XRANGE` from scripts)
+    pub stream_manager: Option<Arc<StreamManager>>,
 }
 
 #[derive(Default)]
@@ -41,11 +50,52 @@ const DISABLED_GLOBALS: &[&str] = &[
     "debug",
 ];
 
+/// How often (in VM instructions) a running script checks for SCRIPT KILL and
+/// its deadline. Low enough that a tight loop stops within microseconds, high
+/// enough that the check does not show up in script throughput.
+const INTERRUPT_CHECK_INSTRUCTIONS: u32 = 1_000;
+
+/// Error returned to the caller of a script that was stopped by SCRIPT KILL.
+const SCRIPT_KILLED: &str = "Script killed by user with SCRIPT KILL";
+
 /// Central manager for Lua scripting support
 pub struct ScriptManager {
     cache: RwLock<HashMap<String, ScriptCacheEntry>>,
     default_timeout: Duration,
-    running: AtomicBool,
+    /// Number of scripts currently executing.
+    running: AtomicUsize,
+    /// Bumped by [`ScriptManager::kill_running`]. Each script remembers the
+    /// value it started with and aborts at its next interrupt check once the
+    /// value moves, so a kill stops every script running at that moment and
+    /// none started afterwards.
+    kill_epoch: Arc<AtomicU64>,
+    /// Named function libraries (FUNCTION LOAD / FCALL).
+    functions: FunctionRegistry,
+}
+
+/// What a script execution calls once its sandbox is set up.
+enum Entry<'a> {
+    /// The chunk itself (EVAL/EVALSHA), with KEYS and ARGV as globals.
+    Chunk,
+    /// A function the chunk registers (FCALL), called with `(keys, args)`.
+    Function(&'a str),
+}
+
+/// Keeps [`ScriptManager::running`] accurate even when an evaluation future is
+/// dropped mid-flight (e.g. the client disconnected).
+struct RunningGuard<'a>(&'a AtomicUsize);
+
+impl<'a> RunningGuard<'a> {
+    fn enter(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl ScriptManager {
@@ -53,10 +103,23 @@ impl ScriptManager {
         Self {
             cache: RwLock::new(HashMap::new()),
             default_timeout,
-            running: AtomicBool::new(false),
+            running: AtomicUsize::new(0),
+            kill_epoch: Arc::new(AtomicU64::new(0)),
+            functions: FunctionRegistry::new(),
         }
     }
 
+    /// Use `functions` as the function library registry (e.g. a persisted one).
+    pub fn with_functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Function library registry (FUNCTION LOAD/DELETE/LIST/FLUSH).
+    pub fn functions(&self) -> &FunctionRegistry {
+        &self.functions
+    }
+
     pub fn load_script(&self, source: &str) -> String {
         let sha = compute_sha1(source);
         let mut cache = self.cache.write();
@@ -80,10 +143,25 @@ impl ScriptManager {
         len
     }
 
+    /// Stop every script that is currently executing (SCRIPT KILL).
+    ///
+    /// Cancellation is cooperative: each script checks for a kill every
+    /// [`INTERRUPT_CHECK_INSTRUCTIONS`] VM instructions, so a CPU-bound loop stops
+    /// almost immediately, while a script awaiting a `redis.call` stops when that
+    /// call returns. Writes the script already made are not rolled back.
+    ///
+    /// Returns `false` when no script was running.
     pub fn kill_running(&self) -> bool {
-        // Cooperative cancellation is not implemented yet; this flag allows callers to
-        // observe whether a script was running and reset the state.
-        self.running.swap(false, Ordering::SeqCst)
+        if self.running.load(Ordering::SeqCst) == 0 {
+            return false;
+        }
+        self.kill_epoch.fetch_add(1, Ordering::SeqCst);
+        true
+    }
+
+    /// Number of scripts currently executing.
+    pub fn running_count(&self) -> usize {
+        self.running.load(Ordering::SeqCst)
     }
 
     pub async fn eval(
@@ -114,6 +192,43 @@ impl ScriptManager {
         }
         .ok_or_else(|| SynapError::InvalidRequest(format!("NOSCRIPT {}", sha)))?;
 
+        self.execute(context, &source, Entry::Chunk, keys, args, timeout)
+            .await
+    }
+
+    /// Call a function registered by a loaded library (FCALL).
+    pub async fn fcall(
+        &self,
+        context: ScriptExecContext,
+        function: &str,
+        keys: Vec<String>,
+        args: Vec<String>,
+        timeout: Option<Duration>,
+    ) -> Result<serde_json::Value, SynapError> {
+        let library = self.functions.find(function).ok_or_else(|| {
+            SynapError::InvalidRequest(format!("Function not found: {}", function))
+        })?;
+
+        self.execute(
+            context,
+            &library.lua_source(),
+            Entry::Function(function),
+            keys,
+            args,
+            timeout,
+        )
+        .await
+    }
+
+    async fn execute(
+        &self,
+        context: ScriptExecContext,
+        source: &str,
+        entry: Entry<'_>,
+        keys: Vec<String>,
+        args: Vec<String>,
+        timeout: Option<Duration>,
+    ) -> Result<serde_json::Value, SynapError> {
         let lua = Lua::new();
         apply_sandbox(&lua)?;
         let globals = lua.globals();
@@ -125,7 +240,9 @@ impl ScriptManager {
                 .set((idx + 1) as i64, key.clone())
                 .map_err(map_lua_error)?;
         }
-        globals.set("KEYS", keys_table).map_err(map_lua_error)?;
+        globals
+            .set("KEYS", keys_table.clone())
+            .map_err(map_lua_error)?;
 
         // Populate ARGV table
         let argv_table = lua.create_table().map_err(map_lua_error)?;
@@ -134,7 +251,9 @@ impl ScriptManager {
                 .set((idx + 1) as i64, arg.clone())
                 .map_err(map_lua_error)?;
         }
-        globals.set("ARGV", argv_table).map_err(map_lua_error)?;
+        globals
+            .set("ARGV", argv_table.clone())
+            .map_err(map_lua_error)?;
 
         // Inject redis.call bridge
         let redis_table = lua.create_table().map_err(map_lua_error)?;
@@ -146,19 +265,66 @@ impl ScriptManager {
             })
             .map_err(map_lua_error)?;
         redis_table.set("call", call_fn).map_err(map_lua_error)?;
+        let registered = match entry {
+            Entry::Function(_) => Some(functions::install_register_function(&lua, &redis_table)?),
+            Entry::Chunk => None,
+        };
         globals.set("redis", redis_table).map_err(map_lua_error)?;
 
-        let chunk = lua.load(source.as_str());
+        let chunk = lua.load(source);
         let function = chunk.into_function().map_err(map_lua_error)?;
 
         let duration = timeout.unwrap_or(self.default_timeout);
-        self.running.store(true, Ordering::SeqCst);
-        let future = function.call_async::<LuaValue>(());
+        let deadline = Instant::now() + duration;
+        let kill_epoch = self.kill_epoch.clone();
+        let start_epoch = kill_epoch.load(Ordering::SeqCst);
+
+        // The tokio timeout below only fires when the script yields (inside an
+        // async redis.call); a pure Lua loop never does. The instruction hook
+        // covers that case and is also where SCRIPT KILL takes effect.
+        lua.set_global_hook(
+            HookTriggers::new().every_nth_instruction(INTERRUPT_CHECK_INSTRUCTIONS),
+            move |_lua, _debug| {
+                if kill_epoch.load(Ordering::SeqCst) != start_epoch {
+                    return Err(mlua::Error::RuntimeError(SCRIPT_KILLED.to_string()));
+                }
+                if Instant::now() >= deadline {
+                    return Err(mlua::Error::RuntimeError("Script timed out".to_string()));
+                }
+                Ok(VmState::Continue)
+            },
+        )
+        .map_err(map_lua_error)?;
+
+        let _running = RunningGuard::enter(&self.running);
+        let future = async {
+            match (entry, registered) {
+                (Entry::Function(name), Some(registered)) => {
+                    // Running the library body registers its functions.
+                    function.call_async::<()>(()).await?;
+                    let callback: mlua::Function = registered.get(name)?;
+                    callback
+                        .call_async::<LuaValue>((keys_table, argv_table))
+                        .await
+                }
+                _ => function.call_async::<LuaValue>(()).await,
+            }
+        };
         let result = time::timeout(duration, future).await;
-        self.running.store(false, Ordering::SeqCst);
 
         let value = match result {
-            Ok(inner) => inner.map_err(map_lua_error)?,
+            Ok(Ok(value)) => value,
+            Ok(Err(err)) => {
+                // Classify by what actually happened rather than by the error
+                // text, which the script may have wrapped or rethrown.
+                if self.kill_epoch.load(Ordering::SeqCst) != start_epoch {
+                    return Err(SynapError::InvalidRequest(SCRIPT_KILLED.to_string()));
+                }
+                if Instant::now() >= deadline {
+                    return Err(SynapError::Timeout);
+                }
+                return Err(map_lua_error(err));
+            }
             Err(_) => return Err(SynapError::Timeout),
         };
 
@@ -460,7 +626,10 @@ async fn handle_redis_call(
             while idx < args.len() {
                 let score = lua_value_to_f64(&args[idx], &command_name)?;
                 let member = lua_value_to_bytes(&args[idx + 1])?;
-                let (a, _) = context.sorted_set_store.zadd(&key, member, score, &opts);
+                let (a, _) = context
+                    .sorted_set_store
+                    .try_zadd(&key, member, score, &opts)
+                    .map_err(synap_err_to_lua)?;
                 added += a as i64;
                 idx += 2;
             }
@@ -494,7 +663,10 @@ async fn handle_redis_call(
             let key = lua_value_to_string(&args[0], &command_name)?;
             let increment = lua_value_to_f64(&args[1], &command_name)?;
             let member = lua_value_to_bytes(&args[2])?;
-            let new_score = context.sorted_set_store.zincrby(&key, member, increment);
+            let new_score = context
+                .sorted_set_store
+                .try_zincrby(&key, member, increment)
+                .map_err(synap_err_to_lua)?;
             Ok(LuaValue::String(lua.create_string(new_score.to_string())?))
         }
         "zcount" => {
@@ -609,6 +781,131 @@ async fn handle_redis_call(
             let scores = context.sorted_set_store.zmscore(&key, &members);
             optional_scores_to_lua(lua, scores)
         }
+        "qpublish" => {
+            // QPUBLISH <queue> <payload> [PRIORITY <n>] [MAXRETRIES <n>]
+            ensure_min_args(&args, 2, &command_name)?;
+            let queue = lua_value_to_string(&args[0], &command_name)?;
+            let payload = lua_value_to_bytes(&args[1])?;
+            let mut priority = None;
+            let mut max_retries = None;
+            let mut idx = 2;
+            while idx + 1 < args.len() {
+                let option = lua_value_to_string(&args[idx], &command_name)?;
+                match option.to_ascii_uppercase().as_str() {
+                    "PRIORITY" => {
+                        let value = lua_value_to_u64(&args[idx + 1], &command_name)?;
+                        priority = Some(u8::try_from(value).map_err(|_| {
+                            mlua::Error::RuntimeError(format!(
+                                "{} priority must be between 0 and 255",
+                                command_name
+                            ))
+                        })?);
+                    }
+                    "MAXRETRIES" => {
+                        let value = lua_value_to_u64(&args[idx + 1], &command_name)?;
+                        max_retries = Some(u32::try_from(value).map_err(|_| {
+                            mlua::Error::RuntimeError(format!(
+                                "{} max retries out of range",
+                                command_name
+                            ))
+                        })?);
+                    }
+                    other => {
+                        return Err(mlua::Error::RuntimeError(format!(
+                            "{} unknown option {}",
+                            command_name, other
+                        )));
+                    }
+                }
+                idx += 2;
+            }
+            let message_id = queue_manager(&context, &command_name)?
+                .publish(&queue, payload, priority, max_retries)
+                .await
+                .map_err(synap_err_to_lua)?;
+            Ok(LuaValue::String(lua.create_string(message_id)?))
+        }
+        "qconsume" => {
+            // QCONSUME <queue> [consumer_id] -> {id, payload, priority, retry_count} | nil
+            ensure_min_args(&args, 1, &command_name)?;
+            let queue = lua_value_to_string(&args[0], &command_name)?;
+            let consumer_id = match args.get(1) {
+                Some(value) => lua_value_to_string(value, &command_name)?,
+                None => "lua".to_string(),
+            };
+            let message = queue_manager(&context, &command_name)?
+                .consume(&queue, &consumer_id)
+                .await
+                .map_err(synap_err_to_lua)?;
+            match message {
+                Some(msg) => {
+                    let table = lua.create_table()?;
+                    table.set(1, lua.create_string(&msg.id)?)?;
+                    table.set(2, lua.create_string(&msg.payload[..])?)?;
+                    table.set(3, msg.priority as i64)?;
+                    table.set(4, msg.retry_count as i64)?;
+                    Ok(LuaValue::Table(table))
+                }
+                None => Ok(LuaValue::Nil),
+            }
+        }
+        "xadd" => {
+            // XADD <room> <id> <event> <data> — the id is ignored (offsets are
+            // assigned by the room), matching the RESP3 command.
+            ensure_min_args(&args, 4, &command_name)?;
+            let room = lua_value_to_string(&args[0], &command_name)?;
+            let event = lua_value_to_string(&args[2], &command_name)?;
+            let data = lua_value_to_bytes(&args[3])?;
+            let offset = stream_manager(&context, &command_name)?
+                .publish(&room, &event, data)
+                .await
+                .map_err(mlua::Error::RuntimeError)?;
+            Ok(LuaValue::String(lua.create_string(offset.to_string())?))
+        }
+        "xrange" => {
+            // XRANGE <room> <start> <end> [COUNT <n>] -> {{offset, {event, data}}, ...}
+            ensure_min_args(&args, 3, &command_name)?;
+            let room = lua_value_to_string(&args[0], &command_name)?;
+            let start = lua_value_to_string(&args[1], &command_name)?;
+            let from_offset = if start == "-" {
+                0
+            } else {
+                lua_value_to_u64(&args[1], &command_name)?
+            };
+            let end = lua_value_to_string(&args[2], &command_name)?;
+            let to_offset = if end == "+" {
+                u64::MAX
+            } else {
+                lua_value_to_u64(&args[2], &command_name)?
+            };
+            let limit = if args.len() >= 5
+                && lua_value_to_string(&args[3], &command_name)?.eq_ignore_ascii_case("count")
+            {
+                lua_value_to_usize(&args[4], &command_name)?
+            } else {
+                100
+            };
+            let events = stream_manager(&context, &command_name)?
+                .consume(&room, "lua-range", from_offset, limit)
+                .await
+                .map_err(mlua::Error::RuntimeError)?;
+
+            let table = lua.create_table()?;
+            for (idx, event) in events
+                .into_iter()
+                .filter(|event| event.offset <= to_offset)
+                .enumerate()
+            {
+                let fields = lua.create_table()?;
+                fields.set(1, lua.create_string(event.event)?)?;
+                fields.set(2, lua.create_string(event.data)?)?;
+                let entry = lua.create_table()?;
+                entry.set(1, lua.create_string(event.offset.to_string())?)?;
+                entry.set(2, fields)?;
+                table.set((idx + 1) as i64, entry)?;
+            }
+            Ok(LuaValue::Table(table))
+        }
         _ => Err(mlua::Error::RuntimeError(format!(
             "redis.call does not support command {}",
             command_name
@@ -616,6 +913,24 @@ async fn handle_redis_call(
     }
 }
 
+fn queue_manager<'a>(
+    context: &'a ScriptExecContext,
+    command: &str,
+) -> Result<&'a Arc<QueueManager>, mlua::Error> {
+    context.queue_manager.as_ref().ok_or_else(|| {
+        mlua::Error::RuntimeError(format!("{} queue subsystem not enabled", command))
+    })
+}
+
+fn stream_manager<'a>(
+    context: &'a ScriptExecContext,
+    command: &str,
+) -> Result<&'a Arc<StreamManager>, mlua::Error> {
+    context.stream_manager.as_ref().ok_or_else(|| {
+        mlua::Error::RuntimeError(format!("{} stream subsystem not enabled", command))
+    })
+}
+
 fn ensure_min_args(
     args: &Variadic<LuaValue>,
     required: usize,
@@ -881,6 +1196,8 @@ mod tests {
             list_store: Arc::new(ListStore::new()),
             set_store: Arc::new(SetStore::new()),
             sorted_set_store: Arc::new(SortedSetStore::new()),
+            queue_manager: None,
+            stream_manager: None,
         }
     }
 
@@ -984,6 +1301,61 @@ mod tests {
         assert_eq!(val[3], serde_json::json!(1));
     }
 
+    #[tokio::test]
+    async fn eval_bridges_queue_and_stream_commands() {
+        use crate::core::{QueueConfig, QueueManager, StreamConfig, StreamManager};
+
+        let mgr = ScriptManager::new(Duration::from_secs(5));
+        let queues = Arc::new(QueueManager::new(QueueConfig::default()));
+        queues.create_queue("jobs", None).await.unwrap();
+        let streams = Arc::new(StreamManager::new(StreamConfig::default()));
+        streams.create_room("events").await.unwrap();
+
+        let mut c = ctx();
+        c.queue_manager = Some(queues.clone());
+        c.stream_manager = Some(streams.clone());
+
+        let (val, _) = mgr
+            .eval(
+                c,
+                r#"
+                    redis.call('SET', 'order:1', 'paid')
+                    local id = redis.call('QPUBLISH', 'jobs', 'ship:1', 'PRIORITY', '7')
+                    redis.call('XADD', 'events', '*', 'order.paid', 'order:1')
+                    redis.call('XADD', 'events', '*', 'order.shipped', 'order:1')
+                    local msg = redis.call('QCONSUME', 'jobs', 'worker')
+                    local range = redis.call('XRANGE', 'events', '-', '+')
+                    return {id == msg[1], msg[2], msg[3], #range, range[2][2][1]}
+                "#,
+                vec![],
+                vec![],
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            val,
+            serde_json::json!([true, "ship:1", 7, 2, "order.shipped"])
+        );
+        assert_eq!(streams.room_stats("events").await.unwrap().message_count, 2);
+    }
+
+    #[tokio::test]
+    async fn eval_queue_commands_require_subsystem() {
+        let mgr = ScriptManager::new(Duration::from_secs(5));
+        let err = mgr
+            .eval(
+                ctx(),
+                "return redis.call('QPUBLISH', 'jobs', 'x')",
+                vec![],
+                vec![],
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("queue subsystem not enabled"));
+    }
+
     #[tokio::test]
     async fn evalsha_unknown_hash_is_noscript() {
         let mgr = ScriptManager::new(Duration::from_secs(5));
@@ -994,6 +1366,40 @@ mod tests {
         assert!(format!("{err}").contains("NOSCRIPT"));
     }
 
+    #[tokio::test]
+    async fn fcall_runs_library_function() {
+        let mgr = ScriptManager::new(Duration::from_secs(5));
+        let c = ctx();
+        mgr.functions()
+            .load(
+                "#!lua name=counters\n\
+                 local function step(n) return tonumber(n) or 1 end\n\
+                 redis.register_function('bump', function(keys, args)\n\
+                   return redis.call('INCRBY', keys[1], step(args[1]))\n\
+                 end)",
+                false,
+            )
+            .unwrap();
+
+        let call =
+            |args: Vec<String>| mgr.fcall(c.clone(), "bump", vec!["hits".to_string()], args, None);
+        assert_eq!(
+            call(vec!["5".to_string()]).await.unwrap(),
+            serde_json::json!(5)
+        );
+        assert_eq!(call(vec![]).await.unwrap(), serde_json::json!(6));
+
+        let err = mgr
+            .fcall(c.clone(), "missing", vec![], vec![], None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Function not found"));
+
+        // SCRIPT FLUSH leaves function libraries alone.
+        mgr.flush();
+        assert!(mgr.functions().find("bump").is_some());
+    }
+
     #[tokio::test]
     async fn load_exists_flush_kill() {
         let mgr = ScriptManager::new(Duration::from_secs(5));
@@ -1017,6 +1423,51 @@ mod tests {
         assert!(!mgr.kill_running());
     }
 
+    #[tokio::test]
+    async fn busy_loop_hits_timeout() {
+        let mgr = ScriptManager::new(Duration::from_secs(5));
+        let err = mgr
+            .eval(
+                ctx(),
+                "while true do end",
+                vec![],
+                vec![],
+                Some(Duration::from_millis(50)),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SynapError::Timeout));
+        assert_eq!(mgr.running_count(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn kill_stops_running_script() {
+        let mgr = Arc::new(ScriptManager::new(Duration::from_secs(30)));
+        let runner = {
+            let mgr = mgr.clone();
+            tokio::spawn(async move {
+                mgr.eval(ctx(), "while true do end", vec![], vec![], None)
+                    .await
+            })
+        };
+
+        while mgr.running_count() == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(mgr.kill_running());
+
+        let err = tokio::time::timeout(Duration::from_secs(5), runner)
+            .await
+            .expect("killed script should stop promptly")
+            .unwrap()
+            .unwrap_err();
+        assert!(err.to_string().contains("SCRIPT KILL"));
+        assert_eq!(mgr.running_count(), 0);
+
+        // A kill only affects scripts that were running at the time.
+        assert_eq!(eval(&mgr, ctx(), "return 1").await, serde_json::json!(1));
+    }
+
     #[tokio::test]
     async fn sandbox_blocks_dangerous_globals() {
         let mgr = ScriptManager::new(Duration::from_secs(5));
@@ -0,0 +1,164 @@
+//! Audit records for REST requests.
+//!
+//! Admin endpoints (snapshots, failover, cluster changes, script and function
+//! flushes, ...) are always recorded as [`AuthEventType::AdminOperation`].
+//! Any other `POST`, `PUT`, `PATCH` or `DELETE` is recorded as
+//! [`AuthEventType::Write`] when `auth.audit.log_writes` is set.
+//!
+//! `/api/v1/command` is left to the command handler, which knows the command
+//! name, and `/auth/*` to the auth handlers, which record the user or key
+//! that changed.
+//!
+//! The same writes are handed to the command hooks by [`hook`].
+
+use crate::auth::{AuditLogEntry, AuditLogManager, AuthContext, AuthEventType};
+use crate::monitoring::{CommandHooks, CommandProtocol, CommandRecord};
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Admin endpoints, by method and route
+const ADMIN_ROUTES: &[(&str, &str)] = &[
+    ("POST", "/snapshot"),
+    ("POST", "/warmup"),
+    ("DELETE", "/deprecations"),
+    ("POST", "/replication/failover/announce"),
+    ("POST", "/script/flush"),
+    ("POST", "/script/kill"),
+    ("POST", "/function/flush"),
+    ("POST", "/function/delete"),
+    ("PUT", "/config"),
+    ("POST", "/clients/kill"),
+    ("POST", "/clients/pause"),
+    ("POST", "/clients/unpause"),
+];
+
+/// How a request to `route` is audited, if at all
+fn event_type(method: &Method, route: &str, log_writes: bool) -> Option<AuthEventType> {
+    let is_admin = ADMIN_ROUTES
+        .iter()
+        .any(|&(m, r)| m == method.as_str() && r == route)
+        || (route.starts_with("/cluster/") && *method != Method::GET);
+    if is_admin {
+        return Some(AuthEventType::AdminOperation);
+    }
+
+    (log_writes && is_write(method, route)).then_some(AuthEventType::Write)
+}
+
+/// True for a write to a REST route not recorded by its handler
+fn is_write(method: &Method, route: &str) -> bool {
+    let is_write = matches!(
+        *method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let recorded_elsewhere = route == "/api/v1/command" || route.starts_with("/auth/");
+    is_write && !recorded_elsewhere
+}
+
+/// Middleware: record admin requests, and writes when enabled, with the
+/// caller and whether the request succeeded.
+///
+/// Must run inside the auth middleware so the caller is known.
+pub async fn track(
+    State(audit_log): State<Arc<AuditLogManager>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !audit_log.is_enabled() {
+        return next.run(req).await;
+    }
+    let audited = req.extensions().get::<MatchedPath>().and_then(|route| {
+        event_type(req.method(), route.as_str(), audit_log.logs_writes())
+            .map(|event_type| (event_type, format!("{} {}", req.method(), route.as_str())))
+    });
+    let (Some((event_type, action)), Some(ctx)) = (audited, req.extensions().get::<AuthContext>())
+    else {
+        return next.run(req).await;
+    };
+    let ctx = ctx.clone();
+    let path = req.uri().path().to_string();
+
+    let response = next.run(req).await;
+    audit_log
+        .log(AuditLogEntry::operation(
+            event_type,
+            &ctx,
+            action,
+            Some(path),
+            response.status().is_success(),
+        ))
+        .await;
+    response
+}
+
+/// Middleware: hand REST writes to the command hooks once they have run,
+/// with the caller and the response status.
+///
+/// Must run inside the auth middleware so the caller is known.
+pub async fn hook(State(hooks): State<Arc<CommandHooks>>, req: Request, next: Next) -> Response {
+    if !hooks.is_active() {
+        return next.run(req).await;
+    }
+    let command = req
+        .extensions()
+        .get::<MatchedPath>()
+        .filter(|route| is_write(req.method(), route.as_str()))
+        .map(|route| format!("{} {}", req.method(), route.as_str()));
+    let (Some(command), Some(ctx)) = (command, req.extensions().get::<AuthContext>()) else {
+        return next.run(req).await;
+    };
+    let ctx = ctx.clone();
+    let path = req.uri().path().to_string();
+
+    let started = Instant::now();
+    let response = next.run(req).await;
+    let mut record = CommandRecord::new(
+        CommandProtocol::Http,
+        command,
+        ctx.client_ip.to_string(),
+        started.elapsed(),
+    );
+    record.resource = Some(path);
+    record.user = ctx.user_id;
+    record.api_key_id = ctx.api_key_id;
+    record.success = response.status().is_success();
+    if !record.success {
+        record.error = Some(response.status().to_string());
+    }
+    hooks.emit(record);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_routes_are_always_audited() {
+        assert_eq!(
+            event_type(&Method::POST, "/snapshot", false),
+            Some(AuthEventType::AdminOperation)
+        );
+        assert_eq!(
+            event_type(&Method::POST, "/cluster/slots/assign", false),
+            Some(AuthEventType::AdminOperation)
+        );
+        assert_eq!(event_type(&Method::GET, "/cluster/nodes", true), None);
+    }
+
+    #[test]
+    fn test_writes_are_audited_when_enabled() {
+        assert_eq!(event_type(&Method::POST, "/kv/set", false), None);
+        assert_eq!(
+            event_type(&Method::POST, "/kv/set", true),
+            Some(AuthEventType::Write)
+        );
+        assert_eq!(event_type(&Method::GET, "/kv/get/{key}", true), None);
+        assert_eq!(event_type(&Method::POST, "/api/v1/command", true), None);
+        assert_eq!(event_type(&Method::POST, "/auth/users", true), None);
+    }
+}
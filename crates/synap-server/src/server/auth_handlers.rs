@@ -2,7 +2,10 @@
 //!
 //! Handlers for user management, API key management, and authentication
 
-use crate::auth::{Action, ApiKeyManager, AuthContextExtractor, Permission, UserManager};
+use crate::auth::{
+    Action, ApiKeyManager, AuditLogEntry, AuditLogManager, AuthContext, AuthContextExtractor,
+    AuthEventType, Permission, TokenPair, UserManager,
+};
 use crate::core::SynapError;
 use axum::{
     Json,
@@ -18,6 +21,28 @@ use tracing::debug;
 pub struct AuthState {
     pub user_manager: Arc<UserManager>,
     pub api_key_manager: Arc<ApiKeyManager>,
+    pub audit_log: Arc<AuditLogManager>,
+}
+
+impl AuthState {
+    /// Record a user or API key change made by `ctx`
+    async fn audit(
+        &self,
+        event_type: AuthEventType,
+        ctx: &AuthContext,
+        action: &str,
+        resource: String,
+    ) {
+        self.audit_log
+            .log(AuditLogEntry::operation(
+                event_type,
+                ctx,
+                action,
+                Some(resource),
+                true,
+            ))
+            .await;
+    }
 }
 
 // ==================== Authentication Endpoints ====================
@@ -35,6 +60,22 @@ pub struct LoginResponse {
     pub success: bool,
     pub user: Option<UserInfo>,
     pub message: String,
+    /// Access and refresh tokens, when login tokens are enabled
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<TokenPair>,
+}
+
+/// Refresh or logout request
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// Logout response
+#[derive(Debug, Serialize)]
+pub struct LogoutResponse {
+    /// Whether the refresh token was outstanding
+    pub revoked: bool,
 }
 
 /// User info (without password)
@@ -49,6 +90,7 @@ pub struct UserInfo {
 /// POST /auth/login - Login with username/password
 pub async fn auth_login(
     State(state): State<AuthState>,
+    AuthContextExtractor(auth_context): AuthContextExtractor,
     Json(req): Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>, SynapError> {
     debug!("Login attempt for user: {}", req.username);
@@ -64,24 +106,94 @@ pub async fn auth_login(
                 is_admin: user.is_admin,
                 enabled: user.enabled,
             };
+            let tokens = state
+                .user_manager
+                .tokens()
+                .map(|tokens| tokens.issue(&user.username))
+                .transpose()?;
+            state
+                .audit_log
+                .log(AuditLogEntry::login_success(
+                    user.username.clone(),
+                    auth_context.client_ip.to_string(),
+                ))
+                .await;
 
             Ok(Json(LoginResponse {
                 success: true,
                 user: Some(user_info),
                 message: "Login successful".to_string(),
+                tokens,
             }))
         }
         Err(e) => {
             debug!("Login failed for user {}: {}", req.username, e);
+            state
+                .audit_log
+                .log(AuditLogEntry::login_failure(
+                    Some(req.username),
+                    auth_context.client_ip.to_string(),
+                    e.to_string(),
+                ))
+                .await;
             Ok(Json(LoginResponse {
                 success: false,
                 user: None,
                 message: "Invalid credentials".to_string(),
+                tokens: None,
             }))
         }
     }
 }
 
+/// POST /auth/refresh - Exchange a refresh token for a new token pair
+pub async fn auth_refresh(
+    State(state): State<AuthState>,
+    Json(req): Json<RefreshRequest>,
+) -> Result<Json<TokenPair>, SynapError> {
+    let tokens = state
+        .user_manager
+        .tokens()
+        .ok_or_else(|| SynapError::InvalidRequest("Login tokens are not enabled".to_string()))?;
+
+    // The old refresh token is spent even if the account was disabled since
+    let username = tokens.redeem(&req.refresh_token)?;
+    match state.user_manager.get_user(&username) {
+        Some(user) if user.enabled => Ok(Json(tokens.issue(&username)?)),
+        _ => {
+            debug!(
+                "Refresh rejected for missing or disabled user: {}",
+                username
+            );
+            Err(SynapError::Unauthorized("Account disabled".to_string()))
+        }
+    }
+}
+
+/// POST /auth/logout - Revoke a refresh token
+pub async fn auth_logout(
+    State(state): State<AuthState>,
+    AuthContextExtractor(auth_context): AuthContextExtractor,
+    Json(req): Json<RefreshRequest>,
+) -> Result<Json<LogoutResponse>, SynapError> {
+    let revoked = state
+        .user_manager
+        .tokens()
+        .is_some_and(|tokens| tokens.revoke(&req.refresh_token));
+    if revoked {
+        state
+            .audit_log
+            .log(AuditLogEntry::new(
+                AuthEventType::Logout,
+                auth_context.user_id,
+                auth_context.client_ip.to_string(),
+                true,
+            ))
+            .await;
+    }
+    Ok(Json(LogoutResponse { revoked }))
+}
+
 /// GET /auth/me - Get current user info
 pub async fn auth_me(
     State(_state): State<AuthState>,
@@ -185,6 +297,14 @@ pub async fn auth_create_key(
             .api_key_manager
             .create(req.name, username, permissions, allowed_ips, None)?
     };
+    state
+        .audit(
+            AuthEventType::ApiKeyCreated,
+            &auth_context,
+            "create_api_key",
+            format!("api_key:{}", api_key.id),
+        )
+        .await;
 
     Ok(Json(CreateKeyResponse {
         success: true,
@@ -281,6 +401,16 @@ pub async fn auth_revoke_key(
     }
 
     let revoked = state.api_key_manager.revoke(&key_id)?;
+    if revoked {
+        state
+            .audit(
+                AuthEventType::ApiKeyRevoked,
+                &auth_context,
+                "revoke_api_key",
+                format!("api_key:{key_id}"),
+            )
+            .await;
+    }
 
     Ok(Json(RevokeKeyResponse {
         success: revoked,
@@ -343,6 +473,14 @@ pub async fn auth_create_user(
             state.user_manager.add_user_role(&req.username, &role)?;
         }
     }
+    state
+        .audit(
+            AuthEventType::UserCreated,
+            &auth_context,
+            "create_user",
+            format!("user:{}", req.username),
+        )
+        .await;
 
     Ok(Json(CreateUserResponse {
         success: true,
@@ -441,6 +579,16 @@ pub async fn auth_delete_user(
     }
 
     let deleted = state.user_manager.delete_user(&username)?;
+    if deleted {
+        state
+            .audit(
+                AuthEventType::UserDeleted,
+                &auth_context,
+                "delete_user",
+                format!("user:{username}"),
+            )
+            .await;
+    }
 
     Ok(Json(DeleteUserResponse {
         success: deleted,
@@ -484,6 +632,14 @@ pub async fn auth_change_password(
     state
         .user_manager
         .change_password(&username, &req.new_password)?;
+    state
+        .audit(
+            AuthEventType::PasswordChanged,
+            &auth_context,
+            "change_password",
+            format!("user:{username}"),
+        )
+        .await;
 
     Ok(Json(ChangePasswordResponse {
         success: true,
@@ -519,6 +675,14 @@ pub async fn auth_enable_user(
     }
 
     state.user_manager.set_user_enabled(&username, true)?;
+    state
+        .audit(
+            AuthEventType::UserEnabled,
+            &auth_context,
+            "enable_user",
+            format!("user:{username}"),
+        )
+        .await;
 
     Ok(Json(SetUserEnabledResponse {
         success: true,
@@ -541,6 +705,14 @@ pub async fn auth_disable_user(
     }
 
     state.user_manager.set_user_enabled(&username, false)?;
+    state
+        .audit(
+            AuthEventType::UserDisabled,
+            &auth_context,
+            "disable_user",
+            format!("user:{username}"),
+        )
+        .await;
 
     Ok(Json(SetUserEnabledResponse {
         success: true,
@@ -579,6 +751,14 @@ pub async fn auth_grant_role(
     }
 
     state.user_manager.add_user_role(&username, &req.role)?;
+    state
+        .audit(
+            AuthEventType::RoleChanged,
+            &auth_context,
+            &format!("grant_role:{}", req.role),
+            format!("user:{username}"),
+        )
+        .await;
 
     Ok(Json(GrantRoleResponse {
         success: true,
@@ -601,6 +781,14 @@ pub async fn auth_revoke_role(
     }
 
     state.user_manager.remove_user_role(&username, &role)?;
+    state
+        .audit(
+            AuthEventType::RoleChanged,
+            &auth_context,
+            &format!("revoke_role:{role}"),
+            format!("user:{username}"),
+        )
+        .await;
 
     Ok(Json(GrantRoleResponse {
         success: true,
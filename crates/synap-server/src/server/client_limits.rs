@@ -0,0 +1,55 @@
+//! CLIENT PAUSE and per-connection limits on HTTP requests.
+//!
+//! [`enforce`] holds a request back while a pause applies to it, and answers
+//! 429 once its connection already has `clients.max_in_flight` requests
+//! running. `/api/v1/command` is paused by its handler instead, which knows
+//! whether the command writes. `/clients/*` and `/health` are never paused, so
+//! a pause can always be lifted and probes keep answering.
+
+use crate::monitoring::ClientListManager;
+use axum::Json;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde_json::json;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Middleware: wait out CLIENT PAUSE, then count the request against its
+/// connection's in-flight limit
+pub async fn enforce(
+    State(clients): State<Arc<ClientListManager>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let path = req.uri().path();
+    let exempt =
+        path.starts_with("/clients") || path.starts_with("/health") || path == "/api/v1/command";
+    if !exempt {
+        let write = !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+        clients.wait_unpaused(write).await;
+    }
+
+    let _in_flight = if clients.limits().max_in_flight > 0
+        && let Some(ConnectInfo(addr)) = req.extensions().get::<ConnectInfo<SocketAddr>>()
+    {
+        match clients.begin_request(*addr) {
+            Some(guard) => Some(guard),
+            None => {
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    Json(json!({
+                        "error": "Too many requests in flight on this connection",
+                        "code": StatusCode::TOO_MANY_REQUESTS.as_u16(),
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    } else {
+        None
+    };
+
+    next.run(req).await
+}
@@ -0,0 +1,103 @@
+//! Deprecation notices on HTTP responses.
+//!
+//! A request that uses a deprecated surface (see
+//! [`DEPRECATIONS`](crate::monitoring::DEPRECATIONS)) still succeeds, but the
+//! response carries:
+//!
+//! - `Deprecation: true`,
+//! - `Sunset: <HTTP-date>` when a removal date is set,
+//! - one `Warning: 299 - "..."` per deprecated surface used, naming the
+//!   replacement,
+//!
+//! and the use is counted against the calling API key in the
+//! [`DeprecationTracker`], which backs the `GET /deprecations` report.
+//!
+//! Endpoints are matched by route in [`track`]. Commands and request fields are
+//! only known to their handler, which calls [`notice`]; the notice travels back
+//! to [`track`] through a task-local, like the request id does.
+
+use crate::auth::AuthContext;
+use crate::monitoring::{Deprecation, DeprecationTracker, lookup, lookup_route};
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{HeaderName, HeaderValue, header};
+use axum::middleware::Next;
+use axum::response::Response;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// `Deprecation` response header (RFC 9745)
+pub static DEPRECATION_HEADER: HeaderName = HeaderName::from_static("deprecation");
+/// `Sunset` response header (RFC 8594)
+pub static SUNSET_HEADER: HeaderName = HeaderName::from_static("sunset");
+
+tokio::task_local! {
+    static NOTICES: RefCell<Vec<&'static Deprecation>>;
+}
+
+/// Report that the request being handled used the deprecated `surface`.
+///
+/// Does nothing outside an HTTP request (RESP3, SynapRPC, background tasks).
+pub fn notice(surface: &str) {
+    let Some(deprecation) = lookup(surface) else {
+        debug_assert!(false, "unregistered deprecated surface: {surface}");
+        return;
+    };
+    let _ = NOTICES.try_with(|notices| {
+        let mut notices = notices.borrow_mut();
+        if !notices.contains(&deprecation) {
+            notices.push(deprecation);
+        }
+    });
+}
+
+/// Who made the request, for usage attribution
+fn client_id(req: &Request) -> String {
+    req.extensions()
+        .get::<AuthContext>()
+        .map_or_else(|| "unknown".to_string(), AuthContext::client_id)
+}
+
+/// Middleware: collect the deprecated surfaces a request used, record them
+/// and add the deprecation headers to the response.
+///
+/// Must run inside the auth middleware so the caller is known.
+pub async fn track(
+    State(tracker): State<Arc<DeprecationTracker>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| lookup_route(req.method().as_str(), path.as_str()));
+    let client = client_id(&req);
+
+    let notices = RefCell::new(route.into_iter().collect::<Vec<_>>());
+    let (mut response, notices) = NOTICES
+        .scope(notices, async {
+            let response = next.run(req).await;
+            (response, NOTICES.with(|n| n.take()))
+        })
+        .await;
+    if notices.is_empty() {
+        return response;
+    }
+
+    let headers = response.headers_mut();
+    headers.insert(DEPRECATION_HEADER.clone(), HeaderValue::from_static("true"));
+    for deprecation in notices {
+        tracker.record(deprecation, &client);
+
+        if let Some(sunset) = deprecation.sunset {
+            headers.insert(SUNSET_HEADER.clone(), HeaderValue::from_static(sunset));
+        }
+        let warning = format!(
+            "299 - \"{} is deprecated, use {}\"",
+            deprecation.surface, deprecation.replacement
+        );
+        if let Ok(value) = HeaderValue::from_str(&warning) {
+            headers.append(header::WARNING, value);
+        }
+    }
+    response
+}
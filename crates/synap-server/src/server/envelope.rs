@@ -1,14 +1,27 @@
+//! StreamableHTTP request/response envelope.
+
 use serde::{Deserialize, Serialize};
 
+pub use crate::core::{ErrorCategory, ErrorInfo};
+
 /// StreamableHTTP request envelope
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Request {
     /// Command to execute (e.g., "kv.set", "kv.get")
     pub command: String,
-    /// Unique request identifier
+    /// Unique request identifier. When omitted, the server uses the request's
+    /// `X-Request-Id` so the envelope, logs and WAL all agree.
+    #[serde(default)]
     pub request_id: String,
     /// Command payload
     pub payload: serde_json::Value,
+    /// Logical database the command runs in; 0 when omitted
+    #[serde(default, skip_serializing_if = "is_default_db")]
+    pub db: u32,
+}
+
+fn is_default_db(db: &u32) -> bool {
+    *db == 0
 }
 
 /// StreamableHTTP response envelope
@@ -22,6 +35,10 @@ pub struct Response {
     pub payload: Option<serde_json::Value>,
     /// Error message (if failed)
     pub error: Option<String>,
+    /// Machine-readable form of `error`, for branching without parsing the
+    /// message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_info: Option<ErrorInfo>,
 }
 
 impl Request {
@@ -31,8 +48,15 @@ impl Request {
             command: command.into(),
             request_id: uuid::Uuid::new_v4().to_string(),
             payload,
+            db: 0,
         }
     }
+
+    /// Run the request in logical database `db`
+    pub fn with_db(mut self, db: u32) -> Self {
+        self.db = db;
+        self
+    }
 }
 
 impl Response {
@@ -43,6 +67,7 @@ impl Response {
             request_id,
             payload: Some(payload),
             error: None,
+            error_info: None,
         }
     }
 
@@ -53,6 +78,18 @@ impl Response {
             request_id,
             payload: None,
             error: Some(error.into()),
+            error_info: None,
+        }
+    }
+
+    /// Create an error response carrying a structured [`ErrorInfo`]
+    pub fn failure(request_id: String, info: ErrorInfo) -> Self {
+        Self {
+            success: false,
+            request_id,
+            payload: None,
+            error: Some(info.message.clone()),
+            error_info: Some(info),
         }
     }
 }
@@ -85,6 +122,19 @@ mod tests {
         assert_eq!(request.payload, deserialized.payload);
     }
 
+    #[test]
+    fn test_request_db_defaults_to_zero() {
+        let request: Request =
+            serde_json::from_value(json!({"command": "kv.get", "payload": {}})).unwrap();
+        assert_eq!(request.db, 0);
+
+        let serialized = serde_json::to_value(Request::new("kv.get", json!({}))).unwrap();
+        assert!(serialized.get("db").is_none());
+        let serialized =
+            serde_json::to_value(Request::new("kv.get", json!({})).with_db(3)).unwrap();
+        assert_eq!(serialized["db"], 3);
+    }
+
     #[test]
     fn test_response_success() {
         let request_id = "req-123".to_string();
@@ -122,6 +172,39 @@ mod tests {
         assert_eq!(response.error, deserialized.error);
     }
 
+    #[test]
+    fn test_response_failure_carries_error_info() {
+        let info = ErrorInfo {
+            code: "KEY_NOT_FOUND".to_string(),
+            category: ErrorCategory::NotFound,
+            message: "Key not found: k".to_string(),
+            retryable: false,
+        };
+        let response = Response::failure("req-1".to_string(), info.clone());
+        assert!(!response.success);
+        assert_eq!(response.error.as_deref(), Some("Key not found: k"));
+
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value["error_info"]["code"], "KEY_NOT_FOUND");
+        assert_eq!(value["error_info"]["category"], "not_found");
+        assert_eq!(value["error_info"]["retryable"], false);
+
+        let decoded: Response = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.error_info, Some(info));
+    }
+
+    #[test]
+    fn test_response_without_error_info() {
+        let value = serde_json::to_value(Response::error("req-2".to_string(), "boom")).unwrap();
+        assert!(value.get("error_info").is_none());
+
+        let decoded: Response = serde_json::from_value(
+            json!({"success": false, "request_id": "r", "payload": null, "error": "boom"}),
+        )
+        .unwrap();
+        assert!(decoded.error_info.is_none());
+    }
+
     #[test]
     fn test_request_unique_ids() {
         let req1 = Request::new("test", json!({}));
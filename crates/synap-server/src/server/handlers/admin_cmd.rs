@@ -39,15 +39,18 @@ pub(super) async fn handle_info_cmd(
     }
 
     if section == InfoSection::All || section == InfoSection::Replication {
-        let repl_info = ReplicationInfo::collect(state.replication.as_ref()).await;
+        let repl_info = ReplicationInfo::collect(state.replication_handle().as_ref()).await;
         response["replication"] = serde_json::to_value(repl_info)
             .map_err(|e| SynapError::SerializationError(e.to_string()))?;
     }
 
     if section == InfoSection::All || section == InfoSection::Keyspace {
         let stores = state.monitoring.stores();
-        let keyspace_info =
+        let mut keyspace_info =
             KeyspaceInfo::collect(stores.0, stores.1, stores.2, stores.3, stores.4).await;
+        keyspace_info
+            .add_databases(&state.monitoring.databases())
+            .await;
         response["keyspace"] = serde_json::to_value(keyspace_info)
             .map_err(|e| SynapError::SerializationError(e.to_string()))?;
     }
@@ -90,6 +93,140 @@ pub(super) async fn handle_slowlog_reset_cmd(
     }))
 }
 
+pub(super) async fn handle_slowlog_len_cmd(
+    state: AppState,
+    _request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    Ok(serde_json::json!({
+        "len": state.monitoring.slow_log().len().await
+    }))
+}
+
+pub(super) async fn handle_slowlog_config_get_cmd(
+    state: AppState,
+    _request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    serde_json::to_value(state.monitoring.slow_log().config())
+        .map_err(|e| SynapError::SerializationError(e.to_string()))
+}
+
+pub(super) async fn handle_slowlog_config_set_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let field = |name: &str| match request.payload.get(name) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(v) => v.as_u64().map(Some).ok_or_else(|| {
+            SynapError::InvalidRequest(format!("'{}' must be a non-negative integer", name))
+        }),
+    };
+    let threshold_ms = field("threshold_ms")?;
+    let max_entries = field("max_entries")?.map(|v| v as usize);
+
+    let config = state
+        .monitoring
+        .slow_log()
+        .configure(threshold_ms, max_entries)
+        .await;
+    serde_json::to_value(config).map_err(|e| SynapError::SerializationError(e.to_string()))
+}
+
+pub(super) async fn handle_config_get_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let pattern = request
+        .payload
+        .get("pattern")
+        .and_then(|v| v.as_str())
+        .unwrap_or("*");
+    Ok(super::runtime_config::config_get_report(&state, pattern))
+}
+
+pub(super) async fn handle_config_set_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let params = request
+        .payload
+        .get("params")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'params' object".to_string()))?;
+    super::runtime_config::config_set_report(&state, params).await
+}
+
+pub(super) async fn handle_latency_latest_cmd(
+    state: AppState,
+    _request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    Ok(serde_json::json!({
+        "events": state.monitoring.latency().latest()
+    }))
+}
+
+pub(super) async fn handle_latency_history_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let event = request
+        .payload
+        .get("event")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'event' field".to_string()))?;
+
+    Ok(serde_json::json!({
+        "event": event,
+        "samples": state.monitoring.latency().history(event)
+    }))
+}
+
+pub(super) async fn handle_latency_reset_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let events: Vec<String> = request
+        .payload
+        .get("events")
+        .and_then(|v| v.as_array())
+        .map(|events| {
+            events
+                .iter()
+                .filter_map(|e| e.as_str().map(str::to_owned))
+                .collect()
+        })
+        .unwrap_or_default();
+    let count = state.monitoring.latency().reset(&events);
+
+    Ok(serde_json::json!({
+        "success": true,
+        "reset": count
+    }))
+}
+
+pub(super) async fn handle_memory_doctor_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let top = request
+        .payload
+        .get("top")
+        .and_then(|v| v.as_u64())
+        .map_or(super::kv::DOCTOR_DEFAULT_TOP, |t| t as usize);
+    Ok(super::kv::memory_doctor_report(&state, top))
+}
+
+pub(super) async fn handle_hotkeys_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let top = request
+        .payload
+        .get("top")
+        .and_then(|v| v.as_u64())
+        .map_or(super::kv::HOT_KEYS_DEFAULT_TOP, |t| t as usize);
+    super::kv::hot_keys_report(&state, top)
+}
+
 pub(super) async fn handle_memory_usage_cmd(
     state: AppState,
     request: &Request,
@@ -122,12 +259,7 @@ pub(super) async fn handle_memory_usage_cmd(
     let usage = MemoryUsage::calculate_with_stores(
         key_type, key, &stores.0, &stores.1, &stores.2, &stores.3, &stores.4,
     )
-    .await
-    .unwrap_or_else(|| MemoryUsage {
-        key: key.to_string(),
-        bytes: 0,
-        human: "0B".to_string(),
-    });
+    .unwrap_or_else(|| MemoryUsage::missing(key));
 
     serde_json::to_value(usage).map_err(|e| SynapError::SerializationError(e.to_string()))
 }
@@ -169,6 +301,54 @@ pub(super) async fn handle_client_list_cmd(
     }))
 }
 
+pub(super) async fn handle_client_kill_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let id = request.payload.get("id").and_then(|v| v.as_str());
+    let addr = request.payload.get("addr").and_then(|v| v.as_str());
+    super::kv::client_kill_report(&state, id, addr).await
+}
+
+pub(super) async fn handle_client_pause_cmd(
+    state: AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let timeout_ms = request
+        .payload
+        .get("timeout_ms")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'timeout_ms' field".to_string()))?;
+    let mode = match request.payload.get("mode").and_then(|v| v.as_str()) {
+        None | Some("all") => crate::monitoring::PauseMode::All,
+        Some("write") => crate::monitoring::PauseMode::Write,
+        Some(other) => {
+            return Err(SynapError::InvalidRequest(format!(
+                "Invalid pause mode '{}', expected 'all' or 'write'",
+                other
+            )));
+        }
+    };
+
+    state
+        .client_list_manager
+        .pause(std::time::Duration::from_millis(timeout_ms), mode);
+    Ok(serde_json::json!({
+        "paused": true,
+        "mode": mode,
+        "timeout_ms": timeout_ms
+    }))
+}
+
+pub(super) async fn handle_client_unpause_cmd(
+    state: AppState,
+    _request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    Ok(serde_json::json!({
+        "unpaused": state.client_list_manager.unpause()
+    }))
+}
+
 // ============================================================================
 // Transaction StreamableHTTP Command Handlers
 // ============================================================================
@@ -21,10 +21,13 @@ pub struct BitmapGetBitResponse {
     pub value: u8,
 }
 
+/// Range of a BITCOUNT/BITPOS query. Negative indices count from the end;
+/// `unit` is `bit` (default) or `byte`.
 #[derive(Debug, Deserialize)]
 pub struct BitmapCountRequest {
-    pub start: Option<usize>,
-    pub end: Option<usize>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub unit: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,8 +39,9 @@ pub struct BitmapCountResponse {
 #[derive(Debug, Deserialize)]
 pub struct BitmapPosRequest {
     pub value: u8,
-    pub start: Option<usize>,
-    pub end: Option<usize>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub unit: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -75,6 +79,17 @@ pub struct BitmapFieldResponse {
     pub results: Vec<i64>,
 }
 
+/// Range unit of a BITCOUNT/BITPOS request; bits unless `byte` is asked for,
+/// which keeps the offsets these endpoints have always taken
+fn range_unit(unit: Option<&str>) -> Result<crate::core::BitRangeUnit, SynapError> {
+    unit.map_or(Ok(crate::core::BitRangeUnit::Bit), str::parse)
+}
+
+/// BITPOS reports "not found" as -1; the JSON APIs use `null`
+fn found_position(position: i64) -> Option<usize> {
+    usize::try_from(position).ok()
+}
+
 pub async fn bitmap_setbit(
     State(state): State<AppState>,
     AuthContextExtractor(ctx): AuthContextExtractor,
@@ -148,9 +163,12 @@ pub async fn bitmap_bitcount(
     let scoped_key =
         crate::hub::MultiTenant::scope_kv_key(hub_ctx.as_ref().map(|c| c.user_id()), &key);
 
-    let count = state
-        .bitmap_store
-        .bitcount(&scoped_key, params.start, params.end)?;
+    let count = state.bitmap_store.bitcount_range(
+        &scoped_key,
+        params.start.unwrap_or(0),
+        params.end.unwrap_or(-1),
+        range_unit(params.unit.as_deref())?,
+    )?;
 
     Ok(Json(BitmapCountResponse { key, count }))
 }
@@ -177,12 +195,18 @@ pub async fn bitmap_bitpos(
     let scoped_key =
         crate::hub::MultiTenant::scope_kv_key(hub_ctx.as_ref().map(|c| c.user_id()), &key);
 
-    let position =
-        state
-            .bitmap_store
-            .bitpos(&scoped_key, params.value, params.start, params.end)?;
+    let position = state.bitmap_store.bitpos_range(
+        &scoped_key,
+        params.value,
+        params.start.unwrap_or(0),
+        params.end,
+        range_unit(params.unit.as_deref())?,
+    )?;
 
-    Ok(Json(BitmapPosResponse { key, position }))
+    Ok(Json(BitmapPosResponse {
+        key,
+        position: found_position(position),
+    }))
 }
 
 /// POST /bitmap/:destination/bitop - Perform bitwise operation
@@ -220,6 +244,11 @@ pub async fn bitmap_bitop(
         .collect();
 
     let operation = req.operation.parse::<crate::core::BitmapOperation>()?;
+    state.check_same_slot(
+        [scoped_destination.as_ref()]
+            .into_iter()
+            .chain(scoped_sources.iter().map(String::as_str)),
+    )?;
     let length = state
         .bitmap_store
         .bitop(operation, &scoped_destination, &scoped_sources)?;
@@ -391,15 +420,16 @@ pub(super) async fn handle_bitmap_bitcount_cmd(
     let start = request
         .payload
         .get("start")
-        .and_then(|v| v.as_u64())
-        .map(|v| v as usize);
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
     let end = request
         .payload
         .get("end")
-        .and_then(|v| v.as_u64())
-        .map(|v| v as usize);
+        .and_then(|v| v.as_i64())
+        .unwrap_or(-1);
+    let unit = range_unit(request.payload.get("unit").and_then(|v| v.as_str()))?;
 
-    let count = state.bitmap_store.bitcount(key, start, end)?;
+    let count = state.bitmap_store.bitcount_range(key, start, end, unit)?;
 
     Ok(serde_json::json!({ "key": key, "count": count }))
 }
@@ -424,17 +454,16 @@ pub(super) async fn handle_bitmap_bitpos_cmd(
     let start = request
         .payload
         .get("start")
-        .and_then(|v| v.as_u64())
-        .map(|v| v as usize);
-    let end = request
-        .payload
-        .get("end")
-        .and_then(|v| v.as_u64())
-        .map(|v| v as usize);
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
+    let end = request.payload.get("end").and_then(|v| v.as_i64());
+    let unit = range_unit(request.payload.get("unit").and_then(|v| v.as_str()))?;
 
-    let position = state.bitmap_store.bitpos(key, value, start, end)?;
+    let position = state
+        .bitmap_store
+        .bitpos_range(key, value, start, end, unit)?;
 
-    Ok(serde_json::json!({ "key": key, "position": position }))
+    Ok(serde_json::json!({ "key": key, "position": found_position(position) }))
 }
 
 pub(super) async fn handle_bitmap_bitop_cmd(
@@ -467,6 +496,11 @@ pub(super) async fn handle_bitmap_bitop_cmd(
         .collect::<Result<Vec<_>, _>>()?;
 
     let operation = operation_str.parse::<crate::core::BitmapOperation>()?;
+    state.check_same_slot(
+        [destination]
+            .into_iter()
+            .chain(source_keys.iter().map(String::as_str)),
+    )?;
     let length = state
         .bitmap_store
         .bitop(operation, destination, &source_keys)?;
@@ -0,0 +1,101 @@
+use super::*;
+use crate::protocol::command::bloom::{
+    BloomAdd, BloomAddResponse, BloomExists, BloomExistsResponse, BloomMAdd, BloomMAddResponse,
+    BloomMExists, BloomMExistsResponse, BloomReserve, BloomReserveResponse,
+};
+
+/// Add `items` to the filter on `key` and log the ones that were new to the
+/// WAL; the rest left the filter as it was.
+async fn add_and_log(
+    state: &AppState,
+    key: &str,
+    items: Vec<String>,
+) -> Result<Vec<bool>, SynapError> {
+    if items.is_empty() {
+        return Err(SynapError::InvalidRequest(
+            "Bloom filter add needs at least one item".to_string(),
+        ));
+    }
+    let added = state.bloom_store.add(key, &items);
+
+    let new_items: Vec<Vec<u8>> = items
+        .into_iter()
+        .zip(&added)
+        .filter(|(_, new)| **new)
+        .map(|(item, _)| item.into_bytes())
+        .collect();
+    if !new_items.is_empty()
+        && let Some(ref persistence) = state.persistence
+        && let Err(e) = persistence.log_bloom_add(key.to_string(), new_items).await
+    {
+        error!("Failed to log BF.ADD to WAL: {}", e);
+    }
+
+    Ok(added)
+}
+
+// ============================================================================
+// Bloom Filter StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_bf_reserve_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let BloomReserve {
+        key,
+        error_rate,
+        capacity,
+    } = decode_payload(request)?;
+
+    let reserved = state.bloom_store.reserve(&key, error_rate, capacity)?;
+    if reserved
+        && let Some(ref persistence) = state.persistence
+        && let Err(e) = persistence
+            .log_bloom_reserve(key, error_rate, capacity)
+            .await
+    {
+        error!("Failed to log BF.RESERVE to WAL: {}", e);
+    }
+    encode_response(BloomReserveResponse { reserved })
+}
+
+pub(super) async fn handle_bf_add_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let BloomAdd { key, item } = decode_payload(request)?;
+
+    let added = add_and_log(state, &key, vec![item]).await?;
+    encode_response(BloomAddResponse { added: added[0] })
+}
+
+pub(super) async fn handle_bf_madd_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let BloomMAdd { key, items } = decode_payload(request)?;
+
+    let added = add_and_log(state, &key, items).await?;
+    encode_response(BloomMAddResponse { added })
+}
+
+pub(super) async fn handle_bf_exists_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let BloomExists { key, item } = decode_payload(request)?;
+
+    let exists = state.bloom_store.exists(&key, &[item]);
+    encode_response(BloomExistsResponse { exists: exists[0] })
+}
+
+pub(super) async fn handle_bf_mexists_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let BloomMExists { key, items } = decode_payload(request)?;
+
+    let exists = state.bloom_store.exists(&key, &items);
+    encode_response(BloomMExistsResponse { exists })
+}
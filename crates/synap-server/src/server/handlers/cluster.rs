@@ -373,16 +373,7 @@ pub async fn cluster_migration_status(
         .ok_or_else(|| SynapError::InvalidRequest("Cluster mode not enabled".to_string()))?;
 
     if let Some(migration_status) = migration.get_migration(slot) {
-        Ok(Json(json!({
-            "slot": migration_status.slot,
-            "from_node": migration_status.from_node,
-            "to_node": migration_status.to_node,
-            "state": format!("{:?}", migration_status.state),
-            "keys_migrated": migration_status.keys_migrated,
-            "total_keys": migration_status.total_keys,
-            "started_at": migration_status.started_at,
-            "completed_at": migration_status.completed_at
-        })))
+        Ok(Json(migration_json(&migration_status)))
     } else {
         Ok(Json(json!({
             "slot": slot,
@@ -392,66 +383,347 @@ pub async fn cluster_migration_status(
     }
 }
 
-// ============================================================================
-// HiveHub Integration - Quota Stats Handler
-// ============================================================================
+fn migration_json(migration: &crate::cluster::migration::SlotMigration) -> serde_json::Value {
+    json!({
+        "slot": migration.slot,
+        "from_node": migration.from_node,
+        "to_node": migration.to_node,
+        "state": format!("{:?}", migration.state),
+        "keys_migrated": migration.keys_migrated,
+        "total_keys": migration.total_keys,
+        "copy_done": migration.copy_done,
+        "started_at": migration.started_at,
+        "completed_at": migration.completed_at
+    })
+}
 
-/// GET /hub/quota - Get user quota statistics
-///
-/// Returns quota information for the authenticated Hub user.
-/// Only available when HiveHub integration is enabled.
-pub async fn hub_quota_stats(
-    State(state): State<AppState>,
-    crate::hub::HubContextExtractor(hub_context_opt): crate::hub::HubContextExtractor,
-) -> Result<Json<serde_json::Value>, SynapError> {
-    // Get Hub context (user_id from Hub access key)
-    let hub_context = hub_context_opt.ok_or_else(|| {
-        SynapError::Unauthorized(
-            "Hub integration enabled but no Hub authentication found".to_string(),
-        )
-    })?;
-
-    // Get HubClient from app state
-    let hub_client = state
-        .hub_client
-        .as_ref()
-        .ok_or_else(|| SynapError::InternalError("HubClient not initialized".to_string()))?;
-
-    // Get quota from cache or fetch from Hub
-    let quota = hub_client
-        .quota_manager()
-        .get_quota(&hub_context.user_id)
-        .ok_or_else(|| {
-            SynapError::InternalError(format!(
-                "Quota not found for user {}. Try authenticating again.",
-                hub_context.user_id
-            ))
-        })?;
-
-    // Return quota statistics
-    Ok(Json(json!({
-        "user_id": hub_context.user_id,
-        "plan": format!("{:?}", quota.plan),
-        "storage": {
-            "used_bytes": quota.storage_used,
-            "limit_bytes": quota.storage_limit,
-            "remaining_bytes": quota.remaining_storage(),
-            "usage_percent": if quota.storage_limit > 0 {
-                (quota.storage_used as f64 / quota.storage_limit as f64 * 100.0).round()
-            } else {
-                0.0
+type ClusterParts<'a> = (
+    &'a Arc<crate::cluster::ClusterTopology>,
+    &'a Arc<crate::cluster::SlotMigrationManager>,
+);
+
+fn cluster_parts(state: &AppState) -> Result<ClusterParts<'_>, SynapError> {
+    match (&state.cluster_topology, &state.cluster_migration) {
+        (Some(topology), Some(migration)) => Ok((topology, migration)),
+        _ => Err(SynapError::InvalidRequest(
+            "Cluster mode not enabled".to_string(),
+        )),
+    }
+}
+
+/// `start..=end` (or just `start`) as a validated slot range
+fn slot_range(
+    start: u16,
+    end: Option<u16>,
+) -> Result<crate::cluster::types::SlotRange, SynapError> {
+    use crate::cluster::types::{SlotRange, TOTAL_SLOTS};
+
+    let end = end.unwrap_or(start);
+    if start > end || end >= TOTAL_SLOTS {
+        return Err(SynapError::InvalidRequest(format!(
+            "Invalid slot range {}-{} (slots are 0-{})",
+            start,
+            end,
+            TOTAL_SLOTS - 1
+        )));
+    }
+    Ok(SlotRange::new(start, end))
+}
+
+/// Request type for `CLUSTER SETSLOT`
+#[derive(Debug, Deserialize)]
+pub struct SetSlotRequest {
+    pub slot: u16,
+    /// Apply to every slot from `slot` through `end_slot`
+    pub end_slot: Option<u16>,
+    /// `migrating`, `importing`, `stable` or `node`
+    pub action: String,
+    /// Destination (`migrating`), source (`importing`) or new owner (`node`)
+    pub node_id: Option<String>,
+}
+
+fn set_slot(state: &AppState, req: &SetSlotRequest) -> Result<serde_json::Value, SynapError> {
+    let (topology, migration) = cluster_parts(state)?;
+    let range = slot_range(req.slot, req.end_slot)?;
+    let action = req.action.to_lowercase();
+
+    let node_id = || {
+        req.node_id.as_deref().ok_or_else(|| {
+            SynapError::InvalidRequest(format!("SETSLOT {} requires a node_id", action))
+        })
+    };
+    let known_node = |node_id: &str| {
+        topology
+            .get_node(node_id)
+            .map(|_| ())
+            .map_err(|e| SynapError::InvalidRequest(e.to_string()))
+    };
+
+    match action.as_str() {
+        "migrating" => {
+            let to_node = node_id()?;
+            known_node(to_node)?;
+            let slots = (range.start..=range.end)
+                .map(|slot| {
+                    topology
+                        .get_slot_owner(slot)
+                        .map(|owner| (slot, owner))
+                        .map_err(|e| SynapError::InvalidRequest(e.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            migration
+                .start_migrations(&slots, to_node)
+                .map_err(|e| SynapError::InvalidRequest(e.to_string()))?;
+        }
+        "importing" => {
+            let from_node = node_id()?;
+            known_node(from_node)?;
+            let slots: Vec<(u16, String)> = (range.start..=range.end)
+                .map(|slot| (slot, from_node.to_string()))
+                .collect();
+            migration
+                .start_migrations(&slots, topology.my_node_id())
+                .map_err(|e| SynapError::InvalidRequest(e.to_string()))?;
+        }
+        "stable" => {
+            for slot in range.start..=range.end {
+                migration.clear_migration(slot);
             }
-        },
-        "operations": {
-            "monthly_count": quota.monthly_operations,
-            "monthly_limit": quota.monthly_operations_limit,
-            "remaining": quota.remaining_operations(),
-            "usage_percent": if quota.monthly_operations_limit > 0 {
-                (quota.monthly_operations as f64 / quota.monthly_operations_limit as f64 * 100.0).round()
-            } else {
-                0.0
+        }
+        "node" => {
+            topology
+                .set_slots_owner(range, node_id()?)
+                .map_err(|e| SynapError::InvalidRequest(e.to_string()))?;
+            for slot in range.start..=range.end {
+                migration.clear_migration(slot);
             }
-        },
-        "updated_at": format!("{:?}", quota.updated_at)
-    })))
+        }
+        other => {
+            return Err(SynapError::InvalidRequest(format!(
+                "Unknown SETSLOT action '{}' (expected migrating, importing, stable or node)",
+                other
+            )));
+        }
+    }
+
+    Ok(json!({
+        "success": true,
+        "action": action,
+        "start": range.start,
+        "end": range.end,
+        "slots": range.count(),
+        "node_id": req.node_id
+    }))
+}
+
+/// POST /cluster/setslot - Set the migration state or owner of slots
+pub async fn cluster_setslot(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(req): Json<SetSlotRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!(
+        "REST POST /cluster/setslot: slot={}, end_slot={:?}, action={}",
+        req.slot, req.end_slot, req.action
+    );
+
+    // Check permission
+    require_permission(&ctx, "cluster:migration", Action::Write)?;
+
+    Ok(Json(set_slot(&state, &req)?))
+}
+
+/// Request type for `CLUSTER MIGRATE`
+#[derive(Debug, Deserialize)]
+pub struct MigrateSlotsRequest {
+    pub start: u16,
+    /// Last slot of the range (defaults to `start`)
+    pub end: Option<u16>,
+    pub to_node: String,
+    /// Also set the migration throttle
+    pub max_keys_per_sec: Option<u64>,
+}
+
+fn migrate_slots(
+    state: &AppState,
+    req: &MigrateSlotsRequest,
+) -> Result<serde_json::Value, SynapError> {
+    let (topology, migration) = cluster_parts(state)?;
+    let range = slot_range(req.start, req.end)?;
+
+    topology
+        .get_node(&req.to_node)
+        .map_err(|e| SynapError::InvalidRequest(e.to_string()))?;
+
+    // Each slot migrates from its current owner; slots already on the
+    // destination are left alone
+    let mut slots = Vec::new();
+    for slot in range.start..=range.end {
+        let owner = topology
+            .get_slot_owner(slot)
+            .map_err(|e| SynapError::InvalidRequest(e.to_string()))?;
+        if owner != req.to_node {
+            slots.push((slot, owner));
+        }
+    }
+
+    if let Some(max_keys_per_sec) = req.max_keys_per_sec {
+        migration.set_throttle(max_keys_per_sec);
+    }
+
+    migration
+        .start_migrations(&slots, &req.to_node)
+        .map_err(|e| SynapError::InvalidRequest(e.to_string()))?;
+
+    Ok(json!({
+        "success": true,
+        "start": range.start,
+        "end": range.end,
+        "to_node": req.to_node,
+        "slots_started": slots.len(),
+        "slots_skipped": range.count() as usize - slots.len(),
+        "max_keys_per_sec": migration.throttle()
+    }))
+}
+
+/// POST /cluster/migrate - Migrate a slot range to a node
+pub async fn cluster_migrate_slots(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(req): Json<MigrateSlotsRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!(
+        "REST POST /cluster/migrate: slots={}-{:?}, to={}",
+        req.start, req.end, req.to_node
+    );
+
+    // Check permission
+    require_permission(&ctx, "cluster:migration", Action::Write)?;
+
+    Ok(Json(migrate_slots(&state, &req)?))
+}
+
+fn migrations_overview(state: &AppState) -> Result<serde_json::Value, SynapError> {
+    use crate::cluster::migration::MigrationState;
+
+    let (_, migration) = cluster_parts(state)?;
+    let migrations = migration.list_migrations();
+
+    let active = migrations
+        .iter()
+        .filter(|m| {
+            matches!(
+                m.state,
+                MigrationState::Pending | MigrationState::InProgress
+            )
+        })
+        .count();
+    let copied = migrations.iter().filter(|m| m.copy_done).count();
+
+    Ok(json!({
+        "migrations": migrations.iter().map(migration_json).collect::<Vec<_>>(),
+        "count": migrations.len(),
+        "active": active,
+        "copied": copied,
+        "keys_migrated": migrations.iter().map(|m| m.keys_migrated).sum::<usize>(),
+        "total_keys": migrations.iter().map(|m| m.total_keys).sum::<usize>(),
+        "max_keys_per_sec": migration.throttle()
+    }))
+}
+
+/// GET /cluster/migrations - Progress of every slot migration
+pub async fn cluster_migrations(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST GET /cluster/migrations");
+
+    // Check permission
+    require_permission(&ctx, "cluster:migration", Action::Read)?;
+
+    Ok(Json(migrations_overview(&state)?))
+}
+
+/// Request type for the migration throttle
+#[derive(Debug, Deserialize)]
+pub struct MigrationThrottleRequest {
+    /// Keys copied per second (0 = unthrottled)
+    pub max_keys_per_sec: u64,
+}
+
+fn set_migration_throttle(
+    state: &AppState,
+    req: &MigrationThrottleRequest,
+) -> Result<serde_json::Value, SynapError> {
+    let (_, migration) = cluster_parts(state)?;
+    migration.set_throttle(req.max_keys_per_sec);
+
+    Ok(json!({
+        "success": true,
+        "max_keys_per_sec": req.max_keys_per_sec
+    }))
+}
+
+/// POST /cluster/migrations/throttle - Limit how fast migrations copy keys
+pub async fn cluster_migration_throttle(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(req): Json<MigrationThrottleRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!(
+        "REST POST /cluster/migrations/throttle: max_keys_per_sec={}",
+        req.max_keys_per_sec
+    );
+
+    // Check permission
+    require_permission(&ctx, "cluster:migration", Action::Write)?;
+
+    Ok(Json(set_migration_throttle(&state, &req)?))
+}
+
+// ============================================================================
+// Cluster StreamableHTTP Command Handlers
+// ============================================================================
+
+fn parse_cluster_payload<T: serde::de::DeserializeOwned>(
+    request: &Request,
+) -> Result<T, SynapError> {
+    serde_json::from_value(request.payload.clone()).map_err(|e| {
+        SynapError::InvalidRequest(format!("Invalid {} request: {}", request.command, e))
+    })
+}
+
+pub(super) async fn handle_cluster_setslot_cmd(
+    state: &AppState,
+    ctx: &crate::auth::AuthContext,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    require_permission(ctx, "cluster:migration", Action::Write)?;
+    set_slot(state, &parse_cluster_payload(request)?)
+}
+
+pub(super) async fn handle_cluster_migrate_cmd(
+    state: &AppState,
+    ctx: &crate::auth::AuthContext,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    require_permission(ctx, "cluster:migration", Action::Write)?;
+    migrate_slots(state, &parse_cluster_payload(request)?)
+}
+
+pub(super) async fn handle_cluster_migrations_cmd(
+    state: &AppState,
+    ctx: &crate::auth::AuthContext,
+) -> Result<serde_json::Value, SynapError> {
+    require_permission(ctx, "cluster:migration", Action::Read)?;
+    migrations_overview(state)
+}
+
+pub(super) async fn handle_cluster_throttle_cmd(
+    state: &AppState,
+    ctx: &crate::auth::AuthContext,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    require_permission(ctx, "cluster:migration", Action::Write)?;
+    set_migration_throttle(state, &parse_cluster_payload(request)?)
 }
@@ -0,0 +1,559 @@
+//! Permission checks for `POST /api/v1/command`
+//!
+//! Every command maps to the resources it touches and the action it needs,
+//! using the same `"<type>:<name>"` resources and actions as the REST
+//! endpoints, so a role grants the same access over both. Denials and admin
+//! commands are recorded in the audit log, and so is every other write when
+//! `auth.audit.log_writes` is set. Writes are also handed to the command
+//! hooks.
+
+use super::AppState;
+use crate::auth::{Action, AuditLogEntry, AuthContext, AuthEventType, require_permission};
+use crate::core::SynapError;
+use crate::monitoring::{CommandProtocol, CommandRecord};
+use crate::server::envelope::{Request, Response};
+use serde_json::Value;
+
+/// Resources a command touches and the action it needs on each
+#[derive(Debug, PartialEq)]
+pub(super) struct CommandPermission {
+    pub resources: Vec<String>,
+    pub action: Action,
+}
+
+/// Map a command to the permission it needs
+///
+/// Returns `None` for commands whose handler checks permissions itself.
+/// Commands that name no key need the type-wide resource (`kv:*`).
+pub(super) fn command_permission(command: &str, payload: &Value) -> Option<CommandPermission> {
+    let (namespace, verb) = command.split_once('.').unwrap_or((command, ""));
+
+    let admin = |action| {
+        Some(CommandPermission {
+            resources: vec!["admin:*".to_string()],
+            action,
+        })
+    };
+    match command {
+        // Handlers that take the auth context and check it themselves
+        "warmup.run" | "subscriptions.list" => return None,
+        _ if namespace == "cluster" => return None,
+        // Whole-keyspace wipes and server administration
+        "kv.flushdb"
+        | "kv.flushall"
+        | "slowlog.reset"
+        | "slowlog.config.set"
+        | "config.set"
+        | "latency.reset"
+        | "client.kill"
+        | "client.pause"
+        | "client.unpause"
+        | "script.flush"
+        | "script.kill"
+        | "function.flush"
+        | "function.delete"
+        | "shovel.create"
+        | "shovel.delete"
+        | "webhook.create"
+        | "webhook.delete"
+        | "schedule.create"
+        | "schedule.delete"
+        | "pubsub.route.set"
+        | "pubsub.route.delete" => {
+            return admin(Action::Admin);
+        }
+        "info"
+        | "slowlog.get"
+        | "slowlog.len"
+        | "slowlog.config.get"
+        | "config.get"
+        | "latency.latest"
+        | "latency.history"
+        | "memory.usage"
+        | "memory.doctor"
+        | "monitoring.hotkeys"
+        | "client.list"
+        | "replication.topology"
+        | "shovel.list"
+        | "webhook.list"
+        | "schedule.list"
+        | "schedule.get"
+        | "message.trace"
+        | "pubsub.route.list" => {
+            return admin(Action::Read);
+        }
+        _ => {}
+    }
+
+    let resource_type = match namespace {
+        "key" => "kv",
+        "function" => "script",
+        other => other,
+    };
+    let action = match verb {
+        "create" | "get_or_create" | "notify.set" | "notify.del" => Action::Configure,
+        "del" | "mdel" | "delete" | "purge" | "rem" | "zrem" | "zremrangebyrank"
+        | "zremrangebyscore" => Action::Delete,
+        _ if is_read(verb) => Action::Read,
+        _ => Action::Write,
+    };
+
+    // Scripts and transactions are checked as a whole; the keys a script
+    // touches are not known up front
+    let mut names = if matches!(resource_type, "script" | "transaction") {
+        Vec::new()
+    } else {
+        resource_names(payload)
+    };
+    if names.is_empty() {
+        names.push("*".to_string());
+    }
+
+    Some(CommandPermission {
+        resources: names
+            .into_iter()
+            .map(|name| format!("{resource_type}:{name}"))
+            .collect(),
+        action,
+    })
+}
+
+/// Verbs that only read
+fn is_read(verb: &str) -> bool {
+    matches!(
+        verb,
+        "get"
+            | "notify.get"
+            | "getall"
+            | "mget"
+            | "scan"
+            | "exists"
+            | "mexists"
+            | "keys"
+            | "vals"
+            | "ttl"
+            | "strlen"
+            | "getrange"
+            | "type"
+            | "randomkey"
+            | "dump"
+            | "dbsize"
+            | "stats"
+            | "len"
+            | "llen"
+            | "lrange"
+            | "range"
+            | "lindex"
+            | "index"
+            | "lpos"
+            | "ismember"
+            | "members"
+            | "size"
+            | "card"
+            | "randmember"
+            | "inter"
+            | "union"
+            | "diff"
+            | "intercard"
+            | "zscore"
+            | "zmscore"
+            | "zcard"
+            | "zcount"
+            | "zrange"
+            | "zrevrange"
+            | "zrangebyscore"
+            | "zrank"
+            | "zrevrank"
+            | "zinter"
+            | "zunion"
+            | "zdiff"
+            | "pfcount"
+            | "getbit"
+            | "bitcount"
+            | "bitpos"
+            | "geodist"
+            | "georadius"
+            | "georadiusbymember"
+            | "geopos"
+            | "geohash"
+            | "geosearch"
+            | "list"
+            | "browse"
+            | "lag"
+            | "consume"
+            | "consume_many"
+            | "ack"
+            | "nack"
+            | "subscribe"
+            | "unsubscribe"
+            | "topics"
+            | "filters"
+            | "info"
+            | "watch"
+            | "unwatch"
+    )
+}
+
+/// Names of the keys, queues, rooms and topics a payload addresses
+fn resource_names(payload: &Value) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut push = |value: &Value| {
+        if let Some(name) = value.as_str() {
+            names.push(name.to_string());
+        }
+    };
+
+    for field in [
+        "key",
+        "source",
+        "destination",
+        "queue",
+        "name",
+        "room",
+        "topic",
+        "group_id",
+    ] {
+        if let Some(value) = payload.get(field) {
+            push(value);
+        }
+    }
+    for field in ["keys", "sources", "source_keys", "topics"] {
+        if let Some(values) = payload.get(field).and_then(Value::as_array) {
+            values.iter().for_each(&mut push);
+        }
+    }
+    // `kv.mset` pairs and `stream.consume_many` rooms
+    for (field, name) in [("pairs", "key"), ("rooms", "room")] {
+        if let Some(items) = payload.get(field).and_then(Value::as_array) {
+            for item in items {
+                if let Some(value) = item.get(name) {
+                    push(value);
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Check that `ctx` may run `request`, auditing a denial
+pub(super) async fn authorize_command(
+    state: &AppState,
+    ctx: &AuthContext,
+    request: &Request,
+) -> Result<(), SynapError> {
+    // Fast path: admin, or authentication disabled
+    if ctx.is_admin {
+        return Ok(());
+    }
+    let Some(permission) = command_permission(&request.command, &request.payload) else {
+        return Ok(());
+    };
+
+    for resource in &permission.resources {
+        if let Err(denied) = require_permission(ctx, resource, permission.action) {
+            let mut entry = AuditLogEntry::permission_denied(
+                ctx.user_id.clone(),
+                ctx.api_key_id.clone(),
+                ctx.client_ip.to_string(),
+                resource.clone(),
+                permission.action.as_str().to_string(),
+            );
+            entry
+                .metadata
+                .insert("command".to_string(), request.command.clone());
+            state.monitoring.audit_log().log(entry).await;
+            return Err(denied);
+        }
+    }
+    Ok(())
+}
+
+/// Commands recorded in the audit log whether or not writes are
+fn is_admin_command(command: &str) -> bool {
+    matches!(
+        command,
+        "kv.flushdb"
+            | "kv.flushall"
+            | "slowlog.reset"
+            | "slowlog.config.set"
+            | "config.set"
+            | "latency.reset"
+            | "client.kill"
+            | "client.pause"
+            | "client.unpause"
+            | "script.flush"
+            | "script.kill"
+            | "function.flush"
+            | "function.delete"
+            | "warmup.run"
+            | "shovel.create"
+            | "shovel.delete"
+            | "webhook.create"
+            | "webhook.delete"
+            | "schedule.create"
+            | "schedule.delete"
+            | "pubsub.route.set"
+            | "pubsub.route.delete"
+            | "cluster.setslot"
+            | "cluster.migrate"
+            | "cluster.throttle"
+    )
+}
+
+/// What to record in the audit log for a command, if anything: admin
+/// commands always, other writes when `log_writes` is set
+pub(super) fn audit_event(
+    command: &str,
+    payload: &Value,
+    log_writes: bool,
+) -> Option<(AuthEventType, Option<String>)> {
+    let permission = command_permission(command, payload);
+    let resource = permission.as_ref().map(|p| p.resources.join(","));
+    if is_admin_command(command) {
+        return Some((AuthEventType::AdminOperation, resource));
+    }
+    match permission {
+        Some(p) if log_writes && p.action != Action::Read => Some((AuthEventType::Write, resource)),
+        _ => None,
+    }
+}
+
+/// Record a command that ran, with `audit` from [`audit_event`]
+pub(super) async fn audit_command(
+    state: &AppState,
+    ctx: &AuthContext,
+    command: &str,
+    audit: Option<(AuthEventType, Option<String>)>,
+    response: &Result<Response, SynapError>,
+) {
+    // Denied commands were already recorded by `authorize_command`
+    let Some((event_type, resource)) = audit else {
+        return;
+    };
+    if matches!(response, Err(SynapError::Forbidden(_))) {
+        return;
+    }
+    let success = matches!(response, Ok(r) if r.success);
+    state
+        .monitoring
+        .audit_log()
+        .log(AuditLogEntry::operation(
+            event_type, ctx, command, resource, success,
+        ))
+        .await;
+}
+
+/// Whether a command needs more than read permission; CLIENT PAUSE in write
+/// mode holds these back
+pub(crate) fn is_write(command: &str, payload: &Value) -> bool {
+    command_permission(command, payload).is_some_and(|p| p.action != Action::Read)
+}
+
+/// Resources of a write command, for the command hooks; `None` for reads
+/// and for commands without a permission mapping
+pub(crate) fn write_resource(command: &str, payload: &Value) -> Option<String> {
+    command_permission(command, payload)
+        .filter(|p| p.action != Action::Read)
+        .map(|p| p.resources.join(","))
+}
+
+/// Hand a write command that ran to the command hooks, with `resource`
+/// from [`write_resource`] and the error it failed with, if any
+pub(crate) fn hook_command(
+    state: &AppState,
+    protocol: CommandProtocol,
+    ctx: &AuthContext,
+    command: &str,
+    resource: String,
+    error: Option<String>,
+    duration: std::time::Duration,
+) {
+    let mut record = CommandRecord::new(protocol, command, ctx.client_ip.to_string(), duration);
+    record.resource = Some(resource);
+    record.user = ctx.user_id.clone();
+    record.api_key_id = ctx.api_key_id.clone();
+    record.success = error.is_none();
+    record.error = error;
+    state.monitoring.command_hooks().emit(record);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn permission(command: &str, payload: Value) -> (Vec<String>, Action) {
+        let permission = command_permission(command, &payload).unwrap();
+        (permission.resources, permission.action)
+    }
+
+    #[test]
+    fn test_key_commands_map_to_their_resources() {
+        assert_eq!(
+            permission("kv.get", json!({"key": "user:1"})),
+            (vec!["kv:user:1".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("hash.set", json!({"key": "h", "field": "f", "value": 1})),
+            (vec!["hash:h".to_string()], Action::Write)
+        );
+        assert_eq!(
+            permission("kv.mdel", json!({"keys": ["a", "b"]})),
+            (vec!["kv:a".to_string(), "kv:b".to_string()], Action::Delete)
+        );
+        assert_eq!(
+            permission("queue.browse", json!({"queue": "jobs"})),
+            (vec!["queue:jobs".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("consumer_group.lag", json!({"group_id": "billing"})),
+            (vec!["consumer_group:billing".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("kv.mset", json!({"pairs": [{"key": "a", "value": 1}]})),
+            (vec!["kv:a".to_string()], Action::Write)
+        );
+        assert_eq!(
+            permission("key.rename", json!({"source": "a", "destination": "b"})),
+            (vec!["kv:a".to_string(), "kv:b".to_string()], Action::Write)
+        );
+        assert_eq!(
+            permission("key.dump", json!({"key": "a"})),
+            (vec!["kv:a".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("lock.acquire", json!({"key": "job", "ttl": 1000})),
+            (vec!["lock:job".to_string()], Action::Write)
+        );
+        assert_eq!(
+            permission("ratelimit.check", json!({"key": "api:1", "max_tokens": 10})),
+            (vec!["ratelimit:api:1".to_string()], Action::Write)
+        );
+        assert_eq!(
+            permission("ts.range", json!({"key": "requests", "aggregation": "sum"})),
+            (vec!["ts:requests".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("bf.mexists", json!({"key": "seen", "items": ["a"]})),
+            (vec!["bf:seen".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("bf.madd", json!({"key": "seen", "items": ["a"]})),
+            (vec!["bf:seen".to_string()], Action::Write)
+        );
+        assert_eq!(
+            permission("set.intercard", json!({"keys": ["a", "b"], "limit": 1})),
+            (vec!["set:a".to_string(), "set:b".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission(
+                "set.unionstore",
+                json!({"destination": "all", "keys": ["a", "b"]})
+            ),
+            (
+                vec![
+                    "set:all".to_string(),
+                    "set:a".to_string(),
+                    "set:b".to_string()
+                ],
+                Action::Write
+            )
+        );
+    }
+
+    #[test]
+    fn test_keyless_commands_need_the_type_wildcard() {
+        assert_eq!(
+            permission("kv.scan", json!({"prefix": "user:"})),
+            (vec!["kv:*".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("queue.list", json!({})),
+            (vec!["queue:*".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("script.eval", json!({"script": "return 1", "keys": ["k"]})),
+            (vec!["script:*".to_string()], Action::Write)
+        );
+    }
+
+    #[test]
+    fn test_admin_and_configure_commands() {
+        assert_eq!(
+            permission("kv.flushall", json!({})),
+            (vec!["admin:*".to_string()], Action::Admin)
+        );
+        assert_eq!(
+            permission("queue.create", json!({"name": "jobs"})),
+            (vec!["queue:jobs".to_string()], Action::Configure)
+        );
+        assert_eq!(
+            permission("stream.consume", json!({"room": "chat"})),
+            (vec!["stream:chat".to_string()], Action::Read)
+        );
+        assert_eq!(
+            permission("sortedset.notify.set", json!({"key": "board", "top_n": 10})),
+            (vec!["sortedset:board".to_string()], Action::Configure)
+        );
+        assert_eq!(
+            permission("sortedset.notify.get", json!({"key": "board"})),
+            (vec!["sortedset:board".to_string()], Action::Read)
+        );
+        assert!(command_permission("cluster.migrate", &json!({})).is_none());
+        // Shovels publish to queues the caller may not otherwise reach
+        assert_eq!(
+            permission("shovel.create", json!({"name": "mirror", "source": "jobs"})),
+            (vec!["admin:*".to_string()], Action::Admin)
+        );
+        assert_eq!(
+            permission("shovel.list", json!({})),
+            (vec!["admin:*".to_string()], Action::Read)
+        );
+        // Webhooks send messages to arbitrary URLs
+        assert_eq!(
+            permission("webhook.create", json!({"name": "fn", "queue": "jobs"})),
+            (vec!["admin:*".to_string()], Action::Admin)
+        );
+        // Schedules publish to queues, topics and rooms on the server's behalf
+        assert_eq!(
+            permission(
+                "schedule.create",
+                json!({"name": "nightly", "cron": "@daily"})
+            ),
+            (vec!["admin:*".to_string()], Action::Admin)
+        );
+        assert_eq!(
+            permission("schedule.get", json!({"name": "nightly"})),
+            (vec!["admin:*".to_string()], Action::Read)
+        );
+        // Traces name the consumers of every queue and room
+        assert_eq!(
+            permission("message.trace", json!({"id": "m1"})),
+            (vec!["admin:*".to_string()], Action::Read)
+        );
+        // Routes redirect every publisher's messages, not one topic's
+        assert_eq!(
+            permission(
+                "pubsub.route.set",
+                json!({"name": "eu", "source": "orders.eu"})
+            ),
+            (vec!["admin:*".to_string()], Action::Admin)
+        );
+    }
+
+    #[test]
+    fn test_audit_events() {
+        let flush = audit_event("kv.flushall", &json!({}), false);
+        assert_eq!(
+            flush,
+            Some((AuthEventType::AdminOperation, Some("admin:*".to_string())))
+        );
+        let set = json!({"key": "a", "value": 1});
+        assert_eq!(audit_event("kv.set", &set, false), None);
+        assert_eq!(
+            audit_event("kv.set", &set, true),
+            Some((AuthEventType::Write, Some("kv:a".to_string())))
+        );
+        assert_eq!(audit_event("kv.get", &json!({"key": "a"}), true), None);
+    }
+}
@@ -0,0 +1,103 @@
+//! Content negotiation for `POST /api/v1/command`
+//!
+//! The envelope is read in the encoding named by `Content-Type` (JSON,
+//! MessagePack or CBOR, see [`Encoding`]) and answered in the first encoding
+//! the `Accept` header lists, falling back to the request's. JSON bodies go
+//! through axum's `Json` extractor, so JSON clients see exactly the same
+//! rejections as before. Error responses (`SynapError`) are always JSON.
+
+use crate::core::SynapError;
+use crate::protocol::encoding::Encoding;
+use crate::server::envelope::{Request, Response};
+use axum::Json;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request as HttpRequest};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response as AxumResponse};
+use serde_json::json;
+
+/// A command envelope and the encoding its response should use
+pub struct EncodedRequest {
+    pub request: Request,
+    pub response_encoding: Encoding,
+}
+
+impl<S: Send + Sync> FromRequest<S> for EncodedRequest {
+    type Rejection = AxumResponse;
+
+    async fn from_request(req: HttpRequest, state: &S) -> Result<Self, Self::Rejection> {
+        let encoding = request_encoding(req.headers()).map_err(|ct| unsupported_media_type(&ct))?;
+        let response_encoding = req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Encoding::from_accept)
+            .unwrap_or(encoding);
+
+        let request = match encoding {
+            Encoding::Json => {
+                let Json(request) = Json::<Request>::from_request(req, state)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+                request
+            }
+            binary => {
+                let body = Bytes::from_request(req, state)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+                binary
+                    .decode(&body)
+                    .map_err(|e| SynapError::InvalidRequest(e.to_string()).into_response())?
+            }
+        };
+
+        Ok(Self {
+            request,
+            response_encoding,
+        })
+    }
+}
+
+/// Encoding of the request body, or the unsupported `Content-Type`. JSON
+/// requests keep axum's own check, so a missing `Content-Type` is only
+/// rejected by the `Json` extractor.
+fn request_encoding(headers: &HeaderMap) -> Result<Encoding, String> {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE) else {
+        return Ok(Encoding::Json);
+    };
+    let content_type = content_type.to_str().unwrap_or_default();
+    match Encoding::from_content_type(content_type) {
+        Some(encoding) => Ok(encoding),
+        // `application/*+json` and friends are still JSON to axum
+        None if content_type.contains("json") => Ok(Encoding::Json),
+        None => Err(content_type.to_string()),
+    }
+}
+
+fn unsupported_media_type(content_type: &str) -> AxumResponse {
+    (
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        Json(json!({
+            "error": format!(
+                "unsupported Content-Type '{content_type}': use application/json, \
+                 application/msgpack or application/cbor"
+            ),
+            "code": StatusCode::UNSUPPORTED_MEDIA_TYPE.as_u16(),
+        })),
+    )
+        .into_response()
+}
+
+/// Write a command response in the negotiated encoding
+pub(super) fn encode_response(
+    encoding: Encoding,
+    response: &Response,
+) -> Result<AxumResponse, SynapError> {
+    if encoding == Encoding::Json {
+        return Ok(Json(response).into_response());
+    }
+    let body = encoding
+        .encode(response)
+        .map_err(|e| SynapError::SerializationError(e.to_string()))?;
+    Ok(([(header::CONTENT_TYPE, encoding.content_type())], body).into_response())
+}
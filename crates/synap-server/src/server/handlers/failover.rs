@@ -0,0 +1,71 @@
+use super::*;
+use crate::replication::FailoverCoordinator;
+use crate::replication::sentinel::{Announcement, Topology, VoteRequest, VoteResponse};
+
+fn failover(state: &AppState) -> Result<&Arc<FailoverCoordinator>, SynapError> {
+    state
+        .failover
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Automatic failover not enabled".to_string()))
+}
+
+/// GET /replication/topology - Current master and failover epoch
+pub async fn replication_topology(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<Topology>, SynapError> {
+    debug!("REST GET /replication/topology");
+
+    require_permission(&ctx, "replication:topology", Action::Read)?;
+
+    Ok(Json(failover(&state)?.topology()))
+}
+
+/// POST /replication/failover/vote - Vote request from an election candidate
+pub async fn replication_failover_vote(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(req): Json<VoteRequest>,
+) -> Result<Json<VoteResponse>, SynapError> {
+    debug!(
+        "REST POST /replication/failover/vote: candidate={}, term={}",
+        req.candidate_id, req.term
+    );
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    Ok(Json(failover(&state)?.handle_vote(&req)))
+}
+
+/// POST /replication/failover/announce - A newly promoted master announcing itself
+pub async fn replication_failover_announce(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(req): Json<Announcement>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!(
+        "REST POST /replication/failover/announce: epoch={}",
+        req.epoch
+    );
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let changed = failover(&state)?
+        .handle_announce(req)
+        .await
+        .map_err(|e| SynapError::InternalError(e.to_string()))?;
+
+    Ok(Json(json!({ "changed": changed })))
+}
+
+// ============================================================================
+// Replication StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_replication_topology_cmd(
+    state: AppState,
+    _request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    serde_json::to_value(failover(&state)?.topology())
+        .map_err(|e| SynapError::SerializationError(e.to_string()))
+}
@@ -121,11 +121,7 @@ pub async fn geospatial_geodist(
     Path((key, member1, member2)): Path<(String, String, String)>,
     Query(params): Query<HashMap<String, String>>,
 ) -> Result<Json<GeospatialDistResponse>, SynapError> {
-    let unit_str = params
-        .get("unit")
-        .cloned()
-        .unwrap_or_else(|| "m".to_string());
-    let unit = unit_str.parse::<crate::core::DistanceUnit>()?;
+    let unit = crate::core::DistanceUnit::parse_or_meters(params.get("unit").map(String::as_str))?;
 
     debug!(
         "REST GEODIST key={} member1={} member2={} unit={:?}",
@@ -162,11 +158,7 @@ pub async fn geospatial_georadius(
         .get("radius")
         .and_then(|s| s.parse().ok())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'radius' parameter".to_string()))?;
-    let unit_str = params
-        .get("unit")
-        .cloned()
-        .unwrap_or_else(|| "m".to_string());
-    let unit = unit_str.parse::<crate::core::DistanceUnit>()?;
+    let unit = crate::core::DistanceUnit::parse_or_meters(params.get("unit").map(String::as_str))?;
     let with_dist = params.get("withdist").map(|s| s == "true").unwrap_or(false);
     let with_coord = params
         .get("withcoord")
@@ -226,11 +218,7 @@ pub async fn geospatial_georadiusbymember(
         .get("radius")
         .and_then(|s| s.parse().ok())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'radius' parameter".to_string()))?;
-    let unit_str = params
-        .get("unit")
-        .cloned()
-        .unwrap_or_else(|| "m".to_string());
-    let unit = unit_str.parse::<crate::core::DistanceUnit>()?;
+    let unit = crate::core::DistanceUnit::parse_or_meters(params.get("unit").map(String::as_str))?;
     let with_dist = params.get("withdist").map(|s| s == "true").unwrap_or(false);
     let with_coord = params
         .get("withcoord")
@@ -336,19 +324,19 @@ pub async fn geospatial_geosearch(
     let from_member = req.from_member.as_ref().map(|s| s.as_bytes());
     let from_lonlat = req.from_lonlat;
 
-    let by_radius = req.by_radius.map(|(r, u)| {
-        let unit = u
-            .parse::<crate::core::DistanceUnit>()
-            .unwrap_or(crate::core::DistanceUnit::Meters);
-        (r, unit)
-    });
-
-    let by_box = req.by_box.map(|(w, h, u)| {
-        let unit = u
-            .parse::<crate::core::DistanceUnit>()
-            .unwrap_or(crate::core::DistanceUnit::Meters);
-        (w, h, unit)
-    });
+    // An unknown unit is an error, as in GEOSEARCH, rather than meters
+    let by_radius = req
+        .by_radius
+        .map(|(r, u)| u.parse::<crate::core::DistanceUnit>().map(|unit| (r, unit)))
+        .transpose()?;
+
+    let by_box = req
+        .by_box
+        .map(|(w, h, u)| {
+            u.parse::<crate::core::DistanceUnit>()
+                .map(|unit| (w, h, unit))
+        })
+        .transpose()?;
 
     let results = state.geospatial_store.geosearch(
         &key,
@@ -402,6 +390,18 @@ pub async fn geospatial_stats(
 
 // ==================== Geospatial StreamableHTTP Command Handlers ====================
 
+/// A payload `unit` through [`crate::core::DistanceUnit::parse_or_meters`];
+/// one that is not a string fails like an unknown unit
+fn payload_unit(unit: Option<&serde_json::Value>) -> Result<crate::core::DistanceUnit, SynapError> {
+    match unit {
+        None | Some(serde_json::Value::Null) => crate::core::DistanceUnit::parse_or_meters(None),
+        Some(serde_json::Value::String(unit)) => {
+            crate::core::DistanceUnit::parse_or_meters(Some(unit))
+        }
+        Some(other) => crate::core::DistanceUnit::parse_or_meters(Some(&other.to_string())),
+    }
+}
+
 pub(super) async fn handle_geospatial_geoadd_cmd(
     state: &AppState,
     request: &Request,
@@ -478,12 +478,7 @@ pub(super) async fn handle_geospatial_geodist_cmd(
         .and_then(|v| v.as_str())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'member2' field".to_string()))?;
 
-    let unit_str = request
-        .payload
-        .get("unit")
-        .and_then(|v| v.as_str())
-        .unwrap_or("m");
-    let unit = unit_str.parse::<crate::core::DistanceUnit>()?;
+    let unit = payload_unit(request.payload.get("unit"))?;
 
     let distance =
         state
@@ -525,12 +520,7 @@ pub(super) async fn handle_geospatial_georadius_cmd(
         .and_then(|v| v.as_f64())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'radius' field".to_string()))?;
 
-    let unit_str = request
-        .payload
-        .get("unit")
-        .and_then(|v| v.as_str())
-        .unwrap_or("m");
-    let unit = unit_str.parse::<crate::core::DistanceUnit>()?;
+    let unit = payload_unit(request.payload.get("unit"))?;
 
     let with_dist = request
         .payload
@@ -611,12 +601,7 @@ pub(super) async fn handle_geospatial_georadiusbymember_cmd(
         .and_then(|v| v.as_f64())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'radius' field".to_string()))?;
 
-    let unit_str = request
-        .payload
-        .get("unit")
-        .and_then(|v| v.as_str())
-        .unwrap_or("m");
-    let unit = unit_str.parse::<crate::core::DistanceUnit>()?;
+    let unit = payload_unit(request.payload.get("unit"))?;
 
     let with_dist = request
         .payload
@@ -773,36 +758,22 @@ pub(super) async fn handle_geospatial_geosearch_cmd(
         }
     });
 
-    let by_radius = request.payload.get("by_radius").and_then(|v| {
-        if let Some(arr) = v.as_array() {
-            if arr.len() == 2 {
-                let radius = arr[0].as_f64()?;
-                let unit_str = arr[1].as_str()?;
-                let unit = unit_str.parse::<crate::core::DistanceUnit>().ok()?;
-                Some((radius, unit))
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    });
+    // An unknown unit is an error, as on every other transport
+    let by_radius = match request.payload.get("by_radius").and_then(|v| v.as_array()) {
+        Some(arr) if arr.len() == 2 => match arr[0].as_f64() {
+            Some(radius) => Some((radius, payload_unit(arr.get(1))?)),
+            None => None,
+        },
+        _ => None,
+    };
 
-    let by_box = request.payload.get("by_box").and_then(|v| {
-        if let Some(arr) = v.as_array() {
-            if arr.len() == 3 {
-                let width = arr[0].as_f64()?;
-                let height = arr[1].as_f64()?;
-                let unit_str = arr[2].as_str()?;
-                let unit = unit_str.parse::<crate::core::DistanceUnit>().ok()?;
-                Some((width, height, unit))
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    });
+    let by_box = match request.payload.get("by_box").and_then(|v| v.as_array()) {
+        Some(arr) if arr.len() == 3 => match (arr[0].as_f64(), arr[1].as_f64()) {
+            (Some(width), Some(height)) => Some((width, height, payload_unit(arr.get(2))?)),
+            _ => None,
+        },
+        _ => None,
+    };
 
     let with_dist = request
         .payload
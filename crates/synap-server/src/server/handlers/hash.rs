@@ -610,6 +610,46 @@ pub(super) async fn handle_hash_getall_cmd(
     Ok(serde_json::json!({ "fields": result, "count": result.len() }))
 }
 
+pub(super) async fn handle_hash_scan_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let key = request
+        .payload
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let cursor = request
+        .payload
+        .get("cursor")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let pattern = request.payload.get("match").and_then(|v| v.as_str());
+    let count = request
+        .payload
+        .get("count")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(100) as usize;
+
+    let (next_cursor, items) = state.hash_store.hscan(key, cursor, pattern, count)?;
+
+    let fields: HashMap<String, serde_json::Value> = items
+        .into_iter()
+        .map(|(k, v)| {
+            let json_value: serde_json::Value = serde_json::from_slice(&v).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&v).to_string())
+            });
+            (k, json_value)
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "fields": fields,
+        "count": fields.len(),
+        "next_cursor": next_cursor,
+    }))
+}
+
 pub(super) async fn handle_hash_del_cmd(
     state: &AppState,
     request: &Request,
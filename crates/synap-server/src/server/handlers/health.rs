@@ -0,0 +1,54 @@
+use super::*;
+use crate::health::{Check, Readiness};
+
+/// GET /health and /health/live - Liveness probe: 200 while the server runs
+pub async fn health_check() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "healthy",
+        "service": "synap",
+        "version": env!("CARGO_PKG_VERSION")
+    }))
+}
+
+/// GET /health/ready - Readiness probe: 503 with the failing checks while a
+/// warmup is pending, the replication link is down, cluster slots are
+/// unassigned or the WAL volume is short on space
+pub async fn readiness_check(State(state): State<AppState>) -> AxumResponse {
+    let config = state.monitoring.health_config();
+    let warmup = state.monitoring.warmup();
+
+    let mut readiness = Readiness::default();
+    // Startup probes answer until recovery completes, so here it always has
+    readiness.add("recovery", Check::pass());
+    readiness.add(
+        "warmup",
+        if warmup.is_ready() {
+            Check::pass()
+        } else {
+            Check::fail("cold start warmup pending")
+        },
+    );
+    readiness.add(
+        "replication",
+        crate::health::check_replication(
+            state.replication_handle().as_ref(),
+            config.max_replication_lag,
+        ),
+    );
+    if let Some(topology) = state.cluster_topology() {
+        readiness.add("cluster", crate::health::check_cluster(topology));
+    }
+    if let Some(wal_dir) = state.persistence.as_ref().and_then(|p| p.wal_dir()) {
+        let min_free_mb = config.min_free_disk_mb;
+        let check =
+            tokio::task::spawn_blocking(move || crate::health::check_disk(&wal_dir, min_free_mb))
+                .await
+                .unwrap_or_else(|_| Check::pass());
+        readiness.add("disk", check);
+    }
+
+    let report = warmup
+        .last_report()
+        .and_then(|report| serde_json::to_value(report).ok());
+    crate::health::readiness_response(&readiness, report)
+}
@@ -37,6 +37,52 @@ fn parse_hyperloglog_element(value: &serde_json::Value) -> Result<Vec<u8>, Synap
     serde_json::to_vec(value).map_err(|e| SynapError::SerializationError(e.to_string()))
 }
 
+/// PFADD `elements` to `key`, then log them to the WAL unless nothing
+/// changed: re-adding elements the HyperLogLog has seen is not logged. Every
+/// protocol's PFADD goes through here.
+pub(crate) async fn pfadd_and_log(
+    state: &AppState,
+    key: &str,
+    elements: Vec<Vec<u8>>,
+    ttl_secs: Option<u64>,
+) -> Result<usize, SynapError> {
+    let logged = state.persistence.as_ref().map(|_| elements.clone());
+    let added = state.hyperloglog_store.pfadd(key, elements, ttl_secs)?;
+
+    if let Some(ref persistence) = state.persistence
+        && let Some(elements) = logged
+        && (added > 0 || ttl_secs.is_some())
+        && let Err(e) = persistence
+            .log_hyperloglog_add(key.to_string(), elements, ttl_secs)
+            .await
+    {
+        error!("Failed to log PFADD to WAL: {}", e);
+    }
+
+    Ok(added)
+}
+
+/// PFMERGE `sources` into `destination`, then log the merged registers to the
+/// WAL. Every protocol's PFMERGE goes through here.
+pub(crate) async fn pfmerge_and_log(
+    state: &AppState,
+    destination: &str,
+    sources: Vec<String>,
+) -> Result<u64, SynapError> {
+    let count = state.hyperloglog_store.pfmerge(destination, sources)?;
+
+    if let Some(ref persistence) = state.persistence
+        && let Some(registers) = state.hyperloglog_store.registers(destination)
+        && let Err(e) = persistence
+            .log_hyperloglog_merge(destination.to_string(), registers)
+            .await
+    {
+        error!("Failed to log PFMERGE to WAL: {}", e);
+    }
+
+    Ok(count)
+}
+
 pub(super) async fn handle_hyperloglog_pfadd_cmd(
     state: &AppState,
     request: &Request,
@@ -58,7 +104,7 @@ pub(super) async fn handle_hyperloglog_pfadd_cmd(
 
     let ttl_secs = request.payload.get("ttl_secs").and_then(|v| v.as_u64());
 
-    let added = state.hyperloglog_store.pfadd(key, elements, ttl_secs)?;
+    let added = pfadd_and_log(state, key, elements, ttl_secs).await?;
 
     Ok(serde_json::json!({ "key": key, "added": added }))
 }
@@ -107,7 +153,13 @@ pub(super) async fn handle_hyperloglog_pfmerge_cmd(
         ));
     }
 
-    let count = state.hyperloglog_store.pfmerge(destination, sources)?;
+    state.check_same_slot(
+        [destination]
+            .into_iter()
+            .chain(sources.iter().map(String::as_str)),
+    )?;
+
+    let count = pfmerge_and_log(state, destination, sources).await?;
 
     Ok(serde_json::json!({ "destination": destination, "count": count }))
 }
@@ -163,9 +215,7 @@ pub async fn hyperloglog_pfadd(
         .map(|element| element.into_bytes())
         .collect();
 
-    let added = state
-        .hyperloglog_store
-        .pfadd(&scoped_key, elements, req.ttl_secs)?;
+    let added = pfadd_and_log(&state, &scoped_key, elements, req.ttl_secs).await?;
 
     Ok(Json(HyperLogLogAddResponse { key, added }))
 }
@@ -233,9 +283,13 @@ pub async fn hyperloglog_pfmerge(
         })
         .collect();
 
-    let count = state
-        .hyperloglog_store
-        .pfmerge(&scoped_destination, scoped_sources)?;
+    state.check_same_slot(
+        [scoped_destination.as_ref()]
+            .into_iter()
+            .chain(scoped_sources.iter().map(String::as_str)),
+    )?;
+
+    let count = pfmerge_and_log(&state, &scoped_destination, scoped_sources).await?;
 
     Ok(Json(HyperLogLogMergeResponse { destination, count }))
 }
@@ -0,0 +1,61 @@
+use super::*;
+
+/// GET /hub/quota - Get user quota statistics
+///
+/// Returns quota information for the authenticated Hub user.
+/// Only available when HiveHub integration is enabled.
+pub async fn hub_quota_stats(
+    State(state): State<AppState>,
+    crate::hub::HubContextExtractor(hub_context_opt): crate::hub::HubContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    // Get Hub context (user_id from Hub access key)
+    let hub_context = hub_context_opt.ok_or_else(|| {
+        SynapError::Unauthorized(
+            "Hub integration enabled but no Hub authentication found".to_string(),
+        )
+    })?;
+
+    // Get HubClient from app state
+    let hub_client = state
+        .hub_client
+        .as_ref()
+        .ok_or_else(|| SynapError::InternalError("HubClient not initialized".to_string()))?;
+
+    // Get quota from cache or fetch from Hub
+    let quota = hub_client
+        .quota_manager()
+        .get_quota(&hub_context.user_id)
+        .ok_or_else(|| {
+            SynapError::InternalError(format!(
+                "Quota not found for user {}. Try authenticating again.",
+                hub_context.user_id
+            ))
+        })?;
+
+    // Return quota statistics
+    Ok(Json(json!({
+        "user_id": hub_context.user_id,
+        "plan": format!("{:?}", quota.plan),
+        "storage": {
+            "used_bytes": quota.storage_used,
+            "limit_bytes": quota.storage_limit,
+            "remaining_bytes": quota.remaining_storage(),
+            "usage_percent": if quota.storage_limit > 0 {
+                (quota.storage_used as f64 / quota.storage_limit as f64 * 100.0).round()
+            } else {
+                0.0
+            }
+        },
+        "operations": {
+            "monthly_count": quota.monthly_operations,
+            "monthly_limit": quota.monthly_operations_limit,
+            "remaining": quota.remaining_operations(),
+            "usage_percent": if quota.monthly_operations_limit > 0 {
+                (quota.monthly_operations as f64 / quota.monthly_operations_limit as f64 * 100.0).round()
+            } else {
+                0.0
+            }
+        },
+        "updated_at": format!("{:?}", quota.updated_at)
+    })))
+}
@@ -1,4 +1,5 @@
 use super::*;
+use bytes::Bytes;
 
 /// Encode a request value the way the KV store expects it.
 ///
@@ -58,16 +59,12 @@ pub async fn kv_set(
 
     let value_bytes = encode_value_bytes(&req.value)?;
 
-    // Reject oversized values before any allocation in the store
-    if let Some(max_bytes) = state.kv_store.config().max_value_size_bytes
-        && value_bytes.len() > max_bytes
-    {
-        return Err(SynapError::InvalidRequest(format!(
-            "Value size {} bytes exceeds max_value_size_bytes {}",
-            value_bytes.len(),
-            max_bytes
-        )));
-    }
+    // Reject oversized values before the write-ahead WAL append below, so a
+    // refused SET is never logged
+    state
+        .kv_store
+        .key_limits()
+        .check_value(&scoped_key, value_bytes.len())?;
 
     // WAL write-ahead: when durability mode is Sync (fsync_mode=Always),
     // log to WAL BEFORE writing to memory. If WAL fails the request fails
@@ -87,6 +84,10 @@ pub async fn kv_set(
         .unwrap_or_default()
         .as_millis() as u64;
 
+    if req.ttl.is_some() {
+        crate::server::deprecation::notice("POST /kv/set ttl");
+    }
+
     // Resolve expiry: `expiry` field takes precedence over legacy `ttl`.
     // Convert to absolute-ms form using the pre-read clock.
     let expiry = req
@@ -100,12 +101,9 @@ pub async fn kv_set(
             already_absolute => already_absolute,
         });
 
-    // Clone value only when WAL persistence is active; move the original into set_with_opts.
-    let wal_value = if state.persistence.is_some() {
-        Some(value_bytes.clone())
-    } else {
-        None
-    };
+    // One allocation serves the store and the WAL: both hold the same
+    // reference-counted buffer, so logging the write never copies the value.
+    let value: Arc<[u8]> = value_bytes.into();
 
     // WAL write-ahead (sync mode): log BEFORE writing to memory
     if is_sync && let Some(ref persistence) = state.persistence {
@@ -117,7 +115,7 @@ pub async fn kv_set(
         persistence
             .log_kv_set(
                 scoped_key.clone().into_owned(),
-                value_bytes.clone(),
+                Bytes::from_owner(Arc::clone(&value)),
                 ttl_secs,
             )
             .await
@@ -134,10 +132,9 @@ pub async fn kv_set(
         return_old: req.get,
     };
 
-    // Set in KV store — move value_bytes (no extra clone when WAL is disabled).
     let result = state
         .kv_store
-        .set_with_opts(&scoped_key, value_bytes, expiry, opts)
+        .set_with_opts(&scoped_key, Arc::clone(&value), expiry, opts)
         .await?;
 
     // Async WAL: log after memory write (default, Periodic/Never modes)
@@ -151,11 +148,9 @@ pub async fn kv_set(
             Expiry::Milliseconds(ms) => Some(ms / 1_000),
             _ => None,
         });
-        // wal_value is Some when persistence is active (cloned above).
-        if let Some(wv) = wal_value
-            && let Err(e) = persistence
-                .log_kv_set(scoped_key.into_owned(), wv, ttl_secs)
-                .await
+        if let Err(e) = persistence
+            .log_kv_set(scoped_key.into_owned(), Bytes::from_owner(value), ttl_secs)
+            .await
         {
             error!(
                 "Failed to log KV SET to WAL (async mode, data in memory): {}",
@@ -255,15 +250,7 @@ pub async fn trigger_snapshot(
 
     if let Some(ref persistence) = state.persistence {
         persistence
-            .maybe_snapshot(crate::persistence::StoreRefs {
-                kv_store: &state.kv_store,
-                hash_store: Some(state.hash_store.as_ref()),
-                list_store: Some(state.list_store.as_ref()),
-                set_store: Some(state.set_store.as_ref()),
-                sorted_set_store: Some(state.sorted_set_store.as_ref()),
-                queue_manager: state.queue_manager.as_deref(),
-                stream_manager: state.stream_manager.as_deref(),
-            })
+            .maybe_snapshot(state.store_refs())
             .await
             .map_err(|e| SynapError::InternalError(format!("Snapshot failed: {}", e)))?;
 
@@ -278,6 +265,62 @@ pub async fn trigger_snapshot(
     }
 }
 
+/// SNAPSHOT EXPORT endpoint - every store as a portable dump file
+pub async fn export_snapshot(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<impl IntoResponse, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Read)?;
+    debug!("REST SNAPSHOT EXPORT");
+
+    let dump = crate::persistence::dump::export(state.store_refs())
+        .await
+        .map_err(|e| SynapError::InternalError(format!("Export failed: {}", e)))?;
+    let filename = format!(
+        "attachment; filename=\"synap-{}.dump\"",
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                "application/octet-stream".to_string(),
+            ),
+            (axum::http::header::CONTENT_DISPOSITION, filename),
+        ],
+        dump,
+    ))
+}
+
+/// SNAPSHOT IMPORT endpoint - replay a dump from `export_snapshot`, then
+/// snapshot so the imported data survives a restart
+pub async fn import_snapshot(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    body: Bytes,
+) -> Result<Json<crate::persistence::ImportSummary>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+    debug!("REST SNAPSHOT IMPORT ({} bytes)", body.len());
+
+    use crate::persistence::PersistenceError;
+    let summary = crate::persistence::dump::import(state.store_refs(), &body)
+        .await
+        .map_err(|e| match e {
+            PersistenceError::SerializationError(_) | PersistenceError::ChecksumMismatch { .. } => {
+                SynapError::InvalidRequest(format!("Invalid dump: {}", e))
+            }
+            e => SynapError::InternalError(format!("Import failed: {}", e)),
+        })?;
+
+    if let Some(ref persistence) = state.persistence {
+        persistence
+            .snapshot_now(state.store_refs())
+            .await
+            .map_err(|e| SynapError::InternalError(format!("Snapshot failed: {}", e)))?;
+    }
+    Ok(Json(summary))
+}
+
 /// STATS endpoint - get store statistics
 pub async fn kv_stats(
     State(state): State<AppState>,
@@ -300,6 +343,12 @@ pub async fn kv_stats(
             misses: stats.misses,
         },
         hit_rate: stats.hit_rate(),
+        evictions: EvictionStats {
+            evicted_keys: stats.evicted_keys,
+            evicted_bytes: stats.evicted_bytes,
+            rejected_writes: stats.rejected_writes,
+        },
+        compression: state.kv_store.compression_stats(),
     }))
 }
 
@@ -737,6 +786,8 @@ pub async fn key_rename(
         )
     };
 
+    state.check_same_slot([&scoped_source, &scoped_dest])?;
+
     let manager = create_key_manager(&state);
     manager.rename(&scoped_source, &scoped_dest).await?;
 
@@ -785,6 +836,8 @@ pub async fn key_renamenx(
         )
     };
 
+    state.check_same_slot([&scoped_source, &scoped_dest])?;
+
     let manager = create_key_manager(&state);
     let success = manager.renamenx(&scoped_source, &scoped_dest).await?;
 
@@ -829,6 +882,8 @@ pub async fn key_copy(
         )
     };
 
+    state.check_same_slot([&scoped_source, &scoped_dest])?;
+
     let manager = create_key_manager(&state);
     let replace = req.replace.unwrap_or(false);
     let success = manager.copy(&scoped_source, &scoped_dest, replace).await?;
@@ -934,15 +989,18 @@ pub async fn info(
     }
 
     if section == InfoSection::All || section == InfoSection::Replication {
-        let repl_info = ReplicationInfo::collect(state.replication.as_ref()).await;
+        let repl_info = ReplicationInfo::collect(state.replication_handle().as_ref()).await;
         response["replication"] = serde_json::to_value(repl_info)
             .map_err(|e| SynapError::SerializationError(e.to_string()))?;
     }
 
     if section == InfoSection::All || section == InfoSection::Keyspace {
         let stores = state.monitoring.stores();
-        let keyspace_info =
+        let mut keyspace_info =
             KeyspaceInfo::collect(stores.0, stores.1, stores.2, stores.3, stores.4).await;
+        keyspace_info
+            .add_databases(&state.monitoring.databases())
+            .await;
         response["keyspace"] = serde_json::to_value(keyspace_info)
             .map_err(|e| SynapError::SerializationError(e.to_string()))?;
     }
@@ -956,7 +1014,7 @@ pub async fn info(
 
 /// Build the INFO `cluster` section from the live topology (issue #232).
 pub(crate) fn info_cluster_section(state: &AppState) -> serde_json::Value {
-    match &state.cluster_topology {
+    match state.cluster_topology() {
         Some(topo) => serde_json::json!({
             "cluster_enabled": 1,
             "cluster_my_id": topo.my_node_id(),
@@ -984,6 +1042,524 @@ pub async fn slowlog(
     })))
 }
 
+/// SLOWLOG reset endpoint - clear the slow query log
+pub async fn slowlog_reset(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let count = state.monitoring.slow_log().reset().await;
+    Ok(Json(serde_json::json!({ "cleared": count })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlowLogConfigRequest {
+    pub threshold_ms: Option<u64>,
+    pub max_entries: Option<usize>,
+}
+
+/// SLOWLOG config endpoint - current threshold and length
+pub async fn slowlog_config(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<crate::monitoring::SlowLogConfig>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    Ok(Json(state.monitoring.slow_log().config()))
+}
+
+/// SLOWLOG config endpoint - change the threshold and/or length at runtime
+pub async fn slowlog_config_set(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(req): Json<SlowLogConfigRequest>,
+) -> Result<Json<crate::monitoring::SlowLogConfig>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let config = state
+        .monitoring
+        .slow_log()
+        .configure(req.threshold_ms, req.max_entries)
+        .await;
+    Ok(Json(config))
+}
+
+/// LATENCY LATEST endpoint - last and slowest spike of every event
+pub async fn latency_latest(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    Ok(Json(serde_json::json!({
+        "events": state.monitoring.latency().latest()
+    })))
+}
+
+/// LATENCY HISTORY endpoint - recorded spikes of one event, oldest first
+pub async fn latency_history(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(event): Path<String>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    let samples = state.monitoring.latency().history(&event);
+    Ok(Json(serde_json::json!({
+        "event": event,
+        "samples": samples
+    })))
+}
+
+/// LATENCY RESET endpoint - drop the history of every event
+pub async fn latency_reset(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let count = state.monitoring.latency().reset(&[]);
+    Ok(Json(serde_json::json!({ "reset": count })))
+}
+
+/// LATENCY RESET endpoint for one event
+pub async fn latency_reset_event(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(event): Path<String>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let count = state.monitoring.latency().reset(&[event]);
+    Ok(Json(serde_json::json!({ "reset": count })))
+}
+
+/// DEPRECATIONS endpoint - which clients still use deprecated API surfaces
+pub async fn deprecations_report(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    Ok(Json(serde_json::json!({
+        "deprecations": state.monitoring.deprecations().report()
+    })))
+}
+
+/// DEPRECATIONS reset endpoint - start counting from zero, e.g. after a
+/// migration deadline
+pub async fn deprecations_reset(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    state.monitoring.deprecations().reset();
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// AUDIT endpoint - recent authentication events, admin operations and
+/// permission denials, most recent first
+///
+/// Query: `limit` (default 100), `event_type` (e.g. `PermissionDenied`) and
+/// `username`.
+pub async fn audit_log(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let limit = params
+        .get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(100);
+    let event_type = params
+        .get("event_type")
+        .map(|v| serde_json::from_value(serde_json::Value::String(v.clone())))
+        .transpose()
+        .map_err(|_| SynapError::InvalidRequest("Unknown event_type".to_string()))?;
+
+    let entries = state
+        .monitoring
+        .audit_log()
+        .get_entries(
+            Some(limit),
+            event_type,
+            params.get("username").map(String::as_str),
+        )
+        .await;
+    Ok(Json(serde_json::json!({
+        "entries": entries,
+        "count": entries.len()
+    })))
+}
+
+/// EVICTION SIMULATION endpoint - project hit rates of candidate eviction
+/// strategies by replaying sampled GETs (`kv_store.access_sampling`).
+///
+/// Query: `capacity` (keys the full-size cache would hold; defaults to the
+/// current key count) and `strategies` (comma-separated, default all).
+pub async fn eviction_simulation(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    use crate::cache::{CacheStrategy, adaptive::simulator};
+
+    let sampler = state.kv_store.access_sampler().ok_or_else(|| {
+        SynapError::InvalidRequest(
+            "Access sampling is disabled (set kv_store.access_sampling.enabled)".to_string(),
+        )
+    })?;
+
+    let strategies = match params.get("strategies") {
+        Some(list) => list
+            .split(',')
+            .map(|name| {
+                CacheStrategy::from_name(name.trim()).ok_or_else(|| {
+                    SynapError::InvalidRequest(format!("Unknown strategy: {}", name.trim()))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => CacheStrategy::ALL.to_vec(),
+    };
+
+    let capacity = match params.get("capacity") {
+        Some(c) => c
+            .parse::<usize>()
+            .map_err(|_| SynapError::InvalidRequest(format!("Invalid capacity: {}", c)))?,
+        None => state.kv_store.dbsize().await?,
+    };
+
+    let policy = state.kv_store.config().eviction_policy;
+    let baseline = simulator::strategy_for_policy(policy);
+    let sample_rate = sampler.sample_rate();
+    // Sampled keys are 1/sample_rate of the keyspace, so the simulated cache is too.
+    let simulated_capacity = capacity.div_ceil(sample_rate as usize).max(1);
+    let trace = sampler.snapshot();
+    let samples = trace.len();
+
+    let results = tokio::task::spawn_blocking(move || {
+        let mut strategies = strategies;
+        if let Some(b) = baseline
+            && !strategies.contains(&b)
+        {
+            strategies.push(b);
+        }
+        strategies
+            .into_iter()
+            .map(|s| simulator::simulate(&trace, simulated_capacity, s))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| SynapError::InternalError(format!("Simulation failed: {}", e)))?;
+
+    let baseline_rate = baseline.and_then(|b| {
+        results
+            .iter()
+            .find(|r| r.strategy == b)
+            .map(|r| r.stats.hit_rate)
+    });
+
+    let results: Vec<serde_json::Value> = results
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "strategy": r.strategy.name(),
+                "hits": r.stats.hits,
+                "misses": r.stats.misses,
+                "evictions": r.stats.evictions,
+                "hit_rate": r.stats.hit_rate,
+                "hit_rate_delta": baseline_rate.map(|b| r.stats.hit_rate - b),
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "current_policy": policy,
+        "baseline": baseline.map(|b| b.name()),
+        "sample_rate": sample_rate,
+        "samples": samples,
+        "capacity": capacity,
+        "simulated_capacity": simulated_capacity,
+        "results": results,
+    })))
+}
+
+/// QUOTAS endpoint - per-namespace key count and memory usage against the
+/// limits in `kv_store.quotas`
+pub async fn namespace_quotas(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let namespaces = state
+        .kv_store
+        .namespace_quotas()
+        .map(|quotas| quotas.usage())
+        .unwrap_or_default();
+    Ok(Json(serde_json::json!({
+        "enabled": !namespaces.is_empty(),
+        "namespaces": namespaces
+    })))
+}
+
+/// Largest keys reported per datatype when `top` is not given.
+pub(super) const DOCTOR_DEFAULT_TOP: usize = 10;
+/// Upper bound on `top`, so the report stays a report.
+const DOCTOR_MAX_TOP: usize = 100;
+/// Share of all estimated key bytes above which one key is flagged.
+const DOCTOR_DOMINANT_SHARE: f64 = 0.5;
+/// Items above which a collection is flagged as a big key.
+const DOCTOR_BIG_COLLECTION: usize = 10_000;
+/// Bytes above which a string is flagged as a big key.
+const DOCTOR_BIG_STRING: usize = 1024 * 1024;
+
+/// The memory doctor report: per-datatype memory estimates and encodings,
+/// the `top` biggest keys overall, the biggest hashes and the most idle keys,
+/// the largest keys of each datatype against the per-key limits, and
+/// suggestions naming the keys worth a look. Shared by `GET /memory/doctor`
+/// and the `memory.doctor` command.
+pub(super) fn memory_doctor_report(state: &AppState, top: usize) -> serde_json::Value {
+    use crate::monitoring::format_bytes;
+
+    let top = top.min(DOCTOR_MAX_TOP);
+    let limits = *state.kv_store.key_limits();
+    let offenders = |keys: &[crate::core::KeySize], limit: Option<usize>| {
+        keys.iter()
+            .map(|k| {
+                serde_json::json!({
+                    "key": k.key,
+                    "size": k.size,
+                    "limit": limit,
+                    "over_limit": limit.is_some_and(|max| k.size > max),
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    let sized = |k: &crate::core::KeySize| serde_json::json!({"key": k.key, "bytes": k.size, "human": format_bytes(k.size)});
+
+    let profiles = [
+        ("string", "strings", state.kv_store.memory_profile(top)),
+        ("hash", "hashes", state.hash_store.memory_profile(top)),
+        ("list", "lists", state.list_store.memory_profile(top)),
+        ("set", "sets", state.set_store.memory_profile(top)),
+        (
+            "zset",
+            "sorted_sets",
+            state.sorted_set_store.memory_profile(top),
+        ),
+    ];
+    let total_bytes: usize = profiles.iter().map(|(_, _, p)| p.bytes).sum();
+    let total_keys: usize = profiles.iter().map(|(_, _, p)| p.keys).sum();
+
+    let mut structures = serde_json::Map::new();
+    let mut biggest = Vec::new();
+    for (key_type, name, profile) in &profiles {
+        structures.insert(
+            name.to_string(),
+            serde_json::json!({
+                "keys": profile.keys,
+                "elements": profile.elements,
+                "bytes": profile.bytes,
+                "human": format_bytes(profile.bytes),
+                "avg_item_bytes": profile.avg_item_bytes(),
+                "bytes_per_key": profile.bytes_per_key(),
+                "encodings": profile.encodings,
+            }),
+        );
+        biggest.extend(profile.biggest.iter().map(|k| (*key_type, k)));
+    }
+    biggest.sort_by_key(|(_, k)| std::cmp::Reverse(k.size));
+    biggest.truncate(top);
+
+    let largest = [
+        (
+            "String",
+            "bytes",
+            state.kv_store.largest_keys(top),
+            limits.max_value_bytes,
+        ),
+        (
+            "Hash",
+            "fields",
+            state.hash_store.largest_keys(top),
+            limits.max_hash_fields,
+        ),
+        (
+            "List",
+            "elements",
+            state.list_store.largest_keys(top),
+            limits.max_list_length,
+        ),
+        (
+            "Sorted set",
+            "members",
+            state.sorted_set_store.largest_keys(top),
+            limits.max_zset_members,
+        ),
+    ];
+
+    let mut suggestions = Vec::new();
+    if let Some((key_type, k)) = biggest.first()
+        && total_keys > 1
+        && k.size as f64 >= total_bytes as f64 * DOCTOR_DOMINANT_SHARE
+    {
+        suggestions.push(format!(
+            "Key '{}' ({}) holds {:.0}% of the estimated {} in keys; consider splitting it",
+            k.key,
+            key_type,
+            k.size as f64 * 100.0 / total_bytes as f64,
+            format_bytes(total_bytes)
+        ));
+    }
+    for (label, unit, keys, limit) in &largest {
+        let big = if *unit == "bytes" {
+            DOCTOR_BIG_STRING
+        } else {
+            DOCTOR_BIG_COLLECTION
+        };
+        for k in keys {
+            if let Some(max) = limit.filter(|max| k.size > *max) {
+                suggestions.push(format!(
+                    "{} '{}' has {} {}, over its limit of {}; trim it",
+                    label, k.key, k.size, unit, max
+                ));
+            } else if k.size > big {
+                suggestions.push(format!(
+                    "{} '{}' has {} {}; reading it whole is slow, consider splitting it",
+                    label, k.key, k.size, unit
+                ));
+            }
+        }
+    }
+
+    serde_json::json!({
+        "limits": limits,
+        "top": top,
+        "total_bytes": total_bytes,
+        "total_human": format_bytes(total_bytes),
+        "structures": structures,
+        "biggest_keys": biggest
+            .iter()
+            .map(|(key_type, k)| {
+                let mut entry = sized(k);
+                entry["type"] = serde_json::json!(key_type);
+                entry
+            })
+            .collect::<Vec<_>>(),
+        "biggest_hashes": profiles[1].2.biggest.iter().map(sized).collect::<Vec<_>>(),
+        "most_idle": state
+            .kv_store
+            .most_idle_keys(top)
+            .into_iter()
+            .map(|k| serde_json::json!({"key": k.key, "idle_secs": k.size}))
+            .collect::<Vec<_>>(),
+        "largest": {
+            "strings": offenders(&largest[0].2, limits.max_value_bytes),
+            "hashes": offenders(&largest[1].2, limits.max_hash_fields),
+            "lists": offenders(&largest[2].2, limits.max_list_length),
+            "sorted_sets": offenders(&largest[3].2, limits.max_zset_members),
+        },
+        "suggestions": suggestions,
+    })
+}
+
+/// MEMORY DOCTOR endpoint - the largest keys of each datatype against the
+/// per-key `limits`
+pub async fn memory_doctor(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    let top = match params.get("top") {
+        Some(t) => t
+            .parse::<usize>()
+            .map_err(|_| SynapError::InvalidRequest(format!("Invalid top: {}", t)))?,
+        None => DOCTOR_DEFAULT_TOP,
+    };
+    Ok(Json(memory_doctor_report(&state, top)))
+}
+
+/// Hottest keys reported when `top` is not given.
+pub(super) const HOT_KEYS_DEFAULT_TOP: usize = 10;
+/// Upper bound on `top` for the hot key report.
+const HOT_KEYS_MAX_TOP: usize = 100;
+
+/// The `top` hottest KV keys of the running and the last completed interval.
+/// Shared by `GET /monitoring/hotkeys` and the `monitoring.hotkeys` command.
+pub(super) fn hot_keys_report(
+    state: &AppState,
+    top: usize,
+) -> Result<serde_json::Value, SynapError> {
+    let tracker = state.kv_store.hot_keys().ok_or_else(|| {
+        SynapError::InvalidRequest(
+            "Hot key tracking is disabled (set kv_store.hot_keys.enabled)".to_string(),
+        )
+    })?;
+    serde_json::to_value(tracker.report(top.min(HOT_KEYS_MAX_TOP)))
+        .map_err(|e| SynapError::SerializationError(e.to_string()))
+}
+
+/// HOTKEYS endpoint - the KV keys with the most sampled GETs and SETs per
+/// interval (`kv_store.hot_keys`)
+pub async fn hot_keys(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    let top = match params.get("top") {
+        Some(t) => t
+            .parse::<usize>()
+            .map_err(|_| SynapError::InvalidRequest(format!("Invalid top: {}", t)))?,
+        None => HOT_KEYS_DEFAULT_TOP,
+    };
+    Ok(Json(hot_keys_report(&state, top)?))
+}
+
+/// DEBUG RUNTIME endpoint - Tokio scheduler activity over a short window
+/// (`?sample_ms=`, default 100)
+#[cfg(feature = "runtime-diagnostics")]
+pub async fn debug_runtime(
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<crate::monitoring::RuntimeReport>, SynapError> {
+    use crate::monitoring::runtime::{DEFAULT_SAMPLE_MS, MAX_SAMPLE_MS};
+
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    let sample_ms = match params.get("sample_ms") {
+        Some(ms) => ms
+            .parse::<u64>()
+            .ok()
+            .filter(|ms| (1..=MAX_SAMPLE_MS).contains(ms))
+            .ok_or_else(|| {
+                SynapError::InvalidRequest(format!(
+                    "Invalid sample_ms: {} (expected 1-{})",
+                    ms, MAX_SAMPLE_MS
+                ))
+            })?,
+        None => DEFAULT_SAMPLE_MS,
+    };
+    let handle = tokio::runtime::Handle::current();
+    Ok(Json(
+        crate::monitoring::RuntimeReport::sample(
+            &handle,
+            std::time::Duration::from_millis(sample_ms),
+        )
+        .await,
+    ))
+}
+
 /// MEMORY USAGE endpoint - get memory usage for a key
 pub async fn memory_usage(
     State(state): State<AppState>,
@@ -1001,23 +1577,10 @@ pub async fn memory_usage(
     let key_type = key_manager.key_type(&key).await?;
 
     // Return 0 usage if key doesn't exist (REST endpoint behavior)
-    if key_type == crate::core::KeyType::None {
-        return Ok(Json(serde_json::json!({
-            "key": key,
-            "bytes": 0,
-            "human": "0B"
-        })));
-    }
-
     let usage = MemoryUsage::calculate_with_stores(
         key_type, &key, &stores.0, &stores.1, &stores.2, &stores.3, &stores.4,
     )
-    .await
-    .unwrap_or_else(|| MemoryUsage {
-        key: key.clone(),
-        bytes: 0,
-        human: "0B".to_string(),
-    });
+    .unwrap_or_else(|| MemoryUsage::missing(&key));
 
     Ok(Json(serde_json::to_value(usage).map_err(|e| {
         SynapError::SerializationError(e.to_string())
@@ -1067,6 +1630,88 @@ pub async fn client_list(
     })))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ClientKillRequest {
+    pub id: Option<String>,
+    /// `ip:port`, as shown by CLIENT LIST
+    pub addr: Option<String>,
+}
+
+/// CLIENT KILL endpoint - disconnect connections by id and/or address
+pub async fn client_kill(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<ClientKillRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    crate::hub::require_standalone_mode(&hub_ctx)?;
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    Ok(Json(
+        client_kill_report(&state, req.id.as_deref(), req.addr.as_deref()).await?,
+    ))
+}
+
+/// Shared by `POST /clients/kill` and the `client.kill` command.
+pub(super) async fn client_kill_report(
+    state: &AppState,
+    id: Option<&str>,
+    addr: Option<&str>,
+) -> Result<serde_json::Value, SynapError> {
+    if id.is_none() && addr.is_none() {
+        return Err(SynapError::InvalidRequest(
+            "CLIENT KILL needs 'id' and/or 'addr'".to_string(),
+        ));
+    }
+    let killed = state.client_list_manager.kill(id, addr).await;
+    Ok(serde_json::json!({ "killed": killed }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClientPauseRequest {
+    pub timeout_ms: u64,
+    #[serde(default = "default_pause_mode")]
+    pub mode: crate::monitoring::PauseMode,
+}
+
+fn default_pause_mode() -> crate::monitoring::PauseMode {
+    crate::monitoring::PauseMode::All
+}
+
+/// CLIENT PAUSE endpoint - hold back every command, or only writes, for
+/// `timeout_ms`
+pub async fn client_pause(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<ClientPauseRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    crate::hub::require_standalone_mode(&hub_ctx)?;
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    state
+        .client_list_manager
+        .pause(std::time::Duration::from_millis(req.timeout_ms), req.mode);
+    Ok(Json(serde_json::json!({
+        "paused": true,
+        "mode": req.mode,
+        "timeout_ms": req.timeout_ms
+    })))
+}
+
+/// CLIENT UNPAUSE endpoint - end a pause early
+pub async fn client_unpause(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    crate::hub::require_standalone_mode(&hub_ctx)?;
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let was_paused = state.client_list_manager.unpause();
+    Ok(Json(serde_json::json!({ "unpaused": was_paused })))
+}
+
 // ==================== Transaction REST Endpoints ====================
 
 #[derive(Debug, Deserialize)]
@@ -1,42 +1,56 @@
 use super::*;
+use crate::protocol::command::kv::{
+    DbSizeResponse, ExpiryResponse, FlushResponse, KeyCopy, KeyDump, KeyDumpResponse, KeyExists,
+    KeyExistsResponse, KeyListResponse, KeyMoveResponse, KeyRename, KeyRenameNx, KeyRestore,
+    KeyType, KeyTypeResponse, KvAppend, KvCompressionStats, KvCounterResponse, KvDecr, KvDel,
+    KvDelResponse, KvEvictionStats, KvExists, KvExistsResponse, KvExpire, KvGet, KvGetRange,
+    KvGetSet, KvIncr, KvKeys, KvMdel, KvMdelResponse, KvMget, KvMgetResponse, KvMset, KvMsetNx,
+    KvOperationStats, KvPair, KvPersist, KvScan, KvSet, KvSetRange, KvSetResponse, KvStatsResponse,
+    KvStrlen, KvTtl, LengthResponse, QueuedResponse, RandomKeyResponse, SuccessResponse,
+    TtlResponse, ValueFormat,
+};
+use bytes::Bytes;
+
+/// The client's open transaction, if it named one
+fn transaction_client(client_id: &Option<String>) -> Option<&str> {
+    client_id.as_deref().filter(|id| !id.is_empty())
+}
+
+fn json_bytes(value: &serde_json::Value) -> Result<Vec<u8>, SynapError> {
+    serde_json::to_vec(value).map_err(|e| SynapError::SerializationError(e.to_string()))
+}
+
+fn pair_bytes(pairs: Vec<KvPair>) -> Result<Vec<(String, Vec<u8>)>, SynapError> {
+    pairs
+        .into_iter()
+        .map(|pair| Ok((pair.key, json_bytes(&pair.value)?)))
+        .collect()
+}
 
 pub(super) async fn handle_kv_set_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
-
-    let value = request
-        .payload
-        .get("value")
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'value' field".to_string()))?;
-
-    let ttl = request.payload.get("ttl").and_then(|v| v.as_u64());
+    let KvSet {
+        key,
+        value,
+        ttl,
+        client_id,
+    } = decode_payload(request)?;
 
     // Store strings as raw UTF-8 so round-trips return the original string,
     // not a JSON-encoded form. Non-string values are JSON-encoded as before.
-    let value_bytes = if let Some(s) = value.as_str() {
-        s.as_bytes().to_vec()
-    } else {
-        serde_json::to_vec(value).map_err(|e| SynapError::SerializationError(e.to_string()))?
+    let value_bytes = match value.as_str() {
+        Some(s) => s.as_bytes().to_vec(),
+        None => json_bytes(&value)?,
     };
 
     // Check if there's an active transaction for this client_id
-    let client_id = request
-        .payload
-        .get("client_id")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    if !client_id.is_empty() {
+    if let Some(client_id) = transaction_client(&client_id) {
         let was_queued = state.transaction_manager.queue_command_if_transaction(
             client_id,
             crate::core::transaction::TransactionCommand::KVSet {
-                key: key.to_string(),
+                key: key.clone(),
                 value: value_bytes.clone(),
                 ttl,
             },
@@ -44,259 +58,176 @@ pub(super) async fn handle_kv_set_cmd(
 
         if was_queued {
             // Command queued in transaction, return success immediately
-            return Ok(serde_json::json!({ "success": true, "queued": true }));
+            return encode_response(QueuedResponse::QUEUED);
         }
     }
 
-    // No active transaction, execute immediately
-    state.kv_store.set(key, value_bytes.clone(), ttl).await?;
+    // No active transaction, execute immediately. The store and the WAL share
+    // one buffer.
+    let value: Arc<[u8]> = value_bytes.into();
+    state.kv_store.set(&key, Arc::clone(&value), ttl).await?;
 
     // Update key version for WATCH (optimistic locking)
-    state.transaction_manager.update_key_version(key);
+    state.transaction_manager.update_key_version(&key);
 
     // Log to WAL
     if let Some(ref persistence) = state.persistence {
         let _ = persistence
-            .log_kv_set(key.to_string(), value_bytes, ttl)
+            .log_kv_set(key, Bytes::from_owner(value), ttl)
             .await;
     }
 
-    Ok(serde_json::json!({ "success": true }))
+    encode_response(KvSetResponse {
+        success: true,
+        queued: false,
+    })
 }
 
 pub(super) async fn handle_kv_get_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
-
-    let return_type = request
-        .payload
-        .get("type")
-        .and_then(|v| v.as_str())
-        .unwrap_or("string");
-
-    let value_bytes = store.get(key).await?;
-
-    if let Some(bytes) = value_bytes {
-        match return_type {
-            "bytes" => Ok(serde_json::json!(bytes)),
-            _ => {
-                // Default: return as string
-                let value_str = String::from_utf8(bytes)
-                    .unwrap_or_else(|e| format!("<binary data: {} bytes>", e.as_bytes().len()));
-
-                Ok(serde_json::json!(value_str))
-            }
+    let KvGet { key, format } = decode_payload(request)?;
+
+    let value = store.get(&key).await?.map(|bytes| match format {
+        Some(ValueFormat::Bytes) => serde_json::json!(bytes),
+        _ => {
+            // Default: return as string
+            let value_str = String::from_utf8(bytes)
+                .unwrap_or_else(|e| format!("<binary data: {} bytes>", e.as_bytes().len()));
+            serde_json::json!(value_str)
         }
-    } else {
-        Ok(serde_json::json!(null))
-    }
+    });
+
+    encode_response(value)
 }
 
 pub(super) async fn handle_kv_del_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KvDel { key, client_id } = decode_payload(request)?;
 
     // Check if there's an active transaction for this client_id
-    let client_id = request
-        .payload
-        .get("client_id")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    if !client_id.is_empty() {
+    if let Some(client_id) = transaction_client(&client_id) {
         let was_queued = state.transaction_manager.queue_command_if_transaction(
             client_id,
             crate::core::transaction::TransactionCommand::KVDel {
-                keys: vec![key.to_string()],
+                keys: vec![key.clone()],
             },
         )?;
 
         if was_queued {
             // Command queued in transaction, return success immediately
-            return Ok(serde_json::json!({ "success": true, "queued": true }));
+            return encode_response(QueuedResponse::QUEUED);
         }
     }
 
     // No active transaction, execute immediately
-    let deleted = state.kv_store.delete(key).await?;
+    let deleted = state.kv_store.delete(&key).await?;
 
     // Update key version for WATCH (optimistic locking) if deleted
     if deleted {
-        state.transaction_manager.update_key_version(key);
+        state.transaction_manager.update_key_version(&key);
     }
 
     // Log to WAL if deleted
     if deleted && let Some(ref persistence) = state.persistence {
-        let _ = persistence.log_kv_del(vec![key.to_string()]).await;
+        let _ = persistence.log_kv_del(vec![key]).await;
     }
 
-    Ok(serde_json::json!({ "deleted": deleted }))
+    encode_response(KvDelResponse {
+        deleted,
+        queued: false,
+    })
 }
 
 pub(super) async fn handle_kv_exists_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KvExists { key } = decode_payload(request)?;
 
-    let exists = store.exists(key).await?;
+    let exists = store.exists(&key).await?;
 
-    Ok(serde_json::json!({ "exists": exists }))
+    encode_response(KvExistsResponse { exists })
 }
 
-pub(super) async fn handle_kv_incr_cmd(
+/// Shared body of `kv.incr` and `kv.decr`; `delta` is signed for the command
+async fn apply_counter(
     state: &AppState,
-    request: &Request,
+    key: String,
+    delta: i64,
+    client_id: Option<String>,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
-
-    let amount = request
-        .payload
-        .get("amount")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(1);
-
     // Check if there's an active transaction for this client_id
-    let client_id = request
-        .payload
-        .get("client_id")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    if !client_id.is_empty() {
+    if let Some(client_id) = transaction_client(&client_id) {
         let was_queued = state.transaction_manager.queue_command_if_transaction(
             client_id,
             crate::core::transaction::TransactionCommand::KVIncr {
-                key: key.to_string(),
-                delta: amount,
+                key: key.clone(),
+                delta,
             },
         )?;
 
         if was_queued {
             // Command queued in transaction, return success immediately
-            return Ok(serde_json::json!({ "success": true, "queued": true }));
+            return encode_response(QueuedResponse::QUEUED);
         }
     }
 
     // No active transaction, execute immediately
-    let value = state.kv_store.incr(key, amount).await?;
+    let value = state.kv_store.incr(&key, delta).await?;
 
     // Update key version for WATCH (optimistic locking)
-    state.transaction_manager.update_key_version(key);
+    state.transaction_manager.update_key_version(&key);
 
-    // Log final value to WAL (INCR is a SET operation)
+    // Log final value to WAL (INCR/DECR is a SET operation)
     if let Some(ref persistence) = state.persistence {
         let _ = persistence
-            .log_kv_set(key.to_string(), value.to_string().into_bytes(), None)
+            .log_kv_set(key, value.to_string().into_bytes(), None)
             .await;
     }
 
-    Ok(serde_json::json!({ "value": value }))
+    encode_response(KvCounterResponse {
+        value,
+        queued: false,
+    })
 }
 
-pub(super) async fn handle_kv_decr_cmd(
+pub(super) async fn handle_kv_incr_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
-
-    let amount = request
-        .payload
-        .get("amount")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(1);
-
-    // Check if there's an active transaction for this client_id
-    let client_id = request
-        .payload
-        .get("client_id")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-
-    if !client_id.is_empty() {
-        let was_queued = state.transaction_manager.queue_command_if_transaction(
-            client_id,
-            crate::core::transaction::TransactionCommand::KVIncr {
-                key: key.to_string(),
-                delta: -amount, // DECR is INCR with negative delta
-            },
-        )?;
+    let KvIncr {
+        key,
+        amount,
+        client_id,
+    } = decode_payload(request)?;
 
-        if was_queued {
-            // Command queued in transaction, return success immediately
-            return Ok(serde_json::json!({ "success": true, "queued": true }));
-        }
-    }
-
-    // No active transaction, execute immediately
-    let value = state.kv_store.decr(key, amount).await?;
-
-    // Update key version for WATCH (optimistic locking)
-    state.transaction_manager.update_key_version(key);
-
-    // Log final value to WAL (DECR is a SET operation)
-    if let Some(ref persistence) = state.persistence {
-        let _ = persistence
-            .log_kv_set(key.to_string(), value.to_string().into_bytes(), None)
-            .await;
-    }
+    apply_counter(state, key, amount.unwrap_or(1), client_id).await
+}
 
-    Ok(serde_json::json!({ "value": value }))
+pub(super) async fn handle_kv_decr_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let KvDecr {
+        key,
+        amount,
+        client_id,
+    } = decode_payload(request)?;
+
+    // DECR is INCR with negative delta
+    apply_counter(state, key, -amount.unwrap_or(1), client_id).await
 }
 
 pub(super) async fn handle_kv_mset_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let pairs_val = request
-        .payload
-        .get("pairs")
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'pairs' field".to_string()))?;
-
-    let pairs_arr = pairs_val
-        .as_array()
-        .ok_or_else(|| SynapError::InvalidRequest("'pairs' must be an array".to_string()))?;
-
-    let mut pairs = Vec::new();
-    for pair in pairs_arr {
-        let key = pair
-            .get("key")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| SynapError::InvalidRequest("Each pair must have 'key'".to_string()))?;
-
-        let value = pair
-            .get("value")
-            .ok_or_else(|| SynapError::InvalidRequest("Each pair must have 'value'".to_string()))?;
-
-        let value_bytes =
-            serde_json::to_vec(value).map_err(|e| SynapError::SerializationError(e.to_string()))?;
-
-        pairs.push((key.to_string(), value_bytes));
-    }
+    let KvMset { pairs } = decode_payload(request)?;
+    let pairs = pair_bytes(pairs)?;
 
     state.kv_store.mset(pairs.clone()).await?;
 
@@ -307,42 +238,30 @@ pub(super) async fn handle_kv_mset_cmd(
         }
     }
 
-    Ok(serde_json::json!({ "success": true }))
+    encode_response(SuccessResponse { success: true })
 }
 
 pub(super) async fn handle_kv_mget_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let keys_val = request
-        .payload
-        .get("keys")
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'keys' field".to_string()))?;
-
-    let keys: Vec<String> = serde_json::from_value(keys_val.clone())
-        .map_err(|e| SynapError::InvalidRequest(format!("Invalid keys array: {}", e)))?;
+    let KvMget { keys } = decode_payload(request)?;
 
-    let values = store.mget(&keys).await?;
-
-    let results: Vec<Option<serde_json::Value>> = values
+    let values = store
+        .mget(&keys)
+        .await?
         .into_iter()
         .map(|opt_bytes| opt_bytes.and_then(|bytes| serde_json::from_slice(&bytes).ok()))
         .collect();
 
-    Ok(serde_json::json!({ "values": results }))
+    encode_response(KvMgetResponse { values })
 }
 
 pub(super) async fn handle_kv_mdel_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let keys_val = request
-        .payload
-        .get("keys")
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'keys' field".to_string()))?;
-
-    let keys: Vec<String> = serde_json::from_value(keys_val.clone())
-        .map_err(|e| SynapError::InvalidRequest(format!("Invalid keys array: {}", e)))?;
+    let KvMdel { keys } = decode_payload(request)?;
 
     let count = state.kv_store.mdel(&keys).await?;
 
@@ -353,26 +272,28 @@ pub(super) async fn handle_kv_mdel_cmd(
         let _ = persistence.log_kv_del(keys).await;
     }
 
-    Ok(serde_json::json!({ "deleted": count }))
+    encode_response(KvMdelResponse { deleted: count })
 }
 
 pub(super) async fn handle_kv_scan_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let prefix = request.payload.get("prefix").and_then(|v| v.as_str());
-    let limit = request
-        .payload
-        .get("limit")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(100) as usize;
-
-    let keys = store.scan(prefix, limit).await?;
-
-    Ok(serde_json::json!({
-        "keys": keys,
-        "count": keys.len()
-    }))
+    let KvScan {
+        prefix,
+        limit,
+        cursor,
+    } = decode_payload(request)?;
+
+    let (keys, next_cursor) = store
+        .scan_page(prefix.as_deref(), cursor.as_deref(), limit.unwrap_or(100))
+        .await?;
+
+    encode_response(KeyListResponse {
+        count: keys.len(),
+        keys,
+        next_cursor,
+    })
 }
 
 pub(super) async fn handle_kv_stats_cmd(
@@ -381,18 +302,30 @@ pub(super) async fn handle_kv_stats_cmd(
 ) -> Result<serde_json::Value, SynapError> {
     let stats = store.stats().await;
 
-    Ok(serde_json::json!({
-        "total_keys": stats.total_keys,
-        "total_memory_bytes": stats.total_memory_bytes,
-        "operations": {
-            "gets": stats.gets,
-            "sets": stats.sets,
-            "dels": stats.dels,
-            "hits": stats.hits,
-            "misses": stats.misses,
+    encode_response(KvStatsResponse {
+        total_keys: u64::try_from(stats.total_keys).unwrap_or_default(),
+        total_memory_bytes: u64::try_from(stats.total_memory_bytes).unwrap_or_default(),
+        operations: KvOperationStats {
+            gets: stats.gets,
+            sets: stats.sets,
+            dels: stats.dels,
+            hits: stats.hits,
+            misses: stats.misses,
+        },
+        hit_rate: stats.hit_rate(),
+        evictions: KvEvictionStats {
+            evicted_keys: stats.evicted_keys,
+            evicted_bytes: stats.evicted_bytes,
+            rejected_writes: stats.rejected_writes,
         },
-        "hit_rate": stats.hit_rate()
-    }))
+        compression: store.compression_stats().map(|c| KvCompressionStats {
+            values: c.values,
+            compressed_values: c.compressed_values,
+            raw_bytes: c.raw_bytes,
+            stored_bytes: c.stored_bytes,
+            ratio: c.ratio,
+        }),
+    })
 }
 
 // ==================== String Extension Command Handlers ====================
@@ -401,186 +334,96 @@ pub(super) async fn handle_kv_append_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KvAppend { key, value } = decode_payload(request)?;
 
-    let value = request
-        .payload
-        .get("value")
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'value' field".to_string()))?;
-
-    let value_bytes =
-        serde_json::to_vec(value).map_err(|e| SynapError::SerializationError(e.to_string()))?;
-
-    let length = state.kv_store.append(key, value_bytes).await?;
+    let length = state.kv_store.append(&key, json_bytes(&value)?).await?;
 
     // Log to WAL (async, non-blocking)
     if let Some(ref persistence) = state.persistence
-        && let Err(e) = persistence.log_kv_set(key.to_string(), vec![], None).await
+        && let Err(e) = persistence.log_kv_set(key, vec![], None).await
     {
         error!("Failed to log KV APPEND to WAL: {}", e);
     }
 
-    Ok(serde_json::json!({ "length": length }))
+    encode_response(LengthResponse { length })
 }
 
 pub(super) async fn handle_kv_getrange_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
-
-    let start = request
-        .payload
-        .get("start")
-        .and_then(|v| v.as_i64())
-        .map(|v| v as isize)
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'start' field".to_string()))?;
-
-    let end = request
-        .payload
-        .get("end")
-        .and_then(|v| v.as_i64())
-        .map(|v| v as isize)
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'end' field".to_string()))?;
-
-    let range_bytes = store.getrange(key, start, end).await?;
-
-    if range_bytes.is_empty() {
-        Ok(serde_json::json!(null))
-    } else {
-        let value_str = String::from_utf8(range_bytes.clone())
-            .unwrap_or_else(|_| format!("<binary data: {} bytes>", range_bytes.len()));
-        Ok(serde_json::json!(value_str))
-    }
+    let KvGetRange { key, start, end } = decode_payload(request)?;
+
+    let range_bytes = store.getrange(&key, start as isize, end as isize).await?;
+
+    let range = (!range_bytes.is_empty()).then(|| {
+        String::from_utf8(range_bytes.clone())
+            .unwrap_or_else(|_| format!("<binary data: {} bytes>", range_bytes.len()))
+    });
+    encode_response(range)
 }
 
 pub(super) async fn handle_kv_setrange_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
-
-    let offset = request
-        .payload
-        .get("offset")
-        .and_then(|v| v.as_u64())
-        .map(|v| v as usize)
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'offset' field".to_string()))?;
-
-    let value = request
-        .payload
-        .get("value")
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'value' field".to_string()))?;
-
-    let value_bytes =
-        serde_json::to_vec(value).map_err(|e| SynapError::SerializationError(e.to_string()))?;
+    let KvSetRange { key, offset, value } = decode_payload(request)?;
 
-    let length = state.kv_store.setrange(key, offset, value_bytes).await?;
+    let length = state
+        .kv_store
+        .setrange(&key, offset, json_bytes(&value)?)
+        .await?;
 
     // Log to WAL (async, non-blocking)
     if let Some(ref persistence) = state.persistence
-        && let Err(e) = persistence.log_kv_set(key.to_string(), vec![], None).await
+        && let Err(e) = persistence.log_kv_set(key, vec![], None).await
     {
         error!("Failed to log KV SETRANGE to WAL: {}", e);
     }
 
-    Ok(serde_json::json!({ "length": length }))
+    encode_response(LengthResponse { length })
 }
 
 pub(super) async fn handle_kv_strlen_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KvStrlen { key } = decode_payload(request)?;
 
-    let length = store.strlen(key).await?;
-    Ok(serde_json::json!({ "length": length }))
+    let length = store.strlen(&key).await?;
+    encode_response(LengthResponse { length })
 }
 
 pub(super) async fn handle_kv_getset_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KvGetSet { key, value } = decode_payload(request)?;
+    let value_bytes = json_bytes(&value)?;
 
-    let value = request
-        .payload
-        .get("value")
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'value' field".to_string()))?;
-
-    let value_bytes =
-        serde_json::to_vec(value).map_err(|e| SynapError::SerializationError(e.to_string()))?;
-
-    let old_value = state.kv_store.getset(key, value_bytes.clone()).await?;
+    let old_value = state.kv_store.getset(&key, value_bytes.clone()).await?;
 
     // Log to WAL (async, non-blocking)
     if let Some(ref persistence) = state.persistence
-        && let Err(e) = persistence
-            .log_kv_set(key.to_string(), value_bytes, None)
-            .await
+        && let Err(e) = persistence.log_kv_set(key, value_bytes, None).await
     {
         error!("Failed to log KV GETSET to WAL: {}", e);
     }
 
-    if let Some(old_bytes) = old_value {
-        let old_str = String::from_utf8(old_bytes.clone())
-            .unwrap_or_else(|_| format!("<binary data: {} bytes>", old_bytes.len()));
-        Ok(serde_json::json!(old_str))
-    } else {
-        Ok(serde_json::json!(null))
-    }
+    let old_value = old_value.map(|old_bytes| {
+        String::from_utf8(old_bytes.clone())
+            .unwrap_or_else(|_| format!("<binary data: {} bytes>", old_bytes.len()))
+    });
+    encode_response(old_value)
 }
 
 pub(super) async fn handle_kv_msetnx_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let pairs = request
-        .payload
-        .get("pairs")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'pairs' array".to_string()))?;
-
-    let kv_pairs: Result<Vec<(String, Vec<u8>)>, SynapError> = pairs
-        .iter()
-        .map(|pair| {
-            let pair_obj = pair
-                .as_object()
-                .ok_or_else(|| SynapError::InvalidRequest("Pair must be an object".to_string()))?;
-            let key = pair_obj
-                .get("key")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' in pair".to_string()))?;
-            let value = pair_obj
-                .get("value")
-                .ok_or_else(|| SynapError::InvalidRequest("Missing 'value' in pair".to_string()))?;
-            let value_bytes = serde_json::to_vec(value)
-                .map_err(|e| SynapError::SerializationError(e.to_string()))?;
-            Ok((key.to_string(), value_bytes))
-        })
-        .collect();
+    let KvMsetNx { pairs } = decode_payload(request)?;
+    let kv_pairs = pair_bytes(pairs)?;
 
-    let kv_pairs = kv_pairs?;
     let success = state.kv_store.msetnx(kv_pairs.clone()).await?;
 
     // Log to WAL if all keys were set
@@ -592,7 +435,7 @@ pub(super) async fn handle_kv_msetnx_cmd(
         }
     }
 
-    Ok(serde_json::json!({ "success": success }))
+    encode_response(SuccessResponse { success })
 }
 
 // ==================== Key Management StreamableHTTP Handlers ====================
@@ -601,94 +444,73 @@ pub(super) async fn handle_key_type_cmd(
     state: AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KeyType { key } = decode_payload(request)?;
 
     let manager = create_key_manager(&state);
-    let key_type = manager.key_type(key).await?;
+    let key_type = manager.key_type(&key).await?;
 
-    Ok(serde_json::json!({
-        "key": key,
-        "type": key_type.as_str()
-    }))
+    encode_response(KeyTypeResponse {
+        key,
+        key_type: key_type.as_str().to_string(),
+    })
 }
 
 pub(super) async fn handle_key_exists_cmd(
     state: AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KeyExists { key } = decode_payload(request)?;
 
     let manager = create_key_manager(&state);
-    let exists = manager.exists(key).await?;
+    let exists = manager.exists(&key).await?;
 
-    Ok(serde_json::json!({
-        "key": key,
-        "exists": exists
-    }))
+    encode_response(KeyExistsResponse { key, exists })
 }
 
 pub(super) async fn handle_key_rename_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let source = request
-        .payload
-        .get("source")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'source' field".to_string()))?;
+    let KeyRename {
+        source,
+        destination,
+    } = decode_payload(request)?;
 
-    let destination = request
-        .payload
-        .get("destination")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'destination' field".to_string()))?;
+    state.check_same_slot([&source, &destination])?;
 
     let manager = create_key_manager(state);
-    manager.rename(source, destination).await?;
+    manager.rename(&source, &destination).await?;
 
     // Log to WAL if persistence is enabled
     if let Some(ref persistence) = state.persistence
         && let Err(e) = persistence
-            .log_kv_rename(source.to_string(), destination.to_string())
+            .log_kv_rename(source.clone(), destination.clone())
             .await
     {
         error!("Failed to log RENAME to WAL: {}", e);
         // Don't fail the request, just log the error
     }
 
-    Ok(serde_json::json!({
-        "success": true,
-        "source": source,
-        "destination": destination
-    }))
+    encode_response(KeyMoveResponse {
+        success: true,
+        source,
+        destination,
+    })
 }
 
 pub(super) async fn handle_key_renamenx_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let source = request
-        .payload
-        .get("source")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'source' field".to_string()))?;
+    let KeyRenameNx {
+        source,
+        destination,
+    } = decode_payload(request)?;
 
-    let destination = request
-        .payload
-        .get("destination")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'destination' field".to_string()))?;
+    state.check_same_slot([&source, &destination])?;
 
     let manager = create_key_manager(state);
-    let success = manager.renamenx(source, destination).await?;
+    let success = manager.renamenx(&source, &destination).await?;
 
     if !success {
         return Err(SynapError::InvalidRequest(
@@ -696,37 +518,27 @@ pub(super) async fn handle_key_renamenx_cmd(
         ));
     }
 
-    Ok(serde_json::json!({
-        "success": true,
-        "source": source,
-        "destination": destination
-    }))
+    encode_response(KeyMoveResponse {
+        success: true,
+        source,
+        destination,
+    })
 }
 
 pub(super) async fn handle_key_copy_cmd(
     state: &AppState,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let source = request
-        .payload
-        .get("source")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'source' field".to_string()))?;
-
-    let destination = request
-        .payload
-        .get("destination")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'destination' field".to_string()))?;
+    let KeyCopy {
+        source,
+        destination,
+        replace,
+    } = decode_payload(request)?;
 
-    let replace = request
-        .payload
-        .get("replace")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+    state.check_same_slot([&source, &destination])?;
 
     let manager = create_key_manager(state);
-    let success = manager.copy(source, destination, replace).await?;
+    let success = manager.copy(&source, &destination, replace).await?;
 
     if !success {
         return Err(SynapError::InvalidRequest(
@@ -734,11 +546,118 @@ pub(super) async fn handle_key_copy_cmd(
         ));
     }
 
-    Ok(serde_json::json!({
-        "success": true,
-        "source": source,
-        "destination": destination
-    }))
+    encode_response(KeyMoveResponse {
+        success: true,
+        source,
+        destination,
+    })
+}
+
+pub(super) async fn handle_key_dump_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let KeyDump { key } = decode_payload(request)?;
+
+    let manager = create_key_manager(state);
+    let payload = manager.dump(&key).await?.map(|dump| dump.encode());
+
+    encode_response(KeyDumpResponse { key, payload })
+}
+
+pub(super) async fn handle_key_restore_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let KeyRestore {
+        key,
+        payload,
+        ttl,
+        replace,
+        absttl,
+    } = decode_payload(request)?;
+
+    let mut dump = crate::core::KeyDump::decode(&payload)?;
+    dump.ttl_ms = match ttl {
+        None => dump.ttl_ms,
+        Some(0) => None,
+        // Zero restores nothing when the deadline has passed
+        Some(at) if absttl => Some(at.saturating_sub(unix_millis())),
+        Some(ms) => Some(ms),
+    };
+
+    let manager = create_key_manager(state);
+    let replaced = manager.restore(&key, &dump, replace).await?;
+
+    if let Some(ref persistence) = state.persistence
+        && let Err(e) = log_restore(persistence, &key, replaced, &dump).await
+    {
+        error!("Failed to log RESTORE to WAL: {}", e);
+    }
+
+    encode_response(SuccessResponse { success: true })
+}
+
+fn unix_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Record a restore as the removal of the old value and the writes that
+/// rebuilt the key
+async fn log_restore(
+    persistence: &crate::persistence::PersistenceLayer,
+    key: &str,
+    replaced: Option<crate::core::KeyDump>,
+    restored: &crate::core::KeyDump,
+) -> crate::persistence::Result<()> {
+    use crate::core::DumpedValue;
+
+    let key = key.to_string();
+    match replaced.map(|dump| dump.value) {
+        None => {}
+        Some(DumpedValue::String(_)) => persistence.log_kv_del(vec![key.clone()]).await?,
+        Some(DumpedValue::Hash(fields)) => {
+            let fields = fields.into_iter().map(|(field, _)| field).collect();
+            persistence.log_hash_del(key.clone(), fields).await?
+        }
+        Some(DumpedValue::List(_)) => persistence.log_list_trim(key.clone(), 1, 0).await?,
+        Some(DumpedValue::Set(members)) => persistence.log_set_rem(key.clone(), members).await?,
+        Some(DumpedValue::SortedSet(members)) => {
+            let members = members.into_iter().map(|(member, _)| member).collect();
+            persistence.log_zrem(key.clone(), members).await?
+        }
+    }
+
+    if restored.ttl_ms == Some(0) {
+        return Ok(());
+    }
+    match &restored.value {
+        DumpedValue::String(value) => {
+            let ttl = restored.ttl_ms.map(|ms| ms.div_ceil(1000));
+            persistence.log_kv_set(key, value.clone(), ttl).await
+        }
+        DumpedValue::Hash(fields) => {
+            for (field, value) in fields {
+                persistence
+                    .log_hash_set(key.clone(), field.clone(), value.clone())
+                    .await?;
+            }
+            Ok(())
+        }
+        DumpedValue::List(items) => persistence.log_list_push(key, items.clone(), false).await,
+        DumpedValue::Set(members) => persistence.log_set_add(key, members.clone()).await,
+        DumpedValue::SortedSet(members) => {
+            for (member, score) in members {
+                persistence
+                    .log_zadd(key.clone(), member.clone(), *score, &Default::default())
+                    .await?;
+            }
+            Ok(())
+        }
+    }
 }
 
 pub(super) async fn handle_key_randomkey_cmd(
@@ -746,19 +665,27 @@ pub(super) async fn handle_key_randomkey_cmd(
     _request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
     let manager = create_key_manager(&state);
-    let random_key = manager.randomkey().await?;
+    let key = manager.randomkey().await?;
 
-    Ok(serde_json::json!({
-        "key": random_key
-    }))
+    encode_response(RandomKeyResponse { key })
 }
 
 pub(super) async fn handle_kv_keys_cmd(
     store: Arc<KVStore>,
-    _request: &Request,
+    request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let keys = store.keys().await?;
-    Ok(serde_json::json!({ "keys": keys, "count": keys.len() }))
+    crate::server::deprecation::notice("kv.keys");
+    let KvKeys { prefix } = decode_payload(request)?;
+
+    let mut keys = store.keys().await?;
+    if let Some(prefix) = prefix {
+        keys.retain(|key| key.starts_with(&prefix));
+    }
+    encode_response(KeyListResponse {
+        count: keys.len(),
+        keys,
+        next_cursor: None,
+    })
 }
 
 pub(super) async fn handle_kv_dbsize_cmd(
@@ -766,69 +693,55 @@ pub(super) async fn handle_kv_dbsize_cmd(
     _request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
     let size = store.dbsize().await?;
-    Ok(serde_json::json!({ "size": size }))
+    encode_response(DbSizeResponse { size })
 }
 
 pub(super) async fn handle_kv_flushdb_cmd(
     store: Arc<KVStore>,
     _request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let count = store.flushdb().await?;
-    Ok(serde_json::json!({ "flushed": count }))
+    let flushed = store.flushdb().await?;
+    encode_response(FlushResponse { flushed })
 }
 
+/// Flushes the KV store of every database
 pub(super) async fn handle_kv_flushall_cmd(
-    store: Arc<KVStore>,
+    state: &AppState,
     _request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let count = store.flushall().await?;
-    Ok(serde_json::json!({ "flushed": count }))
+    let mut flushed = state.monitoring.kv_store().flushall().await?;
+    for (_, database) in state.monitoring.databases().iter() {
+        flushed += database.kv_store.flushall().await?;
+    }
+    encode_response(FlushResponse { flushed })
 }
 
 pub(super) async fn handle_kv_expire_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
-
-    let ttl = request
-        .payload
-        .get("ttl")
-        .and_then(|v| v.as_u64())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'ttl' field".to_string()))?;
+    let KvExpire { key, ttl } = decode_payload(request)?;
 
-    let result = store.expire(key, ttl).await?;
-    Ok(serde_json::json!({ "result": result }))
+    let result = store.expire(&key, ttl).await?;
+    encode_response(ExpiryResponse { result })
 }
 
 pub(super) async fn handle_kv_ttl_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KvTtl { key } = decode_payload(request)?;
 
-    let ttl = store.ttl(key).await?;
-    Ok(serde_json::json!({ "ttl": ttl }))
+    let ttl = store.ttl(&key).await?;
+    encode_response(TtlResponse { ttl })
 }
 
 pub(super) async fn handle_kv_persist_cmd(
     store: Arc<KVStore>,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
-    let key = request
-        .payload
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let KvPersist { key } = decode_payload(request)?;
 
-    let result = store.persist(key).await?;
-    Ok(serde_json::json!({ "result": result }))
+    let result = store.persist(&key).await?;
+    encode_response(ExpiryResponse { result })
 }
@@ -506,6 +506,7 @@ pub(super) async fn handle_list_rpoplpush_cmd(
         .and_then(|v| v.as_str())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'destination' field".to_string()))?;
 
+    state.check_same_slot([source, destination])?;
     let value = state.list_store.rpoplpush(source, destination)?;
 
     let json_value: serde_json::Value = serde_json::from_slice(&value)
@@ -1085,6 +1086,8 @@ pub async fn list_rpoplpush(
     let scoped_destination =
         crate::hub::MultiTenant::scope_kv_key(hub_ctx.as_ref().map(|c| c.user_id()), &destination);
 
+    state.check_same_slot([&scoped_source, &scoped_destination])?;
+
     let value = state
         .list_store
         .rpoplpush(&scoped_source, &scoped_destination)?;
@@ -0,0 +1,70 @@
+use super::*;
+use crate::core::lock::MAX_LEASE;
+use crate::protocol::command::lock::{
+    LockAcquire, LockAcquireResponse, LockRelease, LockReleaseResponse, LockRenew,
+    LockRenewResponse,
+};
+use std::time::Duration;
+
+/// A lease of zero would be granted already expired, and one past
+/// [`MAX_LEASE`] would be cut short without the caller knowing
+fn lease(ttl: u64) -> Result<Duration, SynapError> {
+    if ttl == 0 {
+        return Err(SynapError::InvalidRequest(
+            "Lock ttl must be at least 1 ms".to_string(),
+        ));
+    }
+    let lease = Duration::from_millis(ttl);
+    if lease > MAX_LEASE {
+        return Err(SynapError::InvalidRequest(format!(
+            "Lock ttl must be at most {} ms",
+            MAX_LEASE.as_millis()
+        )));
+    }
+    Ok(lease)
+}
+
+// ============================================================================
+// Lock StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_lock_acquire_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let LockAcquire { key, ttl } = decode_payload(request)?;
+
+    let response = match state.lock_manager.acquire(&key, lease(ttl)?) {
+        Some(grant) => LockAcquireResponse {
+            acquired: true,
+            token: Some(grant.token),
+            ttl: None,
+        },
+        None => LockAcquireResponse {
+            acquired: false,
+            token: None,
+            ttl: state.lock_manager.ttl(&key),
+        },
+    };
+    encode_response(response)
+}
+
+pub(super) async fn handle_lock_renew_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let LockRenew { key, token, ttl } = decode_payload(request)?;
+
+    let renewed = state.lock_manager.renew(&key, token, lease(ttl)?);
+    encode_response(LockRenewResponse { renewed })
+}
+
+pub(super) async fn handle_lock_release_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let LockRelease { key, token } = decode_payload(request)?;
+
+    let released = state.lock_manager.release(&key, token);
+    encode_response(LockReleaseResponse { released })
+}
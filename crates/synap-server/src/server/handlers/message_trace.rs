@@ -0,0 +1,44 @@
+use super::*;
+use crate::core::{MessageTrace, MessageTracer};
+use crate::protocol::command::trace::{MessageTraceGet, MessageTraceResponse};
+
+/// The tracer shared by the queue and stream managers
+fn tracer(state: &AppState) -> Result<&Arc<MessageTracer>, SynapError> {
+    state
+        .queue_manager()
+        .and_then(|queues| queues.tracer())
+        .or_else(|| state.stream_manager().and_then(|streams| streams.tracer()))
+        .ok_or_else(|| {
+            SynapError::InvalidRequest(
+                "Message tracing is disabled (set message_trace.enabled)".to_string(),
+            )
+        })
+}
+
+/// GET /messages/{id}/trace - Delivery timeline of a queue message or stream
+/// event (`message_trace`)
+pub async fn message_trace(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(id): Path<String>,
+) -> Result<Json<MessageTrace>, SynapError> {
+    debug!("REST GET /messages/{}/trace", id);
+
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    tracer(&state)?
+        .get(&id)
+        .map(Json)
+        .ok_or_else(|| SynapError::ResourceNotFound(format!("Trace of message '{}'", id)))
+}
+
+pub(super) async fn handle_message_trace_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let MessageTraceGet { id } = decode_payload(request)?;
+
+    encode_response(MessageTraceResponse {
+        trace: tracer(state)?.get(&id),
+    })
+}
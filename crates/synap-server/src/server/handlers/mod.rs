@@ -5,59 +5,103 @@ use crate::core::{
     SortedSetStore, SynapError, TransactionManager,
 };
 use crate::monitoring::{
-    InfoSection, KeyspaceInfo, MemoryInfo, MemoryUsage, ReplicationInfo, ServerInfo, StatsInfo,
+    CommandProtocol, InfoSection, KeyspaceInfo, MemoryInfo, MemoryUsage, ReplicationInfo,
+    ServerInfo, StatsInfo,
 };
+use crate::protocol::command::Command;
+#[cfg(feature = "scripting")]
 use crate::scripting::{ScriptExecContext, ScriptManager};
 use crate::server::envelope::{Request, Response};
+use crate::server::ws::{self, WebSocket, WebSocketUpgrade};
 use axum::{
     Json,
-    extract::{
-        Path, Query, State,
-        ws::{WebSocket, WebSocketUpgrade},
-    },
+    extract::{Path, Query, State},
     response::{IntoResponse, Response as AxumResponse},
 };
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::{collections::HashMap, time::Duration};
 use tokio::sync::mpsc;
-use tracing::{debug, error, info, warn};
+use tracing::{Instrument, debug, error, info, warn};
 
 pub mod admin_cmd;
 pub mod bitmap;
+pub mod bloom;
+#[cfg(feature = "cluster")]
 pub mod cluster;
+mod command_acl;
+pub mod command_encoding;
+pub(crate) use command_acl::{hook_command, write_resource};
+pub mod failover;
 pub mod geospatial;
 pub mod hash;
+pub mod health;
 pub mod hll;
+pub mod hub;
 pub mod kv;
 pub mod kv_cmd;
 pub mod list;
+pub mod lock;
+pub mod message_trace;
+#[cfg(feature = "streams")]
 pub mod partition;
+#[cfg(feature = "pubsub")]
 pub mod pubsub;
+#[cfg(feature = "queues")]
 pub mod queue;
+pub mod ratelimit;
+pub mod runtime_config;
+pub mod schedule;
+#[cfg(feature = "scripting")]
 pub mod script;
 pub mod set;
+#[cfg(feature = "queues")]
+pub mod shovel;
 pub mod sorted_set;
+#[cfg(feature = "streams")]
 pub mod stream;
+pub mod subscriptions;
+pub mod timeseries;
+pub mod warmup;
+#[cfg(feature = "queues")]
+pub mod webhook;
 pub mod websocket;
 
 pub use bitmap::*;
+#[cfg(feature = "cluster")]
 pub use cluster::*;
+pub use failover::*;
 pub use geospatial::*;
 pub use hash::*;
+pub use health::*;
 pub use hll::*;
+pub use hub::*;
 pub use kv::*;
 pub use list::*;
+pub use message_trace::*;
+#[cfg(feature = "streams")]
 pub use partition::*;
+#[cfg(feature = "pubsub")]
 pub use pubsub::*;
+#[cfg(feature = "queues")]
 pub use queue::*;
+pub use runtime_config::*;
+pub use schedule::*;
+#[cfg(feature = "scripting")]
 pub use script::*;
 pub use set::*;
+#[cfg(feature = "queues")]
+pub use shovel::*;
 pub use sorted_set::*;
+#[cfg(feature = "streams")]
 pub use stream::*;
+pub use subscriptions::*;
+pub use warmup::*;
+#[cfg(feature = "queues")]
+pub use webhook::*;
 pub use websocket::*;
 
 /// Application state shared across handlers
@@ -71,18 +115,25 @@ pub struct AppState {
     pub hyperloglog_store: Arc<HyperLogLogStore>,
     pub bitmap_store: Arc<crate::core::BitmapStore>,
     pub geospatial_store: Arc<GeospatialStore>,
+    #[cfg(feature = "queues")]
     pub queue_manager: Option<Arc<QueueManager>>,
+    #[cfg(feature = "streams")]
     pub stream_manager: Option<Arc<crate::core::StreamManager>>,
+    #[cfg(feature = "streams")]
     pub partition_manager: Option<Arc<crate::core::PartitionManager>>,
+    #[cfg(feature = "streams")]
     pub consumer_group_manager: Option<Arc<crate::core::ConsumerGroupManager>>,
     pub pubsub_router: Option<Arc<crate::core::PubSubRouter>>,
     pub persistence: Option<Arc<crate::persistence::PersistenceLayer>>,
     pub monitoring: Arc<crate::monitoring::MonitoringManager>,
     pub transaction_manager: Arc<TransactionManager>,
+    #[cfg(feature = "scripting")]
     pub script_manager: Arc<ScriptManager>,
     pub client_list_manager: Arc<crate::monitoring::ClientListManager>,
+    #[cfg(feature = "cluster")]
     /// Optional cluster topology (cluster mode)
     pub cluster_topology: Option<Arc<crate::cluster::topology::ClusterTopology>>,
+    #[cfg(feature = "cluster")]
     /// Optional cluster migration manager (cluster mode)
     pub cluster_migration: Option<Arc<crate::cluster::migration::SlotMigrationManager>>,
     /// Optional Hub client (Hub integration mode)
@@ -94,6 +145,151 @@ pub struct AppState {
     /// Live replication role handle, so INFO/metrics report real status. `None`
     /// when replication is disabled (phase6j item 1.4).
     pub replication: Option<crate::replication::ReplicationHandle>,
+    /// Automatic failover coordinator. `None` unless
+    /// `replication.failover.enabled` is set.
+    pub failover: Option<Arc<crate::replication::FailoverCoordinator>>,
+    /// Lease-based distributed locks (`lock.*` commands).
+    pub lock_manager: Arc<crate::core::LockManager>,
+    /// Per-key rate limiters (`ratelimit.*` commands).
+    pub rate_limits: Arc<crate::core::RateLimitStore>,
+    /// Time-bucketed counters (`ts.*` commands).
+    pub time_series: Arc<crate::core::TimeSeriesStore>,
+    /// Bloom filters (`bf.*` commands).
+    pub bloom_store: Arc<crate::core::BloomFilterStore>,
+    /// Queue shovels. `None` when queues are disabled.
+    #[cfg(feature = "queues")]
+    pub shovels: Option<Arc<crate::shovel::ShovelManager>>,
+    /// Webhook push subscriptions. `None` when queues are disabled.
+    #[cfg(feature = "queues")]
+    pub webhooks: Option<Arc<crate::webhook::WebhookManager>>,
+    /// Cron schedules. `None` in states built without one, e.g. in tests.
+    pub schedules: Option<Arc<crate::scheduler::ScheduleManager>>,
+}
+
+impl AppState {
+    /// Queue manager. `None` when queues are disabled in the config or the
+    /// server was built without the `queues` feature.
+    pub fn queue_manager(&self) -> Option<&Arc<QueueManager>> {
+        #[cfg(feature = "queues")]
+        return self.queue_manager.as_ref();
+        #[cfg(not(feature = "queues"))]
+        None
+    }
+
+    /// Event stream manager. `None` when built without the `streams` feature.
+    pub fn stream_manager(&self) -> Option<&Arc<crate::core::StreamManager>> {
+        #[cfg(feature = "streams")]
+        return self.stream_manager.as_ref();
+        #[cfg(not(feature = "streams"))]
+        None
+    }
+
+    /// Cluster topology. `None` outside cluster mode or when built without
+    /// the `cluster` feature.
+    pub fn cluster_topology(&self) -> Option<&Arc<crate::cluster::topology::ClusterTopology>> {
+        #[cfg(feature = "cluster")]
+        return self.cluster_topology.as_ref();
+        #[cfg(not(feature = "cluster"))]
+        None
+    }
+
+    /// Current replication role. Follows promotions and demotions made by
+    /// automatic failover, unlike the `replication` handle set at startup.
+    pub fn replication_handle(&self) -> Option<crate::replication::ReplicationHandle> {
+        match &self.failover {
+            Some(failover) => Some(failover.handle()),
+            None => self.replication.clone(),
+        }
+    }
+
+    /// This state with database `db`'s stores and persistence in place of
+    /// database 0's
+    pub fn select(&self, db: u32) -> Result<AppState, SynapError> {
+        if db == 0 {
+            return Ok(self.clone());
+        }
+        let databases = self.monitoring.databases();
+        let database = databases.get(db).ok_or_else(|| {
+            SynapError::InvalidRequest(format!(
+                "Database {} does not exist (databases.count is {})",
+                db,
+                databases.count()
+            ))
+        })?;
+        let mut state = self.clone();
+        state.kv_store = database.kv_store.clone();
+        state.hash_store = database.hash_store.clone();
+        state.list_store = database.list_store.clone();
+        state.set_store = database.set_store.clone();
+        state.sorted_set_store = database.sorted_set_store.clone();
+        state.hyperloglog_store = database.hyperloglog_store.clone();
+        state.bitmap_store = database.bitmap_store.clone();
+        state.geospatial_store = database.geospatial_store.clone();
+        state.bloom_store = database.bloom_store.clone();
+        state.transaction_manager = database.transaction_manager.clone();
+        state.persistence = database.persistence.clone();
+        Ok(state)
+    }
+
+    /// Every store, for the persistence routines that walk them all
+    pub fn store_refs(&self) -> crate::persistence::StoreRefs<'_> {
+        crate::persistence::StoreRefs {
+            kv_store: &self.kv_store,
+            hash_store: Some(self.hash_store.as_ref()),
+            list_store: Some(self.list_store.as_ref()),
+            set_store: Some(self.set_store.as_ref()),
+            sorted_set_store: Some(self.sorted_set_store.as_ref()),
+            hyperloglog_store: Some(self.hyperloglog_store.as_ref()),
+            bloom_store: Some(self.bloom_store.as_ref()),
+            bitmap_store: Some(self.bitmap_store.as_ref()),
+            queue_manager: self.queue_manager().map(|qm| qm.as_ref()),
+            stream_manager: self.stream_manager().map(|sm| sm.as_ref()),
+        }
+    }
+
+    /// Durable subscription registry, when `persistence.durable_subscriptions`
+    /// is enabled
+    pub fn subscriptions(&self) -> Option<&Arc<crate::persistence::SubscriptionRegistry>> {
+        self.persistence.as_ref().and_then(|p| p.subscriptions())
+    }
+
+    /// Record durable subscription state. Like WAL logging, a failure is
+    /// logged and does not fail the request.
+    #[cfg(feature = "streams")]
+    pub(crate) async fn record_subscription(&self, record: crate::persistence::SubscriptionRecord) {
+        if let Some(registry) = self.subscriptions()
+            && let Err(e) = registry.put(record).await
+        {
+            error!("Failed to record subscription: {}", e);
+        }
+    }
+
+    /// Drop durable subscription records matching `predicate`
+    #[cfg(feature = "streams")]
+    pub(crate) async fn forget_subscriptions(
+        &self,
+        predicate: impl Fn(&crate::persistence::SubscriptionRecord) -> bool,
+    ) {
+        if let Some(registry) = self.subscriptions()
+            && let Err(e) = registry.remove_where(predicate).await
+        {
+            error!("Failed to remove subscription records: {}", e);
+        }
+    }
+
+    /// Multi-key commands on stores without their own cluster routing: in
+    /// cluster mode every key must hash to the same slot (`CROSSSLOT`
+    /// otherwise). Related keys share a slot through a `{hash tag}`.
+    pub fn check_same_slot<I, K>(&self, keys: I) -> Result<(), SynapError>
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<str>,
+    {
+        if self.cluster_topology().is_some() {
+            crate::cluster::hash_slot::same_slot(keys)?;
+        }
+        Ok(())
+    }
 }
 
 // Request/Response types for REST API
@@ -152,6 +348,10 @@ pub struct StatsResponse {
     pub total_memory_bytes: usize,
     pub operations: OperationStats,
     pub hit_rate: f64,
+    pub evictions: EvictionStats,
+    /// Present when value compression is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<crate::compression::ValueCompressionReport>,
 }
 
 #[derive(Debug, Serialize)]
@@ -163,6 +363,13 @@ pub struct OperationStats {
     pub misses: u64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct EvictionStats {
+    pub evicted_keys: u64,
+    pub evicted_bytes: u64,
+    pub rejected_writes: u64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct EvalScriptRequest {
     pub script: String,
@@ -219,13 +426,46 @@ pub struct ScriptKillResponse {
     pub terminated: bool,
 }
 
-/// Health check endpoint
-pub async fn health_check() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "status": "healthy",
-        "service": "synap",
-        "version": env!("CARGO_PKG_VERSION")
-    }))
+#[derive(Debug, Deserialize)]
+pub struct FunctionLoadRequest {
+    pub code: String,
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionLoadResponse {
+    pub library: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FunctionDeleteRequest {
+    pub library: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionDeleteResponse {
+    pub deleted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionListResponse {
+    pub libraries: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FcallRequest {
+    pub function: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<serde_json::Value>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FcallResponse {
+    pub result: serde_json::Value,
 }
 
 // Queue REST API types
@@ -236,6 +476,7 @@ pub struct CreateQueueRequest {
     pub default_max_retries: Option<u32>,
     pub default_priority: Option<u8>,
     pub prefetch_limit: Option<usize>,
+    pub dedup_window_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -244,11 +485,18 @@ pub struct PublishRequest {
     pub priority: Option<u8>,
     pub max_retries: Option<u32>,
     pub headers: Option<HashMap<String, String>>,
+    /// Retries carrying the same key within the queue's dedup window are
+    /// not enqueued again
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct PublishResponse {
     pub message_id: String,
+    /// Set when `idempotency_key` was a repeat and nothing was enqueued
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub duplicate: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -277,12 +525,19 @@ pub struct NackRequest {
 pub struct StreamPublishRequest {
     pub event: String,
     pub data: serde_json::Value,
+    /// Retries carrying the same key within the room's dedup window are
+    /// not appended again
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct StreamPublishResponse {
     pub offset: u64,
     pub room: String,
+    /// Set when `idempotency_key` was a repeat and nothing was appended
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub duplicate: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -291,28 +546,165 @@ pub struct StreamConsumeResponse {
     pub next_offset: u64,
 }
 
+/// One room and the offset to read it from in a multi-room consume
+#[derive(Debug, Deserialize)]
+pub struct StreamRoomOffset {
+    pub room: String,
+    #[serde(default)]
+    pub offset: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamConsumeManyRequest {
+    pub subscriber_id: String,
+    pub rooms: Vec<StreamRoomOffset>,
+    pub limit: Option<usize>,
+    /// How long to wait for the first event when every room is caught up
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamConsumeManyResponse {
+    pub events: Vec<crate::core::StreamEvent>,
+    pub next_offsets: std::collections::BTreeMap<String, u64>,
+}
+
 pub fn default_unit() -> String {
     "m".to_string()
 }
 
 pub async fn command_handler(
     State(state): State<AppState>,
-    Json(request): Json<Request>,
-) -> Result<Json<Response>, SynapError> {
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    command_encoding::EncodedRequest {
+        mut request,
+        response_encoding,
+    }: command_encoding::EncodedRequest,
+) -> Result<AxumResponse, SynapError> {
+    if request.request_id.is_empty()
+        && let Some(id) = crate::server::request_id::current()
+    {
+        request.request_id = id;
+    }
+
+    let response = execute_command(&state, &ctx, request, CommandProtocol::Http).await;
+    command_encoding::encode_response(response_encoding, &response?)
+}
+
+/// Run one command envelope with the bookkeeping every envelope endpoint
+/// shares: CLIENT PAUSE, the audit log, command hooks, latency and slow log
+async fn execute_command(
+    state: &AppState,
+    ctx: &crate::auth::AuthContext,
+    request: Request,
+    protocol: CommandProtocol,
+) -> Result<Response, SynapError> {
     debug!(
         "Command: {} (request_id={})",
         request.command, request.request_id
     );
 
-    let response = handle_command(state, request).await?;
-    Ok(Json(response))
+    let command = request.command.clone();
+    let audit = command_acl::audit_event(
+        &request.command,
+        &request.payload,
+        state.monitoring.audit_log().logs_writes(),
+    );
+    let hooked = if state.monitoring.command_hooks().is_active() {
+        command_acl::write_resource(&request.command, &request.payload)
+    } else {
+        None
+    };
+    // CLIENT PAUSE; `client.*` always runs so a pause can be lifted
+    if !command.starts_with("client.") {
+        state
+            .client_list_manager
+            .wait_unpaused(command_acl::is_write(&command, &request.payload))
+            .await;
+    }
+    let started = std::time::Instant::now();
+    let span_protocol = match protocol {
+        CommandProtocol::WebSocket => "websocket",
+        _ => "http",
+    };
+    let span = crate::telemetry::command_span(span_protocol, &command);
+    let response = handle_command(state.clone(), ctx, request)
+        .instrument(span)
+        .await;
+    command_acl::audit_command(state, ctx, &command, audit, &response).await;
+    if let Some(resource) = hooked {
+        let error = match &response {
+            Ok(r) if r.success => None,
+            Ok(r) => Some(r.error.clone().unwrap_or_default()),
+            Err(e) => Some(e.to_string()),
+        };
+        command_acl::hook_command(
+            state,
+            protocol,
+            ctx,
+            &command,
+            resource,
+            error,
+            started.elapsed(),
+        );
+    }
+    state.monitoring.latency().record(
+        &crate::monitoring::LatencyMonitor::command_event(&command),
+        started.elapsed(),
+    );
+    state
+        .monitoring
+        .slow_log()
+        .record(command, Vec::new(), started.elapsed())
+        .await;
+
+    response
 }
 
 /// Handle individual commands
-async fn handle_command(state: AppState, request: Request) -> Result<Response, SynapError> {
+async fn handle_command(
+    state: AppState,
+    ctx: &crate::auth::AuthContext,
+    request: Request,
+) -> Result<Response, SynapError> {
     let request_id = request.request_id.clone();
+    command_acl::authorize_command(&state, ctx, &request).await?;
+
+    let command = request.command.clone();
+    let started = std::time::Instant::now();
+    let result = run_command(state, ctx, request).await;
+    // Unknown names are not recorded, so they cannot add label values
+    if !matches!(result, Err(SynapError::UnknownCommand(_))) {
+        crate::metrics::record_command_op(
+            &command,
+            result.is_ok(),
+            started.elapsed().as_secs_f64(),
+        );
+    }
+    match result {
+        Ok(payload) => Ok(Response::success(request_id, payload)),
+        Err(e) => {
+            error!("Command error: {}", e);
+            Ok(Response::failure(request_id, e.error_info()))
+        }
+    }
+}
 
-    let result = match request.command.as_str() {
+/// Run a command that has already been authorized, returning its payload
+pub(crate) async fn run_command(
+    state: AppState,
+    ctx: &crate::auth::AuthContext,
+    request: Request,
+) -> Result<serde_json::Value, SynapError> {
+    // Keyspace commands run in the envelope's database; shared ones only
+    // check that it exists
+    let selected = state.select(request.db)?;
+    let state = if crate::databases::is_per_database(&request.command) {
+        selected
+    } else {
+        state
+    };
+    match request.command.as_str() {
         "kv.set" => kv_cmd::handle_kv_set_cmd(&state, &request).await,
         "kv.get" => kv_cmd::handle_kv_get_cmd(state.kv_store.clone(), &request).await,
         "kv.del" => kv_cmd::handle_kv_del_cmd(&state, &request).await,
@@ -326,7 +718,7 @@ async fn handle_command(state: AppState, request: Request) -> Result<Response, S
         "kv.keys" => kv_cmd::handle_kv_keys_cmd(state.kv_store.clone(), &request).await,
         "kv.dbsize" => kv_cmd::handle_kv_dbsize_cmd(state.kv_store.clone(), &request).await,
         "kv.flushdb" => kv_cmd::handle_kv_flushdb_cmd(state.kv_store.clone(), &request).await,
-        "kv.flushall" => kv_cmd::handle_kv_flushall_cmd(state.kv_store.clone(), &request).await,
+        "kv.flushall" => kv_cmd::handle_kv_flushall_cmd(&state, &request).await,
         "kv.expire" => kv_cmd::handle_kv_expire_cmd(state.kv_store.clone(), &request).await,
         "kv.ttl" => kv_cmd::handle_kv_ttl_cmd(state.kv_store.clone(), &request).await,
         "kv.persist" => kv_cmd::handle_kv_persist_cmd(state.kv_store.clone(), &request).await,
@@ -345,12 +737,61 @@ async fn handle_command(state: AppState, request: Request) -> Result<Response, S
         "key.renamenx" => kv_cmd::handle_key_renamenx_cmd(&state, &request).await,
         "key.copy" => kv_cmd::handle_key_copy_cmd(&state, &request).await,
         "key.randomkey" => kv_cmd::handle_key_randomkey_cmd(state.clone(), &request).await,
+        "key.dump" => kv_cmd::handle_key_dump_cmd(&state, &request).await,
+        "key.restore" => kv_cmd::handle_key_restore_cmd(&state, &request).await,
+        // Distributed locks
+        "lock.acquire" => lock::handle_lock_acquire_cmd(&state, &request).await,
+        "lock.renew" => lock::handle_lock_renew_cmd(&state, &request).await,
+        "lock.release" => lock::handle_lock_release_cmd(&state, &request).await,
+        // Rate limiters
+        "ratelimit.check" => ratelimit::handle_ratelimit_check_cmd(&state, &request).await,
+        "ratelimit.reset" => ratelimit::handle_ratelimit_reset_cmd(&state, &request).await,
+        // Time series
+        "ts.incr" => timeseries::handle_ts_incr_cmd(&state, &request).await,
+        "ts.range" => timeseries::handle_ts_range_cmd(&state, &request).await,
+        "ts.del" => timeseries::handle_ts_del_cmd(&state, &request).await,
+        // Bloom filters
+        "bf.reserve" => bloom::handle_bf_reserve_cmd(&state, &request).await,
+        "bf.add" => bloom::handle_bf_add_cmd(&state, &request).await,
+        "bf.madd" => bloom::handle_bf_madd_cmd(&state, &request).await,
+        "bf.exists" => bloom::handle_bf_exists_cmd(&state, &request).await,
+        "bf.mexists" => bloom::handle_bf_mexists_cmd(&state, &request).await,
         // Monitoring commands
         "info" => admin_cmd::handle_info_cmd(state.clone(), &request).await,
         "slowlog.get" => admin_cmd::handle_slowlog_get_cmd(state.clone(), &request).await,
         "slowlog.reset" => admin_cmd::handle_slowlog_reset_cmd(state.clone(), &request).await,
+        "slowlog.len" => admin_cmd::handle_slowlog_len_cmd(state.clone(), &request).await,
+        "slowlog.config.get" => {
+            admin_cmd::handle_slowlog_config_get_cmd(state.clone(), &request).await
+        }
+        "slowlog.config.set" => {
+            admin_cmd::handle_slowlog_config_set_cmd(state.clone(), &request).await
+        }
+        "config.get" => admin_cmd::handle_config_get_cmd(state.clone(), &request).await,
+        "config.set" => admin_cmd::handle_config_set_cmd(state.clone(), &request).await,
+        "latency.latest" => admin_cmd::handle_latency_latest_cmd(state.clone(), &request).await,
+        "latency.history" => admin_cmd::handle_latency_history_cmd(state.clone(), &request).await,
+        "latency.reset" => admin_cmd::handle_latency_reset_cmd(state.clone(), &request).await,
         "memory.usage" => admin_cmd::handle_memory_usage_cmd(state.clone(), &request).await,
+        "memory.doctor" => admin_cmd::handle_memory_doctor_cmd(state.clone(), &request).await,
+        "monitoring.hotkeys" => admin_cmd::handle_hotkeys_cmd(state.clone(), &request).await,
         "client.list" => admin_cmd::handle_client_list_cmd(state.clone(), &request).await,
+        "client.kill" => admin_cmd::handle_client_kill_cmd(state.clone(), &request).await,
+        "client.pause" => admin_cmd::handle_client_pause_cmd(state.clone(), &request).await,
+        "client.unpause" => admin_cmd::handle_client_unpause_cmd(state.clone(), &request).await,
+        "warmup.run" => warmup::handle_warmup_run_cmd(state.clone(), ctx, &request).await,
+        "subscriptions.list" => subscriptions::handle_subscriptions_list_cmd(&state, ctx).await,
+        #[cfg(feature = "cluster")]
+        "cluster.setslot" => cluster::handle_cluster_setslot_cmd(&state, ctx, &request).await,
+        #[cfg(feature = "cluster")]
+        "cluster.migrate" => cluster::handle_cluster_migrate_cmd(&state, ctx, &request).await,
+        #[cfg(feature = "cluster")]
+        "cluster.migrations" => cluster::handle_cluster_migrations_cmd(&state, ctx).await,
+        #[cfg(feature = "cluster")]
+        "cluster.throttle" => cluster::handle_cluster_throttle_cmd(&state, ctx, &request).await,
+        "replication.topology" => {
+            failover::handle_replication_topology_cmd(state.clone(), &request).await
+        }
         // Transaction commands
         "transaction.multi" => {
             admin_cmd::handle_transaction_multi_cmd(state.clone(), &request).await
@@ -369,6 +810,7 @@ async fn handle_command(state: AppState, request: Request) -> Result<Response, S
         "hash.set" => hash::handle_hash_set_cmd(&state, &request).await,
         "hash.get" => hash::handle_hash_get_cmd(&state, &request).await,
         "hash.getall" => hash::handle_hash_getall_cmd(&state, &request).await,
+        "hash.scan" => hash::handle_hash_scan_cmd(&state, &request).await,
         "hash.del" => hash::handle_hash_del_cmd(&state, &request).await,
         "hash.exists" => hash::handle_hash_exists_cmd(&state, &request).await,
         "hash.len" => hash::handle_hash_len_cmd(&state, &request).await,
@@ -427,29 +869,61 @@ async fn handle_command(state: AppState, request: Request) -> Result<Response, S
             geospatial::handle_geospatial_geosearch_cmd(&state, &request).await
         }
         "geospatial.stats" => geospatial::handle_geospatial_stats_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.create" => queue::handle_queue_create_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.delete" => queue::handle_queue_delete_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.publish" => queue::handle_queue_publish_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.consume" => queue::handle_queue_consume_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.ack" => queue::handle_queue_ack_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.nack" => queue::handle_queue_nack_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.list" => queue::handle_queue_list_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.stats" => queue::handle_queue_stats_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
+        "queue.browse" => queue::handle_queue_browse_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
         "queue.purge" => queue::handle_queue_purge_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
+        "shovel.create" => shovel::handle_shovel_create_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
+        "shovel.list" => shovel::handle_shovel_list_cmd(&state).await,
+        #[cfg(feature = "queues")]
+        "shovel.delete" => shovel::handle_shovel_delete_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
+        "webhook.create" => webhook::handle_webhook_create_cmd(&state, &request).await,
+        #[cfg(feature = "queues")]
+        "webhook.list" => webhook::handle_webhook_list_cmd(&state).await,
+        #[cfg(feature = "queues")]
+        "webhook.delete" => webhook::handle_webhook_delete_cmd(&state, &request).await,
+        "schedule.create" => schedule::handle_schedule_create_cmd(&state, &request).await,
+        "schedule.list" => schedule::handle_schedule_list_cmd(&state).await,
+        "schedule.get" => schedule::handle_schedule_get_cmd(&state, &request).await,
+        "schedule.delete" => schedule::handle_schedule_delete_cmd(&state, &request).await,
+        "message.trace" => message_trace::handle_message_trace_cmd(&state, &request).await,
         // Set commands
         "set.add" => set::handle_set_add_cmd(&state, &request).await,
         "set.rem" => set::handle_set_rem_cmd(&state, &request).await,
         "set.ismember" => set::handle_set_ismember_cmd(&state, &request).await,
         "set.members" => set::handle_set_members_cmd(&state, &request).await,
+        "set.scan" => set::handle_set_scan_cmd(&state, &request).await,
         "set.size" => set::handle_set_size_cmd(&state, &request).await,
         "set.card" => set::handle_set_size_cmd(&state, &request).await, // Alias for SDK compatibility (Redis-style)
         "set.pop" => set::handle_set_pop_cmd(&state, &request).await,
         "set.randmember" => set::handle_set_randmember_cmd(&state, &request).await,
         "set.move" => set::handle_set_move_cmd(&state, &request).await,
         "set.inter" => set::handle_set_inter_cmd(&state, &request).await,
-        "set.interstore" => set::handle_set_inter_cmd(&state, &request).await, // Alias for SDK compatibility (note: returns result, doesn't store)
         "set.union" => set::handle_set_union_cmd(&state, &request).await,
         "set.diff" => set::handle_set_diff_cmd(&state, &request).await,
+        "set.interstore" => set::handle_set_interstore_cmd(&state, &request).await,
+        "set.unionstore" => set::handle_set_unionstore_cmd(&state, &request).await,
+        "set.diffstore" => set::handle_set_diffstore_cmd(&state, &request).await,
+        "set.intercard" => set::handle_set_intercard_cmd(&state, &request).await,
         "set.stats" => set::handle_set_stats_cmd(&state, &request).await,
         // Sorted Set commands
         "sortedset.zadd" => sorted_set::handle_sortedset_zadd_cmd(&state, &request).await,
@@ -458,6 +932,7 @@ async fn handle_command(state: AppState, request: Request) -> Result<Response, S
         "sortedset.zcard" => sorted_set::handle_sortedset_zcard_cmd(&state, &request).await,
         "sortedset.zincrby" => sorted_set::handle_sortedset_zincrby_cmd(&state, &request).await,
         "sortedset.zrange" => sorted_set::handle_sortedset_zrange_cmd(&state, &request).await,
+        "sortedset.zscan" => sorted_set::handle_sortedset_zscan_cmd(&state, &request).await,
         "sortedset.zrevrange" => sorted_set::handle_sortedset_zrevrange_cmd(&state, &request).await,
         "sortedset.zrank" => sorted_set::handle_sortedset_zrank_cmd(&state, &request).await,
         "sortedset.zrevrank" => sorted_set::handle_sortedset_zrevrank_cmd(&state, &request).await,
@@ -473,6 +948,9 @@ async fn handle_command(state: AppState, request: Request) -> Result<Response, S
         "sortedset.zremrangebyscore" => {
             sorted_set::handle_sortedset_zremrangebyscore_cmd(&state, &request).await
         }
+        "sortedset.zinter" => sorted_set::handle_sortedset_zinter_cmd(&state, &request).await,
+        "sortedset.zunion" => sorted_set::handle_sortedset_zunion_cmd(&state, &request).await,
+        "sortedset.zdiff" => sorted_set::handle_sortedset_zdiff_cmd(&state, &request).await,
         "sortedset.zinterstore" => {
             sorted_set::handle_sortedset_zinterstore_cmd(&state, &request).await
         }
@@ -484,43 +962,95 @@ async fn handle_command(state: AppState, request: Request) -> Result<Response, S
         }
         "sortedset.zmscore" => sorted_set::handle_sortedset_zmscore_cmd(&state, &request).await,
         "sortedset.stats" => sorted_set::handle_sortedset_stats_cmd(&state, &request).await,
+        "sortedset.notify.set" => {
+            sorted_set::handle_sortedset_notify_set_cmd(&state, &request).await
+        }
+        "sortedset.notify.get" => {
+            sorted_set::handle_sortedset_notify_get_cmd(&state, &request).await
+        }
+        "sortedset.notify.del" => {
+            sorted_set::handle_sortedset_notify_del_cmd(&state, &request).await
+        }
+        #[cfg(feature = "scripting")]
         "script.eval" => script::handle_script_eval_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
         "script.evalsha" => script::handle_script_evalsha_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
         "script.load" => script::handle_script_load_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
         "script.exists" => script::handle_script_exists_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
         "script.flush" => script::handle_script_flush_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
         "script.kill" => script::handle_script_kill_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
+        "function.load" => script::handle_function_load_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
+        "function.delete" => script::handle_function_delete_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
+        "function.list" => script::handle_function_list_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
+        "function.flush" => script::handle_function_flush_cmd(&state, &request).await,
+        #[cfg(feature = "scripting")]
+        "function.fcall" => script::handle_function_fcall_cmd(&state, &request).await,
+        #[cfg(feature = "pubsub")]
         "pubsub.subscribe" => pubsub::handle_pubsub_subscribe_cmd(&state, &request).await,
-        "pubsub.publish" => pubsub::handle_pubsub_publish_cmd(&state, &request).await,
+        #[cfg(feature = "pubsub")]
+        "pubsub.publish" => pubsub::handle_pubsub_publish_cmd(&state, ctx, &request).await,
+        #[cfg(feature = "pubsub")]
         "pubsub.unsubscribe" => pubsub::handle_pubsub_unsubscribe_cmd(&state, &request).await,
+        #[cfg(feature = "pubsub")]
         "pubsub.stats" => pubsub::handle_pubsub_stats_cmd(&state, &request).await,
+        #[cfg(feature = "pubsub")]
+        "pubsub.filters" => pubsub::handle_pubsub_filters_cmd(&state).await,
+        #[cfg(feature = "pubsub")]
+        "pubsub.route.set" => pubsub::handle_pubsub_route_set_cmd(&state, &request).await,
+        #[cfg(feature = "pubsub")]
+        "pubsub.route.list" => pubsub::handle_pubsub_route_list_cmd(&state).await,
+        #[cfg(feature = "pubsub")]
+        "pubsub.route.delete" => pubsub::handle_pubsub_route_delete_cmd(&state, &request).await,
+        #[cfg(feature = "pubsub")]
         "pubsub.topics" => pubsub::handle_pubsub_topics_cmd(&state, &request).await,
+        #[cfg(feature = "pubsub")]
         "pubsub.info" => pubsub::handle_pubsub_info_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
         "stream.create" => stream::handle_stream_create_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
         "stream.get_or_create" => stream::handle_stream_get_or_create_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
         "stream.publish" => stream::handle_stream_publish_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
         "stream.consume" => stream::handle_stream_consume_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
+        "stream.consume_many" => stream::handle_stream_consume_many_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
         "stream.stats" => stream::handle_stream_stats_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
         "stream.list" => stream::handle_stream_list_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
         "stream.delete" => stream::handle_stream_delete_cmd(&state, &request).await,
+        #[cfg(feature = "streams")]
+        "consumer_group.lag" => partition::handle_consumer_group_lag_cmd(&state, &request).await,
         _ => Err(SynapError::UnknownCommand(request.command.clone())),
-    };
-
-    match result {
-        Ok(payload) => Ok(Response {
-            success: true,
-            request_id,
-            payload: Some(payload),
-            error: None,
-        }),
-        Err(e) => {
-            error!("Command error: {}", e);
-            Ok(Response {
-                success: false,
-                request_id,
-                payload: None,
-                error: Some(e.to_string()),
-            })
-        }
     }
 }
+
+/// Decode a request payload into its typed command
+fn decode_payload<C: Command>(request: &Request) -> Result<C, SynapError> {
+    serde_json::from_value(request.payload.clone()).map_err(|e| {
+        // serde says "missing field `key`"; match the capitalised messages
+        // handlers have always returned
+        let message = e.to_string();
+        let mut chars = message.chars();
+        let message = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => message,
+        };
+        SynapError::InvalidRequest(message)
+    })
+}
+
+/// Encode a typed command response as the envelope payload
+fn encode_response<T: Serialize>(response: T) -> Result<serde_json::Value, SynapError> {
+    serde_json::to_value(response).map_err(|e| SynapError::SerializationError(e.to_string()))
+}
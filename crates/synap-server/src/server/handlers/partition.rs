@@ -1,4 +1,5 @@
 use super::*;
+use crate::persistence::SubscriptionRecord;
 
 // =====================================
 // Partitioned Stream Handlers (Kafka-style)
@@ -134,6 +135,12 @@ pub async fn publish_to_partition(
 pub struct ConsumePartitionRequest {
     pub from_offset: Option<u64>,
     pub limit: Option<usize>,
+    /// Hold back events published less than this many milliseconds ago
+    pub min_age_ms: Option<u64>,
+    /// Consumer group member consuming; without an explicit `min_age_ms` its
+    /// configured delayed visibility applies
+    pub group_id: Option<String>,
+    pub member_id: Option<String>,
 }
 
 /// Consume from specific partition
@@ -150,8 +157,22 @@ pub async fn consume_from_partition(
     let from_offset = req.from_offset.unwrap_or(0);
     let limit = req.limit.unwrap_or(100).min(1000);
 
+    let min_age_ms = match (req.min_age_ms, &req.group_id, &req.member_id) {
+        (Some(min_age_ms), _, _) => min_age_ms,
+        (None, Some(group_id), Some(member_id)) => state
+            .consumer_group_manager
+            .as_ref()
+            .ok_or_else(|| {
+                SynapError::InvalidRequest("Consumer group system disabled".to_string())
+            })?
+            .member_min_age(group_id, member_id)
+            .await
+            .map_err(SynapError::InvalidRequest)?,
+        _ => 0,
+    };
+
     let events = partition_manager
-        .consume_partition(&topic, partition_id, from_offset, limit)
+        .consume_partition_with_min_age(&topic, partition_id, from_offset, limit, min_age_ms)
         .await
         .map_err(SynapError::InvalidRequest)?;
 
@@ -162,7 +183,8 @@ pub async fn consume_from_partition(
         "partition_id": partition_id,
         "events": events,
         "next_offset": next_offset,
-        "count": events.len()
+        "count": events.len(),
+        "min_age_ms": min_age_ms
     })))
 }
 
@@ -235,6 +257,8 @@ pub struct CreateConsumerGroupRequest {
     pub partition_count: usize,
     pub strategy: Option<String>,
     pub session_timeout_secs: Option<u64>,
+    /// Default delayed visibility for the group's members
+    pub min_age_ms: Option<u64>,
 }
 
 /// Create consumer group
@@ -267,11 +291,27 @@ pub async fn create_consumer_group(
         config.session_timeout_secs = timeout;
     }
 
+    config.min_age_ms = req.min_age_ms;
+
     consumer_group_manager
-        .create_group(&group_id, &req.topic, req.partition_count, Some(config))
+        .create_group(
+            &group_id,
+            &req.topic,
+            req.partition_count,
+            Some(config.clone()),
+        )
         .await
         .map_err(SynapError::InvalidRequest)?;
 
+    state
+        .record_subscription(SubscriptionRecord::ConsumerGroup {
+            group_id: group_id.clone(),
+            topic: req.topic.clone(),
+            partition_count: req.partition_count,
+            config,
+        })
+        .await;
+
     Ok(Json(json!({
         "success": true,
         "group_id": group_id,
@@ -282,6 +322,8 @@ pub async fn create_consumer_group(
 #[derive(Debug, Deserialize)]
 pub struct JoinGroupRequest {
     pub session_timeout_secs: Option<u64>,
+    /// Delayed visibility for this member, overriding the group default
+    pub min_age_ms: Option<u64>,
 }
 
 /// Join consumer group
@@ -302,6 +344,22 @@ pub async fn join_consumer_group(
         .await
         .map_err(SynapError::InvalidRequest)?;
 
+    if req.min_age_ms.is_some() {
+        consumer_group_manager
+            .set_member_min_age(&group_id, &member.id, req.min_age_ms)
+            .await
+            .map_err(SynapError::InvalidRequest)?;
+    }
+
+    state
+        .record_subscription(SubscriptionRecord::GroupMember {
+            group_id: group_id.clone(),
+            member_id: member.id.clone(),
+            session_timeout_secs: timeout,
+            min_age_ms: req.min_age_ms,
+        })
+        .await;
+
     // Trigger rebalance
     let _ = consumer_group_manager.rebalance_group(&group_id).await;
 
@@ -326,6 +384,13 @@ pub async fn leave_consumer_group(
         .await
         .map_err(SynapError::InvalidRequest)?;
 
+    state
+        .forget_subscriptions(|record| {
+            matches!(record, SubscriptionRecord::GroupMember { group_id: g, member_id: m, .. }
+                if *g == group_id && *m == member_id)
+        })
+        .await;
+
     // Trigger rebalance
     let _ = consumer_group_manager.rebalance_group(&group_id).await;
 
@@ -379,6 +444,14 @@ pub async fn commit_offset(
         .await
         .map_err(SynapError::InvalidRequest)?;
 
+    state
+        .record_subscription(SubscriptionRecord::GroupOffset {
+            group_id: group_id.clone(),
+            partition_id: req.partition_id,
+            offset: req.offset,
+        })
+        .await;
+
     Ok(Json(json!({
         "success": true,
         "partition_id": req.partition_id,
@@ -428,6 +501,90 @@ pub async fn get_consumer_group_stats(
     })?))
 }
 
+/// A group's topic and its lag on every partition of that topic
+async fn consumer_group_lag(
+    state: &AppState,
+    group_id: &str,
+) -> Result<(String, Vec<crate::core::PartitionLag>), SynapError> {
+    let consumer_group_manager = state
+        .consumer_group_manager
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Consumer group system disabled".to_string()))?;
+
+    let topic = consumer_group_manager
+        .group_stats(group_id)
+        .await
+        .map_err(SynapError::InvalidRequest)?
+        .topic;
+
+    // A group may name a topic that was never created; it has nothing to lag
+    let mut end_offsets = HashMap::new();
+    if let Some(partition_manager) = &state.partition_manager
+        && let Ok(partitions) = partition_manager.topic_stats(&topic).await
+    {
+        end_offsets.extend(partitions.iter().map(|p| (p.partition_id, p.max_offset)));
+    }
+
+    let lag = consumer_group_manager
+        .group_lag(group_id, &end_offsets)
+        .await
+        .map_err(SynapError::InvalidRequest)?;
+    Ok((topic, lag))
+}
+
+/// Get consumer group lag per partition
+pub async fn get_consumer_group_lag(
+    State(state): State<AppState>,
+    Path(group_id): Path<String>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    let (topic, partitions) = consumer_group_lag(&state, &group_id).await?;
+    let total_lag: u64 = partitions.iter().map(|p| p.lag).sum();
+
+    Ok(Json(json!({
+        "group_id": group_id,
+        "topic": topic,
+        "partitions": partitions,
+        "total_lag": total_lag
+    })))
+}
+
+pub(super) async fn handle_consumer_group_lag_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let group_id = request
+        .payload
+        .get("group_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'group_id' field".to_string()))?;
+    let offset = request
+        .payload
+        .get("offset")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
+    let limit = request
+        .payload
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(100)
+        .clamp(1, 1000) as usize;
+
+    let (topic, partitions) = consumer_group_lag(state, group_id).await?;
+    let total = partitions.len();
+    let total_lag: u64 = partitions.iter().map(|p| p.lag).sum();
+    let page: Vec<_> = partitions.into_iter().skip(offset).take(limit).collect();
+    let next = offset + page.len();
+    Ok(json!({
+        "group_id": group_id,
+        "topic": topic,
+        "partitions": page,
+        "total": total,
+        "total_lag": total_lag,
+        // null once the page reached the last partition
+        "next_offset": (next < total).then_some(next),
+    }))
+}
+
 /// List consumer groups
 pub async fn list_consumer_groups(
     State(state): State<AppState>,
@@ -1,9 +1,13 @@
 use super::*;
+use crate::core::{SubscriptionFilter, TopicRoute};
 
 // Pub/Sub REST API types
 #[derive(Debug, Deserialize)]
 pub struct SubscribeRequest {
     pub topics: Vec<String>,
+    /// Content filter expression, see [`crate::core::SubscriptionFilter`]
+    #[serde(default)]
+    pub filter: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,7 +58,18 @@ pub async fn pubsub_subscribe(
         })
         .collect();
 
-    match pubsub_router.subscribe(scoped_topics) {
+    let filter = req
+        .filter
+        .as_deref()
+        .map(SubscriptionFilter::parse)
+        .transpose()
+        .map_err(|e| {
+            Json(serde_json::json!({
+                "error": e.to_string()
+            }))
+        })?;
+
+    match pubsub_router.subscribe_filtered(scoped_topics, filter) {
         Ok(result) => {
             // Unscope topics in response
 
@@ -64,6 +79,7 @@ pub async fn pubsub_subscribe(
                 "subscriber_id": result.subscriber_id,
                 "topics": response_topics,
                 "subscription_count": result.subscription_count,
+                "filter": req.filter,
             })))
         }
         Err(e) => {
@@ -83,20 +99,22 @@ pub async fn pubsub_publish(
     crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
     Path(topic): Path<String>,
     Json(req): Json<PublishMessageRequest>,
-) -> Result<Json<serde_json::Value>, Json<serde_json::Value>> {
+) -> Result<Json<serde_json::Value>, axum::response::Response> {
     debug!("POST /pubsub/{}/publish", topic);
 
     // Check permission
     if require_permission(&ctx, &format!("pubsub:{}", topic), Action::Write).is_err() {
         return Err(Json(serde_json::json!({
             "error": format!("Insufficient permissions for topic: {}", topic)
-        })));
+        }))
+        .into_response());
     }
 
     let pubsub_router = state.pubsub_router.as_ref().ok_or_else(|| {
         Json(serde_json::json!({
             "error": "Pub/Sub system disabled"
         }))
+        .into_response()
     })?;
 
     // Apply multi-tenant scoping if Hub mode is active
@@ -104,7 +122,10 @@ pub async fn pubsub_publish(
     let scoped_topic =
         crate::hub::MultiTenant::scope_topic(hub_ctx.as_ref().map(|c| c.user_id()), &topic);
 
-    match pubsub_router.publish(&scoped_topic, req.payload, req.metadata) {
+    match pubsub_router
+        .publish_as(&ctx.client_id(), &scoped_topic, req.payload, req.metadata)
+        .inspect_err(crate::metrics::record_pubsub_rejected)
+    {
         Ok(result) => {
             // Unscope topic in response
 
@@ -118,11 +139,17 @@ pub async fn pubsub_publish(
                 "subscribers_matched": result.subscribers_matched,
             })))
         }
+        // Limit rejections answer 413 / 429 so publishers can back off
+        Err(e @ (SynapError::MessageTooLarge { .. } | SynapError::PublishRateLimited { .. })) => {
+            debug!("Publish rejected: {}", e);
+            Err(e.into_response())
+        }
         Err(e) => {
             error!("Publish error: {}", e);
             Err(Json(serde_json::json!({
                 "error": e.to_string()
-            })))
+            }))
+            .into_response())
         }
     }
 }
@@ -195,6 +222,33 @@ pub async fn pubsub_stats(
     ))
 }
 
+/// GET /pubsub/filters - Per-subscriber filter statistics
+pub async fn pubsub_filters(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, Json<serde_json::Value>> {
+    debug!("GET /pubsub/filters");
+
+    // Check permission (read access to any pubsub topic)
+    if require_permission(&ctx, "pubsub:*", Action::Read).is_err() {
+        return Err(Json(serde_json::json!({
+            "error": "Insufficient permissions"
+        })));
+    }
+
+    let pubsub_router = state.pubsub_router.as_ref().ok_or_else(|| {
+        Json(serde_json::json!({
+            "error": "Pub/Sub system disabled"
+        }))
+    })?;
+
+    let filters = pubsub_router.filter_stats();
+    Ok(Json(serde_json::json!({
+        "count": filters.len(),
+        "filters": filters,
+    })))
+}
+
 /// GET /pubsub/topics - List all topics
 pub async fn pubsub_list_topics(
     State(state): State<AppState>,
@@ -271,6 +325,74 @@ pub async fn pubsub_topic_info(
     }
 }
 
+/// Body of `PUT /pubsub/routes/{name}`
+#[derive(Debug, Deserialize)]
+pub struct RouteRequest {
+    pub source: String,
+    pub targets: Vec<String>,
+    #[serde(default)]
+    pub redirect: bool,
+}
+
+fn pubsub_router(state: &AppState) -> Result<&Arc<crate::core::PubSubRouter>, SynapError> {
+    state
+        .pubsub_router
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Pub/Sub system disabled".to_string()))
+}
+
+/// GET /pubsub/routes - List topic routing rules
+pub async fn pubsub_list_routes(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("GET /pubsub/routes");
+
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    Ok(Json(
+        json!({ "routes": pubsub_router(&state)?.list_routes() }),
+    ))
+}
+
+/// PUT /pubsub/routes/{name} - Add or replace a topic routing rule
+pub async fn pubsub_set_route(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(name): Path<String>,
+    Json(req): Json<RouteRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!(
+        "PUT /pubsub/routes/{}: {} -> {:?}",
+        name, req.source, req.targets
+    );
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let route = TopicRoute {
+        name,
+        source: req.source,
+        targets: req.targets,
+        redirect: req.redirect,
+    };
+    pubsub_router(&state)?.set_route(route.clone())?;
+    Ok(Json(json!({ "route": route })))
+}
+
+/// DELETE /pubsub/routes/{name} - Remove a topic routing rule
+pub async fn pubsub_delete_route(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("DELETE /pubsub/routes/{}", name);
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let deleted = pubsub_router(&state)?.delete_route(&name);
+    Ok(Json(json!({ "deleted": deleted })))
+}
+
 // ============================================================================
 // Pub/Sub StreamableHTTP Command Handlers
 // ============================================================================
@@ -304,17 +426,76 @@ pub(super) async fn handle_pubsub_subscribe_cmd(
         ));
     }
 
-    let result = pubsub_router.subscribe(topics)?;
+    let filter = match request.payload.get("filter") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(expression)) => Some(SubscriptionFilter::parse(expression)?),
+        Some(_) => {
+            return Err(SynapError::InvalidRequest(
+                "'filter' must be a string".to_string(),
+            ));
+        }
+    };
+    let filter_expression = filter.as_ref().map(|f| f.expression().to_string());
+
+    let result = pubsub_router.subscribe_filtered(topics, filter)?;
 
     Ok(serde_json::json!({
         "subscriber_id": result.subscriber_id,
         "topics": result.topics,
-        "subscription_count": result.subscription_count
+        "subscription_count": result.subscription_count,
+        "filter": filter_expression
+    }))
+}
+
+pub(super) async fn handle_pubsub_route_set_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let route: TopicRoute = serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid route: {}", e)))?;
+
+    pubsub_router(state)?.set_route(route.clone())?;
+    Ok(json!({ "route": route }))
+}
+
+pub(super) async fn handle_pubsub_route_list_cmd(
+    state: &AppState,
+) -> Result<serde_json::Value, SynapError> {
+    Ok(json!({ "routes": pubsub_router(state)?.list_routes() }))
+}
+
+pub(super) async fn handle_pubsub_route_delete_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let name = request
+        .payload
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'name' field".to_string()))?;
+
+    let deleted = pubsub_router(state)?.delete_route(name);
+    Ok(json!({ "deleted": deleted }))
+}
+
+pub(super) async fn handle_pubsub_filters_cmd(
+    state: &AppState,
+) -> Result<serde_json::Value, SynapError> {
+    let pubsub_router = state
+        .pubsub_router
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Pub/Sub system disabled".to_string()))?;
+
+    let filters = pubsub_router.filter_stats();
+    Ok(serde_json::json!({
+        "count": filters.len(),
+        "filters": filters
     }))
 }
 
 pub(super) async fn handle_pubsub_publish_cmd(
     state: &AppState,
+    ctx: &crate::auth::AuthContext,
     request: &Request,
 ) -> Result<serde_json::Value, SynapError> {
     let pubsub_router = state
@@ -347,7 +528,9 @@ pub(super) async fn handle_pubsub_publish_cmd(
         }
     });
 
-    let result = pubsub_router.publish(topic, payload, metadata)?;
+    let result = pubsub_router
+        .publish_as(&ctx.client_id(), topic, payload, metadata)
+        .inspect_err(crate::metrics::record_pubsub_rejected)?;
 
     Ok(serde_json::json!({
         "message_id": result.message_id,
@@ -31,13 +31,18 @@ pub async fn queue_create(
     let config = if req.max_depth.is_some()
         || req.ack_deadline_secs.is_some()
         || req.prefetch_limit.is_some()
+        || req.dedup_window_secs.is_some()
     {
+        let defaults = queue_manager.default_config();
         Some(crate::core::QueueConfig {
-            max_depth: req.max_depth.unwrap_or(100_000),
-            ack_deadline_secs: req.ack_deadline_secs.unwrap_or(30),
-            default_max_retries: req.default_max_retries.unwrap_or(3),
-            default_priority: req.default_priority.unwrap_or(5),
-            prefetch_limit: req.prefetch_limit.unwrap_or(0),
+            max_depth: req.max_depth.unwrap_or(defaults.max_depth),
+            ack_deadline_secs: req.ack_deadline_secs.unwrap_or(defaults.ack_deadline_secs),
+            default_max_retries: req
+                .default_max_retries
+                .unwrap_or(defaults.default_max_retries),
+            default_priority: req.default_priority.unwrap_or(defaults.default_priority),
+            prefetch_limit: req.prefetch_limit.unwrap_or(defaults.prefetch_limit),
+            dedup_window_secs: req.dedup_window_secs.unwrap_or(defaults.dedup_window_secs),
         })
     } else {
         None
@@ -77,9 +82,31 @@ pub async fn queue_publish(
         &queue_name,
     );
 
-    let message = queue_manager
-        .publish_with_message(&scoped_name, req.payload, req.priority, req.max_retries)
-        .await?;
+    let message = match req.idempotency_key.as_deref() {
+        Some(key) => match queue_manager
+            .publish_idempotent(
+                &scoped_name,
+                req.payload,
+                req.priority,
+                req.max_retries,
+                key,
+            )
+            .await?
+        {
+            crate::core::QueuePublish::Published(message) => message,
+            crate::core::QueuePublish::Duplicate(message_id) => {
+                return Ok(Json(PublishResponse {
+                    message_id,
+                    duplicate: true,
+                }));
+            }
+        },
+        None => {
+            queue_manager
+                .publish_with_message(&scoped_name, req.payload, req.priority, req.max_retries)
+                .await?
+        }
+    };
 
     let message_id = message.id.clone();
 
@@ -93,7 +120,10 @@ pub async fn queue_publish(
         // Don't fail the request, just log the error
     }
 
-    Ok(Json(PublishResponse { message_id }))
+    Ok(Json(PublishResponse {
+        message_id,
+        duplicate: false,
+    }))
 }
 
 /// Consume message endpoint
@@ -126,7 +156,7 @@ pub async fn queue_consume(
     if let Some(msg) = message {
         Ok(Json(ConsumeResponse {
             message_id: Some(msg.id),
-            payload: Some((*msg.payload).clone()), // Convert Arc<Vec<u8>> to Vec<u8>
+            payload: Some(msg.payload.to_vec()),
             priority: Some(msg.priority),
             retry_count: Some(msg.retry_count),
             max_retries: Some(msg.max_retries),
@@ -381,19 +411,23 @@ pub(super) async fn handle_queue_create_cmd(
             .get("prefetch_limit")
             .and_then(|d| d.as_u64())
             .map(|d| d as usize);
+        let dedup_window_secs = v.get("dedup_window_secs").and_then(|d| d.as_u64());
 
         if max_depth.is_some()
             || ack_deadline_secs.is_some()
             || default_max_retries.is_some()
             || default_priority.is_some()
             || prefetch_limit.is_some()
+            || dedup_window_secs.is_some()
         {
+            let defaults = queue_manager.default_config();
             Some(crate::core::QueueConfig {
-                max_depth: max_depth.unwrap_or(100_000),
-                ack_deadline_secs: ack_deadline_secs.unwrap_or(30),
-                default_max_retries: default_max_retries.unwrap_or(3),
-                default_priority: default_priority.unwrap_or(5),
-                prefetch_limit: prefetch_limit.unwrap_or(0),
+                max_depth: max_depth.unwrap_or(defaults.max_depth),
+                ack_deadline_secs: ack_deadline_secs.unwrap_or(defaults.ack_deadline_secs),
+                default_max_retries: default_max_retries.unwrap_or(defaults.default_max_retries),
+                default_priority: default_priority.unwrap_or(defaults.default_priority),
+                prefetch_limit: prefetch_limit.unwrap_or(defaults.prefetch_limit),
+                dedup_window_secs: dedup_window_secs.unwrap_or(defaults.dedup_window_secs),
             })
         } else {
             None
@@ -462,10 +496,63 @@ pub(super) async fn handle_queue_publish_cmd(
         .map(|r| r as u32);
     // Note: headers are ignored for now - not supported by the queue manager
     // let headers = request.payload.get("headers")...
+    let idempotency_key = request
+        .payload
+        .get("idempotency_key")
+        .and_then(|v| v.as_str());
 
-    let message = queue_manager
-        .publish_with_message(queue, payload_bytes, priority, max_retries)
-        .await?;
+    // Check if there's an active transaction for this client_id
+    let client_id = request
+        .payload
+        .get("client_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    if !client_id.is_empty() {
+        if idempotency_key.is_some()
+            && state
+                .transaction_manager
+                .get_transaction(client_id)
+                .is_some()
+        {
+            return Err(SynapError::InvalidRequest(
+                "'idempotency_key' is not supported inside a transaction".to_string(),
+            ));
+        }
+
+        let was_queued = state.transaction_manager.queue_command_if_transaction(
+            client_id,
+            crate::core::transaction::TransactionCommand::QueuePublish {
+                queue: queue.to_string(),
+                payload: payload_bytes.clone(),
+                priority,
+                max_retries,
+            },
+        )?;
+
+        if was_queued {
+            return Ok(serde_json::json!({ "success": true, "queued": true }));
+        }
+    }
+
+    // A retry of a key seen within the queue's window enqueues nothing, so
+    // there is nothing to log either
+    let message = match idempotency_key {
+        Some(key) => match queue_manager
+            .publish_idempotent(queue, payload_bytes, priority, max_retries, key)
+            .await?
+        {
+            crate::core::QueuePublish::Published(message) => message,
+            crate::core::QueuePublish::Duplicate(message_id) => {
+                return Ok(serde_json::json!({ "message_id": message_id, "duplicate": true }));
+            }
+        },
+        None => {
+            queue_manager
+                .publish_with_message(queue, payload_bytes, priority, max_retries)
+                .await?
+        }
+    };
 
     let message_id = message.id.clone();
 
@@ -509,7 +596,7 @@ pub(super) async fn handle_queue_consume_cmd(
         Ok(serde_json::json!({
             "message": {
                 "id": msg.id,
-                "payload": (*msg.payload).clone(), // Convert Arc<Vec<u8>> to Vec<u8>
+                "payload": msg.payload,
                 "priority": msg.priority,
                 "retry_count": msg.retry_count,
                 "max_retries": msg.max_retries,
@@ -631,6 +718,55 @@ pub(super) async fn handle_queue_stats_cmd(
     serde_json::to_value(stats).map_err(|e| SynapError::SerializationError(e.to_string()))
 }
 
+pub(super) async fn handle_queue_browse_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let queue_manager = state
+        .queue_manager
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Queue system disabled".to_string()))?;
+
+    let queue = request
+        .payload
+        .get("queue")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'queue' field".to_string()))?;
+    let offset = request
+        .payload
+        .get("offset")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
+    let limit = request
+        .payload
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(100)
+        .clamp(1, 1000) as usize;
+
+    let (messages, total) = queue_manager.browse(queue, offset, limit).await?;
+    let next = offset + messages.len();
+    let messages: Vec<_> = messages
+        .into_iter()
+        .map(|msg| {
+            serde_json::json!({
+                "id": msg.id,
+                "payload": msg.payload,
+                "priority": msg.priority,
+                "retry_count": msg.retry_count,
+                "max_retries": msg.max_retries,
+                "headers": msg.headers,
+            })
+        })
+        .collect();
+    Ok(serde_json::json!({
+        "messages": messages,
+        "total": total,
+        // null once the page reached the end of the queue
+        "next_offset": (next < total).then_some(next),
+    }))
+}
+
 pub(super) async fn handle_queue_purge_cmd(
     state: &AppState,
     request: &Request,
@@ -0,0 +1,80 @@
+use super::*;
+use crate::core::RateLimit;
+use crate::protocol::command::ratelimit::{
+    RateLimitAlgorithm, RateLimitCheck, RateLimitCheckResponse, RateLimitReset,
+    RateLimitResetResponse,
+};
+use std::time::Duration;
+
+/// The limit a check asks for, validated
+fn rate_limit(check: &RateLimitCheck) -> Result<RateLimit, SynapError> {
+    if check.max_tokens == 0 {
+        return Err(SynapError::InvalidRequest(
+            "max_tokens must be at least 1".to_string(),
+        ));
+    }
+    let limit = match check.algorithm {
+        RateLimitAlgorithm::TokenBucket => match check.refill_rate {
+            Some(rate) if rate.is_finite() && rate > 0.0 => RateLimit::TokenBucket {
+                capacity: check.max_tokens,
+                refill_per_sec: rate,
+            },
+            _ => {
+                return Err(SynapError::InvalidRequest(
+                    "Token bucket needs a positive refill_rate".to_string(),
+                ));
+            }
+        },
+        RateLimitAlgorithm::SlidingWindow => match check.window {
+            Some(window) if window > 0 => RateLimit::SlidingWindow {
+                limit: check.max_tokens,
+                window: Duration::from_millis(window),
+            },
+            _ => {
+                return Err(SynapError::InvalidRequest(
+                    "Sliding window needs a window of at least 1 ms".to_string(),
+                ));
+            }
+        },
+    };
+    // A cost above the limit could never be allowed
+    if check.cost == 0 || check.cost > limit.max_cost() {
+        return Err(SynapError::InvalidRequest(format!(
+            "cost must be between 1 and max_tokens ({})",
+            check.max_tokens
+        )));
+    }
+    Ok(limit)
+}
+
+// ============================================================================
+// Rate Limit StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_ratelimit_check_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let check: RateLimitCheck = decode_payload(request)?;
+    let limit = rate_limit(&check)?;
+
+    let decision = state.rate_limits.check(&check.key, &limit, check.cost);
+    encode_response(RateLimitCheckResponse {
+        allowed: decision.allowed,
+        remaining: decision.remaining,
+        // Rounded up, so waiting that long is always enough
+        retry_after: decision
+            .retry_after
+            .map(|wait| u64::try_from(wait.as_micros().div_ceil(1000)).unwrap_or(u64::MAX)),
+    })
+}
+
+pub(super) async fn handle_ratelimit_reset_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let RateLimitReset { key } = decode_payload(request)?;
+
+    let reset = state.rate_limits.reset(&key);
+    encode_response(RateLimitResetResponse { reset })
+}
@@ -0,0 +1,92 @@
+use super::*;
+use crate::runtime_config as runtime;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigGetQuery {
+    pub pattern: Option<String>,
+}
+
+/// GET /config?pattern=slowlog.* - Current value of the runtime parameters
+pub async fn config_get(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Query(query): Query<ConfigGetQuery>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Read)?;
+    Ok(Json(config_get_report(
+        &state,
+        query.pattern.as_deref().unwrap_or("*"),
+    )))
+}
+
+/// PUT /config - Change runtime parameters, e.g. `{"slowlog.threshold_ms": 5}`
+pub async fn config_set(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(params): Json<serde_json::Map<String, serde_json::Value>>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+    Ok(Json(config_set_report(&state, &params).await?))
+}
+
+/// Shared by `GET /config` and the `config.get` command. Parameters whose
+/// component is off report `null`.
+pub(super) fn config_get_report(state: &AppState, pattern: &str) -> serde_json::Value {
+    let parameters: BTreeMap<&str, Option<u64>> = runtime::matching(pattern)
+        .map(|p| (p.name, runtime::current(state, p.name)))
+        .collect();
+    let overrides = state.monitoring.runtime_config().overrides();
+    let overridden: Vec<&String> = overrides
+        .keys()
+        .filter(|name| crate::core::glob_match(pattern, name))
+        .collect();
+    json!({
+        "parameters": parameters,
+        "overridden": overridden,
+    })
+}
+
+/// Shared by `PUT /config` and the `config.set` command. Every parameter is
+/// checked before any is applied.
+pub(super) async fn config_set_report(
+    state: &AppState,
+    params: &serde_json::Map<String, serde_json::Value>,
+) -> Result<serde_json::Value, SynapError> {
+    if params.is_empty() {
+        return Err(SynapError::InvalidRequest(
+            "No parameters to set".to_string(),
+        ));
+    }
+    let mut changes = BTreeMap::new();
+    for (name, value) in params {
+        let value = runtime::validate(name, value).map_err(SynapError::InvalidRequest)?;
+        if let Some(reason) = runtime::unavailable(state, name) {
+            return Err(SynapError::InvalidRequest(format!(
+                "'{}' cannot be changed: {}",
+                name, reason
+            )));
+        }
+        changes.insert(name.clone(), value);
+    }
+
+    for (name, value) in &changes {
+        runtime::apply(state, name, *value).await;
+        info!("CONFIG SET {} = {}", name, value);
+    }
+
+    let persisted = state
+        .monitoring
+        .runtime_config()
+        .record(&changes)
+        .map_err(|e| {
+            SynapError::InternalError(format!(
+                "Applied, but the overrides file could not be written: {:#}",
+                e
+            ))
+        })?;
+    Ok(json!({
+        "applied": changes,
+        "persisted": persisted,
+    }))
+}
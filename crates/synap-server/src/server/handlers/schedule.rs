@@ -0,0 +1,117 @@
+use super::*;
+use crate::protocol::command::schedule::{
+    ScheduleDelete, ScheduleDeleteResponse, ScheduleGet, ScheduleGetResponse, ScheduleListResponse,
+};
+use crate::scheduler::{ScheduleContext, ScheduleManager, ScheduleSpec, ScheduleStatus};
+
+fn schedules(state: &AppState) -> Result<&Arc<ScheduleManager>, SynapError> {
+    state
+        .schedules
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Scheduler disabled".to_string()))
+}
+
+/// GET /schedules - List schedules with their fire counters
+pub async fn schedule_list(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<ScheduleListResponse>, SynapError> {
+    debug!("REST GET /schedules");
+
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    Ok(Json(ScheduleListResponse {
+        schedules: schedules(&state)?.list(),
+    }))
+}
+
+/// POST /schedules - Start publishing a templated message on a cron schedule
+pub async fn schedule_create(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(spec): Json<ScheduleSpec>,
+) -> Result<Json<ScheduleStatus>, SynapError> {
+    debug!("REST POST /schedules: {} '{}'", spec.name, spec.cron);
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let status = schedules(&state)?
+        .create(spec, ScheduleContext::new(&state))
+        .await?;
+    Ok(Json(status))
+}
+
+/// GET /schedules/{name} - One schedule with its fire counters
+pub async fn schedule_get(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(name): Path<String>,
+) -> Result<Json<ScheduleStatus>, SynapError> {
+    debug!("REST GET /schedules/{}", name);
+
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    schedules(&state)?
+        .get(&name)
+        .map(Json)
+        .ok_or_else(|| SynapError::ResourceNotFound(format!("Schedule '{}'", name)))
+}
+
+/// DELETE /schedules/{name} - Stop and remove a schedule
+pub async fn schedule_delete(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(name): Path<String>,
+) -> Result<Json<ScheduleDeleteResponse>, SynapError> {
+    debug!("REST DELETE /schedules/{}", name);
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let deleted = schedules(&state)?.delete(&name);
+    Ok(Json(ScheduleDeleteResponse { deleted }))
+}
+
+// ============================================================================
+// Schedule StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_schedule_create_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let spec: ScheduleSpec = decode_payload(request)?;
+
+    let status = schedules(state)?
+        .create(spec, ScheduleContext::new(state))
+        .await?;
+    encode_response(status)
+}
+
+pub(super) async fn handle_schedule_list_cmd(
+    state: &AppState,
+) -> Result<serde_json::Value, SynapError> {
+    encode_response(ScheduleListResponse {
+        schedules: schedules(state)?.list(),
+    })
+}
+
+pub(super) async fn handle_schedule_get_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let ScheduleGet { name } = decode_payload(request)?;
+
+    encode_response(ScheduleGetResponse {
+        schedule: schedules(state)?.get(&name),
+    })
+}
+
+pub(super) async fn handle_schedule_delete_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let ScheduleDelete { name } = decode_payload(request)?;
+
+    let deleted = schedules(state)?.delete(&name);
+    encode_response(ScheduleDeleteResponse { deleted })
+}
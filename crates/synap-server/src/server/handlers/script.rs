@@ -1,16 +1,11 @@
 use super::*;
+use std::time::Duration;
 
 pub async fn script_eval(
     State(state): State<AppState>,
     Json(req): Json<EvalScriptRequest>,
 ) -> Result<Json<EvalScriptResponse>, SynapError> {
-    let context = ScriptExecContext {
-        kv_store: state.kv_store.clone(),
-        hash_store: state.hash_store.clone(),
-        list_store: state.list_store.clone(),
-        set_store: state.set_store.clone(),
-        sorted_set_store: state.sorted_set_store.clone(),
-    };
+    let context = exec_context(&state);
     let args = json_args_to_strings(req.args);
     let timeout = req.timeout_ms.map(Duration::from_millis);
 
@@ -26,13 +21,7 @@ pub async fn script_evalsha(
     State(state): State<AppState>,
     Json(req): Json<EvalShaRequest>,
 ) -> Result<Json<EvalScriptResponse>, SynapError> {
-    let context = ScriptExecContext {
-        kv_store: state.kv_store.clone(),
-        hash_store: state.hash_store.clone(),
-        list_store: state.list_store.clone(),
-        set_store: state.set_store.clone(),
-        sorted_set_store: state.sorted_set_store.clone(),
-    };
+    let context = exec_context(&state);
     let args = json_args_to_strings(req.args);
     let timeout = req.timeout_ms.map(Duration::from_millis);
 
@@ -89,6 +78,105 @@ pub async fn script_kill(
     Ok(Json(ScriptKillResponse { terminated }))
 }
 
+pub async fn function_load(
+    State(state): State<AppState>,
+
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<FunctionLoadRequest>,
+) -> Result<Json<FunctionLoadResponse>, SynapError> {
+    // Block in Hub mode - function names are shared by all users
+
+    crate::hub::require_standalone_mode(&hub_ctx)?;
+
+    let library = state
+        .script_manager
+        .functions()
+        .load(&req.code, req.replace)?;
+    Ok(Json(FunctionLoadResponse { library }))
+}
+
+pub async fn function_delete(
+    State(state): State<AppState>,
+
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<FunctionDeleteRequest>,
+) -> Result<Json<FunctionDeleteResponse>, SynapError> {
+    // Block in Hub mode - could delete other users' libraries
+
+    crate::hub::require_standalone_mode(&hub_ctx)?;
+
+    state.script_manager.functions().delete(&req.library)?;
+    Ok(Json(FunctionDeleteResponse { deleted: true }))
+}
+
+pub async fn function_list(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<FunctionListResponse>, SynapError> {
+    let with_code = params.get("withcode").is_some_and(|v| v == "true");
+    Ok(Json(FunctionListResponse {
+        libraries: list_libraries(&state, with_code),
+    }))
+}
+
+pub async fn function_flush(
+    State(state): State<AppState>,
+
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+) -> Result<Json<ScriptFlushResponse>, SynapError> {
+    // Block in Hub mode - this would flush ALL users' libraries
+
+    crate::hub::require_standalone_mode(&hub_ctx)?;
+
+    let cleared = state.script_manager.functions().flush()?;
+    Ok(Json(ScriptFlushResponse { cleared }))
+}
+
+pub async fn function_fcall(
+    State(state): State<AppState>,
+    Json(req): Json<FcallRequest>,
+) -> Result<Json<FcallResponse>, SynapError> {
+    let context = exec_context(&state);
+    let args = json_args_to_strings(req.args);
+    let timeout = req.timeout_ms.map(Duration::from_millis);
+
+    let result = state
+        .script_manager
+        .fcall(context, &req.function, req.keys, args, timeout)
+        .await?;
+
+    Ok(Json(FcallResponse { result }))
+}
+
+fn exec_context(state: &AppState) -> ScriptExecContext {
+    ScriptExecContext {
+        kv_store: state.kv_store.clone(),
+        hash_store: state.hash_store.clone(),
+        list_store: state.list_store.clone(),
+        set_store: state.set_store.clone(),
+        sorted_set_store: state.sorted_set_store.clone(),
+        queue_manager: state.queue_manager().cloned(),
+        stream_manager: state.stream_manager().cloned(),
+    }
+}
+
+/// FUNCTION LIST entries: name and functions, plus the code when asked for.
+fn list_libraries(state: &AppState, with_code: bool) -> Vec<serde_json::Value> {
+    state
+        .script_manager
+        .functions()
+        .list()
+        .iter()
+        .map(|lib| {
+            let mut entry = json!({ "name": lib.name, "functions": lib.functions });
+            if with_code {
+                entry["code"] = json!(lib.code);
+            }
+            entry
+        })
+        .collect()
+}
+
 fn json_args_to_strings(args: Vec<serde_json::Value>) -> Vec<String> {
     args.into_iter()
         .map(|value| match value {
@@ -152,13 +240,7 @@ pub(super) async fn handle_script_eval_cmd(
         .and_then(|v| v.as_u64())
         .map(Duration::from_millis);
 
-    let context = ScriptExecContext {
-        kv_store: state.kv_store.clone(),
-        hash_store: state.hash_store.clone(),
-        list_store: state.list_store.clone(),
-        set_store: state.set_store.clone(),
-        sorted_set_store: state.sorted_set_store.clone(),
-    };
+    let context = exec_context(state);
 
     let (result, sha1) = state
         .script_manager
@@ -186,13 +268,7 @@ pub(super) async fn handle_script_evalsha_cmd(
         .and_then(|v| v.as_u64())
         .map(Duration::from_millis);
 
-    let context = ScriptExecContext {
-        kv_store: state.kv_store.clone(),
-        hash_store: state.hash_store.clone(),
-        list_store: state.list_store.clone(),
-        set_store: state.set_store.clone(),
-        sorted_set_store: state.sorted_set_store.clone(),
-    };
+    let context = exec_context(state);
 
     let result = state
         .script_manager
@@ -240,3 +316,82 @@ pub(super) async fn handle_script_kill_cmd(
     let terminated = state.script_manager.kill_running();
     Ok(json!({ "terminated": terminated }))
 }
+
+pub(super) async fn handle_function_load_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let code = request
+        .payload
+        .get("code")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'code' field".into()))?;
+    let replace = request
+        .payload
+        .get("replace")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let library = state.script_manager.functions().load(code, replace)?;
+    Ok(json!({ "library": library }))
+}
+
+pub(super) async fn handle_function_delete_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let library = request
+        .payload
+        .get("library")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'library' field".into()))?;
+
+    state.script_manager.functions().delete(library)?;
+    Ok(json!({ "deleted": true }))
+}
+
+pub(super) async fn handle_function_list_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let with_code = request
+        .payload
+        .get("withcode")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    Ok(json!({ "libraries": list_libraries(state, with_code) }))
+}
+
+pub(super) async fn handle_function_flush_cmd(
+    state: &AppState,
+    _request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let cleared = state.script_manager.functions().flush()?;
+    Ok(json!({ "cleared": cleared }))
+}
+
+pub(super) async fn handle_function_fcall_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let function = request
+        .payload
+        .get("function")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'function' field".into()))?;
+
+    let keys = extract_string_list(&request.payload, "keys")?;
+    let args = json_args_to_strings(extract_json_list(&request.payload, "args")?);
+    let timeout = request
+        .payload
+        .get("timeout_ms")
+        .and_then(|v| v.as_u64())
+        .map(Duration::from_millis);
+
+    let result = state
+        .script_manager
+        .fcall(exec_context(state), function, keys, args, timeout)
+        .await?;
+
+    Ok(json!({ "result": result }))
+}
@@ -28,6 +28,20 @@ pub struct SetMemberRequest {
     pub member: serde_json::Value,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SetStoreRequest {
+    pub destination: String,
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetInterCardRequest {
+    pub keys: Vec<String>,
+    /// Stop counting here; 0 counts the whole intersection
+    #[serde(default)]
+    pub limit: usize,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SetStatsResponse {
     pub total_sets: usize,
@@ -201,6 +215,54 @@ pub(super) async fn handle_set_members_cmd(
     Ok(serde_json::json!({ "members": json_members }))
 }
 
+pub(super) async fn handle_set_scan_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let key = request
+        .payload
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let cursor = request
+        .payload
+        .get("cursor")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let pattern = request.payload.get("match").and_then(|v| v.as_str());
+    let count = request
+        .payload
+        .get("count")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(100) as usize;
+
+    // Members added over HTTP are stored JSON-encoded, so MATCH is applied
+    // to the decoded text (strings without their quotes) rather than the
+    // stored bytes
+    let (next_cursor, members) = state.set_store.sscan(key, cursor, None, count)?;
+
+    let json_members: Vec<serde_json::Value> = members
+        .into_iter()
+        .map(|m| {
+            serde_json::from_slice(&m).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&m).to_string())
+            })
+        })
+        .filter(|member| {
+            pattern.is_none_or(|p| match member {
+                serde_json::Value::String(s) => crate::core::glob_match(p, s),
+                other => crate::core::glob_match(p, &other.to_string()),
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "members": json_members,
+        "count": json_members.len(),
+        "next_cursor": next_cursor,
+    }))
+}
+
 pub(super) async fn handle_set_size_cmd(
     state: &AppState,
     request: &Request,
@@ -226,11 +288,12 @@ pub(super) async fn handle_set_pop_cmd(
         .and_then(|v| v.as_str())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
 
-    let count = request
-        .payload
-        .get("count")
-        .and_then(|v| v.as_u64())
-        .map(|c| c as usize);
+    let count = match request.payload.get("count") {
+        Some(count) => Some(count.as_u64().ok_or_else(|| {
+            SynapError::InvalidRequest("'count' must be a non-negative integer".to_string())
+        })? as usize),
+        None => None,
+    };
 
     let members = state.set_store.spop(key, count)?;
 
@@ -261,11 +324,14 @@ pub(super) async fn handle_set_randmember_cmd(
         .and_then(|v| v.as_str())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
 
-    let count = request
-        .payload
-        .get("count")
-        .and_then(|v| v.as_u64())
-        .map(|c| c as usize);
+    // A negative count allows the same member more than once
+    let count =
+        match request.payload.get("count") {
+            Some(count) => Some(count.as_i64().ok_or_else(|| {
+                SynapError::InvalidRequest("'count' must be an integer".to_string())
+            })?),
+            None => None,
+        };
 
     let members = state.set_store.srandmember(key, count)?;
 
@@ -305,6 +371,7 @@ pub(super) async fn handle_set_move_cmd(
     let member_bytes =
         serde_json::to_vec(member).map_err(|e| SynapError::SerializationError(e.to_string()))?;
 
+    state.check_same_slot([source, destination])?;
     let moved = state.set_store.smove(source, destination, member_bytes)?;
 
     // Update key versions for WATCH (optimistic locking) if moved
@@ -329,6 +396,7 @@ pub(super) async fn handle_set_inter_cmd(
         .filter_map(|v| v.as_str().map(String::from))
         .collect();
 
+    state.check_same_slot(&keys)?;
     let members = state.set_store.sinter(&keys)?;
 
     let json_members: Vec<serde_json::Value> = members
@@ -356,6 +424,7 @@ pub(super) async fn handle_set_union_cmd(
         .filter_map(|v| v.as_str().map(String::from))
         .collect();
 
+    state.check_same_slot(&keys)?;
     let members = state.set_store.sunion(&keys)?;
 
     let json_members: Vec<serde_json::Value> = members
@@ -383,6 +452,7 @@ pub(super) async fn handle_set_diff_cmd(
         .filter_map(|v| v.as_str().map(String::from))
         .collect();
 
+    state.check_same_slot(&keys)?;
     let members = state.set_store.sdiff(&keys)?;
 
     let json_members: Vec<serde_json::Value> = members
@@ -397,6 +467,75 @@ pub(super) async fn handle_set_diff_cmd(
     Ok(serde_json::json!({ "members": json_members }))
 }
 
+/// The `destination` and `keys` of a `set.*store` command
+fn store_payload(request: &Request) -> Result<SetStoreRequest, SynapError> {
+    serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid payload: {}", e)))
+}
+
+/// Run one of the `S*STORE` operations and report the destination's new size
+fn run_store(
+    state: &AppState,
+    req: &SetStoreRequest,
+    store: fn(&crate::core::SetStore, &str, &[String]) -> Result<usize, SynapError>,
+) -> Result<serde_json::Value, SynapError> {
+    state.check_same_slot(std::iter::once(&req.destination).chain(&req.keys))?;
+    let count = store(&state.set_store, &req.destination, &req.keys)?;
+
+    // Update key version for WATCH (optimistic locking)
+    state
+        .transaction_manager
+        .update_key_version(&req.destination);
+
+    Ok(serde_json::json!({ "count": count, "destination": req.destination }))
+}
+
+pub(super) async fn handle_set_interstore_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    run_store(
+        state,
+        &store_payload(request)?,
+        crate::core::SetStore::sinterstore,
+    )
+}
+
+pub(super) async fn handle_set_unionstore_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    run_store(
+        state,
+        &store_payload(request)?,
+        crate::core::SetStore::sunionstore,
+    )
+}
+
+pub(super) async fn handle_set_diffstore_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    run_store(
+        state,
+        &store_payload(request)?,
+        crate::core::SetStore::sdiffstore,
+    )
+}
+
+pub(super) async fn handle_set_intercard_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let req: SetInterCardRequest = serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid payload: {}", e)))?;
+
+    state.check_same_slot(&req.keys)?;
+    let count = state.set_store.sintercard(&req.keys, req.limit)?;
+
+    Ok(serde_json::json!({ "count": count }))
+}
+
 pub(super) async fn handle_set_stats_cmd(
     state: &AppState,
     _request: &Request,
@@ -609,7 +748,8 @@ pub async fn set_randmember(
     Path(key): Path<String>,
     Query(params): Query<std::collections::HashMap<String, String>>,
 ) -> Result<Json<serde_json::Value>, SynapError> {
-    let count = params.get("count").and_then(|s| s.parse::<usize>().ok());
+    // A negative count allows the same member more than once
+    let count = params.get("count").and_then(|s| s.parse::<i64>().ok());
 
     debug!("REST SRANDMEMBER key={} count={:?}", key, count);
 
@@ -661,6 +801,8 @@ pub async fn set_move(
     let member = serde_json::to_vec(&req.member)
         .map_err(|e| SynapError::InvalidValue(format!("Failed to serialize member: {}", e)))?;
 
+    state.check_same_slot([&scoped_source, &scoped_destination])?;
+
     let moved = state
         .set_store
         .smove(&scoped_source, &scoped_destination, member)?;
@@ -703,6 +845,7 @@ pub async fn set_inter(
         })
         .collect();
 
+    state.check_same_slot(&scoped_keys)?;
     let members = state.set_store.sinter(&scoped_keys)?;
 
     let json_members: Vec<serde_json::Value> = members
@@ -750,6 +893,7 @@ pub async fn set_union(
         })
         .collect();
 
+    state.check_same_slot(&scoped_keys)?;
     let members = state.set_store.sunion(&scoped_keys)?;
 
     let json_members: Vec<serde_json::Value> = members
@@ -797,6 +941,7 @@ pub async fn set_diff(
         })
         .collect();
 
+    state.check_same_slot(&scoped_keys)?;
     let members = state.set_store.sdiff(&scoped_keys)?;
 
     let json_members: Vec<serde_json::Value> = members
@@ -811,6 +956,132 @@ pub async fn set_diff(
     Ok(Json(json!({ "members": json_members })))
 }
 
+/// Shared body of the `POST /set/*store` endpoints
+fn set_store_rest(
+    state: &AppState,
+    ctx: &crate::auth::AuthContext,
+    hub_ctx: Option<&crate::hub::HubUserContext>,
+    mut req: SetStoreRequest,
+    store: fn(&crate::core::SetStore, &str, &[String]) -> Result<usize, SynapError>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    // Sources are only read; the destination is replaced
+    for key in &req.keys {
+        require_permission(ctx, &format!("set:{}", key), Action::Read)?;
+    }
+    require_permission(ctx, &format!("set:{}", req.destination), Action::Write)?;
+
+    // Apply multi-tenant scoping if Hub mode is active
+    let user_id = hub_ctx.map(|c| c.user_id());
+    let destination = std::mem::take(&mut req.destination);
+    let scoped = SetStoreRequest {
+        destination: crate::hub::MultiTenant::scope_kv_key(user_id, &destination).into_owned(),
+        keys: req
+            .keys
+            .iter()
+            .map(|key| crate::hub::MultiTenant::scope_kv_key(user_id, key).into_owned())
+            .collect(),
+    };
+
+    let mut response = run_store(state, &scoped, store)?;
+    response["destination"] = json!(destination);
+    Ok(Json(response))
+}
+
+/// POST /set/interstore - Store the intersection of sets in a destination
+pub async fn set_interstore(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<SetStoreRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!(
+        "REST SINTERSTORE dest={} keys={:?}",
+        req.destination, req.keys
+    );
+    set_store_rest(
+        &state,
+        &ctx,
+        hub_ctx.as_ref(),
+        req,
+        crate::core::SetStore::sinterstore,
+    )
+}
+
+/// POST /set/unionstore - Store the union of sets in a destination
+pub async fn set_unionstore(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<SetStoreRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!(
+        "REST SUNIONSTORE dest={} keys={:?}",
+        req.destination, req.keys
+    );
+    set_store_rest(
+        &state,
+        &ctx,
+        hub_ctx.as_ref(),
+        req,
+        crate::core::SetStore::sunionstore,
+    )
+}
+
+/// POST /set/diffstore - Store the difference of sets in a destination
+pub async fn set_diffstore(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<SetStoreRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!(
+        "REST SDIFFSTORE dest={} keys={:?}",
+        req.destination, req.keys
+    );
+    set_store_rest(
+        &state,
+        &ctx,
+        hub_ctx.as_ref(),
+        req,
+        crate::core::SetStore::sdiffstore,
+    )
+}
+
+/// POST /set/intercard - Size of the intersection of sets
+pub async fn set_intercard(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<SetInterCardRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST SINTERCARD keys={:?} limit={}", req.keys, req.limit);
+
+    // Check permissions for all keys
+    for key in &req.keys {
+        require_permission(&ctx, &format!("set:{}", key), Action::Read)?;
+    }
+
+    // Apply multi-tenant scoping if Hub mode is active
+
+    let scoped_keys: Vec<String> = req
+        .keys
+        .iter()
+        .map(|key| {
+            crate::hub::MultiTenant::scope_kv_key(hub_ctx.as_ref().map(|c| c.user_id()), key)
+                .into_owned()
+        })
+        .collect();
+
+    state.check_same_slot(&scoped_keys)?;
+    let count = state.set_store.sintercard(&scoped_keys, req.limit)?;
+
+    Ok(Json(json!({ "count": count })))
+}
+
 /// GET /set/stats - Get set statistics
 pub async fn set_stats(
     State(state): State<AppState>,
@@ -0,0 +1,91 @@
+use super::*;
+use crate::shovel::{ShovelContext, ShovelManager, ShovelSpec, ShovelStatus};
+
+fn shovels(state: &AppState) -> Result<&Arc<ShovelManager>, SynapError> {
+    state
+        .shovels
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Queue system disabled".to_string()))
+}
+
+/// GET /shovels - List shovels with their progress
+pub async fn shovel_list(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST GET /shovels");
+
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    Ok(Json(json!({ "shovels": shovels(&state)?.list() })))
+}
+
+/// POST /shovels - Start a shovel
+pub async fn shovel_create(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(spec): Json<ShovelSpec>,
+) -> Result<Json<ShovelStatus>, SynapError> {
+    debug!(
+        "REST POST /shovels: {} ({} -> {})",
+        spec.name, spec.source, spec.destination
+    );
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let status = shovels(&state)?
+        .create(spec, ShovelContext::new(&state)?)
+        .await?;
+    Ok(Json(status))
+}
+
+/// DELETE /shovels/{name} - Stop and remove a shovel
+pub async fn shovel_delete(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST DELETE /shovels/{}", name);
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let deleted = shovels(&state)?.delete(&name);
+    Ok(Json(json!({ "deleted": deleted })))
+}
+
+// ============================================================================
+// Shovel StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_shovel_create_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let spec: ShovelSpec = serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid shovel: {}", e)))?;
+
+    let status = shovels(state)?
+        .create(spec, ShovelContext::new(state)?)
+        .await?;
+    serde_json::to_value(status).map_err(|e| SynapError::SerializationError(e.to_string()))
+}
+
+pub(super) async fn handle_shovel_list_cmd(
+    state: &AppState,
+) -> Result<serde_json::Value, SynapError> {
+    Ok(json!({ "shovels": shovels(state)?.list() }))
+}
+
+pub(super) async fn handle_shovel_delete_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let name = request
+        .payload
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'name' field".to_string()))?;
+
+    let deleted = shovels(state)?.delete(name);
+    Ok(json!({ "deleted": deleted }))
+}
@@ -46,6 +46,54 @@ pub struct ZInterstoreRequest {
     pub aggregate: String, // "sum", "min", "max"
 }
 
+/// Body for ZINTER / ZUNION / ZDIFF: like the STORE variants, minus the
+/// destination. `weights` and `aggregate` are ignored by ZDIFF.
+#[derive(Debug, Deserialize)]
+pub struct ZCombineRequest {
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub weights: Option<Vec<f64>>,
+    #[serde(default)]
+    pub aggregate: String, // "sum", "min", "max"
+    #[serde(default)]
+    pub withscores: bool,
+}
+
+fn parse_aggregate(aggregate: &str) -> crate::core::Aggregate {
+    match aggregate.to_lowercase().as_str() {
+        "min" => crate::core::Aggregate::Min,
+        "max" => crate::core::Aggregate::Max,
+        _ => crate::core::Aggregate::Sum,
+    }
+}
+
+fn check_weights(keys: usize, weights: Option<&[f64]>) -> Result<(), SynapError> {
+    match weights {
+        Some(w) if w.len() != keys => Err(SynapError::InvalidRequest(format!(
+            "Expected {} weights (one per key), got {}",
+            keys,
+            w.len()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// `{"members": [...]}` with `{member, score}` objects when `withscores`,
+/// bare member strings otherwise.
+fn combine_response(
+    members: Vec<crate::core::ScoredMember>,
+    withscores: bool,
+) -> serde_json::Value {
+    if withscores {
+        return json!({ "members": serialize_scored_members(members) });
+    }
+    let names: Vec<String> = members
+        .into_iter()
+        .map(|m| String::from_utf8_lossy(&m.member).into_owned())
+        .collect();
+    json!({ "members": names })
+}
+
 fn serialize_scored_members(members: Vec<crate::core::ScoredMember>) -> Vec<serde_json::Value> {
     members
         .into_iter()
@@ -71,19 +119,37 @@ pub(super) async fn handle_sortedset_zadd_cmd(
         .and_then(|v| v.as_str())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
 
-    let member = request
-        .payload
-        .get("member")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'member' field".to_string()))?;
-
-    let score = request
-        .payload
-        .get("score")
-        .and_then(|v| v.as_f64())
-        .ok_or_else(|| SynapError::InvalidRequest("Missing 'score' field".to_string()))?;
-
-    let member_bytes = member.as_bytes().to_vec();
+    // Either a single `member`/`score` or `members: [{member, score}, ...]`
+    let pairs: Vec<(Vec<u8>, f64)> = match request.payload.get("members") {
+        Some(members) => members
+            .as_array()
+            .ok_or_else(|| SynapError::InvalidRequest("'members' must be an array".to_string()))?
+            .iter()
+            .map(|entry| {
+                let member = entry.get("member").and_then(|v| v.as_str());
+                let score = entry.get("score").and_then(|v| v.as_f64());
+                match (member, score) {
+                    (Some(member), Some(score)) => Ok((member.as_bytes().to_vec(), score)),
+                    _ => Err(SynapError::InvalidRequest(
+                        "Each of 'members' needs a 'member' and a 'score'".to_string(),
+                    )),
+                }
+            })
+            .collect::<Result<_, _>>()?,
+        None => {
+            let member = request
+                .payload
+                .get("member")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| SynapError::InvalidRequest("Missing 'member' field".to_string()))?;
+            let score = request
+                .payload
+                .get("score")
+                .and_then(|v| v.as_f64())
+                .ok_or_else(|| SynapError::InvalidRequest("Missing 'score' field".to_string()))?;
+            vec![(member.as_bytes().to_vec(), score)]
+        }
+    };
 
     let opts = crate::core::ZAddOptions {
         nx: request
@@ -118,7 +184,12 @@ pub(super) async fn handle_sortedset_zadd_cmd(
             .unwrap_or(false),
     };
 
-    let (added, changed) = state.sorted_set_store.zadd(key, member_bytes, score, &opts);
+    let (mut added, mut changed) = (0, 0);
+    for (member, score) in pairs {
+        let (a, c) = state.sorted_set_store.try_zadd(key, member, score, &opts)?;
+        added += a;
+        changed += c;
+    }
 
     Ok(serde_json::json!({ "added": added, "changed": changed, "key": key }))
 }
@@ -215,7 +286,9 @@ pub(super) async fn handle_sortedset_zincrby_cmd(
 
     let member_bytes = member.as_bytes().to_vec();
 
-    let new_score = state.sorted_set_store.zincrby(key, member_bytes, increment);
+    let new_score = state
+        .sorted_set_store
+        .try_zincrby(key, member_bytes, increment)?;
 
     Ok(serde_json::json!({ "score": new_score, "key": key }))
 }
@@ -254,6 +327,42 @@ pub(super) async fn handle_sortedset_zrange_cmd(
     Ok(serde_json::json!({ "members": members, "key": key }))
 }
 
+pub(super) async fn handle_sortedset_zscan_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let key = request
+        .payload
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+    let cursor = request
+        .payload
+        .get("cursor")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let pattern = request.payload.get("match").and_then(|v| v.as_str());
+    let count = request
+        .payload
+        .get("count")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(100) as usize;
+
+    let (next_cursor, items) = state.sorted_set_store.zscan(key, cursor, pattern, count);
+    let members = serialize_scored_members(
+        items
+            .into_iter()
+            .map(|(member, score)| crate::core::ScoredMember { member, score })
+            .collect(),
+    );
+
+    Ok(serde_json::json!({
+        "members": members,
+        "count": members.len(),
+        "next_cursor": next_cursor,
+    }))
+}
+
 pub(super) async fn handle_sortedset_zrevrange_cmd(
     state: &AppState,
     request: &Request,
@@ -533,6 +642,8 @@ pub(super) async fn handle_sortedset_zinterstore_cmd(
         _ => crate::core::Aggregate::Sum,
     };
 
+    state.check_same_slot([destination].iter().chain(&key_strs))?;
+
     let count =
         state
             .sorted_set_store
@@ -577,6 +688,8 @@ pub(super) async fn handle_sortedset_zunionstore_cmd(
         _ => crate::core::Aggregate::Sum,
     };
 
+    state.check_same_slot([destination].iter().chain(&key_strs))?;
+
     let count =
         state
             .sorted_set_store
@@ -603,11 +716,64 @@ pub(super) async fn handle_sortedset_zdiffstore_cmd(
 
     let key_strs: Vec<&str> = keys.iter().filter_map(|v| v.as_str()).collect();
 
+    state.check_same_slot([destination].iter().chain(&key_strs))?;
+
     let count = state.sorted_set_store.zdiffstore(destination, &key_strs);
 
     Ok(serde_json::json!({ "count": count, "destination": destination }))
 }
 
+fn parse_combine_payload(request: &Request) -> Result<ZCombineRequest, SynapError> {
+    let req: ZCombineRequest = serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid payload: {}", e)))?;
+    check_weights(req.keys.len(), req.weights.as_deref())?;
+    Ok(req)
+}
+
+pub(super) async fn handle_sortedset_zinter_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let req = parse_combine_payload(request)?;
+    let keys: Vec<&str> = req.keys.iter().map(|s| s.as_str()).collect();
+    state.check_same_slot(&keys)?;
+    let members = state.sorted_set_store.zinter(
+        &keys,
+        req.weights.as_deref(),
+        parse_aggregate(&req.aggregate),
+    );
+
+    Ok(combine_response(members, req.withscores))
+}
+
+pub(super) async fn handle_sortedset_zunion_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let req = parse_combine_payload(request)?;
+    let keys: Vec<&str> = req.keys.iter().map(|s| s.as_str()).collect();
+    state.check_same_slot(&keys)?;
+    let members = state.sorted_set_store.zunion(
+        &keys,
+        req.weights.as_deref(),
+        parse_aggregate(&req.aggregate),
+    );
+
+    Ok(combine_response(members, req.withscores))
+}
+
+pub(super) async fn handle_sortedset_zdiff_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let req = parse_combine_payload(request)?;
+    let keys: Vec<&str> = req.keys.iter().map(|s| s.as_str()).collect();
+    state.check_same_slot(&keys)?;
+    let members = state.sorted_set_store.zdiff(&keys);
+
+    Ok(combine_response(members, req.withscores))
+}
+
 pub(super) async fn handle_sortedset_zmscore_cmd(
     state: &AppState,
     request: &Request,
@@ -653,6 +819,75 @@ pub(super) async fn handle_sortedset_stats_cmd(
     }))
 }
 
+/// The leaderboard notifier, where per-key windows live
+fn leaderboard_notifier(
+    state: &AppState,
+) -> Result<&Arc<crate::core::LeaderboardNotifier>, SynapError> {
+    state
+        .sorted_set_store
+        .leaderboard_notifier()
+        .ok_or_else(|| SynapError::InvalidRequest("Leaderboard notifications disabled".to_string()))
+}
+
+/// A key's leaderboard window and the channel its events are published on
+fn leaderboard_window_json(
+    notifier: &crate::core::LeaderboardNotifier,
+    key: &str,
+) -> serde_json::Value {
+    json!({
+        "key": key,
+        "window": notifier.window(key),
+        "channel": notifier.channel_for(key),
+    })
+}
+
+pub(super) async fn handle_sortedset_notify_set_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let key = request
+        .payload
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+
+    let window: crate::core::LeaderboardWindow = serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid window: {e}")))?;
+
+    let notifier = leaderboard_notifier(state)?;
+    notifier.set_window(key, window)?;
+
+    Ok(leaderboard_window_json(notifier, key))
+}
+
+pub(super) async fn handle_sortedset_notify_get_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let key = request
+        .payload
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+
+    Ok(leaderboard_window_json(leaderboard_notifier(state)?, key))
+}
+
+pub(super) async fn handle_sortedset_notify_del_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let key = request
+        .payload
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'key' field".to_string()))?;
+
+    let removed = leaderboard_notifier(state)?.remove_window(key);
+
+    Ok(json!({ "key": key, "removed": removed }))
+}
+
 pub async fn sortedset_zadd(
     State(state): State<AppState>,
     AuthContextExtractor(ctx): AuthContextExtractor,
@@ -692,7 +927,7 @@ pub async fn sortedset_zadd(
             })?;
             let (added, _) = state
                 .sorted_set_store
-                .zadd(&key, member_bytes, score, &opts);
+                .try_zadd(&key, member_bytes, score, &opts)?;
             added
         }
         ZAddRequest::Multiple {
@@ -709,9 +944,10 @@ pub async fn sortedset_zadd(
                 let member_bytes = serde_json::to_vec(&member).map_err(|e| {
                     SynapError::InvalidValue(format!("Failed to serialize member: {}", e))
                 })?;
-                let (added, _) = state
-                    .sorted_set_store
-                    .zadd(&key, member_bytes, score, &opts);
+                let (added, _) =
+                    state
+                        .sorted_set_store
+                        .try_zadd(&key, member_bytes, score, &opts)?;
                 total_added += added;
             }
             total_added
@@ -795,7 +1031,7 @@ pub async fn sortedset_zincrby(
 
     let new_score = state
         .sorted_set_store
-        .zincrby(&key, member_bytes, increment);
+        .try_zincrby(&key, member_bytes, increment)?;
 
     Ok(Json(json!({ "score": new_score, "key": key })))
 }
@@ -897,6 +1133,8 @@ pub async fn sortedset_zinterstore(
         _ => crate::core::Aggregate::Sum,
     };
 
+    state.check_same_slot([req.destination.as_str()].iter().chain(&keys))?;
+
     let count = state
         .sorted_set_store
         .zinterstore(&req.destination, &keys, weights, aggregate);
@@ -925,6 +1163,8 @@ pub async fn sortedset_zunionstore(
         _ => crate::core::Aggregate::Sum,
     };
 
+    state.check_same_slot([req.destination.as_str()].iter().chain(&keys))?;
+
     let count = state
         .sorted_set_store
         .zunionstore(&req.destination, &keys, weights, aggregate);
@@ -1135,6 +1375,7 @@ pub async fn sortedset_zdiffstore(
     );
 
     let keys: Vec<&str> = req.keys.iter().map(|s| s.as_str()).collect();
+    state.check_same_slot([req.destination.as_str()].iter().chain(&keys))?;
     let count = state.sorted_set_store.zdiffstore(&req.destination, &keys);
 
     Ok(Json(
@@ -1142,6 +1383,60 @@ pub async fn sortedset_zdiffstore(
     ))
 }
 
+/// POST /sortedset/zinter - Intersection without storing
+pub async fn sortedset_zinter(
+    State(state): State<AppState>,
+    Json(req): Json<ZCombineRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST ZINTER keys={:?}", req.keys);
+
+    let keys: Vec<&str> = req.keys.iter().map(|s| s.as_str()).collect();
+    check_weights(keys.len(), req.weights.as_deref())?;
+
+    state.check_same_slot(&keys)?;
+    let members = state.sorted_set_store.zinter(
+        &keys,
+        req.weights.as_deref(),
+        parse_aggregate(&req.aggregate),
+    );
+
+    Ok(Json(combine_response(members, req.withscores)))
+}
+
+/// POST /sortedset/zunion - Union without storing
+pub async fn sortedset_zunion(
+    State(state): State<AppState>,
+    Json(req): Json<ZCombineRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST ZUNION keys={:?}", req.keys);
+
+    let keys: Vec<&str> = req.keys.iter().map(|s| s.as_str()).collect();
+    check_weights(keys.len(), req.weights.as_deref())?;
+
+    state.check_same_slot(&keys)?;
+    let members = state.sorted_set_store.zunion(
+        &keys,
+        req.weights.as_deref(),
+        parse_aggregate(&req.aggregate),
+    );
+
+    Ok(Json(combine_response(members, req.withscores)))
+}
+
+/// POST /sortedset/zdiff - Difference without storing
+pub async fn sortedset_zdiff(
+    State(state): State<AppState>,
+    Json(req): Json<ZCombineRequest>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST ZDIFF keys={:?}", req.keys);
+
+    let keys: Vec<&str> = req.keys.iter().map(|s| s.as_str()).collect();
+    state.check_same_slot(&keys)?;
+    let members = state.sorted_set_store.zdiff(&keys);
+
+    Ok(Json(combine_response(members, req.withscores)))
+}
+
 // ==================== Geospatial Request/Response Types ====================
 
 #[derive(Debug, Deserialize)]
@@ -1175,3 +1470,51 @@ pub struct GeospatialDistRequest {
     #[serde(default = "default_unit")]
     pub unit: String,
 }
+
+/// PUT /sortedset/:key/notify - Publish leaderboard notifications for a key
+pub async fn sortedset_notify_set(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(key): Path<String>,
+    Json(window): Json<crate::core::LeaderboardWindow>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST SORTEDSET NOTIFY SET key={}", key);
+
+    require_permission(&ctx, &format!("sortedset:{}", key), Action::Configure)?;
+
+    let notifier = leaderboard_notifier(&state)?;
+    notifier.set_window(&key, window)?;
+
+    Ok(Json(leaderboard_window_json(notifier, &key)))
+}
+
+/// GET /sortedset/:key/notify - Get a key's leaderboard window
+pub async fn sortedset_notify_get(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(key): Path<String>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST SORTEDSET NOTIFY GET key={}", key);
+
+    require_permission(&ctx, &format!("sortedset:{}", key), Action::Read)?;
+
+    Ok(Json(leaderboard_window_json(
+        leaderboard_notifier(&state)?,
+        &key,
+    )))
+}
+
+/// DELETE /sortedset/:key/notify - Stop leaderboard notifications for a key
+pub async fn sortedset_notify_del(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(key): Path<String>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST SORTEDSET NOTIFY DEL key={}", key);
+
+    require_permission(&ctx, &format!("sortedset:{}", key), Action::Configure)?;
+
+    let removed = leaderboard_notifier(&state)?.remove_window(&key);
+
+    Ok(Json(json!({ "key": key, "removed": removed })))
+}
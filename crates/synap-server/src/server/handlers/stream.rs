@@ -1,4 +1,5 @@
 use super::*;
+use std::time::Duration;
 
 // ==================== Event Stream REST Endpoints ====================
 
@@ -106,14 +107,22 @@ pub async fn stream_publish(
     let data_bytes =
         serde_json::to_vec(&req.data).map_err(|e| SynapError::SerializationError(e.to_string()))?;
 
-    let offset = stream_manager
-        .publish(&scoped_name, &req.event, data_bytes)
-        .await
-        .map_err(SynapError::InvalidRequest)?;
+    let (offset, duplicate) = match req.idempotency_key.as_deref() {
+        Some(key) => stream_manager
+            .publish_idempotent(&scoped_name, &req.event, data_bytes, key)
+            .await
+            .map_err(SynapError::InvalidRequest)?,
+        None => stream_manager
+            .publish(&scoped_name, &req.event, data_bytes)
+            .await
+            .map(|offset| (offset, false))
+            .map_err(SynapError::InvalidRequest)?,
+    };
 
     Ok(Json(StreamPublishResponse {
         offset,
         room: room_name,
+        duplicate,
     }))
 }
 
@@ -156,8 +165,14 @@ pub async fn stream_consume(
         .and_then(|s| s.parse::<usize>().ok())
         .unwrap_or(100);
 
+    // Delayed visibility: hold back events younger than this
+    let min_age_ms = params
+        .get("min_age_ms")
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0);
+
     let events = stream_manager
-        .consume(&scoped_name, &subscriber_id, from_offset, limit)
+        .consume_with_min_age(&scoped_name, &subscriber_id, from_offset, limit, min_age_ms)
         .await
         .map_err(SynapError::InvalidRequest)?;
 
@@ -169,6 +184,116 @@ pub async fn stream_consume(
     }))
 }
 
+/// Consume from several rooms in one long-poll call
+///
+/// Waits up to `timeout_ms` (capped at 30s) for the first event when every
+/// room is caught up. Events come back interleaved and tagged with their room,
+/// and `next_offsets` holds the offset to resume each room from.
+pub async fn stream_consume_many(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+
+    crate::hub::HubContextExtractor(hub_ctx): crate::hub::HubContextExtractor,
+    Json(req): Json<StreamConsumeManyRequest>,
+) -> Result<Json<StreamConsumeManyResponse>, SynapError> {
+    debug!(
+        "REST STREAM CONSUME MANY: {} rooms, subscriber: {}",
+        req.rooms.len(),
+        req.subscriber_id
+    );
+
+    for room in &req.rooms {
+        require_permission(&ctx, &format!("stream:{}", room.room), Action::Read)?;
+    }
+
+    let stream_manager = state
+        .stream_manager
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Stream system disabled".to_string()))?;
+
+    // Apply multi-tenant scoping if Hub mode is active
+    let scoped: Vec<(String, u64)> = req
+        .rooms
+        .iter()
+        .map(|r| {
+            let name = crate::hub::MultiTenant::scope_stream_name(
+                hub_ctx.as_ref().map(|c| c.user_id()),
+                &r.room,
+            );
+            (name, r.offset)
+        })
+        .collect();
+
+    let mut events = consume_many_wait(
+        stream_manager,
+        &req.subscriber_id,
+        &scoped,
+        req.limit.unwrap_or(100),
+        req.timeout_ms.unwrap_or(0),
+    )
+    .await?;
+    let next_offsets = untag_rooms(&req.rooms, &scoped, &mut events);
+
+    Ok(Json(StreamConsumeManyResponse {
+        events,
+        next_offsets,
+    }))
+}
+
+/// Drop durable subscriber positions of a deleted room
+async fn forget_stream_subscribers(state: &AppState, room: &str) {
+    state
+        .forget_subscriptions(|record| {
+            matches!(record, crate::persistence::SubscriptionRecord::StreamSubscriber { room: r, .. } if r == room)
+        })
+        .await;
+}
+
+/// Longest a multi-room consume waits for the first event
+const CONSUME_MANY_MAX_WAIT_MS: u64 = 30_000;
+
+/// Poll `rooms` every 100ms until any of them has events or `timeout_ms` runs out
+async fn consume_many_wait(
+    stream_manager: &crate::core::StreamManager,
+    subscriber_id: &str,
+    rooms: &[(String, u64)],
+    limit: usize,
+    timeout_ms: u64,
+) -> Result<Vec<crate::core::StreamEvent>, SynapError> {
+    let deadline = tokio::time::Instant::now()
+        + Duration::from_millis(timeout_ms.min(CONSUME_MANY_MAX_WAIT_MS));
+    loop {
+        let events = stream_manager
+            .consume_many(subscriber_id, rooms, limit)
+            .await
+            .map_err(SynapError::InvalidRequest)?;
+        if !events.is_empty() || tokio::time::Instant::now() >= deadline {
+            return Ok(events);
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Tag events with the room name the caller asked for (not the tenant-scoped
+/// one) and work out where each room resumes
+fn untag_rooms(
+    requested: &[StreamRoomOffset],
+    scoped: &[(String, u64)],
+    events: &mut [crate::core::StreamEvent],
+) -> std::collections::BTreeMap<String, u64> {
+    let mut next_offsets: std::collections::BTreeMap<String, u64> = requested
+        .iter()
+        .map(|r| (r.room.clone(), r.offset))
+        .collect();
+    for event in events.iter_mut() {
+        if let Some(i) = scoped.iter().position(|(name, _)| *name == event.room) {
+            event.room = requested[i].room.clone();
+            next_offsets.insert(event.room.clone(), event.offset + 1);
+        }
+    }
+    next_offsets
+}
+
 /// Get stream room statistics
 pub async fn stream_room_stats(
     State(state): State<AppState>,
@@ -264,6 +389,7 @@ pub async fn stream_delete_room(
         .delete_room(&scoped_name)
         .await
         .map_err(SynapError::InvalidRequest)?;
+    forget_stream_subscribers(&state, &scoped_name).await;
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -290,10 +416,24 @@ pub(super) async fn handle_stream_create_cmd(
         .and_then(|v| v.as_str())
         .ok_or_else(|| SynapError::InvalidRequest("Missing 'room' field".to_string()))?;
 
-    stream_manager
-        .create_room(room)
-        .await
-        .map_err(SynapError::InvalidRequest)?;
+    // Optional per-room config; fields left out take the manager's defaults
+    let dedup_window_secs = request
+        .payload
+        .get("config")
+        .and_then(|v| v.get("dedup_window_secs"))
+        .and_then(|d| d.as_u64());
+
+    match dedup_window_secs {
+        Some(dedup_window_secs) => {
+            let config = crate::core::StreamConfig {
+                dedup_window_secs,
+                ..stream_manager.default_config()
+            };
+            stream_manager.create_room_with_config(room, config).await
+        }
+        None => stream_manager.create_room(room).await,
+    }
+    .map_err(SynapError::InvalidRequest)?;
 
     Ok(serde_json::json!({
         "success": true,
@@ -357,15 +497,65 @@ pub(super) async fn handle_stream_publish_cmd(
     let data_bytes =
         serde_json::to_vec(data).map_err(|e| SynapError::SerializationError(e.to_string()))?;
 
-    let offset = stream_manager
-        .publish(room, event, data_bytes)
-        .await
-        .map_err(SynapError::InvalidRequest)?;
+    let idempotency_key = request
+        .payload
+        .get("idempotency_key")
+        .and_then(|v| v.as_str());
 
-    Ok(serde_json::json!({
+    // Check if there's an active transaction for this client_id
+    let client_id = request
+        .payload
+        .get("client_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    if !client_id.is_empty() {
+        if idempotency_key.is_some()
+            && state
+                .transaction_manager
+                .get_transaction(client_id)
+                .is_some()
+        {
+            return Err(SynapError::InvalidRequest(
+                "'idempotency_key' is not supported inside a transaction".to_string(),
+            ));
+        }
+
+        let was_queued = state.transaction_manager.queue_command_if_transaction(
+            client_id,
+            crate::core::transaction::TransactionCommand::StreamPublish {
+                room: room.to_string(),
+                event: event.to_string(),
+                data: data_bytes.clone(),
+            },
+        )?;
+
+        if was_queued {
+            return Ok(serde_json::json!({ "success": true, "queued": true }));
+        }
+    }
+
+    let (offset, duplicate) = match idempotency_key {
+        Some(key) => {
+            stream_manager
+                .publish_idempotent(room, event, data_bytes, key)
+                .await
+        }
+        None => stream_manager
+            .publish(room, event, data_bytes)
+            .await
+            .map(|offset| (offset, false)),
+    }
+    .map_err(SynapError::InvalidRequest)?;
+
+    let mut response = serde_json::json!({
         "offset": offset,
         "room": room
-    }))
+    });
+    if duplicate {
+        response["duplicate"] = serde_json::json!(true);
+    }
+    Ok(response)
 }
 
 pub(super) async fn handle_stream_consume_cmd(
@@ -401,8 +591,14 @@ pub(super) async fn handle_stream_consume_cmd(
         .and_then(|v| v.as_u64())
         .unwrap_or(100) as usize;
 
+    let min_age_ms = request
+        .payload
+        .get("min_age_ms")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
     let events = stream_manager
-        .consume(room, subscriber_id, from_offset, limit)
+        .consume_with_min_age(room, subscriber_id, from_offset, limit, min_age_ms)
         .await
         .map_err(SynapError::InvalidRequest)?;
 
@@ -414,6 +610,40 @@ pub(super) async fn handle_stream_consume_cmd(
     }))
 }
 
+pub(super) async fn handle_stream_consume_many_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let stream_manager = state
+        .stream_manager
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Stream system disabled".to_string()))?;
+
+    let req: StreamConsumeManyRequest = serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid payload: {}", e)))?;
+
+    let rooms: Vec<(String, u64)> = req
+        .rooms
+        .iter()
+        .map(|r| (r.room.clone(), r.offset))
+        .collect();
+
+    let mut events = consume_many_wait(
+        stream_manager,
+        &req.subscriber_id,
+        &rooms,
+        req.limit.unwrap_or(100),
+        req.timeout_ms.unwrap_or(0),
+    )
+    .await?;
+    let next_offsets = untag_rooms(&req.rooms, &rooms, &mut events);
+
+    Ok(serde_json::json!({
+        "events": events,
+        "next_offsets": next_offsets
+    }))
+}
+
 pub(super) async fn handle_stream_stats_cmd(
     state: &AppState,
     request: &Request,
@@ -473,6 +703,7 @@ pub(super) async fn handle_stream_delete_cmd(
         .delete_room(room)
         .await
         .map_err(SynapError::InvalidRequest)?;
+    forget_stream_subscribers(state, room).await;
 
     Ok(serde_json::json!({
         "success": true,
@@ -0,0 +1,60 @@
+use super::*;
+
+/// Durable subscription records as JSON: the record's fields plus its `id`
+/// and `kind`
+fn subscriptions_json(state: &AppState) -> Result<serde_json::Value, SynapError> {
+    let Some(registry) = state.subscriptions() else {
+        return Ok(json!({"enabled": false, "records": [], "count": 0}));
+    };
+
+    let records = registry
+        .records()
+        .into_iter()
+        .map(|record| {
+            let id = record.id();
+            let value = serde_json::to_value(&record)
+                .map_err(|e| SynapError::SerializationError(e.to_string()))?;
+            // Externally tagged: {"GroupMember": {...}}
+            let (kind, fields) = value
+                .as_object()
+                .and_then(|o| o.iter().next())
+                .map(|(kind, fields)| (kind.clone(), fields.clone()))
+                .unwrap_or_default();
+            let mut entry = json!({"id": id, "kind": kind});
+            if let (Some(entry), Some(fields)) = (entry.as_object_mut(), fields.as_object()) {
+                entry.extend(fields.clone());
+            }
+            Ok(entry)
+        })
+        .collect::<Result<Vec<_>, SynapError>>()?;
+
+    Ok(json!({
+        "enabled": true,
+        "count": records.len(),
+        "records": records
+    }))
+}
+
+/// GET /subscriptions - List durable subscription records
+pub async fn subscriptions_list(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST GET /subscriptions");
+
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    Ok(Json(subscriptions_json(&state)?))
+}
+
+// ============================================================================
+// Subscription Registry StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_subscriptions_list_cmd(
+    state: &AppState,
+    ctx: &crate::auth::AuthContext,
+) -> Result<serde_json::Value, SynapError> {
+    require_permission(ctx, "admin:*", Action::Read)?;
+    subscriptions_json(state)
+}
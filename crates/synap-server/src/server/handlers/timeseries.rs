@@ -0,0 +1,80 @@
+use super::*;
+use crate::core::{SeriesOptions, TimeSeriesAggregation, TimeSeriesResolution};
+use crate::protocol::command::timeseries::{
+    self as protocol, TimeSeriesDelete, TimeSeriesDeleteResponse, TimeSeriesIncr,
+    TimeSeriesIncrResponse, TimeSeriesRange, TimeSeriesRangeResponse,
+};
+use std::time::Duration;
+
+fn resolution(resolution: protocol::TimeSeriesResolution) -> TimeSeriesResolution {
+    match resolution {
+        protocol::TimeSeriesResolution::Second => TimeSeriesResolution::Second,
+        protocol::TimeSeriesResolution::Minute => TimeSeriesResolution::Minute,
+        protocol::TimeSeriesResolution::Hour => TimeSeriesResolution::Hour,
+    }
+}
+
+fn aggregation(aggregation: protocol::TimeSeriesAggregation) -> TimeSeriesAggregation {
+    match aggregation {
+        protocol::TimeSeriesAggregation::Sum => TimeSeriesAggregation::Sum,
+        protocol::TimeSeriesAggregation::Avg => TimeSeriesAggregation::Avg,
+        protocol::TimeSeriesAggregation::Max => TimeSeriesAggregation::Max,
+    }
+}
+
+// ============================================================================
+// Time Series StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_ts_incr_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let incr: TimeSeriesIncr = decode_payload(request)?;
+    let options = SeriesOptions {
+        resolution: incr.resolution.map(resolution),
+        retention: incr.retention.map(Duration::from_millis),
+    };
+
+    let point = state
+        .time_series
+        .incr(&incr.key, incr.value, incr.timestamp, &options)?;
+    encode_response(TimeSeriesIncrResponse {
+        timestamp: point.timestamp,
+        value: point.value,
+    })
+}
+
+pub(super) async fn handle_ts_range_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let range: TimeSeriesRange = decode_payload(request)?;
+
+    let points = state.time_series.range(
+        &range.key,
+        range.from.unwrap_or(0),
+        range.to.unwrap_or(u64::MAX),
+        aggregation(range.aggregation),
+        range.bucket,
+    )?;
+    encode_response(TimeSeriesRangeResponse {
+        points: points
+            .into_iter()
+            .map(|point| protocol::TimeSeriesPoint {
+                timestamp: point.timestamp,
+                value: point.value,
+            })
+            .collect(),
+    })
+}
+
+pub(super) async fn handle_ts_del_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let TimeSeriesDelete { key } = decode_payload(request)?;
+
+    let deleted = state.time_series.delete(&key);
+    encode_response(TimeSeriesDeleteResponse { deleted })
+}
@@ -0,0 +1,46 @@
+use super::*;
+use crate::warmup::WarmupManifest;
+
+/// POST /warmup - Prime the manifest's keys and mark the node ready
+pub async fn warmup_run(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(manifest): Json<WarmupManifest>,
+) -> Result<Json<crate::warmup::WarmupReport>, SynapError> {
+    debug!(
+        "REST POST /warmup: {} keys, {} prefixes",
+        manifest.keys.len(),
+        manifest.prefixes.len()
+    );
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let report = state
+        .monitoring
+        .warmup()
+        .run(&state.kv_store, &manifest)
+        .await;
+    Ok(Json(report))
+}
+
+// ============================================================================
+// Warmup StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_warmup_run_cmd(
+    state: AppState,
+    ctx: &crate::auth::AuthContext,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    require_permission(ctx, "admin:*", Action::Admin)?;
+
+    let manifest: WarmupManifest = serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid warmup manifest: {}", e)))?;
+
+    let report = state
+        .monitoring
+        .warmup()
+        .run(&state.kv_store, &manifest)
+        .await;
+    serde_json::to_value(report).map_err(|e| SynapError::SerializationError(e.to_string()))
+}
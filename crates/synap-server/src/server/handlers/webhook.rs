@@ -0,0 +1,88 @@
+use super::*;
+use crate::webhook::{WebhookContext, WebhookManager, WebhookSpec, WebhookStatus};
+
+fn webhooks(state: &AppState) -> Result<&Arc<WebhookManager>, SynapError> {
+    state
+        .webhooks
+        .as_ref()
+        .ok_or_else(|| SynapError::InvalidRequest("Queue system disabled".to_string()))
+}
+
+/// GET /webhooks - List webhooks with their delivery counters
+pub async fn webhook_list(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST GET /webhooks");
+
+    require_permission(&ctx, "admin:*", Action::Read)?;
+
+    Ok(Json(json!({ "webhooks": webhooks(&state)?.list() })))
+}
+
+/// POST /webhooks - Start pushing a queue or topic to a URL
+pub async fn webhook_create(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Json(spec): Json<WebhookSpec>,
+) -> Result<Json<WebhookStatus>, SynapError> {
+    debug!("REST POST /webhooks: {} -> {}", spec.name, spec.url);
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let status = webhooks(&state)?
+        .create(spec, WebhookContext::new(&state)?)
+        .await?;
+    Ok(Json(status))
+}
+
+/// DELETE /webhooks/{name} - Stop and remove a webhook
+pub async fn webhook_delete(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, SynapError> {
+    debug!("REST DELETE /webhooks/{}", name);
+
+    require_permission(&ctx, "admin:*", Action::Admin)?;
+
+    let deleted = webhooks(&state)?.delete(&name);
+    Ok(Json(json!({ "deleted": deleted })))
+}
+
+// ============================================================================
+// Webhook StreamableHTTP Command Handlers
+// ============================================================================
+
+pub(super) async fn handle_webhook_create_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let spec: WebhookSpec = serde_json::from_value(request.payload.clone())
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid webhook: {}", e)))?;
+
+    let status = webhooks(state)?
+        .create(spec, WebhookContext::new(state)?)
+        .await?;
+    serde_json::to_value(status).map_err(|e| SynapError::SerializationError(e.to_string()))
+}
+
+pub(super) async fn handle_webhook_list_cmd(
+    state: &AppState,
+) -> Result<serde_json::Value, SynapError> {
+    Ok(json!({ "webhooks": webhooks(state)?.list() }))
+}
+
+pub(super) async fn handle_webhook_delete_cmd(
+    state: &AppState,
+    request: &Request,
+) -> Result<serde_json::Value, SynapError> {
+    let name = request
+        .payload
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| SynapError::InvalidRequest("Missing 'name' field".to_string()))?;
+
+    let deleted = webhooks(state)?.delete(name);
+    Ok(json!({ "deleted": deleted }))
+}
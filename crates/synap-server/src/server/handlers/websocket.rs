@@ -1,4 +1,44 @@
 use super::*;
+use crate::protocol::encoding::Encoding;
+
+/// Persist the new position of every room that delivered events
+#[cfg(feature = "streams")]
+async fn record_stream_offsets(
+    registry: &crate::persistence::SubscriptionRegistry,
+    subscriber_id: &str,
+    rooms: &[(String, u64)],
+    events: &[crate::core::StreamEvent],
+) {
+    for (room, offset) in rooms {
+        if !events.iter().any(|e| e.room == *room) {
+            continue;
+        }
+        let record = crate::persistence::SubscriptionRecord::StreamSubscriber {
+            room: room.clone(),
+            subscriber_id: subscriber_id.to_string(),
+            offset: *offset,
+        };
+        if let Err(e) = registry.put(record).await {
+            error!("Failed to record stream subscriber offset: {}", e);
+        }
+    }
+}
+
+/// Rejection for a connection subscribing to more topics, keys or rooms than
+/// `clients.max_subscriptions` allows
+fn subscription_limit_rejection(state: &AppState, count: usize) -> Option<AxumResponse> {
+    let max = state.client_list_manager.limits().max_subscriptions;
+    (max > 0 && count > max).then(|| {
+        (
+            axum::http::StatusCode::BAD_REQUEST,
+            format!(
+                "Too many subscriptions on one connection: {} (max {})",
+                count, max
+            ),
+        )
+            .into_response()
+    })
+}
 
 // ============================================================================
 // Pub/Sub WebSocket Handler
@@ -42,6 +82,9 @@ pub async fn kv_websocket(
         )
             .into_response();
     }
+    if let Some(response) = subscription_limit_rejection(&state, channels.len()) {
+        return response;
+    }
 
     info!("KV WebSocket WATCH connection for channels: {:?}", channels);
 
@@ -53,18 +96,302 @@ pub async fn kv_websocket(
             socket,
             pubsub_router,
             channels,
+            None,
             client_list_manager,
             client_addr,
         )
     })
 }
 
+/// Client-side caching endpoint: `GET /kv/tracking/ws`.
+///
+/// The welcome frame carries the connection's `client_id`. The client sends
+/// `{"type": "track", "id": n, "keys": [...]}` before reading keys it means to
+/// cache and waits for `{"type": "tracked", "id": n}`; from then on, a write to
+/// one of those keys pushes `{"type": "invalidate", "keys": [...]}` once.
+/// `"keys": null` means every key changed. The socket closes if the client
+/// falls behind, and the client must then flush its cache.
+pub async fn kv_tracking_websocket(
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+    axum::extract::ConnectInfo(addr): axum::extract::ConnectInfo<SocketAddr>,
+) -> AxumResponse {
+    let Some(tracking) = state.kv_store.tracking().cloned() else {
+        return (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            "Client-side caching disabled",
+        )
+            .into_response();
+    };
+
+    let client_list_manager = state.client_list_manager.clone();
+    let client_addr = addr.to_string();
+
+    ws.on_upgrade(move |socket| {
+        handle_tracking_socket(socket, tracking, client_list_manager, client_addr)
+    })
+}
+
+/// Handle a client-side caching WebSocket connection
+async fn handle_tracking_socket(
+    socket: WebSocket,
+    tracking: Arc<crate::core::TrackingTable>,
+    client_list_manager: Arc<crate::monitoring::ClientListManager>,
+    client_addr: String,
+) {
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+
+    let (tracking_id, mut rx) = tracking.register();
+    let client_id = format!("tracking-{}", tracking_id);
+    let client_info = crate::monitoring::ClientInfo::new(
+        client_id.clone(),
+        client_addr,
+        std::time::SystemTime::now(),
+    );
+    let kill = client_list_manager.add(client_info).await;
+
+    let welcome = json!({
+        "type": "connected",
+        "client_id": tracking_id,
+    });
+
+    if ws_sender
+        .send(ws::Message::Text(welcome.to_string().into()))
+        .await
+        .is_ok()
+    {
+        loop {
+            tokio::select! {
+                invalidation = rx.recv() => {
+                    // `None`: dropped as a slow consumer
+                    let Some(invalidation) = invalidation else {
+                        warn!("Tracking client {} fell behind, disconnecting", tracking_id);
+                        break;
+                    };
+                    let keys = match invalidation {
+                        crate::core::Invalidation::Keys(keys) => json!(keys),
+                        crate::core::Invalidation::Flush => serde_json::Value::Null,
+                    };
+                    let frame = json!({"type": "invalidate", "keys": keys});
+                    if ws_sender
+                        .send(ws::Message::Text(frame.to_string().into()))
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+
+                _ = kill.killed() => {
+                    info!("Tracking client {} killed", tracking_id);
+                    break;
+                }
+
+                Some(msg) = ws_receiver.next() => {
+                    let reply = match msg {
+                        Ok(ws::Message::Text(text)) => {
+                            tracking_reply(&tracking, tracking_id, &text)
+                        }
+                        Ok(ws::Message::Ping(data)) => {
+                            if ws_sender.send(ws::Message::Pong(data)).await.is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+                        Ok(ws::Message::Close(_)) | Err(_) => break,
+                        Ok(_) => continue,
+                    };
+                    if ws_sender
+                        .send(ws::Message::Text(reply.to_string().into()))
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+
+                else => break,
+            }
+        }
+    }
+
+    tracking.unregister(tracking_id);
+    client_list_manager.remove(&client_id).await;
+    debug!("Tracking client {} disconnected", tracking_id);
+}
+
+/// Answer one client frame on a tracking socket
+fn tracking_reply(
+    tracking: &crate::core::TrackingTable,
+    tracking_id: u64,
+    text: &str,
+) -> serde_json::Value {
+    #[derive(Deserialize)]
+    struct Track {
+        #[serde(rename = "type")]
+        kind: String,
+        #[serde(default)]
+        id: Option<u64>,
+        #[serde(default)]
+        keys: Vec<String>,
+    }
+
+    match serde_json::from_str::<Track>(text) {
+        Ok(frame) if frame.kind == "track" => {
+            tracking.track(tracking_id, &frame.keys);
+            json!({"type": "tracked", "id": frame.id})
+        }
+        Ok(frame) => json!({
+            "type": "error",
+            "id": frame.id,
+            "error": format!("unknown frame type: {}", frame.kind),
+        }),
+        Err(e) => json!({"type": "error", "error": e.to_string()}),
+    }
+}
+
+// ============================================================================
+// Command WebSocket Handler
+// ============================================================================
+
+/// Commands one command socket runs at once; the next frame is read when one
+/// of them has answered
+const COMMAND_WS_MAX_IN_FLIGHT: usize = 256;
+
+/// Multiplexed command endpoint: `GET /api/v1/command/ws`.
+///
+/// Each frame is one command envelope, the `Request` that
+/// `POST /api/v1/command` takes, and is answered by a `Response` carrying the
+/// same `request_id`. Commands run concurrently, so responses can arrive out
+/// of order; a request sent without an id gets a generated one. Text frames
+/// are JSON and binary frames MessagePack, and each response is encoded like
+/// its request. Credentials are checked once, on the upgrade request.
+pub async fn command_websocket(
+    State(state): State<AppState>,
+    AuthContextExtractor(ctx): AuthContextExtractor,
+    ws: WebSocketUpgrade,
+    axum::extract::ConnectInfo(addr): axum::extract::ConnectInfo<SocketAddr>,
+) -> AxumResponse {
+    let client_addr = addr.to_string();
+    ws.on_upgrade(move |socket| handle_command_socket(socket, state, ctx, client_addr))
+}
+
+/// Handle a multiplexed command WebSocket connection
+async fn handle_command_socket(
+    socket: WebSocket,
+    state: AppState,
+    ctx: crate::auth::AuthContext,
+    client_addr: String,
+) {
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+
+    let client_id = format!("command-ws-{}", uuid::Uuid::new_v4());
+    let client_info = crate::monitoring::ClientInfo::new(
+        client_id.clone(),
+        client_addr,
+        std::time::SystemTime::now(),
+    );
+    let kill = state.client_list_manager.add(client_info).await;
+
+    // A response frame carries its command's permit until it is written, so
+    // the reader stops taking commands while the client is not reading
+    let in_flight = Arc::new(tokio::sync::Semaphore::new(COMMAND_WS_MAX_IN_FLIGHT));
+    let (tx, mut rx) = mpsc::channel::<(ws::Message, Option<tokio::sync::OwnedSemaphorePermit>)>(
+        COMMAND_WS_MAX_IN_FLIGHT,
+    );
+
+    let writer = tokio::spawn(async move {
+        while let Some((frame, _permit)) = rx.recv().await {
+            if ws_sender.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let ctx = Arc::new(ctx);
+    loop {
+        let permit = tokio::select! {
+            permit = in_flight.clone().acquire_owned() => {
+                permit.expect("command semaphore is never closed")
+            }
+            _ = kill.killed() => break,
+        };
+        let msg = tokio::select! {
+            msg = ws_receiver.next() => msg,
+            _ = kill.killed() => break,
+        };
+        let (encoding, body) = match msg {
+            Some(Ok(ws::Message::Text(text))) => (
+                Encoding::Json,
+                axum::body::Bytes::from(text.as_str().to_owned()),
+            ),
+            Some(Ok(ws::Message::Binary(data))) => (Encoding::MessagePack, data),
+            Some(Ok(ws::Message::Ping(data))) => {
+                if tx.send((ws::Message::Pong(data), None)).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            Some(Ok(ws::Message::Close(_))) | None => break,
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => {
+                warn!("WebSocket error for command client {}: {}", client_id, e);
+                break;
+            }
+        };
+
+        let state = state.clone();
+        let ctx = ctx.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let response = match encoding.decode::<Request>(&body) {
+                Ok(mut request) => {
+                    if request.request_id.is_empty() {
+                        request.request_id = uuid::Uuid::new_v4().to_string();
+                    }
+                    let request_id = request.request_id.clone();
+                    execute_command(&state, &ctx, request, CommandProtocol::WebSocket)
+                        .await
+                        .unwrap_or_else(|e| Response::failure(request_id, e.error_info()))
+                }
+                Err(e) => Response::failure(
+                    String::new(),
+                    SynapError::InvalidRequest(e.to_string()).error_info(),
+                ),
+            };
+            if let Some(frame) = command_frame(encoding, &response) {
+                let _ = tx.send((frame, Some(permit))).await;
+            }
+        });
+    }
+
+    writer.abort();
+    state.client_list_manager.remove(&client_id).await;
+    debug!("Command client {} disconnected", client_id);
+}
+
+/// A command response as a frame in its request's encoding
+fn command_frame(encoding: Encoding, response: &Response) -> Option<ws::Message> {
+    let body = match encoding.encode(response) {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to encode command response: {}", e);
+            return None;
+        }
+    };
+    Some(match encoding {
+        Encoding::Json => ws::Message::Text(String::from_utf8(body).ok()?.into()),
+        _ => ws::Message::Binary(body.into()),
+    })
+}
+
 // ============================================================================
 // Queue WebSocket Handler
 // ============================================================================
 
 /// WebSocket handler for Queue continuous consume (real-time message delivery)
 /// GET /queue/:name/ws/:consumer_id
+#[cfg(feature = "queues")]
 pub async fn queue_websocket(
     State(state): State<AppState>,
     Path((queue_name, consumer_id)): Path<(String, String)>,
@@ -105,6 +432,7 @@ pub async fn queue_websocket(
 }
 
 /// Handle Queue WebSocket connection
+#[cfg(feature = "queues")]
 pub(super) async fn handle_queue_socket(
     socket: WebSocket,
     queue_manager: Arc<crate::core::QueueManager>,
@@ -117,7 +445,7 @@ pub(super) async fn handle_queue_socket(
     let connected_at = std::time::SystemTime::now();
     let client_info =
         crate::monitoring::ClientInfo::new(client_id.clone(), client_addr, connected_at);
-    client_list_manager.add(client_info).await;
+    let kill = client_list_manager.add(client_info).await;
 
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
@@ -129,7 +457,7 @@ pub(super) async fn handle_queue_socket(
     });
 
     if ws_sender
-        .send(axum::extract::ws::Message::Text(welcome.to_string().into()))
+        .send(ws::Message::Text(welcome.to_string().into()))
         .await
         .is_err()
     {
@@ -147,14 +475,14 @@ pub(super) async fn handle_queue_socket(
                         let msg_json = json!({
                             "type": "message",
                             "message_id": msg.id,
-                            "payload": (*msg.payload).clone(),  // Clone Vec<u8> from Arc
+                            "payload": msg.payload,
                             "priority": msg.priority,
                             "retry_count": msg.retry_count,
                             "created_at": msg.created_at,
                             "headers": msg.headers
                         });
 
-                        if ws_sender.send(axum::extract::ws::Message::Text(msg_json.to_string().into())).await.is_err() {
+                        if ws_sender.send(ws::Message::Text(msg_json.to_string().into())).await.is_err() {
                             warn!("Failed to send message to consumer: {}", consumer_id);
                             break;
                         }
@@ -164,7 +492,7 @@ pub(super) async fn handle_queue_socket(
                     }
                     Err(e) => {
                          error!("Queue consume error: {}", e);
-                        let _ = ws_sender.send(axum::extract::ws::Message::Text(
+                        let _ = ws_sender.send(ws::Message::Text(
                             json!({"type": "error", "error": e.to_string()}).to_string().into()
                         )).await;
                         break;
@@ -172,10 +500,15 @@ pub(super) async fn handle_queue_socket(
                 }
             }
 
+            _ = kill.killed() => {
+                info!("Queue consumer {} killed", consumer_id);
+                break;
+            }
+
             // Handle incoming WebSocket messages (ACK/NACK commands)
             Some(msg) = ws_receiver.next() => {
                 match msg {
-                    Ok(axum::extract::ws::Message::Text(text)) => {
+                    Ok(ws::Message::Text(text)) => {
                         if let Ok(cmd) = serde_json::from_str::<serde_json::Value>(&text) {
                             match cmd["command"].as_str() {
                                 Some("ack") => {
@@ -198,12 +531,12 @@ pub(super) async fn handle_queue_socket(
                             }
                         }
                     }
-                    Ok(axum::extract::ws::Message::Close(_)) => {
+                    Ok(ws::Message::Close(_)) => {
                         info!("Queue consumer {} closed connection", consumer_id);
                         break;
                     }
-                    Ok(axum::extract::ws::Message::Ping(data)) => {
-                        if ws_sender.send(axum::extract::ws::Message::Pong(data)).await.is_err() {
+                    Ok(ws::Message::Ping(data)) => {
+                        if ws_sender.send(ws::Message::Pong(data)).await.is_err() {
                             break;
                         }
                     }
@@ -232,6 +565,7 @@ pub(super) async fn handle_queue_socket(
 
 /// WebSocket handler for Event Streams (real-time event push)
 /// GET /stream/:room/ws/:subscriber_id?from_offset=0
+#[cfg(feature = "streams")]
 pub async fn stream_websocket(
     State(state): State<AppState>,
     Path((room_name, subscriber_id)): Path<(String, String)>,
@@ -250,9 +584,16 @@ pub async fn stream_websocket(
         }
     };
 
+    // Without an explicit offset a durable subscriber resumes where it left off
+    let subscriptions = state.subscriptions().cloned();
     let from_offset = params
         .get("from_offset")
         .and_then(|s| s.parse::<u64>().ok())
+        .or_else(|| {
+            subscriptions
+                .as_ref()
+                .and_then(|r| r.stream_offset(&room_name, &subscriber_id))
+        })
         .unwrap_or(0);
 
     info!(
@@ -269,9 +610,89 @@ pub async fn stream_websocket(
             socket,
             StreamSocketParams {
                 stream_manager,
-                room_name,
+                rooms: vec![(room_name, from_offset)],
+                subscriber_id,
+                subscriptions,
+                client_list_manager,
+                client_id,
+                client_addr,
+            },
+        )
+    })
+}
+
+/// WebSocket handler for several Event Stream rooms on one connection
+/// GET /stream/ws/:subscriber_id?rooms=room1:0,room2:12
+///
+/// Each room is `name[:offset]`; without an offset a durable subscriber resumes
+/// where it left off, anyone else starts at 0. Events from all rooms
+/// are pushed interleaved, each tagged with its `room`.
+#[cfg(feature = "streams")]
+pub async fn stream_websocket_many(
+    State(state): State<AppState>,
+    Path(subscriber_id): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+    axum::extract::ConnectInfo(addr): axum::extract::ConnectInfo<SocketAddr>,
+    ws: WebSocketUpgrade,
+) -> AxumResponse {
+    let stream_manager = match state.stream_manager.as_ref() {
+        Some(sm) => sm.clone(),
+        None => {
+            return (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                "Stream system disabled",
+            )
+                .into_response();
+        }
+    };
+
+    let subscriptions = state.subscriptions().cloned();
+    let rooms: Vec<(String, u64)> = match params.get("rooms").map(|s| parse_room_offsets(s)) {
+        Some(Ok(rooms)) if !rooms.is_empty() => rooms
+            .into_iter()
+            .map(|(room, offset)| {
+                let offset = offset
+                    .or_else(|| {
+                        subscriptions
+                            .as_ref()
+                            .and_then(|r| r.stream_offset(&room, &subscriber_id))
+                    })
+                    .unwrap_or(0);
+                (room, offset)
+            })
+            .collect(),
+        Some(Err(e)) => return (axum::http::StatusCode::BAD_REQUEST, e).into_response(),
+        _ => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Missing 'rooms' parameter",
+            )
+                .into_response();
+        }
+    };
+
+    if let Some(response) = subscription_limit_rejection(&state, rooms.len()) {
+        return response;
+    }
+
+    info!(
+        "Stream WebSocket connection: {} rooms, subscriber={}",
+        rooms.len(),
+        subscriber_id
+    );
+
+    let client_list_manager = state.client_list_manager.clone();
+    let client_addr = addr.to_string();
+    let client_id = format!("stream-many-{}", subscriber_id);
+
+    ws.on_upgrade(move |socket| {
+        handle_stream_socket(
+            socket,
+            StreamSocketParams {
+                stream_manager,
+                rooms,
                 subscriber_id,
-                from_offset,
+                subscriptions,
                 client_list_manager,
                 client_id,
                 client_addr,
@@ -280,24 +701,43 @@ pub async fn stream_websocket(
     })
 }
 
+/// Parse `room1:0,room2` into room/offset pairs (`None` when no offset is given)
+#[cfg(feature = "streams")]
+fn parse_room_offsets(spec: &str) -> Result<Vec<(String, Option<u64>)>, String> {
+    spec.split(',')
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.rsplit_once(':') {
+            Some((room, offset)) => offset
+                .parse::<u64>()
+                .map(|offset| (room.to_string(), Some(offset)))
+                .map_err(|_| format!("Invalid offset in '{}'", part)),
+            None => Ok((part.to_string(), None)),
+        })
+        .collect()
+}
+
 /// Everything a stream WebSocket session needs besides the socket itself.
+#[cfg(feature = "streams")]
 pub(super) struct StreamSocketParams {
     pub stream_manager: Arc<crate::core::StreamManager>,
-    pub room_name: String,
+    /// Rooms to follow, each with the offset to start from
+    pub rooms: Vec<(String, u64)>,
     pub subscriber_id: String,
-    pub from_offset: u64,
+    /// Durable registry the session's progress is recorded in, if enabled
+    pub subscriptions: Option<Arc<crate::persistence::SubscriptionRegistry>>,
     pub client_list_manager: Arc<crate::monitoring::ClientListManager>,
     pub client_id: String,
     pub client_addr: String,
 }
 
 /// Handle Event Stream WebSocket connection
+#[cfg(feature = "streams")]
 pub(super) async fn handle_stream_socket(socket: WebSocket, params: StreamSocketParams) {
     let StreamSocketParams {
         stream_manager,
-        room_name,
+        mut rooms,
         subscriber_id,
-        from_offset: mut current_offset,
+        subscriptions,
         client_list_manager,
         client_id,
         client_addr,
@@ -305,20 +745,26 @@ pub(super) async fn handle_stream_socket(socket: WebSocket, params: StreamSocket
     let connected_at = std::time::SystemTime::now();
     let client_info =
         crate::monitoring::ClientInfo::new(client_id.clone(), client_addr, connected_at);
-    client_list_manager.add(client_info).await;
+    let kill = client_list_manager.add(client_info).await;
 
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
     // Send welcome message
-    let welcome = json!({
+    let mut welcome = json!({
         "type": "connected",
-        "room": room_name,
         "subscriber_id": subscriber_id,
-        "from_offset": current_offset
+        "rooms": rooms
+            .iter()
+            .map(|(room, offset)| json!({"room": room, "offset": offset}))
+            .collect::<Vec<_>>()
     });
+    if let [(room, offset)] = rooms.as_slice() {
+        welcome["room"] = json!(room);
+        welcome["from_offset"] = json!(offset);
+    }
 
     if ws_sender
-        .send(axum::extract::ws::Message::Text(welcome.to_string().into()))
+        .send(ws::Message::Text(welcome.to_string().into()))
         .await
         .is_err()
     {
@@ -333,7 +779,7 @@ pub(super) async fn handle_stream_socket(socket: WebSocket, params: StreamSocket
         tokio::select! {
             // Poll for new events (100ms interval)
             _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {
-                match stream_manager.consume(&room_name, &subscriber_id, current_offset, 100).await {
+                match stream_manager.consume_many(&subscriber_id, &rooms, 100).await {
                     Ok(events) => {
                         if !events.is_empty() {
                             for event in &events {
@@ -343,29 +789,35 @@ pub(super) async fn handle_stream_socket(socket: WebSocket, params: StreamSocket
 
                                 let event_json = json!({
                                     "type": "event",
+                                    "room": event.room,
                                     "offset": event.offset,
                                     "event": event.event,
                                     "data": data_json,
                                     "timestamp": event.timestamp
                                 });
 
-                                if ws_sender.send(axum::extract::ws::Message::Text(event_json.to_string().into())).await.is_err() {
+                                if ws_sender.send(ws::Message::Text(event_json.to_string().into())).await.is_err() {
                                     warn!("Failed to send event to subscriber: {}", subscriber_id);
                                     client_list_manager.remove(&client_id).await;
                                     return;
                                 }
                             }
 
-                            // Update offset to next expected event (events may be
-                            // empty, in which case the offset stays put).
-                            if let Some(last) = events.last() {
-                                current_offset = last.offset + 1;
+                            // Advance each room past the events it delivered;
+                            // rooms with nothing new stay put.
+                            for event in &events {
+                                if let Some((_, offset)) = rooms.iter_mut().find(|(room, _)| *room == event.room) {
+                                    *offset = event.offset + 1;
+                                }
+                            }
+                            if let Some(registry) = &subscriptions {
+                                record_stream_offsets(registry, &subscriber_id, &rooms, &events).await;
                             }
                         }
                     }
                     Err(e) => {
                         error!("Stream consume error: {}", e);
-                        let _ = ws_sender.send(axum::extract::ws::Message::Text(
+                        let _ = ws_sender.send(ws::Message::Text(
                             json!({"type": "error", "error": e}).to_string().into()
                         )).await;
                         break;
@@ -373,15 +825,20 @@ pub(super) async fn handle_stream_socket(socket: WebSocket, params: StreamSocket
                 }
             }
 
+            _ = kill.killed() => {
+                info!("Stream subscriber {} killed", subscriber_id);
+                break;
+            }
+
             // Handle incoming WebSocket messages (control messages)
             Some(msg) = ws_receiver.next() => {
                 match msg {
-                    Ok(axum::extract::ws::Message::Close(_)) => {
+                    Ok(ws::Message::Close(_)) => {
                         info!("Stream subscriber {} closed connection", subscriber_id);
                         break;
                     }
-                    Ok(axum::extract::ws::Message::Ping(data)) => {
-                        if ws_sender.send(axum::extract::ws::Message::Pong(data)).await.is_err() {
+                    Ok(ws::Message::Ping(data)) => {
+                        if ws_sender.send(ws::Message::Pong(data)).await.is_err() {
                             break;
                         }
                     }
@@ -402,8 +859,9 @@ pub(super) async fn handle_stream_socket(socket: WebSocket, params: StreamSocket
     // Cleanup: remove client from tracking
     client_list_manager.remove(&client_id).await;
     info!(
-        "Stream subscriber {} disconnected from room {}",
-        subscriber_id, room_name
+        "Stream subscriber {} disconnected from {} room(s)",
+        subscriber_id,
+        rooms.len()
     );
 }
 
@@ -412,7 +870,8 @@ pub(super) async fn handle_stream_socket(socket: WebSocket, params: StreamSocket
 // ============================================================================
 
 /// WebSocket handler for Pub/Sub subscriptions
-/// GET /pubsub/ws?topics=topic1,topic2,*.wildcard
+/// GET /pubsub/ws?topics=topic1,topic2,*.wildcard[&filter=<expression>]
+#[cfg(feature = "pubsub")]
 pub async fn pubsub_websocket(
     State(state): State<AppState>,
     ws: WebSocketUpgrade,
@@ -445,6 +904,19 @@ pub async fn pubsub_websocket(
         )
             .into_response();
     }
+    if let Some(response) = subscription_limit_rejection(&state, topics.len()) {
+        return response;
+    }
+
+    // Optional content filter, rejected before the upgrade when malformed
+    let filter = match params
+        .get("filter")
+        .map(|expression| crate::core::SubscriptionFilter::parse(expression))
+        .transpose()
+    {
+        Ok(filter) => filter,
+        Err(e) => return (axum::http::StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
 
     info!("WebSocket connection requested for topics: {:?}", topics);
 
@@ -456,6 +928,7 @@ pub async fn pubsub_websocket(
             socket,
             pubsub_router,
             topics,
+            filter,
             client_list_manager,
             client_addr,
         )
@@ -467,18 +940,21 @@ pub(super) async fn handle_pubsub_socket(
     socket: WebSocket,
     pubsub_router: Arc<crate::core::PubSubRouter>,
     topics: Vec<String>,
+    filter: Option<crate::core::SubscriptionFilter>,
     client_list_manager: Arc<crate::monitoring::ClientListManager>,
     client_addr: String,
 ) {
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
+    let filter_expression = filter.as_ref().map(|f| f.expression().to_string());
+
     // Subscribe to topics
-    let subscribe_result = match pubsub_router.subscribe(topics.clone()) {
+    let subscribe_result = match pubsub_router.subscribe_filtered(topics.clone(), filter) {
         Ok(result) => result,
         Err(e) => {
             error!("Failed to subscribe: {}", e);
             let _ = ws_sender
-                .send(axum::extract::ws::Message::Text(
+                .send(ws::Message::Text(
                     json!({
                         "error": e.to_string()
                     })
@@ -502,7 +978,7 @@ pub(super) async fn handle_pubsub_socket(
     // Track client connection
     let client_info =
         crate::monitoring::ClientInfo::new(client_id.clone(), client_addr, connected_at);
-    client_list_manager.add(client_info).await;
+    let kill = client_list_manager.add(client_info).await;
 
     // Create bounded channel for receiving messages (slow-consumer protection).
     let (tx, mut rx) = mpsc::channel::<Message>(crate::core::pubsub::SUBSCRIBER_CHANNEL_CAPACITY);
@@ -515,14 +991,13 @@ pub(super) async fn handle_pubsub_socket(
         "type": "connected",
         "subscriber_id": subscriber_id,
         "topics": topics,
-        "subscription_count": subscribe_result.subscription_count
+        "subscription_count": subscribe_result.subscription_count,
+        "filter": filter_expression
     });
 
     // Send welcome message
     if ws_sender
-        .send(axum::extract::ws::Message::Text(
-            welcome_msg.to_string().into(),
-        ))
+        .send(ws::Message::Text(welcome_msg.to_string().into()))
         .await
         .is_err()
     {
@@ -550,7 +1025,7 @@ pub(super) async fn handle_pubsub_socket(
                 .expect("a json! Value always serializes to a string");
 
                 if ws_sender
-                    .send(axum::extract::ws::Message::Text(msg_json.into()))
+                    .send(ws::Message::Text(msg_json.into()))
                     .await
                     .is_err()
                 {
@@ -559,15 +1034,20 @@ pub(super) async fn handle_pubsub_socket(
                 }
             }
 
+            _ = kill.killed() => {
+                info!("Subscriber {} killed", subscriber_id);
+                break;
+            }
+
             // Handle incoming WebSocket messages (keepalive/pings)
             Some(msg) = ws_receiver.next() => {
                 match msg {
-                    Ok(axum::extract::ws::Message::Close(_)) => {
+                    Ok(ws::Message::Close(_)) => {
                         info!("Subscriber {} closed connection", subscriber_id);
                         break;
                     }
-                    Ok(axum::extract::ws::Message::Ping(data)) => {
-                        if ws_sender.send(axum::extract::ws::Message::Pong(data)).await.is_err() {
+                    Ok(ws::Message::Ping(data)) => {
+                        if ws_sender.send(ws::Message::Pong(data)).await.is_err() {
                             break;
                         }
                     }
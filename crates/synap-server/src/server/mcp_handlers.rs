@@ -662,8 +662,7 @@ async fn handle_queue_publish(
     state: Arc<AppState>,
 ) -> Result<CallToolResult, ErrorData> {
     let queue_manager = state
-        .queue_manager
-        .as_ref()
+        .queue_manager()
         .ok_or_else(|| ErrorData::internal_error("Queue system disabled", None))?;
 
     let args = request
@@ -832,7 +831,10 @@ async fn handle_sortedset_zadd(
     let member_bytes = member.as_bytes().to_vec();
     let opts = crate::core::ZAddOptions::default();
 
-    let (added, _) = state.sorted_set_store.zadd(key, member_bytes, score, &opts);
+    let (added, _) = state
+        .sorted_set_store
+        .try_zadd(key, member_bytes, score, &opts)
+        .map_err(|e| ErrorData::internal_error(format!("ZADD failed: {}", e), None))?;
 
     Ok(CallToolResult::success(vec![ContentBlock::text(
         json!({"added": added > 0, "key": key, "member": member, "score": score}).to_string(),
@@ -30,7 +30,7 @@ pub fn get_mcp_tools(config: &McpConfig) -> Vec<Tool> {
     }
 
     // Queue Tools (1)
-    if config.enable_queue_tools {
+    if cfg!(feature = "queues") && config.enable_queue_tools {
         tools.extend(get_queue_tools());
     }
 
@@ -1,5 +1,6 @@
 //! Prometheus Metrics HTTP Handler
 
+#[cfg(feature = "streams")]
 use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
 
@@ -7,6 +8,10 @@ use axum::{extract::State, http::StatusCode, response::IntoResponse};
 
 use super::handlers::AppState;
 
+/// Hottest keys of the last hot key interval exported as gauge labels; kept
+/// small so key names do not blow up the series count.
+const HOT_KEYS_METRICS_TOP: usize = 10;
+
 /// GET /metrics - Prometheus metrics endpoint
 pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
     // Refresh system + broker gauges before encoding so the scrape reflects
@@ -53,6 +58,18 @@ pub fn init_metrics() {
     let _ = &*crate::metrics::KV_OP_DURATION;
     let _ = &*crate::metrics::KV_KEYS_TOTAL;
     let _ = &*crate::metrics::KV_MEMORY_BYTES;
+    let _ = &*crate::metrics::HASH_OPS_TOTAL;
+    let _ = &*crate::metrics::HASH_OP_DURATION;
+    let _ = &*crate::metrics::LIST_OPS_TOTAL;
+    let _ = &*crate::metrics::LIST_OP_DURATION;
+    let _ = &*crate::metrics::SET_OPS_TOTAL;
+    let _ = &*crate::metrics::SET_OP_DURATION;
+    let _ = &*crate::metrics::SORTED_SET_OPS_TOTAL;
+    let _ = &*crate::metrics::SORTED_SET_OP_DURATION;
+    let _ = &*crate::metrics::TRANSACTION_OPS_TOTAL;
+    let _ = &*crate::metrics::TRANSACTION_OP_DURATION;
+    let _ = &*crate::metrics::SCRIPT_OPS_TOTAL;
+    let _ = &*crate::metrics::SCRIPT_OP_DURATION;
     let _ = &*crate::metrics::QUEUE_OPS_TOTAL;
     let _ = &*crate::metrics::QUEUE_DEPTH;
     let _ = &*crate::metrics::QUEUE_OP_DURATION;
@@ -70,12 +87,17 @@ pub fn init_metrics() {
     let _ = &*crate::metrics::PUBSUB_OPS_TOTAL;
     let _ = &*crate::metrics::PUBSUB_MESSAGES_TOTAL;
     let _ = &*crate::metrics::PUBSUB_SUBSCRIPTIONS;
+    let _ = &*crate::metrics::PUBSUB_REJECTED_TOTAL;
     let _ = &*crate::metrics::REPL_LAG;
     let _ = &*crate::metrics::REPL_OPS_TOTAL;
     let _ = &*crate::metrics::REPL_BYTES_TOTAL;
+    let _ = &*crate::metrics::WAL_COMMIT_BATCH_SIZE;
+    let _ = &*crate::metrics::WAL_COMMITS_TOTAL;
+    let _ = &*crate::metrics::WAL_FSYNC_DURATION;
     let _ = &*crate::metrics::HTTP_REQUESTS_TOTAL;
     let _ = &*crate::metrics::HTTP_REQUEST_DURATION;
     let _ = &*crate::metrics::HTTP_CONNECTIONS;
+    let _ = &*crate::metrics::DEPRECATED_REQUESTS_TOTAL;
     let _ = &*crate::metrics::PROCESS_MEMORY_BYTES;
     let _ = &*crate::metrics::PROCESS_CPU_USAGE;
     let _ = &*crate::metrics::HOST_MEMORY_BYTES;
@@ -132,7 +154,7 @@ pub async fn update_broker_metrics(state: &AppState) {
     crate::metrics::reset_broker_gauges();
 
     // ── Streams / rooms: buffered length, last offset, subscribers ──
-    if let Some(sm) = &state.stream_manager {
+    if let Some(sm) = state.stream_manager() {
         for room in sm.list_rooms().await {
             if let Ok(s) = sm.room_stats(&room).await {
                 crate::metrics::set_stream_gauges(
@@ -147,7 +169,9 @@ pub async fn update_broker_metrics(state: &AppState) {
 
     // ── Partitioned topics: messages + end offset (high-water-mark) per
     //    partition. The end offset also anchors consumer-group lag below. ──
+    #[cfg(feature = "streams")]
     let mut partition_end: HashMap<(String, usize), u64> = HashMap::new();
+    #[cfg(feature = "streams")]
     if let Some(pm) = &state.partition_manager {
         for topic in pm.list_topics().await {
             if let Ok(parts) = pm.topic_stats(&topic).await {
@@ -167,6 +191,7 @@ pub async fn update_broker_metrics(state: &AppState) {
 
     // ── Consumer groups: members, committed offset, and lag =
     //    (last-published offset − committed offset), clamped at 0. ──
+    #[cfg(feature = "streams")]
     if let Some(cg) = &state.consumer_group_manager {
         for group_id in cg.list_groups().await {
             let Ok(stats) = cg.group_stats(&group_id).await else {
@@ -213,7 +238,7 @@ pub async fn update_broker_metrics(state: &AppState) {
     }
 
     // ── Queues: ready depth + dead-letter count ──
-    if let Some(qm) = &state.queue_manager
+    if let Some(qm) = state.queue_manager()
         && let Ok(queues) = qm.list_queues().await
     {
         for q in queues {
@@ -225,18 +250,49 @@ pub async fn update_broker_metrics(state: &AppState) {
 
     // ── Per-datatype memory toward the shared maxmemory budget (audit M-018).
     //    The sum is the total the eviction/refusal path uses. ──
-    crate::metrics::set_datatype_memory(
-        "kv",
-        state.kv_store.stats().await.total_memory_bytes.max(0),
+    let kv_stats = state.kv_store.stats().await;
+    crate::metrics::set_datatype_memory("kv", kv_stats.total_memory_bytes.max(0));
+    crate::metrics::sync_kv_eviction_counters(
+        state.kv_store.config().eviction_policy.as_str(),
+        kv_stats.evicted_keys,
+        kv_stats.evicted_bytes,
+        kv_stats.rejected_writes,
+    );
+    crate::metrics::sync_kv_defrag_counters(
+        kv_stats.defrag_passes,
+        kv_stats.defrag_reclaimed_bytes,
+        kv_stats.defrag_relocated_bytes,
     );
+    for shard in state.kv_store.shard_stats() {
+        crate::metrics::sync_kv_shard_metrics(
+            shard.shard,
+            shard.keys,
+            shard.reads,
+            shard.writes,
+            shard.contended,
+        );
+    }
+    if let Some(tracker) = state.kv_store.hot_keys() {
+        for key in tracker.report(HOT_KEYS_METRICS_TOP).previous {
+            crate::metrics::set_hot_key_gauges(&key.key, key.reads, key.writes);
+        }
+    }
+    for hook in state.monitoring.command_hooks().stats() {
+        crate::metrics::sync_command_hook_counters(
+            &hook.name,
+            hook.delivered,
+            hook.failed,
+            hook.dropped,
+        );
+    }
     crate::metrics::set_datatype_memory("hash", state.hash_store.memory_bytes() as i64);
     crate::metrics::set_datatype_memory("list", state.list_store.memory_bytes() as i64);
     crate::metrics::set_datatype_memory("set", state.set_store.memory_bytes() as i64);
     crate::metrics::set_datatype_memory("sorted_set", state.sorted_set_store.memory_bytes() as i64);
-    if let Some(sm) = &state.stream_manager {
+    if let Some(sm) = state.stream_manager() {
         crate::metrics::set_datatype_memory("stream", sm.memory_bytes() as i64);
     }
-    if let Some(qm) = &state.queue_manager {
+    if let Some(qm) = state.queue_manager() {
         crate::metrics::set_datatype_memory("queue", qm.memory_bytes() as i64);
     }
 }
@@ -1,4 +1,7 @@
+pub mod audit;
 pub mod auth_handlers;
+pub mod client_limits;
+pub mod deprecation;
 pub mod envelope;
 pub mod handlers;
 pub mod mcp_handlers;
@@ -6,12 +9,14 @@ pub mod mcp_server;
 pub mod mcp_tools;
 pub mod metrics_handler;
 pub mod rate_limit;
+pub mod request_id;
 pub mod router;
 pub mod umicp;
+pub mod ws;
 
 pub use handlers::AppState;
 pub use mcp_handlers::handle_mcp_tool;
 pub use mcp_server::SynapMcpService;
 pub use mcp_tools::get_mcp_tools;
 pub use metrics_handler::init_metrics;
-pub use router::create_router;
+pub use router::{create_router, create_router_with_compression};
@@ -2,17 +2,26 @@
 //!
 //! Token bucket rate limiting with:
 //! - Per-user rate limiting (Hub mode) - uses Plan-based limits
-//! - Per-IP rate limiting (standalone mode or fallback)
-//! - Configurable requests per second
-//! - Burst capacity
-
-use axum::{extract::ConnectInfo, http::StatusCode, middleware::Next, response::Response};
+//! - Per-identity budgets: per API key, per user, or per IP for anonymous
+//!   requests
+//! - Configurable tiers with their own rate and burst for listed callers
+//! - `X-RateLimit-*` response headers, and `Retry-After` on 429
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, State},
+    http::{HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
 use parking_lot::RwLock;
+use serde_json::json;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use crate::auth::AuthContext;
 use crate::config::RateLimitConfig;
 
 use crate::hub::{
@@ -31,6 +40,8 @@ pub struct RateLimitResult {
     pub remaining: u64,
     /// Time until bucket refills
     pub reset_in: Duration,
+    /// Time until the next request would be allowed (zero when allowed)
+    pub retry_after: Duration,
 }
 
 /// Token bucket for rate limiting
@@ -69,35 +80,117 @@ impl TokenBucket {
             false
         }
     }
+
+    /// Time until the bucket holds `tokens` again
+    fn time_until(&self, tokens: f64) -> Duration {
+        let missing = (tokens - self.tokens).max(0.0);
+        if missing == 0.0 || self.refill_rate <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(missing / self.refill_rate)
+    }
+
+    fn result(&self, allowed: bool, limit: u64) -> RateLimitResult {
+        RateLimitResult {
+            allowed,
+            limit,
+            remaining: self.tokens.floor() as u64,
+            reset_in: self.time_until(self.capacity),
+            retry_after: if allowed {
+                Duration::ZERO
+            } else {
+                self.time_until(1.0)
+            },
+        }
+    }
 }
 
 /// Rate limiter state
 pub struct RateLimiter {
-    /// Buckets keyed by: "user:{user_id}" or "ip:{ip_address}"
+    /// Buckets keyed by: "user:{user_id}", "api_key:{key_id}" or "ip:{ip_address}"
     buckets: Arc<RwLock<HashMap<String, TokenBucket>>>,
-    config: RateLimitConfig,
+    config: Arc<RwLock<RateLimitConfig>>,
 }
 
 impl RateLimiter {
     pub fn new(config: RateLimitConfig) -> Self {
         Self {
             buckets: Arc::new(RwLock::new(HashMap::new())),
-            config,
+            config: Arc::new(RwLock::new(config)),
         }
     }
 
+    /// Global rate and burst
+    pub fn limits(&self) -> (u64, u64) {
+        let config = self.config.read();
+        (config.requests_per_second, config.burst_size)
+    }
+
+    /// Change the global rate and burst. Every budget starts over full;
+    /// tiers keep their own limits.
+    pub fn set_limits(&self, requests_per_second: u64, burst_size: u64) {
+        {
+            let mut config = self.config.write();
+            config.requests_per_second = requests_per_second;
+            config.burst_size = burst_size;
+        }
+        self.buckets.write().clear();
+    }
+
     /// Check rate limit for IP address (standalone mode or fallback)
     pub fn check_rate_limit(&self, ip: &str) -> bool {
         let mut buckets = self.buckets.write();
 
         let key = format!("ip:{}", ip);
-        let bucket = buckets.entry(key).or_insert_with(|| {
-            TokenBucket::new(self.config.burst_size, self.config.requests_per_second)
-        });
+        let (requests_per_second, burst_size) = self.limits();
+        let bucket = buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(burst_size, requests_per_second));
 
         bucket.try_consume(1.0)
     }
 
+    /// Check rate limit for the caller of an authenticated request
+    ///
+    /// API keys and users each get their own budget; anonymous requests are
+    /// limited per IP. The first configured tier listing the caller sets the
+    /// rate and burst, otherwise the global ones apply.
+    pub fn check_identity_rate_limit(&self, ctx: &AuthContext) -> RateLimitResult {
+        let key = if let Some(ref key_id) = ctx.api_key_id {
+            format!("api_key:{}", key_id)
+        } else if let Some(ref user_id) = ctx.user_id {
+            format!("user:{}", user_id)
+        } else {
+            format!("ip:{}", ctx.client_ip)
+        };
+
+        let config = self.config.read();
+        let (requests_per_second, burst_size) = config
+            .tiers
+            .iter()
+            .find(|tier| {
+                ctx.api_key_id
+                    .as_ref()
+                    .is_some_and(|id| tier.api_keys.contains(id))
+                    || ctx
+                        .user_id
+                        .as_ref()
+                        .is_some_and(|user| tier.users.contains(user))
+                    || tier.ips.contains(&ctx.client_ip)
+            })
+            .map(|tier| (tier.requests_per_second, tier.burst_size))
+            .unwrap_or((config.requests_per_second, config.burst_size));
+        drop(config);
+
+        let mut buckets = self.buckets.write();
+        let bucket = buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(burst_size, requests_per_second));
+
+        let allowed = bucket.try_consume(1.0);
+        bucket.result(allowed, requests_per_second)
+    }
+
     /// Check rate limit for authenticated user (Hub mode)
     ///
     /// Uses Plan-based limits from HubSaaSRestrictions
@@ -115,13 +208,7 @@ impl RateLimiter {
             .or_insert_with(|| TokenBucket::new(burst_size, requests_per_second));
 
         let allowed = bucket.try_consume(1.0);
-
-        RateLimitResult {
-            allowed,
-            limit: requests_per_second,
-            remaining: bucket.tokens.floor() as u64,
-            reset_in: Duration::from_secs(1), // Tokens refill every second
-        }
+        bucket.result(allowed, requests_per_second)
     }
 
     /// Cleanup old entries periodically
@@ -132,73 +219,94 @@ impl RateLimiter {
             bucket.last_refill.elapsed() < Duration::from_secs(300)
         });
     }
+
+    /// Run [`cleanup`](Self::cleanup) every minute for as long as the
+    /// limiter is in use
+    pub fn start_cleanup_task(self: &Arc<Self>) {
+        let limiter = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                let Some(limiter) = limiter.upgrade() else {
+                    break;
+                };
+                limiter.cleanup();
+            }
+        });
+    }
 }
 
 impl Clone for RateLimiter {
     fn clone(&self) -> Self {
         Self {
             buckets: Arc::clone(&self.buckets),
-            config: self.config.clone(),
+            config: Arc::clone(&self.config),
         }
     }
 }
 
 /// Rate limit middleware
 ///
-/// Uses per-user rate limiting in Hub mode (with Plan-based limits)
-/// Falls back to IP-based rate limiting in standalone mode
+/// Uses per-user rate limiting in Hub mode (with Plan-based limits).
+/// Otherwise limits the caller identified by the auth middleware, so it must
+/// run inside it; requests without an [`AuthContext`] fall back to their IP.
 pub async fn rate_limit_middleware(
-    limiter: Arc<RateLimiter>,
-    mut request: axum::extract::Request,
+    State(limiter): State<Arc<RateLimiter>>,
+    request: axum::extract::Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    {
-        // Try to get Hub user context
-        if let Some(hub_ctx) = request.extensions().get::<HubUserContext>().cloned() {
-            // Use per-user rate limiting with Plan-based limits
-            let user_id = hub_ctx.user_id.to_string();
-            let result = limiter.check_user_rate_limit(&user_id, hub_ctx.plan);
-
-            if !result.allowed {
-                tracing::warn!(
-                    "Rate limit exceeded for user {}: {} req/s limit ({:?} plan)",
-                    hub_ctx.user_id,
-                    result.limit,
-                    hub_ctx.plan
-                );
-                return Err(StatusCode::TOO_MANY_REQUESTS);
-            }
-
-            // Store rate limit info in request extensions for response headers
-            request.extensions_mut().insert(result);
-
-            let mut response = next.run(request).await;
-
-            // Add rate limit headers to response (Task 5.4)
-            add_rate_limit_headers(&mut response, &limiter, Some(&user_id), Some(hub_ctx.plan));
-
-            return Ok(response);
+) -> Response {
+    let result = if let Some(hub_ctx) = request.extensions().get::<HubUserContext>() {
+        // Use per-user rate limiting with Plan-based limits
+        let result = limiter.check_user_rate_limit(&hub_ctx.user_id.to_string(), hub_ctx.plan);
+        if !result.allowed {
+            tracing::warn!(
+                "Rate limit exceeded for user {}: {} req/s limit ({:?} plan)",
+                hub_ctx.user_id,
+                result.limit,
+                hub_ctx.plan
+            );
         }
-    }
-
-    // Fallback to IP-based rate limiting (standalone mode or no Hub context)
-    let ip = request
-        .extensions()
-        .get::<ConnectInfo<SocketAddr>>()
-        .map(|ConnectInfo(addr)| addr.ip().to_string())
-        .unwrap_or_else(|| "unknown".to_string());
-
-    if !limiter.check_rate_limit(&ip) {
-        tracing::warn!("Rate limit exceeded for IP: {}", ip);
-        return Err(StatusCode::TOO_MANY_REQUESTS);
-    }
-
-    let mut response = next.run(request).await;
-
-    // Add basic rate limit headers for IP-based limiting
-    add_rate_limit_headers(&mut response, &limiter, None, None);
-
-    Ok(response)
+        result
+    } else {
+        let ctx = request.extensions().get::<AuthContext>().cloned();
+        let ctx = ctx.unwrap_or_else(|| {
+            let ip = request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip())
+                .unwrap_or(std::net::Ipv4Addr::UNSPECIFIED.into());
+            AuthContext::anonymous(ip)
+        });
+        let result = limiter.check_identity_rate_limit(&ctx);
+        if !result.allowed {
+            tracing::warn!(
+                "Rate limit exceeded for {}: {} req/s limit",
+                ctx.api_key_id
+                    .as_deref()
+                    .or(ctx.user_id.as_deref())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| ctx.client_ip.to_string()),
+                result.limit
+            );
+        }
+        result
+    };
+
+    let mut response = if result.allowed {
+        next.run(request).await
+    } else {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({
+                "error": "Rate limit exceeded",
+                "code": StatusCode::TOO_MANY_REQUESTS.as_u16(),
+            })),
+        )
+            .into_response()
+    };
+    add_rate_limit_headers(&mut response, &result);
+    response
 }
 
 /// Add rate limit headers to response (Task 5.4)
@@ -206,57 +314,26 @@ pub async fn rate_limit_middleware(
 /// Headers follow standard conventions:
 /// - X-RateLimit-Limit: Requests per second allowed
 /// - X-RateLimit-Remaining: Remaining requests in current window
-/// - X-RateLimit-Reset: Seconds until rate limit resets
-fn add_rate_limit_headers(
-    response: &mut Response,
-    limiter: &RateLimiter,
-    user_id: Option<&str>,
-    plan: Option<Plan>,
-) {
-    if let (Some(uid), Some(p)) = (user_id, plan) {
-        // Get plan-specific limits
-        let requests_per_second = HubSaaSRestrictions::max_requests_per_second(p) as u64;
-
-        // Get current bucket state
-        let buckets = limiter.buckets.read();
-        let key = format!("user:{}", uid);
-
-        if let Some(bucket) = buckets.get(&key) {
-            let remaining = bucket.tokens.floor() as u64;
-
-            response.headers_mut().insert(
-                "X-RateLimit-Limit",
-                requests_per_second
-                    .to_string()
-                    .parse()
-                    .expect("a numeric string is a valid header value"),
-            );
-            response.headers_mut().insert(
-                "X-RateLimit-Remaining",
-                remaining
-                    .to_string()
-                    .parse()
-                    .expect("a numeric string is a valid header value"),
-            );
-            response.headers_mut().insert(
-                "X-RateLimit-Reset",
-                "1".parse().expect("\"1\" is a valid header value"), // Refills every 1 second
-            );
-
-            return;
-        }
-    }
-
-    // Fallback: Add global config-based headers
-    response.headers_mut().insert(
-        "X-RateLimit-Limit",
-        limiter
-            .config
-            .requests_per_second
-            .to_string()
-            .parse()
-            .expect("a numeric string is a valid header value"),
+/// - X-RateLimit-Reset: Seconds until the bucket is full again
+/// - Retry-After: Seconds to wait before retrying (429 only)
+fn add_rate_limit_headers(response: &mut Response, result: &RateLimitResult) {
+    let headers = response.headers_mut();
+    let mut insert = |name: &'static str, value: u64| {
+        headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+    };
+    insert("x-ratelimit-limit", result.limit);
+    insert("x-ratelimit-remaining", result.remaining);
+    insert(
+        "x-ratelimit-reset",
+        result.reset_in.as_secs_f64().ceil() as u64,
     );
+    if !result.allowed {
+        // Whole seconds, rounded up so a retry at that time succeeds
+        insert(
+            "retry-after",
+            result.retry_after.as_secs_f64().ceil().max(1.0) as u64,
+        );
+    }
 }
 
 #[cfg(test)]
@@ -285,6 +362,7 @@ mod tests {
             enabled: true,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         };
 
         let limiter = RateLimiter::new(config);
@@ -307,6 +385,7 @@ mod tests {
             enabled: true,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         };
 
         let limiter = RateLimiter::new(config);
@@ -327,6 +406,7 @@ mod tests {
             enabled: true,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         };
 
         let limiter = RateLimiter::new(config);
@@ -356,6 +436,7 @@ mod tests {
             enabled: true,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         };
 
         let limiter = RateLimiter::new(config);
@@ -370,4 +451,48 @@ mod tests {
         let result_enterprise = limiter.check_user_rate_limit("ent-user", Plan::Enterprise);
         assert_eq!(result_enterprise.limit, 1000); // Enterprise: 1000 req/s
     }
+
+    #[test]
+    fn test_identity_budgets_and_tiers() {
+        let config = RateLimitConfig {
+            enabled: true,
+            requests_per_second: 1,
+            burst_size: 2,
+            tiers: vec![crate::config::RateLimitTier {
+                name: "premium".to_string(),
+                requests_per_second: 100,
+                burst_size: 5,
+                api_keys: vec![],
+                users: vec!["alice".to_string()],
+                ips: vec![],
+            }],
+        };
+        let limiter = RateLimiter::new(config);
+        let ip = "10.0.0.1".parse().unwrap();
+
+        let mut bob = AuthContext::anonymous(ip);
+        bob.user_id = Some("bob".to_string());
+        let mut alice = AuthContext::anonymous(ip);
+        alice.user_id = Some("alice".to_string());
+        let mut key = AuthContext::anonymous(ip);
+        key.api_key_id = Some("key-1".to_string());
+        key.user_id = Some("bob".to_string());
+
+        // Global budget for bob, exhausted after the burst
+        assert_eq!(limiter.check_identity_rate_limit(&bob).remaining, 1);
+        assert!(limiter.check_identity_rate_limit(&bob).allowed);
+        let denied = limiter.check_identity_rate_limit(&bob);
+        assert!(!denied.allowed);
+        assert!(denied.retry_after > Duration::ZERO);
+
+        // Same IP, other identities: their own budgets
+        let premium = limiter.check_identity_rate_limit(&alice);
+        assert_eq!((premium.limit, premium.remaining), (100, 4));
+        assert!(limiter.check_identity_rate_limit(&key).allowed);
+        assert!(
+            limiter
+                .check_identity_rate_limit(&AuthContext::anonymous(ip))
+                .allowed
+        );
+    }
 }
@@ -0,0 +1,115 @@
+//! Per-request correlation ids (`X-Request-Id`).
+//!
+//! Every HTTP request gets an id: the caller's `X-Request-Id` header when it is
+//! well-formed, otherwise a fresh UUID. The id is:
+//!
+//! - echoed back in the `X-Request-Id` response header,
+//! - recorded as the `request_id` field of the request's tracing span, so every
+//!   log line emitted while handling it carries the id,
+//! - exposed to the rest of the server through a task-local ([`current`]), which
+//!   is how the WAL, the replication stream and the slowlog pick it up without
+//!   threading it through every handler signature.
+
+use axum::extract::Request;
+use axum::http::{HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::Span;
+
+/// Header carrying the request id, in both directions.
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest caller-supplied id we accept; anything longer is replaced.
+const MAX_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT: String;
+}
+
+/// Request id of the request being handled by the current task, if any.
+///
+/// Returns `None` outside an HTTP request (RESP3, SynapRPC, background tasks,
+/// recovery), so callers record the id only when there is one to record.
+pub fn current() -> Option<String> {
+    CURRENT.try_with(|id| id.clone()).ok()
+}
+
+/// Run `fut` with `id` as the current request id.
+pub async fn scope<F: Future>(id: String, fut: F) -> F::Output {
+    CURRENT.scope(id, fut).await
+}
+
+/// Accept a caller-supplied id only if it is short, non-empty printable ASCII.
+/// The id ends up in logs, WAL entries and replication frames, so arbitrary
+/// client bytes are not passed through.
+fn is_valid(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// The caller's `X-Request-Id`, or a freshly generated one.
+fn resolve(req: &Request) -> String {
+    req.headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| is_valid(id))
+        .map(str::to_owned)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Middleware: resolve the id, expose it to the handler (header + task-local)
+/// and return it in the response.
+pub async fn propagate(mut req: Request, next: Next) -> Response {
+    let id = resolve(&req);
+    // Validated ASCII or a UUID, so this cannot fail.
+    let value = HeaderValue::from_str(&id).expect("request id is a valid header value");
+    req.headers_mut()
+        .insert(REQUEST_ID_HEADER.clone(), value.clone());
+
+    let mut response = scope(id, next.run(req)).await;
+    response
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER.clone(), value);
+    response
+}
+
+/// `TraceLayer` span factory that tags the request span with its id and,
+/// with the `otel` feature, continues the caller's `traceparent` trace.
+///
+/// Runs inside [`propagate`], so the header is always present by then.
+pub fn make_span<B>(req: &axum::http::Request<B>) -> Span {
+    let id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let span = tracing::info_span!(
+        "request",
+        method = %req.method(),
+        uri = %req.uri(),
+        request_id = %id,
+    );
+    crate::telemetry::set_parent(&span, req.headers());
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid() {
+        assert!(is_valid("req-42"));
+        assert!(is_valid(&uuid::Uuid::new_v4().to_string()));
+        assert!(!is_valid(""));
+        assert!(!is_valid("has space"));
+        assert!(!is_valid(&"a".repeat(MAX_LEN + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_current_is_scoped() {
+        assert_eq!(current(), None);
+        let seen = scope("abc".to_string(), async { current() }).await;
+        assert_eq!(seen.as_deref(), Some("abc"));
+        assert_eq!(current(), None);
+    }
+}
@@ -1,14 +1,24 @@
+use super::audit;
 use super::auth_handlers;
+use super::deprecation;
 use super::handlers::{self, AppState};
 use super::mcp_server::SynapMcpService;
-use crate::auth::{ApiKeyManager, AuthMiddleware, UserManager};
+use super::rate_limit;
+use super::request_id;
+use super::ws::WsCompression;
+use crate::auth::{ApiKeyManager, AuditLogEntry, AuthMiddleware, UserManager};
+use crate::config::HttpCompressionConfig;
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
     routing::{delete, get, post},
 };
 use std::sync::Arc;
 use tower_http::{
-    compression::CompressionLayer,
+    compression::{
+        CompressionLayer,
+        predicate::{NotForContentType, Predicate, SizeAbove},
+    },
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
@@ -17,11 +27,16 @@ use tracing::debug;
 /// Create the Axum router with all endpoints
 /// Paths that must answer without credentials.
 ///
-/// A liveness probe that requires authentication is not a liveness probe: the
+/// A health probe that requires authentication is not a health probe: the
 /// container HEALTHCHECK, Kubernetes, and any load balancer all probe
 /// unauthenticated. `/metrics` is the same contract — a scraper is not a user.
+/// Login and token refresh are how a client obtains credentials, and check
+/// the ones in their body themselves.
 fn is_public_path(path: &str) -> bool {
-    matches!(path, "/health" | "/metrics")
+    matches!(
+        path,
+        "/health" | "/health/live" | "/health/ready" | "/metrics" | "/auth/login" | "/auth/refresh"
+    )
 }
 
 pub fn create_router(
@@ -32,17 +47,48 @@ pub fn create_router(
     api_key_manager: Arc<ApiKeyManager>,
     auth_enabled: bool,
     require_auth: bool,
+) -> Router {
+    create_router_with_compression(
+        state,
+        rate_limit_config,
+        mcp_config,
+        user_manager,
+        api_key_manager,
+        auth_enabled,
+        require_auth,
+        &HttpCompressionConfig::default(),
+    )
+}
+
+/// [`create_router`] with explicit HTTP response compression settings
+#[allow(clippy::too_many_arguments)]
+pub fn create_router_with_compression(
+    state: AppState,
+    rate_limit_config: crate::config::RateLimitConfig,
+    mcp_config: crate::config::McpConfig,
+    user_manager: Arc<UserManager>,
+    api_key_manager: Arc<ApiKeyManager>,
+    auth_enabled: bool,
+    require_auth: bool,
+    compression: &HttpCompressionConfig,
 ) -> Router {
     // CORS configuration
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([
+            request_id::REQUEST_ID_HEADER.clone(),
+            deprecation::DEPRECATION_HEADER.clone(),
+            deprecation::SUNSET_HEADER.clone(),
+            axum::http::header::WARNING,
+        ]);
 
     // Create auth state for auth handlers
     let auth_state = auth_handlers::AuthState {
         user_manager: user_manager.clone(),
         api_key_manager: api_key_manager.clone(),
+        audit_log: state.monitoring.audit_log(),
     };
 
     // Create authentication middleware
@@ -74,6 +120,8 @@ pub fn create_router(
     let auth_router = Router::new()
         // Authentication endpoints
         .route("/auth/login", post(auth_handlers::auth_login))
+        .route("/auth/refresh", post(auth_handlers::auth_refresh))
+        .route("/auth/logout", post(auth_handlers::auth_logout))
         .route("/auth/me", get(auth_handlers::auth_me))
         // API key management
         .route("/auth/keys", post(auth_handlers::auth_create_key))
@@ -115,10 +163,15 @@ pub fn create_router(
     let api_router = Router::new()
         // Health check (always public)
         .route("/health", get(handlers::health_check))
+        // Liveness probe (always public)
+        .route("/health/live", get(handlers::health_check))
+        // Readiness probe with dependency checks (always public)
+        .route("/health/ready", get(handlers::readiness_check))
         // Prometheus metrics (always public)
         .route("/metrics", get(super::metrics_handler::metrics_handler))
         // KV endpoints
         .route("/kv/ws", get(handlers::kv_websocket)) // WebSocket for WATCH
+        .route("/kv/tracking/ws", get(handlers::kv_tracking_websocket)) // Client-side caching
         .route("/kv/set", post(handlers::kv_set))
         .route("/kv/get/{key}", get(handlers::kv_get))
         .route("/kv/del/{key}", delete(handlers::kv_delete))
@@ -139,9 +192,57 @@ pub fn create_router(
         .route("/key/randomkey", get(handlers::key_randomkey))
         // Monitoring endpoints
         .route("/info", get(handlers::info))
-        .route("/slowlog", get(handlers::slowlog))
+        .route(
+            "/slowlog",
+            get(handlers::slowlog).delete(handlers::slowlog_reset),
+        )
+        .route(
+            "/slowlog/config",
+            get(handlers::slowlog_config).put(handlers::slowlog_config_set),
+        )
+        // Runtime configuration (CONFIG GET / SET)
+        .route(
+            "/config",
+            get(handlers::config_get).put(handlers::config_set),
+        )
+        .route(
+            "/latency",
+            get(handlers::latency_latest).delete(handlers::latency_reset),
+        )
+        .route(
+            "/latency/{event}",
+            get(handlers::latency_history).delete(handlers::latency_reset_event),
+        )
+        .route(
+            "/deprecations",
+            get(handlers::deprecations_report).delete(handlers::deprecations_reset),
+        )
+        .route("/audit", get(handlers::audit_log))
+        .route(
+            "/memory/eviction-simulation",
+            get(handlers::eviction_simulation),
+        )
+        .route("/memory/doctor", get(handlers::memory_doctor))
         .route("/memory/{key}/usage", get(handlers::memory_usage))
+        .route("/monitoring/hotkeys", get(handlers::hot_keys))
+        .route("/quotas", get(handlers::namespace_quotas))
         .route("/clients", get(handlers::client_list))
+        .route("/clients/kill", post(handlers::client_kill))
+        .route("/clients/pause", post(handlers::client_pause))
+        .route("/clients/unpause", post(handlers::client_unpause))
+        .route("/warmup", post(handlers::warmup_run))
+        .route("/subscriptions", get(handlers::subscriptions_list))
+        // Cron schedules publishing to queues, topics and stream rooms
+        .route(
+            "/schedules",
+            get(handlers::schedule_list).post(handlers::schedule_create),
+        )
+        .route(
+            "/schedules/{name}",
+            get(handlers::schedule_get).delete(handlers::schedule_delete),
+        )
+        // Delivery timeline of a queue message or stream event
+        .route("/messages/{id}/trace", get(handlers::message_trace))
         // Transaction endpoints
         .route("/transaction/multi", post(handlers::transaction_multi))
         .route("/transaction/exec", post(handlers::transaction_exec))
@@ -175,6 +276,10 @@ pub fn create_router(
         .route("/set/inter", post(handlers::set_inter))
         .route("/set/union", post(handlers::set_union))
         .route("/set/diff", post(handlers::set_diff))
+        .route("/set/interstore", post(handlers::set_interstore))
+        .route("/set/unionstore", post(handlers::set_unionstore))
+        .route("/set/diffstore", post(handlers::set_diffstore))
+        .route("/set/intercard", post(handlers::set_intercard))
         .route("/set/stats", get(handlers::set_stats))
         // Sorted Set endpoints
         .route("/sortedset/{key}/zadd", post(handlers::sortedset_zadd))
@@ -197,6 +302,9 @@ pub fn create_router(
             "/sortedset/{key}/{member}/zrank",
             get(handlers::sortedset_zrank),
         )
+        .route("/sortedset/zinter", post(handlers::sortedset_zinter))
+        .route("/sortedset/zunion", post(handlers::sortedset_zunion))
+        .route("/sortedset/zdiff", post(handlers::sortedset_zdiff))
         .route(
             "/sortedset/zinterstore",
             post(handlers::sortedset_zinterstore),
@@ -239,13 +347,12 @@ pub fn create_router(
             post(handlers::sortedset_zremrangebyscore),
         )
         .route("/sortedset/stats", get(handlers::sortedset_stats))
-        // Lua scripting endpoints
-        .route("/script/eval", post(handlers::script_eval))
-        .route("/script/evalsha", post(handlers::script_evalsha))
-        .route("/script/load", post(handlers::script_load))
-        .route("/script/exists", post(handlers::script_exists))
-        .route("/script/flush", post(handlers::script_flush))
-        .route("/script/kill", post(handlers::script_kill))
+        .route(
+            "/sortedset/{key}/notify",
+            get(handlers::sortedset_notify_get)
+                .put(handlers::sortedset_notify_set)
+                .delete(handlers::sortedset_notify_del),
+        )
         // List endpoints
         .route("/list/{key}/lpush", post(handlers::list_lpush))
         .route("/list/{key}/lpushx", post(handlers::list_lpushx))
@@ -322,129 +429,50 @@ pub fn create_router(
         .route("/geospatial/stats", get(handlers::geospatial_stats))
         // Persistence endpoints
         .route("/snapshot", post(handlers::trigger_snapshot))
-        // Event Stream endpoints
-        .route(
-            "/stream/{room}/ws/{subscriber_id}",
-            get(handlers::stream_websocket),
-        ) // WebSocket for real-time push
+        .route("/snapshot/export", get(handlers::export_snapshot))
+        // Dumps routinely exceed the default 2 MB request body limit
         .route(
-            "/stream/{room}",
-            post(handlers::stream_create_room)
-                .put(handlers::stream_get_or_create_room)
-                .delete(handlers::stream_delete_room),
-        )
-        .route("/stream/{room}/publish", post(handlers::stream_publish))
-        .route(
-            "/stream/{room}/consume/{subscriber_id}",
-            get(handlers::stream_consume),
-        )
-        .route("/stream/{room}/stats", get(handlers::stream_room_stats))
-        .route("/stream/list", get(handlers::stream_list_rooms))
-        // Queue endpoints
-        .route(
-            "/queue/{name}/ws/{consumer_id}",
-            get(handlers::queue_websocket),
-        ) // WebSocket for continuous consume
-        .route("/queue/{name}", post(handlers::queue_create))
-        .route("/queue/{name}/publish", post(handlers::queue_publish))
-        .route(
-            "/queue/{name}/consume/{consumer_id}",
-            get(handlers::queue_consume),
-        )
-        .route("/queue/{name}/ack", post(handlers::queue_ack))
-        .route("/queue/{name}/nack", post(handlers::queue_nack))
-        .route("/queue/{name}/stats", get(handlers::queue_stats))
-        .route("/queue/{name}/purge", post(handlers::queue_purge))
-        .route("/queue/{name}", delete(handlers::queue_delete))
-        .route("/queue/list", get(handlers::queue_list))
-        // Pub/Sub endpoints
-        .route("/pubsub/ws", get(handlers::pubsub_websocket)) // WebSocket for subscriptions
-        .route("/pubsub/subscribe", post(handlers::pubsub_subscribe)) // Legacy REST (deprecated)
-        .route("/pubsub/{topic}/publish", post(handlers::pubsub_publish))
-        .route("/pubsub/unsubscribe", post(handlers::pubsub_unsubscribe))
-        .route("/pubsub/stats", get(handlers::pubsub_stats))
-        .route("/pubsub/topics", get(handlers::pubsub_list_topics))
-        .route("/pubsub/{topic}/info", get(handlers::pubsub_topic_info))
-        // Partitioned Stream endpoints (Kafka-style)
-        .route("/topics", get(handlers::list_topics))
-        .route("/topics/{topic}", post(handlers::create_partitioned_topic))
-        .route("/topics/{topic}", delete(handlers::delete_topic))
-        .route("/topics/{topic}/stats", get(handlers::get_topic_stats))
-        .route(
-            "/topics/{topic}/publish",
-            post(handlers::publish_to_partition),
-        )
-        .route(
-            "/topics/{topic}/partitions/{partition_id}/consume",
-            post(handlers::consume_from_partition),
-        )
-        // Consumer Group endpoints
-        .route("/consumer-groups", get(handlers::list_consumer_groups))
-        .route(
-            "/consumer-groups/{group_id}",
-            post(handlers::create_consumer_group),
-        )
-        .route(
-            "/consumer-groups/{group_id}/join",
-            post(handlers::join_consumer_group),
-        )
-        .route(
-            "/consumer-groups/{group_id}/members/{member_id}/leave",
-            delete(handlers::leave_consumer_group),
-        )
-        .route(
-            "/consumer-groups/{group_id}/members/{member_id}/assignment",
-            get(handlers::get_partition_assignment),
-        )
-        .route(
-            "/consumer-groups/{group_id}/members/{member_id}/heartbeat",
-            post(handlers::consumer_heartbeat),
-        )
-        .route(
-            "/consumer-groups/{group_id}/offsets/commit",
-            post(handlers::commit_offset),
-        )
-        .route(
-            "/consumer-groups/{group_id}/offsets/{partition_id}",
-            get(handlers::get_committed_offset),
-        )
-        .route(
-            "/consumer-groups/{group_id}/stats",
-            get(handlers::get_consumer_group_stats),
+            "/snapshot/import",
+            post(handlers::import_snapshot).layer(DefaultBodyLimit::disable()),
         )
         // StreamableHTTP command endpoint
         .route("/api/v1/command", post(handlers::command_handler))
-        // Cluster management endpoints
-        .route("/cluster/info", get(handlers::cluster_info))
-        .route("/cluster/nodes", get(handlers::cluster_nodes))
-        .route("/cluster/nodes", post(handlers::cluster_add_node))
-        .route("/cluster/nodes/{node_id}", get(handlers::cluster_node_info))
+        .route("/api/v1/command/ws", get(handlers::command_websocket))
+        // Replication failover endpoints
+        .route("/replication/topology", get(handlers::replication_topology))
         .route(
-            "/cluster/nodes/{node_id}",
-            delete(handlers::cluster_remove_node),
-        )
-        .route("/cluster/slots", get(handlers::cluster_slots))
-        .route(
-            "/cluster/slots/assign",
-            post(handlers::cluster_assign_slots),
+            "/replication/failover/vote",
+            post(handlers::replication_failover_vote),
         )
         .route(
-            "/cluster/migration/start",
-            post(handlers::cluster_start_migration),
-        )
-        .route(
-            "/cluster/migration/complete",
-            post(handlers::cluster_complete_migration),
-        )
-        .route(
-            "/cluster/migration/{slot}",
-            get(handlers::cluster_migration_status),
+            "/replication/failover/announce",
+            post(handlers::replication_failover_announce),
         );
 
+    // Subsystems compiled out of a minimal build register no routes
+    #[cfg(feature = "scripting")]
+    let api_router = script_routes(api_router);
+    #[cfg(feature = "streams")]
+    let api_router = stream_routes(api_router);
+    #[cfg(feature = "queues")]
+    let api_router = queue_routes(api_router);
+    #[cfg(feature = "pubsub")]
+    let api_router = pubsub_routes(api_router);
+    #[cfg(feature = "cluster")]
+    let api_router = cluster_routes(api_router);
+    #[cfg(feature = "runtime-diagnostics")]
+    let api_router = api_router.route("/debug/runtime", get(handlers::debug_runtime));
+
     // HiveHub Integration endpoints (conditionally compiled)
 
     let api_router = api_router.route("/hub/quota", get(handlers::hub_quota_stats));
 
+    let deprecations = state.monitoring.deprecations();
+    let clients = state.client_list_manager.clone();
+    let runtime_config = state.monitoring.runtime_config();
+    let audit_log = state.monitoring.audit_log();
+    let command_hooks = state.monitoring.command_hooks();
+
     // Add state to API router
     let api_router = api_router.with_state(state);
 
@@ -454,12 +482,59 @@ pub fn create_router(
         .merge(auth_router) // Authentication endpoints
         .merge(api_router); // Main API endpoints
 
+    // Deprecation headers and per-client usage. Added before the auth layer so
+    // it runs inside it and sees who is calling.
+    router = router.layer(axum::middleware::from_fn_with_state(
+        deprecations,
+        deprecation::track,
+    ));
+
+    // Audited REST writes (`auth.audit.log_writes`), inside the auth layer for
+    // the same reason
+    router = router.layer(axum::middleware::from_fn_with_state(
+        audit_log.clone(),
+        audit::track,
+    ));
+
+    // REST writes for the command hooks, inside the auth layer as well
+    router = router.layer(axum::middleware::from_fn_with_state(
+        command_hooks,
+        audit::hook,
+    ));
+
+    // CLIENT PAUSE and per-connection in-flight limits
+    router = router.layer(axum::middleware::from_fn_with_state(
+        clients,
+        super::client_limits::enforce,
+    ));
+
+    // Per-caller rate limits, inside the auth layer so budgets follow the API
+    // key or user rather than the IP
+    if rate_limit_config.enabled {
+        tracing::info!(
+            "Rate limiting enabled ({} req/s, burst: {}, {} tier(s))",
+            rate_limit_config.requests_per_second,
+            rate_limit_config.burst_size,
+            rate_limit_config.tiers.len()
+        );
+        let limiter = Arc::new(rate_limit::RateLimiter::new(rate_limit_config));
+        limiter.start_cleanup_task();
+        runtime_config.attach_rate_limiter(limiter.clone());
+        router = router.layer(axum::middleware::from_fn_with_state(
+            limiter,
+            rate_limit::rate_limit_middleware,
+        ));
+    } else {
+        tracing::info!("Rate limiting disabled (default)");
+    }
+
     // Apply authentication middleware (always apply, but behavior depends on auth_enabled)
     if let Some(auth) = auth_middleware {
         let auth_clone = auth.clone();
         router = router.layer(axum::middleware::from_fn(
             move |mut req: axum::extract::Request, next: axum::middleware::Next| {
                 let auth = auth_clone.clone();
+                let audit_log = audit_log.clone();
                 async move {
                     let client_ip = AuthMiddleware::get_client_ip(&req);
 
@@ -483,15 +558,43 @@ pub fn create_router(
 
                     debug!("Processing authentication for IP: {}", client_ip);
 
-                    // Try API Key authentication first (from header or query param)
+                    // Try a JWT access token first
+                    match AuthMiddleware::authenticate_jwt(&auth, &req, client_ip) {
+                        Ok(Some(auth_context)) => {
+                            req.extensions_mut().insert(auth_context);
+                            return Ok(next.run(req).await);
+                        }
+                        Err(e) => {
+                            debug!("Invalid access token provided");
+                            audit_log
+                                .log(AuditLogEntry::login_failure(
+                                    None,
+                                    client_ip.to_string(),
+                                    e.to_string(),
+                                ))
+                                .await;
+                            return Err(axum::http::StatusCode::UNAUTHORIZED);
+                        }
+                        Ok(None) => {
+                            // No access token provided, continue to API key
+                        }
+                    }
+
+                    // Try API Key authentication (from header or query param)
                     match AuthMiddleware::authenticate_api_key(&auth, &req, client_ip) {
                         Ok(Some(auth_context)) => {
                             req.extensions_mut().insert(auth_context);
                             return Ok(next.run(req).await);
                         }
-                        Err(_) => {
+                        Err(e) => {
                             // API key provided but invalid - return 401
                             debug!("Invalid API key provided");
+                            audit_log
+                                .log(AuditLogEntry::api_key_failure(
+                                    client_ip.to_string(),
+                                    e.to_string(),
+                                ))
+                                .await;
                             return Err(axum::http::StatusCode::UNAUTHORIZED);
                         }
                         Ok(None) => {
@@ -505,9 +608,16 @@ pub fn create_router(
                             req.extensions_mut().insert(auth_context);
                             return Ok(next.run(req).await);
                         }
-                        Err(_) => {
+                        Err(e) => {
                             // Basic Auth credentials provided but invalid - return 401
                             debug!("Invalid Basic Auth credentials");
+                            audit_log
+                                .log(AuditLogEntry::login_failure(
+                                    None,
+                                    client_ip.to_string(),
+                                    e.to_string(),
+                                ))
+                                .await;
                             return Err(axum::http::StatusCode::UNAUTHORIZED);
                         }
                         Ok(None) => {
@@ -544,27 +654,225 @@ pub fn create_router(
         ));
     }
 
+    if compression.enabled {
+        router = router.layer(compression_layer(compression));
+    }
+    router = router.layer(axum::Extension(WsCompression::from_config(compression)));
+
     router = router
-        .layer(CompressionLayer::new()) // Gzip compression for responses
-        .layer(TraceLayer::new_for_http())
+        .layer(TraceLayer::new_for_http().make_span_with(request_id::make_span))
+        .layer(axum::middleware::from_fn(request_id::propagate))
         .layer(cors);
 
-    // NOTE: Rate limiting implementation available but disabled by default
-    // The rate_limit::RateLimiter is fully implemented with token bucket algorithm
-    // To enable, set rate_limit.enabled = true in config.yml
-    // Implementation details in src/server/rate_limit.rs
+    router
+}
 
-    if rate_limit_config.enabled {
-        tracing::warn!(
-            "Rate limiting configured ({} req/s, burst: {}) but not active - requires middleware integration",
-            rate_limit_config.requests_per_second,
-            rate_limit_config.burst_size
-        );
-    } else {
-        tracing::info!("Rate limiting disabled (default)");
-    }
+/// Response compression negotiated from `Accept-Encoding`. Small bodies,
+/// images, gRPC and server-sent events (which must flush per event) are
+/// passed through as-is.
+fn compression_layer(config: &HttpCompressionConfig) -> CompressionLayer<impl Predicate + use<>> {
+    let predicate = SizeAbove::new(config.min_size_bytes)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE);
+    CompressionLayer::new()
+        .gzip(config.gzip)
+        .zstd(config.zstd)
+        .no_br()
+        .no_deflate()
+        .compress_when(predicate)
+}
 
+/// Lua scripting and function endpoints
+#[cfg(feature = "scripting")]
+fn script_routes(router: Router<AppState>) -> Router<AppState> {
     router
+        // Lua scripting endpoints
+        .route("/script/eval", post(handlers::script_eval))
+        .route("/script/evalsha", post(handlers::script_evalsha))
+        .route("/script/load", post(handlers::script_load))
+        .route("/script/exists", post(handlers::script_exists))
+        .route("/script/flush", post(handlers::script_flush))
+        .route("/script/kill", post(handlers::script_kill))
+        .route("/function/load", post(handlers::function_load))
+        .route("/function/delete", post(handlers::function_delete))
+        .route("/function/list", get(handlers::function_list))
+        .route("/function/flush", post(handlers::function_flush))
+        .route("/function/fcall", post(handlers::function_fcall))
+}
+
+/// Event stream, partitioned topic and consumer group endpoints
+#[cfg(feature = "streams")]
+fn stream_routes(router: Router<AppState>) -> Router<AppState> {
+    router
+        // Event Stream endpoints
+        .route(
+            "/stream/{room}/ws/{subscriber_id}",
+            get(handlers::stream_websocket),
+        ) // WebSocket for real-time push
+        .route(
+            "/stream/{room}",
+            post(handlers::stream_create_room)
+                .put(handlers::stream_get_or_create_room)
+                .delete(handlers::stream_delete_room),
+        )
+        .route("/stream/{room}/publish", post(handlers::stream_publish))
+        .route(
+            "/stream/{room}/consume/{subscriber_id}",
+            get(handlers::stream_consume),
+        )
+        .route("/stream/{room}/stats", get(handlers::stream_room_stats))
+        .route("/stream/consume", post(handlers::stream_consume_many))
+        .route(
+            "/stream/ws/{subscriber_id}",
+            get(handlers::stream_websocket_many),
+        )
+        .route("/stream/list", get(handlers::stream_list_rooms))
+        // Partitioned Stream endpoints (Kafka-style)
+        .route("/topics", get(handlers::list_topics))
+        .route("/topics/{topic}", post(handlers::create_partitioned_topic))
+        .route("/topics/{topic}", delete(handlers::delete_topic))
+        .route("/topics/{topic}/stats", get(handlers::get_topic_stats))
+        .route(
+            "/topics/{topic}/publish",
+            post(handlers::publish_to_partition),
+        )
+        .route(
+            "/topics/{topic}/partitions/{partition_id}/consume",
+            post(handlers::consume_from_partition),
+        )
+        // Consumer Group endpoints
+        .route("/consumer-groups", get(handlers::list_consumer_groups))
+        .route(
+            "/consumer-groups/{group_id}",
+            post(handlers::create_consumer_group),
+        )
+        .route(
+            "/consumer-groups/{group_id}/join",
+            post(handlers::join_consumer_group),
+        )
+        .route(
+            "/consumer-groups/{group_id}/members/{member_id}/leave",
+            delete(handlers::leave_consumer_group),
+        )
+        .route(
+            "/consumer-groups/{group_id}/members/{member_id}/assignment",
+            get(handlers::get_partition_assignment),
+        )
+        .route(
+            "/consumer-groups/{group_id}/members/{member_id}/heartbeat",
+            post(handlers::consumer_heartbeat),
+        )
+        .route(
+            "/consumer-groups/{group_id}/offsets/commit",
+            post(handlers::commit_offset),
+        )
+        .route(
+            "/consumer-groups/{group_id}/offsets/{partition_id}",
+            get(handlers::get_committed_offset),
+        )
+        .route(
+            "/consumer-groups/{group_id}/stats",
+            get(handlers::get_consumer_group_stats),
+        )
+        .route(
+            "/consumer-groups/{group_id}/lag",
+            get(handlers::get_consumer_group_lag),
+        )
+}
+
+/// Message queue endpoints
+#[cfg(feature = "queues")]
+fn queue_routes(router: Router<AppState>) -> Router<AppState> {
+    router
+        // Queue endpoints
+        .route(
+            "/queue/{name}/ws/{consumer_id}",
+            get(handlers::queue_websocket),
+        ) // WebSocket for continuous consume
+        .route("/queue/{name}", post(handlers::queue_create))
+        .route("/queue/{name}/publish", post(handlers::queue_publish))
+        .route(
+            "/queue/{name}/consume/{consumer_id}",
+            get(handlers::queue_consume),
+        )
+        .route("/queue/{name}/ack", post(handlers::queue_ack))
+        .route("/queue/{name}/nack", post(handlers::queue_nack))
+        .route("/queue/{name}/stats", get(handlers::queue_stats))
+        .route("/queue/{name}/purge", post(handlers::queue_purge))
+        .route("/queue/{name}", delete(handlers::queue_delete))
+        .route("/queue/list", get(handlers::queue_list))
+        // Shovels (queue-to-queue movers)
+        .route(
+            "/shovels",
+            get(handlers::shovel_list).post(handlers::shovel_create),
+        )
+        .route("/shovels/{name}", delete(handlers::shovel_delete))
+        // Webhook push subscriptions (queues and Pub/Sub topics to URLs)
+        .route(
+            "/webhooks",
+            get(handlers::webhook_list).post(handlers::webhook_create),
+        )
+        .route("/webhooks/{name}", delete(handlers::webhook_delete))
+}
+
+/// Pub/Sub endpoints
+#[cfg(feature = "pubsub")]
+fn pubsub_routes(router: Router<AppState>) -> Router<AppState> {
+    router
+        // Pub/Sub endpoints
+        .route("/pubsub/ws", get(handlers::pubsub_websocket)) // WebSocket for subscriptions
+        .route("/pubsub/subscribe", post(handlers::pubsub_subscribe)) // Legacy REST (deprecated)
+        .route("/pubsub/{topic}/publish", post(handlers::pubsub_publish))
+        .route("/pubsub/unsubscribe", post(handlers::pubsub_unsubscribe))
+        .route("/pubsub/stats", get(handlers::pubsub_stats))
+        .route("/pubsub/filters", get(handlers::pubsub_filters))
+        .route("/pubsub/routes", get(handlers::pubsub_list_routes))
+        .route(
+            "/pubsub/routes/{name}",
+            axum::routing::put(handlers::pubsub_set_route).delete(handlers::pubsub_delete_route),
+        )
+        .route("/pubsub/topics", get(handlers::pubsub_list_topics))
+        .route("/pubsub/{topic}/info", get(handlers::pubsub_topic_info))
+}
+
+/// Cluster management endpoints
+#[cfg(feature = "cluster")]
+fn cluster_routes(router: Router<AppState>) -> Router<AppState> {
+    router
+        // Cluster management endpoints
+        .route("/cluster/info", get(handlers::cluster_info))
+        .route("/cluster/nodes", get(handlers::cluster_nodes))
+        .route("/cluster/nodes", post(handlers::cluster_add_node))
+        .route("/cluster/nodes/{node_id}", get(handlers::cluster_node_info))
+        .route(
+            "/cluster/nodes/{node_id}",
+            delete(handlers::cluster_remove_node),
+        )
+        .route("/cluster/slots", get(handlers::cluster_slots))
+        .route(
+            "/cluster/slots/assign",
+            post(handlers::cluster_assign_slots),
+        )
+        .route(
+            "/cluster/migration/start",
+            post(handlers::cluster_start_migration),
+        )
+        .route(
+            "/cluster/migration/complete",
+            post(handlers::cluster_complete_migration),
+        )
+        .route(
+            "/cluster/migration/{slot}",
+            get(handlers::cluster_migration_status),
+        )
+        .route("/cluster/setslot", post(handlers::cluster_setslot))
+        .route("/cluster/migrate", post(handlers::cluster_migrate_slots))
+        .route("/cluster/migrations", get(handlers::cluster_migrations))
+        .route(
+            "/cluster/migrations/throttle",
+            post(handlers::cluster_migration_throttle),
+        )
 }
 
 /// Create MCP router with StreamableHTTP service and authentication
@@ -580,6 +888,8 @@ fn create_mcp_router(
     use rmcp::transport::streamable_http_server::StreamableHttpService;
     use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
 
+    let audit_log = state.monitoring.audit_log();
+
     // Create authentication middleware for MCP
     let auth_middleware = if auth_enabled {
         Some(AuthMiddleware::new(
@@ -641,18 +951,45 @@ fn create_mcp_router(
         router = router.layer(axum::middleware::from_fn(
             move |mut req: axum::extract::Request, next: axum::middleware::Next| {
                 let auth = auth_clone.clone();
+                let audit_log = audit_log.clone();
                 async move {
                     let client_ip = AuthMiddleware::get_client_ip(&req);
                     debug!("MCP: Processing authentication for IP: {}", client_ip);
 
-                    // Try API Key authentication first
+                    // Try a JWT access token first
+                    match AuthMiddleware::authenticate_jwt(&auth, &req, client_ip) {
+                        Ok(Some(auth_context)) => {
+                            req.extensions_mut().insert(auth_context);
+                            return Ok(next.run(req).await);
+                        }
+                        Err(e) => {
+                            debug!("MCP: Invalid access token provided");
+                            audit_log
+                                .log(AuditLogEntry::login_failure(
+                                    None,
+                                    client_ip.to_string(),
+                                    e.to_string(),
+                                ))
+                                .await;
+                            return Err(axum::http::StatusCode::UNAUTHORIZED);
+                        }
+                        Ok(None) => {}
+                    }
+
+                    // Try API Key authentication
                     match AuthMiddleware::authenticate_api_key(&auth, &req, client_ip) {
                         Ok(Some(auth_context)) => {
                             req.extensions_mut().insert(auth_context);
                             return Ok(next.run(req).await);
                         }
-                        Err(_) => {
+                        Err(e) => {
                             debug!("MCP: Invalid API key provided");
+                            audit_log
+                                .log(AuditLogEntry::api_key_failure(
+                                    client_ip.to_string(),
+                                    e.to_string(),
+                                ))
+                                .await;
                             return Err(axum::http::StatusCode::UNAUTHORIZED);
                         }
                         Ok(None) => {}
@@ -664,8 +1001,15 @@ fn create_mcp_router(
                             req.extensions_mut().insert(auth_context);
                             return Ok(next.run(req).await);
                         }
-                        Err(_) => {
+                        Err(e) => {
                             debug!("MCP: Invalid Basic Auth credentials");
+                            audit_log
+                                .log(AuditLogEntry::login_failure(
+                                    None,
+                                    client_ip.to_string(),
+                                    e.to_string(),
+                                ))
+                                .await;
                             return Err(axum::http::StatusCode::UNAUTHORIZED);
                         }
                         Ok(None) => {}
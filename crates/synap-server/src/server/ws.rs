@@ -0,0 +1,630 @@
+//! WebSocket upgrades with `permessage-deflate` (RFC 7692).
+//!
+//! tungstenite refuses every frame with a reserved bit set, so the extension
+//! is layered around it: [`FrameScanner`] walks the inbound bytes before
+//! tungstenite parses them, clears RSV1 on the first frame of each compressed
+//! message and notes which messages were compressed, and [`WebSocket`]
+//! inflates those. Outbound text and binary messages of at least
+//! `http_compression.websocket_min_size_bytes` are deflated on their own
+//! (`server_no_context_takeover`); the inflate context lives as long as the
+//! connection, which reads clients with or without context takeover.
+//!
+//! Handlers take [`WebSocketUpgrade`] and get a [`WebSocket`] that is a
+//! `Stream` and `Sink` of tungstenite [`Message`]s, like axum's.
+
+use std::collections::VecDeque;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+
+use axum::body::{Body, Bytes};
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+use axum::response::Response;
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
+use futures_util::{Sink, Stream};
+use hyper::upgrade::{OnUpgrade, Upgraded};
+use hyper_util::rt::TokioIo;
+use parking_lot::Mutex;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Utf8Bytes;
+use tokio_tungstenite::tungstenite::error::CapacityError;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::frame::Frame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::{Data, OpCode};
+use tokio_tungstenite::tungstenite::protocol::{Role, WebSocketConfig};
+
+pub use tokio_tungstenite::tungstenite::{Error, Message};
+
+use crate::config::HttpCompressionConfig;
+
+/// Extension response: each outbound message is compressed on its own
+const DEFLATE_RESPONSE: &str = "permessage-deflate; server_no_context_takeover";
+
+/// Empty stored block a sync flush ends with; RFC 7692 leaves it off the wire
+const DEFLATE_TAIL: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// Largest message inflated, the cap tungstenite puts on uncompressed ones
+const MAX_INFLATED_BYTES: usize = 64 << 20;
+
+const RSV1: u8 = 0x40;
+const OPCODE_MASK: u8 = 0x0f;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+
+/// `permessage-deflate` settings, handed to [`WebSocketUpgrade`] as a request
+/// extension by the router; without one the extension is never negotiated
+#[derive(Debug, Clone, Copy)]
+pub struct WsCompression {
+    pub enabled: bool,
+    /// Outbound messages smaller than this are sent uncompressed
+    pub min_size_bytes: usize,
+}
+
+impl WsCompression {
+    pub fn from_config(config: &HttpCompressionConfig) -> Self {
+        Self {
+            enabled: config.enabled && config.websocket,
+            min_size_bytes: usize::try_from(config.websocket_min_size_bytes).unwrap_or(usize::MAX),
+        }
+    }
+}
+
+/// Whether one of the client's `permessage-deflate` offers can be accepted.
+/// Offers asking for a smaller server window than the 32 KiB the compressor
+/// uses, or carrying a parameter this server does not know, are declined as
+/// RFC 7692 allows.
+fn accepts_deflate(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::SEC_WEBSOCKET_EXTENSIONS)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|offer| {
+            let mut params = offer.split(';').map(str::trim);
+            params.next() == Some("permessage-deflate")
+                && params.all(|param| {
+                    let (name, value) = match param.split_once('=') {
+                        Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                        None => (param, None),
+                    };
+                    match name {
+                        "server_no_context_takeover" | "client_no_context_takeover" => {
+                            value.is_none()
+                        }
+                        "client_max_window_bits" => value.is_none_or(|bits| {
+                            bits.parse::<u8>()
+                                .is_ok_and(|bits| (8..=15).contains(&bits))
+                        }),
+                        "server_max_window_bits" => value == Some("15"),
+                        _ => false,
+                    }
+                })
+        })
+}
+
+/// Extractor for a WebSocket handshake, negotiating `permessage-deflate`
+/// when the router enabled it
+pub struct WebSocketUpgrade {
+    on_upgrade: OnUpgrade,
+    accept_key: HeaderValue,
+    compression: Option<WsCompression>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for WebSocketUpgrade {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if parts.method != Method::GET {
+            return Err((
+                StatusCode::METHOD_NOT_ALLOWED,
+                "WebSocket upgrade requires GET",
+            ));
+        }
+        if !header_has_token(&parts.headers, header::CONNECTION, "upgrade") {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Connection header did not include 'upgrade'",
+            ));
+        }
+        if !header_has_token(&parts.headers, header::UPGRADE, "websocket") {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Upgrade header did not include 'websocket'",
+            ));
+        }
+        if parts
+            .headers
+            .get(header::SEC_WEBSOCKET_VERSION)
+            .is_none_or(|version| version != "13")
+        {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Sec-WebSocket-Version header did not include '13'",
+            ));
+        }
+        let Some(key) = parts.headers.get(header::SEC_WEBSOCKET_KEY) else {
+            return Err((StatusCode::BAD_REQUEST, "Sec-WebSocket-Key header missing"));
+        };
+        let accept_key = HeaderValue::from_str(&derive_accept_key(key.as_bytes()))
+            .expect("a base64 accept key is a valid header value");
+        let Some(on_upgrade) = parts.extensions.remove::<OnUpgrade>() else {
+            return Err((
+                StatusCode::UPGRADE_REQUIRED,
+                "WebSocket request couldn't be upgraded since no upgrade state was present",
+            ));
+        };
+
+        let compression = parts
+            .extensions
+            .get::<WsCompression>()
+            .copied()
+            .filter(|compression| compression.enabled && accepts_deflate(&parts.headers));
+
+        Ok(Self {
+            on_upgrade,
+            accept_key,
+            compression,
+        })
+    }
+}
+
+fn header_has_token(headers: &HeaderMap, name: header::HeaderName, token: &str) -> bool {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|value| value.trim().eq_ignore_ascii_case(token))
+}
+
+impl WebSocketUpgrade {
+    /// Answer the handshake and run `callback` on the upgraded connection
+    #[must_use = "the handshake response must be returned for the upgrade to happen"]
+    pub fn on_upgrade<C, Fut>(self, callback: C) -> Response
+    where
+        C: FnOnce(WebSocket) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let compression = self.compression;
+        let on_upgrade = self.on_upgrade;
+        tokio::spawn(async move {
+            let upgraded = match on_upgrade.await {
+                Ok(upgraded) => upgraded,
+                Err(e) => {
+                    tracing::debug!("WebSocket upgrade failed: {}", e);
+                    return;
+                }
+            };
+            callback(WebSocket::new(TokioIo::new(upgraded), compression).await).await;
+        });
+
+        let mut response = Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(header::CONNECTION, "upgrade")
+            .header(header::UPGRADE, "websocket")
+            .header(header::SEC_WEBSOCKET_ACCEPT, self.accept_key)
+            .body(Body::empty())
+            .expect("static handshake headers are valid");
+        if self.compression.is_some() {
+            response.headers_mut().insert(
+                header::SEC_WEBSOCKET_EXTENSIONS,
+                HeaderValue::from_static(DEFLATE_RESPONSE),
+            );
+        }
+        response
+    }
+}
+
+/// An inbound data message, as its first frame arrived
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Inbound {
+    Plain,
+    Deflated { text: bool },
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    First,
+    Length,
+    ExtendedLength,
+    Mask,
+    Payload,
+}
+
+/// Frame boundary tracker over the raw inbound bytes.
+///
+/// A compressed message's first frame gets RSV1 cleared and is relabelled
+/// binary, so tungstenite neither rejects it nor checks the deflated bytes as
+/// UTF-8; the original opcode goes on the inbound queue, one entry per data
+/// message, for [`WebSocket`] to pick up in order.
+#[derive(Debug, Default)]
+struct FrameScanner {
+    state: ScanState,
+    masked: bool,
+    /// Payload length, or payload bytes still to skip
+    len: u64,
+    /// Extended length or mask bytes still to read
+    remaining: u8,
+}
+
+impl FrameScanner {
+    fn scan(&mut self, bytes: &mut [u8], inbound: &mut VecDeque<Inbound>) {
+        let mut i = 0;
+        while i < bytes.len() {
+            match self.state {
+                ScanState::First => {
+                    let byte = &mut bytes[i];
+                    let opcode = *byte & OPCODE_MASK;
+                    if opcode == OPCODE_TEXT || opcode == OPCODE_BINARY {
+                        if *byte & RSV1 != 0 {
+                            *byte = (*byte & !(RSV1 | OPCODE_MASK)) | OPCODE_BINARY;
+                            inbound.push_back(Inbound::Deflated {
+                                text: opcode == OPCODE_TEXT,
+                            });
+                        } else {
+                            inbound.push_back(Inbound::Plain);
+                        }
+                    }
+                    self.state = ScanState::Length;
+                    i += 1;
+                }
+                ScanState::Length => {
+                    let byte = bytes[i];
+                    i += 1;
+                    self.masked = byte & 0x80 != 0;
+                    match byte & 0x7f {
+                        126 => self.extended_length(2),
+                        127 => self.extended_length(8),
+                        len => {
+                            self.len = u64::from(len);
+                            self.after_length();
+                        }
+                    }
+                }
+                ScanState::ExtendedLength => {
+                    self.len = self.len << 8 | u64::from(bytes[i]);
+                    i += 1;
+                    self.remaining -= 1;
+                    if self.remaining == 0 {
+                        self.after_length();
+                    }
+                }
+                ScanState::Mask => {
+                    let n = (bytes.len() - i).min(usize::from(self.remaining));
+                    i += n;
+                    self.remaining -= n as u8;
+                    if self.remaining == 0 {
+                        self.start_payload();
+                    }
+                }
+                ScanState::Payload => {
+                    let n = u64::try_from(bytes.len() - i).map_or(self.len, |n| n.min(self.len));
+                    i += n as usize;
+                    self.len -= n;
+                    if self.len == 0 {
+                        self.state = ScanState::First;
+                    }
+                }
+            }
+        }
+    }
+
+    fn extended_length(&mut self, bytes: u8) {
+        self.state = ScanState::ExtendedLength;
+        self.len = 0;
+        self.remaining = bytes;
+    }
+
+    fn after_length(&mut self) {
+        if self.masked {
+            self.state = ScanState::Mask;
+            self.remaining = 4;
+        } else {
+            self.start_payload();
+        }
+    }
+
+    fn start_payload(&mut self) {
+        self.state = if self.len == 0 {
+            ScanState::First
+        } else {
+            ScanState::Payload
+        };
+    }
+}
+
+/// The upgraded connection, with the frame scanner on its read side when
+/// the extension was negotiated
+struct ExtensionIo<S> {
+    io: S,
+    scanner: Option<(FrameScanner, Arc<Mutex<VecDeque<Inbound>>>)>,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for ExtensionIo<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut this.io).poll_read(cx, buf))?;
+        if let Some((scanner, inbound)) = &mut this.scanner {
+            scanner.scan(&mut buf.filled_mut()[filled..], &mut inbound.lock());
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for ExtensionIo<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+}
+
+/// Per-connection `permessage-deflate` state
+struct Deflate {
+    inbound: Arc<Mutex<VecDeque<Inbound>>>,
+    /// Created on the first compressed message
+    inflater: Option<Decompress>,
+    min_size_bytes: usize,
+}
+
+impl Deflate {
+    /// Restore a message the scanner marked as compressed
+    fn inbound(&mut self, message: Message) -> Result<Message, Error> {
+        if !matches!(message, Message::Text(_) | Message::Binary(_)) {
+            return Ok(message);
+        }
+        let Some(Inbound::Deflated { text }) = self.inbound.lock().pop_front() else {
+            return Ok(message);
+        };
+        let data = inflate(
+            self.inflater.get_or_insert_with(|| Decompress::new(false)),
+            &message.into_data(),
+        )?;
+        Ok(if text {
+            Message::Text(Utf8Bytes::try_from(data)?)
+        } else {
+            Message::Binary(data)
+        })
+    }
+
+    /// Compress a data message at or above the threshold into one RSV1 frame
+    fn outbound(&self, message: Message) -> Result<Message, Error> {
+        let opcode = match message {
+            Message::Text(_) => Data::Text,
+            Message::Binary(_) => Data::Binary,
+            _ => return Ok(message),
+        };
+        if message.len() < self.min_size_bytes {
+            return Ok(message);
+        }
+        let mut frame = Frame::message(deflate(&message.into_data())?, OpCode::Data(opcode), true);
+        frame.header_mut().rsv1 = true;
+        Ok(Message::Frame(frame))
+    }
+}
+
+/// Raw DEFLATE with a sync flush and the trailing empty block removed
+fn deflate(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut deflater = Compress::new(Compression::fast(), false);
+    let mut out = Vec::with_capacity(data.len() / 2 + 64);
+    loop {
+        if out.len() == out.capacity() {
+            out.reserve(out.capacity());
+        }
+        let consumed = deflater.total_in() as usize;
+        deflater
+            .compress_vec(&data[consumed..], &mut out, FlushCompress::Sync)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // Room left over means the flush is complete
+        if deflater.total_in() as usize == data.len() && out.len() < out.capacity() {
+            break;
+        }
+    }
+    if out.ends_with(&DEFLATE_TAIL) {
+        out.truncate(out.len() - DEFLATE_TAIL.len());
+    }
+    Ok(out)
+}
+
+/// Inflate one message, refusing output past [`MAX_INFLATED_BYTES`]
+fn inflate(inflater: &mut Decompress, data: &[u8]) -> Result<Bytes, Error> {
+    let input = [data, &DEFLATE_TAIL].concat();
+    let mut out = Vec::with_capacity((data.len() * 4).min(MAX_INFLATED_BYTES));
+    let mut consumed = 0;
+    loop {
+        if out.len() > MAX_INFLATED_BYTES {
+            return Err(CapacityError::MessageTooLong {
+                size: out.len(),
+                max_size: MAX_INFLATED_BYTES,
+            }
+            .into());
+        }
+        if out.len() == out.capacity() {
+            // One byte past the cap is enough to tell an oversized message
+            out.reserve_exact(out.len().clamp(1024, MAX_INFLATED_BYTES + 1 - out.len()));
+        }
+        let (before_in, before_out) = (inflater.total_in(), out.len());
+        let status = inflater
+            .decompress_vec(&input[consumed..], &mut out, FlushDecompress::Sync)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        consumed += (inflater.total_in() - before_in) as usize;
+        let done = consumed == input.len() && out.len() < out.capacity();
+        let stuck = inflater.total_in() == before_in && out.len() == before_out;
+        if done || status == Status::StreamEnd || stuck {
+            break;
+        }
+    }
+    Ok(out.into())
+}
+
+/// An upgraded WebSocket connection
+pub struct WebSocket {
+    inner: WebSocketStream<ExtensionIo<TokioIo<Upgraded>>>,
+    deflate: Option<Deflate>,
+}
+
+impl WebSocket {
+    async fn new(io: TokioIo<Upgraded>, compression: Option<WsCompression>) -> Self {
+        let inbound = Arc::new(Mutex::new(VecDeque::new()));
+        let io = ExtensionIo {
+            io,
+            scanner: compression.map(|_| (FrameScanner::default(), inbound.clone())),
+        };
+        let inner =
+            WebSocketStream::from_raw_socket(io, Role::Server, Some(WebSocketConfig::default()))
+                .await;
+        Self {
+            inner,
+            deflate: compression.map(|compression| Deflate {
+                inbound,
+                inflater: None,
+                min_size_bytes: compression.min_size_bytes,
+            }),
+        }
+    }
+}
+
+impl Stream for WebSocket {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let message = ready!(Pin::new(&mut this.inner).poll_next(cx));
+        Poll::Ready(match (message, &mut this.deflate) {
+            (Some(Ok(message)), Some(deflate)) => Some(deflate.inbound(message)),
+            (message, _) => message,
+        })
+    }
+}
+
+impl Sink<Message> for WebSocket {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, message: Message) -> Result<(), Error> {
+        let this = self.get_mut();
+        let message = match &this.deflate {
+            Some(deflate) => deflate.outbound(message)?,
+            None => message,
+        };
+        Pin::new(&mut this.inner).start_send(message)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offer(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::SEC_WEBSOCKET_EXTENSIONS,
+            HeaderValue::from_static(value),
+        );
+        headers
+    }
+
+    #[test]
+    fn deflate_offers_are_accepted_or_declined() {
+        assert!(accepts_deflate(&offer("permessage-deflate")));
+        assert!(accepts_deflate(&offer(
+            "permessage-deflate; client_max_window_bits"
+        )));
+        assert!(accepts_deflate(&offer(
+            "permessage-deflate; server_max_window_bits=10, permessage-deflate"
+        )));
+        assert!(!accepts_deflate(&offer(
+            "permessage-deflate; server_max_window_bits=10"
+        )));
+        assert!(!accepts_deflate(&offer("x-webkit-deflate-frame")));
+        assert!(!accepts_deflate(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn scanner_clears_rsv1_on_compressed_messages_only() {
+        // Masked compressed text "ab" (len 2), then a plain unmasked binary
+        // frame with a 126-byte payload, then a ping
+        let mut bytes = vec![0x80 | RSV1 | OPCODE_TEXT, 0x80 | 2, 1, 2, 3, 4, 9, 9];
+        bytes.extend([0x80 | OPCODE_BINARY, 126, 0, 126]);
+        bytes.extend([0u8; 126]);
+        bytes.extend([0x89, 0]);
+        let mut expected = bytes.clone();
+        expected[0] = 0x80 | OPCODE_BINARY;
+
+        // Byte-at-a-time reads must land on the same boundaries
+        let mut scanner = FrameScanner::default();
+        let mut inbound = VecDeque::new();
+        for byte in bytes.chunks_mut(1) {
+            scanner.scan(byte, &mut inbound);
+        }
+
+        assert_eq!(bytes, expected);
+        assert_eq!(
+            Vec::from(inbound),
+            vec![Inbound::Deflated { text: true }, Inbound::Plain]
+        );
+        assert_eq!(scanner.state, ScanState::First);
+    }
+
+    #[test]
+    fn deflate_round_trips_through_one_inflate_context() {
+        let mut inflater = Decompress::new(false);
+        for message in [vec![b'x'; 5000], b"second message".to_vec()] {
+            let compressed = deflate(&message).unwrap();
+            assert!(!compressed.ends_with(&DEFLATE_TAIL));
+            assert_eq!(inflate(&mut inflater, &compressed).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn inflate_refuses_oversized_messages() {
+        let compressed = deflate(&vec![0u8; MAX_INFLATED_BYTES + 1]).unwrap();
+        assert!(matches!(
+            inflate(&mut Decompress::new(false), &compressed),
+            Err(Error::Capacity(CapacityError::MessageTooLong { .. }))
+        ));
+    }
+}
@@ -0,0 +1,902 @@
+//! Queue shovels.
+//!
+//! A shovel is a background mover that takes messages from a local queue
+//! and publishes them to another queue, on this server or on another Synap
+//! server over HTTP. It covers three workflows:
+//!
+//! - Migration: drain `orders` into `orders` on a new server
+//! - Mirroring: in `copy` mode, each message goes to the destination (e.g. a
+//!   staging server) and its original to `forward_to`, where production
+//!   consumers read it
+//! - DLQ reprocessing: with `dead_letters`, take the source's dead letters
+//!   and publish them again, to the same queue or another one
+//!
+//! An optional Lua `transform` runs on each message with the payload in
+//! `ARGV[1]` and the priority in `ARGV[2]`. It returns the new payload (a
+//! string, or any other value which is sent as JSON), or `nil` / `false` to
+//! drop the message.
+//!
+//! Delivery is at least once: a message is acked on the source only after
+//! the destination accepted it, and handed back (NACK with requeue, or back
+//! to the front of the dead letters) when publishing or the transform fails.
+//! With persistence enabled the shovel definitions and their counters are
+//! checkpointed to `shovels.json` next to the snapshots, so shovels resume
+//! after a restart and `max_messages` keeps counting where it stopped.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::core::{QueueManager, QueueMessage, SynapError};
+use crate::persistence::{PersistenceLayer, write_atomically};
+use crate::server::AppState;
+
+/// Shovel definitions and counters, kept in the snapshot directory
+pub const SHOVELS_FILE: &str = "shovels.json";
+
+/// Wait before polling an empty source again
+const IDLE_POLL: Duration = Duration::from_millis(100);
+/// Wait after a failed message before taking the next one
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+/// Counters are written at most this often
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+/// Timeout of one publish to a remote destination
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What happens to the source message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShovelMode {
+    /// The message leaves the source for the destination
+    #[default]
+    Move,
+    /// The destination gets a copy; the original goes to `forward_to`
+    Copy,
+}
+
+/// A shovel as created by `shovel.create`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShovelSpec {
+    pub name: String,
+    /// Local queue messages are taken from
+    pub source: String,
+    /// Take the source's dead letters instead of its messages
+    #[serde(default)]
+    pub dead_letters: bool,
+    /// Queue messages are published to
+    pub destination: String,
+    /// Base URL of the Synap server holding `destination`; this server when
+    /// unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_url: Option<String>,
+    /// Bearer token (API key or JWT) for `destination_url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_token: Option<String>,
+    #[serde(default)]
+    pub mode: ShovelMode,
+    /// Local queue that receives the originals in `copy` mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forward_to: Option<String>,
+    /// Lua chunk run on every message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<String>,
+    /// Messages per second; unlimited when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    /// Stop after this many messages (moved or dropped by the transform)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_messages: Option<u64>,
+}
+
+/// Counters of one shovel, checkpointed with its definition
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShovelProgress {
+    /// Messages published to the destination
+    pub moved: u64,
+    /// Messages the transform dropped
+    pub filtered: u64,
+    /// Messages handed back after a failed transform or publish
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Unix timestamp (milliseconds) of the last message moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_moved_at: Option<u64>,
+    /// `max_messages` was reached; the shovel no longer runs
+    pub completed: bool,
+}
+
+impl ShovelProgress {
+    fn handled(&self) -> u64 {
+        self.moved + self.filtered
+    }
+}
+
+/// `shovel.list` entry
+#[derive(Debug, Clone, Serialize)]
+pub struct ShovelStatus {
+    #[serde(flatten)]
+    pub spec: ShovelSpec,
+    #[serde(flatten)]
+    pub progress: ShovelProgress,
+}
+
+/// One line of the checkpoint file
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    spec: ShovelSpec,
+    progress: ShovelProgress,
+}
+
+struct Entry {
+    spec: ShovelSpec,
+    progress: Arc<Mutex<ShovelProgress>>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// What a shovel needs from the server it runs on
+#[derive(Clone)]
+pub struct ShovelContext {
+    queue_manager: Arc<QueueManager>,
+    persistence: Option<Arc<PersistenceLayer>>,
+    #[cfg(feature = "scripting")]
+    scripts: Arc<crate::scripting::ScriptManager>,
+    #[cfg(feature = "scripting")]
+    script_context: crate::scripting::ScriptExecContext,
+    http: reqwest::Client,
+}
+
+impl ShovelContext {
+    pub fn new(state: &AppState) -> Result<Self, SynapError> {
+        let queue_manager = state
+            .queue_manager()
+            .cloned()
+            .ok_or_else(|| SynapError::InvalidRequest("Queue system disabled".to_string()))?;
+        let http = reqwest::Client::builder()
+            .timeout(REMOTE_TIMEOUT)
+            .build()
+            .map_err(|e| SynapError::InternalError(e.to_string()))?;
+
+        Ok(Self {
+            queue_manager,
+            persistence: state.persistence.clone(),
+            #[cfg(feature = "scripting")]
+            scripts: state.script_manager.clone(),
+            #[cfg(feature = "scripting")]
+            script_context: crate::scripting::ScriptExecContext {
+                kv_store: state.kv_store.clone(),
+                hash_store: state.hash_store.clone(),
+                list_store: state.list_store.clone(),
+                set_store: state.set_store.clone(),
+                sorted_set_store: state.sorted_set_store.clone(),
+                queue_manager: state.queue_manager().cloned(),
+                stream_manager: state.stream_manager().cloned(),
+            },
+            http,
+        })
+    }
+}
+
+/// Running shovels, optionally checkpointed to a file
+#[derive(Default)]
+pub struct ShovelManager {
+    shovels: Mutex<HashMap<String, Entry>>,
+    path: Option<PathBuf>,
+}
+
+impl ShovelManager {
+    /// In-memory manager; shovels stop for good on restart
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Manager checkpointed at `path`, loading the shovels saved there.
+    /// They start on [`resume`](Self::resume).
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, SynapError> {
+        let path = path.into();
+        let saved: Vec<Saved> = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| {
+                SynapError::SerializationError(format!("{}: {}", path.display(), e))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(SynapError::IoError(e.to_string())),
+        };
+
+        info!("Loaded {} shovels from {}", saved.len(), path.display());
+        let shovels = saved
+            .into_iter()
+            .map(|saved| {
+                let entry = Entry {
+                    spec: saved.spec,
+                    progress: Arc::new(Mutex::new(saved.progress)),
+                    task: None,
+                };
+                (entry.spec.name.clone(), entry)
+            })
+            .collect();
+        Ok(Self {
+            shovels: Mutex::new(shovels),
+            path: Some(path),
+        })
+    }
+
+    /// Start every loaded shovel that has not completed
+    pub fn resume(self: &Arc<Self>, context: ShovelContext) {
+        let mut shovels = self.shovels.lock();
+        for entry in shovels.values_mut() {
+            if entry.task.is_none() && !entry.progress.lock().completed {
+                entry.task = Some(self.spawn(entry, context.clone()));
+            }
+        }
+    }
+
+    /// Validate and start a shovel (`shovel.create`)
+    pub async fn create(
+        self: &Arc<Self>,
+        spec: ShovelSpec,
+        context: ShovelContext,
+    ) -> Result<ShovelStatus, SynapError> {
+        validate(&spec, &context).await?;
+
+        let mut shovels = self.shovels.lock();
+        if shovels.contains_key(&spec.name) {
+            return Err(SynapError::InvalidRequest(format!(
+                "Shovel '{}' already exists",
+                spec.name
+            )));
+        }
+        let mut entry = Entry {
+            spec,
+            progress: Arc::default(),
+            task: None,
+        };
+        entry.task = Some(self.spawn(&entry, context));
+        let status = status(&entry);
+        info!(
+            "Shovel '{}' started: {} -> {}",
+            entry.spec.name, entry.spec.source, entry.spec.destination
+        );
+        shovels.insert(entry.spec.name.clone(), entry);
+        self.persist(&shovels);
+        Ok(status)
+    }
+
+    /// Every shovel, sorted by name, with tokens redacted (`shovel.list`)
+    pub fn list(&self) -> Vec<ShovelStatus> {
+        let mut list: Vec<ShovelStatus> = self.shovels.lock().values().map(status).collect();
+        list.sort_by(|a, b| a.spec.name.cmp(&b.spec.name));
+        list
+    }
+
+    /// Stop and forget a shovel (`shovel.delete`). A message it was moving
+    /// stays unacked and is redelivered after the source's ACK deadline.
+    pub fn delete(&self, name: &str) -> bool {
+        let mut shovels = self.shovels.lock();
+        let Some(entry) = shovels.remove(name) else {
+            return false;
+        };
+        if let Some(task) = entry.task {
+            task.abort();
+        }
+        info!("Shovel '{}' deleted", name);
+        self.persist(&shovels);
+        true
+    }
+
+    fn spawn(self: &Arc<Self>, entry: &Entry, context: ShovelContext) -> JoinHandle<()> {
+        let worker = Worker {
+            consumer_id: format!("shovel:{}", entry.spec.name),
+            spec: entry.spec.clone(),
+            progress: entry.progress.clone(),
+            manager: Arc::downgrade(self),
+            context,
+        };
+        tokio::spawn(worker.run())
+    }
+
+    fn checkpoint(&self) {
+        self.persist(&self.shovels.lock());
+    }
+
+    /// Write every shovel to the backing file, if any. A failed write is
+    /// logged; the shovels keep running.
+    fn persist(&self, shovels: &HashMap<String, Entry>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let mut saved: Vec<Saved> = shovels
+            .values()
+            .map(|entry| Saved {
+                spec: entry.spec.clone(),
+                progress: entry.progress.lock().clone(),
+            })
+            .collect();
+        saved.sort_by(|a, b| a.spec.name.cmp(&b.spec.name));
+        let result = serde_json::to_vec_pretty(&saved)
+            .map_err(|e| e.to_string())
+            .and_then(|data| write_atomically(path, &data).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to checkpoint shovels to {}: {}", path.display(), e);
+        }
+    }
+}
+
+impl Drop for ShovelManager {
+    fn drop(&mut self) {
+        for entry in self.shovels.get_mut().values_mut() {
+            if let Some(task) = entry.task.take() {
+                task.abort();
+            }
+        }
+    }
+}
+
+fn status(entry: &Entry) -> ShovelStatus {
+    let mut spec = entry.spec.clone();
+    if spec.destination_token.is_some() {
+        spec.destination_token = Some("<redacted>".to_string());
+    }
+    ShovelStatus {
+        spec,
+        progress: entry.progress.lock().clone(),
+    }
+}
+
+async fn validate(spec: &ShovelSpec, context: &ShovelContext) -> Result<(), SynapError> {
+    let invalid = |message: &str| Err(SynapError::InvalidRequest(message.to_string()));
+
+    if spec.name.is_empty() || spec.source.is_empty() || spec.destination.is_empty() {
+        return invalid("Shovel name, source and destination must not be empty");
+    }
+    if spec.rate_limit == Some(0) {
+        return invalid("rate_limit must be at least 1 message per second");
+    }
+    match (spec.mode, &spec.forward_to) {
+        (ShovelMode::Copy, None) => return invalid("copy mode needs a forward_to queue"),
+        (ShovelMode::Move, Some(_)) => return invalid("forward_to is only used in copy mode"),
+        _ => {}
+    }
+    if cfg!(not(feature = "scripting")) && spec.transform.is_some() {
+        return invalid("Transforms need a server built with the scripting feature");
+    }
+
+    let mut local = vec![&spec.source];
+    match &spec.destination_url {
+        Some(url) if !(url.starts_with("http://") || url.starts_with("https://")) => {
+            return invalid("destination_url must be an http:// or https:// URL");
+        }
+        Some(_) => {}
+        None if spec.destination == spec.source && !spec.dead_letters => {
+            return invalid("A shovel cannot move a queue's messages into itself");
+        }
+        None => local.push(&spec.destination),
+    }
+    local.extend(&spec.forward_to);
+    for queue in local {
+        context.queue_manager.stats(queue).await?;
+    }
+    Ok(())
+}
+
+struct Worker {
+    spec: ShovelSpec,
+    consumer_id: String,
+    progress: Arc<Mutex<ShovelProgress>>,
+    manager: Weak<ShovelManager>,
+    context: ShovelContext,
+}
+
+impl Worker {
+    async fn run(self) {
+        let mut pacer = self.spec.rate_limit.map(|rate| {
+            let mut pacer = tokio::time::interval(Duration::from_secs(1) / rate);
+            pacer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            pacer
+        });
+        let mut checkpointed = self.progress.lock().clone();
+        let mut last_checkpoint = Instant::now();
+
+        loop {
+            if last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+                let progress = self.progress.lock().clone();
+                if progress != checkpointed {
+                    self.checkpoint();
+                    checkpointed = progress;
+                }
+                last_checkpoint = Instant::now();
+            }
+            if let Some(max) = self.spec.max_messages
+                && self.progress.lock().handled() >= max
+            {
+                self.progress.lock().completed = true;
+                self.checkpoint();
+                info!(
+                    "Shovel '{}' completed after {} messages",
+                    self.spec.name, max
+                );
+                return;
+            }
+            if let Some(pacer) = &mut pacer {
+                pacer.tick().await;
+            }
+
+            let message = match self.take().await {
+                Ok(Some(message)) => message,
+                Ok(None) => {
+                    tokio::time::sleep(IDLE_POLL).await;
+                    continue;
+                }
+                Err(e) => {
+                    self.record_error(&e);
+                    tokio::time::sleep(RETRY_BACKOFF).await;
+                    continue;
+                }
+            };
+
+            match self.shovel(&message).await {
+                Ok(delivered) => {
+                    self.settle(&message).await;
+                    let mut progress = self.progress.lock();
+                    if delivered {
+                        progress.moved += 1;
+                        progress.last_moved_at = Some(unix_millis());
+                    } else {
+                        progress.filtered += 1;
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        "Shovel '{}' failed on message {}: {}",
+                        self.spec.name, message.id, e
+                    );
+                    self.record_error(&e);
+                    self.give_back(message).await;
+                    tokio::time::sleep(RETRY_BACKOFF).await;
+                }
+            }
+        }
+    }
+
+    fn checkpoint(&self) {
+        if let Some(manager) = self.manager.upgrade() {
+            manager.checkpoint();
+        }
+    }
+
+    fn record_error(&self, error: &SynapError) {
+        let mut progress = self.progress.lock();
+        progress.errors += 1;
+        progress.last_error = Some(error.to_string());
+    }
+
+    async fn take(&self) -> Result<Option<QueueMessage>, SynapError> {
+        let queues = &self.context.queue_manager;
+        if self.spec.dead_letters {
+            queues.pop_dead_letter(&self.spec.source).await
+        } else {
+            queues.consume(&self.spec.source, &self.consumer_id).await
+        }
+    }
+
+    /// Transform and publish one message; `false` when the transform dropped it
+    async fn shovel(&self, message: &QueueMessage) -> Result<bool, SynapError> {
+        let Some(payload) = self.transform(message).await? else {
+            return Ok(false);
+        };
+
+        match &self.spec.destination_url {
+            Some(url) => self.publish_remote(url, payload, message).await?,
+            None => {
+                self.publish_local(&self.spec.destination, payload, message)
+                    .await?
+            }
+        }
+        if let Some(forward_to) = &self.spec.forward_to {
+            self.publish_local(forward_to, message.payload.to_vec(), message)
+                .await?;
+        }
+        Ok(true)
+    }
+
+    #[cfg(feature = "scripting")]
+    async fn transform(&self, message: &QueueMessage) -> Result<Option<Vec<u8>>, SynapError> {
+        let Some(script) = &self.spec.transform else {
+            return Ok(Some(message.payload.to_vec()));
+        };
+        let payload = std::str::from_utf8(&message.payload).map_err(|_| {
+            SynapError::InvalidRequest("Transforms need a UTF-8 payload".to_string())
+        })?;
+
+        let result = self
+            .context
+            .scripts
+            .eval(
+                self.context.script_context.clone(),
+                script,
+                vec![self.spec.source.clone(), self.spec.destination.clone()],
+                vec![payload.to_string(), message.priority.to_string()],
+                None,
+            )
+            .await?
+            .0;
+        Ok(match result {
+            serde_json::Value::Null | serde_json::Value::Bool(false) => None,
+            serde_json::Value::String(payload) => Some(payload.into_bytes()),
+            other => Some(
+                serde_json::to_vec(&other)
+                    .map_err(|e| SynapError::SerializationError(e.to_string()))?,
+            ),
+        })
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    async fn transform(&self, message: &QueueMessage) -> Result<Option<Vec<u8>>, SynapError> {
+        Ok(Some(message.payload.to_vec()))
+    }
+
+    async fn publish_local(
+        &self,
+        queue: &str,
+        payload: Vec<u8>,
+        original: &QueueMessage,
+    ) -> Result<(), SynapError> {
+        let message = self
+            .context
+            .queue_manager
+            .publish_with_message(
+                queue,
+                payload,
+                Some(original.priority),
+                Some(original.max_retries),
+            )
+            .await?;
+
+        if let Some(ref persistence) = self.context.persistence
+            && let Err(e) = persistence
+                .log_queue_publish(queue.to_string(), message)
+                .await
+        {
+            warn!("Failed to log shovel publish to WAL: {}", e);
+        }
+        Ok(())
+    }
+
+    async fn publish_remote(
+        &self,
+        url: &str,
+        payload: Vec<u8>,
+        original: &QueueMessage,
+    ) -> Result<(), SynapError> {
+        let body = json!({
+            "command": "queue.publish",
+            "request_id": format!("{}:{}", self.consumer_id, original.id),
+            "payload": {
+                "queue": self.spec.destination,
+                "payload": payload,
+                "priority": original.priority,
+                "max_retries": original.max_retries,
+            }
+        });
+        let mut request = self
+            .context
+            .http
+            .post(format!("{}/api/v1/command", url.trim_end_matches('/')))
+            .json(&body);
+        if let Some(token) = &self.spec.destination_token {
+            request = request.bearer_auth(token);
+        }
+
+        let remote_error = |e: String| SynapError::InternalError(format!("{}: {}", url, e));
+        let response: serde_json::Value = request
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| remote_error(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| remote_error(e.to_string()))?;
+        if response["success"] != true {
+            let error = response["error"].as_str().unwrap_or("publish failed");
+            return Err(remote_error(error.to_string()));
+        }
+        Ok(())
+    }
+
+    /// The message reached its destination: remove it from the source
+    async fn settle(&self, message: &QueueMessage) {
+        if self.spec.dead_letters {
+            return;
+        }
+        let queues = &self.context.queue_manager;
+        if let Err(e) = queues.ack(&self.spec.source, &message.id).await {
+            warn!(
+                "Shovel '{}' could not ACK {}: {}",
+                self.spec.name, message.id, e
+            );
+            return;
+        }
+        if let Some(ref persistence) = self.context.persistence
+            && let Err(e) = persistence
+                .log_queue_ack(self.spec.source.clone(), message.id.clone())
+                .await
+        {
+            warn!("Failed to log shovel ACK to WAL: {}", e);
+        }
+    }
+
+    /// The message could not be moved: return it to the source
+    async fn give_back(&self, message: QueueMessage) {
+        let queues = &self.context.queue_manager;
+        if self.spec.dead_letters {
+            if let Err(e) = queues.return_dead_letter(&self.spec.source, message).await {
+                warn!("Shovel '{}' lost a dead letter: {}", self.spec.name, e);
+            }
+            return;
+        }
+        if let Err(e) = queues.nack(&self.spec.source, &message.id, true).await {
+            warn!(
+                "Shovel '{}' could not NACK {}: {}",
+                self.spec.name, message.id, e
+            );
+            return;
+        }
+        if let Some(ref persistence) = self.context.persistence
+            && let Err(e) = persistence
+                .log_queue_nack(self.spec.source.clone(), message.id, true)
+                .await
+        {
+            warn!("Failed to log shovel NACK to WAL: {}", e);
+        }
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::QueueConfig;
+
+    fn context(queues: &Arc<QueueManager>) -> ShovelContext {
+        ShovelContext {
+            queue_manager: queues.clone(),
+            persistence: None,
+            #[cfg(feature = "scripting")]
+            scripts: Arc::new(crate::scripting::ScriptManager::new(Duration::from_secs(5))),
+            #[cfg(feature = "scripting")]
+            script_context: crate::scripting::ScriptExecContext {
+                kv_store: Arc::new(crate::core::KVStore::new(Default::default())),
+                hash_store: Arc::new(crate::core::HashStore::new()),
+                list_store: Arc::new(crate::core::ListStore::new()),
+                set_store: Arc::new(crate::core::SetStore::new()),
+                sorted_set_store: Arc::new(crate::core::SortedSetStore::new()),
+                queue_manager: Some(queues.clone()),
+                stream_manager: None,
+            },
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn spec(name: &str, source: &str, destination: &str) -> ShovelSpec {
+        ShovelSpec {
+            name: name.to_string(),
+            source: source.to_string(),
+            dead_letters: false,
+            destination: destination.to_string(),
+            destination_url: None,
+            destination_token: None,
+            mode: ShovelMode::Move,
+            forward_to: None,
+            transform: None,
+            rate_limit: None,
+            max_messages: None,
+        }
+    }
+
+    async fn queues(names: &[&str]) -> Arc<QueueManager> {
+        let queues = Arc::new(QueueManager::new(QueueConfig::default()));
+        for name in names {
+            queues.create_queue(name, None).await.unwrap();
+        }
+        queues
+    }
+
+    async fn wait_for(manager: &ShovelManager, name: &str, done: impl Fn(&ShovelProgress) -> bool) {
+        for _ in 0..200 {
+            let list = manager.list();
+            let status = list.iter().find(|s| s.spec.name == name).unwrap();
+            if done(&status.progress) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("shovel '{}' did not get there: {:?}", name, manager.list());
+    }
+
+    async fn drain(queues: &QueueManager, queue: &str) -> Vec<Vec<u8>> {
+        let mut payloads = Vec::new();
+        while let Some(message) = queues.consume(queue, "test").await.unwrap() {
+            payloads.push(message.payload.to_vec());
+        }
+        payloads
+    }
+
+    #[tokio::test]
+    async fn test_move_stops_after_max_messages() {
+        let queues = queues(&["orders", "orders-v2"]).await;
+        for payload in ["a", "b", "c"] {
+            queues
+                .publish("orders", payload.as_bytes().to_vec(), None, None)
+                .await
+                .unwrap();
+        }
+
+        let manager = Arc::new(ShovelManager::new());
+        let mut migrate = spec("migrate", "orders", "orders-v2");
+        migrate.max_messages = Some(2);
+        manager.create(migrate, context(&queues)).await.unwrap();
+        wait_for(&manager, "migrate", |p| p.completed).await;
+
+        assert_eq!(manager.list()[0].progress.moved, 2);
+        assert_eq!(drain(&queues, "orders-v2").await, [b"a", b"b"]);
+        let source = queues.stats("orders").await.unwrap();
+        assert_eq!((source.depth, source.acked), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn test_copy_forwards_originals() {
+        let queues = queues(&["events", "staging", "events-live"]).await;
+        queues
+            .publish("events", b"e1".to_vec(), None, None)
+            .await
+            .unwrap();
+
+        let manager = Arc::new(ShovelManager::new());
+        let mut mirror = spec("mirror", "events", "staging");
+        mirror.mode = ShovelMode::Copy;
+        mirror.forward_to = Some("events-live".to_string());
+        manager.create(mirror, context(&queues)).await.unwrap();
+        wait_for(&manager, "mirror", |p| p.moved == 1).await;
+
+        assert_eq!(drain(&queues, "staging").await, [b"e1"]);
+        assert_eq!(drain(&queues, "events-live").await, [b"e1"]);
+        assert!(manager.delete("mirror"));
+        assert!(manager.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_are_reprocessed() {
+        let queues = queues(&["jobs"]).await;
+        for payload in ["x", "y"] {
+            queues
+                .publish("jobs", payload.as_bytes().to_vec(), None, Some(0))
+                .await
+                .unwrap();
+            let message = queues.consume("jobs", "worker").await.unwrap().unwrap();
+            queues.nack("jobs", &message.id, true).await.unwrap();
+        }
+        assert!(drain(&queues, "jobs").await.is_empty());
+
+        let manager = Arc::new(ShovelManager::new());
+        let mut replay = spec("replay", "jobs", "jobs");
+        replay.dead_letters = true;
+        manager.create(replay, context(&queues)).await.unwrap();
+        wait_for(&manager, "replay", |p| p.moved == 2).await;
+
+        assert_eq!(drain(&queues, "jobs").await, [b"x", b"y"]);
+        assert!(queues.pop_dead_letter("jobs").await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "scripting")]
+    #[tokio::test]
+    async fn test_transform_rewrites_and_filters() {
+        let queues = queues(&["in", "out"]).await;
+        for payload in ["a", "skip", "b"] {
+            queues
+                .publish("in", payload.as_bytes().to_vec(), None, None)
+                .await
+                .unwrap();
+        }
+
+        let manager = Arc::new(ShovelManager::new());
+        let mut upper = spec("upper", "in", "out");
+        upper.transform = Some(
+            "if ARGV[1] == 'skip' then return nil end return string.upper(ARGV[1])".to_string(),
+        );
+        manager.create(upper, context(&queues)).await.unwrap();
+        wait_for(&manager, "upper", |p| p.moved + p.filtered == 3).await;
+
+        assert_eq!(manager.list()[0].progress.filtered, 1);
+        assert_eq!(drain(&queues, "out").await, [b"A", b"B"]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_shovels_are_rejected() {
+        let queues = queues(&["a", "b"]).await;
+        let manager = Arc::new(ShovelManager::new());
+
+        let mut copy = spec("copy", "a", "b");
+        copy.mode = ShovelMode::Copy;
+        let mut stopped = spec("stopped", "a", "b");
+        stopped.rate_limit = Some(0);
+        let mut remote = spec("remote", "a", "b");
+        remote.destination_url = Some("ftp://elsewhere".to_string());
+        for invalid in [
+            spec("loop", "a", "a"),
+            spec("missing", "a", "nowhere"),
+            copy,
+            stopped,
+            remote,
+        ] {
+            assert!(manager.create(invalid, context(&queues)).await.is_err());
+        }
+
+        manager
+            .create(spec("ok", "a", "b"), context(&queues))
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .create(spec("ok", "b", "a"), context(&queues))
+                .await
+                .is_err()
+        );
+        assert_eq!(manager.list().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_shovels_resume_from_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SHOVELS_FILE);
+        let queues = queues(&["src", "dst", "idle"]).await;
+        for payload in ["1", "2", "3"] {
+            queues
+                .publish("src", payload.as_bytes().to_vec(), None, None)
+                .await
+                .unwrap();
+        }
+
+        let manager = Arc::new(ShovelManager::open(&path).unwrap());
+        let mut once = spec("once", "src", "dst");
+        once.max_messages = Some(1);
+        let mut token = spec("remote", "idle", "elsewhere");
+        token.destination_url = Some("http://127.0.0.1:9".to_string());
+        token.destination_token = Some("secret".to_string());
+        token.rate_limit = Some(1);
+        manager.create(once, context(&queues)).await.unwrap();
+        wait_for(&manager, "once", |p| p.completed).await;
+        manager.create(token, context(&queues)).await.unwrap();
+        let listed = manager.list();
+        assert_eq!(
+            listed[1].spec.destination_token.as_deref(),
+            Some("<redacted>")
+        );
+        manager.delete("remote");
+        drop(manager);
+
+        let reopened = Arc::new(ShovelManager::open(&path).unwrap());
+        reopened.resume(context(&queues));
+        let list = reopened.list();
+        assert_eq!(list.len(), 1);
+        assert!(list[0].progress.completed);
+        assert_eq!(list[0].progress.moved, 1);
+        // Completed shovels are not restarted
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(queues.stats("src").await.unwrap().depth, 2);
+    }
+}
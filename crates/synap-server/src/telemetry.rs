@@ -0,0 +1,189 @@
+//! OpenTelemetry tracing (`otel` feature).
+//!
+//! With `telemetry.enabled`, the server's tracing spans are exported over
+//! OTLP/gRPC: one `request` span per HTTP request, continuing the caller's
+//! trace when the request carries a W3C `traceparent` header, and one
+//! `command` span per command on every transport. Without the feature the
+//! section is parsed but ignored, with a warning when it is enabled.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tracing::Span;
+
+/// Set once the exporter is running
+static EXPORTING: AtomicBool = AtomicBool::new(false);
+
+/// `telemetry` section of the config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// Export spans (needs the `otel` feature)
+    pub enabled: bool,
+    /// OTLP/gRPC collector endpoint
+    pub endpoint: String,
+    /// `service.name` resource attribute
+    pub service_name: String,
+    /// Fraction of new traces to sample, 0.0 - 1.0. Requests that continue
+    /// a caller's trace follow the caller's sampling decision.
+    pub sample_ratio: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4317".to_string(),
+            service_name: "synap".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+/// Span around one command. It is an info span while spans are exported
+/// and a debug span otherwise, so the RESP3 and SynapRPC hot paths do not
+/// pay for it at the default log level.
+pub fn command_span(protocol: &'static str, command: &str) -> Span {
+    if EXPORTING.load(Ordering::Relaxed) {
+        tracing::info_span!("command", protocol, command)
+    } else {
+        tracing::debug_span!("command", protocol, command)
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use otel::{layer, set_parent, shutdown};
+
+/// Continue the caller's trace; a no-op without the `otel` feature
+#[cfg(not(feature = "otel"))]
+pub fn set_parent(_span: &Span, _headers: &axum::http::HeaderMap) {}
+
+/// Flush spans not yet exported; a no-op without the `otel` feature
+#[cfg(not(feature = "otel"))]
+pub fn shutdown() {}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use super::{EXPORTING, TelemetryConfig};
+    use axum::http::HeaderMap;
+    use opentelemetry::propagation::Extractor;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::{KeyValue, global};
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::{Sampler, TracerProvider};
+    use opentelemetry_sdk::{Resource, runtime};
+    use tracing::{Level, Span, Subscriber};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::Layer;
+    use tracing_subscriber::filter::Targets;
+    use tracing_subscriber::registry::LookupSpan;
+
+    /// Crates whose spans are not exported: the HTTP and gRPC stacks,
+    /// including the exporter's own
+    const QUIET: &[&str] = &["h2", "hyper", "hyper_util", "reqwest", "tonic", "tower"];
+
+    /// Start the OTLP exporter and return the layer that feeds it, or `None`
+    /// when `telemetry.enabled` is false
+    pub fn layer<S>(config: &TelemetryConfig) -> anyhow::Result<Option<impl Layer<S>>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        if !config.enabled {
+            return Ok(None);
+        }
+        if !(0.0..=1.0).contains(&config.sample_ratio) {
+            anyhow::bail!("telemetry.sample_ratio must be between 0.0 and 1.0");
+        }
+
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(&config.endpoint)
+            .build()?;
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                config.sample_ratio,
+            ))))
+            .with_resource(Resource::new([KeyValue::new(
+                "service.name",
+                config.service_name.clone(),
+            )]))
+            .build();
+        let tracer = provider.tracer("synap-server");
+        global::set_tracer_provider(provider);
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        EXPORTING.store(true, std::sync::atomic::Ordering::Relaxed);
+
+        let filter = QUIET.iter().fold(
+            Targets::new().with_default(Level::INFO),
+            |filter, target| filter.with_target(*target, tracing::level_filters::LevelFilter::OFF),
+        );
+        Ok(Some(
+            tracing_opentelemetry::layer()
+                .with_tracer(tracer)
+                .with_filter(filter),
+        ))
+    }
+
+    /// Make `span` a child of the trace in the request's `traceparent` header
+    pub fn set_parent(span: &Span, headers: &HeaderMap) {
+        let context = global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(headers))
+        });
+        span.set_parent(context);
+    }
+
+    /// Flush spans not yet exported
+    pub fn shutdown() {
+        global::shutdown_tracer_provider();
+    }
+
+    struct HeaderExtractor<'a>(&'a HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|name| name.as_str()).collect()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_set_parent_continues_the_callers_trace() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = opentelemetry_sdk::trace::TracerProvider::builder()
+            .build()
+            .tracer("test");
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+                .parse()
+                .unwrap(),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request");
+            set_parent(&span, &headers);
+            let context = span.context();
+            assert_eq!(
+                context.span().span_context().trace_id().to_string(),
+                "4bf92f3577b34da6a3ce929d0e0e4736"
+            );
+        });
+    }
+}
@@ -0,0 +1,234 @@
+//! Cold start warmup.
+//!
+//! Recovery brings every key back into memory, but not what was hot: LRU
+//! recency restarts from the snapshot and the L1 cache, when enabled, starts
+//! empty. A warmup manifest names the keys clients read first, as exact keys
+//! or prefixes:
+//!
+//! ```yaml
+//! keys: [config:app, feature-flags]
+//! prefixes: ["session:", "user:top:"]
+//! ```
+//!
+//! Warming a key marks it recently used (so eviction under memory pressure
+//! does not pick it first) and loads it into the L1 cache. Missing keys are
+//! counted, not created.
+//!
+//! While a warmup is pending the node reports not ready on
+//! `GET /health/ready`, so a load balancer only sends traffic once the hot
+//! keys are primed. The node starts pending when `warmup.manifest` or
+//! `warmup.wait_for_manifest` is set; the first completed warmup, from the
+//! startup manifest or `POST /warmup`, marks it ready.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, bail};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::core::KVStore;
+
+/// Most keys one warmup primes; prefixes stop expanding past it
+pub const MAX_WARMUP_KEYS: usize = 100_000;
+
+/// Keys primed between yields to the runtime
+const WARMUP_BATCH: usize = 1024;
+
+/// Keys to prime, listed exactly or by prefix
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WarmupManifest {
+    pub keys: Vec<String>,
+    pub prefixes: Vec<String>,
+}
+
+impl WarmupManifest {
+    /// Read a manifest from a `.json`, `.yaml` or `.yml` file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading warmup manifest {}", path.display()))?;
+        let parsed = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(anyhow::Error::from),
+            Some("yaml" | "yml") => serde_yaml::from_str(&content).map_err(anyhow::Error::from),
+            _ => bail!(
+                "unsupported warmup manifest {} (expected .json, .yaml or .yml)",
+                path.display()
+            ),
+        };
+        parsed.with_context(|| format!("parsing warmup manifest {}", path.display()))
+    }
+}
+
+/// Outcome of one warmup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmupReport {
+    /// Keys looked up: the listed keys plus those the prefixes matched
+    pub keys_requested: usize,
+    pub keys_loaded: usize,
+    pub keys_missing: usize,
+    /// Total size of the loaded values
+    pub bytes_loaded: u64,
+    /// The manifest named more than [`MAX_WARMUP_KEYS`] keys; the rest were
+    /// skipped
+    pub truncated: bool,
+    pub duration_ms: u64,
+    /// Unix timestamp (seconds)
+    pub completed_at: u64,
+}
+
+/// Readiness gate and the last warmup's report
+pub struct Warmup {
+    ready: AtomicBool,
+    last: Mutex<Option<WarmupReport>>,
+}
+
+impl Default for Warmup {
+    fn default() -> Self {
+        Self {
+            ready: AtomicBool::new(true),
+            last: Mutex::new(None),
+        }
+    }
+}
+
+impl Warmup {
+    /// A node that is ready until [`hold`](Self::hold) is called
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report not ready until the next warmup completes
+    pub fn hold(&self) {
+        self.ready.store(false, Ordering::Release);
+    }
+
+    /// Report ready without warming, e.g. when the manifest cannot be read
+    pub fn mark_ready(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Report of the last completed warmup
+    pub fn last_report(&self) -> Option<WarmupReport> {
+        self.last.lock().clone()
+    }
+
+    /// Prime the manifest's keys, then mark the node ready
+    pub async fn run(&self, kv_store: &KVStore, manifest: &WarmupManifest) -> WarmupReport {
+        let started = Instant::now();
+
+        let mut keys: Vec<String> = manifest
+            .keys
+            .iter()
+            .take(MAX_WARMUP_KEYS)
+            .cloned()
+            .collect();
+        let mut truncated = manifest.keys.len() > MAX_WARMUP_KEYS;
+        for prefix in &manifest.prefixes {
+            let room = MAX_WARMUP_KEYS - keys.len();
+            if room == 0 {
+                truncated = true;
+                break;
+            }
+            // Ask for one more than fits to tell a full prefix from a cut one
+            let mut matched = kv_store
+                .scan(Some(prefix), room + 1)
+                .await
+                .unwrap_or_default();
+            if matched.len() > room {
+                matched.truncate(room);
+                truncated = true;
+            }
+            keys.extend(matched);
+        }
+        keys.sort_unstable();
+        keys.dedup();
+
+        let mut keys_loaded = 0;
+        let mut bytes_loaded = 0u64;
+        for batch in keys.chunks(WARMUP_BATCH) {
+            for key in batch {
+                if let Some(size) = kv_store.prime(key) {
+                    keys_loaded += 1;
+                    bytes_loaded += size as u64;
+                }
+            }
+            tokio::task::yield_now().await;
+        }
+
+        let report = WarmupReport {
+            keys_requested: keys.len(),
+            keys_loaded,
+            keys_missing: keys.len() - keys_loaded,
+            bytes_loaded,
+            truncated,
+            duration_ms: started.elapsed().as_millis() as u64,
+            completed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        info!(
+            "Warmup primed {}/{} keys ({} bytes) in {} ms",
+            report.keys_loaded, report.keys_requested, report.bytes_loaded, report.duration_ms
+        );
+
+        *self.last.lock() = Some(report.clone());
+        self.mark_ready();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::KVConfig;
+
+    #[tokio::test]
+    async fn test_run_primes_keys_and_prefixes() {
+        let kv = KVStore::new(KVConfig::default());
+        kv.set("config", b"dark".to_vec(), None).await.unwrap();
+        kv.set("session:1", b"a".to_vec(), None).await.unwrap();
+        kv.set("session:2", b"bb".to_vec(), None).await.unwrap();
+        kv.set("other", b"x".to_vec(), None).await.unwrap();
+
+        let warmup = Warmup::new();
+        warmup.hold();
+        assert!(!warmup.is_ready());
+
+        let manifest = WarmupManifest {
+            keys: vec!["config".into(), "session:1".into(), "gone".into()],
+            prefixes: vec!["session:".into()],
+        };
+        let report = warmup.run(&kv, &manifest).await;
+        assert_eq!(report.keys_requested, 4);
+        assert_eq!(report.keys_loaded, 3);
+        assert_eq!(report.keys_missing, 1);
+        assert_eq!(report.bytes_loaded, 7);
+        assert!(!report.truncated);
+
+        assert!(warmup.is_ready());
+        assert_eq!(warmup.last_report().unwrap().keys_loaded, 3);
+    }
+
+    #[test]
+    fn test_manifest_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = dir.path().join("warmup.yaml");
+        std::fs::write(&yaml, "keys: [a]\nprefixes: [\"user:\"]\n").unwrap();
+        let manifest = WarmupManifest::load(&yaml).unwrap();
+        assert_eq!(manifest.keys, vec!["a"]);
+        assert_eq!(manifest.prefixes, vec!["user:"]);
+
+        let typo = dir.path().join("warmup.json");
+        std::fs::write(&typo, r#"{"key": ["a"]}"#).unwrap();
+        assert!(WarmupManifest::load(&typo).is_err());
+        assert!(WarmupManifest::load(&dir.path().join("warmup.toml")).is_err());
+    }
+}
@@ -0,0 +1,983 @@
+//! Webhook push subscriptions.
+//!
+//! A webhook takes the messages of a local queue or Pub/Sub topic and POSTs
+//! each one to an HTTPS endpoint, for consumers such as serverless functions
+//! that cannot hold a WebSocket open or poll. Every request carries the
+//! message id, source and attempt number as headers; with a `secret` it is
+//! also signed: `X-Synap-Signature` is `sha256=` followed by the hex
+//! HMAC-SHA256 of `"<X-Synap-Timestamp>.<body>"`.
+//!
+//! Any 2xx answer acknowledges the message. Failures back off exponentially
+//! between `min_backoff_ms` and `max_backoff_ms`:
+//!
+//! - Queue messages are NACKed with requeue after each failed attempt, so
+//!   the queue's `max_retries` bounds the attempts and its dead letter queue
+//!   receives the messages that never got through. Delivery is at least
+//!   once.
+//! - Pub/Sub messages are retried up to `max_attempts` times and then
+//!   published to the `dead_letter_queue`, or dropped without one. Pub/Sub
+//!   delivery stays best effort: a webhook that falls a full subscriber
+//!   buffer behind is disconnected like any slow subscriber and subscribes
+//!   again, missing what was published in between.
+//!
+//! With persistence enabled the webhook definitions and their counters are
+//! checkpointed to `webhooks.json` next to the snapshots, so webhooks resume
+//! after a restart.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, KeyInit, Mac};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::core::pubsub::{Message, SUBSCRIBER_CHANNEL_CAPACITY};
+use crate::core::{PubSubRouter, QueueManager, QueueMessage, SynapError};
+use crate::persistence::{PersistenceLayer, write_atomically};
+use crate::server::AppState;
+
+/// Webhook definitions and counters, kept in the snapshot directory
+pub const WEBHOOKS_FILE: &str = "webhooks.json";
+
+/// Wait before polling an empty queue again
+const IDLE_POLL: Duration = Duration::from_millis(100);
+/// Counters are written at most this often
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A webhook as created by `webhook.create`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSpec {
+    pub name: String,
+    /// Local queue whose messages are pushed (set this or `topic`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue: Option<String>,
+    /// Pub/Sub topic or wildcard pattern whose messages are pushed (set this
+    /// or `queue`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// Endpoint the messages are POSTed to; `https://`, or `http://` on a
+    /// loopback host
+    pub url: String,
+    /// Key of the `X-Synap-Signature` HMAC; requests are unsigned when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Attempts per Pub/Sub message before it is dead-lettered. Queue
+    /// messages follow the queue's `max_retries`.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Local queue that receives the Pub/Sub messages that could not be
+    /// delivered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_letter_queue: Option<String>,
+    /// Timeout of one request
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Wait after the first failed attempt
+    #[serde(default = "default_min_backoff_ms")]
+    pub min_backoff_ms: u64,
+    /// Waits double after each failed attempt up to this
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_min_backoff_ms() -> u64 {
+    500
+}
+
+fn default_max_backoff_ms() -> u64 {
+    30_000
+}
+
+/// Counters of one webhook, checkpointed with its definition
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookProgress {
+    /// Messages the endpoint acknowledged
+    pub delivered: u64,
+    /// Requests that failed or got a non-2xx answer
+    pub failed_attempts: u64,
+    /// Messages given up on and moved to a dead letter queue
+    pub dead_lettered: u64,
+    /// Pub/Sub messages given up on without a `dead_letter_queue`
+    pub dropped: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Unix timestamp (milliseconds) of the last message delivered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_delivered_at: Option<u64>,
+}
+
+/// `webhook.list` entry
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookStatus {
+    #[serde(flatten)]
+    pub spec: WebhookSpec,
+    #[serde(flatten)]
+    pub progress: WebhookProgress,
+}
+
+/// One line of the checkpoint file
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    spec: WebhookSpec,
+    progress: WebhookProgress,
+}
+
+struct Entry {
+    spec: WebhookSpec,
+    progress: Arc<Mutex<WebhookProgress>>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// What a webhook needs from the server it runs on
+#[derive(Clone)]
+pub struct WebhookContext {
+    queue_manager: Arc<QueueManager>,
+    pubsub_router: Option<Arc<PubSubRouter>>,
+    persistence: Option<Arc<PersistenceLayer>>,
+    http: reqwest::Client,
+}
+
+impl WebhookContext {
+    pub fn new(state: &AppState) -> Result<Self, SynapError> {
+        let queue_manager = state
+            .queue_manager()
+            .cloned()
+            .ok_or_else(|| SynapError::InvalidRequest("Queue system disabled".to_string()))?;
+
+        Ok(Self {
+            queue_manager,
+            pubsub_router: state.pubsub_router.clone(),
+            persistence: state.persistence.clone(),
+            http: reqwest::Client::new(),
+        })
+    }
+}
+
+/// Running webhooks, optionally checkpointed to a file
+#[derive(Default)]
+pub struct WebhookManager {
+    webhooks: Mutex<HashMap<String, Entry>>,
+    path: Option<PathBuf>,
+}
+
+impl WebhookManager {
+    /// In-memory manager; webhooks stop for good on restart
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Manager checkpointed at `path`, loading the webhooks saved there.
+    /// They start on [`resume`](Self::resume).
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, SynapError> {
+        let path = path.into();
+        let saved: Vec<Saved> = match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| {
+                SynapError::SerializationError(format!("{}: {}", path.display(), e))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(SynapError::IoError(e.to_string())),
+        };
+
+        info!("Loaded {} webhooks from {}", saved.len(), path.display());
+        let webhooks = saved
+            .into_iter()
+            .map(|saved| {
+                let entry = Entry {
+                    spec: saved.spec,
+                    progress: Arc::new(Mutex::new(saved.progress)),
+                    task: None,
+                };
+                (entry.spec.name.clone(), entry)
+            })
+            .collect();
+        Ok(Self {
+            webhooks: Mutex::new(webhooks),
+            path: Some(path),
+        })
+    }
+
+    /// Start every loaded webhook
+    pub fn resume(self: &Arc<Self>, context: WebhookContext) {
+        let mut webhooks = self.webhooks.lock();
+        for entry in webhooks.values_mut() {
+            if entry.task.is_none() {
+                entry.task = Some(self.spawn(entry, context.clone()));
+            }
+        }
+    }
+
+    /// Validate and start a webhook (`webhook.create`)
+    pub async fn create(
+        self: &Arc<Self>,
+        spec: WebhookSpec,
+        context: WebhookContext,
+    ) -> Result<WebhookStatus, SynapError> {
+        validate(&spec, &context).await?;
+
+        let mut webhooks = self.webhooks.lock();
+        if webhooks.contains_key(&spec.name) {
+            return Err(SynapError::InvalidRequest(format!(
+                "Webhook '{}' already exists",
+                spec.name
+            )));
+        }
+        let mut entry = Entry {
+            spec,
+            progress: Arc::default(),
+            task: None,
+        };
+        entry.task = Some(self.spawn(&entry, context));
+        let status = status(&entry);
+        info!(
+            "Webhook '{}' started: {} -> {}",
+            entry.spec.name,
+            source_label(&entry.spec),
+            entry.spec.url
+        );
+        webhooks.insert(entry.spec.name.clone(), entry);
+        self.persist(&webhooks);
+        Ok(status)
+    }
+
+    /// Every webhook, sorted by name, with secrets redacted (`webhook.list`)
+    pub fn list(&self) -> Vec<WebhookStatus> {
+        let mut list: Vec<WebhookStatus> = self.webhooks.lock().values().map(status).collect();
+        list.sort_by(|a, b| a.spec.name.cmp(&b.spec.name));
+        list
+    }
+
+    /// Stop and forget a webhook (`webhook.delete`). A queue message it was
+    /// delivering stays unacked and is redelivered after the queue's ACK
+    /// deadline.
+    pub fn delete(&self, name: &str) -> bool {
+        let mut webhooks = self.webhooks.lock();
+        let Some(entry) = webhooks.remove(name) else {
+            return false;
+        };
+        if let Some(task) = entry.task {
+            task.abort();
+        }
+        info!("Webhook '{}' deleted", name);
+        self.persist(&webhooks);
+        true
+    }
+
+    fn spawn(self: &Arc<Self>, entry: &Entry, context: WebhookContext) -> JoinHandle<()> {
+        let worker = Worker {
+            consumer_id: format!("webhook:{}", entry.spec.name),
+            backoff: Backoff::new(&entry.spec),
+            spec: entry.spec.clone(),
+            progress: entry.progress.clone(),
+            manager: Arc::downgrade(self),
+            context,
+            checkpointed: entry.progress.lock().clone(),
+            last_checkpoint: Instant::now(),
+        };
+        tokio::spawn(worker.run())
+    }
+
+    fn checkpoint(&self) {
+        self.persist(&self.webhooks.lock());
+    }
+
+    /// Write every webhook to the backing file, if any. A failed write is
+    /// logged; the webhooks keep running.
+    fn persist(&self, webhooks: &HashMap<String, Entry>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let mut saved: Vec<Saved> = webhooks
+            .values()
+            .map(|entry| Saved {
+                spec: entry.spec.clone(),
+                progress: entry.progress.lock().clone(),
+            })
+            .collect();
+        saved.sort_by(|a, b| a.spec.name.cmp(&b.spec.name));
+        let result = serde_json::to_vec_pretty(&saved)
+            .map_err(|e| e.to_string())
+            .and_then(|data| write_atomically(path, &data).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to checkpoint webhooks to {}: {}", path.display(), e);
+        }
+    }
+}
+
+impl Drop for WebhookManager {
+    fn drop(&mut self) {
+        for entry in self.webhooks.get_mut().values_mut() {
+            if let Some(task) = entry.task.take() {
+                task.abort();
+            }
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 of `"<timestamp>.<body>"` under `secret`, as
+/// sent in `X-Synap-Signature`
+pub fn signature(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn status(entry: &Entry) -> WebhookStatus {
+    let mut spec = entry.spec.clone();
+    if spec.secret.is_some() {
+        spec.secret = Some("<redacted>".to_string());
+    }
+    WebhookStatus {
+        spec,
+        progress: entry.progress.lock().clone(),
+    }
+}
+
+fn source_label(spec: &WebhookSpec) -> String {
+    match (&spec.queue, &spec.topic) {
+        (Some(queue), _) => format!("queue:{}", queue),
+        (None, Some(topic)) => format!("topic:{}", topic),
+        (None, None) => String::new(),
+    }
+}
+
+async fn validate(spec: &WebhookSpec, context: &WebhookContext) -> Result<(), SynapError> {
+    let invalid = |message: &str| Err(SynapError::InvalidRequest(message.to_string()));
+
+    if spec.name.is_empty() {
+        return invalid("Webhook name must not be empty");
+    }
+    let url = reqwest::Url::parse(&spec.url)
+        .map_err(|e| SynapError::InvalidRequest(format!("Invalid webhook url: {}", e)))?;
+    let loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    if !(url.scheme() == "https" || (url.scheme() == "http" && loopback)) {
+        return invalid("Webhook url must be https:// (http:// only on localhost)");
+    }
+    if spec.secret.as_deref() == Some("") {
+        return invalid("secret must not be empty");
+    }
+    if spec.max_attempts == 0 || spec.timeout_ms == 0 {
+        return invalid("max_attempts and timeout_ms must be at least 1");
+    }
+    if spec.min_backoff_ms == 0 || spec.min_backoff_ms > spec.max_backoff_ms {
+        return invalid("min_backoff_ms must be at least 1 and at most max_backoff_ms");
+    }
+
+    match (&spec.queue, &spec.topic) {
+        (Some(queue), None) => {
+            if spec.dead_letter_queue.is_some() {
+                return invalid(
+                    "Queue webhooks dead-letter to the queue's own DLQ; dead_letter_queue is for topics",
+                );
+            }
+            context.queue_manager.stats(queue).await?;
+        }
+        (None, Some(topic)) => {
+            if topic.is_empty() {
+                return invalid("topic must not be empty");
+            }
+            if context.pubsub_router.is_none() {
+                return invalid("Pub/Sub is disabled on this server");
+            }
+            if let Some(queue) = &spec.dead_letter_queue {
+                context.queue_manager.stats(queue).await?;
+            }
+        }
+        _ => return invalid("Set exactly one of queue and topic"),
+    }
+    Ok(())
+}
+
+/// Exponential wait between failed attempts
+struct Backoff {
+    min: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    fn new(spec: &WebhookSpec) -> Self {
+        let min = Duration::from_millis(spec.min_backoff_ms);
+        Self {
+            min,
+            max: Duration::from_millis(spec.max_backoff_ms),
+            next: min,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.next = self.min;
+    }
+
+    async fn wait(&mut self) {
+        tokio::time::sleep(self.next).await;
+        self.next = (self.next * 2).min(self.max);
+    }
+}
+
+/// A Pub/Sub subscription feeding a webhook; unsubscribes when dropped
+struct Subscription {
+    router: Arc<PubSubRouter>,
+    subscriber_id: String,
+    receiver: mpsc::Receiver<Message>,
+}
+
+impl Subscription {
+    fn open(router: &Arc<PubSubRouter>, topic: &str) -> Result<Self, SynapError> {
+        let subscriber_id = router.subscribe(vec![topic.to_string()])?.subscriber_id;
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+        router.register_connection(subscriber_id.clone(), sender);
+        Ok(Self {
+            router: router.clone(),
+            subscriber_id,
+            receiver,
+        })
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.router.unregister_connection(&self.subscriber_id);
+        let _ = self.router.unsubscribe(&self.subscriber_id, None);
+    }
+}
+
+struct Worker {
+    spec: WebhookSpec,
+    consumer_id: String,
+    progress: Arc<Mutex<WebhookProgress>>,
+    manager: Weak<WebhookManager>,
+    context: WebhookContext,
+    backoff: Backoff,
+    checkpointed: WebhookProgress,
+    last_checkpoint: Instant,
+}
+
+impl Worker {
+    async fn run(mut self) {
+        match (self.spec.queue.clone(), self.spec.topic.clone()) {
+            (Some(queue), _) => self.run_queue(&queue).await,
+            (None, Some(topic)) => self.run_topic(&topic).await,
+            (None, None) => {}
+        }
+    }
+
+    async fn run_queue(&mut self, queue: &str) {
+        loop {
+            self.maybe_checkpoint();
+            let queues = &self.context.queue_manager;
+            let message = match queues.consume(queue, &self.consumer_id).await {
+                Ok(Some(message)) => message,
+                Ok(None) => {
+                    tokio::time::sleep(IDLE_POLL).await;
+                    continue;
+                }
+                Err(e) => {
+                    self.record_error(e.to_string());
+                    self.backoff.wait().await;
+                    continue;
+                }
+            };
+
+            let attempt = message.retry_count + 1;
+            let result = self
+                .post(
+                    &format!("queue:{}", queue),
+                    &message.id,
+                    attempt,
+                    "application/octet-stream",
+                    message.payload.to_vec(),
+                )
+                .await;
+            match result {
+                Ok(()) => {
+                    self.settle(queue, &message).await;
+                    self.record_delivery();
+                    self.backoff.reset();
+                }
+                Err(e) => {
+                    warn!(
+                        "Webhook '{}' failed to deliver {} (attempt {}): {}",
+                        self.spec.name, message.id, attempt, e
+                    );
+                    self.record_error(e);
+                    self.give_back(queue, &message).await;
+                    self.backoff.wait().await;
+                }
+            }
+        }
+    }
+
+    async fn run_topic(&mut self, topic: &str) {
+        let Some(router) = self.context.pubsub_router.clone() else {
+            self.record_error("Pub/Sub is disabled on this server".to_string());
+            return;
+        };
+        loop {
+            let mut subscription = match Subscription::open(&router, topic) {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    self.record_error(e.to_string());
+                    self.backoff.wait().await;
+                    continue;
+                }
+            };
+            loop {
+                self.maybe_checkpoint();
+                let message =
+                    match tokio::time::timeout(CHECKPOINT_INTERVAL, subscription.receiver.recv())
+                        .await
+                    {
+                        Ok(Some(message)) => message,
+                        Ok(None) => break,
+                        Err(_) => continue,
+                    };
+                self.deliver_topic_message(message).await;
+            }
+            warn!(
+                "Webhook '{}' fell behind on topic '{}' and was disconnected; subscribing again",
+                self.spec.name, topic
+            );
+        }
+    }
+
+    /// Deliver one Pub/Sub message, retrying up to `max_attempts` times
+    async fn deliver_topic_message(&mut self, message: Message) {
+        let body = serde_json::to_vec(&message.payload).unwrap_or_default();
+        let source = format!("topic:{}", message.topic);
+        for attempt in 1..=self.spec.max_attempts {
+            match self
+                .post(
+                    &source,
+                    &message.id,
+                    attempt,
+                    "application/json",
+                    body.clone(),
+                )
+                .await
+            {
+                Ok(()) => {
+                    self.record_delivery();
+                    self.backoff.reset();
+                    return;
+                }
+                Err(e) => {
+                    warn!(
+                        "Webhook '{}' failed to deliver {} (attempt {}): {}",
+                        self.spec.name, message.id, attempt, e
+                    );
+                    self.record_error(e);
+                    self.backoff.wait().await;
+                }
+            }
+        }
+        self.backoff.reset();
+        self.dead_letter(&message, body).await;
+    }
+
+    /// POST one message; any 2xx answer is a delivery
+    async fn post(
+        &self,
+        source: &str,
+        message_id: &str,
+        attempt: u32,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<(), String> {
+        let timestamp = unix_millis() / 1000;
+        let mut request = self
+            .context
+            .http
+            .post(&self.spec.url)
+            .timeout(Duration::from_millis(self.spec.timeout_ms))
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .header("X-Synap-Webhook", &self.spec.name)
+            .header("X-Synap-Source", source)
+            .header("X-Synap-Message-Id", message_id)
+            .header("X-Synap-Attempt", attempt.to_string())
+            .header("X-Synap-Timestamp", timestamp.to_string());
+        if let Some(secret) = &self.spec.secret {
+            request = request.header("X-Synap-Signature", signature(secret, timestamp, &body));
+        }
+
+        let response = request.body(body).send().await.map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("{} answered {}", self.spec.url, response.status()))
+        }
+    }
+
+    /// The endpoint took the message: remove it from the queue
+    async fn settle(&self, queue: &str, message: &QueueMessage) {
+        if let Err(e) = self.context.queue_manager.ack(queue, &message.id).await {
+            warn!(
+                "Webhook '{}' could not ACK {}: {}",
+                self.spec.name, message.id, e
+            );
+            return;
+        }
+        if let Some(ref persistence) = self.context.persistence
+            && let Err(e) = persistence
+                .log_queue_ack(queue.to_string(), message.id.clone())
+                .await
+        {
+            warn!("Failed to log webhook ACK to WAL: {}", e);
+        }
+    }
+
+    /// The delivery failed: requeue the message, or let the queue move it
+    /// to its dead letter queue once it is out of retries
+    async fn give_back(&self, queue: &str, message: &QueueMessage) {
+        if let Err(e) = self
+            .context
+            .queue_manager
+            .nack(queue, &message.id, true)
+            .await
+        {
+            warn!(
+                "Webhook '{}' could not NACK {}: {}",
+                self.spec.name, message.id, e
+            );
+            return;
+        }
+        if message.retry_count >= message.max_retries {
+            self.progress.lock().dead_lettered += 1;
+        }
+        if let Some(ref persistence) = self.context.persistence
+            && let Err(e) = persistence
+                .log_queue_nack(queue.to_string(), message.id.clone(), true)
+                .await
+        {
+            warn!("Failed to log webhook NACK to WAL: {}", e);
+        }
+    }
+
+    /// Give up on a Pub/Sub message
+    async fn dead_letter(&self, message: &Message, body: Vec<u8>) {
+        let Some(queue) = &self.spec.dead_letter_queue else {
+            warn!(
+                "Webhook '{}' dropped {} after {} attempts",
+                self.spec.name, message.id, self.spec.max_attempts
+            );
+            self.progress.lock().dropped += 1;
+            return;
+        };
+
+        let published = self
+            .context
+            .queue_manager
+            .publish_with_message(queue, body, None, None)
+            .await;
+        match published {
+            Ok(dead) => {
+                self.progress.lock().dead_lettered += 1;
+                if let Some(ref persistence) = self.context.persistence
+                    && let Err(e) = persistence.log_queue_publish(queue.clone(), dead).await
+                {
+                    warn!("Failed to log webhook dead letter to WAL: {}", e);
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Webhook '{}' lost {}: dead letter queue '{}' refused it: {}",
+                    self.spec.name, message.id, queue, e
+                );
+                self.progress.lock().dropped += 1;
+            }
+        }
+    }
+
+    fn record_delivery(&self) {
+        let mut progress = self.progress.lock();
+        progress.delivered += 1;
+        progress.last_delivered_at = Some(unix_millis());
+    }
+
+    fn record_error(&self, error: String) {
+        let mut progress = self.progress.lock();
+        progress.failed_attempts += 1;
+        progress.last_error = Some(error);
+    }
+
+    fn maybe_checkpoint(&mut self) {
+        if self.last_checkpoint.elapsed() < CHECKPOINT_INTERVAL {
+            return;
+        }
+        let progress = self.progress.lock().clone();
+        if progress != self.checkpointed {
+            if let Some(manager) = self.manager.upgrade() {
+                manager.checkpoint();
+            }
+            self.checkpointed = progress;
+        }
+        self.last_checkpoint = Instant::now();
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::QueueConfig;
+    use axum::http::{HeaderMap, StatusCode};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Requests an endpoint received, as (headers, body)
+    type Received = Arc<Mutex<Vec<(HeaderMap, Vec<u8>)>>>;
+
+    /// An endpoint on a local port that answers 500 to the first `failures`
+    /// requests and 200 afterwards
+    async fn endpoint(failures: usize) -> (String, Received) {
+        let received: Received = Arc::default();
+        let remaining = Arc::new(AtomicUsize::new(failures));
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post({
+                let received = received.clone();
+                move |headers: HeaderMap, body: axum::body::Bytes| {
+                    received.lock().push((headers, body.to_vec()));
+                    let fail = remaining
+                        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                        .is_ok();
+                    async move {
+                        if fail {
+                            StatusCode::INTERNAL_SERVER_ERROR
+                        } else {
+                            StatusCode::OK
+                        }
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (url, received)
+    }
+
+    fn context(queues: &Arc<QueueManager>) -> WebhookContext {
+        WebhookContext {
+            queue_manager: queues.clone(),
+            pubsub_router: Some(Arc::new(PubSubRouter::new())),
+            persistence: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn spec(name: &str, url: &str) -> WebhookSpec {
+        WebhookSpec {
+            name: name.to_string(),
+            queue: None,
+            topic: None,
+            url: url.to_string(),
+            secret: None,
+            max_attempts: 2,
+            dead_letter_queue: None,
+            timeout_ms: 1_000,
+            min_backoff_ms: 10,
+            max_backoff_ms: 20,
+        }
+    }
+
+    async fn queues(names: &[&str]) -> Arc<QueueManager> {
+        let queues = Arc::new(QueueManager::new(QueueConfig::default()));
+        for name in names {
+            queues.create_queue(name, None).await.unwrap();
+        }
+        queues
+    }
+
+    async fn wait_for(
+        manager: &WebhookManager,
+        name: &str,
+        done: impl Fn(&WebhookProgress) -> bool,
+    ) {
+        for _ in 0..300 {
+            let list = manager.list();
+            let status = list.iter().find(|s| s.spec.name == name).unwrap();
+            if done(&status.progress) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("webhook '{}' did not get there: {:?}", name, manager.list());
+    }
+
+    #[test]
+    fn test_signature_is_hmac_of_timestamp_and_body() {
+        // HMAC-SHA256("key", "1700000000.hello")
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"key").unwrap();
+        mac.update(b"1700000000.hello");
+        let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert_eq!(signature("key", 1_700_000_000, b"hello"), expected);
+        assert_ne!(signature("other", 1_700_000_000, b"hello"), expected);
+    }
+
+    #[tokio::test]
+    async fn test_queue_messages_are_pushed_signed_and_acked() {
+        let queues = queues(&["orders"]).await;
+        queues
+            .publish("orders", b"order-1".to_vec(), None, None)
+            .await
+            .unwrap();
+        let (url, received) = endpoint(0).await;
+
+        let manager = Arc::new(WebhookManager::new());
+        let mut hook = spec("orders-hook", &url);
+        hook.queue = Some("orders".to_string());
+        hook.secret = Some("s3cret".to_string());
+        let status = manager.create(hook, context(&queues)).await.unwrap();
+        assert_eq!(status.spec.secret.as_deref(), Some("<redacted>"));
+        wait_for(&manager, "orders-hook", |p| p.delivered == 1).await;
+
+        let (headers, body) = received.lock()[0].clone();
+        assert_eq!(body, b"order-1");
+        assert_eq!(headers["x-synap-source"], "queue:orders");
+        assert_eq!(headers["x-synap-attempt"], "1");
+        let timestamp: u64 = headers["x-synap-timestamp"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            headers["x-synap-signature"],
+            signature("s3cret", timestamp, b"order-1").as_str()
+        );
+
+        let stats = queues.stats("orders").await.unwrap();
+        assert_eq!((stats.depth, stats.acked), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_failing_queue_deliveries_end_in_the_queue_dlq() {
+        let queues = queues(&["jobs"]).await;
+        queues
+            .publish("jobs", b"job".to_vec(), None, Some(1))
+            .await
+            .unwrap();
+        let (url, received) = endpoint(usize::MAX).await;
+
+        let manager = Arc::new(WebhookManager::new());
+        let mut hook = spec("jobs-hook", &url);
+        hook.queue = Some("jobs".to_string());
+        manager.create(hook, context(&queues)).await.unwrap();
+        wait_for(&manager, "jobs-hook", |p| p.dead_lettered == 1).await;
+
+        let attempts: Vec<_> = received
+            .lock()
+            .iter()
+            .map(|(headers, _)| headers["x-synap-attempt"].to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(attempts, vec!["1", "2"]);
+        let dead = queues.pop_dead_letter("jobs").await.unwrap().unwrap();
+        assert_eq!(dead.payload.to_vec(), b"job");
+    }
+
+    /// Create a webhook on `topic` and wait until it subscribed
+    async fn topic_hook(
+        manager: &Arc<WebhookManager>,
+        context: &WebhookContext,
+        mut hook: WebhookSpec,
+        topic: &str,
+    ) {
+        hook.topic = Some(topic.to_string());
+        manager.create(hook, context.clone()).await.unwrap();
+        let router = context.pubsub_router.as_ref().unwrap();
+        for _ in 0..100 {
+            if router.has_subscriber(topic) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("webhook never subscribed to '{}'", topic);
+    }
+
+    #[tokio::test]
+    async fn test_topic_messages_are_retried() {
+        let queues = queues(&[]).await;
+        let context = context(&queues);
+        let (url, received) = endpoint(1).await;
+
+        let manager = Arc::new(WebhookManager::new());
+        topic_hook(&manager, &context, spec("alerts", &url), "alerts.cpu").await;
+        let router = context.pubsub_router.as_ref().unwrap();
+        router
+            .publish("alerts.cpu", serde_json::json!({"load": 0.9}), None)
+            .unwrap();
+        wait_for(&manager, "alerts", |p| p.delivered == 1).await;
+
+        let received = received.lock().clone();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1].0["x-synap-attempt"], "2");
+        assert_eq!(received[1].0["x-synap-source"], "topic:alerts.cpu");
+        assert_eq!(received[1].1, br#"{"load":0.9}"#);
+    }
+
+    #[tokio::test]
+    async fn test_undeliverable_topic_messages_are_dead_lettered() {
+        let queues = queues(&["alerts-dlq"]).await;
+        let context = context(&queues);
+        let (url, received) = endpoint(usize::MAX).await;
+
+        let manager = Arc::new(WebhookManager::new());
+        let mut hook = spec("alerts", &url);
+        hook.dead_letter_queue = Some("alerts-dlq".to_string());
+        topic_hook(&manager, &context, hook, "alerts.*").await;
+        let router = context.pubsub_router.as_ref().unwrap();
+        router
+            .publish("alerts.disk", serde_json::json!("full"), None)
+            .unwrap();
+        wait_for(&manager, "alerts", |p| p.dead_lettered == 1).await;
+
+        assert_eq!(received.lock().len(), 2);
+        let dead = queues.consume("alerts-dlq", "test").await.unwrap().unwrap();
+        assert_eq!(dead.payload.to_vec(), br#""full""#);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_webhooks_are_rejected() {
+        let queues = queues(&["q"]).await;
+        let manager = Arc::new(WebhookManager::new());
+        let mut plain_http = spec("remote", "http://example.com/hook");
+        plain_http.queue = Some("q".to_string());
+        let neither = spec("neither", "https://example.com/hook");
+        let mut queue_dlq = spec("queue-dlq", "https://example.com/hook");
+        queue_dlq.queue = Some("q".to_string());
+        queue_dlq.dead_letter_queue = Some("q".to_string());
+        let mut missing = spec("missing", "https://example.com/hook");
+        missing.queue = Some("nope".to_string());
+
+        for bad in [plain_http, neither, queue_dlq, missing] {
+            assert!(manager.create(bad, context(&queues)).await.is_err());
+        }
+        assert!(manager.list().is_empty());
+    }
+}
@@ -6,7 +6,8 @@ use synap_server::core::{
     BitmapStore, GeospatialStore, HashStore, HyperLogLogStore, ListStore, SetStore, SortedSetStore,
 };
 use synap_server::monitoring::{ClientListManager, MonitoringManager};
-use synap_server::{AppState, KVStore, ScriptManager};
+use synap_server::{AppState, KVStore, ScriptManager, create_router};
+use tokio::net::TcpListener;
 
 /// Create a default AppState for testing
 pub fn create_test_app_state_with_stores(
@@ -64,5 +65,43 @@ pub fn create_test_app_state_with_stores(
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     }
 }
+
+/// Serve `state` over HTTP on a random local port, with rate limiting off and
+/// no auth, returning the base URL
+#[allow(dead_code)] // not every test file that includes this module serves HTTP
+pub async fn spawn_server_with_state(state: AppState) -> String {
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(synap_server::auth::UserManager::new()),
+        Arc::new(synap_server::auth::ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    url
+}
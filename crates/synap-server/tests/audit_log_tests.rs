@@ -0,0 +1,189 @@
+//! Audit log: authentication events, user management and admin operations
+//! are recorded, writes only with `log_writes`, and entries reach the audit
+//! file and stream room.
+
+mod app_state_helper;
+
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, AuditLogManager, UserManager};
+use synap_server::config::AuditConfig;
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::monitoring::MonitoringManager;
+use synap_server::{KVConfig, KVStore, StreamConfig, StreamManager, create_router};
+use tokio::net::TcpListener;
+
+/// Start a server that requires authentication, with an admin user `root`
+/// and the audit log built from `config`
+async fn spawn_server(config: AuditConfig, streams: Option<Arc<StreamManager>>) -> String {
+    let user_manager = Arc::new(UserManager::new());
+    user_manager
+        .create_user("root", "root-password-1", true)
+        .unwrap();
+
+    let kv_store = Arc::new(KVStore::new(KVConfig::default()));
+    let hash_store = Arc::new(HashStore::new());
+    let list_store = Arc::new(ListStore::new());
+    let set_store = Arc::new(SetStore::new());
+    let sorted_set_store = Arc::new(SortedSetStore::new());
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        kv_store.clone(),
+        hash_store.clone(),
+        list_store.clone(),
+        set_store.clone(),
+        sorted_set_store.clone(),
+    );
+
+    let mut audit_log = AuditLogManager::from_config(&config).unwrap();
+    if let (Some(streams), Some(room)) = (streams, config.stream_room) {
+        audit_log = audit_log.with_stream(streams, room);
+    }
+    state.monitoring = Arc::new(
+        MonitoringManager::new(
+            kv_store,
+            hash_store,
+            list_store,
+            set_store,
+            sorted_set_store,
+        )
+        .with_audit_log(Arc::new(audit_log)),
+    );
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        user_manager,
+        Arc::new(ApiKeyManager::new()),
+        true,
+        true,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    url
+}
+
+async fn command(client: &Client, url: &str, command: &str, payload: Value) {
+    let response = client
+        .post(format!("{url}/api/v1/command"))
+        .basic_auth("root", Some("root-password-1"))
+        .json(&json!({"command": command, "request_id": "audit", "payload": payload}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+async fn audit(client: &Client, url: &str, query: &str) -> Value {
+    client
+        .get(format!("{url}/audit?{query}"))
+        .basic_auth("root", Some("root-password-1"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_auth_events_and_admin_operations_are_audited() {
+    let url = spawn_server(AuditConfig::default(), None).await;
+    let client = Client::new();
+
+    // Failed login, through the credentials of a request
+    let response = client
+        .get(format!("{url}/audit"))
+        .basic_auth("root", Some("wrong-password"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let failures = audit(&client, &url, "event_type=LoginFailure").await;
+    assert_eq!(failures["count"], 1);
+
+    // User management
+    let response = client
+        .post(format!("{url}/auth/users"))
+        .basic_auth("root", Some("root-password-1"))
+        .json(&json!({"username": "alice", "password": "alice-password-1"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let created = audit(&client, &url, "event_type=UserCreated").await;
+    assert_eq!(created["entries"][0]["username"], "root");
+    assert_eq!(created["entries"][0]["resource"], "user:alice");
+
+    // Admin command; plain writes are not recorded by default
+    command(&client, &url, "kv.set", json!({"key": "a", "value": "1"})).await;
+    command(&client, &url, "kv.flushall", json!({})).await;
+    let admin = audit(&client, &url, "event_type=AdminOperation").await;
+    assert_eq!(admin["count"], 1);
+    assert_eq!(admin["entries"][0]["action"], "kv.flushall");
+    let writes = audit(&client, &url, "event_type=Write").await;
+    assert_eq!(writes["count"], 0);
+
+    let by_user = audit(&client, &url, "username=root").await;
+    assert_eq!(by_user["count"], 2);
+}
+
+#[tokio::test]
+async fn test_writes_reach_the_file_and_stream() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let streams = Arc::new(StreamManager::new(StreamConfig::default()));
+    let url = spawn_server(
+        AuditConfig {
+            log_writes: true,
+            file: Some(path.clone()),
+            stream_room: Some("audit".to_string()),
+            ..AuditConfig::default()
+        },
+        Some(streams.clone()),
+    )
+    .await;
+    let client = Client::new();
+
+    command(&client, &url, "kv.set", json!({"key": "a", "value": "1"})).await;
+    command(&client, &url, "kv.get", json!({"key": "a"})).await;
+    let response = client
+        .post(format!("{url}/kv/set"))
+        .basic_auth("root", Some("root-password-1"))
+        .json(&json!({"key": "b", "value": "2"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let lines: Vec<Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["event_type"], "Write");
+    assert_eq!(lines[0]["action"], "kv.set");
+    assert_eq!(lines[0]["resource"], "kv:a");
+    assert_eq!(lines[1]["action"], "POST /kv/set");
+    assert_eq!(lines[1]["success"], true);
+
+    let events = streams.consume("audit", "test", 0, 10).await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event, "audit");
+}
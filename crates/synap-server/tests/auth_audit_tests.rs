@@ -187,3 +187,30 @@ async fn test_audit_log_clear() {
     manager.clear().await;
     assert_eq!(manager.len().await, 0);
 }
+
+#[tokio::test]
+async fn test_audit_log_appends_to_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let config = synap_server::config::AuditConfig {
+        file: Some(path.clone()),
+        ..Default::default()
+    };
+
+    // Entries from an earlier run are kept
+    for _ in 0..2 {
+        let manager = AuditLogManager::from_config(&config).unwrap();
+        assert!(!manager.logs_writes());
+        manager
+            .log(AuditLogEntry::login_success(
+                "testuser".to_string(),
+                "127.0.0.1".to_string(),
+            ))
+            .await;
+    }
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 2);
+    let entry: AuditLogEntry = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+    assert_eq!(entry.event_type, AuthEventType::LoginSuccess);
+}
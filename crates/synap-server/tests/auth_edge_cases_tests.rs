@@ -108,6 +108,14 @@ async fn spawn_test_server_with_auth(
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     let router = create_router(
@@ -116,6 +124,7 @@ async fn spawn_test_server_with_auth(
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         McpConfig::default(),
         user_manager.clone(),
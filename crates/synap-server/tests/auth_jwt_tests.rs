@@ -0,0 +1,220 @@
+//! JWT login tokens: `POST /auth/login` issues an access token and a refresh
+//! token, the access token authenticates later requests, and
+//! `POST /auth/refresh` rotates the pair.
+
+mod app_state_helper;
+
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, TokenManager, UserManager};
+use synap_server::config::JwtConfig;
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+/// Start a server that requires authentication, with users `alice` (admin)
+/// and `bob`
+async fn spawn_server() -> (String, Arc<UserManager>) {
+    let user_manager = Arc::new(
+        UserManager::new().with_tokens(TokenManager::new(&JwtConfig {
+            secret: "integration-test-secret".to_string(),
+            ..Default::default()
+        })),
+    );
+    user_manager
+        .create_user("alice", "alice-password-1", true)
+        .unwrap();
+    user_manager
+        .create_user("bob", "bob-password-1", false)
+        .unwrap();
+
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        user_manager.clone(),
+        Arc::new(ApiKeyManager::new()),
+        true,
+        true,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    (url, user_manager)
+}
+
+async fn login(client: &Client, url: &str, username: &str, password: &str) -> Value {
+    client
+        .post(format!("{url}/auth/login"))
+        .json(&json!({"username": username, "password": password}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+async fn stats_status(client: &Client, url: &str, token: &str) -> StatusCode {
+    client
+        .get(format!("{url}/kv/stats"))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_login_issues_tokens_that_authenticate() {
+    let (url, _) = spawn_server().await;
+    let client = Client::new();
+
+    // Login itself needs no credentials
+    let session = login(&client, &url, "alice", "alice-password-1").await;
+    assert_eq!(session["success"], true);
+    assert_eq!(session["token_type"], "Bearer");
+    assert_eq!(session["expires_in"], 900);
+    let access_token = session["access_token"].as_str().unwrap();
+    assert!(
+        session["refresh_token"]
+            .as_str()
+            .unwrap()
+            .starts_with("rt_")
+    );
+
+    assert_eq!(
+        stats_status(&client, &url, access_token).await,
+        StatusCode::OK
+    );
+    let me: Value = client
+        .get(format!("{url}/auth/me"))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(me["username"], "alice");
+    assert_eq!(me["is_admin"], true);
+
+    // No token, or a tampered one, is rejected
+    let response = client.get(format!("{url}/kv/stats")).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let tampered = format!("{}x", access_token);
+    assert_eq!(
+        stats_status(&client, &url, &tampered).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    // Wrong password: no tokens
+    let failed = login(&client, &url, "alice", "wrong-password").await;
+    assert_eq!(failed["success"], false);
+    assert!(failed.get("access_token").is_none());
+}
+
+#[tokio::test]
+async fn test_refresh_rotates_and_logout_revokes() {
+    let (url, _) = spawn_server().await;
+    let client = Client::new();
+    let session = login(&client, &url, "alice", "alice-password-1").await;
+    let refresh_token = session["refresh_token"].as_str().unwrap();
+
+    let response = client
+        .post(format!("{url}/auth/refresh"))
+        .json(&json!({"refresh_token": refresh_token}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let rotated: Value = response.json().await.unwrap();
+    let new_refresh = rotated["refresh_token"].as_str().unwrap();
+    assert_ne!(new_refresh, refresh_token);
+    assert_eq!(
+        stats_status(&client, &url, rotated["access_token"].as_str().unwrap()).await,
+        StatusCode::OK
+    );
+
+    // A refresh token works once
+    let response = client
+        .post(format!("{url}/auth/refresh"))
+        .json(&json!({"refresh_token": refresh_token}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Logout revokes the current refresh token
+    let access_token = rotated["access_token"].as_str().unwrap();
+    let logout: Value = client
+        .post(format!("{url}/auth/logout"))
+        .bearer_auth(access_token)
+        .json(&json!({"refresh_token": new_refresh}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(logout["revoked"], true);
+    let response = client
+        .post(format!("{url}/auth/refresh"))
+        .json(&json!({"refresh_token": new_refresh}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_disabled_user_loses_access_and_refresh() {
+    let (url, user_manager) = spawn_server().await;
+    let client = Client::new();
+    let session = login(&client, &url, "bob", "bob-password-1").await;
+    let access_token = session["access_token"].as_str().unwrap();
+    let refresh_token = session["refresh_token"].as_str().unwrap();
+
+    // Admin status comes from the user record, not the token: bob is not one
+    let response = client
+        .get(format!("{url}/auth/users"))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    user_manager.set_user_enabled("bob", false).unwrap();
+    assert_eq!(
+        stats_status(&client, &url, access_token).await,
+        StatusCode::UNAUTHORIZED
+    );
+    let response = client
+        .post(format!("{url}/auth/refresh"))
+        .json(&json!({"refresh_token": refresh_token}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
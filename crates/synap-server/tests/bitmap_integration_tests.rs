@@ -35,6 +35,7 @@ async fn spawn_test_server() -> String {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -190,6 +191,83 @@ async fn test_bitmap_bitcount_rest() {
     assert_eq!(bitcount_range_body["count"], 3); // Bits at 0, 2, 4
 }
 
+#[tokio::test]
+async fn test_bitmap_byte_and_negative_ranges_rest() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    // Byte 0 = 0b1000_0000, byte 1 = 0b0110_0000
+    for offset in [0, 9, 10] {
+        client
+            .post(format!("{}/bitmap/range-test/setbit", base_url))
+            .json(&json!({ "offset": offset, "value": 1 }))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    let last_byte: serde_json::Value = client
+        .get(format!(
+            "{}/bitmap/range-test/bitcount?start=-1&end=-1&unit=byte",
+            base_url
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(last_byte["count"], 2);
+
+    let first_in_last_byte: serde_json::Value = client
+        .get(format!(
+            "{}/bitmap/range-test/bitpos?value=1&start=1&unit=byte",
+            base_url
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(first_in_last_byte["position"], 9);
+
+    let bad_unit = client
+        .get(format!(
+            "{}/bitmap/range-test/bitcount?start=0&end=1&unit=word",
+            base_url
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(bad_unit.status(), 400);
+
+    // A missing source is an empty bitmap, so AND with it is all zeros
+    let bitop: serde_json::Value = client
+        .post(format!("{}/bitmap/range-and/bitop", base_url))
+        .json(&json!({
+            "operation": "AND",
+            "source_keys": ["range-test", "range-missing"]
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(bitop["length"], 2);
+
+    let and_count: serde_json::Value = client
+        .get(format!("{}/bitmap/range-and/bitcount", base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(and_count["count"], 0);
+}
+
 #[tokio::test]
 async fn test_bitmap_bitpos_rest() {
     let base_url = spawn_test_server().await;
@@ -0,0 +1,81 @@
+//! Bloom filters end to end: the SDK's `BloomFilterManager` against the
+//! `bf.*` commands.
+
+mod app_state_helper;
+
+use std::sync::Arc;
+use synap_sdk::{SynapClient, SynapConfig};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore};
+
+async fn spawn_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    app_state_helper::spawn_server_with_state(state).await
+}
+
+fn client(url: &str) -> SynapClient {
+    SynapClient::new(SynapConfig::new(url)).unwrap()
+}
+
+#[tokio::test]
+async fn added_items_exist() {
+    let url = spawn_server().await;
+    let bloom = client(&url).bloom();
+
+    assert!(bloom.add("seen", "a").await.unwrap());
+    assert!(!bloom.add("seen", "a").await.unwrap());
+    assert!(bloom.exists("seen", "a").await.unwrap());
+    assert!(!bloom.exists("missing", "a").await.unwrap());
+
+    assert_eq!(
+        bloom.madd("seen", &["a", "b", "c"]).await.unwrap(),
+        vec![false, true, true]
+    );
+    assert_eq!(
+        bloom.mexists("seen", &["b", "c"]).await.unwrap(),
+        vec![true, true]
+    );
+
+    // A default filter errs on about 1% of absent items
+    let absent: Vec<String> = (0..100).map(|i| format!("absent-{i}")).collect();
+    let hits = bloom.mexists("seen", &absent).await.unwrap();
+    assert!(hits.iter().filter(|hit| **hit).count() < 10);
+}
+
+#[tokio::test]
+async fn reserve_keeps_the_first_parameters() {
+    let url = spawn_server().await;
+    let bloom = client(&url).bloom();
+
+    assert!(bloom.reserve("orders", 0.001, 1000).await.unwrap());
+    assert!(!bloom.reserve("orders", 0.1, 10).await.unwrap());
+
+    let err = bloom.reserve("bad", 1.5, 10).await.unwrap_err();
+    assert!(err.to_string().contains("error rate"), "{err}");
+    let empty: [&str; 0] = [];
+    assert!(bloom.madd("orders", &empty).await.is_err());
+}
+
+#[tokio::test]
+async fn filters_grow_past_their_capacity() {
+    let url = spawn_server().await;
+    let bloom = client(&url).bloom();
+
+    bloom.reserve("small", 0.01, 10).await.unwrap();
+    let items: Vec<String> = (0..200).map(|i| format!("item-{i}")).collect();
+    bloom.madd("small", &items).await.unwrap();
+    assert!(
+        bloom
+            .mexists("small", &items)
+            .await
+            .unwrap()
+            .into_iter()
+            .all(|hit| hit)
+    );
+}
@@ -0,0 +1,240 @@
+//! `/clients/kill`, `/clients/pause`, `/clients/unpause` and the
+//! `clients.max_subscriptions` limit.
+
+mod app_state_helper;
+
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::monitoring::{ClientLimitsConfig, ClientListManager};
+use synap_server::{KVConfig, KVStore, PubSubRouter, create_router};
+use tokio::net::TcpListener;
+use tokio_tungstenite::connect_async;
+
+async fn spawn_test_server(limits: ClientLimitsConfig) -> String {
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.pubsub_router = Some(Arc::new(PubSubRouter::new()));
+    state.client_list_manager = Arc::new(ClientListManager::new().with_limits(limits));
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    url
+}
+
+async fn post(client: &Client, url: String, body: Value) -> Value {
+    client
+        .post(url)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_client_kill_closes_websocket() {
+    let url = spawn_test_server(ClientLimitsConfig::default()).await;
+    let client = Client::new();
+
+    let ws = format!("{}/pubsub/ws?topics=news", url.replace("http://", "ws://"));
+    let (mut socket, _) = connect_async(ws).await.unwrap();
+
+    // Registration happens after the upgrade, so wait for it to show up
+    let mut addr = None;
+    for _ in 0..50 {
+        let list: Value = client
+            .get(format!("{url}/clients"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if let Some(found) = list["clients"][0]["addr"].as_str() {
+            addr = Some(found.to_string());
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let addr = addr.expect("websocket client never listed");
+
+    let body = post(
+        &client,
+        format!("{url}/clients/kill"),
+        json!({"addr": addr}),
+    )
+    .await;
+    assert_eq!(body["killed"], 1);
+
+    // The server ends the socket; skip the welcome message if it is queued
+    let closed = tokio::time::timeout(Duration::from_secs(2), async {
+        while let Some(Ok(message)) = socket.next().await {
+            if message.is_close() {
+                break;
+            }
+        }
+    })
+    .await;
+    assert!(closed.is_ok(), "killed websocket stayed open");
+
+    let body = post(
+        &client,
+        format!("{url}/clients/kill"),
+        json!({"id": "nope"}),
+    )
+    .await;
+    assert_eq!(body["killed"], 0);
+
+    let response = client
+        .post(format!("{url}/clients/kill"))
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_client_pause_write_holds_writes_only() {
+    let url = spawn_test_server(ClientLimitsConfig::default()).await;
+    let client = Client::new();
+
+    let body = post(
+        &client,
+        format!("{url}/clients/pause"),
+        json!({"timeout_ms": 400, "mode": "write"}),
+    )
+    .await;
+    assert_eq!(body["paused"], true);
+    assert_eq!(body["mode"], "write");
+
+    // Reads go through right away
+    let started = Instant::now();
+    let response = client
+        .get(format!("{url}/kv/get/missing"))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success() || response.status() == 404);
+    assert!(started.elapsed() < Duration::from_millis(300));
+
+    // REST and command writes wait for the pause to run out
+    let started = Instant::now();
+    post(
+        &client,
+        format!("{url}/kv/set"),
+        json!({"key": "a", "value": "1"}),
+    )
+    .await;
+    assert!(started.elapsed() >= Duration::from_millis(300));
+
+    client
+        .post(format!("{url}/clients/pause"))
+        .json(&json!({"timeout_ms": 400, "mode": "write"}))
+        .send()
+        .await
+        .unwrap();
+    let started = Instant::now();
+    let body = post(
+        &client,
+        format!("{url}/api/v1/command"),
+        json!({"command": "kv.set", "request_id": "pause", "payload": {"key": "b", "value": "2"}}),
+    )
+    .await;
+    assert_eq!(body["success"], true);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_client_unpause_releases_waiting_requests() {
+    let url = spawn_test_server(ClientLimitsConfig::default()).await;
+    let client = Client::new();
+
+    post(
+        &client,
+        format!("{url}/clients/pause"),
+        json!({"timeout_ms": 10_000}),
+    )
+    .await;
+
+    let started = Instant::now();
+    let waiting = {
+        let client = client.clone();
+        let url = url.clone();
+        tokio::spawn(async move {
+            client
+                .get(format!("{url}/kv/get/missing"))
+                .send()
+                .await
+                .unwrap()
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!waiting.is_finished());
+
+    let body = post(&client, format!("{url}/clients/unpause"), json!({})).await;
+    assert_eq!(body["unpaused"], true);
+    waiting.await.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    let body = post(&client, format!("{url}/clients/unpause"), json!({})).await;
+    assert_eq!(body["unpaused"], false);
+}
+
+#[tokio::test]
+async fn test_max_subscriptions_rejects_upgrade() {
+    let url = spawn_test_server(ClientLimitsConfig {
+        max_subscriptions: 2,
+        ..Default::default()
+    })
+    .await;
+    let ws = url.replace("http://", "ws://");
+
+    assert!(
+        connect_async(format!("{ws}/pubsub/ws?topics=a,b"))
+            .await
+            .is_ok()
+    );
+    let rejected = connect_async(format!("{ws}/pubsub/ws?topics=a,b,c")).await;
+    match rejected {
+        Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+            assert_eq!(response.status(), 400)
+        }
+        other => panic!("expected 400, got {:?}", other.map(|_| ())),
+    }
+}
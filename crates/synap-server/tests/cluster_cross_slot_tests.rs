@@ -0,0 +1,136 @@
+//! Cluster mode: multi-key commands on lists, sets and sorted sets are
+//! rejected with `CROSSSLOT` unless every key hashes to the same slot.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::cluster::topology::ClusterTopology;
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_cluster_server() -> String {
+    let topology = Arc::new(ClusterTopology::new("node-0".to_string()));
+    topology.initialize_cluster(1).unwrap();
+
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.cluster_topology = Some(topology);
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn command(client: &Client, url: &str, command: &str, payload: Value) -> Value {
+    client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({"command": command, "request_id": "t", "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_sorted_set_store_commands_need_one_slot() {
+    let url = spawn_cluster_server().await;
+    let client = Client::new();
+
+    for key in ["{board}.daily", "{board}.weekly"] {
+        let response = command(
+            &client,
+            &url,
+            "sortedset.zadd",
+            json!({"key": key, "member": "alice", "score": 1.0}),
+        )
+        .await;
+        assert_eq!(response["success"], true);
+    }
+
+    let response = command(
+        &client,
+        &url,
+        "sortedset.zinterstore",
+        json!({"destination": "{board}.both", "keys": ["{board}.daily", "{board}.weekly"]}),
+    )
+    .await;
+    assert_eq!(response["success"], true);
+    assert_eq!(response["payload"]["count"], 1);
+
+    let response = command(
+        &client,
+        &url,
+        "sortedset.zinterstore",
+        json!({"destination": "both", "keys": ["{board}.daily", "{board}.weekly"]}),
+    )
+    .await;
+    assert_eq!(response["success"], false);
+    assert!(response["error"].as_str().unwrap().starts_with("CROSSSLOT"));
+}
+
+#[tokio::test]
+async fn test_rpoplpush_needs_one_slot() {
+    let url = spawn_cluster_server().await;
+    let client = Client::new();
+
+    let response = client
+        .post(format!("{url}/list/jobs/rpush"))
+        .json(&json!({"values": ["a", "b"]}))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let response = client
+        .post(format!("{url}/list/jobs/rpoplpush/jobs-done"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .contains("'jobs' and 'jobs-done'")
+    );
+
+    let response = command(
+        &client,
+        &url,
+        "list.rpoplpush",
+        json!({"source": "jobs", "destination": "jobs"}),
+    )
+    .await;
+    assert_eq!(response["success"], true);
+    assert_eq!(response["payload"]["value"], "b");
+}
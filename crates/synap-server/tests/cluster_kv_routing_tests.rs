@@ -288,3 +288,64 @@ async fn test_kv_store_delete_with_cluster_routing() {
         assert!(result.is_err());
     }
 }
+
+#[tokio::test]
+async fn test_kv_store_multi_key_same_slot() {
+    // Test: multi-key commands need every key in one slot; hash tags keep
+    // related keys together
+    let topology = Arc::new(ClusterTopology::new("node-0".to_string()));
+    topology.initialize_cluster(3).unwrap();
+
+    let kv_store = KVStore::new_with_cluster(KVConfig::default(), None, topology.clone(), None);
+
+    // Find a hash tag whose slot belongs to node-0
+    let tag = (0..1000)
+        .map(|i| format!("user:{}", i))
+        .find(|tag| topology.get_slot_owner(hash_slot(tag)).unwrap() == "node-0")
+        .expect("Should find a tag for node-0");
+    let profile = format!("{{{}}}.profile", tag);
+    let sessions = format!("{{{}}}.sessions", tag);
+
+    kv_store
+        .mset(vec![
+            (profile.clone(), b"p".to_vec()),
+            (sessions.clone(), b"s".to_vec()),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(
+        kv_store
+            .mget(&[profile.clone(), sessions.clone()])
+            .await
+            .unwrap(),
+        vec![Some(b"p".to_vec()), Some(b"s".to_vec())]
+    );
+
+    // Untagged keys of different slots are rejected before anything is written
+    let other = (0..1000)
+        .map(|i| format!("other:{}", i))
+        .find(|key| hash_slot(key) != hash_slot(&tag))
+        .unwrap();
+    let result = kv_store
+        .mset(vec![
+            (profile.clone(), b"x".to_vec()),
+            (other.clone(), b"y".to_vec()),
+        ])
+        .await;
+    assert!(matches!(
+        result,
+        Err(synap_server::core::SynapError::ClusterCrossSlot { .. })
+    ));
+    assert_eq!(kv_store.get(&profile).await.unwrap(), Some(b"p".to_vec()));
+
+    let crossed = [profile.clone(), other.clone()];
+    assert!(kv_store.mget(&crossed).await.is_err());
+    assert!(kv_store.mdel(&crossed).await.is_err());
+    assert!(
+        kv_store
+            .msetnx(vec![(other, b"y".to_vec()), (profile, b"x".to_vec())])
+            .await
+            .is_err()
+    );
+    assert_eq!(kv_store.mdel(&[sessions]).await.unwrap(), 1);
+}
@@ -7,7 +7,9 @@ use serde_json::json;
 use std::sync::Arc;
 use std::time::Duration;
 use synap_server::auth::{ApiKeyManager, UserManager};
-use synap_server::cluster::{migration::SlotMigrationManager, topology::ClusterTopology};
+use synap_server::cluster::{
+    hash_slot, migration::SlotMigrationManager, topology::ClusterTopology,
+};
 use synap_server::core::{KVConfig, KVStore};
 use synap_server::monitoring::MonitoringManager;
 use synap_server::{AppState, ScriptManager, create_router};
@@ -23,6 +25,7 @@ async fn spawn_test_server_with_cluster() -> String {
     topology.initialize_cluster(3).unwrap();
 
     let migration = Arc::new(SlotMigrationManager::new(100, Duration::from_secs(60)));
+    migration.attach_kv_store(kv_store.clone());
 
     let monitoring = Arc::new(MonitoringManager::new(
         kv_store.clone(),
@@ -67,6 +70,14 @@ async fn spawn_test_server_with_cluster() -> String {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     let user_manager = Arc::new(UserManager::new());
@@ -78,6 +89,7 @@ async fn spawn_test_server_with_cluster() -> String {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -512,3 +524,158 @@ async fn test_cluster_assign_slots_node_not_found() {
     // Should fail (500 - internal error)
     assert_eq!(response.status(), 500);
 }
+
+#[tokio::test]
+async fn test_cluster_migrate_reports_progress_and_finalizes() {
+    // Test: POST /cluster/migrate copies a slot's keys with progress on
+    // GET /cluster/migrations; SETSLOT NODE hands the slot over
+    let url = spawn_test_server_with_cluster().await;
+    let client = Client::new();
+
+    for i in 0..3 {
+        client
+            .post(format!("{}/kv/set", url))
+            .json(&json!({"key": format!("{{mig}}:{}", i), "value": "v"}))
+            .send()
+            .await
+            .unwrap();
+    }
+    let slot = hash_slot("{mig}");
+    // initialize_cluster(3) gives each node a third of the slots
+    let to_node = format!("node-{}", ((slot as usize / 5461).min(2) + 1) % 3);
+
+    let response = client
+        .post(format!("{}/cluster/migrate", url))
+        .json(&json!({"start": slot, "to_node": to_node, "max_keys_per_sec": 1000}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["slots_started"], 1);
+    assert_eq!(body["max_keys_per_sec"], 1000);
+
+    let mut progress = serde_json::Value::Null;
+    for _ in 0..50 {
+        progress = client
+            .get(format!("{}/cluster/migrations", url))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if progress["copied"] == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(progress["copied"], 1);
+    assert_eq!(progress["keys_migrated"], 3);
+    assert_eq!(progress["total_keys"], 3);
+    assert_eq!(progress["migrations"][0]["slot"], slot);
+    assert_eq!(progress["migrations"][0]["copy_done"], true);
+
+    let response = client
+        .post(format!("{}/cluster/setslot", url))
+        .json(&json!({"slot": slot, "action": "node", "node_id": to_node}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let progress: serde_json::Value = client
+        .get(format!("{}/cluster/migrations", url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(progress["count"], 0);
+
+    let node: serde_json::Value = client
+        .get(format!("{}/cluster/nodes/{}", url, to_node))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let owns_slot = node["slots"].as_array().unwrap().iter().any(|r| {
+        r["start"].as_u64().unwrap() <= slot as u64 && slot as u64 <= r["end"].as_u64().unwrap()
+    });
+    assert!(owns_slot);
+}
+
+#[tokio::test]
+async fn test_cluster_setslot_commands() {
+    // Test: cluster.* commands drive SETSLOT, list migrations and throttle
+    let url = spawn_test_server_with_cluster().await;
+    let client = Client::new();
+
+    let command = |command: &'static str, payload: serde_json::Value| {
+        let client = client.clone();
+        let url = url.clone();
+        async move {
+            client
+                .post(format!("{}/api/v1/command", url))
+                .json(&json!({"command": command, "request_id": "t", "payload": payload}))
+                .send()
+                .await
+                .unwrap()
+                .json::<serde_json::Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    let response = command(
+        "cluster.setslot",
+        json!({"slot": 0, "end_slot": 9, "action": "MIGRATING", "node_id": "node-2"}),
+    )
+    .await;
+    assert_eq!(response["success"], true);
+    assert_eq!(response["payload"]["slots"], 10);
+
+    let response = command("cluster.migrations", json!({})).await;
+    assert_eq!(response["payload"]["count"], 10);
+    assert_eq!(response["payload"]["migrations"][0]["from_node"], "node-0");
+
+    // Overlapping range is refused as a whole
+    let response = command(
+        "cluster.migrate",
+        json!({"start": 5, "end": 20, "to_node": "node-1"}),
+    )
+    .await;
+    assert_eq!(response["success"], false);
+
+    let response = command(
+        "cluster.setslot",
+        json!({"slot": 0, "end_slot": 9, "action": "stable"}),
+    )
+    .await;
+    assert_eq!(response["success"], true);
+    let response = command("cluster.migrations", json!({})).await;
+    assert_eq!(response["payload"]["count"], 0);
+
+    let response = command("cluster.throttle", json!({"max_keys_per_sec": 250})).await;
+    assert_eq!(response["payload"]["max_keys_per_sec"], 250);
+    let response = command("cluster.migrations", json!({})).await;
+    assert_eq!(response["payload"]["max_keys_per_sec"], 250);
+
+    let response = command(
+        "cluster.setslot",
+        json!({"slot": 0, "action": "nowhere", "node_id": "node-1"}),
+    )
+    .await;
+    assert_eq!(response["success"], false);
+    let response = command(
+        "cluster.setslot",
+        json!({"slot": 16384, "action": "stable"}),
+    )
+    .await;
+    assert_eq!(response["success"], false);
+    let response = command("cluster.setslot", json!({"slot": 0, "action": "node"})).await;
+    assert_eq!(response["success"], false);
+}
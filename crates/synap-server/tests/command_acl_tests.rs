@@ -0,0 +1,160 @@
+//! Per-command permissions on `POST /api/v1/command`: each command needs the
+//! same resource and action as its REST endpoint, and denials are audited.
+
+mod app_state_helper;
+
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{Action, ApiKeyManager, Permission, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+/// Start a server that requires authentication, with an admin user `root`
+/// and an API key limited to `kv:public:*`
+async fn spawn_server() -> (String, String) {
+    let user_manager = Arc::new(UserManager::new());
+    user_manager
+        .create_user("root", "root-password-1", true)
+        .unwrap();
+    let api_key_manager = Arc::new(ApiKeyManager::new());
+    let key = api_key_manager
+        .create(
+            "public-only",
+            None,
+            vec![Permission::new("kv:public:*", Action::All)],
+            vec![],
+            None,
+        )
+        .unwrap();
+
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        user_manager,
+        api_key_manager,
+        true,
+        true,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    (url, key.key)
+}
+
+async fn command(
+    client: &Client,
+    url: &str,
+    key: &str,
+    command: &str,
+    payload: Value,
+) -> StatusCode {
+    client
+        .post(format!("{url}/api/v1/command"))
+        .bearer_auth(key)
+        .json(&json!({"command": command, "request_id": "acl", "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_command_needs_resource_permission() {
+    let (url, key) = spawn_server().await;
+    let client = Client::new();
+
+    let set = |k: &str| json!({"key": k, "value": "v"});
+    assert_eq!(
+        command(&client, &url, &key, "kv.set", set("public:1")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        command(&client, &url, &key, "kv.get", json!({"key": "public:1"})).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        command(&client, &url, &key, "kv.set", set("secret:1")).await,
+        StatusCode::FORBIDDEN
+    );
+    // Every key of a multi-key command is checked
+    assert_eq!(
+        command(
+            &client,
+            &url,
+            &key,
+            "kv.mget",
+            json!({"keys": ["public:1", "secret:1"]})
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    // Keyless commands need the type-wide resource, wipes need admin
+    assert_eq!(
+        command(&client, &url, &key, "kv.scan", json!({"prefix": "public:"})).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        command(&client, &url, &key, "kv.flushall", json!({})).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        command(&client, &url, &key, "hash.set", set("public:1")).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn test_denials_are_audited() {
+    let (url, key) = spawn_server().await;
+    let client = Client::new();
+
+    command(&client, &url, &key, "kv.del", json!({"key": "secret:1"})).await;
+
+    let audit: Value = client
+        .get(format!("{url}/audit?event_type=PermissionDenied"))
+        .basic_auth("root", Some("root-password-1"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(audit["count"], 1);
+    let entry = &audit["entries"][0];
+    assert_eq!(entry["resource"], "kv:secret:1");
+    assert_eq!(entry["action"], "delete");
+    assert_eq!(entry["metadata"]["command"], "kv.del");
+    assert!(entry["api_key_id"].is_string());
+
+    // The audit log itself is admin only
+    let response = client
+        .get(format!("{url}/audit"))
+        .bearer_auth(&key)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,138 @@
+//! MessagePack and CBOR bodies on `POST /api/v1/command`: the request is read
+//! in its `Content-Type`, the response written in the `Accept`ed encoding, and
+//! byte arrays travel as binary strings.
+
+mod app_state_helper;
+
+use reqwest::{Client, StatusCode, header};
+use serde_json::json;
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::protocol::encoding::Encoding;
+use synap_server::{KVConfig, KVStore, Request, Response, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_test_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+/// Send `request` in `encoding`, optionally asking for another response
+/// encoding, and return the status, response `Content-Type` and raw body
+async fn send(
+    url: &str,
+    request: &Request,
+    encoding: Encoding,
+    accept: Option<Encoding>,
+) -> (StatusCode, String, Vec<u8>) {
+    let mut builder = Client::new()
+        .post(format!("{url}/api/v1/command"))
+        .header(header::CONTENT_TYPE, encoding.content_type())
+        .body(encoding.encode(request).unwrap());
+    if let Some(accept) = accept {
+        builder = builder.header(header::ACCEPT, accept.content_type());
+    }
+    let response = builder.send().await.unwrap();
+    let status = response.status();
+    let content_type = response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .to_string();
+    (
+        status,
+        content_type,
+        response.bytes().await.unwrap().to_vec(),
+    )
+}
+
+#[tokio::test]
+async fn test_binary_requests_are_answered_in_kind() {
+    let url = spawn_test_server().await;
+    let set = Request::new("kv.set", json!({"key": "greeting", "value": "hello"}));
+
+    for encoding in [Encoding::MessagePack, Encoding::Cbor] {
+        let (status, content_type, body) = send(&url, &set, encoding, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, encoding.content_type());
+        let response: Response = encoding.decode(&body).unwrap();
+        assert!(response.success, "{encoding}: {:?}", response.error);
+        assert_eq!(response.request_id, set.request_id);
+    }
+}
+
+#[tokio::test]
+async fn test_accept_picks_the_response_encoding_and_bytes_stay_binary() {
+    let url = spawn_test_server().await;
+    let set = Request::new("kv.set", json!({"key": "blob", "value": "x".repeat(1024)}));
+    let (status, _, _) = send(&url, &set, Encoding::Json, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let get = Request::new("kv.get", json!({"key": "blob", "type": "bytes"}));
+    let (_, _, json_body) = send(&url, &get, Encoding::Json, None).await;
+    let (status, content_type, cbor_body) =
+        send(&url, &get, Encoding::Json, Some(Encoding::Cbor)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "application/cbor");
+    assert!(cbor_body.len() < 1024 + 128, "{} bytes", cbor_body.len());
+    assert!(json_body.len() > 3 * 1024);
+
+    let response: Response = Encoding::Cbor.decode(&cbor_body).unwrap();
+    assert_eq!(response.payload, Some(json!(vec![b'x'; 1024])));
+}
+
+#[tokio::test]
+async fn test_rejects_unknown_and_malformed_bodies() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    let unsupported = client
+        .post(format!("{url}/api/v1/command"))
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body("kv.get")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(unsupported.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let malformed = client
+        .post(format!("{url}/api/v1/command"))
+        .header(header::CONTENT_TYPE, "application/msgpack")
+        .body(vec![0xc1])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(malformed.status(), StatusCode::BAD_REQUEST);
+    let error: serde_json::Value = malformed.json().await.unwrap();
+    assert!(
+        error["error"]
+            .as_str()
+            .unwrap()
+            .contains("invalid MessagePack body"),
+        "{error}"
+    );
+}
@@ -0,0 +1,152 @@
+//! Multiplexed command WebSocket (`GET /api/v1/command/ws`): raw frames, and
+//! the SDK's `connect_ws()` transport on top of it.
+
+mod app_state_helper;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use synap_sdk::{SynapClient, SynapConfig};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::protocol::encoding::Encoding;
+use synap_server::{KVConfig, KVStore};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// Spawn an in-process server and return its base URL
+async fn spawn_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    app_state_helper::spawn_server_with_state(state).await
+}
+
+fn command_ws_url(base_url: &str) -> String {
+    format!(
+        "{}/api/v1/command/ws",
+        base_url.replacen("http://", "ws://", 1)
+    )
+}
+
+#[tokio::test]
+async fn pipelined_commands_are_answered_by_request_id() {
+    let url = spawn_server().await;
+    let (mut ws, _) = connect_async(command_ws_url(&url)).await.unwrap();
+
+    for i in 0..10 {
+        let request = json!({
+            "command": "kv.set",
+            "request_id": format!("set-{i}"),
+            "payload": {"key": format!("k{i}"), "value": format!("v{i}")},
+        });
+        ws.send(Message::text(request.to_string())).await.unwrap();
+    }
+
+    let mut responses = HashMap::new();
+    while responses.len() < 10 {
+        let Message::Text(text) = ws.next().await.unwrap().unwrap() else {
+            continue;
+        };
+        let response: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(response["success"], true, "{response}");
+        responses.insert(
+            response["request_id"].as_str().unwrap().to_owned(),
+            response,
+        );
+    }
+    for i in 0..10 {
+        assert!(responses.contains_key(&format!("set-{i}")));
+    }
+
+    let request = json!({
+        "command": "kv.get",
+        "request_id": "get-k3",
+        "payload": {"key": "k3"},
+    });
+    ws.send(Message::text(request.to_string())).await.unwrap();
+    let Message::Text(text) = ws.next().await.unwrap().unwrap() else {
+        panic!("expected a text frame");
+    };
+    let response: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(response["request_id"], "get-k3");
+    assert_eq!(response["payload"], "v3");
+}
+
+#[tokio::test]
+async fn binary_frames_are_answered_in_messagepack() {
+    let url = spawn_server().await;
+    let (mut ws, _) = connect_async(command_ws_url(&url)).await.unwrap();
+
+    let request = synap_server::Request {
+        command: "kv.set".to_string(),
+        request_id: "bin-1".to_string(),
+        payload: json!({"key": "packed", "value": "yes"}),
+        db: 0,
+    };
+    let body = Encoding::MessagePack.encode(&request).unwrap();
+    ws.send(Message::binary(body)).await.unwrap();
+
+    let Message::Binary(data) = ws.next().await.unwrap().unwrap() else {
+        panic!("expected a binary frame");
+    };
+    let response: synap_server::Response = Encoding::MessagePack.decode(&data).unwrap();
+    assert!(response.success);
+    assert_eq!(response.request_id, "bin-1");
+}
+
+#[tokio::test]
+async fn failures_keep_the_socket_open() {
+    let url = spawn_server().await;
+    let (mut ws, _) = connect_async(command_ws_url(&url)).await.unwrap();
+
+    ws.send(Message::text("not an envelope")).await.unwrap();
+    let Message::Text(text) = ws.next().await.unwrap().unwrap() else {
+        panic!("expected a text frame");
+    };
+    let response: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(response["success"], false);
+    assert_eq!(response["error_info"]["code"], "INVALID_REQUEST");
+
+    let request = json!({
+        "command": "no.such.command",
+        "request_id": "unknown",
+        "payload": {},
+    });
+    ws.send(Message::text(request.to_string())).await.unwrap();
+    let Message::Text(text) = ws.next().await.unwrap().unwrap() else {
+        panic!("expected a text frame");
+    };
+    let response: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(response["request_id"], "unknown");
+    assert_eq!(response["success"], false);
+}
+
+#[tokio::test]
+async fn sdk_multiplexes_concurrent_commands_over_one_socket() {
+    let url = spawn_server().await;
+    let client = SynapClient::new(SynapConfig::new(&url).connect_ws()).unwrap();
+
+    let writes = (0..50).map(|i| {
+        let kv = client.kv();
+        async move {
+            kv.set(&format!("user:{i}"), format!("name-{i}"), None)
+                .await
+        }
+    });
+    for result in futures_util::future::join_all(writes).await {
+        result.unwrap();
+    }
+
+    let value: Option<String> = client.kv().get("user:42").await.unwrap();
+    assert_eq!(value.as_deref(), Some("name-42"));
+
+    let error = client.queue().publish("missing", b"x", None, None).await;
+    assert!(
+        error.is_err(),
+        "server errors surface as errors over the socket"
+    );
+}
@@ -0,0 +1,145 @@
+//! Consumer-group lag: how far a group's committed offsets trail the last
+//! published offset of each partition, over REST and the command endpoint.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{
+    ConsumerGroupConfig, ConsumerGroupManager, KVConfig, KVStore, PartitionConfig,
+    PartitionManager, create_router,
+};
+use tokio::net::TcpListener;
+
+/// A server with partitions and consumer groups enabled
+async fn spawn_test_server() -> String {
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.partition_manager = Some(Arc::new(PartitionManager::new(PartitionConfig::default())));
+    state.consumer_group_manager = Some(Arc::new(ConsumerGroupManager::new(
+        ConsumerGroupConfig::default(),
+    )));
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+#[tokio::test]
+async fn test_consumer_group_lag() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    client
+        .post(format!("{url}/topics/orders"))
+        .json(&json!({"num_partitions": 1}))
+        .send()
+        .await
+        .unwrap();
+    for i in 0..5 {
+        client
+            .post(format!("{url}/topics/orders/publish"))
+            .json(&json!({"event_type": "created", "data": {"id": i}}))
+            .send()
+            .await
+            .unwrap();
+    }
+    // The group spans a second partition the topic does not have
+    client
+        .post(format!("{url}/consumer-groups/billing"))
+        .json(&json!({"topic": "orders", "partition_count": 2}))
+        .send()
+        .await
+        .unwrap();
+    client
+        .post(format!("{url}/consumer-groups/billing/offsets/commit"))
+        .json(&json!({"partition_id": 0, "offset": 1}))
+        .send()
+        .await
+        .unwrap();
+
+    let body: Value = client
+        .get(format!("{url}/consumer-groups/billing/lag"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["topic"], "orders");
+    assert_eq!(body["partitions"][0]["end_offset"], 4);
+    assert_eq!(body["partitions"][0]["lag"], 3);
+    assert_eq!(body["partitions"][1]["committed_offset"], Value::Null);
+    assert_eq!(body["total_lag"], 3);
+
+    // The command pages through the partitions
+    let command = |offset: u64| {
+        let client = client.clone();
+        let url = url.clone();
+        async move {
+            client
+                .post(format!("{url}/api/v1/command"))
+                .json(&json!({
+                    "command": "consumer_group.lag",
+                    "request_id": "t",
+                    "payload": {"group_id": "billing", "offset": offset, "limit": 1}
+                }))
+                .send()
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()
+        }
+    };
+    let first = command(0).await;
+    assert_eq!(first["success"], true);
+    assert_eq!(first["payload"]["partitions"][0]["lag"], 3);
+    assert_eq!(first["payload"]["total"], 2);
+    assert_eq!(first["payload"]["next_offset"], 1);
+
+    let last = command(1).await;
+    assert_eq!(last["payload"]["partitions"][0]["partition_id"], 1);
+    assert_eq!(last["payload"]["next_offset"], Value::Null);
+
+    let missing: Value = client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({
+            "command": "consumer_group.lag",
+            "request_id": "t",
+            "payload": {"group_id": "nobody"}
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(missing["success"], false);
+}
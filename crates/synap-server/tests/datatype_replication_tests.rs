@@ -11,11 +11,13 @@
 //! a deadline because propagation is fire-and-forget over a heartbeat-driven
 //! channel and would otherwise be racy on a loaded CI runner.
 
+use bytes::Bytes;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use synap_server::core::{
-    HashStore, ListStore, SetStore, SortedSetStore, StreamConfig, StreamManager,
+    BloomFilterStore, HashStore, HyperLogLogStore, ListStore, SetStore, SortedSetStore,
+    StreamConfig, StreamManager,
 };
 use synap_server::persistence::types::Operation;
 use synap_server::replication::{MasterNode, NodeRole, ReplicaNode, ReplicationConfig};
@@ -45,7 +47,17 @@ async fn create_master() -> (Arc<MasterNode>, SocketAddr) {
     };
     let kv = Arc::new(KVStore::new(KVConfig::default()));
     let stream = Arc::new(StreamManager::new(StreamConfig::default()));
-    let master = Arc::new(MasterNode::new(config, kv, Some(stream)).await.unwrap());
+    let master = Arc::new(
+        MasterNode::new(
+            config,
+            synap_server::persistence::StoreArcs {
+                stream_manager: Some(stream),
+                ..synap_server::persistence::StoreArcs::kv_only(kv)
+            },
+        )
+        .await
+        .unwrap(),
+    );
     sleep(Duration::from_millis(100)).await;
     (master, addr)
 }
@@ -57,6 +69,8 @@ struct ReplicaStores {
     list: Arc<ListStore>,
     set: Arc<SetStore>,
     zset: Arc<SortedSetStore>,
+    hll: Arc<HyperLogLogStore>,
+    bloom: Arc<BloomFilterStore>,
 }
 
 async fn create_replica(master_addr: SocketAddr) -> ReplicaStores {
@@ -74,6 +88,8 @@ async fn create_replica(master_addr: SocketAddr) -> ReplicaStores {
     let list = Arc::new(ListStore::new());
     let set = Arc::new(SetStore::new());
     let zset = Arc::new(SortedSetStore::new());
+    let hll = Arc::new(HyperLogLogStore::new());
+    let bloom = Arc::new(BloomFilterStore::new());
     let replica = ReplicaNode::new(
         config,
         synap_server::persistence::StoreArcs {
@@ -82,6 +98,9 @@ async fn create_replica(master_addr: SocketAddr) -> ReplicaStores {
             list_store: Some(list.clone()),
             set_store: Some(set.clone()),
             sorted_set_store: Some(zset.clone()),
+            hyperloglog_store: Some(hll.clone()),
+            bloom_store: Some(bloom.clone()),
+            bitmap_store: None,
             queue_manager: None,
             stream_manager: Some(stream),
         },
@@ -96,6 +115,8 @@ async fn create_replica(master_addr: SocketAddr) -> ReplicaStores {
         list,
         set,
         zset,
+        hll,
+        bloom,
     }
 }
 
@@ -121,7 +142,7 @@ async fn hash_write_converges_on_replica() {
     master.replicate(Operation::HashSet {
         key: "h1".to_string(),
         field: "f1".to_string(),
-        value: b"v1".to_vec(),
+        value: Bytes::from_static(b"v1"),
     });
 
     poll_until(
@@ -138,7 +159,7 @@ async fn list_write_converges_on_replica() {
 
     master.replicate(Operation::ListPush {
         key: "l1".to_string(),
-        values: vec![b"a".to_vec(), b"b".to_vec()],
+        values: vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")],
         left: false,
     });
 
@@ -159,7 +180,7 @@ async fn set_write_converges_on_replica() {
 
     master.replicate(Operation::SetAdd {
         key: "s1".to_string(),
-        members: vec![b"m1".to_vec()],
+        members: vec![Bytes::from_static(b"m1")],
     });
 
     poll_until(
@@ -176,7 +197,7 @@ async fn sorted_set_write_converges_on_replica() {
 
     master.replicate(Operation::ZAdd {
         key: "z1".to_string(),
-        member: b"m1".to_vec(),
+        member: Bytes::from_static(b"m1"),
         score: 1.5,
         nx: false,
         xx: false,
@@ -190,3 +211,104 @@ async fn sorted_set_write_converges_on_replica() {
     )
     .await;
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn hyperloglog_merge_converges_on_replica() {
+    let (master, addr) = create_master().await;
+    let stores = create_replica(addr).await;
+
+    let source = HyperLogLogStore::new();
+    source
+        .pfadd(
+            "hll",
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()],
+            None,
+        )
+        .unwrap();
+    master.replicate(Operation::HyperLogLogMerge {
+        destination: "hll".to_string(),
+        registers: Bytes::from(source.registers("hll").unwrap()),
+    });
+
+    poll_until(
+        || stores.hll.pfcount("hll").unwrap_or(0) == 3,
+        "hyperloglog registers",
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn bloom_writes_converge_on_replica() {
+    let (master, addr) = create_master().await;
+    let stores = create_replica(addr).await;
+
+    master.replicate(Operation::BloomReserve {
+        key: "seen".to_string(),
+        error_rate: 0.001,
+        capacity: 1000,
+    });
+    master.replicate(Operation::BloomAdd {
+        key: "seen".to_string(),
+        items: vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")],
+    });
+
+    poll_until(
+        || stores.bloom.exists("seen", &[b"a", b"b"]) == vec![true, true],
+        "bloom filter items",
+    )
+    .await;
+    assert_eq!(stores.bloom.get("seen").unwrap().capacity(), 1000);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn collections_written_before_connect_arrive_with_full_sync() {
+    let port = next_port();
+    let addr: SocketAddr = format!("127.0.0.1:{}", port).parse().unwrap();
+    let config = ReplicationConfig {
+        enabled: true,
+        role: NodeRole::Master,
+        replica_listen_address: Some(addr),
+        heartbeat_interval_ms: 100,
+        ..Default::default()
+    };
+    let hash = Arc::new(HashStore::new());
+    let list = Arc::new(ListStore::new());
+    let set = Arc::new(SetStore::new());
+    let zset = Arc::new(SortedSetStore::new());
+    hash.hset("h1", "f1", b"v1".to_vec()).unwrap();
+    list.rpush("l1", vec![b"a".to_vec(), b"b".to_vec()], false)
+        .unwrap();
+    set.sadd("s1", vec![b"m1".to_vec()]).unwrap();
+    zset.zadd("z1", b"m1".to_vec(), 1.5, &Default::default());
+    let _master = MasterNode::new(
+        config,
+        synap_server::persistence::StoreArcs {
+            hash_store: Some(hash),
+            list_store: Some(list),
+            set_store: Some(set),
+            sorted_set_store: Some(zset),
+            ..synap_server::persistence::StoreArcs::kv_only(Arc::new(KVStore::new(
+                KVConfig::default(),
+            )))
+        },
+    )
+    .await
+    .unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    // Nothing is replicated incrementally: the data only exists in the
+    // master's stores, so it has to come with the snapshot.
+    let stores = create_replica(addr).await;
+
+    poll_until(
+        || {
+            stores.hash.hget("h1", "f1").ok().flatten() == Some(b"v1".to_vec())
+                && stores.list.lrange("l1", 0, -1).unwrap_or_default()
+                    == vec![b"a".to_vec(), b"b".to_vec()]
+                && stores.set.sismember("s1", b"m1".to_vec()).unwrap_or(false)
+                && stores.zset.zscore("z1", b"m1") == Some(1.5)
+        },
+        "full-sync collections",
+    )
+    .await;
+}
@@ -0,0 +1,207 @@
+//! Deprecated surfaces keep working, answer with deprecation headers, and are
+//! reported per API key by `GET /deprecations`.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::auth::{Action, ApiKeyManager, Permission, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+struct TestServer {
+    url: String,
+    client_key: String,
+    client_key_id: String,
+    admin_key: String,
+}
+
+async fn spawn_test_server() -> TestServer {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+
+    let api_key_manager = Arc::new(ApiKeyManager::new());
+    let client = api_key_manager
+        .create(
+            "legacy-app",
+            None,
+            vec![Permission::new("*", Action::All)],
+            vec![],
+            None,
+        )
+        .unwrap();
+    let admin = api_key_manager
+        .create(
+            "admin",
+            None,
+            vec![Permission::new("*", Action::All)],
+            vec![],
+            None,
+        )
+        .unwrap();
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        api_key_manager,
+        true,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    TestServer {
+        url,
+        client_key: client.key,
+        client_key_id: client.id,
+        admin_key: admin.key,
+    }
+}
+
+async fn command(server: &TestServer, name: &str, payload: Value) -> reqwest::Response {
+    Client::new()
+        .post(format!("{}/api/v1/command", server.url))
+        .bearer_auth(&server.client_key)
+        .json(&json!({"command": name, "request_id": "t", "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_deprecated_command_and_field_get_headers() {
+    let server = spawn_test_server().await;
+
+    let response = command(&server, "kv.keys", json!({})).await;
+    assert!(response.status().is_success());
+    assert_eq!(response.headers()["deprecation"], "true");
+    let warning = response.headers()["warning"].to_str().unwrap();
+    assert!(warning.starts_with("299 "));
+    assert!(warning.contains("kv.keys is deprecated, use kv.scan"));
+
+    let response = command(&server, "kv.scan", json!({})).await;
+    assert!(response.status().is_success());
+    assert!(response.headers().get("deprecation").is_none());
+
+    let response = Client::new()
+        .post(format!("{}/kv/set", server.url))
+        .bearer_auth(&server.client_key)
+        .json(&json!({"key": "k", "value": "v", "ttl": 60}))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    assert!(
+        response.headers()["warning"]
+            .to_str()
+            .unwrap()
+            .contains("use expiry")
+    );
+
+    let response = Client::new()
+        .post(format!("{}/kv/set", server.url))
+        .bearer_auth(&server.client_key)
+        .json(&json!({"key": "k", "value": "v", "expiry": {"type": "seconds", "value": 60}}))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.headers().get("deprecation").is_none());
+}
+
+#[tokio::test]
+async fn test_deprecated_route_gets_headers() {
+    let server = spawn_test_server().await;
+
+    let response = Client::new()
+        .post(format!("{}/pubsub/subscribe", server.url))
+        .bearer_auth(&server.client_key)
+        .json(&json!({"topics": ["news"]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["deprecation"], "true");
+    assert!(
+        response.headers()["warning"]
+            .to_str()
+            .unwrap()
+            .contains("POST /pubsub/subscribe is deprecated")
+    );
+}
+
+#[tokio::test]
+async fn test_report_counts_usage_per_api_key() {
+    let server = spawn_test_server().await;
+    for _ in 0..3 {
+        command(&server, "kv.keys", json!({})).await;
+    }
+
+    let admin = Client::new();
+    let report: Value = admin
+        .get(format!("{}/deprecations", server.url))
+        .bearer_auth(&server.admin_key)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let rows = report["deprecations"].as_array().unwrap();
+    let keys = rows.iter().find(|r| r["surface"] == "kv.keys").unwrap();
+    assert_eq!(keys["replacement"], "kv.scan");
+    assert_eq!(keys["total"], 3);
+    assert_eq!(
+        keys["clients"][0]["client"],
+        format!("api_key:{}", server.client_key_id)
+    );
+    assert_eq!(keys["clients"][0]["count"], 3);
+    let subscribe = rows
+        .iter()
+        .find(|r| r["surface"] == "POST /pubsub/subscribe")
+        .unwrap();
+    assert_eq!(subscribe["total"], 0);
+
+    let reset = admin
+        .delete(format!("{}/deprecations", server.url))
+        .bearer_auth(&server.admin_key)
+        .send()
+        .await
+        .unwrap();
+    assert!(reset.status().is_success());
+    let report: Value = admin
+        .get(format!("{}/deprecations", server.url))
+        .bearer_auth(&server.admin_key)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(
+        report["deprecations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|r| r["total"] == 0)
+    );
+}
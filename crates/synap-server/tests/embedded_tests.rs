@@ -0,0 +1,194 @@
+//! Embedded mode: commands run in-process with the semantics of the command
+//! endpoint, and persistence survives a restart.
+
+use futures_util::future::BoxFuture;
+use parking_lot::Mutex;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::monitoring::{CommandHook, CommandProtocol, CommandRecord};
+use synap_server::persistence::types::{FsyncMode, PersistenceConfig, SnapshotConfig, WALConfig};
+use synap_server::{Embedded, ServerConfig, SynapError};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_embedded_kv() {
+    let synap = Embedded::start(ServerConfig::default()).await.unwrap();
+    let kv = synap.kv();
+
+    kv.set("user:1", "alice", None).await.unwrap();
+    kv.set("user:2", vec![1, 2, 3], Some(60)).await.unwrap();
+    assert_eq!(
+        kv.get::<_, String>("user:1").await.unwrap().as_deref(),
+        Some("alice")
+    );
+    assert_eq!(
+        kv.get::<_, Vec<u8>>("user:2").await.unwrap(),
+        Some(vec![1, 2, 3])
+    );
+    assert_eq!(kv.get::<_, String>("missing").await.unwrap(), None);
+
+    assert_eq!(kv.incr("hits").await.unwrap(), 1);
+    assert_eq!(kv.decr("hits").await.unwrap(), 0);
+    assert!(kv.exists("user:1").await.unwrap());
+    assert_eq!(kv.keys("user:").await.unwrap().len(), 2);
+    assert!(kv.delete("user:1").await.unwrap());
+    assert!(!kv.delete("user:1").await.unwrap());
+    assert!(kv.stats().await.unwrap().total_keys > 0);
+}
+
+#[tokio::test]
+async fn test_embedded_commands() {
+    let synap = Embedded::start(ServerConfig::default()).await.unwrap();
+
+    synap
+        .send_command("list.rpush", json!({"key": "jobs", "values": ["a", "b"]}))
+        .await
+        .unwrap();
+    let range = synap
+        .send_command("list.range", json!({"key": "jobs", "start": 0, "stop": -1}))
+        .await
+        .unwrap();
+    assert_eq!(range["values"], json!(["a", "b"]));
+
+    // Errors keep their type instead of becoming a failed envelope
+    let unknown = synap.send_command("kv.nope", json!({})).await;
+    assert!(matches!(unknown, Err(SynapError::UnknownCommand(_))));
+}
+
+#[tokio::test]
+async fn test_embedded_listen_serves_the_http_api() {
+    let synap = Embedded::start(ServerConfig::default()).await.unwrap();
+    synap.kv().set("user:1", "alice", None).await.unwrap();
+
+    let listener = synap.listen("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let url = format!("http://{}/api/v1/command", listener.local_addr());
+    let response: serde_json::Value = reqwest::Client::new()
+        .post(&url)
+        .json(&json!({"command": "kv.get", "request_id": "r1", "payload": {"key": "user:1"}}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response["payload"], "alice");
+
+    drop(listener);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(reqwest::Client::new().post(&url).send().await.is_err());
+}
+
+/// Keeps every record it receives
+#[derive(Default)]
+struct RecordingHook(Mutex<Vec<CommandRecord>>);
+
+impl CommandHook for RecordingHook {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    fn on_command<'a>(&'a self, record: &'a CommandRecord) -> BoxFuture<'a, std::io::Result<()>> {
+        self.0.lock().push(record.clone());
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[tokio::test]
+async fn test_embedded_writes_reach_command_hooks() {
+    let synap = Embedded::start(ServerConfig::default()).await.unwrap();
+    let hook = Arc::new(RecordingHook::default());
+    synap
+        .state()
+        .monitoring
+        .command_hooks()
+        .register(hook.clone());
+
+    let kv = synap.kv();
+    kv.set("user:1", "alice", None).await.unwrap();
+    kv.get::<_, String>("user:1").await.unwrap();
+    assert!(kv.incr("user:1").await.is_err());
+
+    let hooks = synap.state().monitoring.command_hooks();
+    for _ in 0..100 {
+        if hooks.stats()[0].delivered >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let records = hook.0.lock().clone();
+    let seen: Vec<_> = records
+        .iter()
+        .map(|r| (r.command.as_str(), r.resource.as_deref(), r.success))
+        .collect();
+    assert_eq!(
+        seen,
+        [
+            ("kv.set", Some("kv:user:1"), true),
+            ("kv.incr", Some("kv:user:1"), false),
+        ]
+    );
+    assert_eq!(records[0].protocol, CommandProtocol::Embedded);
+    assert!(records[1].error.is_some());
+}
+
+#[tokio::test]
+async fn test_embedded_persistence() {
+    let dir = TempDir::new().unwrap();
+    let snapshot_dir = dir.path().join("snapshots");
+    std::fs::create_dir_all(&snapshot_dir).unwrap();
+    let mut config = ServerConfig::default();
+    config.persistence = PersistenceConfig {
+        enabled: true,
+        wal: WALConfig {
+            enabled: true,
+            path: dir.path().join("synap.wal"),
+            buffer_size_kb: 64,
+            fsync_mode: FsyncMode::Always,
+            fsync_interval_ms: 1000,
+            max_size_mb: 1024,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
+        },
+        snapshot: SnapshotConfig {
+            enabled: true,
+            directory: snapshot_dir,
+            interval_secs: 3600,
+            operation_threshold: 10000,
+            max_snapshots: 3,
+            compression: false,
+            incremental: Default::default(),
+        },
+        durable_subscriptions: false,
+        archive: Default::default(),
+    };
+
+    {
+        let synap = Embedded::start(config.clone()).await.unwrap();
+        synap.kv().set("config:app", "v1", None).await.unwrap();
+        synap
+            .send_command(
+                "hash.set",
+                json!({"key": "user:1", "field": "name", "value": "alice"}),
+            )
+            .await
+            .unwrap();
+        synap.snapshot().await.unwrap();
+    }
+
+    let synap = Embedded::start(config).await.unwrap();
+    assert_eq!(
+        synap
+            .kv()
+            .get::<_, String>("config:app")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("v1")
+    );
+    let name = synap
+        .send_command("hash.get", json!({"key": "user:1", "field": "name"}))
+        .await
+        .unwrap();
+    assert_eq!(name["value"], "alice");
+}
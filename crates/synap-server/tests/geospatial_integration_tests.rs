@@ -70,6 +70,14 @@ async fn spawn_test_server() -> String {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     let user_manager = Arc::new(UserManager::new());
@@ -80,6 +88,7 @@ async fn spawn_test_server() -> String {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         McpConfig::default(),
         user_manager,
@@ -658,6 +667,19 @@ async fn test_geospatial_geosearch_rest() {
     let body: serde_json::Value = response.json().await.unwrap();
     let results = body["results"].as_array().unwrap();
     assert!(results.len() <= 2);
+
+    // An unknown unit is rejected, not read as meters
+    let response = client
+        .post(format!("{}/geospatial/cities/geosearch", base_url))
+        .json(&serde_json::json!({
+            "from_member": "San Francisco",
+            "by_radius": [50.0, "furlongs"]
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -731,6 +753,82 @@ async fn test_geospatial_geosearch_streamable() {
     assert!(results.len() <= 2);
 }
 
+#[tokio::test]
+async fn test_geospatial_unknown_unit_streamable() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    client
+        .post(format!("{}/api/v1/command", base_url))
+        .json(&serde_json::json!({
+            "command": "geospatial.geoadd",
+            "request_id": "test-1",
+            "payload": {
+                "key": "cities",
+                "locations": [
+                    {"lat": 37.7749, "lon": -122.4194, "member": "San Francisco"},
+                    {"lat": 37.8044, "lon": -122.2711, "member": "Oakland"}
+                ]
+            }
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    // A unit that is unknown, or not a string, is refused with the same
+    // error as on REST and RESP3 rather than read as meters
+    let payloads = [
+        (
+            "geospatial.geodist",
+            serde_json::json!({"key": "cities", "member1": "San Francisco", "member2": "Oakland", "unit": "furlongs"}),
+        ),
+        (
+            "geospatial.georadiusbymember",
+            serde_json::json!({"key": "cities", "member": "San Francisco", "radius": 50.0, "unit": 5}),
+        ),
+        (
+            "geospatial.geosearch",
+            serde_json::json!({"key": "cities", "from_member": "San Francisco", "by_radius": [50.0, "furlongs"]}),
+        ),
+        (
+            "geospatial.geosearch",
+            serde_json::json!({"key": "cities", "from_lonlat": [-122.4194, 37.7749], "by_box": [1.0, 1.0, "furlongs"]}),
+        ),
+    ];
+    for (command, payload) in payloads {
+        let response = client
+            .post(format!("{}/api/v1/command", base_url))
+            .json(&serde_json::json!({
+                "command": command,
+                "request_id": "test-2",
+                "payload": payload
+            }))
+            .send()
+            .await
+            .unwrap();
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["success"], false, "{command}: {body}");
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("Invalid distance unit"),
+            "{command}: {body}"
+        );
+    }
+
+    let response = client
+        .get(format!(
+            "{}/geospatial/cities/geodist/San Francisco/Oakland?unit=furlongs",
+            base_url
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_geospatial_stats_streamable() {
     let base_url = spawn_test_server().await;
@@ -1,17 +1,26 @@
 // Gzip Compression Tests
-// Tests that REST API supports gzip compression (tower-http CompressionLayer)
+// Tests that REST API supports gzip and zstd compression (tower-http CompressionLayer)
+// and that WebSockets negotiate permessage-deflate
 
-use reqwest::Client;
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress};
+use reqwest::{Client, header};
 use serde_json::json;
 use std::sync::Arc;
 use std::time::Duration;
 use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::config::HttpCompressionConfig;
 use synap_server::{
-    AppState, KVConfig, KVStore, QueueConfig, QueueManager, ScriptManager, create_router,
+    AppState, KVConfig, KVStore, QueueConfig, QueueManager, ScriptManager,
+    create_router_with_compression,
 };
-use tokio::net::TcpListener;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
 
 async fn spawn_test_server() -> String {
+    spawn_test_server_with(HttpCompressionConfig::default()).await
+}
+
+async fn spawn_test_server_with(compression: HttpCompressionConfig) -> String {
     let kv_store = Arc::new(KVStore::new(KVConfig::default()));
     let queue_manager = Arc::new(QueueManager::new(QueueConfig::default()));
 
@@ -64,22 +73,32 @@ async fn spawn_test_server() -> String {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     let user_manager = Arc::new(UserManager::new());
     let api_key_manager = Arc::new(ApiKeyManager::new());
-    let app = create_router(
+    let app = create_router_with_compression(
         state,
         synap_server::config::RateLimitConfig {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
         api_key_manager,
         false,
         false,
+        &compression,
     );
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -87,7 +106,12 @@ async fn spawn_test_server() -> String {
     let url = format!("http://{}", addr);
 
     tokio::spawn(async move {
-        axum::serve(listener, app).await.unwrap();
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
     });
 
     tokio::time::sleep(Duration::from_millis(100)).await;
@@ -316,3 +340,220 @@ async fn test_compression_with_large_payload() {
     assert!(value_str.contains("Large data content"));
     assert!(value_str.len() > 1000);
 }
+
+/// Store a value well above the default 1 KiB threshold under `key`
+async fn set_large_value(client: &Client, base_url: &str, key: &str) -> String {
+    let value = "Large data content ".repeat(200);
+    client
+        .post(format!("{}/kv/set", base_url))
+        .json(&json!({"key": key, "value": value}))
+        .send()
+        .await
+        .unwrap();
+    value
+}
+
+/// `Content-Encoding` of a GET sent with the given `Accept-Encoding`
+async fn content_encoding(client: &Client, url: &str, accept: &str) -> Option<String> {
+    let response = client
+        .get(url)
+        .header(header::ACCEPT_ENCODING, accept)
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    response
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .map(|v| v.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn test_encoding_follows_accept_encoding() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+    let value = set_large_value(&client, &base_url, "negotiated").await;
+    let url = format!("{}/kv/get/negotiated", base_url);
+
+    assert_eq!(
+        content_encoding(&client, &url, "gzip").await.as_deref(),
+        Some("gzip")
+    );
+    assert_eq!(
+        content_encoding(&client, &url, "identity").await,
+        None,
+        "no acceptable encoding means an identity response"
+    );
+
+    let response = client
+        .get(&url)
+        .header(header::ACCEPT_ENCODING, "zstd")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "zstd");
+    let body = zstd::decode_all(&response.bytes().await.unwrap()[..]).unwrap();
+    let decoded: String = serde_json::from_slice(&body).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[tokio::test]
+async fn test_small_responses_are_not_compressed() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    let encoding = content_encoding(&client, &format!("{}/health", base_url), "gzip, zstd").await;
+    assert_eq!(encoding, None, "/health is below the 1 KiB threshold");
+}
+
+#[tokio::test]
+async fn test_compression_settings_are_honored() {
+    let gzip_only = spawn_test_server_with(HttpCompressionConfig {
+        zstd: false,
+        ..Default::default()
+    })
+    .await;
+    let disabled = spawn_test_server_with(HttpCompressionConfig {
+        enabled: false,
+        ..Default::default()
+    })
+    .await;
+    let client = Client::new();
+
+    set_large_value(&client, &gzip_only, "k").await;
+    let encoding = content_encoding(
+        &client,
+        &format!("{}/kv/get/k", gzip_only),
+        "zstd, gzip;q=0.5",
+    )
+    .await;
+    assert_eq!(encoding.as_deref(), Some("gzip"));
+
+    set_large_value(&client, &disabled, "k").await;
+    let encoding = content_encoding(&client, &format!("{}/kv/get/k", disabled), "gzip").await;
+    assert_eq!(encoding, None);
+}
+
+// ==================== WebSocket permessage-deflate ====================
+
+const FIN: u8 = 0x80;
+const RSV1: u8 = 0x40;
+const TEXT: u8 = 0x1;
+
+/// Open `/api/v1/command/ws` by hand, offering `permessage-deflate`, and
+/// return the socket with the lowercased handshake response
+async fn command_ws_with_deflate(base_url: &str) -> (TcpStream, String) {
+    let addr = base_url.trim_start_matches("http://");
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!(
+        "GET /api/v1/command/ws HTTP/1.1\r\n\
+         Host: {addr}\r\n\
+         Connection: Upgrade\r\n\
+         Upgrade: websocket\r\n\
+         Sec-WebSocket-Version: 13\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+         Sec-WebSocket-Extensions: permessage-deflate; client_max_window_bits\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        response.push(stream.read_u8().await.unwrap());
+    }
+    let response = String::from_utf8(response).unwrap().to_lowercase();
+    assert!(response.starts_with("http/1.1 101"), "{response}");
+    (stream, response)
+}
+
+/// Send one masked text frame; `deflated` compresses it and sets RSV1
+async fn send_text(stream: &mut TcpStream, text: &str, deflated: bool) {
+    let (first, payload) = if deflated {
+        let mut deflater = Compress::new(Compression::default(), false);
+        let mut payload = Vec::with_capacity(text.len() + 64);
+        deflater
+            .compress_vec(text.as_bytes(), &mut payload, FlushCompress::Sync)
+            .unwrap();
+        payload.truncate(payload.len() - 4);
+        (FIN | RSV1 | TEXT, payload)
+    } else {
+        (FIN | TEXT, text.as_bytes().to_vec())
+    };
+
+    let mask = [0x12, 0x34, 0x56, 0x78];
+    let mut frame = vec![first];
+    match payload.len() {
+        len @ 0..126 => frame.push(0x80 | len as u8),
+        len => {
+            frame.push(0x80 | 126);
+            frame.extend((len as u16).to_be_bytes());
+        }
+    }
+    frame.extend(mask);
+    frame.extend(payload.iter().zip(mask.iter().cycle()).map(|(b, m)| b ^ m));
+    stream.write_all(&frame).await.unwrap();
+}
+
+/// Read one unfragmented server frame: its first header byte and its
+/// payload, inflated when RSV1 is set
+async fn recv_frame(stream: &mut TcpStream) -> (u8, serde_json::Value) {
+    let first = stream.read_u8().await.unwrap();
+    let len = match stream.read_u8().await.unwrap() {
+        126 => stream.read_u16().await.unwrap() as usize,
+        127 => stream.read_u64().await.unwrap() as usize,
+        len => len as usize,
+    };
+    let mut payload = vec![0; len];
+    stream.read_exact(&mut payload).await.unwrap();
+
+    if first & RSV1 != 0 {
+        payload.extend([0x00, 0x00, 0xff, 0xff]);
+        let mut inflated = Vec::with_capacity(1 << 16);
+        Decompress::new(false)
+            .decompress_vec(&payload, &mut inflated, FlushDecompress::Sync)
+            .unwrap();
+        payload = inflated;
+    }
+    (first, serde_json::from_slice(&payload).unwrap())
+}
+
+#[tokio::test]
+async fn test_websocket_permessage_deflate() {
+    let base_url = spawn_test_server().await;
+    let (mut ws, response) = command_ws_with_deflate(&base_url).await;
+    assert!(
+        response
+            .contains("sec-websocket-extensions: permessage-deflate; server_no_context_takeover"),
+        "{response}"
+    );
+
+    // A compressed request is inflated; the short answer goes out as-is
+    let value = "Large data content ".repeat(200);
+    let set = json!({
+        "command": "kv.set",
+        "request_id": "set",
+        "payload": {"key": "k", "value": value},
+    });
+    send_text(&mut ws, &set.to_string(), true).await;
+    let (first, response) = recv_frame(&mut ws).await;
+    assert_eq!(first, FIN | TEXT);
+    assert_eq!(response["request_id"], "set");
+    assert_eq!(response["success"], true, "{response}");
+
+    // An answer past websocket_min_size_bytes is compressed
+    let get = json!({"command": "kv.get", "request_id": "get", "payload": {"key": "k"}});
+    send_text(&mut ws, &get.to_string(), false).await;
+    let (first, response) = recv_frame(&mut ws).await;
+    assert_eq!(first, FIN | RSV1 | TEXT);
+    assert_eq!(response["payload"], value.as_str());
+}
+
+#[tokio::test]
+async fn test_websocket_deflate_can_be_disabled() {
+    let base_url = spawn_test_server_with(HttpCompressionConfig {
+        websocket: false,
+        ..Default::default()
+    })
+    .await;
+    let (_ws, response) = command_ws_with_deflate(&base_url).await;
+    assert!(!response.contains("sec-websocket-extensions"), "{response}");
+}
@@ -67,6 +67,14 @@ async fn spawn_test_server() -> String {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     let user_manager = Arc::new(UserManager::new());
@@ -77,6 +85,7 @@ async fn spawn_test_server() -> String {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -627,6 +636,8 @@ async fn test_hash_with_persistence_recovery() {
             fsync_mode: FsyncMode::Always,
             fsync_interval_ms: 1000,
             max_size_mb: 1024,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         },
         snapshot: SnapshotConfig {
             enabled: true,
@@ -635,7 +646,10 @@ async fn test_hash_with_persistence_recovery() {
             operation_threshold: 10000,
             max_snapshots: 3,
             compression: false,
+            incremental: Default::default(),
         },
+        durable_subscriptions: false,
+        archive: Default::default(),
     };
 
     // First run: Create hash data
@@ -672,7 +686,7 @@ async fn test_hash_with_persistence_recovery() {
         let kv_config = KVConfig::default();
         let queue_config = QueueConfig::default();
 
-        let (_, hash_store, _, _, _, _, _) =
+        let (_, hash_store, ..) =
             synap_server::persistence::recover(&persist_config, kv_config, queue_config)
                 .await
                 .unwrap();
@@ -709,6 +723,8 @@ async fn test_hash_hincrby_persistence() {
             fsync_mode: FsyncMode::Always,
             fsync_interval_ms: 1000,
             max_size_mb: 1024,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
         },
         snapshot: SnapshotConfig {
             enabled: true,
@@ -717,7 +733,10 @@ async fn test_hash_hincrby_persistence() {
             operation_threshold: 10000,
             max_snapshots: 3,
             compression: false,
+            incremental: Default::default(),
         },
+        durable_subscriptions: false,
+        archive: Default::default(),
     };
 
     // First run: Increment counter
@@ -745,7 +764,7 @@ async fn test_hash_hincrby_persistence() {
         let kv_config = KVConfig::default();
         let queue_config = QueueConfig::default();
 
-        let (_, hash_store, _, _, _, _, _) =
+        let (_, hash_store, ..) =
             synap_server::persistence::recover(&persist_config, kv_config, queue_config)
                 .await
                 .unwrap();
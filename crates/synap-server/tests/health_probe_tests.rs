@@ -0,0 +1,153 @@
+//! `/health/live` and `/health/ready`: dependency checks report 503 with a
+//! reason, and the startup probes answer while recovery runs.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::Value;
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::cluster::topology::ClusterTopology;
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::health::StartupProbes;
+use synap_server::persistence::StoreArcs;
+use synap_server::replication::{NodeRole, ReplicaNode, ReplicationConfig, ReplicationHandle};
+use synap_server::server::handlers::AppState;
+use synap_server::{KVConfig, KVStore, create_router};
+
+fn test_state() -> AppState {
+    app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    )
+}
+
+fn router(state: AppState) -> axum::Router {
+    create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    )
+}
+
+async fn spawn_test_server(state: AppState) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let app = router(state);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn get(client: &Client, url: String) -> (u16, Value) {
+    let response = client.get(url).send().await.unwrap();
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_ready_when_every_check_passes() {
+    let url = spawn_test_server(test_state()).await;
+    let client = Client::new();
+
+    let (status, body) = get(&client, format!("{url}/health/live")).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "healthy");
+
+    let (status, body) = get(&client, format!("{url}/health/ready")).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ready"], true);
+    assert!(body.get("reason").is_none());
+    for check in ["recovery", "warmup", "replication"] {
+        assert_eq!(body["checks"][check]["ok"], true, "{check}");
+    }
+}
+
+#[tokio::test]
+async fn test_unassigned_cluster_slots_are_not_ready() {
+    let mut state = test_state();
+    state.cluster_topology = Some(Arc::new(ClusterTopology::new("node-0".to_string())));
+    let url = spawn_test_server(state).await;
+    let client = Client::new();
+
+    let (status, body) = get(&client, format!("{url}/health/ready")).await;
+    assert_eq!(status, 503);
+    assert_eq!(body["ready"], false);
+    assert_eq!(body["checks"]["cluster"]["ok"], false);
+    assert!(body["reason"].as_str().unwrap().contains("cluster slots"));
+
+    // Liveness is unaffected
+    let (status, _) = get(&client, format!("{url}/health/live")).await;
+    assert_eq!(status, 200);
+}
+
+#[tokio::test]
+async fn test_replica_without_master_link_is_not_ready() {
+    let mut state = test_state();
+    let mut config = ReplicationConfig::default();
+    config.enabled = true;
+    config.role = NodeRole::Replica;
+    config.master_address = Some("127.0.0.1:1".parse().unwrap());
+    config.auto_reconnect = false;
+    let replica = ReplicaNode::new(config, StoreArcs::kv_only(state.kv_store.clone()))
+        .await
+        .unwrap();
+    state.replication = Some(ReplicationHandle::Replica(replica));
+    let url = spawn_test_server(state).await;
+
+    let (status, body) = get(&Client::new(), format!("{url}/health/ready")).await;
+    assert_eq!(status, 503);
+    assert_eq!(
+        body["checks"]["replication"]["reason"],
+        "replication link to master is down"
+    );
+}
+
+#[tokio::test]
+async fn test_startup_probes_hand_over_to_the_router() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let probes = StartupProbes::serve(listener.try_clone().unwrap()).unwrap();
+    let client = Client::new();
+
+    let (status, body) = get(&client, format!("{url}/health/live")).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "starting");
+
+    let (status, body) = get(&client, format!("{url}/health/ready")).await;
+    assert_eq!(status, 503);
+    assert_eq!(body["reason"], "persistence recovery in progress");
+    assert_eq!(body["checks"]["recovery"]["ok"], false);
+
+    let response = client
+        .get(format!("{url}/kv/get/anything"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+
+    probes.stop().await;
+    listener.set_nonblocking(true).unwrap();
+    let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+    let app = router(test_state());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let (status, body) = get(&Client::new(), format!("{url}/health/ready")).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["checks"]["recovery"]["ok"], true);
+}
@@ -0,0 +1,113 @@
+//! `/monitoring/hotkeys` and `monitoring.hotkeys`: sampled per-interval
+//! access counts of KV keys.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, HotKeyTracker, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_test_server(kv: Arc<KVStore>) -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        kv,
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    url
+}
+
+#[tokio::test]
+async fn test_hot_keys_ranks_sampled_reads_and_writes() {
+    let tracker = HotKeyTracker::new(1, Duration::from_secs(60), 1_000);
+    let kv = Arc::new(KVStore::new(KVConfig::default()).with_hot_keys(Some(Arc::new(tracker))));
+
+    kv.set("hot", b"v".to_vec(), None).await.unwrap();
+    for _ in 0..5 {
+        kv.get("hot").await.unwrap();
+    }
+    kv.set("warm", b"v".to_vec(), None).await.unwrap();
+    kv.get("warm").await.unwrap();
+    kv.get("cold").await.unwrap();
+
+    let base_url = spawn_test_server(kv).await;
+    let client = Client::new();
+
+    let body: serde_json::Value = client
+        .get(format!("{base_url}/monitoring/hotkeys?top=2"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(body["sample_rate"], 1);
+    assert_eq!(body["interval_secs"], 60);
+    let current = body["current"].as_array().unwrap();
+    assert_eq!(current.len(), 2);
+    assert_eq!(current[0], json!({"key": "hot", "reads": 5, "writes": 1}));
+    assert_eq!(current[1], json!({"key": "warm", "reads": 1, "writes": 1}));
+    assert!(body["previous"].as_array().unwrap().is_empty());
+
+    let response: serde_json::Value = client
+        .post(format!("{base_url}/api/v1/command"))
+        .json(&json!({
+            "command": "monitoring.hotkeys",
+            "request_id": "hk-1",
+            "payload": {"top": 1}
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(response["success"], true);
+    let current = response["payload"]["current"].as_array().unwrap();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0]["key"], "hot");
+}
+
+#[tokio::test]
+async fn test_hot_keys_requires_tracking() {
+    let kv = Arc::new(KVStore::new(KVConfig::default()));
+    let base_url = spawn_test_server(kv).await;
+
+    let response = Client::new()
+        .get(format!("{base_url}/monitoring/hotkeys"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+}
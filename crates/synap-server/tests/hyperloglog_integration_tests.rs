@@ -35,6 +35,7 @@ async fn spawn_test_server() -> String {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -184,7 +184,7 @@ async fn test_async_wal_group_commit() {
     for i in 0..1000 {
         let op = Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![0u8; 64],
+            value: vec![0u8; 64].into(),
             ttl: None,
         };
         wal.append(op).await.unwrap();
@@ -0,0 +1,229 @@
+//! Per-key limits end to end: writes that would grow a key past its limit are
+//! refused with 413 on REST and an error on the command endpoint, and
+//! `GET /memory/doctor` reports the largest keys against their limits along
+//! with per-datatype memory estimates.
+
+mod app_state_helper;
+
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::core::{HashStore, KeyLimits, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+const LIMITS: KeyLimits = KeyLimits {
+    max_value_bytes: Some(8),
+    max_hash_fields: Some(2),
+    max_list_length: Some(3),
+    max_zset_members: Some(2),
+};
+
+async fn spawn_test_server(limits: KeyLimits) -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default()).with_key_limits(limits)),
+        Arc::new(HashStore::new().with_key_limits(limits)),
+        Arc::new(ListStore::new().with_key_limits(limits)),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new().with_key_limits(limits)),
+    );
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(synap_server::auth::UserManager::new()),
+        Arc::new(synap_server::auth::ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn command(url: &str, command: &str, payload: Value) -> Value {
+    Client::new()
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({"command": command, "request_id": "t", "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_oversized_writes_are_refused() {
+    let url = spawn_test_server(LIMITS).await;
+    let client = Client::new();
+
+    let set = |value: &str| {
+        client
+            .post(format!("{url}/kv/set"))
+            .json(&json!({"key": "s", "value": value}))
+            .send()
+    };
+    assert_eq!(set("short").await.unwrap().status(), StatusCode::OK);
+    let refused = set("far too long").await.unwrap();
+    assert_eq!(refused.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body: Value = refused.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("max_value_bytes"));
+
+    for field in ["a", "b"] {
+        let ok = command(
+            &url,
+            "hash.set",
+            json!({"key": "h", "field": field, "value": "1"}),
+        )
+        .await;
+        assert_eq!(ok["success"], true);
+    }
+    let refused = command(
+        &url,
+        "hash.set",
+        json!({"key": "h", "field": "c", "value": "1"}),
+    )
+    .await;
+    assert_eq!(refused["success"], false);
+    assert!(
+        refused["error"]
+            .as_str()
+            .unwrap()
+            .contains("max_hash_fields")
+    );
+
+    let push = command(
+        &url,
+        "list.rpush",
+        json!({"key": "l", "values": ["1", "2", "3", "4"]}),
+    )
+    .await;
+    assert_eq!(push["success"], false);
+
+    for (member, score) in [("a", 1.0), ("b", 2.0)] {
+        let ok = command(
+            &url,
+            "sortedset.zadd",
+            json!({"key": "z", "member": member, "score": score}),
+        )
+        .await;
+        assert_eq!(ok["success"], true);
+    }
+    let refused = command(
+        &url,
+        "sortedset.zadd",
+        json!({"key": "z", "member": "c", "score": 3.0}),
+    )
+    .await;
+    assert_eq!(refused["success"], false);
+    // Updating an existing member does not grow the set
+    let update = command(
+        &url,
+        "sortedset.zadd",
+        json!({"key": "z", "member": "a", "score": 9.0}),
+    )
+    .await;
+    assert_eq!(update["success"], true);
+}
+
+#[tokio::test]
+async fn test_memory_doctor_reports_largest_keys() {
+    let url = spawn_test_server(LIMITS).await;
+    command(
+        &url,
+        "list.rpush",
+        json!({"key": "big", "values": ["1", "2", "3"]}),
+    )
+    .await;
+    command(&url, "list.rpush", json!({"key": "small", "values": ["1"]})).await;
+
+    let report: Value = Client::new()
+        .get(format!("{url}/memory/doctor?top=1"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(report["top"], 1);
+    assert_eq!(report["limits"]["max_list_length"], 3);
+    assert_eq!(
+        report["largest"]["lists"],
+        json!([{"key": "big", "size": 3, "limit": 3, "over_limit": false}])
+    );
+    assert_eq!(report["largest"]["hashes"], json!([]));
+
+    let via_command = command(&url, "memory.doctor", json!({"top": 5})).await;
+    assert_eq!(via_command["success"], true);
+    assert_eq!(via_command["payload"]["top"], 5);
+    assert_eq!(
+        via_command["payload"]["largest"]["lists"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_memory_doctor_profiles_structures() {
+    let url = spawn_test_server(KeyLimits::default()).await;
+    command(
+        &url,
+        "hash.mset",
+        json!({"key": "profile", "fields": {"name": "ada", "city": "london"}}),
+    )
+    .await;
+    command(
+        &url,
+        "set.add",
+        json!({"key": "tags", "members": ["a", "b"]}),
+    )
+    .await;
+    command(
+        &url,
+        "kv.set",
+        json!({"key": "blob", "value": "x".repeat(4096)}),
+    )
+    .await;
+
+    let report = command(&url, "memory.doctor", json!({"top": 2})).await;
+    assert_eq!(report["success"], true);
+    let report = &report["payload"];
+
+    let hashes = &report["structures"]["hashes"];
+    assert_eq!(hashes["keys"], 1);
+    assert_eq!(hashes["elements"], 2);
+    assert_eq!(hashes["encodings"]["hashtable"], 1);
+    assert_eq!(report["structures"]["sets"]["encodings"]["packed"], 1);
+    assert_eq!(report["structures"]["lists"]["keys"], 0);
+
+    let biggest = report["biggest_keys"].as_array().unwrap();
+    assert_eq!(biggest.len(), 2);
+    assert_eq!(biggest[0]["key"], "blob");
+    assert_eq!(biggest[0]["type"], "string");
+    assert_eq!(report["biggest_hashes"][0]["key"], "profile");
+
+    // The 4 KiB string dwarfs the other keys.
+    let suggestions = report["suggestions"].as_array().unwrap();
+    assert!(
+        suggestions
+            .iter()
+            .any(|s| s.as_str().unwrap().starts_with("Key 'blob' (string)"))
+    );
+
+    let usage = command(&url, "memory.usage", json!({"key": "tags"})).await;
+    assert_eq!(usage["payload"]["type"], "set");
+    assert_eq!(usage["payload"]["encoding"], "packed");
+    assert_eq!(usage["payload"]["elements"], 2);
+    // Members are stored JSON-encoded: `"a"` is 3 bytes.
+    assert_eq!(usage["payload"]["avg_item_bytes"], 3);
+}
@@ -3,6 +3,7 @@
 //! Comprehensive tests for KV operations with master-slave replication.
 //! Validates that all KV operations are correctly replicated to replica nodes.
 
+use bytes::Bytes;
 use std::sync::Arc;
 use std::time::Duration;
 use synap_server::persistence::types::Operation;
@@ -33,9 +34,12 @@ async fn create_kv_master() -> (Arc<MasterNode>, Arc<KVStore>, std::net::SocketA
 
     let kv = Arc::new(KVStore::new(KVConfig::default()));
     let master = Arc::new(
-        MasterNode::new(config.clone(), Arc::clone(&kv), None)
-            .await
-            .unwrap(),
+        MasterNode::new(
+            config.clone(),
+            synap_server::persistence::StoreArcs::kv_only(Arc::clone(&kv)),
+        )
+        .await
+        .unwrap(),
     );
 
     sleep(Duration::from_millis(100)).await;
@@ -109,7 +113,7 @@ async fn test_kv_set_get_replication() {
         .unwrap();
     master.replicate(Operation::KVSet {
         key: "key4".to_string(),
-        value: b"value4".to_vec(),
+        value: Bytes::from_static(b"value4"),
         ttl: None,
     });
 
@@ -197,7 +201,7 @@ async fn test_kv_batch_operations_replication() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -248,7 +252,7 @@ async fn test_kv_mset_mdel_replication() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -331,7 +335,7 @@ async fn test_kv_ttl_replication() {
         .unwrap();
     master.replicate(Operation::KVSet {
         key: "ttl_key3".to_string(),
-        value: b"expiring".to_vec(),
+        value: Bytes::from_static(b"expiring"),
         ttl: Some(1800),
     });
 
@@ -376,7 +380,7 @@ async fn test_kv_update_operations_replication() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -428,7 +432,7 @@ async fn test_kv_scan_operations_replication() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -490,7 +494,7 @@ async fn test_kv_overwrite_replication() {
             .unwrap();
         master.replicate(Operation::KVSet {
             key: "overwrite_key".to_string(),
-            value,
+            value: value.into(),
             ttl: None,
         });
         sleep(Duration::from_millis(100)).await;
@@ -561,7 +565,7 @@ async fn test_kv_mixed_operations_replication() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -573,7 +577,7 @@ async fn test_kv_mixed_operations_replication() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -0,0 +1,123 @@
+//! Client-side caching end to end: the SDK's local cache over
+//! `GET /kv/tracking/ws`, invalidated when another client writes a key it
+//! holds.
+
+mod app_state_helper;
+
+use std::sync::Arc;
+use std::time::Duration;
+use synap_sdk::{ClientCacheConfig, SynapClient, SynapConfig};
+use synap_server::core::{
+    HashStore, KeyWatchNotifier, ListStore, SetStore, SortedSetStore, TrackingTable,
+};
+use synap_server::{KVConfig, KVStore, PubSubRouter, create_router};
+use tokio::net::TcpListener;
+
+/// Spawn a server whose KV store carries a tracking table, the way `main.rs`
+/// wires it, and return its base URL.
+async fn spawn_tracking_server() -> String {
+    let notifier = KeyWatchNotifier::new(Arc::new(PubSubRouter::new()), 0)
+        .with_tracking(Arc::new(TrackingTable::default()));
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default()).with_watch_notifier(Some(Arc::new(notifier)))),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(synap_server::auth::UserManager::new()),
+        Arc::new(synap_server::auth::ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    url
+}
+
+fn cached_client(url: &str) -> SynapClient {
+    SynapClient::new(SynapConfig::new(url).with_client_cache(ClientCacheConfig::default())).unwrap()
+}
+
+#[tokio::test]
+async fn a_cached_read_is_invalidated_by_another_clients_write() {
+    let url = spawn_tracking_server().await;
+    let reader = cached_client(&url);
+    let writer = SynapClient::new(SynapConfig::new(&url)).unwrap();
+
+    writer.kv().set("user:1", "alice", None).await.unwrap();
+    let first: Option<String> = reader.kv().get("user:1").await.unwrap();
+    let second: Option<String> = reader.kv().get("user:1").await.unwrap();
+    assert_eq!(first.as_deref(), Some("alice"));
+    assert_eq!(second.as_deref(), Some("alice"));
+    let stats = reader.kv().cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+    writer.kv().set("user:1", "bob", None).await.unwrap();
+
+    let value = tokio::time::timeout(Duration::from_secs(2), async {
+        loop {
+            let value: Option<String> = reader.kv().get("user:1").await.unwrap();
+            if value.as_deref() == Some("bob") {
+                return value;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the invalidation reaches the reader");
+    assert_eq!(value.as_deref(), Some("bob"));
+    assert!(reader.kv().cache_stats().unwrap().invalidations >= 1);
+}
+
+#[tokio::test]
+async fn a_missing_key_is_cached_until_it_is_written() {
+    let url = spawn_tracking_server().await;
+    let reader = cached_client(&url);
+    let writer = SynapClient::new(SynapConfig::new(&url)).unwrap();
+
+    let missing: Option<String> = reader.kv().get("later").await.unwrap();
+    assert_eq!(missing, None);
+    assert_eq!(reader.kv().cache_stats().unwrap().entries, 1);
+
+    writer.kv().set("later", "here", None).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while reader.kv().cache_stats().unwrap().entries > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the invalidation reaches the reader");
+    let value: Option<String> = reader.kv().get("later").await.unwrap();
+    assert_eq!(value.as_deref(), Some("here"));
+}
+
+#[tokio::test]
+async fn a_client_reads_its_own_writes() {
+    let url = spawn_tracking_server().await;
+    let client = cached_client(&url);
+
+    client.kv().set("counter", "1", None).await.unwrap();
+    let _: Option<String> = client.kv().get("counter").await.unwrap();
+    client.kv().incr("counter").await.unwrap();
+
+    let value: Option<String> = client.kv().get("counter").await.unwrap();
+    assert_eq!(value.as_deref(), Some("2"));
+}
@@ -0,0 +1,143 @@
+//! `/latency` and the `latency.*` commands: latency spike history per
+//! command class and snapshot.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::monitoring::LatencyMonitor;
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_test_server() -> (String, Arc<LatencyMonitor>) {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    let latency = state.monitoring.latency();
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    (url, latency)
+}
+
+async fn command(client: &Client, base_url: &str, body: serde_json::Value) -> serde_json::Value {
+    client
+        .post(format!("{base_url}/api/v1/command"))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_latency_latest_history_and_reset() {
+    let (base_url, latency) = spawn_test_server().await;
+    latency.record("snapshot", Duration::from_millis(250));
+    latency.record("command.kv", Duration::from_millis(120));
+    // Below the default 100ms threshold
+    latency.record("command.hash", Duration::from_millis(20));
+    let client = Client::new();
+
+    let body: serde_json::Value = client
+        .get(format!("{base_url}/latency"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let events = body["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["event"], "command.kv");
+    assert_eq!(events[0]["latency_ms"], 120);
+    assert_eq!(events[1]["event"], "snapshot");
+    assert_eq!(events[1]["max_latency_ms"], 250);
+
+    let body: serde_json::Value = client
+        .get(format!("{base_url}/latency/snapshot"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["event"], "snapshot");
+    assert_eq!(body["samples"].as_array().unwrap().len(), 1);
+    assert_eq!(body["samples"][0]["latency_ms"], 250);
+
+    let response = command(
+        &client,
+        &base_url,
+        json!({
+            "command": "latency.history",
+            "request_id": "lat-1",
+            "payload": {"event": "command.kv"}
+        }),
+    )
+    .await;
+    assert_eq!(response["payload"]["samples"][0]["latency_ms"], 120);
+
+    let body: serde_json::Value = client
+        .delete(format!("{base_url}/latency/snapshot"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["reset"], 1);
+
+    let response = command(
+        &client,
+        &base_url,
+        json!({
+            "command": "latency.reset",
+            "request_id": "lat-2",
+            "payload": {}
+        }),
+    )
+    .await;
+    assert_eq!(response["payload"]["reset"], 1);
+
+    let response = command(
+        &client,
+        &base_url,
+        json!({
+            "command": "latency.latest",
+            "request_id": "lat-3",
+            "payload": {}
+        }),
+    )
+    .await;
+    assert!(response["payload"]["events"].as_array().unwrap().is_empty());
+}
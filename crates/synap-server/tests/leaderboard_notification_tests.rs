@@ -0,0 +1,150 @@
+//! Leaderboard notifications end to end: a window configured over the command
+//! endpoint or `PUT /sortedset/{key}/notify` makes `ZADD`s publish to the key's
+//! `__zset@0__:<key>` channel.
+
+mod app_state_helper;
+
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{
+    HashStore, LeaderboardEvent, LeaderboardNotifier, ListStore, PubSubRouter, SetStore,
+    SortedSetStore,
+};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+async fn spawn_test_server(notifier: Option<Arc<LeaderboardNotifier>>) -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new().with_leaderboard_notifier(notifier)),
+    );
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn command(url: &str, command: &str, payload: Value) -> Value {
+    Client::new()
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({"command": command, "request_id": "t", "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_zadd_into_the_window_publishes_entered() {
+    let router = Arc::new(PubSubRouter::new());
+    let notifier = Arc::new(LeaderboardNotifier::new(router.clone(), 0));
+    let url = spawn_test_server(Some(notifier)).await;
+
+    let set = command(
+        &url,
+        "sortedset.notify.set",
+        json!({"key": "board", "top_n": 2}),
+    )
+    .await;
+    assert_eq!(set["success"], true);
+    assert_eq!(set["payload"]["channel"], "__zset@0__:board");
+    assert_eq!(set["payload"]["window"]["top_n"], 2);
+
+    let subscription = router
+        .subscribe(vec!["__zset@0__:board".to_string()])
+        .unwrap();
+    let (tx, mut rx) = mpsc::channel(16);
+    router.register_connection(subscription.subscriber_id, tx);
+
+    command(
+        &url,
+        "sortedset.zadd",
+        json!({"key": "board", "member": "alice", "score": 10.0}),
+    )
+    .await;
+
+    let message = rx.try_recv().expect("entered event published");
+    let event: LeaderboardEvent = serde_json::from_value(message.payload).unwrap();
+    assert_eq!(event.event, "entered");
+    assert_eq!(event.member, "alice");
+    assert_eq!(event.rank, Some(0));
+}
+
+#[tokio::test]
+async fn test_rest_window_round_trip() {
+    let notifier = Arc::new(LeaderboardNotifier::new(Arc::new(PubSubRouter::new()), 0));
+    let url = spawn_test_server(Some(notifier)).await;
+    let client = Client::new();
+    let endpoint = format!("{url}/sortedset/board/notify");
+
+    let put = client
+        .put(&endpoint)
+        .json(&json!({"top_n": 5, "rank_threshold": 2}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(put.status(), StatusCode::OK);
+
+    let get: Value = client
+        .get(&endpoint)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(get["window"], json!({"top_n": 5, "rank_threshold": 2}));
+
+    let invalid = client
+        .put(&endpoint)
+        .json(&json!({"top_n": 0}))
+        .send()
+        .await
+        .unwrap();
+    assert!(invalid.status().is_client_error());
+
+    let del: Value = client
+        .delete(&endpoint)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(del["removed"], true);
+}
+
+#[tokio::test]
+async fn test_notify_without_notifier_is_rejected() {
+    let url = spawn_test_server(None).await;
+    let response = command(
+        &url,
+        "sortedset.notify.set",
+        json!({"key": "board", "top_n": 2}),
+    )
+    .await;
+    assert_eq!(response["success"], false);
+}
@@ -0,0 +1,126 @@
+//! Distributed locks end to end: the SDK's `LockManager` against the
+//! `lock.*` commands.
+
+mod app_state_helper;
+
+use std::sync::Arc;
+use std::time::Duration;
+use synap_sdk::{SynapClient, SynapConfig};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore};
+
+async fn spawn_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    app_state_helper::spawn_server_with_state(state).await
+}
+
+fn client(url: &str) -> SynapClient {
+    SynapClient::new(SynapConfig::new(url)).unwrap()
+}
+
+#[tokio::test]
+async fn a_lock_has_one_holder_until_released() {
+    let url = spawn_server().await;
+    let (a, b) = (client(&url), client(&url));
+    let ttl = Duration::from_secs(30);
+
+    let lock = a.lock().try_acquire("job", ttl).await.unwrap().unwrap();
+    assert!(lock.is_held());
+    assert!(b.lock().try_acquire("job", ttl).await.unwrap().is_none());
+
+    assert!(lock.release().await.unwrap());
+    let next = b.lock().try_acquire("job", ttl).await.unwrap().unwrap();
+    assert!(next.is_held());
+}
+
+#[tokio::test]
+async fn a_waiting_acquire_gets_the_lock_when_the_lease_runs_out() {
+    let url = spawn_server().await;
+    let (a, b) = (client(&url), client(&url));
+
+    let stale = a
+        .lock()
+        .try_acquire("job", Duration::from_millis(200))
+        .await
+        .unwrap()
+        .unwrap();
+    let fresh = b
+        .lock()
+        .acquire("job", Duration::from_secs(30), Duration::from_secs(2))
+        .await
+        .unwrap()
+        .expect("the expired lease is granted again");
+
+    assert!(
+        fresh.token() > stale.token(),
+        "the new holder fences the old"
+    );
+    assert!(!stale.is_held());
+    assert!(!stale.renew().await.unwrap());
+    assert!(
+        !stale.release().await.unwrap(),
+        "a stale token frees nothing"
+    );
+    assert!(fresh.is_held());
+}
+
+#[tokio::test]
+async fn keep_alive_holds_the_lock_past_its_ttl() {
+    let url = spawn_server().await;
+    let (a, b) = (client(&url), client(&url));
+
+    let mut lock = a
+        .lock()
+        .try_acquire("job", Duration::from_millis(300))
+        .await
+        .unwrap()
+        .unwrap();
+    lock.keep_alive();
+    tokio::time::sleep(Duration::from_millis(700)).await;
+
+    assert!(lock.is_held());
+    assert!(
+        b.lock()
+            .try_acquire("job", Duration::from_secs(1))
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn a_ttl_past_the_maximum_lease_is_refused() {
+    let url = spawn_server().await;
+    let a = client(&url);
+
+    let error = a
+        .send_command(
+            "lock.acquire",
+            serde_json::json!({"key": "job", "ttl": u64::MAX}),
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("at most"), "{error}");
+
+    let lock = a
+        .lock()
+        .try_acquire("job", Duration::from_secs(30))
+        .await
+        .unwrap()
+        .unwrap();
+    let error = a
+        .send_command(
+            "lock.renew",
+            serde_json::json!({"key": "job", "token": lock.token(), "ttl": u64::MAX}),
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("at most"), "{error}");
+    assert!(lock.is_held());
+}
@@ -0,0 +1,244 @@
+//! Logical databases: the envelope's `db` field picks the stores a command
+//! runs in, FLUSHDB and INFO keyspace are per database, shared namespaces
+//! ignore the selection, and each database has its own WAL.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::config::ServerConfig;
+use synap_server::core::{
+    GlobalMemory, HashStore, ListStore, PubSubRouter, SetStore, SortedSetStore,
+};
+use synap_server::databases::{Database, Databases};
+use synap_server::monitoring::{LatencyMonitor, MonitoringManager};
+use synap_server::persistence::types::FsyncMode;
+use synap_server::{KVConfig, KVStore, QueueConfig, QueueManager, create_router};
+
+fn kv_config() -> KVConfig {
+    KVConfig {
+        allow_flush_commands: true,
+        ..KVConfig::default()
+    }
+}
+
+/// Start a server with databases 0 to 2
+async fn spawn_test_server() -> String {
+    let kv_store = Arc::new(KVStore::new(kv_config()));
+    let hash_store = Arc::new(HashStore::new());
+    let list_store = Arc::new(ListStore::new());
+    let set_store = Arc::new(SetStore::new());
+    let sorted_set_store = Arc::new(SortedSetStore::new());
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        kv_store.clone(),
+        hash_store.clone(),
+        list_store.clone(),
+        set_store.clone(),
+        sorted_set_store.clone(),
+    );
+    state.queue_manager = Some(Arc::new(QueueManager::new(QueueConfig::default())));
+    let databases = Databases::new(vec![
+        Database::new(KVStore::new(kv_config())),
+        Database::new(KVStore::new(kv_config())),
+    ]);
+    state.monitoring = Arc::new(
+        MonitoringManager::new(
+            kv_store,
+            hash_store,
+            list_store,
+            set_store,
+            sorted_set_store,
+        )
+        .with_databases(Arc::new(databases)),
+    );
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 1000,
+            burst_size: 1000,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    url
+}
+
+async fn command(client: &Client, url: &str, db: u32, name: &str, payload: Value) -> Value {
+    client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({"command": name, "request_id": name, "db": db, "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_databases_keep_keys_apart() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    for db in 0..3 {
+        let body = command(
+            &client,
+            &url,
+            db,
+            "kv.set",
+            json!({"key": "name", "value": format!("db{db}")}),
+        )
+        .await;
+        assert_eq!(body["success"], true, "{body}");
+    }
+    command(
+        &client,
+        &url,
+        1,
+        "hash.set",
+        json!({"key": "h", "field": "f", "value": "v"}),
+    )
+    .await;
+
+    for db in 0..3 {
+        let body = command(&client, &url, db, "kv.get", json!({"key": "name"})).await;
+        assert_eq!(body["payload"], format!("db{db}"), "{body}");
+    }
+    let body = command(
+        &client,
+        &url,
+        0,
+        "hash.get",
+        json!({"key": "h", "field": "f"}),
+    )
+    .await;
+    assert!(body["payload"]["value"].is_null(), "{body}");
+
+    let body = command(&client, &url, 3, "kv.get", json!({"key": "name"})).await;
+    assert_eq!(body["success"], false);
+    assert!(body["error"].as_str().unwrap().contains("does not exist"));
+
+    // Queues are shared by every database
+    command(&client, &url, 0, "queue.create", json!({"name": "jobs"})).await;
+    let body = command(
+        &client,
+        &url,
+        2,
+        "queue.publish",
+        json!({"queue": "jobs", "payload": [1, 2, 3]}),
+    )
+    .await;
+    assert_eq!(body["success"], true, "{body}");
+}
+
+#[tokio::test]
+async fn test_flushdb_flushall_and_keyspace_info() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    for db in 0..3 {
+        for key in ["a", "b"] {
+            command(
+                &client,
+                &url,
+                db,
+                "kv.set",
+                json!({"key": key, "value": "1"}),
+            )
+            .await;
+        }
+    }
+
+    let body = command(&client, &url, 0, "info", json!({"section": "keyspace"})).await;
+    let keyspace = &body["payload"]["keyspace"];
+    assert_eq!(keyspace["db0"], "keys=2,expires=0,avg_ttl=0", "{body}");
+    assert_eq!(keyspace["db2"], "keys=2,expires=0,avg_ttl=0");
+
+    command(&client, &url, 1, "kv.flushdb", json!({})).await;
+    for (db, size) in [(0, 2), (1, 0), (2, 2)] {
+        let body = command(&client, &url, db, "kv.dbsize", json!({})).await;
+        assert_eq!(body["payload"]["size"], size, "db {db}: {body}");
+    }
+    let body = command(&client, &url, 0, "info", json!({"section": "keyspace"})).await;
+    assert!(body["payload"]["keyspace"]["db1"].is_null(), "{body}");
+
+    let body = command(&client, &url, 2, "kv.flushall", json!({})).await;
+    assert_eq!(body["payload"]["flushed"], 4, "{body}");
+    for db in 0..3 {
+        let body = command(&client, &url, db, "kv.dbsize", json!({})).await;
+        assert_eq!(body["payload"]["size"], 0);
+    }
+}
+
+#[tokio::test]
+async fn test_each_database_recovers_from_its_own_wal() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = ServerConfig::default();
+    config.databases.count = 2;
+    config.persistence.enabled = true;
+    config.persistence.wal.path = dir.path().join("wal/synap.wal");
+    config.persistence.wal.fsync_mode = FsyncMode::Always;
+    config.persistence.snapshot.directory = dir.path().join("snapshots");
+    let global_mem = GlobalMemory::new(64 * 1024 * 1024);
+    let pubsub = Arc::new(PubSubRouter::new());
+    let latency = Arc::new(LatencyMonitor::new(Default::default()));
+
+    {
+        let databases = Databases::open(&config, &global_mem, &pubsub, &latency)
+            .await
+            .unwrap();
+        let db1 = databases.get(1).unwrap();
+        db1.kv_store.set("k", b"v".to_vec(), None).await.unwrap();
+        db1.persistence
+            .as_ref()
+            .unwrap()
+            .log_kv_set("k".to_string(), b"v".to_vec(), None)
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    assert!(dir.path().join("wal/db1/synap.wal").exists());
+    assert!(!dir.path().join("wal/synap.wal").exists());
+
+    let databases = Databases::open(&config, &global_mem, &pubsub, &latency)
+        .await
+        .unwrap();
+    let value = databases.get(1).unwrap().kv_store.get("k").await.unwrap();
+    assert_eq!(value, Some(b"v".to_vec()));
+}
+
+#[tokio::test]
+async fn test_replication_refuses_more_than_one_database() {
+    let mut config = ServerConfig::default();
+    config.databases.count = 2;
+    config.replication.enabled = true;
+    let global_mem = GlobalMemory::new(64 * 1024 * 1024);
+    let pubsub = Arc::new(PubSubRouter::new());
+    let latency = Arc::new(LatencyMonitor::new(Default::default()));
+
+    let err = Databases::open(&config, &global_mem, &pubsub, &latency)
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("database 0 only"), "{err}");
+}
@@ -19,6 +19,7 @@ async fn spawn_test_server() -> String {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -168,6 +169,73 @@ async fn test_script_exists_and_flush() {
     assert_eq!(exists_after_body["exists"], json!([false]));
 }
 
+#[tokio::test]
+async fn test_function_load_fcall_list_delete() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    let code = "#!lua name=greet\n\
+        redis.register_function('hello', function(keys, args)\n\
+          redis.call('SET', keys[1], args[1])\n\
+          return 'hello ' .. args[1]\n\
+        end)";
+
+    let load_res = client
+        .post(format!("{}/function/load", base_url))
+        .json(&json!({ "code": code }))
+        .send()
+        .await
+        .unwrap();
+    assert!(load_res.status().is_success());
+    let load_body: serde_json::Value = load_res.json().await.unwrap();
+    assert_eq!(load_body["library"], "greet");
+
+    // Loading again without REPLACE is rejected.
+    let again = client
+        .post(format!("{}/function/load", base_url))
+        .json(&json!({ "code": code }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(again.status(), StatusCode::BAD_REQUEST);
+
+    let fcall_res = client
+        .post(format!("{}/function/fcall", base_url))
+        .json(&json!({ "function": "hello", "keys": ["who"], "args": ["world"] }))
+        .send()
+        .await
+        .unwrap();
+    let fcall_body: serde_json::Value = fcall_res.json().await.unwrap();
+    assert_eq!(fcall_body["result"], "hello world");
+
+    let list_res = client
+        .get(format!("{}/function/list", base_url))
+        .send()
+        .await
+        .unwrap();
+    let list_body: serde_json::Value = list_res.json().await.unwrap();
+    assert_eq!(
+        list_body["libraries"],
+        json!([{ "name": "greet", "functions": ["hello"] }])
+    );
+
+    let delete_res = client
+        .post(format!("{}/function/delete", base_url))
+        .json(&json!({ "library": "greet" }))
+        .send()
+        .await
+        .unwrap();
+    assert!(delete_res.status().is_success());
+
+    let missing = client
+        .post(format!("{}/function/fcall", base_url))
+        .json(&json!({ "function": "hello", "keys": ["who"], "args": ["again"] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_script_disables_dangerous_functions() {
     let base_url = spawn_test_server().await;
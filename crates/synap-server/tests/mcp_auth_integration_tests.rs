@@ -79,6 +79,14 @@ async fn spawn_test_server_with_mcp_auth(
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     // Create user manager and API key manager
@@ -133,6 +141,7 @@ async fn spawn_test_server_with_mcp_auth(
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         McpConfig::default(),
         user_manager.clone(),
@@ -325,6 +334,14 @@ async fn test_mcp_permission_check_read_only() {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     });
 
     // Set a value first
@@ -424,6 +441,14 @@ async fn test_mcp_permission_check_write_allowed() {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     });
 
     // Create write-enabled auth context
@@ -508,6 +533,14 @@ async fn test_mcp_admin_bypass_permissions() {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     });
 
     // Create admin auth context (no specific permissions needed)
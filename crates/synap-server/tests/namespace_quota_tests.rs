@@ -0,0 +1,121 @@
+//! Namespace quotas: writes over a prefix's key or memory cap are refused
+//! with 429, and `/quotas` reports usage against the limits.
+
+mod app_state_helper;
+
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{
+    HashStore, ListStore, NamespaceLimit, NamespaceQuotas, SetStore, SortedSetStore,
+};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_test_server(kv: Arc<KVStore>) -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        kv,
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig::default(),
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    url
+}
+
+#[tokio::test]
+async fn test_writes_over_quota_are_refused_and_usage_is_reported() {
+    let kv = Arc::new(
+        KVStore::new(KVConfig::default()).with_namespace_quotas(Some(NamespaceQuotas::new(vec![
+            NamespaceLimit {
+                prefix: "tenant:".to_string(),
+                max_keys: Some(2),
+                max_memory_bytes: None,
+            },
+            NamespaceLimit {
+                prefix: "blob:".to_string(),
+                max_keys: None,
+                max_memory_bytes: Some(1024),
+            },
+        ]))),
+    );
+    let base_url = spawn_test_server(kv).await;
+    let client = Client::new();
+    let set = |key: &str, value: String| {
+        client
+            .post(format!("{base_url}/kv/set"))
+            .json(&json!({"key": key, "value": value}))
+            .send()
+    };
+
+    assert_eq!(
+        set("tenant:1", "a".into()).await.unwrap().status(),
+        StatusCode::OK
+    );
+    assert_eq!(
+        set("tenant:2", "b".into()).await.unwrap().status(),
+        StatusCode::OK
+    );
+    let refused = set("tenant:3", "c".into()).await.unwrap();
+    assert_eq!(refused.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body: serde_json::Value = refused.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("tenant:"));
+    assert_eq!(
+        set("blob:big", "x".repeat(2048)).await.unwrap().status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        set("other", "d".into()).await.unwrap().status(),
+        StatusCode::OK
+    );
+
+    let body: serde_json::Value = client
+        .get(format!("{base_url}/quotas"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["enabled"], true);
+    let namespaces = body["namespaces"].as_array().unwrap();
+    assert_eq!(namespaces[0]["prefix"], "blob:");
+    assert_eq!(namespaces[0]["memory_bytes"], 0);
+    assert_eq!(namespaces[0]["max_memory_bytes"], 1024);
+    assert_eq!(namespaces[1]["prefix"], "tenant:");
+    assert_eq!(namespaces[1]["keys"], 2);
+    assert_eq!(namespaces[1]["max_keys"], 2);
+}
+
+#[tokio::test]
+async fn test_quotas_endpoint_without_quotas() {
+    let base_url = spawn_test_server(Arc::new(KVStore::new(KVConfig::default()))).await;
+    let body: serde_json::Value = Client::new()
+        .get(format!("{base_url}/quotas"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body, json!({"enabled": false, "namespaces": []}));
+}
@@ -20,6 +20,7 @@ fn test_request_serialization() {
         command: "kv.get".to_string(),
         request_id: "test-id-123".to_string(),
         payload: json!({"key": "mykey"}),
+        db: 0,
     };
 
     let json_str = serde_json::to_string(&req).unwrap();
@@ -58,6 +59,7 @@ fn test_response_serialization() {
         request_id: "test-789".to_string(),
         payload: Some(json!({"data": "test"})),
         error: None,
+        error_info: None,
     };
 
     let json_str = serde_json::to_string(&res).unwrap();
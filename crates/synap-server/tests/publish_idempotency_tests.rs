@@ -0,0 +1,152 @@
+//! Idempotent publishing: `idempotency_key` on queue and stream publishes
+//! deduplicates retries within each queue's or room's `dedup_window_secs`.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{
+    KVConfig, KVStore, QueueConfig, QueueManager, StreamConfig, StreamManager, create_router,
+};
+use tokio::net::TcpListener;
+
+/// A server with queues and streams enabled
+async fn spawn_test_server() -> String {
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.queue_manager = Some(Arc::new(QueueManager::new(QueueConfig::default())));
+    state.stream_manager = Some(Arc::new(StreamManager::new(StreamConfig::default())));
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn command(client: &Client, url: &str, command: &str, payload: Value) -> Value {
+    let body: Value = client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({"command": command, "request_id": "t", "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["success"], true, "{command}: {body}");
+    body["payload"].clone()
+}
+
+#[tokio::test]
+async fn test_queue_publish_retry_is_not_enqueued_twice() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    command(&client, &url, "queue.create", json!({"name": "orders"})).await;
+    let publish = json!({"queue": "orders", "payload": [1, 2, 3], "idempotency_key": "order-1"});
+    let first = command(&client, &url, "queue.publish", publish.clone()).await;
+    let retry = command(&client, &url, "queue.publish", publish).await;
+
+    assert!(first.get("duplicate").is_none());
+    assert_eq!(retry["duplicate"], true);
+    assert_eq!(retry["message_id"], first["message_id"]);
+
+    let stats = command(&client, &url, "queue.stats", json!({"queue": "orders"})).await;
+    assert_eq!(stats["depth"], 1);
+}
+
+#[tokio::test]
+async fn test_queue_dedup_window_is_configurable_per_queue() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    command(
+        &client,
+        &url,
+        "queue.create",
+        json!({"name": "no-dedup", "config": {"dedup_window_secs": 0}}),
+    )
+    .await;
+    let publish = json!({"queue": "no-dedup", "payload": [1], "idempotency_key": "k"});
+    command(&client, &url, "queue.publish", publish.clone()).await;
+    let retry = command(&client, &url, "queue.publish", publish).await;
+    assert!(retry.get("duplicate").is_none());
+
+    let stats = command(&client, &url, "queue.stats", json!({"queue": "no-dedup"})).await;
+    assert_eq!(stats["depth"], 2);
+}
+
+#[tokio::test]
+async fn test_stream_publish_retry_is_not_appended_twice() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    command(&client, &url, "stream.create", json!({"room": "chat"})).await;
+    let publish = json!({
+        "room": "chat",
+        "event": "message",
+        "data": {"text": "hi"},
+        "idempotency_key": "msg-1",
+    });
+    let first = command(&client, &url, "stream.publish", publish.clone()).await;
+    let retry = command(&client, &url, "stream.publish", publish).await;
+    assert_eq!(retry["duplicate"], true);
+    assert_eq!(retry["offset"], first["offset"]);
+
+    // Over REST as well
+    let response: Value = client
+        .post(format!("{url}/stream/chat/publish"))
+        .json(&json!({"event": "message", "data": {"text": "hi"}, "idempotency_key": "msg-1"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response["duplicate"], true);
+    assert_eq!(response["offset"], first["offset"]);
+
+    let stats = command(&client, &url, "stream.stats", json!({"room": "chat"})).await;
+    assert_eq!(stats["total_published"], 1);
+}
+
+#[tokio::test]
+async fn test_stream_room_with_dedup_off_appends_retries() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    command(
+        &client,
+        &url,
+        "stream.create",
+        json!({"room": "raw", "config": {"dedup_window_secs": 0}}),
+    )
+    .await;
+    let publish = json!({"room": "raw", "event": "e", "data": 1, "idempotency_key": "k"});
+    command(&client, &url, "stream.publish", publish.clone()).await;
+    let retry = command(&client, &url, "stream.publish", publish).await;
+    assert_eq!(retry["offset"], 1);
+}
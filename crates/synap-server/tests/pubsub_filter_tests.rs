@@ -0,0 +1,266 @@
+//! Subscription filters over HTTP and WebSocket: a filtered subscriber only
+//! receives matching messages, malformed filters are rejected up front, and
+//! per-subscription counters are reported by `GET /pubsub/filters`.
+
+mod app_state_helper;
+
+use futures_util::{SinkExt, StreamExt};
+use reqwest::{Client, Url};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, PubSubRouter, create_router};
+use tokio::net::TcpListener;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+async fn spawn_test_server() -> String {
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.pubsub_router = Some(Arc::new(PubSubRouter::new()));
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    url
+}
+
+fn ws_url(base: &str, topics: &str, filter: &str) -> Url {
+    Url::parse_with_params(
+        &format!("{}/pubsub/ws", base.replace("http://", "ws://")),
+        &[("topics", topics), ("filter", filter)],
+    )
+    .unwrap()
+}
+
+async fn publish(client: &Client, url: &str, topic: &str, body: Value) -> Value {
+    client
+        .post(format!("{url}/pubsub/{topic}/publish"))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_websocket_subscriber_receives_only_matching_messages() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    let (ws, _) = connect_async(
+        ws_url(
+            &url,
+            "orders.*",
+            r#"$.status == "paid" && header.region == "eu""#,
+        )
+        .as_str(),
+    )
+    .await
+    .unwrap();
+    let (mut write, mut read) = ws.split();
+    let Some(Ok(Message::Text(welcome))) = read.next().await else {
+        panic!("no welcome message");
+    };
+    let welcome: Value = serde_json::from_str(&welcome).unwrap();
+    assert_eq!(
+        welcome["filter"],
+        r#"$.status == "paid" && header.region == "eu""#
+    );
+
+    let skipped = publish(
+        &client,
+        &url,
+        "orders.created",
+        json!({"payload": {"status": "pending"}, "metadata": {"region": "eu"}}),
+    )
+    .await;
+    assert_eq!(skipped["subscribers_matched"], 0);
+    publish(
+        &client,
+        &url,
+        "orders.created",
+        json!({"payload": {"status": "paid"}, "metadata": {"region": "us"}}),
+    )
+    .await;
+    let delivered = publish(
+        &client,
+        &url,
+        "orders.created",
+        json!({"payload": {"status": "paid", "id": 7}, "metadata": {"region": "eu"}}),
+    )
+    .await;
+    assert_eq!(delivered["subscribers_matched"], 1);
+
+    let message = tokio::time::timeout(Duration::from_secs(2), async {
+        loop {
+            if let Some(Ok(Message::Text(text))) = read.next().await {
+                let msg: Value = serde_json::from_str(&text).unwrap();
+                if msg["type"] == "message" {
+                    return msg;
+                }
+            }
+        }
+    })
+    .await
+    .expect("the matching message is delivered");
+    assert_eq!(message["payload"]["id"], 7);
+
+    let stats: Value = client
+        .get(format!("{url}/pubsub/filters"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stats["count"], 1);
+    assert_eq!(
+        stats["filters"][0]["subscriber_id"],
+        welcome["subscriber_id"]
+    );
+    assert_eq!(stats["filters"][0]["matched"], 1);
+    assert_eq!(stats["filters"][0]["filtered"], 2);
+
+    write.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_malformed_filter_is_rejected() {
+    let url = spawn_test_server().await;
+
+    let response = Client::new()
+        .get(
+            ws_url(&url, "orders.*", "$.status = paid")
+                .as_str()
+                .replace("ws://", "http://"),
+        )
+        .header("connection", "upgrade")
+        .header("upgrade", "websocket")
+        .header("sec-websocket-version", "13")
+        .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    assert!(
+        response
+            .text()
+            .await
+            .unwrap()
+            .contains("invalid subscription filter")
+    );
+
+    let body: Value = Client::new()
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({
+            "command": "pubsub.subscribe",
+            "request_id": "t",
+            "payload": {"topics": ["orders.*"], "filter": "status == paid"}
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["success"], false);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .contains("invalid subscription filter")
+    );
+}
+
+#[tokio::test]
+async fn test_filters_command_reports_subscriptions() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+    let command = |name: &str, payload: Value| {
+        client
+            .post(format!("{url}/api/v1/command"))
+            .json(&json!({"command": name, "request_id": "t", "payload": payload}))
+            .send()
+    };
+
+    let subscribed: Value = command(
+        "pubsub.subscribe",
+        json!({"topics": ["metrics"], "filter": "$.value != 0"}),
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(subscribed["payload"]["filter"], "$.value != 0");
+    command("pubsub.subscribe", json!({"topics": ["metrics"]}))
+        .await
+        .unwrap();
+
+    let published: Value = command(
+        "pubsub.publish",
+        json!({"topic": "metrics", "payload": {"value": 0}}),
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(published["payload"]["subscribers_matched"], 1);
+
+    let filters: Value = command("pubsub.filters", json!({}))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(filters["payload"]["count"], 1);
+    let row = &filters["payload"]["filters"][0];
+    assert_eq!(row["subscriber_id"], subscribed["payload"]["subscriber_id"]);
+    assert_eq!(row["filter"], "$.value != 0");
+    assert_eq!(
+        (row["matched"].as_u64(), row["filtered"].as_u64()),
+        (Some(0), Some(1))
+    );
+
+    let stats: Value = client
+        .get(format!("{url}/pubsub/stats"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stats["messages_filtered"], 1);
+}
@@ -0,0 +1,134 @@
+//! Per-topic publish limits over HTTP: oversized messages answer 413, chatty
+//! publishers 429, and rejections are counted in Prometheus.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{
+    HashStore, ListStore, PubSubConfig, SetStore, SortedSetStore, TopicLimits,
+};
+use synap_server::{KVConfig, KVStore, PubSubRouter, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_test_server() -> String {
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.pubsub_router = Some(Arc::new(
+        PubSubRouter::with_config(PubSubConfig {
+            max_message_bytes: Some(128),
+            max_messages_per_sec: None,
+            topics: vec![TopicLimits {
+                pattern: "ticks.*".to_string(),
+                max_message_bytes: None,
+                max_messages_per_sec: Some(2),
+            }],
+            routes: Vec::new(),
+        })
+        .unwrap(),
+    ));
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn publish(client: &Client, url: &str, topic: &str, payload: Value) -> reqwest::Response {
+    client
+        .post(format!("{url}/pubsub/{topic}/publish"))
+        .json(&json!({ "payload": payload }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_oversized_message_rejected() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    let response = publish(&client, &url, "chat", json!({ "text": "x".repeat(200) })).await;
+    assert_eq!(response.status(), 413);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("128 byte limit"));
+
+    let response = publish(&client, &url, "chat", json!({ "text": "hello" })).await;
+    assert_eq!(response.status(), 200);
+
+    // The command endpoint enforces the same limit, reported in its envelope
+    let response = client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({
+            "command": "pubsub.publish",
+            "request_id": "t",
+            "payload": { "topic": "chat", "payload": { "text": "x".repeat(200) } }
+        }))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["success"], false);
+    assert!(body["error"].as_str().unwrap().contains("128 byte limit"));
+}
+
+#[tokio::test]
+async fn test_publish_rate_limited() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    for _ in 0..2 {
+        let response = publish(&client, &url, "ticks.btc", json!({ "p": 1 })).await;
+        assert_eq!(response.status(), 200);
+    }
+    let response = publish(&client, &url, "ticks.btc", json!({ "p": 1 })).await;
+    assert_eq!(response.status(), 429);
+
+    // Topics without a rate limit are unaffected
+    let response = publish(&client, &url, "chat", json!({ "p": 1 })).await;
+    assert_eq!(response.status(), 200);
+
+    let stats: Value = client
+        .get(format!("{url}/pubsub/stats"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stats["messages_rate_limited"], 1);
+
+    let metrics = client
+        .get(format!("{url}/metrics"))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.contains("synap_pubsub_rejected_total{reason=\"rate_limited\"}"));
+}
@@ -0,0 +1,120 @@
+//! Rate limits: each API key, user and anonymous IP has its own budget,
+//! tiers override the global one, and responses carry the limit headers.
+
+mod app_state_helper;
+
+use reqwest::{Client, RequestBuilder, StatusCode};
+use std::sync::Arc;
+use synap_server::auth::{Action, ApiKeyManager, Permission, UserManager};
+use synap_server::config::{RateLimitConfig, RateLimitTier};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+/// Start a server with optional authentication, user `alice` in the
+/// `premium` tier, and an API key with no tier. Global budget: a burst of 2.
+async fn spawn_server() -> (String, String) {
+    let user_manager = Arc::new(UserManager::new());
+    user_manager
+        .create_user("alice", "alice-password-1", true)
+        .unwrap();
+    let api_key_manager = Arc::new(ApiKeyManager::new());
+    let key = api_key_manager
+        .create(
+            "client",
+            None,
+            vec![Permission::new("*", Action::All)],
+            vec![],
+            None,
+        )
+        .unwrap();
+
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    let app = create_router(
+        state,
+        RateLimitConfig {
+            enabled: true,
+            requests_per_second: 1,
+            burst_size: 2,
+            tiers: vec![RateLimitTier {
+                name: "premium".to_string(),
+                requests_per_second: 100,
+                burst_size: 10,
+                api_keys: vec![],
+                users: vec!["alice".to_string()],
+                ips: vec![],
+            }],
+        },
+        synap_server::config::McpConfig::default(),
+        user_manager,
+        api_key_manager,
+        true,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    (url, key.key)
+}
+
+fn header(response: &reqwest::Response, name: &str) -> Option<u64> {
+    response.headers().get(name)?.to_str().ok()?.parse().ok()
+}
+
+async fn send(request: RequestBuilder) -> reqwest::Response {
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_budgets_per_identity_with_headers() {
+    let (url, key) = spawn_server().await;
+    let client = Client::new();
+    let health = format!("{url}/health");
+    let stats = format!("{url}/kv/stats");
+
+    // Anonymous callers share their IP's budget
+    let first = send(client.get(&health)).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(header(&first, "x-ratelimit-limit"), Some(1));
+    assert_eq!(header(&first, "x-ratelimit-remaining"), Some(1));
+    assert_eq!(header(&first, "retry-after"), None);
+    send(client.get(&health)).await;
+    let limited = send(client.get(&health)).await;
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header(&limited, "x-ratelimit-remaining"), Some(0));
+    assert_eq!(header(&limited, "retry-after"), Some(1));
+
+    // The API key has its own budget from the same IP
+    for _ in 0..2 {
+        let response = send(client.get(&stats).bearer_auth(&key)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = send(client.get(&stats).bearer_auth(&key)).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // alice is in the premium tier
+    for _ in 0..5 {
+        let response = send(
+            client
+                .get(&stats)
+                .basic_auth("alice", Some("alice-password-1")),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(header(&response, "x-ratelimit-limit"), Some(100));
+    }
+}
@@ -0,0 +1,96 @@
+//! Rate limiters end to end: the SDK's `RateLimiterManager` against the
+//! `ratelimit.*` commands.
+
+mod app_state_helper;
+
+use std::sync::Arc;
+use std::time::Duration;
+use synap_sdk::{RateLimit, SynapClient, SynapConfig};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore};
+
+async fn spawn_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    app_state_helper::spawn_server_with_state(state).await
+}
+
+fn client(url: &str) -> SynapClient {
+    SynapClient::new(SynapConfig::new(url)).unwrap()
+}
+
+const BUCKET: RateLimit = RateLimit::TokenBucket {
+    max_tokens: 5,
+    refill_rate: 0.001,
+};
+
+#[tokio::test]
+async fn concurrent_checks_never_exceed_the_limit() {
+    let url = spawn_server().await;
+
+    let checks = (0..20).map(|_| {
+        let limiter = client(&url).rate_limiter();
+        tokio::spawn(async move { limiter.check("api:1", BUCKET).await.unwrap() })
+    });
+    let mut allowed = 0;
+    for check in checks {
+        if check.await.unwrap().allowed {
+            allowed += 1;
+        }
+    }
+    assert_eq!(allowed, 5);
+
+    let denied = client(&url)
+        .rate_limiter()
+        .check("api:1", BUCKET)
+        .await
+        .unwrap();
+    assert!(!denied.allowed);
+    assert_eq!(denied.remaining, 0);
+    assert!(denied.retry_after.unwrap() > Duration::from_secs(60));
+}
+
+#[tokio::test]
+async fn a_sliding_window_allows_its_limit_per_window() {
+    let url = spawn_server().await;
+    let limiter = client(&url).rate_limiter();
+    let limit = RateLimit::SlidingWindow {
+        max_requests: 3,
+        window: Duration::from_secs(60),
+    };
+
+    let first = limiter.check_cost("login:alice", limit, 2).await.unwrap();
+    assert!(first.allowed);
+    assert_eq!(first.remaining, 1);
+    assert!(
+        !limiter
+            .check_cost("login:alice", limit, 2)
+            .await
+            .unwrap()
+            .allowed
+    );
+    assert!(limiter.check("login:alice", limit).await.unwrap().allowed);
+    assert!(!limiter.check("login:alice", limit).await.unwrap().allowed);
+
+    assert!(limiter.reset("login:alice").await.unwrap());
+    assert!(limiter.check("login:alice", limit).await.unwrap().allowed);
+}
+
+#[tokio::test]
+async fn an_impossible_limit_is_rejected() {
+    let url = spawn_server().await;
+    let limiter = client(&url).rate_limiter();
+
+    let zero_rate = RateLimit::TokenBucket {
+        max_tokens: 5,
+        refill_rate: 0.0,
+    };
+    let error = limiter.check("k", zero_rate).await.unwrap_err();
+    assert!(error.to_string().contains("refill_rate"), "{error}");
+    assert!(limiter.check_cost("k", BUCKET, 6).await.is_err());
+}
@@ -0,0 +1,150 @@
+//! Replication Link Compression Tests
+//!
+//! A master → replica link is compressed only when both nodes enable
+//! `replication.compression`; otherwise it falls back to plain frames.
+
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::compression::LinkCompressionConfig;
+use synap_server::persistence::types::Operation;
+use synap_server::replication::{MasterNode, NodeRole, ReplicaNode, ReplicationConfig};
+use synap_server::{KVConfig, KVStore};
+use tokio::time::sleep;
+
+/// Ask the OS for a free ephemeral port (see kv_replication_tests.rs)
+fn next_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .expect("bind ephemeral port")
+        .local_addr()
+        .expect("ephemeral port addr")
+        .port()
+}
+
+fn compression(enabled: bool) -> LinkCompressionConfig {
+    LinkCompressionConfig {
+        enabled,
+        dictionary_size: 4096,
+        ..Default::default()
+    }
+}
+
+async fn start_pair(
+    master_compression: bool,
+    replica_compression: bool,
+) -> (
+    Arc<MasterNode>,
+    Arc<KVStore>,
+    Arc<ReplicaNode>,
+    Arc<KVStore>,
+) {
+    let addr: std::net::SocketAddr = format!("127.0.0.1:{}", next_port()).parse().unwrap();
+
+    let master_kv = Arc::new(KVStore::new(KVConfig::default()));
+    for i in 0..500 {
+        master_kv
+            .set(
+                &format!("session:{i:05}"),
+                format!("{{\"user\":{i},\"state\":\"active\"}}").into_bytes(),
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    let master_config = ReplicationConfig {
+        enabled: true,
+        role: NodeRole::Master,
+        replica_listen_address: Some(addr),
+        heartbeat_interval_ms: 100,
+        compression: compression(master_compression),
+        ..Default::default()
+    };
+    let master = Arc::new(
+        MasterNode::new(
+            master_config,
+            synap_server::persistence::StoreArcs::kv_only(Arc::clone(&master_kv)),
+        )
+        .await
+        .unwrap(),
+    );
+    sleep(Duration::from_millis(100)).await;
+
+    let replica_config = ReplicationConfig {
+        enabled: true,
+        role: NodeRole::Replica,
+        master_address: Some(addr),
+        auto_reconnect: true,
+        reconnect_delay_ms: 100,
+        compression: compression(replica_compression),
+        ..Default::default()
+    };
+    let replica_kv = Arc::new(KVStore::new(KVConfig::default()));
+    let replica = ReplicaNode::new(
+        replica_config,
+        synap_server::persistence::StoreArcs::kv_only(Arc::clone(&replica_kv)),
+    )
+    .await
+    .unwrap();
+
+    (master, master_kv, replica, replica_kv)
+}
+
+/// Replicate `count` writes and wait until the replica has them all
+async fn replicate_writes(master: &MasterNode, replica_kv: &KVStore, count: usize) {
+    for i in 0..count {
+        master.replicate(Operation::KVSet {
+            key: format!("live:{i:05}"),
+            value: format!("{{\"user\":{i},\"state\":\"active\"}}")
+                .into_bytes()
+                .into(),
+            ttl: None,
+        });
+    }
+
+    let last = format!("live:{:05}", count - 1);
+    for _ in 0..50 {
+        if replica_kv.get(&last).await.unwrap().is_some() {
+            return;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    panic!("replica did not receive {last}");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_compressed_link_replicates_and_saves_bandwidth() {
+    let (master, _master_kv, replica, replica_kv) = start_pair(true, true).await;
+    sleep(Duration::from_millis(500)).await;
+    replicate_writes(&master, &replica_kv, 300).await;
+
+    assert_eq!(
+        replica_kv.get("session:00042").await.unwrap(),
+        Some(b"{\"user\":42,\"state\":\"active\"}".to_vec())
+    );
+
+    let replicas = master.list_replicas();
+    assert_eq!(replicas.len(), 1);
+    assert!(replicas[0].compressed);
+    assert!(replicas[0].compression_saved_bytes > 0);
+    assert!(master.stats().compression_saved_bytes > 0);
+    assert!(replica.stats().await.compression_saved_bytes > 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_link_stays_plain_unless_both_sides_enable_compression() {
+    for (master_compression, replica_compression) in [(false, true), (true, false)] {
+        let (master, _master_kv, replica, replica_kv) =
+            start_pair(master_compression, replica_compression).await;
+        sleep(Duration::from_millis(500)).await;
+        replicate_writes(&master, &replica_kv, 50).await;
+
+        assert!(replica_kv.get("session:00499").await.unwrap().is_some());
+        let replicas = master.list_replicas();
+        assert_eq!(replicas.len(), 1);
+        assert!(!replicas[0].compressed);
+        assert_eq!(master.stats().compression_saved_bytes, 0);
+        assert_eq!(replica.stats().await.compression_saved_bytes, 0);
+
+        replica.stop();
+    }
+}
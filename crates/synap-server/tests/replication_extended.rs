@@ -2,6 +2,7 @@
 //!
 //! These tests provide comprehensive coverage of replication components
 
+use bytes::Bytes;
 use std::sync::Arc;
 use synap_server::persistence::types::Operation;
 use synap_server::replication::{
@@ -17,7 +18,7 @@ async fn test_replication_log_wraparound() {
     for i in 0..200 {
         log.append(Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![i as u8],
+            value: vec![i as u8].into(),
             ttl: None,
         });
     }
@@ -44,7 +45,7 @@ async fn test_replication_log_concurrent_append() {
             for i in 0..100 {
                 log_clone.append(Operation::KVSet {
                     key: format!("task_{}_{}", task_id, i),
-                    value: vec![i as u8],
+                    value: vec![i as u8].into(),
                     ttl: None,
                 });
             }
@@ -69,13 +70,17 @@ async fn test_master_multiple_operations() {
     config.replica_listen_address = Some("127.0.0.1:25000".parse().unwrap());
 
     let kv = Arc::new(KVStore::new(KVConfig::default()));
-    let master = Arc::new(MasterNode::new(config, kv, None).await.unwrap());
+    let master = Arc::new(
+        MasterNode::new(config, synap_server::persistence::StoreArcs::kv_only(kv))
+            .await
+            .unwrap(),
+    );
 
     // Replicate 1000 operations
     for i in 0..1000 {
         master.replicate(Operation::KVSet {
             key: format!("key_{}", i),
-            value: format!("value_{}", i).into_bytes(),
+            value: format!("value_{}", i).into_bytes().into(),
             ttl: None,
         });
     }
@@ -92,7 +97,11 @@ async fn test_master_list_replicas_empty() {
     config.replica_listen_address = Some("127.0.0.1:25001".parse().unwrap());
 
     let kv = Arc::new(KVStore::new(KVConfig::default()));
-    let master = Arc::new(MasterNode::new(config, kv, None).await.unwrap());
+    let master = Arc::new(
+        MasterNode::new(config, synap_server::persistence::StoreArcs::kv_only(kv))
+            .await
+            .unwrap(),
+    );
 
     let replicas = master.list_replicas();
     assert!(replicas.is_empty());
@@ -144,7 +153,7 @@ async fn test_replication_log_lag_various_offsets() {
     for i in 0..500 {
         log.append(Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![i as u8],
+            value: vec![i as u8].into(),
             ttl: None,
         });
     }
@@ -206,7 +215,7 @@ async fn test_replication_log_get_all_from_start() {
     for i in 0..50 {
         log.append(Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![i as u8],
+            value: vec![i as u8].into(),
             ttl: None,
         });
     }
@@ -224,7 +233,7 @@ async fn test_delete_operation_replication() {
     // Set operation
     log.append(Operation::KVSet {
         key: "test_key".to_string(),
-        value: b"test_value".to_vec(),
+        value: Bytes::from_static(b"test_value"),
         ttl: None,
     });
 
@@ -275,13 +284,17 @@ async fn test_master_replication_various_operations() {
     config.replica_listen_address = Some("127.0.0.1:25004".parse().unwrap());
 
     let kv = Arc::new(KVStore::new(KVConfig::default()));
-    let master = Arc::new(MasterNode::new(config, kv, None).await.unwrap());
+    let master = Arc::new(
+        MasterNode::new(config, synap_server::persistence::StoreArcs::kv_only(kv))
+            .await
+            .unwrap(),
+    );
 
     // SET operations
     for i in 0..10 {
         master.replicate(Operation::KVSet {
             key: format!("key_{}", i),
-            value: vec![i as u8],
+            value: vec![i as u8].into(),
             ttl: None,
         });
     }
@@ -301,7 +314,7 @@ async fn test_replication_with_ttl() {
 
     log.append(Operation::KVSet {
         key: "expiring_key".to_string(),
-        value: b"value".to_vec(),
+        value: Bytes::from_static(b"value"),
         ttl: Some(60), // 60 seconds TTL
     });
 
@@ -309,7 +322,7 @@ async fn test_replication_with_ttl() {
     match &ops[0].operation {
         Operation::KVSet { key, value, ttl } => {
             assert_eq!(key, "expiring_key");
-            assert_eq!(value, b"value");
+            assert_eq!(value, &b"value"[..]);
             assert_eq!(*ttl, Some(60));
         }
         _ => panic!("Expected SET operation with TTL"),
@@ -0,0 +1,236 @@
+//! Automatic failover: two replicas lose their master, elect one of them,
+//! and the other follows the promoted node.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::persistence::{PersistenceConfig, PersistenceLayer, StoreArcs};
+use synap_server::replication::sentinel::Topology;
+use synap_server::replication::{
+    FailoverCoordinator, NodeRole, ReplicaNode, ReplicationConfig, ReplicationHandle,
+};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+fn free_address() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+struct Node {
+    url: String,
+    kv: Arc<KVStore>,
+    coordinator: Arc<FailoverCoordinator>,
+}
+
+async fn spawn_node(
+    node_id: &str,
+    listener: TcpListener,
+    master: SocketAddr,
+    peers: Vec<String>,
+) -> Node {
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let replication_address = free_address();
+
+    let mut config = ReplicationConfig {
+        enabled: true,
+        role: NodeRole::Replica,
+        master_address: Some(master),
+        replica_listen_address: Some(replication_address),
+        reconnect_delay_ms: 100,
+        heartbeat_interval_ms: 100,
+        ..Default::default()
+    };
+    config.failover.enabled = true;
+    config.failover.node_id = node_id.to_string();
+    config.failover.advertise_url = Some(url.clone());
+    config.failover.peers = peers;
+    config.failover.down_after_ms = 300;
+    config.failover.election_timeout_ms = 300;
+    config.validate().unwrap();
+
+    let kv = Arc::new(KVStore::new(KVConfig::default()));
+    let stores = StoreArcs::kv_only(kv.clone());
+    let replica = ReplicaNode::new(config.clone(), stores.clone())
+        .await
+        .unwrap();
+    let handle = ReplicationHandle::Replica(replica);
+    let persistence = Arc::new(
+        PersistenceLayer::new_with_replication(
+            PersistenceConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap(),
+    );
+    let coordinator =
+        FailoverCoordinator::new(config, handle.clone(), stores, Some(persistence.clone()))
+            .unwrap();
+
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        kv.clone(),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.persistence = Some(persistence);
+    state.replication = Some(handle);
+    state.failover = Some(coordinator.clone());
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    Node {
+        url,
+        kv,
+        coordinator,
+    }
+}
+
+async fn topology(client: &Client, url: &str) -> Topology {
+    client
+        .get(format!("{url}/replication/topology"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_replicas_elect_new_master_and_follow_it() {
+    // The master is already gone: nothing listens on its addresses.
+    let dead_master = free_address();
+    let dead_master_url = format!("http://{}", free_address());
+
+    let listener_a = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let listener_b = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url_a = format!("http://{}", listener_a.local_addr().unwrap());
+    let url_b = format!("http://{}", listener_b.local_addr().unwrap());
+
+    let a = spawn_node(
+        "replica-a",
+        listener_a,
+        dead_master,
+        vec![url_b.clone(), dead_master_url.clone()],
+    )
+    .await;
+    let b = spawn_node(
+        "replica-b",
+        listener_b,
+        dead_master,
+        vec![url_a.clone(), dead_master_url],
+    )
+    .await;
+    a.coordinator.start();
+    b.coordinator.start();
+
+    let client = Client::new();
+    let deadline = Instant::now() + Duration::from_secs(15);
+    let (master, follower) = loop {
+        assert!(Instant::now() < deadline, "no master elected in time");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let (ta, tb) = (
+            topology(&client, &a.url).await,
+            topology(&client, &b.url).await,
+        );
+        let follower_connected = |node: &Node| match node.coordinator.handle() {
+            ReplicationHandle::Replica(replica) => replica.is_connected(),
+            ReplicationHandle::Master(_) => false,
+        };
+        match (ta.role, tb.role) {
+            (NodeRole::Master, NodeRole::Replica) if ta.epoch == tb.epoch => {
+                if follower_connected(&b) {
+                    assert_eq!(tb.master.unwrap().url.as_deref(), Some(a.url.as_str()));
+                    break (&a, &b);
+                }
+            }
+            (NodeRole::Replica, NodeRole::Master) if ta.epoch == tb.epoch => {
+                if follower_connected(&a) {
+                    assert_eq!(ta.master.unwrap().url.as_deref(), Some(b.url.as_str()));
+                    break (&b, &a);
+                }
+            }
+            (NodeRole::Master, NodeRole::Master) => panic!("two masters elected"),
+            _ => {}
+        }
+    };
+
+    // Writes on the promoted master reach the follower.
+    let response = client
+        .post(format!("{}/kv/set", master.url))
+        .json(&serde_json::json!({"key": "after-failover", "value": "ok"}))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while follower.kv.get("after-failover").await.unwrap().is_none() {
+        assert!(Instant::now() < deadline, "write not replicated");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_topology_requires_failover() {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let response = Client::new()
+        .get(format!("{url}/replication/topology"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
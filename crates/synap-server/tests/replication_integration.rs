@@ -39,9 +39,12 @@ async fn create_master() -> (Arc<MasterNode>, Arc<KVStore>, std::net::SocketAddr
 
     let kv = Arc::new(KVStore::new(KVConfig::default()));
     let master = Arc::new(
-        MasterNode::new(config.clone(), Arc::clone(&kv), None)
-            .await
-            .unwrap(),
+        MasterNode::new(
+            config.clone(),
+            synap_server::persistence::StoreArcs::kv_only(Arc::clone(&kv)),
+        )
+        .await
+        .unwrap(),
     );
 
     // Give master time to bind to port
@@ -149,7 +152,7 @@ async fn test_partial_sync_after_disconnect() {
         // Add to replication log so partial sync can work
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -238,7 +241,7 @@ async fn test_stress_thousands_of_operations() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -285,7 +288,7 @@ async fn test_data_consistency_after_updates() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -408,7 +411,7 @@ async fn test_replication_lag_monitoring() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -465,7 +468,7 @@ async fn test_replica_auto_reconnect() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -505,7 +508,7 @@ async fn test_concurrent_writes_during_sync() {
                 .unwrap();
             master_clone.replicate(Operation::KVSet {
                 key,
-                value,
+                value: value.into(),
                 ttl: None,
             });
 
@@ -578,7 +581,7 @@ async fn test_large_values_replication() {
         master_kv.set(&key, value.clone(), None).await.unwrap();
         master.replicate(Operation::KVSet {
             key,
-            value,
+            value: value.into(),
             ttl: None,
         });
     }
@@ -607,3 +610,31 @@ async fn test_large_values_replication() {
         key_count
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_master_refuses_replica_on_older_protocol() {
+    use synap_server::replication::ReplicationCommand;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (master, _master_kv, master_addr) = create_master().await;
+
+    // A version 1 replica sends its offset and nothing else
+    let mut stream = tokio::net::TcpStream::connect(master_addr).await.unwrap();
+    let handshake = bincode::serde::encode_to_vec(0u64, bincode::config::legacy()).unwrap();
+    stream.write_all(&handshake).await.unwrap();
+
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len).await.unwrap();
+    let mut frame = vec![0u8; u32::from_be_bytes(len) as usize];
+    stream.read_exact(&mut frame).await.unwrap();
+    let (reply, _): (ReplicationCommand, _) =
+        bincode::serde::decode_from_slice(&frame, bincode::config::legacy()).unwrap();
+    match reply {
+        ReplicationCommand::Refused { reason } => assert!(reason.contains("v1"), "{reason}"),
+        other => panic!("expected a refusal, got {other:?}"),
+    }
+
+    // The master closes the connection and never registers the replica
+    assert_eq!(stream.read(&mut len).await.unwrap(), 0);
+    assert!(master.list_replicas().is_empty());
+}
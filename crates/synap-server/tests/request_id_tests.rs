@@ -0,0 +1,115 @@
+//! `X-Request-Id` handling on the HTTP API: accepted or generated per request,
+//! echoed in the response header, and used for envelopes that omit one.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_test_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    url
+}
+
+fn header(response: &reqwest::Response) -> String {
+    response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_request_id_generated_when_absent() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    let first = client
+        .get(format!("{base_url}/health"))
+        .send()
+        .await
+        .unwrap();
+    let second = client
+        .get(format!("{base_url}/health"))
+        .send()
+        .await
+        .unwrap();
+
+    let (a, b) = (header(&first), header(&second));
+    assert!(uuid::Uuid::parse_str(&a).is_ok());
+    assert_ne!(a, b);
+}
+
+#[tokio::test]
+async fn test_request_id_echoed_or_replaced() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    let echoed = client
+        .get(format!("{base_url}/health"))
+        .header("x-request-id", "trace-abc.1")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(header(&echoed), "trace-abc.1");
+
+    // Not printable-ASCII-without-spaces: the server substitutes its own id.
+    let replaced = client
+        .get(format!("{base_url}/health"))
+        .header("x-request-id", "two words")
+        .send()
+        .await
+        .unwrap();
+    assert_ne!(header(&replaced), "two words");
+}
+
+#[tokio::test]
+async fn test_envelope_without_request_id_uses_header() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    let response = client
+        .post(format!("{base_url}/api/v1/command"))
+        .header("x-request-id", "cmd-77")
+        .json(&json!({"command": "kv.get", "payload": {"key": "missing"}}))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(header(&response), "cmd-77");
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["request_id"], "cmd-77");
+}
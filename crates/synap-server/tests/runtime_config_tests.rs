@@ -0,0 +1,190 @@
+//! `GET /config`, `PUT /config` and the `config.get` / `config.set`
+//! commands: changes apply live, are validated as a whole and are written to
+//! the overrides file.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{GlobalMemory, HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::monitoring::MonitoringManager;
+use synap_server::runtime_config::RuntimeConfig;
+use synap_server::{KVConfig, KVStore, QueueConfig, QueueManager, create_router};
+
+async fn spawn_test_server(overrides_path: Option<PathBuf>, rate_limit: bool) -> String {
+    let kv_store = Arc::new(
+        KVStore::new(KVConfig::default()).with_global_memory(GlobalMemory::new(512 * 1024 * 1024)),
+    );
+    let hash_store = Arc::new(HashStore::new());
+    let list_store = Arc::new(ListStore::new());
+    let set_store = Arc::new(SetStore::new());
+    let sorted_set_store = Arc::new(SortedSetStore::new());
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        kv_store.clone(),
+        hash_store.clone(),
+        list_store.clone(),
+        set_store.clone(),
+        sorted_set_store.clone(),
+    );
+    state.queue_manager = Some(Arc::new(QueueManager::new(QueueConfig::default())));
+    state.monitoring = Arc::new(
+        MonitoringManager::new(
+            kv_store,
+            hash_store,
+            list_store,
+            set_store,
+            sorted_set_store,
+        )
+        .with_runtime_config(Arc::new(RuntimeConfig::load(overrides_path).unwrap())),
+    );
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: rate_limit,
+            requests_per_second: 1000,
+            burst_size: 1000,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    url
+}
+
+async fn get_config(client: &Client, url: &str, pattern: &str) -> Value {
+    client
+        .get(format!("{url}/config?pattern={pattern}"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+async fn put_config(client: &Client, url: &str, body: Value) -> (u16, Value) {
+    let response = client
+        .put(format!("{url}/config"))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_config_set_applies_live_and_persists() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.overrides.yml");
+    let url = spawn_test_server(Some(path.clone()), false).await;
+    let client = Client::new();
+
+    let body = get_config(&client, &url, "slowlog.*").await;
+    let parameters = body["parameters"].as_object().unwrap();
+    assert_eq!(parameters.len(), 2);
+    assert!(parameters["slowlog.threshold_ms"].is_u64());
+    assert_eq!(body["overridden"], json!([]));
+
+    let (status, body) = put_config(
+        &client,
+        &url,
+        json!({"slowlog.threshold_ms": 5, "kv_store.max_memory_mb": "64"}),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["applied"]["slowlog.threshold_ms"], 5);
+    assert_eq!(body["persisted"], true);
+
+    let body = get_config(&client, &url, "*").await;
+    assert_eq!(body["parameters"]["slowlog.threshold_ms"], 5);
+    assert_eq!(body["parameters"]["kv_store.max_memory_mb"], 64);
+    assert_eq!(
+        body["overridden"],
+        json!(["kv_store.max_memory_mb", "slowlog.threshold_ms"])
+    );
+
+    let reloaded = RuntimeConfig::load(Some(path)).unwrap();
+    assert_eq!(reloaded.overrides()["slowlog.threshold_ms"], 5);
+    assert_eq!(reloaded.overrides()["kv_store.max_memory_mb"], 64);
+}
+
+#[tokio::test]
+async fn test_config_set_rejects_the_whole_change() {
+    let url = spawn_test_server(None, false).await;
+    let client = Client::new();
+
+    for body in [
+        json!({"slowlog.threshold_ms": 5, "nope": 1}),
+        json!({"slowlog.threshold_ms": 5, "queue.default_priority": 10}),
+        json!({"slowlog.threshold_ms": 5, "rate_limit.burst_size": 10}),
+        json!({}),
+    ] {
+        let (status, _) = put_config(&client, &url, body.clone()).await;
+        assert_eq!(status, 400, "{body}");
+    }
+
+    // Nothing was applied, and rate limiting is reported as off
+    let body = get_config(&client, &url, "*").await;
+    assert_ne!(body["parameters"]["slowlog.threshold_ms"], 5);
+    assert!(body["parameters"]["rate_limit.burst_size"].is_null());
+}
+
+#[tokio::test]
+async fn test_config_commands_and_rate_limit() {
+    let url = spawn_test_server(None, true).await;
+    let client = Client::new();
+
+    let command = |name: &'static str, payload: Value| {
+        let client = client.clone();
+        let url = url.clone();
+        async move {
+            client
+                .post(format!("{url}/api/v1/command"))
+                .json(&json!({"command": name, "request_id": name, "payload": payload}))
+                .send()
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    let body = command(
+        "config.set",
+        json!({"params": {"rate_limit.requests_per_second": 42, "queue.max_depth": 7}}),
+    )
+    .await;
+    assert_eq!(body["success"], true, "{body}");
+    assert_eq!(body["payload"]["persisted"], false);
+
+    let body = command("config.get", json!({"pattern": "rate_limit.*"})).await;
+    assert_eq!(
+        body["payload"]["parameters"]["rate_limit.requests_per_second"],
+        42
+    );
+    let body = command("config.get", json!({"pattern": "queue.max_depth"})).await;
+    assert_eq!(body["payload"]["parameters"]["queue.max_depth"], 7);
+
+    let body = command("config.set", json!({})).await;
+    assert_eq!(body["success"], false);
+}
@@ -65,6 +65,14 @@ async fn spawn_test_server() -> String {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
     let user_manager = Arc::new(UserManager::new());
     let api_key_manager = Arc::new(ApiKeyManager::new());
@@ -74,6 +82,7 @@ async fn spawn_test_server() -> String {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -313,6 +322,125 @@ async fn test_streamable_kv_scan() {
     assert_eq!(res["success"], true);
     let keys = res["payload"]["keys"].as_array().unwrap();
     assert_eq!(keys.len(), 10);
+    assert!(res["payload"].get("next_cursor").is_none());
+
+    // Page through with a cursor
+    let mut cursor = serde_json::Value::Null;
+    let mut scanned = Vec::new();
+    loop {
+        let res = send_command(
+            &client,
+            &base_url,
+            "kv.scan",
+            json!({"prefix": "user:", "limit": 4, "cursor": cursor}),
+        )
+        .await;
+        let payload = &res["payload"];
+        scanned.extend(payload["keys"].as_array().unwrap().iter().cloned());
+        match payload.get("next_cursor") {
+            Some(next) => cursor = next.clone(),
+            None => break,
+        }
+    }
+    assert_eq!(scanned.len(), 10);
+    assert_eq!(scanned[0], "user:1");
+    assert_eq!(scanned[1], "user:10");
+}
+
+#[tokio::test]
+async fn test_streamable_hash_scan() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    for i in 0..5 {
+        send_command(
+            &client,
+            &base_url,
+            "hash.set",
+            json!({"key": "profile", "field": format!("f{}", i), "value": i}),
+        )
+        .await;
+    }
+
+    let res = send_command(
+        &client,
+        &base_url,
+        "hash.scan",
+        json!({"key": "profile", "count": 3}),
+    )
+    .await;
+    assert_eq!(res["success"], true);
+    assert_eq!(res["payload"]["count"], 3);
+    assert_eq!(res["payload"]["fields"]["f0"], 0);
+    assert_eq!(res["payload"]["next_cursor"], 3);
+
+    let res = send_command(
+        &client,
+        &base_url,
+        "hash.scan",
+        json!({"key": "profile", "cursor": 3, "count": 3}),
+    )
+    .await;
+    assert_eq!(res["payload"]["count"], 2);
+    assert_eq!(res["payload"]["next_cursor"], 0);
+}
+
+#[tokio::test]
+async fn test_streamable_set_and_zset_scan_with_match() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    send_command(
+        &client,
+        &base_url,
+        "set.add",
+        json!({"key": "tags", "members": ["red", "rose", "blue", "ruby", "green"]}),
+    )
+    .await;
+    for (i, member) in ["alice", "bob", "anna"].iter().enumerate() {
+        send_command(
+            &client,
+            &base_url,
+            "sortedset.zadd",
+            json!({"key": "scores", "member": member, "score": i}),
+        )
+        .await;
+    }
+
+    // COUNT bounds the members examined, so a filtered page can be short
+    let res = send_command(
+        &client,
+        &base_url,
+        "set.scan",
+        json!({"key": "tags", "match": "r*", "count": 3}),
+    )
+    .await;
+    assert_eq!(res["success"], true);
+    assert_eq!(res["payload"]["members"], json!(["red"]));
+    assert_eq!(res["payload"]["next_cursor"], 3);
+
+    let res = send_command(
+        &client,
+        &base_url,
+        "set.scan",
+        json!({"key": "tags", "match": "r*", "cursor": 3, "count": 3}),
+    )
+    .await;
+    assert_eq!(res["payload"]["members"], json!(["rose", "ruby"]));
+    assert_eq!(res["payload"]["next_cursor"], 0);
+
+    let res = send_command(
+        &client,
+        &base_url,
+        "sortedset.zscan",
+        json!({"key": "scores", "match": "a*"}),
+    )
+    .await;
+    assert_eq!(res["payload"]["count"], 2);
+    assert_eq!(res["payload"]["members"][0]["member"], "alice");
+    assert_eq!(res["payload"]["members"][1]["member"], "anna");
+    assert_eq!(res["payload"]["members"][1]["score"], 2.0);
+    assert_eq!(res["payload"]["next_cursor"], 0);
 }
 
 #[tokio::test]
@@ -331,12 +459,24 @@ async fn test_streamable_kv_keys() {
         .await;
     }
 
+    send_command(
+        &client,
+        &base_url,
+        "kv.set",
+        json!({"key": "other:1", "value": 1}),
+    )
+    .await;
+
     // Test KEYS
     let res = send_command(&client, &base_url, "kv.keys", json!({})).await;
 
     assert_eq!(res["success"], true);
     let keys = res["payload"]["keys"].as_array().unwrap();
-    assert_eq!(keys.len(), 5);
+    assert_eq!(keys.len(), 6);
+
+    // The prefix the SDKs send narrows the listing
+    let res = send_command(&client, &base_url, "kv.keys", json!({"prefix": "item:"})).await;
+    assert_eq!(res["payload"]["count"], 5);
 }
 
 #[tokio::test]
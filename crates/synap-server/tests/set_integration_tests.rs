@@ -65,6 +65,14 @@ async fn spawn_test_server() -> String {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     let user_manager = Arc::new(UserManager::new());
@@ -75,6 +83,7 @@ async fn spawn_test_server() -> String {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -461,6 +470,27 @@ async fn test_set_randmember() {
 
     let body: serde_json::Value = resp.json().await.unwrap();
     assert_eq!(body["count"], 5);
+
+    // A negative count returns exactly that many, repeating members
+    let resp = client
+        .get(format!("{}/set/rand_set/randmember?count=-8", base_url))
+        .send()
+        .await
+        .unwrap();
+
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["members"].as_array().unwrap().len(), 8);
+
+    // Repeats are bounded by `limits.max_random_count`
+    let resp = client
+        .get(format!(
+            "{}/set/rand_set/randmember?count=-9223372036854775808",
+            base_url
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -576,3 +606,137 @@ async fn test_set_large_set() {
     let body: serde_json::Value = resp.json().await.unwrap();
     assert_eq!(body["count"], 90);
 }
+
+#[tokio::test]
+async fn test_set_store_operations() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    for (key, members) in [
+        ("store1", json!(["a", "b", "c"])),
+        ("store2", json!(["b", "c", "d"])),
+    ] {
+        client
+            .post(format!("{}/set/{}/add", base_url, key))
+            .json(&json!({ "members": members }))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    for (op, count) in [("interstore", 2), ("unionstore", 4), ("diffstore", 1)] {
+        let resp = client
+            .post(format!("{}/set/{}", base_url, op))
+            .json(&json!({"destination": op, "keys": ["store1", "store2"]}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body["count"], count, "{op}");
+        assert_eq!(body["destination"], op);
+
+        let resp = client
+            .get(format!("{}/set/{}/card", base_url, op))
+            .send()
+            .await
+            .unwrap();
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body["count"], count, "{op}");
+    }
+
+    // An empty result removes the destination
+    let resp = client
+        .post(format!("{}/set/interstore", base_url))
+        .json(&json!({"destination": "unionstore", "keys": ["store1", "missing"]}))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["count"], 0);
+    let resp = client
+        .get(format!("{}/set/unionstore/card", base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_set_intercard() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    for (key, members) in [
+        ("card1", json!(["a", "b", "c"])),
+        ("card2", json!(["b", "c", "d"])),
+    ] {
+        client
+            .post(format!("{}/set/{}/add", base_url, key))
+            .json(&json!({ "members": members }))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    let resp = client
+        .post(format!("{}/set/intercard", base_url))
+        .json(&json!({"keys": ["card1", "card2"]}))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["count"], 2);
+
+    // Over /api/v1/command, with a limit
+    let resp = client
+        .post(format!("{}/api/v1/command", base_url))
+        .json(&json!({
+            "command": "set.intercard",
+            "request_id": "set-intercard-1",
+            "payload": {"keys": ["card1", "card2"], "limit": 1}
+        }))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["payload"]["count"], 1);
+}
+
+#[tokio::test]
+async fn test_set_interstore_command_stores_result() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    for (key, members) in [("cmd1", json!(["a", "b"])), ("cmd2", json!(["b", "c"]))] {
+        client
+            .post(format!("{}/set/{}/add", base_url, key))
+            .json(&json!({ "members": members }))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    let resp = client
+        .post(format!("{}/api/v1/command", base_url))
+        .json(&json!({
+            "command": "set.interstore",
+            "request_id": "set-interstore-1",
+            "payload": {"destination": "cmd_out", "keys": ["cmd1", "cmd2"]}
+        }))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["payload"]["count"], 1);
+
+    let resp = client
+        .get(format!("{}/set/cmd_out/members", base_url))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["members"], json!(["b"]));
+}
@@ -0,0 +1,143 @@
+//! `/slowlog/config` and the `slowlog.*` commands: slow log threshold and
+//! length changed at runtime.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_test_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    url
+}
+
+async fn command(
+    client: &Client,
+    base_url: &str,
+    command: &str,
+    payload: serde_json::Value,
+) -> serde_json::Value {
+    client
+        .post(format!("{base_url}/api/v1/command"))
+        .json(&json!({"command": command, "request_id": "slowlog-test", "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_slowlog_config_applies_at_runtime() {
+    let base_url = spawn_test_server().await;
+    let client = Client::new();
+
+    let body: serde_json::Value = client
+        .get(format!("{base_url}/slowlog/config"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body, json!({"threshold_ms": 10, "max_entries": 128}));
+
+    // Log every command, keep two
+    let body: serde_json::Value = client
+        .put(format!("{base_url}/slowlog/config"))
+        .json(&json!({"threshold_ms": 0, "max_entries": 2}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body, json!({"threshold_ms": 0, "max_entries": 2}));
+
+    for i in 0..3 {
+        command(
+            &client,
+            &base_url,
+            "kv.set",
+            json!({"key": format!("k{i}"), "value": "v"}),
+        )
+        .await;
+    }
+    let response = command(&client, &base_url, "slowlog.len", json!({})).await;
+    assert_eq!(response["payload"]["len"], 2);
+
+    let response = command(&client, &base_url, "slowlog.get", json!({})).await;
+    let entries = response["payload"]["entries"].as_array().unwrap();
+    assert_eq!(entries[0]["command"], "slowlog.len");
+    assert_eq!(entries[1]["command"], "kv.set");
+
+    // Unset fields are kept
+    let response = command(
+        &client,
+        &base_url,
+        "slowlog.config.set",
+        json!({"max_entries": 5}),
+    )
+    .await;
+    assert_eq!(
+        response["payload"],
+        json!({"threshold_ms": 0, "max_entries": 5})
+    );
+
+    let response = command(
+        &client,
+        &base_url,
+        "slowlog.config.set",
+        json!({"threshold_ms": "fast"}),
+    )
+    .await;
+    assert_eq!(response["success"], false);
+
+    let response = command(&client, &base_url, "slowlog.config.get", json!({})).await;
+    assert_eq!(response["payload"]["max_entries"], 5);
+
+    let body: serde_json::Value = client
+        .delete(format!("{base_url}/slowlog"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["cleared"], 5);
+}
@@ -0,0 +1,204 @@
+//! Portable dumps end to end: `GET /snapshot/export` on one server and
+//! `POST /snapshot/import` on another clone its stores, TTLs included.
+//! `key.dump` / `key.restore` do the same for a single key.
+
+mod app_state_helper;
+
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+async fn spawn_test_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(synap_server::auth::UserManager::new()),
+        Arc::new(synap_server::auth::ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn command(url: &str, command: &str, payload: Value) -> Value {
+    Client::new()
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({"command": command, "request_id": "t", "payload": payload}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_export_then_import_clones_the_server() {
+    let source = spawn_test_server().await;
+    let target = spawn_test_server().await;
+
+    // Together more than the default 2 MB request body limit
+    let big = "x".repeat(1536 * 1024);
+    for key in ["big:1", "big:2"] {
+        command(&source, "kv.set", json!({"key": key, "value": big})).await;
+    }
+    command(
+        &source,
+        "kv.set",
+        json!({"key": "session", "value": "s", "ttl": 600}),
+    )
+    .await;
+    command(
+        &source,
+        "hash.set",
+        json!({"key": "user:1", "field": "name", "value": "ada"}),
+    )
+    .await;
+    command(
+        &source,
+        "sortedset.zadd",
+        json!({"key": "board", "member": "ada", "score": 3.5}),
+    )
+    .await;
+
+    let client = Client::new();
+    let export = client
+        .get(format!("{source}/snapshot/export"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(export.status(), StatusCode::OK);
+    assert_eq!(export.headers()["content-type"], "application/octet-stream");
+    let dump = export.bytes().await.unwrap();
+    assert!(dump.starts_with(b"SYNAPDMP"));
+
+    let summary: Value = client
+        .post(format!("{target}/snapshot/import"))
+        .body(dump)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(summary["keys"], 5);
+    assert_eq!(summary["expired_keys"], 0);
+
+    let got = command(&target, "kv.get", json!({"key": "big:2"})).await;
+    assert_eq!(got["payload"].as_str().map(str::len), Some(big.len()));
+    let ttl = command(&target, "kv.ttl", json!({"key": "session"})).await;
+    assert!(ttl["payload"]["ttl"].as_u64().is_some_and(|t| t > 590));
+    let name = command(
+        &target,
+        "hash.get",
+        json!({"key": "user:1", "field": "name"}),
+    )
+    .await;
+    assert_eq!(name["payload"]["value"], "ada");
+    let score = command(
+        &target,
+        "sortedset.zscore",
+        json!({"key": "board", "member": "ada"}),
+    )
+    .await;
+    assert_eq!(score["payload"]["score"], 3.5);
+}
+
+#[tokio::test]
+async fn test_import_rejects_a_file_that_is_not_a_dump() {
+    let url = spawn_test_server().await;
+    let response = Client::new()
+        .post(format!("{url}/snapshot/import"))
+        .body("SYNAP004 not a dump")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("not a Synap dump"));
+}
+
+#[tokio::test]
+async fn test_key_dump_restores_on_another_server() {
+    let source = spawn_test_server().await;
+    let target = spawn_test_server().await;
+
+    command(
+        &source,
+        "kv.set",
+        json!({"key": "session", "value": "s", "ttl": 600}),
+    )
+    .await;
+    command(
+        &source,
+        "list.rpush",
+        json!({"key": "jobs", "values": ["a", "b"]}),
+    )
+    .await;
+
+    let missing = command(&source, "key.dump", json!({"key": "nope"})).await;
+    assert_eq!(missing["payload"]["payload"], Value::Null);
+
+    let session = command(&source, "key.dump", json!({"key": "session"})).await;
+    let session = session["payload"]["payload"].clone();
+    let restored = command(
+        &target,
+        "key.restore",
+        json!({"key": "session", "payload": session}),
+    )
+    .await;
+    assert_eq!(restored["success"], true);
+    let ttl = command(&target, "kv.ttl", json!({"key": "session"})).await;
+    assert!(ttl["payload"]["ttl"].as_u64().is_some_and(|t| t > 590));
+
+    // BUSYKEY without replace; `ttl: 0` drops the payload's expiry
+    let again = command(
+        &target,
+        "key.restore",
+        json!({"key": "session", "payload": session}),
+    )
+    .await;
+    assert_eq!(again["success"], false);
+    let jobs = command(&source, "key.dump", json!({"key": "jobs"})).await;
+    command(
+        &target,
+        "key.restore",
+        json!({"key": "session", "payload": jobs["payload"]["payload"], "ttl": 0, "replace": true}),
+    )
+    .await;
+    let range = command(
+        &target,
+        "list.range",
+        json!({"key": "session", "start": 0, "stop": -1}),
+    )
+    .await;
+    assert_eq!(range["payload"]["values"], json!(["a", "b"]));
+
+    let corrupt = command(
+        &target,
+        "key.restore",
+        json!({"key": "other", "payload": [1, 2, 3]}),
+    )
+    .await;
+    assert!(corrupt["error"].as_str().unwrap().contains("checksum"));
+}
@@ -1,4 +1,11 @@
-use synap_server::core::{Aggregate, SortedSetStore, ZAddOptions};
+mod app_state_helper;
+
+use serde_json::json;
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{Aggregate, HashStore, ListStore, SetStore, SortedSetStore, ZAddOptions};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
 
 // ==================== Set Operations Tests ====================
 
@@ -406,3 +413,110 @@ fn test_set_operations_overwrites_destination() {
     assert_eq!(store.zscore("dest", b"old"), None); // Old data removed
     assert_eq!(store.zscore("dest", b"new"), Some(3.0));
 }
+
+// ==================== Non-store Variants (ZINTER/ZUNION/ZDIFF) ====================
+
+async fn spawn_test_server(zset: Arc<SortedSetStore>) -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        zset,
+    );
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    url
+}
+
+#[tokio::test]
+async fn test_zinter_zunion_zdiff_return_without_storing() {
+    let store = Arc::new(SortedSetStore::new());
+    let opts = ZAddOptions::default();
+    store.zadd("zset1", b"a".to_vec(), 1.0, &opts);
+    store.zadd("zset1", b"b".to_vec(), 2.0, &opts);
+    store.zadd("zset2", b"b".to_vec(), 4.0, &opts);
+    store.zadd("zset2", b"c".to_vec(), 5.0, &opts);
+
+    let base_url = spawn_test_server(store.clone()).await;
+    let client = reqwest::Client::new();
+    let command = |command: &str, payload: serde_json::Value| {
+        client
+            .post(format!("{base_url}/api/v1/command"))
+            .json(&json!({"command": command, "payload": payload}))
+            .send()
+    };
+
+    let body: serde_json::Value = command(
+        "sortedset.zunion",
+        json!({"keys": ["zset1", "zset2"], "weights": [1.0, 2.0], "withscores": true}),
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    // a=1, then b and c tie on 10 (2+4*2, 5*2) and are ordered by member.
+    let members: Vec<&str> = body["payload"]["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["member"].as_str().unwrap())
+        .collect();
+    assert_eq!(members, vec!["a", "b", "c"]);
+    assert_eq!(body["payload"]["members"][2]["score"], 10.0);
+
+    let body: serde_json::Value = client
+        .post(format!("{base_url}/sortedset/zinter"))
+        .json(&json!({"keys": ["zset1", "zset2"], "aggregate": "max"}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["members"], json!(["b"]));
+
+    let body: serde_json::Value = command("sortedset.zdiff", json!({"keys": ["zset2", "zset1"]}))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["payload"]["members"], json!(["c"]));
+
+    // Mismatched weights are rejected rather than silently defaulted.
+    let body: serde_json::Value = command(
+        "sortedset.zinter",
+        json!({"keys": ["zset1", "zset2"], "weights": [1.0]}),
+    )
+    .await
+    .unwrap()
+    .json()
+    .await
+    .unwrap();
+    assert_eq!(body["success"], false);
+
+    // Nothing was written besides the two source sets.
+    assert_eq!(store.stats().total_keys, 2);
+}
@@ -0,0 +1,161 @@
+//! Multi-room stream consumption: one long-poll call reads several rooms,
+//! each from its own offset, and returns events tagged by room.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, StreamConfig, StreamManager, create_router};
+use tokio::net::TcpListener;
+
+/// A server with streams enabled
+async fn spawn_test_server() -> String {
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.stream_manager = Some(Arc::new(StreamManager::new(StreamConfig::default())));
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+async fn publish(client: &Client, url: &str, room: &str, count: usize) {
+    for i in 0..count {
+        client
+            .post(format!("{url}/stream/{room}/publish"))
+            .json(&json!({"event": "tick", "data": {"n": i}}))
+            .send()
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_consume_many_rest() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    for room in ["cpu", "mem", "disk"] {
+        client
+            .post(format!("{url}/stream/{room}"))
+            .send()
+            .await
+            .unwrap();
+    }
+    publish(&client, &url, "cpu", 3).await;
+    publish(&client, &url, "mem", 2).await;
+
+    let body: Value = client
+        .post(format!("{url}/stream/consume"))
+        .json(&json!({
+            "subscriber_id": "dashboard",
+            "rooms": [
+                {"room": "cpu", "offset": 1},
+                {"room": "mem"},
+                {"room": "disk"}
+            ]
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let events = body["events"].as_array().unwrap();
+    assert_eq!(events.len(), 4);
+    let rooms: Vec<&str> = events.iter().map(|e| e["room"].as_str().unwrap()).collect();
+    assert_eq!(rooms.iter().filter(|r| **r == "cpu").count(), 2);
+    assert_eq!(rooms.iter().filter(|r| **r == "mem").count(), 2);
+    assert_eq!(body["next_offsets"], json!({"cpu": 3, "mem": 2, "disk": 0}));
+
+    // A missing room fails the whole request
+    let response = client
+        .post(format!("{url}/stream/consume"))
+        .json(&json!({"subscriber_id": "dashboard", "rooms": [{"room": "gpu"}]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_consume_many_long_poll_command() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    for room in ["orders", "payments"] {
+        client
+            .post(format!("{url}/stream/{room}"))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    // Publish while the request is already waiting
+    let publisher = {
+        let client = client.clone();
+        let url = url.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            publish(&client, &url, "payments", 1).await;
+        })
+    };
+
+    let started = Instant::now();
+    let response: Value = client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({
+            "command": "stream.consume_many",
+            "request_id": "t",
+            "payload": {
+                "subscriber_id": "sub",
+                "rooms": [{"room": "orders", "offset": 0}, {"room": "payments", "offset": 0}],
+                "timeout_ms": 5000
+            }
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    publisher.await.unwrap();
+
+    assert_eq!(response["success"], true);
+    let events = response["payload"]["events"].as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["room"], "payments");
+    assert_eq!(
+        response["payload"]["next_offsets"],
+        json!({"orders": 0, "payments": 1})
+    );
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
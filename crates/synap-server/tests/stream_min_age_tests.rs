@@ -0,0 +1,209 @@
+//! Delayed visibility: `min_age_ms` on stream and partition consumption holds
+//! back events younger than the given age, with per-member defaults from
+//! consumer groups.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{
+    ConsumerGroupConfig, ConsumerGroupManager, KVConfig, KVStore, PartitionConfig,
+    PartitionManager, StreamConfig, StreamManager, create_router,
+};
+use tokio::net::TcpListener;
+
+/// A server with streams, partitions and consumer groups enabled
+async fn spawn_test_server() -> String {
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.stream_manager = Some(Arc::new(StreamManager::new(StreamConfig::default())));
+    state.partition_manager = Some(Arc::new(PartitionManager::new(PartitionConfig {
+        num_partitions: 1,
+        ..Default::default()
+    })));
+    state.consumer_group_manager = Some(Arc::new(ConsumerGroupManager::new(
+        ConsumerGroupConfig::default(),
+    )));
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+#[tokio::test]
+async fn test_stream_consume_min_age() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    client
+        .post(format!("{url}/stream/orders"))
+        .send()
+        .await
+        .unwrap();
+    for i in 0..2 {
+        let response = client
+            .post(format!("{url}/stream/orders/publish"))
+            .json(&json!({"event": "created", "data": {"id": i}}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    // Everything was just published, so a one-minute lag hides it all
+    let body: Value = client
+        .get(format!(
+            "{url}/stream/orders/consume/sub1?from_offset=0&min_age_ms=60000"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["events"].as_array().unwrap().len(), 0);
+    assert_eq!(body["next_offset"], 0);
+
+    let body: Value = client
+        .get(format!("{url}/stream/orders/consume/sub1?from_offset=0"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["events"].as_array().unwrap().len(), 2);
+
+    // Same through the command endpoint
+    let response: Value = client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({
+            "command": "stream.consume",
+            "request_id": "t",
+            "payload": {
+                "room": "orders",
+                "subscriber_id": "sub2",
+                "from_offset": 0,
+                "min_age_ms": 60000
+            }
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response["success"], true);
+    assert_eq!(response["payload"]["events"].as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_partition_consume_member_min_age() {
+    let url = spawn_test_server().await;
+    let client = Client::new();
+
+    let response = client
+        .post(format!("{url}/topics/payments"))
+        .json(&json!({"num_partitions": 1}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    client
+        .post(format!("{url}/topics/payments/publish"))
+        .json(&json!({"event_type": "charged", "data": {"amount": 10}}))
+        .send()
+        .await
+        .unwrap();
+
+    let response = client
+        .post(format!("{url}/consumer-groups/auditors"))
+        .json(&json!({"topic": "payments", "partition_count": 1, "min_age_ms": 60000}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let lagging: Value = client
+        .post(format!("{url}/consumer-groups/auditors/join"))
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let realtime: Value = client
+        .post(format!("{url}/consumer-groups/auditors/join"))
+        .json(&json!({"min_age_ms": 0}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let consume = |member: Value, extra: Value| {
+        let client = client.clone();
+        let url = url.clone();
+        async move {
+            let mut body = json!({
+                "from_offset": 0,
+                "group_id": "auditors",
+                "member_id": member["member_id"]
+            });
+            body.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            client
+                .post(format!("{url}/topics/payments/partitions/0/consume"))
+                .json(&body)
+                .send()
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    // The group default applies to a member without its own setting
+    let body = consume(lagging.clone(), json!({})).await;
+    assert_eq!(body["count"], 0);
+    assert_eq!(body["min_age_ms"], 60000);
+
+    // A member override wins over the group default
+    let body = consume(realtime, json!({})).await;
+    assert_eq!(body["count"], 1);
+    assert_eq!(body["min_age_ms"], 0);
+
+    // An explicit value on the request wins over both
+    let body = consume(lagging, json!({"min_age_ms": 0})).await;
+    assert_eq!(body["count"], 1);
+}
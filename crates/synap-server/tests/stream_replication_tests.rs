@@ -45,9 +45,15 @@ async fn create_stream_master() -> (
     let stream_mgr = Arc::new(StreamManager::new(StreamConfig::default()));
 
     let master = Arc::new(
-        MasterNode::new(config, Arc::clone(&kv), Some(Arc::clone(&stream_mgr)))
-            .await
-            .unwrap(),
+        MasterNode::new(
+            config,
+            synap_server::persistence::StoreArcs {
+                stream_manager: Some(Arc::clone(&stream_mgr)),
+                ..synap_server::persistence::StoreArcs::kv_only(Arc::clone(&kv))
+            },
+        )
+        .await
+        .unwrap(),
     );
 
     sleep(Duration::from_millis(100)).await;
@@ -166,7 +172,7 @@ async fn test_stream_partial_sync() {
         master.replicate(synap_server::persistence::types::Operation::StreamPublish {
             room: "live_room".to_string(),
             event_type: "live_event".to_string(),
-            payload: data,
+            payload: data.into(),
         });
 
         // Small delay
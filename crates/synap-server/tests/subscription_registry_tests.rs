@@ -0,0 +1,314 @@
+//! Durable subscriptions: consumer group memberships and committed offsets
+//! recorded through the REST API are listed on `GET /subscriptions` and put
+//! back after a restart; stream WebSocket subscribers resume where they left
+//! off.
+
+mod app_state_helper;
+
+use futures_util::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::path::Path;
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{
+    ConsumerGroupConfig, ConsumerGroupManager, HashStore, ListStore, SetStore, SortedSetStore,
+};
+use synap_server::persistence::PersistenceLayer;
+use synap_server::persistence::types::{FsyncMode, PersistenceConfig, SnapshotConfig, WALConfig};
+use synap_server::{KVConfig, KVStore, StreamConfig, StreamManager, create_router};
+use tempfile::TempDir;
+use tokio::net::TcpListener;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+fn persistence_config(dir: &Path) -> PersistenceConfig {
+    PersistenceConfig {
+        enabled: true,
+        wal: WALConfig {
+            enabled: true,
+            path: dir.join("synap.wal"),
+            buffer_size_kb: 64,
+            fsync_mode: FsyncMode::Always,
+            fsync_interval_ms: 1000,
+            max_size_mb: 1024,
+            group_commit: Default::default(),
+            fsync_by_class: Default::default(),
+        },
+        snapshot: SnapshotConfig {
+            enabled: false,
+            directory: dir.join("snapshots"),
+            interval_secs: 3600,
+            operation_threshold: 10000,
+            max_snapshots: 3,
+            compression: false,
+            incremental: Default::default(),
+        },
+        durable_subscriptions: true,
+        archive: Default::default(),
+    }
+}
+
+/// Boot a server against `dir`, restoring recorded subscriptions like `main` does
+async fn spawn_server(dir: &Path) -> (String, Arc<ConsumerGroupManager>) {
+    let persistence = Arc::new(
+        PersistenceLayer::new(persistence_config(dir))
+            .await
+            .unwrap(),
+    );
+    let consumer_groups = Arc::new(ConsumerGroupManager::new(ConsumerGroupConfig::default()));
+    persistence
+        .subscriptions()
+        .expect("durable subscriptions enabled")
+        .restore(&consumer_groups)
+        .await;
+
+    let mut state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.consumer_group_manager = Some(consumer_groups.clone());
+    state.stream_manager = Some(Arc::new(StreamManager::new(StreamConfig::default())));
+    state.persistence = Some(persistence);
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    (url, consumer_groups)
+}
+
+#[tokio::test]
+async fn test_consumer_group_membership_survives_restart() {
+    let dir = TempDir::new().unwrap();
+    let client = Client::new();
+
+    let (url, _) = spawn_server(dir.path()).await;
+    let response = client
+        .post(format!("{url}/consumer-groups/billing"))
+        .json(&json!({"topic": "orders", "partition_count": 3}))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let joined: Value = client
+        .post(format!("{url}/consumer-groups/billing/join"))
+        .json(&json!({"session_timeout_secs": 60}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let member_id = joined["member_id"].as_str().unwrap().to_string();
+
+    let response = client
+        .post(format!("{url}/consumer-groups/billing/offsets/commit"))
+        .json(&json!({"partition_id": 1, "offset": 17}))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let listed: Value = client
+        .get(format!("{url}/subscriptions"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed["enabled"], true);
+    assert_eq!(listed["count"], 3);
+    let member = listed["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["kind"] == "GroupMember")
+        .unwrap();
+    assert_eq!(member["member_id"], member_id.as_str());
+    assert_eq!(member["session_timeout_secs"], 60);
+
+    // Restart: fresh managers, same data directory
+    let (url, consumer_groups) = spawn_server(dir.path()).await;
+    assert_eq!(
+        consumer_groups.get_offset("billing", 1).await.unwrap(),
+        Some(17)
+    );
+
+    let assignment: Value = client
+        .get(format!(
+            "{url}/consumer-groups/billing/members/{member_id}/assignment"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(assignment["partitions"].as_array().unwrap().len(), 3);
+
+    // Leaving removes the membership record
+    let response = client
+        .delete(format!(
+            "{url}/consumer-groups/billing/members/{member_id}/leave"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let listed: Value = client
+        .get(format!("{url}/subscriptions"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed["count"], 2);
+    assert!(
+        listed["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|r| r["kind"] != "GroupMember")
+    );
+}
+
+#[tokio::test]
+async fn test_subscriptions_disabled_without_registry() {
+    let dir = TempDir::new().unwrap();
+    let mut config = persistence_config(dir.path());
+    config.durable_subscriptions = false;
+    let layer = PersistenceLayer::new(config).await.unwrap();
+    assert!(layer.subscriptions().is_none());
+    assert!(
+        !dir.path().join("subscriptions.wal").exists(),
+        "no subscription log is opened when the registry is off"
+    );
+}
+
+/// Next text message of `kind` on a WebSocket, skipping anything else
+async fn next_message<S>(read: &mut S, kind: &str) -> Value
+where
+    S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    tokio::time::timeout(std::time::Duration::from_secs(2), async {
+        loop {
+            if let Some(Ok(Message::Text(text))) = read.next().await {
+                let msg: Value = serde_json::from_str(&text).unwrap();
+                if msg["type"] == kind {
+                    return msg;
+                }
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for WebSocket message")
+}
+
+#[tokio::test]
+async fn test_stream_subscriber_resumes_from_recorded_offset() {
+    let dir = TempDir::new().unwrap();
+    let client = Client::new();
+    let (url, _) = spawn_server(dir.path()).await;
+    let ws_url = url.replace("http://", "ws://");
+
+    client
+        .post(format!("{url}/stream/feed"))
+        .send()
+        .await
+        .unwrap();
+    for i in 0..3 {
+        client
+            .post(format!("{url}/stream/feed/publish"))
+            .json(&json!({"event": "tick", "data": {"n": i}}))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    let (ws, _) = connect_async(format!("{ws_url}/stream/feed/ws/worker"))
+        .await
+        .unwrap();
+    let (mut write, mut read) = ws.split();
+    assert_eq!(next_message(&mut read, "connected").await["from_offset"], 0);
+    for expected in 0..3 {
+        assert_eq!(next_message(&mut read, "event").await["offset"], expected);
+    }
+    write.close().await.unwrap();
+
+    // The position is recorded once the batch has been delivered
+    let mut recorded = Value::Null;
+    for _ in 0..20 {
+        let listed: Value = client
+            .get(format!("{url}/subscriptions"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if let Some(record) = listed["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["id"] == "stream:feed:worker")
+        {
+            recorded = record.clone();
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(recorded["kind"], "StreamSubscriber");
+    assert_eq!(recorded["offset"], 3);
+
+    // Reconnecting without from_offset picks up after the last delivery
+    let (ws, _) = connect_async(format!("{ws_url}/stream/feed/ws/worker"))
+        .await
+        .unwrap();
+    let (mut write, mut read) = ws.split();
+    assert_eq!(next_message(&mut read, "connected").await["from_offset"], 3);
+    write.close().await.unwrap();
+
+    // Deleting the room forgets its subscribers
+    client
+        .delete(format!("{url}/stream/feed"))
+        .send()
+        .await
+        .unwrap();
+    let listed: Value = client
+        .get(format!("{url}/subscriptions"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed["count"], 0);
+}
@@ -0,0 +1,151 @@
+//! Time series end to end: the SDK's `TimeSeriesManager` against the `ts.*`
+//! commands.
+
+mod app_state_helper;
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use synap_sdk::{
+    SynapClient, SynapConfig, TimeSeriesAggregation, TimeSeriesIncrOptions, TimeSeriesPoint,
+    TimeSeriesQuery, TimeSeriesResolution,
+};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore};
+
+async fn spawn_server() -> String {
+    let state = app_state_helper::create_test_app_state_with_stores(
+        Arc::new(KVStore::new(KVConfig::default())),
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    app_state_helper::spawn_server_with_state(state).await
+}
+
+fn client(url: &str) -> SynapClient {
+    SynapClient::new(SynapConfig::new(url)).unwrap()
+}
+
+/// The start of the current hour, so test increments share known buckets
+fn this_hour() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    now - now % 3_600_000
+}
+
+#[tokio::test]
+async fn increments_are_bucketed_and_aggregated() {
+    let url = spawn_server().await;
+    let ts = client(&url).time_series();
+    let hour = this_hour();
+
+    let at = |offset: u64| TimeSeriesIncrOptions {
+        timestamp: Some(hour + offset),
+        resolution: Some(TimeSeriesResolution::Minute),
+        ..Default::default()
+    };
+    for (offset, value) in [(0, 3.0), (30_000, 5.0), (60_000, 1.0)] {
+        ts.incr_with("latency", value, at(offset)).await.unwrap();
+    }
+
+    let minutes = ts
+        .range("latency", TimeSeriesQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        minutes,
+        vec![
+            TimeSeriesPoint {
+                timestamp: hour,
+                value: 8.0
+            },
+            TimeSeriesPoint {
+                timestamp: hour + 60_000,
+                value: 1.0
+            },
+        ]
+    );
+
+    let hourly = |aggregation| TimeSeriesQuery {
+        from: Some(hour),
+        aggregation,
+        bucket: Some(Duration::from_secs(3600)),
+        ..Default::default()
+    };
+    let value = |points: Vec<TimeSeriesPoint>| points[0].value;
+    assert_eq!(
+        value(
+            ts.range("latency", hourly(TimeSeriesAggregation::Sum))
+                .await
+                .unwrap()
+        ),
+        9.0
+    );
+    assert_eq!(
+        value(
+            ts.range("latency", hourly(TimeSeriesAggregation::Avg))
+                .await
+                .unwrap()
+        ),
+        3.0
+    );
+    assert_eq!(
+        value(
+            ts.range("latency", hourly(TimeSeriesAggregation::Max))
+                .await
+                .unwrap()
+        ),
+        5.0
+    );
+}
+
+#[tokio::test]
+async fn a_series_keeps_its_resolution() {
+    let url = spawn_server().await;
+    let ts = client(&url).time_series();
+
+    ts.incr("signups", 1.0).await.unwrap();
+    let hourly = TimeSeriesIncrOptions {
+        resolution: Some(TimeSeriesResolution::Hour),
+        ..Default::default()
+    };
+    let err = ts.incr_with("signups", 1.0, hourly).await.unwrap_err();
+    assert!(err.to_string().contains("resolution"), "{err}");
+
+    // Buckets narrower than the resolution cannot be returned
+    let query = TimeSeriesQuery {
+        bucket: Some(Duration::from_millis(500)),
+        ..Default::default()
+    };
+    assert!(ts.range("signups", query).await.is_err());
+}
+
+#[tokio::test]
+async fn retention_and_delete() {
+    let url = spawn_server().await;
+    let ts = client(&url).time_series();
+
+    let short = TimeSeriesIncrOptions {
+        retention: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+    ts.incr_with("errors", 1.0, short).await.unwrap();
+    let stale = TimeSeriesIncrOptions {
+        timestamp: Some(this_hour() - 3_600_000),
+        ..Default::default()
+    };
+    let err = ts.incr_with("errors", 1.0, stale).await.unwrap_err();
+    assert!(err.to_string().contains("retention"), "{err}");
+
+    assert!(ts.delete("errors").await.unwrap());
+    assert!(!ts.delete("errors").await.unwrap());
+    assert!(
+        ts.range("errors", TimeSeriesQuery::default())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
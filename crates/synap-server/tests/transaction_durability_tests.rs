@@ -118,7 +118,7 @@ async fn committed_transaction_survives_crash() {
 
     // --- Run 2: recover from the WAL and verify every write is present. ---
     {
-        let (kv, hash, _list, set, _zset, _qm, _offset) =
+        let (kv, hash, _list, set, _zset, _hll, _bf, _qm, _offset) =
             recover(&config, kv_config, queue_config).await.unwrap();
 
         assert_eq!(
@@ -163,9 +163,15 @@ async fn committed_transaction_reaches_replica() {
     let master_kv = Arc::new(KVStore::new(KVConfig::default()));
     let master_stream = Arc::new(StreamManager::new(StreamConfig::default()));
     let master = Arc::new(
-        MasterNode::new(master_cfg, master_kv, Some(master_stream))
-            .await
-            .unwrap(),
+        MasterNode::new(
+            master_cfg,
+            synap_server::persistence::StoreArcs {
+                stream_manager: Some(master_stream),
+                ..synap_server::persistence::StoreArcs::kv_only(master_kv)
+            },
+        )
+        .await
+        .unwrap(),
     );
     sleep(Duration::from_millis(100)).await;
 
@@ -199,6 +205,9 @@ async fn committed_transaction_reaches_replica() {
             list_store: Some(Arc::clone(&r_list)),
             set_store: Some(Arc::clone(&r_set)),
             sorted_set_store: Some(Arc::clone(&r_zset)),
+            hyperloglog_store: None,
+            bloom_store: None,
+            bitmap_store: None,
             queue_manager: None,
             stream_manager: Some(Arc::clone(&r_stream)),
         },
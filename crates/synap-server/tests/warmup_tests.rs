@@ -0,0 +1,144 @@
+//! Cold start warmup: a held node answers 503 on `/health/ready` until a
+//! warmup through `POST /warmup` or the `warmup.run` command has completed.
+
+mod app_state_helper;
+
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use synap_server::auth::{ApiKeyManager, UserManager};
+use synap_server::core::{HashStore, ListStore, SetStore, SortedSetStore};
+use synap_server::{KVConfig, KVStore, create_router};
+use tokio::net::TcpListener;
+
+/// A server whose readiness is held until a warmup runs, with `seeded` keys
+async fn spawn_held_server(seeded: &[&str]) -> String {
+    let kv_store = Arc::new(KVStore::new(KVConfig::default()));
+    for key in seeded {
+        kv_store.set(*key, b"value".to_vec(), None).await.unwrap();
+    }
+    let state = app_state_helper::create_test_app_state_with_stores(
+        kv_store,
+        Arc::new(HashStore::new()),
+        Arc::new(ListStore::new()),
+        Arc::new(SetStore::new()),
+        Arc::new(SortedSetStore::new()),
+    );
+    state.monitoring.warmup().hold();
+
+    let app = create_router(
+        state,
+        synap_server::config::RateLimitConfig {
+            enabled: false,
+            requests_per_second: 100,
+            burst_size: 10,
+            ..Default::default()
+        },
+        synap_server::config::McpConfig::default(),
+        Arc::new(UserManager::new()),
+        Arc::new(ApiKeyManager::new()),
+        false,
+        false,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+#[tokio::test]
+async fn test_rest_warmup_releases_readiness() {
+    let url = spawn_held_server(&["config", "session:1", "session:2"]).await;
+    let client = Client::new();
+
+    let response = client
+        .get(format!("{url}/health/ready"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["ready"], false);
+    assert!(body["warmup"].is_null());
+
+    // Liveness is unaffected
+    let response = client.get(format!("{url}/health")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let report: Value = client
+        .post(format!("{url}/warmup"))
+        .json(&json!({"keys": ["config", "absent"], "prefixes": ["session:"]}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(report["keys_requested"], 4);
+    assert_eq!(report["keys_loaded"], 3);
+    assert_eq!(report["keys_missing"], 1);
+    assert_eq!(report["bytes_loaded"], 15);
+
+    let response = client
+        .get(format!("{url}/health/ready"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["ready"], true);
+    assert_eq!(body["warmup"]["keys_loaded"], 3);
+}
+
+#[tokio::test]
+async fn test_warmup_command() {
+    let url = spawn_held_server(&["a", "b"]).await;
+    let client = Client::new();
+
+    let response: Value = client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({
+            "command": "warmup.run",
+            "request_id": "t",
+            "payload": {"keys": ["a", "b"]}
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response["success"], true);
+    assert_eq!(response["payload"]["keys_loaded"], 2);
+
+    let response = client
+        .get(format!("{url}/health/ready"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response: Value = client
+        .post(format!("{url}/api/v1/command"))
+        .json(&json!({
+            "command": "warmup.run",
+            "request_id": "t",
+            "payload": {"key": ["a"]}
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response["success"], false);
+    assert!(
+        response["error"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid warmup manifest")
+    );
+}
@@ -69,6 +69,14 @@ pub fn watch_app_state() -> AppState {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     }
 }
 
@@ -85,6 +93,7 @@ async fn spawn_watch_server() -> (String, tokio::sync::oneshot::Sender<()>) {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -235,6 +244,7 @@ async fn rpc_and_websocket_deliver_the_same_envelope() {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
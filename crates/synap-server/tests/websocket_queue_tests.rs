@@ -74,6 +74,14 @@ async fn spawn_test_server() -> (String, tokio::sync::oneshot::Sender<()>) {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     let user_manager = Arc::new(UserManager::new());
@@ -84,6 +92,7 @@ async fn spawn_test_server() -> (String, tokio::sync::oneshot::Sender<()>) {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -75,6 +75,14 @@ async fn spawn_test_server() -> (String, tokio::sync::oneshot::Sender<()>) {
         user_manager: None,
         require_auth: false,
         replication: None,
+        failover: None,
+        lock_manager: Arc::default(),
+        rate_limits: Arc::default(),
+        time_series: Arc::default(),
+        bloom_store: Arc::default(),
+        shovels: None,
+        webhooks: None,
+        schedules: None,
     };
 
     let user_manager = Arc::new(UserManager::new());
@@ -85,6 +93,7 @@ async fn spawn_test_server() -> (String, tokio::sync::oneshot::Sender<()>) {
             enabled: false,
             requests_per_second: 100,
             burst_size: 10,
+            ..Default::default()
         },
         synap_server::config::McpConfig::default(),
         user_manager,
@@ -334,3 +343,69 @@ async fn test_stream_websocket_from_offset() {
     write.close().await.unwrap();
     let _ = shutdown.send(());
 }
+
+#[cfg(feature = "s2s-tests")]
+#[tokio::test]
+async fn test_stream_websocket_multiple_rooms() {
+    let (base_url, shutdown) = spawn_test_server().await;
+    let ws_url = base_url.replace("http://", "ws://");
+    let client = reqwest::Client::new();
+
+    for room in ["dash_a", "dash_b"] {
+        client
+            .post(format!("{}/stream/{}", base_url, room))
+            .send()
+            .await
+            .unwrap();
+        for i in 0..3 {
+            client
+                .post(format!("{}/stream/{}/publish", base_url, room))
+                .json(&json!({"event": "tick", "data": {"num": i}}))
+                .send()
+                .await
+                .unwrap();
+        }
+    }
+
+    // dash_a from the start, dash_b from its last event
+    let (ws_stream, _) = connect_async(format!(
+        "{}/stream/ws/dashboard?rooms=dash_a:0,dash_b:2",
+        ws_url
+    ))
+    .await
+    .unwrap();
+
+    let (mut write, mut read) = ws_stream.split();
+
+    let welcome: serde_json::Value = match read.next().await {
+        Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+        other => panic!("Expected welcome, got {:?}", other),
+    };
+    assert_eq!(welcome["rooms"].as_array().unwrap().len(), 2);
+
+    let mut received = Vec::new();
+    tokio::time::timeout(tokio::time::Duration::from_secs(2), async {
+        while let Some(Ok(Message::Text(text))) = read.next().await {
+            let msg: serde_json::Value = serde_json::from_str(&text).unwrap();
+            if msg["type"] == "event" {
+                received.push((
+                    msg["room"].as_str().unwrap().to_string(),
+                    msg["offset"].as_u64().unwrap(),
+                ));
+                if received.len() == 4 {
+                    break;
+                }
+            }
+        }
+    })
+    .await
+    .expect("Timeout");
+
+    assert_eq!(
+        received.iter().filter(|(room, _)| room == "dash_a").count(),
+        3
+    );
+    assert!(received.contains(&("dash_b".to_string(), 2)));
+    write.close().await.unwrap();
+    let _ = shutdown.send(());
+}
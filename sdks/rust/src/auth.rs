@@ -0,0 +1,207 @@
+//! Request authentication.
+//!
+//! Credentials set with [`SynapConfig::with_api_key`](crate::SynapConfig::with_api_key)
+//! (sent as `Authorization: Bearer <key>`) or
+//! [`SynapConfig::with_basic_auth`](crate::SynapConfig::with_basic_auth) are
+//! attached to every HTTP request and WebSocket upgrade the client makes.
+//!
+//! With [`SynapConfig::with_token_refresh`](crate::SynapConfig::with_token_refresh),
+//! a request rejected with `401 Unauthorized` calls the refresh callback once
+//! and is retried with the token it returns. Concurrent requests that hit the
+//! same rejection share one refresh. The binary transports authenticate in
+//! their connection handshake with the credentials from the configuration
+//! and are not refreshed.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use reqwest::header::{AUTHORIZATION, HeaderValue};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+
+use crate::client::SynapConfig;
+use crate::error::{Result, SynapError};
+use crate::metrics::{ClientMetrics, Retry};
+
+type RefreshFuture = Pin<Box<dyn Future<Output = Result<String>> + Send>>;
+
+/// Callback producing a fresh API key or bearer token after a `401`.
+///
+/// Built by [`SynapConfig::with_token_refresh`](crate::SynapConfig::with_token_refresh).
+#[derive(Clone)]
+pub struct TokenRefresh(Arc<dyn Fn() -> RefreshFuture + Send + Sync>);
+
+impl TokenRefresh {
+    /// Wrap an async callback.
+    pub fn new<F, Fut>(refresh: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String>> + Send + 'static,
+    {
+        Self(Arc::new(move || Box::pin(refresh())))
+    }
+}
+
+impl fmt::Debug for TokenRefresh {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenRefresh")
+    }
+}
+
+/// The `Authorization` header currently in use, shared by all clones of a
+/// client.
+pub(crate) struct Credentials {
+    header: RwLock<Option<HeaderValue>>,
+    refresh: Option<TokenRefresh>,
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+impl Credentials {
+    pub(crate) fn from_config(config: &SynapConfig) -> Result<Self> {
+        let header = if let Some(token) = &config.auth_token {
+            Some(bearer(token)?)
+        } else if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            use base64::Engine;
+            let encoded = base64::engine::general_purpose::STANDARD
+                .encode(format!("{}:{}", username, password));
+            Some(header_value(format!("Basic {}", encoded))?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            header: RwLock::new(header),
+            refresh: config.token_refresh.clone(),
+            refreshing: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// The header to send, if any credentials are configured.
+    pub(crate) fn header(&self) -> Option<HeaderValue> {
+        self.header.read().unwrap().clone()
+    }
+
+    /// Replace the credentials after `rejected` was refused. Returns whether
+    /// a retry can use different credentials.
+    pub(crate) async fn refresh(&self, rejected: Option<&HeaderValue>) -> Result<bool> {
+        let Some(refresh) = &self.refresh else {
+            return Ok(false);
+        };
+
+        let _guard = self.refreshing.lock().await;
+        // Another request refreshed while this one waited for the lock.
+        if self.header().as_ref() != rejected {
+            return Ok(true);
+        }
+
+        tracing::debug!("credentials rejected, refreshing token");
+        let token = (refresh.0)().await?;
+        *self.header.write().unwrap() = Some(bearer(&token)?);
+        Ok(true)
+    }
+}
+
+fn bearer(token: &str) -> Result<HeaderValue> {
+    header_value(format!("Bearer {}", token))
+}
+
+fn header_value(value: String) -> Result<HeaderValue> {
+    let mut value = HeaderValue::from_str(&value)
+        .map_err(|_| SynapError::Other("credentials contain invalid header characters".into()))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+/// HTTP client that authenticates every request and retries once with
+/// refreshed credentials on `401`.
+#[derive(Clone)]
+pub(crate) struct HttpClient {
+    client: Client,
+    credentials: Arc<Credentials>,
+    metrics: Arc<ClientMetrics>,
+}
+
+impl HttpClient {
+    pub(crate) fn new(
+        client: Client,
+        credentials: Arc<Credentials>,
+        metrics: Arc<ClientMetrics>,
+    ) -> Self {
+        Self {
+            client,
+            credentials,
+            metrics,
+        }
+    }
+
+    pub(crate) fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
+
+    /// Send the request built by `request`; it is built again for the retry.
+    pub(crate) async fn send(
+        &self,
+        request: impl Fn(&Client) -> RequestBuilder,
+    ) -> Result<Response> {
+        let authorization = self.credentials.header();
+        let response = authorize(request(&self.client), authorization.as_ref())
+            .send()
+            .await?;
+        if response.status() != StatusCode::UNAUTHORIZED
+            || !self.credentials.refresh(authorization.as_ref()).await?
+        {
+            return Ok(response);
+        }
+
+        self.metrics.retry(Retry::AuthRefresh);
+        let authorization = self.credentials.header();
+        Ok(authorize(request(&self.client), authorization.as_ref())
+            .send()
+            .await?)
+    }
+}
+
+fn authorize(request: RequestBuilder, authorization: Option<&HeaderValue>) -> RequestBuilder {
+    match authorization {
+        Some(value) => request.header(AUTHORIZATION, value.clone()),
+        None => request,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_refresh_is_shared_by_concurrent_rejections() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let config = SynapConfig::new("http://localhost:15500")
+            .with_api_key("old")
+            .with_token_refresh(move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                async move { Ok(format!("new-{}", n)) }
+            });
+        let credentials = Credentials::from_config(&config).unwrap();
+
+        let rejected = credentials.header();
+        assert_eq!(rejected.as_ref().unwrap(), "Bearer old");
+        assert!(credentials.refresh(rejected.as_ref()).await.unwrap());
+        // A second request rejected with the old token reuses the new one.
+        assert!(credentials.refresh(rejected.as_ref()).await.unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(credentials.header().unwrap(), "Bearer new-1");
+    }
+
+    #[tokio::test]
+    async fn test_no_refresh_without_callback() {
+        let config = SynapConfig::new("http://localhost:15500").with_basic_auth("user", "pass");
+        let credentials = Credentials::from_config(&config).unwrap();
+        let header = credentials.header().unwrap();
+        assert!(header.is_sensitive());
+        assert_eq!(header, "Basic dXNlcjpwYXNz");
+        assert!(!credentials.refresh(Some(&header)).await.unwrap());
+    }
+}
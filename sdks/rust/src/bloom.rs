@@ -0,0 +1,112 @@
+//! Bloom filters
+//!
+//! Sends the typed `bf.*` commands from
+//! [`protocol::bloom`](crate::protocol::bloom). A filter answers "maybe
+//! present" or "definitely absent" in a few bits per item, so deduplicating a
+//! large stream no longer needs a set holding every member.
+//!
+//! The Bloom filter commands have no native mapping yet; use an `http://` URL.
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::protocol::bloom::{BloomAdd, BloomExists, BloomMAdd, BloomMExists, BloomReserve};
+
+/// Bloom filter operations
+#[derive(Clone)]
+pub struct BloomFilterManager {
+    client: SynapClient,
+}
+
+impl BloomFilterManager {
+    /// Create a new Bloom filter interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Create the filter on `key` sized for `capacity` items at `error_rate`
+    ///
+    /// A filter that fills up grows by adding a larger layer with a tighter
+    /// error rate. Filters created by [`add`](Self::add) on a missing key use
+    /// the server defaults (100 items, 1%). Returns `false` if the key already
+    /// held a filter, which keeps its parameters.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let bloom = client.bloom();
+    /// bloom.reserve("seen:orders", 0.001, 1_000_000).await?;
+    /// if bloom.add("seen:orders", "order-42").await? {
+    ///     println!("first time we see order-42");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reserve(&self, key: &str, error_rate: f64, capacity: u64) -> Result<bool> {
+        let response = self
+            .client
+            .execute(&BloomReserve {
+                key: key.to_owned(),
+                error_rate,
+                capacity,
+            })
+            .await?;
+        Ok(response.reserved)
+    }
+
+    /// Add `item` to the filter on `key`
+    ///
+    /// Returns `false` if the item may have been added before.
+    pub async fn add(&self, key: &str, item: &str) -> Result<bool> {
+        let response = self
+            .client
+            .execute(&BloomAdd {
+                key: key.to_owned(),
+                item: item.to_owned(),
+            })
+            .await?;
+        Ok(response.added)
+    }
+
+    /// Add several items to the filter on `key`
+    ///
+    /// Returns one flag per item, as [`add`](Self::add) does.
+    pub async fn madd<S: AsRef<str>>(&self, key: &str, items: &[S]) -> Result<Vec<bool>> {
+        let response = self
+            .client
+            .execute(&BloomMAdd {
+                key: key.to_owned(),
+                items: items.iter().map(|item| item.as_ref().to_owned()).collect(),
+            })
+            .await?;
+        Ok(response.added)
+    }
+
+    /// Check whether `item` may be in the filter on `key`
+    ///
+    /// `false` is certain; a missing key holds nothing.
+    pub async fn exists(&self, key: &str, item: &str) -> Result<bool> {
+        let response = self
+            .client
+            .execute(&BloomExists {
+                key: key.to_owned(),
+                item: item.to_owned(),
+            })
+            .await?;
+        Ok(response.exists)
+    }
+
+    /// Check several items against the filter on `key`
+    pub async fn mexists<S: AsRef<str>>(&self, key: &str, items: &[S]) -> Result<Vec<bool>> {
+        let response = self
+            .client
+            .execute(&BloomMExists {
+                key: key.to_owned(),
+                items: items.iter().map(|item| item.as_ref().to_owned()).collect(),
+            })
+            .await?;
+        Ok(response.exists)
+    }
+}
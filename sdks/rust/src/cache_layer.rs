@@ -0,0 +1,302 @@
+//! Read-through / write-through caching on top of [`KvOps`].
+//!
+//! [`CacheLayer`] wraps the usual Synap-as-cache boilerplate around a source
+//! of truth such as a database:
+//!
+//! - [`get_or_insert_with`](CacheLayer::get_or_insert_with) returns the cached
+//!   value, or runs the fetch, caches its result and returns it. Concurrent
+//!   misses on one key are single-flighted: one caller fetches while the rest
+//!   wait and then read what it cached, so a hot key expiring does not send a
+//!   stampede to the database.
+//! - [`write_through`](CacheLayer::write_through) writes the source of truth
+//!   first and caches the value only once that succeeded.
+//!
+//! Single-flight is per client: every clone of a [`SynapClient`](crate::SynapClient)
+//! and every [`KVStore`] it hands out share one set of key locks, but two
+//! processes missing the same key both fetch.
+//!
+//! ```no_run
+//! use synap_sdk::{CacheLayer, SynapClient, SynapConfig};
+//!
+//! # async fn load_user(id: u64) -> synap_sdk::Result<String> { Ok(format!("user {id}")) }
+//! # #[tokio::main]
+//! # async fn main() -> synap_sdk::Result<()> {
+//! let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+//! let kv = client.kv();
+//!
+//! let name: String = kv
+//!     .get_or_insert_with("user:42", Some(300), || load_user(42))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::OwnedMutexGuard;
+
+use crate::error::SynapError;
+use crate::kv::{KVStore, decode_value};
+use crate::memory::MemoryKv;
+use crate::ops::KvOps;
+
+/// Per-key locks for single-flight fills and writes.
+///
+/// An entry lives only while someone holds or waits for its key, so the map
+/// stays as small as the set of keys being filled right now.
+#[derive(Default)]
+pub struct SingleFlight {
+    locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl SingleFlight {
+    /// Wait for exclusive use of `key`.
+    pub async fn lock(&self, key: &str) -> FlightGuard<'_> {
+        let lock = Arc::clone(self.locks().entry(key.to_owned()).or_default());
+        FlightGuard {
+            flights: self,
+            key: key.to_owned(),
+            guard: Some(lock.lock_owned().await),
+        }
+    }
+
+    /// Keys currently locked or waited for.
+    pub fn in_flight(&self) -> usize {
+        self.locks().len()
+    }
+
+    fn locks(&self) -> MutexGuard<'_, HashMap<String, Arc<tokio::sync::Mutex<()>>>> {
+        self.locks.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Exclusive use of one key, released on drop.
+pub struct FlightGuard<'a> {
+    flights: &'a SingleFlight,
+    key: String,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for FlightGuard<'_> {
+    fn drop(&mut self) {
+        let mut locks = self.flights.locks();
+        let Some(guard) = self.guard.take() else {
+            return;
+        };
+        // The map and this guard hold the only references: nobody waits.
+        // Waiters clone the lock under the map mutex, so this cannot race.
+        if Arc::strong_count(OwnedMutexGuard::mutex(&guard)) == 2 {
+            locks.remove(&self.key);
+        }
+    }
+}
+
+/// Read-through and write-through helpers over a [`KvOps`] cache.
+///
+/// Implemented for [`KVStore`] and for [`MemoryKv`], so code written against
+/// it can be tested without a server. Values are stored as JSON, the way
+/// [`KVStore::set`] stores them.
+#[async_trait]
+pub trait CacheLayer: KvOps {
+    /// The key locks shared by every handle on this cache.
+    fn flights(&self) -> &SingleFlight;
+
+    /// Return the value cached under `key`, or run `fetch`, cache its result
+    /// for `ttl` seconds (forever when `None`) and return it.
+    ///
+    /// A failed fetch caches nothing and its error is returned as is. A cached
+    /// value that does not decode as `T` is treated as a miss and replaced.
+    async fn get_or_insert_with<T, E, F, Fut>(
+        &self,
+        key: &str,
+        ttl: Option<u64>,
+        fetch: F,
+    ) -> std::result::Result<T, E>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync,
+        E: From<SynapError> + Send,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = std::result::Result<T, E>> + Send,
+    {
+        if let Some(value) = self.cached(key).await? {
+            return Ok(value);
+        }
+
+        let _flight = self.flights().lock(key).await;
+        // Whoever held the lock before us may have filled the key
+        if let Some(value) = self.cached(key).await? {
+            return Ok(value);
+        }
+
+        let value = fetch().await?;
+        let json = serde_json::to_value(&value).map_err(SynapError::from)?;
+        self.set(key, json, ttl).await?;
+        Ok(value)
+    }
+
+    /// Run `write` against the source of truth, then cache `value` under
+    /// `key` for `ttl` seconds.
+    ///
+    /// When `write` fails the cache is left alone and its error returned.
+    /// Fills of the same key wait for the write, so none of them can cache
+    /// the value it replaces.
+    async fn write_through<T, E, F, Fut>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl: Option<u64>,
+        write: F,
+    ) -> std::result::Result<(), E>
+    where
+        T: Serialize + Sync,
+        E: From<SynapError> + Send,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = std::result::Result<(), E>> + Send,
+    {
+        let json = serde_json::to_value(value).map_err(SynapError::from)?;
+        let _flight = self.flights().lock(key).await;
+        write().await?;
+        self.set(key, json, ttl).await?;
+        Ok(())
+    }
+
+    /// Drop `key` from the cache, after any fill of it in progress, so the
+    /// next read fetches it again.
+    async fn invalidate(&self, key: &str) -> crate::Result<()> {
+        let _flight = self.flights().lock(key).await;
+        self.delete(key).await?;
+        Ok(())
+    }
+
+    /// The cached value, `None` on a miss or when it does not decode as `T`.
+    #[doc(hidden)]
+    async fn cached<T>(&self, key: &str) -> crate::Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(self
+            .get(key)
+            .await?
+            .and_then(|value| decode_value(value).ok()))
+    }
+}
+
+impl CacheLayer for KVStore {
+    fn flights(&self) -> &SingleFlight {
+        self.client.flights()
+    }
+}
+
+impl CacheLayer for MemoryKv {
+    fn flights(&self) -> &SingleFlight {
+        self.backend.flights()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryBackend;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn a_miss_is_fetched_once_and_then_served_from_the_cache() {
+        let kv = MemoryBackend::new().kv();
+        let fetches = AtomicUsize::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, SynapError>(vec![1, 2, 3])
+        };
+
+        let first: Vec<u8> = kv.get_or_insert_with("k", None, fetch).await.unwrap();
+        let second: Vec<u8> = kv.get_or_insert_with("k", None, fetch).await.unwrap();
+
+        assert_eq!(first, vec![1, 2, 3]);
+        assert_eq!(second, first);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_misses_share_one_fetch() {
+        let kv = MemoryBackend::new().kv();
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let readers = (0..16).map(|_| {
+            let kv = kv.clone();
+            let fetches = Arc::clone(&fetches);
+            tokio::spawn(async move {
+                kv.get_or_insert_with("hot", Some(60), || async move {
+                    fetches.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    Ok::<_, SynapError>("value".to_string())
+                })
+                .await
+                .unwrap()
+            })
+        });
+        for reader in readers {
+            assert_eq!(reader.await.unwrap(), "value");
+        }
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(kv.flights().in_flight(), 0, "key locks are released");
+    }
+
+    #[tokio::test]
+    async fn a_failed_fetch_caches_nothing() {
+        let kv = MemoryBackend::new().kv();
+
+        let result: Result<String, SynapError> = kv
+            .get_or_insert_with("k", None, || async {
+                Err(SynapError::ServerError("database down".into()))
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(kv.get("k").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn write_through_caches_only_after_the_write_succeeds() {
+        let kv = MemoryBackend::new().kv();
+
+        let failed = kv
+            .write_through("k", &"new", None, || async {
+                Err(SynapError::ServerError("constraint violated".into()))
+            })
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(kv.get("k").await.unwrap(), None);
+
+        kv.write_through("k", &"new", None, || async { Ok::<_, SynapError>(()) })
+            .await
+            .unwrap();
+        let cached: String = kv
+            .get_or_insert_with("k", None, || async {
+                unreachable!("the written value is cached") as Result<_, SynapError>
+            })
+            .await
+            .unwrap();
+        assert_eq!(cached, "new");
+    }
+
+    #[tokio::test]
+    async fn invalidate_makes_the_next_read_fetch() {
+        let kv = MemoryBackend::new().kv();
+        kv.set("k", serde_json::json!("old"), None).await.unwrap();
+
+        kv.invalidate("k").await.unwrap();
+        let value: String = kv
+            .get_or_insert_with("k", None, || async { Ok::<_, SynapError>("fresh".into()) })
+            .await
+            .unwrap();
+
+        assert_eq!(value, "fresh");
+    }
+}
@@ -3,22 +3,45 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::protocol::{Command, Encoding, Request};
 use reqwest::Client;
 use serde_json::Value;
+use tracing::Instrument;
 use url::Url;
 
-use crate::error::{Result, SynapError};
+use crate::auth::{Credentials, HttpClient, TokenRefresh};
+use crate::cache_layer::SingleFlight;
+use crate::client_cache::{ClientCache, ClientCacheConfig};
+use crate::cluster::{
+    ClusterNode, ClusterRouter, MAX_REDIRECTS, command_key, hash_slot, parse_redirect,
+};
+use crate::error::{ApiError, Result, SynapError};
+use crate::key_prefix;
+use crate::metrics::{ClientMetrics, MetricsSnapshot, Retry};
+use crate::replica::{
+    Endpoint, ReadPreference, ReplicaSet, ReplicaStatus, ReplicationTopology, is_endpoint_failure,
+    is_read_only, spawn_health_checks,
+};
+use crate::telemetry;
+use crate::tls::TlsConfig;
+use crate::transport::http::api_error_from_body;
+use crate::transport::websocket::CommandSocket;
 use crate::transport::{
-    Resp3Transport, RpcCredentials, SynapRpcTransport, TransportMode, map_command, map_response,
+    HttpTransport, Resp3Transport, RpcCredentials, SynapRpcTransport, Transport, TransportMode,
+    WsCommandTransport,
 };
 use crate::{
-    BitmapManager, GeospatialManager, HashManager, HyperLogLogManager, KVStore, ListManager,
-    PubSubManager, QueueManager, ScriptManager, SetManager, SortedSetManager, StreamManager,
-    TransactionManager,
+    BitmapManager, BloomFilterManager, GeospatialManager, HashManager, HyperLogLogManager, KVStore,
+    ListManager, LockManager, PubSubManager, QueueManager, RateLimiterManager, SchedulerManager,
+    ScriptManager, SetManager, SnapshotImport, SortedSetManager, StreamManager, TimeSeriesManager,
+    TransactionManager, WarmupManifest, WarmupReport,
 };
 
 // ── SynapConfig ───────────────────────────────────────────────────────────────
 
+/// Default for [`SynapConfig::keys_warn_threshold`]
+const DEFAULT_KEYS_WARN_THRESHOLD: usize = 10_000;
+
 /// Synap client configuration.
 #[derive(Debug, Clone)]
 pub struct SynapConfig {
@@ -44,6 +67,41 @@ pub struct SynapConfig {
     pub username: Option<String>,
     /// Optional password for HTTP Basic Auth.
     pub password: Option<String>,
+    /// Called for a new token when the server answers `401`. See
+    /// [`crate::auth`].
+    pub token_refresh: Option<TokenRefresh>,
+    /// Read replica URLs, in any of the schemes accepted by [`SynapConfig::new`].
+    pub replica_urls: Vec<String>,
+    /// Where read-only commands are sent when replicas are configured
+    /// (default: [`ReadPreference::Primary`]).
+    pub read_preference: ReadPreference,
+    /// How often replicas are health-checked (default: 5 seconds).
+    pub replica_health_interval: Duration,
+    /// Route keyed commands to the cluster node owning the key's slot
+    /// (default: `false`). See [`crate::cluster`].
+    pub cluster: bool,
+    /// TLS options for `https://` and `wss://` connections. See
+    /// [`crate::tls`].
+    pub tls: TlsConfig,
+    /// Body encoding for HTTP commands (default: [`Encoding::Json`]). See
+    /// [`SynapConfig::with_http_encoding`].
+    pub http_encoding: Encoding,
+    /// Result size above which [`KVStore::keys`] logs a
+    /// warning suggesting [`KVStore::scan`] (default:
+    /// 10 000; `None` never warns).
+    pub keys_warn_threshold: Option<usize>,
+    /// Cache [`KVStore::get`] results locally, invalidated by the server
+    /// (default: off). See [`crate::client_cache`].
+    pub client_cache: Option<ClientCacheConfig>,
+    /// Logical database commands run in (default: 0). See
+    /// [`SynapConfig::with_db`].
+    pub db: u32,
+    /// Prepended to every key, queue name and stream room the client sends
+    /// (default: none). See [`SynapConfig::with_key_prefix`].
+    pub key_prefix: Option<String>,
+    /// Record client-side metrics (default: `false`). See
+    /// [`crate::metrics`].
+    pub metrics: bool,
 }
 
 impl SynapConfig {
@@ -86,6 +144,18 @@ impl SynapConfig {
                 auth_token: None,
                 username: None,
                 password: None,
+                token_refresh: None,
+                replica_urls: Vec::new(),
+                read_preference: ReadPreference::Primary,
+                replica_health_interval: Duration::from_secs(5),
+                cluster: false,
+                tls: TlsConfig::default(),
+                http_encoding: Encoding::Json,
+                keys_warn_threshold: Some(DEFAULT_KEYS_WARN_THRESHOLD),
+                client_cache: None,
+                db: 0,
+                key_prefix: None,
+                metrics: false,
             };
         }
 
@@ -103,6 +173,18 @@ impl SynapConfig {
                 auth_token: None,
                 username: None,
                 password: None,
+                token_refresh: None,
+                replica_urls: Vec::new(),
+                read_preference: ReadPreference::Primary,
+                replica_health_interval: Duration::from_secs(5),
+                cluster: false,
+                tls: TlsConfig::default(),
+                http_encoding: Encoding::Json,
+                keys_warn_threshold: Some(DEFAULT_KEYS_WARN_THRESHOLD),
+                client_cache: None,
+                db: 0,
+                key_prefix: None,
+                metrics: false,
             };
         }
 
@@ -119,6 +201,18 @@ impl SynapConfig {
             auth_token: None,
             username: None,
             password: None,
+            token_refresh: None,
+            replica_urls: Vec::new(),
+            read_preference: ReadPreference::Primary,
+            replica_health_interval: Duration::from_secs(5),
+            cluster: false,
+            tls: TlsConfig::default(),
+            http_encoding: Encoding::Json,
+            keys_warn_threshold: Some(DEFAULT_KEYS_WARN_THRESHOLD),
+            client_cache: None,
+            db: 0,
+            key_prefix: None,
+            metrics: false,
         }
     }
 
@@ -178,6 +272,26 @@ impl SynapConfig {
         self
     }
 
+    /// Send commands over one multiplexed WebSocket to
+    /// `/api/v1/command/ws` instead of one HTTP request each.
+    ///
+    /// The socket carries the same envelope as the HTTP transport, so every
+    /// command and [`SynapConfig::with_db`] work unchanged; concurrent calls
+    /// share it and are answered as soon as each command finishes. Frames are
+    /// MessagePack when [`SynapConfig::with_http_encoding`] selects it, JSON
+    /// otherwise. The base URL stays `http://` or `https://`.
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::SynapConfig;
+    ///
+    /// let config = SynapConfig::new("http://localhost:15500").connect_ws();
+    /// ```
+    pub fn connect_ws(mut self) -> Self {
+        self.transport = TransportMode::WebSocket;
+        self
+    }
+
     /// Override the SynapRPC listener address (host + port).
     ///
     /// # Deprecated
@@ -212,6 +326,19 @@ impl SynapConfig {
         self
     }
 
+    /// Authenticate with a Synap API key, sent as a bearer token on HTTP
+    /// requests and WebSocket upgrades and in the RPC handshake.
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::SynapConfig;
+    ///
+    /// let config = SynapConfig::new("http://localhost:15500").with_api_key("sk_live_abc123");
+    /// ```
+    pub fn with_api_key(self, api_key: impl Into<String>) -> Self {
+        self.with_auth_token(api_key)
+    }
+
     /// Set the authentication token (API key / Bearer token for HTTP).
     pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
         self.auth_token = Some(token.into());
@@ -232,11 +359,217 @@ impl SynapConfig {
         self
     }
 
+    /// Fetch a new token when the server rejects the current credentials.
+    ///
+    /// On a `401` response the callback is awaited once and the request is
+    /// retried with `Authorization: Bearer <token>`; the new token is kept
+    /// for later requests. HTTP and WebSocket only.
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::SynapConfig;
+    ///
+    /// let config = SynapConfig::new("http://localhost:15500")
+    ///     .with_api_key("sk_expiring")
+    ///     .with_token_refresh(|| async {
+    ///         // e.g. ask a secrets manager for the rotated key
+    ///         Ok("sk_rotated".to_string())
+    ///     });
+    /// ```
+    pub fn with_token_refresh<F, Fut>(mut self, refresh: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<String>> + Send + 'static,
+    {
+        self.token_refresh = Some(TokenRefresh::new(refresh));
+        self
+    }
+
     /// Set the maximum HTTP retry attempts.
     pub fn with_max_retries(mut self, max_retries: u32) -> Self {
         self.max_retries = max_retries;
         self
     }
+
+    /// Add read replicas. Reads only go to them once a read preference other
+    /// than [`ReadPreference::Primary`] is set.
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::{ReadPreference, SynapConfig};
+    ///
+    /// let config = SynapConfig::new("synap://primary:15501")
+    ///     .with_replicas(["synap://replica-1:15501", "synap://replica-2:15501"])
+    ///     .with_read_preference(ReadPreference::Replica);
+    /// ```
+    pub fn with_replicas<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.replica_urls.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set where read-only commands are sent.
+    pub fn with_read_preference(mut self, preference: ReadPreference) -> Self {
+        self.read_preference = preference;
+        self
+    }
+
+    /// Set how often replicas are health-checked.
+    pub fn with_replica_health_interval(mut self, interval: Duration) -> Self {
+        self.replica_health_interval = interval;
+        self
+    }
+
+    /// Treat the URL as a seed node of a Synap cluster: keyed commands are
+    /// sent to the node that owns the key's hash slot, and `MOVED` / `ASK`
+    /// redirects are followed.
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::SynapConfig;
+    ///
+    /// let config = SynapConfig::new("synap://node-1:15501").with_cluster();
+    /// ```
+    pub fn with_cluster(mut self) -> Self {
+        self.cluster = true;
+        self
+    }
+
+    /// Send HTTP commands as MessagePack or CBOR instead of JSON.
+    ///
+    /// Byte payloads (queue messages, `kv.get` with `type: bytes`) travel
+    /// as binary strings rather than arrays of numbers, which cuts both the
+    /// body size and the encode/decode cost. Requires a server that
+    /// negotiates `/api/v1/command` bodies; error responses are still JSON.
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::{Encoding, SynapConfig};
+    ///
+    /// let config = SynapConfig::new("http://localhost:15500")
+    ///     .with_http_encoding(Encoding::MessagePack);
+    /// ```
+    pub fn with_http_encoding(mut self, encoding: Encoding) -> Self {
+        self.http_encoding = encoding;
+        self
+    }
+
+    /// Set the result size above which [`KVStore::keys`]
+    /// warns, or `None` to never warn.
+    pub fn with_keys_warn_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.keys_warn_threshold = threshold;
+        self
+    }
+
+    /// Cache [`KVStore::get`] results locally. The server pushes an
+    /// invalidation when a cached key changes; see [`crate::client_cache`].
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::{ClientCacheConfig, SynapConfig};
+    ///
+    /// let config = SynapConfig::new("http://localhost:15500")
+    ///     .with_client_cache(ClientCacheConfig::default());
+    /// ```
+    pub fn with_client_cache(mut self, cache: ClientCacheConfig) -> Self {
+        self.client_cache = Some(cache);
+        self
+    }
+
+    /// Record per-command counts and latencies, retries and connection
+    /// stats, read with [`SynapClient::metrics`]; see [`crate::metrics`].
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::SynapConfig;
+    ///
+    /// let config = SynapConfig::new("http://localhost:15500").with_metrics();
+    /// ```
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = true;
+        self
+    }
+
+    /// Replace all TLS options at once.
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Prefix every key, queue name and stream room the client sends, and
+    /// strip it from the names listed back (`kv().keys`, `kv().scan`,
+    /// `queue().list`, `stream().list`), so several applications or
+    /// environments can share a server without seeing each other's data.
+    ///
+    /// KV, hash, list, set, sorted set, HyperLogLog, bitmap, geospatial,
+    /// Bloom filter, script and transaction keys are prefixed, as are queue
+    /// names and stream rooms. Pub/Sub topics, locks, rate limiters and time
+    /// series are not.
+    ///
+    /// # Example
+    /// ```
+    /// use synap_sdk::SynapConfig;
+    ///
+    /// let config = SynapConfig::new("http://localhost:15500").with_key_prefix("staging:");
+    /// ```
+    pub fn with_key_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.key_prefix = Some(prefix.into()).filter(|prefix| !prefix.is_empty());
+        self
+    }
+
+    /// Run commands in logical database `db` instead of database 0. The
+    /// server must have `databases.count` above `db`.
+    ///
+    /// Only the HTTP transport carries the database; over SynapRPC and RESP3
+    /// any command sent with a database other than 0 fails with
+    /// [`SynapError::UnsupportedCommand`]. Replicas are not used, since only
+    /// database 0 is replicated.
+    pub fn with_db(mut self, db: u32) -> Self {
+        self.db = db;
+        self
+    }
+
+    /// Trust the root certificates in a PEM bundle, in addition to the
+    /// system roots.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use synap_sdk::SynapConfig;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let config = SynapConfig::new("https://synap.internal:443")
+    ///     .with_root_ca_pem(std::fs::read("/etc/synap/ca.pem")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_root_ca_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.tls.root_ca_pem = Some(pem.into());
+        self
+    }
+
+    /// Present a client certificate for mutual TLS. `key_pem` must be a
+    /// PKCS #8 private key.
+    pub fn with_client_cert_pem(
+        mut self,
+        cert_pem: impl Into<Vec<u8>>,
+        key_pem: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.tls.client_cert_pem = Some(cert_pem.into());
+        self.tls.client_key_pem = Some(key_pem.into());
+        self
+    }
+
+    /// Accept any server certificate, including self-signed and expired ones.
+    ///
+    /// Only for development against a test server: this disables all
+    /// protection against man-in-the-middle attacks.
+    pub fn with_danger_accept_invalid_certs(mut self) -> Self {
+        self.tls.danger_accept_invalid_certs = true;
+        self
+    }
 }
 
 /// Resolve the RPC handshake credentials from the client configuration.
@@ -255,85 +588,374 @@ fn rpc_credentials(config: &SynapConfig) -> Option<RpcCredentials> {
     }
 }
 
-// ── Internal transport enum ───────────────────────────────────────────────────
+// ── Transport construction ────────────────────────────────────────────────────
+
+/// What the built-in transports share with the client: its authenticating
+/// HTTP client, WebSocket TLS connector and metrics.
+struct TransportContext<'a> {
+    http: &'a HttpClient,
+    ws_connector: Option<&'a tokio_tungstenite::Connector>,
+    metrics: &'a Arc<ClientMetrics>,
+}
+
+fn rpc_transport(config: &SynapConfig, metrics: &Arc<ClientMetrics>) -> SynapRpcTransport {
+    SynapRpcTransport::new(
+        &config.rpc_host,
+        config.rpc_port,
+        config.timeout,
+        rpc_credentials(config),
+        Arc::clone(metrics),
+    )
+}
 
-enum Transport {
-    Http,
-    SynapRpc(Arc<SynapRpcTransport>),
-    Resp3(Arc<Resp3Transport>),
+/// Build the transport selected by `config`.
+fn build_transport(
+    config: &SynapConfig,
+    base_url: &Url,
+    context: &TransportContext<'_>,
+) -> Arc<dyn Transport> {
+    match config.transport {
+        TransportMode::Http => Arc::new(HttpTransport::new(
+            context.http.clone(),
+            base_url.clone(),
+            config.http_encoding,
+        )),
+        TransportMode::SynapRpc => Arc::new(rpc_transport(config, context.metrics)),
+        TransportMode::Resp3 => Arc::new(Resp3Transport::new(
+            &config.resp3_host,
+            config.resp3_port,
+            config.timeout,
+            Arc::clone(context.metrics),
+        )),
+        TransportMode::WebSocket => Arc::new(WsCommandTransport::new(
+            context.http.clone(),
+            context.ws_connector.cloned(),
+            base_url.clone(),
+            config.http_encoding,
+            config.timeout,
+            Arc::clone(context.metrics),
+        )),
+    }
+}
+
+/// Scheme cluster node addresses are connected with: the primary's.
+fn node_scheme(config: &SynapConfig, base_url: &Url) -> &'static str {
+    match config.transport {
+        TransportMode::SynapRpc => "synap",
+        TransportMode::Resp3 => "resp3",
+        TransportMode::Http | TransportMode::WebSocket if base_url.scheme() == "https" => "https",
+        TransportMode::Http | TransportMode::WebSocket => "http",
+    }
+}
+
+/// Build the endpoint for one replica or cluster node URL, reusing the
+/// primary's timeout and credentials.
+fn peer_endpoint(
+    config: &SynapConfig,
+    context: &TransportContext<'_>,
+    url: &str,
+) -> Result<Endpoint> {
+    let mut replica = SynapConfig::new(url);
+    replica.timeout = config.timeout;
+    replica.auth_token = config.auth_token.clone();
+    replica.username = config.username.clone();
+    replica.password = config.password.clone();
+    replica.http_encoding = config.http_encoding;
+
+    let base_url = Url::parse(&replica.base_url)?;
+    let transport = build_transport(&replica, &base_url, context);
+    Ok(Endpoint::new(url.to_owned(), base_url, transport))
 }
 
 // ── SynapClient ───────────────────────────────────────────────────────────────
 
 /// Main Synap client.
 ///
-/// Internally uses one of three transports — SynapRPC (default), RESP3, or
-/// HTTP — selected via [`SynapConfig::transport`].  Commands that have no
-/// native-protocol mapping automatically fall back to HTTP regardless of the
-/// chosen transport.
+/// Internally uses one of four transports — SynapRPC (default), RESP3, HTTP
+/// or a multiplexed WebSocket — selected via [`SynapConfig::transport`].
+/// Commands that have no native-protocol mapping automatically fall back to
+/// HTTP regardless of the chosen transport.
+///
+/// With [`SynapConfig::with_replicas`], read-only commands can be served by
+/// replicas according to [`SynapConfig::read_preference`]; see
+/// [`crate::replica`].
+///
+/// With [`SynapConfig::with_cluster`], keyed commands are routed by hash slot
+/// instead; see [`crate::cluster`].
 #[derive(Clone)]
 pub struct SynapClient {
-    #[allow(dead_code)]
     config: Arc<SynapConfig>,
-    http_client: Client,
-    base_url: Url,
-    transport: Arc<Transport>,
+    http_client: HttpClient,
+    primary: Arc<Endpoint>,
+    /// The primary's transport when it is SynapRPC, for push subscriptions
+    rpc: Option<Arc<SynapRpcTransport>>,
+    replicas: Option<Arc<ReplicaSet>>,
+    cluster: Option<Arc<ClusterRouter>>,
+    ws_connector: Option<tokio_tungstenite::Connector>,
+    cache: Option<Arc<ClientCache>>,
+    flights: Arc<SingleFlight>,
+    metrics: Arc<ClientMetrics>,
 }
 
 impl SynapClient {
     /// Create a new Synap client using the provided configuration.
     pub fn new(config: SynapConfig) -> Result<Self> {
+        Self::build(config, None)
+    }
+
+    /// Create a client whose commands to the primary all go through
+    /// `transport` instead of the one [`SynapConfig::transport`] selects.
+    ///
+    /// Everything above the transport works as usual: key prefixes, the
+    /// client-side cache, metrics and error handling. Replicas and cluster
+    /// nodes keep the transport their URL selects, and push subscriptions
+    /// and REST-only calls (snapshots, topology) still open their own
+    /// connection to [`SynapConfig::base_url`].
+    ///
+    /// # Example
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use serde_json::{Value, json};
+    /// use synap_sdk::{SynapClient, SynapConfig};
+    /// # use synap_sdk::transport::Transport;
+    /// # struct Fixed;
+    /// # #[async_trait]
+    /// # impl Transport for Fixed {
+    /// #     async fn send(&self, _: synap_sdk::protocol::Request) -> synap_sdk::Result<Value> {
+    /// #         Ok(json!("alice"))
+    /// #     }
+    /// # }
+    /// # #[tokio::main]
+    /// # async fn main() -> synap_sdk::Result<()> {
+    /// let client = SynapClient::with_transport(
+    ///     SynapConfig::new("http://localhost:15500"),
+    ///     Arc::new(Fixed),
+    /// )?;
+    /// let name: Option<String> = client.kv().get("user:1").await?;
+    /// assert_eq!(name.as_deref(), Some("alice"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_transport(config: SynapConfig, transport: Arc<dyn Transport>) -> Result<Self> {
+        Self::build(config, Some(transport))
+    }
+
+    /// A client on a fresh in-process server, for integration tests that
+    /// should not need Docker or a `synap-server` binary.
+    ///
+    /// Commands run straight through the server's command handlers, with
+    /// the same names, payloads, results and error codes as over the
+    /// network. The HTTP API is also served on a random local port, which
+    /// [`SynapConfig::base_url`] points at, for the REST-only calls and
+    /// WebSocket subscriptions. Every server subsystem starts with its
+    /// defaults, persistence off; the server stops once the client and all
+    /// its clones are dropped.
+    ///
+    /// Requires the `test-util` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> synap_sdk::Result<()> {
+    /// use synap_sdk::SynapClient;
+    ///
+    /// let client = SynapClient::embedded().await?;
+    /// client.kv().set("user:1", "alice", None).await?;
+    /// client.queue().create_queue("jobs", None, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub async fn embedded() -> Result<Self> {
+        let mut server = synap_server::ServerConfig::default();
+        server.persistence.enabled = false;
+        Self::embedded_with(server, |config| config).await
+    }
+
+    /// [`SynapClient::embedded`] with a custom server configuration, and the
+    /// client configuration adjusted by `configure` (key prefix, client
+    /// cache, …) after its `base_url` is set.
+    ///
+    /// Requires the `test-util` feature.
+    #[cfg(feature = "test-util")]
+    pub async fn embedded_with(
+        server: synap_server::ServerConfig,
+        configure: impl FnOnce(SynapConfig) -> SynapConfig,
+    ) -> Result<Self> {
+        use std::net::{Ipv4Addr, SocketAddr};
+
+        let synap = synap_server::Embedded::start(server)
+            .await
+            .map_err(|e| SynapError::Other(format!("embedded server failed to start: {e:#}")))?;
+        let listener = synap
+            .listen(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+            .await
+            .map_err(|e| SynapError::Transport(e.to_string()))?;
+        let config = configure(SynapConfig::new(format!(
+            "http://{}",
+            listener.local_addr()
+        )));
+        let transport = crate::transport::embedded::EmbeddedTransport::new(synap, listener);
+        Self::with_transport(config, Arc::new(transport))
+    }
+
+    fn build(config: SynapConfig, transport: Option<Arc<dyn Transport>>) -> Result<Self> {
         let base_url = Url::parse(&config.base_url)?;
+        let metrics = Arc::new(ClientMetrics::new(config.metrics));
 
         // Build reqwest HTTP client (needed for fallback and Http transport).
-        let mut builder = Client::builder().timeout(config.timeout);
-
-        if let Some(ref token) = config.auth_token {
-            let mut headers = reqwest::header::HeaderMap::new();
-            headers.insert(
-                reqwest::header::AUTHORIZATION,
-                format!("Bearer {}", token).parse().unwrap(),
-            );
-            builder = builder.default_headers(headers);
-        } else if let (Some(username), Some(password)) = (&config.username, &config.password) {
-            use base64::Engine;
-            let encoded = base64::engine::general_purpose::STANDARD
-                .encode(format!("{}:{}", username, password));
-            let mut headers = reqwest::header::HeaderMap::new();
-            headers.insert(
-                reqwest::header::AUTHORIZATION,
-                format!("Basic {}", encoded).parse().unwrap(),
-            );
-            builder = builder.default_headers(headers);
-        }
+        // Credentials are attached per request so a refreshed token applies
+        // to every clone of the client.
+        let builder = Client::builder().timeout(config.timeout);
+        let http_client = HttpClient::new(
+            config.tls.configure_http(builder)?.build()?,
+            Arc::new(Credentials::from_config(&config)?),
+            Arc::clone(&metrics),
+        );
+        let ws_connector = config.tls.websocket_connector()?;
+        let context = TransportContext {
+            http: &http_client,
+            ws_connector: ws_connector.as_ref(),
+            metrics: &metrics,
+        };
 
-        let http_client = builder.build()?;
-
-        let transport = match config.transport {
-            TransportMode::Http => Arc::new(Transport::Http),
-            TransportMode::SynapRpc => {
-                Arc::new(Transport::SynapRpc(Arc::new(SynapRpcTransport::new(
-                    &config.rpc_host,
-                    config.rpc_port,
-                    config.timeout,
-                    rpc_credentials(&config),
-                ))))
+        // Push subscriptions need the concrete SynapRPC transport
+        let rpc = match (&transport, &config.transport) {
+            (None, TransportMode::SynapRpc) => Some(Arc::new(rpc_transport(&config, &metrics))),
+            _ => None,
+        };
+        let transport = match (transport, &rpc) {
+            (Some(transport), _) => transport,
+            (None, Some(rpc)) => Arc::clone(rpc) as Arc<dyn Transport>,
+            (None, None) => build_transport(&config, &base_url, &context),
+        };
+
+        let cluster = config
+            .cluster
+            .then(|| Arc::new(ClusterRouter::new(node_scheme(&config, &base_url))));
+
+        let primary = Arc::new(Endpoint::new(config.base_url.clone(), base_url, transport));
+
+        let replicas = if config.replica_urls.is_empty() {
+            None
+        } else {
+            let endpoints = config
+                .replica_urls
+                .iter()
+                .map(|url| peer_endpoint(&config, &context, url))
+                .collect::<Result<Vec<_>>>()?;
+            let set = Arc::new(ReplicaSet::new(endpoints));
+            if config.read_preference != ReadPreference::Primary {
+                spawn_health_checks(
+                    Arc::downgrade(&set),
+                    Arc::downgrade(&primary),
+                    config.replica_health_interval,
+                );
             }
-            TransportMode::Resp3 => Arc::new(Transport::Resp3(Arc::new(Resp3Transport::new(
-                &config.resp3_host,
-                config.resp3_port,
-                config.timeout,
-            )))),
+            Some(set)
         };
 
+        let cache = config
+            .client_cache
+            .clone()
+            .map(|cache| Arc::new(ClientCache::new(cache)));
+
         Ok(Self {
             config: Arc::new(config),
             http_client,
-            base_url,
-            transport,
+            primary,
+            rpc,
+            replicas,
+            cluster,
+            ws_connector,
+            cache,
+            flights: Arc::default(),
+            metrics,
         })
     }
 
+    /// What [`peer_endpoint`] builds cluster node transports with
+    fn transport_context(&self) -> TransportContext<'_> {
+        TransportContext {
+            http: &self.http_client,
+            ws_connector: self.ws_connector.as_ref(),
+            metrics: &self.metrics,
+        }
+    }
+
+    /// A client for logical database `db`, sharing this one's connections.
+    /// See [`SynapConfig::with_db`]. The client-side cache is not used on
+    /// other databases.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let sessions = client.select(1);
+    /// sessions.kv().set("session:42", "alice", None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn select(&self, db: u32) -> Self {
+        let mut config = (*self.config).clone();
+        let cache = if db == config.db {
+            self.cache.clone()
+        } else {
+            None
+        };
+        config.db = db;
+        Self {
+            config: Arc::new(config),
+            cache,
+            ..self.clone()
+        }
+    }
+
+    /// The logical database this client's commands run in.
+    pub fn db(&self) -> u32 {
+        self.config.db
+    }
+
+    /// Client-side metrics recorded so far, or `None` unless enabled with
+    /// [`SynapConfig::with_metrics`]. See [`crate::metrics`].
+    pub fn metrics(&self) -> Option<MetricsSnapshot> {
+        self.metrics.is_enabled().then(|| self.metrics.snapshot())
+    }
+
+    /// Add this client's metrics to `registry` as `synap_client_*` series.
+    /// Does nothing unless metrics are enabled with
+    /// [`SynapConfig::with_metrics`]; fails if the registry already holds
+    /// the series, e.g. from another client.
+    #[cfg(feature = "prometheus")]
+    pub fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()> {
+        self.metrics
+            .register(registry)
+            .map_err(|e| SynapError::Other(format!("registering client metrics: {e}")))
+    }
+
+    /// The configured key prefix, empty for none.
+    pub(crate) fn key_prefix(&self) -> &str {
+        self.config.key_prefix.as_deref().unwrap_or_default()
+    }
+
+    /// The configuration this client was built from.
+    pub(crate) fn config(&self) -> &SynapConfig {
+        &self.config
+    }
+
+    /// The client-side cache, when enabled.
+    pub(crate) fn client_cache(&self) -> Option<&Arc<ClientCache>> {
+        self.cache.as_ref()
+    }
+
+    /// Key locks for [`CacheLayer`](crate::CacheLayer), shared by every clone.
+    pub(crate) fn flights(&self) -> &SingleFlight {
+        &self.flights
+    }
+
     // ── Manager accessors ─────────────────────────────────────────────────────
 
     /// Get the Key-Value store interface.
@@ -401,8 +1023,56 @@ impl SynapClient {
         GeospatialManager::new(self.clone())
     }
 
+    /// Get the distributed lock interface.
+    pub fn lock(&self) -> LockManager {
+        LockManager::new(self.clone())
+    }
+
+    /// Get the rate limiter interface.
+    pub fn rate_limiter(&self) -> RateLimiterManager {
+        RateLimiterManager::new(self.clone())
+    }
+
+    /// Get the time series interface.
+    pub fn time_series(&self) -> TimeSeriesManager {
+        TimeSeriesManager::new(self.clone())
+    }
+
+    /// Get the Bloom filter interface.
+    pub fn bloom(&self) -> BloomFilterManager {
+        BloomFilterManager::new(self.clone())
+    }
+
+    /// Get the cron scheduler interface.
+    pub fn scheduler(&self) -> SchedulerManager {
+        SchedulerManager::new(self.clone())
+    }
+
     // ── Command dispatch ──────────────────────────────────────────────────────
 
+    /// Send a typed command from [`protocol`](crate::protocol) and decode its
+    /// response.
+    ///
+    /// The payload and response types are the ones the server parses and
+    /// produces, so a mismatch is a compile error rather than a runtime one.
+    ///
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// use synap_sdk::protocol::kv::KvStrlen;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let len = client.execute(&KvStrlen { key: "greeting".into() }).await?;
+    /// println!("{} bytes", len.length);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute<C: Command>(&self, command: &C) -> Result<C::Response> {
+        let payload = serde_json::to_value(command)?;
+        let response = self.send_command(C::NAME, payload).await?;
+        Ok(serde_json::from_value(response)?)
+    }
+
     /// Dispatch a command to the active transport.
     ///
     /// For SynapRPC and RESP3 transports every command must have a native
@@ -410,67 +1080,139 @@ impl SynapClient {
     /// [`SynapError::UnsupportedCommand`] — there is no silent HTTP fallback.
     /// Use an `http://` URL if you need HTTP REST for a command that is not
     /// yet in the mapper.
+    ///
+    /// Read-only commands go to a replica when the read preference selects
+    /// one. If that replica cannot be reached it is taken out of rotation and
+    /// the command is retried on the primary.
+    ///
+    /// In cluster mode, commands with a key go to the node owning its slot.
+    ///
+    /// With a [key prefix](SynapConfig::with_key_prefix), the names in
+    /// `payload` are prefixed before it is sent.
+    ///
+    /// With the `otel` feature the command runs in a `synap.command` span;
+    /// see [`crate::telemetry`].
     pub async fn send_command(&self, command: &str, payload: Value) -> Result<Value> {
-        match self.transport.as_ref() {
-            Transport::Http => self.send_http(command, payload).await,
+        let started = std::time::Instant::now();
+        let result = self
+            .send_scoped(command, payload)
+            .instrument(telemetry::command_span(command, self.config.db))
+            .await;
+        self.metrics
+            .record(command, result.is_ok(), started.elapsed());
+        result
+    }
+
+    /// [`send_command`](Self::send_command) within the key prefix
+    async fn send_scoped(&self, command: &str, mut payload: Value) -> Result<Value> {
+        let prefix = self.key_prefix();
+        if prefix.is_empty() {
+            return self.route(command, payload).await;
+        }
+        key_prefix::scope_request(prefix, command, &mut payload);
+        let mut response = self.route(command, payload).await?;
+        key_prefix::unscope_response(prefix, command, &mut response);
+        Ok(response)
+    }
+
+    /// Send a command to the cluster node, replica or primary it belongs on.
+    async fn route(&self, command: &str, payload: Value) -> Result<Value> {
+        if let Some(cluster) = &self.cluster
+            && let Some(key) = command_key(&payload)
+        {
+            let slot = hash_slot(key);
+            return self.send_clustered(cluster, slot, command, payload).await;
+        }
 
-            Transport::SynapRpc(rpc) => match map_command(command, &payload) {
-                Some((raw_cmd, args)) => {
-                    let wire = rpc.execute(raw_cmd, args).await?;
-                    Ok(map_response(command, wire))
+        if let Some(replicas) = &self.replicas
+            && self.config.db == 0
+            && is_read_only(command)
+            && let Some(replica) = replicas.select(self.config.read_preference)
+        {
+            match self.dispatch(replica, command, payload.clone()).await {
+                Err(e) if is_endpoint_failure(&e) => {
+                    replica.mark_down(&e);
+                    self.metrics.retry(Retry::ReplicaFallback);
                 }
-                None => Err(SynapError::UnsupportedCommand {
-                    command: command.to_owned(),
-                    transport: "SynapRpc".to_owned(),
-                }),
-            },
-
-            Transport::Resp3(resp3) => match map_command(command, &payload) {
-                Some((raw_cmd, args)) => {
-                    let wire = resp3.execute(raw_cmd, args).await?;
-                    Ok(map_response(command, wire))
-                }
-                None => Err(SynapError::UnsupportedCommand {
-                    command: command.to_owned(),
-                    transport: "Resp3".to_owned(),
-                }),
-            },
+                result => return result,
+            }
         }
-    }
 
-    /// Send a command via HTTP REST (original `api/v1/command` endpoint).
-    async fn send_http(&self, command: &str, payload: Value) -> Result<Value> {
-        let request_id = uuid::Uuid::new_v4().to_string();
-
-        let body = serde_json::json!({
-            "command": command,
-            "request_id": request_id,
-            "payload": payload,
-        });
+        self.dispatch(&self.primary, command, payload).await
+    }
 
-        let url = self
-            .base_url
-            .join("api/v1/command")
-            .map_err(SynapError::InvalidUrl)?;
+    /// Send a command to the owner of `slot`, following redirects.
+    async fn send_clustered(
+        &self,
+        cluster: &ClusterRouter,
+        slot: u16,
+        command: &str,
+        payload: Value,
+    ) -> Result<Value> {
+        if !cluster.is_loaded() {
+            self.refresh_cluster().await?;
+        }
 
-        let response = self.http_client.post(url).json(&body).send().await?;
+        let mut target = self.slot_endpoint(cluster, slot)?;
+        let mut refreshed = false;
+        for _ in 0..MAX_REDIRECTS {
+            let error = match self.dispatch(&target, command, payload.clone()).await {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(SynapError::ServerError(error_text));
+            if let Some(redirect) = parse_redirect(&error) {
+                tracing::debug!(command, ?redirect, "cluster redirect");
+                if !redirect.ask {
+                    cluster.set_owner(redirect.slot, &redirect.address);
+                }
+                target = cluster.endpoint(&redirect.address, |url| {
+                    peer_endpoint(&self.config, &self.transport_context(), url)
+                })?;
+                self.metrics.retry(Retry::Cluster);
+            } else if is_endpoint_failure(&error) && !refreshed {
+                // The node may be gone: reload the map and retry if the slot
+                // has a new owner.
+                refreshed = true;
+                if self.refresh_cluster().await.is_err() {
+                    return Err(error);
+                }
+                let owner = self.slot_endpoint(cluster, slot)?;
+                if Arc::ptr_eq(&owner, &target) {
+                    return Err(error);
+                }
+                target = owner;
+                self.metrics.retry(Retry::Cluster);
+            } else {
+                return Err(error);
+            }
         }
 
-        let result: Value = response.json().await?;
+        Err(SynapError::Other(format!(
+            "{command}: too many cluster redirects for slot {slot}"
+        )))
+    }
 
-        if !result["success"].as_bool().unwrap_or(false) {
-            let error_msg = result["error"]
-                .as_str()
-                .unwrap_or("Unknown error")
-                .to_string();
-            return Err(SynapError::ServerError(error_msg));
+    /// The endpoint of the node owning `slot`, or the seed when the slot is
+    /// unassigned (the seed then answers with the cluster error).
+    fn slot_endpoint(&self, cluster: &ClusterRouter, slot: u16) -> Result<Arc<Endpoint>> {
+        match cluster.owner(slot) {
+            Some(address) => cluster.endpoint(&address, |url| {
+                peer_endpoint(&self.config, &self.transport_context(), url)
+            }),
+            None => Ok(Arc::clone(&self.primary)),
         }
+    }
 
-        Ok(result["payload"].clone())
+    /// Send a command to one endpoint over its transport.
+    async fn dispatch(&self, endpoint: &Endpoint, command: &str, payload: Value) -> Result<Value> {
+        let request = Request {
+            command: command.to_owned(),
+            request_id: uuid::Uuid::new_v4().to_string(),
+            payload,
+            db: self.config.db,
+        };
+        endpoint.transport.send(request).await
     }
 
     // ── Accessors ─────────────────────────────────────────────────────────────
@@ -478,22 +1220,143 @@ impl SynapClient {
     /// Get the configured base URL.
     #[allow(dead_code)]
     pub fn base_url(&self) -> &Url {
-        &self.base_url
+        &self.primary.base_url
+    }
+
+    /// Health of each configured replica, in configuration order.
+    pub fn replica_status(&self) -> Vec<ReplicaStatus> {
+        self.replicas
+            .as_ref()
+            .map(|set| set.status())
+            .unwrap_or_default()
+    }
+
+    /// Probe every replica now instead of waiting for the next scheduled
+    /// check. Replicas that answer are put back in rotation.
+    pub async fn check_replicas(&self) {
+        if let Some(set) = &self.replicas {
+            set.check(Some(&self.primary)).await;
+        }
+    }
+
+    /// Reload the cluster slot map now, from the seed node or, failing that,
+    /// any known node. Does nothing outside cluster mode.
+    pub async fn refresh_cluster(&self) -> Result<()> {
+        match &self.cluster {
+            Some(cluster) => {
+                cluster
+                    .refresh(
+                        &self.http_client,
+                        std::slice::from_ref(&self.primary.base_url),
+                    )
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Nodes of the cluster with the number of slots each owns, loading the
+    /// slot map if needed. Empty outside cluster mode.
+    pub async fn cluster_nodes(&self) -> Result<Vec<ClusterNode>> {
+        let Some(cluster) = &self.cluster else {
+            return Ok(Vec::new());
+        };
+        if !cluster.is_loaded() {
+            self.refresh_cluster().await?;
+        }
+        Ok(cluster.nodes())
+    }
+
+    /// Current replication master and failover epoch, as seen by the server.
+    ///
+    /// Poll it to find the new master after an automatic failover; a higher
+    /// `epoch` means a promotion happened. Requires automatic failover on the
+    /// server. HTTP transport only.
+    pub async fn replication_topology(&self) -> Result<ReplicationTopology> {
+        self.execute(&crate::protocol::replication::ReplicationTopology {})
+            .await
+    }
+
+    /// Prime the manifest's keys on the server ahead of traffic. Completing a
+    /// warmup also marks a server held by `warmup.wait_for_manifest` ready.
+    /// HTTP transport only.
+    pub async fn warmup(&self, manifest: &WarmupManifest) -> Result<WarmupReport> {
+        self.execute(manifest).await
+    }
+
+    /// Whether the server is ready for traffic (`GET /health/ready`), i.e. no
+    /// cold start warmup is pending. Always asked over HTTP.
+    pub async fn is_ready(&self) -> Result<bool> {
+        let url = self.primary.base_url.join("health/ready")?;
+        let response = self.http_client.send(|c| c.get(url.clone())).await?;
+        Ok(response.status().is_success())
+    }
+
+    /// Every store on the server as a portable dump (`GET /snapshot/export`):
+    /// KV keys with their TTLs, hashes, lists, sets, sorted sets, queue
+    /// messages and stream events. Needs `admin:*` read access. Always over
+    /// HTTP.
+    pub async fn export_snapshot(&self) -> Result<Vec<u8>> {
+        let url = self.primary.base_url.join("snapshot/export")?;
+        let response = self.http_client.send(|c| c.get(url.clone())).await?;
+        let response = Self::error_for_status(response).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Replay a dump from [`export_snapshot`](Self::export_snapshot) into
+    /// the server (`POST /snapshot/import`). Existing keys are overwritten,
+    /// and keys whose TTL ran out since the export are skipped. Needs
+    /// `admin:*` admin access. Always over HTTP.
+    pub async fn import_snapshot(&self, dump: Vec<u8>) -> Result<SnapshotImport> {
+        let url = self.primary.base_url.join("snapshot/import")?;
+        let response = self
+            .http_client
+            .send(|c| {
+                c.post(url.clone())
+                    .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                    .body(dump.clone())
+            })
+            .await?;
+        let response = Self::error_for_status(response).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Turn a non-2xx REST response into an [`ApiError`]
+    async fn error_for_status(response: reqwest::Response) -> Result<reqwest::Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let error_text = response.text().await.unwrap_or_default();
+        let error = match serde_json::from_str::<Value>(&error_text) {
+            Ok(body) => api_error_from_body(&body, error_text),
+            Err(_) => ApiError::new(error_text),
+        };
+        Err(error.with_status(status.as_u16()).into())
     }
 
-    /// Get the underlying reqwest HTTP client.
+    /// Get the underlying authenticating HTTP client.
     #[allow(dead_code)]
-    pub(crate) fn http_client(&self) -> &Client {
+    pub(crate) fn http_client(&self) -> &HttpClient {
         &self.http_client
     }
 
+    /// Open a WebSocket to `url` with the configured TLS options and
+    /// credentials, refreshing them once if the upgrade is refused with `401`.
+    pub(crate) async fn connect_websocket(&self, url: &str) -> Result<CommandSocket> {
+        crate::transport::websocket::connect_websocket(
+            &self.http_client,
+            self.ws_connector.clone(),
+            url,
+        )
+        .await
+    }
+
     /// Return a reference to the `SynapRpcTransport` when the active transport
-    /// is `SynapRpc`, or `None` for HTTP / RESP3.
+    /// is `SynapRpc`, or `None` for every other transport (including one
+    /// passed to [`SynapClient::with_transport`]).
     pub(crate) fn synap_rpc_transport(&self) -> Option<Arc<SynapRpcTransport>> {
-        match self.transport.as_ref() {
-            Transport::SynapRpc(rpc) => Some(Arc::clone(rpc)),
-            _ => None,
-        }
+        self.rpc.clone()
     }
 }
 
@@ -740,4 +1603,37 @@ mod tests {
         assert!(debug_str.contains("SynapConfig"));
         assert!(debug_str.contains("http://localhost:15500"));
     }
+
+    #[tokio::test]
+    async fn test_with_transport_sends_through_the_custom_transport() {
+        let mock = Arc::new(
+            crate::transport::mock::MockTransport::default()
+                .respond("kv.set", serde_json::json!({"success": true}))
+                .respond("kv.get", serde_json::json!("alice")),
+        );
+        let config = http_config().with_key_prefix("app:").with_db(2);
+        let client = SynapClient::with_transport(config, mock.clone()).unwrap();
+
+        client.kv().set("user:1", "alice", None).await.unwrap();
+        let name: Option<String> = client.kv().get("user:1").await.unwrap();
+        assert_eq!(name.as_deref(), Some("alice"));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].command, "kv.set");
+        assert_eq!(requests[0].payload["key"], "app:user:1");
+        assert_eq!(requests[1].command, "kv.get");
+        assert!(requests.iter().all(|r| r.db == 2));
+        assert_ne!(requests[0].request_id, requests[1].request_id);
+    }
+
+    #[tokio::test]
+    async fn test_with_transport_surfaces_transport_errors() {
+        let mock = Arc::new(crate::transport::mock::MockTransport::default());
+        let client = SynapClient::with_transport(http_config(), mock).unwrap();
+
+        let error = client.kv().get::<_, String>("missing").await.unwrap_err();
+        assert!(error.to_string().contains("no mock response for kv.get"));
+        assert!(client.synap_rpc_transport().is_none());
+    }
 }
@@ -0,0 +1,564 @@
+//! Client-side caching for [`KVStore::get`](crate::KVStore::get).
+//!
+//! With [`SynapConfig::with_client_cache`](crate::SynapConfig::with_client_cache)
+//! set, reads populate a local LRU and the server pushes an invalidation when a
+//! cached key changes, the way Redis client-side caching works. Invalidations
+//! arrive on a WebSocket to `/kv/tracking/ws` on the server's HTTP port, opened
+//! on the first cache miss, whatever the command transport.
+//!
+//! Before each miss the key is registered on that socket, and the read only
+//! populates the cache when no invalidation for the key arrived in between, so
+//! a write racing the read cannot leave a stale entry behind. Every entry also
+//! expires after [`ClientCacheConfig::ttl`] as a fallback. While the socket is
+//! down nothing is cached, and the cache is flushed when it drops, since
+//! invalidations may have been missed. Writes through this client evict their
+//! key locally right away, so a client always reads its own writes.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use synap_sdk::{ClientCacheConfig, SynapClient, SynapConfig};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SynapClient::new(
+//!     SynapConfig::new("http://localhost:15500").with_client_cache(ClientCacheConfig {
+//!         max_entries: 50_000,
+//!         ttl: Duration::from_secs(300),
+//!     }),
+//! )?;
+//!
+//! let name: Option<String> = client.kv().get("user:1").await?; // server
+//! let name: Option<String> = client.kv().get("user:1").await?; // local
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use crate::client::SynapClient;
+use crate::error::{Result, SynapError};
+
+/// How long after a failed connection attempt misses skip the cache before
+/// the tracking socket is tried again.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Client-side cache settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientCacheConfig {
+    /// Most entries kept; the least recently used one is dropped past it.
+    pub max_entries: usize,
+    /// How long an entry is served without hearing from the server, in case
+    /// an invalidation is lost.
+    pub ttl: Duration,
+}
+
+impl Default for ClientCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Client-side cache counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClientCacheStats {
+    /// Entries currently cached.
+    pub entries: usize,
+    /// Reads served locally.
+    pub hits: u64,
+    /// Reads sent to the server.
+    pub misses: u64,
+    /// Invalidations received from the server.
+    pub invalidations: u64,
+}
+
+struct Entry {
+    /// The raw `kv.get` response; `None` caches a missing key.
+    value: Option<Value>,
+    expires_at: Instant,
+    /// Position in `CacheState::recency`.
+    tick: u64,
+}
+
+/// Reads in flight for one key.
+#[derive(Default)]
+struct Inflight {
+    readers: usize,
+    /// An invalidation arrived since the first of them started.
+    dirty: bool,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+    /// Use order, oldest first: tick → key.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    inflight: HashMap<String, Inflight>,
+    stats: ClientCacheStats,
+}
+
+impl CacheState {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.tick);
+        }
+        if let Some(inflight) = self.inflight.get_mut(key) {
+            inflight.dirty = true;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        for inflight in self.inflight.values_mut() {
+            inflight.dirty = true;
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+/// A read in flight, see [`ClientCache::begin_read`].
+struct PendingRead<'a> {
+    cache: &'a ClientCache,
+    key: &'a str,
+    done: bool,
+}
+
+impl PendingRead<'_> {
+    fn finish(mut self, tracked: bool, value: Option<&Option<Value>>) {
+        self.done = true;
+        self.cache.end_read(self.key, tracked, value);
+    }
+}
+
+impl Drop for PendingRead<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.cache.end_read(self.key, false, None);
+        }
+    }
+}
+
+/// A registration request for the tracking socket task.
+type TrackRequest = (Vec<String>, oneshot::Sender<()>);
+
+/// The local cache shared by every clone of a [`SynapClient`].
+pub(crate) struct ClientCache {
+    config: ClientCacheConfig,
+    state: Mutex<CacheState>,
+    /// Sender to the tracking socket task; closed once the socket drops.
+    link: tokio::sync::Mutex<Link>,
+}
+
+#[derive(Default)]
+struct Link {
+    tx: Option<mpsc::UnboundedSender<TrackRequest>>,
+    retry_at: Option<Instant>,
+}
+
+impl ClientCache {
+    pub(crate) fn new(config: ClientCacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CacheState::default()),
+            link: tokio::sync::Mutex::new(Link::default()),
+        }
+    }
+
+    /// Serve `key` locally, or fetch it and cache the result when the server
+    /// tracks it for us.
+    pub(crate) async fn get_or_fetch<F, Fut>(
+        self: &Arc<Self>,
+        client: &SynapClient,
+        key: &str,
+        fetch: F,
+    ) -> Result<Option<Value>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<Option<Value>>>,
+    {
+        if let Some(value) = self.lookup(key) {
+            return Ok(value);
+        }
+
+        let read = self.begin_read(key);
+        let tracked = self.track(client, key).await;
+        let result = fetch().await;
+        read.finish(tracked, result.as_ref().ok());
+        result
+    }
+
+    /// Drop `key` from the cache.
+    pub(crate) fn invalidate(&self, key: &str) {
+        self.state.lock().unwrap().remove(key);
+    }
+
+    /// Drop every entry.
+    pub(crate) fn clear(&self) {
+        self.state.lock().unwrap().clear();
+    }
+
+    pub(crate) fn stats(&self) -> ClientCacheStats {
+        let state = self.state.lock().unwrap();
+        ClientCacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    fn lookup(&self, key: &str) -> Option<Option<Value>> {
+        let mut state = self.state.lock().unwrap();
+        let hit = match state.entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.tick),
+            Some(_) => {
+                state.remove(key);
+                None
+            }
+            None => None,
+        };
+        let Some(old_tick) = hit else {
+            state.stats.misses += 1;
+            return None;
+        };
+
+        state.stats.hits += 1;
+        let tick = state.next_tick();
+        state.recency.remove(&old_tick);
+        state.recency.insert(tick, key.to_owned());
+        let entry = state.entries.get_mut(key)?;
+        entry.tick = tick;
+        Some(entry.value.clone())
+    }
+
+    /// Start a read of `key` from the server. Dropping the guard without
+    /// [`PendingRead::finish`] (e.g. on cancellation) caches nothing.
+    fn begin_read<'a>(&'a self, key: &'a str) -> PendingRead<'a> {
+        let mut state = self.state.lock().unwrap();
+        state.inflight.entry(key.to_owned()).or_default().readers += 1;
+        PendingRead {
+            cache: self,
+            key,
+            done: false,
+        }
+    }
+
+    /// End a read started by [`Self::begin_read`], caching `value` when the
+    /// key was tracked and not invalidated meanwhile.
+    fn end_read(&self, key: &str, tracked: bool, value: Option<&Option<Value>>) {
+        let mut state = self.state.lock().unwrap();
+        let Some(inflight) = state.inflight.get_mut(key) else {
+            return;
+        };
+        let dirty = inflight.dirty;
+        inflight.readers -= 1;
+        if inflight.readers == 0 {
+            state.inflight.remove(key);
+        }
+
+        let (true, false, Some(value)) = (tracked, dirty, value) else {
+            return;
+        };
+        if let Some(old) = state.entries.remove(key) {
+            state.recency.remove(&old.tick);
+        }
+        while state.entries.len() >= self.config.max_entries.max(1) {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+        let tick = state.next_tick();
+        state.recency.insert(tick, key.to_owned());
+        state.entries.insert(
+            key.to_owned(),
+            Entry {
+                value: value.clone(),
+                expires_at: Instant::now() + self.config.ttl,
+                tick,
+            },
+        );
+    }
+
+    fn apply_invalidation(&self, keys: Option<Vec<String>>) {
+        let mut state = self.state.lock().unwrap();
+        state.stats.invalidations += 1;
+        match keys {
+            Some(keys) => keys.iter().for_each(|key| state.remove(key)),
+            None => state.clear(),
+        }
+    }
+
+    /// Register `key` with the server, connecting the tracking socket first
+    /// if needed. `false` means the result must not be cached.
+    async fn track(self: &Arc<Self>, client: &SynapClient, key: &str) -> bool {
+        let Some(tx) = self.link(client).await else {
+            return false;
+        };
+        let (ack_tx, ack_rx) = oneshot::channel();
+        if tx.send((vec![key.to_owned()], ack_tx)).is_err() {
+            return false;
+        }
+        matches!(
+            tokio::time::timeout(client.config().timeout, ack_rx).await,
+            Ok(Ok(()))
+        )
+    }
+
+    /// The live tracking socket, (re)connecting it when it is down.
+    async fn link(
+        self: &Arc<Self>,
+        client: &SynapClient,
+    ) -> Option<mpsc::UnboundedSender<TrackRequest>> {
+        let mut link = self.link.lock().await;
+        if let Some(tx) = link.tx.as_ref().filter(|tx| !tx.is_closed()) {
+            return Some(tx.clone());
+        }
+        link.tx = None;
+        if link.retry_at.is_some_and(|at| Instant::now() < at) {
+            return None;
+        }
+
+        match connect(client).await {
+            Ok(socket) => {
+                let (tx, rx) = mpsc::unbounded_channel();
+                tokio::spawn(run_link(Arc::downgrade(self), socket, rx));
+                link.tx = Some(tx.clone());
+                link.retry_at = None;
+                Some(tx)
+            }
+            Err(e) => {
+                tracing::warn!("Client cache tracking socket unavailable: {}", e);
+                link.retry_at = Some(Instant::now() + RECONNECT_INTERVAL);
+                None
+            }
+        }
+    }
+}
+
+type TrackingSocket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Open the tracking socket and wait for its welcome frame.
+async fn connect(client: &SynapClient) -> Result<TrackingSocket> {
+    let base_url = client.base_url();
+    let scheme = match base_url.scheme() {
+        "https" => "wss",
+        _ => "ws",
+    };
+    let url = format!("{}://{}/kv/tracking/ws", scheme, base_url.authority());
+    let handshake = async {
+        let mut socket = client.connect_websocket(&url).await?;
+        while let Some(frame) = socket.next().await {
+            let frame = frame.map_err(|e| SynapError::Transport(e.to_string()))?;
+            if let WsMessage::Text(text) = frame
+                && decode_frame(&text) == Some(Frame::Connected)
+            {
+                return Ok(socket);
+            }
+        }
+        Err(SynapError::Transport(
+            "tracking socket closed before its welcome frame".to_string(),
+        ))
+    };
+    tokio::time::timeout(client.config().timeout, handshake)
+        .await
+        .map_err(|_| SynapError::Timeout)?
+}
+
+/// Drive one tracking socket until it drops or the cache goes away, then
+/// flush the cache: invalidations may have been missed.
+async fn run_link(
+    cache: Weak<ClientCache>,
+    socket: TrackingSocket,
+    mut requests: mpsc::UnboundedReceiver<TrackRequest>,
+) {
+    let (mut write, mut read) = socket.split();
+    let mut pending: HashMap<u64, oneshot::Sender<()>> = HashMap::new();
+    let mut next_id = 0u64;
+
+    loop {
+        tokio::select! {
+            request = requests.recv() => {
+                // `None`: the cache was dropped
+                let Some((keys, ack)) = request else { break };
+                next_id += 1;
+                let frame = json!({"type": "track", "id": next_id, "keys": keys});
+                if write.send(WsMessage::Text(frame.to_string().into())).await.is_err() {
+                    break;
+                }
+                pending.insert(next_id, ack);
+            }
+            frame = read.next() => {
+                let text = match frame {
+                    Some(Ok(WsMessage::Text(text))) => text,
+                    Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                match decode_frame(&text) {
+                    Some(Frame::Tracked(id)) => {
+                        if let Some(ack) = pending.remove(&id) {
+                            let _ = ack.send(());
+                        }
+                    }
+                    Some(Frame::Invalidate(keys)) => {
+                        let Some(cache) = cache.upgrade() else { break };
+                        cache.apply_invalidation(keys);
+                    }
+                    Some(Frame::Connected) | None => {}
+                }
+            }
+        }
+    }
+
+    tracing::debug!("Client cache tracking socket closed");
+    if let Some(cache) = cache.upgrade() {
+        cache.clear();
+    }
+}
+
+/// One server frame on the tracking socket.
+#[derive(Debug, PartialEq)]
+enum Frame {
+    Connected,
+    Tracked(u64),
+    /// `None` invalidates every key.
+    Invalidate(Option<Vec<String>>),
+}
+
+fn decode_frame(text: &str) -> Option<Frame> {
+    let json: Value = serde_json::from_str(text).ok()?;
+    match json.get("type")?.as_str()? {
+        "connected" => Some(Frame::Connected),
+        "tracked" => Some(Frame::Tracked(json.get("id")?.as_u64()?)),
+        "invalidate" => Some(Frame::Invalidate(
+            serde_json::from_value(json.get("keys")?.clone()).ok()?,
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_entries: usize, ttl: Duration) -> ClientCache {
+        ClientCache::new(ClientCacheConfig { max_entries, ttl })
+    }
+
+    /// Run a tracked read of `key` returning `value`.
+    fn read(cache: &ClientCache, key: &str, value: &str) {
+        cache
+            .begin_read(key)
+            .finish(true, Some(&Some(json!(value))));
+    }
+
+    #[test]
+    fn a_tracked_read_is_served_locally_afterwards() {
+        let cache = cache(10, Duration::from_secs(60));
+        assert_eq!(cache.lookup("k"), None);
+
+        read(&cache, "k", "v");
+
+        assert_eq!(cache.lookup("k"), Some(Some(json!("v"))));
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    }
+
+    #[test]
+    fn an_untracked_read_is_not_cached() {
+        let cache = cache(10, Duration::from_secs(60));
+        cache.begin_read("k").finish(false, Some(&Some(json!("v"))));
+
+        assert_eq!(cache.lookup("k"), None);
+    }
+
+    #[test]
+    fn an_invalidation_during_a_read_keeps_its_result_out() {
+        let cache = cache(10, Duration::from_secs(60));
+        let pending = cache.begin_read("k");
+        cache.apply_invalidation(Some(vec!["k".to_string()]));
+        pending.finish(true, Some(&Some(json!("stale"))));
+
+        assert_eq!(cache.lookup("k"), None);
+    }
+
+    #[test]
+    fn a_cancelled_read_leaves_nothing_in_flight() {
+        let cache = cache(10, Duration::from_secs(60));
+        drop(cache.begin_read("k"));
+
+        assert!(cache.state.lock().unwrap().inflight.is_empty());
+    }
+
+    #[test]
+    fn invalidations_drop_entries() {
+        let cache = cache(10, Duration::from_secs(60));
+        read(&cache, "a", "1");
+        read(&cache, "b", "2");
+
+        cache.apply_invalidation(Some(vec!["a".to_string()]));
+        assert_eq!(cache.lookup("a"), None);
+        assert!(cache.lookup("b").is_some());
+
+        cache.apply_invalidation(None);
+        assert_eq!(cache.lookup("b"), None);
+        assert_eq!(cache.stats().invalidations, 2);
+    }
+
+    #[test]
+    fn the_least_recently_used_entry_is_evicted() {
+        let cache = cache(2, Duration::from_secs(60));
+        read(&cache, "a", "1");
+        read(&cache, "b", "2");
+        cache.lookup("a");
+
+        read(&cache, "c", "3");
+
+        assert!(cache.lookup("a").is_some());
+        assert_eq!(cache.lookup("b"), None);
+        assert!(cache.lookup("c").is_some());
+    }
+
+    #[test]
+    fn entries_expire_after_the_ttl() {
+        let cache = cache(10, Duration::ZERO);
+        read(&cache, "k", "v");
+
+        assert_eq!(cache.lookup("k"), None);
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn server_frames_decode() {
+        assert_eq!(
+            decode_frame(r#"{"type":"connected","client_id":3}"#),
+            Some(Frame::Connected)
+        );
+        assert_eq!(
+            decode_frame(r#"{"type":"tracked","id":7}"#),
+            Some(Frame::Tracked(7))
+        );
+        assert_eq!(
+            decode_frame(r#"{"type":"invalidate","keys":["a"]}"#),
+            Some(Frame::Invalidate(Some(vec!["a".to_string()])))
+        );
+        assert_eq!(
+            decode_frame(r#"{"type":"invalidate","keys":null}"#),
+            Some(Frame::Invalidate(None))
+        );
+    }
+}
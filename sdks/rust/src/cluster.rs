@@ -0,0 +1,362 @@
+//! Cluster routing.
+//!
+//! A [`SynapClient`](crate::SynapClient) configured with
+//! [`SynapConfig::with_cluster`](crate::SynapConfig::with_cluster) hashes the
+//! key of every keyed command to one of the 16384 slots (CRC16 of the key, or
+//! of its `{hash tag}`, like Redis Cluster) and sends the command to the node
+//! that owns the slot. The slot map is loaded from `GET /cluster/slots` and
+//! `GET /cluster/nodes` on first use.
+//!
+//! Redirects are followed transparently: `MOVED` updates the slot map and
+//! retries on the new owner, `ASK` retries once on the node importing the slot
+//! without touching the map. When a node cannot be reached the slot map is
+//! reloaded and the command retried if the slot moved.
+//!
+//! Node addresses are the `cluster.node_address` values the servers advertise;
+//! the client connects to them with the transport of the seed URL, so set them
+//! to the address clients use for that transport. Commands without a key
+//! (queues, streams, pub/sub, `kv.scan`, ...) go to the seed node.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use serde::Deserialize;
+use serde_json::Value;
+use url::Url;
+
+use crate::auth::HttpClient;
+use crate::error::{Result, SynapError};
+use crate::replica::Endpoint;
+
+/// Number of hash slots in a Synap cluster.
+pub const SLOT_COUNT: u16 = 16384;
+
+/// Most redirects followed for one command before giving up.
+pub(crate) const MAX_REDIRECTS: usize = 5;
+
+/// CRC16-CCITT (XMODEM), the checksum Redis Cluster and Synap hash keys with.
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Hash slot of `key`.
+///
+/// When the key contains a non-empty `{...}` hash tag only the tag is hashed,
+/// so `{user:1}:profile` and `{user:1}:orders` land on the same node.
+pub fn hash_slot(key: &str) -> u16 {
+    let tagged = key
+        .find('{')
+        .and_then(|open| {
+            let rest = &key[open + 1..];
+            rest.find('}').map(|close| &rest[..close])
+        })
+        .filter(|tag| !tag.is_empty());
+    crc16(tagged.unwrap_or(key).as_bytes()) % SLOT_COUNT
+}
+
+/// The key a command is routed by: the `key` field, or the first of `keys`.
+pub(crate) fn command_key(payload: &Value) -> Option<&str> {
+    payload["key"]
+        .as_str()
+        .or_else(|| payload["keys"].get(0).and_then(Value::as_str))
+}
+
+/// A `MOVED` or `ASK` reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Redirect {
+    /// `false` for `MOVED` (the slot changed owner), `true` for `ASK` (the
+    /// slot is migrating; only this command goes elsewhere).
+    pub(crate) ask: bool,
+    pub(crate) slot: u16,
+    pub(crate) address: String,
+}
+
+/// Parse a redirect out of a server error. HTTP envelopes carry
+/// `MOVED <slot> <address>`, RESP3 prefixes it with `ERR`.
+pub(crate) fn parse_redirect(error: &SynapError) -> Option<Redirect> {
+    let message = match error {
+        SynapError::Api(e) => e.message.as_str(),
+        SynapError::ServerError(message) => message.as_str(),
+        _ => return None,
+    };
+    let mut tokens = message.split_whitespace();
+    let ask = loop {
+        match tokens.next()? {
+            "MOVED" => break false,
+            "ASK" => break true,
+            _ => {}
+        }
+    };
+    let slot = tokens.next()?.parse().ok()?;
+    let address = tokens.next()?.to_owned();
+    Some(Redirect { ask, slot, address })
+}
+
+/// A node of the cluster, as reported by
+/// [`SynapClient::cluster_nodes`](crate::SynapClient::cluster_nodes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterNode {
+    pub id: String,
+    pub address: String,
+    /// Number of slots the node owns.
+    pub slot_count: usize,
+}
+
+#[derive(Deserialize)]
+struct NodesResponse {
+    nodes: Vec<NodeEntry>,
+}
+
+#[derive(Deserialize)]
+struct NodeEntry {
+    id: String,
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct SlotsResponse {
+    slots: Vec<SlotEntry>,
+}
+
+#[derive(Deserialize)]
+struct SlotEntry {
+    slot: u16,
+    owner: String,
+}
+
+/// Slot owners plus a connection per known node, shared by a client's clones.
+pub(crate) struct ClusterRouter {
+    /// Scheme node addresses are connected with (`http`, `synap`, `resp3`).
+    scheme: &'static str,
+    /// Owner address of every slot; empty until the first load.
+    slots: RwLock<Vec<Option<Arc<str>>>>,
+    /// Node id to address, from the last load.
+    node_ids: RwLock<HashMap<String, Arc<str>>>,
+    /// Connections by node address, created on first use.
+    endpoints: RwLock<HashMap<Arc<str>, Arc<Endpoint>>>,
+}
+
+impl ClusterRouter {
+    /// `scheme` is the one the seed was reached with; see the field.
+    pub(crate) fn new(scheme: &'static str) -> Self {
+        Self {
+            scheme,
+            slots: RwLock::new(Vec::new()),
+            node_ids: RwLock::new(HashMap::new()),
+            endpoints: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Whether the slot map has been loaded.
+    pub(crate) fn is_loaded(&self) -> bool {
+        !self
+            .node_ids
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    /// Address of the node owning `slot`, if known.
+    pub(crate) fn owner(&self, slot: u16) -> Option<Arc<str>> {
+        self.slots
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(slot as usize)
+            .cloned()
+            .flatten()
+    }
+
+    /// Record that `address` now owns `slot` (after a `MOVED`).
+    pub(crate) fn set_owner(&self, slot: u16, address: &str) {
+        let mut slots = self.slots.write().unwrap_or_else(|e| e.into_inner());
+        if slots.is_empty() {
+            slots.resize(SLOT_COUNT as usize, None);
+        }
+        slots[slot as usize] = Some(Arc::from(address));
+    }
+
+    /// The connection to the node at `address`, created with `connect` on
+    /// first use.
+    pub(crate) fn endpoint(
+        &self,
+        address: &str,
+        connect: impl FnOnce(&str) -> Result<Endpoint>,
+    ) -> Result<Arc<Endpoint>> {
+        if let Some(endpoint) = self
+            .endpoints
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(address)
+        {
+            return Ok(Arc::clone(endpoint));
+        }
+        let endpoint = Arc::new(connect(&format!("{}://{}", self.scheme, address))?);
+        Ok(Arc::clone(
+            self.endpoints
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(Arc::from(address))
+                .or_insert(endpoint),
+        ))
+    }
+
+    /// Reload the slot map from the first of `sources` (HTTP base URLs) that
+    /// answers, then from the known nodes.
+    pub(crate) async fn refresh(&self, http: &HttpClient, sources: &[Url]) -> Result<()> {
+        let known: Vec<Url> = self
+            .endpoints
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .map(|e| e.base_url.clone())
+            .collect();
+
+        let mut last_error = SynapError::Other("no cluster node to load the slot map from".into());
+        for base_url in sources.iter().chain(&known) {
+            match Self::load(http, base_url).await {
+                Ok((node_ids, slots)) => {
+                    *self.node_ids.write().unwrap_or_else(|e| e.into_inner()) = node_ids;
+                    *self.slots.write().unwrap_or_else(|e| e.into_inner()) = slots;
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::debug!(node = %base_url, error = %e, "cluster slot map load failed");
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    #[allow(clippy::type_complexity)]
+    async fn load(
+        http: &HttpClient,
+        base_url: &Url,
+    ) -> Result<(HashMap<String, Arc<str>>, Vec<Option<Arc<str>>>)> {
+        let nodes_url = base_url.join("cluster/nodes")?;
+        let slots_url = base_url.join("cluster/slots")?;
+        let nodes: NodesResponse = http
+            .send(|c| c.get(nodes_url.clone()))
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let assigned: SlotsResponse = http
+            .send(|c| c.get(slots_url.clone()))
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let node_ids: HashMap<String, Arc<str>> = nodes
+            .nodes
+            .into_iter()
+            .map(|n| (n.id, Arc::from(n.address)))
+            .collect();
+        let mut slots = vec![None; SLOT_COUNT as usize];
+        for entry in assigned.slots {
+            if let (Some(slot), Some(address)) = (
+                slots.get_mut(entry.slot as usize),
+                node_ids.get(&entry.owner),
+            ) {
+                *slot = Some(Arc::clone(address));
+            }
+        }
+        Ok((node_ids, slots))
+    }
+
+    /// Known nodes with the number of slots each owns.
+    pub(crate) fn nodes(&self) -> Vec<ClusterNode> {
+        let slots = self.slots.read().unwrap_or_else(|e| e.into_inner());
+        let mut nodes: Vec<ClusterNode> = self
+            .node_ids
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(id, address)| ClusterNode {
+                id: id.clone(),
+                address: address.to_string(),
+                slot_count: slots
+                    .iter()
+                    .filter(|owner| owner.as_deref() == Some(&**address))
+                    .count(),
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiError;
+    use serde_json::json;
+
+    #[test]
+    fn test_hash_slot_matches_redis_cluster() {
+        // Reference values from the Redis Cluster specification; the server
+        // hashes keys the same way (synap_core::cluster::hash_slot).
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+        assert_eq!(hash_slot("foo"), 12182);
+        assert_eq!(
+            hash_slot("{user1000}.following"),
+            hash_slot("{user1000}.followers")
+        );
+        // An empty tag hashes the whole key.
+        assert_eq!(hash_slot("foo{}{bar}"), crc16(b"foo{}{bar}") % SLOT_COUNT);
+    }
+
+    #[test]
+    fn test_command_key() {
+        assert_eq!(command_key(&json!({"key": "a"})), Some("a"));
+        assert_eq!(command_key(&json!({"keys": ["b", "c"]})), Some("b"));
+        assert_eq!(command_key(&json!({"topic": "t"})), None);
+    }
+
+    #[test]
+    fn test_parse_redirect() {
+        let moved = SynapError::from(ApiError::new("MOVED 3999 10.0.0.2:15500"));
+        assert_eq!(
+            parse_redirect(&moved),
+            Some(Redirect {
+                ask: false,
+                slot: 3999,
+                address: "10.0.0.2:15500".into()
+            })
+        );
+
+        let ask = SynapError::from(ApiError::from_resp_message(
+            "ERR ASK 12 10.0.0.3:15501".into(),
+        ));
+        let redirect = parse_redirect(&ask).unwrap();
+        assert!(redirect.ask);
+        assert_eq!(redirect.slot, 12);
+
+        assert!(parse_redirect(&SynapError::from(ApiError::new("WRONGTYPE"))).is_none());
+        assert!(parse_redirect(&SynapError::Timeout).is_none());
+    }
+
+    #[test]
+    fn test_moved_updates_one_slot() {
+        let router = ClusterRouter::new("http");
+        assert!(!router.is_loaded());
+        assert_eq!(router.owner(7), None);
+
+        router.set_owner(7, "10.0.0.2:15500");
+        assert!(!router.is_loaded());
+        assert_eq!(router.owner(7).as_deref(), Some("10.0.0.2:15500"));
+        assert_eq!(router.owner(8), None);
+    }
+}
@@ -1,5 +1,7 @@
 //! Error types for Synap SDK
 
+use std::fmt;
+
 use thiserror::Error;
 
 /// Result type alias for Synap SDK operations
@@ -56,10 +58,20 @@ pub enum SynapError {
     #[error("Operation timeout")]
     Timeout,
 
+    /// A paginated result held more items than the cap passed to
+    /// [`collect_all`](crate::CollectAll::collect_all)
+    #[error("Result exceeds the cap of {0} items")]
+    LimitExceeded(usize),
+
     /// TCP transport or I/O error
     #[error("Transport error: {0}")]
     Transport(String),
 
+    /// Invalid TLS configuration (unreadable CA bundle, mismatched client
+    /// certificate and key, ...)
+    #[error("TLS error: {0}")]
+    Tls(String),
+
     /// Command has no native mapping for the active transport.
     ///
     /// Raised when `synap://` or `resp3://` transport is selected and the
@@ -73,7 +85,229 @@ pub enum SynapError {
         transport: String,
     },
 
+    /// The server rejected a command.
+    ///
+    /// Unlike [`Self::ServerError`], this keeps the context the server sent
+    /// back — HTTP status, structured error code, request id — plus the SDK
+    /// command that failed, so callers can branch on [`Self::is_not_found`],
+    /// [`Self::is_conflict`] or [`Self::is_retryable`] instead of matching on
+    /// the message text.
+    #[error("{0}")]
+    Api(Box<ApiError>),
+
     /// Generic error
     #[error("{0}")]
     Other(String),
 }
+
+/// Details of a command the server rejected. See [`SynapError::Api`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ApiError {
+    /// Human-readable message, verbatim from the server.
+    pub message: String,
+    /// The SDK command that failed (e.g. `"kv.get"`).
+    pub command: Option<String>,
+    /// HTTP status of the response, when the error came over HTTP.
+    pub status: Option<u16>,
+    /// Machine-readable error code: the server's `error_code` field over HTTP,
+    /// or the leading RESP-style token (`ERR`, `WRONGTYPE`, `NOPERM`, …) on the
+    /// binary transports.
+    pub error_code: Option<String>,
+    /// The request id the command was sent under, for log correlation.
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    /// Create an error carrying only a message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Attach the failing SDK command name.
+    pub fn with_command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into());
+        self
+    }
+
+    /// Attach the HTTP status.
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Attach the structured error code.
+    pub fn with_error_code(mut self, code: impl Into<String>) -> Self {
+        self.error_code = Some(code.into());
+        self
+    }
+
+    /// Attach the request id.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Build an error from a RESP-style server message (`"WRONGTYPE …"`),
+    /// taking the leading upper-case token as the error code.
+    pub(crate) fn from_resp_message(message: String) -> Self {
+        let code = message
+            .split_whitespace()
+            .next()
+            .filter(|t| t.len() > 1 && t.chars().all(|c| c.is_ascii_uppercase() || c == '_'))
+            .map(str::to_owned);
+        Self {
+            message,
+            error_code: code,
+            ..Default::default()
+        }
+    }
+
+    fn code_is(&self, codes: &[&str]) -> bool {
+        self.error_code
+            .as_deref()
+            .is_some_and(|c| codes.iter().any(|k| c.eq_ignore_ascii_case(k)))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Server error: {}", self.message)?;
+        if let Some(command) = &self.command {
+            write!(f, " (command: {command}")?;
+            if let Some(id) = &self.request_id {
+                write!(f, ", request_id: {id}")?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Error codes that mean "the addressed thing does not exist".
+const NOT_FOUND_CODES: &[&str] = &[
+    "NOT_FOUND",
+    "KEY_NOT_FOUND",
+    "QUEUE_NOT_FOUND",
+    "ROOM_NOT_FOUND",
+    "MESSAGE_NOT_FOUND",
+    "CONSUMER_NOT_FOUND",
+    "KEY_EXPIRED",
+];
+
+/// Error codes that mean "the write lost a race with current state".
+const CONFLICT_CODES: &[&str] = &["CONFLICT", "KEY_EXISTS", "CAS_FAILED"];
+
+/// Error codes for transient conditions worth retrying.
+const RETRYABLE_CODES: &[&str] = &[
+    "TIMEOUT",
+    "RATE_LIMITED",
+    "QUOTA_EXCEEDED",
+    "BUSY",
+    "LOADING",
+    "TRYAGAIN",
+    "CLUSTERDOWN",
+    "UNAVAILABLE",
+];
+
+/// Error codes for rejected or missing credentials.
+const AUTH_CODES: &[&str] = &["UNAUTHORIZED", "FORBIDDEN", "NOAUTH", "WRONGPASS", "NOPERM"];
+
+impl SynapError {
+    /// The server-side context of an [`Api`](Self::Api) error, if this is one.
+    pub fn api(&self) -> Option<&ApiError> {
+        match self {
+            Self::Api(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// HTTP status the server answered with, when known.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Api(e) => e.status,
+            Self::HttpError(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Structured error code reported by the server, when known.
+    pub fn error_code(&self) -> Option<&str> {
+        self.api().and_then(|e| e.error_code.as_deref())
+    }
+
+    /// Request id of the failed call, when known.
+    pub fn request_id(&self) -> Option<&str> {
+        self.api().and_then(|e| e.request_id.as_deref())
+    }
+
+    /// The SDK command that failed, when known.
+    pub fn command(&self) -> Option<&str> {
+        match self {
+            Self::Api(e) => e.command.as_deref(),
+            Self::UnsupportedCommand { command, .. } => Some(command),
+            _ => None,
+        }
+    }
+
+    /// Whether the addressed key, queue, room or message does not exist.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::KeyNotFound(_) | Self::QueueNotFound(_) | Self::RoomNotFound(_) => true,
+            Self::Api(e) => e.status == Some(404) || e.code_is(NOT_FOUND_CODES),
+            _ => false,
+        }
+    }
+
+    /// Whether the request conflicted with the current server state (key
+    /// already exists, CAS mismatch).
+    pub fn is_conflict(&self) -> bool {
+        match self {
+            Self::Api(e) => e.status == Some(409) || e.code_is(CONFLICT_CODES),
+            _ => false,
+        }
+    }
+
+    /// Whether the server rejected the credentials or the caller lacks the
+    /// permission for the command.
+    pub fn is_auth(&self) -> bool {
+        match self {
+            Self::Unauthorized(_) => true,
+            Self::HttpError(e) => e.status().is_some_and(|s| is_auth_status(s.as_u16())),
+            Self::Api(e) => e.status.is_some_and(is_auth_status) || e.code_is(AUTH_CODES),
+            _ => false,
+        }
+    }
+
+    /// Whether retrying the same request may succeed: timeouts, connection
+    /// failures, throttling and temporarily unavailable servers.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Timeout | Self::Transport(_) => true,
+            Self::HttpError(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(|s| is_retryable_status(s.as_u16()))
+            }
+            Self::Api(e) => e.status.is_some_and(is_retryable_status) || e.code_is(RETRYABLE_CODES),
+            _ => false,
+        }
+    }
+}
+
+fn is_auth_status(status: u16) -> bool {
+    matches!(status, 401 | 403)
+}
+
+fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 502 | 503 | 504)
+}
+
+impl From<ApiError> for SynapError {
+    fn from(e: ApiError) -> Self {
+        Self::Api(Box::new(e))
+    }
+}
@@ -1,310 +1,340 @@
-//! Hash data structure operations
-use crate::client::SynapClient;
-use crate::error::Result;
-use serde_json::json;
-use std::collections::HashMap;
-
-/// Hash data structure interface (Redis-compatible)
-///
-/// Hash is a field-value map, ideal for storing objects.
-#[derive(Clone)]
-pub struct HashManager {
-    client: SynapClient,
-}
-
-impl HashManager {
-    /// Create a new Hash manager interface
-    pub(crate) fn new(client: SynapClient) -> Self {
-        Self { client }
-    }
-
-    /// Set field in hash
-    pub async fn set<K, F, V>(&self, key: K, field: F, value: V) -> Result<bool>
-    where
-        K: AsRef<str>,
-        F: AsRef<str>,
-        V: ToString,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "field": field.as_ref(),
-            "value": value.to_string(),
-        });
-
-        let response = self.client.send_command("hash.set", payload).await?;
-        Ok(response
-            .get("success")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false))
-    }
-
-    /// Get field from hash
-    pub async fn get<K, F>(&self, key: K, field: F) -> Result<Option<String>>
-    where
-        K: AsRef<str>,
-        F: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "field": field.as_ref(),
-        });
-
-        let response = self.client.send_command("hash.get", payload).await?;
-        Ok(response
-            .get("value")
-            .and_then(|v| v.as_str())
-            .map(String::from))
-    }
-
-    /// Get all fields and values from hash
-    pub async fn get_all<K>(&self, key: K) -> Result<HashMap<String, String>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("hash.getall", payload).await?;
-
-        let fields = response
-            .get("fields")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(fields)
-    }
-
-    /// Delete field from hash
-    pub async fn del<K, F>(&self, key: K, field: F) -> Result<i64>
-    where
-        K: AsRef<str>,
-        F: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "field": field.as_ref(),
-            "fields": [field.as_ref()],
-        });
-
-        let response = self.client.send_command("hash.del", payload).await?;
-        Ok(response
-            .get("deleted")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(0))
-    }
-
-    /// Check if field exists in hash
-    pub async fn exists<K, F>(&self, key: K, field: F) -> Result<bool>
-    where
-        K: AsRef<str>,
-        F: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "field": field.as_ref(),
-        });
-
-        let response = self.client.send_command("hash.exists", payload).await?;
-        Ok(response
-            .get("exists")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false))
-    }
-
-    /// Get all field names in hash
-    pub async fn keys<K>(&self, key: K) -> Result<Vec<String>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("hash.keys", payload).await?;
-
-        let keys = response
-            .get("fields")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(keys)
-    }
-
-    /// Get all values in hash
-    pub async fn values<K>(&self, key: K) -> Result<Vec<String>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("hash.values", payload).await?;
-
-        let values = response
-            .get("values")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(values)
-    }
-
-    /// Get number of fields in hash
-    pub async fn len<K>(&self, key: K) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("hash.len", payload).await?;
-        Ok(response.get("length").and_then(|v| v.as_u64()).unwrap_or(0) as usize)
-    }
-
-    /// Set multiple fields in hash
-    ///
-    /// Supports both HashMap format (backward compatible) and array format (Redis-compatible)
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::SynapClient;
-    /// # use std::collections::HashMap;
-    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
-    /// // HashMap format (backward compatible)
-    /// let mut fields = HashMap::new();
-    /// fields.insert("name".to_string(), "Alice".to_string());
-    /// fields.insert("age".to_string(), "30".to_string());
-    /// client.hash().mset("user:1", fields).await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn mset<K>(&self, key: K, fields: HashMap<String, String>) -> Result<bool>
-    where
-        K: AsRef<str>,
-    {
-        // Convert HashMap to object format (backward compatible)
-        let payload = json!({
-            "key": key.as_ref(),
-            "fields": fields,
-        });
-
-        let response = self.client.send_command("hash.mset", payload).await?;
-        Ok(response
-            .get("success")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false))
-    }
-
-    /// Set multiple fields in hash using array format (Redis-compatible)
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::SynapClient;
-    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
-    /// let fields = vec![
-    ///     ("name".to_string(), "Alice".to_string()),
-    ///     ("age".to_string(), "30".to_string()),
-    /// ];
-    /// client.hash().mset_array("user:1", fields).await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn mset_array<K>(&self, key: K, fields: Vec<(String, String)>) -> Result<bool>
-    where
-        K: AsRef<str>,
-    {
-        // Convert to array format: [{"field": "...", "value": "..."}, ...]
-        let fields_array: Vec<_> = fields
-            .into_iter()
-            .map(|(field, value)| {
-                json!({
-                    "field": field,
-                    "value": value,
-                })
-            })
-            .collect();
-
-        let payload = json!({
-            "key": key.as_ref(),
-            "fields": fields_array,
-        });
-
-        let response = self.client.send_command("hash.mset", payload).await?;
-        Ok(response
-            .get("success")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false))
-    }
-
-    /// Get multiple fields from hash
-    pub async fn mget<K>(
-        &self,
-        key: K,
-        fields: Vec<String>,
-    ) -> Result<HashMap<String, Option<String>>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "fields": fields,
-        });
-
-        let response = self.client.send_command("hash.mget", payload).await?;
-
-        let values = response
-            .get("values")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(values)
-    }
-
-    /// Increment field value by integer
-    pub async fn incr_by<K, F>(&self, key: K, field: F, increment: i64) -> Result<i64>
-    where
-        K: AsRef<str>,
-        F: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "field": field.as_ref(),
-            "increment": increment,
-        });
-
-        let response = self.client.send_command("hash.incrby", payload).await?;
-        Ok(response.get("value").and_then(|v| v.as_i64()).unwrap_or(0))
-    }
-
-    /// Increment field value by float
-    pub async fn incr_by_float<K, F>(&self, key: K, field: F, increment: f64) -> Result<f64>
-    where
-        K: AsRef<str>,
-        F: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "field": field.as_ref(),
-            "increment": increment,
-        });
-
-        let response = self
-            .client
-            .send_command("hash.incrbyfloat", payload)
-            .await?;
-        Ok(response
-            .get("value")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0))
-    }
-
-    /// Set field only if it doesn't exist
-    pub async fn set_nx<K, F, V>(&self, key: K, field: F, value: V) -> Result<bool>
-    where
-        K: AsRef<str>,
-        F: AsRef<str>,
-        V: ToString,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "field": field.as_ref(),
-            "value": value.to_string(),
-        });
-
-        let response = self.client.send_command("hash.setnx", payload).await?;
-        Ok(response
-            .get("created")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false))
-    }
-}
+//! Hash data structure operations
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::paging::{PAGE_SIZE, PageStream, ScanOptions, json_text, scan_stream};
+use crate::protocol::hash::{
+    HDel, HExists, HGet, HGetAll, HIncrBy, HIncrByFloat, HKeys, HLen, HMGet, HMSet, HScan, HSet,
+    HSetNx, HVals,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Hash data structure interface (Redis-compatible)
+///
+/// Hash is a field-value map, ideal for storing objects.
+#[derive(Clone)]
+pub struct HashManager {
+    client: SynapClient,
+}
+
+impl HashManager {
+    /// Create a new Hash manager interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Set field in hash, returning `true` if the field is new
+    pub async fn set<K, F, V>(&self, key: K, field: F, value: V) -> Result<bool>
+    where
+        K: AsRef<str>,
+        F: AsRef<str>,
+        V: ToString,
+    {
+        let response = self
+            .client
+            .execute(&HSet {
+                key: key.as_ref().to_owned(),
+                field: field.as_ref().to_owned(),
+                value: Value::String(value.to_string()),
+                client_id: None,
+            })
+            .await?;
+        Ok(response.created)
+    }
+
+    /// Get field from hash
+    pub async fn get<K, F>(&self, key: K, field: F) -> Result<Option<String>>
+    where
+        K: AsRef<str>,
+        F: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HGet {
+                key: key.as_ref().to_owned(),
+                field: field.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.value.map(json_text))
+    }
+
+    /// Get all fields and values from hash
+    pub async fn get_all<K>(&self, key: K) -> Result<HashMap<String, String>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HGetAll {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response
+            .fields
+            .into_iter()
+            .map(|(field, value)| (field, json_text(value)))
+            .collect())
+    }
+
+    /// Stream every field and value of a hash, in field order
+    ///
+    /// Unlike [`get_all`](Self::get_all), the fields are fetched a page at a
+    /// time with `hash.scan` (`HSCAN` on the binary transports), so a hash of
+    /// any size can be processed in bounded memory. Non-string values are
+    /// returned as their JSON text.
+    pub fn scan<K>(&self, key: K) -> PageStream<(String, String)>
+    where
+        K: AsRef<str>,
+    {
+        self.scan_with(key, ScanOptions::default())
+    }
+
+    /// Like [`scan`](Self::scan), keeping only fields that match
+    /// `options.pattern` and fetching `options.count` fields per request
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{ScanOptions, SynapClient, SynapConfig};
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let options = ScanOptions {
+    ///     pattern: Some("addr:*".into()),
+    ///     count: Some(500),
+    /// };
+    /// let mut fields = client.hash().scan_with("user:1", options);
+    /// while let Some(field) = fields.next().await {
+    ///     let (name, value) = field?;
+    ///     println!("{name} = {value}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scan_with<K>(&self, key: K, options: ScanOptions) -> PageStream<(String, String)>
+    where
+        K: AsRef<str>,
+    {
+        let key = key.as_ref().to_owned();
+        scan_stream(
+            self.client.clone(),
+            move |cursor| HScan {
+                key: key.clone(),
+                cursor,
+                pattern: options.pattern.clone(),
+                count: Some(options.count.unwrap_or(PAGE_SIZE)),
+            },
+            |page| {
+                let fields = page
+                    .fields
+                    .into_iter()
+                    .map(|(field, value)| (field, json_text(value)))
+                    .collect();
+                (fields, page.next_cursor)
+            },
+        )
+    }
+
+    /// Delete field from hash
+    pub async fn del<K, F>(&self, key: K, field: F) -> Result<i64>
+    where
+        K: AsRef<str>,
+        F: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HDel {
+                key: key.as_ref().to_owned(),
+                fields: vec![field.as_ref().to_owned()],
+                client_id: None,
+            })
+            .await?;
+        Ok(response.deleted as i64)
+    }
+
+    /// Check if field exists in hash
+    pub async fn exists<K, F>(&self, key: K, field: F) -> Result<bool>
+    where
+        K: AsRef<str>,
+        F: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HExists {
+                key: key.as_ref().to_owned(),
+                field: field.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.exists)
+    }
+
+    /// Get all field names in hash
+    pub async fn keys<K>(&self, key: K) -> Result<Vec<String>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HKeys {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.keys)
+    }
+
+    /// Get all values in hash
+    pub async fn values<K>(&self, key: K) -> Result<Vec<String>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HVals {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.values.into_iter().map(json_text).collect())
+    }
+
+    /// Get number of fields in hash
+    pub async fn len<K>(&self, key: K) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HLen {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.length)
+    }
+
+    /// Set multiple fields in hash
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::SynapClient;
+    /// # use std::collections::HashMap;
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// let mut fields = HashMap::new();
+    /// fields.insert("name".to_string(), "Alice".to_string());
+    /// fields.insert("age".to_string(), "30".to_string());
+    /// client.hash().mset("user:1", fields).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn mset<K>(&self, key: K, fields: HashMap<String, String>) -> Result<bool>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HMSet {
+                key: key.as_ref().to_owned(),
+                fields: fields
+                    .into_iter()
+                    .map(|(field, value)| (field, Value::String(value)))
+                    .collect(),
+            })
+            .await?;
+        Ok(response.success)
+    }
+
+    /// Like [`mset`](Self::mset), from a list of field-value pairs
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::SynapClient;
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// let fields = vec![
+    ///     ("name".to_string(), "Alice".to_string()),
+    ///     ("age".to_string(), "30".to_string()),
+    /// ];
+    /// client.hash().mset_array("user:1", fields).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn mset_array<K>(&self, key: K, fields: Vec<(String, String)>) -> Result<bool>
+    where
+        K: AsRef<str>,
+    {
+        self.mset(key, fields.into_iter().collect()).await
+    }
+
+    /// Get multiple fields from hash
+    pub async fn mget<K>(
+        &self,
+        key: K,
+        fields: Vec<String>,
+    ) -> Result<HashMap<String, Option<String>>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HMGet {
+                key: key.as_ref().to_owned(),
+                fields: fields.clone(),
+            })
+            .await?;
+        Ok(fields
+            .into_iter()
+            .zip(response.values)
+            .map(|(field, value)| (field, value.map(json_text)))
+            .collect())
+    }
+
+    /// Increment field value by integer
+    pub async fn incr_by<K, F>(&self, key: K, field: F, increment: i64) -> Result<i64>
+    where
+        K: AsRef<str>,
+        F: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HIncrBy {
+                key: key.as_ref().to_owned(),
+                field: field.as_ref().to_owned(),
+                increment,
+                client_id: None,
+            })
+            .await?;
+        Ok(response.value)
+    }
+
+    /// Increment field value by float
+    pub async fn incr_by_float<K, F>(&self, key: K, field: F, increment: f64) -> Result<f64>
+    where
+        K: AsRef<str>,
+        F: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&HIncrByFloat {
+                key: key.as_ref().to_owned(),
+                field: field.as_ref().to_owned(),
+                increment,
+            })
+            .await?;
+        Ok(response.value)
+    }
+
+    /// Set field only if it doesn't exist
+    pub async fn set_nx<K, F, V>(&self, key: K, field: F, value: V) -> Result<bool>
+    where
+        K: AsRef<str>,
+        F: AsRef<str>,
+        V: ToString,
+    {
+        let response = self
+            .client
+            .execute(&HSetNx {
+                key: key.as_ref().to_owned(),
+                field: field.as_ref().to_owned(),
+                value: Value::String(value.to_string()),
+            })
+            .await?;
+        Ok(response.created)
+    }
+}
@@ -0,0 +1,285 @@
+//! Key prefixing for [`SynapConfig::with_key_prefix`](crate::SynapConfig::with_key_prefix).
+//!
+//! Every command goes through [`SynapClient::send_command`](crate::SynapClient::send_command),
+//! so the prefix is applied there, to the payload fields that name keys,
+//! queues and stream rooms, and stripped from the names that come back.
+
+use std::borrow::Cow;
+
+use serde_json::Value;
+
+/// Namespaces whose `key`, `keys`, `source`, `sources`, `source_keys` and
+/// `destination` fields name keys
+const KEYED: &[&str] = &[
+    "kv",
+    "key",
+    "hash",
+    "list",
+    "set",
+    "sortedset",
+    "hyperloglog",
+    "bitmap",
+    "geospatial",
+    "bf",
+    "script",
+    "function",
+    "transaction",
+];
+
+/// Fields holding one key name
+const KEY_FIELDS: &[&str] = &["key", "source", "destination"];
+
+/// Fields holding a list of key names
+const KEYS_FIELDS: &[&str] = &["keys", "sources", "source_keys"];
+
+/// `prefix` + `name`, borrowing `name` when there is no prefix
+pub(crate) fn scoped<'a>(prefix: &str, name: &'a str) -> Cow<'a, str> {
+    if prefix.is_empty() {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("{prefix}{name}"))
+    }
+}
+
+/// `name` without `prefix`; names outside the prefix are left as they are
+pub(crate) fn unscoped<'a>(prefix: &str, name: &'a str) -> &'a str {
+    name.strip_prefix(prefix).unwrap_or(name)
+}
+
+/// Prefix the names in a command's payload
+pub(crate) fn scope_request(prefix: &str, command: &str, payload: &mut Value) {
+    let Some(object) = payload.as_object_mut() else {
+        return;
+    };
+    let scope = |value: &mut Value| {
+        if let Value::String(name) = value {
+            name.insert_str(0, prefix);
+        }
+    };
+
+    match namespace(command) {
+        "queue" => {
+            for field in ["queue", "name"] {
+                if let Some(name) = object.get_mut(field) {
+                    scope(name);
+                }
+            }
+        }
+        "stream" => {
+            if let Some(room) = object.get_mut("room") {
+                scope(room);
+            }
+            if let Some(Value::Array(rooms)) = object.get_mut("rooms") {
+                for room in rooms {
+                    match room.get_mut("room") {
+                        Some(name) => scope(name),
+                        None => scope(room),
+                    }
+                }
+            }
+        }
+        // The queue or room a schedule publishes to; topics are shared
+        "schedule" => {
+            if let Some(Value::Object(target)) = object.get_mut("target") {
+                for field in ["queue", "stream"] {
+                    if let Some(name) = target.get_mut(field) {
+                        scope(name);
+                    }
+                }
+            }
+        }
+        namespace if KEYED.contains(&namespace) => {
+            for field in KEY_FIELDS {
+                if let Some(key) = object.get_mut(*field) {
+                    scope(key);
+                }
+            }
+            for field in KEYS_FIELDS {
+                if let Some(Value::Array(keys)) = object.get_mut(*field) {
+                    keys.iter_mut().for_each(scope);
+                }
+            }
+            if let Some(Value::Array(pairs)) = object.get_mut("pairs") {
+                for key in pairs.iter_mut().filter_map(|pair| pair.get_mut("key")) {
+                    scope(key);
+                }
+            }
+            if command == "kv.scan" {
+                match object.get_mut("prefix") {
+                    Some(value @ Value::String(_)) => scope(value),
+                    _ => {
+                        object.insert("prefix".to_string(), Value::String(prefix.to_string()));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Strip the prefix from the names a command returns. Only the commands
+/// that list names are touched, so stored values are never rewritten.
+pub(crate) fn unscope_response(prefix: &str, command: &str, response: &mut Value) {
+    let unscope = |value: &mut Value| {
+        if let Value::String(name) = value
+            && name.starts_with(prefix)
+        {
+            name.drain(..prefix.len());
+        }
+    };
+    let Some(object) = response.as_object_mut() else {
+        return;
+    };
+    let field = match command {
+        "kv.scan" | "kv.keys" => "keys",
+        "key.randomkey" => "key",
+        "queue.list" => "queues",
+        "stream.list" => "rooms",
+        "schedule.create" | "schedule.get" | "schedule.list" => {
+            let schedules = match command {
+                "schedule.create" => std::slice::from_mut(response),
+                "schedule.get" => match object.get_mut("schedule") {
+                    Some(schedule) => std::slice::from_mut(schedule),
+                    None => return,
+                },
+                _ => match object.get_mut("schedules") {
+                    Some(Value::Array(schedules)) => schedules.as_mut_slice(),
+                    _ => return,
+                },
+            };
+            for target in schedules
+                .iter_mut()
+                .filter_map(|schedule| schedule.pointer_mut("/spec/target"))
+                .filter_map(Value::as_object_mut)
+            {
+                for field in ["queue", "stream"] {
+                    if let Some(name) = target.get_mut(field) {
+                        unscope(name);
+                    }
+                }
+            }
+            return;
+        }
+        "stream.consume_many" => {
+            if let Some(Value::Array(events)) = object.get_mut("events") {
+                for room in events.iter_mut().filter_map(|event| event.get_mut("room")) {
+                    unscope(room);
+                }
+            }
+            if let Some(Value::Object(offsets)) = object.get_mut("next_offsets") {
+                *offsets = std::mem::take(offsets)
+                    .into_iter()
+                    .map(|(room, offset)| (unscoped(prefix, &room).to_string(), offset))
+                    .collect();
+            }
+            return;
+        }
+        _ => return,
+    };
+    match object.get_mut(field) {
+        Some(Value::Array(names)) => names.iter_mut().for_each(unscope),
+        Some(name) => unscope(name),
+        None => {}
+    }
+}
+
+fn namespace(command: &str) -> &str {
+    command
+        .split_once('.')
+        .map_or(command, |(namespace, _)| namespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_scope_request() {
+        let mut payload = json!({"key": "a", "value": "a", "keys": ["b", "c"]});
+        scope_request("dev:", "kv.set", &mut payload);
+        assert_eq!(
+            payload,
+            json!({"key": "dev:a", "value": "a", "keys": ["dev:b", "dev:c"]})
+        );
+
+        let mut payload = json!({"pairs": [{"key": "a", "value": 1}]});
+        scope_request("dev:", "kv.mset", &mut payload);
+        assert_eq!(payload["pairs"][0]["key"], "dev:a");
+
+        let mut payload = json!({"source": "a", "destination": "b", "member": "m"});
+        scope_request("dev:", "set.move", &mut payload);
+        assert_eq!(
+            payload,
+            json!({"source": "dev:a", "destination": "dev:b", "member": "m"})
+        );
+
+        let mut payload = json!({"limit": 10});
+        scope_request("dev:", "kv.scan", &mut payload);
+        assert_eq!(payload["prefix"], "dev:");
+
+        let mut payload = json!({"name": "jobs"});
+        scope_request("dev:", "queue.create", &mut payload);
+        assert_eq!(payload["name"], "dev:jobs");
+
+        let mut payload = json!({"rooms": [{"room": "chat", "offset": 0}]});
+        scope_request("dev:", "stream.consume_many", &mut payload);
+        assert_eq!(payload["rooms"][0]["room"], "dev:chat");
+
+        let mut payload = json!({"name": "nightly", "target": {"queue": "jobs"}});
+        scope_request("dev:", "schedule.create", &mut payload);
+        assert_eq!(
+            payload,
+            json!({"name": "nightly", "target": {"queue": "dev:jobs"}})
+        );
+
+        // Shared namespaces are left alone
+        let mut payload = json!({"topic": "news", "key": "k"});
+        scope_request("dev:", "pubsub.publish", &mut payload);
+        assert_eq!(payload, json!({"topic": "news", "key": "k"}));
+    }
+
+    #[test]
+    fn test_unscope_response() {
+        let mut response = json!({"keys": ["dev:a", "other"], "next_cursor": "dev:a"});
+        unscope_response("dev:", "kv.scan", &mut response);
+        assert_eq!(
+            response,
+            json!({"keys": ["a", "other"], "next_cursor": "dev:a"})
+        );
+
+        let mut response = json!({"queues": ["dev:jobs"]});
+        unscope_response("dev:", "queue.list", &mut response);
+        assert_eq!(response["queues"], json!(["jobs"]));
+
+        let mut response = json!({
+            "events": [{"room": "dev:chat", "offset": 1}],
+            "next_offsets": {"dev:chat": 2}
+        });
+        unscope_response("dev:", "stream.consume_many", &mut response);
+        assert_eq!(response["events"][0]["room"], "chat");
+        assert_eq!(response["next_offsets"], json!({"chat": 2}));
+
+        let mut response = json!({"schedules": [
+            {"spec": {"name": "a", "target": {"stream": "dev:ticks"}}, "fired": 1},
+            {"spec": {"name": "b", "target": {"topic": "dev:ticks"}}, "fired": 0}
+        ]});
+        unscope_response("dev:", "schedule.list", &mut response);
+        assert_eq!(
+            response["schedules"][0]["spec"]["target"]["stream"],
+            "ticks"
+        );
+        assert_eq!(
+            response["schedules"][1]["spec"]["target"]["topic"],
+            "dev:ticks"
+        );
+    }
+
+    #[test]
+    fn test_scoped_names() {
+        assert_eq!(scoped("", "a"), "a");
+        assert_eq!(scoped("dev:", "a"), "dev:a");
+        assert_eq!(unscoped("dev:", "dev:a"), "a");
+        assert_eq!(unscoped("dev:", "a"), "a");
+    }
+}
@@ -1,204 +1,523 @@
-//! Key-Value Store operations
-
-use crate::client::SynapClient;
-use crate::error::Result;
-use crate::types::KVStats;
-use serde::{Deserialize, Serialize};
-use serde_json::json;
-
-/// Key-Value Store interface
-///
-/// Uses StreamableHTTP protocol for all operations.
-#[derive(Clone)]
-pub struct KVStore {
-    pub(crate) client: SynapClient,
-}
-
-impl KVStore {
-    /// Create a new KV store interface
-    pub(crate) fn new(client: SynapClient) -> Self {
-        Self { client }
-    }
-
-    /// Set a key-value pair
-    ///
-    /// # Arguments
-    /// * `key` - The key to set
-    /// * `value` - The value to store
-    /// * `ttl` - Optional time-to-live in seconds
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::{SynapClient, SynapConfig};
-    /// # #[tokio::main]
-    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
-    /// client.kv().set("user:1", "John Doe", None).await?;
-    /// client.kv().set("session:abc", "token123", Some(3600)).await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn set<K, V>(&self, key: K, value: V, ttl: Option<u64>) -> Result<()>
-    where
-        K: AsRef<str>,
-        V: Serialize,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "value": value,
-            "ttl": ttl,
-        });
-
-        self.client.send_command("kv.set", payload).await?;
-        Ok(())
-    }
-
-    /// Get a value by key
-    ///
-    /// Returns `None` if the key doesn't exist or has expired.
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::{SynapClient, SynapConfig};
-    /// # #[tokio::main]
-    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
-    /// let value: Option<String> = client.kv().get("user:1").await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn get<K, V>(&self, key: K) -> Result<Option<V>>
-    where
-        K: AsRef<str>,
-        V: for<'de> Deserialize<'de>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("kv.get", payload).await?;
-
-        // StreamableHTTP returns null for not found
-        if response.is_null() {
-            return Ok(None);
-        }
-
-        // Parse the value.
-        //
-        // On the binary transport a structured value is JSON-encoded into a
-        // string on the way out (`transport::mapping::to_wire` has no array or
-        // object arm), and the server stores exactly those bytes. Nothing on
-        // the way back re-parses them, so `set(k, vec![1, 2, 3])` followed by
-        // `get::<Vec<u8>>(k)` used to fail on a value the SDK itself wrote.
-        //
-        // Re-parsing is attempted only after the direct decode has already
-        // failed, so a value that genuinely is a string still decodes as one:
-        // `get::<String>` succeeds on the first branch and never reaches here.
-        match serde_json::from_value::<V>(response.clone()) {
-            Ok(value) => Ok(Some(value)),
-            Err(direct) => match response.as_str() {
-                Some(text) => serde_json::from_str::<V>(text)
-                    .map(Some)
-                    .map_err(|_| direct.into()),
-                None => Err(direct.into()),
-            },
-        }
-    }
-
-    /// Delete a key
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::{SynapClient, SynapConfig};
-    /// # #[tokio::main]
-    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
-    /// client.kv().delete("user:1").await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn delete<K>(&self, key: K) -> Result<bool>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("kv.del", payload).await?;
-
-        Ok(response["deleted"].as_bool().unwrap_or(false))
-    }
-
-    /// Check if a key exists
-    pub async fn exists<K>(&self, key: K) -> Result<bool>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("kv.exists", payload).await?;
-
-        Ok(response["exists"].as_bool().unwrap_or(false))
-    }
-
-    /// Increment a numeric value
-    pub async fn incr<K>(&self, key: K) -> Result<i64>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("kv.incr", payload).await?;
-
-        Ok(response["value"].as_i64().unwrap_or(0))
-    }
-
-    /// Decrement a numeric value
-    pub async fn decr<K>(&self, key: K) -> Result<i64>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("kv.decr", payload).await?;
-
-        Ok(response["value"].as_i64().unwrap_or(0))
-    }
-
-    /// Get KV store statistics
-    pub async fn stats(&self) -> Result<KVStats> {
-        let response = self.client.send_command("kv.stats", json!({})).await?;
-        Ok(serde_json::from_value(response)?)
-    }
-
-    /// Get all keys matching a prefix
-    pub async fn keys<P>(&self, prefix: P) -> Result<Vec<String>>
-    where
-        P: AsRef<str>,
-    {
-        let payload = json!({"prefix": prefix.as_ref()});
-        let response = self.client.send_command("kv.keys", payload).await?;
-
-        Ok(serde_json::from_value(response["keys"].clone())?)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::SynapConfig;
-
-    #[tokio::test]
-    async fn test_kv_operations() {
-        let config = SynapConfig::new("http://localhost:15500");
-        let client = SynapClient::new(config).unwrap();
-        let kv = client.kv();
-
-        // Test that KV store can be created
-        assert!(std::mem::size_of_val(&kv) > 0);
-    }
-
-    #[test]
-    fn test_kv_clone() {
-        let config = SynapConfig::new("http://localhost:15500");
-        let client = SynapClient::new(config).unwrap();
-        let kv1 = client.kv();
-        let kv2 = kv1.clone();
-
-        // Both should exist
-        assert!(std::mem::size_of_val(&kv1) > 0);
-        assert!(std::mem::size_of_val(&kv2) > 0);
-    }
-}
+//! Key-Value Store operations
+
+use crate::client::SynapClient;
+use crate::client_cache::ClientCacheStats;
+use crate::error::Result;
+use crate::key_prefix;
+use crate::paging::{PAGE_SIZE, PageStream};
+use crate::protocol::kv::{
+    KeyDump, KeyRestore, KvDecr, KvDel, KvExists, KvGet, KvIncr, KvScan, KvSet,
+};
+use crate::types::{KVStats, RestoreOptions};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Key-Value Store interface
+///
+/// Sends the typed `kv.*` commands from [`protocol::kv`](crate::protocol::kv).
+#[derive(Clone)]
+pub struct KVStore {
+    pub(crate) client: SynapClient,
+}
+
+impl KVStore {
+    /// Create a new KV store interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Set a key-value pair
+    ///
+    /// # Arguments
+    /// * `key` - The key to set
+    /// * `value` - The value to store
+    /// * `ttl` - Optional time-to-live in seconds
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// client.kv().set("user:1", "John Doe", None).await?;
+    /// client.kv().set("session:abc", "token123", Some(3600)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set<K, V>(&self, key: K, value: V, ttl: Option<u64>) -> Result<()>
+    where
+        K: AsRef<str>,
+        V: Serialize,
+    {
+        let command = KvSet {
+            key: key.as_ref().to_owned(),
+            value: serde_json::to_value(value)?,
+            ttl,
+            client_id: None,
+        };
+
+        self.client.execute(&command).await?;
+        self.forget(&command.key);
+        Ok(())
+    }
+
+    /// Get a value by key
+    ///
+    /// Returns `None` if the key doesn't exist or has expired. Served from the
+    /// local cache when [`SynapConfig::with_client_cache`](crate::SynapConfig::with_client_cache)
+    /// is set.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let value: Option<String> = client.kv().get("user:1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get<K, V>(&self, key: K) -> Result<Option<V>>
+    where
+        K: AsRef<str>,
+        V: for<'de> Deserialize<'de>,
+    {
+        let key = key.as_ref();
+        let fetch = || async {
+            let command = KvGet {
+                key: key.to_owned(),
+                format: None,
+            };
+            self.client.execute(&command).await
+        };
+
+        // StreamableHTTP returns null for not found
+        // The cache is keyed by the names the server tracks and invalidates
+        let response = match self.client.client_cache() {
+            Some(cache) => {
+                let scoped = key_prefix::scoped(self.client.key_prefix(), key);
+                cache.get_or_fetch(&self.client, &scoped, fetch).await?
+            }
+            None => fetch().await?,
+        };
+        let Some(response) = response else {
+            return Ok(None);
+        };
+
+        decode_value(response).map(Some)
+    }
+
+    /// Delete a key
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// client.kv().delete("user:1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete<K>(&self, key: K) -> Result<bool>
+    where
+        K: AsRef<str>,
+    {
+        let command = KvDel {
+            key: key.as_ref().to_owned(),
+            client_id: None,
+        };
+
+        let deleted = self.client.execute(&command).await?.deleted;
+        self.forget(&command.key);
+        Ok(deleted)
+    }
+
+    /// Check if a key exists
+    pub async fn exists<K>(&self, key: K) -> Result<bool>
+    where
+        K: AsRef<str>,
+    {
+        let command = KvExists {
+            key: key.as_ref().to_owned(),
+        };
+
+        Ok(self.client.execute(&command).await?.exists)
+    }
+
+    /// Increment a numeric value
+    pub async fn incr<K>(&self, key: K) -> Result<i64>
+    where
+        K: AsRef<str>,
+    {
+        let command = KvIncr {
+            key: key.as_ref().to_owned(),
+            amount: None,
+            client_id: None,
+        };
+
+        let value = self.client.execute(&command).await?.value;
+        self.forget(&command.key);
+        Ok(value)
+    }
+
+    /// Decrement a numeric value
+    pub async fn decr<K>(&self, key: K) -> Result<i64>
+    where
+        K: AsRef<str>,
+    {
+        let command = KvDecr {
+            key: key.as_ref().to_owned(),
+            amount: None,
+            client_id: None,
+        };
+
+        let value = self.client.execute(&command).await?.value;
+        self.forget(&command.key);
+        Ok(value)
+    }
+
+    /// Serialize a key of any type, with its TTL, into an opaque payload
+    ///
+    /// Returns `None` if the key does not exist. Pass the payload to
+    /// [`restore`](Self::restore) on this or another server.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{RestoreOptions, SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let source = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// # let target = SynapClient::new(SynapConfig::new("http://localhost:15501"))?;
+    /// if let Some(payload) = source.kv().dump("cart:42").await? {
+    ///     target
+    ///         .kv()
+    ///         .restore("cart:42", payload, RestoreOptions::default())
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn dump<K>(&self, key: K) -> Result<Option<Vec<u8>>>
+    where
+        K: AsRef<str>,
+    {
+        let command = KeyDump {
+            key: key.as_ref().to_owned(),
+        };
+
+        Ok(self.client.execute(&command).await?.payload)
+    }
+
+    /// Create a key from a [`dump`](Self::dump) payload
+    ///
+    /// Fails if the key exists, unless `options.replace` is set.
+    pub async fn restore<K>(&self, key: K, payload: Vec<u8>, options: RestoreOptions) -> Result<()>
+    where
+        K: AsRef<str>,
+    {
+        let command = KeyRestore {
+            key: key.as_ref().to_owned(),
+            payload,
+            ttl: options.ttl_ms,
+            replace: options.replace,
+            absttl: options.absttl,
+        };
+
+        self.client.execute(&command).await?;
+        self.forget(&command.key);
+        Ok(())
+    }
+
+    /// Client-side cache counters, or `None` when the cache is off
+    pub fn cache_stats(&self) -> Option<ClientCacheStats> {
+        self.client.client_cache().map(|cache| cache.stats())
+    }
+
+    /// Drop a key written through this client from the local cache, so the
+    /// next read sees the write without waiting for the server's invalidation
+    fn forget(&self, key: &str) {
+        if let Some(cache) = self.client.client_cache() {
+            cache.invalidate(&key_prefix::scoped(self.client.key_prefix(), key));
+        }
+    }
+
+    /// Get KV store statistics
+    pub async fn stats(&self) -> Result<KVStats> {
+        let stats = self
+            .client
+            .execute(&crate::protocol::kv::KvStats {})
+            .await?;
+        Ok(KVStats {
+            total_keys: stats.total_keys as usize,
+            total_memory_bytes: stats.total_memory_bytes as usize,
+            hit_rate: stats.hit_rate,
+        })
+    }
+
+    /// Get every key matching a pattern
+    ///
+    /// `pattern` is a glob (`*`, `?`, `[a-z]`, `\` to escape), or a plain
+    /// prefix when it has no wildcards, so `"user:"` and `"user:*"` match the
+    /// same keys. The keys are fetched page by page with `kv.scan` rather
+    /// than one unbounded `kv.keys`, then collected; a glob is matched on
+    /// the client after scanning by its literal prefix.
+    ///
+    /// Holding every key in memory does not scale, so once the result grows
+    /// past [`SynapConfig::keys_warn_threshold`](crate::SynapConfig::keys_warn_threshold)
+    /// a warning is logged. Use [`scan`](Self::scan) to stream large results.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let sessions = client.kv().keys("session:*:active").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn keys<P>(&self, pattern: P) -> Result<Vec<String>>
+    where
+        P: AsRef<str>,
+    {
+        let pattern = pattern.as_ref();
+        let glob = has_wildcards(pattern);
+        let threshold = self.client.config().keys_warn_threshold;
+
+        let mut scan = self.scan(literal_prefix(pattern));
+        let mut keys = Vec::new();
+        while let Some(key) = scan.next().await {
+            let key = key?;
+            if glob && !glob_match(pattern.as_bytes(), key.as_bytes()) {
+                continue;
+            }
+            keys.push(key);
+            if threshold.is_some_and(|t| keys.len() == t + 1) {
+                tracing::warn!(
+                    pattern,
+                    threshold = threshold.unwrap_or_default(),
+                    "kv().keys() matched more keys than the warning threshold; \
+                     use kv().scan() to stream them in bounded memory"
+                );
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Stream every key matching a prefix, in key order
+    ///
+    /// Unlike [`keys`](Self::keys), the keys are fetched a page at a time
+    /// with `kv.scan`, so any number of them can be processed in bounded
+    /// memory. The binary transports have no scan cursor and deliver the
+    /// keys in a single page.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let mut keys = client.kv().scan("session:");
+    /// while let Some(key) = keys.next().await {
+    ///     client.kv().delete(&key?).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scan<P>(&self, prefix: P) -> PageStream<String>
+    where
+        P: AsRef<str>,
+    {
+        let client = self.client.clone();
+        let prefix = prefix.as_ref().to_owned();
+        Box::pin(async_stream::try_stream! {
+            let mut cursor = None;
+            loop {
+                let command = KvScan {
+                    prefix: Some(prefix.clone()),
+                    limit: Some(PAGE_SIZE),
+                    cursor: cursor.take(),
+                };
+                let page = client.execute(&command).await?;
+                for key in page.keys {
+                    yield key;
+                }
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+        })
+    }
+}
+/// Decode a stored value as `V`.
+///
+/// On the binary transport a structured value is JSON-encoded into a string
+/// on the way out (`transport::mapping::to_wire` has no array or object arm),
+/// and the server stores exactly those bytes. Nothing on the way back
+/// re-parses them, so `set(k, vec![1, 2, 3])` followed by
+/// `get::<Vec<u8>>(k)` used to fail on a value the SDK itself wrote.
+///
+/// Re-parsing is attempted only after the direct decode has already failed,
+/// so a value that genuinely is a string still decodes as one: `get::<String>`
+/// succeeds on the first branch and never reaches here.
+pub(crate) fn decode_value<V>(response: Value) -> Result<V>
+where
+    V: for<'de> Deserialize<'de>,
+{
+    match serde_json::from_value::<V>(response.clone()) {
+        Ok(value) => Ok(value),
+        Err(direct) => match response.as_str() {
+            Some(text) => serde_json::from_str::<V>(text).map_err(|_| direct.into()),
+            None => Err(direct.into()),
+        },
+    }
+}
+
+/// Whether `pattern` has a glob wildcard, making it more than a prefix
+pub(crate) fn has_wildcards(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '[', '\\'])
+}
+
+/// The part of `pattern` before its first wildcard, for a prefix scan
+fn literal_prefix(pattern: &str) -> &str {
+    let end = pattern.find(['*', '?', '[', '\\']).unwrap_or(pattern.len());
+    &pattern[..end]
+}
+
+/// Match `text` against a glob: `*` any run, `?` any one byte, `[abc]` /
+/// `[a-z]` / `[^a]` a class, `\` escapes the next byte
+pub(crate) fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Where to resume after the last `*`: (pattern after it, text it covers up to)
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        let step = match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p + 1, t));
+                p += 1;
+                continue;
+            }
+            Some(b'?') => Some(p + 1),
+            Some(b'[') => match_class(pattern, p, text[t]),
+            Some(b'\\') if p + 1 < pattern.len() => (pattern[p + 1] == text[t]).then_some(p + 2),
+            Some(&c) => (c == text[t]).then_some(p + 1),
+            None => None,
+        };
+        match (step, star) {
+            (Some(next), _) => {
+                p = next;
+                t += 1;
+            }
+            (None, Some((after, covered))) => {
+                p = after;
+                t = covered + 1;
+                star = Some((after, covered + 1));
+            }
+            (None, None) => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Match `c` against the class opening at `pattern[open]`, returning the
+/// index after the class when it matches
+fn match_class(pattern: &[u8], open: usize, c: u8) -> Option<usize> {
+    let mut i = open + 1;
+    let negate = pattern.get(i) == Some(&b'^');
+    if negate {
+        i += 1;
+    }
+    let mut matched = false;
+    while i < pattern.len() && pattern[i] != b']' {
+        if pattern[i] == b'\\' && i + 1 < pattern.len() {
+            i += 1;
+        }
+        if i + 2 < pattern.len() && pattern[i + 1] == b'-' && pattern[i + 2] != b']' {
+            let (lo, hi) = (
+                pattern[i].min(pattern[i + 2]),
+                pattern[i].max(pattern[i + 2]),
+            );
+            matched |= (lo..=hi).contains(&c);
+            i += 3;
+        } else {
+            matched |= pattern[i] == c;
+            i += 1;
+        }
+    }
+    // An unterminated class matches nothing
+    (i < pattern.len() && matched != negate).then_some(i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SynapConfig;
+
+    #[test]
+    fn test_glob_match() {
+        for (pattern, text) in [
+            ("user:*", "user:1"),
+            ("user:*", "user:"),
+            ("*:active", "session:9:active"),
+            ("h?llo", "hello"),
+            ("h[ae]llo", "hallo"),
+            ("h[a-c]llo", "hbllo"),
+            ("h[^e]llo", "hallo"),
+            ("a\\*b", "a*b"),
+            ("*a*b*", "xxaxxbxx"),
+        ] {
+            assert!(
+                glob_match(pattern.as_bytes(), text.as_bytes()),
+                "{pattern} ~ {text}"
+            );
+        }
+        for (pattern, text) in [
+            ("user:*", "users:1"),
+            ("h?llo", "hllo"),
+            ("h[ae]llo", "hillo"),
+            ("h[^e]llo", "hello"),
+            ("a\\*b", "axb"),
+            ("h[a-c", "ha-c"),
+        ] {
+            assert!(
+                !glob_match(pattern.as_bytes(), text.as_bytes()),
+                "{pattern} !~ {text}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(literal_prefix("user:*"), "user:");
+        assert_eq!(literal_prefix("user:"), "user:");
+        assert_eq!(literal_prefix("*"), "");
+        assert!(!has_wildcards("user:"));
+        assert!(has_wildcards("user:?"));
+    }
+
+    #[tokio::test]
+    async fn test_kv_operations() {
+        let config = SynapConfig::new("http://localhost:15500");
+        let client = SynapClient::new(config).unwrap();
+        let kv = client.kv();
+
+        // Test that KV store can be created
+        assert!(std::mem::size_of_val(&kv) > 0);
+    }
+
+    #[test]
+    fn test_kv_clone() {
+        let config = SynapConfig::new("http://localhost:15500");
+        let client = SynapClient::new(config).unwrap();
+        let kv1 = client.kv();
+        let kv2 = kv1.clone();
+
+        // Both should exist
+        assert!(std::mem::size_of_val(&kv1) > 0);
+        assert!(std::mem::size_of_val(&kv2) > 0);
+    }
+}
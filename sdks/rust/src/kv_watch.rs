@@ -5,11 +5,12 @@
 //! `http://` / `https://` URLs fall back to the `/kv/ws` WebSocket endpoint.
 //! See `docs/features/kv-watch.md` in the server repository for the envelope semantics.
 
+use crate::key_prefix;
 use crate::reactive::{MessageStream, SubscriptionHandle};
 use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 
 /// One key-change event delivered to a watcher.
 ///
@@ -35,6 +36,14 @@ pub struct WatchEvent {
     pub truncated: bool,
 }
 
+impl WatchEvent {
+    /// The event with the client's key prefix taken off `key`
+    fn unscoped(mut self, prefix: &str) -> Self {
+        self.key = key_prefix::unscoped(prefix, &self.key).to_owned();
+        self
+    }
+}
+
 /// Per-subscription delivery mode for [`KVStore::watch_with_mode`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum WatchMode {
@@ -87,7 +96,8 @@ impl crate::kv::KVStore {
         pattern: impl Into<String>,
         mode: WatchMode,
     ) -> (impl Stream<Item = WatchEvent> + 'static, SubscriptionHandle) {
-        let pattern = pattern.into();
+        let pattern = key_prefix::scoped(self.client.key_prefix(), &pattern.into()).into_owned();
+        let prefix = self.client.key_prefix().to_owned();
         let client = self.client.clone();
 
         let (tx, rx) = mpsc::unbounded_channel::<WatchEvent>();
@@ -121,7 +131,7 @@ impl crate::kv::KVStore {
                                     match msg {
                                         Some(json) => {
                                             if let Some(event) = decode_push_frame(&json)
-                                                && tx.send(event).is_err()
+                                                && tx.send(event.unscoped(&prefix)).is_err()
                                             {
                                                 break; // downstream receiver dropped
                                             }
@@ -162,8 +172,8 @@ impl crate::kv::KVStore {
             };
             let ws_endpoint = format!("{}/kv/ws?keys={}", ws_url, pattern);
 
-            let ws_stream = match connect_async(&ws_endpoint).await {
-                Ok((stream, _)) => stream,
+            let ws_stream = match client.connect_websocket(&ws_endpoint).await {
+                Ok(stream) => stream,
                 Err(e) => {
                     tracing::error!("Failed to connect KV watch WebSocket: {}", e);
                     return;
@@ -181,7 +191,7 @@ impl crate::kv::KVStore {
                         match msg {
                             Some(Ok(WsMessage::Text(text))) => {
                                 if let Some(event) = decode_ws_frame(&text)
-                                    && tx.send(event).is_err()
+                                    && tx.send(event.unscoped(&prefix)).is_err()
                                 {
                                     break;
                                 }
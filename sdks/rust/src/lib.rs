@@ -31,33 +31,59 @@
 //! }
 //! ```
 
+pub mod auth;
 pub mod bitmap;
+pub mod bloom;
+pub mod cache_layer;
 pub mod client;
+pub mod client_cache;
+pub mod cluster;
 pub mod error;
 pub mod geospatial;
 pub mod hash;
 pub mod hyperloglog;
+mod key_prefix;
 pub mod kv;
 pub mod kv_watch;
 pub mod list;
+pub mod lock;
+pub mod memory;
+pub mod metrics;
+pub mod ops;
+pub mod paging;
+pub mod protocol;
 pub mod pubsub;
 mod pubsub_reactive;
 pub mod queue;
 mod queue_reactive;
+pub mod ratelimit;
 pub mod reactive;
+pub mod replica;
 pub mod rx; // RxJS-style reactive programming
+pub mod scheduler;
 pub mod scripting;
 pub mod set;
 pub mod sorted_set;
 pub mod stream;
 mod stream_reactive;
+pub mod telemetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod timeseries;
+pub mod tls;
 pub mod transactions;
 pub mod transport;
 pub mod types;
+pub mod warmup;
 
-pub use bitmap::{BitmapManager, BitmapOperation, BitmapStats};
+pub use auth::TokenRefresh;
+pub use bitmap::{BitRangeUnit, BitmapManager, BitmapOperation, BitmapStats};
+pub use bloom::BloomFilterManager;
+pub use cache_layer::{CacheLayer, FlightGuard, SingleFlight};
 pub use client::{SynapClient, SynapConfig};
-pub use error::{Result, SynapError};
+pub use client_cache::{ClientCacheConfig, ClientCacheStats};
+pub use cluster::{ClusterNode, hash_slot};
+pub use error::{ApiError, Result, SynapError};
 pub use geospatial::{
     Coordinate, DistanceUnit, GeoradiusResult, GeospatialManager, GeospatialStats, Location,
 };
@@ -66,19 +92,34 @@ pub use hyperloglog::HyperLogLogManager;
 pub use kv::KVStore;
 pub use kv_watch::{WatchEvent, WatchMode};
 pub use list::ListManager;
+pub use lock::{Lock, LockManager};
+pub use metrics::{ConnectionStats, MetricsSnapshot, OperationStats, RetryStats};
+pub use ops::{KvOps, QueueOps, StreamOps};
+pub use paging::{CollectAll, PageStream, ScanOptions};
+pub use protocol::Encoding;
+pub use protocol::schedule::{ScheduleProgress, ScheduleSpec, ScheduleStatus, ScheduleTarget};
+pub use protocol::timeseries::{TimeSeriesAggregation, TimeSeriesPoint, TimeSeriesResolution};
 pub use pubsub::PubSubManager;
-pub use queue::QueueManager;
+pub use queue::{PublishStream, QueueManager, QueuePublisher};
+pub use ratelimit::{RateLimit, RateLimitStatus, RateLimiterManager};
 pub use reactive::{MessageStream, SubscriptionHandle};
+pub use replica::{ReadPreference, ReplicaStatus, ReplicationTopology, TopologyMaster};
+pub use scheduler::SchedulerManager;
 pub use scripting::{
-    ScriptEvalOptions, ScriptEvalResponse, ScriptExistsResponse, ScriptFlushResponse,
-    ScriptKillResponse, ScriptManager,
+    FunctionLibraryInfo, ScriptEvalOptions, ScriptEvalResponse, ScriptExistsResponse,
+    ScriptFlushResponse, ScriptKillResponse, ScriptManager,
 };
 pub use set::SetManager;
-pub use sorted_set::{ScoredMember, SortedSetManager, SortedSetStats};
+pub use sorted_set::{
+    LeaderboardEvent, LeaderboardWindow, ScoredMember, SortedSetManager, SortedSetStats,
+};
 pub use stream::StreamManager;
+pub use timeseries::{TimeSeriesIncrOptions, TimeSeriesManager, TimeSeriesQuery};
+pub use tls::TlsConfig;
 pub use transactions::{
     TransactionCommandClient, TransactionExecResult, TransactionManager, TransactionOptions,
     TransactionResponse,
 };
-pub use transport::TransportMode;
-pub use types::HyperLogLogStats;
+pub use transport::{Transport, TransportMode};
+pub use types::{HyperLogLogStats, MultiRoomBatch, RestoreOptions, RoomEvent, SnapshotImport};
+pub use warmup::{WarmupManifest, WarmupReport};
@@ -0,0 +1,254 @@
+//! Distributed locks
+//!
+//! Sends the typed `lock.*` commands from [`protocol::lock`](crate::protocol::lock).
+//! A [`Lock`] is a lease: it expires unless renewed, and every grant carries a
+//! fencing token that increases with each acquisition, so a resource can
+//! reject writes from a holder whose lease has already passed to someone else.
+//!
+//! The lock commands have no native mapping yet; use an `http://` URL.
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::protocol::lock::{LockAcquire, LockRelease, LockRenew};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Pause between attempts while waiting for a held lock
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Lease-based distributed locks
+#[derive(Clone)]
+pub struct LockManager {
+    client: SynapClient,
+}
+
+impl LockManager {
+    /// Create a new lock manager interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Take `name` for `ttl` if it is free
+    ///
+    /// Returns `None` while someone else holds it. The lease is not renewed
+    /// unless you call [`Lock::renew`] or [`Lock::keep_alive`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// if let Some(lock) = client.lock().try_acquire("jobs:nightly", Duration::from_secs(30)).await? {
+    ///     // ... pass lock.token() along with every write the lock protects
+    ///     lock.release().await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn try_acquire(&self, name: &str, ttl: Duration) -> Result<Option<Lock>> {
+        self.attempt(name, ttl).await.map(|(lock, _)| lock)
+    }
+
+    /// Take `name` for `ttl`, waiting up to `wait` for the holder to let go
+    ///
+    /// Returns `None` if the lock is still held when `wait` runs out.
+    pub async fn acquire(&self, name: &str, ttl: Duration, wait: Duration) -> Result<Option<Lock>> {
+        let deadline = Instant::now() + wait;
+        loop {
+            let (lock, holder_ttl) = self.attempt(name, ttl).await?;
+            if lock.is_some() {
+                return Ok(lock);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            // No point polling a lease faster than it can run out
+            let pause = holder_ttl.map_or(RETRY_INTERVAL, |left| left.min(RETRY_INTERVAL));
+            tokio::time::sleep(pause.min(deadline - now)).await;
+        }
+    }
+
+    /// One acquisition, with the holder's remaining lease when it fails
+    async fn attempt(&self, name: &str, ttl: Duration) -> Result<(Option<Lock>, Option<Duration>)> {
+        let sent = Instant::now();
+        let response = self
+            .client
+            .execute(&LockAcquire {
+                key: name.to_owned(),
+                ttl: lease_ms(ttl),
+            })
+            .await?;
+
+        match response.token {
+            Some(token) if response.acquired => {
+                let lock = Lock {
+                    client: self.client.clone(),
+                    name: name.to_owned(),
+                    token,
+                    ttl,
+                    // Counted from before the request, so the local view
+                    // never outlives the server's
+                    deadline: Arc::new(Mutex::new(Some(sent + ttl))),
+                    heartbeat: None,
+                };
+                Ok((Some(lock), None))
+            }
+            _ => Ok((None, response.ttl.map(Duration::from_millis))),
+        }
+    }
+}
+
+/// A held lease on a named lock
+///
+/// Dropping a `Lock` stops its heartbeat but does not release it; the lease
+/// runs out on its own. Call [`release`](Self::release) to free it right away.
+pub struct Lock {
+    client: SynapClient,
+    name: String,
+    token: u64,
+    ttl: Duration,
+    /// When the lease runs out as far as this client knows; `None` once lost
+    deadline: Arc<Mutex<Option<Instant>>>,
+    heartbeat: Option<JoinHandle<()>>,
+}
+
+impl Lock {
+    /// The lock name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The fencing token of this lease
+    ///
+    /// Greater than the token of every earlier lease on any lock, so a
+    /// resource that remembers the highest token it has seen can refuse a
+    /// stale holder.
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+
+    /// Whether the lease is still held, as far as this client can tell
+    ///
+    /// `false` once the lease ran out, a renewal was refused, or the lock was
+    /// released.
+    pub fn is_held(&self) -> bool {
+        lock_deadline(&self.deadline).is_some_and(|deadline| Instant::now() < deadline)
+    }
+
+    /// Extend the lease to the lock's TTL from now
+    ///
+    /// Returns `false` if the lease was already lost; the lock is then free
+    /// or held by someone else.
+    pub async fn renew(&self) -> Result<bool> {
+        renew(
+            &self.client,
+            &self.name,
+            self.token,
+            self.ttl,
+            &self.deadline,
+        )
+        .await
+    }
+
+    /// Renew the lease in the background every third of its TTL
+    ///
+    /// The heartbeat stops when a renewal is refused, which [`is_held`](Self::is_held)
+    /// then reports. Failed requests are retried on the next beat while the
+    /// lease may still be valid.
+    pub fn keep_alive(&mut self) {
+        if self.heartbeat.is_some() {
+            return;
+        }
+        let client = self.client.clone();
+        let name = self.name.clone();
+        let (token, ttl) = (self.token, self.ttl);
+        let deadline = Arc::clone(&self.deadline);
+        let interval = (ttl / 3).max(Duration::from_millis(1));
+
+        self.heartbeat = Some(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                match renew(&client, &name, token, ttl, &deadline).await {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(e) => tracing::warn!("Failed to renew lock {}: {}", name, e),
+                }
+            }
+        }));
+    }
+
+    /// Release the lock
+    ///
+    /// Returns `false` if the lease was already lost, in which case nothing
+    /// is released: the server only drops a lease for its own token.
+    pub async fn release(mut self) -> Result<bool> {
+        if let Some(heartbeat) = self.heartbeat.take() {
+            heartbeat.abort();
+        }
+        *lock_deadline(&self.deadline) = None;
+        let response = self
+            .client
+            .execute(&LockRelease {
+                key: self.name.clone(),
+                token: self.token,
+            })
+            .await?;
+        Ok(response.released)
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if let Some(heartbeat) = self.heartbeat.take() {
+            heartbeat.abort();
+        }
+    }
+}
+
+impl std::fmt::Debug for Lock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lock")
+            .field("name", &self.name)
+            .field("token", &self.token)
+            .field("ttl", &self.ttl)
+            .field("held", &self.is_held())
+            .finish()
+    }
+}
+
+async fn renew(
+    client: &SynapClient,
+    name: &str,
+    token: u64,
+    ttl: Duration,
+    deadline: &Mutex<Option<Instant>>,
+) -> Result<bool> {
+    let sent = Instant::now();
+    let response = client
+        .execute(&LockRenew {
+            key: name.to_owned(),
+            token,
+            ttl: lease_ms(ttl),
+        })
+        .await?;
+
+    let mut deadline = lock_deadline(deadline);
+    // A release racing the renewal wins
+    if deadline.is_some() {
+        *deadline = response.renewed.then(|| sent + ttl);
+    }
+    Ok(response.renewed)
+}
+
+fn lock_deadline(deadline: &Mutex<Option<Instant>>) -> MutexGuard<'_, Option<Instant>> {
+    deadline.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The server refuses a zero lease
+fn lease_ms(ttl: Duration) -> u64 {
+    (ttl.as_millis() as u64).max(1)
+}
@@ -0,0 +1,468 @@
+//! In-memory implementations of the manager traits, for tests
+//!
+//! [`MemoryBackend`] keeps keys, queues and stream rooms in the process and
+//! hands out views implementing [`KvOps`], [`QueueOps`] and [`StreamOps`],
+//! the way [`SynapClient`](crate::SynapClient) hands out its managers. The
+//! views share the backend's data, so what one test step writes the next one
+//! reads.
+//!
+//! ```
+//! use serde_json::json;
+//! use synap_sdk::memory::MemoryBackend;
+//! use synap_sdk::{KvOps, QueueOps};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> synap_sdk::Result<()> {
+//! let synap = MemoryBackend::new();
+//! synap.kv().set("user:1", json!("alice"), None).await?;
+//! assert_eq!(synap.kv().get("user:1").await?, Some(json!("alice")));
+//!
+//! synap.queue().create_queue("jobs", None, None).await?;
+//! synap.queue().publish("jobs", b"resize", None, None).await?;
+//! let job = synap.queue().consume("jobs", "worker-1").await?.unwrap();
+//! synap.queue().ack("jobs", &job.id).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Semantics follow the server's: queues deliver by priority and dead-letter
+//! a message nacked more than `max_retries` times, and stream offsets start
+//! at 0. Ack deadlines are not enforced.
+
+use crate::cache_layer::SingleFlight;
+use crate::error::{Result, SynapError};
+use crate::kv::{glob_match, has_wildcards};
+use crate::ops::{KvOps, QueueOps, StreamOps};
+use crate::types::{Event, Message, QueueStats, StreamStats};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Defaults of a server queue created without a config
+const DEFAULT_MAX_DEPTH: usize = 100_000;
+const DEFAULT_PRIORITY: u8 = 5;
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Keys, queues and stream rooms held in memory
+#[derive(Clone, Default)]
+pub struct MemoryBackend {
+    state: Arc<Mutex<State>>,
+    flights: Arc<SingleFlight>,
+}
+
+#[derive(Default)]
+struct State {
+    kv: BTreeMap<String, (Value, Option<Instant>)>,
+    queues: BTreeMap<String, QueueState>,
+    rooms: BTreeMap<String, RoomState>,
+}
+
+struct QueueState {
+    max_depth: usize,
+    messages: VecDeque<Message>,
+    /// Delivered and not yet acked, by message ID, with the consumer
+    pending: HashMap<String, (String, Message)>,
+    stats: QueueStats,
+}
+
+struct RoomState {
+    max_events: Option<usize>,
+    events: VecDeque<Event>,
+    next_offset: u64,
+    total_consumed: u64,
+}
+
+impl MemoryBackend {
+    /// Create an empty backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Key-value operations
+    pub fn kv(&self) -> MemoryKv {
+        MemoryKv {
+            backend: self.clone(),
+        }
+    }
+
+    /// Queue operations
+    pub fn queue(&self) -> MemoryQueue {
+        MemoryQueue {
+            backend: self.clone(),
+        }
+    }
+
+    /// Event stream operations
+    pub fn stream(&self) -> MemoryStream {
+        MemoryStream {
+            backend: self.clone(),
+        }
+    }
+
+    /// Key locks for [`CacheLayer`](crate::CacheLayer) on [`MemoryBackend::kv`]
+    pub(crate) fn flights(&self) -> &SingleFlight {
+        &self.flights
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// [`KvOps`] on a [`MemoryBackend`]
+#[derive(Clone)]
+pub struct MemoryKv {
+    pub(crate) backend: MemoryBackend,
+}
+
+/// [`QueueOps`] on a [`MemoryBackend`]
+#[derive(Clone)]
+pub struct MemoryQueue {
+    backend: MemoryBackend,
+}
+
+/// [`StreamOps`] on a [`MemoryBackend`]
+#[derive(Clone)]
+pub struct MemoryStream {
+    backend: MemoryBackend,
+}
+
+impl State {
+    /// Live value of `key`, dropping it if it has expired
+    fn live(&mut self, key: &str) -> Option<&Value> {
+        let expired = matches!(
+            self.kv.get(key),
+            Some((_, Some(expires_at))) if *expires_at <= Instant::now()
+        );
+        if expired {
+            self.kv.remove(key);
+        }
+        self.kv.get(key).map(|(value, _)| value)
+    }
+
+    fn add(&mut self, key: &str, amount: i64) -> Result<i64> {
+        let current = match self.live(key) {
+            None => Some(0),
+            Some(Value::Number(n)) => n.as_i64(),
+            Some(Value::String(s)) => s.parse().ok(),
+            Some(_) => None,
+        }
+        .ok_or_else(|| SynapError::ServerError(format!("Value of '{key}' is not an integer")))?;
+        let value = current + amount;
+        let expires_at = self.kv.get(key).and_then(|(_, expires_at)| *expires_at);
+        self.kv
+            .insert(key.to_owned(), (Value::from(value), expires_at));
+        Ok(value)
+    }
+
+    fn queue(&mut self, name: &str) -> Result<&mut QueueState> {
+        self.queues
+            .get_mut(name)
+            .ok_or_else(|| SynapError::QueueNotFound(name.to_owned()))
+    }
+
+    fn room(&mut self, name: &str) -> Result<&mut RoomState> {
+        self.rooms
+            .get_mut(name)
+            .ok_or_else(|| SynapError::RoomNotFound(name.to_owned()))
+    }
+}
+
+impl QueueState {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            messages: VecDeque::new(),
+            pending: HashMap::new(),
+            stats: QueueStats {
+                depth: 0,
+                consumers: 0,
+                published: 0,
+                consumed: 0,
+                acked: 0,
+                nacked: 0,
+                dead_lettered: 0,
+            },
+        }
+    }
+
+    /// Take `message_id` out of the pending set
+    fn settle(&mut self, message_id: &str) -> Result<Message> {
+        let (_, message) = self
+            .pending
+            .remove(message_id)
+            .ok_or_else(|| SynapError::ServerError(format!("Message not found: {message_id}")))?;
+        self.update_counts();
+        Ok(message)
+    }
+
+    fn update_counts(&mut self) {
+        let mut consumers: Vec<&str> = self.pending.values().map(|(c, _)| c.as_str()).collect();
+        consumers.sort_unstable();
+        consumers.dedup();
+        self.stats.consumers = consumers.len();
+        self.stats.depth = self.messages.len();
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[async_trait]
+impl KvOps for MemoryKv {
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<()> {
+        let expires_at = ttl.map(|secs| Instant::now() + Duration::from_secs(secs));
+        self.backend
+            .lock()
+            .kv
+            .insert(key.to_owned(), (value, expires_at));
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>> {
+        Ok(self.backend.lock().live(key).cloned())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let mut state = self.backend.lock();
+        let existed = state.live(key).is_some();
+        state.kv.remove(key);
+        Ok(existed)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.backend.lock().live(key).is_some())
+    }
+
+    async fn incr(&self, key: &str) -> Result<i64> {
+        self.backend.lock().add(key, 1)
+    }
+
+    async fn decr(&self, key: &str) -> Result<i64> {
+        self.backend.lock().add(key, -1)
+    }
+
+    async fn keys(&self, pattern: &str) -> Result<Vec<String>> {
+        let mut state = self.backend.lock();
+        let now = Instant::now();
+        state
+            .kv
+            .retain(|_, (_, expires_at)| expires_at.is_none_or(|at| at > now));
+        Ok(state
+            .kv
+            .keys()
+            .filter(|key| {
+                if has_wildcards(pattern) {
+                    glob_match(pattern.as_bytes(), key.as_bytes())
+                } else {
+                    key.starts_with(pattern)
+                }
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl QueueOps for MemoryQueue {
+    async fn create_queue(
+        &self,
+        queue_name: &str,
+        max_depth: Option<usize>,
+        _ack_deadline_secs: Option<u64>,
+    ) -> Result<()> {
+        self.backend
+            .lock()
+            .queues
+            .entry(queue_name.to_owned())
+            .or_insert_with(|| QueueState::new(max_depth.unwrap_or(DEFAULT_MAX_DEPTH)));
+        Ok(())
+    }
+
+    async fn publish(
+        &self,
+        queue_name: &str,
+        payload: &[u8],
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+    ) -> Result<String> {
+        let mut state = self.backend.lock();
+        let queue = state.queue(queue_name)?;
+        if queue.messages.len() >= queue.max_depth {
+            return Err(SynapError::ServerError(format!(
+                "Queue is full: {queue_name}"
+            )));
+        }
+
+        let message = Message {
+            id: uuid::Uuid::new_v4().to_string(),
+            payload: payload.to_vec(),
+            priority: priority.unwrap_or(DEFAULT_PRIORITY),
+            retry_count: 0,
+            max_retries: max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            deadline: None,
+        };
+        let id = message.id.clone();
+
+        // Higher priority first, FIFO within a priority
+        let position = queue
+            .messages
+            .iter()
+            .position(|m| m.priority < message.priority)
+            .unwrap_or(queue.messages.len());
+        queue.messages.insert(position, message);
+        queue.stats.published += 1;
+        queue.update_counts();
+        Ok(id)
+    }
+
+    async fn consume(&self, queue_name: &str, consumer_id: &str) -> Result<Option<Message>> {
+        let mut state = self.backend.lock();
+        let queue = state.queue(queue_name)?;
+        let Some(message) = queue.messages.pop_front() else {
+            return Ok(None);
+        };
+        queue.pending.insert(
+            message.id.clone(),
+            (consumer_id.to_owned(), message.clone()),
+        );
+        queue.stats.consumed += 1;
+        queue.update_counts();
+        Ok(Some(message))
+    }
+
+    async fn ack(&self, queue_name: &str, message_id: &str) -> Result<()> {
+        let mut state = self.backend.lock();
+        let queue = state.queue(queue_name)?;
+        queue.settle(message_id)?;
+        queue.stats.acked += 1;
+        Ok(())
+    }
+
+    async fn nack(&self, queue_name: &str, message_id: &str) -> Result<()> {
+        let mut state = self.backend.lock();
+        let queue = state.queue(queue_name)?;
+        let mut message = queue.settle(message_id)?;
+        queue.stats.nacked += 1;
+
+        message.retry_count += 1;
+        if message.retry_count > message.max_retries {
+            queue.stats.dead_lettered += 1;
+        } else {
+            queue.messages.push_back(message);
+        }
+        queue.update_counts();
+        Ok(())
+    }
+
+    async fn stats(&self, queue_name: &str) -> Result<QueueStats> {
+        Ok(self.backend.lock().queue(queue_name)?.stats.clone())
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self.backend.lock().queues.keys().cloned().collect())
+    }
+
+    async fn delete_queue(&self, queue_name: &str) -> Result<()> {
+        self.backend
+            .lock()
+            .queues
+            .remove(queue_name)
+            .map(|_| ())
+            .ok_or_else(|| SynapError::QueueNotFound(queue_name.to_owned()))
+    }
+}
+
+#[async_trait]
+impl StreamOps for MemoryStream {
+    async fn create_room(&self, room: &str, max_events: Option<usize>) -> Result<()> {
+        let mut state = self.backend.lock();
+        if state.rooms.contains_key(room) {
+            return Err(SynapError::ServerError(format!(
+                "Room '{room}' already exists"
+            )));
+        }
+        state.rooms.insert(
+            room.to_owned(),
+            RoomState {
+                max_events,
+                events: VecDeque::new(),
+                next_offset: 0,
+                total_consumed: 0,
+            },
+        );
+        Ok(())
+    }
+
+    async fn publish(&self, room: &str, event: &str, data: Value) -> Result<u64> {
+        let mut state = self.backend.lock();
+        let room = state.room(room)?;
+        let offset = room.next_offset;
+        room.next_offset += 1;
+        room.events.push_back(Event {
+            offset,
+            event: event.to_owned(),
+            data,
+            timestamp: Some(now_secs()),
+        });
+        if let Some(max) = room.max_events {
+            while room.events.len() > max {
+                room.events.pop_front();
+            }
+        }
+        Ok(offset)
+    }
+
+    async fn consume(
+        &self,
+        room: &str,
+        offset: Option<u64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Event>> {
+        let mut state = self.backend.lock();
+        let room = state.room(room)?;
+        let from = offset.unwrap_or(0);
+        let events: Vec<Event> = room
+            .events
+            .iter()
+            .filter(|event| event.offset >= from)
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        room.total_consumed += events.len() as u64;
+        Ok(events)
+    }
+
+    async fn stats(&self, room: &str) -> Result<StreamStats> {
+        let mut state = self.backend.lock();
+        let stats = state.room(room)?;
+        Ok(StreamStats {
+            name: room.to_owned(),
+            message_count: stats.events.len(),
+            min_offset: stats.events.front().map_or(0, |e| e.offset),
+            max_offset: stats.next_offset.saturating_sub(1),
+            total_published: stats.next_offset,
+            total_consumed: stats.total_consumed,
+            ..Default::default()
+        })
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self.backend.lock().rooms.keys().cloned().collect())
+    }
+
+    async fn delete_room(&self, room: &str) -> Result<()> {
+        self.backend
+            .lock()
+            .rooms
+            .remove(room)
+            .map(|_| ())
+            .ok_or_else(|| SynapError::RoomNotFound(room.to_owned()))
+    }
+}
@@ -0,0 +1,398 @@
+//! Client-side metrics ([`SynapConfig::with_metrics`]).
+//!
+//! With metrics enabled the client records, for every command sent through
+//! [`SynapClient::send_command`], its count, errors and latency by command
+//! name, along with the retries it makes on its own and the TCP connections
+//! its SynapRPC and RESP3 transports open. Read them with
+//! [`SynapClient::metrics`]:
+//!
+//! ```no_run
+//! # use synap_sdk::{SynapClient, SynapConfig};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SynapClient::new(SynapConfig::new("http://localhost:15500").with_metrics())?;
+//! client.kv().set("a", "1", None).await?;
+//!
+//! let metrics = client.metrics().expect("metrics are enabled");
+//! let set = &metrics.operations["kv.set"];
+//! println!("{} calls, mean {:?}", set.count, set.mean_latency());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `prometheus` feature, [`SynapClient::register_metrics`] adds the
+//! same data to a `prometheus::Registry` as `synap_client_*` series.
+//!
+//! Metrics are shared by every clone of a client, including those returned
+//! by [`SynapClient::select`]. Commands served from the
+//! [client-side cache](crate::client_cache) are not counted.
+//!
+//! [`SynapConfig::with_metrics`]: crate::SynapConfig::with_metrics
+//! [`SynapClient::send_command`]: crate::SynapClient::send_command
+//! [`SynapClient::metrics`]: crate::SynapClient::metrics
+//! [`SynapClient::register_metrics`]: crate::SynapClient::register_metrics
+//! [`SynapClient::select`]: crate::SynapClient::select
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Everything recorded since the client was created
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Per command name, e.g. `kv.set`
+    pub operations: BTreeMap<String, OperationStats>,
+    /// Requests the client sent again on its own
+    pub retries: RetryStats,
+    /// SynapRPC and RESP3 connections; HTTP uses reqwest's pool
+    pub connections: ConnectionStats,
+}
+
+/// Calls of one command
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationStats {
+    /// Completed calls, failed ones included
+    pub count: u64,
+    /// Calls that returned an error
+    pub errors: u64,
+    /// Sum of every call's latency
+    pub total_latency: Duration,
+    /// Slowest call
+    pub max_latency: Duration,
+}
+
+impl OperationStats {
+    /// Average latency per call; zero before the first one
+    pub fn mean_latency(&self) -> Duration {
+        match u32::try_from(self.count) {
+            Ok(0) => Duration::ZERO,
+            Ok(count) => self.total_latency / count,
+            Err(_) => Duration::from_secs_f64(self.total_latency.as_secs_f64() / self.count as f64),
+        }
+    }
+}
+
+/// Requests sent again by the client, by reason
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryStats {
+    /// Resent with a new token after `401`. See [`crate::auth`].
+    pub auth_refreshes: u64,
+    /// Resent to the primary after a replica could not be reached
+    pub replica_fallbacks: u64,
+    /// Resent after a cluster redirect or a topology reload
+    pub cluster_retries: u64,
+    /// Resent on a new connection after the previous one broke
+    pub reconnects: u64,
+}
+
+/// TCP connections of the SynapRPC and RESP3 transports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// Connections established
+    pub opened: u64,
+    /// Connection attempts that failed
+    pub failed: u64,
+    /// Connections open now, subscriptions included
+    pub open: u64,
+}
+
+/// Why a request was sent again
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Retry {
+    AuthRefresh,
+    ReplicaFallback,
+    Cluster,
+    Reconnect,
+}
+
+impl Retry {
+    #[cfg(feature = "prometheus")]
+    fn label(self) -> &'static str {
+        match self {
+            Self::AuthRefresh => "auth_refresh",
+            Self::ReplicaFallback => "replica_fallback",
+            Self::Cluster => "cluster",
+            Self::Reconnect => "reconnect",
+        }
+    }
+}
+
+/// Recorder shared by a client and its transports; records nothing unless
+/// enabled
+#[derive(Default)]
+pub(crate) struct ClientMetrics {
+    enabled: bool,
+    operations: Mutex<HashMap<String, OperationStats>>,
+    auth_refreshes: AtomicU64,
+    replica_fallbacks: AtomicU64,
+    cluster_retries: AtomicU64,
+    reconnects: AtomicU64,
+    opened: AtomicU64,
+    failed: AtomicU64,
+    open: AtomicU64,
+    #[cfg(feature = "prometheus")]
+    prometheus: Option<prometheus_metrics::PrometheusMetrics>,
+}
+
+impl ClientMetrics {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            #[cfg(feature = "prometheus")]
+            prometheus: enabled.then(prometheus_metrics::PrometheusMetrics::new),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record one completed command
+    pub(crate) fn record(&self, command: &str, ok: bool, latency: Duration) {
+        if !self.enabled {
+            return;
+        }
+        {
+            let mut operations = self.operations.lock().unwrap_or_else(|e| e.into_inner());
+            let stats = match operations.get_mut(command) {
+                Some(stats) => stats,
+                None => operations.entry(command.to_owned()).or_default(),
+            };
+            stats.count += 1;
+            stats.errors += u64::from(!ok);
+            stats.total_latency += latency;
+            stats.max_latency = stats.max_latency.max(latency);
+        }
+        #[cfg(feature = "prometheus")]
+        if let Some(prometheus) = &self.prometheus {
+            prometheus.record(command, ok, latency);
+        }
+    }
+
+    /// Record a request sent again
+    pub(crate) fn retry(&self, retry: Retry) {
+        if !self.enabled {
+            return;
+        }
+        let counter = match retry {
+            Retry::AuthRefresh => &self.auth_refreshes,
+            Retry::ReplicaFallback => &self.replica_fallbacks,
+            Retry::Cluster => &self.cluster_retries,
+            Retry::Reconnect => &self.reconnects,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "prometheus")]
+        if let Some(prometheus) = &self.prometheus {
+            prometheus.retries.with_label_values(&[retry.label()]).inc();
+        }
+    }
+
+    /// Record a connection attempt; the returned guard counts the connection
+    /// as open until it is dropped with it
+    pub(crate) fn connected<T, E>(
+        self: &Arc<Self>,
+        attempt: std::result::Result<T, E>,
+    ) -> std::result::Result<(T, OpenConnection), E> {
+        let counter = if attempt.is_ok() {
+            &self.opened
+        } else {
+            &self.failed
+        };
+        if self.enabled {
+            counter.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "prometheus")]
+            if let Some(prometheus) = &self.prometheus {
+                let status = if attempt.is_ok() { "ok" } else { "error" };
+                prometheus.connects.with_label_values(&[status]).inc();
+            }
+        }
+        attempt.map(|connection| (connection, OpenConnection::new(self)))
+    }
+
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        MetricsSnapshot {
+            operations: self
+                .operations
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|(command, stats)| (command.clone(), stats.clone()))
+                .collect(),
+            retries: RetryStats {
+                auth_refreshes: load(&self.auth_refreshes),
+                replica_fallbacks: load(&self.replica_fallbacks),
+                cluster_retries: load(&self.cluster_retries),
+                reconnects: load(&self.reconnects),
+            },
+            connections: ConnectionStats {
+                opened: load(&self.opened),
+                failed: load(&self.failed),
+                open: load(&self.open),
+            },
+        }
+    }
+
+    #[cfg(feature = "prometheus")]
+    pub(crate) fn register(&self, registry: &prometheus::Registry) -> prometheus::Result<()> {
+        match &self.prometheus {
+            Some(prometheus) => prometheus.register(registry),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Counts one connection as open while it lives
+pub(crate) struct OpenConnection(Option<Arc<ClientMetrics>>);
+
+impl OpenConnection {
+    fn new(metrics: &Arc<ClientMetrics>) -> Self {
+        if !metrics.enabled {
+            return Self(None);
+        }
+        metrics.open.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "prometheus")]
+        if let Some(prometheus) = &metrics.prometheus {
+            prometheus.open.inc();
+        }
+        Self(Some(Arc::clone(metrics)))
+    }
+}
+
+impl Drop for OpenConnection {
+    fn drop(&mut self) {
+        if let Some(metrics) = &self.0 {
+            metrics.open.fetch_sub(1, Ordering::Relaxed);
+            #[cfg(feature = "prometheus")]
+            if let Some(prometheus) = &metrics.prometheus {
+                prometheus.open.dec();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "prometheus")]
+mod prometheus_metrics {
+    use std::time::Duration;
+
+    use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry};
+
+    /// The `synap_client_*` series, updated along with the snapshot
+    /// counters and registered on demand
+    pub(super) struct PrometheusMetrics {
+        operations: IntCounterVec,
+        latency: HistogramVec,
+        pub(super) retries: IntCounterVec,
+        pub(super) connects: IntCounterVec,
+        pub(super) open: IntGauge,
+    }
+
+    impl PrometheusMetrics {
+        pub(super) fn new() -> Self {
+            let valid = "metric options are static and valid";
+            Self {
+                operations: IntCounterVec::new(
+                    Opts::new(
+                        "synap_client_operations_total",
+                        "Commands sent by the Synap client",
+                    ),
+                    &["command", "status"],
+                )
+                .expect(valid),
+                latency: HistogramVec::new(
+                    HistogramOpts::new(
+                        "synap_client_operation_duration_seconds",
+                        "Synap command latency seen by the client in seconds",
+                    )
+                    .buckets(vec![
+                        0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1,
+                        0.25, 0.5, 1.0, 5.0,
+                    ]),
+                    &["command"],
+                )
+                .expect(valid),
+                retries: IntCounterVec::new(
+                    Opts::new(
+                        "synap_client_retries_total",
+                        "Requests the Synap client sent again, by reason",
+                    ),
+                    &["reason"],
+                )
+                .expect(valid),
+                connects: IntCounterVec::new(
+                    Opts::new(
+                        "synap_client_connects_total",
+                        "SynapRPC and RESP3 connection attempts",
+                    ),
+                    &["status"],
+                )
+                .expect(valid),
+                open: IntGauge::new(
+                    "synap_client_connections_open",
+                    "Open SynapRPC and RESP3 connections",
+                )
+                .expect(valid),
+            }
+        }
+
+        pub(super) fn record(&self, command: &str, ok: bool, latency: Duration) {
+            let status = if ok { "ok" } else { "error" };
+            self.operations.with_label_values(&[command, status]).inc();
+            self.latency
+                .with_label_values(&[command])
+                .observe(latency.as_secs_f64());
+        }
+
+        pub(super) fn register(&self, registry: &Registry) -> prometheus::Result<()> {
+            registry.register(Box::new(self.operations.clone()))?;
+            registry.register(Box::new(self.latency.clone()))?;
+            registry.register(Box::new(self.retries.clone()))?;
+            registry.register(Box::new(self.connects.clone()))?;
+            registry.register(Box::new(self.open.clone()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_nothing_when_disabled() {
+        let metrics = Arc::new(ClientMetrics::new(false));
+        metrics.record("kv.get", true, Duration::from_millis(1));
+        metrics.retry(Retry::Reconnect);
+        let (_, _open) = metrics.connected::<_, ()>(Ok(())).unwrap();
+        assert_eq!(metrics.snapshot(), MetricsSnapshot::default());
+    }
+
+    #[test]
+    fn test_operation_and_connection_stats() {
+        let metrics = Arc::new(ClientMetrics::new(true));
+        metrics.record("kv.get", true, Duration::from_millis(1));
+        metrics.record("kv.get", false, Duration::from_millis(3));
+        metrics.retry(Retry::ReplicaFallback);
+        let open = metrics.connected::<_, ()>(Ok(())).unwrap();
+        assert!(metrics.connected::<(), _>(Err(())).is_err());
+
+        let snapshot = metrics.snapshot();
+        let get = &snapshot.operations["kv.get"];
+        assert_eq!((get.count, get.errors), (2, 1));
+        assert_eq!(get.max_latency, Duration::from_millis(3));
+        assert_eq!(get.mean_latency(), Duration::from_millis(2));
+        assert_eq!(snapshot.retries.replica_fallbacks, 1);
+        assert_eq!(
+            snapshot.connections,
+            ConnectionStats {
+                opened: 1,
+                failed: 1,
+                open: 1
+            }
+        );
+
+        drop(open);
+        assert_eq!(metrics.snapshot().connections.open, 0);
+    }
+}
@@ -0,0 +1,239 @@
+//! Manager traits, for code that should not depend on a live server
+//!
+//! [`KvOps`], [`QueueOps`] and [`StreamOps`] cover the core operations of
+//! [`KVStore`], [`QueueManager`] and [`StreamManager`], which implement them.
+//! Write application code against the traits and pass it the real managers
+//! in production, and a [`MemoryBackend`](crate::memory::MemoryBackend) or
+//! your own mock in tests.
+//!
+//! The traits are object safe, so `&dyn KvOps` and `Arc<dyn QueueOps>` work
+//! as well as generics. Values are [`serde_json::Value`] rather than generic
+//! parameters for that reason; the managers' own methods stay typed.
+//!
+//! ```no_run
+//! use serde_json::json;
+//! use synap_sdk::{KvOps, Result, SynapClient, SynapConfig};
+//!
+//! async fn remember_login(kv: &dyn KvOps, user: &str) -> Result<i64> {
+//!     kv.set(&format!("last-login:{user}"), json!("now"), Some(3600))
+//!         .await?;
+//!     kv.incr(&format!("logins:{user}")).await
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<()> {
+//! let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+//! remember_login(&client.kv(), "alice").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::kv::KVStore;
+use crate::queue::QueueManager;
+use crate::stream::StreamManager;
+use crate::types::{Event, Message, QueueStats, StreamStats};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Key-value operations
+#[async_trait]
+pub trait KvOps: Send + Sync {
+    /// Set a key, optionally expiring after `ttl` seconds
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<()>;
+
+    /// Get a key's value as stored. Returns `None` if the key doesn't exist
+    /// or has expired.
+    async fn get(&self, key: &str) -> Result<Option<Value>>;
+
+    /// Delete a key. Returns whether it existed.
+    async fn delete(&self, key: &str) -> Result<bool>;
+
+    /// Check if a key exists
+    async fn exists(&self, key: &str) -> Result<bool>;
+
+    /// Increment a numeric value
+    async fn incr(&self, key: &str) -> Result<i64>;
+
+    /// Decrement a numeric value
+    async fn decr(&self, key: &str) -> Result<i64>;
+
+    /// Get all keys matching a glob pattern, or a prefix when it has no
+    /// wildcards
+    async fn keys(&self, pattern: &str) -> Result<Vec<String>>;
+}
+
+/// Message queue operations
+#[async_trait]
+pub trait QueueOps: Send + Sync {
+    /// Create a queue. Creating an existing queue is not an error.
+    async fn create_queue(
+        &self,
+        queue_name: &str,
+        max_depth: Option<usize>,
+        ack_deadline_secs: Option<u64>,
+    ) -> Result<()>;
+
+    /// Publish a message. Returns its ID.
+    async fn publish(
+        &self,
+        queue_name: &str,
+        payload: &[u8],
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+    ) -> Result<String>;
+
+    /// Take the next message, if any, until it is acked or nacked
+    async fn consume(&self, queue_name: &str, consumer_id: &str) -> Result<Option<Message>>;
+
+    /// Acknowledge a message
+    async fn ack(&self, queue_name: &str, message_id: &str) -> Result<()>;
+
+    /// Negative acknowledge a message (requeue)
+    async fn nack(&self, queue_name: &str, message_id: &str) -> Result<()>;
+
+    /// Get queue statistics
+    async fn stats(&self, queue_name: &str) -> Result<QueueStats>;
+
+    /// List all queues
+    async fn list(&self) -> Result<Vec<String>>;
+
+    /// Delete a queue
+    async fn delete_queue(&self, queue_name: &str) -> Result<()>;
+}
+
+/// Event stream operations
+#[async_trait]
+pub trait StreamOps: Send + Sync {
+    /// Create a room, keeping at most `max_events` events
+    async fn create_room(&self, room: &str, max_events: Option<usize>) -> Result<()>;
+
+    /// Publish an event. Returns its offset.
+    async fn publish(&self, room: &str, event: &str, data: Value) -> Result<u64>;
+
+    /// Read up to `limit` events from `offset` on (0 when `None`)
+    async fn consume(
+        &self,
+        room: &str,
+        offset: Option<u64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Event>>;
+
+    /// Get room statistics
+    async fn stats(&self, room: &str) -> Result<StreamStats>;
+
+    /// List all rooms
+    async fn list(&self) -> Result<Vec<String>>;
+
+    /// Delete a room
+    async fn delete_room(&self, room: &str) -> Result<()>;
+}
+
+#[async_trait]
+impl KvOps for KVStore {
+    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<()> {
+        KVStore::set(self, key, value, ttl).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>> {
+        KVStore::get(self, key).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        KVStore::delete(self, key).await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        KVStore::exists(self, key).await
+    }
+
+    async fn incr(&self, key: &str) -> Result<i64> {
+        KVStore::incr(self, key).await
+    }
+
+    async fn decr(&self, key: &str) -> Result<i64> {
+        KVStore::decr(self, key).await
+    }
+
+    async fn keys(&self, pattern: &str) -> Result<Vec<String>> {
+        KVStore::keys(self, pattern).await
+    }
+}
+
+#[async_trait]
+impl QueueOps for QueueManager {
+    async fn create_queue(
+        &self,
+        queue_name: &str,
+        max_depth: Option<usize>,
+        ack_deadline_secs: Option<u64>,
+    ) -> Result<()> {
+        QueueManager::create_queue(self, queue_name, max_depth, ack_deadline_secs).await
+    }
+
+    async fn publish(
+        &self,
+        queue_name: &str,
+        payload: &[u8],
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+    ) -> Result<String> {
+        QueueManager::publish(self, queue_name, payload, priority, max_retries).await
+    }
+
+    async fn consume(&self, queue_name: &str, consumer_id: &str) -> Result<Option<Message>> {
+        QueueManager::consume(self, queue_name, consumer_id).await
+    }
+
+    async fn ack(&self, queue_name: &str, message_id: &str) -> Result<()> {
+        QueueManager::ack(self, queue_name, message_id).await
+    }
+
+    async fn nack(&self, queue_name: &str, message_id: &str) -> Result<()> {
+        QueueManager::nack(self, queue_name, message_id).await
+    }
+
+    async fn stats(&self, queue_name: &str) -> Result<QueueStats> {
+        QueueManager::stats(self, queue_name).await
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        QueueManager::list(self).await
+    }
+
+    async fn delete_queue(&self, queue_name: &str) -> Result<()> {
+        QueueManager::delete_queue(self, queue_name).await
+    }
+}
+
+#[async_trait]
+impl StreamOps for StreamManager {
+    async fn create_room(&self, room: &str, max_events: Option<usize>) -> Result<()> {
+        StreamManager::create_room(self, room, max_events).await
+    }
+
+    async fn publish(&self, room: &str, event: &str, data: Value) -> Result<u64> {
+        StreamManager::publish(self, room, event, data).await
+    }
+
+    async fn consume(
+        &self,
+        room: &str,
+        offset: Option<u64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Event>> {
+        StreamManager::consume(self, room, offset, limit).await
+    }
+
+    async fn stats(&self, room: &str) -> Result<StreamStats> {
+        StreamManager::stats(self, room).await
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        StreamManager::list(self).await
+    }
+
+    async fn delete_room(&self, room: &str) -> Result<()> {
+        StreamManager::delete_room(self, room).await
+    }
+}
@@ -0,0 +1,129 @@
+//! Paginated results as async streams
+//!
+//! Listings that can grow without bound — key scans, big hashes, stream
+//! history — come back as a [`PageStream`], which fetches one page per
+//! request as it is polled. Memory stays bounded by the page size however
+//! large the result; drop the stream to stop early. When a result is known to
+//! be small, [`CollectAll::collect_all`] gathers it into a `Vec`.
+
+use crate::client::SynapClient;
+use crate::error::{Result, SynapError};
+use crate::protocol::Command;
+use futures::{Stream, StreamExt};
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Items requested per page by the paginated APIs
+pub(crate) const PAGE_SIZE: usize = 100;
+
+/// A paginated result, fetched lazily page by page
+pub type PageStream<T> = Pin<Box<dyn Stream<Item = Result<T>> + Send + 'static>>;
+
+/// `MATCH` and `COUNT` for the collection scans (hash, set, sorted set)
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Only yield elements matching this glob (`*`, `?`, `[a-z]`)
+    pub pattern: Option<String>,
+    /// Elements examined per request (default 100). With a pattern a page
+    /// may yield fewer, or none, and the scan still goes on.
+    pub count: Option<usize>,
+}
+
+/// Walk a `*.scan` command's cursor: `scan` builds the command for a cursor
+/// and `page` splits its response into the page's items and the next cursor
+pub(crate) fn scan_stream<C, T, S, F>(client: SynapClient, scan: S, page: F) -> PageStream<T>
+where
+    C: Command + Send + Sync + 'static,
+    C::Response: Send,
+    T: Send + 'static,
+    S: Fn(u64) -> C + Send + 'static,
+    F: Fn(C::Response) -> (Vec<T>, u64) + Send + 'static,
+{
+    Box::pin(async_stream::try_stream! {
+        let mut cursor = 0;
+        loop {
+            let response = client.execute(&scan(cursor)).await?;
+            let (items, next_cursor) = page(response);
+            for item in items {
+                yield item;
+            }
+            cursor = next_cursor;
+            if cursor == 0 {
+                break;
+            }
+        }
+    })
+}
+
+/// A JSON element as text: strings as they are, anything else as JSON
+pub(crate) fn json_text(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+/// Collect a paginated result into memory, with a cap
+pub trait CollectAll<T>: Stream<Item = Result<T>> {
+    /// Collect every item, stopping at the first error
+    ///
+    /// Fails with [`SynapError::LimitExceeded`] as soon as the stream yields
+    /// more than `cap` items, so an unexpectedly large result cannot exhaust
+    /// memory.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{CollectAll, SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let keys = client.kv().scan("session:").collect_all(10_000).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn collect_all(self, cap: usize) -> impl Future<Output = Result<Vec<T>>> + Send;
+}
+
+impl<S, T> CollectAll<T> for S
+where
+    S: Stream<Item = Result<T>> + Send,
+    T: Send,
+{
+    async fn collect_all(self, cap: usize) -> Result<Vec<T>> {
+        let mut stream = std::pin::pin!(self);
+        let mut items = Vec::new();
+        while let Some(item) = stream.next().await {
+            if items.len() == cap {
+                return Err(SynapError::LimitExceeded(cap));
+            }
+            items.push(item?);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    #[tokio::test]
+    async fn test_collect_all_within_cap() {
+        let items = stream::iter((0..3).map(Ok)).collect_all(3).await.unwrap();
+        assert_eq!(items, vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_over_cap() {
+        let result = stream::iter((0..4).map(Ok)).collect_all(3).await;
+        assert!(matches!(result, Err(SynapError::LimitExceeded(3))));
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_stops_at_error() {
+        let items: Vec<Result<i32>> = vec![Ok(1), Err(SynapError::Timeout), Ok(2)];
+        let result = stream::iter(items).collect_all(10).await;
+        assert!(matches!(result, Err(SynapError::Timeout)));
+    }
+}
@@ -0,0 +1,399 @@
+//! Administration commands: `info`, `slowlog.*`, `config.*`, `latency.*`,
+//! `memory.*`, `monitoring.*`, `client.*` and `subscriptions.*`.
+//!
+//! `info`, `memory.doctor` and `monitoring.hotkeys` answer with diagnostic
+//! reports whose sections follow the server's monitoring output; they are
+//! left as JSON.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::command::commands;
+
+/// One slow command
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlowLogEntry {
+    pub id: u64,
+    /// Unix seconds
+    pub timestamp: u64,
+    pub duration_us: u64,
+    pub command: String,
+    pub args: Vec<String>,
+    /// `X-Request-Id` of the HTTP request that ran the command
+    pub request_id: Option<String>,
+}
+
+/// Slow log settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlowLogConfig {
+    /// Log commands taking at least this long; 0 logs every command
+    pub threshold_ms: u64,
+    /// Entries kept, oldest dropped first
+    pub max_entries: usize,
+}
+
+/// Last spike of a latency event
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyLatest {
+    pub event: String,
+    pub timestamp: u64,
+    pub latency_ms: u64,
+    /// Slowest spike since the event was last reset
+    pub max_latency_ms: u64,
+}
+
+/// One spike in a latency event's history
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencySample {
+    /// Unix seconds
+    pub timestamp: u64,
+    pub latency_ms: u64,
+}
+
+/// A connected client
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientInfo {
+    pub id: String,
+    pub addr: String,
+    /// Seconds connected
+    pub age: u64,
+    /// Seconds since the last command
+    pub idle: u64,
+    pub flags: String,
+    pub db: u32,
+    pub sub: usize,
+    pub psub: usize,
+    pub multi: usize,
+    pub qbuf: usize,
+    pub qbuf_free: usize,
+    pub obl: usize,
+    pub oll: usize,
+    pub omem: usize,
+    pub events: String,
+    pub cmd: String,
+}
+
+/// Which commands `client.pause` holds back
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PauseMode {
+    /// Every command
+    #[default]
+    All,
+    /// Writes only; reads keep being served
+    Write,
+}
+
+// ==================== Payloads ====================
+
+/// `info`: server report, one `section` or all of them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Info {
+    /// `server`, `memory`, `stats`, `replication`, `keyspace`, `cluster` or
+    /// `all` (the default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+/// `slowlog.get`: the newest `count` entries, all when unset
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlowlogGet {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// `slowlog.reset`: clear the slow log
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlowlogReset {}
+
+/// `slowlog.len`: entries in the slow log
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlowlogLen {}
+
+/// `slowlog.config.get`: slow log settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlowlogConfigGet {}
+
+/// `slowlog.config.set`: change the settings that are set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlowlogConfigSet {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<usize>,
+}
+
+/// `config.get`: runtime parameters matching a glob `pattern` (default `*`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigGet {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+/// `config.set`: change runtime parameters, e.g.
+/// `{"slowlog.threshold_ms": 5}`; all are checked before any is applied
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSet {
+    pub params: Map<String, Value>,
+}
+
+/// `latency.latest`: last spike of every event
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyLatestEvents {}
+
+/// `latency.history`: recent spikes of one event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyHistory {
+    pub event: String,
+}
+
+/// `latency.reset`: forget `events`, or every event when empty
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyReset {
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// `memory.usage`: estimated size of one key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub key: String,
+}
+
+/// `memory.doctor`: memory report naming the `top` largest keys
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryDoctor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<usize>,
+}
+
+/// `monitoring.hotkeys`: the `top` most accessed keys
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HotKeys {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<usize>,
+}
+
+/// `client.list`: connected clients
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientList {}
+
+/// `client.kill`: disconnect the clients matching `id` and/or `addr`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientKill {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub addr: Option<String>,
+}
+
+/// `client.pause`: hold back commands for `timeout_ms`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientPause {
+    pub timeout_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<PauseMode>,
+}
+
+/// `client.unpause`: end a pause early
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientUnpause {}
+
+/// `subscriptions.list`: live WebSocket subscriptions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionsList {}
+
+// ==================== Responses ====================
+
+/// `slowlog.get` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlowlogGetResponse {
+    pub entries: Vec<SlowLogEntry>,
+    /// Entries in the slow log
+    pub total: usize,
+}
+
+/// `slowlog.reset` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlowlogResetResponse {
+    pub success: bool,
+    pub cleared: usize,
+}
+
+/// `slowlog.len` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlowlogLenResponse {
+    pub len: usize,
+}
+
+/// `config.get` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigGetResponse {
+    /// `None` for parameters whose component is off
+    pub parameters: BTreeMap<String, Option<u64>>,
+    /// Matching parameters changed at runtime
+    pub overridden: Vec<String>,
+}
+
+/// `config.set` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigSetResponse {
+    pub applied: BTreeMap<String, u64>,
+    /// The overrides were written to disk
+    pub persisted: bool,
+}
+
+/// `latency.latest` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyLatestResponse {
+    pub events: Vec<LatencyLatest>,
+}
+
+/// `latency.history` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyHistoryResponse {
+    pub event: String,
+    pub samples: Vec<LatencySample>,
+}
+
+/// `latency.reset` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyResetResponse {
+    pub success: bool,
+    /// Events forgotten
+    pub reset: usize,
+}
+
+/// `memory.usage` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryUsageResponse {
+    pub key: String,
+    pub bytes: usize,
+    pub human: String,
+    #[serde(rename = "type")]
+    pub key_type: String,
+    /// Internal representation, e.g. `packed` or `hashset` for a set
+    pub encoding: String,
+    /// Fields, elements or members held; 1 for a string
+    pub elements: usize,
+    pub avg_item_bytes: usize,
+}
+
+/// `client.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientListResponse {
+    pub clients: Vec<ClientInfo>,
+    pub count: usize,
+}
+
+/// `client.kill` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientKillResponse {
+    pub killed: usize,
+}
+
+/// `client.pause` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientPauseResponse {
+    pub paused: bool,
+    pub mode: PauseMode,
+    pub timeout_ms: u64,
+}
+
+/// `client.unpause` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientUnpauseResponse {
+    /// A pause was in effect
+    pub unpaused: bool,
+}
+
+/// `subscriptions.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubscriptionsListResponse {
+    /// `false` when the server tracks no subscriptions
+    pub enabled: bool,
+    pub count: usize,
+    /// One record per subscription; the fields besides `id` and `kind`
+    /// depend on the kind
+    pub records: Vec<Value>,
+}
+
+commands! {
+    Info => "info", Value;
+    SlowlogGet => "slowlog.get", SlowlogGetResponse;
+    SlowlogReset => "slowlog.reset", SlowlogResetResponse;
+    SlowlogLen => "slowlog.len", SlowlogLenResponse;
+    SlowlogConfigGet => "slowlog.config.get", SlowLogConfig;
+    SlowlogConfigSet => "slowlog.config.set", SlowLogConfig;
+    ConfigGet => "config.get", ConfigGetResponse;
+    ConfigSet => "config.set", ConfigSetResponse;
+    LatencyLatestEvents => "latency.latest", LatencyLatestResponse;
+    LatencyHistory => "latency.history", LatencyHistoryResponse;
+    LatencyReset => "latency.reset", LatencyResetResponse;
+    MemoryUsage => "memory.usage", MemoryUsageResponse;
+    MemoryDoctor => "memory.doctor", Value;
+    HotKeys => "monitoring.hotkeys", Value;
+    ClientList => "client.list", ClientListResponse;
+    ClientKill => "client.kill", ClientKillResponse;
+    ClientPause => "client.pause", ClientPauseResponse;
+    ClientUnpause => "client.unpause", ClientUnpauseResponse;
+    SubscriptionsList => "subscriptions.list", SubscriptionsListResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_memory_usage_type_field() {
+        let usage: MemoryUsageResponse = serde_json::from_value(json!({
+            "key": "k", "bytes": 64, "human": "64B", "type": "set",
+            "encoding": "packed", "elements": 3, "avg_item_bytes": 8
+        }))
+        .unwrap();
+        assert_eq!(usage.key_type, "set");
+    }
+
+    #[test]
+    fn test_client_pause_mode_is_lowercase() {
+        let pause = ClientPause {
+            timeout_ms: 500,
+            mode: Some(PauseMode::Write),
+        };
+        assert_eq!(
+            serde_json::to_value(&pause).unwrap(),
+            json!({"timeout_ms": 500, "mode": "write"})
+        );
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(Info::NAME, "info");
+        assert_eq!(SlowlogConfigSet::NAME, "slowlog.config.set");
+        assert_eq!(HotKeys::NAME, "monitoring.hotkeys");
+        assert_eq!(SubscriptionsList::NAME, "subscriptions.list");
+    }
+}
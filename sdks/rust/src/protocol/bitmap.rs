@@ -0,0 +1,237 @@
+//! `bitmap.*` commands.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// Bitwise operation of `bitmap.bitop`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum BitmapOperation {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+/// Unit of a `bitmap.bitcount`/`bitmap.bitpos` range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BitRangeUnit {
+    /// Offsets index bytes, as in Redis' default
+    Byte,
+    /// Offsets index bits
+    #[default]
+    Bit,
+}
+
+/// One operation of `bitmap.bitfield` on the `width`-bit integer at bit
+/// `offset`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "operation", rename_all = "UPPERCASE")]
+pub enum BitFieldOperation {
+    Get {
+        offset: usize,
+        width: usize,
+        #[serde(default)]
+        signed: bool,
+    },
+    Set {
+        offset: usize,
+        width: usize,
+        #[serde(default)]
+        signed: bool,
+        value: i64,
+    },
+    IncrBy {
+        offset: usize,
+        width: usize,
+        #[serde(default)]
+        signed: bool,
+        increment: i64,
+        /// `WRAP` (the default), `SAT` or `FAIL`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        overflow: Option<String>,
+    },
+}
+
+// ==================== Payloads ====================
+
+/// `bitmap.setbit`: set the bit at `offset` to `value` (0 or 1)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetBit {
+    pub key: String,
+    pub offset: usize,
+    pub value: u8,
+}
+
+/// `bitmap.getbit`: bit at `offset`, 0 past the end
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetBit {
+    pub key: String,
+    pub offset: usize,
+}
+
+/// `bitmap.bitcount`: set bits between `start` (default 0) and `end`
+/// (default -1), both inclusive; negative offsets count from the end
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BitCount {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<i64>,
+    /// Defaults to [`BitRangeUnit::Bit`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<BitRangeUnit>,
+}
+
+/// `bitmap.bitpos`: first bit set to `value` in the range, as for
+/// `bitmap.bitcount`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BitPos {
+    pub key: String,
+    pub value: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<BitRangeUnit>,
+}
+
+/// `bitmap.bitop`: store `operation` over `source_keys` in `destination`;
+/// missing sources count as empty bitmaps
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BitOp {
+    pub destination: String,
+    pub operation: BitmapOperation,
+    pub source_keys: Vec<String>,
+}
+
+/// `bitmap.bitfield`: run `operations` in order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BitField {
+    pub key: String,
+    pub operations: Vec<BitFieldOperation>,
+}
+
+/// `bitmap.stats`: store statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BitmapStats {}
+
+// ==================== Responses ====================
+
+/// `bitmap.setbit` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SetBitResponse {
+    pub old_value: u8,
+}
+
+/// `bitmap.getbit` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GetBitResponse {
+    pub value: u8,
+}
+
+/// `bitmap.bitcount` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BitCountResponse {
+    pub count: usize,
+}
+
+/// `bitmap.bitpos` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BitPosResponse {
+    /// Bit offset from the start of the bitmap; `None` if no bit matches
+    pub position: Option<usize>,
+}
+
+/// `bitmap.bitop` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BitOpResponse {
+    /// Length of the result in bytes; 0 means it was empty and `destination`
+    /// was deleted
+    pub length: usize,
+}
+
+/// `bitmap.bitfield` response, one value per operation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BitFieldResponse {
+    pub results: Vec<i64>,
+}
+
+/// `bitmap.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BitmapStatsResponse {
+    pub total_bitmaps: usize,
+    pub total_bits: usize,
+    pub setbit_count: usize,
+    pub getbit_count: usize,
+    pub bitcount_count: usize,
+    pub bitop_count: usize,
+    pub bitpos_count: usize,
+    pub bitfield_count: usize,
+}
+
+commands! {
+    SetBit => "bitmap.setbit", SetBitResponse;
+    GetBit => "bitmap.getbit", GetBitResponse;
+    BitCount => "bitmap.bitcount", BitCountResponse;
+    BitPos => "bitmap.bitpos", BitPosResponse;
+    BitOp => "bitmap.bitop", BitOpResponse;
+    BitField => "bitmap.bitfield", BitFieldResponse;
+    BitmapStats => "bitmap.stats", BitmapStatsResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_bitop_operation_is_uppercase() {
+        let op = BitOp {
+            destination: "both".into(),
+            operation: BitmapOperation::And,
+            source_keys: vec!["a".into(), "b".into()],
+        };
+        assert_eq!(
+            serde_json::to_value(&op).unwrap(),
+            json!({"destination": "both", "operation": "AND", "source_keys": ["a", "b"]})
+        );
+    }
+
+    #[test]
+    fn test_bitfield_operation_tag() {
+        let op: BitFieldOperation = serde_json::from_value(
+            json!({"operation": "INCRBY", "offset": 0, "width": 8, "increment": 10}),
+        )
+        .unwrap();
+        assert_eq!(
+            op,
+            BitFieldOperation::IncrBy {
+                offset: 0,
+                width: 8,
+                signed: false,
+                increment: 10,
+                overflow: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(BitCount::NAME, "bitmap.bitcount");
+        assert_eq!(BitField::NAME, "bitmap.bitfield");
+        assert_eq!(BitmapStats::NAME, "bitmap.stats");
+    }
+}
@@ -0,0 +1,123 @@
+//! `bf.*` commands: Bloom filters.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// `bf.reserve`: create an empty filter for `capacity` items at `error_rate`
+///
+/// A filter that outgrows its capacity adds a larger layer instead of losing
+/// accuracy, so the capacity only needs to be a rough estimate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloomReserve {
+    pub key: String,
+    /// Chance that a check of an item never added says it exists, e.g. `0.01`
+    pub error_rate: f64,
+    pub capacity: u64,
+}
+
+/// `bf.add`: add one item, creating a filter for 100 items at 1% error when
+/// the key holds none
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloomAdd {
+    pub key: String,
+    pub item: String,
+}
+
+/// `bf.madd`: add several items, creating the filter like `bf.add`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloomMAdd {
+    pub key: String,
+    pub items: Vec<String>,
+}
+
+/// `bf.exists`: check one item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloomExists {
+    pub key: String,
+    pub item: String,
+}
+
+/// `bf.mexists`: check several items
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloomMExists {
+    pub key: String,
+    pub items: Vec<String>,
+}
+
+/// `bf.reserve` response; `false` when the key already held a filter, which
+/// is left as it is
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BloomReserveResponse {
+    pub reserved: bool,
+}
+
+/// `bf.add` response; `false` when the item may have been added before
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BloomAddResponse {
+    pub added: bool,
+}
+
+/// `bf.madd` response, one flag per item as for `bf.add`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BloomMAddResponse {
+    pub added: Vec<bool>,
+}
+
+/// `bf.exists` response; `false` means the item was never added, `true` that
+/// it probably was
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BloomExistsResponse {
+    pub exists: bool,
+}
+
+/// `bf.mexists` response, one flag per item as for `bf.exists`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BloomMExistsResponse {
+    pub exists: Vec<bool>,
+}
+
+commands! {
+    BloomReserve => "bf.reserve", BloomReserveResponse;
+    BloomAdd => "bf.add", BloomAddResponse;
+    BloomMAdd => "bf.madd", BloomMAddResponse;
+    BloomExists => "bf.exists", BloomExistsResponse;
+    BloomMExists => "bf.mexists", BloomMExistsResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_madd_wire_format() {
+        let madd = BloomMAdd {
+            key: "seen".to_string(),
+            items: vec!["a".to_string(), "b".to_string()],
+        };
+        assert_eq!(
+            serde_json::to_value(&madd).unwrap(),
+            json!({"key": "seen", "items": ["a", "b"]})
+        );
+
+        let response: BloomMAddResponse =
+            serde_json::from_value(json!({"added": [true, false]})).unwrap();
+        assert_eq!(response.added, vec![true, false]);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(BloomReserve::NAME, "bf.reserve");
+        assert_eq!(BloomAdd::NAME, "bf.add");
+        assert_eq!(BloomMAdd::NAME, "bf.madd");
+        assert_eq!(BloomExists::NAME, "bf.exists");
+        assert_eq!(BloomMExists::NAME, "bf.mexists");
+    }
+}
@@ -0,0 +1,150 @@
+//! `cluster.*` slot migration commands.
+//!
+//! Slot ranges are inclusive and a missing end means the single slot
+//! `start`.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// Progress of one slot migration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlotMigration {
+    pub slot: u16,
+    pub from_node: String,
+    pub to_node: String,
+    /// `Pending`, `InProgress`, `Complete` or `Failed`
+    pub state: String,
+    pub keys_migrated: usize,
+    /// Estimated
+    pub total_keys: usize,
+    /// Every key has been copied; the migration waits for completion
+    pub copy_done: bool,
+    pub started_at: u64,
+    pub completed_at: Option<u64>,
+}
+
+// ==================== Payloads ====================
+
+/// `cluster.setslot`: set the migration state or owner of a slot range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterSetSlot {
+    pub slot: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_slot: Option<u16>,
+    /// `migrating`, `importing`, `stable` or `node`
+    pub action: String,
+    /// Destination (`migrating`), source (`importing`) or new owner (`node`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+}
+
+/// `cluster.migrate`: migrate a slot range from its owners to `to_node`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterMigrate {
+    pub start: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<u16>,
+    pub to_node: String,
+    /// Also set the migration throttle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_keys_per_sec: Option<u64>,
+}
+
+/// `cluster.migrations`: progress of every slot migration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClusterMigrations {}
+
+/// `cluster.throttle`: limit how fast migrations copy keys
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterThrottle {
+    /// Keys copied per second; 0 is unthrottled
+    pub max_keys_per_sec: u64,
+}
+
+// ==================== Responses ====================
+
+/// `cluster.setslot` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClusterSetSlotResponse {
+    pub success: bool,
+    pub action: String,
+    pub start: u16,
+    pub end: u16,
+    pub slots: usize,
+    pub node_id: Option<String>,
+}
+
+/// `cluster.migrate` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClusterMigrateResponse {
+    pub success: bool,
+    pub start: u16,
+    pub end: u16,
+    pub to_node: String,
+    pub slots_started: usize,
+    /// Slots already owned by `to_node`
+    pub slots_skipped: usize,
+    pub max_keys_per_sec: u64,
+}
+
+/// `cluster.migrations` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClusterMigrationsResponse {
+    pub migrations: Vec<SlotMigration>,
+    pub count: usize,
+    /// Pending or in progress
+    pub active: usize,
+    /// Copied and waiting for completion
+    pub copied: usize,
+    pub keys_migrated: usize,
+    pub total_keys: usize,
+    pub max_keys_per_sec: u64,
+}
+
+/// `cluster.throttle` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClusterThrottleResponse {
+    pub success: bool,
+    pub max_keys_per_sec: u64,
+}
+
+commands! {
+    ClusterSetSlot => "cluster.setslot", ClusterSetSlotResponse;
+    ClusterMigrate => "cluster.migrate", ClusterMigrateResponse;
+    ClusterMigrations => "cluster.migrations", ClusterMigrationsResponse;
+    ClusterThrottle => "cluster.throttle", ClusterThrottleResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_single_slot() {
+        let migrate = ClusterMigrate {
+            start: 42,
+            end: None,
+            to_node: "node-b".into(),
+            max_keys_per_sec: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&migrate).unwrap(),
+            json!({"start": 42, "to_node": "node-b"})
+        );
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(ClusterSetSlot::NAME, "cluster.setslot");
+        assert_eq!(ClusterMigrations::NAME, "cluster.migrations");
+        assert_eq!(ClusterThrottle::NAME, "cluster.throttle");
+    }
+}
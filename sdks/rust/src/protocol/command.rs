@@ -0,0 +1,32 @@
+//! Typed commands.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// A command payload and the response it produces.
+///
+/// The server decodes the payload of a [`Request`](crate::protocol::Request) named
+/// [`NAME`](Command::NAME) into `Self` and answers with `Self::Response`; the
+/// SDK serializes `Self` and decodes `Self::Response`, so a field renamed on
+/// one side fails to compile on the other.
+pub trait Command: Serialize + DeserializeOwned {
+    /// Command name, e.g. `"kv.set"`
+    const NAME: &'static str;
+
+    /// Payload returned on success
+    type Response: Serialize + DeserializeOwned;
+}
+
+/// Implement [`Command`] for a list of `Type => "name", Response` entries.
+macro_rules! commands {
+    ($($command:ty => $name:literal, $response:ty;)+) => {
+        $(
+            impl $crate::protocol::Command for $command {
+                const NAME: &'static str = $name;
+                type Response = $response;
+            }
+        )+
+    };
+}
+
+pub(crate) use commands;
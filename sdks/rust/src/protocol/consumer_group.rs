@@ -0,0 +1,81 @@
+//! `consumer_group.*` commands.
+//!
+//! Consumer groups read partitioned topics; joining, committing and
+//! assignments are still REST-only (`/consumer-groups/...`).
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// How far a group is behind on one partition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionLag {
+    pub partition_id: usize,
+    /// Last published offset
+    pub end_offset: u64,
+    /// `None` until the group commits on this partition
+    #[serde(default)]
+    pub committed_offset: Option<u64>,
+    /// `end_offset - committed_offset`, clamped at 0
+    pub lag: u64,
+}
+
+// ==================== Payloads ====================
+
+/// `consumer_group.lag`: lag of up to `limit` (default 100, at most 1000)
+/// partitions of the group's topic from `offset`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsumerGroupLag {
+    pub group_id: String,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+// ==================== Responses ====================
+
+/// `consumer_group.lag` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsumerGroupLagResponse {
+    pub group_id: String,
+    pub topic: String,
+    pub partitions: Vec<PartitionLag>,
+    /// Partitions of the topic
+    pub total: usize,
+    /// Lag summed over every partition, not just this page
+    pub total_lag: u64,
+    /// Offset of the next page, `None` after the last one
+    pub next_offset: Option<usize>,
+}
+
+commands! {
+    ConsumerGroupLag => "consumer_group.lag", ConsumerGroupLagResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_lag_response_never_committed() {
+        let response: ConsumerGroupLagResponse = serde_json::from_value(json!({
+            "group_id": "billing", "topic": "orders", "total": 2, "total_lag": 7,
+            "next_offset": 1,
+            "partitions": [
+                {"partition_id": 0, "end_offset": 7, "committed_offset": null, "lag": 7}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(response.partitions[0].committed_offset, None);
+        assert_eq!(response.next_offset, Some(1));
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(ConsumerGroupLag::NAME, "consumer_group.lag");
+    }
+}
@@ -0,0 +1,357 @@
+//! Body encodings for `POST /api/v1/command`.
+//!
+//! JSON is the default. MessagePack and CBOR carry the same envelope, chosen
+//! with `Content-Type` (request) and `Accept` (response). Payloads stay
+//! JSON-shaped: on the way out, an array made only of integers `0..=255` is
+//! written as a binary string, and on the way in a binary string is read back
+//! as that array. A 4 KiB queue payload is 4 KiB on the wire instead of
+//! ~15 KiB of comma-separated numbers, and commands need no per-format code.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+use std::fmt;
+
+/// Body encoding of a command request or response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// `application/json`
+    #[default]
+    Json,
+    /// `application/msgpack`
+    MessagePack,
+    /// `application/cbor`
+    Cbor,
+}
+
+/// A body that could not be encoded or decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodingError {
+    /// Encoding of the body
+    pub encoding: Encoding,
+    /// What went wrong
+    pub message: String,
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} body: {}", self.encoding, self.message)
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Json => "JSON",
+            Self::MessagePack => "MessagePack",
+            Self::Cbor => "CBOR",
+        })
+    }
+}
+
+impl Encoding {
+    /// Media type sent in `Content-Type`
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::MessagePack => "application/msgpack",
+            Self::Cbor => "application/cbor",
+        }
+    }
+
+    /// Encoding named by a `Content-Type` value, ignoring parameters such as
+    /// `charset`. `None` for media types Synap does not speak.
+    pub fn from_content_type(value: &str) -> Option<Self> {
+        let media = value.split(';').next().unwrap_or_default().trim();
+        match media.to_ascii_lowercase().as_str() {
+            "application/json" => Some(Self::Json),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Self::MessagePack)
+            }
+            "application/cbor" => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    /// First supported encoding listed in an `Accept` value. Entries with
+    /// `q=0` are skipped; other quality values do not reorder the list.
+    pub fn from_accept(value: &str) -> Option<Self> {
+        value
+            .split(',')
+            .filter(|entry| {
+                !entry.split(';').skip(1).any(|param| {
+                    param
+                        .trim()
+                        .strip_prefix("q=")
+                        .and_then(|q| q.trim().parse::<f32>().ok())
+                        == Some(0.0)
+                })
+            })
+            .find_map(Self::from_content_type)
+    }
+
+    /// Serialize `value` in this encoding
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, EncodingError> {
+        let error = |message: String| EncodingError {
+            encoding: self,
+            message,
+        };
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| error(e.to_string())),
+            Self::MessagePack => {
+                let value = serde_json::to_value(value).map_err(|e| error(e.to_string()))?;
+                let mut body = Vec::new();
+                rmpv::encode::write_value(&mut body, &to_msgpack(value))
+                    .map_err(|e| error(e.to_string()))?;
+                Ok(body)
+            }
+            Self::Cbor => {
+                let value = serde_json::to_value(value).map_err(|e| error(e.to_string()))?;
+                let mut body = Vec::new();
+                ciborium::into_writer(&to_cbor(value), &mut body)
+                    .map_err(|e| error(e.to_string()))?;
+                Ok(body)
+            }
+        }
+    }
+
+    /// Deserialize a body written in this encoding
+    pub fn decode<T: DeserializeOwned>(self, body: &[u8]) -> Result<T, EncodingError> {
+        let error = |message: String| EncodingError {
+            encoding: self,
+            message,
+        };
+        let value = match self {
+            Self::Json => return serde_json::from_slice(body).map_err(|e| error(e.to_string())),
+            Self::MessagePack => {
+                let mut reader = body;
+                let value =
+                    rmpv::decode::read_value(&mut reader).map_err(|e| error(e.to_string()))?;
+                from_msgpack(value).map_err(error)?
+            }
+            Self::Cbor => {
+                let value: ciborium::Value =
+                    ciborium::from_reader(body).map_err(|e| error(e.to_string()))?;
+                from_cbor(value).map_err(error)?
+            }
+        };
+        serde_json::from_value(value).map_err(|e| error(e.to_string()))
+    }
+}
+
+/// The bytes of an array made only of integers `0..=255`, if it is one
+fn as_bytes(items: &[Value]) -> Option<Vec<u8>> {
+    if items.is_empty() {
+        return None;
+    }
+    items
+        .iter()
+        .map(|item| item.as_u64().and_then(|n| u8::try_from(n).ok()))
+        .collect()
+}
+
+fn number(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+fn to_msgpack(value: Value) -> rmpv::Value {
+    match value {
+        Value::Null => rmpv::Value::Nil,
+        Value::Bool(b) => rmpv::Value::Boolean(b),
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => rmpv::Value::from(u),
+            (None, Some(i)) => rmpv::Value::from(i),
+            _ => rmpv::Value::F64(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => rmpv::Value::from(s),
+        Value::Array(items) => match as_bytes(&items) {
+            Some(bytes) => rmpv::Value::Binary(bytes),
+            None => rmpv::Value::Array(items.into_iter().map(to_msgpack).collect()),
+        },
+        Value::Object(map) => rmpv::Value::Map(
+            map.into_iter()
+                .map(|(k, v)| (rmpv::Value::from(k), to_msgpack(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn from_msgpack(value: rmpv::Value) -> Result<Value, String> {
+    Ok(match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(b) => Value::Bool(b),
+        rmpv::Value::Integer(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => Value::from(u),
+            (None, Some(i)) => Value::from(i),
+            _ => return Err(format!("integer out of range: {n}")),
+        },
+        rmpv::Value::F32(f) => number(f64::from(f)),
+        rmpv::Value::F64(f) => number(f),
+        rmpv::Value::String(s) => Value::String(
+            s.into_str()
+                .ok_or_else(|| "string is not valid UTF-8".to_string())?,
+        ),
+        rmpv::Value::Binary(bytes) => Value::Array(bytes.into_iter().map(Value::from).collect()),
+        rmpv::Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(from_msgpack)
+                .collect::<Result<_, _>>()?,
+        ),
+        rmpv::Value::Map(entries) => {
+            let mut map = Map::with_capacity(entries.len());
+            for (k, v) in entries {
+                let rmpv::Value::String(k) = k else {
+                    return Err("map keys must be strings".to_string());
+                };
+                let k = k
+                    .into_str()
+                    .ok_or_else(|| "map key is not valid UTF-8".to_string())?;
+                map.insert(k, from_msgpack(v)?);
+            }
+            Value::Object(map)
+        }
+        rmpv::Value::Ext(kind, _) => return Err(format!("unsupported extension type {kind}")),
+    })
+}
+
+fn to_cbor(value: Value) -> ciborium::Value {
+    match value {
+        Value::Null => ciborium::Value::Null,
+        Value::Bool(b) => ciborium::Value::Bool(b),
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => ciborium::Value::Integer(u.into()),
+            (None, Some(i)) => ciborium::Value::Integer(i.into()),
+            _ => ciborium::Value::Float(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => ciborium::Value::Text(s),
+        Value::Array(items) => match as_bytes(&items) {
+            Some(bytes) => ciborium::Value::Bytes(bytes),
+            None => ciborium::Value::Array(items.into_iter().map(to_cbor).collect()),
+        },
+        Value::Object(map) => ciborium::Value::Map(
+            map.into_iter()
+                .map(|(k, v)| (ciborium::Value::Text(k), to_cbor(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn from_cbor(value: ciborium::Value) -> Result<Value, String> {
+    Ok(match value {
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Bool(b) => Value::Bool(b),
+        ciborium::Value::Integer(n) => {
+            let n = i128::from(n);
+            if let Ok(u) = u64::try_from(n) {
+                Value::from(u)
+            } else if let Ok(i) = i64::try_from(n) {
+                Value::from(i)
+            } else {
+                return Err(format!("integer out of range: {n}"));
+            }
+        }
+        ciborium::Value::Float(f) => number(f),
+        ciborium::Value::Text(s) => Value::String(s),
+        ciborium::Value::Bytes(bytes) => Value::Array(bytes.into_iter().map(Value::from).collect()),
+        ciborium::Value::Tag(_, inner) => from_cbor(*inner)?,
+        ciborium::Value::Array(items) => {
+            Value::Array(items.into_iter().map(from_cbor).collect::<Result<_, _>>()?)
+        }
+        ciborium::Value::Map(entries) => {
+            let mut map = Map::with_capacity(entries.len());
+            for (k, v) in entries {
+                let ciborium::Value::Text(k) = k else {
+                    return Err("map keys must be strings".to_string());
+                };
+                map.insert(k, from_cbor(v)?);
+            }
+            Value::Object(map)
+        }
+        _ => return Err("unsupported CBOR value".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Request;
+    use serde_json::json;
+
+    fn request() -> Request {
+        Request {
+            command: "queue.publish".into(),
+            request_id: "r-1".into(),
+            payload: json!({
+                "queue": "jobs",
+                "payload": (0..=255).collect::<Vec<u32>>(),
+                "priority": 9,
+                "scores": [1.5, -2, 300],
+                "empty": [],
+                "meta": {"retry": null, "ok": true},
+            }),
+            db: 0,
+        }
+    }
+
+    #[test]
+    fn test_binary_encodings_round_trip_the_envelope() {
+        for encoding in [Encoding::Json, Encoding::MessagePack, Encoding::Cbor] {
+            let body = encoding.encode(&request()).unwrap();
+            let decoded: Request = encoding.decode(&body).unwrap();
+            assert_eq!(decoded.command, "queue.publish", "{encoding}");
+            assert_eq!(decoded.request_id, "r-1");
+            assert_eq!(decoded.payload, request().payload, "{encoding}");
+        }
+    }
+
+    #[test]
+    fn test_byte_arrays_travel_as_binary() {
+        let publish = Request::new("queue.publish", json!({"payload": vec![0xAB_u8; 4096]}));
+        let json = Encoding::Json.encode(&publish).unwrap();
+        assert!(json.len() > 4 * 4096);
+        for encoding in [Encoding::MessagePack, Encoding::Cbor] {
+            let body = encoding.encode(&publish).unwrap();
+            assert!(body.len() < 4096 + 128, "{encoding}: {}", body.len());
+        }
+    }
+
+    #[test]
+    fn test_negotiation_headers() {
+        assert_eq!(
+            Encoding::from_content_type("application/json; charset=utf-8"),
+            Some(Encoding::Json)
+        );
+        assert_eq!(
+            Encoding::from_content_type("Application/X-MsgPack"),
+            Some(Encoding::MessagePack)
+        );
+        assert_eq!(Encoding::from_content_type("text/plain"), None);
+        assert_eq!(
+            Encoding::from_accept("text/html, application/cbor;q=0.9, application/json"),
+            Some(Encoding::Cbor)
+        );
+        assert_eq!(
+            Encoding::from_accept("application/msgpack;q=0, application/json"),
+            Some(Encoding::Json)
+        );
+        assert_eq!(Encoding::from_accept("*/*"), None);
+    }
+
+    #[test]
+    fn test_rejects_non_string_map_keys() {
+        let mut body = Vec::new();
+        rmpv::encode::write_value(
+            &mut body,
+            &rmpv::Value::Map(vec![(rmpv::Value::from(1), rmpv::Value::Nil)]),
+        )
+        .unwrap();
+        let err = Encoding::MessagePack.decode::<Value>(&body).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid MessagePack body: map keys must be strings"
+        );
+    }
+}
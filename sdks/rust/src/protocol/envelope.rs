@@ -0,0 +1,258 @@
+//! StreamableHTTP request/response envelope.
+
+use serde::{Deserialize, Serialize};
+
+/// StreamableHTTP request envelope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    /// Command to execute (e.g., "kv.set", "kv.get")
+    pub command: String,
+    /// Unique request identifier. When omitted, the server uses the request's
+    /// `X-Request-Id` so the envelope, logs and WAL all agree.
+    #[serde(default)]
+    pub request_id: String,
+    /// Command payload
+    pub payload: serde_json::Value,
+    /// Logical database the command runs in; 0 when omitted
+    #[serde(default, skip_serializing_if = "is_default_db")]
+    pub db: u32,
+}
+
+fn is_default_db(db: &u32) -> bool {
+    *db == 0
+}
+
+/// StreamableHTTP response envelope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Matching request identifier
+    pub request_id: String,
+    /// Response payload (if successful)
+    pub payload: Option<serde_json::Value>,
+    /// Error message (if failed)
+    pub error: Option<String>,
+    /// Machine-readable form of `error`, for branching without parsing the
+    /// message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_info: Option<ErrorInfo>,
+}
+
+/// Structured description of a failed command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorInfo {
+    /// Stable upper-snake-case code (e.g. `KEY_NOT_FOUND`, `CAS_FAILED`)
+    pub code: String,
+    /// Broad class the code belongs to
+    pub category: ErrorCategory,
+    /// Human-readable message, same text as `Response::error`
+    pub message: String,
+    /// Whether sending the same request again may succeed
+    pub retryable: bool,
+}
+
+/// Broad class of a failed command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// The addressed key, queue, message or resource does not exist
+    NotFound,
+    /// The write lost against current state (key exists, CAS mismatch)
+    Conflict,
+    /// The request itself is malformed or out of range
+    InvalidRequest,
+    /// Missing or rejected credentials, or insufficient permissions
+    Auth,
+    /// A size, memory, quota or rate limit was hit
+    LimitExceeded,
+    /// The server or slot cannot serve the request right now
+    Unavailable,
+    /// The key lives on another cluster node
+    Redirect,
+    /// Unexpected server-side failure
+    Internal,
+}
+
+impl Request {
+    /// Create a new request
+    pub fn new(command: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            command: command.into(),
+            request_id: uuid::Uuid::new_v4().to_string(),
+            payload,
+            db: 0,
+        }
+    }
+
+    /// Run the request in logical database `db`
+    pub fn with_db(mut self, db: u32) -> Self {
+        self.db = db;
+        self
+    }
+}
+
+impl Response {
+    /// Create a successful response
+    pub fn success(request_id: String, payload: serde_json::Value) -> Self {
+        Self {
+            success: true,
+            request_id,
+            payload: Some(payload),
+            error: None,
+            error_info: None,
+        }
+    }
+
+    /// Create an error response
+    pub fn error(request_id: String, error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            request_id,
+            payload: None,
+            error: Some(error.into()),
+            error_info: None,
+        }
+    }
+
+    /// Create an error response carrying a structured [`ErrorInfo`]
+    pub fn failure(request_id: String, info: ErrorInfo) -> Self {
+        Self {
+            success: false,
+            request_id,
+            payload: None,
+            error: Some(info.message.clone()),
+            error_info: Some(info),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_new() {
+        let payload = json!({"key": "value"});
+        let request = Request::new("kv.set", payload.clone());
+
+        assert_eq!(request.command, "kv.set");
+        assert_eq!(request.payload, payload);
+        assert!(!request.request_id.is_empty());
+    }
+
+    #[test]
+    fn test_request_serialization() {
+        let payload = json!({"key": "test", "value": "data"});
+        let request = Request::new("kv.get", payload);
+
+        let serialized = serde_json::to_string(&request).unwrap();
+        let deserialized: Request = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(request.command, deserialized.command);
+        assert_eq!(request.request_id, deserialized.request_id);
+        assert_eq!(request.payload, deserialized.payload);
+    }
+
+    #[test]
+    fn test_request_db_defaults_to_zero() {
+        let request: Request =
+            serde_json::from_value(json!({"command": "kv.get", "payload": {}})).unwrap();
+        assert_eq!(request.db, 0);
+
+        let serialized = serde_json::to_value(Request::new("kv.get", json!({}))).unwrap();
+        assert!(serialized.get("db").is_none());
+        let serialized =
+            serde_json::to_value(Request::new("kv.get", json!({})).with_db(3)).unwrap();
+        assert_eq!(serialized["db"], 3);
+    }
+
+    #[test]
+    fn test_response_success() {
+        let request_id = "req-123".to_string();
+        let payload = json!({"result": "ok"});
+        let response = Response::success(request_id.clone(), payload.clone());
+
+        assert!(response.success);
+        assert_eq!(response.request_id, request_id);
+        assert_eq!(response.payload, Some(payload));
+        assert_eq!(response.error, None);
+    }
+
+    #[test]
+    fn test_response_error() {
+        let request_id = "req-456".to_string();
+        let error_msg = "Something went wrong";
+        let response = Response::error(request_id.clone(), error_msg);
+
+        assert!(!response.success);
+        assert_eq!(response.request_id, request_id);
+        assert_eq!(response.payload, None);
+        assert_eq!(response.error, Some(error_msg.to_string()));
+    }
+
+    #[test]
+    fn test_response_serialization() {
+        let response = Response::success("req-789".to_string(), json!({"data": "test"}));
+
+        let serialized = serde_json::to_string(&response).unwrap();
+        let deserialized: Response = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(response.success, deserialized.success);
+        assert_eq!(response.request_id, deserialized.request_id);
+        assert_eq!(response.payload, deserialized.payload);
+        assert_eq!(response.error, deserialized.error);
+    }
+
+    #[test]
+    fn test_response_failure_carries_error_info() {
+        let info = ErrorInfo {
+            code: "KEY_NOT_FOUND".to_string(),
+            category: ErrorCategory::NotFound,
+            message: "Key not found: k".to_string(),
+            retryable: false,
+        };
+        let response = Response::failure("req-1".to_string(), info.clone());
+        assert!(!response.success);
+        assert_eq!(response.error.as_deref(), Some("Key not found: k"));
+
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value["error_info"]["code"], "KEY_NOT_FOUND");
+        assert_eq!(value["error_info"]["category"], "not_found");
+        assert_eq!(value["error_info"]["retryable"], false);
+
+        let decoded: Response = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.error_info, Some(info));
+    }
+
+    #[test]
+    fn test_response_without_error_info() {
+        let value = serde_json::to_value(Response::error("req-2".to_string(), "boom")).unwrap();
+        assert!(value.get("error_info").is_none());
+
+        let decoded: Response = serde_json::from_value(
+            json!({"success": false, "request_id": "r", "payload": null, "error": "boom"}),
+        )
+        .unwrap();
+        assert!(decoded.error_info.is_none());
+    }
+
+    #[test]
+    fn test_request_unique_ids() {
+        let req1 = Request::new("test", json!({}));
+        let req2 = Request::new("test", json!({}));
+
+        // Each request should have a unique ID
+        assert_ne!(req1.request_id, req2.request_id);
+    }
+
+    #[test]
+    fn test_response_error_string_conversion() {
+        let response = Response::error("req-1".to_string(), String::from("error"));
+        assert_eq!(response.error, Some("error".to_string()));
+
+        let response = Response::error("req-2".to_string(), "error");
+        assert_eq!(response.error, Some("error".to_string()));
+    }
+}
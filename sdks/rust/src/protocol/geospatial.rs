@@ -0,0 +1,267 @@
+//! `geospatial.*` commands.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// Distance unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DistanceUnit {
+    #[default]
+    #[serde(rename = "m")]
+    Meters,
+    #[serde(rename = "km")]
+    Kilometers,
+    #[serde(rename = "mi")]
+    Miles,
+    #[serde(rename = "ft")]
+    Feet,
+}
+
+/// Location coordinate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub lat: f64,
+    pub lon: f64,
+    pub member: String,
+}
+
+/// Coordinate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Coordinate {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Georadius result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoradiusResult {
+    pub member: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coord: Option<Coordinate>,
+}
+
+// ==================== Payloads ====================
+
+/// `geospatial.geoadd`: add or move `locations`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoAdd {
+    pub key: String,
+    pub locations: Vec<Location>,
+    /// Only add new members
+    #[serde(default)]
+    pub nx: bool,
+    /// Only move existing members
+    #[serde(default)]
+    pub xx: bool,
+    /// Count moved members as well as new ones
+    #[serde(default)]
+    pub ch: bool,
+}
+
+/// `geospatial.geodist`: distance between two members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoDist {
+    pub key: String,
+    pub member1: String,
+    pub member2: String,
+    #[serde(default)]
+    pub unit: DistanceUnit,
+}
+
+/// `geospatial.georadius`: members within `radius` of a point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoRadius {
+    pub key: String,
+    pub center_lat: f64,
+    pub center_lon: f64,
+    pub radius: f64,
+    #[serde(default)]
+    pub unit: DistanceUnit,
+    #[serde(default)]
+    pub with_dist: bool,
+    #[serde(default)]
+    pub with_coord: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    /// `ASC` or `DESC` by distance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+}
+
+/// `geospatial.georadiusbymember`: members within `radius` of `member`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoRadiusByMember {
+    pub key: String,
+    pub member: String,
+    pub radius: f64,
+    #[serde(default)]
+    pub unit: DistanceUnit,
+    #[serde(default)]
+    pub with_dist: bool,
+    #[serde(default)]
+    pub with_coord: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+}
+
+/// `geospatial.geopos`: coordinates of `members`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoPos {
+    pub key: String,
+    pub members: Vec<String>,
+}
+
+/// `geospatial.geohash`: geohash strings of `members`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoHash {
+    pub key: String,
+    pub members: Vec<String>,
+}
+
+/// `geospatial.geosearch`: members inside a circle or box around a member
+/// or a point; one of `from_member`/`from_lonlat` and one of
+/// `by_radius`/`by_box` must be set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoSearch {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_member: Option<String>,
+    /// `(lon, lat)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_lonlat: Option<(f64, f64)>,
+    /// `(radius, unit)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_radius: Option<(f64, DistanceUnit)>,
+    /// `(width, height, unit)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by_box: Option<(f64, f64, DistanceUnit)>,
+    #[serde(default)]
+    pub with_dist: bool,
+    #[serde(default)]
+    pub with_coord: bool,
+    /// Not supported by the store yet
+    #[serde(default)]
+    pub with_hash: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+}
+
+/// `geospatial.stats`: store statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeospatialStats {}
+
+// ==================== Responses ====================
+
+/// `geospatial.geoadd` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeoAddResponse {
+    /// New members, plus moved ones with `ch`
+    pub added: usize,
+}
+
+/// `geospatial.geodist` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeoDistResponse {
+    /// `None` if either member is missing
+    pub distance: Option<f64>,
+}
+
+/// `geospatial.georadius`, `geospatial.georadiusbymember` and
+/// `geospatial.geosearch` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeoResultsResponse {
+    pub results: Vec<GeoradiusResult>,
+}
+
+/// `geospatial.geopos` response, `None` for each missing member
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeoPosResponse {
+    pub coordinates: Vec<Option<Coordinate>>,
+}
+
+/// `geospatial.geohash` response, `None` for each missing member
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeoHashResponse {
+    pub geohashes: Vec<Option<String>>,
+}
+
+/// `geospatial.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeospatialStatsResponse {
+    pub total_keys: usize,
+    pub total_locations: usize,
+    pub geoadd_count: usize,
+    pub geodist_count: usize,
+    pub georadius_count: usize,
+    pub geopos_count: usize,
+    pub geohash_count: usize,
+}
+
+commands! {
+    GeoAdd => "geospatial.geoadd", GeoAddResponse;
+    GeoDist => "geospatial.geodist", GeoDistResponse;
+    GeoRadius => "geospatial.georadius", GeoResultsResponse;
+    GeoRadiusByMember => "geospatial.georadiusbymember", GeoResultsResponse;
+    GeoPos => "geospatial.geopos", GeoPosResponse;
+    GeoHash => "geospatial.geohash", GeoHashResponse;
+    GeoSearch => "geospatial.geosearch", GeoResultsResponse;
+    GeospatialStats => "geospatial.stats", GeospatialStatsResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_geosearch_shapes_are_arrays() {
+        let search = GeoSearch {
+            key: "places".into(),
+            from_member: None,
+            from_lonlat: Some((13.4, 52.5)),
+            by_radius: None,
+            by_box: Some((2.0, 1.0, DistanceUnit::Kilometers)),
+            with_dist: false,
+            with_coord: false,
+            with_hash: false,
+            count: None,
+            sort: None,
+        };
+        let value = serde_json::to_value(&search).unwrap();
+        assert_eq!(value["from_lonlat"], json!([13.4, 52.5]));
+        assert_eq!(value["by_box"], json!([2.0, 1.0, "km"]));
+        assert!(value.get("by_radius").is_none());
+    }
+
+    #[test]
+    fn test_geopos_response_keeps_missing_members() {
+        let pos: GeoPosResponse = serde_json::from_value(json!({
+            "key": "places",
+            "coordinates": [{"lat": 52.5, "lon": 13.4}, null]
+        }))
+        .unwrap();
+        assert_eq!(pos.coordinates.len(), 2);
+        assert!(pos.coordinates[1].is_none());
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(GeoRadiusByMember::NAME, "geospatial.georadiusbymember");
+        assert_eq!(GeoSearch::NAME, "geospatial.geosearch");
+        assert_eq!(GeospatialStats::NAME, "geospatial.stats");
+    }
+}
@@ -0,0 +1,311 @@
+//! `hash.*` commands.
+//!
+//! Field values are JSON: the SDK stores strings, and the server answers with
+//! whatever JSON each field holds.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+use super::kv::SuccessResponse;
+
+// ==================== Payloads ====================
+
+/// `hash.set`: set `field` of the hash at `key`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HSet {
+    pub key: String,
+    pub field: String,
+    pub value: Value,
+    /// Queue the write in this client's open transaction, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `hash.get`: read one field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HGet {
+    pub key: String,
+    pub field: String,
+}
+
+/// `hash.getall`: read every field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HGetAll {
+    pub key: String,
+}
+
+/// `hash.scan`: up to `count` (default 100) fields after `cursor` (0 to
+/// start), keeping those that match the `pattern` glob
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HScan {
+    pub key: String,
+    pub cursor: u64,
+    #[serde(rename = "match", default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// `hash.del`: delete fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HDel {
+    pub key: String,
+    pub fields: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `hash.exists`: check whether a field exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HExists {
+    pub key: String,
+    pub field: String,
+}
+
+/// `hash.len`: number of fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HLen {
+    pub key: String,
+}
+
+/// `hash.keys`: every field name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HKeys {
+    pub key: String,
+}
+
+/// `hash.vals`: every field value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HVals {
+    pub key: String,
+}
+
+/// `hash.mset`: set several fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HMSet {
+    pub key: String,
+    pub fields: HashMap<String, Value>,
+}
+
+/// `hash.mget`: read several fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HMGet {
+    pub key: String,
+    pub fields: Vec<String>,
+}
+
+/// `hash.incrby`: add `increment` to an integer field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HIncrBy {
+    pub key: String,
+    pub field: String,
+    pub increment: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `hash.incrbyfloat`: add `increment` to a numeric field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HIncrByFloat {
+    pub key: String,
+    pub field: String,
+    pub increment: f64,
+}
+
+/// `hash.setnx`: set a field only if it does not exist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HSetNx {
+    pub key: String,
+    pub field: String,
+    pub value: Value,
+}
+
+/// `hash.stats`: store statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HashStats {}
+
+// ==================== Responses ====================
+
+/// `hash.set` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HSetResponse {
+    /// The field did not exist before
+    pub created: bool,
+    /// The write was queued in a transaction
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `hash.get` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HGetResponse {
+    pub found: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+/// `hash.getall` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HGetAllResponse {
+    pub fields: HashMap<String, Value>,
+    pub count: usize,
+}
+
+/// `hash.scan` response; `next_cursor` is 0 on the last page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HScanResponse {
+    pub fields: BTreeMap<String, Value>,
+    pub count: usize,
+    pub next_cursor: u64,
+}
+
+/// `hash.del` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HDelResponse {
+    pub deleted: usize,
+    /// The delete was queued in a transaction; `deleted` is not known yet
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `hash.exists` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HExistsResponse {
+    pub exists: bool,
+}
+
+/// `hash.len` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HLenResponse {
+    pub length: usize,
+}
+
+/// `hash.keys` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HKeysResponse {
+    pub keys: Vec<String>,
+    pub count: usize,
+}
+
+/// `hash.vals` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HValsResponse {
+    pub values: Vec<Value>,
+    pub count: usize,
+}
+
+/// `hash.mget` response, one entry per requested field
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HMGetResponse {
+    pub values: Vec<Option<Value>>,
+}
+
+/// `hash.incrby` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HIncrByResponse {
+    pub value: i64,
+    /// The update was queued in a transaction; `value` is not known yet
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `hash.incrbyfloat` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HIncrByFloatResponse {
+    pub value: f64,
+}
+
+/// `hash.setnx` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HSetNxResponse {
+    pub created: bool,
+}
+
+/// Operation counters in [`HashStatsResponse`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HashOperationStats {
+    pub hset_count: u64,
+    pub hget_count: u64,
+    pub hdel_count: u64,
+}
+
+/// `hash.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HashStatsResponse {
+    pub total_hashes: usize,
+    pub total_fields: usize,
+    pub operations: HashOperationStats,
+}
+
+commands! {
+    HSet => "hash.set", HSetResponse;
+    HGet => "hash.get", HGetResponse;
+    HGetAll => "hash.getall", HGetAllResponse;
+    HScan => "hash.scan", HScanResponse;
+    HDel => "hash.del", HDelResponse;
+    HExists => "hash.exists", HExistsResponse;
+    HLen => "hash.len", HLenResponse;
+    HKeys => "hash.keys", HKeysResponse;
+    HVals => "hash.vals", HValsResponse;
+    HMSet => "hash.mset", SuccessResponse;
+    HMGet => "hash.mget", HMGetResponse;
+    HIncrBy => "hash.incrby", HIncrByResponse;
+    HIncrByFloat => "hash.incrbyfloat", HIncrByFloatResponse;
+    HSetNx => "hash.setnx", HSetNxResponse;
+    HashStats => "hash.stats", HashStatsResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_scan_pattern_is_sent_as_match() {
+        let scan = HScan {
+            key: "user:1".into(),
+            cursor: 0,
+            pattern: Some("addr:*".into()),
+            count: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&scan).unwrap(),
+            json!({"key": "user:1", "cursor": 0, "match": "addr:*"})
+        );
+    }
+
+    #[test]
+    fn test_get_response() {
+        let found: HGetResponse =
+            serde_json::from_value(json!({"found": true, "value": "Alice"})).unwrap();
+        assert_eq!(found.value, Some(json!("Alice")));
+        let missing: HGetResponse = serde_json::from_value(json!({"found": false})).unwrap();
+        assert_eq!(missing.value, None);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(HVals::NAME, "hash.vals");
+        assert_eq!(HMSet::NAME, "hash.mset");
+        assert_eq!(HashStats::NAME, "hash.stats");
+    }
+}
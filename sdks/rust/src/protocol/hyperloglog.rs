@@ -0,0 +1,98 @@
+//! `hyperloglog.*` commands.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+// ==================== Payloads ====================
+
+/// `hyperloglog.pfadd`: add `elements`, creating the HyperLogLog if needed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PfAdd {
+    pub key: String,
+    /// Raw element bytes; the server also accepts strings
+    pub elements: Vec<Vec<u8>>,
+    /// Expire the key this many seconds after the write
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+}
+
+/// `hyperloglog.pfcount`: estimated cardinality, 0 for a missing key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PfCount {
+    pub key: String,
+}
+
+/// `hyperloglog.pfmerge`: merge `sources` into `destination`, which keeps its
+/// own elements; missing sources count as empty
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PfMerge {
+    pub destination: String,
+    pub sources: Vec<String>,
+}
+
+/// `hyperloglog.stats`: store statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HyperLogLogStats {}
+
+// ==================== Responses ====================
+
+/// `hyperloglog.pfadd` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PfAddResponse {
+    pub added: usize,
+}
+
+/// `hyperloglog.pfcount` and `hyperloglog.pfmerge` response; for a merge,
+/// the destination's cardinality afterwards
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PfCountResponse {
+    pub count: u64,
+}
+
+/// `hyperloglog.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HyperLogLogStatsResponse {
+    pub total_hlls: u64,
+    pub total_cardinality: u64,
+    pub pfadd_count: u64,
+    pub pfcount_count: u64,
+    pub pfmerge_count: u64,
+}
+
+commands! {
+    PfAdd => "hyperloglog.pfadd", PfAddResponse;
+    PfCount => "hyperloglog.pfcount", PfCountResponse;
+    PfMerge => "hyperloglog.pfmerge", PfCountResponse;
+    HyperLogLogStats => "hyperloglog.stats", HyperLogLogStatsResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_pfadd_elements_are_byte_arrays() {
+        let add = PfAdd {
+            key: "visitors".into(),
+            elements: vec![b"ab".to_vec()],
+            ttl_secs: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&add).unwrap(),
+            json!({"key": "visitors", "elements": [[97, 98]]})
+        );
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(PfAdd::NAME, "hyperloglog.pfadd");
+        assert_eq!(PfMerge::NAME, "hyperloglog.pfmerge");
+        assert_eq!(HyperLogLogStats::NAME, "hyperloglog.stats");
+    }
+}
@@ -0,0 +1,596 @@
+//! `kv.*` and `key.*` commands.
+//!
+//! Response fields the binary transports leave out decode as their defaults,
+//! so the same types work whichever transport the SDK is using.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Key/value pair for the multi-key set commands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvPair {
+    pub key: String,
+    pub value: Value,
+}
+
+/// How `kv.get` returns a stored value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueFormat {
+    /// Array of byte values
+    Bytes,
+    /// UTF-8 string (the default; unknown formats fall back to it)
+    #[default]
+    #[serde(other)]
+    String,
+}
+
+// ==================== Payloads ====================
+
+/// `kv.set`: store `value` under `key`, optionally expiring after `ttl` seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvSet {
+    pub key: String,
+    /// Strings are stored as raw UTF-8, anything else JSON-encoded
+    pub value: Value,
+    /// Seconds until expiry; sent as `null` when unset, as the SDKs always have
+    #[serde(default)]
+    pub ttl: Option<u64>,
+    /// Queue the write in this client's open transaction, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `kv.get`: read a value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvGet {
+    pub key: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<ValueFormat>,
+}
+
+/// `kv.del`: delete a key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvDel {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `kv.exists`: check whether a key exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvExists {
+    pub key: String,
+}
+
+/// `kv.incr`: add `amount` (default 1) to a numeric value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvIncr {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `kv.decr`: subtract `amount` (default 1) from a numeric value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvDecr {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `kv.mset`: set several keys
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvMset {
+    pub pairs: Vec<KvPair>,
+}
+
+/// `kv.mget`: read several keys
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvMget {
+    pub keys: Vec<String>,
+}
+
+/// `kv.mdel`: delete several keys
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvMdel {
+    pub keys: Vec<String>,
+}
+
+/// `kv.scan`: list up to `limit` (default 100) keys starting with `prefix`,
+/// in key order, after `cursor` (the previous page's `next_cursor`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvScan {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// `kv.keys`: list every key, or those starting with `prefix` (deprecated
+/// in favour of `kv.scan`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvKeys {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
+/// `kv.stats`: store statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvStats {}
+
+/// `kv.dbsize`: number of keys
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvDbSize {}
+
+/// `kv.flushdb`: delete every key in the current database
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvFlushDb {}
+
+/// `kv.flushall`: delete every key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KvFlushAll {}
+
+/// `kv.expire`: expire a key after `ttl` seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvExpire {
+    pub key: String,
+    pub ttl: u64,
+}
+
+/// `kv.ttl`: seconds until a key expires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvTtl {
+    pub key: String,
+}
+
+/// `kv.persist`: remove a key's expiry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvPersist {
+    pub key: String,
+}
+
+/// `kv.append`: append to a string value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvAppend {
+    pub key: String,
+    pub value: Value,
+}
+
+/// `kv.getrange`: substring between byte offsets `start` and `end`
+/// (inclusive, negative counts from the end)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvGetRange {
+    pub key: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// `kv.setrange`: overwrite a string value from byte `offset`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvSetRange {
+    pub key: String,
+    pub offset: usize,
+    pub value: Value,
+}
+
+/// `kv.strlen`: length of a string value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvStrlen {
+    pub key: String,
+}
+
+/// `kv.getset`: set a value and return the previous one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvGetSet {
+    pub key: String,
+    pub value: Value,
+}
+
+/// `kv.msetnx`: set several keys only if none of them exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvMsetNx {
+    pub pairs: Vec<KvPair>,
+}
+
+/// `key.type`: type of the value stored under a key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyType {
+    pub key: String,
+}
+
+/// `key.exists`: check whether a key exists in any store
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyExists {
+    pub key: String,
+}
+
+/// `key.rename`: rename a key, overwriting the destination
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRename {
+    pub source: String,
+    pub destination: String,
+}
+
+/// `key.renamenx`: rename a key unless the destination exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRenameNx {
+    pub source: String,
+    pub destination: String,
+}
+
+/// `key.copy`: copy a key, overwriting the destination only with `replace`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyCopy {
+    pub source: String,
+    pub destination: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub replace: bool,
+}
+
+/// `key.randomkey`: a random key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyRandom {}
+
+/// `key.dump`: serialize a key of any type, with its TTL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyDump {
+    pub key: String,
+}
+
+/// `key.restore`: create a key from a `key.dump` payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRestore {
+    pub key: String,
+    pub payload: Vec<u8>,
+    /// TTL in milliseconds; `0` for none. Without it the payload's TTL is kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+    /// Overwrite the key if it exists
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub replace: bool,
+    /// `ttl` is a Unix timestamp in milliseconds
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub absttl: bool,
+}
+
+// ==================== Responses ====================
+
+/// Answer to a write queued in a transaction instead of executed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedResponse {
+    pub success: bool,
+    pub queued: bool,
+}
+
+impl QueuedResponse {
+    pub const QUEUED: Self = Self {
+        success: true,
+        queued: true,
+    };
+}
+
+/// `kv.set` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvSetResponse {
+    pub success: bool,
+    /// The write was queued in a transaction
+    #[serde(skip_serializing_if = "is_false")]
+    pub queued: bool,
+}
+
+/// `kv.del` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvDelResponse {
+    pub deleted: bool,
+    /// The delete was queued in a transaction; `deleted` is not known yet
+    #[serde(skip_serializing_if = "is_false")]
+    pub queued: bool,
+}
+
+/// `kv.exists` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvExistsResponse {
+    pub exists: bool,
+}
+
+/// `kv.incr` / `kv.decr` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvCounterResponse {
+    pub value: i64,
+    /// The update was queued in a transaction; `value` is not known yet
+    #[serde(skip_serializing_if = "is_false")]
+    pub queued: bool,
+}
+
+/// Response of commands that only report success
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SuccessResponse {
+    pub success: bool,
+}
+
+/// `kv.mget` response, one entry per requested key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvMgetResponse {
+    pub values: Vec<Option<Value>>,
+}
+
+/// `kv.mdel` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvMdelResponse {
+    pub deleted: usize,
+}
+
+/// `kv.scan` / `kv.keys` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyListResponse {
+    pub keys: Vec<String>,
+    pub count: usize,
+    /// `kv.scan` only: where the next page starts, `None` on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Operation counters in [`KvStatsResponse`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvOperationStats {
+    pub gets: u64,
+    pub sets: u64,
+    pub dels: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Eviction counters in [`KvStatsResponse`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvEvictionStats {
+    /// Keys removed to stay under `max_memory_mb`
+    pub evicted_keys: u64,
+    /// Bytes freed by those evictions
+    pub evicted_bytes: u64,
+    /// Writes refused because nothing could be evicted
+    pub rejected_writes: u64,
+}
+
+/// Value compression counters in [`KvStatsResponse`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvCompressionStats {
+    /// Values written since startup
+    pub values: u64,
+    /// Of those, values stored compressed
+    pub compressed_values: u64,
+    /// Bytes written before compression
+    pub raw_bytes: u64,
+    /// Bytes actually stored
+    pub stored_bytes: u64,
+    /// `raw_bytes / stored_bytes`
+    pub ratio: f64,
+}
+
+/// `kv.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KvStatsResponse {
+    pub total_keys: u64,
+    pub total_memory_bytes: u64,
+    pub operations: KvOperationStats,
+    pub hit_rate: f64,
+    pub evictions: KvEvictionStats,
+    /// Present when value compression is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<KvCompressionStats>,
+}
+
+/// `kv.dbsize` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DbSizeResponse {
+    pub size: usize,
+}
+
+/// `kv.flushdb` / `kv.flushall` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlushResponse {
+    pub flushed: usize,
+}
+
+/// `kv.expire` / `kv.persist` response: whether the key's expiry changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpiryResponse {
+    pub result: bool,
+}
+
+/// `kv.ttl` response; `None` when the key has no expiry or does not exist
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TtlResponse {
+    pub ttl: Option<u64>,
+}
+
+/// `kv.append` / `kv.setrange` / `kv.strlen` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LengthResponse {
+    pub length: usize,
+}
+
+/// `key.type` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyTypeResponse {
+    pub key: String,
+    /// `"string"`, `"hash"`, `"list"`, `"set"`, `"zset"` or `"none"`
+    #[serde(rename = "type")]
+    pub key_type: String,
+}
+
+/// `key.exists` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyExistsResponse {
+    pub key: String,
+    pub exists: bool,
+}
+
+/// `key.rename` / `key.renamenx` / `key.copy` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyMoveResponse {
+    pub success: bool,
+    pub source: String,
+    pub destination: String,
+}
+
+/// `key.dump` response; `payload` is `None` when the key does not exist
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyDumpResponse {
+    pub key: String,
+    pub payload: Option<Vec<u8>>,
+}
+
+/// `key.randomkey` response; `None` when the database is empty
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RandomKeyResponse {
+    pub key: Option<String>,
+}
+
+commands! {
+    KvSet => "kv.set", KvSetResponse;
+    // `null` when the key does not exist
+    KvGet => "kv.get", Option<Value>;
+    KvDel => "kv.del", KvDelResponse;
+    KvExists => "kv.exists", KvExistsResponse;
+    KvIncr => "kv.incr", KvCounterResponse;
+    KvDecr => "kv.decr", KvCounterResponse;
+    KvMset => "kv.mset", SuccessResponse;
+    KvMget => "kv.mget", KvMgetResponse;
+    KvMdel => "kv.mdel", KvMdelResponse;
+    KvScan => "kv.scan", KeyListResponse;
+    KvKeys => "kv.keys", KeyListResponse;
+    KvStats => "kv.stats", KvStatsResponse;
+    KvDbSize => "kv.dbsize", DbSizeResponse;
+    KvFlushDb => "kv.flushdb", FlushResponse;
+    KvFlushAll => "kv.flushall", FlushResponse;
+    KvExpire => "kv.expire", ExpiryResponse;
+    KvTtl => "kv.ttl", TtlResponse;
+    KvPersist => "kv.persist", ExpiryResponse;
+    KvAppend => "kv.append", LengthResponse;
+    // `null` when the range is empty
+    KvGetRange => "kv.getrange", Option<String>;
+    KvSetRange => "kv.setrange", LengthResponse;
+    KvStrlen => "kv.strlen", LengthResponse;
+    // Previous value, `null` when the key was unset
+    KvGetSet => "kv.getset", Option<String>;
+    KvMsetNx => "kv.msetnx", SuccessResponse;
+    KeyType => "key.type", KeyTypeResponse;
+    KeyExists => "key.exists", KeyExistsResponse;
+    KeyRename => "key.rename", KeyMoveResponse;
+    KeyRenameNx => "key.renamenx", KeyMoveResponse;
+    KeyCopy => "key.copy", KeyMoveResponse;
+    KeyRandom => "key.randomkey", RandomKeyResponse;
+    KeyDump => "key.dump", KeyDumpResponse;
+    KeyRestore => "key.restore", SuccessResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_optional_fields() {
+        let set = KvSet {
+            key: "user:1".into(),
+            value: json!("Alice"),
+            ttl: None,
+            client_id: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&set).unwrap(),
+            json!({"key": "user:1", "value": "Alice", "ttl": null})
+        );
+
+        let decoded: KvSet =
+            serde_json::from_value(json!({"key": "k", "value": 1, "ttl": 60})).unwrap();
+        assert_eq!(decoded.ttl, Some(60));
+        assert_eq!(decoded.client_id, None);
+    }
+
+    #[test]
+    fn test_missing_required_field_is_rejected() {
+        let err = serde_json::from_value::<KvSet>(json!({"key": "k"})).unwrap_err();
+        assert!(err.to_string().contains("missing field `value`"));
+    }
+
+    #[test]
+    fn test_get_format() {
+        let get: KvGet = serde_json::from_value(json!({"key": "k", "type": "bytes"})).unwrap();
+        assert_eq!(get.format, Some(ValueFormat::Bytes));
+        // Unknown formats fall back to strings, as the server always did
+        let get: KvGet = serde_json::from_value(json!({"key": "k", "type": "hex"})).unwrap();
+        assert_eq!(get.format, Some(ValueFormat::String));
+    }
+
+    #[test]
+    fn test_queued_flag_only_sent_when_set() {
+        assert_eq!(
+            serde_json::to_value(KvSetResponse {
+                success: true,
+                queued: false
+            })
+            .unwrap(),
+            json!({"success": true})
+        );
+        // A queued answer decodes into the command's own response type
+        let del: KvDelResponse =
+            serde_json::from_value(serde_json::to_value(QueuedResponse::QUEUED).unwrap()).unwrap();
+        assert!(del.queued);
+        assert!(!del.deleted);
+    }
+
+    #[test]
+    fn test_responses_tolerate_missing_fields() {
+        // The binary transports answer `kv.set` with `{}` and `kv.keys`
+        // without a count
+        let set: KvSetResponse = serde_json::from_value(json!({})).unwrap();
+        assert!(!set.success);
+        let keys: KeyListResponse = serde_json::from_value(json!({"keys": ["a", "b"]})).unwrap();
+        assert_eq!(keys.keys, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(KvSet::NAME, "kv.set");
+        assert_eq!(KvGetRange::NAME, "kv.getrange");
+        assert_eq!(KeyRandom::NAME, "key.randomkey");
+        assert_eq!(KeyRestore::NAME, "key.restore");
+    }
+}
@@ -0,0 +1,277 @@
+//! `list.*` commands.
+//!
+//! Elements are JSON values. The server also accepts the short aliases
+//! `list.range`, `list.len`, `list.index`, `list.set` and `list.trim`; the
+//! types here use the `l`-prefixed names.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+use super::kv::SuccessResponse;
+
+// ==================== Payloads ====================
+
+/// `list.lpush`: push `values` onto the head, creating the list if needed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LPush {
+    pub key: String,
+    pub values: Vec<Value>,
+    /// Queue the push in this client's open transaction, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `list.lpushx`: push onto the head only if the list exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LPushX {
+    pub key: String,
+    pub values: Vec<Value>,
+}
+
+/// `list.rpush`: push `values` onto the tail, creating the list if needed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPush {
+    pub key: String,
+    pub values: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `list.rpushx`: push onto the tail only if the list exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPushX {
+    pub key: String,
+    pub values: Vec<Value>,
+}
+
+/// `list.lpop`: pop `count` (default 1) elements from the head
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LPop {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    /// Queue the pop in this client's open transaction, if any; only a
+    /// `count` of 1 is queued
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `list.rpop`: pop `count` (default 1) elements from the tail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPop {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `list.lrange`: elements `start..=stop`; negative indexes count from the
+/// tail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LRange {
+    pub key: String,
+    pub start: i64,
+    pub stop: i64,
+}
+
+/// `list.llen`: list length, 0 for a missing list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LLen {
+    pub key: String,
+}
+
+/// `list.lindex`: element at `index`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LIndex {
+    pub key: String,
+    pub index: i64,
+}
+
+/// `list.lset`: replace the element at `index`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LSet {
+    pub key: String,
+    pub index: i64,
+    pub value: Value,
+}
+
+/// `list.ltrim`: keep only elements `start..=stop`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LTrim {
+    pub key: String,
+    pub start: i64,
+    pub stop: i64,
+}
+
+/// `list.lrem`: remove `count` occurrences of `value`, from the head if
+/// positive, from the tail if negative, all of them if 0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LRem {
+    pub key: String,
+    pub count: i64,
+    pub value: Value,
+}
+
+/// `list.linsert`: insert `value` before (or after) the first `pivot`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LInsert {
+    pub key: String,
+    pub before: bool,
+    pub pivot: Value,
+    pub value: Value,
+}
+
+/// `list.lpos`: index of the first element equal to `value`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LPos {
+    pub key: String,
+    pub value: Value,
+}
+
+/// `list.rpoplpush`: move the tail of `source` to the head of `destination`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPopLPush {
+    pub source: String,
+    pub destination: String,
+}
+
+/// `list.stats`: store statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListStats {}
+
+// ==================== Responses ====================
+
+/// `list.lpush`, `list.rpush`, `list.lpushx`, `list.rpushx` and
+/// `list.linsert` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListLengthResponse {
+    /// Length after the write
+    pub length: i64,
+    /// The push was queued in a transaction; `length` is not known yet
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `list.lpop`, `list.rpop` and `list.lrange` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListValuesResponse {
+    pub values: Vec<Value>,
+    /// The pop was queued in a transaction; `values` is empty
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `list.llen` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LLenResponse {
+    pub length: usize,
+}
+
+/// `list.lindex` and `list.rpoplpush` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListValueResponse {
+    pub value: Value,
+}
+
+/// `list.lrem` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LRemResponse {
+    pub removed: usize,
+}
+
+/// `list.lpos` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LPosResponse {
+    /// `None` if no element matches
+    pub position: Option<usize>,
+}
+
+/// Operation counters in [`ListStatsResponse`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListOperationStats {
+    pub lpush_count: u64,
+    pub rpush_count: u64,
+    pub lpop_count: u64,
+    pub rpop_count: u64,
+    pub lrange_count: u64,
+    pub llen_count: u64,
+    pub lindex_count: u64,
+    pub lset_count: u64,
+    pub ltrim_count: u64,
+    pub lrem_count: u64,
+    pub linsert_count: u64,
+    pub rpoplpush_count: u64,
+    pub blpop_count: u64,
+    pub brpop_count: u64,
+}
+
+/// `list.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ListStatsResponse {
+    pub total_lists: usize,
+    pub total_elements: usize,
+    pub operations: ListOperationStats,
+}
+
+commands! {
+    LPush => "list.lpush", ListLengthResponse;
+    LPushX => "list.lpushx", ListLengthResponse;
+    RPush => "list.rpush", ListLengthResponse;
+    RPushX => "list.rpushx", ListLengthResponse;
+    LPop => "list.lpop", ListValuesResponse;
+    RPop => "list.rpop", ListValuesResponse;
+    LRange => "list.lrange", ListValuesResponse;
+    LLen => "list.llen", LLenResponse;
+    LIndex => "list.lindex", ListValueResponse;
+    LSet => "list.lset", SuccessResponse;
+    LTrim => "list.ltrim", SuccessResponse;
+    LRem => "list.lrem", LRemResponse;
+    LInsert => "list.linsert", ListLengthResponse;
+    LPos => "list.lpos", LPosResponse;
+    RPopLPush => "list.rpoplpush", ListValueResponse;
+    ListStats => "list.stats", ListStatsResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_pop_count_is_optional() {
+        let pop = LPop {
+            key: "jobs".into(),
+            count: None,
+            client_id: None,
+        };
+        assert_eq!(serde_json::to_value(&pop).unwrap(), json!({"key": "jobs"}));
+    }
+
+    #[test]
+    fn test_lpos_response() {
+        let found: LPosResponse =
+            serde_json::from_value(json!({"position": 2, "key": "l"})).unwrap();
+        assert_eq!(found.position, Some(2));
+        let missing: LPosResponse =
+            serde_json::from_value(json!({"position": null, "key": "l"})).unwrap();
+        assert_eq!(missing.position, None);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(LRange::NAME, "list.lrange");
+        assert_eq!(LInsert::NAME, "list.linsert");
+        assert_eq!(RPopLPush::NAME, "list.rpoplpush");
+    }
+}
@@ -0,0 +1,61 @@
+//! Synap wire protocol.
+//!
+//! Typed payloads and responses for every command of the StreamableHTTP API
+//! (`POST /api/v1/command`); the native transports carry the same commands.
+//! The SDK owns these types: the server decodes payloads with its own
+//! handlers, and the embedded drift test checks the two still agree.
+//!
+//! - [`envelope`] — the [`Request`]/[`Response`] envelope every command
+//!   travels in.
+//! - [`encoding`] — the JSON, MessagePack and CBOR bodies the envelope can be
+//!   sent in.
+//! - [`command`] — the [`Command`] trait tying a payload type to its command
+//!   name and response type.
+//!
+//! One module per command family:
+//!
+//! - data types: [`kv`] (`kv.*`, `key.*`), [`hash`], [`list`], [`set`],
+//!   [`sorted_set`], [`hyperloglog`], [`bitmap`], [`geospatial`], [`bloom`]
+//!   (`bf.*`) and [`timeseries`] (`ts.*`).
+//! - messaging: [`queue`], [`stream`], [`consumer_group`], [`pubsub`],
+//!   [`trace`] (`message.trace`), [`shovel`] and [`webhook`].
+//! - coordination: [`lock`], [`ratelimit`] and [`schedule`].
+//! - scripting and transactions: [`script`] (`script.*`, `function.*`) and
+//!   [`transaction`].
+//! - operations: [`admin`] (`info`, `client.*`, `config.*`, `slowlog.*`,
+//!   `latency.*`, `memory.*`, `monitoring.*`, `subscriptions.*`),
+//!   [`cluster`], [`replication`] and [`warmup`].
+
+pub mod admin;
+pub mod bitmap;
+pub mod bloom;
+pub mod cluster;
+pub mod command;
+pub mod consumer_group;
+pub mod encoding;
+pub mod envelope;
+pub mod geospatial;
+pub mod hash;
+pub mod hyperloglog;
+pub mod kv;
+pub mod list;
+pub mod lock;
+pub mod pubsub;
+pub mod queue;
+pub mod ratelimit;
+pub mod replication;
+pub mod schedule;
+pub mod script;
+pub mod set;
+pub mod shovel;
+pub mod sorted_set;
+pub mod stream;
+pub mod timeseries;
+pub mod trace;
+pub mod transaction;
+pub mod warmup;
+pub mod webhook;
+
+pub use command::Command;
+pub use encoding::{Encoding, EncodingError};
+pub use envelope::{ErrorCategory, ErrorInfo, Request, Response};
@@ -0,0 +1,244 @@
+//! `pubsub.*` commands.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+
+/// Routing rule copying messages published on `source` to `targets`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TopicRoute {
+    pub name: String,
+    /// Exact topic or wildcard pattern (`orders.*`, `events.#`)
+    pub source: String,
+    /// Exact topics that also receive the message
+    pub targets: Vec<String>,
+    /// Deliver to `targets` only, not to the subscribers of `source`
+    #[serde(default)]
+    pub redirect: bool,
+}
+
+/// Filter counters of one subscriber
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterStats {
+    pub subscriber_id: String,
+    pub filter: String,
+    /// Messages on the subscribed topics that passed the filter
+    pub matched: u64,
+    /// Messages on the subscribed topics that the filter held back
+    pub filtered: u64,
+}
+
+// ==================== Payloads ====================
+
+/// `pubsub.subscribe`: subscribe to `topics`, which may use the `*` and `#`
+/// wildcards; the server assigns the subscriber id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PubSubSubscribe {
+    pub topics: Vec<String>,
+    /// Only deliver messages whose payload matches this expression
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+/// `pubsub.publish`: send `payload` to the subscribers of `topic`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PubSubPublish {
+    pub topic: String,
+    pub payload: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// `pubsub.unsubscribe`: drop `topics`, or every subscription when unset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PubSubUnsubscribe {
+    pub subscriber_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topics: Option<Vec<String>>,
+}
+
+/// `pubsub.stats`: router statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PubSubStats {}
+
+/// `pubsub.filters`: counters of every filtered subscription
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PubSubFilters {}
+
+/// `pubsub.route.set`: install or replace the route named `route.name`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PubSubRouteSet {
+    #[serde(flatten)]
+    pub route: TopicRoute,
+}
+
+/// `pubsub.route.list`: every installed route
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PubSubRouteList {}
+
+/// `pubsub.route.delete`: remove a route
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PubSubRouteDelete {
+    pub name: String,
+}
+
+/// `pubsub.topics`: every topic with a subscriber
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PubSubTopics {}
+
+/// `pubsub.info`: counters of one topic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PubSubInfo {
+    pub topic: String,
+}
+
+// ==================== Responses ====================
+
+/// `pubsub.subscribe` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubSubscribeResponse {
+    pub subscriber_id: String,
+    pub topics: Vec<String>,
+    pub subscription_count: usize,
+    pub filter: Option<String>,
+}
+
+/// `pubsub.publish` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubPublishResponse {
+    pub message_id: String,
+    pub topic: String,
+    pub subscribers_matched: usize,
+}
+
+/// `pubsub.unsubscribe` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubUnsubscribeResponse {
+    /// Subscriptions removed
+    pub unsubscribed: usize,
+}
+
+/// `pubsub.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubStatsResponse {
+    pub total_topics: usize,
+    pub total_subscribers: usize,
+    pub total_wildcard_subscriptions: usize,
+    pub messages_published: u64,
+    pub messages_delivered: u64,
+    pub slow_consumers_dropped: u64,
+    pub messages_too_large: u64,
+    pub messages_rate_limited: u64,
+    pub messages_filtered: u64,
+}
+
+/// `pubsub.filters` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubFiltersResponse {
+    pub count: usize,
+    pub filters: Vec<FilterStats>,
+}
+
+/// `pubsub.route.set` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubRouteSetResponse {
+    pub route: TopicRoute,
+}
+
+/// `pubsub.route.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubRouteListResponse {
+    pub routes: Vec<TopicRoute>,
+}
+
+/// `pubsub.route.delete` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubRouteDeleteResponse {
+    pub deleted: bool,
+}
+
+/// `pubsub.topics` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubTopicsResponse {
+    pub topics: Vec<String>,
+    pub count: usize,
+}
+
+/// `pubsub.info` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubSubInfoResponse {
+    pub topic: String,
+    pub subscriber_count: usize,
+    pub message_count: u64,
+    /// Unix seconds
+    pub created_at: u64,
+}
+
+commands! {
+    PubSubSubscribe => "pubsub.subscribe", PubSubSubscribeResponse;
+    PubSubPublish => "pubsub.publish", PubSubPublishResponse;
+    PubSubUnsubscribe => "pubsub.unsubscribe", PubSubUnsubscribeResponse;
+    PubSubStats => "pubsub.stats", PubSubStatsResponse;
+    PubSubFilters => "pubsub.filters", PubSubFiltersResponse;
+    PubSubRouteSet => "pubsub.route.set", PubSubRouteSetResponse;
+    PubSubRouteList => "pubsub.route.list", PubSubRouteListResponse;
+    PubSubRouteDelete => "pubsub.route.delete", PubSubRouteDeleteResponse;
+    PubSubTopics => "pubsub.topics", PubSubTopicsResponse;
+    PubSubInfo => "pubsub.info", PubSubInfoResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_route_set_is_flat() {
+        let set = PubSubRouteSet {
+            route: TopicRoute {
+                name: "audit".into(),
+                source: "orders.*".into(),
+                targets: vec!["audit".into()],
+                redirect: false,
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&set).unwrap(),
+            json!({"name": "audit", "source": "orders.*", "targets": ["audit"], "redirect": false})
+        );
+    }
+
+    #[test]
+    fn test_unsubscribe_all_topics() {
+        let unsubscribe = PubSubUnsubscribe {
+            subscriber_id: "sub-1".into(),
+            topics: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&unsubscribe).unwrap(),
+            json!({"subscriber_id": "sub-1"})
+        );
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(PubSubRouteSet::NAME, "pubsub.route.set");
+        assert_eq!(PubSubFilters::NAME, "pubsub.filters");
+        assert_eq!(PubSubInfo::NAME, "pubsub.info");
+    }
+}
@@ -0,0 +1,265 @@
+//! `queue.*` commands.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+use super::kv::SuccessResponse;
+use crate::types::from_str_or_num;
+
+/// Queue message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub id: String,
+    pub payload: Vec<u8>,
+    #[serde(default)]
+    pub priority: u8,
+    #[serde(default)]
+    pub retry_count: u32,
+    #[serde(default)]
+    pub max_retries: u32,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub deadline: Option<u64>,
+}
+
+/// Settings of `queue.create`; the server defaults fill in the rest
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueueConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ack_deadline_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_max_retries: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_priority: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefetch_limit: Option<usize>,
+    /// How long an idempotency key is remembered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup_window_secs: Option<u64>,
+}
+
+// ==================== Payloads ====================
+
+/// `queue.create`: create a queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueCreate {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<QueueConfig>,
+}
+
+/// `queue.delete`: delete a queue and its messages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueDelete {
+    pub queue: String,
+}
+
+/// `queue.publish`: enqueue `payload`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuePublish {
+    pub queue: String,
+    pub payload: Vec<u8>,
+    /// 0-9, higher first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// Enqueue at most once per key within the queue's dedup window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Queue the publish in this client's open transaction, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `queue.consume`: take the next message for `consumer_id`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueConsume {
+    pub queue: String,
+    pub consumer_id: String,
+}
+
+/// `queue.ack`: settle a consumed message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueAck {
+    pub queue: String,
+    pub message_id: String,
+}
+
+/// `queue.nack`: reject a consumed message, requeueing it unless `requeue`
+/// is `false`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueNack {
+    pub queue: String,
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requeue: Option<bool>,
+}
+
+/// `queue.list`: every queue name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueueList {}
+
+/// `queue.stats`: counters of one queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueStats {
+    pub queue: String,
+}
+
+/// `queue.browse`: up to `limit` (default 100, at most 1000) waiting
+/// messages from `offset`, without consuming them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueBrowse {
+    pub queue: String,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// `queue.purge`: drop every waiting message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuePurge {
+    pub queue: String,
+}
+
+// ==================== Responses ====================
+
+/// `queue.delete` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueDeleteResponse {
+    pub deleted: bool,
+}
+
+/// `queue.publish` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueuePublishResponse {
+    /// Empty when the publish was queued in a transaction
+    pub message_id: String,
+    /// The idempotency key was seen before; `message_id` is the first
+    /// publish's
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub duplicate: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `queue.consume` response
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueConsumeResponse {
+    /// `None` when the queue is empty
+    pub message: Option<Message>,
+}
+
+/// `queue.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueListResponse {
+    pub queues: Vec<String>,
+}
+
+/// `queue.stats` response
+///
+/// RESP3 sends every counter as a string, so each is read from either form.
+/// A queue's consumers compete for one backlog, `depth`; per-group lag is a
+/// property of partitioned topics, see
+/// [`ConsumerGroupLag`](super::consumer_group::ConsumerGroupLag).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueStatsResponse {
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub depth: usize,
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub consumers: usize,
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub published: u64,
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub consumed: u64,
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub acked: u64,
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub nacked: u64,
+    #[serde(deserialize_with = "from_str_or_num")]
+    pub dead_lettered: usize,
+}
+
+/// `queue.browse` response
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueBrowseResponse {
+    pub messages: Vec<Message>,
+    /// Messages waiting in the queue
+    pub total: usize,
+    /// Offset of the next page, `None` after the last one
+    pub next_offset: Option<usize>,
+}
+
+/// `queue.purge` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueuePurgeResponse {
+    pub purged: usize,
+}
+
+commands! {
+    QueueCreate => "queue.create", SuccessResponse;
+    QueueDelete => "queue.delete", QueueDeleteResponse;
+    QueuePublish => "queue.publish", QueuePublishResponse;
+    QueueConsume => "queue.consume", QueueConsumeResponse;
+    QueueAck => "queue.ack", SuccessResponse;
+    QueueNack => "queue.nack", SuccessResponse;
+    QueueList => "queue.list", QueueListResponse;
+    QueueStats => "queue.stats", QueueStatsResponse;
+    QueueBrowse => "queue.browse", QueueBrowseResponse;
+    QueuePurge => "queue.purge", QueuePurgeResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_publish_skips_unset_options() {
+        let publish = QueuePublish {
+            queue: "jobs".into(),
+            payload: b"hi".to_vec(),
+            priority: Some(9),
+            max_retries: None,
+            idempotency_key: None,
+            client_id: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&publish).unwrap(),
+            json!({"queue": "jobs", "payload": [104, 105], "priority": 9})
+        );
+    }
+
+    #[test]
+    fn test_consume_response_empty_queue() {
+        let empty: QueueConsumeResponse = serde_json::from_value(json!({"message": null})).unwrap();
+        assert!(empty.message.is_none());
+    }
+
+    #[test]
+    fn test_stats_counters_may_be_strings() {
+        let stats: QueueStatsResponse = serde_json::from_value(json!({
+            "depth": "3", "consumers": 1, "published": "10", "consumed": 7,
+            "acked": 7, "nacked": 0, "dead_lettered": "0"
+        }))
+        .unwrap();
+        assert_eq!(stats.depth, 3);
+        assert_eq!(stats.published, 10);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(QueueBrowse::NAME, "queue.browse");
+        assert_eq!(QueueNack::NAME, "queue.nack");
+        assert_eq!(QueuePurge::NAME, "queue.purge");
+    }
+}
@@ -0,0 +1,64 @@
+//! `replication.*` commands.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// The current master in a [`ReplicationTopologyResponse`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopologyMaster {
+    /// `None` when the answering replica has not seen a failover yet.
+    pub node_id: Option<String>,
+    /// HTTP URL to send writes to, when the master advertises one.
+    pub url: Option<String>,
+    pub replication_address: String,
+}
+
+// ==================== Payloads ====================
+
+/// `replication.topology`: current master and failover epoch; needs
+/// automatic failover on the server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplicationTopology {}
+
+// ==================== Responses ====================
+
+/// `replication.topology` response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplicationTopologyResponse {
+    /// Failover epoch; increases with every promotion, 0 before the first.
+    pub epoch: u64,
+    /// Id of the server that answered.
+    pub node_id: String,
+    /// Role of the server that answered (`"master"` or `"replica"`).
+    pub role: String,
+    pub master: Option<TopologyMaster>,
+}
+
+commands! {
+    ReplicationTopology => "replication.topology", ReplicationTopologyResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_topology_before_first_failover() {
+        let topology: ReplicationTopologyResponse = serde_json::from_value(json!({
+            "epoch": 0,
+            "node_id": "replica-1",
+            "role": "replica",
+            "master": {"node_id": null, "url": null, "replication_address": "10.0.0.1:15501"}
+        }))
+        .unwrap();
+        assert_eq!(topology.master.unwrap().node_id, None);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(ReplicationTopology::NAME, "replication.topology");
+    }
+}
@@ -0,0 +1,215 @@
+//! `script.*` and `function.*` commands.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+
+/// A function library as reported by `function.list`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionLibraryInfo {
+    pub name: String,
+    pub functions: Vec<String>,
+    /// Library source, present when listed with `with_code`
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+// ==================== Payloads ====================
+
+/// `script.eval`: run a Lua script and cache it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptEval {
+    pub script: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// Scalars are passed as strings, anything else as its JSON text
+    #[serde(default)]
+    pub args: Vec<Value>,
+    /// Abort the script after this long
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// `script.evalsha`: run a cached script by its SHA1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptEvalSha {
+    pub sha1: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<Value>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// `script.load`: cache a script without running it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptLoad {
+    pub script: String,
+}
+
+/// `script.exists`: which of `hashes` are cached
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptExists {
+    pub hashes: Vec<String>,
+}
+
+/// `script.flush`: drop every cached script
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScriptFlush {}
+
+/// `script.kill`: stop the running script at its next interrupt check
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScriptKill {}
+
+/// `function.load`: register a library; `code` starts with
+/// `#!lua name=<library>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionLoad {
+    pub code: String,
+    /// Swap out an existing library of the same name
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// `function.delete`: remove a library
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionDelete {
+    pub library: String,
+}
+
+/// `function.list`: every loaded library
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionList {
+    /// Include each library's source
+    #[serde(default, rename = "withcode")]
+    pub with_code: bool,
+}
+
+/// `function.flush`: remove every library
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionFlush {}
+
+/// `function.fcall`: call a function of a loaded library
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionFcall {
+    pub function: String,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<Value>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+// ==================== Responses ====================
+
+/// `script.eval`, `script.evalsha` and `function.fcall` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptResultResponse {
+    pub result: Value,
+    /// SHA1 of the script; empty for `function.fcall`
+    pub sha1: String,
+}
+
+/// `script.load` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptLoadResponse {
+    pub sha1: String,
+}
+
+/// `script.exists` response, one flag per hash
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptExistsResponse {
+    pub exists: Vec<bool>,
+}
+
+/// `script.flush` and `function.flush` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptFlushResponse {
+    pub cleared: u64,
+}
+
+/// `script.kill` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptKillResponse {
+    /// A script was running
+    pub terminated: bool,
+}
+
+/// `function.load` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FunctionLoadResponse {
+    pub library: String,
+}
+
+/// `function.delete` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FunctionDeleteResponse {
+    pub deleted: bool,
+}
+
+/// `function.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FunctionListResponse {
+    pub libraries: Vec<FunctionLibraryInfo>,
+}
+
+commands! {
+    ScriptEval => "script.eval", ScriptResultResponse;
+    ScriptEvalSha => "script.evalsha", ScriptResultResponse;
+    ScriptLoad => "script.load", ScriptLoadResponse;
+    ScriptExists => "script.exists", ScriptExistsResponse;
+    ScriptFlush => "script.flush", ScriptFlushResponse;
+    ScriptKill => "script.kill", ScriptKillResponse;
+    FunctionLoad => "function.load", FunctionLoadResponse;
+    FunctionDelete => "function.delete", FunctionDeleteResponse;
+    FunctionList => "function.list", FunctionListResponse;
+    FunctionFlush => "function.flush", ScriptFlushResponse;
+    FunctionFcall => "function.fcall", ScriptResultResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_function_list_withcode() {
+        assert_eq!(
+            serde_json::to_value(FunctionList { with_code: true }).unwrap(),
+            json!({"withcode": true})
+        );
+    }
+
+    #[test]
+    fn test_eval_sends_unset_timeout_as_null() {
+        let eval = ScriptEval {
+            script: "return 1".into(),
+            keys: vec!["k".into()],
+            args: vec![json!(2)],
+            timeout_ms: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&eval).unwrap(),
+            json!({"script": "return 1", "keys": ["k"], "args": [2], "timeout_ms": null})
+        );
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(ScriptEvalSha::NAME, "script.evalsha");
+        assert_eq!(FunctionFcall::NAME, "function.fcall");
+        assert_eq!(FunctionFlush::NAME, "function.flush");
+    }
+}
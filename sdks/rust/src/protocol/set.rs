@@ -0,0 +1,284 @@
+//! `set.*` commands.
+//!
+//! Members are JSON values. The server also accepts `set.card` for
+//! `set.size`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+
+// ==================== Payloads ====================
+
+/// `set.add`: add members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SAdd {
+    pub key: String,
+    pub members: Vec<Value>,
+    /// Queue the write in this client's open transaction, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `set.rem`: remove members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SRem {
+    pub key: String,
+    pub members: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `set.ismember`: check membership
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SIsMember {
+    pub key: String,
+    pub member: Value,
+}
+
+/// `set.members`: every member
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SMembers {
+    pub key: String,
+}
+
+/// `set.scan`: up to `count` (default 100) members after `cursor` (0 to
+/// start), keeping those that match the `pattern` glob
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SScan {
+    pub key: String,
+    pub cursor: u64,
+    #[serde(rename = "match", default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// `set.size`: number of members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SCard {
+    pub key: String,
+}
+
+/// `set.pop`: remove and return up to `count` (default 1) random members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SPop {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// `set.randmember`: random members without removing them; a negative
+/// `count` may repeat members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SRandMember {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+}
+
+/// `set.move`: move `member` from `source` to `destination`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SMove {
+    pub source: String,
+    pub destination: String,
+    pub member: Value,
+}
+
+/// `set.inter`: members common to all `keys`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SInter {
+    pub keys: Vec<String>,
+}
+
+/// `set.union`: members of any of `keys`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SUnion {
+    pub keys: Vec<String>,
+}
+
+/// `set.diff`: members of the first key that are in none of the others
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SDiff {
+    pub keys: Vec<String>,
+}
+
+/// `set.interstore`: store the intersection of `keys` in `destination`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SInterStore {
+    pub destination: String,
+    pub keys: Vec<String>,
+}
+
+/// `set.unionstore`: store the union of `keys` in `destination`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SUnionStore {
+    pub destination: String,
+    pub keys: Vec<String>,
+}
+
+/// `set.diffstore`: store the difference of `keys` in `destination`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SDiffStore {
+    pub destination: String,
+    pub keys: Vec<String>,
+}
+
+/// `set.intercard`: size of the intersection of `keys`, counting no
+/// further than `limit` (0 for no limit)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SInterCard {
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub limit: usize,
+}
+
+/// `set.stats`: store statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetStats {}
+
+// ==================== Responses ====================
+
+/// `set.add` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SAddResponse {
+    pub added: usize,
+    /// The write was queued in a transaction; `added` is not known yet
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `set.rem` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SRemResponse {
+    pub removed: usize,
+    /// The write was queued in a transaction; `removed` is not known yet
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `set.ismember` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SIsMemberResponse {
+    pub is_member: bool,
+}
+
+/// Response of the commands that return members: `set.members`, `set.pop`,
+/// `set.randmember`, `set.inter`, `set.union` and `set.diff`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SetMembersResponse {
+    pub members: Vec<Value>,
+}
+
+/// `set.scan` response; `next_cursor` is 0 on the last page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SScanResponse {
+    pub members: Vec<Value>,
+    pub count: usize,
+    pub next_cursor: u64,
+}
+
+/// `set.size` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SCardResponse {
+    pub size: usize,
+}
+
+/// `set.move` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SMoveResponse {
+    pub moved: bool,
+}
+
+/// `set.interstore`, `set.unionstore`, `set.diffstore` and `set.intercard`
+/// response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SetCountResponse {
+    pub count: usize,
+}
+
+/// Operation counters in [`SetStatsResponse`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SetOperationStats {
+    pub sadd_count: u64,
+    pub srem_count: u64,
+    pub sismember_count: u64,
+    pub smembers_count: u64,
+    pub scard_count: u64,
+    pub spop_count: u64,
+    pub srandmember_count: u64,
+    pub smove_count: u64,
+    pub sinter_count: u64,
+    pub sunion_count: u64,
+    pub sdiff_count: u64,
+}
+
+/// `set.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SetStatsResponse {
+    pub total_sets: usize,
+    pub total_members: usize,
+    pub operations: SetOperationStats,
+}
+
+commands! {
+    SAdd => "set.add", SAddResponse;
+    SRem => "set.rem", SRemResponse;
+    SIsMember => "set.ismember", SIsMemberResponse;
+    SMembers => "set.members", SetMembersResponse;
+    SScan => "set.scan", SScanResponse;
+    SCard => "set.size", SCardResponse;
+    SPop => "set.pop", SetMembersResponse;
+    SRandMember => "set.randmember", SetMembersResponse;
+    SMove => "set.move", SMoveResponse;
+    SInter => "set.inter", SetMembersResponse;
+    SUnion => "set.union", SetMembersResponse;
+    SDiff => "set.diff", SetMembersResponse;
+    SInterStore => "set.interstore", SetCountResponse;
+    SUnionStore => "set.unionstore", SetCountResponse;
+    SDiffStore => "set.diffstore", SetCountResponse;
+    SInterCard => "set.intercard", SetCountResponse;
+    SetStats => "set.stats", SetStatsResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_randmember_count_is_signed() {
+        let rand = SRandMember {
+            key: "arms".into(),
+            count: Some(-10),
+        };
+        assert_eq!(
+            serde_json::to_value(&rand).unwrap(),
+            json!({"key": "arms", "count": -10})
+        );
+    }
+
+    #[test]
+    fn test_size_response() {
+        let size: SCardResponse = serde_json::from_value(json!({"size": 3})).unwrap();
+        assert_eq!(size.size, 3);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(SCard::NAME, "set.size");
+        assert_eq!(SInterCard::NAME, "set.intercard");
+        assert_eq!(SetStats::NAME, "set.stats");
+    }
+}
@@ -0,0 +1,152 @@
+//! `shovel.*` commands.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// What happens to the source message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShovelMode {
+    /// The message leaves the source for the destination
+    #[default]
+    Move,
+    /// The destination gets a copy; the original goes to `forward_to`
+    Copy,
+}
+
+/// Counters of one shovel
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShovelProgress {
+    /// Messages published to the destination
+    pub moved: u64,
+    /// Messages the transform dropped
+    pub filtered: u64,
+    /// Messages handed back after a failed transform or publish
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Unix milliseconds of the last message moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_moved_at: Option<u64>,
+    /// `max_messages` was reached; the shovel no longer runs
+    pub completed: bool,
+}
+
+// ==================== Payloads ====================
+
+/// `shovel.create`: move messages from the local queue `source` to
+/// `destination`, here or on the server at `destination_url`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShovelCreate {
+    pub name: String,
+    pub source: String,
+    /// Take the source's dead letters instead of its messages
+    #[serde(default)]
+    pub dead_letters: bool,
+    pub destination: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_url: Option<String>,
+    /// Bearer token (API key or JWT) for `destination_url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_token: Option<String>,
+    #[serde(default)]
+    pub mode: ShovelMode,
+    /// Local queue that receives the originals in `copy` mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forward_to: Option<String>,
+    /// Lua chunk run on every message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<String>,
+    /// Messages per second; unlimited when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    /// Stop after this many messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_messages: Option<u64>,
+}
+
+/// `shovel.list`: every shovel with its counters
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShovelList {}
+
+/// `shovel.delete`: stop and remove a shovel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShovelDelete {
+    pub name: String,
+}
+
+// ==================== Responses ====================
+
+/// `shovel.create` response and `shovel.list` entry
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShovelStatus {
+    #[serde(flatten)]
+    pub spec: ShovelCreate,
+    #[serde(flatten)]
+    pub progress: ShovelProgress,
+}
+
+/// `shovel.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShovelListResponse {
+    pub shovels: Vec<ShovelStatus>,
+}
+
+/// `shovel.delete` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShovelDeleteResponse {
+    pub deleted: bool,
+}
+
+commands! {
+    ShovelCreate => "shovel.create", ShovelStatus;
+    ShovelList => "shovel.list", ShovelListResponse;
+    ShovelDelete => "shovel.delete", ShovelDeleteResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_create_skips_unset_options() {
+        let create = ShovelCreate {
+            name: "drain".into(),
+            source: "jobs".into(),
+            destination: "jobs-eu".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&create).unwrap(),
+            json!({
+                "name": "drain", "source": "jobs", "dead_letters": false,
+                "destination": "jobs-eu", "mode": "move"
+            })
+        );
+    }
+
+    #[test]
+    fn test_status_is_flat() {
+        let status: ShovelStatus = serde_json::from_value(json!({
+            "name": "drain", "source": "jobs", "dead_letters": false,
+            "destination": "jobs-eu", "mode": "copy", "forward_to": "jobs-done",
+            "moved": 3, "filtered": 1, "errors": 0, "completed": false
+        }))
+        .unwrap();
+        assert_eq!(status.spec.mode, ShovelMode::Copy);
+        assert_eq!(status.progress.moved, 3);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(ShovelCreate::NAME, "shovel.create");
+        assert_eq!(ShovelList::NAME, "shovel.list");
+        assert_eq!(ShovelDelete::NAME, "shovel.delete");
+    }
+}
@@ -0,0 +1,473 @@
+//! `sortedset.*` commands.
+//!
+//! Members are strings, ordered by an `f64` score. Combining commands take
+//! an `aggregate` of `"sum"` (the default), `"min"` or `"max"`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+
+/// A member with its score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoredMember {
+    pub member: String,
+    pub score: f64,
+}
+
+/// Leaderboard notification window for a sorted set key
+///
+/// `top_n` members (1..=1000) are watched for entering or leaving the top;
+/// ranked members moving by at least `rank_threshold` places also publish a
+/// `rank_changed` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardWindow {
+    pub top_n: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rank_threshold: Option<usize>,
+}
+
+fn is_false(flag: &bool) -> bool {
+    !flag
+}
+
+// ==================== Payloads ====================
+
+/// `sortedset.zadd`: add or update one member, with the Redis `ZADD` flags
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZAdd {
+    pub key: String,
+    pub member: String,
+    pub score: f64,
+    /// Only add new members
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub nx: bool,
+    /// Only update existing members
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub xx: bool,
+    /// Only update when the new score is greater
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub gt: bool,
+    /// Only update when the new score is less
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub lt: bool,
+    /// Count changed members, not only added ones, in `changed`
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub ch: bool,
+    /// Add `score` to the current score instead of replacing it
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub incr: bool,
+}
+
+/// `sortedset.zadd` with several members at once
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZAddMany {
+    pub key: String,
+    pub members: Vec<ScoredMember>,
+}
+
+/// `sortedset.zrem`: remove members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZRem {
+    pub key: String,
+    pub members: Vec<String>,
+}
+
+/// `sortedset.zscore`: score of a member
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZScore {
+    pub key: String,
+    pub member: String,
+}
+
+/// `sortedset.zmscore`: scores of several members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZMScore {
+    pub key: String,
+    pub members: Vec<String>,
+}
+
+/// `sortedset.zcard`: number of members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZCard {
+    pub key: String,
+}
+
+/// `sortedset.zincrby`: add `increment` to a member's score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZIncrBy {
+    pub key: String,
+    pub member: String,
+    pub increment: f64,
+}
+
+/// `sortedset.zrange`: members by rank `start..=stop`, lowest score first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZRange {
+    pub key: String,
+    pub start: i64,
+    pub stop: i64,
+    #[serde(default)]
+    pub withscores: bool,
+}
+
+/// `sortedset.zrevrange`: members by rank `start..=stop`, highest score
+/// first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZRevRange {
+    pub key: String,
+    pub start: i64,
+    pub stop: i64,
+    #[serde(default)]
+    pub withscores: bool,
+}
+
+/// `sortedset.zrangebyscore`: members with `min <= score <= max`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZRangeByScore {
+    pub key: String,
+    pub min: f64,
+    pub max: f64,
+    #[serde(default)]
+    pub withscores: bool,
+}
+
+/// `sortedset.zscan`: up to `count` (default 100) members after `cursor` (0
+/// to start), keeping those that match the `pattern` glob
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZScan {
+    pub key: String,
+    pub cursor: u64,
+    #[serde(rename = "match", default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// `sortedset.zrank`: rank of a member, lowest score first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZRank {
+    pub key: String,
+    pub member: String,
+}
+
+/// `sortedset.zrevrank`: rank of a member, highest score first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZRevRank {
+    pub key: String,
+    pub member: String,
+}
+
+/// `sortedset.zcount`: number of members with `min <= score <= max`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZCount {
+    pub key: String,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// `sortedset.zpopmin`: remove and return the `count` (default 1) lowest
+/// scored members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZPopMin {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// `sortedset.zpopmax`: remove and return the `count` (default 1) highest
+/// scored members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZPopMax {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// `sortedset.zremrangebyrank`: remove members by rank `start..=stop`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZRemRangeByRank {
+    pub key: String,
+    pub start: i64,
+    pub stop: i64,
+}
+
+/// `sortedset.zremrangebyscore`: remove members with `min <= score <= max`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZRemRangeByScore {
+    pub key: String,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// `sortedset.zinter`: members in every one of `keys`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZInter {
+    pub keys: Vec<String>,
+    /// One multiplier per key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<f64>>,
+    #[serde(default)]
+    pub aggregate: String,
+    #[serde(default)]
+    pub withscores: bool,
+}
+
+/// `sortedset.zunion`: members in any of `keys`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZUnion {
+    pub keys: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<f64>>,
+    #[serde(default)]
+    pub aggregate: String,
+    #[serde(default)]
+    pub withscores: bool,
+}
+
+/// `sortedset.zdiff`: members of the first key in none of the others, with
+/// the first key's scores
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZDiff {
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub withscores: bool,
+}
+
+/// `sortedset.zinterstore`: store [`ZInter`]'s result in `destination`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZInterStore {
+    pub destination: String,
+    pub keys: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<f64>>,
+    #[serde(default)]
+    pub aggregate: String,
+}
+
+/// `sortedset.zunionstore`: store [`ZUnion`]'s result in `destination`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZUnionStore {
+    pub destination: String,
+    pub keys: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<f64>>,
+    #[serde(default)]
+    pub aggregate: String,
+}
+
+/// `sortedset.zdiffstore`: store [`ZDiff`]'s result in `destination`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZDiffStore {
+    pub destination: String,
+    pub keys: Vec<String>,
+}
+
+/// `sortedset.stats`: store statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SortedSetStats {}
+
+/// `sortedset.notify.set`: configure the leaderboard window of `key`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZNotifySet {
+    pub key: String,
+    #[serde(flatten)]
+    pub window: LeaderboardWindow,
+}
+
+/// `sortedset.notify.get`: the leaderboard window of `key`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZNotifyGet {
+    pub key: String,
+}
+
+/// `sortedset.notify.del`: remove the leaderboard window of `key`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZNotifyDel {
+    pub key: String,
+}
+
+// ==================== Responses ====================
+
+/// `sortedset.zadd` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZAddResponse {
+    pub added: usize,
+    pub changed: usize,
+}
+
+/// `sortedset.zrem`, `sortedset.zremrangebyrank` and
+/// `sortedset.zremrangebyscore` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZRemovedResponse {
+    pub removed: usize,
+}
+
+/// `sortedset.zscore` and `sortedset.zincrby` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZScoreResponse {
+    /// `None` if the member does not exist
+    pub score: Option<f64>,
+}
+
+/// `sortedset.zmscore` response, one entry per requested member
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZMScoreResponse {
+    pub scores: Vec<Option<f64>>,
+}
+
+/// `sortedset.zcard`, `sortedset.zcount` and the `*store` commands' response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZCountResponse {
+    pub count: usize,
+}
+
+/// Response of the range and pop commands
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZMembersResponse {
+    /// Scores are included even without `withscores`
+    pub members: Vec<ScoredMember>,
+}
+
+/// `sortedset.zscan` response; `next_cursor` is 0 on the last page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZScanResponse {
+    pub members: Vec<ScoredMember>,
+    pub count: usize,
+    pub next_cursor: u64,
+}
+
+/// `sortedset.zrank` and `sortedset.zrevrank` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZRankResponse {
+    /// `None` if the member does not exist
+    pub rank: Option<usize>,
+}
+
+/// `sortedset.zinter`, `sortedset.zunion` and `sortedset.zdiff` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZCombineResponse {
+    /// Member names, or [`ScoredMember`] objects with `withscores`
+    pub members: Vec<Value>,
+}
+
+/// `sortedset.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SortedSetStatsResponse {
+    pub total_keys: usize,
+    pub total_members: usize,
+    pub avg_members_per_key: f64,
+    pub memory_bytes: usize,
+}
+
+/// `sortedset.notify.set` and `sortedset.notify.get` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZNotifyResponse {
+    pub key: String,
+    /// `None` if no window is configured
+    pub window: Option<LeaderboardWindow>,
+    /// Pub/sub channel the key's leaderboard events are published on
+    pub channel: String,
+}
+
+/// `sortedset.notify.del` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZNotifyDelResponse {
+    pub key: String,
+    pub removed: bool,
+}
+
+commands! {
+    ZAdd => "sortedset.zadd", ZAddResponse;
+    ZAddMany => "sortedset.zadd", ZAddResponse;
+    ZRem => "sortedset.zrem", ZRemovedResponse;
+    ZScore => "sortedset.zscore", ZScoreResponse;
+    ZMScore => "sortedset.zmscore", ZMScoreResponse;
+    ZCard => "sortedset.zcard", ZCountResponse;
+    ZIncrBy => "sortedset.zincrby", ZScoreResponse;
+    ZRange => "sortedset.zrange", ZMembersResponse;
+    ZRevRange => "sortedset.zrevrange", ZMembersResponse;
+    ZRangeByScore => "sortedset.zrangebyscore", ZMembersResponse;
+    ZScan => "sortedset.zscan", ZScanResponse;
+    ZRank => "sortedset.zrank", ZRankResponse;
+    ZRevRank => "sortedset.zrevrank", ZRankResponse;
+    ZCount => "sortedset.zcount", ZCountResponse;
+    ZPopMin => "sortedset.zpopmin", ZMembersResponse;
+    ZPopMax => "sortedset.zpopmax", ZMembersResponse;
+    ZRemRangeByRank => "sortedset.zremrangebyrank", ZRemovedResponse;
+    ZRemRangeByScore => "sortedset.zremrangebyscore", ZRemovedResponse;
+    ZInter => "sortedset.zinter", ZCombineResponse;
+    ZUnion => "sortedset.zunion", ZCombineResponse;
+    ZDiff => "sortedset.zdiff", ZCombineResponse;
+    ZInterStore => "sortedset.zinterstore", ZCountResponse;
+    ZUnionStore => "sortedset.zunionstore", ZCountResponse;
+    ZDiffStore => "sortedset.zdiffstore", ZCountResponse;
+    SortedSetStats => "sortedset.stats", SortedSetStatsResponse;
+    ZNotifySet => "sortedset.notify.set", ZNotifyResponse;
+    ZNotifyGet => "sortedset.notify.get", ZNotifyResponse;
+    ZNotifyDel => "sortedset.notify.del", ZNotifyDelResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_zadd_flags_are_omitted_when_unset() {
+        let add = ZAdd {
+            key: "board".into(),
+            member: "alice".into(),
+            score: 10.0,
+            nx: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&add).unwrap(),
+            json!({"key": "board", "member": "alice", "score": 10.0, "nx": true})
+        );
+    }
+
+    #[test]
+    fn test_notify_set_flattens_the_window() {
+        let set = ZNotifySet {
+            key: "board".into(),
+            window: LeaderboardWindow {
+                top_n: 10,
+                rank_threshold: None,
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&set).unwrap(),
+            json!({"key": "board", "top_n": 10})
+        );
+    }
+
+    #[test]
+    fn test_rank_response() {
+        let ranked: ZRankResponse = serde_json::from_value(json!({"rank": 0})).unwrap();
+        assert_eq!(ranked.rank, Some(0));
+        let missing: ZRankResponse = serde_json::from_value(json!({"rank": null})).unwrap();
+        assert_eq!(missing.rank, None);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(ZAddMany::NAME, ZAdd::NAME);
+        assert_eq!(ZRemRangeByScore::NAME, "sortedset.zremrangebyscore");
+        assert_eq!(ZNotifySet::NAME, "sortedset.notify.set");
+    }
+}
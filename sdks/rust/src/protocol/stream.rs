@@ -0,0 +1,231 @@
+//! `stream.*` commands.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+use super::kv::SuccessResponse;
+
+/// Settings of `stream.create`; the server defaults fill in the rest
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamConfig {
+    /// How long a publish idempotency key is remembered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup_window_secs: Option<u64>,
+}
+
+/// Stream event as the server sends it
+///
+/// HTTP carries `data` as the bytes of the published JSON, the native
+/// transports as a string; [`Event`](crate::types::Event) decodes either.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamEvent {
+    #[serde(default)]
+    pub room: String,
+    #[serde(default)]
+    pub offset: u64,
+    pub event: String,
+    pub data: Value,
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+}
+
+/// Room and offset of a `stream.consume_many` read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamRoomOffset {
+    pub room: String,
+    #[serde(default)]
+    pub offset: u64,
+}
+
+// ==================== Payloads ====================
+
+/// `stream.create`: create a room
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamCreate {
+    pub room: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<StreamConfig>,
+}
+
+/// `stream.get_or_create`: create a room unless it exists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamGetOrCreate {
+    pub room: String,
+}
+
+/// `stream.publish`: append an event to `room`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamPublish {
+    pub room: String,
+    pub event: String,
+    pub data: Value,
+    /// Append at most once per key within the room's dedup window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Queue the publish in this client's open transaction, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `stream.consume`: up to `limit` (default 100) events from `from_offset`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamConsume {
+    pub room: String,
+    pub subscriber_id: String,
+    #[serde(default)]
+    pub from_offset: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Hold back events published less than this many milliseconds ago
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_age_ms: Option<u64>,
+}
+
+/// `stream.consume_many`: read several rooms, each from its own offset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamConsumeMany {
+    pub subscriber_id: String,
+    pub rooms: Vec<StreamRoomOffset>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Long-poll this long for the first event when every room is caught up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// `stream.stats`: counters of one room
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamStats {
+    pub room: String,
+}
+
+/// `stream.list`: every room name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamList {}
+
+/// `stream.delete`: delete a room and its events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamDelete {
+    pub room: String,
+}
+
+// ==================== Responses ====================
+
+/// `stream.get_or_create` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamGetOrCreateResponse {
+    /// `false` if the room already existed
+    pub created: bool,
+}
+
+/// `stream.publish` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamPublishResponse {
+    pub offset: u64,
+    /// The idempotency key was seen before; `offset` is the first publish's
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub duplicate: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub queued: bool,
+}
+
+/// `stream.consume` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamConsumeResponse {
+    pub events: Vec<StreamEvent>,
+    /// Offset to resume from
+    pub next_offset: u64,
+}
+
+/// `stream.consume_many` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamConsumeManyResponse {
+    /// Events from all rooms, interleaved by publish time
+    pub events: Vec<StreamEvent>,
+    /// Offset to resume each requested room from
+    pub next_offsets: HashMap<String, u64>,
+}
+
+/// `stream.stats` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamStatsResponse {
+    #[serde(alias = "room")]
+    pub name: String,
+    pub message_count: usize,
+    pub min_offset: u64,
+    pub max_offset: u64,
+    pub total_published: u64,
+    pub total_consumed: u64,
+    pub subscriber_count: usize,
+    /// Events evicted while still unread by the slowest subscriber
+    pub dropped: u64,
+}
+
+/// `stream.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamListResponse {
+    pub rooms: Vec<String>,
+}
+
+commands! {
+    StreamCreate => "stream.create", SuccessResponse;
+    StreamGetOrCreate => "stream.get_or_create", StreamGetOrCreateResponse;
+    StreamPublish => "stream.publish", StreamPublishResponse;
+    StreamConsume => "stream.consume", StreamConsumeResponse;
+    StreamConsumeMany => "stream.consume_many", StreamConsumeManyResponse;
+    StreamStats => "stream.stats", StreamStatsResponse;
+    StreamList => "stream.list", StreamListResponse;
+    StreamDelete => "stream.delete", SuccessResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_consume_skips_unset_options() {
+        let consume = StreamConsume {
+            room: "chat".into(),
+            subscriber_id: "sdk-default".into(),
+            from_offset: 5,
+            limit: None,
+            min_age_ms: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&consume).unwrap(),
+            json!({"room": "chat", "subscriber_id": "sdk-default", "from_offset": 5})
+        );
+    }
+
+    #[test]
+    fn test_consume_many_response() {
+        let batch: StreamConsumeManyResponse = serde_json::from_value(json!({
+            "events": [{
+                "id": "e1", "room": "cpu", "offset": 7, "event": "tick",
+                "data": [49], "timestamp": 1
+            }],
+            "next_offsets": {"cpu": 8, "mem": 0}
+        }))
+        .unwrap();
+        assert_eq!(batch.events[0].room, "cpu");
+        assert_eq!(batch.next_offsets["cpu"], 8);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(StreamGetOrCreate::NAME, "stream.get_or_create");
+        assert_eq!(StreamConsumeMany::NAME, "stream.consume_many");
+        assert_eq!(StreamDelete::NAME, "stream.delete");
+    }
+}
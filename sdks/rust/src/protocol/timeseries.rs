@@ -0,0 +1,149 @@
+//! `ts.*` commands: time-bucketed counters.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// Bucket width of a series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeSeriesResolution {
+    #[serde(rename = "1s")]
+    Second,
+    #[serde(rename = "1m")]
+    Minute,
+    #[serde(rename = "1h")]
+    Hour,
+}
+
+/// How the increments in each range bucket are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeSeriesAggregation {
+    /// Total of the increments
+    #[default]
+    Sum,
+    /// Mean increment
+    Avg,
+    /// Largest single increment
+    Max,
+}
+
+/// `ts.incr`: add `value` to the bucket holding `timestamp`
+///
+/// `resolution` and `retention` set up the series when this creates it;
+/// afterwards a different resolution is refused and a retention replaces the
+/// current one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeSeriesIncr {
+    pub key: String,
+    pub value: f64,
+    /// Unix milliseconds; now when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Bucket width; `1s` when a new series leaves it out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<TimeSeriesResolution>,
+    /// How long buckets are kept, in milliseconds; one day when a new series
+    /// leaves it out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention: Option<u64>,
+}
+
+/// `ts.range`: aggregate the buckets starting between `from` and `to`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeSeriesRange {
+    pub key: String,
+    /// Unix milliseconds, inclusive; the start of the series when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// Unix milliseconds, inclusive; the end of the series when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+    #[serde(default)]
+    pub aggregation: TimeSeriesAggregation,
+    /// Width in milliseconds of the returned buckets, a multiple of the
+    /// series' resolution; the resolution itself when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<u64>,
+}
+
+/// `ts.del`: drop a series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeSeriesDelete {
+    pub key: String,
+}
+
+/// A bucket, identified by its start in unix milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimeSeriesPoint {
+    pub timestamp: u64,
+    pub value: f64,
+}
+
+/// `ts.incr` response: the bucket incremented and its new total
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeSeriesIncrResponse {
+    pub timestamp: u64,
+    pub value: f64,
+}
+
+/// `ts.range` response; buckets with no increments are left out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeSeriesRangeResponse {
+    pub points: Vec<TimeSeriesPoint>,
+}
+
+/// `ts.del` response; `false` when the key held no series
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeSeriesDeleteResponse {
+    pub deleted: bool,
+}
+
+commands! {
+    TimeSeriesIncr => "ts.incr", TimeSeriesIncrResponse;
+    TimeSeriesRange => "ts.range", TimeSeriesRangeResponse;
+    TimeSeriesDelete => "ts.del", TimeSeriesDeleteResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_incr_wire_format() {
+        let incr = TimeSeriesIncr {
+            key: "requests".to_string(),
+            value: 1.0,
+            timestamp: None,
+            resolution: Some(TimeSeriesResolution::Minute),
+            retention: Some(3_600_000),
+        };
+        assert_eq!(
+            serde_json::to_value(&incr).unwrap(),
+            json!({"key": "requests", "value": 1.0, "resolution": "1m", "retention": 3600000})
+        );
+    }
+
+    #[test]
+    fn test_range_defaults() {
+        let range: TimeSeriesRange =
+            serde_json::from_value(json!({"key": "requests", "aggregation": "max"})).unwrap();
+        assert_eq!(range.aggregation, TimeSeriesAggregation::Max);
+        assert_eq!((range.from, range.to, range.bucket), (None, None, None));
+
+        let range: TimeSeriesRange = serde_json::from_value(json!({"key": "requests"})).unwrap();
+        assert_eq!(range.aggregation, TimeSeriesAggregation::Sum);
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(TimeSeriesIncr::NAME, "ts.incr");
+        assert_eq!(TimeSeriesRange::NAME, "ts.range");
+        assert_eq!(TimeSeriesDelete::NAME, "ts.del");
+    }
+}
@@ -0,0 +1,193 @@
+//! `message.trace` command: the delivery timeline of a queue message or
+//! stream event.
+//!
+//! With `message_trace.enabled`, the server records every step of a message
+//! from publish to ack: who it was delivered to, on which attempt, and how
+//! it left the queue. Only the most recently published messages are kept.
+//!
+//! Delivered queue messages also carry trace headers, whether or not the
+//! timeline is recorded: [`ENQUEUED_AT_HEADER`], [`DELIVERY_ATTEMPT_HEADER`]
+//! and [`CONSUMER_HEADER`].
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// Header with the Unix time (milliseconds) the message was enqueued
+pub const ENQUEUED_AT_HEADER: &str = "x-synap-enqueued-at";
+/// Header with the delivery attempt, 1 on the first delivery
+pub const DELIVERY_ATTEMPT_HEADER: &str = "x-synap-delivery-attempt";
+/// Header with the id of the consumer the message was delivered to
+pub const CONSUMER_HEADER: &str = "x-synap-consumer";
+
+/// Where a traced message was published, e.g. `{"queue": "orders"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceSource {
+    Queue(String),
+    /// A stream room
+    Stream(String),
+}
+
+/// What became of a message its consumer gave back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NackOutcome {
+    /// Put back on the queue for another delivery
+    Requeued,
+    /// Out of retries, moved to the dead letter queue
+    DeadLettered,
+    /// Nacked without requeue, so dropped
+    Discarded,
+}
+
+/// One step of a message's life, e.g. `{"step": "acked", "consumer": "w1"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum TraceStep {
+    /// Published to a queue
+    Enqueued {
+        priority: u8,
+    },
+    /// Appended to a stream room
+    Published {
+        offset: u64,
+    },
+    /// Handed to a queue consumer
+    Delivered {
+        consumer: String,
+        attempt: u32,
+    },
+    /// Read by a stream subscriber
+    Read {
+        consumer: String,
+    },
+    Acked {
+        consumer: String,
+    },
+    Nacked {
+        consumer: String,
+        outcome: NackOutcome,
+    },
+    /// The consumer's ack deadline passed
+    Expired {
+        consumer: String,
+        outcome: NackOutcome,
+    },
+    /// Removed by a queue purge
+    Purged,
+}
+
+/// A step and when it happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEvent {
+    /// Unix timestamp (milliseconds)
+    pub at: u64,
+    #[serde(flatten)]
+    pub step: TraceStep,
+}
+
+/// The recorded life of one message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageTrace {
+    pub id: String,
+    pub source: TraceSource,
+    /// Unix timestamp (milliseconds) of the publish
+    pub enqueued_at: u64,
+    /// Queue deliveries, or stream reads
+    #[serde(default)]
+    pub deliveries: u32,
+    /// Consumers the message went to, in order of first delivery
+    #[serde(default)]
+    pub consumers: Vec<String>,
+    /// Unix timestamp (milliseconds) of the ack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acked_at: Option<u64>,
+    /// Steps in order. Past the per-message limit the oldest steps after the
+    /// publish are dropped and counted in `dropped_events`.
+    #[serde(default)]
+    pub events: Vec<TraceEvent>,
+    #[serde(default)]
+    pub dropped_events: u64,
+}
+
+/// `message.trace`: the timeline of a queue message or stream event id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageTraceGet {
+    pub id: String,
+}
+
+/// `message.trace` response; `trace` is absent when the id was not traced or
+/// its trace was evicted
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageTraceResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<MessageTrace>,
+}
+
+commands! {
+    MessageTraceGet => "message.trace", MessageTraceResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_trace_round_trips() {
+        let trace = MessageTrace {
+            id: "m1".to_string(),
+            source: TraceSource::Queue("orders".to_string()),
+            enqueued_at: 1_000,
+            deliveries: 1,
+            consumers: vec!["w1".to_string()],
+            acked_at: None,
+            events: vec![
+                TraceEvent {
+                    at: 1_000,
+                    step: TraceStep::Enqueued { priority: 5 },
+                },
+                TraceEvent {
+                    at: 1_500,
+                    step: TraceStep::Nacked {
+                        consumer: "w1".to_string(),
+                        outcome: NackOutcome::DeadLettered,
+                    },
+                },
+            ],
+            dropped_events: 0,
+        };
+        let value = serde_json::to_value(&trace).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "id": "m1",
+                "source": {"queue": "orders"},
+                "enqueued_at": 1000,
+                "deliveries": 1,
+                "consumers": ["w1"],
+                "events": [
+                    {"at": 1000, "step": "enqueued", "priority": 5},
+                    {"at": 1500, "step": "nacked", "consumer": "w1", "outcome": "dead_lettered"}
+                ],
+                "dropped_events": 0
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<MessageTrace>(value).unwrap(),
+            trace
+        );
+    }
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(MessageTraceGet::NAME, "message.trace");
+        assert_eq!(
+            serde_json::from_value::<MessageTraceResponse>(json!({})).unwrap(),
+            MessageTraceResponse::default()
+        );
+    }
+}
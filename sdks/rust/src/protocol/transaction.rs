@@ -0,0 +1,115 @@
+//! `transaction.*` commands.
+//!
+//! Every command names the transaction by `client_id`; without one the
+//! server falls back to the request id, which only suits a single request.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::command::commands;
+
+// ==================== Payloads ====================
+
+/// `transaction.multi`: start queueing the client's commands
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionMulti {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `transaction.discard`: drop the queued commands
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionDiscard {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `transaction.watch`: abort the next exec if any of `keys` changes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionWatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    pub keys: Vec<String>,
+}
+
+/// `transaction.unwatch`: forget every watched key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionUnwatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+/// `transaction.exec`: run the queued commands atomically
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionExec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+// ==================== Responses ====================
+
+/// `transaction.multi`, `transaction.discard`, `transaction.watch` and
+/// `transaction.unwatch` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionResponse {
+    #[serde(default = "default_success")]
+    pub success: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+fn default_success() -> bool {
+    true
+}
+
+/// `transaction.exec` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionExecResult {
+    /// One result per queued command
+    Success { results: Vec<Value> },
+    /// A watched key changed; nothing ran
+    Aborted {
+        aborted: bool,
+        #[serde(default)]
+        message: Option<String>,
+    },
+}
+
+commands! {
+    TransactionMulti => "transaction.multi", TransactionResponse;
+    TransactionDiscard => "transaction.discard", TransactionResponse;
+    TransactionWatch => "transaction.watch", TransactionResponse;
+    TransactionUnwatch => "transaction.unwatch", TransactionResponse;
+    TransactionExec => "transaction.exec", TransactionExecResult;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_exec_result_shapes() {
+        let ran: TransactionExecResult =
+            serde_json::from_value(json!({"success": true, "results": [1, "OK"]})).unwrap();
+        assert!(matches!(ran, TransactionExecResult::Success { results } if results.len() == 2));
+
+        let aborted: TransactionExecResult = serde_json::from_value(
+            json!({"aborted": true, "message": "Transaction aborted: watched keys changed"}),
+        )
+        .unwrap();
+        assert!(matches!(
+            aborted,
+            TransactionExecResult::Aborted { aborted: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(TransactionMulti::NAME, "transaction.multi");
+        assert_eq!(TransactionWatch::NAME, "transaction.watch");
+        assert_eq!(TransactionExec::NAME, "transaction.exec");
+    }
+}
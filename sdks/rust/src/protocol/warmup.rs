@@ -0,0 +1,55 @@
+//! `warmup.*` commands.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+// ==================== Payloads ====================
+
+/// `warmup.run`: prime these keys, listed exactly or by prefix
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WarmupManifest {
+    pub keys: Vec<String>,
+    pub prefixes: Vec<String>,
+}
+
+// ==================== Responses ====================
+
+/// `warmup.run` response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmupReport {
+    /// Keys looked up: the listed keys plus those the prefixes matched.
+    pub keys_requested: usize,
+    pub keys_loaded: usize,
+    pub keys_missing: usize,
+    /// Total size of the loaded values.
+    pub bytes_loaded: u64,
+    /// The manifest named more keys than the server primes in one warmup.
+    pub truncated: bool,
+    pub duration_ms: u64,
+    /// Unix timestamp (seconds).
+    pub completed_at: u64,
+}
+
+commands! {
+    WarmupManifest => "warmup.run", WarmupReport;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_empty_manifest_fields_default() {
+        let manifest: WarmupManifest = serde_json::from_value(json!({"keys": ["a"]})).unwrap();
+        assert!(manifest.prefixes.is_empty());
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(WarmupManifest::NAME, "warmup.run");
+    }
+}
@@ -0,0 +1,129 @@
+//! `webhook.*` commands.
+
+use serde::{Deserialize, Serialize};
+
+use super::command::commands;
+
+/// Counters of one webhook
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookProgress {
+    /// Messages the endpoint acknowledged
+    pub delivered: u64,
+    /// Requests that failed or got a non-2xx answer
+    pub failed_attempts: u64,
+    /// Messages given up on and moved to a dead letter queue
+    pub dead_lettered: u64,
+    /// Pub/Sub messages given up on without a `dead_letter_queue`
+    pub dropped: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Unix milliseconds of the last message delivered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_delivered_at: Option<u64>,
+}
+
+// ==================== Payloads ====================
+
+/// `webhook.create`: POST the messages of `queue` or `topic` to `url`
+///
+/// Unset limits take the server defaults: 5 attempts, a 10 s timeout and
+/// backoff from 500 ms to 30 s.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebhookCreate {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue: Option<String>,
+    /// Topic or wildcard pattern
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// `https://`, or `http://` on a loopback host
+    pub url: String,
+    /// Key of the `X-Synap-Signature` HMAC; requests are unsigned when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Attempts per Pub/Sub message; queue messages follow the queue's
+    /// `max_retries`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    /// Local queue that receives the Pub/Sub messages that could not be
+    /// delivered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_letter_queue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_backoff_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_backoff_ms: Option<u64>,
+}
+
+/// `webhook.list`: every webhook with its counters
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebhookList {}
+
+/// `webhook.delete`: stop and remove a webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookDelete {
+    pub name: String,
+}
+
+// ==================== Responses ====================
+
+/// `webhook.create` response and `webhook.list` entry; the limits are
+/// always filled in
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebhookStatus {
+    #[serde(flatten)]
+    pub spec: WebhookCreate,
+    #[serde(flatten)]
+    pub progress: WebhookProgress,
+}
+
+/// `webhook.list` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookListResponse {
+    pub webhooks: Vec<WebhookStatus>,
+}
+
+/// `webhook.delete` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookDeleteResponse {
+    pub deleted: bool,
+}
+
+commands! {
+    WebhookCreate => "webhook.create", WebhookStatus;
+    WebhookList => "webhook.list", WebhookListResponse;
+    WebhookDelete => "webhook.delete", WebhookDeleteResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Command;
+    use serde_json::json;
+
+    #[test]
+    fn test_create_leaves_limits_to_server() {
+        let create = WebhookCreate {
+            name: "fn".into(),
+            queue: Some("jobs".into()),
+            url: "https://example.com/hook".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&create).unwrap(),
+            json!({"name": "fn", "queue": "jobs", "url": "https://example.com/hook"})
+        );
+    }
+
+    #[test]
+    fn test_command_names() {
+        assert_eq!(WebhookCreate::NAME, "webhook.create");
+        assert_eq!(WebhookList::NAME, "webhook.list");
+        assert_eq!(WebhookDelete::NAME, "webhook.delete");
+    }
+}
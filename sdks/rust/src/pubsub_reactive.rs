@@ -10,7 +10,7 @@ use crate::types::PubSubMessage;
 use futures::{Stream, StreamExt};
 use serde_json::Value;
 use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 
 impl crate::pubsub::PubSubManager {
     /// Observe messages from Pub/Sub topics reactively using WebSocket
@@ -135,8 +135,8 @@ impl crate::pubsub::PubSubManager {
 
             tracing::debug!("Connecting to WebSocket: {}", ws_endpoint);
 
-            let ws_stream = match connect_async(&ws_endpoint).await {
-                Ok((stream, _)) => stream,
+            let ws_stream = match client.connect_websocket(&ws_endpoint).await {
+                Ok(stream) => stream,
                 Err(e) => {
                     tracing::error!("Failed to connect WebSocket: {}", e);
                     return;
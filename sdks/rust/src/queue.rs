@@ -1,178 +1,485 @@
-//! Queue operations
-
-use crate::client::SynapClient;
-use crate::error::Result;
-use crate::types::{Message, QueueStats};
-use serde_json::json;
-
-// Re-export for convenience
-pub use crate::reactive::{MessageStream, SubscriptionHandle};
-
-/// Queue Manager interface
-///
-/// Uses StreamableHTTP protocol for all operations.
-#[derive(Clone)]
-pub struct QueueManager {
-    pub(crate) client: SynapClient,
-}
-
-impl QueueManager {
-    /// Create a new Queue manager interface
-    pub(crate) fn new(client: SynapClient) -> Self {
-        Self { client }
-    }
-
-    /// Create a new queue
-    ///
-    /// # Arguments
-    /// * `queue_name` - Name of the queue
-    /// * `max_depth` - Maximum queue depth (optional)
-    /// * `ack_deadline_secs` - ACK deadline in seconds (optional)
-    pub async fn create_queue(
-        &self,
-        queue_name: &str,
-        max_depth: Option<usize>,
-        ack_deadline_secs: Option<u64>,
-    ) -> Result<()> {
-        let mut config = serde_json::Map::new();
-        if let Some(depth) = max_depth {
-            config.insert("max_depth".to_string(), json!(depth));
-        }
-        if let Some(deadline) = ack_deadline_secs {
-            config.insert("ack_deadline_secs".to_string(), json!(deadline));
-        }
-        let mut payload = json!({"name": queue_name});
-        if !config.is_empty() {
-            payload["config"] = serde_json::Value::Object(config);
-        }
-
-        self.client.send_command("queue.create", payload).await?;
-        Ok(())
-    }
-
-    /// Publish a message to a queue
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::{SynapClient, SynapConfig};
-    /// # #[tokio::main]
-    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
-    /// client.queue().publish("tasks", b"process-video", Some(9), None).await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn publish(
-        &self,
-        queue_name: &str,
-        payload: &[u8],
-        priority: Option<u8>,
-        max_retries: Option<u32>,
-    ) -> Result<String> {
-        let body = json!({
-            "queue": queue_name,
-            "payload": payload,
-            "priority": priority,
-            "max_retries": max_retries,
-        });
-
-        let response = self.client.send_command("queue.publish", body).await?;
-
-        Ok(response["message_id"]
-            .as_str()
-            .unwrap_or_default()
-            .to_string())
-    }
-
-    /// Consume a message from a queue
-    pub async fn consume(&self, queue_name: &str, consumer_id: &str) -> Result<Option<Message>> {
-        let payload = json!({
-            "queue": queue_name,
-            "consumer_id": consumer_id,
-        });
-
-        let response = self.client.send_command("queue.consume", payload).await?;
-
-        // HTTP transport wraps the message: {"message": {...}} or {"message": null}
-        // Native transports (SynapRPC, RESP3) return the message directly or Null.
-        let msg_val = if let Some(inner) = response.get("message") {
-            inner.clone()
-        } else {
-            response
-        };
-
-        if msg_val.is_null() {
-            return Ok(None);
-        }
-
-        Ok(serde_json::from_value(msg_val).ok())
-    }
-
-    /// Acknowledge a message
-    pub async fn ack(&self, queue_name: &str, message_id: &str) -> Result<()> {
-        let payload = json!({
-            "queue": queue_name,
-            "message_id": message_id
-        });
-
-        self.client.send_command("queue.ack", payload).await?;
-        Ok(())
-    }
-
-    /// Negative acknowledge a message (requeue)
-    pub async fn nack(&self, queue_name: &str, message_id: &str) -> Result<()> {
-        let payload = json!({
-            "queue": queue_name,
-            "message_id": message_id
-        });
-
-        self.client.send_command("queue.nack", payload).await?;
-        Ok(())
-    }
-
-    /// Get queue statistics
-    pub async fn stats(&self, queue_name: &str) -> Result<QueueStats> {
-        let payload = json!({"queue": queue_name});
-        let response = self.client.send_command("queue.stats", payload).await?;
-        Ok(serde_json::from_value(response)?)
-    }
-
-    /// List all queues
-    pub async fn list(&self) -> Result<Vec<String>> {
-        let response = self.client.send_command("queue.list", json!({})).await?;
-        Ok(serde_json::from_value(response["queues"].clone())?)
-    }
-
-    /// Delete a queue
-    pub async fn delete_queue(&self, queue_name: &str) -> Result<()> {
-        let payload = json!({"queue": queue_name});
-        self.client.send_command("queue.delete", payload).await?;
-        Ok(())
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::SynapConfig;
-
-    #[test]
-    fn test_queue_manager_creation() {
-        let config = SynapConfig::new("http://localhost:15500");
-        let client = SynapClient::new(config).unwrap();
-        let queue = client.queue();
-
-        assert!(std::mem::size_of_val(&queue) > 0);
-    }
-
-    #[test]
-    fn test_queue_manager_clone() {
-        let config = SynapConfig::new("http://localhost:15500");
-        let client = SynapClient::new(config).unwrap();
-        let queue1 = client.queue();
-        let queue2 = queue1.clone();
-
-        assert!(std::mem::size_of_val(&queue1) > 0);
-        assert!(std::mem::size_of_val(&queue2) > 0);
-    }
-}
+//! Queue operations
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::paging::{PAGE_SIZE, PageStream};
+use crate::protocol::queue::{
+    QueueAck, QueueBrowse, QueueConfig, QueueConsume, QueueCreate, QueueDelete, QueueList,
+    QueueNack, QueuePublish, QueueStats as Stats,
+};
+use crate::types::{Message, QueueStats};
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+// Re-export for convenience
+pub use crate::reactive::{MessageStream, SubscriptionHandle};
+
+/// Message ids of a batch publish, in the order the payloads were given
+pub type PublishStream = Pin<Box<dyn Stream<Item = Result<String>> + Send + 'static>>;
+
+/// Queue Manager interface
+///
+/// Uses StreamableHTTP protocol for all operations.
+#[derive(Clone)]
+pub struct QueueManager {
+    pub(crate) client: SynapClient,
+}
+
+impl QueueManager {
+    /// Create a new Queue manager interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Create a new queue
+    ///
+    /// # Arguments
+    /// * `queue_name` - Name of the queue
+    /// * `max_depth` - Maximum queue depth (optional)
+    /// * `ack_deadline_secs` - ACK deadline in seconds (optional)
+    pub async fn create_queue(
+        &self,
+        queue_name: &str,
+        max_depth: Option<usize>,
+        ack_deadline_secs: Option<u64>,
+    ) -> Result<()> {
+        let config = (max_depth.is_some() || ack_deadline_secs.is_some()).then(|| QueueConfig {
+            max_depth,
+            ack_deadline_secs,
+            ..Default::default()
+        });
+
+        self.client
+            .execute(&QueueCreate {
+                name: queue_name.to_owned(),
+                config,
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Publish a message to a queue
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// client.queue().publish("tasks", b"process-video", Some(9), None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn publish(
+        &self,
+        queue_name: &str,
+        payload: &[u8],
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+    ) -> Result<String> {
+        let response = self
+            .client
+            .execute(&QueuePublish {
+                queue: queue_name.to_owned(),
+                payload: payload.to_vec(),
+                priority,
+                max_retries,
+                idempotency_key: None,
+                client_id: None,
+            })
+            .await?;
+        Ok(response.message_id)
+    }
+
+    /// Publish a message that is enqueued at most once per `idempotency_key`
+    ///
+    /// The server remembers keys for the queue's `dedup_window_secs`
+    /// (5 minutes unless set at creation), so retrying a publish whose answer
+    /// was lost with the same key returns the first message's id instead of
+    /// enqueueing it again. Needs the HTTP or WebSocket transport: the native
+    /// transports fail it with [`SynapError::UnsupportedCommand`](crate::SynapError::UnsupportedCommand).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let id = client
+    ///     .queue()
+    ///     .publish_idempotent("orders", b"order-42", None, None, "order-42")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn publish_idempotent(
+        &self,
+        queue_name: &str,
+        payload: &[u8],
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+        idempotency_key: &str,
+    ) -> Result<String> {
+        let response = self
+            .client
+            .execute(&QueuePublish {
+                queue: queue_name.to_owned(),
+                payload: payload.to_vec(),
+                priority,
+                max_retries,
+                idempotency_key: Some(idempotency_key.to_owned()),
+                client_id: None,
+            })
+            .await?;
+        Ok(response.message_id)
+    }
+
+    /// A publisher that keeps at most `max_in_flight` publishes waiting on
+    /// the server at once (at least 1). Clones share the limit, so one
+    /// publisher can be handed to every producer task.
+    pub fn publisher(&self, max_in_flight: usize) -> QueuePublisher {
+        QueuePublisher {
+            queues: self.clone(),
+            permits: Arc::new(Semaphore::new(max_in_flight.max(1))),
+            max_in_flight: max_in_flight.max(1),
+        }
+    }
+
+    /// Publish every payload to `queue_name`, pipelining up to
+    /// `max_in_flight` of them, and stream the message ids back in order.
+    ///
+    /// Payloads are pulled from `payloads` only as publishes complete, so a
+    /// producer of 100k messages holds at most `max_in_flight` requests open
+    /// instead of flooding the connection. A failed publish is an `Err` item;
+    /// the rest of the batch carries on. See [`QueuePublisher`] for priority
+    /// and retry options, or to share the limit between batches.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// use futures::StreamExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let payloads = (0..100_000).map(|i| format!("job-{i}").into_bytes());
+    /// let mut ids = client.queue().publish_batch("tasks", payloads, 64);
+    /// while let Some(id) = ids.next().await {
+    ///     id?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn publish_batch<I>(
+        &self,
+        queue_name: &str,
+        payloads: I,
+        max_in_flight: usize,
+    ) -> PublishStream
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+        I::Item: AsRef<[u8]> + Send + 'static,
+    {
+        self.publisher(max_in_flight)
+            .publish_batch(queue_name, payloads, None, None)
+    }
+
+    /// Consume a message from a queue
+    pub async fn consume(&self, queue_name: &str, consumer_id: &str) -> Result<Option<Message>> {
+        let response = self
+            .client
+            .execute(&QueueConsume {
+                queue: queue_name.to_owned(),
+                consumer_id: consumer_id.to_owned(),
+            })
+            .await?;
+        Ok(response.message)
+    }
+
+    /// Stream the messages waiting in a queue, in delivery order, without
+    /// consuming them (HTTP only)
+    ///
+    /// Fetches a page per request, so a deep queue is inspected in bounded
+    /// memory. Messages already delivered and awaiting an ack are not
+    /// listed, and a queue being consumed meanwhile may skip or repeat a
+    /// message.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let mut messages = client.queue().browse("jobs");
+    /// while let Some(message) = messages.next().await {
+    ///     let message = message?;
+    ///     println!("{} (priority {})", message.id, message.priority);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn browse(&self, queue_name: &str) -> PageStream<Message> {
+        let client = self.client.clone();
+        let queue = queue_name.to_owned();
+        Box::pin(async_stream::try_stream! {
+            let mut offset = 0;
+            loop {
+                let response = client
+                    .execute(&QueueBrowse {
+                        queue: queue.clone(),
+                        offset,
+                        limit: Some(PAGE_SIZE),
+                    })
+                    .await?;
+                for message in response.messages {
+                    yield message;
+                }
+                match response.next_offset {
+                    Some(next) => offset = next,
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// Acknowledge a message
+    pub async fn ack(&self, queue_name: &str, message_id: &str) -> Result<()> {
+        self.client
+            .execute(&QueueAck {
+                queue: queue_name.to_owned(),
+                message_id: message_id.to_owned(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Negative acknowledge a message (requeue)
+    pub async fn nack(&self, queue_name: &str, message_id: &str) -> Result<()> {
+        self.client
+            .execute(&QueueNack {
+                queue: queue_name.to_owned(),
+                message_id: message_id.to_owned(),
+                requeue: None,
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Get queue statistics
+    pub async fn stats(&self, queue_name: &str) -> Result<QueueStats> {
+        self.client
+            .execute(&Stats {
+                queue: queue_name.to_owned(),
+            })
+            .await
+    }
+
+    /// List all queues
+    pub async fn list(&self) -> Result<Vec<String>> {
+        let response = self.client.execute(&QueueList {}).await?;
+        Ok(response.queues)
+    }
+
+    /// Delete a queue
+    pub async fn delete_queue(&self, queue_name: &str) -> Result<()> {
+        self.client
+            .execute(&QueueDelete {
+                queue: queue_name.to_owned(),
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+/// Publishes with a bound on how many wait on the server at once, from
+/// [`QueueManager::publisher`].
+///
+/// Each publish takes a slot before it is sent and frees it when the server
+/// answers, so callers beyond the limit wait instead of opening more
+/// requests. Cheap to clone; clones share the slots.
+#[derive(Clone)]
+pub struct QueuePublisher {
+    queues: QueueManager,
+    permits: Arc<Semaphore>,
+    max_in_flight: usize,
+}
+
+impl QueuePublisher {
+    /// Publish one message, waiting for a free slot first. See
+    /// [`QueueManager::publish`].
+    pub async fn publish(
+        &self,
+        queue_name: &str,
+        payload: &[u8],
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+    ) -> Result<String> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("publisher semaphore is never closed");
+        self.queues
+            .publish(queue_name, payload, priority, max_retries)
+            .await
+    }
+
+    /// Publish every payload to `queue_name` with the same `priority` and
+    /// `max_retries`, streaming the message ids back in order. See
+    /// [`QueueManager::publish_batch`].
+    pub fn publish_batch<I>(
+        &self,
+        queue_name: &str,
+        payloads: I,
+        priority: Option<u8>,
+        max_retries: Option<u32>,
+    ) -> PublishStream
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+        I::Item: AsRef<[u8]> + Send + 'static,
+    {
+        let publisher = self.clone();
+        let queue_name: Arc<str> = Arc::from(queue_name);
+        let publishes = futures::stream::iter(payloads).map(move |payload| {
+            let publisher = publisher.clone();
+            let queue_name = Arc::clone(&queue_name);
+            async move {
+                publisher
+                    .publish(&queue_name, payload.as_ref(), priority, max_retries)
+                    .await
+            }
+        });
+        Box::pin(publishes.buffered(self.max_in_flight))
+    }
+
+    /// Publishes currently waiting on the server
+    pub fn in_flight(&self) -> usize {
+        self.max_in_flight - self.permits.available_permits()
+    }
+
+    /// The most publishes that wait on the server at once
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SynapConfig;
+    use crate::error::ApiError;
+    use crate::transport::Transport;
+    use async_trait::async_trait;
+    use serde_json::{Value, json};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Answers `queue.publish` after a pause with the payload's first byte
+    /// as the message id, failing payloads that start with 0, and records
+    /// how many publishes were waiting at once.
+    #[derive(Default)]
+    struct SlowQueue {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Transport for SlowQueue {
+        async fn send(&self, request: crate::protocol::Request) -> Result<Value> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            match request.payload["payload"][0].as_u64() {
+                Some(0) | None => Err(ApiError::new("rejected").into()),
+                Some(id) => Ok(json!({"message_id": format!("m{id}")})),
+            }
+        }
+    }
+
+    fn slow_client() -> (SynapClient, Arc<SlowQueue>) {
+        let transport = Arc::new(SlowQueue::default());
+        let config = SynapConfig::new("http://localhost:15500");
+        let client = SynapClient::with_transport(config, transport.clone()).unwrap();
+        (client, transport)
+    }
+
+    #[tokio::test]
+    async fn test_publish_batch_bounds_in_flight_and_keeps_order() {
+        let (client, transport) = slow_client();
+        let payloads: Vec<Vec<u8>> = (1..=20).map(|i| vec![i]).collect();
+
+        let ids: Vec<String> = client
+            .queue()
+            .publish_batch("jobs", payloads, 4)
+            .map(|id| id.unwrap())
+            .collect()
+            .await;
+
+        let expected: Vec<String> = (1..=20).map(|i| format!("m{i}")).collect();
+        assert_eq!(ids, expected);
+        let peak = transport.peak.load(Ordering::SeqCst);
+        assert!((2..=4).contains(&peak), "peak in flight was {peak}");
+    }
+
+    #[tokio::test]
+    async fn test_publish_batch_reports_failures_per_message() {
+        let (client, _) = slow_client();
+        let results: Vec<Result<String>> = client
+            .queue()
+            .publish_batch("jobs", [vec![1u8], vec![0], vec![3]], 2)
+            .collect()
+            .await;
+
+        assert_eq!(results[0].as_deref().unwrap(), "m1");
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_deref().unwrap(), "m3");
+    }
+
+    #[tokio::test]
+    async fn test_publisher_clones_share_the_limit() {
+        let (client, transport) = slow_client();
+        let publisher = client.queue().publisher(3);
+        assert_eq!(publisher.max_in_flight(), 3);
+
+        let tasks: Vec<_> = (1..=12u8)
+            .map(|i| {
+                let publisher = publisher.clone();
+                tokio::spawn(async move { publisher.publish("jobs", &[i], None, None).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        assert!(transport.peak.load(Ordering::SeqCst) <= 3);
+        assert_eq!(publisher.in_flight(), 0);
+        assert_eq!(client.queue().publisher(0).max_in_flight(), 1);
+    }
+
+    #[test]
+    fn test_queue_manager_creation() {
+        let config = SynapConfig::new("http://localhost:15500");
+        let client = SynapClient::new(config).unwrap();
+        let queue = client.queue();
+
+        assert!(std::mem::size_of_val(&queue) > 0);
+    }
+
+    #[test]
+    fn test_queue_manager_clone() {
+        let config = SynapConfig::new("http://localhost:15500");
+        let client = SynapClient::new(config).unwrap();
+        let queue1 = client.queue();
+        let queue2 = queue1.clone();
+
+        assert!(std::mem::size_of_val(&queue1) > 0);
+        assert!(std::mem::size_of_val(&queue2) > 0);
+    }
+}
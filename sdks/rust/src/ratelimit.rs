@@ -0,0 +1,131 @@
+//! Server-side rate limiters
+//!
+//! Sends the typed `ratelimit.*` commands from
+//! [`protocol::ratelimit`](crate::protocol::ratelimit). The server decides and
+//! updates the limiter in one step, which `INCR` + `EXPIRE` from the client
+//! cannot do without racing.
+//!
+//! The rate limit commands have no native mapping yet; use an `http://` URL.
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::protocol::ratelimit::{RateLimitAlgorithm, RateLimitCheck, RateLimitReset};
+use std::time::Duration;
+
+/// How a key is limited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimit {
+    /// Bursts of up to `max_tokens`, refilled at `refill_rate` per second
+    TokenBucket { max_tokens: u64, refill_rate: f64 },
+    /// At most `max_requests` per sliding `window`
+    SlidingWindow { max_requests: u64, window: Duration },
+}
+
+/// Outcome of a rate limit check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub allowed: bool,
+    /// Units still available after this check
+    pub remaining: u64,
+    /// How long to wait before the same check would be allowed; `None` when
+    /// it was allowed
+    pub retry_after: Option<Duration>,
+}
+
+/// Rate limiter operations
+#[derive(Clone)]
+pub struct RateLimiterManager {
+    client: SynapClient,
+}
+
+impl RateLimiterManager {
+    /// Create a new rate limiter interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Take one unit from the limiter on `key`
+    ///
+    /// The limiter is created full on first use; every check names the limit,
+    /// so changing it takes effect on the next call.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{RateLimit, SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let limit = RateLimit::TokenBucket { max_tokens: 10, refill_rate: 2.0 };
+    /// let status = client.rate_limiter().check("api:user:42", limit).await?;
+    /// if !status.allowed {
+    ///     println!("slow down, retry in {:?}", status.retry_after);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check(&self, key: &str, limit: RateLimit) -> Result<RateLimitStatus> {
+        self.check_cost(key, limit, 1).await
+    }
+
+    /// Take `cost` units from the limiter on `key`
+    ///
+    /// A denied check takes nothing. `cost` cannot exceed the limit's
+    /// `max_tokens` / `max_requests`.
+    pub async fn check_cost(
+        &self,
+        key: &str,
+        limit: RateLimit,
+        cost: u64,
+    ) -> Result<RateLimitStatus> {
+        let (algorithm, max_tokens, refill_rate, window) = match limit {
+            RateLimit::TokenBucket {
+                max_tokens,
+                refill_rate,
+            } => (
+                RateLimitAlgorithm::TokenBucket,
+                max_tokens,
+                Some(refill_rate),
+                None,
+            ),
+            RateLimit::SlidingWindow {
+                max_requests,
+                window,
+            } => (
+                RateLimitAlgorithm::SlidingWindow,
+                max_requests,
+                None,
+                Some(window.as_millis() as u64),
+            ),
+        };
+        let response = self
+            .client
+            .execute(&RateLimitCheck {
+                key: key.to_owned(),
+                max_tokens,
+                refill_rate,
+                window,
+                algorithm,
+                cost,
+            })
+            .await?;
+
+        Ok(RateLimitStatus {
+            allowed: response.allowed,
+            remaining: response.remaining,
+            retry_after: response.retry_after.map(Duration::from_millis),
+        })
+    }
+
+    /// Drop the limiter on `key`, so its next check starts full
+    ///
+    /// Returns `false` if the key held no limiter.
+    pub async fn reset(&self, key: &str) -> Result<bool> {
+        let response = self
+            .client
+            .execute(&RateLimitReset {
+                key: key.to_owned(),
+            })
+            .await?;
+        Ok(response.reset)
+    }
+}
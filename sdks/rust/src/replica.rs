@@ -0,0 +1,342 @@
+//! Read-replica routing.
+//!
+//! A [`SynapClient`](crate::SynapClient) configured with replica URLs sends
+//! read-only commands (see [`is_read_only`]) to a replica chosen by the
+//! [`ReadPreference`]; every other command goes to the primary. Replicas are
+//! probed in the background and taken out of rotation when a probe or a read
+//! fails at the connection level, then put back once a probe succeeds again.
+//!
+//! Replication is asynchronous, so a read served by a replica may not yet see
+//! a write the same client just made on the primary. Keep
+//! [`ReadPreference::Primary`] for read-your-writes paths.
+//!
+//! When the server runs automatic failover,
+//! [`SynapClient::replication_topology`](crate::SynapClient::replication_topology)
+//! reports the current master so callers can reconnect after a promotion.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use crate::error::SynapError;
+use crate::transport::Transport;
+
+pub use crate::protocol::replication::{
+    ReplicationTopologyResponse as ReplicationTopology, TopologyMaster,
+};
+
+/// Where read-only commands are sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReadPreference {
+    /// Every command goes to the primary (default).
+    #[default]
+    Primary,
+    /// Reads are spread round-robin over healthy replicas, falling back to the
+    /// primary when none is available.
+    Replica,
+    /// Reads go to whichever of the primary and the healthy replicas answered
+    /// the last health probe fastest.
+    Nearest,
+}
+
+/// Commands that never modify data and may be served by a replica.
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "kv.get",
+    "kv.mget",
+    "kv.exists",
+    "kv.keys",
+    "kv.scan",
+    "kv.ttl",
+    "kv.dbsize",
+    "kv.stats",
+    "hash.get",
+    "hash.getall",
+    "hash.scan",
+    "hash.mget",
+    "hash.exists",
+    "hash.keys",
+    "hash.vals",
+    "hash.len",
+    "list.lrange",
+    "list.llen",
+    "list.lindex",
+    "list.lpos",
+    "set.members",
+    "set.ismember",
+    "set.size",
+    "set.randmember",
+    "set.inter",
+    "set.union",
+    "set.diff",
+    "set.intercard",
+    "set.scan",
+    "sortedset.zscore",
+    "sortedset.zcard",
+    "sortedset.zrank",
+    "sortedset.zrevrank",
+    "sortedset.zcount",
+    "sortedset.zrange",
+    "sortedset.zrevrange",
+    "sortedset.zrangebyscore",
+    "sortedset.zinter",
+    "sortedset.zunion",
+    "sortedset.zdiff",
+    "sortedset.zscan",
+    "sortedset.stats",
+    "hyperloglog.pfcount",
+    "hyperloglog.stats",
+    "bitmap.getbit",
+    "bitmap.bitcount",
+    "bitmap.bitpos",
+    "bitmap.stats",
+    "geospatial.geodist",
+    "geospatial.geohash",
+    "geospatial.geopos",
+    "geospatial.georadius",
+    "geospatial.georadiusbymember",
+    "geospatial.geosearch",
+    "geospatial.stats",
+];
+
+/// Whether `command` only reads data and can be routed to a replica.
+pub fn is_read_only(command: &str) -> bool {
+    READ_ONLY_COMMANDS.contains(&command)
+}
+
+/// Whether an error means the endpoint itself is unreachable, as opposed to
+/// the server rejecting the command.
+pub(crate) fn is_endpoint_failure(error: &SynapError) -> bool {
+    match error {
+        SynapError::Timeout | SynapError::Transport(_) => true,
+        SynapError::HttpError(e) => e.is_connect() || e.is_timeout(),
+        _ => false,
+    }
+}
+
+/// Health of one replica, as reported by
+/// [`SynapClient::replica_status`](crate::SynapClient::replica_status).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicaStatus {
+    pub url: String,
+    /// Whether reads are currently routed to this replica.
+    pub healthy: bool,
+    /// Round trip of the last successful health probe.
+    pub latency: Option<Duration>,
+}
+
+/// A server the client can send commands to.
+pub(crate) struct Endpoint {
+    pub(crate) url: String,
+    pub(crate) base_url: Url,
+    pub(crate) transport: Arc<dyn Transport>,
+    healthy: AtomicBool,
+    /// Last probe round trip in microseconds; 0 until the first probe.
+    latency_us: AtomicU64,
+}
+
+impl Endpoint {
+    pub(crate) fn new(url: String, base_url: Url, transport: Arc<dyn Transport>) -> Self {
+        Self {
+            url,
+            base_url,
+            transport,
+            healthy: AtomicBool::new(true),
+            latency_us: AtomicU64::new(0),
+        }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn latency(&self) -> Option<Duration> {
+        match self.latency_us.load(Ordering::Relaxed) {
+            0 => None,
+            us => Some(Duration::from_micros(us)),
+        }
+    }
+
+    /// Take the endpoint out of rotation until the next successful probe.
+    pub(crate) fn mark_down(&self, error: &SynapError) {
+        if self.healthy.swap(false, Ordering::Relaxed) {
+            tracing::warn!(replica = %self.url, %error, "replica marked down");
+        }
+    }
+
+    /// Run one health probe with [`Transport::ping`].
+    async fn probe(&self) {
+        let started = Instant::now();
+        let result = self.transport.ping().await;
+
+        match result {
+            Ok(()) => {
+                let us = (started.elapsed().as_micros() as u64).max(1);
+                self.latency_us.store(us, Ordering::Relaxed);
+                if !self.healthy.swap(true, Ordering::Relaxed) {
+                    tracing::info!(replica = %self.url, "replica back in rotation");
+                }
+            }
+            Err(e) => self.mark_down(&e),
+        }
+    }
+}
+
+/// The replicas of one client plus the routing state shared by its clones.
+pub(crate) struct ReplicaSet {
+    replicas: Vec<Endpoint>,
+    /// Probe round trip to the primary, for [`ReadPreference::Nearest`].
+    primary_latency_us: AtomicU64,
+    next: AtomicUsize,
+}
+
+impl ReplicaSet {
+    pub(crate) fn new(replicas: Vec<Endpoint>) -> Self {
+        Self {
+            replicas,
+            primary_latency_us: AtomicU64::new(0),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pick the replica a read should go to, or `None` for the primary.
+    pub(crate) fn select(&self, preference: ReadPreference) -> Option<&Endpoint> {
+        match preference {
+            ReadPreference::Primary => None,
+            ReadPreference::Replica => {
+                let len = self.replicas.len();
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..len)
+                    .map(|i| &self.replicas[(start + i) % len])
+                    .find(|r| r.is_healthy())
+            }
+            ReadPreference::Nearest => {
+                let primary = match self.primary_latency_us.load(Ordering::Relaxed) {
+                    0 => u64::MAX,
+                    us => us,
+                };
+                self.replicas
+                    .iter()
+                    .filter(|r| r.is_healthy())
+                    .filter_map(|r| r.latency().map(|l| (l.as_micros() as u64, r)))
+                    .filter(|(us, _)| *us < primary)
+                    .min_by_key(|(us, _)| *us)
+                    .map(|(_, r)| r)
+            }
+        }
+    }
+
+    /// Probe every replica, and the primary when `primary` is given.
+    pub(crate) async fn check(&self, primary: Option<&Endpoint>) {
+        let probes = self.replicas.iter().map(|r| r.probe());
+        futures::future::join_all(probes).await;
+
+        if let Some(primary) = primary {
+            primary.probe().await;
+            let us = primary.latency_us.load(Ordering::Relaxed);
+            let us = if primary.is_healthy() { us } else { 0 };
+            self.primary_latency_us.store(us, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn status(&self) -> Vec<ReplicaStatus> {
+        self.replicas
+            .iter()
+            .map(|r| ReplicaStatus {
+                url: r.url.clone(),
+                healthy: r.is_healthy(),
+                latency: r.latency(),
+            })
+            .collect()
+    }
+}
+
+/// Probe `set` every `interval` until the client that owns it is dropped.
+pub(crate) fn spawn_health_checks(
+    set: Weak<ReplicaSet>,
+    primary: Weak<Endpoint>,
+    interval: Duration,
+) {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        tracing::debug!("no Tokio runtime, replica health checks disabled");
+        return;
+    };
+
+    handle.spawn(async move {
+        loop {
+            let (Some(set), Some(primary)) = (set.upgrade(), primary.upgrade()) else {
+                break;
+            };
+            set.check(Some(&primary)).await;
+            drop((set, primary));
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::MockTransport;
+
+    fn endpoint(url: &str) -> Endpoint {
+        Endpoint::new(
+            url.into(),
+            Url::parse(url).unwrap(),
+            Arc::new(MockTransport::default()),
+        )
+    }
+
+    #[test]
+    fn test_read_only_classification() {
+        assert!(is_read_only("kv.get"));
+        assert!(is_read_only("sortedset.zrange"));
+        assert!(!is_read_only("kv.set"));
+        assert!(!is_read_only("script.eval"));
+        assert!(!is_read_only("queue.consume"));
+    }
+
+    #[test]
+    fn test_replica_preference_round_robins_healthy_replicas() {
+        let set = ReplicaSet::new(vec![
+            endpoint("http://r1:15500"),
+            endpoint("http://r2:15500"),
+            endpoint("http://r3:15500"),
+        ]);
+        set.replicas[1].mark_down(&SynapError::Timeout);
+
+        let picked: Vec<_> = (0..4)
+            .map(|_| set.select(ReadPreference::Replica).unwrap().url.clone())
+            .collect();
+        assert!(picked.iter().all(|u| u != "http://r2:15500"));
+        assert!(picked.contains(&"http://r1:15500".to_string()));
+        assert!(picked.contains(&"http://r3:15500".to_string()));
+
+        set.replicas[0].mark_down(&SynapError::Timeout);
+        set.replicas[2].mark_down(&SynapError::Timeout);
+        assert!(set.select(ReadPreference::Replica).is_none());
+        assert!(set.select(ReadPreference::Primary).is_none());
+    }
+
+    #[test]
+    fn test_nearest_prefers_lowest_probe_latency() {
+        let set = ReplicaSet::new(vec![
+            endpoint("http://r1:15500"),
+            endpoint("http://r2:15500"),
+        ]);
+        // No probe yet: stay on the primary.
+        assert!(set.select(ReadPreference::Nearest).is_none());
+
+        set.replicas[0].latency_us.store(900, Ordering::Relaxed);
+        set.replicas[1].latency_us.store(300, Ordering::Relaxed);
+        set.primary_latency_us.store(500, Ordering::Relaxed);
+        assert_eq!(
+            set.select(ReadPreference::Nearest).unwrap().url,
+            "http://r2:15500"
+        );
+
+        set.primary_latency_us.store(100, Ordering::Relaxed);
+        assert!(set.select(ReadPreference::Nearest).is_none());
+    }
+}
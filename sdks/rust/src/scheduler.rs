@@ -0,0 +1,113 @@
+//! Cron schedules
+//!
+//! Sends the typed `schedule.*` commands from
+//! [`protocol::schedule`](crate::protocol::schedule). A schedule makes the
+//! server publish a templated message to a queue, Pub/Sub topic or stream
+//! room whenever its cron expression fires, so periodic jobs need no
+//! separate cron daemon. Schedules survive server restarts when persistence
+//! is enabled. Managing them needs admin permissions.
+//!
+//! The schedule commands have no native mapping yet; use an `http://` URL.
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::protocol::schedule::{
+    ScheduleDelete, ScheduleGet, ScheduleList, ScheduleSpec, ScheduleStatus, ScheduleTarget,
+};
+use serde_json::Value;
+
+/// Server-side cron schedules
+#[derive(Clone)]
+pub struct SchedulerManager {
+    client: SynapClient,
+}
+
+impl SchedulerManager {
+    /// Create a new scheduler interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Publish `payload` to `target` every time `cron` fires
+    ///
+    /// `cron` has five fields (minute, hour, day of month, month, day of
+    /// week) evaluated in UTC, or is one of `@hourly`, `@daily`, `@weekly`,
+    /// `@monthly`, `@yearly`. Strings in `payload` may use the placeholders
+    /// listed in [`protocol::schedule`](crate::protocol::schedule). Fails if
+    /// a schedule named `name` exists, the expression is invalid, or the
+    /// target queue or room does not.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{ScheduleTarget, SynapClient, SynapConfig};
+    /// # use serde_json::json;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let schedule = client
+    ///     .scheduler()
+    ///     .create(
+    ///         "nightly-report",
+    ///         "0 2 * * *",
+    ///         ScheduleTarget::Queue("reports".into()),
+    ///         json!({"report": "daily", "requested_at": "{{fire_time}}"}),
+    ///     )
+    ///     .await?;
+    /// println!("next run at {:?}", schedule.progress.next_fire_at);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create(
+        &self,
+        name: &str,
+        cron: &str,
+        target: ScheduleTarget,
+        payload: Value,
+    ) -> Result<ScheduleStatus> {
+        self.create_spec(ScheduleSpec {
+            name: name.to_owned(),
+            cron: cron.to_owned(),
+            target,
+            payload,
+            event: None,
+        })
+        .await
+    }
+
+    /// Start a schedule from a full spec, e.g. to set the `event` type of
+    /// stream publishes
+    pub async fn create_spec(&self, spec: ScheduleSpec) -> Result<ScheduleStatus> {
+        self.client.execute(&spec).await
+    }
+
+    /// Every schedule with its counters, sorted by name
+    pub async fn list(&self) -> Result<Vec<ScheduleStatus>> {
+        let response = self.client.execute(&ScheduleList {}).await?;
+        Ok(response.schedules)
+    }
+
+    /// One schedule with its counters, or `None` if there is none by that
+    /// name
+    pub async fn get(&self, name: &str) -> Result<Option<ScheduleStatus>> {
+        let response = self
+            .client
+            .execute(&ScheduleGet {
+                name: name.to_owned(),
+            })
+            .await?;
+        Ok(response.schedule)
+    }
+
+    /// Stop and remove a schedule
+    ///
+    /// Returns `false` if there was none by that name.
+    pub async fn delete(&self, name: &str) -> Result<bool> {
+        let response = self
+            .client
+            .execute(&ScheduleDelete {
+                name: name.to_owned(),
+            })
+            .await?;
+        Ok(response.deleted)
+    }
+}
@@ -1,136 +1,227 @@
-//! Lua scripting support
-
-use crate::client::SynapClient;
-use crate::error::Result;
-use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
-use serde_json::{Value, json};
-
-/// Options for executing scripts (keys, args, timeout)
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct ScriptEvalOptions {
-    #[serde(default)]
-    pub keys: Vec<String>,
-    #[serde(default)]
-    pub args: Vec<Value>,
-    #[serde(rename = "timeout_ms")]
-    pub timeout_ms: Option<u64>,
-}
-
-/// Response returned by EVAL/EVALSHA commands
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScriptEvalResponse<T> {
-    pub result: T,
-    pub sha1: String,
-}
-
-/// Response for SCRIPT EXISTS
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScriptExistsResponse {
-    pub exists: Vec<bool>,
-}
-
-/// Response for SCRIPT FLUSH
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScriptFlushResponse {
-    pub cleared: u64,
-}
-
-/// Response for SCRIPT KILL
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScriptKillResponse {
-    pub terminated: bool,
-}
-
-/// Lua scripting manager
-#[derive(Clone)]
-pub struct ScriptManager {
-    client: SynapClient,
-}
-
-impl ScriptManager {
-    pub(crate) fn new(client: SynapClient) -> Self {
-        Self { client }
-    }
-
-    /// Execute a Lua script using EVAL
-    pub async fn eval<T>(
-        &self,
-        script: &str,
-        options: ScriptEvalOptions,
-    ) -> Result<ScriptEvalResponse<T>>
-    where
-        T: DeserializeOwned,
-    {
-        let payload = json!({
-            "script": script,
-            "keys": options.keys,
-            "args": options.args,
-            "timeout_ms": options.timeout_ms,
-        });
-
-        let response = self.client.send_command("script.eval", payload).await?;
-        self.parse_eval_response(response)
-    }
-
-    /// Execute a cached script using SHA1 hash
-    pub async fn evalsha<T>(
-        &self,
-        sha1: &str,
-        options: ScriptEvalOptions,
-    ) -> Result<ScriptEvalResponse<T>>
-    where
-        T: DeserializeOwned,
-    {
-        let payload = json!({
-            "sha1": sha1,
-            "keys": options.keys,
-            "args": options.args,
-            "timeout_ms": options.timeout_ms,
-        });
-
-        let response = self.client.send_command("script.evalsha", payload).await?;
-        self.parse_eval_response(response)
-    }
-
-    /// Load a script into the cache and return its SHA1 hash
-    pub async fn load(&self, script: &str) -> Result<String> {
-        let payload = json!({ "script": script });
-        let response = self.client.send_command("script.load", payload).await?;
-        Ok(response["sha1"].as_str().unwrap_or_default().to_string())
-    }
-
-    /// Check whether scripts exist in cache
-    pub async fn exists(&self, hashes: &[impl AsRef<str>]) -> Result<Vec<bool>> {
-        let payload = json!({
-            "hashes": hashes.iter().map(|h| h.as_ref()).collect::<Vec<_>>()
-        });
-        let response = self.client.send_command("script.exists", payload).await?;
-        let parsed: ScriptExistsResponse = serde_json::from_value(response)?;
-        Ok(parsed.exists)
-    }
-
-    /// Flush all cached scripts
-    pub async fn flush(&self) -> Result<u64> {
-        let response = self.client.send_command("script.flush", json!({})).await?;
-        let parsed: ScriptFlushResponse = serde_json::from_value(response)?;
-        Ok(parsed.cleared)
-    }
-
-    /// Kill the currently running script (if any)
-    pub async fn kill(&self) -> Result<bool> {
-        let response = self.client.send_command("script.kill", json!({})).await?;
-        let parsed: ScriptKillResponse = serde_json::from_value(response)?;
-        Ok(parsed.terminated)
-    }
-
-    fn parse_eval_response<T>(&self, response: Value) -> Result<ScriptEvalResponse<T>>
-    where
-        T: DeserializeOwned,
-    {
-        let sha1 = response["sha1"].as_str().unwrap_or_default().to_string();
-        let result_value = response.get("result").cloned().unwrap_or(Value::Null);
-        let result: T = serde_json::from_value(result_value)?;
-        Ok(ScriptEvalResponse { result, sha1 })
-    }
-}
+//! Lua scripting support
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::protocol::script::{
+    FunctionDelete, FunctionFcall, FunctionFlush, FunctionList, FunctionLoad, ScriptEval,
+    ScriptEvalSha, ScriptExists, ScriptFlush, ScriptKill, ScriptLoad, ScriptResultResponse,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub use crate::protocol::script::{
+    FunctionLibraryInfo, ScriptExistsResponse, ScriptFlushResponse, ScriptKillResponse,
+};
+
+/// Options for executing scripts (keys, args, timeout)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScriptEvalOptions {
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<Value>,
+    #[serde(rename = "timeout_ms")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Response returned by EVAL/EVALSHA commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptEvalResponse<T> {
+    pub result: T,
+    pub sha1: String,
+}
+
+/// Lua scripting manager
+#[derive(Clone)]
+pub struct ScriptManager {
+    client: SynapClient,
+}
+
+impl ScriptManager {
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Execute a Lua script using EVAL
+    pub async fn eval<T>(
+        &self,
+        script: &str,
+        options: ScriptEvalOptions,
+    ) -> Result<ScriptEvalResponse<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .client
+            .execute(&ScriptEval {
+                script: script.to_owned(),
+                keys: options.keys,
+                args: options.args,
+                timeout_ms: options.timeout_ms,
+            })
+            .await?;
+        parse_eval_response(response)
+    }
+
+    /// Execute a cached script using SHA1 hash
+    pub async fn evalsha<T>(
+        &self,
+        sha1: &str,
+        options: ScriptEvalOptions,
+    ) -> Result<ScriptEvalResponse<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .client
+            .execute(&ScriptEvalSha {
+                sha1: sha1.to_owned(),
+                keys: options.keys,
+                args: options.args,
+                timeout_ms: options.timeout_ms,
+            })
+            .await?;
+        parse_eval_response(response)
+    }
+
+    /// Load a script into the cache and return its SHA1 hash
+    pub async fn load(&self, script: &str) -> Result<String> {
+        let response = self
+            .client
+            .execute(&ScriptLoad {
+                script: script.to_owned(),
+            })
+            .await?;
+        Ok(response.sha1)
+    }
+
+    /// Check whether scripts exist in cache
+    pub async fn exists(&self, hashes: &[impl AsRef<str>]) -> Result<Vec<bool>> {
+        let response = self
+            .client
+            .execute(&ScriptExists {
+                hashes: hashes.iter().map(|h| h.as_ref().to_owned()).collect(),
+            })
+            .await?;
+        Ok(response.exists)
+    }
+
+    /// Flush all cached scripts
+    pub async fn flush(&self) -> Result<u64> {
+        Ok(self.client.execute(&ScriptFlush {}).await?.cleared)
+    }
+
+    /// Kill the currently running script (if any)
+    ///
+    /// Returns `true` when a script was running. The killed script stops at its
+    /// next interrupt check and its caller receives a "Script killed" error;
+    /// writes it already made are kept.
+    pub async fn kill(&self) -> Result<bool> {
+        Ok(self.client.execute(&ScriptKill {}).await?.terminated)
+    }
+
+    /// Register a function library (FUNCTION LOAD) and return its name
+    ///
+    /// The code must start with `#!lua name=<library>` and register its
+    /// functions with `redis.register_function`. With `replace`, an existing
+    /// library of the same name is swapped out. Libraries are persisted by the
+    /// server when persistence is enabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::SynapClient;
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// let code = "#!lua name=counters\n\
+    ///     redis.register_function('bump', function(keys, args)\n\
+    ///       return redis.call('INCRBY', keys[1], args[1])\n\
+    ///     end)";
+    /// client.script().function_load(code, false).await?;
+    ///
+    /// let hits: i64 = client
+    ///     .script()
+    ///     .fcall("bump", vec!["hits".into()], vec![5.into()])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn function_load(&self, code: &str, replace: bool) -> Result<String> {
+        let response = self
+            .client
+            .execute(&FunctionLoad {
+                code: code.to_owned(),
+                replace,
+            })
+            .await?;
+        Ok(response.library)
+    }
+
+    /// Remove a function library (FUNCTION DELETE)
+    pub async fn function_delete(&self, library: &str) -> Result<bool> {
+        let response = self
+            .client
+            .execute(&FunctionDelete {
+                library: library.to_owned(),
+            })
+            .await?;
+        Ok(response.deleted)
+    }
+
+    /// List loaded function libraries (FUNCTION LIST)
+    pub async fn function_list(&self, with_code: bool) -> Result<Vec<FunctionLibraryInfo>> {
+        let response = self.client.execute(&FunctionList { with_code }).await?;
+        Ok(response.libraries)
+    }
+
+    /// Remove every function library (FUNCTION FLUSH)
+    pub async fn function_flush(&self) -> Result<u64> {
+        Ok(self.client.execute(&FunctionFlush {}).await?.cleared)
+    }
+
+    /// Call a function from a loaded library (FCALL)
+    pub async fn fcall<T>(&self, function: &str, keys: Vec<String>, args: Vec<Value>) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.fcall_with_options(
+            function,
+            ScriptEvalOptions {
+                keys,
+                args,
+                timeout_ms: None,
+            },
+        )
+        .await
+    }
+
+    /// Call a function with explicit keys, args and timeout (FCALL)
+    pub async fn fcall_with_options<T>(
+        &self,
+        function: &str,
+        options: ScriptEvalOptions,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .client
+            .execute(&FunctionFcall {
+                function: function.to_owned(),
+                keys: options.keys,
+                args: options.args,
+                timeout_ms: options.timeout_ms,
+            })
+            .await?;
+        Ok(serde_json::from_value(response.result)?)
+    }
+}
+
+fn parse_eval_response<T>(response: ScriptResultResponse) -> Result<ScriptEvalResponse<T>>
+where
+    T: DeserializeOwned,
+{
+    Ok(ScriptEvalResponse {
+        result: serde_json::from_value(response.result)?,
+        sha1: response.sha1,
+    })
+}
@@ -1,244 +1,290 @@
-//! Set data structure operations
-
-use crate::client::SynapClient;
-use crate::error::Result;
-use serde_json::json;
-
-/// Set data structure interface (Redis-compatible)
-///
-/// Set is a collection of unique strings with set algebra operations.
-#[derive(Clone)]
-pub struct SetManager {
-    client: SynapClient,
-}
-
-impl SetManager {
-    /// Create a new Set manager interface
-    pub(crate) fn new(client: SynapClient) -> Self {
-        Self { client }
-    }
-
-    /// Add members to set
-    pub async fn add<K>(&self, key: K, members: Vec<String>) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "members": members,
-        });
-
-        let response = self.client.send_command("set.add", payload).await?;
-        Ok(response.get("added").and_then(|v| v.as_u64()).unwrap_or(0) as usize)
-    }
-
-    /// Remove members from set
-    pub async fn rem<K>(&self, key: K, members: Vec<String>) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "members": members,
-        });
-
-        let response = self.client.send_command("set.rem", payload).await?;
-        Ok(response
-            .get("removed")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as usize)
-    }
-
-    /// Check if member exists in set
-    pub async fn is_member<K>(&self, key: K, member: String) -> Result<bool>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "member": member,
-        });
-
-        let response = self.client.send_command("set.ismember", payload).await?;
-        Ok(response
-            .get("is_member")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false))
-    }
-
-    /// Get all members of set
-    pub async fn members<K>(&self, key: K) -> Result<Vec<String>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("set.members", payload).await?;
-
-        let members = response
-            .get("members")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(members)
-    }
-
-    /// Get set cardinality (size)
-    pub async fn card<K>(&self, key: K) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("set.card", payload).await?;
-        Ok(response
-            .get("cardinality")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as usize)
-    }
-
-    /// Remove and return random members
-    pub async fn pop<K>(&self, key: K, count: usize) -> Result<Vec<String>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "count": count,
-        });
-
-        let response = self.client.send_command("set.pop", payload).await?;
-        let members = response
-            .get("members")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(members)
-    }
-
-    /// Get random members without removing
-    pub async fn rand_member<K>(&self, key: K, count: usize) -> Result<Vec<String>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "count": count,
-        });
-
-        let response = self.client.send_command("set.randmember", payload).await?;
-        let members = response
-            .get("members")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(members)
-    }
-
-    /// Move member from source to destination set
-    pub async fn r#move<S, D>(&self, source: S, destination: D, member: String) -> Result<bool>
-    where
-        S: AsRef<str>,
-        D: AsRef<str>,
-    {
-        let payload = json!({
-            "source": source.as_ref(),
-            "destination": destination.as_ref(),
-            "member": member,
-        });
-
-        let response = self.client.send_command("set.move", payload).await?;
-        Ok(response
-            .get("moved")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false))
-    }
-
-    /// Get intersection of sets
-    pub async fn inter(&self, keys: Vec<String>) -> Result<Vec<String>> {
-        let payload = json!({"keys": keys});
-        let response = self.client.send_command("set.inter", payload).await?;
-
-        let members = response
-            .get("members")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(members)
-    }
-
-    /// Get union of sets
-    pub async fn union(&self, keys: Vec<String>) -> Result<Vec<String>> {
-        let payload = json!({"keys": keys});
-        let response = self.client.send_command("set.union", payload).await?;
-
-        let members = response
-            .get("members")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(members)
-    }
-
-    /// Get difference of sets (first minus others)
-    pub async fn diff(&self, keys: Vec<String>) -> Result<Vec<String>> {
-        let payload = json!({"keys": keys});
-        let response = self.client.send_command("set.diff", payload).await?;
-
-        let members = response
-            .get("members")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(members)
-    }
-
-    /// Store intersection result in destination
-    pub async fn inter_store<D>(&self, destination: D, keys: Vec<String>) -> Result<usize>
-    where
-        D: AsRef<str>,
-    {
-        let payload = json!({
-            "destination": destination.as_ref(),
-            "keys": keys,
-        });
-
-        let response = self.client.send_command("set.interstore", payload).await?;
-        Ok(response
-            .get("cardinality")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as usize)
-    }
-
-    /// Store union result in destination
-    pub async fn union_store<D>(&self, destination: D, keys: Vec<String>) -> Result<usize>
-    where
-        D: AsRef<str>,
-    {
-        let payload = json!({
-            "destination": destination.as_ref(),
-            "keys": keys,
-        });
-
-        let response = self.client.send_command("set.unionstore", payload).await?;
-        Ok(response
-            .get("cardinality")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as usize)
-    }
-
-    /// Store difference result in destination
-    pub async fn diff_store<D>(&self, destination: D, keys: Vec<String>) -> Result<usize>
-    where
-        D: AsRef<str>,
-    {
-        let payload = json!({
-            "destination": destination.as_ref(),
-            "keys": keys,
-        });
-
-        let response = self.client.send_command("set.diffstore", payload).await?;
-        Ok(response
-            .get("cardinality")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as usize)
-    }
-}
+//! Set data structure operations
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::paging::{PAGE_SIZE, PageStream, ScanOptions, json_text, scan_stream};
+use crate::protocol::set::{
+    SAdd, SCard, SDiff, SDiffStore, SInter, SInterCard, SInterStore, SIsMember, SMembers, SMove,
+    SPop, SRandMember, SRem, SScan, SUnion, SUnionStore,
+};
+use serde_json::Value;
+
+/// Set data structure interface (Redis-compatible)
+///
+/// Set is a collection of unique strings with set algebra operations.
+#[derive(Clone)]
+pub struct SetManager {
+    client: SynapClient,
+}
+
+/// Set members as text: strings as they are, anything else as JSON
+fn member_texts(members: Vec<Value>) -> Vec<String> {
+    members.into_iter().map(json_text).collect()
+}
+
+impl SetManager {
+    /// Create a new Set manager interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Add members to set
+    pub async fn add<K>(&self, key: K, members: Vec<String>) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SAdd {
+                key: key.as_ref().to_owned(),
+                members: members.into_iter().map(Value::String).collect(),
+                client_id: None,
+            })
+            .await?;
+        Ok(response.added)
+    }
+
+    /// Remove members from set
+    pub async fn rem<K>(&self, key: K, members: Vec<String>) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SRem {
+                key: key.as_ref().to_owned(),
+                members: members.into_iter().map(Value::String).collect(),
+                client_id: None,
+            })
+            .await?;
+        Ok(response.removed)
+    }
+
+    /// Check if member exists in set
+    pub async fn is_member<K>(&self, key: K, member: String) -> Result<bool>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SIsMember {
+                key: key.as_ref().to_owned(),
+                member: Value::String(member),
+            })
+            .await?;
+        Ok(response.is_member)
+    }
+
+    /// Get all members of set
+    pub async fn members<K>(&self, key: K) -> Result<Vec<String>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SMembers {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(member_texts(response.members))
+    }
+
+    /// Get set cardinality (size)
+    pub async fn card<K>(&self, key: K) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SCard {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.size)
+    }
+
+    /// Remove and return up to `count` distinct random members
+    ///
+    /// A missing set has nothing to pop.
+    pub async fn pop<K>(&self, key: K, count: usize) -> Result<Vec<String>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SPop {
+                key: key.as_ref().to_owned(),
+                count: Some(count),
+            })
+            .await?;
+        Ok(member_texts(response.members))
+    }
+
+    /// Get random members without removing them
+    ///
+    /// As with Redis `SRANDMEMBER`, a positive `count` returns up to that many
+    /// distinct members, while a negative one returns exactly `-count`
+    /// members drawn independently, so the same member may repeat.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// // Three different variants for an A/B/C test
+    /// let arms = client.set().rand_member("experiment:arms", 3).await?;
+    /// // Ten draws with replacement
+    /// let draws = client.set().rand_member("experiment:arms", -10).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rand_member<K>(&self, key: K, count: i64) -> Result<Vec<String>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SRandMember {
+                key: key.as_ref().to_owned(),
+                count: Some(count),
+            })
+            .await?;
+        Ok(member_texts(response.members))
+    }
+
+    /// Move member from source to destination set
+    pub async fn r#move<S, D>(&self, source: S, destination: D, member: String) -> Result<bool>
+    where
+        S: AsRef<str>,
+        D: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SMove {
+                source: source.as_ref().to_owned(),
+                destination: destination.as_ref().to_owned(),
+                member: Value::String(member),
+            })
+            .await?;
+        Ok(response.moved)
+    }
+
+    /// Get intersection of sets
+    pub async fn inter(&self, keys: Vec<String>) -> Result<Vec<String>> {
+        let response = self.client.execute(&SInter { keys }).await?;
+        Ok(member_texts(response.members))
+    }
+
+    /// Get union of sets
+    pub async fn union(&self, keys: Vec<String>) -> Result<Vec<String>> {
+        let response = self.client.execute(&SUnion { keys }).await?;
+        Ok(member_texts(response.members))
+    }
+
+    /// Get difference of sets (first minus others)
+    pub async fn diff(&self, keys: Vec<String>) -> Result<Vec<String>> {
+        let response = self.client.execute(&SDiff { keys }).await?;
+        Ok(member_texts(response.members))
+    }
+
+    /// Stream every member of a set, in member order
+    ///
+    /// Unlike [`members`](Self::members), the members are fetched a page at
+    /// a time with `set.scan` (`SSCAN` on the binary transports), so a set of
+    /// any size can be processed in bounded memory.
+    pub fn scan<K>(&self, key: K) -> PageStream<String>
+    where
+        K: AsRef<str>,
+    {
+        self.scan_with(key, ScanOptions::default())
+    }
+
+    /// Like [`scan`](Self::scan), keeping only members that match
+    /// `options.pattern` and fetching `options.count` members per request
+    pub fn scan_with<K>(&self, key: K, options: ScanOptions) -> PageStream<String>
+    where
+        K: AsRef<str>,
+    {
+        let key = key.as_ref().to_owned();
+        scan_stream(
+            self.client.clone(),
+            move |cursor| SScan {
+                key: key.clone(),
+                cursor,
+                pattern: options.pattern.clone(),
+                count: Some(options.count.unwrap_or(PAGE_SIZE)),
+            },
+            |page| (member_texts(page.members), page.next_cursor),
+        )
+    }
+
+    /// Count the members common to all `keys`
+    ///
+    /// Counting stops at `limit` when one is given, which is cheaper than
+    /// [`inter`](Self::inter) when only a threshold matters.
+    pub async fn inter_card(&self, keys: Vec<String>, limit: Option<usize>) -> Result<usize> {
+        let response = self
+            .client
+            .execute(&SInterCard {
+                keys,
+                limit: limit.unwrap_or(0),
+            })
+            .await?;
+        Ok(response.count)
+    }
+
+    /// Store intersection result in destination
+    ///
+    /// Replaces `destination`, which may be one of `keys`, and removes it when
+    /// the result is empty. Returns the size of the result.
+    pub async fn inter_store<D>(&self, destination: D, keys: Vec<String>) -> Result<usize>
+    where
+        D: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SInterStore {
+                destination: destination.as_ref().to_owned(),
+                keys,
+            })
+            .await?;
+        Ok(response.count)
+    }
+
+    /// Store union result in destination
+    ///
+    /// Replaces `destination`, which may be one of `keys`, and removes it when
+    /// the result is empty. Returns the size of the result.
+    pub async fn union_store<D>(&self, destination: D, keys: Vec<String>) -> Result<usize>
+    where
+        D: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SUnionStore {
+                destination: destination.as_ref().to_owned(),
+                keys,
+            })
+            .await?;
+        Ok(response.count)
+    }
+
+    /// Store difference result in destination
+    ///
+    /// Replaces `destination`, which may be one of `keys`, and removes it when
+    /// the result is empty. Returns the size of the result.
+    pub async fn diff_store<D>(&self, destination: D, keys: Vec<String>) -> Result<usize>
+    where
+        D: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&SDiffStore {
+                destination: destination.as_ref().to_owned(),
+                keys,
+            })
+            .await?;
+        Ok(response.count)
+    }
+}
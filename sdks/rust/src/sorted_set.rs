@@ -1,504 +1,703 @@
-//! Sorted Set data structure operations
-//!
-//! Sorted Sets are collections of unique members, each associated with a score.
-//! Members are ordered by score, enabling range queries, ranking, and leaderboard functionality.
-//!
-//! Use cases:
-//! - Gaming leaderboards
-//! - Priority queues
-//! - Rate limiting with timestamps
-//! - Time-series data
-//! - Auto-complete with relevance scores
-
-use crate::client::SynapClient;
-use crate::error::Result;
-use serde::{Deserialize, Serialize};
-use serde_json::json;
-
-/// Sorted Set data structure interface (Redis-compatible)
-///
-/// Provides scored, ordered collections with ranking and range query capabilities.
-#[derive(Clone)]
-pub struct SortedSetManager {
-    client: SynapClient,
-}
-
-/// A member with its score
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScoredMember {
-    pub member: String,
-    pub score: f64,
-}
-
-impl SortedSetManager {
-    /// Create a new Sorted Set manager interface
-    pub(crate) fn new(client: SynapClient) -> Self {
-        Self { client }
-    }
-
-    /// Add member with score to sorted set (ZADD)
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::SynapClient;
-    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
-    /// client.sorted_set().add("leaderboard", "player1", 100.0).await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn add<K, M>(&self, key: K, member: M, score: f64) -> Result<bool>
-    where
-        K: AsRef<str>,
-        M: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "member": member.as_ref(),
-            "score": score,
-        });
-
-        let response = self.client.send_command("sortedset.zadd", payload).await?;
-        Ok(response.get("added").and_then(|v| v.as_u64()).unwrap_or(0) > 0)
-    }
-
-    /// Add multiple members with scores to sorted set (ZADD with array)
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::{SynapClient, sorted_set::ScoredMember};
-    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
-    /// let members = vec![
-    ///     ScoredMember { member: "player1".to_string(), score: 100.0 },
-    ///     ScoredMember { member: "player2".to_string(), score: 200.0 },
-    /// ];
-    /// client.sorted_set().add_multiple("leaderboard", members).await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn add_multiple<K>(&self, key: K, members: Vec<ScoredMember>) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "members": members.iter().map(|m| json!({
-                "member": m.member,
-                "score": m.score,
-            })).collect::<Vec<_>>(),
-        });
-
-        let response = self.client.send_command("sortedset.zadd", payload).await?;
-        Ok(response.get("added").and_then(|v| v.as_u64()).unwrap_or(0) as usize)
-    }
-
-    /// Remove members from sorted set (ZREM)
-    pub async fn rem<K>(&self, key: K, members: Vec<String>) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "members": members,
-        });
-
-        let response = self.client.send_command("sortedset.zrem", payload).await?;
-        Ok(response
-            .get("removed")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as usize)
-    }
-
-    /// Get score of a member (ZSCORE)
-    pub async fn score<K, M>(&self, key: K, member: M) -> Result<Option<f64>>
-    where
-        K: AsRef<str>,
-        M: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "member": member.as_ref(),
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zscore", payload)
-            .await?;
-        Ok(response.get("score").and_then(|v| v.as_f64()))
-    }
-
-    /// Get cardinality (number of members) (ZCARD)
-    pub async fn card<K>(&self, key: K) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({"key": key.as_ref()});
-        let response = self.client.send_command("sortedset.zcard", payload).await?;
-        Ok(response.get("count").and_then(|v| v.as_u64()).unwrap_or(0) as usize)
-    }
-
-    /// Increment score of member (ZINCRBY)
-    pub async fn incr_by<K, M>(&self, key: K, member: M, increment: f64) -> Result<f64>
-    where
-        K: AsRef<str>,
-        M: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "member": member.as_ref(),
-            "increment": increment,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zincrby", payload)
-            .await?;
-        Ok(response
-            .get("score")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0))
-    }
-
-    /// Get range by rank (0-based index) (ZRANGE)
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::SynapClient;
-    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
-    /// // Get top 10 from leaderboard
-    /// let top10 = client.sorted_set().range("leaderboard", 0, 9, true).await?;
-    /// for member in top10 {
-    ///     tracing::info!("{}: {}", member.member, member.score);
-    /// }
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn range<K>(
-        &self,
-        key: K,
-        start: i64,
-        stop: i64,
-        with_scores: bool,
-    ) -> Result<Vec<ScoredMember>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "start": start,
-            "stop": stop,
-            "withscores": with_scores,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zrange", payload)
-            .await?;
-
-        if let Some(members_val) = response.get("members") {
-            Ok(serde_json::from_value(members_val.clone()).unwrap_or_default())
-        } else {
-            Ok(Vec::new())
-        }
-    }
-
-    /// Get reverse range by rank (highest to lowest) (ZREVRANGE)
-    pub async fn rev_range<K>(
-        &self,
-        key: K,
-        start: i64,
-        stop: i64,
-        with_scores: bool,
-    ) -> Result<Vec<ScoredMember>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "start": start,
-            "stop": stop,
-            "withscores": with_scores,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zrevrange", payload)
-            .await?;
-
-        if let Some(members_val) = response.get("members") {
-            Ok(serde_json::from_value(members_val.clone()).unwrap_or_default())
-        } else {
-            Ok(Vec::new())
-        }
-    }
-
-    /// Get rank of member (0-based, lowest score = rank 0) (ZRANK)
-    pub async fn rank<K, M>(&self, key: K, member: M) -> Result<Option<usize>>
-    where
-        K: AsRef<str>,
-        M: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "member": member.as_ref(),
-        });
-
-        let response = self.client.send_command("sortedset.zrank", payload).await?;
-        Ok(response
-            .get("rank")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as usize))
-    }
-
-    /// Get reverse rank of member (0-based, highest score = rank 0) (ZREVRANK)
-    pub async fn rev_rank<K, M>(&self, key: K, member: M) -> Result<Option<usize>>
-    where
-        K: AsRef<str>,
-        M: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "member": member.as_ref(),
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zrevrank", payload)
-            .await?;
-        Ok(response
-            .get("rank")
-            .and_then(|v| v.as_u64())
-            .map(|v| v as usize))
-    }
-
-    /// Count members with scores in range (ZCOUNT)
-    pub async fn count<K>(&self, key: K, min: f64, max: f64) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "min": min,
-            "max": max,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zcount", payload)
-            .await?;
-        Ok(response.get("count").and_then(|v| v.as_u64()).unwrap_or(0) as usize)
-    }
-
-    /// Get range by score (ZRANGEBYSCORE)
-    pub async fn range_by_score<K>(
-        &self,
-        key: K,
-        min: f64,
-        max: f64,
-        with_scores: bool,
-    ) -> Result<Vec<ScoredMember>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "min": min,
-            "max": max,
-            "withscores": with_scores,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zrangebyscore", payload)
-            .await?;
-
-        if let Some(members_val) = response.get("members") {
-            Ok(serde_json::from_value(members_val.clone()).unwrap_or_default())
-        } else {
-            Ok(Vec::new())
-        }
-    }
-
-    /// Pop minimum scored members (ZPOPMIN)
-    pub async fn pop_min<K>(&self, key: K, count: usize) -> Result<Vec<ScoredMember>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "count": count,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zpopmin", payload)
-            .await?;
-
-        if let Some(members_val) = response.get("members") {
-            Ok(serde_json::from_value(members_val.clone()).unwrap_or_default())
-        } else {
-            Ok(Vec::new())
-        }
-    }
-
-    /// Pop maximum scored members (ZPOPMAX)
-    pub async fn pop_max<K>(&self, key: K, count: usize) -> Result<Vec<ScoredMember>>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "count": count,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zpopmax", payload)
-            .await?;
-
-        if let Some(members_val) = response.get("members") {
-            Ok(serde_json::from_value(members_val.clone()).unwrap_or_default())
-        } else {
-            Ok(Vec::new())
-        }
-    }
-
-    /// Remove members by rank range (ZREMRANGEBYRANK)
-    pub async fn rem_range_by_rank<K>(&self, key: K, start: i64, stop: i64) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "start": start,
-            "stop": stop,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zremrangebyrank", payload)
-            .await?;
-        Ok(response
-            .get("removed")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as usize)
-    }
-
-    /// Remove members by score range (ZREMRANGEBYSCORE)
-    pub async fn rem_range_by_score<K>(&self, key: K, min: f64, max: f64) -> Result<usize>
-    where
-        K: AsRef<str>,
-    {
-        let payload = json!({
-            "key": key.as_ref(),
-            "min": min,
-            "max": max,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zremrangebyscore", payload)
-            .await?;
-        Ok(response
-            .get("removed")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0) as usize)
-    }
-
-    /// Compute intersection and store in destination (ZINTERSTORE)
-    ///
-    /// # Example
-    /// ```no_run
-    /// # use synap_sdk::SynapClient;
-    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
-    /// // Intersect two leaderboards
-    /// let count = client.sorted_set().inter_store(
-    ///     "combined",
-    ///     vec!["board1", "board2"],
-    ///     None,
-    ///     "sum",
-    /// ).await?;
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub async fn inter_store<D>(
-        &self,
-        destination: D,
-        keys: Vec<&str>,
-        weights: Option<Vec<f64>>,
-        aggregate: &str,
-    ) -> Result<usize>
-    where
-        D: AsRef<str>,
-    {
-        let payload = json!({
-            "destination": destination.as_ref(),
-            "keys": keys,
-            "weights": weights,
-            "aggregate": aggregate,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zinterstore", payload)
-            .await?;
-        Ok(response.get("count").and_then(|v| v.as_u64()).unwrap_or(0) as usize)
-    }
-
-    /// Compute union and store in destination (ZUNIONSTORE)
-    pub async fn union_store<D>(
-        &self,
-        destination: D,
-        keys: Vec<&str>,
-        weights: Option<Vec<f64>>,
-        aggregate: &str,
-    ) -> Result<usize>
-    where
-        D: AsRef<str>,
-    {
-        let payload = json!({
-            "destination": destination.as_ref(),
-            "keys": keys,
-            "weights": weights,
-            "aggregate": aggregate,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zunionstore", payload)
-            .await?;
-        Ok(response.get("count").and_then(|v| v.as_u64()).unwrap_or(0) as usize)
-    }
-
-    /// Compute difference and store in destination (ZDIFFSTORE)
-    pub async fn diff_store<D>(&self, destination: D, keys: Vec<&str>) -> Result<usize>
-    where
-        D: AsRef<str>,
-    {
-        let payload = json!({
-            "destination": destination.as_ref(),
-            "keys": keys,
-        });
-
-        let response = self
-            .client
-            .send_command("sortedset.zdiffstore", payload)
-            .await?;
-        Ok(response.get("count").and_then(|v| v.as_u64()).unwrap_or(0) as usize)
-    }
-
-    /// Get statistics
-    pub async fn stats(&self) -> Result<SortedSetStats> {
-        let payload = json!({});
-        let response = self.client.send_command("sortedset.stats", payload).await?;
-        Ok(serde_json::from_value(response).unwrap_or_default())
-    }
-}
-
-/// Statistics for sorted sets
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct SortedSetStats {
-    pub total_keys: usize,
-    pub total_members: usize,
-    pub avg_members_per_key: f64,
-    pub memory_bytes: usize,
-}
+//! Sorted Set data structure operations
+//!
+//! Sorted Sets are collections of unique members, each associated with a score.
+//! Members are ordered by score, enabling range queries, ranking, and leaderboard functionality.
+//!
+//! Use cases:
+//! - Gaming leaderboards
+//! - Priority queues
+//! - Rate limiting with timestamps
+//! - Time-series data
+//! - Auto-complete with relevance scores
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::paging::{PAGE_SIZE, PageStream, ScanOptions, scan_stream};
+use crate::protocol::Command;
+use crate::protocol::sorted_set::{
+    SortedSetStats as Stats, ZAdd, ZAddMany, ZCard, ZCombineResponse, ZCount, ZDiff, ZDiffStore,
+    ZIncrBy, ZInter, ZInterStore, ZNotifyDel, ZNotifyGet, ZNotifySet, ZPopMax, ZPopMin, ZRange,
+    ZRangeByScore, ZRank, ZRem, ZRemRangeByRank, ZRemRangeByScore, ZRevRange, ZRevRank, ZScan,
+    ZScore, ZUnion, ZUnionStore,
+};
+use crate::reactive::{MessageStream, SubscriptionHandle};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub use crate::protocol::sorted_set::{
+    LeaderboardWindow, ScoredMember, SortedSetStatsResponse as SortedSetStats,
+};
+
+/// Sorted Set data structure interface (Redis-compatible)
+///
+/// Provides scored, ordered collections with ranking and range query capabilities.
+#[derive(Clone)]
+pub struct SortedSetManager {
+    client: SynapClient,
+}
+
+impl SortedSetManager {
+    /// Create a new Sorted Set manager interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Add member with score to sorted set (ZADD)
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::SynapClient;
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// client.sorted_set().add("leaderboard", "player1", 100.0).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add<K, M>(&self, key: K, member: M, score: f64) -> Result<bool>
+    where
+        K: AsRef<str>,
+        M: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZAdd {
+                key: key.as_ref().to_owned(),
+                member: member.as_ref().to_owned(),
+                score,
+                ..Default::default()
+            })
+            .await?;
+        Ok(response.added > 0)
+    }
+
+    /// Add multiple members with scores to sorted set (ZADD with array)
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, sorted_set::ScoredMember};
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// let members = vec![
+    ///     ScoredMember { member: "player1".to_string(), score: 100.0 },
+    ///     ScoredMember { member: "player2".to_string(), score: 200.0 },
+    /// ];
+    /// client.sorted_set().add_multiple("leaderboard", members).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_multiple<K>(&self, key: K, members: Vec<ScoredMember>) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZAddMany {
+                key: key.as_ref().to_owned(),
+                members,
+            })
+            .await?;
+        Ok(response.added)
+    }
+
+    /// Remove members from sorted set (ZREM)
+    pub async fn rem<K>(&self, key: K, members: Vec<String>) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZRem {
+                key: key.as_ref().to_owned(),
+                members,
+            })
+            .await?;
+        Ok(response.removed)
+    }
+
+    /// Get score of a member (ZSCORE)
+    pub async fn score<K, M>(&self, key: K, member: M) -> Result<Option<f64>>
+    where
+        K: AsRef<str>,
+        M: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZScore {
+                key: key.as_ref().to_owned(),
+                member: member.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.score)
+    }
+
+    /// Get cardinality (number of members) (ZCARD)
+    pub async fn card<K>(&self, key: K) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZCard {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.count)
+    }
+
+    /// Increment score of member (ZINCRBY)
+    pub async fn incr_by<K, M>(&self, key: K, member: M, increment: f64) -> Result<f64>
+    where
+        K: AsRef<str>,
+        M: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZIncrBy {
+                key: key.as_ref().to_owned(),
+                member: member.as_ref().to_owned(),
+                increment,
+            })
+            .await?;
+        Ok(response.score.unwrap_or(0.0))
+    }
+
+    /// Get range by rank (0-based index) (ZRANGE)
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::SynapClient;
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// // Get top 10 from leaderboard
+    /// let top10 = client.sorted_set().range("leaderboard", 0, 9, true).await?;
+    /// for member in top10 {
+    ///     tracing::info!("{}: {}", member.member, member.score);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn range<K>(
+        &self,
+        key: K,
+        start: i64,
+        stop: i64,
+        with_scores: bool,
+    ) -> Result<Vec<ScoredMember>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZRange {
+                key: key.as_ref().to_owned(),
+                start,
+                stop,
+                withscores: with_scores,
+            })
+            .await?;
+        Ok(response.members)
+    }
+
+    /// Stream every member and score of a sorted set, in member order
+    /// (ZSCAN)
+    ///
+    /// The members are fetched a page at a time, so a sorted set of any size
+    /// can be processed in bounded memory. Use [`range`](Self::range) for
+    /// score order.
+    pub fn scan<K>(&self, key: K) -> PageStream<ScoredMember>
+    where
+        K: AsRef<str>,
+    {
+        self.scan_with(key, ScanOptions::default())
+    }
+
+    /// Like [`scan`](Self::scan), keeping only members that match
+    /// `options.pattern` and fetching `options.count` members per request
+    pub fn scan_with<K>(&self, key: K, options: ScanOptions) -> PageStream<ScoredMember>
+    where
+        K: AsRef<str>,
+    {
+        let key = key.as_ref().to_owned();
+        scan_stream(
+            self.client.clone(),
+            move |cursor| ZScan {
+                key: key.clone(),
+                cursor,
+                pattern: options.pattern.clone(),
+                count: Some(options.count.unwrap_or(PAGE_SIZE)),
+            },
+            |page| (page.members, page.next_cursor),
+        )
+    }
+
+    /// Get reverse range by rank (highest to lowest) (ZREVRANGE)
+    pub async fn rev_range<K>(
+        &self,
+        key: K,
+        start: i64,
+        stop: i64,
+        with_scores: bool,
+    ) -> Result<Vec<ScoredMember>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZRevRange {
+                key: key.as_ref().to_owned(),
+                start,
+                stop,
+                withscores: with_scores,
+            })
+            .await?;
+        Ok(response.members)
+    }
+
+    /// Get rank of member (0-based, lowest score = rank 0) (ZRANK)
+    pub async fn rank<K, M>(&self, key: K, member: M) -> Result<Option<usize>>
+    where
+        K: AsRef<str>,
+        M: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZRank {
+                key: key.as_ref().to_owned(),
+                member: member.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.rank)
+    }
+
+    /// Get reverse rank of member (0-based, highest score = rank 0) (ZREVRANK)
+    pub async fn rev_rank<K, M>(&self, key: K, member: M) -> Result<Option<usize>>
+    where
+        K: AsRef<str>,
+        M: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZRevRank {
+                key: key.as_ref().to_owned(),
+                member: member.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.rank)
+    }
+
+    /// Count members with scores in range (ZCOUNT)
+    pub async fn count<K>(&self, key: K, min: f64, max: f64) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZCount {
+                key: key.as_ref().to_owned(),
+                min,
+                max,
+            })
+            .await?;
+        Ok(response.count)
+    }
+
+    /// Get range by score (ZRANGEBYSCORE)
+    pub async fn range_by_score<K>(
+        &self,
+        key: K,
+        min: f64,
+        max: f64,
+        with_scores: bool,
+    ) -> Result<Vec<ScoredMember>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZRangeByScore {
+                key: key.as_ref().to_owned(),
+                min,
+                max,
+                withscores: with_scores,
+            })
+            .await?;
+        Ok(response.members)
+    }
+
+    /// Pop minimum scored members (ZPOPMIN)
+    pub async fn pop_min<K>(&self, key: K, count: usize) -> Result<Vec<ScoredMember>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZPopMin {
+                key: key.as_ref().to_owned(),
+                count: Some(count),
+            })
+            .await?;
+        Ok(response.members)
+    }
+
+    /// Pop maximum scored members (ZPOPMAX)
+    pub async fn pop_max<K>(&self, key: K, count: usize) -> Result<Vec<ScoredMember>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZPopMax {
+                key: key.as_ref().to_owned(),
+                count: Some(count),
+            })
+            .await?;
+        Ok(response.members)
+    }
+
+    /// Remove members by rank range (ZREMRANGEBYRANK)
+    pub async fn rem_range_by_rank<K>(&self, key: K, start: i64, stop: i64) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZRemRangeByRank {
+                key: key.as_ref().to_owned(),
+                start,
+                stop,
+            })
+            .await?;
+        Ok(response.removed)
+    }
+
+    /// Remove members by score range (ZREMRANGEBYSCORE)
+    pub async fn rem_range_by_score<K>(&self, key: K, min: f64, max: f64) -> Result<usize>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZRemRangeByScore {
+                key: key.as_ref().to_owned(),
+                min,
+                max,
+            })
+            .await?;
+        Ok(response.removed)
+    }
+
+    /// Compute intersection and return members, ordered by score (ZINTER)
+    ///
+    /// Same `weights`/`aggregate` semantics as [`inter_store`](Self::inter_store),
+    /// without writing a destination key.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::SynapClient;
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// let both = client
+    ///     .sorted_set()
+    ///     .inter(vec!["board1", "board2"], None, "sum")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn inter(
+        &self,
+        keys: Vec<&str>,
+        weights: Option<Vec<f64>>,
+        aggregate: &str,
+    ) -> Result<Vec<String>> {
+        self.combine(&ZInter {
+            keys: owned(keys),
+            weights,
+            aggregate: aggregate.to_owned(),
+            withscores: false,
+        })
+        .await
+    }
+
+    /// Compute intersection and return members with aggregated scores (ZINTER WITHSCORES)
+    pub async fn inter_with_scores(
+        &self,
+        keys: Vec<&str>,
+        weights: Option<Vec<f64>>,
+        aggregate: &str,
+    ) -> Result<Vec<ScoredMember>> {
+        self.combine(&ZInter {
+            keys: owned(keys),
+            weights,
+            aggregate: aggregate.to_owned(),
+            withscores: true,
+        })
+        .await
+    }
+
+    /// Compute union and return members, ordered by score (ZUNION)
+    pub async fn union(
+        &self,
+        keys: Vec<&str>,
+        weights: Option<Vec<f64>>,
+        aggregate: &str,
+    ) -> Result<Vec<String>> {
+        self.combine(&ZUnion {
+            keys: owned(keys),
+            weights,
+            aggregate: aggregate.to_owned(),
+            withscores: false,
+        })
+        .await
+    }
+
+    /// Compute union and return members with aggregated scores (ZUNION WITHSCORES)
+    pub async fn union_with_scores(
+        &self,
+        keys: Vec<&str>,
+        weights: Option<Vec<f64>>,
+        aggregate: &str,
+    ) -> Result<Vec<ScoredMember>> {
+        self.combine(&ZUnion {
+            keys: owned(keys),
+            weights,
+            aggregate: aggregate.to_owned(),
+            withscores: true,
+        })
+        .await
+    }
+
+    /// Members of the first set not present in the others (ZDIFF)
+    pub async fn diff(&self, keys: Vec<&str>) -> Result<Vec<String>> {
+        self.combine(&ZDiff {
+            keys: owned(keys),
+            withscores: false,
+        })
+        .await
+    }
+
+    /// Like [`diff`](Self::diff), keeping the first set's scores (ZDIFF WITHSCORES)
+    pub async fn diff_with_scores(&self, keys: Vec<&str>) -> Result<Vec<ScoredMember>> {
+        self.combine(&ZDiff {
+            keys: owned(keys),
+            withscores: true,
+        })
+        .await
+    }
+
+    async fn combine<C, T>(&self, command: &C) -> Result<Vec<T>>
+    where
+        C: Command<Response = ZCombineResponse>,
+        T: DeserializeOwned,
+    {
+        let response = self.client.execute(command).await?;
+        Ok(serde_json::from_value(Value::Array(response.members))?)
+    }
+
+    /// Compute intersection and store in destination (ZINTERSTORE)
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::SynapClient;
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// // Intersect two leaderboards
+    /// let count = client.sorted_set().inter_store(
+    ///     "combined",
+    ///     vec!["board1", "board2"],
+    ///     None,
+    ///     "sum",
+    /// ).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn inter_store<D>(
+        &self,
+        destination: D,
+        keys: Vec<&str>,
+        weights: Option<Vec<f64>>,
+        aggregate: &str,
+    ) -> Result<usize>
+    where
+        D: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZInterStore {
+                destination: destination.as_ref().to_owned(),
+                keys: owned(keys),
+                weights,
+                aggregate: aggregate.to_owned(),
+            })
+            .await?;
+        Ok(response.count)
+    }
+
+    /// Compute union and store in destination (ZUNIONSTORE)
+    pub async fn union_store<D>(
+        &self,
+        destination: D,
+        keys: Vec<&str>,
+        weights: Option<Vec<f64>>,
+        aggregate: &str,
+    ) -> Result<usize>
+    where
+        D: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZUnionStore {
+                destination: destination.as_ref().to_owned(),
+                keys: owned(keys),
+                weights,
+                aggregate: aggregate.to_owned(),
+            })
+            .await?;
+        Ok(response.count)
+    }
+
+    /// Compute difference and store in destination (ZDIFFSTORE)
+    pub async fn diff_store<D>(&self, destination: D, keys: Vec<&str>) -> Result<usize>
+    where
+        D: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZDiffStore {
+                destination: destination.as_ref().to_owned(),
+                keys: owned(keys),
+            })
+            .await?;
+        Ok(response.count)
+    }
+
+    /// Configure the leaderboard notification window for a key
+    ///
+    /// Once set, mutations that move a member into or out of the top
+    /// `top_n`, or move a ranked member by at least `rank_threshold`
+    /// places, are published to the key's leaderboard channel. See
+    /// [`Self::watch_leaderboard`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{LeaderboardWindow, SynapClient};
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// let window = LeaderboardWindow { top_n: 10, rank_threshold: Some(3) };
+    /// client.sorted_set().set_leaderboard_window("leaderboard", window).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_leaderboard_window<K>(&self, key: K, window: LeaderboardWindow) -> Result<()>
+    where
+        K: AsRef<str>,
+    {
+        self.client
+            .execute(&ZNotifySet {
+                key: key.as_ref().to_owned(),
+                window,
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Get the leaderboard notification window configured for a key
+    pub async fn leaderboard_window<K>(&self, key: K) -> Result<Option<LeaderboardWindow>>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZNotifyGet {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.window)
+    }
+
+    /// Remove the leaderboard notification window for a key
+    ///
+    /// Returns `true` if a window was configured.
+    pub async fn remove_leaderboard_window<K>(&self, key: K) -> Result<bool>
+    where
+        K: AsRef<str>,
+    {
+        let response = self
+            .client
+            .execute(&ZNotifyDel {
+                key: key.as_ref().to_owned(),
+            })
+            .await?;
+        Ok(response.removed)
+    }
+
+    /// Stream leaderboard events for a key
+    ///
+    /// Subscribes to the key's `__zset@0__:<key>` channel through the
+    /// reactive pub/sub stack, so it works on every transport. Events are
+    /// only published while a window is configured for the key (see
+    /// [`Self::set_leaderboard_window`]) and delivery is best-effort:
+    /// re-read the top of the board with [`Self::rev_range`] after a gap in
+    /// [`LeaderboardEvent::version`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// # use synap_sdk::SynapClient;
+    /// # async fn example(client: &SynapClient) -> synap_sdk::Result<()> {
+    /// let (mut events, handle) = client.sorted_set().watch_leaderboard("leaderboard");
+    ///
+    /// while let Some(event) = events.next().await {
+    ///     println!("{} {} -> {:?}", event.event, event.member, event.rank);
+    /// }
+    ///
+    /// handle.unsubscribe();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_leaderboard<K>(
+        &self,
+        key: K,
+    ) -> (MessageStream<LeaderboardEvent>, SubscriptionHandle)
+    where
+        K: AsRef<str>,
+    {
+        let key = key.as_ref();
+        let (messages, handle) = self
+            .client
+            .pubsub()
+            .observe(format!("leaderboard-{key}"), vec![leaderboard_channel(key)]);
+        let events =
+            messages.filter_map(|message| async move { serde_json::from_value(message.data).ok() });
+        (Box::pin(events), handle)
+    }
+
+    /// Get statistics
+    pub async fn stats(&self) -> Result<SortedSetStats> {
+        self.client.execute(&Stats {}).await
+    }
+}
+
+/// One leaderboard change, as published on `__zset@0__:<key>`
+///
+/// Ranks are 0-based and count from the highest score, as in ZREVRANK.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEvent {
+    pub key: String,
+    /// `entered`, `left` or `rank_changed`
+    pub event: String,
+    pub member: String,
+    #[serde(default)]
+    pub score: Option<f64>,
+    #[serde(default)]
+    pub rank: Option<usize>,
+    #[serde(default)]
+    pub previous_rank: Option<usize>,
+    /// Per-key counter for gap detection
+    pub version: u64,
+}
+
+/// Owned key names
+fn owned(keys: Vec<&str>) -> Vec<String> {
+    keys.into_iter().map(str::to_owned).collect()
+}
+
+/// Pub/sub channel carrying leaderboard events for `key`
+fn leaderboard_channel(key: &str) -> String {
+    format!("__zset@0__:{key}")
+}
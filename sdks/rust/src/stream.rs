@@ -2,25 +2,18 @@
 
 use crate::client::SynapClient;
 use crate::error::Result;
-use crate::types::{Event, StreamStats};
-use serde::Deserialize;
-use serde_json::{Value, json};
-
-/// Wire format of a stream event as returned by the server.
-/// HTTP returns `data` as `Vec<u8>` (serde_json::to_vec of the original JSON).
-/// SynapRPC may return `data` as a string.  We accept both via `Value`.
-#[derive(Deserialize)]
-struct RawStreamEvent {
-    #[serde(default)]
-    offset: u64,
-    event: String,
-    data: Value,
-    #[serde(default)]
-    timestamp: Option<u64>,
-}
+use crate::paging::{PAGE_SIZE, PageStream};
+use crate::protocol::consumer_group::{ConsumerGroupLag, PartitionLag};
+use crate::protocol::stream::{
+    StreamConsume, StreamConsumeMany, StreamCreate, StreamDelete, StreamEvent, StreamGetOrCreate,
+    StreamList, StreamPublish, StreamRoomOffset, StreamStats as Stats,
+};
+use crate::types::{Event, MultiRoomBatch, RoomEvent, StreamStats};
+use serde_json::Value;
+use std::time::Duration;
 
-impl From<RawStreamEvent> for Event {
-    fn from(raw: RawStreamEvent) -> Self {
+impl From<StreamEvent> for Event {
+    fn from(raw: StreamEvent) -> Self {
         let data = match &raw.data {
             // HTTP: data arrives as JSON array of bytes → decode back to JSON
             Value::Array(arr) => {
@@ -60,13 +53,16 @@ impl StreamManager {
     }
 
     /// Create a new stream room
-    pub async fn create_room(&self, room: &str, max_events: Option<usize>) -> Result<()> {
-        let mut payload = json!({"room": room});
-        if let Some(max) = max_events {
-            payload["max_events"] = json!(max);
-        }
-
-        self.client.send_command("stream.create", payload).await?;
+    ///
+    /// A server sizes every room's buffer from its own configuration, so the
+    /// `max_events` bound only applies to the in-memory backend.
+    pub async fn create_room(&self, room: &str, _max_events: Option<usize>) -> Result<()> {
+        self.client
+            .execute(&StreamCreate {
+                room: room.to_owned(),
+                config: None,
+            })
+            .await?;
         Ok(())
     }
 
@@ -97,18 +93,14 @@ impl StreamManager {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn get_or_create_room(&self, room: &str, max_events: Option<usize>) -> Result<bool> {
-        let mut payload = json!({"room": room});
-        if let Some(max) = max_events {
-            payload["max_events"] = json!(max);
-        }
-
+    pub async fn get_or_create_room(&self, room: &str, _max_events: Option<usize>) -> Result<bool> {
         let response = self
             .client
-            .send_command("stream.get_or_create", payload)
+            .execute(&StreamGetOrCreate {
+                room: room.to_owned(),
+            })
             .await?;
-
-        Ok(response["created"].as_bool().unwrap_or(false))
+        Ok(response.created)
     }
 
     /// Publish an event to a stream
@@ -132,15 +124,44 @@ impl StreamManager {
     /// # }
     /// ```
     pub async fn publish(&self, room: &str, event: &str, data: Value) -> Result<u64> {
-        let payload = json!({
-            "room": room,
-            "event": event,
-            "data": data,
-        });
-
-        let response = self.client.send_command("stream.publish", payload).await?;
+        let response = self
+            .client
+            .execute(&StreamPublish {
+                room: room.to_owned(),
+                event: event.to_owned(),
+                data,
+                idempotency_key: None,
+                client_id: None,
+            })
+            .await?;
+        Ok(response.offset)
+    }
 
-        Ok(response["offset"].as_u64().unwrap_or(0))
+    /// Publish an event that is appended at most once per `idempotency_key`
+    ///
+    /// The server remembers keys for the room's `dedup_window_secs`
+    /// (5 minutes unless set at creation), so retrying a publish whose answer
+    /// was lost with the same key returns the first event's offset instead of
+    /// appending it again. Needs the HTTP or WebSocket transport: the native
+    /// transports fail it with [`SynapError::UnsupportedCommand`](crate::SynapError::UnsupportedCommand).
+    pub async fn publish_idempotent(
+        &self,
+        room: &str,
+        event: &str,
+        data: Value,
+        idempotency_key: &str,
+    ) -> Result<u64> {
+        let response = self
+            .client
+            .execute(&StreamPublish {
+                room: room.to_owned(),
+                event: event.to_owned(),
+                data,
+                idempotency_key: Some(idempotency_key.to_owned()),
+                client_id: None,
+            })
+            .await?;
+        Ok(response.offset)
     }
 
     /// Consume events from a stream
@@ -155,39 +176,196 @@ impl StreamManager {
         offset: Option<u64>,
         limit: Option<usize>,
     ) -> Result<Vec<Event>> {
-        let payload = json!({
-            "room": room,
-            "subscriber_id": "sdk-default",
-            "from_offset": offset.unwrap_or(0),
-            "limit": limit,
-        });
+        self.consume_with_min_age(room, offset, limit, 0).await
+    }
+
+    /// Stream a room's events from `offset` up to the newest one
+    ///
+    /// Pages through [`consume`](Self::consume) and ends once it has caught
+    /// up, so a long history can be replayed in bounded memory. To keep
+    /// following the room after that, use
+    /// [`observe_events`](Self::observe_events).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let mut events = client.stream().events("audit-log", None);
+    /// while let Some(event) = events.next().await {
+    ///     let event = event?;
+    ///     println!("#{} {}", event.offset, event.event);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn events(&self, room: &str, offset: Option<u64>) -> PageStream<Event> {
+        let manager = self.clone();
+        let room = room.to_owned();
+        Box::pin(async_stream::try_stream! {
+            let mut offset = offset.unwrap_or(0);
+            loop {
+                let events = manager.consume(&room, Some(offset), Some(PAGE_SIZE)).await?;
+                let Some(last) = events.last() else {
+                    break;
+                };
+                offset = last.offset + 1;
+                for event in events {
+                    yield event;
+                }
+            }
+        })
+    }
+
+    /// Lag of a consumer group on every partition of its topic
+    ///
+    /// Partitions come one page per request, in partition order; a group on
+    /// a topic with thousands of partitions is walked in bounded memory.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{CollectAll, SynapClient, SynapConfig};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let partitions = client.stream().group_lag("billing").collect_all(10_000).await?;
+    /// let behind: u64 = partitions.iter().map(|p| p.lag).sum();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn group_lag(&self, group_id: &str) -> PageStream<PartitionLag> {
+        let client = self.client.clone();
+        let group_id = group_id.to_owned();
+        Box::pin(async_stream::try_stream! {
+            let mut offset = 0;
+            loop {
+                let response = client
+                    .execute(&ConsumerGroupLag {
+                        group_id: group_id.clone(),
+                        offset,
+                        limit: Some(PAGE_SIZE),
+                    })
+                    .await?;
+                for partition in response.partitions {
+                    yield partition;
+                }
+                match response.next_offset {
+                    Some(next) => offset = next,
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// Consume events published at least `min_age_ms` milliseconds ago
+    ///
+    /// Younger events are held back by the server, so the consumer lags
+    /// behind real time (e.g. to leave room for upstream corrections). The
+    /// batch stops at the first event that is too recent.
+    pub async fn consume_with_min_age(
+        &self,
+        room: &str,
+        offset: Option<u64>,
+        limit: Option<usize>,
+        min_age_ms: u64,
+    ) -> Result<Vec<Event>> {
+        let response = self
+            .client
+            .execute(&StreamConsume {
+                room: room.to_owned(),
+                subscriber_id: "sdk-default".to_owned(),
+                from_offset: offset.unwrap_or(0),
+                limit,
+                min_age_ms: (min_age_ms > 0).then_some(min_age_ms),
+            })
+            .await?;
+        Ok(response.events.into_iter().map(Into::into).collect())
+    }
 
-        let response = self.client.send_command("stream.consume", payload).await?;
+    /// Consume from several rooms in one call, each from its own offset
+    ///
+    /// Events come back interleaved by publish time and tagged with their
+    /// room; `next_offsets` says where to resume each room. With a `wait`,
+    /// the server long-polls until any room has events (capped at 30s).
+    ///
+    /// Only available over HTTP; the binary transports return
+    /// [`SynapError::UnsupportedCommand`](crate::SynapError::UnsupportedCommand).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let batch = client
+    ///     .stream()
+    ///     .consume_many(&[("cpu", 0), ("mem", 42)], Some(100), Some(Duration::from_secs(5)))
+    ///     .await?;
+    /// for item in &batch.events {
+    ///     println!("{} #{}: {}", item.room, item.event.offset, item.event.data);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn consume_many(
+        &self,
+        rooms: &[(&str, u64)],
+        limit: Option<usize>,
+        wait: Option<Duration>,
+    ) -> Result<MultiRoomBatch> {
+        let response = self
+            .client
+            .execute(&StreamConsumeMany {
+                subscriber_id: "sdk-default".to_owned(),
+                rooms: rooms
+                    .iter()
+                    .map(|&(room, offset)| StreamRoomOffset {
+                        room: room.to_owned(),
+                        offset,
+                    })
+                    .collect(),
+                limit,
+                timeout_ms: wait.map(|wait| wait.as_millis() as u64),
+            })
+            .await?;
 
-        // Server stores data as Vec<u8> (serde_json::to_vec) and serialises the
-        // struct directly, so `data` arrives as a JSON byte-array.  We decode
-        // each event's data bytes back into the original JSON value.
-        let raw_events: Vec<RawStreamEvent> = serde_json::from_value(response["events"].clone())?;
-        Ok(raw_events.into_iter().map(Into::into).collect())
+        Ok(MultiRoomBatch {
+            events: response
+                .events
+                .into_iter()
+                .map(|raw| RoomEvent {
+                    room: raw.room.clone(),
+                    event: raw.into(),
+                })
+                .collect(),
+            next_offsets: response.next_offsets,
+        })
     }
 
     /// Get stream statistics
     pub async fn stats(&self, room: &str) -> Result<StreamStats> {
-        let payload = json!({"room": room});
-        let response = self.client.send_command("stream.stats", payload).await?;
-        Ok(serde_json::from_value(response)?)
+        self.client
+            .execute(&Stats {
+                room: room.to_owned(),
+            })
+            .await
     }
 
     /// List all stream rooms
     pub async fn list(&self) -> Result<Vec<String>> {
-        let response = self.client.send_command("stream.list", json!({})).await?;
-        Ok(serde_json::from_value(response["rooms"].clone())?)
+        Ok(self.client.execute(&StreamList {}).await?.rooms)
     }
 
     /// Delete a stream room
     pub async fn delete_room(&self, room: &str) -> Result<()> {
-        let payload = json!({"room": room});
-        self.client.send_command("stream.delete", payload).await?;
+        self.client
+            .execute(&StreamDelete {
+                room: room.to_owned(),
+            })
+            .await?;
         Ok(())
     }
 }
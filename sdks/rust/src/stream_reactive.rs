@@ -3,7 +3,7 @@
 //! Provides Stream-based event consumption for event streams.
 
 use crate::reactive::{MessageStream, SubscriptionHandle};
-use crate::types::Event;
+use crate::types::{Event, RoomEvent};
 use futures::Stream;
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -134,4 +134,83 @@ impl crate::stream::StreamManager {
 
         (stream, handle)
     }
+
+    /// Subscribe to several rooms at once, each from its own offset
+    ///
+    /// Events from all rooms arrive on one stream, interleaved and tagged with
+    /// their room, over a single long-poll loop instead of one poller per room.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use synap_sdk::{SynapClient, SynapConfig};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let (mut stream, handle) = client.stream().subscribe_many(&[("cpu", 0), ("mem", 0)]);
+    ///
+    /// while let Some(item) = stream.next().await {
+    ///     tracing::info!("{} #{}: {:?}", item.room, item.event.offset, item.event.data);
+    /// }
+    ///
+    /// handle.unsubscribe();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe_many(
+        &self,
+        rooms: &[(&str, u64)],
+    ) -> (impl Stream<Item = RoomEvent> + 'static, SubscriptionHandle) {
+        let mut offsets: Vec<(String, u64)> = rooms
+            .iter()
+            .map(|(room, offset)| (room.to_string(), *offset))
+            .collect();
+        let client = self.client.clone();
+
+        let (tx, rx) = mpsc::unbounded_channel::<RoomEvent>();
+        let (cancel_tx, mut cancel_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let rooms: Vec<(&str, u64)> = offsets
+                    .iter()
+                    .map(|(room, offset)| (room.as_str(), *offset))
+                    .collect();
+                let stream = client.stream();
+                let consume = stream.consume_many(&rooms, Some(100), Some(Duration::from_secs(5)));
+
+                tokio::select! {
+                    _ = cancel_rx.recv() => {
+                        tracing::debug!("Multi-room subscription cancelled");
+                        break;
+                    }
+                    result = consume => match result {
+                        Ok(batch) => {
+                            for (room, offset) in offsets.iter_mut() {
+                                if let Some(next) = batch.next_offsets.get(room.as_str()) {
+                                    *offset = *next;
+                                }
+                            }
+                            for event in batch.events {
+                                if tx.send(event).is_err() {
+                                    return; // Receiver dropped
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!("Error consuming events: {}", e);
+                            sleep(Duration::from_secs(1)).await;
+                        }
+                    }
+                }
+            }
+        });
+
+        let stream: MessageStream<RoomEvent> =
+            Box::pin(tokio_stream::wrappers::UnboundedReceiverStream::new(rx));
+        let handle = SubscriptionHandle::new(cancel_tx);
+
+        (stream, handle)
+    }
 }
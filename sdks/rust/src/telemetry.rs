@@ -0,0 +1,62 @@
+//! OpenTelemetry support (`otel` feature).
+//!
+//! Every command runs in a `synap.command` span (fields `command` and `db`),
+//! so each manager call shows up in the application's traces. HTTP requests
+//! carry the span's context in a W3C `traceparent` header, which the server
+//! continues when it exports spans too.
+//!
+//! The SDK only creates spans. The application installs the exporter: a
+//! `tracing_opentelemetry` layer on its subscriber and a text map propagator,
+//! e.g. `TraceContextPropagator`, with
+//! `opentelemetry::global::set_text_map_propagator`. Without the feature both
+//! functions below do nothing.
+
+use reqwest::header::HeaderMap;
+use tracing::Span;
+
+/// Span around one command
+#[cfg(feature = "otel")]
+pub(crate) fn command_span(command: &str, db: u32) -> Span {
+    tracing::info_span!("synap.command", command, db)
+}
+
+/// Span around one command
+#[cfg(not(feature = "otel"))]
+pub(crate) fn command_span(_command: &str, _db: u32) -> Span {
+    Span::none()
+}
+
+/// Trace context headers for the current span
+#[cfg(feature = "otel")]
+pub(crate) fn trace_headers() -> HeaderMap {
+    use opentelemetry::global;
+    use opentelemetry::propagation::Injector;
+    use reqwest::header::{HeaderName, HeaderValue};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+    impl Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    let mut headers = HeaderMap::new();
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers))
+    });
+    headers
+}
+
+/// Trace context headers for the current span
+#[cfg(not(feature = "otel"))]
+pub(crate) fn trace_headers() -> HeaderMap {
+    HeaderMap::new()
+}
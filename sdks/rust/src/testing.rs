@@ -0,0 +1,225 @@
+//! Mock Synap servers for testing code that takes a [`SynapClient`]
+//!
+//! [`MockSynap`] collects the commands a test expects and the answers to
+//! give, then [`MockSynap::start`] serves them over HTTP with
+//! [`mockito`]. Each expectation matches a command envelope on
+//! `POST /api/v1/command` by name and (partial) payload, so tests state what
+//! the code under test sends without writing raw HTTP mocks:
+//!
+//! ```
+//! use serde_json::json;
+//! use synap_sdk::testing::MockSynap;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> synap_sdk::Result<()> {
+//! let synap = MockSynap::new()
+//!     .expect_kv_get("user:1", json!("alice"))
+//!     .expect_kv_set("user:1", json!("bob"))
+//!     .expect_queue_publish("emails", "msg-1")
+//!     .start()
+//!     .await;
+//!
+//! let client = synap.client();
+//! let name: Option<String> = client.kv().get("user:1").await?;
+//! assert_eq!(name.as_deref(), Some("alice"));
+//! client.kv().set("user:1", "bob", None).await?;
+//! client.queue().publish("emails", b"welcome", None, None).await?;
+//!
+//! synap.assert().await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Expectations are matched against what goes on the wire: with
+//! [`SynapConfig::with_key_prefix`] on the client, expect the prefixed keys.
+//! A command no expectation matches fails with an HTTP 501 error. Requires
+//! the `testing` feature.
+
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use serde_json::{Value, json};
+
+use crate::{SynapClient, SynapConfig};
+
+/// One expected command and the envelope to answer it with
+struct Expectation {
+    command: String,
+    payload: Value,
+    response: Value,
+    times: Option<usize>,
+}
+
+/// Builder for a mock Synap server; see the [module docs](self).
+#[derive(Default)]
+pub struct MockSynap {
+    expectations: Vec<Expectation>,
+}
+
+impl MockSynap {
+    /// A mock that expects nothing yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect `command` with a payload containing `payload` (a partial
+    /// match: fields left out match anything) and answer with `response`
+    /// as the envelope's payload.
+    pub fn expect_command(self, command: &str, payload: Value, response: Value) -> Self {
+        self.push(
+            command,
+            payload,
+            json!({"success": true, "payload": response}),
+        )
+    }
+
+    /// Expect `command` with a payload containing `payload`, and fail it
+    /// with the server error `code` (e.g. `QUEUE_NOT_FOUND`) and `message`.
+    pub fn expect_error(self, command: &str, payload: Value, code: &str, message: &str) -> Self {
+        self.push(
+            command,
+            payload,
+            json!({"success": false, "error": message, "error_info": {"code": code}}),
+        )
+    }
+
+    /// Expect `kv.get` of `key` and answer with `value`; `Value::Null` for a
+    /// missing key.
+    pub fn expect_kv_get(self, key: &str, value: Value) -> Self {
+        self.expect_command("kv.get", json!({"key": key}), value)
+    }
+
+    /// Expect `kv.set` of `key` to `value`
+    pub fn expect_kv_set(self, key: &str, value: Value) -> Self {
+        self.expect_command(
+            "kv.set",
+            json!({"key": key, "value": value}),
+            json!({"success": true}),
+        )
+    }
+
+    /// Expect `kv.del` of `key`, answering whether it existed
+    pub fn expect_kv_delete(self, key: &str, deleted: bool) -> Self {
+        self.expect_command("kv.del", json!({"key": key}), json!({"deleted": deleted}))
+    }
+
+    /// Expect `queue.publish` to `queue` and answer with `message_id`
+    pub fn expect_queue_publish(self, queue: &str, message_id: &str) -> Self {
+        self.expect_command(
+            "queue.publish",
+            json!({"queue": queue}),
+            json!({"message_id": message_id}),
+        )
+    }
+
+    /// Expect `queue.consume` from `queue` and hand out `message`, a
+    /// [`Message`](crate::types::Message) as JSON, or `Value::Null` when the
+    /// queue is empty.
+    pub fn expect_queue_consume(self, queue: &str, message: Value) -> Self {
+        self.expect_command(
+            "queue.consume",
+            json!({"queue": queue}),
+            json!({"message": message}),
+        )
+    }
+
+    /// Expect `stream.publish` of `event` to `room` and answer with `offset`
+    pub fn expect_stream_publish(self, room: &str, event: &str, offset: u64) -> Self {
+        self.expect_command(
+            "stream.publish",
+            json!({"room": room, "event": event}),
+            json!({"offset": offset}),
+        )
+    }
+
+    /// Expect `pubsub.publish` to `topic`, reaching `subscribers` subscribers
+    pub fn expect_pubsub_publish(self, topic: &str, subscribers: usize) -> Self {
+        self.expect_command(
+            "pubsub.publish",
+            json!({"topic": topic}),
+            json!({"subscribers_matched": subscribers}),
+        )
+    }
+
+    /// Expect the last expectation exactly `n` times instead of at least
+    /// once. Repeated commands are answered by the first expectation that
+    /// still expects calls, so answers can be queued in order.
+    ///
+    /// # Panics
+    /// If nothing has been expected yet.
+    pub fn times(mut self, n: usize) -> Self {
+        self.expectations
+            .last_mut()
+            .expect("times() needs an expectation before it")
+            .times = Some(n);
+        self
+    }
+
+    /// Serve the expectations on a local port
+    pub async fn start(self) -> MockSynapServer {
+        let mut server = Server::new_async().await;
+        let mut mocks = Vec::with_capacity(self.expectations.len());
+        for expectation in self.expectations {
+            let mut mock = server
+                .mock("POST", "/api/v1/command")
+                .match_body(Matcher::PartialJson(json!({
+                    "command": expectation.command,
+                    "payload": expectation.payload,
+                })))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(expectation.response.to_string());
+            mock = match expectation.times {
+                Some(n) => mock.expect(n),
+                None => mock.expect_at_least(1),
+            };
+            mocks.push(mock.create_async().await);
+        }
+        MockSynapServer { server, mocks }
+    }
+
+    fn push(mut self, command: &str, payload: Value, response: Value) -> Self {
+        self.expectations.push(Expectation {
+            command: command.to_owned(),
+            payload,
+            response,
+            times: None,
+        });
+        self
+    }
+}
+
+/// A running mock from [`MockSynap::start`], served until dropped.
+pub struct MockSynapServer {
+    server: ServerGuard,
+    mocks: Vec<Mock>,
+}
+
+impl MockSynapServer {
+    /// Base URL of the mock
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// A configuration pointing at the mock over HTTP, to adjust before
+    /// building a client
+    pub fn config(&self) -> SynapConfig {
+        SynapConfig::new(self.url())
+    }
+
+    /// A client for the mock
+    pub fn client(&self) -> SynapClient {
+        SynapClient::new(self.config()).expect("mock server URL is valid")
+    }
+
+    /// The underlying mockito server, for REST endpoints the expectations
+    /// do not cover
+    pub fn server_mut(&mut self) -> &mut ServerGuard {
+        &mut self.server
+    }
+
+    /// Panic unless every expectation was met the expected number of times
+    pub async fn assert(&self) {
+        for mock in &self.mocks {
+            mock.assert_async().await;
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Time-series counters
+//!
+//! Sends the typed `ts.*` commands from
+//! [`protocol::timeseries`](crate::protocol::timeseries). Increments are
+//! summed into buckets of 1s, 1m or 1h on the server and trimmed once they
+//! are older than the series' retention.
+//!
+//! The time series commands have no native mapping yet; use an `http://` URL.
+
+use crate::client::SynapClient;
+use crate::error::Result;
+use crate::protocol::timeseries::{
+    TimeSeriesAggregation, TimeSeriesDelete, TimeSeriesIncr, TimeSeriesPoint, TimeSeriesRange,
+    TimeSeriesResolution,
+};
+use std::time::Duration;
+
+/// Options for [`TimeSeriesManager::incr_with`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeSeriesIncrOptions {
+    /// Unix milliseconds the increment counts at; now when `None`
+    pub timestamp: Option<u64>,
+    /// Bucket width of a new series; `1s` when `None`. An existing series
+    /// refuses a different one.
+    pub resolution: Option<TimeSeriesResolution>,
+    /// How long buckets are kept; one day for a new series when `None`
+    pub retention: Option<Duration>,
+}
+
+/// A range query for [`TimeSeriesManager::range`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeSeriesQuery {
+    /// Unix milliseconds, inclusive; the start of the series when `None`
+    pub from: Option<u64>,
+    /// Unix milliseconds, inclusive; the end of the series when `None`
+    pub to: Option<u64>,
+    pub aggregation: TimeSeriesAggregation,
+    /// Width of the returned buckets, a multiple of the series' resolution;
+    /// the resolution itself when `None`
+    pub bucket: Option<Duration>,
+}
+
+/// Time series operations
+#[derive(Clone)]
+pub struct TimeSeriesManager {
+    client: SynapClient,
+}
+
+impl TimeSeriesManager {
+    /// Create a new time series interface
+    pub(crate) fn new(client: SynapClient) -> Self {
+        Self { client }
+    }
+
+    /// Add `value` to the current bucket of `key`, creating a `1s` series
+    ///
+    /// Returns the bucket and its new total.
+    pub async fn incr(&self, key: &str, value: f64) -> Result<TimeSeriesPoint> {
+        self.incr_with(key, value, TimeSeriesIncrOptions::default())
+            .await
+    }
+
+    /// Add `value` to the bucket holding `options.timestamp`
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig, TimeSeriesIncrOptions, TimeSeriesResolution};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let options = TimeSeriesIncrOptions {
+    ///     resolution: Some(TimeSeriesResolution::Minute),
+    ///     retention: Some(Duration::from_secs(7 * 24 * 3600)),
+    ///     ..Default::default()
+    /// };
+    /// client.time_series().incr_with("signups", 1.0, options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn incr_with(
+        &self,
+        key: &str,
+        value: f64,
+        options: TimeSeriesIncrOptions,
+    ) -> Result<TimeSeriesPoint> {
+        let response = self
+            .client
+            .execute(&TimeSeriesIncr {
+                key: key.to_owned(),
+                value,
+                timestamp: options.timestamp,
+                resolution: options.resolution,
+                retention: options
+                    .retention
+                    .map(|retention| retention.as_millis() as u64),
+            })
+            .await?;
+
+        Ok(TimeSeriesPoint {
+            timestamp: response.timestamp,
+            value: response.value,
+        })
+    }
+
+    /// Aggregate the buckets of `key` in a range
+    ///
+    /// Buckets with no increments are left out; a missing key has no points.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use synap_sdk::{SynapClient, SynapConfig, TimeSeriesAggregation, TimeSeriesQuery};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SynapClient::new(SynapConfig::new("http://localhost:15500"))?;
+    /// let query = TimeSeriesQuery {
+    ///     aggregation: TimeSeriesAggregation::Max,
+    ///     bucket: Some(Duration::from_secs(3600)),
+    ///     ..Default::default()
+    /// };
+    /// for point in client.time_series().range("latency", query).await? {
+    ///     println!("{}: {}", point.timestamp, point.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn range(&self, key: &str, query: TimeSeriesQuery) -> Result<Vec<TimeSeriesPoint>> {
+        let response = self
+            .client
+            .execute(&TimeSeriesRange {
+                key: key.to_owned(),
+                from: query.from,
+                to: query.to,
+                aggregation: query.aggregation,
+                bucket: query.bucket.map(|bucket| bucket.as_millis() as u64),
+            })
+            .await?;
+        Ok(response.points)
+    }
+
+    /// Drop the series on `key`
+    ///
+    /// Returns `false` if the key held no series.
+    pub async fn delete(&self, key: &str) -> Result<bool> {
+        let response = self
+            .client
+            .execute(&TimeSeriesDelete {
+                key: key.to_owned(),
+            })
+            .await?;
+        Ok(response.deleted)
+    }
+}
@@ -0,0 +1,48 @@
+//! Commands run in the host process by a [`synap_server::Embedded`]
+//! instance, for [`SynapClient::embedded`](crate::SynapClient::embedded).
+
+use crate::protocol::Request;
+use async_trait::async_trait;
+use serde_json::Value;
+use synap_server::{Embedded, EmbeddedListener};
+
+use super::Transport;
+use crate::error::{ApiError, Result};
+
+/// Sends commands straight to the embedded handlers, and keeps the HTTP API
+/// the client's REST-only calls use alive for as long as the client.
+pub(crate) struct EmbeddedTransport {
+    synap: Embedded,
+    _listener: EmbeddedListener,
+}
+
+impl EmbeddedTransport {
+    pub(crate) fn new(synap: Embedded, listener: EmbeddedListener) -> Self {
+        Self {
+            synap,
+            _listener: listener,
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for EmbeddedTransport {
+    async fn send(&self, request: Request) -> Result<Value> {
+        let command = request.command.clone();
+        let request_id = request.request_id.clone();
+        let request = synap_server::Request {
+            command: request.command,
+            request_id: request.request_id,
+            payload: request.payload,
+            db: request.db,
+        };
+        self.synap.send_request(request).await.map_err(|e| {
+            // The same fields the HTTP transport reads from a failed envelope
+            ApiError::new(e.to_string())
+                .with_error_code(e.error_code())
+                .with_command(command)
+                .with_request_id(request_id)
+                .into()
+        })
+    }
+}
@@ -0,0 +1,138 @@
+//! JSON (or MessagePack / CBOR) envelopes over `POST /api/v1/command`.
+
+use crate::protocol::{Encoding, Request};
+use async_trait::async_trait;
+use serde_json::Value;
+use url::Url;
+
+use super::Transport;
+use crate::auth::HttpClient;
+use crate::error::{ApiError, Result, SynapError};
+use crate::telemetry;
+
+/// The original REST transport: one HTTP request per command.
+pub(crate) struct HttpTransport {
+    http: HttpClient,
+    base_url: Url,
+    encoding: Encoding,
+}
+
+impl HttpTransport {
+    pub(crate) fn new(http: HttpClient, base_url: Url, encoding: Encoding) -> Self {
+        Self {
+            http,
+            base_url,
+            encoding,
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, request: Request) -> Result<Value> {
+        let url = self
+            .base_url
+            .join("api/v1/command")
+            .map_err(SynapError::InvalidUrl)?;
+
+        let encoding = self.encoding;
+        let body = encoding
+            .encode(&request)
+            .map_err(|e| SynapError::Other(e.to_string()))?;
+
+        // Send the id as `X-Request-Id` too, so the server tags its logs, WAL
+        // entries and slowlog with the same id the caller sees in errors.
+        let trace_headers = telemetry::trace_headers();
+        let response = self
+            .http
+            .send(|c| {
+                c.post(url.clone())
+                    .headers(trace_headers.clone())
+                    .header("x-request-id", &request.request_id)
+                    .header(reqwest::header::CONTENT_TYPE, encoding.content_type())
+                    .header(reqwest::header::ACCEPT, encoding.content_type())
+                    .body(body.clone())
+            })
+            .await?;
+
+        // Prefer the id the server actually used (it may replace a malformed one).
+        let request_id = response
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned)
+            .unwrap_or(request.request_id);
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            let mut error = match serde_json::from_str::<Value>(&error_text) {
+                Ok(body) => api_error_from_body(&body, error_text),
+                Err(_) => ApiError::new(error_text),
+            };
+            error = error
+                .with_command(&request.command)
+                .with_status(status.as_u16());
+            if error.request_id.is_none() {
+                error = error.with_request_id(request_id);
+            }
+            return Err(error.into());
+        }
+
+        // Errors are always JSON, so decode whatever the server says it sent
+        let encoding = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Encoding::from_content_type)
+            .unwrap_or_default();
+        let result: Value = encoding
+            .decode(&response.bytes().await?)
+            .map_err(|e| SynapError::InvalidResponse(e.to_string()))?;
+
+        envelope_payload(&request.command, request_id, result)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        let url = self.base_url.join("health")?;
+        self.http
+            .send(|c| c.get(url.clone()))
+            .await
+            .and_then(|r| r.error_for_status().map_err(SynapError::from))
+            .map(drop)
+    }
+}
+
+/// The payload of a response envelope, or its error as an [`ApiError`].
+pub(crate) fn envelope_payload(command: &str, request_id: String, result: Value) -> Result<Value> {
+    if !result["success"].as_bool().unwrap_or(false) {
+        let mut error =
+            api_error_from_body(&result, "Unknown error".to_owned()).with_command(command);
+        if error.request_id.is_none() {
+            error = error.with_request_id(request_id);
+        }
+        return Err(error.into());
+    }
+
+    Ok(result["payload"].clone())
+}
+
+/// Pull the message, error code and `request_id` out of a server error
+/// body (REST `error_code` or envelope `error_info.code`), falling back to `default_message` when it carries no `error`.
+pub(crate) fn api_error_from_body(body: &Value, default_message: String) -> ApiError {
+    let message = body["error"]
+        .as_str()
+        .map(str::to_owned)
+        .unwrap_or(default_message);
+    let mut error = ApiError::new(message);
+    let code = body["error_code"]
+        .as_str()
+        .or_else(|| body["error_info"]["code"].as_str());
+    if let Some(code) = code {
+        error = error.with_error_code(code);
+    }
+    if let Some(id) = body["request_id"].as_str() {
+        error = error.with_request_id(id);
+    }
+    error
+}
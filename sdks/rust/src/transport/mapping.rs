@@ -83,28 +83,25 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
         ),
         "hash.get" => ("HGET", vec![field_str("key"), field_str("field")]),
         "hash.getall" => ("HGETALL", vec![field_str("key")]),
-        "hash.del" => ("HDEL", vec![field_str("key"), field_str("field")]),
+        "hash.del" => {
+            let mut args = vec![field_str("key")];
+            if let Some(fields) = payload["fields"].as_array() {
+                args.extend(fields.iter().map(to_wire));
+            }
+            ("HDEL", args)
+        }
         "hash.exists" => ("HEXISTS", vec![field_str("key"), field_str("field")]),
         "hash.keys" => ("HKEYS", vec![field_str("key")]),
-        "hash.values" => ("HVALS", vec![field_str("key")]),
+        "hash.vals" => ("HVALS", vec![field_str("key")]),
         "hash.len" => ("HLEN", vec![field_str("key")]),
 
         "hash.mset" => {
             let mut args = vec![field_str("key")];
             if let Some(obj) = payload["fields"].as_object() {
-                // HashMap format: {"field": "value", ...}
                 for (k, v) in obj {
                     args.push(WireValue::Str(k.clone()));
                     args.push(to_wire(v));
                 }
-            } else if let Some(arr) = payload["fields"].as_array() {
-                // Array format: [{"field": "...", "value": "..."}, ...]
-                for item in arr {
-                    if let Some(f) = item["field"].as_str() {
-                        args.push(WireValue::Str(f.to_string()));
-                        args.push(to_wire(&item["value"]));
-                    }
-                }
             }
             // Redis 4+ HSET handles multi-field: HSET key f1 v1 f2 v2 ...
             ("HSET", args)
@@ -154,9 +151,7 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             };
             let mut args = vec![field_str("key")];
             if let Some(vals) = payload["values"].as_array() {
-                for v in vals {
-                    args.push(WireValue::Str(v.as_str().unwrap_or("").to_string()));
-                }
+                args.extend(vals.iter().map(to_wire));
             }
             (cmd_name, args)
         }
@@ -169,9 +164,7 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             };
             let mut args = vec![field_str("key")];
             if let Some(vals) = payload["values"].as_array() {
-                for v in vals {
-                    args.push(WireValue::Str(v.as_str().unwrap_or("").to_string()));
-                }
+                args.extend(vals.iter().map(to_wire));
             }
             (cmd_name, args)
         }
@@ -192,7 +185,7 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             ("RPOP", args)
         }
 
-        "list.range" => (
+        "list.lrange" => (
             "LRANGE",
             vec![
                 field_str("key"),
@@ -201,9 +194,9 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             ],
         ),
 
-        "list.len" => ("LLEN", vec![field_str("key")]),
+        "list.llen" => ("LLEN", vec![field_str("key")]),
 
-        "list.index" => (
+        "list.lindex" => (
             "LINDEX",
             vec![
                 field_str("key"),
@@ -211,7 +204,7 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             ],
         ),
 
-        "list.set" => (
+        "list.lset" => (
             "LSET",
             vec![
                 field_str("key"),
@@ -220,25 +213,25 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             ],
         ),
 
-        "list.trim" => (
+        "list.ltrim" => (
             "LTRIM",
             vec![
                 field_str("key"),
                 WireValue::Int(payload["start"].as_i64().unwrap_or(0)),
-                WireValue::Int(payload["end"].as_i64().unwrap_or(-1)),
+                WireValue::Int(payload["stop"].as_i64().unwrap_or(-1)),
             ],
         ),
 
-        "list.rem" => (
+        "list.lrem" => (
             "LREM",
             vec![
                 field_str("key"),
                 WireValue::Int(payload["count"].as_i64().unwrap_or(0)),
-                to_wire(&payload["element"]),
+                to_wire(&payload["value"]),
             ],
         ),
 
-        "list.insert" => {
+        "list.linsert" => {
             let before_after = if payload["before"].as_bool().unwrap_or(true) {
                 "BEFORE"
             } else {
@@ -257,18 +250,16 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
 
         "list.rpoplpush" => (
             "RPOPLPUSH",
-            vec![field_str("key"), field_str("destination")],
+            vec![field_str("source"), field_str("destination")],
         ),
 
-        "list.pos" => ("LPOS", vec![field_str("key"), to_wire(&payload["element"])]),
+        "list.lpos" => ("LPOS", vec![field_str("key"), to_wire(&payload["value"])]),
 
         // ── Set ───────────────────────────────────────────────────────────────
         "set.add" => {
             let mut args = vec![field_str("key")];
             if let Some(members) = payload["members"].as_array() {
-                for m in members {
-                    args.push(WireValue::Str(m.as_str().unwrap_or("").to_string()));
-                }
+                args.extend(members.iter().map(to_wire));
             }
             ("SADD", args)
         }
@@ -276,17 +267,18 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
         "set.rem" => {
             let mut args = vec![field_str("key")];
             if let Some(members) = payload["members"].as_array() {
-                for m in members {
-                    args.push(WireValue::Str(m.as_str().unwrap_or("").to_string()));
-                }
+                args.extend(members.iter().map(to_wire));
             }
             ("SREM", args)
         }
 
-        "set.ismember" => ("SISMEMBER", vec![field_str("key"), field_str("member")]),
+        "set.ismember" => (
+            "SISMEMBER",
+            vec![field_str("key"), to_wire(&payload["member"])],
+        ),
 
         "set.members" => ("SMEMBERS", vec![field_str("key")]),
-        "set.card" => ("SCARD", vec![field_str("key")]),
+        "set.size" => ("SCARD", vec![field_str("key")]),
 
         "set.pop" => (
             "SPOP",
@@ -300,16 +292,16 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             "SRANDMEMBER",
             vec![
                 field_str("key"),
-                WireValue::Int(payload["count"].as_u64().unwrap_or(1) as i64),
+                WireValue::Int(payload["count"].as_i64().unwrap_or(1)),
             ],
         ),
 
         "set.move" => (
             "SMOVE",
             vec![
-                field_str("key"),
+                field_str("source"),
                 field_str("destination"),
-                field_str("member"),
+                to_wire(&payload["member"]),
             ],
         ),
 
@@ -330,6 +322,21 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             (raw, args)
         }
 
+        "set.intercard" => {
+            let keys = payload["keys"].as_array().cloned().unwrap_or_default();
+            let mut args = vec![WireValue::Int(keys.len() as i64)];
+            args.extend(
+                keys.iter()
+                    .filter_map(|k| k.as_str())
+                    .map(|k| WireValue::Str(k.to_string())),
+            );
+            if let Some(limit) = payload["limit"].as_u64().filter(|&limit| limit > 0) {
+                args.push(WireValue::Str("LIMIT".into()));
+                args.push(WireValue::Int(limit as i64));
+            }
+            ("SINTERCARD", args)
+        }
+
         "set.interstore" | "set.unionstore" | "set.diffstore" => {
             let raw: &'static str = match cmd {
                 "set.interstore" => "SINTERSTORE",
@@ -350,6 +357,11 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
         // ── Sorted Set ────────────────────────────────────────────────────────
         "sortedset.zadd" => {
             let mut args = vec![field_str("key")];
+            for flag in ["nx", "xx", "gt", "lt", "ch", "incr"] {
+                if payload[flag].as_bool().unwrap_or(false) {
+                    args.push(WireValue::Str(flag.to_ascii_uppercase()));
+                }
+            }
             if let Some(members) = payload["members"].as_array() {
                 // add_multiple format: [{member, score}, ...]
                 for m in members {
@@ -373,9 +385,7 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
         "sortedset.zrem" => {
             let mut args = vec![field_str("key")];
             if let Some(members) = payload["members"].as_array() {
-                for m in members {
-                    args.push(WireValue::Str(m.as_str().unwrap_or("").to_string()));
-                }
+                args.extend(members.iter().map(to_wire));
             } else {
                 args.push(to_wire(&payload["member"]));
             }
@@ -506,6 +516,26 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             ],
         ),
 
+        "sortedset.notify.set" => {
+            let mut args = vec![
+                WireValue::Str("SET".into()),
+                field_str("key"),
+                WireValue::Int(payload["top_n"].as_i64().unwrap_or(0)),
+            ];
+            if let Some(threshold) = payload["rank_threshold"].as_i64() {
+                args.push(WireValue::Int(threshold));
+            }
+            ("ZNOTIFY", args)
+        }
+        "sortedset.notify.get" => (
+            "ZNOTIFY",
+            vec![WireValue::Str("GET".into()), field_str("key")],
+        ),
+        "sortedset.notify.del" => (
+            "ZNOTIFY",
+            vec![WireValue::Str("DEL".into()), field_str("key")],
+        ),
+
         "sortedset.zinterstore" | "sortedset.zunionstore" | "sortedset.zdiffstore" => {
             let raw: &'static str = match cmd {
                 "sortedset.zinterstore" => "ZINTERSTORE",
@@ -526,6 +556,18 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
                     args.push(WireValue::Str(k.as_str().unwrap_or("").to_string()));
                 }
             }
+            if let Some(weights) = payload["weights"].as_array() {
+                args.push(WireValue::Str("WEIGHTS".into()));
+                args.extend(
+                    weights
+                        .iter()
+                        .map(|w| WireValue::Str(w.as_f64().unwrap_or(1.0).to_string())),
+                );
+            }
+            if let Some(aggregate) = payload["aggregate"].as_str() {
+                args.push(WireValue::Str("AGGREGATE".into()));
+                args.push(WireValue::Str(aggregate.to_ascii_uppercase()));
+            }
             (raw, args)
         }
 
@@ -533,6 +575,11 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
         "queue.create" => ("QCREATE", vec![field_str("name")]),
         "queue.delete" => ("QDELETE", vec![field_str("queue")]),
         "queue.list" => ("QLIST", vec![]),
+        // QPUBLISH / SPUBLISH have no idempotency key argument; refuse rather
+        // than publish without deduplication
+        "queue.publish" | "stream.publish" if !payload["idempotency_key"].is_null() => {
+            return None;
+        }
         "queue.publish" => {
             let payload_bytes: WireValue = match &payload["payload"] {
                 Value::Array(arr) => WireValue::from(
@@ -582,17 +629,13 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             )
         }
         "stream.consume" => {
-            let from = WireValue::Int(payload["offset"].as_u64().unwrap_or(0) as i64);
+            let from = WireValue::Int(payload["from_offset"].as_u64().unwrap_or(0) as i64);
             let limit = WireValue::Int(payload["limit"].as_u64().unwrap_or(100) as i64);
-            (
-                "SREAD",
-                vec![
-                    field_str("room"),
-                    WireValue::Str("sdk-consumer".into()),
-                    from,
-                    limit,
-                ],
-            )
+            let mut args = vec![field_str("room"), field_str("subscriber_id"), from, limit];
+            if let Some(min_age_ms) = payload["min_age_ms"].as_u64() {
+                args.push(WireValue::Int(min_age_ms as i64));
+            }
+            ("SREAD", args)
         }
         "stream.stats" => ("SSTATS", vec![field_str("room")]),
 
@@ -727,7 +770,15 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             let mut args = vec![field_str("key")];
             if let Some(elements) = payload["elements"].as_array() {
                 for e in elements {
-                    args.push(to_wire(e));
+                    args.push(match e {
+                        Value::Array(bytes) => WireValue::from(
+                            bytes
+                                .iter()
+                                .filter_map(|b| b.as_u64().map(|n| n as u8))
+                                .collect::<Vec<u8>>(),
+                        ),
+                        other => to_wire(other),
+                    });
                 }
             }
             ("PFADD", args)
@@ -747,15 +798,19 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
         // ── Geospatial ────────────────────────────────────────────────────────
         "geospatial.geoadd" => {
             let mut args = vec![field_str("key")];
-            if let Some(members) = payload["members"].as_array() {
-                for m in members {
-                    // SDK sends {lat, lon, member}
-                    let lat = m["lat"].as_f64().unwrap_or(0.0);
-                    let lon = m["lon"].as_f64().unwrap_or(0.0);
-                    let name = m["member"].as_str().unwrap_or("").to_string();
-                    args.push(WireValue::Float(lat));
-                    args.push(WireValue::Float(lon));
-                    args.push(WireValue::Str(name));
+            for flag in ["nx", "xx", "ch"] {
+                if payload[flag].as_bool().unwrap_or(false) {
+                    args.push(WireValue::Str(flag.to_ascii_uppercase()));
+                }
+            }
+            if let Some(locations) = payload["locations"].as_array() {
+                for loc in locations {
+                    // SDK sends {lat, lon, member}; GEOADD takes lat lon member
+                    args.push(WireValue::Float(loc["lat"].as_f64().unwrap_or(0.0)));
+                    args.push(WireValue::Float(loc["lon"].as_f64().unwrap_or(0.0)));
+                    args.push(WireValue::Str(
+                        loc["member"].as_str().unwrap_or("").to_string(),
+                    ));
                 }
             }
             ("GEOADD", args)
@@ -769,28 +824,73 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
                 WireValue::Str(payload["unit"].as_str().unwrap_or("m").to_uppercase()),
             ],
         ),
-        "geospatial.georadius" => {
-            let mut args = vec![
-                field_str("key"),
-                WireValue::Float(payload["lat"].as_f64().unwrap_or(0.0)),
-                WireValue::Float(payload["lon"].as_f64().unwrap_or(0.0)),
-                WireValue::Float(payload["radius"].as_f64().unwrap_or(0.0)),
-                WireValue::Str(payload["unit"].as_str().unwrap_or("m").to_uppercase()),
-            ];
-            if payload["with_distance"].as_bool().unwrap_or(false) {
+        "geospatial.georadius" | "geospatial.georadiusbymember" | "geospatial.geosearch" => {
+            let mut args = vec![field_str("key")];
+            let raw: &'static str = match cmd {
+                "geospatial.georadius" => {
+                    args.push(WireValue::Float(
+                        payload["center_lat"].as_f64().unwrap_or(0.0),
+                    ));
+                    args.push(WireValue::Float(
+                        payload["center_lon"].as_f64().unwrap_or(0.0),
+                    ));
+                    args.push(WireValue::Float(payload["radius"].as_f64().unwrap_or(0.0)));
+                    args.push(WireValue::Str(
+                        payload["unit"].as_str().unwrap_or("m").to_uppercase(),
+                    ));
+                    "GEORADIUS"
+                }
+                "geospatial.georadiusbymember" => {
+                    args.push(WireValue::Str(
+                        payload["member"].as_str().unwrap_or("").to_string(),
+                    ));
+                    args.push(WireValue::Float(payload["radius"].as_f64().unwrap_or(0.0)));
+                    args.push(WireValue::Str(
+                        payload["unit"].as_str().unwrap_or("m").to_uppercase(),
+                    ));
+                    "GEORADIUSBYMEMBER"
+                }
+                _ => {
+                    if let Some(member) = payload["from_member"].as_str() {
+                        args.push(WireValue::Str("FROMMEMBER".into()));
+                        args.push(WireValue::Str(member.to_string()));
+                    }
+                    if let Some(lonlat) = payload["from_lonlat"].as_array() {
+                        args.push(WireValue::Str("FROMLONLAT".into()));
+                        args.extend(
+                            lonlat
+                                .iter()
+                                .map(|v| WireValue::Float(v.as_f64().unwrap_or(0.0))),
+                        );
+                    }
+                    // by_radius is [radius, unit] and by_box [width, height, unit]
+                    for (field, keyword) in [("by_radius", "BYRADIUS"), ("by_box", "BYBOX")] {
+                        if let Some(shape) = payload[field].as_array() {
+                            args.push(WireValue::Str(keyword.into()));
+                            args.extend(shape.iter().map(|v| match v.as_f64() {
+                                Some(n) => WireValue::Float(n),
+                                None => WireValue::Str(v.as_str().unwrap_or("m").to_uppercase()),
+                            }));
+                        }
+                    }
+                    "GEOSEARCH"
+                }
+            };
+            if payload["with_dist"].as_bool().unwrap_or(false) {
+                args.push(WireValue::Str("WITHDIST".into()));
+            }
+            if payload["with_coord"].as_bool().unwrap_or(false) {
                 args.push(WireValue::Str("WITHCOORD".into()));
             }
-            ("GEORADIUS", args)
+            if let Some(count) = payload["count"].as_u64() {
+                args.push(WireValue::Str("COUNT".into()));
+                args.push(WireValue::Int(count as i64));
+            }
+            if let Some(sort) = payload["sort"].as_str() {
+                args.push(WireValue::Str(sort.to_ascii_uppercase()));
+            }
+            (raw, args)
         }
-        "geospatial.georadiusbymember" => (
-            "GEORADIUSBYMEMBER",
-            vec![
-                field_str("key"),
-                WireValue::Str(payload["member"].as_str().unwrap_or("").to_string()),
-                WireValue::Float(payload["radius"].as_f64().unwrap_or(0.0)),
-                WireValue::Str(payload["unit"].as_str().unwrap_or("m").to_uppercase()),
-            ],
-        ),
         "geospatial.geopos" => {
             let mut args = vec![field_str("key")];
             if let Some(members) = payload["members"].as_array() {
@@ -800,16 +900,6 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             }
             ("GEOPOS", args)
         }
-        "geospatial.geosearch" => (
-            "GEOSEARCH",
-            vec![
-                field_str("key"),
-                WireValue::Float(payload["lat"].as_f64().unwrap_or(0.0)),
-                WireValue::Float(payload["lon"].as_f64().unwrap_or(0.0)),
-                WireValue::Float(payload["radius"].as_f64().unwrap_or(0.0)),
-                WireValue::Str(payload["unit"].as_str().unwrap_or("m").to_uppercase()),
-            ],
-        ),
         "geospatial.geohash" => {
             let mut args = vec![field_str("key")];
             if let Some(members) = payload["members"].as_array() {
@@ -867,6 +957,26 @@ pub(crate) fn map_command(cmd: &str, payload: &Value) -> Option<(&'static str, V
             };
             ("KEYS", vec![WireValue::Str(pattern)])
         }
+        "hash.scan" | "set.scan" | "sortedset.zscan" => {
+            let raw: &'static str = match cmd {
+                "hash.scan" => "HSCAN",
+                "set.scan" => "SSCAN",
+                _ => "ZSCAN",
+            };
+            let mut args = vec![
+                field_str("key"),
+                WireValue::Int(payload["cursor"].as_i64().unwrap_or(0)),
+            ];
+            if let Some(pattern) = payload["match"].as_str() {
+                args.push(WireValue::Str("MATCH".into()));
+                args.push(WireValue::Str(pattern.to_string()));
+            }
+            if let Some(count) = payload["count"].as_i64() {
+                args.push(WireValue::Str("COUNT".into()));
+                args.push(WireValue::Int(count));
+            }
+            (raw, args)
+        }
 
         // Commands with no native mapping — caller returns UnsupportedCommand.
         _ => return None,
@@ -905,14 +1015,13 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
         "kv.ttl" => wire.to_json(),
 
         // ── Hash ──────────────────────────────────────────────────────────────
-        "hash.set" => json!({"success": wire.as_int().map(|n| n >= 0).unwrap_or(true)}),
+        "hash.set" => json!({"created": wire.as_int().unwrap_or(0) > 0}),
         "hash.get" => {
-            let v = if wire.is_null() {
-                Value::Null
+            if wire.is_null() {
+                json!({"found": false})
             } else {
-                wire.to_json()
-            };
-            json!({"value": v})
+                json!({"found": true, "value": wire.to_json()})
+            }
         }
         "hash.getall" => {
             // HGETALL returns a flat array: [field1, val1, field2, val2, ...]
@@ -947,9 +1056,9 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
                 WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
                 _ => vec![],
             };
-            json!({"fields": arr})
+            json!({"keys": arr})
         }
-        "hash.values" => {
+        "hash.vals" => {
             let arr: Vec<Value> = match wire {
                 WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
                 _ => vec![],
@@ -992,20 +1101,19 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
             };
             json!({"values": values})
         }
-        "list.range" => {
+        "list.lrange" => {
             let values: Vec<Value> = match wire {
                 WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
                 _ => vec![],
             };
             json!({"values": values})
         }
-        "list.len" => json!({"length": wire.as_int().unwrap_or(0)}),
-        "list.index" => wire.to_json(),
-        "list.set" | "list.trim" => json!({}),
-        "list.rem" => json!({"count": wire.as_int().unwrap_or(0)}),
-        "list.insert" => json!({"length": wire.as_int().unwrap_or(-1)}),
-        "list.rpoplpush" => wire.to_json(),
-        "list.pos" => wire.to_json(),
+        "list.llen" => json!({"length": wire.as_int().unwrap_or(0)}),
+        "list.lindex" | "list.rpoplpush" => json!({"value": wire.to_json()}),
+        "list.lset" | "list.ltrim" => json!({"success": !wire.is_null()}),
+        "list.lrem" => json!({"removed": wire.as_int().unwrap_or(0)}),
+        "list.linsert" => json!({"length": wire.as_int().unwrap_or(-1)}),
+        "list.lpos" => json!({"position": wire.as_int()}),
 
         // ── Set ───────────────────────────────────────────────────────────────
         "set.add" => json!({"added": wire.as_int().unwrap_or(0)}),
@@ -1020,7 +1128,7 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
             };
             json!({"members": members})
         }
-        "set.card" => json!({"cardinality": wire.as_int().unwrap_or(0)}),
+        "set.size" => json!({"size": wire.as_int().unwrap_or(0)}),
         "set.move" => json!({"moved": wire.as_int().unwrap_or(0) > 0}),
         "set.inter" | "set.union" | "set.diff" => {
             let members: Vec<Value> = match wire {
@@ -1029,7 +1137,7 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
             };
             json!({"members": members})
         }
-        "set.interstore" | "set.unionstore" | "set.diffstore" => {
+        "set.interstore" | "set.unionstore" | "set.diffstore" | "set.intercard" => {
             json!({"count": wire.as_int().unwrap_or(0)})
         }
 
@@ -1051,14 +1159,11 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
             json!({"score": score})
         }
         "sortedset.zrank" | "sortedset.zrevrank" => {
-            if wire.is_null() {
-                json!({"rank": null})
-            } else {
-                json!({"rank": wire.as_int().unwrap_or(-1)})
-            }
+            json!({"rank": wire.as_int()})
         }
-        "sortedset.zcount" | "sortedset.zremrangebyrank" | "sortedset.zremrangebyscore" => {
-            json!({"count": wire.as_int().unwrap_or(0)})
+        "sortedset.zcount" => json!({"count": wire.as_int().unwrap_or(0)}),
+        "sortedset.zremrangebyrank" | "sortedset.zremrangebyscore" => {
+            json!({"removed": wire.as_int().unwrap_or(0)})
         }
         "sortedset.zrange" | "sortedset.zrevrange" | "sortedset.zrangebyscore" => {
             // ZRANGE … WITHSCORES returns interleaved [member, score, ...].
@@ -1114,9 +1219,23 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
         "sortedset.zinterstore" | "sortedset.zunionstore" | "sortedset.zdiffstore" => {
             json!({"count": wire.as_int().unwrap_or(0)})
         }
+        "sortedset.notify.set" => json!({}),
+        "sortedset.notify.get" => match wire {
+            WireValue::Array(arr) if !arr.is_empty() => json!({
+                "window": {
+                    "top_n": arr[0].as_int().unwrap_or(0),
+                    "rank_threshold": arr.get(1).and_then(WireValue::as_int),
+                }
+            }),
+            _ => json!({"window": null}),
+        },
+        "sortedset.notify.del" => json!({"removed": wire.as_int().unwrap_or(0) > 0}),
 
         // ── Queue ─────────────────────────────────────────────────────────────
-        "queue.create" | "queue.delete" => json!({}),
+        "queue.create" => json!({"success": true}),
+        "queue.delete" => {
+            json!({"deleted": matches!(wire, WireValue::Bool(true)) || wire.as_int().unwrap_or(0) > 0})
+        }
         "queue.list" => {
             let queues: Vec<Value> = match wire {
                 WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
@@ -1133,7 +1252,7 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
             // RESP3 returns a positional Array [id, payload, priority, retry_count].
             // Normalise both into a JSON object that `Message` can deserialise.
             if wire.is_null() {
-                Value::Null
+                json!({"message": null})
             } else {
                 let mut json = match wire {
                     WireValue::Array(ref arr) if arr.len() >= 4 => {
@@ -1160,10 +1279,10 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
                     let byte_arr: Vec<Value> = s.bytes().map(|b| json!(b as u64)).collect();
                     json["payload"] = Value::Array(byte_arr);
                 }
-                json
+                json!({"message": json})
             }
         }
-        "queue.ack" | "queue.nack" => json!({}),
+        "queue.ack" | "queue.nack" => json!({"success": true}),
         "queue.stats" => {
             // RESP3 returns a flat array [key, val, key, val, ...].
             // SynapRPC returns a Map.  Normalise both to a JSON object.
@@ -1184,7 +1303,7 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
         }
 
         // ── Stream ────────────────────────────────────────────────────────────
-        "stream.create" | "stream.delete" => json!({}),
+        "stream.create" | "stream.delete" => json!({"success": true}),
         "stream.list" => {
             let rooms: Vec<Value> = match wire {
                 WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
@@ -1230,9 +1349,9 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
                 // Generate a synthetic one so the SDK API is consistent.
                 _ => format!("sub-{}", uuid::Uuid::new_v4()),
             };
-            json!({"subscription_id": sub_id})
+            json!({"subscriber_id": sub_id})
         }
-        "pubsub.unsubscribe" => json!({}),
+        "pubsub.unsubscribe" => json!({"unsubscribed": wire.as_int().unwrap_or(0)}),
         "pubsub.topics" => {
             let topics: Vec<Value> = match wire {
                 WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
@@ -1246,13 +1365,14 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
         | "transaction.discard"
         | "transaction.watch"
         | "transaction.unwatch" => json!({"success": true}),
-        "transaction.exec" => {
-            let results: Vec<Value> = match wire {
-                WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
-                _ => vec![],
-            };
-            json!({"results": results})
-        }
+        "transaction.exec" => match wire {
+            // A nil reply means a watched key changed
+            WireValue::Null => json!({"aborted": true}),
+            WireValue::Array(arr) => {
+                json!({"results": arr.iter().map(WireValue::to_json).collect::<Vec<_>>()})
+            }
+            _ => json!({"results": []}),
+        },
 
         // ── Scripting ─────────────────────────────────────────────────────────
         "script.eval" | "script.evalsha" => {
@@ -1288,7 +1408,7 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
         }
 
         // ── HyperLogLog ───────────────────────────────────────────────────────
-        "hyperloglog.pfadd" => json!({"modified": wire.as_int().unwrap_or(0) > 0}),
+        "hyperloglog.pfadd" => json!({"added": wire.as_int().unwrap_or(0)}),
         "hyperloglog.pfcount" => json!({"count": wire.as_int().unwrap_or(0)}),
         "hyperloglog.pfmerge" => json!({}),
         "hyperloglog.stats" => wire.to_json(),
@@ -1302,25 +1422,50 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
             json!({"distance": dist})
         }
         "geospatial.georadius" | "geospatial.georadiusbymember" | "geospatial.geosearch" => {
-            let members: Vec<Value> = match wire {
-                WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
+            // Each hit is a bare member, or [member, distance|nil, [lat, lon]|nil]
+            // when WITHDIST/WITHCOORD was asked for
+            let results: Vec<Value> = match wire {
+                WireValue::Array(arr) => arr
+                    .iter()
+                    .map(|hit| match hit {
+                        WireValue::Array(parts) => {
+                            let mut result = json!({
+                                "member": parts.first().and_then(WireValue::as_str).unwrap_or(""),
+                            });
+                            if let Some(distance) = parts.get(1).and_then(WireValue::as_float) {
+                                result["distance"] = json!(distance);
+                            }
+                            if let Some(WireValue::Array(coord)) = parts.get(2) {
+                                result["coord"] = geo_coord(coord);
+                            }
+                            result
+                        }
+                        member => json!({"member": member.as_str().unwrap_or("")}),
+                    })
+                    .collect(),
                 _ => vec![],
             };
-            json!({"members": members})
+            json!({"results": results})
         }
         "geospatial.geopos" => {
-            let positions: Vec<Value> = match wire {
-                WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
+            let coordinates: Vec<Value> = match wire {
+                WireValue::Array(arr) => arr
+                    .iter()
+                    .map(|pos| match pos {
+                        WireValue::Array(coord) => geo_coord(coord),
+                        _ => Value::Null,
+                    })
+                    .collect(),
                 _ => vec![],
             };
-            json!({"positions": positions})
+            json!({"coordinates": coordinates})
         }
         "geospatial.geohash" => {
-            let hashes: Vec<Value> = match wire {
+            let geohashes: Vec<Value> = match wire {
                 WireValue::Array(arr) => arr.iter().map(WireValue::to_json).collect(),
                 _ => vec![],
             };
-            json!({"hashes": hashes})
+            json!({"geohashes": geohashes})
         }
         "geospatial.stats" => wire.to_json(),
 
@@ -1372,12 +1517,84 @@ pub(crate) fn map_response(cmd: &str, wire: WireValue) -> Value {
             };
             json!({"keys": keys})
         }
+        "hash.scan" => {
+            let (next_cursor, items) = scan_reply(wire);
+            let mut fields = serde_json::Map::new();
+            for pair in items.chunks(2) {
+                if let [field, value] = pair
+                    && let Value::String(field) = field.to_json()
+                {
+                    fields.insert(field, value.to_json());
+                }
+            }
+            json!({"count": fields.len(), "fields": fields, "next_cursor": next_cursor})
+        }
+        "set.scan" => {
+            let (next_cursor, items) = scan_reply(wire);
+            let members: Vec<Value> = items.iter().map(WireValue::to_json).collect();
+            json!({"count": members.len(), "members": members, "next_cursor": next_cursor})
+        }
+        "sortedset.zscan" => {
+            let (next_cursor, items) = scan_reply(wire);
+            let members: Vec<Value> = items
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [member, score] => {
+                        let score = score
+                            .as_float()
+                            .or_else(|| score.to_json().as_str().and_then(|s| s.parse().ok()))
+                            .unwrap_or(0.0);
+                        Some(json!({"member": member.to_json(), "score": score}))
+                    }
+                    _ => None,
+                })
+                .collect();
+            json!({"count": members.len(), "members": members, "next_cursor": next_cursor})
+        }
 
         // Fallthrough: return the raw JSON representation.
         _ => wire.to_json(),
     }
 }
 
+/// Split a `*SCAN` reply `[cursor, items]` into the next cursor and a flat
+/// item list. SynapRPC sends the cursor as an integer and field/score pairs
+/// as a map; RESP3 sends a decimal string and a flat `[k, v, …]` array.
+fn scan_reply(wire: WireValue) -> (u64, Vec<WireValue>) {
+    let WireValue::Array(parts) = wire else {
+        return (0, Vec::new());
+    };
+    let mut parts = parts.into_iter();
+    let (Some(cursor), Some(items)) = (parts.next(), parts.next()) else {
+        return (0, Vec::new());
+    };
+    let cursor = cursor
+        .as_int()
+        .map(|n| n.max(0) as u64)
+        .or_else(|| cursor.to_json().as_str().and_then(|s| s.parse().ok()))
+        .unwrap_or(0);
+    let items = match items {
+        WireValue::Array(items) => items,
+        WireValue::Map(pairs) => pairs.into_iter().flat_map(|(k, v)| [k, v]).collect(),
+        _ => Vec::new(),
+    };
+    (cursor, items)
+}
+
+/// A `[lat, lon]` pair from GEOPOS or a WITHCOORD hit as `{lat, lon}`
+fn geo_coord(coord: &[WireValue]) -> Value {
+    let axis = |i: usize| {
+        coord.get(i).and_then(|v| {
+            v.as_float()
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+        })
+    };
+    match (axis(0), axis(1)) {
+        (Some(lat), Some(lon)) => json!({"lat": lat, "lon": lon}),
+        _ => Value::Null,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1431,13 +1648,16 @@ mod tests {
         "kv.mset",
         "kv.mdel",
         "kv.scan",
+        "hash.scan",
+        "set.scan",
+        "sortedset.zscan",
         "hash.set",
         "hash.get",
         "hash.getall",
         "hash.del",
         "hash.exists",
         "hash.keys",
-        "hash.values",
+        "hash.vals",
         "hash.len",
         "hash.mset",
         "hash.mget",
@@ -1448,20 +1668,20 @@ mod tests {
         "list.rpush",
         "list.lpop",
         "list.rpop",
-        "list.range",
-        "list.len",
-        "list.index",
-        "list.set",
-        "list.trim",
-        "list.rem",
-        "list.insert",
-        "list.pos",
+        "list.lrange",
+        "list.llen",
+        "list.lindex",
+        "list.lset",
+        "list.ltrim",
+        "list.lrem",
+        "list.linsert",
+        "list.lpos",
         "list.rpoplpush",
         "set.add",
         "set.rem",
         "set.ismember",
         "set.members",
-        "set.card",
+        "set.size",
         "set.pop",
         "set.randmember",
         "set.move",
@@ -1484,6 +1704,9 @@ mod tests {
         "sortedset.zremrangebyscore",
         "sortedset.zinterstore",
         "sortedset.zunionstore",
+        "sortedset.notify.set",
+        "sortedset.notify.get",
+        "sortedset.notify.del",
         "queue.create",
         "queue.delete",
         "queue.list",
@@ -1587,12 +1810,15 @@ mod tests {
         "kv.mset",
         "kv.mdel",
         "kv.scan",
+        "hash.scan",
+        "set.scan",
+        "sortedset.zscan",
         "hash.get",
         "hash.getall",
         "hash.del",
         "hash.exists",
         "hash.keys",
-        "hash.values",
+        "hash.vals",
         "hash.len",
         "hash.mget",
         "hash.mset",
@@ -1601,20 +1827,20 @@ mod tests {
         "hash.incrby",
         "hash.incrbyfloat",
         "list.lpush",
-        "list.range",
-        "list.len",
-        "list.index",
-        "list.set",
-        "list.pos",
-        "list.rem",
-        "list.insert",
+        "list.lrange",
+        "list.llen",
+        "list.lindex",
+        "list.lset",
+        "list.lpos",
+        "list.lrem",
+        "list.linsert",
         "list.lpop",
         "list.rpoplpush",
         "set.add",
         "set.rem",
         "set.ismember",
         "set.members",
-        "set.card",
+        "set.size",
         "set.inter",
         "set.move",
         "set.interstore",
@@ -1628,6 +1854,8 @@ mod tests {
         "sortedset.zrange",
         "sortedset.zpopmin",
         "sortedset.zinterstore",
+        "sortedset.notify.get",
+        "sortedset.notify.del",
         "queue.create",
         "queue.publish",
         "queue.consume",
@@ -1697,6 +1925,29 @@ mod tests {
         // EXISTS maps an int to a bool-ish JSON.
         let exists = map_response("kv.exists", WireValue::Int(1));
         assert!(exists == json!(true) || exists == json!(1) || exists["exists"] == json!(true));
+        // *SCAN replies: RESP3 string cursor + flat pairs, RPC int cursor + map.
+        let resp3 = WireValue::Array(vec![
+            WireValue::Str("2".into()),
+            WireValue::Array(vec![
+                WireValue::Str("a".into()),
+                WireValue::Str("1.5".into()),
+            ]),
+        ]);
+        assert_eq!(
+            map_response("sortedset.zscan", resp3),
+            json!({"count": 1, "members": [{"member": "a", "score": 1.5}], "next_cursor": 2})
+        );
+        let rpc = WireValue::Array(vec![
+            WireValue::Int(0),
+            WireValue::Map(vec![(
+                WireValue::Str("f".into()),
+                WireValue::Str("v".into()),
+            )]),
+        ]);
+        assert_eq!(
+            map_response("hash.scan", rpc),
+            json!({"count": 1, "fields": {"f": "v"}, "next_cursor": 0})
+        );
         // Unknown command falls through to a generic conversion (no panic).
         let _ = map_response("unknown.cmd", WireValue::Str("x".into()));
     }
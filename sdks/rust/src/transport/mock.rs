@@ -0,0 +1,54 @@
+//! In-memory [`Transport`] for unit tests: canned payloads by command name,
+//! and a record of every request sent.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::protocol::Request;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::Transport;
+use crate::error::{ApiError, Result};
+
+#[derive(Default)]
+pub(crate) struct MockTransport {
+    responses: Mutex<HashMap<String, Value>>,
+    requests: Mutex<Vec<Request>>,
+}
+
+impl MockTransport {
+    /// Answer every `command` with `payload`
+    pub(crate) fn respond(self, command: &str, payload: Value) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .insert(command.to_owned(), payload);
+        self
+    }
+
+    /// Every request sent so far, oldest first
+    pub(crate) fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&self, request: Request) -> Result<Value> {
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .get(&request.command)
+            .cloned();
+        let command = request.command.clone();
+        self.requests.lock().unwrap().push(request);
+        response.ok_or_else(|| {
+            ApiError::new(format!("no mock response for {command}"))
+                .with_error_code("UNKNOWN_COMMAND")
+                .with_command(command)
+                .into()
+        })
+    }
+}
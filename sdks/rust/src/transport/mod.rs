@@ -1,6 +1,7 @@
-//! Binary TCP transports for the Synap SDK.
+//! Transports for the Synap SDK.
 //!
-//! Two native protocols are supported alongside the original HTTP REST transport:
+//! Every command a manager sends ends in [`Transport::send`]. Two native
+//! protocols are supported alongside the original HTTP REST transport:
 //!
 //! - **SynapRPC** (default): 4-byte LE length-prefixed MessagePack frames.
 //!   Same wire format as `synap-server`'s binary TCP listener.
@@ -8,7 +9,11 @@
 //!   Interoperable with any RESP2/RESP3 client.
 //! - **Http**: Original JSON-over-HTTP REST transport (fallback for commands
 //!   not yet mapped to a native protocol, e.g. pub/sub, queues, streams).
+//! - **WebSocket**: the HTTP envelope multiplexed over one persistent
+//!   WebSocket, see [`websocket`].
 
+use crate::protocol::Request;
+use async_trait::async_trait;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
@@ -18,7 +23,8 @@ use tokio::sync::Mutex;
 
 use serde_json::Value;
 
-use crate::error::{Result, SynapError};
+use crate::error::{ApiError, Result, SynapError};
+use crate::metrics::{ClientMetrics, OpenConnection, Retry};
 
 // ── Transport selection ───────────────────────────────────────────────────────
 
@@ -35,6 +41,86 @@ pub enum TransportMode {
     Resp3,
     /// JSON over HTTP REST (original SDK transport, best compatibility).
     Http,
+    /// The HTTP command envelope over one multiplexed WebSocket
+    /// (`/api/v1/command/ws`). Every command is supported, without a
+    /// round of HTTP request overhead each.
+    WebSocket,
+}
+
+// ── Transport trait ───────────────────────────────────────────────────────────
+
+/// How a [`SynapClient`](crate::SynapClient) delivers commands.
+///
+/// Managers build a command envelope and the client routes it to an endpoint,
+/// which sends it with this trait. The SDK implements it for HTTP, the
+/// multiplexed WebSocket, SynapRPC and RESP3 (see [`TransportMode`]);
+/// [`SynapClient::with_transport`](crate::SynapClient::with_transport) runs a
+/// client on any other implementation, such as an in-memory double in unit
+/// tests.
+///
+/// # Example
+/// ```
+/// use async_trait::async_trait;
+/// use serde_json::{Value, json};
+/// use synap_sdk::protocol::Request;
+/// use synap_sdk::transport::Transport;
+///
+/// /// Answers every `kv.get` with the same value.
+/// struct Fixed;
+///
+/// #[async_trait]
+/// impl Transport for Fixed {
+///     async fn send(&self, request: Request) -> synap_sdk::Result<Value> {
+///         match request.command.as_str() {
+///             "kv.get" => Ok(json!("alice")),
+///             other => Err(synap_sdk::ApiError::new(format!("unexpected {other}")).into()),
+///         }
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Send one command envelope and return the payload of its response.
+    ///
+    /// A command the server rejected is an `Err`, normally
+    /// [`SynapError::ApiError`] carrying the server's error code.
+    async fn send(&self, request: Request) -> Result<Value>;
+
+    /// Check that the server answers. Replicas whose probe fails leave
+    /// rotation until it succeeds again. The default always succeeds.
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// `request` as a native command, or why this transport cannot send it.
+/// Logical databases and unmapped commands need the HTTP envelope.
+fn native_command(transport: &str, request: &Request) -> Result<(&'static str, Vec<WireValue>)> {
+    if request.db != 0 {
+        return Err(SynapError::UnsupportedCommand {
+            command: format!("{} (db {})", request.command, request.db),
+            transport: transport.to_owned(),
+        });
+    }
+    map_command(&request.command, &request.payload).ok_or_else(|| SynapError::UnsupportedCommand {
+        command: request.command.clone(),
+        transport: transport.to_owned(),
+    })
+}
+
+/// A native reply as the envelope payload the managers expect.
+///
+/// Server rejections on the binary transports arrive as bare RESP-style
+/// strings; attach the command so the error is loggable on its own.
+fn native_payload(command: &str, result: Result<WireValue>) -> Result<Value> {
+    result
+        .map(|wire| map_response(command, wire))
+        .map_err(|e| match e {
+            SynapError::ServerError(message) => ApiError::from_resp_message(message)
+                .with_command(command)
+                .into(),
+            other => other,
+        })
 }
 
 // ── Shared wire types (single source of truth) ────────────────────────────────
@@ -102,7 +188,8 @@ pub(crate) struct SynapRpcTransport {
     endpoint: String,
     client_config: thunder::ClientConfig,
     /// Connected on first use — `new` is sync, dialing is not.
-    client: Mutex<Option<Arc<thunder::Client>>>,
+    client: Mutex<Option<(Arc<thunder::Client>, OpenConnection)>>,
+    metrics: Arc<ClientMetrics>,
 }
 
 impl SynapRpcTransport {
@@ -111,6 +198,7 @@ impl SynapRpcTransport {
         port: u16,
         timeout: Duration,
         credentials: Option<RpcCredentials>,
+        metrics: Arc<ClientMetrics>,
     ) -> Self {
         let mut client_config = thunder::ClientConfig::new()
             .connect_timeout(timeout)
@@ -129,19 +217,22 @@ impl SynapRpcTransport {
             endpoint: format!("synap://{host}:{port}"),
             client_config,
             client: Mutex::new(None),
+            metrics,
         }
     }
 
     /// Dial a fresh Thunder client against the configured endpoint.
-    async fn dial(&self) -> Result<Arc<thunder::Client>> {
-        thunder::Client::connect_with(
+    async fn dial(&self) -> Result<(Arc<thunder::Client>, OpenConnection)> {
+        let attempt = thunder::Client::connect_with(
             &self.endpoint,
             synap_protocol_config(),
             self.client_config.clone(),
         )
-        .await
-        .map(Arc::new)
-        .map_err(map_client_error)
+        .await;
+        self.metrics
+            .connected(attempt)
+            .map(|(client, open)| (Arc::new(client), open))
+            .map_err(map_client_error)
     }
 
     /// The shared client, dialed on first use and replaced if it died.
@@ -150,13 +241,13 @@ impl SynapRpcTransport {
     /// case where the client itself was poisoned beyond recovery.
     async fn client(&self) -> Result<Arc<thunder::Client>> {
         let mut guard = self.client.lock().await;
-        if let Some(existing) = guard.as_ref()
-            && existing.is_alive()
-        {
-            return Ok(Arc::clone(existing));
+        match guard.as_ref() {
+            Some((existing, _)) if existing.is_alive() => return Ok(Arc::clone(existing)),
+            Some(_) => self.metrics.retry(Retry::Reconnect),
+            None => {}
         }
-        let fresh = self.dial().await?;
-        *guard = Some(Arc::clone(&fresh));
+        let (fresh, open) = self.dial().await?;
+        *guard = Some((Arc::clone(&fresh), open));
         Ok(fresh)
     }
 
@@ -177,7 +268,7 @@ impl SynapRpcTransport {
     /// [`PushSubscription`] — dropping that closes the socket and ends the
     /// reader task, with no keeper task or liveness polling in between.
     pub(crate) async fn subscribe_push(&self, topics: Vec<String>) -> Result<PushSubscription> {
-        let client = self.dial().await?;
+        let (client, open) = self.dial().await?;
 
         // Register the hook before SUBSCRIBE, so a message published between
         // the server's reply and the registration cannot slip past.
@@ -204,6 +295,7 @@ impl SynapRpcTransport {
             subscriber_id,
             messages,
             client,
+            _open: open,
         })
     }
 
@@ -215,7 +307,7 @@ impl SynapRpcTransport {
         pattern: String,
         mode: Option<String>,
     ) -> Result<PushSubscription> {
-        let client = self.dial().await?;
+        let (client, open) = self.dial().await?;
 
         // Register the hook before KV.WATCH, so an event published between the
         // server's reply and the registration cannot slip past.
@@ -243,10 +335,23 @@ impl SynapRpcTransport {
             subscriber_id,
             messages,
             client,
+            _open: open,
         })
     }
 }
 
+#[async_trait]
+impl Transport for SynapRpcTransport {
+    async fn send(&self, request: Request) -> Result<Value> {
+        let (cmd, args) = native_command("SynapRpc", &request)?;
+        native_payload(&request.command, self.execute(cmd, args).await)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.execute("PING", Vec::new()).await.map(drop)
+    }
+}
+
 /// A live SUBSCRIBE, with the connection that serves it.
 ///
 /// `messages` yields each push frame as a `serde_json::Value` with fields
@@ -257,6 +362,7 @@ pub(crate) struct PushSubscription {
     pub(crate) subscriber_id: String,
     pub(crate) messages: tokio::sync::mpsc::UnboundedReceiver<Value>,
     client: Arc<thunder::Client>,
+    _open: OpenConnection,
 }
 
 impl PushSubscription {
@@ -279,6 +385,7 @@ impl PushSubscription {
 struct Resp3Conn {
     writer: OwnedWriteHalf,
     reader: BufReader<OwnedReadHalf>,
+    _open: OpenConnection,
 }
 
 /// Single persistent TCP connection to a RESP3 (Redis-compatible) listener.
@@ -286,26 +393,38 @@ pub(crate) struct Resp3Transport {
     addr: String,
     conn: Mutex<Option<Resp3Conn>>,
     timeout: Duration,
+    metrics: Arc<ClientMetrics>,
 }
 
 impl Resp3Transport {
-    pub(crate) fn new(host: &str, port: u16, timeout: Duration) -> Self {
+    pub(crate) fn new(
+        host: &str,
+        port: u16,
+        timeout: Duration,
+        metrics: Arc<ClientMetrics>,
+    ) -> Self {
         Self {
             addr: format!("{}:{}", host, port),
             conn: Mutex::new(None),
             timeout,
+            metrics,
         }
     }
 
     async fn do_connect(&self) -> Result<Resp3Conn> {
-        let stream = tokio::time::timeout(self.timeout, TcpStream::connect(&self.addr))
+        let attempt = tokio::time::timeout(self.timeout, TcpStream::connect(&self.addr))
             .await
-            .map_err(|_| SynapError::Timeout)?
-            .map_err(|e| SynapError::Other(format!("RESP3 connect {}: {}", self.addr, e)))?;
+            .map_err(|_| SynapError::Timeout)
+            .and_then(|connected| {
+                connected
+                    .map_err(|e| SynapError::Other(format!("RESP3 connect {}: {}", self.addr, e)))
+            });
+        let (stream, open) = self.metrics.connected(attempt)?;
         let (r, w) = stream.into_split();
         Ok(Resp3Conn {
             writer: w,
             reader: BufReader::new(r),
+            _open: open,
         })
     }
 
@@ -331,6 +450,9 @@ impl Resp3Transport {
         let mut guard = self.conn.lock().await;
 
         for attempt in 0..2u8 {
+            if attempt == 1 {
+                self.metrics.retry(Retry::Reconnect);
+            }
             if guard.is_none() || attempt == 1 {
                 *guard = Some(self.do_connect().await?);
             }
@@ -362,6 +484,18 @@ impl Resp3Transport {
     }
 }
 
+#[async_trait]
+impl Transport for Resp3Transport {
+    async fn send(&self, request: Request) -> Result<Value> {
+        let (cmd, args) = native_command("Resp3", &request)?;
+        native_payload(&request.command, self.execute(cmd, args).await)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.execute("PING", Vec::new()).await.map(drop)
+    }
+}
+
 /// Render a `WireValue` as raw bytes for inclusion in a RESP2 bulk string.
 fn wire_value_to_resp_bytes(v: &WireValue) -> Vec<u8> {
     match v {
@@ -559,5 +693,17 @@ fn parse_resp3_type(
 pub(crate) mod mapping;
 pub(crate) use mapping::{map_command, map_response};
 
+pub(crate) mod http;
+pub(crate) use http::HttpTransport;
+
+pub(crate) mod websocket;
+pub(crate) use websocket::WsCommandTransport;
+
+#[cfg(feature = "test-util")]
+pub(crate) mod embedded;
+
+#[cfg(test)]
+pub(crate) mod mock;
+
 #[cfg(test)]
 mod tests;
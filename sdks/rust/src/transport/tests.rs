@@ -119,6 +119,39 @@ fn map_command_set_add_multi() {
     assert_eq!(args.len(), 4); // key + 3 members
 }
 
+#[test]
+fn map_command_set_randmember_keeps_negative_count() {
+    let payload = json!({"key": "arms", "count": -3});
+    let (cmd, args) = map_command("set.randmember", &payload).unwrap();
+    assert_eq!(cmd, "SRANDMEMBER");
+    assert_eq!(
+        args,
+        vec![WireValue::Str("arms".into()), WireValue::Int(-3)]
+    );
+}
+
+#[test]
+fn map_command_set_intercard_with_limit() {
+    let payload = json!({"keys": ["a", "b"], "limit": 3});
+    let (cmd, args) = map_command("set.intercard", &payload).unwrap();
+    assert_eq!(cmd, "SINTERCARD");
+    assert_eq!(
+        args,
+        vec![
+            WireValue::Int(2),
+            WireValue::Str("a".into()),
+            WireValue::Str("b".into()),
+            WireValue::Str("LIMIT".into()),
+            WireValue::Int(3),
+        ]
+    );
+
+    // No limit counts the whole intersection
+    let payload = json!({"keys": ["a"], "limit": 0});
+    let (_, args) = map_command("set.intercard", &payload).unwrap();
+    assert_eq!(args.len(), 2);
+}
+
 #[test]
 fn wire_value_roundtrip_msgpack() {
     let vals = vec![
@@ -286,6 +319,15 @@ fn map_command_stream_publish_returns_some() {
     assert_eq!(result.unwrap().0, "SPUBLISH");
 }
 
+#[test]
+fn map_command_idempotent_publish_returns_none() {
+    // QPUBLISH / SPUBLISH cannot carry the key, so it must not be dropped
+    let queue = json!({"queue": "q", "payload": [], "idempotency_key": "k"});
+    assert!(map_command("queue.publish", &queue).is_none());
+    let stream = json!({"room": "r", "event": "e", "data": 1, "idempotency_key": "k"});
+    assert!(map_command("stream.publish", &stream).is_none());
+}
+
 // ── map_response – additional cases ──────────────────────────────────────
 
 #[test]
@@ -393,7 +435,13 @@ async fn synap_rpc_transport_execute_get() {
 
     tokio::spawn(run_synap_rpc_server_once(listener, "GET"));
 
-    let transport = SynapRpcTransport::new("127.0.0.1", port, Duration::from_secs(5), None);
+    let transport = SynapRpcTransport::new(
+        "127.0.0.1",
+        port,
+        Duration::from_secs(5),
+        None,
+        Arc::default(),
+    );
     let result = transport
         .execute("GET", vec![WireValue::Str("testkey".into())])
         .await
@@ -413,7 +461,13 @@ async fn synap_rpc_transport_command_uppercased() {
 
     tokio::spawn(run_synap_rpc_server_once(listener, "SET"));
 
-    let transport = SynapRpcTransport::new("127.0.0.1", port, Duration::from_secs(5), None);
+    let transport = SynapRpcTransport::new(
+        "127.0.0.1",
+        port,
+        Duration::from_secs(5),
+        None,
+        Arc::default(),
+    );
     let _ = transport
         .execute(
             "set",
@@ -479,7 +533,7 @@ async fn resp3_transport_execute_get() {
 
     tokio::spawn(run_resp3_server_once(listener));
 
-    let transport = Resp3Transport::new("127.0.0.1", port, Duration::from_secs(5));
+    let transport = Resp3Transport::new("127.0.0.1", port, Duration::from_secs(5), Arc::default());
     let result = transport
         .execute("GET", vec![WireValue::Str("testkey".into())])
         .await
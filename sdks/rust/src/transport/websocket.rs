@@ -0,0 +1,284 @@
+//! Multiplexed command channel over `GET /api/v1/command/ws`.
+//!
+//! One WebSocket carries every command as the same `Request` envelope
+//! `POST /api/v1/command` takes. The server runs commands concurrently and
+//! answers them out of order, so each call waits on its own `request_id` and a
+//! reader task routes responses back to the waiting calls. The socket is
+//! opened on first use and reopened after it drops; calls in flight when it
+//! drops fail with [`SynapError::Transport`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::protocol::{Encoding, Request};
+use async_trait::async_trait;
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::sync::{Mutex, oneshot};
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+use super::Transport;
+use super::http::envelope_payload;
+use crate::auth::HttpClient;
+use crate::error::{Result, SynapError};
+use crate::metrics::{ClientMetrics, OpenConnection, Retry};
+
+/// A WebSocket opened by [`connect_websocket`]
+pub(crate) type CommandSocket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Calls waiting for their response, by `request_id`; `None` once the socket
+/// is gone, so no call can wait on a reader that has stopped
+type Pending = Arc<std::sync::Mutex<Option<HashMap<String, oneshot::Sender<Result<Value>>>>>>;
+
+/// Open a WebSocket to `url` with `http`'s credentials and the given TLS
+/// connector, refreshing the credentials once if the upgrade is refused with
+/// `401`.
+pub(crate) async fn connect_websocket(
+    http: &HttpClient,
+    connector: Option<tokio_tungstenite::Connector>,
+    url: &str,
+) -> Result<CommandSocket> {
+    use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest};
+
+    let credentials = http.credentials();
+    let mut refreshed = false;
+    loop {
+        let authorization = credentials.header();
+        let mut request = url
+            .into_client_request()
+            .map_err(|e| SynapError::Transport(e.to_string()))?;
+        if let Some(value) = &authorization {
+            request
+                .headers_mut()
+                .insert(reqwest::header::AUTHORIZATION, value.clone());
+        }
+
+        match tokio_tungstenite::connect_async_tls_with_config(
+            request,
+            None,
+            false,
+            connector.clone(),
+        )
+        .await
+        {
+            Ok((stream, _)) => return Ok(stream),
+            Err(tungstenite::Error::Http(response))
+                if response.status() == reqwest::StatusCode::UNAUTHORIZED
+                    && !refreshed
+                    && credentials.refresh(authorization.as_ref()).await? =>
+            {
+                refreshed = true;
+            }
+            Err(e) => return Err(SynapError::Transport(e.to_string())),
+        }
+    }
+}
+
+/// The command socket shared by every call to one endpoint.
+pub(crate) struct WsCommandTransport {
+    http: HttpClient,
+    connector: Option<tokio_tungstenite::Connector>,
+    base_url: Url,
+    encoding: Encoding,
+    timeout: Duration,
+    /// Opened on first use — `new` is sync, connecting is not.
+    connection: Mutex<Option<Arc<WsConnection>>>,
+    metrics: Arc<ClientMetrics>,
+}
+
+impl WsCommandTransport {
+    /// Commands are sent as MessagePack binary frames when `encoding` is
+    /// MessagePack and as JSON text frames otherwise.
+    pub(crate) fn new(
+        http: HttpClient,
+        connector: Option<tokio_tungstenite::Connector>,
+        base_url: Url,
+        encoding: Encoding,
+        timeout: Duration,
+        metrics: Arc<ClientMetrics>,
+    ) -> Self {
+        Self {
+            http,
+            connector,
+            base_url,
+            encoding,
+            timeout,
+            connection: Mutex::new(None),
+            metrics,
+        }
+    }
+
+    /// The open socket, connected when there is none or the last one
+    /// dropped.
+    async fn connection(&self) -> Result<Arc<WsConnection>> {
+        let mut guard = self.connection.lock().await;
+        match guard.as_ref() {
+            Some(existing) if existing.is_alive() => return Ok(Arc::clone(existing)),
+            Some(_) => self.metrics.retry(Retry::Reconnect),
+            None => {}
+        }
+        let scheme = match self.base_url.scheme() {
+            "https" => "wss",
+            _ => "ws",
+        };
+        let url = format!(
+            "{}://{}/api/v1/command/ws",
+            scheme,
+            self.base_url.authority()
+        );
+        let connect = connect_websocket(&self.http, self.connector.clone(), &url);
+        let attempt = tokio::time::timeout(self.timeout, connect)
+            .await
+            .unwrap_or(Err(SynapError::Timeout));
+        let (socket, open) = self.metrics.connected(attempt)?;
+        let fresh = Arc::new(WsConnection::new(socket, open));
+        *guard = Some(Arc::clone(&fresh));
+        Ok(fresh)
+    }
+}
+
+#[async_trait]
+impl Transport for WsCommandTransport {
+    async fn send(&self, request: Request) -> Result<Value> {
+        let frame = match self.encoding {
+            Encoding::MessagePack => Message::binary(
+                self.encoding
+                    .encode(&request)
+                    .map_err(|e| SynapError::Other(e.to_string()))?,
+            ),
+            _ => Message::text(serde_json::to_string(&request)?),
+        };
+
+        let connection = self.connection().await?;
+        let (tx, rx) = oneshot::channel();
+        connection.register(&request.request_id, tx)?;
+        if let Err(e) = connection.sink.lock().await.send(frame).await {
+            connection.forget(&request.request_id);
+            return Err(SynapError::Transport(e.to_string()));
+        }
+
+        let result = match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(result)) => result?,
+            Ok(Err(_)) => return Err(SynapError::Transport("command socket closed".to_owned())),
+            Err(_) => {
+                connection.forget(&request.request_id);
+                return Err(SynapError::Timeout);
+            }
+        };
+        envelope_payload(&request.command, request.request_id, result)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        let url = self.base_url.join("health")?;
+        self.http
+            .send(|c| c.get(url.clone()))
+            .await
+            .and_then(|r| r.error_for_status().map_err(SynapError::from))
+            .map(drop)
+    }
+}
+
+/// One open command socket and the task reading its responses.
+struct WsConnection {
+    sink: Mutex<SplitSink<CommandSocket, Message>>,
+    pending: Pending,
+    reader: tokio::task::JoinHandle<()>,
+    _open: OpenConnection,
+}
+
+impl WsConnection {
+    fn new(socket: CommandSocket, open: OpenConnection) -> Self {
+        let (sink, stream) = socket.split();
+        let pending: Pending = Arc::new(std::sync::Mutex::new(Some(HashMap::new())));
+        let reader = tokio::spawn(read_responses(stream, Arc::clone(&pending)));
+        Self {
+            sink: Mutex::new(sink),
+            pending,
+            reader,
+            _open: open,
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        !self.reader.is_finished()
+    }
+
+    /// Wait for the response to `request_id`
+    fn register(&self, request_id: &str, tx: oneshot::Sender<Result<Value>>) -> Result<()> {
+        match self
+            .pending
+            .lock()
+            .expect("pending calls poisoned")
+            .as_mut()
+        {
+            Some(pending) => {
+                pending.insert(request_id.to_owned(), tx);
+                Ok(())
+            }
+            None => Err(SynapError::Transport("command socket closed".to_owned())),
+        }
+    }
+
+    /// Stop waiting for the response to `request_id`
+    fn forget(&self, request_id: &str) {
+        if let Some(pending) = self
+            .pending
+            .lock()
+            .expect("pending calls poisoned")
+            .as_mut()
+        {
+            pending.remove(request_id);
+        }
+    }
+}
+
+impl Drop for WsConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Route each response frame to the call waiting on its `request_id`, then
+/// fail the calls still waiting once the socket is gone.
+async fn read_responses(mut stream: SplitStream<CommandSocket>, pending: Pending) {
+    let reason = loop {
+        let response = match stream.next().await {
+            Some(Ok(Message::Text(text))) => serde_json::from_str::<Value>(text.as_str())
+                .map_err(|e| SynapError::InvalidResponse(e.to_string())),
+            Some(Ok(Message::Binary(data))) => Encoding::MessagePack
+                .decode::<Value>(&data)
+                .map_err(|e| SynapError::InvalidResponse(e.to_string())),
+            Some(Ok(Message::Close(_))) | None => break "command socket closed".to_owned(),
+            // Pings are answered by tungstenite
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => break e.to_string(),
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("Discarding undecodable command response: {}", e);
+                continue;
+            }
+        };
+        let Some(request_id) = response["request_id"].as_str() else {
+            continue;
+        };
+        let waiter = pending
+            .lock()
+            .expect("pending calls poisoned")
+            .as_mut()
+            .and_then(|pending| pending.remove(request_id));
+        if let Some(tx) = waiter {
+            let _ = tx.send(Ok(response));
+        }
+    };
+
+    let waiting = pending.lock().expect("pending calls poisoned").take();
+    for (_, tx) in waiting.into_iter().flatten() {
+        let _ = tx.send(Err(SynapError::Transport(reason.clone())));
+    }
+}
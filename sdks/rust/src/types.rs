@@ -1,117 +1,114 @@
-//! Common types for Synap SDK
-
-use serde::{Deserialize, Deserializer, Serialize};
-
-/// Deserialize a value that may arrive as either a number or a string
-/// (RESP3 returns all scalars as strings).
-fn from_str_or_num<'de, D, T>(deserializer: D) -> Result<T, D::Error>
-where
-    D: Deserializer<'de>,
-    T: std::str::FromStr,
-    T::Err: std::fmt::Display,
-{
-    use serde::de::Error;
-    let value = serde_json::Value::deserialize(deserializer)?;
-    match &value {
-        serde_json::Value::Number(n) => {
-            let s = n.to_string();
-            s.parse::<T>().map_err(Error::custom)
-        }
-        serde_json::Value::String(s) => s.parse::<T>().map_err(Error::custom),
-        other => Err(Error::custom(format!(
-            "expected number or string, got {other}"
-        ))),
-    }
-}
-
-/// Queue message
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Message {
-    pub id: String,
-    pub payload: Vec<u8>,
-    #[serde(default)]
-    pub priority: u8,
-    #[serde(default)]
-    pub retry_count: u32,
-    #[serde(default)]
-    pub max_retries: u32,
-    #[serde(skip_serializing_if = "Option::is_none", default)]
-    pub deadline: Option<u64>,
-}
-
-/// Queue statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct QueueStats {
-    #[serde(deserialize_with = "from_str_or_num")]
-    pub depth: usize,
-    #[serde(deserialize_with = "from_str_or_num")]
-    pub consumers: usize,
-    #[serde(deserialize_with = "from_str_or_num")]
-    pub published: u64,
-    #[serde(deserialize_with = "from_str_or_num")]
-    pub consumed: u64,
-    #[serde(deserialize_with = "from_str_or_num")]
-    pub acked: u64,
-    #[serde(deserialize_with = "from_str_or_num")]
-    pub nacked: u64,
-    #[serde(deserialize_with = "from_str_or_num")]
-    pub dead_lettered: usize,
-}
-
-/// Stream event
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Event {
-    #[serde(default)]
-    pub offset: u64,
-    #[serde(alias = "event_type")]
-    pub event: String,
-    pub data: serde_json::Value,
-    #[serde(default)]
-    pub timestamp: Option<u64>,
-}
-
-/// Stream statistics
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct StreamStats {
-    #[serde(alias = "room", default)]
-    pub name: String,
-    #[serde(default)]
-    pub message_count: usize,
-    #[serde(default)]
-    pub max_offset: u64,
-    #[serde(default)]
-    pub total_published: u64,
-    #[serde(default)]
-    pub total_consumed: u64,
-    #[serde(default)]
-    pub subscriber_count: usize,
-}
-
-/// Pub/Sub message
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PubSubMessage {
-    pub topic: String,
-    pub data: serde_json::Value,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub priority: Option<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<std::collections::HashMap<String, String>>,
-}
-
-/// KV Store statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct KVStats {
-    pub total_keys: usize,
-    pub total_memory_bytes: usize,
-    pub hit_rate: f64,
-}
-
-/// HyperLogLog statistics
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct HyperLogLogStats {
-    pub total_hlls: u64,
-    pub pfadd_count: u64,
-    pub pfcount_count: u64,
-    pub pfmerge_count: u64,
-    pub total_cardinality: u64,
-}
+//! Common types for Synap SDK
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Deserialize a value that may arrive as either a number or a string
+/// (RESP3 returns all scalars as strings).
+pub(crate) fn from_str_or_num<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    use serde::de::Error;
+    let value = serde_json::Value::deserialize(deserializer)?;
+    match &value {
+        serde_json::Value::Number(n) => {
+            let s = n.to_string();
+            s.parse::<T>().map_err(Error::custom)
+        }
+        serde_json::Value::String(s) => s.parse::<T>().map_err(Error::custom),
+        other => Err(Error::custom(format!(
+            "expected number or string, got {other}"
+        ))),
+    }
+}
+
+pub use crate::protocol::queue::{Message, QueueStatsResponse as QueueStats};
+pub use crate::protocol::stream::StreamStatsResponse as StreamStats;
+
+/// Stream event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    #[serde(default)]
+    pub offset: u64,
+    #[serde(alias = "event_type")]
+    pub event: String,
+    pub data: serde_json::Value,
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+}
+
+/// Stream event tagged with the room it was published to
+#[derive(Debug, Clone)]
+pub struct RoomEvent {
+    pub room: String,
+    pub event: Event,
+}
+
+/// One batch from a multi-room consume
+#[derive(Debug, Clone, Default)]
+pub struct MultiRoomBatch {
+    /// Events from all rooms, interleaved by publish time
+    pub events: Vec<RoomEvent>,
+    /// Offset to resume each requested room from
+    pub next_offsets: std::collections::HashMap<String, u64>,
+}
+
+/// Pub/Sub message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PubSubMessage {
+    pub topic: String,
+    pub data: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<std::collections::HashMap<String, String>>,
+}
+
+/// KV Store statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KVStats {
+    pub total_keys: usize,
+    pub total_memory_bytes: usize,
+    pub hit_rate: f64,
+}
+
+/// Outcome of [`SynapClient::import_snapshot`](crate::SynapClient::import_snapshot)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotImport {
+    /// Unix timestamp (seconds) at which the dump was exported.
+    pub created_at: u64,
+    /// Version of the server that exported it.
+    pub server_version: String,
+    /// KV keys, hashes, lists, sets and sorted sets in the dump.
+    pub keys: usize,
+    pub queues: usize,
+    pub queue_messages: usize,
+    pub streams: usize,
+    pub stream_events: usize,
+    /// KV keys whose TTL ran out between export and import.
+    pub expired_keys: usize,
+    pub operations_applied: usize,
+}
+
+/// How [`KVStore::restore`](crate::KVStore::restore) creates the key
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreOptions {
+    /// TTL in milliseconds, `Some(0)` for none; `None` keeps the dumped TTL.
+    pub ttl_ms: Option<u64>,
+    /// Overwrite the key if it exists.
+    pub replace: bool,
+    /// `ttl_ms` is a Unix timestamp in milliseconds.
+    pub absttl: bool,
+}
+
+/// HyperLogLog statistics
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HyperLogLogStats {
+    pub total_hlls: u64,
+    pub pfadd_count: u64,
+    pub pfcount_count: u64,
+    pub pfmerge_count: u64,
+    pub total_cardinality: u64,
+}
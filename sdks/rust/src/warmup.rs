@@ -0,0 +1,50 @@
+//! Cold start warmup manifests.
+//!
+//! A manifest lists the keys a service reads first after a deploy, exactly
+//! or by prefix. Ship it with the service and send it with
+//! [`SynapClient::warmup`](crate::SynapClient::warmup) before routing traffic,
+//! so the server primes those keys instead of taking the misses at once.
+
+use crate::error::{Result, SynapError};
+use std::path::Path;
+
+pub use crate::protocol::warmup::{WarmupManifest, WarmupReport};
+
+impl WarmupManifest {
+    /// An empty manifest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one key.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.keys.push(key.into());
+        self
+    }
+
+    /// Add several keys.
+    pub fn keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keys.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add every key starting with `prefix`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Read a JSON manifest (`{"keys": [...], "prefixes": [...]}`), the same
+    /// format the server accepts as `warmup.manifest`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            SynapError::Other(format!("reading warmup manifest {}: {}", path.display(), e))
+        })?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
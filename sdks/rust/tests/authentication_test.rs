@@ -4,11 +4,17 @@ mod common;
 
 #[cfg(test)]
 mod tests {
-    use super::common::setup_test_client;
+    use super::common::{create_mock_server, setup_test_client};
+    use futures::StreamExt;
     use mockito::Matcher;
     use serde_json::json;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::time::Duration;
+    use synap_sdk::SynapError;
     use synap_sdk::client::{SynapClient, SynapConfig};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
 
     const TEST_URL: &str = "http://localhost:15500";
     const TEST_USERNAME: &str = "root";
@@ -30,6 +36,16 @@ mod tests {
         assert_eq!(config.password, None);
     }
 
+    #[tokio::test]
+    async fn test_with_api_key_sets_bearer_token() {
+        let config = SynapConfig::new(TEST_URL)
+            .with_basic_auth("user", "pass")
+            .with_api_key("sk_test123");
+        assert_eq!(config.auth_token, Some("sk_test123".to_string()));
+        assert_eq!(config.username, None);
+        assert!(config.token_refresh.is_none());
+    }
+
     #[tokio::test]
     async fn test_config_builder_pattern() {
         let config = SynapConfig::new(TEST_URL)
@@ -119,4 +135,119 @@ mod tests {
         assert!(result.is_ok());
         _mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_unauthorized_refreshes_token_and_retries() {
+        let mut server = create_mock_server().await;
+        let rejected = server
+            .mock("POST", "/api/v1/command")
+            .match_header("authorization", "Bearer sk_expired")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("POST", "/api/v1/command")
+            .match_header("authorization", "Bearer sk_rotated")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let counter = refreshes.clone();
+        let config = SynapConfig::new(server.url())
+            .with_api_key("sk_expired")
+            .with_token_refresh(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { Ok("sk_rotated".to_string()) }
+            });
+        let client = SynapClient::new(config).unwrap();
+
+        client.kv().set("a", "1", None).await.unwrap();
+        // A clone keeps using the refreshed token without another refresh.
+        client.clone().kv().set("b", "2", None).await.unwrap();
+
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+        rejected.assert_async().await;
+        accepted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_without_refresh_is_returned() {
+        let mut server = create_mock_server().await;
+        let _mock = server
+            .mock("POST", "/api/v1/command")
+            .with_status(401)
+            .with_body(r#"{"error": "Unauthorized"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = SynapConfig::new(server.url()).with_api_key("sk_bad");
+        let client = SynapClient::new(config).unwrap();
+        match client.kv().set("a", "1", None).await {
+            Err(SynapError::Api(e)) => assert_eq!(e.status, Some(401)),
+            other => panic!("expected a 401 API error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    // The handshake callback's error type is fixed by tungstenite.
+    #[allow(clippy::result_large_err)]
+    async fn test_websocket_upgrade_sends_and_refreshes_credentials() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen_by_server = seen.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let seen = seen_by_server.clone();
+                let callback = move |request: &Request, response: Response| {
+                    let authorization = request
+                        .headers()
+                        .get("authorization")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string();
+                    seen.lock().unwrap().push(authorization.clone());
+                    if authorization == "Bearer sk_rotated" {
+                        Ok(response)
+                    } else {
+                        let mut rejection = ErrorResponse::new(None);
+                        *rejection.status_mut() = reqwest::StatusCode::UNAUTHORIZED;
+                        Err(rejection)
+                    }
+                };
+                if let Ok(mut ws) = tokio_tungstenite::accept_hdr_async(stream, callback).await {
+                    use futures::SinkExt;
+                    let frame = json!({"type": "message", "topic": "news", "payload": "hi"});
+                    ws.send(frame.to_string().into()).await.unwrap();
+                    // Keep the socket open until the client goes away.
+                    while ws.next().await.is_some() {}
+                }
+            }
+        });
+
+        let config = SynapConfig::new(url)
+            .with_api_key("sk_expired")
+            .with_token_refresh(|| async { Ok("sk_rotated".to_string()) });
+        let client = SynapClient::new(config).unwrap();
+        let (mut messages, handle) = client.pubsub().observe("sub-1", vec!["news".into()]);
+
+        let message = tokio::time::timeout(Duration::from_secs(5), messages.next())
+            .await
+            .expect("no message over the WebSocket")
+            .unwrap();
+        assert_eq!(message.topic, "news");
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec!["Bearer sk_expired", "Bearer sk_rotated"]
+        );
+        handle.unsubscribe();
+    }
 }
@@ -7,7 +7,7 @@ mod tests {
     use super::common::setup_test_client;
     use mockito::Matcher;
     use serde_json::json;
-    use synap_sdk::bitmap::BitmapOperation;
+    use synap_sdk::bitmap::{BitRangeUnit, BitmapOperation};
 
     #[tokio::test]
     async fn test_bitmap_setbit() {
@@ -129,6 +129,56 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_bitmap_bitcount_byte_range() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "bitmap.bitcount",
+                "payload": {"key": "bm:users", "start": -2, "end": -1, "unit": "byte"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"count": 3}}"#)
+            .create_async()
+            .await;
+
+        let count = client
+            .bitmap()
+            .bitcount_range("bm:users", -2, -1, BitRangeUnit::Byte)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_bitmap_bitpos_byte_range() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "bitmap.bitpos",
+                "payload": {"key": "bm:users", "value": 1, "start": 1, "end": 2, "unit": "byte"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"position": 12}}"#)
+            .create_async()
+            .await;
+
+        let position = client
+            .bitmap()
+            .bitpos_range("bm:users", 1, 1, Some(2), BitRangeUnit::Byte)
+            .await
+            .unwrap();
+        assert_eq!(position, Some(12));
+
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_bitmap_bitpos_found() {
         let (client, mut server) = setup_test_client().await;
@@ -0,0 +1,101 @@
+//! Tests for Bloom filter commands
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_reserve() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "bf.reserve",
+                "payload": {"key": "seen", "error_rate": 0.001, "capacity": 10000}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"reserved": true}}"#)
+            .create_async()
+            .await;
+
+        assert!(client.bloom().reserve("seen", 0.001, 10_000).await.unwrap());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_add_and_exists() {
+        let (client, mut server) = setup_test_client().await;
+
+        let add = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "bf.add",
+                "payload": {"key": "seen", "item": "a"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"added": true}}"#)
+            .create_async()
+            .await;
+        let exists = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "bf.exists",
+                "payload": {"key": "seen", "item": "b"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"exists": false}}"#)
+            .create_async()
+            .await;
+
+        assert!(client.bloom().add("seen", "a").await.unwrap());
+        assert!(!client.bloom().exists("seen", "b").await.unwrap());
+
+        add.assert_async().await;
+        exists.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_madd_and_mexists() {
+        let (client, mut server) = setup_test_client().await;
+
+        let madd = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "bf.madd",
+                "payload": {"key": "seen", "items": ["a", "b"]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"added": [false, true]}}"#)
+            .create_async()
+            .await;
+        let mexists = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "bf.mexists",
+                "payload": {"key": "seen", "items": ["a", "c"]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"exists": [true, false]}}"#)
+            .create_async()
+            .await;
+
+        let bloom = client.bloom();
+        assert_eq!(
+            bloom.madd("seen", &["a", "b"]).await.unwrap(),
+            vec![false, true]
+        );
+        assert_eq!(
+            bloom.mexists("seen", &["a", "c"]).await.unwrap(),
+            vec![true, false]
+        );
+
+        madd.assert_async().await;
+        mexists.assert_async().await;
+    }
+}
@@ -0,0 +1,192 @@
+//! Cluster routing: keyed commands go to the slot owner from the slot map,
+//! MOVED / ASK redirects are followed, and the map is reloaded when a node
+//! cannot be reached.
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Mock, Server, ServerGuard};
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use synap_sdk::{SynapClient, SynapConfig, hash_slot};
+
+    fn client(seed: &str) -> SynapClient {
+        let config = SynapConfig::new(seed)
+            .with_timeout(Duration::from_secs(5))
+            .with_cluster();
+        SynapClient::new(config).unwrap()
+    }
+
+    /// A port nothing listens on.
+    async fn dead_address() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        addr.to_string()
+    }
+
+    /// Serve a slot map from `seed` where node `owner` owns the slot of "k".
+    async fn serve_topology(seed: &mut ServerGuard, nodes: &[(&str, &str)], owner: &str) {
+        let nodes: Vec<_> = nodes
+            .iter()
+            .map(|(id, address)| json!({"id": id, "address": address}))
+            .collect();
+        seed.mock("GET", "/cluster/nodes")
+            .with_body(json!({"nodes": nodes}).to_string())
+            .expect_at_least(1)
+            .create_async()
+            .await;
+        seed.mock("GET", "/cluster/slots")
+            .with_body(json!({"slots": [{"slot": hash_slot("k"), "owner": owner}]}).to_string())
+            .expect_at_least(1)
+            .create_async()
+            .await;
+    }
+
+    async fn answer_get(server: &mut ServerGuard, body: &str, hits: usize) -> Mock {
+        server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "kv.get"})))
+            .with_body(body)
+            .expect(hits)
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_keyed_command_goes_to_slot_owner() {
+        let mut seed = Server::new_async().await;
+        let mut owner = Server::new_async().await;
+        let seed_address = seed.host_with_port();
+        serve_topology(
+            &mut seed,
+            &[("a", &seed_address), ("b", &owner.host_with_port())],
+            "b",
+        )
+        .await;
+        let read = answer_get(&mut owner, r#"{"success": true, "payload": "from-b"}"#, 1).await;
+
+        let client = client(&seed.url());
+        let value: Option<String> = client.kv().get("k").await.unwrap();
+        assert_eq!(value.as_deref(), Some("from-b"));
+        read.assert_async().await;
+
+        let mut nodes = client.cluster_nodes().await.unwrap();
+        nodes.sort_by(|x, y| x.id.cmp(&y.id));
+        assert_eq!(nodes[0].slot_count, 0);
+        assert_eq!(nodes[1].id, "b");
+        assert_eq!(nodes[1].slot_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_moved_updates_slot_map() {
+        let mut seed = Server::new_async().await;
+        let mut owner = Server::new_async().await;
+        let seed_address = seed.host_with_port();
+        serve_topology(&mut seed, &[("a", &seed_address)], "a").await;
+        let moved = format!(
+            r#"{{"success": false, "error": "MOVED {} {}"}}"#,
+            hash_slot("k"),
+            owner.host_with_port()
+        );
+        let stale = answer_get(&mut seed, &moved, 1).await;
+        let read = answer_get(&mut owner, r#"{"success": true, "payload": "v"}"#, 2).await;
+
+        let client = client(&seed.url());
+        for _ in 0..2 {
+            let value: Option<String> = client.kv().get("k").await.unwrap();
+            assert_eq!(value.as_deref(), Some("v"));
+        }
+
+        // Only the first read hit the old owner.
+        stale.assert_async().await;
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_ask_redirects_once_without_updating_map() {
+        let mut seed = Server::new_async().await;
+        let mut importing = Server::new_async().await;
+        let seed_address = seed.host_with_port();
+        serve_topology(&mut seed, &[("a", &seed_address)], "a").await;
+        let ask = format!(
+            r#"{{"success": false, "error": "ASK {} {}"}}"#,
+            hash_slot("k"),
+            importing.host_with_port()
+        );
+        let migrating = answer_get(&mut seed, &ask, 2).await;
+        let read = answer_get(&mut importing, r#"{"success": true, "payload": "v"}"#, 2).await;
+
+        let client = client(&seed.url());
+        for _ in 0..2 {
+            let value: Option<String> = client.kv().get("k").await.unwrap();
+            assert_eq!(value.as_deref(), Some("v"));
+        }
+
+        migrating.assert_async().await;
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_node_triggers_topology_refresh() {
+        let mut seed = Server::new_async().await;
+        let mut owner = Server::new_async().await;
+        let seed_address = seed.host_with_port();
+        let dead = dead_address().await;
+        let owner_address = owner.host_with_port();
+
+        seed.mock("GET", "/cluster/nodes")
+            .with_body(
+                json!({"nodes": [
+                    {"id": "a", "address": seed_address},
+                    {"id": "dead", "address": dead},
+                    {"id": "b", "address": owner_address},
+                ]})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        // First load: the dead node owns the slot. Afterwards: node b.
+        let loads = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&loads);
+        seed.mock("GET", "/cluster/slots")
+            .with_body_from_request(move |_| {
+                let owner = match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 => "dead",
+                    _ => "b",
+                };
+                json!({"slots": [{"slot": hash_slot("k"), "owner": owner}]})
+                    .to_string()
+                    .into()
+            })
+            .create_async()
+            .await;
+        let read = answer_get(&mut owner, r#"{"success": true, "payload": "v"}"#, 1).await;
+
+        let client = client(&seed.url());
+        let value: Option<String> = client.kv().get("k").await.unwrap();
+        assert_eq!(value.as_deref(), Some("v"));
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unkeyed_command_goes_to_seed() {
+        let mut seed = Server::new_async().await;
+        let publish = seed
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "pubsub.publish"})))
+            .with_body(r#"{"success": true, "payload": {"subscribers_matched": 0}}"#)
+            .create_async()
+            .await;
+
+        let client = client(&seed.url());
+        client
+            .pubsub()
+            .publish("news", json!("hi"), None, None)
+            .await
+            .unwrap();
+        publish.assert_async().await;
+    }
+}
@@ -0,0 +1,158 @@
+//! Tests for the `test-util` feature: a client on an in-process server
+
+#![cfg(feature = "test-util")]
+
+use serde_json::json;
+use synap_sdk::protocol::{
+    admin, hash, hyperloglog, kv, list, lock, pubsub, schedule, set, shovel, sorted_set, stream,
+    webhook,
+};
+use synap_sdk::{SynapClient, SynapConfig};
+
+#[tokio::test]
+async fn test_embedded_runs_commands_in_process() {
+    let client = SynapClient::embedded().await.unwrap();
+
+    client.kv().set("user:1", "alice", None).await.unwrap();
+    let name: Option<String> = client.kv().get("user:1").await.unwrap();
+    assert_eq!(name.as_deref(), Some("alice"));
+
+    client
+        .queue()
+        .create_queue("jobs", None, None)
+        .await
+        .unwrap();
+    client
+        .queue()
+        .publish("jobs", b"resize", None, None)
+        .await
+        .unwrap();
+    let message = client.queue().consume("jobs", "worker-1").await.unwrap();
+    assert_eq!(message.unwrap().payload, b"resize");
+}
+
+#[tokio::test]
+async fn test_embedded_errors_carry_server_codes() {
+    let client = SynapClient::embedded().await.unwrap();
+
+    let error = client
+        .queue()
+        .publish("missing", b"x", None, None)
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), Some("QUEUE_NOT_FOUND"));
+}
+
+#[tokio::test]
+async fn test_embedded_instances_are_isolated() {
+    let first = SynapClient::embedded().await.unwrap();
+    let second = SynapClient::embedded().await.unwrap();
+
+    first.kv().set("shared", "first", None).await.unwrap();
+    let seen: Option<String> = second.kv().get("shared").await.unwrap();
+    assert_eq!(seen, None);
+}
+
+#[tokio::test]
+async fn test_embedded_with_configures_the_client() {
+    let mut server = synap_server::ServerConfig::default();
+    server.persistence.enabled = false;
+    let client = SynapClient::embedded_with(server, |config: SynapConfig| {
+        config.with_key_prefix("tenant-a:")
+    })
+    .await
+    .unwrap();
+
+    client.kv().set("user:1", "alice", None).await.unwrap();
+    // Sent as `tenant-a:user:`, listed back without the prefix
+    let keys = client
+        .send_command("kv.keys", serde_json::json!({"prefix": "user:"}))
+        .await
+        .unwrap();
+    assert_eq!(keys["keys"], serde_json::json!(["user:1"]));
+
+    // REST-only calls reach the listener the embedded server serves
+    assert!(client.is_ready().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_embedded_decodes_typed_responses() {
+    // One command per family: a response shape that drifted from the
+    // server's fails to decode here
+    let client = SynapClient::embedded().await.unwrap();
+
+    client
+        .execute(&kv::KvSet {
+            key: "k".into(),
+            value: json!("v"),
+            ttl: None,
+            client_id: None,
+        })
+        .await
+        .unwrap();
+    client
+        .execute(&hash::HSet {
+            key: "h".into(),
+            field: "f".into(),
+            value: json!(1),
+            client_id: None,
+        })
+        .await
+        .unwrap();
+    let pushed = client
+        .execute(&list::RPush {
+            key: "l".into(),
+            values: vec![json!("a"), json!("b")],
+            client_id: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(pushed.length, 2);
+    client
+        .execute(&set::SAdd {
+            key: "s".into(),
+            members: vec![json!("a")],
+            client_id: None,
+        })
+        .await
+        .unwrap();
+    let size = client
+        .execute(&set::SCard { key: "s".into() })
+        .await
+        .unwrap();
+    assert_eq!(size.size, 1);
+    let card = client
+        .execute(&sorted_set::ZCard { key: "z".into() })
+        .await
+        .unwrap();
+    assert_eq!(card.count, 0);
+    client
+        .execute(&hyperloglog::HyperLogLogStats {})
+        .await
+        .unwrap();
+
+    client.execute(&stream::StreamList {}).await.unwrap();
+    client.execute(&pubsub::PubSubStats {}).await.unwrap();
+    client.execute(&shovel::ShovelList {}).await.unwrap();
+    client.execute(&webhook::WebhookList {}).await.unwrap();
+    client.execute(&schedule::ScheduleList {}).await.unwrap();
+    client
+        .execute(&lock::LockAcquire {
+            key: "job".into(),
+            ttl: 30,
+        })
+        .await
+        .unwrap();
+
+    client.execute(&admin::SlowlogConfigGet {}).await.unwrap();
+    client.execute(&admin::ConfigGet::default()).await.unwrap();
+    client
+        .execute(&admin::LatencyLatestEvents {})
+        .await
+        .unwrap();
+    let usage = client
+        .execute(&admin::MemoryUsage { key: "s".into() })
+        .await
+        .unwrap();
+    assert_eq!(usage.key_type, "set");
+}
@@ -0,0 +1,208 @@
+//! Typed SDK errors: status, error code, request id and command survive the trip
+//! from the server into `SynapError`.
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use serde_json::json;
+    use synap_sdk::{ApiError, SynapError};
+
+    #[tokio::test]
+    async fn test_http_error_status_keeps_server_context() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "error": "Key not found: user:1",
+                    "code": 404,
+                    "error_code": "KEY_NOT_FOUND",
+                    "request_id": "req-42"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let err = client.kv().get::<_, String>("user:1").await.unwrap_err();
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(err.error_code(), Some("KEY_NOT_FOUND"));
+        assert_eq!(err.request_id(), Some("req-42"));
+        assert_eq!(err.command(), Some("kv.get"));
+        assert!(err.is_not_found());
+        assert!(!err.is_conflict());
+        assert!(!err.is_retryable());
+        assert_eq!(
+            err.to_string(),
+            "Server error: Key not found: user:1 (command: kv.get, request_id: req-42)"
+        );
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_envelope_failure_falls_back_to_sent_request_id() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"success": false, "error": "CAS failed", "error_code": "CAS_FAILED"}"#)
+            .create_async()
+            .await;
+
+        let err = client.kv().set("k", "v", None).await.unwrap_err();
+        let api = err.api().expect("api error");
+        assert_eq!(api.message, "CAS failed");
+        assert_eq!(api.status, None);
+        assert_eq!(api.command.as_deref(), Some("kv.set"));
+        // The server did not echo an id, so the one the SDK sent is kept.
+        assert!(api.request_id.as_deref().is_some_and(|id| !id.is_empty()));
+        assert!(err.is_conflict());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_envelope_error_info_code_is_used() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "success": false,
+                    "request_id": "req-7",
+                    "payload": null,
+                    "error": "Quota exceeded: writes",
+                    "error_info": {
+                        "code": "QUOTA_EXCEEDED",
+                        "category": "limit_exceeded",
+                        "message": "Quota exceeded: writes",
+                        "retryable": true
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let err = client.kv().set("k", "v", None).await.unwrap_err();
+        assert_eq!(err.error_code(), Some("QUOTA_EXCEEDED"));
+        assert_eq!(err.request_id(), Some("req-7"));
+        assert!(err.is_retryable());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_response_header_request_id_is_surfaced() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_header("x-request-id", mockito::Matcher::Regex(".+".into()))
+            .with_status(500)
+            .with_header("content-type", "application/json")
+            .with_header("x-request-id", "srv-9")
+            .with_body(json!({"error": "boom", "code": 500}).to_string())
+            .create_async()
+            .await;
+
+        let err = client.kv().get::<_, String>("k").await.unwrap_err();
+        assert_eq!(err.request_id(), Some("srv-9"));
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_non_json_error_body_is_kept_verbatim() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .with_status(503)
+            .with_body("upstream unavailable")
+            .create_async()
+            .await;
+
+        let err = client.kv().get::<_, String>("k").await.unwrap_err();
+        assert_eq!(err.api().unwrap().message, "upstream unavailable");
+        assert!(err.is_retryable());
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_helpers_on_local_variants() {
+        assert!(SynapError::Timeout.is_retryable());
+        assert!(SynapError::Transport("reset".into()).is_retryable());
+        assert!(SynapError::KeyNotFound("k".into()).is_not_found());
+        assert!(SynapError::QueueNotFound("q".into()).is_not_found());
+        assert!(!SynapError::Other("x".into()).is_retryable());
+        assert_eq!(SynapError::Other("x".into()).status(), None);
+
+        let unsupported = SynapError::UnsupportedCommand {
+            command: "pubsub.subscribe".into(),
+            transport: "Resp3".into(),
+        };
+        assert_eq!(unsupported.command(), Some("pubsub.subscribe"));
+    }
+
+    #[test]
+    fn test_error_code_drives_classification_without_status() {
+        let busy: SynapError = ApiError::new("BUSY script running")
+            .with_error_code("BUSY")
+            .into();
+        assert!(busy.is_retryable());
+
+        let conflict: SynapError = ApiError::new("exists").with_error_code("key_exists").into();
+        assert!(conflict.is_conflict());
+        assert!(!conflict.is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_response_is_auth_error() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .with_status(403)
+            .with_header("content-type", "application/json")
+            .with_header("x-request-id", "req-403")
+            .with_body(json!({ "error": "Permission denied" }).to_string())
+            .create_async()
+            .await;
+
+        let err = client.kv().set("k", "v", None).await.unwrap_err();
+        assert!(err.is_auth());
+        assert!(!err.is_retryable());
+        assert_eq!(err.status(), Some(403));
+        assert_eq!(err.request_id(), Some("req-403"));
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_auth_classification() {
+        assert!(SynapError::Unauthorized("expired".into()).is_auth());
+        assert!(!SynapError::Timeout.is_auth());
+
+        let noauth: SynapError = ApiError::new("NOAUTH Authentication required")
+            .with_error_code("NOAUTH")
+            .into();
+        assert!(noauth.is_auth());
+        assert!(!noauth.is_not_found());
+
+        let missing: SynapError = ApiError::new("gone").with_status(404).into();
+        assert!(!missing.is_auth());
+    }
+}
@@ -1,6 +1,11 @@
 //! Hash Manager tests
 
-use synap_sdk::{SynapClient, SynapConfig};
+mod common;
+
+use common::setup_test_client;
+use mockito::Matcher;
+use serde_json::json;
+use synap_sdk::{CollectAll, SynapClient, SynapConfig};
 
 #[tokio::test]
 async fn test_hash_operations() {
@@ -65,3 +70,51 @@ async fn test_hash_incr() {
     let incr_float_result = hash.incr_by_float("test:metrics", "score", 0.5).await;
     assert!(incr_float_result.is_ok() || incr_float_result.is_err());
 }
+
+#[tokio::test]
+async fn test_hash_scan_pages_through_fields() {
+    let (client, mut server) = setup_test_client().await;
+
+    let second = server
+        .mock("POST", "/api/v1/command")
+        .match_body(Matcher::PartialJson(json!({
+            "command": "hash.scan",
+            "payload": {"key": "profile", "cursor": 2}
+        })))
+        .with_status(200)
+        .with_body(
+            r#"{"success": true, "payload": {"fields": {"name": "Ada"}, "count": 1, "next_cursor": 0}}"#,
+        )
+        .create_async()
+        .await;
+    let first = server
+        .mock("POST", "/api/v1/command")
+        .match_body(Matcher::PartialJson(json!({
+            "command": "hash.scan",
+            "payload": {"key": "profile", "cursor": 0, "count": 100}
+        })))
+        .with_status(200)
+        .with_body(
+            r#"{"success": true, "payload": {"fields": {"age": 36, "email": "ada@example.com"}, "count": 2, "next_cursor": 2}}"#,
+        )
+        .create_async()
+        .await;
+
+    let fields = client
+        .hash()
+        .scan("profile")
+        .collect_all(100)
+        .await
+        .unwrap();
+    assert_eq!(
+        fields,
+        vec![
+            ("age".to_string(), "36".to_string()),
+            ("email".to_string(), "ada@example.com".to_string()),
+            ("name".to_string(), "Ada".to_string()),
+        ]
+    );
+
+    first.assert_async().await;
+    second.assert_async().await;
+}
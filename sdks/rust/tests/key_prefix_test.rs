@@ -0,0 +1,143 @@
+//! Tests for `SynapConfig::with_key_prefix`
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::create_mock_server;
+    use futures::StreamExt;
+    use mockito::{Matcher, ServerGuard};
+    use serde_json::json;
+    use synap_sdk::{SynapClient, SynapConfig};
+
+    async fn setup_prefixed_client() -> (SynapClient, ServerGuard) {
+        let server = create_mock_server().await;
+        let config = SynapConfig::new(server.url()).with_key_prefix("staging:");
+        (SynapClient::new(config).unwrap(), server)
+    }
+
+    #[tokio::test]
+    async fn test_key_prefix_on_keys() {
+        let (client, mut server) = setup_prefixed_client().await;
+
+        let set = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.set",
+                "payload": {"key": "staging:user:1", "value": "alice"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+        let hash = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "hash.set",
+                "payload": {"key": "staging:profile", "field": "name"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"success": true}}"#)
+            .create_async()
+            .await;
+        let inter = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "set.inter",
+                "payload": {"keys": ["staging:a", "staging:b"]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"members": []}}"#)
+            .create_async()
+            .await;
+
+        client.kv().set("user:1", "alice", None).await.unwrap();
+        client.hash().set("profile", "name", "alice").await.unwrap();
+        client
+            .set()
+            .inter(vec!["a".into(), "b".into()])
+            .await
+            .unwrap();
+
+        set.assert_async().await;
+        hash.assert_async().await;
+        inter.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_key_prefix_stripped_from_scan() {
+        let (client, mut server) = setup_prefixed_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.scan",
+                "payload": {"prefix": "staging:user:"}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"keys": ["staging:user:1", "staging:user:2"]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let keys: Vec<String> = client
+            .kv()
+            .scan("user:")
+            .map(|key| key.unwrap())
+            .collect()
+            .await;
+        assert_eq!(keys, ["user:1", "user:2"]);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_key_prefix_on_queues_and_rooms() {
+        let (client, mut server) = setup_prefixed_client().await;
+
+        let publish = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.publish",
+                "payload": {"queue": "staging:jobs"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"message_id": "m1"}}"#)
+            .create_async()
+            .await;
+        let list = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "queue.list"})))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"queues": ["staging:jobs"]}}"#)
+            .create_async()
+            .await;
+        let room = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "stream.publish",
+                "payload": {"room": "staging:chat"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"offset": 0}}"#)
+            .create_async()
+            .await;
+
+        client
+            .queue()
+            .publish("jobs", b"work", None, None)
+            .await
+            .unwrap();
+        assert_eq!(client.queue().list().await.unwrap(), ["jobs"]);
+        client
+            .stream()
+            .publish("chat", "message", json!({"text": "hi"}))
+            .await
+            .unwrap();
+
+        publish.assert_async().await;
+        list.assert_async().await;
+        room.assert_async().await;
+    }
+}
@@ -59,6 +59,43 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_kv_select_sends_db() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.set",
+                "db": 2,
+                "payload": {"key": "k", "value": "v"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+
+        let db2 = client.select(2);
+        assert_eq!(db2.db(), 2);
+        assert_eq!(client.db(), 0);
+        db2.kv().set("k", "v", None).await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_kv_select_needs_http() {
+        let client = synap_sdk::SynapClient::new(
+            synap_sdk::SynapConfig::new("resp3://127.0.0.1:1").with_db(1),
+        )
+        .unwrap();
+        let err = client.kv().set("k", "v", None).await.unwrap_err();
+        assert!(matches!(
+            err,
+            synap_sdk::SynapError::UnsupportedCommand { .. }
+        ));
+    }
+
     #[tokio::test]
     async fn test_kv_get_found() {
         let (client, mut server) = setup_test_client().await;
@@ -215,11 +252,13 @@ mod tests {
         let mock = server
             .mock("POST", "/api/v1/command")
             .match_body(Matcher::PartialJson(json!({
-                "command": "kv.keys",
+                "command": "kv.scan",
                 "payload": {"prefix": "user:"}
             })))
             .with_status(200)
-            .with_body(r#"{"success": true, "payload": {"keys": ["user:1", "user:2", "user:3"]}}"#)
+            .with_body(
+                r#"{"success": true, "payload": {"keys": ["user:1", "user:2", "user:3"], "count": 3, "next_cursor": null}}"#,
+            )
             .create_async()
             .await;
 
@@ -229,4 +268,181 @@ mod tests {
 
         mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_kv_keys_pattern_pages_through_scan() {
+        let (client, mut server) = setup_test_client().await;
+
+        // The more specific mock goes first: the first page request carries
+        // no cursor and only matches the second one
+        let second = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.scan",
+                "payload": {"prefix": "session:", "cursor": "session:1:idle"}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"keys": ["session:2:active"], "count": 1, "next_cursor": null}}"#,
+            )
+            .create_async()
+            .await;
+        let first = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.scan",
+                "payload": {"prefix": "session:"}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"keys": ["session:1:active", "session:1:idle"], "count": 2, "next_cursor": "session:1:idle"}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let keys = client.kv().keys("session:*:active").await.unwrap();
+        assert_eq!(keys, vec!["session:1:active", "session:2:active"]);
+
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_typed_command() {
+        use synap_sdk::protocol::kv::{KvGetRange, KvMdel, KvMdelResponse};
+
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.mdel",
+                "payload": {"keys": ["a", "b"]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"deleted": 2}}"#)
+            .create_async()
+            .await;
+
+        let command = KvMdel {
+            keys: vec!["a".into(), "b".into()],
+        };
+        let response = client.execute(&command).await.unwrap();
+        assert_eq!(response, KvMdelResponse { deleted: 2 });
+        mock.assert_async().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.getrange",
+                "payload": {"key": "greeting", "start": 0, "end": 4}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": "hello"}"#)
+            .create_async()
+            .await;
+
+        let command = KvGetRange {
+            key: "greeting".into(),
+            start: 0,
+            end: 4,
+        };
+        let range = client.execute(&command).await.unwrap();
+        assert_eq!(range.as_deref(), Some("hello"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_kv_scan_follows_cursor() {
+        use futures::StreamExt;
+        use synap_sdk::CollectAll;
+
+        let (client, mut server) = setup_test_client().await;
+
+        let second = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.scan",
+                "payload": {"prefix": "user:", "cursor": "user:2"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"keys": ["user:3"], "count": 1}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let first = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.scan",
+                "payload": {"prefix": "user:", "limit": 100}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"keys": ["user:1", "user:2"], "count": 2, "next_cursor": "user:2"}}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let keys: Vec<String> = client
+            .kv()
+            .scan("user:")
+            .map(|key| key.unwrap())
+            .collect()
+            .await;
+        assert_eq!(keys, vec!["user:1", "user:2", "user:3"]);
+
+        // Over the cap: the error surfaces instead of a truncated result
+        let result = client.kv().scan("user:").collect_all(2).await;
+        assert!(matches!(
+            result,
+            Err(synap_sdk::SynapError::LimitExceeded(2))
+        ));
+
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_kv_dump_and_restore() {
+        let (client, mut server) = setup_test_client().await;
+
+        let dump = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "key.dump",
+                "payload": {"key": "cart:42"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"key": "cart:42", "payload": [1, 2, 3]}}"#)
+            .create_async()
+            .await;
+        let restore = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "key.restore",
+                "payload": {"key": "cart:43", "payload": [1, 2, 3], "ttl": 0, "replace": true}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"success": true}}"#)
+            .create_async()
+            .await;
+
+        let payload = client.kv().dump("cart:42").await.unwrap().unwrap();
+        assert_eq!(payload, vec![1, 2, 3]);
+        let options = synap_sdk::RestoreOptions {
+            ttl_ms: Some(0),
+            replace: true,
+            ..Default::default()
+        };
+        client
+            .kv()
+            .restore("cart:43", payload, options)
+            .await
+            .unwrap();
+
+        dump.assert_async().await;
+        restore.assert_async().await;
+    }
 }
@@ -0,0 +1,104 @@
+//! Tests for distributed lock commands
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_lock_acquire_and_release() {
+        let (client, mut server) = setup_test_client().await;
+
+        let acquire = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "lock.acquire",
+                "payload": {"key": "job", "ttl": 30000}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"acquired": true, "token": 42}}"#)
+            .create_async()
+            .await;
+        let release = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "lock.release",
+                "payload": {"key": "job", "token": 42}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"released": true}}"#)
+            .create_async()
+            .await;
+
+        let lock = client
+            .lock()
+            .try_acquire("job", Duration::from_secs(30))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lock.name(), "job");
+        assert_eq!(lock.token(), 42);
+        assert!(lock.is_held());
+        assert!(lock.release().await.unwrap());
+
+        acquire.assert_async().await;
+        release.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_lock_held_elsewhere() {
+        let (client, mut server) = setup_test_client().await;
+
+        let _mock = server
+            .mock("POST", "/api/v1/command")
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"acquired": false, "ttl": 5000}}"#)
+            .create_async()
+            .await;
+
+        let lock = client
+            .lock()
+            .acquire("job", Duration::from_secs(30), Duration::from_millis(120))
+            .await
+            .unwrap();
+        assert!(lock.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lock_renew_refused_marks_it_lost() {
+        let (client, mut server) = setup_test_client().await;
+
+        let _acquire = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "lock.acquire"})))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"acquired": true, "token": 7}}"#)
+            .create_async()
+            .await;
+        let renew = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "lock.renew",
+                "payload": {"key": "job", "token": 7, "ttl": 30000}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"renewed": false}}"#)
+            .create_async()
+            .await;
+
+        let lock = client
+            .lock()
+            .try_acquire("job", Duration::from_secs(30))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!lock.renew().await.unwrap());
+        assert!(!lock.is_held());
+
+        renew.assert_async().await;
+    }
+}
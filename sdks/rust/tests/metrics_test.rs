@@ -0,0 +1,98 @@
+//! Tests for client-side metrics (`SynapConfig::with_metrics`)
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::{create_mock_server, setup_test_client};
+    use synap_sdk::{SynapClient, SynapConfig};
+
+    const OK: &str = r#"{"success": true, "payload": {}}"#;
+
+    #[tokio::test]
+    async fn test_metrics_are_off_by_default() {
+        let (client, _server) = setup_test_client().await;
+        assert!(client.metrics().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_operations_and_auth_retries() {
+        let mut server = create_mock_server().await;
+        let _rejected = server
+            .mock("POST", "/api/v1/command")
+            .match_header("authorization", "Bearer sk_expired")
+            .with_status(401)
+            .create_async()
+            .await;
+        let _accepted = server
+            .mock("POST", "/api/v1/command")
+            .match_header("authorization", "Bearer sk_rotated")
+            .with_status(200)
+            .with_body(OK)
+            .create_async()
+            .await;
+
+        let config = SynapConfig::new(server.url())
+            .with_api_key("sk_expired")
+            .with_token_refresh(|| async { Ok("sk_rotated".to_string()) })
+            .with_metrics();
+        let client = SynapClient::new(config).unwrap();
+
+        client.kv().set("a", "1", None).await.unwrap();
+        // Clones, including other databases, share the metrics
+        client.select(1).kv().set("b", "2", None).await.unwrap();
+        let _ = client.send_command("kv.nope", serde_json::json!({})).await;
+
+        let metrics = client.metrics().unwrap();
+        let set = &metrics.operations["kv.set"];
+        assert_eq!((set.count, set.errors), (2, 0));
+        assert!(set.max_latency >= set.mean_latency());
+        assert_eq!(metrics.operations["kv.nope"].count, 1);
+        assert_eq!(metrics.retries.auth_refreshes, 1);
+        assert_eq!(metrics.connections.opened, 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_connections_and_errors() {
+        let port = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let config = SynapConfig::new(format!("resp3://127.0.0.1:{port}"))
+            .with_timeout(std::time::Duration::from_secs(1))
+            .with_metrics();
+        let client = SynapClient::new(config).unwrap();
+
+        assert!(client.kv().get::<_, String>("a").await.is_err());
+
+        let metrics = client.metrics().unwrap();
+        assert_eq!(metrics.operations["kv.get"].errors, 1);
+        assert_eq!(metrics.connections.failed, 1);
+        assert_eq!(metrics.connections.open, 0);
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[tokio::test]
+    async fn test_register_metrics() {
+        let mut server = create_mock_server().await;
+        let _mock = server
+            .mock("POST", "/api/v1/command")
+            .with_status(200)
+            .with_body(OK)
+            .create_async()
+            .await;
+        let client = SynapClient::new(SynapConfig::new(server.url()).with_metrics()).unwrap();
+        let registry = prometheus::Registry::new();
+        client.register_metrics(&registry).unwrap();
+        assert!(client.register_metrics(&registry).is_err());
+
+        client.kv().set("a", "1", None).await.unwrap();
+
+        let families = registry.gather();
+        let operations = families
+            .iter()
+            .find(|family| family.name() == "synap_client_operations_total")
+            .unwrap();
+        assert_eq!(operations.get_metric()[0].counter.value(), 1.0);
+    }
+}
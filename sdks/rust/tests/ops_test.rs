@@ -0,0 +1,143 @@
+//! Tests for the manager traits: the same application code runs against the
+//! real managers and against the in-memory backend
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use serde_json::json;
+    use synap_sdk::memory::MemoryBackend;
+    use synap_sdk::{KvOps, QueueOps, StreamOps, SynapError};
+
+    /// Application code written against the traits
+    async fn count_visit(kv: &dyn KvOps, page: &str) -> synap_sdk::Result<i64> {
+        kv.incr(&format!("visits:{page}")).await
+    }
+
+    #[tokio::test]
+    async fn test_real_manager_through_trait() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "kv.incr",
+                "payload": {"key": "visits:home"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"value": 7}}"#)
+            .create_async()
+            .await;
+
+        assert_eq!(count_visit(&client.kv(), "home").await.unwrap(), 7);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_memory_kv() {
+        let synap = MemoryBackend::new();
+        let kv = synap.kv();
+
+        assert_eq!(count_visit(&kv, "home").await.unwrap(), 1);
+        assert_eq!(count_visit(&synap.kv(), "home").await.unwrap(), 2);
+        assert_eq!(kv.decr("visits:home").await.unwrap(), 1);
+
+        kv.set("user:1", json!({"name": "alice"}), None)
+            .await
+            .unwrap();
+        kv.set("user:2", json!("bob"), Some(0)).await.unwrap();
+        assert_eq!(
+            kv.get("user:1").await.unwrap(),
+            Some(json!({"name": "alice"}))
+        );
+        // Expired on arrival
+        assert_eq!(kv.get("user:2").await.unwrap(), None);
+        assert_eq!(kv.keys("user:").await.unwrap(), vec!["user:1"]);
+        assert_eq!(kv.keys("user:?").await.unwrap(), vec!["user:1"]);
+        assert!(kv.keys("user:?0").await.unwrap().is_empty());
+        assert!(matches!(
+            kv.incr("user:1").await,
+            Err(SynapError::ServerError(_))
+        ));
+        assert!(kv.delete("user:1").await.unwrap());
+        assert!(!kv.exists("user:1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_memory_queue() {
+        let synap = MemoryBackend::new();
+        let queue = synap.queue();
+
+        assert!(matches!(
+            queue.publish("jobs", b"a", None, None).await,
+            Err(SynapError::QueueNotFound(_))
+        ));
+        queue.create_queue("jobs", None, None).await.unwrap();
+        queue.publish("jobs", b"low", Some(1), None).await.unwrap();
+        queue
+            .publish("jobs", b"high", Some(9), Some(0))
+            .await
+            .unwrap();
+
+        // Higher priority first
+        let high = queue.consume("jobs", "w1").await.unwrap().unwrap();
+        assert_eq!(high.payload, b"high");
+        // No retries left: dead-lettered
+        queue.nack("jobs", &high.id).await.unwrap();
+
+        let low = queue.consume("jobs", "w1").await.unwrap().unwrap();
+        queue.nack("jobs", &low.id).await.unwrap();
+        let low = queue.consume("jobs", "w2").await.unwrap().unwrap();
+        assert_eq!(low.retry_count, 1);
+        queue.ack("jobs", &low.id).await.unwrap();
+        assert!(queue.consume("jobs", "w1").await.unwrap().is_none());
+
+        let stats = queue.stats("jobs").await.unwrap();
+        assert_eq!(stats.published, 2);
+        assert_eq!(stats.consumed, 3);
+        assert_eq!((stats.acked, stats.nacked, stats.dead_lettered), (1, 2, 1));
+        assert_eq!(stats.depth, 0);
+        assert_eq!(queue.list().await.unwrap(), vec!["jobs"]);
+    }
+
+    #[tokio::test]
+    async fn test_memory_stream() {
+        let synap = MemoryBackend::new();
+        let stream = synap.stream();
+
+        stream.create_room("chat", Some(2)).await.unwrap();
+        assert!(stream.create_room("chat", None).await.is_err());
+        for text in ["a", "b", "c"] {
+            stream
+                .publish("chat", "message", json!({"text": text}))
+                .await
+                .unwrap();
+        }
+
+        // Only the newest two are kept; offsets keep counting
+        let events = stream.consume("chat", None, None).await.unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.offset).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(events[1].data, json!({"text": "c"}));
+        assert_eq!(
+            stream
+                .consume("chat", Some(2), Some(10))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let stats = stream.stats("chat").await.unwrap();
+        assert_eq!((stats.message_count, stats.max_offset), (2, 2));
+        stream.delete_room("chat").await.unwrap();
+        assert!(matches!(
+            stream.publish("chat", "message", json!(null)).await,
+            Err(SynapError::RoomNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+//! Tests for the `otel` feature: commands carry the caller's trace context
+
+#![cfg(feature = "otel")]
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use tracing::Instrument;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[tokio::test]
+    async fn test_http_command_sends_traceparent() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = opentelemetry_sdk::trace::TracerProvider::builder()
+            .build()
+            .tracer("test");
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let (client, mut server) = setup_test_client().await;
+        let span = tracing::info_span!("checkout");
+        let trace_id = span.context().span().span_context().trace_id();
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_header(
+                "traceparent",
+                Matcher::Regex(format!("^00-{trace_id}-[0-9a-f]{{16}}-01$")),
+            )
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+
+        client
+            .kv()
+            .set("k", "v", None)
+            .instrument(span)
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+}
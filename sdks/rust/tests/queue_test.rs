@@ -1,255 +1,346 @@
-//! Comprehensive tests for Queue operations
-
-mod common;
-
-#[cfg(test)]
-mod tests {
-    use super::common::setup_test_client;
-    use mockito::Matcher;
-    use serde_json::json;
-
-    #[tokio::test]
-    async fn test_queue_create() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.create",
-                "payload": {"name": "test_queue"}
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": {}}"#)
-            .create_async()
-            .await;
-
-        let result = client.queue().create_queue("test_queue", None, None).await;
-        assert!(result.is_ok());
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_create_with_options() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.create",
-                "payload": {
-                    "name": "custom_queue",
-                    "config": {
-                        "max_depth": 1000,
-                        "ack_deadline_secs": 30
-                    }
-                }
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": {}}"#)
-            .create_async()
-            .await;
-
-        let result = client
-            .queue()
-            .create_queue("custom_queue", Some(1000), Some(30))
-            .await;
-        assert!(result.is_ok());
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_publish() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.publish",
-                "payload": {
-                    "queue": "test_queue",
-                    "priority": 9,
-                    "max_retries": 3
-                }
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": {"message_id": "msg-123"}}"#)
-            .create_async()
-            .await;
-
-        let msg_id = client
-            .queue()
-            .publish("test_queue", b"test", Some(9), Some(3))
-            .await
-            .unwrap();
-        assert_eq!(msg_id, "msg-123");
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_consume() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.consume",
-                "payload": {
-                    "queue": "test_queue",
-                    "consumer_id": "worker-1"
-                }
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": {"id": "msg-123", "payload": [116,101,115,116], "priority": 5, "retry_count": 0, "max_retries": 3, "deadline": null}}"#)
-            .create_async()
-            .await;
-
-        let message = client
-            .queue()
-            .consume("test_queue", "worker-1")
-            .await
-            .unwrap();
-        assert!(message.is_some());
-        let msg = message.unwrap();
-        assert_eq!(msg.id, "msg-123");
-        assert_eq!(msg.priority, 5);
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_consume_empty() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.consume",
-                "payload": {"queue": "empty_queue", "consumer_id": "worker-1"}
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": null}"#)
-            .create_async()
-            .await;
-
-        let message = client
-            .queue()
-            .consume("empty_queue", "worker-1")
-            .await
-            .unwrap();
-        assert!(message.is_none());
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_ack() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.ack",
-                "payload": {"queue": "test_queue", "message_id": "msg-123"}
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": {}}"#)
-            .create_async()
-            .await;
-
-        let result = client.queue().ack("test_queue", "msg-123").await;
-        assert!(result.is_ok());
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_nack() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.nack",
-                "payload": {"queue": "test_queue", "message_id": "msg-123"}
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": {}}"#)
-            .create_async()
-            .await;
-
-        let result = client.queue().nack("test_queue", "msg-123").await;
-        assert!(result.is_ok());
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_stats() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.stats",
-                "payload": {"queue": "test_queue"}
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": {"depth": 10, "consumers": 5, "published": 100, "consumed": 90, "acked": 85, "nacked": 5, "dead_lettered": 2}}"#)
-            .create_async()
-            .await;
-
-        let stats = client.queue().stats("test_queue").await.unwrap();
-        assert_eq!(stats.depth, 10);
-        assert_eq!(stats.published, 100);
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_list() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.list",
-                "payload": {}
-            })))
-            .with_status(200)
-            .with_body(
-                r#"{"success": true, "payload": {"queues": ["queue1", "queue2", "queue3"]}}"#,
-            )
-            .create_async()
-            .await;
-
-        let queues = client.queue().list().await.unwrap();
-        assert_eq!(queues.len(), 3);
-        assert_eq!(queues[0], "queue1");
-
-        mock.assert_async().await;
-    }
-
-    #[tokio::test]
-    async fn test_queue_delete() {
-        let (client, mut server) = setup_test_client().await;
-
-        let mock = server
-            .mock("POST", "/api/v1/command")
-            .match_body(Matcher::PartialJson(json!({
-                "command": "queue.delete",
-                "payload": {"queue": "test_queue"}
-            })))
-            .with_status(200)
-            .with_body(r#"{"success": true, "payload": {}}"#)
-            .create_async()
-            .await;
-
-        let result = client.queue().delete_queue("test_queue").await;
-        assert!(result.is_ok());
-
-        mock.assert_async().await;
-    }
-}
+//! Comprehensive tests for Queue operations
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use serde_json::json;
+    use synap_sdk::protocol::{Request, Response};
+    use synap_sdk::{Encoding, SynapClient, SynapConfig};
+
+    #[tokio::test]
+    async fn test_queue_create() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.create",
+                "payload": {"name": "test_queue"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+
+        let result = client.queue().create_queue("test_queue", None, None).await;
+        assert!(result.is_ok());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_create_with_options() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.create",
+                "payload": {
+                    "name": "custom_queue",
+                    "config": {
+                        "max_depth": 1000,
+                        "ack_deadline_secs": 30
+                    }
+                }
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+
+        let result = client
+            .queue()
+            .create_queue("custom_queue", Some(1000), Some(30))
+            .await;
+        assert!(result.is_ok());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_publish() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.publish",
+                "payload": {
+                    "queue": "test_queue",
+                    "priority": 9,
+                    "max_retries": 3
+                }
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"message_id": "msg-123"}}"#)
+            .create_async()
+            .await;
+
+        let msg_id = client
+            .queue()
+            .publish("test_queue", b"test", Some(9), Some(3))
+            .await
+            .unwrap();
+        assert_eq!(msg_id, "msg-123");
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_consume() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.consume",
+                "payload": {
+                    "queue": "test_queue",
+                    "consumer_id": "worker-1"
+                }
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"message": {"id": "msg-123", "payload": [116,101,115,116], "priority": 5, "retry_count": 0, "max_retries": 3}}}"#)
+            .create_async()
+            .await;
+
+        let message = client
+            .queue()
+            .consume("test_queue", "worker-1")
+            .await
+            .unwrap();
+        assert!(message.is_some());
+        let msg = message.unwrap();
+        assert_eq!(msg.id, "msg-123");
+        assert_eq!(msg.priority, 5);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_consume_empty() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.consume",
+                "payload": {"queue": "empty_queue", "consumer_id": "worker-1"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"message": null}}"#)
+            .create_async()
+            .await;
+
+        let message = client
+            .queue()
+            .consume("empty_queue", "worker-1")
+            .await
+            .unwrap();
+        assert!(message.is_none());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_ack() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.ack",
+                "payload": {"queue": "test_queue", "message_id": "msg-123"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+
+        let result = client.queue().ack("test_queue", "msg-123").await;
+        assert!(result.is_ok());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_nack() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.nack",
+                "payload": {"queue": "test_queue", "message_id": "msg-123"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+
+        let result = client.queue().nack("test_queue", "msg-123").await;
+        assert!(result.is_ok());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_stats() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.stats",
+                "payload": {"queue": "test_queue"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"depth": 10, "consumers": 5, "published": 100, "consumed": 90, "acked": 85, "nacked": 5, "dead_lettered": 2}}"#)
+            .create_async()
+            .await;
+
+        let stats = client.queue().stats("test_queue").await.unwrap();
+        assert_eq!(stats.depth, 10);
+        assert_eq!(stats.published, 100);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_list() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.list",
+                "payload": {}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"queues": ["queue1", "queue2", "queue3"]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let queues = client.queue().list().await.unwrap();
+        assert_eq!(queues.len(), 3);
+        assert_eq!(queues[0], "queue1");
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_delete() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.delete",
+                "payload": {"queue": "test_queue"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+
+        let result = client.queue().delete_queue("test_queue").await;
+        assert!(result.is_ok());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_publish_over_msgpack() {
+        let mut server = super::common::create_mock_server().await;
+        let config = SynapConfig::new(server.url()).with_http_encoding(Encoding::MessagePack);
+        let client = SynapClient::new(config).unwrap();
+
+        let payload = vec![0xAB; 4096];
+        let request = Request::new(
+            "queue.publish",
+            json!({"queue": "jobs", "payload": payload}),
+        );
+        let encoded = Encoding::MessagePack.encode(&request).unwrap();
+        // The payload travels as one MessagePack bin, not 4096 integers
+        assert!(encoded.len() < payload.len() + 128);
+
+        let response = Response::success(String::new(), json!({"message_id": "msg-1"}));
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_header("content-type", "application/msgpack")
+            .match_header("accept", "application/msgpack")
+            .match_request(move |req| {
+                let sent: Request = Encoding::MessagePack.decode(req.body().unwrap()).unwrap();
+                sent.command == "queue.publish" && sent.payload == request.payload
+            })
+            .with_status(200)
+            .with_header("content-type", "application/msgpack")
+            .with_body(Encoding::MessagePack.encode(&response).unwrap())
+            .create_async()
+            .await;
+
+        let id = client
+            .queue()
+            .publish("jobs", &payload, None, None)
+            .await
+            .unwrap();
+        assert_eq!(id, "msg-1");
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_queue_browse_pages_to_the_end() {
+        use synap_sdk::CollectAll;
+
+        let (client, mut server) = setup_test_client().await;
+        let message = |id: &str| json!({"id": id, "payload": [1, 2], "priority": 5});
+
+        let first = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.browse",
+                "payload": {"queue": "jobs", "offset": 0, "limit": 100}
+            })))
+            .with_status(200)
+            .with_body(
+                json!({"success": true, "payload": {
+                    "messages": [message("m1"), message("m2")],
+                    "total": 3,
+                    "next_offset": 2
+                }})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let last = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "queue.browse",
+                "payload": {"queue": "jobs", "offset": 2}
+            })))
+            .with_status(200)
+            .with_body(
+                json!({"success": true, "payload": {"messages": [message("m3")], "total": 3}})
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let messages = client.queue().browse("jobs").collect_all(10).await.unwrap();
+        assert_eq!(
+            messages.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+            ["m1", "m2", "m3"]
+        );
+        assert_eq!(messages[0].payload, vec![1, 2]);
+
+        first.assert_async().await;
+        last.assert_async().await;
+    }
+}
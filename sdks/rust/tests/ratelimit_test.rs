@@ -0,0 +1,102 @@
+//! Tests for rate limiter commands
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use serde_json::json;
+    use std::time::Duration;
+    use synap_sdk::RateLimit;
+
+    #[tokio::test]
+    async fn test_token_bucket_check() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "ratelimit.check",
+                "payload": {
+                    "key": "api:1",
+                    "max_tokens": 10,
+                    "refill_rate": 2.0,
+                    "algorithm": "token_bucket"
+                }
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"allowed": true, "remaining": 9}}"#)
+            .create_async()
+            .await;
+
+        let limit = RateLimit::TokenBucket {
+            max_tokens: 10,
+            refill_rate: 2.0,
+        };
+        let status = client.rate_limiter().check("api:1", limit).await.unwrap();
+        assert!(status.allowed);
+        assert_eq!(status.remaining, 9);
+        assert_eq!(status.retry_after, None);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_denied() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "ratelimit.check",
+                "payload": {
+                    "key": "login:alice",
+                    "max_tokens": 5,
+                    "window": 60000,
+                    "algorithm": "sliding_window",
+                    "cost": 2
+                }
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"allowed": false, "remaining": 1, "retry_after": 1500}}"#,
+            )
+            .create_async()
+            .await;
+
+        let limit = RateLimit::SlidingWindow {
+            max_requests: 5,
+            window: Duration::from_secs(60),
+        };
+        let status = client
+            .rate_limiter()
+            .check_cost("login:alice", limit, 2)
+            .await
+            .unwrap();
+        assert!(!status.allowed);
+        assert_eq!(status.retry_after, Some(Duration::from_millis(1500)));
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_reset() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "ratelimit.reset",
+                "payload": {"key": "api:1"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"reset": true}}"#)
+            .create_async()
+            .await;
+
+        assert!(client.rate_limiter().reset("api:1").await.unwrap());
+
+        mock.assert_async().await;
+    }
+}
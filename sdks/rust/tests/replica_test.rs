@@ -0,0 +1,212 @@
+//! Read-replica routing: reads go to replicas per the read preference, writes
+//! to the primary, and unreachable replicas are failed out. Also covers the
+//! failover topology query.
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+    use std::time::Duration;
+    use synap_sdk::{ReadPreference, SynapClient, SynapConfig};
+
+    fn client(primary: &str, replicas: &[String], preference: ReadPreference) -> SynapClient {
+        let config = SynapConfig::new(primary)
+            .with_timeout(Duration::from_secs(5))
+            .with_replicas(replicas.iter().cloned())
+            .with_read_preference(preference)
+            // Keep the background checker out of the way after its first pass.
+            .with_replica_health_interval(Duration::from_secs(3600));
+        SynapClient::new(config).unwrap()
+    }
+
+    /// A port nothing listens on.
+    async fn dead_url() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn test_reads_go_to_replica_and_writes_to_primary() {
+        let mut primary = Server::new_async().await;
+        let mut replica = Server::new_async().await;
+
+        for server in [&mut primary, &mut replica] {
+            server
+                .mock("GET", "/health")
+                .with_status(200)
+                .expect_at_least(0)
+                .create_async()
+                .await;
+        }
+        let read = replica
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "kv.get"})))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": "from-replica"}"#)
+            .create_async()
+            .await;
+        let write = primary
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "kv.set"})))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"success": true}}"#)
+            .create_async()
+            .await;
+
+        let client = client(&primary.url(), &[replica.url()], ReadPreference::Replica);
+
+        client.kv().set("k", "v", None).await.unwrap();
+        let value: Option<String> = client.kv().get("k").await.unwrap();
+        assert_eq!(value.as_deref(), Some("from-replica"));
+
+        read.assert_async().await;
+        write.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_primary_preference_ignores_replicas() {
+        let mut primary = Server::new_async().await;
+        let replica = Server::new_async().await;
+
+        let read = primary
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "kv.get"})))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": "from-primary"}"#)
+            .create_async()
+            .await;
+
+        let client = client(&primary.url(), &[replica.url()], ReadPreference::Primary);
+        let value: Option<String> = client.kv().get("k").await.unwrap();
+        assert_eq!(value.as_deref(), Some("from-primary"));
+
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_replica_fails_over_to_primary() {
+        let mut primary = Server::new_async().await;
+        primary
+            .mock("GET", "/health")
+            .with_status(200)
+            .expect_at_least(0)
+            .create_async()
+            .await;
+        let read = primary
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "kv.get"})))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": "from-primary"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let dead = dead_url().await;
+        let client = client(
+            &primary.url(),
+            std::slice::from_ref(&dead),
+            ReadPreference::Replica,
+        );
+
+        for _ in 0..2 {
+            let value: Option<String> = client.kv().get("k").await.unwrap();
+            assert_eq!(value.as_deref(), Some("from-primary"));
+        }
+
+        let status = client.replica_status();
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].url, dead);
+        assert!(!status[0].healthy);
+
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_health_check_removes_and_restores_replica() {
+        let mut primary = Server::new_async().await;
+        let mut replica = Server::new_async().await;
+
+        primary
+            .mock("GET", "/health")
+            .with_status(200)
+            .expect_at_least(0)
+            .create_async()
+            .await;
+        let unhealthy = replica
+            .mock("GET", "/health")
+            .with_status(503)
+            .expect_at_least(1)
+            .create_async()
+            .await;
+        primary
+            .mock("POST", "/api/v1/command")
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": "from-primary"}"#)
+            .create_async()
+            .await;
+        replica
+            .mock("POST", "/api/v1/command")
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": "from-replica"}"#)
+            .create_async()
+            .await;
+
+        let client = client(&primary.url(), &[replica.url()], ReadPreference::Replica);
+
+        client.check_replicas().await;
+        assert!(!client.replica_status()[0].healthy);
+        let value: Option<String> = client.kv().get("k").await.unwrap();
+        assert_eq!(value.as_deref(), Some("from-primary"));
+
+        unhealthy.remove_async().await;
+        replica
+            .mock("GET", "/health")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        client.check_replicas().await;
+        let status = &client.replica_status()[0];
+        assert!(status.healthy);
+        assert!(status.latency.is_some());
+        let value: Option<String> = client.kv().get("k").await.unwrap();
+        assert_eq!(value.as_deref(), Some("from-replica"));
+    }
+
+    #[tokio::test]
+    async fn test_replication_topology() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(
+                json!({"command": "replication.topology"}),
+            ))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {
+                    "epoch": 2,
+                    "node_id": "replica-2",
+                    "role": "replica",
+                    "master": {
+                        "node_id": "replica-1",
+                        "url": "http://replica-1:15500",
+                        "replication_address": "10.0.0.2:15501"
+                    }
+                }}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = client(&server.url(), &[], ReadPreference::Primary);
+        let topology = client.replication_topology().await.unwrap();
+
+        assert_eq!(topology.epoch, 2);
+        assert_eq!(topology.role, "replica");
+        let master = topology.master.unwrap();
+        assert_eq!(master.node_id.as_deref(), Some("replica-1"));
+        assert_eq!(master.url.as_deref(), Some("http://replica-1:15500"));
+        mock.assert_async().await;
+    }
+}
@@ -0,0 +1,108 @@
+//! Tests for schedule commands
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use serde_json::json;
+    use synap_sdk::ScheduleTarget;
+
+    #[tokio::test]
+    async fn test_create() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "schedule.create",
+                "payload": {
+                    "name": "nightly",
+                    "cron": "0 2 * * *",
+                    "target": {"queue": "reports"},
+                    "payload": {"at": "{{fire_time}}"}
+                }
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {
+                    "spec": {"name": "nightly", "cron": "0 2 * * *",
+                             "target": {"queue": "reports"},
+                             "payload": {"at": "{{fire_time}}"}},
+                    "fired": 0, "failed": 0, "next_fire_at": 1772330400000
+                }}"#,
+            )
+            .create_async()
+            .await;
+
+        let status = client
+            .scheduler()
+            .create(
+                "nightly",
+                "0 2 * * *",
+                ScheduleTarget::Queue("reports".to_string()),
+                json!({"at": "{{fire_time}}"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(status.spec.name, "nightly");
+        assert_eq!(status.progress.next_fire_at, Some(1_772_330_400_000));
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_get_and_delete() {
+        let (client, mut server) = setup_test_client().await;
+
+        let list = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({"command": "schedule.list"})))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"schedules": [
+                    {"spec": {"name": "tick", "cron": "* * * * *",
+                              "target": {"topic": "ticks"}, "payload": null},
+                     "fired": 12, "failed": 1, "last_error": "boom"}
+                ]}}"#,
+            )
+            .create_async()
+            .await;
+        let get = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "schedule.get",
+                "payload": {"name": "missing"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {}}"#)
+            .create_async()
+            .await;
+        let delete = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "schedule.delete",
+                "payload": {"name": "tick"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"deleted": true}}"#)
+            .create_async()
+            .await;
+
+        let schedules = client.scheduler().list().await.unwrap();
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(
+            schedules[0].spec.target,
+            ScheduleTarget::Topic("ticks".to_string())
+        );
+        assert_eq!(schedules[0].progress.fired, 12);
+        assert_eq!(schedules[0].progress.last_error.as_deref(), Some("boom"));
+        assert!(client.scheduler().get("missing").await.unwrap().is_none());
+        assert!(client.scheduler().delete("tick").await.unwrap());
+
+        list.assert_async().await;
+        get.assert_async().await;
+        delete.assert_async().await;
+    }
+}
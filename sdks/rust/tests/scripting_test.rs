@@ -157,4 +157,67 @@ mod tests {
         flush_mock.assert_async().await;
         kill_mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_function_load_list_and_fcall() {
+        let (client, mut server) = setup_test_client().await;
+
+        let load_mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "function.load",
+                "payload": {"replace": true}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"library": "counters"}}"#)
+            .create_async()
+            .await;
+
+        let list_mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "function.list",
+                "payload": {"withcode": false}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"libraries": [{"name": "counters", "functions": ["bump"]}]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let fcall_mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "function.fcall",
+                "payload": {"function": "bump", "keys": ["hits"], "args": [5]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"result": 5}}"#)
+            .create_async()
+            .await;
+
+        let library = client
+            .script()
+            .function_load("#!lua name=counters\n...", true)
+            .await
+            .unwrap();
+        assert_eq!(library, "counters");
+
+        let libraries = client.script().function_list(false).await.unwrap();
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(libraries[0].functions, vec!["bump"]);
+        assert!(libraries[0].code.is_none());
+
+        let hits: i64 = client
+            .script()
+            .fcall("bump", vec!["hits".into()], vec![json!(5)])
+            .await
+            .unwrap();
+        assert_eq!(hits, 5);
+
+        load_mock.assert_async().await;
+        list_mock.assert_async().await;
+        fcall_mock.assert_async().await;
+    }
 }
@@ -0,0 +1,93 @@
+//! Mock-based tests for Set operations (no running server required)
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_set_store_operations() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mut mocks = Vec::new();
+        for (command, count) in [
+            ("set.interstore", 2),
+            ("set.unionstore", 4),
+            ("set.diffstore", 1),
+        ] {
+            mocks.push(
+                server
+                    .mock("POST", "/api/v1/command")
+                    .match_body(Matcher::PartialJson(json!({
+                        "command": command,
+                        "payload": {"destination": "out", "keys": ["a", "b"]}
+                    })))
+                    .with_status(200)
+                    .with_body(format!(
+                        r#"{{"success": true, "payload": {{"count": {count}, "destination": "out"}}}}"#
+                    ))
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let set = client.set();
+        let keys = || vec!["a".to_string(), "b".to_string()];
+        assert_eq!(set.inter_store("out", keys()).await.unwrap(), 2);
+        assert_eq!(set.union_store("out", keys()).await.unwrap(), 4);
+        assert_eq!(set.diff_store("out", keys()).await.unwrap(), 1);
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_inter_card() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "set.intercard",
+                "payload": {"keys": ["a", "b"], "limit": 10}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"count": 3}}"#)
+            .create_async()
+            .await;
+
+        let count = client
+            .set()
+            .inter_card(vec!["a".to_string(), "b".to_string()], Some(10))
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_set_rand_member_negative_count() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "set.randmember",
+                "payload": {"key": "arms", "count": -3}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"members": ["a", "a", "b"]}}"#)
+            .create_async()
+            .await;
+
+        let draws = client.set().rand_member("arms", -3).await.unwrap();
+        assert_eq!(draws, vec!["a", "a", "b"]);
+
+        mock.assert_async().await;
+    }
+}
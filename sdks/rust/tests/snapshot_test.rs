@@ -0,0 +1,77 @@
+//! Portable dumps: `export_snapshot` downloads `/snapshot/export` and
+//! `import_snapshot` uploads to `/snapshot/import`.
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+    use std::time::Duration;
+    use synap_sdk::{SynapClient, SynapConfig, SynapError};
+
+    fn client(url: &str) -> SynapClient {
+        let config = SynapConfig::new(url).with_timeout(Duration::from_secs(5));
+        SynapClient::new(config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import_snapshot() {
+        let mut server = Server::new_async().await;
+        let dump = b"SYNAPDMP\x01binary-body".to_vec();
+        let export = server
+            .mock("GET", "/snapshot/export")
+            .with_header("content-type", "application/octet-stream")
+            .with_body(&dump)
+            .create_async()
+            .await;
+        let import = server
+            .mock("POST", "/snapshot/import")
+            .match_header("content-type", "application/octet-stream")
+            .match_body(Matcher::from(dump.clone()))
+            .with_body(
+                json!({
+                    "created_at": 1_760_000_000u64,
+                    "server_version": "1.3.0",
+                    "keys": 12,
+                    "queues": 1,
+                    "queue_messages": 4,
+                    "streams": 0,
+                    "stream_events": 0,
+                    "expired_keys": 2,
+                    "operations_applied": 14
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = client(&server.url());
+        let exported = client.export_snapshot().await.unwrap();
+        assert_eq!(exported, dump);
+        let summary = client.import_snapshot(exported).await.unwrap();
+        assert_eq!(summary.keys, 12);
+        assert_eq!(summary.expired_keys, 2);
+        export.assert_async().await;
+        import.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_import_snapshot_reports_rejection() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/snapshot/import")
+            .with_status(400)
+            .with_body(r#"{"error": "Invalid dump: not a Synap dump file"}"#)
+            .create_async()
+            .await;
+
+        let err = client(&server.url())
+            .import_snapshot(b"garbage".to_vec())
+            .await
+            .unwrap_err();
+        let SynapError::Api(api) = err else {
+            panic!("expected an API error, got {err:?}");
+        };
+        assert_eq!(api.status, Some(400));
+        assert!(api.message.contains("not a Synap dump file"));
+    }
+}
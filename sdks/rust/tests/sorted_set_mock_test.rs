@@ -7,6 +7,7 @@ mod tests {
     use super::common::setup_test_client;
     use mockito::Matcher;
     use serde_json::json;
+    use synap_sdk::LeaderboardWindow;
 
     #[tokio::test]
     async fn test_sorted_set_zadd() {
@@ -606,6 +607,94 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_sorted_set_zinter() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "sortedset.zinter",
+                "payload": {
+                    "keys": ["set1", "set2"],
+                    "weights": [1.0, 2.0],
+                    "aggregate": "sum",
+                    "withscores": false
+                }
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"members": ["a", "b"]}}"#)
+            .create_async()
+            .await;
+
+        let members = client
+            .sorted_set()
+            .inter(vec!["set1", "set2"], Some(vec![1.0, 2.0]), "sum")
+            .await
+            .unwrap();
+        assert_eq!(members, vec!["a", "b"]);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_sorted_set_zunion_with_scores() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "sortedset.zunion",
+                "payload": {
+                    "keys": ["set1", "set2"],
+                    "aggregate": "max",
+                    "withscores": true
+                }
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"members": [{"member": "a", "score": 1.0}, {"member": "b", "score": 5.0}]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let members = client
+            .sorted_set()
+            .union_with_scores(vec!["set1", "set2"], None, "max")
+            .await
+            .unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[1].member, "b");
+        assert_eq!(members[1].score, 5.0);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_sorted_set_zdiff() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "sortedset.zdiff",
+                "payload": {"keys": ["set1", "set2"]}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"members": ["c"]}}"#)
+            .create_async()
+            .await;
+
+        let members = client
+            .sorted_set()
+            .diff(vec!["set1", "set2"])
+            .await
+            .unwrap();
+        assert_eq!(members, vec!["c"]);
+
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_sorted_set_stats() {
         let (client, mut server) = setup_test_client().await;
@@ -627,4 +716,101 @@ mod tests {
 
         mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_sorted_set_zscan_sends_match_and_count() {
+        use synap_sdk::{CollectAll, ScanOptions};
+
+        let (client, mut server) = setup_test_client().await;
+
+        let second = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "sortedset.zscan",
+                "payload": {"key": "leaderboard", "cursor": 2, "match": "player*", "count": 2}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"members": [{"member": "player3", "score": 7.5}], "count": 1, "next_cursor": 0}}"#,
+            )
+            .create_async()
+            .await;
+        let first = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "sortedset.zscan",
+                "payload": {"key": "leaderboard", "cursor": 0, "match": "player*", "count": 2}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"members": [{"member": "player1", "score": 100.0}], "count": 1, "next_cursor": 2}}"#,
+            )
+            .create_async()
+            .await;
+
+        let options = ScanOptions {
+            pattern: Some("player*".to_string()),
+            count: Some(2),
+        };
+        let members = client
+            .sorted_set()
+            .scan_with("leaderboard", options)
+            .collect_all(100)
+            .await
+            .unwrap();
+        let members: Vec<(String, f64)> =
+            members.into_iter().map(|m| (m.member, m.score)).collect();
+        assert_eq!(
+            members,
+            vec![("player1".to_string(), 100.0), ("player3".to_string(), 7.5)]
+        );
+
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_sorted_set_leaderboard_window() {
+        let (client, mut server) = setup_test_client().await;
+
+        let set = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "sortedset.notify.set",
+                "payload": {"key": "leaderboard", "top_n": 10, "rank_threshold": 3}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"key": "leaderboard", "window": {"top_n": 10, "rank_threshold": 3}, "channel": "__zset@0__:leaderboard"}}"#)
+            .create_async()
+            .await;
+        let get = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "sortedset.notify.get",
+                "payload": {"key": "leaderboard"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"key": "leaderboard", "window": {"top_n": 10, "rank_threshold": 3}, "channel": "__zset@0__:leaderboard"}}"#)
+            .create_async()
+            .await;
+
+        let window = LeaderboardWindow {
+            top_n: 10,
+            rank_threshold: Some(3),
+        };
+        client
+            .sorted_set()
+            .set_leaderboard_window("leaderboard", window)
+            .await
+            .unwrap();
+        let current = client
+            .sorted_set()
+            .leaderboard_window("leaderboard")
+            .await
+            .unwrap();
+        assert_eq!(current, Some(window));
+
+        set.assert_async().await;
+        get.assert_async().await;
+    }
 }
@@ -66,7 +66,7 @@ mod tests {
             .mock("POST", "/api/v1/command")
             .match_body(Matcher::PartialJson(json!({
                 "command": "stream.get_or_create",
-                "payload": {"room": "already-here", "max_events": 5000}
+                "payload": {"room": "already-here"}
             })))
             .with_status(200)
             .with_body(
@@ -96,7 +96,7 @@ mod tests {
             .mock("POST", "/api/v1/command")
             .match_body(Matcher::PartialJson(json!({
                 "command": "stream.create",
-                "payload": {"room": "chat-1", "max_events": 5000}
+                "payload": {"room": "chat-1"}
             })))
             .with_status(200)
             .with_body(r#"{"success": true, "payload": {}}"#)
@@ -183,6 +183,80 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_stream_consume_with_min_age() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "stream.consume",
+                "payload": {"room": "chat-1", "from_offset": 0, "min_age_ms": 30000}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"events": []}}"#)
+            .create_async()
+            .await;
+
+        let events = client
+            .stream()
+            .consume_with_min_age("chat-1", Some(0), Some(10), 30_000)
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_stream_consume_many() {
+        let (client, mut server) = setup_test_client().await;
+
+        let data_bytes: Vec<u8> = serde_json::to_vec(&json!({"load": 0.5})).unwrap();
+        let response_body = serde_json::to_string(&json!({
+            "success": true,
+            "payload": {
+                "events": [
+                    {"room": "mem", "offset": 7, "event": "tick", "data": data_bytes},
+                    {"room": "cpu", "offset": 0, "event": "tick", "data": data_bytes}
+                ],
+                "next_offsets": {"cpu": 1, "mem": 8}
+            }
+        }))
+        .unwrap();
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "stream.consume_many",
+                "payload": {
+                    "rooms": [{"room": "cpu", "offset": 0}, {"room": "mem", "offset": 7}],
+                    "timeout_ms": 2000
+                }
+            })))
+            .with_status(200)
+            .with_body(response_body)
+            .create_async()
+            .await;
+
+        let batch = client
+            .stream()
+            .consume_many(
+                &[("cpu", 0), ("mem", 7)],
+                Some(10),
+                Some(std::time::Duration::from_secs(2)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(batch.events.len(), 2);
+        assert_eq!(batch.events[0].room, "mem");
+        assert_eq!(batch.events[0].event.offset, 7);
+        assert_eq!(batch.events[1].event.data["load"], 0.5);
+        assert_eq!(batch.next_offsets["cpu"], 1);
+
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_stream_stats() {
         let (client, mut server) = setup_test_client().await;
@@ -247,4 +321,121 @@ mod tests {
 
         mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_stream_events_pages_until_caught_up() {
+        use synap_sdk::CollectAll;
+
+        let (client, mut server) = setup_test_client().await;
+        let event = |offset: u64| {
+            json!({
+                "offset": offset,
+                "event": "tick",
+                "data": serde_json::to_vec(&json!(offset)).unwrap(),
+            })
+        };
+
+        let first = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "stream.consume",
+                "payload": {"room": "ticks", "from_offset": 5, "limit": 100}
+            })))
+            .with_status(200)
+            .with_body(
+                json!({"success": true, "payload": {"events": [event(5), event(6)]}}).to_string(),
+            )
+            .create_async()
+            .await;
+        let caught_up = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "stream.consume",
+                "payload": {"room": "ticks", "from_offset": 7}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"events": []}}"#)
+            .create_async()
+            .await;
+
+        let events = client
+            .stream()
+            .events("ticks", Some(5))
+            .collect_all(10)
+            .await
+            .unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.offset).collect::<Vec<_>>(),
+            vec![5, 6]
+        );
+        assert_eq!(events[1].data, json!(6));
+
+        first.assert_async().await;
+        caught_up.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_stream_group_lag_pages_to_the_end() {
+        use synap_sdk::CollectAll;
+
+        let (client, mut server) = setup_test_client().await;
+        let partition = |id: u64, committed: Option<u64>, lag: u64| {
+            json!({
+                "partition_id": id,
+                "end_offset": 50,
+                "committed_offset": committed,
+                "lag": lag
+            })
+        };
+
+        let first = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "consumer_group.lag",
+                "payload": {"group_id": "billing", "offset": 0, "limit": 100}
+            })))
+            .with_status(200)
+            .with_body(
+                json!({"success": true, "payload": {
+                    "group_id": "billing", "topic": "orders",
+                    "partitions": [partition(0, Some(50), 0), partition(1, Some(20), 30)],
+                    "total": 3, "total_lag": 80, "next_offset": 2
+                }})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let last = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "consumer_group.lag",
+                "payload": {"group_id": "billing", "offset": 2}
+            })))
+            .with_status(200)
+            .with_body(
+                json!({"success": true, "payload": {
+                    "group_id": "billing", "topic": "orders",
+                    "partitions": [partition(2, None, 50)],
+                    "total": 3, "total_lag": 80, "next_offset": null
+                }})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let partitions = client
+            .stream()
+            .group_lag("billing")
+            .collect_all(10)
+            .await
+            .unwrap();
+        assert_eq!(
+            partitions.iter().map(|p| p.lag).collect::<Vec<_>>(),
+            [0, 30, 50]
+        );
+        assert_eq!(partitions[2].committed_offset, None);
+
+        first.assert_async().await;
+        last.assert_async().await;
+    }
 }
@@ -0,0 +1,112 @@
+//! Tests for the `testing` feature: the fluent mock server
+
+#![cfg(feature = "testing")]
+
+use serde_json::json;
+use synap_sdk::testing::MockSynap;
+
+#[tokio::test]
+async fn test_mock_answers_expected_commands() {
+    let synap = MockSynap::new()
+        .expect_kv_get("user:1", json!("alice"))
+        .expect_kv_get("missing", json!(null))
+        .expect_kv_delete("user:1", true)
+        .expect_stream_publish("events", "user.created", 7)
+        .expect_pubsub_publish("news.sports", 3)
+        .start()
+        .await;
+    let client = synap.client();
+
+    let name: Option<String> = client.kv().get("user:1").await.unwrap();
+    assert_eq!(name.as_deref(), Some("alice"));
+    let missing: Option<String> = client.kv().get("missing").await.unwrap();
+    assert_eq!(missing, None);
+    assert!(client.kv().delete("user:1").await.unwrap());
+
+    let offset = client
+        .stream()
+        .publish("events", "user.created", json!({"id": 1}))
+        .await
+        .unwrap();
+    assert_eq!(offset, 7);
+    let matched = client
+        .pubsub()
+        .publish("news.sports", json!("goal"), None, None)
+        .await
+        .unwrap();
+    assert_eq!(matched, 3);
+
+    synap.assert().await;
+}
+
+#[tokio::test]
+async fn test_mock_errors_carry_codes() {
+    let synap = MockSynap::new()
+        .expect_error(
+            "queue.publish",
+            json!({"queue": "missing"}),
+            "QUEUE_NOT_FOUND",
+            "Queue not found: missing",
+        )
+        .start()
+        .await;
+
+    let error = synap
+        .client()
+        .queue()
+        .publish("missing", b"x", None, None)
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), Some("QUEUE_NOT_FOUND"));
+    synap.assert().await;
+}
+
+#[tokio::test]
+async fn test_mock_queues_answers_in_order() {
+    let synap = MockSynap::new()
+        .expect_queue_consume(
+            "jobs",
+            json!({"id": "m1", "payload": [104, 105], "priority": 5}),
+        )
+        .times(1)
+        .expect_queue_consume("jobs", json!(null))
+        .start()
+        .await;
+    let queue = synap.client().queue();
+
+    let first = queue.consume("jobs", "worker-1").await.unwrap().unwrap();
+    assert_eq!(first.id, "m1");
+    assert_eq!(first.payload, b"hi");
+    assert!(queue.consume("jobs", "worker-1").await.unwrap().is_none());
+
+    synap.assert().await;
+}
+
+#[tokio::test]
+async fn test_mock_matches_prefixed_keys() {
+    let synap = MockSynap::new()
+        .expect_kv_set("tenant-a:user:1", json!("alice"))
+        .start()
+        .await;
+    let client = synap_sdk::SynapClient::new(synap.config().with_key_prefix("tenant-a:")).unwrap();
+
+    client.kv().set("user:1", "alice", None).await.unwrap();
+    synap.assert().await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_mock_assert_fails_on_missed_expectations() {
+    let synap = MockSynap::new()
+        .expect_kv_get("user:1", json!("alice"))
+        .start()
+        .await;
+    synap.assert().await;
+}
+
+#[tokio::test]
+async fn test_mock_rejects_unexpected_commands() {
+    let synap = MockSynap::new().start().await;
+    let result: synap_sdk::Result<Option<String>> = synap.client().kv().get("user:1").await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,134 @@
+//! Tests for time series commands
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::setup_test_client;
+    use mockito::Matcher;
+    use serde_json::json;
+    use std::time::Duration;
+    use synap_sdk::{
+        TimeSeriesAggregation, TimeSeriesIncrOptions, TimeSeriesQuery, TimeSeriesResolution,
+    };
+
+    #[tokio::test]
+    async fn test_incr() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "ts.incr",
+                "payload": {"key": "requests", "value": 1.0}
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"timestamp": 1700000000000, "value": 4.0}}"#,
+            )
+            .create_async()
+            .await;
+
+        let point = client.time_series().incr("requests", 1.0).await.unwrap();
+        assert_eq!(point.timestamp, 1_700_000_000_000);
+        assert_eq!(point.value, 4.0);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_incr_with_options() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "ts.incr",
+                "payload": {
+                    "key": "signups",
+                    "value": 2.0,
+                    "timestamp": 1700000030000u64,
+                    "resolution": "1m",
+                    "retention": 604800000
+                }
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"timestamp": 1699999980000, "value": 2.0}}"#,
+            )
+            .create_async()
+            .await;
+
+        let options = TimeSeriesIncrOptions {
+            timestamp: Some(1_700_000_030_000),
+            resolution: Some(TimeSeriesResolution::Minute),
+            retention: Some(Duration::from_secs(7 * 24 * 3600)),
+        };
+        let point = client
+            .time_series()
+            .incr_with("signups", 2.0, options)
+            .await
+            .unwrap();
+        assert_eq!(point.timestamp, 1_699_999_980_000);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_range() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "ts.range",
+                "payload": {
+                    "key": "latency",
+                    "from": 1700000000000u64,
+                    "aggregation": "avg",
+                    "bucket": 3600000
+                }
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"success": true, "payload": {"points": [
+                    {"timestamp": 1699999200000, "value": 12.5},
+                    {"timestamp": 1700002800000, "value": 8.0}
+                ]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let query = TimeSeriesQuery {
+            from: Some(1_700_000_000_000),
+            aggregation: TimeSeriesAggregation::Avg,
+            bucket: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        let points = client.time_series().range("latency", query).await.unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].value, 12.5);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let (client, mut server) = setup_test_client().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "ts.del",
+                "payload": {"key": "requests"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"success": true, "payload": {"deleted": true}}"#)
+            .create_async()
+            .await;
+
+        assert!(client.time_series().delete("requests").await.unwrap());
+
+        mock.assert_async().await;
+    }
+}
@@ -0,0 +1,67 @@
+//! TLS configuration tests.
+//!
+//! The fixtures under `tests/fixtures/tls` are a throwaway test CA and a
+//! client certificate it signed (PKCS #8 key); they secure nothing.
+
+mod common;
+
+use synap_sdk::{SynapClient, SynapConfig, SynapError, TlsConfig};
+
+const CA_PEM: &[u8] = include_bytes!("fixtures/tls/ca.pem");
+const CLIENT_CERT_PEM: &[u8] = include_bytes!("fixtures/tls/client.pem");
+const CLIENT_KEY_PEM: &[u8] = include_bytes!("fixtures/tls/client.key");
+
+#[test]
+fn test_client_accepts_root_ca_and_client_certificate() {
+    let config = SynapConfig::new("https://synap.internal:15500")
+        .with_root_ca_pem(CA_PEM)
+        .with_client_cert_pem(CLIENT_CERT_PEM, CLIENT_KEY_PEM);
+    assert!(config.tls.is_customized());
+    assert!(SynapClient::new(config).is_ok());
+}
+
+#[test]
+fn test_client_rejects_invalid_root_ca() {
+    let config = SynapConfig::new("https://synap.internal:15500")
+        .with_root_ca_pem(b"-----BEGIN CERTIFICATE-----\nnot base64\n".to_vec());
+    assert!(matches!(SynapClient::new(config), Err(SynapError::Tls(_))));
+
+    let config = SynapConfig::new("https://synap.internal:15500").with_root_ca_pem("no pem here");
+    assert!(matches!(SynapClient::new(config), Err(SynapError::Tls(_))));
+}
+
+#[test]
+fn test_client_rejects_certificate_without_key() {
+    let config = SynapConfig::new("https://synap.internal:15500").with_tls(TlsConfig {
+        client_cert_pem: Some(CLIENT_CERT_PEM.to_vec()),
+        ..Default::default()
+    });
+    assert!(matches!(SynapClient::new(config), Err(SynapError::Tls(_))));
+}
+
+#[test]
+fn test_tls_debug_redacts_client_key() {
+    let config = SynapConfig::new("https://synap.internal:15500")
+        .with_client_cert_pem(CLIENT_CERT_PEM, CLIENT_KEY_PEM);
+    let debug = format!("{:?}", config.tls);
+    assert!(debug.contains("<redacted>"));
+    assert!(!debug.contains("PRIVATE KEY"));
+}
+
+#[tokio::test]
+async fn test_tls_options_leave_plain_http_working() {
+    let mut server = common::create_mock_server().await;
+    let mock = server
+        .mock("GET", "/health/ready")
+        .with_status(200)
+        .with_body(r#"{"ready":true}"#)
+        .create_async()
+        .await;
+
+    let config = SynapConfig::new(server.url())
+        .with_root_ca_pem(CA_PEM)
+        .with_danger_accept_invalid_certs();
+    let client = SynapClient::new(config).unwrap();
+    assert!(client.is_ready().await.unwrap());
+    mock.assert_async().await;
+}
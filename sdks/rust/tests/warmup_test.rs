@@ -0,0 +1,85 @@
+//! Cold start warmup: manifests are sent as `warmup.run` and readiness is read
+//! from `/health/ready`.
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+    use std::time::Duration;
+    use synap_sdk::{SynapClient, SynapConfig, WarmupManifest};
+
+    fn client(url: &str) -> SynapClient {
+        let config = SynapConfig::new(url).with_timeout(Duration::from_secs(5));
+        SynapClient::new(config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_warmup_sends_manifest() {
+        let mut server = Server::new_async().await;
+        let warmup = server
+            .mock("POST", "/api/v1/command")
+            .match_body(Matcher::PartialJson(json!({
+                "command": "warmup.run",
+                "payload": {"keys": ["config", "flags"], "prefixes": ["session:"]}
+            })))
+            .with_body(
+                json!({
+                    "success": true,
+                    "payload": {
+                        "keys_requested": 12,
+                        "keys_loaded": 11,
+                        "keys_missing": 1,
+                        "bytes_loaded": 2048,
+                        "truncated": false,
+                        "duration_ms": 3,
+                        "completed_at": 1_760_000_000u64
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let manifest = WarmupManifest::new()
+            .keys(["config", "flags"])
+            .prefix("session:");
+        let report = client(&server.url()).warmup(&manifest).await.unwrap();
+        assert_eq!(report.keys_loaded, 11);
+        assert_eq!(report.keys_missing, 1);
+        assert!(!report.truncated);
+        warmup.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_is_ready_follows_status() {
+        let mut server = Server::new_async().await;
+        let pending = server
+            .mock("GET", "/health/ready")
+            .with_status(503)
+            .with_body(r#"{"ready": false, "warmup": null}"#)
+            .create_async()
+            .await;
+
+        let client = client(&server.url());
+        assert!(!client.is_ready().await.unwrap());
+        pending.remove_async().await;
+
+        server
+            .mock("GET", "/health/ready")
+            .with_body(r#"{"ready": true, "warmup": null}"#)
+            .create_async()
+            .await;
+        assert!(client.is_ready().await.unwrap());
+    }
+
+    #[test]
+    fn test_manifest_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("warmup.json");
+        std::fs::write(&path, r#"{"keys": ["a"], "prefixes": ["user:"]}"#).unwrap();
+
+        let manifest = WarmupManifest::from_file(&path).unwrap();
+        assert_eq!(manifest, WarmupManifest::new().key("a").prefix("user:"));
+        assert!(WarmupManifest::from_file(dir.path().join("missing.json")).is_err());
+    }
+}